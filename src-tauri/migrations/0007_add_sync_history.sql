@@ -0,0 +1,17 @@
+-- Add sync history log (one row per sync attempt)
+-- Local-only diagnostics table: not tracked by sync triggers.
+
+CREATE TABLE IF NOT EXISTS sync_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    started_at TEXT NOT NULL,
+    finished_at TEXT NOT NULL,
+    direction TEXT NOT NULL CHECK (direction IN ('none','up','down','both')),
+    ops_uploaded INTEGER NOT NULL DEFAULT 0,
+    ops_downloaded INTEGER NOT NULL DEFAULT 0,
+    bytes_uploaded INTEGER NOT NULL DEFAULT 0,
+    bytes_downloaded INTEGER NOT NULL DEFAULT 0,
+    result TEXT NOT NULL CHECK (result IN ('success','failed')),
+    error TEXT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_history_started ON sync_history(started_at);
@@ -0,0 +1,25 @@
+-- Add staging area for "review before apply" sync mode
+-- Local-only tables: not tracked by sync triggers.
+
+-- One row per downloaded remote delta awaiting approval.
+CREATE TABLE IF NOT EXISTS sync_staged_deltas (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    delta_key TEXT NOT NULL UNIQUE,
+    source_device_id TEXT NOT NULL,
+    source_timestamp INTEGER NOT NULL,
+    payload TEXT NOT NULL,          -- full Delta JSON (applied verbatim on approval)
+    staged_at TEXT NOT NULL
+);
+
+-- One row per operation, with a human-readable summary for review UI.
+CREATE TABLE IF NOT EXISTS sync_staged_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    staged_delta_id INTEGER NOT NULL,
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    operation TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    FOREIGN KEY(staged_delta_id) REFERENCES sync_staged_deltas(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_staged_changes_delta ON sync_staged_changes(staged_delta_id);
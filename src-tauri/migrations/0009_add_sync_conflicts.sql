@@ -0,0 +1,20 @@
+-- Add sync conflict journal
+-- Local-only table: records automatic resolutions so users can audit / override them.
+
+CREATE TABLE IF NOT EXISTS sync_conflicts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    local_data TEXT NULL,           -- local row JSON at detection time
+    local_version INTEGER NULL,
+    remote_data TEXT NOT NULL,      -- incoming remote row JSON
+    remote_version INTEGER NOT NULL,
+    remote_device_id TEXT NOT NULL,
+    auto_resolution TEXT NOT NULL CHECK (auto_resolution IN ('local','remote')),
+    detected_at TEXT NOT NULL,
+    resolved_at TEXT NULL,
+    resolution TEXT NULL CHECK (resolution IN ('local','remote','merged'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_conflicts_record ON sync_conflicts(table_name, record_id);
+CREATE INDEX IF NOT EXISTS idx_sync_conflicts_unresolved ON sync_conflicts(resolved_at);
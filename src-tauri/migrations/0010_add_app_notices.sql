@@ -0,0 +1,13 @@
+-- App notices: one-off messages for the user (e.g. data dir migrated at startup).
+-- Local-only table: not tracked by sync triggers.
+
+CREATE TABLE IF NOT EXISTS app_notices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    level TEXT NOT NULL CHECK (level IN ('info','warning')),
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    dismissed_at TEXT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_app_notices_dismissed ON app_notices(dismissed_at);
@@ -0,0 +1,10 @@
+-- Field-level merge bases: last row snapshot known to be shared with other devices.
+-- Local-only table: not tracked by sync triggers.
+
+CREATE TABLE IF NOT EXISTS sync_record_base (
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (table_name, record_id)
+);
@@ -0,0 +1,119 @@
+-- Delete tombstones: stop stale remote upserts from resurrecting deleted rows.
+-- Local-only table: not tracked by sync triggers.
+-- Versioned tables only (project_tags has no _version and stays set-like).
+
+CREATE TABLE IF NOT EXISTS sync_tombstones (
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    deleted_at TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    PRIMARY KEY (table_name, record_id)
+);
+
+-- 1. 触发器：persons DELETE 写入 tombstone
+CREATE TRIGGER IF NOT EXISTS trk_persons_tombstone
+AFTER DELETE ON persons
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('persons', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+-- 2. 触发器：persons INSERT 清除 tombstone（记录重新存在）
+CREATE TRIGGER IF NOT EXISTS trk_persons_untombstone
+AFTER INSERT ON persons
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'persons' AND record_id = NEW.id;
+END;
+
+-- 3. 触发器：partners DELETE 写入 tombstone
+CREATE TRIGGER IF NOT EXISTS trk_partners_tombstone
+AFTER DELETE ON partners
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('partners', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+-- 4. 触发器：partners INSERT 清除 tombstone（记录重新存在）
+CREATE TRIGGER IF NOT EXISTS trk_partners_untombstone
+AFTER INSERT ON partners
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'partners' AND record_id = NEW.id;
+END;
+
+-- 5. 触发器：projects DELETE 写入 tombstone
+CREATE TRIGGER IF NOT EXISTS trk_projects_tombstone
+AFTER DELETE ON projects
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('projects', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+-- 6. 触发器：projects INSERT 清除 tombstone（记录重新存在）
+CREATE TRIGGER IF NOT EXISTS trk_projects_untombstone
+AFTER INSERT ON projects
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'projects' AND record_id = NEW.id;
+END;
+
+-- 7. 触发器：assignments DELETE 写入 tombstone
+CREATE TRIGGER IF NOT EXISTS trk_assignments_tombstone
+AFTER DELETE ON assignments
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('assignments', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+-- 8. 触发器：assignments INSERT 清除 tombstone（记录重新存在）
+CREATE TRIGGER IF NOT EXISTS trk_assignments_untombstone
+AFTER INSERT ON assignments
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'assignments' AND record_id = NEW.id;
+END;
+
+-- 9. 触发器：status_history DELETE 写入 tombstone
+CREATE TRIGGER IF NOT EXISTS trk_status_history_tombstone
+AFTER DELETE ON status_history
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('status_history', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+-- 10. 触发器：status_history INSERT 清除 tombstone（记录重新存在）
+CREATE TRIGGER IF NOT EXISTS trk_status_history_untombstone
+AFTER INSERT ON status_history
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'status_history' AND record_id = NEW.id;
+END;
+
+-- 11. 触发器：project_comments DELETE 写入 tombstone
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_tombstone
+AFTER DELETE ON project_comments
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('project_comments', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+-- 12. 触发器：project_comments INSERT 清除 tombstone（记录重新存在）
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_untombstone
+AFTER INSERT ON project_comments
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'project_comments' AND record_id = NEW.id;
+END;
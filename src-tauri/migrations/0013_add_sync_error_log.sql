@@ -0,0 +1,13 @@
+-- Rolling sync error log (one row per failed sync attempt, local-only diagnostics).
+-- Complements sync_config.last_sync_error, which only keeps the latest message.
+
+CREATE TABLE IF NOT EXISTS sync_error_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    occurred_at TEXT NOT NULL,
+    phase TEXT NOT NULL,
+    error_code TEXT NOT NULL,
+    message TEXT NOT NULL,
+    delta_key TEXT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_error_log_occurred ON sync_error_log(occurred_at);
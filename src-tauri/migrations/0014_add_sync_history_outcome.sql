@@ -0,0 +1,8 @@
+-- Per-attempt outcome counters for sync_history (structured sync result).
+
+ALTER TABLE sync_history ADD COLUMN deltas_uploaded INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sync_history ADD COLUMN deltas_applied INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sync_history ADD COLUMN deltas_staged INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sync_history ADD COLUMN skipped_stale_upserts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sync_history ADD COLUMN conflicts_detected INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sync_history ADD COLUMN quarantined_objects INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,24 @@
+-- Project templates: reusable defaults for recurring project types.
+-- Local-only table: not tracked by sync triggers.
+-- tags / members / milestones are JSON arrays:
+--   tags:       ["tag", ...]
+--   members:    [{"personId": "...", "role": "..."}, ...]
+--   milestones: [{"title": "...", "offsetDays": 14}, ...]
+
+CREATE TABLE IF NOT EXISTS project_templates (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    name_pattern TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    priority INTEGER NOT NULL DEFAULT 3,
+    country_code TEXT NOT NULL,
+    partner_id TEXT NOT NULL,
+    owner_person_id TEXT NOT NULL,
+    product_name TEXT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    members TEXT NOT NULL DEFAULT '[]',
+    milestones TEXT NOT NULL DEFAULT '[]',
+    instance_count INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
@@ -0,0 +1,22 @@
+-- Recurrence rules: create a project from a template every N days/weeks/months.
+-- Local-only table: not tracked by sync triggers (a rule runs on the device that owns it).
+
+CREATE TABLE IF NOT EXISTS recurrence_rules (
+    id TEXT PRIMARY KEY,
+    template_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    interval_unit TEXT NOT NULL CHECK (interval_unit IN ('day','week','month')),
+    interval_count INTEGER NOT NULL CHECK (interval_count >= 1),
+    anchor_on TEXT NOT NULL,
+    next_run_on TEXT NOT NULL,
+    due_offset_days INTEGER NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    last_run_at TEXT NULL,
+    last_project_id TEXT NULL,
+    last_error TEXT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_recurrence_rules_next ON recurrence_rules(enabled, next_run_on);
+CREATE INDEX IF NOT EXISTS idx_recurrence_rules_template ON recurrence_rules(template_id);
@@ -0,0 +1,147 @@
+-- Custom fields: team-defined project fields (text / number / date / select) and their values.
+-- Both tables are synced like other business tables.
+-- Field names are unique case-insensitively in the app layer (no UNIQUE index, so a remote
+-- insert never replaces a local row with the same name).
+-- A value row id is '<project_id>:<field_id>', so two devices setting the same field converge by LWW.
+
+-- 1. Tables
+CREATE TABLE IF NOT EXISTS custom_field_defs (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    field_type TEXT NOT NULL CHECK (field_type IN ('text','number','date','select')),
+    options TEXT NOT NULL DEFAULT '[]', -- JSON array of strings (select only)
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    _version INTEGER DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS idx_custom_field_defs_name ON custom_field_defs(name);
+
+CREATE TABLE IF NOT EXISTS project_custom_values (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    field_id TEXT NOT NULL,
+    value TEXT NOT NULL, -- normalized: number as decimal text, date as YYYY-MM-DD
+    updated_at TEXT NOT NULL,
+    _version INTEGER DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS idx_custom_values_project ON project_custom_values(project_id);
+CREATE INDEX IF NOT EXISTS idx_custom_values_field ON project_custom_values(field_id, value);
+
+-- 2. Sync triggers
+CREATE TRIGGER IF NOT EXISTS trk_custom_field_defs_insert
+AFTER INSERT ON custom_field_defs
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::custom_field_defs'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'custom_field_defs', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'name',NEW.name,'field_type',NEW.field_type,'options',NEW.options,'sort_order',NEW.sort_order,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_custom_field_defs_update
+AFTER UPDATE ON custom_field_defs
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::custom_field_defs'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'custom_field_defs', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'name',NEW.name,'field_type',NEW.field_type,'options',NEW.options,'sort_order',NEW.sort_order,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_custom_field_defs_delete
+AFTER DELETE ON custom_field_defs
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::custom_field_defs'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'custom_field_defs', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_custom_values_insert
+AFTER INSERT ON project_custom_values
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_custom_values'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_custom_values', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'field_id',NEW.field_id,'value',NEW.value,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_custom_values_update
+AFTER UPDATE ON project_custom_values
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_custom_values'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_custom_values', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'field_id',NEW.field_id,'value',NEW.value,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_custom_values_delete
+AFTER DELETE ON project_custom_values
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_custom_values'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_custom_values', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 3. Delete tombstones (stale remote upserts must not resurrect deleted rows)
+CREATE TRIGGER IF NOT EXISTS trk_custom_field_defs_tombstone
+AFTER DELETE ON custom_field_defs
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('custom_field_defs', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_custom_field_defs_untombstone
+AFTER INSERT ON custom_field_defs
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'custom_field_defs' AND record_id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_custom_values_tombstone
+AFTER DELETE ON project_custom_values
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('project_custom_values', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_custom_values_untombstone
+AFTER INSERT ON project_custom_values
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'project_custom_values' AND record_id = NEW.id;
+END;
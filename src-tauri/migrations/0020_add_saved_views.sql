@@ -0,0 +1,11 @@
+-- Saved views: named project_list filter + sort combinations.
+-- Local-only table: not tracked by sync triggers.
+-- filters is a serialized ProjectListReq (camelCase JSON, without limit/offset).
+
+CREATE TABLE IF NOT EXISTS saved_views (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    filters TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
@@ -0,0 +1,75 @@
+-- Kanban ordering: manual rank of a project within its status column (ascending).
+-- NULL = never reordered; such projects sort after ranked ones (most recently updated first).
+-- Ranks are midpoints between neighbours; a column is renumbered when the gap runs out.
+
+ALTER TABLE projects ADD COLUMN rank REAL NULL;
+
+CREATE INDEX IF NOT EXISTS idx_projects_status_rank ON projects(current_status, rank);
+
+-- Update sync triggers for projects to include rank in data_snapshot.
+DROP TRIGGER IF EXISTS trk_projects_insert;
+DROP TRIGGER IF EXISTS trk_projects_update;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_insert
+AFTER INSERT ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', NEW.id, 'INSERT',
+        json_object(
+            'id',NEW.id,
+            'name',NEW.name,
+            'description',NEW.description,
+            'priority',NEW.priority,
+            'current_status',NEW.current_status,
+            'country_code',NEW.country_code,
+            'partner_id',NEW.partner_id,
+            'owner_person_id',NEW.owner_person_id,
+            'product_name',NEW.product_name,
+            'parent_project_id',NEW.parent_project_id,
+            'rank',NEW.rank,
+            'start_date',NEW.start_date,
+            'due_date',NEW.due_date,
+            'created_at',NEW.created_at,
+            'updated_at',NEW.updated_at,
+            'archived_at',NEW.archived_at,
+            '_version',NEW._version
+        ),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_update
+AFTER UPDATE ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', NEW.id, 'UPDATE',
+        json_object(
+            'id',NEW.id,
+            'name',NEW.name,
+            'description',NEW.description,
+            'priority',NEW.priority,
+            'current_status',NEW.current_status,
+            'country_code',NEW.country_code,
+            'partner_id',NEW.partner_id,
+            'owner_person_id',NEW.owner_person_id,
+            'product_name',NEW.product_name,
+            'parent_project_id',NEW.parent_project_id,
+            'rank',NEW.rank,
+            'start_date',NEW.start_date,
+            'due_date',NEW.due_date,
+            'created_at',NEW.created_at,
+            'updated_at',NEW.updated_at,
+            'archived_at',NEW.archived_at,
+            '_version',NEW._version
+        ),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
@@ -0,0 +1,134 @@
+-- Project size estimate (story points) and its change history.
+-- size_points uses a fixed scale: Fibonacci 0/1/2/3/5/8/13/21 (T-shirt XS..XL map to 1/2/3/5/8).
+-- NULL = not estimated. project_size_history is append-only, like status_history.
+
+ALTER TABLE projects ADD COLUMN size_points INTEGER NULL;
+
+CREATE TABLE IF NOT EXISTS project_size_history (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    from_points INTEGER NULL,
+    to_points INTEGER NULL,
+    changed_at TEXT NOT NULL,
+    changed_by_person_id TEXT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    _version INTEGER NOT NULL DEFAULT 1,
+    FOREIGN KEY (project_id) REFERENCES projects(id),
+    FOREIGN KEY (changed_by_person_id) REFERENCES persons(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_size_history_project_time
+    ON project_size_history(project_id, changed_at DESC);
+
+-- Update sync triggers for projects to include size_points in data_snapshot.
+DROP TRIGGER IF EXISTS trk_projects_insert;
+DROP TRIGGER IF EXISTS trk_projects_update;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_insert
+AFTER INSERT ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', NEW.id, 'INSERT',
+        json_object(
+            'id',NEW.id,
+            'name',NEW.name,
+            'description',NEW.description,
+            'priority',NEW.priority,
+            'current_status',NEW.current_status,
+            'country_code',NEW.country_code,
+            'partner_id',NEW.partner_id,
+            'owner_person_id',NEW.owner_person_id,
+            'product_name',NEW.product_name,
+            'parent_project_id',NEW.parent_project_id,
+            'rank',NEW.rank,
+            'size_points',NEW.size_points,
+            'start_date',NEW.start_date,
+            'due_date',NEW.due_date,
+            'created_at',NEW.created_at,
+            'updated_at',NEW.updated_at,
+            'archived_at',NEW.archived_at,
+            '_version',NEW._version
+        ),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_update
+AFTER UPDATE ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', NEW.id, 'UPDATE',
+        json_object(
+            'id',NEW.id,
+            'name',NEW.name,
+            'description',NEW.description,
+            'priority',NEW.priority,
+            'current_status',NEW.current_status,
+            'country_code',NEW.country_code,
+            'partner_id',NEW.partner_id,
+            'owner_person_id',NEW.owner_person_id,
+            'product_name',NEW.product_name,
+            'parent_project_id',NEW.parent_project_id,
+            'rank',NEW.rank,
+            'size_points',NEW.size_points,
+            'start_date',NEW.start_date,
+            'due_date',NEW.due_date,
+            'created_at',NEW.created_at,
+            'updated_at',NEW.updated_at,
+            'archived_at',NEW.archived_at,
+            '_version',NEW._version
+        ),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_size_history_insert
+AFTER INSERT ON project_size_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_size_history'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_size_history', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'from_points',NEW.from_points,'to_points',NEW.to_points,'changed_at',NEW.changed_at,'changed_by_person_id',NEW.changed_by_person_id,'note',NEW.note,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_size_history_delete
+AFTER DELETE ON project_size_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_size_history'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_size_history', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_size_history_tombstone
+AFTER DELETE ON project_size_history
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('project_size_history', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_size_history_untombstone
+AFTER INSERT ON project_size_history
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'project_size_history' AND record_id = NEW.id;
+END;
@@ -0,0 +1,23 @@
+-- Report schedules: generate a report file (e.g. partner scorecard PDF) every N days/weeks/months.
+-- Local-only table: not tracked by sync triggers (output paths belong to this device).
+
+CREATE TABLE IF NOT EXISTS report_schedules (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    report_kind TEXT NOT NULL CHECK (report_kind IN ('partner_scorecard')),
+    partner_id TEXT NULL,
+    interval_unit TEXT NOT NULL CHECK (interval_unit IN ('day','week','month')),
+    interval_count INTEGER NOT NULL CHECK (interval_count >= 1),
+    anchor_on TEXT NOT NULL,
+    next_run_on TEXT NOT NULL,
+    -- NULL = <data dir>/reports
+    output_dir TEXT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    last_run_at TEXT NULL,
+    last_output_path TEXT NULL,
+    last_error TEXT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_schedules_next ON report_schedules(enabled, next_run_on);
@@ -0,0 +1,19 @@
+-- Link health: results of the latest link check (URLs found in descriptions, comments and
+-- text custom fields). Each run replaces all rows.
+-- Local-only table: not tracked by sync triggers (reachability depends on this device's network).
+
+CREATE TABLE IF NOT EXISTS link_check_results (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    source TEXT NOT NULL CHECK (source IN ('description','comment','custom_field')),
+    -- comment id / custom field id; NULL for the description
+    source_id TEXT NULL,
+    -- ok | dead | unverified
+    outcome TEXT NOT NULL CHECK (outcome IN ('ok','dead','unverified')),
+    status_code INTEGER NULL,
+    error TEXT NULL,
+    checked_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_link_check_results_project ON link_check_results(project_id, outcome);
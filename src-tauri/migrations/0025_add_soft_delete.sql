@@ -0,0 +1,150 @@
+-- Soft delete (trash bin) for projects, persons and partners.
+-- deleted_at set = in the trash: hidden from lists, restorable until purged (hard DELETE).
+-- The column travels in the sync snapshots, so a deletion reaches other devices as an
+-- UPDATE first; only a purge produces DELETE ops and tombstones.
+
+ALTER TABLE projects ADD COLUMN deleted_at TEXT NULL;
+ALTER TABLE persons ADD COLUMN deleted_at TEXT NULL;
+ALTER TABLE partners ADD COLUMN deleted_at TEXT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_projects_deleted_at ON projects(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_persons_deleted_at ON persons(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_partners_deleted_at ON partners(deleted_at);
+
+-- Recreate insert/update sync triggers to include deleted_at in data_snapshot.
+
+-- 1. persons
+DROP TRIGGER IF EXISTS trk_persons_insert;
+DROP TRIGGER IF EXISTS trk_persons_update;
+
+CREATE TRIGGER IF NOT EXISTS trk_persons_insert
+AFTER INSERT ON persons
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::persons'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'persons', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'display_name',NEW.display_name,'email',NEW.email,'role',NEW.role,'note',NEW.note,'is_active',NEW.is_active,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'deleted_at',NEW.deleted_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_persons_update
+AFTER UPDATE ON persons
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::persons'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'persons', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'display_name',NEW.display_name,'email',NEW.email,'role',NEW.role,'note',NEW.note,'is_active',NEW.is_active,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'deleted_at',NEW.deleted_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+-- 2. partners
+DROP TRIGGER IF EXISTS trk_partners_insert;
+DROP TRIGGER IF EXISTS trk_partners_update;
+
+CREATE TRIGGER IF NOT EXISTS trk_partners_insert
+AFTER INSERT ON partners
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::partners'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'partners', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'name',NEW.name,'note',NEW.note,'is_active',NEW.is_active,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'deleted_at',NEW.deleted_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_partners_update
+AFTER UPDATE ON partners
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::partners'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'partners', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'name',NEW.name,'note',NEW.note,'is_active',NEW.is_active,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'deleted_at',NEW.deleted_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+-- 3. projects
+DROP TRIGGER IF EXISTS trk_projects_insert;
+DROP TRIGGER IF EXISTS trk_projects_update;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_insert
+AFTER INSERT ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', NEW.id, 'INSERT',
+        json_object(
+            'id',NEW.id,
+            'name',NEW.name,
+            'description',NEW.description,
+            'priority',NEW.priority,
+            'current_status',NEW.current_status,
+            'country_code',NEW.country_code,
+            'partner_id',NEW.partner_id,
+            'owner_person_id',NEW.owner_person_id,
+            'product_name',NEW.product_name,
+            'parent_project_id',NEW.parent_project_id,
+            'rank',NEW.rank,
+            'size_points',NEW.size_points,
+            'start_date',NEW.start_date,
+            'due_date',NEW.due_date,
+            'created_at',NEW.created_at,
+            'updated_at',NEW.updated_at,
+            'archived_at',NEW.archived_at,
+            'deleted_at',NEW.deleted_at,
+            '_version',NEW._version
+        ),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_update
+AFTER UPDATE ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', NEW.id, 'UPDATE',
+        json_object(
+            'id',NEW.id,
+            'name',NEW.name,
+            'description',NEW.description,
+            'priority',NEW.priority,
+            'current_status',NEW.current_status,
+            'country_code',NEW.country_code,
+            'partner_id',NEW.partner_id,
+            'owner_person_id',NEW.owner_person_id,
+            'product_name',NEW.product_name,
+            'parent_project_id',NEW.parent_project_id,
+            'rank',NEW.rank,
+            'size_points',NEW.size_points,
+            'start_date',NEW.start_date,
+            'due_date',NEW.due_date,
+            'created_at',NEW.created_at,
+            'updated_at',NEW.updated_at,
+            'archived_at',NEW.archived_at,
+            'deleted_at',NEW.deleted_at,
+            '_version',NEW._version
+        ),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
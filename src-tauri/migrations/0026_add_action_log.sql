@@ -0,0 +1,20 @@
+-- Undo/redo journal for reversible edits (project update, status change, assignment end,
+-- comment delete). Each row stores the affected rows before (undo_payload) and after
+-- (redo_payload) the action. Every profile has its own database, so the journal is per profile.
+-- Local-only table: not tracked by sync triggers (undo / redo themselves sync as normal edits).
+
+CREATE TABLE IF NOT EXISTS action_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL CHECK (action IN ('project_update','project_status','assignment_end','comment_delete')),
+    -- project id (project update / status / assignment end) or comment id
+    entity_id TEXT NOT NULL,
+    -- JSON [{ table, key, row }]: row images to write back on undo (row = null: delete)
+    undo_payload TEXT NOT NULL,
+    -- JSON [{ table, key, row }]: row images to write back on redo
+    redo_payload TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    -- set while the action is undone (on the redo stack)
+    undone_at TEXT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_action_log_undone ON action_log(undone_at, id);
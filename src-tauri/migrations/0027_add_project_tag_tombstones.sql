@@ -0,0 +1,37 @@
+-- Set-union merge for project_tags: removals carry their time and leave a tombstone, so a
+-- concurrent add on another device survives while an intentional removal still propagates.
+-- Tag tombstones live in sync_tombstones with version 0; deleted_at is the removal time (UTC).
+
+-- 1. DELETE ops carry project_id / tag / deleted_at
+DROP TRIGGER IF EXISTS trk_project_tags_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_tags_delete
+AFTER DELETE ON project_tags
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_tags'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_tags', OLD.project_id || ':' || OLD.tag, 'DELETE',
+        json_object('project_id',OLD.project_id,'tag',OLD.tag,'deleted_at',strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        1, datetime('now'), 0
+    );
+END;
+
+-- 2. 触发器：project_tags DELETE 写入 tombstone
+CREATE TRIGGER IF NOT EXISTS trk_project_tags_tombstone
+AFTER DELETE ON project_tags
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('project_tags', OLD.project_id || ':' || OLD.tag, strftime('%Y-%m-%dT%H:%M:%fZ','now'), 0)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at;
+END;
+
+-- 3. 触发器：project_tags INSERT 清除 tombstone（标签重新存在）
+CREATE TRIGGER IF NOT EXISTS trk_project_tags_untombstone
+AFTER INSERT ON project_tags
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'project_tags' AND record_id = NEW.project_id || ':' || NEW.tag;
+END;
@@ -0,0 +1,17 @@
+-- Field-level audit trail for project / person / partner updates: one row per changed field
+-- with the old and new value (as text), who made the change and when.
+-- Local-only table: not tracked by sync triggers.
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('project','person','partner')),
+    entity_id TEXT NOT NULL,
+    -- column name; projects also record 'tags' (sorted, comma separated)
+    field TEXT NOT NULL,
+    old_value TEXT NULL,
+    new_value TEXT NULL,
+    changed_by_person_id TEXT NULL,
+    changed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log(entity_type, entity_id, id);
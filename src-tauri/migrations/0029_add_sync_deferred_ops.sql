@@ -0,0 +1,17 @@
+-- Strict foreign-key mode: remote upserts whose referenced rows have not arrived yet wait here
+-- instead of being applied as dangling rows; they are retried after every applied delta.
+-- Local-only table: not tracked by sync triggers.
+
+CREATE TABLE IF NOT EXISTS sync_deferred_ops (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    -- serialized Operation
+    operation TEXT NOT NULL,
+    -- origin delta: device and vector clock (JSON), used when the operation is finally applied
+    source_device_id TEXT NOT NULL,
+    vector_clock TEXT NOT NULL,
+    -- first missing reference, e.g. "partners:<id>"
+    missing_ref TEXT NOT NULL,
+    deferred_at TEXT NOT NULL
+);
@@ -0,0 +1,30 @@
+-- History rows are append-only, except for person references rewritten by a person merge
+-- (changed_by_person_id). Track those updates so other devices see the merged person.
+
+CREATE TRIGGER IF NOT EXISTS trk_status_history_update
+AFTER UPDATE ON status_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::status_history'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'status_history', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'from_status',NEW.from_status,'to_status',NEW.to_status,'changed_at',NEW.changed_at,'changed_by_person_id',NEW.changed_by_person_id,'note',NEW.note,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_size_history_update
+AFTER UPDATE ON project_size_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_size_history'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_size_history', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'from_points',NEW.from_points,'to_points',NEW.to_points,'changed_at',NEW.changed_at,'changed_by_person_id',NEW.changed_by_person_id,'note',NEW.note,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
@@ -0,0 +1,18 @@
+-- Notifications: reminders generated by the background checker (due soon, overdue, blocked too long).
+-- dedupe_key identifies the condition (kind + project + due date / blocked since), so each
+-- condition is reported once; a changed due date or a new BLOCKED period yields a new reminder.
+-- Local-only table: not tracked by sync triggers (every device generates its own reminders).
+
+CREATE TABLE IF NOT EXISTS notifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL CHECK (kind IN ('DUE_SOON','OVERDUE','BLOCKED_TOO_LONG')),
+    project_id TEXT NOT NULL,
+    dedupe_key TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    dismissed_at TEXT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_dedupe ON notifications(dedupe_key);
+CREATE INDEX IF NOT EXISTS idx_notifications_dismissed ON notifications(dismissed_at);
@@ -0,0 +1,35 @@
+-- Outbound webhooks: POST signed JSON payloads to external services (Slack, n8n, ...).
+-- webhook_deliveries is an outbox: events are queued in the writing transaction and sent by
+-- the dispatcher with retry / backoff; the rows double as the delivery log.
+-- Local-only tables: not tracked by sync triggers (each device delivers its own events).
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    -- HMAC-SHA256 key for X-Projex-Signature; '' = unsigned
+    secret TEXT NOT NULL DEFAULT '',
+    -- JSON array of event names; [] = all events
+    events TEXT NOT NULL DEFAULT '[]',
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id TEXT NOT NULL,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('pending','delivered','failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT NULL,
+    last_status_code INTEGER NULL,
+    last_error TEXT NULL,
+    created_at TEXT NOT NULL,
+    delivered_at TEXT NULL,
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, id DESC);
@@ -0,0 +1,3 @@
+-- Keyset pagination for project_list: (updated_at, id) cursor seeks instead of OFFSET scans.
+
+CREATE INDEX IF NOT EXISTS idx_projects_updated_id ON projects(updated_at, id);
@@ -0,0 +1,43 @@
+-- Device registry: friendly name / platform / last seen per sync device id, and revocation.
+-- Synced like business tables, but always on (not a per-table toggle): revocations must reach
+-- every device. Each device maintains its own row; revoked_at, once set, is never cleared by sync.
+-- No DELETE path: rows are never removed, so there are no tombstone triggers.
+
+-- 1. Table
+CREATE TABLE IF NOT EXISTS devices (
+    id TEXT PRIMARY KEY, -- sync device_id
+    name TEXT NOT NULL,
+    platform TEXT NOT NULL DEFAULT '',
+    last_seen_at TEXT,
+    revoked_at TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    _version INTEGER DEFAULT 1
+);
+
+-- 2. Sync triggers
+CREATE TRIGGER IF NOT EXISTS trk_devices_insert
+AFTER INSERT ON devices
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'devices', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'name',NEW.name,'platform',NEW.platform,'last_seen_at',NEW.last_seen_at,'revoked_at',NEW.revoked_at,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_devices_update
+AFTER UPDATE ON devices
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'devices', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'name',NEW.name,'platform',NEW.platform,'last_seen_at',NEW.last_seen_at,'revoked_at',NEW.revoked_at,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
@@ -0,0 +1,521 @@
+-- Read-only replica: when sync_config 'read_only_replica' = '1' this device applies remote
+-- deltas but never records local changes. Recreate the business change-tracking triggers
+-- with the extra WHEN condition. The devices triggers stay as they are, so the replica still
+-- publishes its own registry row (last seen, name) and can be revoked like any device.
+
+-- 1. persons
+DROP TRIGGER IF EXISTS trk_persons_insert;
+DROP TRIGGER IF EXISTS trk_persons_update;
+DROP TRIGGER IF EXISTS trk_persons_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_persons_insert
+AFTER INSERT ON persons
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::persons'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'persons', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'display_name',NEW.display_name,'email',NEW.email,'role',NEW.role,'note',NEW.note,'is_active',NEW.is_active,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'deleted_at',NEW.deleted_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_persons_update
+AFTER UPDATE ON persons
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::persons'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'persons', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'display_name',NEW.display_name,'email',NEW.email,'role',NEW.role,'note',NEW.note,'is_active',NEW.is_active,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'deleted_at',NEW.deleted_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_persons_delete
+AFTER DELETE ON persons
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::persons'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'persons', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 2. partners
+DROP TRIGGER IF EXISTS trk_partners_insert;
+DROP TRIGGER IF EXISTS trk_partners_update;
+DROP TRIGGER IF EXISTS trk_partners_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_partners_insert
+AFTER INSERT ON partners
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::partners'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'partners', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'name',NEW.name,'note',NEW.note,'is_active',NEW.is_active,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'deleted_at',NEW.deleted_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_partners_update
+AFTER UPDATE ON partners
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::partners'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'partners', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'name',NEW.name,'note',NEW.note,'is_active',NEW.is_active,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'deleted_at',NEW.deleted_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_partners_delete
+AFTER DELETE ON partners
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::partners'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'partners', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 3. projects
+DROP TRIGGER IF EXISTS trk_projects_insert;
+DROP TRIGGER IF EXISTS trk_projects_update;
+DROP TRIGGER IF EXISTS trk_projects_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_insert
+AFTER INSERT ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', NEW.id, 'INSERT',
+        json_object(
+            'id',NEW.id,
+            'name',NEW.name,
+            'description',NEW.description,
+            'priority',NEW.priority,
+            'current_status',NEW.current_status,
+            'country_code',NEW.country_code,
+            'partner_id',NEW.partner_id,
+            'owner_person_id',NEW.owner_person_id,
+            'product_name',NEW.product_name,
+            'parent_project_id',NEW.parent_project_id,
+            'rank',NEW.rank,
+            'size_points',NEW.size_points,
+            'cloned_from_id',NEW.cloned_from_id,
+            'start_date',NEW.start_date,
+            'due_date',NEW.due_date,
+            'created_at',NEW.created_at,
+            'updated_at',NEW.updated_at,
+            'archived_at',NEW.archived_at,
+            'deleted_at',NEW.deleted_at,
+            '_version',NEW._version
+        ),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_update
+AFTER UPDATE ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', NEW.id, 'UPDATE',
+        json_object(
+            'id',NEW.id,
+            'name',NEW.name,
+            'description',NEW.description,
+            'priority',NEW.priority,
+            'current_status',NEW.current_status,
+            'country_code',NEW.country_code,
+            'partner_id',NEW.partner_id,
+            'owner_person_id',NEW.owner_person_id,
+            'product_name',NEW.product_name,
+            'parent_project_id',NEW.parent_project_id,
+            'rank',NEW.rank,
+            'size_points',NEW.size_points,
+            'cloned_from_id',NEW.cloned_from_id,
+            'start_date',NEW.start_date,
+            'due_date',NEW.due_date,
+            'created_at',NEW.created_at,
+            'updated_at',NEW.updated_at,
+            'archived_at',NEW.archived_at,
+            'deleted_at',NEW.deleted_at,
+            '_version',NEW._version
+        ),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_projects_delete
+AFTER DELETE ON projects
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::projects'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'projects', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 4. assignments
+DROP TRIGGER IF EXISTS trk_assignments_insert;
+DROP TRIGGER IF EXISTS trk_assignments_update;
+DROP TRIGGER IF EXISTS trk_assignments_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_assignments_insert
+AFTER INSERT ON assignments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::assignments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'assignments', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'person_id',NEW.person_id,'role',NEW.role,'start_at',NEW.start_at,'end_at',NEW.end_at,'created_at',NEW.created_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_assignments_update
+AFTER UPDATE ON assignments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::assignments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'assignments', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'person_id',NEW.person_id,'role',NEW.role,'start_at',NEW.start_at,'end_at',NEW.end_at,'created_at',NEW.created_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_assignments_delete
+AFTER DELETE ON assignments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::assignments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'assignments', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 5. status_history
+DROP TRIGGER IF EXISTS trk_status_history_insert;
+DROP TRIGGER IF EXISTS trk_status_history_update;
+DROP TRIGGER IF EXISTS trk_status_history_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_status_history_insert
+AFTER INSERT ON status_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::status_history'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'status_history', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'from_status',NEW.from_status,'to_status',NEW.to_status,'changed_at',NEW.changed_at,'changed_by_person_id',NEW.changed_by_person_id,'note',NEW.note,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_status_history_update
+AFTER UPDATE ON status_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::status_history'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'status_history', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'from_status',NEW.from_status,'to_status',NEW.to_status,'changed_at',NEW.changed_at,'changed_by_person_id',NEW.changed_by_person_id,'note',NEW.note,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_status_history_delete
+AFTER DELETE ON status_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::status_history'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'status_history', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 6. project_tags
+DROP TRIGGER IF EXISTS trk_project_tags_insert;
+DROP TRIGGER IF EXISTS trk_project_tags_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_tags_insert
+AFTER INSERT ON project_tags
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_tags'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_tags', NEW.project_id || ':' || NEW.tag, 'INSERT',
+        json_object('project_id',NEW.project_id,'tag',NEW.tag,'created_at',NEW.created_at),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        1, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_tags_delete
+AFTER DELETE ON project_tags
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_tags'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_tags', OLD.project_id || ':' || OLD.tag, 'DELETE',
+        json_object('project_id',OLD.project_id,'tag',OLD.tag,'deleted_at',strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        1, datetime('now'), 0
+    );
+END;
+
+-- 7. project_comments
+DROP TRIGGER IF EXISTS trk_project_comments_insert;
+DROP TRIGGER IF EXISTS trk_project_comments_update;
+DROP TRIGGER IF EXISTS trk_project_comments_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_insert
+AFTER INSERT ON project_comments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_comments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_comments', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'person_id',NEW.person_id,'content',NEW.content,'is_pinned',NEW.is_pinned,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_update
+AFTER UPDATE ON project_comments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_comments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_comments', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'person_id',NEW.person_id,'content',NEW.content,'is_pinned',NEW.is_pinned,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_delete
+AFTER DELETE ON project_comments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_comments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_comments', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 8. custom_field_defs
+DROP TRIGGER IF EXISTS trk_custom_field_defs_insert;
+DROP TRIGGER IF EXISTS trk_custom_field_defs_update;
+DROP TRIGGER IF EXISTS trk_custom_field_defs_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_custom_field_defs_insert
+AFTER INSERT ON custom_field_defs
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::custom_field_defs'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'custom_field_defs', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'name',NEW.name,'field_type',NEW.field_type,'options',NEW.options,'sort_order',NEW.sort_order,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_custom_field_defs_update
+AFTER UPDATE ON custom_field_defs
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::custom_field_defs'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'custom_field_defs', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'name',NEW.name,'field_type',NEW.field_type,'options',NEW.options,'sort_order',NEW.sort_order,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_custom_field_defs_delete
+AFTER DELETE ON custom_field_defs
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::custom_field_defs'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'custom_field_defs', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 9. project_custom_values
+DROP TRIGGER IF EXISTS trk_project_custom_values_insert;
+DROP TRIGGER IF EXISTS trk_project_custom_values_update;
+DROP TRIGGER IF EXISTS trk_project_custom_values_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_custom_values_insert
+AFTER INSERT ON project_custom_values
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_custom_values'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_custom_values', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'field_id',NEW.field_id,'value',NEW.value,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_custom_values_update
+AFTER UPDATE ON project_custom_values
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_custom_values'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_custom_values', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'field_id',NEW.field_id,'value',NEW.value,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_custom_values_delete
+AFTER DELETE ON project_custom_values
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_custom_values'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_custom_values', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 10. project_size_history
+DROP TRIGGER IF EXISTS trk_project_size_history_insert;
+DROP TRIGGER IF EXISTS trk_project_size_history_update;
+DROP TRIGGER IF EXISTS trk_project_size_history_delete;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_size_history_insert
+AFTER INSERT ON project_size_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_size_history'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_size_history', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'from_points',NEW.from_points,'to_points',NEW.to_points,'changed_at',NEW.changed_at,'changed_by_person_id',NEW.changed_by_person_id,'note',NEW.note,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_size_history_update
+AFTER UPDATE ON project_size_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_size_history'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_size_history', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'from_points',NEW.from_points,'to_points',NEW.to_points,'changed_at',NEW.changed_at,'changed_by_person_id',NEW.changed_by_person_id,'note',NEW.note,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_size_history_delete
+AFTER DELETE ON project_size_history
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_size_history'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_size_history', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
@@ -0,0 +1,121 @@
+-- Comment threads and reactions.
+-- Replies point at their top-level comment (soft reference, one level deep; NULL = top level).
+-- Reactions carry the comment's project_id so they follow the project (sync scope, purge).
+
+ALTER TABLE project_comments ADD COLUMN parent_comment_id TEXT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_comments_parent ON project_comments(parent_comment_id);
+
+-- 1. Recreate project_comments insert/update sync triggers to include parent_comment_id.
+DROP TRIGGER IF EXISTS trk_project_comments_insert;
+DROP TRIGGER IF EXISTS trk_project_comments_update;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_insert
+AFTER INSERT ON project_comments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_comments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_comments', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'person_id',NEW.person_id,'parent_comment_id',NEW.parent_comment_id,'content',NEW.content,'is_pinned',NEW.is_pinned,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_update
+AFTER UPDATE ON project_comments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_comments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_comments', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'person_id',NEW.person_id,'parent_comment_id',NEW.parent_comment_id,'content',NEW.content,'is_pinned',NEW.is_pinned,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+-- 2. comment_reactions: one row per (comment, person, emoji).
+CREATE TABLE IF NOT EXISTS comment_reactions (
+    id TEXT PRIMARY KEY,
+    comment_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    person_id TEXT NOT NULL,
+    emoji TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    _version INTEGER DEFAULT 1,
+    FOREIGN KEY(comment_id) REFERENCES project_comments(id),
+    FOREIGN KEY(project_id) REFERENCES projects(id),
+    FOREIGN KEY(person_id) REFERENCES persons(id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_comment_reactions_unique
+    ON comment_reactions(comment_id, person_id, emoji);
+CREATE INDEX IF NOT EXISTS idx_comment_reactions_project ON comment_reactions(project_id);
+
+-- 3. Sync triggers for comment_reactions
+CREATE TRIGGER IF NOT EXISTS trk_comment_reactions_insert
+AFTER INSERT ON comment_reactions
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::comment_reactions'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'comment_reactions', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'comment_id',NEW.comment_id,'project_id',NEW.project_id,'person_id',NEW.person_id,'emoji',NEW.emoji,'created_at',NEW.created_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_comment_reactions_update
+AFTER UPDATE ON comment_reactions
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::comment_reactions'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'comment_reactions', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'comment_id',NEW.comment_id,'project_id',NEW.project_id,'person_id',NEW.person_id,'emoji',NEW.emoji,'created_at',NEW.created_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_comment_reactions_delete
+AFTER DELETE ON comment_reactions
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::comment_reactions'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'comment_reactions', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 4. Delete tombstones (stale remote upserts must not resurrect deleted reactions)
+CREATE TRIGGER IF NOT EXISTS trk_comment_reactions_tombstone
+AFTER DELETE ON comment_reactions
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('comment_reactions', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_comment_reactions_untombstone
+AFTER INSERT ON comment_reactions
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'comment_reactions' AND record_id = NEW.id;
+END;
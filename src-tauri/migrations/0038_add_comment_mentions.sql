@@ -0,0 +1,89 @@
+-- Comment @mentions.
+-- comment_mentions is a derived index of the TipTap mention nodes in project_comments.content
+-- ({"type":"mention","attrs":{"id":<person id>,"label":...}}), one row per (comment, person).
+-- It is kept by the triggers below, so comments written by any path (commands, sync, snapshot
+-- restore, import, undo) are indexed the same way. Local-only: not tracked by sync triggers.
+
+CREATE TABLE IF NOT EXISTS comment_mentions (
+    comment_id TEXT NOT NULL,
+    person_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    mentioned_at TEXT NOT NULL,
+    PRIMARY KEY (comment_id, person_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_comment_mentions_person ON comment_mentions(person_id, mentioned_at);
+
+-- 1. Index triggers. INSERT OR REPLACE (delta sync, restore) does not fire the delete trigger,
+--    so the insert trigger clears whatever was indexed for the id first.
+--    An edit keeps the time of mentions that were already there; new ones get updated_at.
+CREATE TRIGGER IF NOT EXISTS trg_comment_mentions_insert
+AFTER INSERT ON project_comments
+BEGIN
+    DELETE FROM comment_mentions WHERE comment_id = NEW.id;
+    INSERT OR IGNORE INTO comment_mentions (comment_id, person_id, project_id, mentioned_at)
+    SELECT DISTINCT NEW.id, json_extract(t.value, '$.attrs.id'), NEW.project_id, NEW.created_at
+    FROM json_tree(CASE WHEN json_valid(NEW.content) THEN NEW.content ELSE '{}' END) t
+    WHERE t.type = 'object'
+      AND json_extract(t.value, '$.type') = 'mention'
+      AND COALESCE(json_extract(t.value, '$.attrs.id'), '') <> '';
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_comment_mentions_update
+AFTER UPDATE OF content, project_id ON project_comments
+BEGIN
+    DELETE FROM comment_mentions
+    WHERE comment_id = NEW.id
+      AND person_id NOT IN (
+          SELECT json_extract(t.value, '$.attrs.id')
+          FROM json_tree(CASE WHEN json_valid(NEW.content) THEN NEW.content ELSE '{}' END) t
+          WHERE t.type = 'object'
+            AND json_extract(t.value, '$.type') = 'mention'
+            AND json_extract(t.value, '$.attrs.id') IS NOT NULL
+      );
+    UPDATE comment_mentions SET project_id = NEW.project_id WHERE comment_id = NEW.id;
+    INSERT OR IGNORE INTO comment_mentions (comment_id, person_id, project_id, mentioned_at)
+    SELECT DISTINCT NEW.id, json_extract(t.value, '$.attrs.id'), NEW.project_id, NEW.updated_at
+    FROM json_tree(CASE WHEN json_valid(NEW.content) THEN NEW.content ELSE '{}' END) t
+    WHERE t.type = 'object'
+      AND json_extract(t.value, '$.type') = 'mention'
+      AND COALESCE(json_extract(t.value, '$.attrs.id'), '') <> '';
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_comment_mentions_delete
+AFTER DELETE ON project_comments
+BEGIN
+    DELETE FROM comment_mentions WHERE comment_id = OLD.id;
+END;
+
+-- 2. Backfill existing comments.
+INSERT OR IGNORE INTO comment_mentions (comment_id, person_id, project_id, mentioned_at)
+SELECT DISTINCT c.id, json_extract(t.value, '$.attrs.id'), c.project_id, c.created_at
+FROM project_comments c,
+     json_tree(CASE WHEN json_valid(c.content) THEN c.content ELSE '{}' END) t
+WHERE t.type = 'object'
+  AND json_extract(t.value, '$.type') = 'mention'
+  AND COALESCE(json_extract(t.value, '$.attrs.id'), '') <> '';
+
+-- 3. notifications: allow kind MENTIONED and record who was mentioned (NULL for project reminders).
+--    SQLite cannot alter a CHECK constraint, so the table is rebuilt.
+CREATE TABLE notifications_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL CHECK (kind IN ('DUE_SOON','OVERDUE','BLOCKED_TOO_LONG','MENTIONED')),
+    project_id TEXT NOT NULL,
+    person_id TEXT NULL,
+    dedupe_key TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    dismissed_at TEXT NULL
+);
+
+INSERT INTO notifications_new (id, kind, project_id, dedupe_key, title, message, created_at, dismissed_at)
+SELECT id, kind, project_id, dedupe_key, title, message, created_at, dismissed_at FROM notifications;
+
+DROP TABLE notifications;
+ALTER TABLE notifications_new RENAME TO notifications;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_dedupe ON notifications(dedupe_key);
+CREATE INDEX IF NOT EXISTS idx_notifications_dismissed ON notifications(dismissed_at);
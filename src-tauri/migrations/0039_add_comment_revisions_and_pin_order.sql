@@ -0,0 +1,121 @@
+-- Comment edit history and pin order.
+-- comment_revisions keeps the content a comment had before each edit (written by comment_update);
+-- like reactions it carries the comment's project_id so it follows the project (sync scope, purge).
+-- pin_order orders pinned comments (ascending); NULL for unpinned comments and for pins made before
+-- this migration, which sort as 0 and then newest first.
+
+ALTER TABLE project_comments ADD COLUMN pin_order INTEGER NULL;
+
+-- 1. Recreate project_comments insert/update sync triggers to include pin_order.
+DROP TRIGGER IF EXISTS trk_project_comments_insert;
+DROP TRIGGER IF EXISTS trk_project_comments_update;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_insert
+AFTER INSERT ON project_comments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_comments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_comments', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'person_id',NEW.person_id,'parent_comment_id',NEW.parent_comment_id,'content',NEW.content,'is_pinned',NEW.is_pinned,'pin_order',NEW.pin_order,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_project_comments_update
+AFTER UPDATE ON project_comments
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::project_comments'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'project_comments', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'person_id',NEW.person_id,'parent_comment_id',NEW.parent_comment_id,'content',NEW.content,'is_pinned',NEW.is_pinned,'pin_order',NEW.pin_order,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+-- 2. comment_revisions: one row per edit, holding the replaced content.
+CREATE TABLE IF NOT EXISTS comment_revisions (
+    id TEXT PRIMARY KEY,
+    comment_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    person_id TEXT NULL,
+    content TEXT NOT NULL,
+    written_at TEXT NOT NULL,
+    replaced_at TEXT NOT NULL,
+    _version INTEGER DEFAULT 1,
+    FOREIGN KEY(comment_id) REFERENCES project_comments(id),
+    FOREIGN KEY(project_id) REFERENCES projects(id),
+    FOREIGN KEY(person_id) REFERENCES persons(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_comment_revisions_comment ON comment_revisions(comment_id, replaced_at);
+CREATE INDEX IF NOT EXISTS idx_comment_revisions_project ON comment_revisions(project_id);
+
+-- 3. Sync triggers for comment_revisions
+CREATE TRIGGER IF NOT EXISTS trk_comment_revisions_insert
+AFTER INSERT ON comment_revisions
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::comment_revisions'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'comment_revisions', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'comment_id',NEW.comment_id,'project_id',NEW.project_id,'person_id',NEW.person_id,'content',NEW.content,'written_at',NEW.written_at,'replaced_at',NEW.replaced_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_comment_revisions_update
+AFTER UPDATE ON comment_revisions
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::comment_revisions'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'comment_revisions', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'comment_id',NEW.comment_id,'project_id',NEW.project_id,'person_id',NEW.person_id,'content',NEW.content,'written_at',NEW.written_at,'replaced_at',NEW.replaced_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_comment_revisions_delete
+AFTER DELETE ON comment_revisions
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::comment_revisions'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'comment_revisions', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- 4. Delete tombstones (stale remote upserts must not resurrect deleted revisions)
+CREATE TRIGGER IF NOT EXISTS trk_comment_revisions_tombstone
+AFTER DELETE ON comment_revisions
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('comment_revisions', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_comment_revisions_untombstone
+AFTER INSERT ON comment_revisions
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'comment_revisions' AND record_id = NEW.id;
+END;
@@ -0,0 +1,79 @@
+-- Project checklists: lightweight to-dos that sit between "no tasks" and the status machine.
+-- sort_order is 1..n within the project (rewritten by reorder); done_at is set when ticked.
+
+CREATE TABLE IF NOT EXISTS checklist_items (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    text TEXT NOT NULL,
+    done INTEGER NOT NULL DEFAULT 0,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    done_at TEXT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    _version INTEGER DEFAULT 1,
+    FOREIGN KEY(project_id) REFERENCES projects(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_checklist_items_project ON checklist_items(project_id, sort_order);
+
+-- Sync triggers for checklist_items
+CREATE TRIGGER IF NOT EXISTS trk_checklist_items_insert
+AFTER INSERT ON checklist_items
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::checklist_items'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'checklist_items', NEW.id, 'INSERT',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'text',NEW.text,'done',NEW.done,'sort_order',NEW.sort_order,'done_at',NEW.done_at,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_checklist_items_update
+AFTER UPDATE ON checklist_items
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::checklist_items'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'checklist_items', NEW.id, 'UPDATE',
+        json_object('id',NEW.id,'project_id',NEW.project_id,'text',NEW.text,'done',NEW.done,'sort_order',NEW.sort_order,'done_at',NEW.done_at,'created_at',NEW.created_at,'updated_at',NEW.updated_at,'_version',NEW._version),
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        NEW._version, datetime('now'), 0
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_checklist_items_delete
+AFTER DELETE ON checklist_items
+WHEN (SELECT value FROM sync_config WHERE key = 'sync_enabled') = '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'sync_table_disabled::checklist_items'), '0') <> '1'
+  AND COALESCE((SELECT value FROM sync_config WHERE key = 'read_only_replica'), '0') <> '1'
+BEGIN
+    INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+    VALUES (
+        'checklist_items', OLD.id, 'DELETE', NULL,
+        (SELECT value FROM sync_config WHERE key = 'device_id'),
+        OLD._version, datetime('now'), 0
+    );
+END;
+
+-- Delete tombstones (stale remote upserts must not resurrect deleted items)
+CREATE TRIGGER IF NOT EXISTS trk_checklist_items_tombstone
+AFTER DELETE ON checklist_items
+BEGIN
+    INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+    VALUES ('checklist_items', OLD.id, datetime('now'), OLD._version)
+    ON CONFLICT(table_name, record_id) DO UPDATE SET
+        deleted_at = excluded.deleted_at,
+        version = MAX(version, excluded.version);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trk_checklist_items_untombstone
+AFTER INSERT ON checklist_items
+BEGIN
+    DELETE FROM sync_tombstones WHERE table_name = 'checklist_items' AND record_id = NEW.id;
+END;
@@ -0,0 +1,30 @@
+-- Configurable status workflows (local to this device, like templates and saved views).
+-- statuses: JSON array of {"key","label"} in board order; transitions: JSON array of
+-- {"from","to","noteRequired"}. Exactly one workflow is active; project_change_status checks it.
+-- The built-in state machine is seeded as the active 'default' workflow.
+
+CREATE TABLE IF NOT EXISTS workflow_definitions (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    statuses TEXT NOT NULL,
+    transitions TEXT NOT NULL,
+    is_active INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflow_definitions_name
+    ON workflow_definitions(name COLLATE NOCASE);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflow_definitions_active
+    ON workflow_definitions(is_active) WHERE is_active = 1;
+
+INSERT OR IGNORE INTO workflow_definitions (id, name, statuses, transitions, is_active, created_at, updated_at)
+VALUES (
+    'default',
+    'Default',
+    '[{"key":"BACKLOG","label":"Backlog"},{"key":"PLANNED","label":"Planned"},{"key":"IN_PROGRESS","label":"In progress"},{"key":"BLOCKED","label":"Blocked"},{"key":"DONE","label":"Done"},{"key":"ARCHIVED","label":"Archived"}]',
+    '[{"from":"BACKLOG","to":"PLANNED","noteRequired":false},{"from":"BACKLOG","to":"ARCHIVED","noteRequired":true},{"from":"PLANNED","to":"IN_PROGRESS","noteRequired":false},{"from":"PLANNED","to":"ARCHIVED","noteRequired":true},{"from":"IN_PROGRESS","to":"BLOCKED","noteRequired":false},{"from":"IN_PROGRESS","to":"DONE","noteRequired":false},{"from":"BLOCKED","to":"IN_PROGRESS","noteRequired":false},{"from":"DONE","to":"IN_PROGRESS","noteRequired":true},{"from":"DONE","to":"ARCHIVED","noteRequired":false},{"from":"ARCHIVED","to":"BACKLOG","noteRequired":true}]',
+    1,
+    strftime('%Y-%m-%dT%H:%M:%SZ', 'now'),
+    strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+);
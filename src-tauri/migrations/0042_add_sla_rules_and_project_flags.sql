@@ -0,0 +1,51 @@
+-- SLA / staleness rules ("BLOCKED for more than 14 days", "no status change in 30 days") and the
+-- project flags the notification checker derives from them. Both are local to this device, like
+-- notifications: not tracked by sync triggers (every device evaluates its own rules).
+
+-- 1. Rules. status NULL = any open status (not DONE / ARCHIVED).
+CREATE TABLE IF NOT EXISTS sla_rules (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    status TEXT NULL,
+    max_days INTEGER NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sla_rules_name ON sla_rules(name COLLATE NOCASE);
+
+-- 2. Flags: one per (project, rule) while the project breaks the rule. since = when the project
+--    entered its current status; a new status period replaces the flag.
+CREATE TABLE IF NOT EXISTS project_flags (
+    project_id TEXT NOT NULL,
+    rule_id TEXT NOT NULL,
+    since TEXT NOT NULL,
+    flagged_at TEXT NOT NULL,
+    PRIMARY KEY (project_id, rule_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_flags_rule ON project_flags(rule_id);
+
+-- 3. notifications: allow kind SLA_BREACHED.
+--    SQLite cannot alter a CHECK constraint, so the table is rebuilt.
+CREATE TABLE notifications_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL CHECK (kind IN ('DUE_SOON','OVERDUE','BLOCKED_TOO_LONG','MENTIONED','SLA_BREACHED')),
+    project_id TEXT NOT NULL,
+    person_id TEXT NULL,
+    dedupe_key TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    dismissed_at TEXT NULL
+);
+
+INSERT INTO notifications_new (id, kind, project_id, person_id, dedupe_key, title, message, created_at, dismissed_at)
+SELECT id, kind, project_id, person_id, dedupe_key, title, message, created_at, dismissed_at FROM notifications;
+
+DROP TABLE notifications;
+ALTER TABLE notifications_new RENAME TO notifications;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_dedupe ON notifications(dedupe_key);
+CREATE INDEX IF NOT EXISTS idx_notifications_dismissed ON notifications(dismissed_at);
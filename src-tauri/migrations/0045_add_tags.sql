@@ -0,0 +1,12 @@
+-- Tag catalog: optional color and description per tag name. Tags themselves stay plain strings on
+-- project_tags (a tag without a catalog row is still listed, just without a color). Local to this
+-- device, like templates and saved views: not tracked by sync triggers. Renames and merges rewrite
+-- project_tags, which syncs as usual.
+
+CREATE TABLE IF NOT EXISTS tags (
+    name TEXT PRIMARY KEY,
+    color TEXT NULL,              -- #rrggbb
+    description TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
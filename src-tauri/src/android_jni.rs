@@ -40,12 +40,22 @@ fn get_or_init_pool() -> Option<DbPool> {
 fn init_pool_for_android() -> Option<DbPool> {
     use crate::infra::init_db;
 
-    let base = dirs::data_dir()?;
-    let data_dir = base.join("com.nickdu.projex").join("default");
+    let data_dir = crate::android_profile_data_dir();
+    let legacy_dir = crate::resolve_legacy_default_dir(&crate::app_data_dir());
+    let migration = match crate::infra::layout::migrate_legacy_layout(&legacy_dir, &data_dir) {
+        Ok(migration) => migration,
+        Err(e) => {
+            log::error!("[android_sync] legacy data dir migration failed: {}", e);
+            return None;
+        }
+    };
     std::fs::create_dir_all(&data_dir).ok()?;
-    let db_path = data_dir.join("projex.db");
+    let db_path = data_dir.join("app.db");
 
     let pool = init_db(&db_path).ok()?;
+    if let Some(migration) = &migration {
+        crate::record_layout_migration(&pool, migration);
+    }
     let mut guard = ANDROID_POOL.get_or_init(|| Mutex::new(None)).lock().ok()?;
     *guard = Some(pool.clone());
     Some(pool)
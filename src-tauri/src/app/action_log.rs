@@ -0,0 +1,488 @@
+//! Undo/redo journal (`action_log`) for reversible edits.
+//!
+//! - 记录的操作：项目更新、状态变更、结束成员、删除评论。用例在同一事务内于修改前后各抓取一次受影响的行，
+//!   差异写入 `undo_payload`（修改前的行）与 `redo_payload`（修改后的行）。
+//! - `undo_last`：撤销最近一条未撤销的记录；`redo_last`：重做最近撤销的一条。新的操作会清空重做栈。
+//! - 撤销 / 重做前检查当前行仍与记录一致（忽略 `updated_at` / `_version`），否则返回
+//!   `CONFLICT`（`UNDO_STALE:`），避免覆盖之后的修改（包括同步下来的修改）。
+//! - 写回时刷新 `updated_at`、递增 `_version`，因此撤销 / 重做像普通编辑一样同步到其它设备。
+//! - 每个 profile 有独立数据库，日志天然按 profile 隔离；仅保留最近 `ACTION_LOG_MAX_ENTRIES` 条。
+
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use chrono::Utc;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Journal entries kept (undone ones included).
+pub const ACTION_LOG_MAX_ENTRIES: i64 = 100;
+
+/// Columns refreshed on every write-back and ignored when checking for later edits.
+const VOLATILE_COLUMNS: &[&str] = &["updated_at", "_version"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    ProjectUpdate,
+    ProjectStatus,
+    AssignmentEnd,
+    CommentDelete,
+}
+
+impl ActionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ProjectUpdate => "project_update",
+            Self::ProjectStatus => "project_status",
+            Self::AssignmentEnd => "assignment_end",
+            Self::CommentDelete => "comment_delete",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "project_update" => Some(Self::ProjectUpdate),
+            "project_status" => Some(Self::ProjectStatus),
+            "assignment_end" => Some(Self::AssignmentEnd),
+            "comment_delete" => Some(Self::CommentDelete),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionLogEntryDto {
+    pub id: i64,
+    pub action: ActionKind,
+    pub entity_id: String,
+    pub created_at: String,
+    pub undone_at: Option<String>,
+}
+
+/// Rows of one table touched by an action, selected by `filter_column = filter_value`.
+pub(crate) struct JournalScope {
+    table: &'static str,
+    key_columns: &'static [&'static str],
+    filter_column: &'static str,
+    filter_value: String,
+}
+
+impl JournalScope {
+    pub(crate) fn new(
+        table: &'static str,
+        key_columns: &'static [&'static str],
+        filter_column: &'static str,
+        filter_value: &str,
+    ) -> Self {
+        Self {
+            table,
+            key_columns,
+            filter_column,
+            filter_value: filter_value.to_string(),
+        }
+    }
+}
+
+/// Row images captured before an action.
+pub(crate) struct JournalCapture(Vec<Vec<Map<String, Value>>>);
+
+/// One row to write back: `row = None` means the row must not exist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RowImage {
+    table: String,
+    key: Map<String, Value>,
+    row: Option<Map<String, Value>>,
+}
+
+/// Capture the rows an action is about to change.
+pub(crate) fn journal_capture(
+    conn: &Connection,
+    scopes: &[JournalScope],
+) -> Result<JournalCapture, AppError> {
+    let mut images = Vec::with_capacity(scopes.len());
+    for scope in scopes {
+        images.push(select_rows(
+            conn,
+            scope.table,
+            &[(
+                scope.filter_column,
+                Value::String(scope.filter_value.clone()),
+            )],
+        )?);
+    }
+    Ok(JournalCapture(images))
+}
+
+/// Diff `before` with the current rows and journal the action (no-op if nothing changed).
+/// Starts a new history branch: the redo stack is cleared.
+pub(crate) fn journal_record(
+    conn: &Connection,
+    action: ActionKind,
+    entity_id: &str,
+    scopes: &[JournalScope],
+    before: JournalCapture,
+) -> Result<(), AppError> {
+    let after = journal_capture(conn, scopes)?;
+    let mut undo = Vec::new();
+    let mut redo = Vec::new();
+    for ((scope, before_rows), after_rows) in scopes.iter().zip(before.0).zip(after.0) {
+        let key_of = |row: &Map<String, Value>| -> Map<String, Value> {
+            scope
+                .key_columns
+                .iter()
+                .map(|col| {
+                    (
+                        col.to_string(),
+                        row.get(*col).cloned().unwrap_or(Value::Null),
+                    )
+                })
+                .collect()
+        };
+        let mut keys: Vec<Map<String, Value>> = before_rows.iter().map(&key_of).collect();
+        for row in &after_rows {
+            let key = key_of(row);
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        for key in keys {
+            let old = before_rows.iter().find(|row| key_of(row) == key).cloned();
+            let new = after_rows.iter().find(|row| key_of(row) == key).cloned();
+            if old == new {
+                continue;
+            }
+            undo.push(RowImage {
+                table: scope.table.to_string(),
+                key: key.clone(),
+                row: old,
+            });
+            redo.push(RowImage {
+                table: scope.table.to_string(),
+                key,
+                row: new,
+            });
+        }
+    }
+    if undo.is_empty() {
+        return Ok(());
+    }
+
+    conn.execute("DELETE FROM action_log WHERE undone_at IS NOT NULL", [])?;
+    conn.execute(
+        "INSERT INTO action_log (action, entity_id, undo_payload, redo_payload, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            action.as_str(),
+            entity_id,
+            to_json(&undo)?,
+            to_json(&redo)?,
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    conn.execute(
+        "DELETE FROM action_log WHERE id NOT IN (SELECT id FROM action_log ORDER BY id DESC LIMIT ?1)",
+        [ACTION_LOG_MAX_ENTRIES],
+    )?;
+    Ok(())
+}
+
+/// Revert the most recent action still in effect. `None` when there is nothing to undo.
+pub fn undo_last(pool: &DbPool) -> Result<Option<ActionLogEntryDto>, AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let Some((entry, undo, redo)) = load_entry(
+        &tx,
+        "SELECT id, action, entity_id, created_at, undone_at, undo_payload, redo_payload
+         FROM action_log WHERE undone_at IS NULL ORDER BY id DESC LIMIT 1",
+    )?
+    else {
+        return Ok(None);
+    };
+
+    // Undo runs backwards: children are restored before their parents are touched.
+    let now = Utc::now().to_rfc3339();
+    apply_images(&tx, redo.iter().rev(), undo.iter().rev(), &now)?;
+    tx.execute(
+        "UPDATE action_log SET undone_at = ?1 WHERE id = ?2",
+        params![now, entry.id],
+    )?;
+    tx.commit()?;
+    Ok(Some(ActionLogEntryDto {
+        undone_at: Some(now),
+        ..entry
+    }))
+}
+
+/// Re-apply the most recently undone action. `None` when the redo stack is empty.
+pub fn redo_last(pool: &DbPool) -> Result<Option<ActionLogEntryDto>, AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let Some((entry, undo, redo)) = load_entry(
+        &tx,
+        "SELECT id, action, entity_id, created_at, undone_at, undo_payload, redo_payload
+         FROM action_log WHERE undone_at IS NOT NULL ORDER BY undone_at DESC, id ASC LIMIT 1",
+    )?
+    else {
+        return Ok(None);
+    };
+
+    let now = Utc::now().to_rfc3339();
+    apply_images(&tx, undo.iter(), redo.iter(), &now)?;
+    tx.execute(
+        "UPDATE action_log SET undone_at = NULL WHERE id = ?1",
+        [entry.id],
+    )?;
+    tx.commit()?;
+    Ok(Some(ActionLogEntryDto {
+        undone_at: None,
+        ..entry
+    }))
+}
+
+type LoadedEntry = (ActionLogEntryDto, Vec<RowImage>, Vec<RowImage>);
+
+fn load_entry(conn: &Connection, sql: &str) -> Result<Option<LoadedEntry>, AppError> {
+    let row = conn
+        .query_row(sql, [], |r| {
+            Ok((
+                r.get::<_, i64>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, String>(5)?,
+                r.get::<_, String>(6)?,
+            ))
+        })
+        .optional()?;
+    let Some((id, action, entity_id, created_at, undone_at, undo, redo)) = row else {
+        return Ok(None);
+    };
+    let action = ActionKind::parse(&action)
+        .ok_or_else(|| AppError::Db(format!("unknown journal action: {}", action)))?;
+    Ok(Some((
+        ActionLogEntryDto {
+            id,
+            action,
+            entity_id,
+            created_at,
+            undone_at,
+        },
+        from_json(&undo)?,
+        from_json(&redo)?,
+    )))
+}
+
+/// Check every row still matches `expected`, then write `target`.
+fn apply_images<'a>(
+    conn: &Connection,
+    expected: impl Iterator<Item = &'a RowImage> + Clone,
+    target: impl Iterator<Item = &'a RowImage>,
+    now: &str,
+) -> Result<(), AppError> {
+    for image in expected.clone() {
+        let current = current_row(conn, image)?;
+        if !same_row(current.as_ref(), image.row.as_ref()) {
+            return Err(AppError::Conflict(format!(
+                "UNDO_STALE: {} {} was changed after this action",
+                image.table,
+                key_label(&image.key)
+            )));
+        }
+    }
+    for image in target {
+        write_row(conn, image, now)?;
+    }
+    Ok(())
+}
+
+fn current_row(
+    conn: &Connection,
+    image: &RowImage,
+) -> Result<Option<Map<String, Value>>, AppError> {
+    let filters: Vec<(&str, Value)> = image
+        .key
+        .iter()
+        .map(|(col, value)| (col.as_str(), value.clone()))
+        .collect();
+    Ok(select_rows(conn, &image.table, &filters)?
+        .into_iter()
+        .next())
+}
+
+fn same_row(a: Option<&Map<String, Value>>, b: Option<&Map<String, Value>>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => {
+            let stable = |row: &Map<String, Value>| -> Map<String, Value> {
+                row.iter()
+                    .filter(|(col, _)| !VOLATILE_COLUMNS.contains(&col.as_str()))
+                    .map(|(col, value)| (col.clone(), value.clone()))
+                    .collect()
+            };
+            stable(a) == stable(b)
+        }
+        _ => false,
+    }
+}
+
+fn write_row(conn: &Connection, image: &RowImage, now: &str) -> Result<(), AppError> {
+    let current = current_row(conn, image)?;
+    let (where_sql, mut values) = key_filter(&image.key, 1);
+    match (&image.row, current) {
+        (None, None) => {}
+        (None, Some(_)) => {
+            conn.execute(
+                &format!("DELETE FROM {} WHERE {}", image.table, where_sql),
+                params_from_iter(values),
+            )?;
+        }
+        (Some(row), Some(current)) => {
+            let mut sets = Vec::new();
+            let mut set_values = Vec::new();
+            for (col, value) in row {
+                if image.key.contains_key(col) || VOLATILE_COLUMNS.contains(&col.as_str()) {
+                    continue;
+                }
+                set_values.push(to_sql(value));
+                sets.push(format!("{} = ?{}", col, set_values.len()));
+            }
+            if current.contains_key("updated_at") {
+                set_values.push(SqlValue::Text(now.to_string()));
+                sets.push(format!("updated_at = ?{}", set_values.len()));
+            }
+            if current.contains_key("_version") {
+                sets.push("_version = COALESCE(_version, 0) + 1".to_string());
+            }
+            if sets.is_empty() {
+                return Ok(());
+            }
+            let (where_sql, key_values) = key_filter(&image.key, set_values.len() + 1);
+            set_values.extend(key_values);
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET {} WHERE {}",
+                    image.table,
+                    sets.join(", "),
+                    where_sql
+                ),
+                params_from_iter(set_values),
+            )?;
+        }
+        (Some(row), None) => {
+            // Re-inserted rows get a higher version than their delete tombstone.
+            let mut cols = Vec::new();
+            values.clear();
+            for (col, value) in row {
+                cols.push(col.as_str());
+                values.push(match col.as_str() {
+                    "updated_at" => SqlValue::Text(now.to_string()),
+                    "_version" => SqlValue::Integer(value.as_i64().unwrap_or(0) + 1),
+                    _ => to_sql(value),
+                });
+            }
+            let placeholders: Vec<String> = (1..=cols.len()).map(|i| format!("?{}", i)).collect();
+            conn.execute(
+                &format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    image.table,
+                    cols.join(", "),
+                    placeholders.join(", ")
+                ),
+                params_from_iter(values),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn key_filter(key: &Map<String, Value>, first_param: usize) -> (String, Vec<SqlValue>) {
+    let clauses: Vec<String> = key
+        .keys()
+        .enumerate()
+        .map(|(i, col)| format!("{} = ?{}", col, first_param + i))
+        .collect();
+    (clauses.join(" AND "), key.values().map(to_sql).collect())
+}
+
+fn key_label(key: &Map<String, Value>) -> String {
+    key.values()
+        .map(|v| match v {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// All columns of the rows matching every `column = value` filter.
+fn select_rows(
+    conn: &Connection,
+    table: &str,
+    filters: &[(&str, Value)],
+) -> Result<Vec<Map<String, Value>>, AppError> {
+    let clauses: Vec<String> = filters
+        .iter()
+        .enumerate()
+        .map(|(i, (col, _))| format!("{} = ?{}", col, i + 1))
+        .collect();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT * FROM {} WHERE {} ORDER BY rowid",
+        table,
+        clauses.join(" AND ")
+    ))?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let rows = stmt
+        .query_map(
+            params_from_iter(filters.iter().map(|(_, v)| to_sql(v))),
+            |r| {
+                let mut row = Map::new();
+                for (i, col) in columns.iter().enumerate() {
+                    row.insert(col.clone(), from_sql(r.get_ref(i)?));
+                }
+                Ok(row)
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+fn from_sql(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => Value::from(b.to_vec()),
+    }
+}
+
+fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(items) => SqlValue::Blob(
+            items
+                .iter()
+                .map(|v| v.as_u64().unwrap_or_default() as u8)
+                .collect(),
+        ),
+        Value::Object(_) => SqlValue::Text(value.to_string()),
+    }
+}
+
+fn to_json(images: &[RowImage]) -> Result<String, AppError> {
+    serde_json::to_string(images).map_err(|e| AppError::Db(e.to_string()))
+}
+
+fn from_json(payload: &str) -> Result<Vec<RowImage>, AppError> {
+    serde_json::from_str(payload).map_err(|e| AppError::Db(format!("bad journal payload: {}", e)))
+}
@@ -0,0 +1,226 @@
+//! Activity feed: one timeline over the whole workspace.
+//!
+//! 不单独维护表，而是查询时合并现有数据（UNION ALL）：
+//! - `project_created`：projects.created_at（人员取初始状态记录的操作人）
+//! - `status_change`：status_history（不含创建时的初始状态）
+//! - `comment`：project_comments.created_at
+//! - `assignment_start` / `assignment_end`：assignments.start_at / end_at
+//! - `edit`：sync_metadata 中项目 / 成员 / 合作方的 UPDATE（仅在启用同步后才有记录）
+//!
+//! 时间统一按 UTC 比较与排序（`strftime` 规范化 RFC3339 与 SQLite `datetime('now')` 两种格式）；
+//! 回收站中项目的事件不出现在时间线中。
+
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use rusqlite::params_from_iter;
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+
+pub const ACTIVITY_TYPES: &[&str] = &[
+    "project_created",
+    "status_change",
+    "comment",
+    "assignment_start",
+    "assignment_end",
+    "edit",
+];
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+const COMMENT_EXCERPT_CHARS: i64 = 200;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityListReq {
+    pub project_id: Option<String>,
+    /// Events caused by or about this person (author, changer, member, edited person).
+    pub person_id: Option<String>,
+    /// Inclusive lower bound (RFC3339 or YYYY-MM-DD, UTC).
+    pub from: Option<String>,
+    /// Exclusive upper bound (RFC3339 or YYYY-MM-DD, UTC).
+    pub to: Option<String>,
+    /// Any of `ACTIVITY_TYPES`; empty / absent = all.
+    pub types: Option<Vec<String>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityItemDto {
+    /// One of `ACTIVITY_TYPES`.
+    #[serde(rename = "type")]
+    pub activity_type: String,
+    /// Normalized UTC timestamp (`YYYY-MM-DDTHH:MM:SS.SSSZ`).
+    pub at: String,
+    pub project_id: Option<String>,
+    pub project_name: Option<String>,
+    pub person_id: Option<String>,
+    pub person_name: Option<String>,
+    /// Row behind the event: history / comment / assignment id, or the edited record.
+    pub entity_id: String,
+    /// `status_change`: "FROM → TO" (the initial status is part of `project_created`);
+    /// `comment`: content excerpt; `assignment_*`: role; `edit`: edited table.
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityPage {
+    pub items: Vec<ActivityItemDto>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Paginated workspace timeline, newest first.
+pub fn activity_list(pool: &DbPool, req: ActivityListReq) -> Result<ActivityPage, AppError> {
+    let limit = req.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = req.offset.unwrap_or(0).max(0);
+
+    let mut conditions = vec!["(ev.project_id IS NULL OR p.deleted_at IS NULL)".to_string()];
+    let mut values: Vec<Value> = vec![Value::Integer(COMMENT_EXCERPT_CHARS)];
+
+    if let Some(project_id) = non_empty(req.project_id.as_deref()) {
+        let p = bind(&mut values, project_id.to_string());
+        conditions.push(format!("ev.project_id = {}", p));
+    }
+    if let Some(person_id) = non_empty(req.person_id.as_deref()) {
+        let p = bind(&mut values, person_id.to_string());
+        conditions.push(format!("ev.person_id = {}", p));
+    }
+    if let Some(from) = non_empty(req.from.as_deref()) {
+        let p = bind(&mut values, normalize_bound(from, "from")?);
+        conditions.push(format!("ev.at >= {}", p));
+    }
+    if let Some(to) = non_empty(req.to.as_deref()) {
+        let p = bind(&mut values, normalize_bound(to, "to")?);
+        conditions.push(format!("ev.at < {}", p));
+    }
+    if let Some(types) = req.types.as_ref().filter(|t| !t.is_empty()) {
+        let mut placeholders = Vec::new();
+        for t in types {
+            let t = t.trim();
+            if !ACTIVITY_TYPES.contains(&t) {
+                return Err(AppError::Validation(format!(
+                    "unknown activity type: {} (expected one of {})",
+                    t,
+                    ACTIVITY_TYPES.join(", ")
+                )));
+            }
+            placeholders.push(bind(&mut values, t.to_string()));
+        }
+        conditions.push(format!("ev.type IN ({})", placeholders.join(", ")));
+    }
+
+    let from_sql = format!(
+        "FROM ({}) ev
+         LEFT JOIN projects p ON p.id = ev.project_id
+         LEFT JOIN persons pe ON pe.id = ev.person_id
+         WHERE {}",
+        EVENTS_SQL,
+        conditions.join(" AND ")
+    );
+
+    let conn = get_read_connection(pool);
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) {}", from_sql),
+        params_from_iter(values.iter()),
+        |r| r.get(0),
+    )?;
+
+    let limit_p = format!("?{}", values.len() + 1);
+    let offset_p = format!("?{}", values.len() + 2);
+    values.push(Value::Integer(limit));
+    values.push(Value::Integer(offset));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT ev.type, ev.at, ev.project_id, p.name, ev.person_id, pe.display_name, ev.entity_id, ev.detail
+         {}
+         ORDER BY ev.at DESC, ev.type, ev.entity_id
+         LIMIT {} OFFSET {}",
+        from_sql, limit_p, offset_p
+    ))?;
+    let items = stmt
+        .query_map(params_from_iter(values.iter()), |r| {
+            Ok(ActivityItemDto {
+                activity_type: r.get(0)?,
+                at: r.get(1)?,
+                project_id: r.get(2)?,
+                project_name: r.get(3)?,
+                person_id: r.get(4)?,
+                person_name: r.get(5)?,
+                entity_id: r.get(6)?,
+                detail: r.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ActivityPage {
+        items,
+        total,
+        limit,
+        offset,
+    })
+}
+
+/// All events; `?1` is the comment excerpt length.
+const EVENTS_SQL: &str = "
+    SELECT 'project_created' AS type, COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at) AS at,
+           id AS project_id,
+           (SELECT h.changed_by_person_id FROM status_history h
+            WHERE h.project_id = projects.id AND h.from_status IS NULL
+            ORDER BY h.changed_at LIMIT 1) AS person_id,
+           id AS entity_id, NULL AS detail
+    FROM projects
+    UNION ALL
+    SELECT 'status_change', COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', changed_at), changed_at),
+           project_id, changed_by_person_id, id, from_status || ' → ' || to_status
+    FROM status_history WHERE from_status IS NOT NULL
+    UNION ALL
+    SELECT 'comment', COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at),
+           project_id, person_id, id, substr(content, 1, ?1)
+    FROM project_comments
+    UNION ALL
+    SELECT 'assignment_start', COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', start_at), start_at),
+           project_id, person_id, id, role
+    FROM assignments
+    UNION ALL
+    SELECT 'assignment_end', COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', end_at), end_at),
+           project_id, person_id, id, role
+    FROM assignments WHERE end_at IS NOT NULL
+    UNION ALL
+    SELECT 'edit', COALESCE(strftime('%Y-%m-%dT%H:%M:%fZ', created_at), created_at),
+           CASE WHEN table_name = 'projects' THEN record_id END,
+           CASE WHEN table_name = 'persons' THEN record_id END,
+           record_id, table_name
+    FROM sync_metadata
+    WHERE operation = 'UPDATE' AND table_name IN ('projects', 'persons', 'partners')";
+
+/// Append a text parameter and return its placeholder.
+fn bind(values: &mut Vec<Value>, value: String) -> String {
+    values.push(Value::Text(value));
+    format!("?{}", values.len())
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Normalize a date or RFC3339 bound to the feed's UTC timestamp format.
+fn normalize_bound(value: &str, field: &str) -> Result<String, AppError> {
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(format!("{}T00:00:00.000Z", date.format("%Y-%m-%d")));
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|at| {
+            at.with_timezone(&chrono::Utc)
+                .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+                .to_string()
+        })
+        .map_err(|_| {
+            AppError::Validation(format!(
+                "{} must be YYYY-MM-DD or an RFC3339 timestamp",
+                field
+            ))
+        })
+}
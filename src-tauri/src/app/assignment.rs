@@ -1,10 +1,13 @@
-//! Assignment use cases: add member, end member, list by project.
+//! Assignment use cases: add member, end member, list by project;
+//! archive / unarchive automation for a project's active assignments.
 
+use crate::app::action_log::{journal_capture, journal_record, ActionKind, JournalScope};
+use crate::app::integrity::{ensure_person_usable, ensure_project_open, load_strict_fk_enabled};
 use crate::error::AppError;
-use crate::infra::get_connection;
 use crate::infra::DbPool;
+use crate::infra::{get_connection, get_read_connection};
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -48,6 +51,10 @@ pub fn assignment_add_member(pool: &DbPool, req: AssignmentAddReq) -> Result<(),
         .to_string();
 
     let conn = get_connection(pool);
+    if load_strict_fk_enabled(&conn) {
+        ensure_project_open(&conn, &req.project_id)?;
+        ensure_person_usable(&conn, &req.person_id)?;
+    }
     let has_active: i32 = conn
         .query_row(
             "SELECT COUNT(1) FROM assignments WHERE project_id = ?1 AND person_id = ?2 AND end_at IS NULL",
@@ -77,7 +84,18 @@ pub fn assignment_end_member(pool: &DbPool, req: AssignmentEndReq) -> Result<(),
         .unwrap_or(&now);
 
     let conn = get_connection(pool);
-    let changed = conn
+    if load_strict_fk_enabled(&conn) {
+        ensure_project_open(&conn, &req.project_id)?;
+    }
+    let tx = conn.unchecked_transaction()?;
+    let scopes = [JournalScope::new(
+        "assignments",
+        &["id"],
+        "project_id",
+        &req.project_id,
+    )];
+    let before = journal_capture(&tx, &scopes)?;
+    let changed = tx
         .execute(
             "UPDATE assignments SET end_at = ?1 WHERE project_id = ?2 AND person_id = ?3 AND end_at IS NULL",
             params![end_at, &req.project_id, &req.person_id],
@@ -86,14 +104,60 @@ pub fn assignment_end_member(pool: &DbPool, req: AssignmentEndReq) -> Result<(),
     if changed == 0 {
         return Err(AppError::AssignmentNotActive);
     }
+    journal_record(
+        &tx,
+        ActionKind::AssignmentEnd,
+        &req.project_id,
+        &scopes,
+        before,
+    )?;
+    tx.commit()?;
     Ok(())
 }
 
+/// End every active assignment of `project_id` at `archived_at` (same tx as the archive).
+/// Returns the number of assignments ended.
+pub(crate) fn end_assignments_on_archive(
+    conn: &Connection,
+    project_id: &str,
+    archived_at: &str,
+) -> Result<usize, AppError> {
+    Ok(conn.execute(
+        "UPDATE assignments SET end_at = ?1, _version = _version + 1
+         WHERE project_id = ?2 AND end_at IS NULL",
+        params![archived_at, project_id],
+    )?)
+}
+
+/// Best-effort reverse of `end_assignments_on_archive` on unarchive: reopen assignments that
+/// ended exactly at the archive time, unless the person already has a new active assignment
+/// on the project or has been deactivated. Returns the number of assignments reopened.
+pub(crate) fn restore_assignments_on_unarchive(
+    conn: &Connection,
+    project_id: &str,
+    archived_at: &str,
+) -> Result<usize, AppError> {
+    Ok(conn.execute(
+        "UPDATE assignments SET end_at = NULL, _version = _version + 1
+         WHERE project_id = ?1 AND end_at = ?2
+           AND NOT EXISTS (
+               SELECT 1 FROM assignments a2
+               WHERE a2.project_id = assignments.project_id
+                 AND a2.person_id = assignments.person_id
+                 AND a2.end_at IS NULL
+           )
+           AND EXISTS (
+               SELECT 1 FROM persons p WHERE p.id = assignments.person_id AND p.is_active = 1
+           )",
+        params![project_id, archived_at],
+    )?)
+}
+
 pub fn assignment_list_by_project(
     pool: &DbPool,
     project_id: &str,
 ) -> Result<Vec<AssignmentItemDto>, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     let mut stmt = conn
         .prepare(
             "SELECT a.id, a.project_id, a.person_id, COALESCE(p.display_name, '?') AS person_name, \
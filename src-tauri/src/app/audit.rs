@@ -0,0 +1,248 @@
+//! Field-level audit log (`audit_log`) for project / person / partner updates.
+//!
+//! 用例在同一事务内于修改前后各抓取一次实体行（项目另含标签，作为 `tags` 字段，按字母序逗号分隔），
+//! 每个变化的字段写一条记录：字段名、旧值、新值、操作人、时间。`updated_at` / `_version` 等随每次写入变化的列不记录。
+//! 值统一存为文本（NULL 保持 NULL）。本地表，不参与同步；删除实体不会清除其审计记录。
+
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const AUDIT_ENTITY_TYPES: &[&str] = &["project", "person", "partner"];
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+/// Columns that change on every write (or never) and carry no audit value.
+const SKIPPED_COLUMNS: &[&str] = &["id", "created_at", "updated_at", "_version"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuditEntity {
+    Project,
+    Person,
+    Partner,
+}
+
+impl AuditEntity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Project => "project",
+            Self::Person => "person",
+            Self::Partner => "partner",
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Project => "projects",
+            Self::Person => "persons",
+            Self::Partner => "partners",
+        }
+    }
+}
+
+/// Field values of one entity captured before an update (`None` = entity missing).
+pub(crate) struct AuditSnapshot(Option<BTreeMap<String, Option<String>>>);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditListReq {
+    /// One of `AUDIT_ENTITY_TYPES`; absent = all.
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntryDto {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_by_person_id: Option<String>,
+    pub changed_by_name: Option<String>,
+    pub changed_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditPage {
+    pub items: Vec<AuditEntryDto>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Capture the entity's fields before an update.
+pub(crate) fn audit_capture(
+    conn: &Connection,
+    entity: AuditEntity,
+    id: &str,
+) -> Result<AuditSnapshot, AppError> {
+    Ok(AuditSnapshot(load_fields(conn, entity, id)?))
+}
+
+/// Diff `before` with the current fields and write one audit row per changed field.
+pub(crate) fn audit_record(
+    conn: &Connection,
+    entity: AuditEntity,
+    id: &str,
+    before: AuditSnapshot,
+    changed_by: Option<&str>,
+    changed_at: &str,
+) -> Result<usize, AppError> {
+    let (Some(before), Some(after)) = (before.0, load_fields(conn, entity, id)?) else {
+        return Ok(0);
+    };
+    let changed_by = changed_by.map(str::trim).filter(|s| !s.is_empty());
+    let mut written = 0;
+    for (field, new_value) in &after {
+        let old_value = before.get(field).cloned().flatten();
+        if old_value.as_ref() == new_value.as_ref() {
+            continue;
+        }
+        conn.execute(
+            "INSERT INTO audit_log (entity_type, entity_id, field, old_value, new_value, changed_by_person_id, changed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                entity.as_str(),
+                id,
+                field,
+                old_value,
+                new_value,
+                changed_by,
+                changed_at
+            ],
+        )?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Paginated audit trail, newest first.
+pub fn audit_list(pool: &DbPool, req: AuditListReq) -> Result<AuditPage, AppError> {
+    let limit = req.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = req.offset.unwrap_or(0).max(0);
+
+    let mut conditions = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    if let Some(entity_type) = non_empty(req.entity_type.as_deref()) {
+        if !AUDIT_ENTITY_TYPES.contains(&entity_type) {
+            return Err(AppError::Validation(format!(
+                "unknown entity type: {} (expected one of {})",
+                entity_type,
+                AUDIT_ENTITY_TYPES.join(", ")
+            )));
+        }
+        values.push(Value::Text(entity_type.to_string()));
+        conditions.push(format!("a.entity_type = ?{}", values.len()));
+    }
+    if let Some(entity_id) = non_empty(req.entity_id.as_deref()) {
+        values.push(Value::Text(entity_id.to_string()));
+        conditions.push(format!("a.entity_id = ?{}", values.len()));
+    }
+    let where_sql = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+
+    let conn = get_read_connection(pool);
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM audit_log a {}", where_sql),
+        params_from_iter(values.iter()),
+        |r| r.get(0),
+    )?;
+
+    let limit_p = values.len() + 1;
+    values.push(Value::Integer(limit));
+    values.push(Value::Integer(offset));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT a.id, a.entity_type, a.entity_id, a.field, a.old_value, a.new_value,
+                a.changed_by_person_id, pe.display_name, a.changed_at
+         FROM audit_log a
+         LEFT JOIN persons pe ON pe.id = a.changed_by_person_id
+         {}
+         ORDER BY a.id DESC
+         LIMIT ?{} OFFSET ?{}",
+        where_sql,
+        limit_p,
+        limit_p + 1
+    ))?;
+    let items = stmt
+        .query_map(params_from_iter(values.iter()), |r| {
+            Ok(AuditEntryDto {
+                id: r.get(0)?,
+                entity_type: r.get(1)?,
+                entity_id: r.get(2)?,
+                field: r.get(3)?,
+                old_value: r.get(4)?,
+                new_value: r.get(5)?,
+                changed_by_person_id: r.get(6)?,
+                changed_by_name: r.get(7)?,
+                changed_at: r.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(AuditPage {
+        items,
+        total,
+        limit,
+        offset,
+    })
+}
+
+fn load_fields(
+    conn: &Connection,
+    entity: AuditEntity,
+    id: &str,
+) -> Result<Option<BTreeMap<String, Option<String>>>, AppError> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {} WHERE id = ?1", entity.table()))?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let fields = stmt
+        .query_row([id], |r| {
+            let mut fields = BTreeMap::new();
+            for (i, col) in columns.iter().enumerate() {
+                if !SKIPPED_COLUMNS.contains(&col.as_str()) {
+                    fields.insert(col.clone(), as_text(r.get_ref(i)?));
+                }
+            }
+            Ok(fields)
+        })
+        .optional()?;
+    let Some(mut fields) = fields else {
+        return Ok(None);
+    };
+
+    if entity == AuditEntity::Project {
+        let mut stmt =
+            conn.prepare("SELECT tag FROM project_tags WHERE project_id = ?1 ORDER BY tag")?;
+        let tags = stmt
+            .query_map([id], |r| r.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        fields.insert("tags".to_string(), Some(tags.join(", ")));
+    }
+    Ok(Some(fields))
+}
+
+fn as_text(value: ValueRef<'_>) -> Option<String> {
+    match value {
+        ValueRef::Null => None,
+        ValueRef::Integer(i) => Some(i.to_string()),
+        ValueRef::Real(f) => Some(f.to_string()),
+        ValueRef::Text(t) => Some(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => Some(format!("<{} bytes>", b.len())),
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
@@ -0,0 +1,522 @@
+//! Local DB backups: automatic before destructive operations, listing and undo.
+//!
+//! 备份写入 `<data_dir>/backups/backup-<UTC 时间>-<reason>.db.gz`（`VACUUM INTO` 生成一致副本后 gzip）。
+//! 每次写入后按 `backup_max_count`（存于 `sync_config`，默认 10）轮转，删除最旧的备份。
+//! 恢复只覆盖业务表（与快照恢复一致），恢复前会再写一份 `pre-backup-restore` 备份以便撤销。
+//! 内存数据库（测试）没有 data dir，自动备份直接跳过。
+//! 加密库的备份使用当前口令加密；更换口令后，旧口令加密的备份无法再恢复。
+//!
+//! 定时备份（与 S3 同步无关）：`backup_schedule`（off / daily / weekly）、`backup_target_dir`
+//! （用户选择的绝对路径，空 = `<data_dir>/backups`）、`last_scheduled_backup_at` 都存于 `sync_config`。
+//! 调度器（`BackupRuntime`）每小时检查一次是否到期；所有备份（自动 / 定时 / 手动）共用同一目录与轮转上限。
+
+use crate::error::AppError;
+use crate::infra::encryption::{attach_database, export_database};
+use crate::infra::{get_connection, DbPool};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+pub const BACKUP_MAX_COUNT_KEY: &str = "backup_max_count";
+pub const DEFAULT_BACKUP_MAX_COUNT: i64 = 10;
+
+pub const BACKUP_REASON_RESTORE_SNAPSHOT: &str = "pre-restore-snapshot";
+pub const BACKUP_REASON_IMPORT_JSON: &str = "pre-import-json";
+pub const BACKUP_REASON_IMPORT_PROJECTS_CSV: &str = "pre-import-projects-csv";
+pub const BACKUP_REASON_BACKUP_RESTORE: &str = "pre-backup-restore";
+pub const BACKUP_REASON_SCHEDULED: &str = "scheduled";
+pub const BACKUP_REASON_MANUAL: &str = "manual";
+
+pub const BACKUP_SCHEDULE_KEY: &str = "backup_schedule";
+pub const BACKUP_TARGET_DIR_KEY: &str = "backup_target_dir";
+pub const LAST_SCHEDULED_BACKUP_AT_KEY: &str = "last_scheduled_backup_at";
+
+const BACKUP_DIR_NAME: &str = "backups";
+const BACKUP_FILE_PREFIX: &str = "backup-";
+const BACKUP_FILE_SUFFIX: &str = ".db.gz";
+const BACKUP_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// Business tables replaced on restore, children first (delete order; insert runs reversed).
+const RESTORED_TABLES: &[&str] = &[
+    "project_custom_values",
+    "custom_field_defs",
+    "comment_reactions",
+    "comment_revisions",
+    "project_comments",
+    "checklist_items",
+    "project_size_history",
+    "status_history",
+    "assignments",
+    "project_tags",
+    "projects",
+    "persons",
+    "partners",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupSchedule {
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl BackupSchedule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "off" => Some(Self::Off),
+            "daily" => Some(Self::Daily),
+            "weekly" => Some(Self::Weekly),
+            _ => None,
+        }
+    }
+
+    fn period(self) -> Option<chrono::Duration> {
+        match self {
+            Self::Off => None,
+            Self::Daily => Some(chrono::Duration::days(1)),
+            Self::Weekly => Some(chrono::Duration::weeks(1)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupConfigDto {
+    pub schedule: BackupSchedule,
+    /// User-chosen directory; `None` means the default `<data_dir>/backups`.
+    pub target_dir: Option<String>,
+    /// Directory actually used (`None` for in-memory databases without a target dir).
+    pub effective_dir: Option<String>,
+    pub max_count: i64,
+    pub last_scheduled_at: Option<String>,
+    /// `None` when off or when no scheduled backup ran yet (due at the next check).
+    pub next_due_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupConfigReq {
+    /// If omitted, keep existing value.
+    pub schedule: Option<BackupSchedule>,
+    /// Absolute path; empty string resets to the default dir. If omitted, keep existing value.
+    pub target_dir: Option<String>,
+    /// Backups kept (>= 1). If omitted, keep existing value.
+    pub max_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfoDto {
+    pub file_name: String,
+    /// Why it was written, e.g. "pre-restore-snapshot".
+    pub reason: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRestoreReq {
+    pub file_name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRestoreResult {
+    pub restored_from: String,
+    /// Backup of the state just before this restore (undo point).
+    pub safety_backup: Option<String>,
+}
+
+/// The configured target dir, else `<data_dir>/backups`; `None` for in-memory databases
+/// without a target dir.
+pub fn backup_dir(pool: &DbPool) -> Option<PathBuf> {
+    let conn = get_connection(pool);
+    backup_dir_for(&conn)
+}
+
+fn backup_dir_for(conn: &Connection) -> Option<PathBuf> {
+    if let Some(dir) = load_config_value(conn, BACKUP_TARGET_DIR_KEY).filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let db_path = conn.path().filter(|p| !p.is_empty())?;
+    Some(Path::new(db_path).parent()?.join(BACKUP_DIR_NAME))
+}
+
+pub fn backup_get_config(pool: &DbPool) -> Result<BackupConfigDto, AppError> {
+    let conn = get_connection(pool);
+    let schedule = load_backup_schedule(&conn);
+    let last_scheduled_at = load_config_value(&conn, LAST_SCHEDULED_BACKUP_AT_KEY);
+    let next_due_at = last_scheduled_at
+        .as_deref()
+        .and_then(|last| next_scheduled_backup_at(schedule, Some(last)))
+        .map(|at| at.to_rfc3339());
+    Ok(BackupConfigDto {
+        schedule,
+        target_dir: load_config_value(&conn, BACKUP_TARGET_DIR_KEY).filter(|v| !v.is_empty()),
+        effective_dir: backup_dir_for(&conn).map(|d| d.display().to_string()),
+        max_count: load_backup_max_count(&conn),
+        last_scheduled_at,
+        next_due_at,
+    })
+}
+
+/// Validate and persist backup settings; the caller refreshes the scheduler.
+pub fn backup_update_config(
+    pool: &DbPool,
+    req: BackupConfigReq,
+) -> Result<BackupConfigDto, AppError> {
+    let target_dir = match req.target_dir.as_deref().map(str::trim) {
+        Some("") => Some(String::new()),
+        Some(raw) => {
+            let dir = PathBuf::from(raw);
+            if !dir.is_absolute() {
+                return Err(AppError::Validation(format!(
+                    "targetDir must be an absolute path: {}",
+                    raw
+                )));
+            }
+            if dir.is_file() {
+                return Err(AppError::Validation(format!(
+                    "targetDir is a file: {}",
+                    raw
+                )));
+            }
+            fs::create_dir_all(&dir)
+                .map_err(|e| AppError::Backup(format!("Failed to create {:?}: {}", dir, e)))?;
+            Some(raw.to_string())
+        }
+        None => None,
+    };
+
+    {
+        let conn = get_connection(pool);
+        let tx = conn.unchecked_transaction()?;
+        if let Some(schedule) = req.schedule {
+            set_config_value(&tx, BACKUP_SCHEDULE_KEY, schedule.as_str())?;
+        }
+        if let Some(dir) = target_dir {
+            set_config_value(&tx, BACKUP_TARGET_DIR_KEY, &dir)?;
+        }
+        if let Some(count) = req.max_count {
+            set_config_value(&tx, BACKUP_MAX_COUNT_KEY, &count.max(1).to_string())?;
+        }
+        tx.commit()?;
+    }
+    backup_get_config(pool)
+}
+
+/// Write a scheduled backup if one is due at `now`. `Ok(None)` when off or not yet due.
+pub fn backup_run_scheduled(
+    pool: &DbPool,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Option<BackupInfoDto>, AppError> {
+    {
+        let conn = get_connection(pool);
+        let schedule = load_backup_schedule(&conn);
+        let last = load_config_value(&conn, LAST_SCHEDULED_BACKUP_AT_KEY);
+        let due = next_scheduled_backup_at(schedule, last.as_deref()).is_some_and(|at| at <= now);
+        if !due {
+            return Ok(None);
+        }
+    }
+
+    let backup = backup_create(pool, BACKUP_REASON_SCHEDULED)?;
+    if backup.is_some() {
+        let conn = get_connection(pool);
+        set_config_value(&conn, LAST_SCHEDULED_BACKUP_AT_KEY, &now.to_rfc3339())?;
+    }
+    Ok(backup)
+}
+
+/// `None` when scheduling is off; the epoch when no scheduled backup ran yet (due now).
+fn next_scheduled_backup_at(
+    schedule: BackupSchedule,
+    last: Option<&str>,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    let period = schedule.period()?;
+    let last = last
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(v.trim()).ok())
+        .map(|at| at.with_timezone(&chrono::Utc));
+    Some(match last {
+        Some(at) => at + period,
+        None => chrono::DateTime::<chrono::Utc>::default(),
+    })
+}
+
+pub fn load_backup_schedule(conn: &Connection) -> BackupSchedule {
+    load_config_value(conn, BACKUP_SCHEDULE_KEY)
+        .and_then(|v| BackupSchedule::parse(&v))
+        .unwrap_or(BackupSchedule::Off)
+}
+
+fn load_config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+}
+
+fn set_config_value(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+/// Write a compressed backup and rotate old ones. `Ok(None)` when the DB has no data dir.
+pub fn backup_create(pool: &DbPool, reason: &str) -> Result<Option<BackupInfoDto>, AppError> {
+    let conn = get_connection(pool);
+    let Some(dir) = backup_dir_for(&conn) else {
+        log::info!("In-memory database, skipping {} backup", reason);
+        return Ok(None);
+    };
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::Backup(format!("Failed to create {:?}: {}", dir, e)))?;
+
+    let now = chrono::Utc::now();
+    let reason = sanitize_reason(reason);
+    let file_name = format!(
+        "{}{}-{}{}",
+        BACKUP_FILE_PREFIX,
+        now.format(BACKUP_TIME_FORMAT),
+        reason,
+        BACKUP_FILE_SUFFIX
+    );
+    let raw_path = dir.join(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let target = dir.join(&file_name);
+
+    // A consistent copy (WAL content included), encrypted like the live database.
+    let key = pool.0.key();
+    export_database(&conn, &raw_path, key.is_some(), key.as_deref())?;
+    let compressed = gzip_file(&raw_path, &target);
+    let _ = fs::remove_file(&raw_path);
+    compressed.map_err(|e| {
+        let _ = fs::remove_file(&target);
+        AppError::Backup(format!("Failed to write {}: {}", file_name, e))
+    })?;
+
+    let max_count = load_backup_max_count(&conn);
+    drop(conn);
+    rotate_backups(&dir, max_count as usize);
+
+    log::info!("Local backup written: {}", file_name);
+    let size_bytes = fs::metadata(&target).map(|m| m.len()).unwrap_or(0);
+    Ok(Some(BackupInfoDto {
+        file_name,
+        reason,
+        created_at: now.to_rfc3339(),
+        size_bytes,
+    }))
+}
+
+/// Backups on disk, newest first.
+pub fn backup_list(pool: &DbPool) -> Result<Vec<BackupInfoDto>, AppError> {
+    let Some(dir) = backup_dir(pool) else {
+        return Ok(Vec::new());
+    };
+    list_backups_in(&dir)
+}
+
+/// Replace business tables with the content of `req.file_name`.
+pub fn backup_restore(
+    pool: &DbPool,
+    req: BackupRestoreReq,
+) -> Result<BackupRestoreResult, AppError> {
+    let dir =
+        backup_dir(pool).ok_or_else(|| AppError::Backup("No data dir for this database".into()))?;
+    let file_name = req.file_name.trim();
+    if parse_backup_file_name(file_name).is_none() {
+        return Err(AppError::Validation(format!(
+            "Invalid backup file name: {}",
+            file_name
+        )));
+    }
+    let source = dir.join(file_name);
+    if !source.is_file() {
+        return Err(AppError::NotFound(format!("backup {}", file_name)));
+    }
+
+    // Decompress first: rotation after the safety backup may delete `source`.
+    let raw_path = dir.join(format!(".{}.restore", uuid::Uuid::new_v4()));
+    gunzip_file(&source, &raw_path).map_err(|e| {
+        let _ = fs::remove_file(&raw_path);
+        AppError::Backup(format!("Failed to read {}: {}", file_name, e))
+    })?;
+
+    let result = backup_create(pool, BACKUP_REASON_BACKUP_RESTORE).and_then(|safety| {
+        let conn = get_connection(pool);
+        restore_from_file(&conn, &raw_path)?;
+        Ok(BackupRestoreResult {
+            restored_from: file_name.to_string(),
+            safety_backup: safety.map(|b| b.file_name),
+        })
+    });
+    let _ = fs::remove_file(&raw_path);
+    if result.is_ok() {
+        log::info!("Restored local backup {}", file_name);
+    }
+    result
+}
+
+fn restore_from_file(conn: &Connection, raw_path: &Path) -> Result<(), AppError> {
+    // Backups taken before encryption was enabled are plaintext.
+    attach_database(conn, raw_path, "backup_src")?;
+    let result = copy_business_tables(conn);
+    let _ = conn.execute("DETACH DATABASE backup_src", []);
+    result
+}
+
+fn copy_business_tables(conn: &Connection) -> Result<(), AppError> {
+    let check: String = conn.query_row("PRAGMA backup_src.quick_check", [], |row| row.get(0))?;
+    if check != "ok" {
+        return Err(AppError::Backup(format!("Backup is corrupt: {}", check)));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    for table in RESTORED_TABLES {
+        tx.execute(&format!("DELETE FROM main.{}", table), [])?;
+    }
+    // Merge bases describe the pre-restore rows (same rule as snapshot restore).
+    tx.execute("DELETE FROM main.sync_record_base", [])?;
+    for table in RESTORED_TABLES.iter().rev() {
+        // Older backups may lack newer columns (or tables): copy the shared ones.
+        let source_columns = table_columns(&tx, "backup_src", table)?;
+        let columns: Vec<String> = table_columns(&tx, "main", table)?
+            .into_iter()
+            .filter(|c| source_columns.contains(c))
+            .collect();
+        if columns.is_empty() {
+            continue;
+        }
+        let list = columns.join(", ");
+        tx.execute(
+            &format!(
+                "INSERT INTO main.{table} ({list}) SELECT {list} FROM backup_src.{table}",
+                table = table,
+                list = list
+            ),
+            [],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn table_columns(conn: &Connection, schema: &str, table: &str) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info({})", schema, table))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns)
+}
+
+/// Configured `backup_max_count` (>= 1), default 10.
+pub fn load_backup_max_count(conn: &Connection) -> i64 {
+    load_config_value(conn, BACKUP_MAX_COUNT_KEY)
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|v| *v >= 1)
+        .unwrap_or(DEFAULT_BACKUP_MAX_COUNT)
+}
+
+fn rotate_backups(dir: &Path, max_count: usize) {
+    let Ok(backups) = list_backups_in(dir) else {
+        return;
+    };
+    for old in backups.iter().skip(max_count.max(1)) {
+        match fs::remove_file(dir.join(&old.file_name)) {
+            Ok(()) => log::info!("Rotated out local backup {}", old.file_name),
+            Err(e) => log::warn!("Failed to remove old backup {}: {}", old.file_name, e),
+        }
+    }
+}
+
+fn list_backups_in(dir: &Path) -> Result<Vec<BackupInfoDto>, AppError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::Backup(format!("Failed to read {:?}: {}", dir, e))),
+    };
+    let mut backups: Vec<BackupInfoDto> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let (created_at, reason) = parse_backup_file_name(&file_name)?;
+            Some(BackupInfoDto {
+                reason: reason.to_string(),
+                created_at: created_at.to_rfc3339(),
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                file_name,
+            })
+        })
+        .collect();
+    // The timestamp prefix sorts chronologically.
+    backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    Ok(backups)
+}
+
+/// `backup-<time>-<reason>.db.gz` -> (time, reason).
+fn parse_backup_file_name(name: &str) -> Option<(chrono::DateTime<chrono::Utc>, &str)> {
+    let stem = name
+        .strip_prefix(BACKUP_FILE_PREFIX)?
+        .strip_suffix(BACKUP_FILE_SUFFIX)?;
+    let (time, reason) = stem.split_once('-')?;
+    let time = chrono::NaiveDateTime::parse_from_str(time, BACKUP_TIME_FORMAT).ok()?;
+    let valid_reason = !reason.is_empty()
+        && reason
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid_reason.then_some((time.and_utc(), reason))
+}
+
+fn sanitize_reason(reason: &str) -> String {
+    let cleaned: String = reason
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if cleaned.is_empty() {
+        "manual".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn gzip_file(src: &Path, dst: &Path) -> io::Result<()> {
+    let mut reader = BufReader::new(File::open(src)?);
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(dst)?), Compression::default());
+    io::copy(&mut reader, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+fn gunzip_file(src: &Path, dst: &Path) -> io::Result<()> {
+    let mut decoder = GzDecoder::new(BufReader::new(File::open(src)?));
+    let mut writer = BufWriter::new(File::create(dst)?);
+    io::copy(&mut decoder, &mut writer)?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! iCalendar export of project dates.
+//!
+//! 按 `project_list` 的筛选条件（排序与分页字段忽略）导出全天事件：截止日期、开始日期，
+//! 以及模板生成的里程碑评论（"Milestones:" 开头，每行 `- 标题 (YYYY-MM-DD)`）。
+//! UID 由项目 / 评论 ID 派生，重复导出或订阅时日历客户端会更新而不是重复添加事件。
+
+use crate::app::project::{project_list_filter, ProjectListReq};
+use crate::app::template::parse_milestone_comment;
+use crate::error::AppError;
+use crate::infra::ics::{render_calendar, IcsEvent};
+use crate::infra::{get_read_connection, DbPool};
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::params_from_iter;
+
+const CALENDAR_NAME: &str = "Projex";
+const UID_DOMAIN: &str = "projex";
+
+/// All-day events for the due dates, start dates and milestones of the matching projects.
+pub fn export_ics(
+    pool: &DbPool,
+    req: ProjectListReq,
+    now: DateTime<Utc>,
+) -> Result<String, AppError> {
+    let conn = get_read_connection(pool);
+    let (where_clause, bind_values) = project_list_filter(&conn, &req)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT p.id, p.name, p.current_status, p.start_date, p.due_date,
+                COALESCE(pt.name, '?'), COALESCE(pe.display_name, '?')
+         FROM projects p
+         LEFT JOIN partners pt ON pt.id = p.partner_id
+         LEFT JOIN persons pe ON pe.id = p.owner_person_id{}
+         ORDER BY p.name COLLATE NOCASE, p.id",
+        where_clause
+    ))?;
+    let projects = stmt
+        .query_map(params_from_iter(bind_values.iter()), |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, Option<String>>(3)?,
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, String>(5)?,
+                r.get::<_, String>(6)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut comment_stmt = conn.prepare(
+        "SELECT id, content FROM project_comments
+         WHERE project_id = ?1 AND content LIKE 'Milestones:%'
+         ORDER BY created_at, id",
+    )?;
+    let mut events = Vec::new();
+    for (id, name, status, start_date, due_date, partner, owner) in projects {
+        let description = format!("Status: {}\nPartner: {}\nOwner: {}", status, partner, owner);
+        if let Some(date) = parse_date(start_date.as_deref()) {
+            events.push(IcsEvent {
+                uid: format!("{}-start@{}", id, UID_DOMAIN),
+                date,
+                summary: format!("Start: {}", name),
+                description: Some(description.clone()),
+                categories: vec!["Start".to_string()],
+            });
+        }
+        if let Some(date) = parse_date(due_date.as_deref()) {
+            events.push(IcsEvent {
+                uid: format!("{}-due@{}", id, UID_DOMAIN),
+                date,
+                summary: format!("Due: {}", name),
+                description: Some(description.clone()),
+                categories: vec!["Due".to_string()],
+            });
+        }
+        let comments = comment_stmt
+            .query_map([&id], |r| {
+                Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (comment_id, content) in comments {
+            for (i, (title, date)) in parse_milestone_comment(&content).into_iter().enumerate() {
+                events.push(IcsEvent {
+                    uid: format!("{}-milestone-{}@{}", comment_id, i, UID_DOMAIN),
+                    date,
+                    summary: format!("{}: {}", name, title),
+                    description: Some(description.clone()),
+                    categories: vec!["Milestone".to_string()],
+                });
+            }
+        }
+    }
+    events.sort_by(|a, b| a.date.cmp(&b.date));
+
+    Ok(render_calendar(CALENDAR_NAME, &events, now))
+}
+
+fn parse_date(value: Option<&str>) -> Option<NaiveDate> {
+    value.and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+}
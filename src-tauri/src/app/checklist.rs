@@ -0,0 +1,238 @@
+//! Project checklist use cases: list, add, toggle, reorder, delete.
+//!
+//! - 清单项是项目下的轻量待办（文本 + 是否完成 + 顺序），介于“无任务”与状态机之间，不替代状态流转。
+//! - 顺序：`sort_order` 升序，新项追加到末尾；`checklist_reorder` 把给出的项排在前面（1..n），
+//!   未列出的项按原顺序跟随。多设备同时追加可能得到相同的 `sort_order`，按创建时间、id 决定先后。
+//! - 勾选时记录 `done_at`，取消勾选清空；已处于目标状态时不写库。
+//! - 清单项随项目同步、导出、进回收站与清除；项目列表的 `checklist_done` / `checklist_total`
+//!   给出完成度。
+
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Longest accepted item text (in chars, after trimming).
+pub const CHECKLIST_TEXT_MAX_CHARS: usize = 500;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistItemDto {
+    pub id: String,
+    pub project_id: String,
+    pub text: String,
+    pub done: bool,
+    /// Position within the project (ascending).
+    pub sort_order: i64,
+    /// When the item was ticked; `None` while open.
+    pub done_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistAddReq {
+    pub project_id: String,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistToggleReq {
+    pub id: String,
+    /// Target state; omitted = flip the current one.
+    pub done: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistReorderReq {
+    pub project_id: String,
+    /// Items in their new order; items left out follow in their current order.
+    pub item_ids: Vec<String>,
+}
+
+const ITEM_COLUMNS: &str =
+    "id, project_id, text, done, sort_order, done_at, created_at, updated_at";
+
+/// A project's checklist in display order
+pub fn checklist_list(
+    pool: &DbPool,
+    project_id: String,
+) -> Result<Vec<ChecklistItemDto>, AppError> {
+    let conn = get_read_connection(pool);
+    ensure_project_exists(&conn, &project_id)?;
+    query_items(&conn, &project_id)
+}
+
+/// Append an item to the end of a project's checklist
+pub fn checklist_add(pool: &DbPool, req: ChecklistAddReq) -> Result<ChecklistItemDto, AppError> {
+    let text = validate_text(&req.text)?;
+    let conn = get_connection(pool);
+    ensure_project_exists(&conn, &req.project_id)?;
+
+    let sort_order: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(sort_order), 0) + 1 FROM checklist_items WHERE project_id = ?1",
+        params![&req.project_id],
+        |row| row.get(0),
+    )?;
+    let now = Utc::now().to_rfc3339();
+    let id = Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO checklist_items (id, project_id, text, done, sort_order, done_at, created_at, updated_at, _version)
+         VALUES (?1, ?2, ?3, 0, ?4, NULL, ?5, ?5, 1)",
+        params![&id, &req.project_id, text, sort_order, &now],
+    )?;
+
+    item_get(&conn, &id)
+}
+
+/// Tick or untick an item
+pub fn checklist_toggle(
+    pool: &DbPool,
+    req: ChecklistToggleReq,
+) -> Result<ChecklistItemDto, AppError> {
+    let conn = get_connection(pool);
+    let item = item_get(&conn, &req.id)?;
+    let done = req.done.unwrap_or(!item.done);
+    if done == item.done {
+        return Ok(item);
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let done_at = done.then_some(now.as_str());
+    conn.execute(
+        "UPDATE checklist_items SET done = ?1, done_at = ?2, updated_at = ?3, _version = _version + 1
+         WHERE id = ?4",
+        params![done as i32, done_at, &now, &req.id],
+    )?;
+
+    item_get(&conn, &req.id)
+}
+
+/// Set the order of a project's checklist; returns the checklist
+pub fn checklist_reorder(
+    pool: &DbPool,
+    req: ChecklistReorderReq,
+) -> Result<Vec<ChecklistItemDto>, AppError> {
+    let conn = get_connection(pool);
+    ensure_project_exists(&conn, &req.project_id)?;
+
+    let items = query_items(&conn, &req.project_id)?;
+    let mut seen = HashSet::new();
+    for id in &req.item_ids {
+        if !seen.insert(id.as_str()) {
+            return Err(AppError::Validation(format!("Item listed twice: {}", id)));
+        }
+        if !items.iter().any(|item| &item.id == id) {
+            return Err(AppError::Validation(format!(
+                "Checklist item {} is not in this project",
+                id
+            )));
+        }
+    }
+
+    let order = req.item_ids.iter().map(String::as_str).chain(
+        items
+            .iter()
+            .map(|item| item.id.as_str())
+            .filter(|id| !seen.contains(id)),
+    );
+    let now = Utc::now().to_rfc3339();
+    let tx = conn.unchecked_transaction()?;
+    for (index, id) in order.enumerate() {
+        let sort_order = index as i64 + 1;
+        let current = items
+            .iter()
+            .find(|item| item.id == id)
+            .map(|item| item.sort_order);
+        if current != Some(sort_order) {
+            tx.execute(
+                "UPDATE checklist_items SET sort_order = ?1, updated_at = ?2, _version = _version + 1
+                 WHERE id = ?3",
+                params![sort_order, &now, id],
+            )?;
+        }
+    }
+    tx.commit()?;
+
+    query_items(&conn, &req.project_id)
+}
+
+/// Delete a checklist item
+pub fn checklist_delete(pool: &DbPool, id: String) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let rows = conn.execute("DELETE FROM checklist_items WHERE id = ?", params![&id])?;
+    if rows == 0 {
+        return Err(AppError::NotFound("Checklist item not found".into()));
+    }
+    Ok(())
+}
+
+fn validate_text(text: &str) -> Result<&str, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::Validation("text is required".into()));
+    }
+    if text.chars().count() > CHECKLIST_TEXT_MAX_CHARS {
+        return Err(AppError::Validation(format!(
+            "Checklist item text is too long (at most {} characters)",
+            CHECKLIST_TEXT_MAX_CHARS
+        )));
+    }
+    Ok(text)
+}
+
+fn ensure_project_exists(conn: &Connection, project_id: &str) -> Result<(), AppError> {
+    let project_exists = conn
+        .query_row(
+            "SELECT 1 FROM projects WHERE id = ?",
+            params![project_id],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !project_exists {
+        return Err(AppError::NotFound("Project not found".into()));
+    }
+    Ok(())
+}
+
+fn item_get(conn: &Connection, id: &str) -> Result<ChecklistItemDto, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM checklist_items WHERE id = ?1", ITEM_COLUMNS),
+        params![id],
+        item_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::NotFound("Checklist item not found".into()))
+}
+
+fn query_items(conn: &Connection, project_id: &str) -> Result<Vec<ChecklistItemDto>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM checklist_items WHERE project_id = ?1
+         ORDER BY sort_order, created_at, id",
+        ITEM_COLUMNS
+    ))?;
+    let items = stmt
+        .query_map(params![project_id], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+fn item_from_row(row: &Row<'_>) -> rusqlite::Result<ChecklistItemDto> {
+    Ok(ChecklistItemDto {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        text: row.get(2)?,
+        done: row.get::<_, i32>(3)? != 0,
+        sort_order: row.get(4)?,
+        done_at: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
@@ -1,12 +1,35 @@
-//! Comment use cases: create, update, delete, list by project.
-
+//! Comment use cases: create, reply, react, update, edit history, pin order, delete, list by
+//! project, mentions by person.
+//!
+//! - 回复只有一层：`parent_comment_id` 指向顶层评论，回复某条回复时挂到它的顶层评论下。
+//! - 表情回应按（评论, 人员, 表情）唯一，重复提交即取消；回应行带评论的 `project_id`，随项目参与同步范围与清除。
+//! - 列表返回顶层评论（置顶优先、新的在前），回复按时间正序嵌套在 `replies` 中；
+//!   父评论缺失的回复（例如只同步到一半）作为顶层评论显示。
+//! - 删除顶层评论时连同其回复、所有回应与编辑历史一起删除，可撤销。
+//! - 编辑历史：`comment_update` 修改内容时把旧内容（及当时的作者、写入时间）存入 `comment_revisions`，
+//!   随项目同步；只改置顶或作者不产生修订。
+//! - 置顶顺序：`pin_order` 升序；新置顶的评论排在最前，取消置顶清空。迁移前的置顶（`pin_order` 为空）
+//!   按 0 处理，再按时间倒序；`comment_reorder_pins` 给出明确顺序。
+//! - @提及：`comment_mentions` 是评论富文本中 mention 节点的派生索引，由触发器维护（migration 0038），
+//!   任何途径写入的评论（命令、同步、快照恢复、导入、撤销）都会被索引；仅本机，不参与同步。
+//!   编辑评论时已有的提及保留原时间，新增的提及以编辑时间计。
+
+use crate::app::action_log::{journal_capture, journal_record, ActionKind, JournalScope};
+use crate::app::webhook::{webhook_notify, WEBHOOK_EVENT_COMMENT_CREATED};
 use crate::error::AppError;
-use crate::infra::{get_connection, DbPool};
+use crate::infra::markdown::rich_text_to_markdown;
+use crate::infra::{get_connection, get_read_connection, DbPool};
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+/// Rows returned by `person_mentions` when no limit is given.
+pub const DEFAULT_PERSON_MENTIONS_LIMIT: i64 = 200;
+/// Longest accepted reaction (in chars); emoji sequences such as flags or families span several.
+const REACTION_MAX_CHARS: usize = 32;
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommentDto {
@@ -18,6 +41,41 @@ pub struct CommentDto {
     pub is_pinned: bool,
     pub created_at: String,
     pub updated_at: String,
+    /// Top-level comment this one replies to; `None` for top-level comments.
+    pub parent_comment_id: Option<String>,
+    /// Position among the project's pinned comments (ascending); `None` when unpinned.
+    pub pin_order: Option<i64>,
+    /// Number of earlier versions (see `comment_history`); 0 = never edited.
+    pub revision_count: i64,
+    /// In order of first use.
+    pub reactions: Vec<CommentReactionDto>,
+    /// Oldest first; always empty on replies.
+    pub replies: Vec<CommentDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReactionDto {
+    pub emoji: String,
+    pub count: usize,
+    pub person_ids: Vec<String>,
+    pub person_names: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentRevisionDto {
+    pub id: String,
+    pub comment_id: String,
+    /// Author at the time of this version.
+    pub person_id: Option<String>,
+    pub person_name: Option<String>,
+    /// Content before the edit (rich-text JSON).
+    pub content: String,
+    /// When this version was written (the comment's `updated_at` before the edit).
+    pub written_at: String,
+    /// When the edit replaced it.
+    pub replaced_at: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,6 +87,23 @@ pub struct CommentCreateReq {
     pub is_pinned: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReplyReq {
+    /// Comment replied to; replies to a reply join its top-level comment.
+    pub comment_id: String,
+    pub person_id: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReactReq {
+    pub comment_id: String,
+    pub person_id: String,
+    pub emoji: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommentUpdateReq {
@@ -38,6 +113,39 @@ pub struct CommentUpdateReq {
     pub is_pinned: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReorderPinsReq {
+    pub project_id: String,
+    /// Pinned comments in their new order; pinned comments left out follow in their current order.
+    pub comment_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonMentionsReq {
+    pub person_id: String,
+    /// Default `DEFAULT_PERSON_MENTIONS_LIMIT`.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonMentionDto {
+    pub comment_id: String,
+    /// Set when the mention is in a reply.
+    pub parent_comment_id: Option<String>,
+    pub project_id: String,
+    pub project_name: String,
+    /// Comment author.
+    pub author_person_id: Option<String>,
+    pub author_name: Option<String>,
+    /// Rich-text JSON, as in `CommentDto::content`.
+    pub content: String,
+    /// Comment creation time, or the edit that added the mention.
+    pub mentioned_at: String,
+}
+
 /// Create a new comment
 pub fn comment_create(pool: &DbPool, req: CommentCreateReq) -> Result<CommentDto, AppError> {
     // Validate: project exists
@@ -54,43 +162,88 @@ pub fn comment_create(pool: &DbPool, req: CommentCreateReq) -> Result<CommentDto
         return Err(AppError::NotFound("Project not found".into()));
     }
 
-    // Validate: person exists if provided
-    if let Some(ref person_id) = req.person_id {
-        let person_exists: bool = conn
-            .query_row(
-                "SELECT 1 FROM persons WHERE id = ?",
-                params![person_id],
-                |_| Ok(true),
-            )
-            .unwrap_or(false);
+    ensure_person_exists(&conn, req.person_id.as_deref())?;
 
-        if !person_exists {
-            return Err(AppError::NotFound("Person not found".into()));
-        }
+    comment_insert(
+        &conn,
+        &req.project_id,
+        None,
+        req.person_id.as_deref(),
+        &req.content,
+        req.is_pinned.unwrap_or(false),
+    )
+}
+
+/// Reply to a comment (in the thread of its top-level comment)
+pub fn comment_reply(pool: &DbPool, req: CommentReplyReq) -> Result<CommentDto, AppError> {
+    let conn = get_connection(pool);
+    let (project_id, root_id): (String, String) = conn
+        .query_row(
+            "SELECT project_id, COALESCE(parent_comment_id, id) FROM project_comments WHERE id = ?",
+            params![&req.comment_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound("Comment not found".into()))?;
+    ensure_person_exists(&conn, req.person_id.as_deref())?;
+
+    comment_insert(
+        &conn,
+        &project_id,
+        Some(&root_id),
+        req.person_id.as_deref(),
+        &req.content,
+        false,
+    )
+}
+
+/// Toggle a person's reaction on a comment; returns the comment with its updated reactions.
+pub fn comment_react(pool: &DbPool, req: CommentReactReq) -> Result<CommentDto, AppError> {
+    let emoji = req.emoji.trim();
+    if emoji.is_empty() {
+        return Err(AppError::Validation("emoji is required".into()));
+    }
+    if emoji.chars().count() > REACTION_MAX_CHARS || emoji.chars().any(char::is_whitespace) {
+        return Err(AppError::Validation(format!(
+            "Invalid reaction: {} (one emoji or short code, at most {} characters)",
+            emoji, REACTION_MAX_CHARS
+        )));
     }
 
-    let now = Utc::now().to_rfc3339();
-    let id = Uuid::new_v4().to_string();
-    let is_pinned = req.is_pinned.unwrap_or(false);
+    let conn = get_connection(pool);
+    let project_id: String = conn
+        .query_row(
+            "SELECT project_id FROM project_comments WHERE id = ?",
+            params![&req.comment_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound("Comment not found".into()))?;
+    ensure_person_exists(&conn, Some(req.person_id.as_str()))?;
 
-    conn.execute(
-        "INSERT INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at, _version)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1)",
-        params![
-            &id,
-            &req.project_id,
-            &req.person_id,
-            &req.content,
-            is_pinned as i32,
-            &now,
-            &now,
-        ],
+    let removed = conn.execute(
+        "DELETE FROM comment_reactions WHERE comment_id = ?1 AND person_id = ?2 AND emoji = ?3",
+        params![&req.comment_id, &req.person_id, emoji],
     )?;
+    if removed == 0 {
+        conn.execute(
+            "INSERT INTO comment_reactions (id, comment_id, project_id, person_id, emoji, created_at, _version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1)",
+            params![
+                Uuid::new_v4().to_string(),
+                &req.comment_id,
+                &project_id,
+                &req.person_id,
+                emoji,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+    }
 
-    comment_get(&conn, &id)
+    comment_get(&conn, &req.comment_id)
 }
 
-/// Update an existing comment
+/// Update an existing comment; a content change keeps the previous version in the history
 pub fn comment_update(pool: &DbPool, req: CommentUpdateReq) -> Result<CommentDto, AppError> {
     let conn = get_connection(pool);
 
@@ -107,77 +260,457 @@ pub fn comment_update(pool: &DbPool, req: CommentUpdateReq) -> Result<CommentDto
         return Err(AppError::NotFound("Comment not found".into()));
     }
 
-    // Validate person_id if provided
-    if let Some(ref person_id) = req.person_id {
-        let person_exists: bool = conn
-            .query_row(
-                "SELECT 1 FROM persons WHERE id = ?",
-                params![person_id],
-                |_| Ok(true),
-            )
-            .unwrap_or(false);
-
-        if !person_exists {
-            return Err(AppError::NotFound("Person not found".into()));
-        }
-    }
+    ensure_person_exists(&conn, req.person_id.as_deref())?;
 
     let now = Utc::now().to_rfc3339();
 
     // Fetch current values to determine what to update
-    let (current_content, current_person_id, current_pinned): (String, Option<String>, i32) = conn
-        .query_row(
-            "SELECT content, person_id, is_pinned FROM project_comments WHERE id = ?",
-            params![&req.id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )?;
+    let (
+        project_id,
+        current_content,
+        current_person_id,
+        current_pinned,
+        current_pin_order,
+        current_updated_at,
+    ) = conn.query_row(
+        "SELECT project_id, content, person_id, is_pinned, pin_order, updated_at FROM project_comments WHERE id = ?",
+        params![&req.id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, i32>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        },
+    )?;
 
-    let final_content = req.content.unwrap_or(current_content);
+    let content_changed = req
+        .content
+        .as_ref()
+        .is_some_and(|content| *content != current_content);
+    let final_content = req.content.unwrap_or_else(|| current_content.clone());
     let final_person_id = if req.person_id.is_some() {
         req.person_id
     } else {
-        current_person_id
+        current_person_id.clone()
     };
     let final_is_pinned = req.is_pinned.unwrap_or(current_pinned != 0);
 
-    conn.execute(
-        "UPDATE project_comments SET content = ?1, person_id = ?2, is_pinned = ?3, updated_at = ?4, _version = _version + 1 WHERE id = ?5",
-        params![final_content, final_person_id, if final_is_pinned { 1 } else { 0 }, &now, &req.id],
+    let tx = conn.unchecked_transaction()?;
+    let final_pin_order = match (current_pinned != 0, final_is_pinned) {
+        (false, true) => Some(top_pin_order(&tx, &project_id)?),
+        (_, false) => None,
+        (true, true) => current_pin_order,
+    };
+    if content_changed {
+        tx.execute(
+            "INSERT INTO comment_revisions (id, comment_id, project_id, person_id, content, written_at, replaced_at, _version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1)",
+            params![
+                Uuid::new_v4().to_string(),
+                &req.id,
+                &project_id,
+                current_person_id,
+                current_content,
+                current_updated_at,
+                &now,
+            ],
+        )?;
+    }
+    tx.execute(
+        "UPDATE project_comments SET content = ?1, person_id = ?2, is_pinned = ?3, pin_order = ?4, updated_at = ?5, _version = _version + 1 WHERE id = ?6",
+        params![final_content, final_person_id, if final_is_pinned { 1 } else { 0 }, final_pin_order, &now, &req.id],
     )?;
+    tx.commit()?;
 
     comment_get(&conn, &req.id)
 }
 
-/// Delete a comment
+/// Earlier versions of a comment, most recent edit first
+pub fn comment_history(
+    pool: &DbPool,
+    comment_id: String,
+) -> Result<Vec<CommentRevisionDto>, AppError> {
+    let conn = get_read_connection(pool);
+    conn.query_row(
+        "SELECT 1 FROM project_comments WHERE id = ?",
+        params![&comment_id],
+        |_| Ok(()),
+    )
+    .optional()?
+    .ok_or_else(|| AppError::NotFound("Comment not found".into()))?;
+
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.comment_id, r.person_id, p.display_name, r.content, r.written_at, r.replaced_at
+         FROM comment_revisions r
+         LEFT JOIN persons p ON r.person_id = p.id
+         WHERE r.comment_id = ?1
+         ORDER BY r.replaced_at DESC, r.id",
+    )?;
+    let revisions = stmt
+        .query_map(params![&comment_id], |row| {
+            Ok(CommentRevisionDto {
+                id: row.get(0)?,
+                comment_id: row.get(1)?,
+                person_id: row.get(2)?,
+                person_name: row.get(3)?,
+                content: row.get(4)?,
+                written_at: row.get(5)?,
+                replaced_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(revisions)
+}
+
+/// Set the order of a project's pinned comments; returns the project's comments
+pub fn comment_reorder_pins(
+    pool: &DbPool,
+    req: CommentReorderPinsReq,
+) -> Result<Vec<CommentDto>, AppError> {
+    let conn = get_connection(pool);
+    let project_exists = conn
+        .query_row(
+            "SELECT 1 FROM projects WHERE id = ?",
+            params![&req.project_id],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !project_exists {
+        return Err(AppError::NotFound("Project not found".into()));
+    }
+
+    // Current order of the pinned comments, as listed.
+    let pinned: Vec<(String, Option<i64>)> = conn
+        .prepare(
+            "SELECT id, pin_order FROM project_comments
+             WHERE project_id = ?1 AND is_pinned = 1
+             ORDER BY COALESCE(pin_order, 0), created_at DESC, id",
+        )?
+        .query_map(params![&req.project_id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?
+        .collect::<Result<_, _>>()?;
+    let mut seen = HashSet::new();
+    for id in &req.comment_ids {
+        if !seen.insert(id.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Comment listed twice: {}",
+                id
+            )));
+        }
+        if !pinned.iter().any(|(pinned_id, _)| pinned_id == id) {
+            return Err(AppError::Validation(format!(
+                "Comment {} is not pinned in this project",
+                id
+            )));
+        }
+    }
+
+    let order = req.comment_ids.iter().map(String::as_str).chain(
+        pinned
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .filter(|id| !seen.contains(id)),
+    );
+    let tx = conn.unchecked_transaction()?;
+    for (index, id) in order.enumerate() {
+        let pin_order = index as i64 + 1;
+        let current = pinned
+            .iter()
+            .find(|(pinned_id, _)| pinned_id == id)
+            .and_then(|(_, order)| *order);
+        if current != Some(pin_order) {
+            tx.execute(
+                "UPDATE project_comments SET pin_order = ?1, _version = _version + 1 WHERE id = ?2",
+                params![pin_order, id],
+            )?;
+        }
+    }
+    tx.commit()?;
+
+    let comments = query_comments(&conn, "c.project_id = ?1", &req.project_id)?;
+    let reactions = query_reactions(&conn, "r.project_id = ?1", &req.project_id)?;
+    Ok(nest_comments(comments, reactions))
+}
+
+/// Delete a comment with its replies and their reactions
 pub fn comment_delete(pool: &DbPool, id: String) -> Result<(), AppError> {
     let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+
+    let mut thread_ids = vec![id.clone()];
+    {
+        let mut stmt = tx.prepare("SELECT id FROM project_comments WHERE parent_comment_id = ?")?;
+        let replies = stmt.query_map(params![&id], |row| row.get::<_, String>(0))?;
+        for reply in replies {
+            thread_ids.push(reply?);
+        }
+    }
+    let mut scopes = vec![
+        JournalScope::new("project_comments", &["id"], "id", &id),
+        JournalScope::new("project_comments", &["id"], "parent_comment_id", &id),
+    ];
+    for comment_id in &thread_ids {
+        scopes.push(JournalScope::new(
+            "comment_reactions",
+            &["id"],
+            "comment_id",
+            comment_id,
+        ));
+        scopes.push(JournalScope::new(
+            "comment_revisions",
+            &["id"],
+            "comment_id",
+            comment_id,
+        ));
+    }
+    let before = journal_capture(&tx, &scopes)?;
 
-    let rows = conn.execute("DELETE FROM project_comments WHERE id = ?", params![&id])?;
+    let rows = tx.execute("DELETE FROM project_comments WHERE id = ?", params![&id])?;
 
     if rows == 0 {
         return Err(AppError::NotFound("Comment not found".into()));
     }
+    tx.execute(
+        "DELETE FROM project_comments WHERE parent_comment_id = ?",
+        params![&id],
+    )?;
+    for comment_id in &thread_ids {
+        tx.execute(
+            "DELETE FROM comment_reactions WHERE comment_id = ?",
+            params![comment_id],
+        )?;
+        tx.execute(
+            "DELETE FROM comment_revisions WHERE comment_id = ?",
+            params![comment_id],
+        )?;
+    }
 
+    journal_record(&tx, ActionKind::CommentDelete, &id, &scopes, before)?;
+    tx.commit()?;
     Ok(())
 }
 
-/// List all comments for a project (pinned first, then by created_at DESC)
+/// List a project's comments: top-level ones pinned first, then by created_at DESC, each with
+/// its replies (oldest first) and reactions
 pub fn comment_list_by_project(
     pool: &DbPool,
     project_id: String,
 ) -> Result<Vec<CommentDto>, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
+
+    let comments = query_comments(&conn, "c.project_id = ?1", &project_id)?;
+    let reactions = query_reactions(&conn, "r.project_id = ?1", &project_id)?;
+    Ok(nest_comments(comments, reactions))
+}
+
+/// Comments that mention a person, newest mention first. Projects in the trash are skipped.
+pub fn person_mentions(
+    pool: &DbPool,
+    req: PersonMentionsReq,
+) -> Result<Vec<PersonMentionDto>, AppError> {
+    let limit = req
+        .limit
+        .unwrap_or(DEFAULT_PERSON_MENTIONS_LIMIT)
+        .clamp(1, 1000);
+    let conn = get_read_connection(pool);
+    ensure_person_exists(&conn, Some(req.person_id.as_str()))?;
 
     let mut stmt = conn.prepare(
-        "SELECT c.id, c.project_id, c.person_id, c.content, c.is_pinned, c.created_at, c.updated_at,
-                p.display_name as person_name
-         FROM project_comments c
-         LEFT JOIN persons p ON c.person_id = p.id
-         WHERE c.project_id = ?
-         ORDER BY c.is_pinned DESC, c.created_at DESC"
-    ).map_err(|e| AppError::Db(e.to_string()))?;
-
-    let rows = stmt.query_map(params![&project_id], |row| {
+        "SELECT m.comment_id, c.parent_comment_id, m.project_id, p.name, c.person_id,
+                a.display_name, c.content, m.mentioned_at
+         FROM comment_mentions m
+         JOIN project_comments c ON c.id = m.comment_id
+         JOIN projects p ON p.id = m.project_id
+         LEFT JOIN persons a ON a.id = c.person_id
+         WHERE m.person_id = ?1 AND p.deleted_at IS NULL
+         ORDER BY m.mentioned_at DESC, m.comment_id
+         LIMIT ?2",
+    )?;
+    let items = stmt
+        .query_map(params![&req.person_id, limit], |row| {
+            Ok(PersonMentionDto {
+                comment_id: row.get(0)?,
+                parent_comment_id: row.get(1)?,
+                project_id: row.get(2)?,
+                project_name: row.get(3)?,
+                author_person_id: row.get(4)?,
+                author_name: row.get(5)?,
+                content: row.get(6)?,
+                mentioned_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Point the mentions of `from_id` in rich-text `content` at another person (used by person
+/// merge). `None` when the content has no such mention.
+pub(crate) fn replace_mentions(
+    content: &str,
+    from_id: &str,
+    to_id: &str,
+    to_label: &str,
+) -> Option<String> {
+    let mut doc: serde_json::Value = serde_json::from_str(content).ok()?;
+    if replace_mention_nodes(&mut doc, from_id, to_id, to_label) {
+        serde_json::to_string(&doc).ok()
+    } else {
+        None
+    }
+}
+
+fn replace_mention_nodes(
+    node: &mut serde_json::Value,
+    from_id: &str,
+    to_id: &str,
+    to_label: &str,
+) -> bool {
+    use serde_json::Value;
+    match node {
+        Value::Object(map) => {
+            let is_match = map.get("type").and_then(Value::as_str) == Some("mention")
+                && map
+                    .get("attrs")
+                    .and_then(|a| a.get("id"))
+                    .and_then(Value::as_str)
+                    == Some(from_id);
+            if is_match {
+                if let Some(Value::Object(attrs)) = map.get_mut("attrs") {
+                    attrs.insert("id".into(), Value::from(to_id));
+                    attrs.insert("label".into(), Value::from(to_label));
+                }
+            }
+            let mut changed = is_match;
+            for value in map.values_mut() {
+                changed |= replace_mention_nodes(value, from_id, to_id, to_label);
+            }
+            changed
+        }
+        Value::Array(items) => items.iter_mut().fold(false, |changed, item| {
+            replace_mention_nodes(item, from_id, to_id, to_label) | changed
+        }),
+        _ => false,
+    }
+}
+
+/// Internal helper to get a single comment (with its replies and reactions)
+fn comment_get(conn: &Connection, id: &str) -> Result<CommentDto, AppError> {
+    let comments = query_comments(conn, "(c.id = ?1 OR c.parent_comment_id = ?1)", id)?;
+    let reactions = query_reactions(
+        conn,
+        "r.comment_id IN (SELECT id FROM project_comments WHERE id = ?1 OR parent_comment_id = ?1)",
+        id,
+    )?;
+    nest_comments(comments, reactions)
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| AppError::NotFound("Comment not found".into()))
+}
+
+fn ensure_person_exists(conn: &Connection, person_id: Option<&str>) -> Result<(), AppError> {
+    let Some(person_id) = person_id else {
+        return Ok(());
+    };
+    let person_exists = conn
+        .query_row(
+            "SELECT 1 FROM persons WHERE id = ?",
+            params![person_id],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !person_exists {
+        return Err(AppError::NotFound("Person not found".into()));
+    }
+    Ok(())
+}
+
+fn comment_insert(
+    conn: &Connection,
+    project_id: &str,
+    parent_comment_id: Option<&str>,
+    person_id: Option<&str>,
+    content: &str,
+    is_pinned: bool,
+) -> Result<CommentDto, AppError> {
+    let now = Utc::now().to_rfc3339();
+    let id = Uuid::new_v4().to_string();
+    let pin_order = if is_pinned {
+        Some(top_pin_order(conn, project_id)?)
+    } else {
+        None
+    };
+
+    conn.execute(
+        "INSERT INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at, _version, parent_comment_id, pin_order)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1, ?8, ?9)",
+        params![
+            &id,
+            project_id,
+            person_id,
+            content,
+            is_pinned as i32,
+            &now,
+            &now,
+            parent_comment_id,
+            pin_order,
+        ],
+    )?;
+
+    let comment = comment_get(conn, &id)?;
+    webhook_notify(
+        conn,
+        WEBHOOK_EVENT_COMMENT_CREATED,
+        serde_json::json!({
+            "commentId": &comment.id,
+            "projectId": &comment.project_id,
+            "personId": &comment.person_id,
+            "parentCommentId": &comment.parent_comment_id,
+            "contentMarkdown": rich_text_to_markdown(&comment.content),
+            "isPinned": comment.is_pinned,
+            "createdAt": &comment.created_at,
+        }),
+    );
+    Ok(comment)
+}
+
+/// `pin_order` that puts a newly pinned comment before the project's other pinned comments.
+fn top_pin_order(conn: &Connection, project_id: &str) -> Result<i64, AppError> {
+    Ok(conn.query_row(
+        "SELECT COALESCE(MIN(COALESCE(pin_order, 0)), 1) - 1 FROM project_comments
+         WHERE project_id = ?1 AND is_pinned = 1",
+        params![project_id],
+        |row| row.get(0),
+    )?)
+}
+
+/// Comments matching `condition` (bound to `?1`), pinned first (by pin order), then newest
+/// first; not yet nested.
+fn query_comments(
+    conn: &Connection,
+    condition: &str,
+    value: &str,
+) -> Result<Vec<CommentDto>, AppError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT c.id, c.project_id, c.person_id, c.content, c.is_pinned, c.created_at, c.updated_at,
+                    p.display_name as person_name, c.parent_comment_id, c.pin_order,
+                    (SELECT COUNT(*) FROM comment_revisions r WHERE r.comment_id = c.id)
+             FROM project_comments c
+             LEFT JOIN persons p ON c.person_id = p.id
+             WHERE {}
+             ORDER BY c.is_pinned DESC, COALESCE(c.pin_order, 0), c.created_at DESC, c.id",
+            condition
+        ))
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let rows = stmt.query_map(params![value], |row| {
         Ok(CommentDto {
             id: row.get(0)?,
             project_id: row.get(1)?,
@@ -187,6 +720,11 @@ pub fn comment_list_by_project(
             created_at: row.get(5)?,
             updated_at: row.get(6)?,
             person_name: row.get(7)?,
+            parent_comment_id: row.get(8)?,
+            pin_order: row.get(9)?,
+            revision_count: row.get(10)?,
+            reactions: Vec::new(),
+            replies: Vec::new(),
         })
     })?;
 
@@ -194,32 +732,81 @@ pub fn comment_list_by_project(
     for comment in rows {
         comments.push(comment?);
     }
-
     Ok(comments)
 }
 
-/// Internal helper to get a single comment
-fn comment_get(conn: &rusqlite::Connection, id: &str) -> Result<CommentDto, AppError> {
-    let mut stmt = conn.prepare(
-        "SELECT c.id, c.project_id, c.person_id, c.content, c.is_pinned, c.created_at, c.updated_at,
-                p.display_name as person_name
-         FROM project_comments c
-         LEFT JOIN persons p ON c.person_id = p.id
-         WHERE c.id = ?"
-    ).map_err(|e| AppError::Db(e.to_string()))?;
-
-    let comment = stmt.query_row(params![id], |row| {
-        Ok(CommentDto {
-            id: row.get(0)?,
-            project_id: row.get(1)?,
-            person_id: row.get(2)?,
-            content: row.get(3)?,
-            is_pinned: row.get::<_, i32>(4)? != 0,
-            created_at: row.get(5)?,
-            updated_at: row.get(6)?,
-            person_name: row.get(7)?,
-        })
+/// Reactions matching `condition` (bound to `?1`), summarized per comment.
+fn query_reactions(
+    conn: &Connection,
+    condition: &str,
+    value: &str,
+) -> Result<HashMap<String, Vec<CommentReactionDto>>, AppError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT r.comment_id, r.emoji, r.person_id, COALESCE(p.display_name, '')
+             FROM comment_reactions r
+             LEFT JOIN persons p ON r.person_id = p.id
+             WHERE {}
+             ORDER BY r.created_at, r.id",
+            condition
+        ))
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let rows = stmt.query_map(params![value], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+        ))
     })?;
 
-    Ok(comment)
+    let mut by_comment: HashMap<String, Vec<CommentReactionDto>> = HashMap::new();
+    for row in rows {
+        let (comment_id, emoji, person_id, person_name) = row?;
+        let reactions = by_comment.entry(comment_id).or_default();
+        let index = match reactions.iter().position(|r| r.emoji == emoji) {
+            Some(index) => index,
+            None => {
+                reactions.push(CommentReactionDto {
+                    emoji,
+                    count: 0,
+                    person_ids: Vec::new(),
+                    person_names: Vec::new(),
+                });
+                reactions.len() - 1
+            }
+        };
+        let reaction = &mut reactions[index];
+        reaction.count += 1;
+        reaction.person_ids.push(person_id);
+        reaction.person_names.push(person_name);
+    }
+    Ok(by_comment)
+}
+
+/// Attach reactions and move replies under their top-level comment (kept in `comments` order);
+/// replies whose parent is missing stay at the top level.
+fn nest_comments(
+    comments: Vec<CommentDto>,
+    mut reactions: HashMap<String, Vec<CommentReactionDto>>,
+) -> Vec<CommentDto> {
+    let ids: HashSet<String> = comments.iter().map(|c| c.id.clone()).collect();
+    let mut top_level = Vec::new();
+    let mut replies: HashMap<String, Vec<CommentDto>> = HashMap::new();
+    for mut comment in comments {
+        comment.reactions = reactions.remove(&comment.id).unwrap_or_default();
+        match comment.parent_comment_id.clone() {
+            Some(parent) if ids.contains(&parent) && parent != comment.id => {
+                replies.entry(parent).or_default().push(comment)
+            }
+            _ => top_level.push(comment),
+        }
+    }
+    for comment in &mut top_level {
+        if let Some(mut thread) = replies.remove(&comment.id) {
+            thread.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+            comment.replies = thread;
+        }
+    }
+    top_level
 }
@@ -0,0 +1,85 @@
+//! Country catalog for the UI (dropdowns, filters, friendly names).
+//!
+//! - `country_list`：内嵌 ISO 3166-1 目录的全部条目，用于创建 / 编辑项目时的下拉框。
+//! - `country_usage`：项目中实际保存的代码（不含回收站）及项目数，用于列表筛选；
+//!   代码按去空白、大写后合并，同步 / 导入带来的未知代码也列出（`known = false`，名称回退为代码本身）。
+
+use crate::domain::{country_by_code, flag_emoji, COUNTRIES};
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryListReq {
+    /// UI locale for `name` (`zh*` -> Chinese, default English).
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryDto {
+    pub code: String,
+    pub alpha3: String,
+    /// Name in the requested locale.
+    pub name: String,
+    pub name_en: String,
+    pub name_zh: String,
+    pub flag: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountryUsageDto {
+    pub code: String,
+    /// Name in the requested locale; the code itself when unknown.
+    pub name: String,
+    pub flag: String,
+    /// In the ISO 3166-1 catalog.
+    pub known: bool,
+    pub project_count: i64,
+}
+
+/// Every ISO 3166-1 entry, ordered by code.
+pub fn country_list(req: CountryListReq) -> Vec<CountryDto> {
+    let locale = req.locale.as_deref().unwrap_or("en");
+    COUNTRIES
+        .iter()
+        .map(|c| CountryDto {
+            code: c.code.to_string(),
+            alpha3: c.alpha3.to_string(),
+            name: c.localized_name(locale).to_string(),
+            name_en: c.name_en.to_string(),
+            name_zh: c.name_zh.to_string(),
+            flag: c.flag(),
+        })
+        .collect()
+}
+
+/// Country codes stored on projects (trash excluded), ordered by code.
+pub fn country_usage(pool: &DbPool, req: CountryListReq) -> Result<Vec<CountryUsageDto>, AppError> {
+    let locale = req.locale.as_deref().unwrap_or("en");
+    let conn = get_read_connection(pool);
+    let rows = conn
+        .prepare(
+            "SELECT UPPER(TRIM(country_code)) AS code, COUNT(*) FROM projects
+             WHERE deleted_at IS NULL AND TRIM(country_code) <> ''
+             GROUP BY code ORDER BY code",
+        )?
+        .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows
+        .into_iter()
+        .map(|(code, project_count)| {
+            let country = country_by_code(&code);
+            CountryUsageDto {
+                name: country
+                    .map_or_else(|| code.clone(), |c| c.localized_name(locale).to_string()),
+                flag: flag_emoji(&code),
+                known: country.is_some(),
+                code,
+                project_count,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,328 @@
+//! Budget currencies: base currency, exchange rates and the optional rate fetch.
+//!
+//! - 货币代码为 ISO 4217 格式（三个字母，保存为大写）；项目预算未指定货币时取合作方默认货币，
+//!   合作方也未设置时取基准货币。
+//! - 基准货币（`currency_base_currency`，默认 USD）与汇率表 `currency_rates` 只保存在本机（按 profile），
+//!   不随同步传播；报表按本机汇率把预算换算为基准货币。
+//! - 汇率含义：1 单位该货币 = `rate` 单位基准货币；基准货币自身固定为 1，不入表。
+//! - 汇率可手动维护（source = `manual`），也可由 `currency_rates_fetch` 从配置的 URL 拉取
+//!   （source = `fetched`，覆盖同一货币的现有汇率）；拉取只在调用时执行，不会自动运行。
+//! - 更换基准货币时用新基准货币的汇率换算全部已有汇率；表非空且缺少该汇率时拒绝更换。
+
+use crate::error::AppError;
+use crate::infra::exchange_rates::{parse_rates_response, RatesClient};
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const CURRENCY_BASE_KEY: &str = "currency_base_currency";
+pub const CURRENCY_RATES_URL_KEY: &str = "currency_rates_url";
+pub const DEFAULT_BASE_CURRENCY: &str = "USD";
+/// `{base}` is replaced with the base currency.
+pub const DEFAULT_RATES_URL: &str = "https://api.frankfurter.app/latest?from={base}";
+pub const RATE_SOURCE_MANUAL: &str = "manual";
+pub const RATE_SOURCE_FETCHED: &str = "fetched";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyConfigDto {
+    pub base_currency: String,
+    /// Source of `currency_rates_fetch`; `{base}` is replaced with the base currency.
+    pub rates_url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyConfigReq {
+    /// Existing rates are converted to the new base currency.
+    pub base_currency: Option<String>,
+    /// `""` restores the default URL.
+    pub rates_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyRateDto {
+    pub currency: String,
+    /// Units of the base currency per 1 unit of `currency`.
+    pub rate: f64,
+    /// `manual` or `fetched`.
+    pub source: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyRateSetReq {
+    pub currency: String,
+    pub rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyRatesFetchResult {
+    pub base_currency: String,
+    /// Rates written (new or replaced).
+    pub updated: usize,
+    pub fetched_at: String,
+}
+
+/// Trimmed, uppercased ISO 4217 code (three ASCII letters).
+pub(crate) fn normalize_currency(code: &str) -> Result<String, AppError> {
+    let normalized = code.trim().to_ascii_uppercase();
+    if normalized.len() == 3 && normalized.bytes().all(|b| b.is_ascii_uppercase()) {
+        Ok(normalized)
+    } else {
+        Err(AppError::Validation(format!(
+            "invalid currency code: {:?} (expected ISO 4217, e.g. USD)",
+            code
+        )))
+    }
+}
+
+pub fn currency_get_config(pool: &DbPool) -> Result<CurrencyConfigDto, AppError> {
+    let conn = get_read_connection(pool);
+    Ok(load_config(&conn))
+}
+
+pub fn currency_update_config(
+    pool: &DbPool,
+    req: CurrencyConfigReq,
+) -> Result<CurrencyConfigDto, AppError> {
+    let base = req
+        .base_currency
+        .as_deref()
+        .map(normalize_currency)
+        .transpose()?;
+    let url = req.rates_url.as_deref().map(str::trim);
+    if let Some(url) = url.filter(|u| !u.is_empty()) {
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(AppError::Validation(
+                "rates URL must start with http:// or https://".into(),
+            ));
+        }
+    }
+
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    match url {
+        Some("") => {
+            tx.execute(
+                "DELETE FROM sync_config WHERE key = ?1",
+                [CURRENCY_RATES_URL_KEY],
+            )?;
+        }
+        Some(url) => set_config_value(&tx, CURRENCY_RATES_URL_KEY, url)?,
+        None => {}
+    }
+    if let Some(base) = base {
+        rebase(&tx, &base)?;
+    }
+    tx.commit()?;
+    Ok(load_config(&conn))
+}
+
+pub fn currency_rate_list(pool: &DbPool) -> Result<Vec<CurrencyRateDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let rates = conn
+        .prepare("SELECT currency, rate, source, updated_at FROM currency_rates ORDER BY currency")?
+        .query_map([], |r| {
+            Ok(CurrencyRateDto {
+                currency: r.get(0)?,
+                rate: r.get(1)?,
+                source: r.get(2)?,
+                updated_at: r.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rates)
+}
+
+/// Add or replace the rate of one currency by hand.
+pub fn currency_rate_set(
+    pool: &DbPool,
+    req: CurrencyRateSetReq,
+) -> Result<CurrencyRateDto, AppError> {
+    let currency = normalize_currency(&req.currency)?;
+    if !req.rate.is_finite() || req.rate <= 0.0 {
+        return Err(AppError::Validation(
+            "rate must be a positive number".into(),
+        ));
+    }
+    let conn = get_connection(pool);
+    if currency == load_base_currency(&conn) {
+        return Err(AppError::Validation(format!(
+            "{} is the base currency (rate is always 1)",
+            currency
+        )));
+    }
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT OR REPLACE INTO currency_rates (currency, rate, source, updated_at) VALUES (?1, ?2, ?3, ?4)",
+        params![&currency, req.rate, RATE_SOURCE_MANUAL, &now],
+    )?;
+    Ok(CurrencyRateDto {
+        currency,
+        rate: req.rate,
+        source: RATE_SOURCE_MANUAL.to_string(),
+        updated_at: now,
+    })
+}
+
+pub fn currency_rate_delete(pool: &DbPool, currency: &str) -> Result<(), AppError> {
+    let currency = normalize_currency(currency)?;
+    let conn = get_connection(pool);
+    let deleted = conn.execute(
+        "DELETE FROM currency_rates WHERE currency = ?1",
+        [&currency],
+    )?;
+    if deleted == 0 {
+        return Err(AppError::NotFound(format!("exchange rate {}", currency)));
+    }
+    Ok(())
+}
+
+/// Fetch rates from the configured URL and store them (replacing rates of the same currencies).
+/// Blocking: call from `spawn_blocking` in async code.
+pub fn currency_rates_fetch(pool: &DbPool) -> Result<CurrencyRatesFetchResult, AppError> {
+    let client =
+        RatesClient::new().map_err(|e| AppError::Report(format!("exchange rates: {}", e)))?;
+    currency_rates_fetch_with(pool, &|url| client.get(url))
+}
+
+/// Fetch with a caller-supplied HTTP GET (tests use a fake one instead of the network).
+pub fn currency_rates_fetch_with(
+    pool: &DbPool,
+    fetch: &dyn Fn(&str) -> Result<String, String>,
+) -> Result<CurrencyRatesFetchResult, AppError> {
+    let (base, url) = {
+        let conn = get_read_connection(pool);
+        let base = load_base_currency(&conn);
+        let url = load_rates_url(&conn).replace("{base}", &base);
+        (base, url)
+    };
+
+    // The DB lock is not held during the request.
+    let body = fetch(&url).map_err(|e| AppError::Report(format!("exchange rates: {}", e)))?;
+    let response = parse_rates_response(&body)
+        .map_err(|e| AppError::Report(format!("exchange rates: {}", e)))?;
+    if let Some(response_base) = &response.base {
+        if !response_base.trim().eq_ignore_ascii_case(&base) {
+            return Err(AppError::Report(format!(
+                "exchange rates: response is based on {}, expected {}",
+                response_base, base
+            )));
+        }
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    if load_base_currency(&tx) != base {
+        return Err(AppError::Conflict(
+            "base currency changed during the fetch".into(),
+        ));
+    }
+    let mut updated = 0;
+    for (code, per_base) in response.rates {
+        let Ok(currency) = normalize_currency(&code) else {
+            continue;
+        };
+        if currency == base || !per_base.is_finite() || per_base <= 0.0 {
+            continue;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO currency_rates (currency, rate, source, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![&currency, 1.0 / per_base, RATE_SOURCE_FETCHED, &now],
+        )?;
+        updated += 1;
+    }
+    tx.commit()?;
+    Ok(CurrencyRatesFetchResult {
+        base_currency: base,
+        updated,
+        fetched_at: now,
+    })
+}
+
+pub(crate) fn load_base_currency(conn: &Connection) -> String {
+    load_config_value(conn, CURRENCY_BASE_KEY)
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_BASE_CURRENCY.to_string())
+}
+
+/// Currency -> units of the base currency per unit.
+pub(crate) fn load_rates(conn: &Connection) -> Result<HashMap<String, f64>, AppError> {
+    let rates = conn
+        .prepare("SELECT currency, rate FROM currency_rates")?
+        .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, f64>(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(rates)
+}
+
+/// Switch the base currency, converting every stored rate through the new base's rate.
+fn rebase(conn: &Connection, new_base: &str) -> Result<(), AppError> {
+    let old_base = load_base_currency(conn);
+    if old_base == new_base {
+        return Ok(());
+    }
+    let has_rates: bool =
+        conn.query_row("SELECT EXISTS(SELECT 1 FROM currency_rates)", [], |r| {
+            r.get(0)
+        })?;
+    if has_rates {
+        let (pivot, source): (f64, String) = conn
+            .query_row(
+                "SELECT rate, source FROM currency_rates WHERE currency = ?1",
+                [new_base],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "no exchange rate for {}; add one before switching the base currency",
+                    new_base
+                ))
+            })?;
+        conn.execute("DELETE FROM currency_rates WHERE currency = ?1", [new_base])?;
+        conn.execute("UPDATE currency_rates SET rate = rate / ?1", [pivot])?;
+        conn.execute(
+            "INSERT OR REPLACE INTO currency_rates (currency, rate, source, updated_at) VALUES (?1, ?2, ?3, ?4)",
+            params![&old_base, 1.0 / pivot, &source, Utc::now().to_rfc3339()],
+        )?;
+    }
+    set_config_value(conn, CURRENCY_BASE_KEY, new_base)
+}
+
+fn load_rates_url(conn: &Connection) -> String {
+    load_config_value(conn, CURRENCY_RATES_URL_KEY)
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_RATES_URL.to_string())
+}
+
+fn load_config(conn: &Connection) -> CurrencyConfigDto {
+    CurrencyConfigDto {
+        base_currency: load_base_currency(conn),
+        rates_url: load_rates_url(conn),
+    }
+}
+
+fn load_config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+}
+
+fn set_config_value(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,418 @@
+//! Custom fields: team-defined project fields (text / number / date / select).
+//!
+//! - 定义与取值都随同步传播；字段名大小写不敏感唯一（应用层校验）。
+//! - 字段类型创建后不可修改（已有取值按旧类型规范化过）；select 的选项可增删，已有取值保留。
+//! - 取值按类型规范化后以文本存储：number 为十进制文本（整数不带小数点），date 为 `YYYY-MM-DD`。
+//!   `project_list` 的筛选对同样规范化后的值做等值匹配（text 大小写不敏感）。
+//! - 取值行 id 为 `<project_id>:<field_id>`，两台设备同时设置同一字段时按 LWW 收敛。
+
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Upper bound for text values (and option labels).
+pub const MAX_CUSTOM_VALUE_LEN: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Date,
+    Select,
+}
+
+impl CustomFieldType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Number => "number",
+            Self::Date => "date",
+            Self::Select => "select",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(Self::Text),
+            "number" => Some(Self::Number),
+            "date" => Some(Self::Date),
+            "select" => Some(Self::Select),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldDefDto {
+    pub id: String,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    /// Allowed values (select only; empty otherwise).
+    pub options: Vec<String>,
+    pub sort_order: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldDefCreateReq {
+    pub name: String,
+    pub field_type: CustomFieldType,
+    /// Required (non-empty) for select fields; ignored otherwise.
+    pub options: Option<Vec<String>>,
+    pub sort_order: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldDefUpdateReq {
+    pub id: String,
+    pub name: Option<String>,
+    /// Select fields only; replaces the option list.
+    pub options: Option<Vec<String>>,
+    pub sort_order: Option<i64>,
+}
+
+/// One defined field and this project's value for it (`None` = not set).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCustomValueDto {
+    pub field_id: String,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCustomValueSetReq {
+    pub project_id: String,
+    pub field_id: String,
+    /// None or blank clears the value.
+    pub value: Option<String>,
+}
+
+/// `project_list` filter: projects whose value for `field_id` equals `value`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldFilter {
+    pub field_id: String,
+    pub value: String,
+}
+
+pub fn custom_field_list(pool: &DbPool) -> Result<Vec<CustomFieldDefDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let ids = conn
+        .prepare("SELECT id FROM custom_field_defs ORDER BY sort_order, name COLLATE NOCASE")?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    ids.iter().map(|id| load_def(&conn, id)).collect()
+}
+
+pub fn custom_field_create(
+    pool: &DbPool,
+    req: CustomFieldDefCreateReq,
+) -> Result<CustomFieldDefDto, AppError> {
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".into()));
+    }
+    let options = normalize_options(req.field_type, req.options.unwrap_or_default())?;
+
+    let conn = get_connection(pool);
+    ensure_field_name_unique(&conn, &name, None)?;
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let sort_order = match req.sort_order {
+        Some(order) => order,
+        None => conn.query_row(
+            "SELECT COALESCE(MAX(sort_order), -1) + 1 FROM custom_field_defs",
+            [],
+            |r| r.get(0),
+        )?,
+    };
+    conn.execute(
+        "INSERT INTO custom_field_defs (id, name, field_type, options, sort_order, created_at, updated_at, _version)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, 1)",
+        params![
+            &id,
+            &name,
+            req.field_type.as_str(),
+            options_json(&options),
+            sort_order,
+            &now
+        ],
+    )?;
+    load_def(&conn, &id)
+}
+
+pub fn custom_field_update(
+    pool: &DbPool,
+    req: CustomFieldDefUpdateReq,
+) -> Result<CustomFieldDefDto, AppError> {
+    let conn = get_connection(pool);
+    let current = load_def(&conn, &req.id)?;
+    let name = match req.name.as_deref().map(str::trim) {
+        Some("") => return Err(AppError::Validation("name is required".into())),
+        Some(name) => {
+            ensure_field_name_unique(&conn, name, Some(&req.id))?;
+            name.to_string()
+        }
+        None => current.name,
+    };
+    let options = match req.options {
+        Some(options) if current.field_type == CustomFieldType::Select => {
+            normalize_options(CustomFieldType::Select, options)?
+        }
+        Some(_) => {
+            return Err(AppError::Validation(
+                "options can only be set on select fields".into(),
+            ))
+        }
+        None => current.options,
+    };
+    conn.execute(
+        "UPDATE custom_field_defs SET name = ?1, options = ?2, sort_order = ?3, updated_at = ?4, _version = _version + 1 WHERE id = ?5",
+        params![
+            &name,
+            options_json(&options),
+            req.sort_order.unwrap_or(current.sort_order),
+            Utc::now().to_rfc3339(),
+            &req.id
+        ],
+    )?;
+    load_def(&conn, &req.id)
+}
+
+/// Delete a field definition together with every project's value for it.
+pub fn custom_field_delete(pool: &DbPool, field_id: &str) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "DELETE FROM project_custom_values WHERE field_id = ?1",
+        [field_id],
+    )?;
+    let changed = tx.execute("DELETE FROM custom_field_defs WHERE id = ?1", [field_id])?;
+    if changed == 0 {
+        return Err(AppError::NotFound(format!("custom field {}", field_id)));
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Set (or clear) one project's value; returns all of the project's custom fields.
+pub fn project_set_custom_value(
+    pool: &DbPool,
+    req: ProjectCustomValueSetReq,
+) -> Result<Vec<ProjectCustomValueDto>, AppError> {
+    let conn = get_connection(pool);
+    let exists: Option<i64> = conn
+        .query_row(
+            "SELECT 1 FROM projects WHERE id = ?1",
+            [&req.project_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if exists.is_none() {
+        return Err(AppError::NotFound(format!("project {}", req.project_id)));
+    }
+    let def = load_def(&conn, &req.field_id)?;
+    let id = format!("{}:{}", req.project_id, req.field_id);
+
+    match req
+        .value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        None => {
+            conn.execute("DELETE FROM project_custom_values WHERE id = ?1", [&id])?;
+        }
+        Some(raw) => {
+            let value = normalize_value(&def, raw)?;
+            conn.execute(
+                "INSERT INTO project_custom_values (id, project_id, field_id, value, updated_at, _version)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 1)
+                 ON CONFLICT(id) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at, _version = _version + 1",
+                params![
+                    &id,
+                    &req.project_id,
+                    &req.field_id,
+                    &value,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+        }
+    }
+    project_custom_values(&conn, &req.project_id)
+}
+
+/// Every defined field with this project's value, in display order.
+pub(crate) fn project_custom_values(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<Vec<ProjectCustomValueDto>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.name, d.field_type, v.value
+         FROM custom_field_defs d
+         LEFT JOIN project_custom_values v ON v.field_id = d.id AND v.project_id = ?1
+         ORDER BY d.sort_order, d.name COLLATE NOCASE",
+    )?;
+    let rows = stmt
+        .query_map([project_id], |r| {
+            let field_type: String = r.get(2)?;
+            Ok(ProjectCustomValueDto {
+                field_id: r.get(0)?,
+                name: r.get(1)?,
+                field_type: CustomFieldType::parse(&field_type).unwrap_or(CustomFieldType::Text),
+                value: r.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// SQL condition (on `projects p`) and bind value for one list filter.
+pub(crate) fn custom_field_filter_condition(
+    conn: &Connection,
+    filter: &CustomFieldFilter,
+) -> Result<(String, Vec<rusqlite::types::Value>), AppError> {
+    use rusqlite::types::Value;
+
+    let def = load_def(conn, &filter.field_id)?;
+    let value = normalize_value(&def, filter.value.trim())?;
+    let collate = if def.field_type == CustomFieldType::Text {
+        " COLLATE NOCASE"
+    } else {
+        ""
+    };
+    Ok((
+        format!(
+            "p.id IN (SELECT project_id FROM project_custom_values WHERE field_id = ? AND value = ?{})",
+            collate
+        ),
+        vec![Value::Text(def.id), Value::Text(value)],
+    ))
+}
+
+/// Canonical stored form of `raw` for `def`'s type.
+pub fn normalize_value(def: &CustomFieldDefDto, raw: &str) -> Result<String, AppError> {
+    let raw = raw.trim();
+    if raw.chars().count() > MAX_CUSTOM_VALUE_LEN {
+        return Err(AppError::Validation(format!(
+            "value of '{}' is longer than {} characters",
+            def.name, MAX_CUSTOM_VALUE_LEN
+        )));
+    }
+    match def.field_type {
+        CustomFieldType::Text => Ok(raw.to_string()),
+        CustomFieldType::Number => {
+            let n: f64 = raw
+                .parse()
+                .ok()
+                .filter(|n: &f64| n.is_finite())
+                .ok_or_else(|| {
+                    AppError::Validation(format!("'{}' expects a number: {}", def.name, raw))
+                })?;
+            if n.fract() == 0.0 && n.abs() < 1e15 {
+                Ok(format!("{}", n as i64))
+            } else {
+                Ok(n.to_string())
+            }
+        }
+        CustomFieldType::Date => NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .map_err(|_| {
+                AppError::Validation(format!(
+                    "'{}' expects a date (YYYY-MM-DD): {}",
+                    def.name, raw
+                ))
+            }),
+        CustomFieldType::Select => def
+            .options
+            .iter()
+            .find(|o| o.as_str() == raw)
+            .cloned()
+            .ok_or_else(|| {
+                AppError::Validation(format!("'{}' is not an option of '{}'", raw, def.name))
+            }),
+    }
+}
+
+fn normalize_options(
+    field_type: CustomFieldType,
+    options: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    if field_type != CustomFieldType::Select {
+        return Ok(Vec::new());
+    }
+    let mut normalized: Vec<String> = Vec::new();
+    for option in options {
+        let option = option.trim();
+        if option.is_empty() || normalized.iter().any(|o| o == option) {
+            continue;
+        }
+        if option.chars().count() > MAX_CUSTOM_VALUE_LEN {
+            return Err(AppError::Validation("option is too long".into()));
+        }
+        normalized.push(option.to_string());
+    }
+    if normalized.is_empty() {
+        return Err(AppError::Validation(
+            "select fields need at least one option".into(),
+        ));
+    }
+    Ok(normalized)
+}
+
+fn options_json(options: &[String]) -> String {
+    serde_json::to_string(options).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn ensure_field_name_unique(
+    conn: &Connection,
+    name: &str,
+    exclude_id: Option<&str>,
+) -> Result<(), AppError> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(1) FROM custom_field_defs WHERE name = ?1 COLLATE NOCASE AND id <> ?2",
+        params![name, exclude_id.unwrap_or_default()],
+        |r| r.get(0),
+    )?;
+    if count > 0 {
+        return Err(AppError::Conflict(
+            "custom field name must be unique".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn load_def(conn: &Connection, id: &str) -> Result<CustomFieldDefDto, AppError> {
+    let def = conn
+        .query_row(
+            "SELECT id, name, field_type, options, sort_order, created_at, updated_at FROM custom_field_defs WHERE id = ?1",
+            [id],
+            |r| {
+                let field_type: String = r.get(2)?;
+                let options: String = r.get(3)?;
+                Ok(CustomFieldDefDto {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    field_type: CustomFieldType::parse(&field_type)
+                        .unwrap_or(CustomFieldType::Text),
+                    options: serde_json::from_str(&options).unwrap_or_default(),
+                    sort_order: r.get(4)?,
+                    created_at: r.get(5)?,
+                    updated_at: r.get(6)?,
+                })
+            },
+        )
+        .optional()?;
+    def.ok_or_else(|| AppError::NotFound(format!("custom field {}", id)))
+}
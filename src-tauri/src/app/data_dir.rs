@@ -0,0 +1,258 @@
+//! Data dir use cases: describe the current location, guided move to a custom path.
+//!
+//! 移动流程：copy（`VACUUM INTO` 生成一致副本 + 复制日志与默认备份目录）→ verify（integrity_check + 各表行数）
+//! → swap（原子写入 location 指针文件）→ switch（命令层在新目录重新打开数据库并替换运行时状态）
+//! → cleanup（新目录加锁后删除旧目录）。
+//! 校验通过后旧库立即设为只读（`PRAGMA query_only`），移动之后的修改不会写进即将废弃的旧库；
+//! 运行时切换失败时需重启才能继续编辑。
+
+use crate::error::AppError;
+use crate::infra::encryption::{export_database, open_with_key, store_passphrase};
+use crate::infra::layout::{
+    read_data_dir_location, write_data_dir_location, DataDirLocation, PROFILE_DATA_SUBDIRS,
+};
+use crate::infra::{get_connection, DbPool};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Tables compared between the live DB and the copy during verification.
+const VERIFIED_TABLES: &[&str] = &[
+    "projects",
+    "persons",
+    "partners",
+    "assignments",
+    "status_history",
+    "project_size_history",
+    "project_tags",
+    "project_comments",
+    "comment_reactions",
+    "comment_revisions",
+    "checklist_items",
+    "custom_field_defs",
+    "project_custom_values",
+    "sync_metadata",
+    "sync_config",
+];
+
+/// Where the running profile keeps its data and where the pointer file lives.
+pub struct DataDirContext<'a> {
+    pub current_dir: &'a Path,
+    pub default_dir: &'a Path,
+    pub location_file: &'a Path,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirInfoDto {
+    pub current_dir: String,
+    pub default_dir: String,
+    /// Persisted target for the next start (may differ from `current_dir` until restart).
+    pub configured_dir: String,
+    pub is_custom: bool,
+    pub pending_cleanup: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirMoveReq {
+    /// Absolute target path; `None`/empty moves back to the default location.
+    pub target_dir: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirMoveResult {
+    pub from_dir: String,
+    pub to_dir: String,
+    pub copied_files: Vec<String>,
+    /// The running app still uses the old (now read-only) dir: restart to continue editing.
+    pub requires_restart: bool,
+}
+
+pub fn data_dir_info(ctx: &DataDirContext<'_>) -> Result<DataDirInfoDto, AppError> {
+    let location = read_data_dir_location(ctx.location_file).map_err(AppError::DataDir)?;
+    let configured = location
+        .data_dir
+        .clone()
+        .unwrap_or_else(|| ctx.default_dir.to_path_buf());
+    Ok(DataDirInfoDto {
+        current_dir: ctx.current_dir.display().to_string(),
+        default_dir: ctx.default_dir.display().to_string(),
+        configured_dir: configured.display().to_string(),
+        is_custom: location.data_dir.is_some(),
+        pending_cleanup: location.pending_cleanup.map(|p| p.display().to_string()),
+    })
+}
+
+/// Copy the profile data to `req.target_dir`, verify it and point the next start at it.
+pub fn data_dir_move(
+    pool: &DbPool,
+    ctx: &DataDirContext<'_>,
+    req: DataDirMoveReq,
+) -> Result<DataDirMoveResult, AppError> {
+    let target = match req.target_dir.as_deref().map(str::trim) {
+        Some(raw) if !raw.is_empty() => PathBuf::from(raw),
+        _ => ctx.default_dir.to_path_buf(),
+    };
+    validate_target(ctx.current_dir, &target)?;
+
+    fs::create_dir_all(&target)
+        .map_err(|e| AppError::DataDir(format!("Failed to create {:?}: {}", target, e)))?;
+
+    let result = copy_and_verify(pool, ctx.current_dir, &target);
+    let copied_files = match result {
+        Ok(files) => files,
+        Err(e) => {
+            // Leave no half-copied profile behind (the dir itself only if we emptied it).
+            let _ = fs::remove_file(target.join("app.db"));
+            for dir in PROFILE_DATA_SUBDIRS {
+                let _ = fs::remove_dir_all(target.join(dir));
+            }
+            let _ = fs::remove_dir(&target);
+            return Err(e);
+        }
+    };
+
+    let location = DataDirLocation {
+        data_dir: (target != ctx.default_dir).then(|| target.clone()),
+        pending_cleanup: Some(ctx.current_dir.to_path_buf()),
+    };
+    if let Err(e) = write_data_dir_location(ctx.location_file, &location) {
+        // The old dir stays the live one: accept writes again.
+        get_connection(pool).execute_batch("PRAGMA query_only = OFF")?;
+        return Err(AppError::DataDir(e));
+    }
+
+    log::info!(
+        "Data dir moved: {:?} -> {:?} (effective after restart)",
+        ctx.current_dir,
+        target
+    );
+    Ok(DataDirMoveResult {
+        from_dir: ctx.current_dir.display().to_string(),
+        to_dir: target.display().to_string(),
+        copied_files,
+        requires_restart: true,
+    })
+}
+
+fn validate_target(current_dir: &Path, target: &Path) -> Result<(), AppError> {
+    if !target.is_absolute() {
+        return Err(AppError::Validation(format!(
+            "target_dir must be an absolute path: {}",
+            target.display()
+        )));
+    }
+    if target == current_dir {
+        return Err(AppError::Validation(
+            "target_dir is already the current data dir".into(),
+        ));
+    }
+    if target.starts_with(current_dir) || current_dir.starts_with(target) {
+        return Err(AppError::Validation(
+            "target_dir must not be nested with the current data dir".into(),
+        ));
+    }
+    if target.is_file() {
+        return Err(AppError::Validation(format!(
+            "target_dir is a file: {}",
+            target.display()
+        )));
+    }
+    let non_empty = fs::read_dir(target)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if non_empty {
+        return Err(AppError::Validation(format!(
+            "DATA_DIR_NOT_EMPTY: {}",
+            target.display()
+        )));
+    }
+    Ok(())
+}
+
+fn copy_and_verify(
+    pool: &DbPool,
+    current_dir: &Path,
+    target: &Path,
+) -> Result<Vec<String>, AppError> {
+    let target_db = target.join("app.db");
+    let key = pool.0.key();
+    let conn = get_connection(pool);
+    // A consistent, defragmented copy (WAL content included), same passphrase as the live DB.
+    export_database(&conn, &target_db, key.is_some(), key.as_deref())?;
+
+    let mut copied = vec!["app.db".to_string()];
+    for dir in PROFILE_DATA_SUBDIRS {
+        let source = current_dir.join(dir);
+        if source.is_dir() {
+            copy_dir(&source, &target.join(dir), dir, &mut copied)?;
+        }
+    }
+
+    verify_copy(&conn, &target_db, key.as_deref())?;
+    if let Some(key) = &key {
+        // The keychain entry is per database path.
+        store_passphrase(&target_db, key)?;
+    }
+    // Still holding the writer: nothing written from here on would reach the new copy.
+    conn.execute_batch("PRAGMA query_only = ON")?;
+    Ok(copied)
+}
+
+/// Copy `source` into `target` recursively, recording `<label>/<path>` for each file.
+fn copy_dir(
+    source: &Path,
+    target: &Path,
+    label: &str,
+    copied: &mut Vec<String>,
+) -> Result<(), AppError> {
+    fs::create_dir_all(target)
+        .map_err(|e| AppError::DataDir(format!("Failed to create {:?}: {}", target, e)))?;
+    let entries = fs::read_dir(source)
+        .map_err(|e| AppError::DataDir(format!("Failed to read {:?}: {}", source, e)))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let entry_label = format!("{}/{}", label, name.to_string_lossy());
+        if path.is_dir() {
+            copy_dir(&path, &target.join(&name), &entry_label, copied)?;
+        } else if path.is_file() {
+            fs::copy(&path, target.join(&name))
+                .map_err(|e| AppError::DataDir(format!("Failed to copy {:?}: {}", path, e)))?;
+            copied.push(entry_label);
+        }
+    }
+    Ok(())
+}
+
+fn verify_copy(live: &Connection, copy_path: &Path, key: Option<&str>) -> Result<(), AppError> {
+    let copy = open_with_key(copy_path, key)
+        .map_err(|e| AppError::DataDir(format!("Failed to open copy: {}", e)))?;
+    let integrity: String = copy
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| AppError::DataDir(format!("Integrity check failed: {}", e)))?;
+    if integrity != "ok" {
+        return Err(AppError::DataDir(format!(
+            "Integrity check failed: {}",
+            integrity
+        )));
+    }
+
+    for table in VERIFIED_TABLES {
+        let sql = format!("SELECT COUNT(*) FROM {}", table);
+        let expected: i64 = live.query_row(&sql, [], |row| row.get(0))?;
+        let actual: i64 = copy
+            .query_row(&sql, [], |row| row.get(0))
+            .map_err(|e| AppError::DataDir(format!("Verify {} failed: {}", table, e)))?;
+        if expected != actual {
+            return Err(AppError::DataDir(format!(
+                "Verify {} failed: expected {} rows, copy has {}",
+                table, expected, actual
+            )));
+        }
+    }
+    Ok(())
+}
@@ -1,11 +1,22 @@
 //! Export / Import use cases: export all data to JSON, import from JSON,
-//! and person-specific CSV export/import.
+//! person-specific CSV export/import, CSV exports of projects, assignments,
+//! status history and comments with column selection, and project CSV import.
 
+use crate::app::project::{
+    project_create_tx, project_list_filter, ProjectCreateReq, ProjectListReq,
+};
+use crate::domain::country_display_name;
 use crate::error::AppError;
-use crate::infra::{get_connection, DbPool};
-use chrono::Utc;
-use rusqlite::params;
+use crate::infra::xlsx::{XlsxCell, XlsxWorkbook};
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,7 +29,24 @@ pub struct ExportRoot {
     pub projects: Vec<ExportProject>,
     pub assignments: Vec<ExportAssignment>,
     pub status_history: Vec<ExportStatusHistory>,
+    /// Absent in exports made before size estimates existed.
+    #[serde(default)]
+    pub size_history: Vec<ExportSizeHistory>,
     pub comments: Vec<ExportComment>,
+    /// Absent in exports made before comment reactions existed.
+    #[serde(default)]
+    pub comment_reactions: Vec<ExportCommentReaction>,
+    /// Absent in exports made before comment edit history existed.
+    #[serde(default)]
+    pub comment_revisions: Vec<ExportCommentRevision>,
+    /// Absent in exports made before checklists existed.
+    #[serde(default)]
+    pub checklist_items: Vec<ExportChecklistItem>,
+    /// Absent in exports made before custom fields existed.
+    #[serde(default)]
+    pub custom_field_defs: Vec<ExportCustomFieldDef>,
+    #[serde(default)]
+    pub custom_field_values: Vec<ExportCustomFieldValue>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,6 +60,9 @@ pub struct ExportPerson {
     pub is_active: bool,
     pub created_at: String,
     pub updated_at: String,
+    /// Moved to the trash at; absent in exports made before the trash existed.
+    #[serde(default)]
+    pub deleted_at: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -43,6 +74,12 @@ pub struct ExportPartner {
     pub is_active: bool,
     pub created_at: String,
     pub updated_at: String,
+    /// Moved to the trash at; absent in exports made before the trash existed.
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    /// Default budget currency; absent in exports made before budget currencies existed.
+    #[serde(default)]
+    pub default_currency: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -55,6 +92,9 @@ pub struct ExportProject {
     pub priority: i32,
     pub current_status: String,
     pub country_code: String,
+    /// Informational (English ISO 3166 name); ignored on import.
+    #[serde(default)]
+    pub country_name: String,
     pub partner_id: String,
     pub owner_person_id: String,
     pub start_date: Option<String>,
@@ -63,6 +103,30 @@ pub struct ExportProject {
     pub updated_at: String,
     pub archived_at: Option<String>,
     pub tags: Vec<String>,
+    /// Absent in exports made before sub-projects existed.
+    #[serde(default)]
+    pub parent_project_id: Option<String>,
+    /// Kanban rank; absent in exports made before manual ordering existed.
+    #[serde(default)]
+    pub rank: Option<f64>,
+    /// Size estimate in points; absent in exports made before size estimates existed.
+    #[serde(default)]
+    pub size_points: Option<i64>,
+    /// Moved to the trash at; absent in exports made before the trash existed.
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    /// Cloned from; absent in exports made before project cloning existed.
+    #[serde(default)]
+    pub cloned_from_id: Option<String>,
+    /// Estimate in hours; absent in exports made before estimates existed.
+    #[serde(default)]
+    pub estimated_hours: Option<f64>,
+    /// Budget; absent in exports made before budgets existed.
+    #[serde(default)]
+    pub budget_amount: Option<f64>,
+    /// Budget currency (`None` = partner default); absent in exports made before budget currencies existed.
+    #[serde(default)]
+    pub budget_currency: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -89,6 +153,18 @@ pub struct ExportStatusHistory {
     pub note: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSizeHistory {
+    pub id: String,
+    pub project_id: String,
+    pub from_points: Option<i64>,
+    pub to_points: Option<i64>,
+    pub changed_at: String,
+    pub changed_by_person_id: Option<String>,
+    pub note: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportComment {
@@ -99,6 +175,70 @@ pub struct ExportComment {
     pub is_pinned: bool,
     pub created_at: String,
     pub updated_at: String,
+    /// Top-level comment of a reply; absent in exports made before comment threads existed.
+    #[serde(default)]
+    pub parent_comment_id: Option<String>,
+    /// Position among pinned comments; absent in exports made before pin order existed.
+    #[serde(default)]
+    pub pin_order: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportCommentReaction {
+    pub id: String,
+    pub comment_id: String,
+    pub project_id: String,
+    pub person_id: String,
+    pub emoji: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportCommentRevision {
+    pub id: String,
+    pub comment_id: String,
+    pub project_id: String,
+    pub person_id: Option<String>,
+    pub content: String,
+    pub written_at: String,
+    pub replaced_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportChecklistItem {
+    pub id: String,
+    pub project_id: String,
+    pub text: String,
+    pub done: bool,
+    pub sort_order: i64,
+    pub done_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportCustomFieldDef {
+    pub id: String,
+    pub name: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub sort_order: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportCustomFieldValue {
+    pub id: String,
+    pub project_id: String,
+    pub field_id: String,
+    pub value: String,
+    pub updated_at: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -109,16 +249,46 @@ pub struct ImportResult {
     pub projects: usize,
     pub assignments: usize,
     pub status_history: usize,
+    pub size_history: usize,
     pub comments: usize,
+    pub comment_reactions: usize,
+    pub comment_revisions: usize,
+    pub checklist_items: usize,
+    pub custom_field_defs: usize,
+    pub custom_field_values: usize,
     pub skipped_duplicates: usize,
 }
 
+impl ImportResult {
+    /// Rows newly inserted across all tables (tags excluded).
+    pub fn imported_rows(&self) -> usize {
+        self.persons
+            + self.partners
+            + self.projects
+            + self.assignments
+            + self.status_history
+            + self.size_history
+            + self.comments
+            + self.comment_reactions
+            + self.comment_revisions
+            + self.checklist_items
+            + self.custom_field_defs
+            + self.custom_field_values
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WipeResult {
     pub wipe_id: String,
+    pub deleted_comment_reactions: usize,
+    pub deleted_comment_revisions: usize,
     pub deleted_project_comments: usize,
+    pub deleted_checklist_items: usize,
+    pub deleted_project_custom_values: usize,
+    pub deleted_custom_field_defs: usize,
     pub deleted_status_history: usize,
+    pub deleted_size_history: usize,
     pub deleted_assignments: usize,
     pub deleted_project_tags: usize,
     pub deleted_projects: usize,
@@ -126,162 +296,649 @@ pub struct WipeResult {
     pub deleted_partners: usize,
 }
 
+/// Selects part of the workspace for the JSON export; the default exports everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonExportReq {
+    /// Export only these projects (with their related rows); combined with `filter` as a union.
+    pub project_ids: Option<Vec<String>>,
+    /// Export only projects matching the `project_list` filters (sort and paging ignored).
+    pub filter: Option<ProjectListReq>,
+    /// Default true.
+    pub include_comments: Option<bool>,
+    /// Status and size history; default true.
+    pub include_history: Option<bool>,
+    /// Only rows created or changed at / after this RFC 3339 timestamp or `YYYY-MM-DD` (UTC).
+    pub changed_since: Option<String>,
+}
+
 /// Export all data as JSON string
 pub fn export_json_string(pool: &DbPool, _schema_version: Option<i32>) -> Result<String, AppError> {
-    let schema_version = 3; // Current schema version (projects.productName added)
-    let exported_at = Utc::now().to_rfc3339();
-
-    let conn = get_connection(pool);
-
-    // 1. Export persons
-    let mut persons = Vec::new();
-    let mut stmt = conn
-        .prepare("SELECT id, display_name, email, role, note, is_active, created_at, updated_at FROM persons ORDER BY display_name")
-        .map_err(|e| AppError::Db(e.to_string()))?;
-    let mut rows = stmt.query([]).map_err(|e| AppError::Db(e.to_string()))?;
-    while let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? {
-        persons.push(ExportPerson {
-            id: row.get(0)?,
-            display_name: row.get(1)?,
-            email: row.get(2)?,
-            role: row.get(3)?,
-            note: row.get(4)?,
-            is_active: row.get::<_, i32>(5)? != 0,
-            created_at: row.get(6)?,
-            updated_at: row.get(7)?,
-        });
-    }
-
-    // 2. Export partners
-    let mut partners = Vec::new();
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, name, note, is_active, created_at, updated_at FROM partners ORDER BY name",
-        )
-        .map_err(|e| AppError::Db(e.to_string()))?;
-    let mut rows = stmt.query([]).map_err(|e| AppError::Db(e.to_string()))?;
-    while let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? {
-        partners.push(ExportPartner {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            note: row.get(2)?,
-            is_active: row.get::<_, i32>(3)? != 0,
-            created_at: row.get(4)?,
-            updated_at: row.get(5)?,
-        });
-    }
+    export_json_selective(pool, JsonExportReq::default())
+}
 
-    // 3. Export projects (with tags)
-    let mut projects = Vec::new();
-    let mut stmt = conn
-        .prepare("SELECT id, name, product_name, description, priority, current_status, country_code, partner_id, owner_person_id, start_date, due_date, created_at, updated_at, archived_at FROM projects ORDER BY created_at DESC")
-        .map_err(|e| AppError::Db(e.to_string()))?;
-    let mut rows = stmt.query([]).map_err(|e| AppError::Db(e.to_string()))?;
-    while let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? {
-        let project_id: String = row.get(0)?;
+/// Export the selected part of the workspace as a JSON string in the `export_json_string` format.
+///
+/// With a project selection only those projects are exported, together with their assignments,
+/// history, comments, checklists, custom field values, and the persons / partners / field definitions they
+/// reference; `parentProjectId` is cleared when the parent is not exported.
+pub fn export_json_selective(pool: &DbPool, req: JsonExportReq) -> Result<String, AppError> {
+    let exported_at = Utc::now().to_rfc3339();
 
-        // Get tags for this project
-        let mut tags = Vec::new();
-        let mut tag_stmt = conn
-            .prepare("SELECT tag FROM project_tags WHERE project_id = ?1 ORDER BY tag")
-            .map_err(|e| AppError::Db(e.to_string()))?;
-        let tag_rows = tag_stmt
-            .query_map([&project_id], |r| r.get::<_, String>(0))
-            .map_err(|e| AppError::Db(e.to_string()))?;
-        for tag in tag_rows.flatten() {
-            tags.push(tag);
-        }
-
-        projects.push(ExportProject {
-            id: project_id,
-            name: row.get(1)?,
-            product_name: row.get(2)?,
-            description: row.get(3)?,
-            priority: row.get(4)?,
-            current_status: row.get(5)?,
-            country_code: row.get(6)?,
-            partner_id: row.get(7)?,
-            owner_person_id: row.get(8)?,
-            start_date: row.get(9)?,
-            due_date: row.get(10)?,
-            created_at: row.get(11)?,
-            updated_at: row.get(12)?,
-            archived_at: row.get(13)?,
-            tags,
-        });
+    let conn = get_read_connection(pool);
+    let selected = selected_project_ids(&conn, &req)?;
+    let changed_since = req
+        .changed_since
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(parse_changed_since)
+        .transpose()?;
+
+    let mut persons = load_rows(&conn, EXPORT_PERSONS_SQL, |row| Ok(export_person(row)?))?;
+    let mut partners = load_rows(&conn, EXPORT_PARTNERS_SQL, |row| Ok(export_partner(row)?))?;
+    let mut projects = load_rows(&conn, EXPORT_PROJECTS_SQL, |row| export_project(&conn, row))?;
+    let mut assignments = load_rows(&conn, EXPORT_ASSIGNMENTS_SQL, |row| {
+        Ok(export_assignment(row)?)
+    })?;
+    let mut status_history = load_rows(&conn, EXPORT_STATUS_HISTORY_SQL, |row| {
+        Ok(export_status_history(row)?)
+    })?;
+    let mut size_history = load_rows(&conn, EXPORT_SIZE_HISTORY_SQL, |row| {
+        Ok(export_size_history(row)?)
+    })?;
+    let mut comments = load_rows(&conn, EXPORT_COMMENTS_SQL, |row| Ok(export_comment(row)?))?;
+    let mut comment_reactions = load_rows(&conn, EXPORT_COMMENT_REACTIONS_SQL, |row| {
+        Ok(export_comment_reaction(row)?)
+    })?;
+    let mut comment_revisions = load_rows(&conn, EXPORT_COMMENT_REVISIONS_SQL, |row| {
+        Ok(export_comment_revision(row)?)
+    })?;
+    let mut checklist_items = load_rows(&conn, EXPORT_CHECKLIST_ITEMS_SQL, |row| {
+        Ok(export_checklist_item(row)?)
+    })?;
+    let mut custom_field_defs = load_rows(&conn, EXPORT_CUSTOM_FIELD_DEFS_SQL, |row| {
+        Ok(export_custom_field_def(row)?)
+    })?;
+    let mut custom_field_values = load_rows(&conn, EXPORT_CUSTOM_FIELD_VALUES_SQL, |row| {
+        Ok(export_custom_field_value(row)?)
+    })?;
+
+    if !req.include_comments.unwrap_or(true) {
+        comments.clear();
+        comment_reactions.clear();
+        comment_revisions.clear();
     }
-
-    // 4. Export assignments
-    let mut assignments = Vec::new();
-    let mut stmt = conn
-        .prepare("SELECT id, project_id, person_id, role, start_at, end_at, created_at FROM assignments ORDER BY start_at DESC")
-        .map_err(|e| AppError::Db(e.to_string()))?;
-    let mut rows = stmt.query([]).map_err(|e| AppError::Db(e.to_string()))?;
-    while let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? {
-        assignments.push(ExportAssignment {
-            id: row.get(0)?,
-            project_id: row.get(1)?,
-            person_id: row.get(2)?,
-            role: row.get(3)?,
-            start_at: row.get(4)?,
-            end_at: row.get(5)?,
-            created_at: row.get(6)?,
-        });
+    if !req.include_history.unwrap_or(true) {
+        status_history.clear();
+        size_history.clear();
     }
 
-    // 5. Export status history
-    let mut status_history = Vec::new();
-    let mut stmt = conn
-        .prepare("SELECT id, project_id, from_status, to_status, changed_at, changed_by_person_id, note FROM status_history ORDER BY changed_at DESC")
-        .map_err(|e| AppError::Db(e.to_string()))?;
-    let mut rows = stmt.query([]).map_err(|e| AppError::Db(e.to_string()))?;
-    while let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? {
-        status_history.push(ExportStatusHistory {
-            id: row.get(0)?,
-            project_id: row.get(1)?,
-            from_status: row.get(2)?,
-            to_status: row.get(3)?,
-            changed_at: row.get(4)?,
-            changed_by_person_id: row.get(5)?,
-            note: row.get(6)?,
-        });
+    if let Some(ids) = &selected {
+        projects.retain(|p| ids.contains(&p.id));
+        for project in &mut projects {
+            if project
+                .parent_project_id
+                .as_ref()
+                .is_some_and(|parent| !ids.contains(parent))
+            {
+                project.parent_project_id = None;
+            }
+        }
+        assignments.retain(|a| ids.contains(&a.project_id));
+        status_history.retain(|h| ids.contains(&h.project_id));
+        size_history.retain(|h| ids.contains(&h.project_id));
+        comments.retain(|c| ids.contains(&c.project_id));
+        comment_reactions.retain(|r| ids.contains(&r.project_id));
+        comment_revisions.retain(|r| ids.contains(&r.project_id));
+        checklist_items.retain(|i| ids.contains(&i.project_id));
+        custom_field_values.retain(|v| ids.contains(&v.project_id));
+
+        let field_ids: HashSet<&str> = custom_field_values
+            .iter()
+            .map(|v| v.field_id.as_str())
+            .collect();
+        custom_field_defs.retain(|d| field_ids.contains(d.id.as_str()));
+        let partner_ids: HashSet<&str> = projects.iter().map(|p| p.partner_id.as_str()).collect();
+        partners.retain(|p| partner_ids.contains(p.id.as_str()));
+        let person_ids: HashSet<&str> = projects
+            .iter()
+            .map(|p| p.owner_person_id.as_str())
+            .chain(assignments.iter().map(|a| a.person_id.as_str()))
+            .chain(
+                status_history
+                    .iter()
+                    .filter_map(|h| h.changed_by_person_id.as_deref()),
+            )
+            .chain(
+                size_history
+                    .iter()
+                    .filter_map(|h| h.changed_by_person_id.as_deref()),
+            )
+            .chain(comments.iter().filter_map(|c| c.person_id.as_deref()))
+            .chain(comment_reactions.iter().map(|r| r.person_id.as_str()))
+            .chain(
+                comment_revisions
+                    .iter()
+                    .filter_map(|r| r.person_id.as_deref()),
+            )
+            .collect();
+        persons.retain(|p| person_ids.contains(p.id.as_str()));
     }
 
-    // 6. Export comments
-    let mut comments = Vec::new();
-    let mut stmt = conn
-        .prepare("SELECT id, project_id, person_id, content, is_pinned, created_at, updated_at FROM project_comments ORDER BY created_at DESC")
-        .map_err(|e| AppError::Db(e.to_string()))?;
-    let mut rows = stmt.query([]).map_err(|e| AppError::Db(e.to_string()))?;
-    while let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? {
-        comments.push(ExportComment {
-            id: row.get(0)?,
-            project_id: row.get(1)?,
-            person_id: row.get(2)?,
-            content: row.get(3)?,
-            is_pinned: row.get::<_, i32>(4)? != 0,
-            created_at: row.get(5)?,
-            updated_at: row.get(6)?,
+    if let Some(since) = &changed_since {
+        let since = since.as_str();
+        persons.retain(|p| p.updated_at.as_str() >= since);
+        partners.retain(|p| p.updated_at.as_str() >= since);
+        projects.retain(|p| p.updated_at.as_str() >= since);
+        assignments.retain(|a| {
+            a.created_at.as_str() >= since || a.end_at.as_deref().is_some_and(|e| e >= since)
         });
+        status_history.retain(|h| h.changed_at.as_str() >= since);
+        size_history.retain(|h| h.changed_at.as_str() >= since);
+        comments.retain(|c| c.updated_at.as_str() >= since);
+        comment_reactions.retain(|r| r.created_at.as_str() >= since);
+        comment_revisions.retain(|r| r.replaced_at.as_str() >= since);
+        checklist_items.retain(|i| i.updated_at.as_str() >= since);
+        custom_field_defs.retain(|d| d.updated_at.as_str() >= since);
+        custom_field_values.retain(|v| v.updated_at.as_str() >= since);
     }
 
     let export_root = ExportRoot {
-        schema_version,
+        schema_version: JSON_EXPORT_SCHEMA_VERSION,
         exported_at,
         persons,
         partners,
         projects,
         assignments,
         status_history,
+        size_history,
         comments,
+        comment_reactions,
+        comment_revisions,
+        checklist_items,
+        custom_field_defs,
+        custom_field_values,
     };
 
     serde_json::to_string_pretty(&export_root)
         .map_err(|e| AppError::Db(format!("JSON serialization failed: {}", e)))
 }
 
+// ──────────────────────── JSON export rows ────────────────────────
+
+/// Current JSON export schema version (projects.productName added).
+const JSON_EXPORT_SCHEMA_VERSION: i32 = 3;
+
+const EXPORT_PERSONS_SQL: &str = "SELECT id, display_name, email, role, note, is_active, created_at, updated_at, deleted_at FROM persons ORDER BY display_name";
+const EXPORT_PARTNERS_SQL: &str =
+    "SELECT id, name, note, is_active, created_at, updated_at, deleted_at, default_currency FROM partners ORDER BY name";
+const EXPORT_PROJECTS_SQL: &str = "SELECT id, name, product_name, description, priority, current_status, country_code, partner_id, owner_person_id, start_date, due_date, created_at, updated_at, archived_at, parent_project_id, rank, size_points, deleted_at, cloned_from_id, estimated_hours, budget_amount, budget_currency FROM projects ORDER BY created_at DESC";
+const EXPORT_ASSIGNMENTS_SQL: &str = "SELECT id, project_id, person_id, role, start_at, end_at, created_at FROM assignments ORDER BY start_at DESC";
+const EXPORT_STATUS_HISTORY_SQL: &str = "SELECT id, project_id, from_status, to_status, changed_at, changed_by_person_id, note FROM status_history ORDER BY changed_at DESC";
+const EXPORT_SIZE_HISTORY_SQL: &str = "SELECT id, project_id, from_points, to_points, changed_at, changed_by_person_id, note FROM project_size_history ORDER BY changed_at DESC";
+const EXPORT_COMMENTS_SQL: &str = "SELECT id, project_id, person_id, content, is_pinned, created_at, updated_at, parent_comment_id, pin_order FROM project_comments ORDER BY created_at DESC";
+const EXPORT_COMMENT_REACTIONS_SQL: &str = "SELECT id, comment_id, project_id, person_id, emoji, created_at FROM comment_reactions ORDER BY created_at";
+const EXPORT_COMMENT_REVISIONS_SQL: &str = "SELECT id, comment_id, project_id, person_id, content, written_at, replaced_at FROM comment_revisions ORDER BY replaced_at";
+const EXPORT_CHECKLIST_ITEMS_SQL: &str = "SELECT id, project_id, text, done, sort_order, done_at, created_at, updated_at FROM checklist_items ORDER BY project_id, sort_order, created_at";
+const EXPORT_CUSTOM_FIELD_DEFS_SQL: &str = "SELECT id, name, field_type, options, sort_order, created_at, updated_at FROM custom_field_defs ORDER BY sort_order, name";
+const EXPORT_CUSTOM_FIELD_VALUES_SQL: &str = "SELECT id, project_id, field_id, value, updated_at FROM project_custom_values ORDER BY project_id, field_id";
+
+/// Run `sql` and hand every row to `f`; returns the number of rows.
+fn for_each_row(
+    conn: &Connection,
+    sql: &str,
+    mut f: impl FnMut(&Row<'_>) -> Result<(), AppError>,
+) -> Result<usize, AppError> {
+    let mut stmt = conn.prepare(sql).map_err(|e| AppError::Db(e.to_string()))?;
+    let mut rows = stmt.query([]).map_err(|e| AppError::Db(e.to_string()))?;
+    let mut count = 0;
+    while let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? {
+        f(row)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn load_rows<T>(
+    conn: &Connection,
+    sql: &str,
+    mut map: impl FnMut(&Row<'_>) -> Result<T, AppError>,
+) -> Result<Vec<T>, AppError> {
+    let mut items = Vec::new();
+    for_each_row(conn, sql, |row| {
+        items.push(map(row)?);
+        Ok(())
+    })?;
+    Ok(items)
+}
+
+fn export_person(row: &Row<'_>) -> rusqlite::Result<ExportPerson> {
+    Ok(ExportPerson {
+        id: row.get(0)?,
+        display_name: row.get(1)?,
+        email: row.get(2)?,
+        role: row.get(3)?,
+        note: row.get(4)?,
+        is_active: row.get::<_, i32>(5)? != 0,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+        deleted_at: row.get(8)?,
+    })
+}
+
+fn export_partner(row: &Row<'_>) -> rusqlite::Result<ExportPartner> {
+    Ok(ExportPartner {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        note: row.get(2)?,
+        is_active: row.get::<_, i32>(3)? != 0,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+        deleted_at: row.get(6)?,
+        default_currency: row.get(7)?,
+    })
+}
+
+/// A project row with its tags.
+fn export_project(conn: &Connection, row: &Row<'_>) -> Result<ExportProject, AppError> {
+    let project_id: String = row.get(0)?;
+
+    let mut tags = Vec::new();
+    let mut tag_stmt = conn
+        .prepare_cached("SELECT tag FROM project_tags WHERE project_id = ?1 ORDER BY tag")
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let tag_rows = tag_stmt
+        .query_map([&project_id], |r| r.get::<_, String>(0))
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    for tag in tag_rows.flatten() {
+        tags.push(tag);
+    }
+
+    let country_code: String = row.get(6)?;
+    Ok(ExportProject {
+        id: project_id,
+        name: row.get(1)?,
+        product_name: row.get(2)?,
+        description: row.get(3)?,
+        priority: row.get(4)?,
+        current_status: row.get(5)?,
+        country_name: country_display_name(&country_code),
+        country_code,
+        partner_id: row.get(7)?,
+        owner_person_id: row.get(8)?,
+        start_date: row.get(9)?,
+        due_date: row.get(10)?,
+        created_at: row.get(11)?,
+        updated_at: row.get(12)?,
+        archived_at: row.get(13)?,
+        tags,
+        parent_project_id: row.get(14)?,
+        rank: row.get(15)?,
+        size_points: row.get(16)?,
+        deleted_at: row.get(17)?,
+        cloned_from_id: row.get(18)?,
+        estimated_hours: row.get(19)?,
+        budget_amount: row.get(20)?,
+        budget_currency: row.get(21)?,
+    })
+}
+
+fn export_assignment(row: &Row<'_>) -> rusqlite::Result<ExportAssignment> {
+    Ok(ExportAssignment {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        person_id: row.get(2)?,
+        role: row.get(3)?,
+        start_at: row.get(4)?,
+        end_at: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn export_status_history(row: &Row<'_>) -> rusqlite::Result<ExportStatusHistory> {
+    Ok(ExportStatusHistory {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        from_status: row.get(2)?,
+        to_status: row.get(3)?,
+        changed_at: row.get(4)?,
+        changed_by_person_id: row.get(5)?,
+        note: row.get(6)?,
+    })
+}
+
+fn export_size_history(row: &Row<'_>) -> rusqlite::Result<ExportSizeHistory> {
+    Ok(ExportSizeHistory {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        from_points: row.get(2)?,
+        to_points: row.get(3)?,
+        changed_at: row.get(4)?,
+        changed_by_person_id: row.get(5)?,
+        note: row.get(6)?,
+    })
+}
+
+fn export_comment(row: &Row<'_>) -> rusqlite::Result<ExportComment> {
+    Ok(ExportComment {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        person_id: row.get(2)?,
+        content: row.get(3)?,
+        is_pinned: row.get::<_, i32>(4)? != 0,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+        parent_comment_id: row.get(7)?,
+        pin_order: row.get(8)?,
+    })
+}
+
+fn export_comment_reaction(row: &Row<'_>) -> rusqlite::Result<ExportCommentReaction> {
+    Ok(ExportCommentReaction {
+        id: row.get(0)?,
+        comment_id: row.get(1)?,
+        project_id: row.get(2)?,
+        person_id: row.get(3)?,
+        emoji: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn export_comment_revision(row: &Row<'_>) -> rusqlite::Result<ExportCommentRevision> {
+    Ok(ExportCommentRevision {
+        id: row.get(0)?,
+        comment_id: row.get(1)?,
+        project_id: row.get(2)?,
+        person_id: row.get(3)?,
+        content: row.get(4)?,
+        written_at: row.get(5)?,
+        replaced_at: row.get(6)?,
+    })
+}
+
+fn export_checklist_item(row: &Row<'_>) -> rusqlite::Result<ExportChecklistItem> {
+    Ok(ExportChecklistItem {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        text: row.get(2)?,
+        done: row.get::<_, i32>(3)? != 0,
+        sort_order: row.get(4)?,
+        done_at: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn export_custom_field_def(row: &Row<'_>) -> rusqlite::Result<ExportCustomFieldDef> {
+    let options: String = row.get(3)?;
+    Ok(ExportCustomFieldDef {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        field_type: row.get(2)?,
+        options: serde_json::from_str(&options).unwrap_or_default(),
+        sort_order: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+fn export_custom_field_value(row: &Row<'_>) -> rusqlite::Result<ExportCustomFieldValue> {
+    Ok(ExportCustomFieldValue {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        field_id: row.get(2)?,
+        value: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+// ──────────────────────── Streaming JSON export ────────────────────────
+
+/// Progress is reported after this many rows (and at the end of every section).
+const JSON_EXPORT_PROGRESS_EVERY: usize = 1000;
+
+/// Progress of `export_json_to_file`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonExportProgress {
+    /// Section being written (`persons`, `projects`, `statusHistory`, ...).
+    pub section: &'static str,
+    /// Rows written so far across all sections.
+    pub rows_written: usize,
+    pub total_rows: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonExportFileResult {
+    pub path: String,
+    pub bytes: u64,
+    pub rows: usize,
+}
+
+/// Write the whole workspace to `path` in the `export_json_string` format, one row at a time,
+/// so memory stays flat on large databases. The rows are read in one transaction (consistent
+/// with each other) and the file only replaces `path` once complete.
+pub fn export_json_to_file(
+    pool: &DbPool,
+    path: &Path,
+    mut on_progress: impl FnMut(&JsonExportProgress),
+) -> Result<JsonExportFileResult, AppError> {
+    type RowWriter<'a> = &'a dyn Fn(&Connection, &Row<'_>, &mut dyn Write) -> Result<(), AppError>;
+    fn write_json<T: Serialize>(out: &mut dyn Write, value: &T) -> Result<(), AppError> {
+        serde_json::to_writer(out, value)
+            .map_err(|e| AppError::Export(format!("JSON serialization failed: {}", e)))
+    }
+    let sections: [(&'static str, &str, &str, RowWriter); 12] = [
+        ("persons", "persons", EXPORT_PERSONS_SQL, &|_, row, out| {
+            write_json(out, &export_person(row)?)
+        }),
+        (
+            "partners",
+            "partners",
+            EXPORT_PARTNERS_SQL,
+            &|_, row, out| write_json(out, &export_partner(row)?),
+        ),
+        (
+            "projects",
+            "projects",
+            EXPORT_PROJECTS_SQL,
+            &|conn, row, out| write_json(out, &export_project(conn, row)?),
+        ),
+        (
+            "assignments",
+            "assignments",
+            EXPORT_ASSIGNMENTS_SQL,
+            &|_, row, out| write_json(out, &export_assignment(row)?),
+        ),
+        (
+            "statusHistory",
+            "status_history",
+            EXPORT_STATUS_HISTORY_SQL,
+            &|_, row, out| write_json(out, &export_status_history(row)?),
+        ),
+        (
+            "sizeHistory",
+            "project_size_history",
+            EXPORT_SIZE_HISTORY_SQL,
+            &|_, row, out| write_json(out, &export_size_history(row)?),
+        ),
+        (
+            "comments",
+            "project_comments",
+            EXPORT_COMMENTS_SQL,
+            &|_, row, out| write_json(out, &export_comment(row)?),
+        ),
+        (
+            "commentReactions",
+            "comment_reactions",
+            EXPORT_COMMENT_REACTIONS_SQL,
+            &|_, row, out| write_json(out, &export_comment_reaction(row)?),
+        ),
+        (
+            "commentRevisions",
+            "comment_revisions",
+            EXPORT_COMMENT_REVISIONS_SQL,
+            &|_, row, out| write_json(out, &export_comment_revision(row)?),
+        ),
+        (
+            "checklistItems",
+            "checklist_items",
+            EXPORT_CHECKLIST_ITEMS_SQL,
+            &|_, row, out| write_json(out, &export_checklist_item(row)?),
+        ),
+        (
+            "customFieldDefs",
+            "custom_field_defs",
+            EXPORT_CUSTOM_FIELD_DEFS_SQL,
+            &|_, row, out| write_json(out, &export_custom_field_def(row)?),
+        ),
+        (
+            "customFieldValues",
+            "project_custom_values",
+            EXPORT_CUSTOM_FIELD_VALUES_SQL,
+            &|_, row, out| write_json(out, &export_custom_field_value(row)?),
+        ),
+    ];
+    let io_err = |e: std::io::Error| AppError::Export(format!("Failed to write {:?}: {}", path, e));
+
+    let conn = get_read_connection(pool);
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let mut total_rows = 0;
+    for (_, table, _, _) in &sections {
+        let count: i64 =
+            tx.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0))?;
+        total_rows += count as usize;
+    }
+
+    let part_path = path.with_extension("json.part");
+    let file = File::create(&part_path).map_err(io_err)?;
+    let mut out = BufWriter::new(file);
+    let result = (|| -> Result<usize, AppError> {
+        write!(
+            out,
+            "{{\"schemaVersion\":{},\"exportedAt\":",
+            JSON_EXPORT_SCHEMA_VERSION
+        )
+        .map_err(io_err)?;
+        write_json(&mut out, &Utc::now().to_rfc3339())?;
+
+        let mut rows_written = 0;
+        for (section, _, sql, write_row) in &sections {
+            let section = *section;
+            write!(out, ",\"{}\":[", section).map_err(io_err)?;
+            let mut first = true;
+            for_each_row(&tx, sql, |row| {
+                if !first {
+                    out.write_all(b",").map_err(io_err)?;
+                }
+                first = false;
+                write_row(&tx, row, &mut out)?;
+                rows_written += 1;
+                if rows_written % JSON_EXPORT_PROGRESS_EVERY == 0 {
+                    on_progress(&JsonExportProgress {
+                        section,
+                        rows_written,
+                        total_rows,
+                    });
+                }
+                Ok(())
+            })?;
+            out.write_all(b"]").map_err(io_err)?;
+            on_progress(&JsonExportProgress {
+                section,
+                rows_written,
+                total_rows,
+            });
+        }
+        out.write_all(b"}").map_err(io_err)?;
+        out.flush().map_err(io_err)?;
+        Ok(rows_written)
+    })();
+    drop(out);
+    drop(tx);
+
+    let rows = match result {
+        Ok(rows) => rows,
+        Err(e) => {
+            let _ = fs::remove_file(&part_path);
+            return Err(e);
+        }
+    };
+    fs::rename(&part_path, path).map_err(io_err)?;
+    let bytes = fs::metadata(path).map_err(io_err)?.len();
+    Ok(JsonExportFileResult {
+        path: path.to_string_lossy().into_owned(),
+        bytes,
+        rows,
+    })
+}
+
+/// Union of `project_ids` and the `filter` matches; `None` when neither is given (export everything).
+fn selected_project_ids(
+    conn: &Connection,
+    req: &JsonExportReq,
+) -> Result<Option<HashSet<String>>, AppError> {
+    let ids: Vec<&String> = req
+        .project_ids
+        .iter()
+        .flatten()
+        .filter(|id| !id.trim().is_empty())
+        .collect();
+    if ids.is_empty() && req.filter.is_none() {
+        return Ok(None);
+    }
+
+    let mut selected = HashSet::new();
+    for id in ids {
+        let exists = conn
+            .query_row("SELECT 1 FROM projects WHERE id = ?1", [id], |_| Ok(()))
+            .optional()?
+            .is_some();
+        if !exists {
+            return Err(AppError::NotFound(format!("project {}", id)));
+        }
+        selected.insert(id.clone());
+    }
+    if let Some(filter) = &req.filter {
+        let (where_clause, bind_values) = project_list_filter(conn, filter)?;
+        let mut stmt = conn.prepare(&format!("SELECT p.id FROM projects p{}", where_clause))?;
+        let rows = stmt.query_map(params_from_iter(bind_values.iter()), |r| {
+            r.get::<_, String>(0)
+        })?;
+        for id in rows {
+            selected.insert(id?);
+        }
+    }
+    Ok(Some(selected))
+}
+
+/// Normalize `changed_since` to a UTC RFC 3339 string comparable with stored timestamps.
+fn parse_changed_since(raw: &str) -> Result<String, AppError> {
+    let raw = raw.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(at.with_timezone(&Utc).to_rfc3339());
+    }
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map(|d| d.and_time(NaiveTime::MIN).and_utc().to_rfc3339())
+        .map_err(|_| {
+            AppError::Validation(format!(
+                "changedSince must be an RFC 3339 timestamp or YYYY-MM-DD: {}",
+                raw
+            ))
+        })
+}
+
 /// Import data from JSON string. Uses INSERT OR IGNORE for idempotency (duplicate IDs are skipped).
 pub fn import_json_string(pool: &DbPool, json: &str) -> Result<ImportResult, AppError> {
+    let conn = get_connection(pool);
+    import_json_conn(&conn, json)
+}
+
+/// `import_json_string` on a connection the caller already holds.
+pub(crate) fn import_json_conn(conn: &Connection, json: &str) -> Result<ImportResult, AppError> {
     let root: ExportRoot = serde_json::from_str(json)
         .map_err(|e| AppError::Validation(format!("Invalid JSON: {}", e)))?;
 
@@ -293,7 +950,6 @@ pub fn import_json_string(pool: &DbPool, json: &str) -> Result<ImportResult, App
         )));
     }
 
-    let conn = get_connection(pool);
     let tx = conn
         .unchecked_transaction()
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -304,8 +960,8 @@ pub fn import_json_string(pool: &DbPool, json: &str) -> Result<ImportResult, App
     let mut persons_count = 0usize;
     for p in &root.persons {
         let changed = tx.execute(
-            "INSERT OR IGNORE INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![p.id, p.display_name, p.email, p.role, p.note, p.is_active as i32, p.created_at, p.updated_at],
+            "INSERT OR IGNORE INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at, deleted_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![p.id, p.display_name, p.email, p.role, p.note, p.is_active as i32, p.created_at, p.updated_at, p.deleted_at],
         ).map_err(|e| AppError::Db(e.to_string()))?;
         if changed > 0 {
             persons_count += 1;
@@ -318,8 +974,8 @@ pub fn import_json_string(pool: &DbPool, json: &str) -> Result<ImportResult, App
     let mut partners_count = 0usize;
     for p in &root.partners {
         let changed = tx.execute(
-            "INSERT OR IGNORE INTO partners (id, name, note, is_active, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![p.id, p.name, p.note, p.is_active as i32, p.created_at, p.updated_at],
+            "INSERT OR IGNORE INTO partners (id, name, note, is_active, created_at, updated_at, deleted_at, default_currency) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![p.id, p.name, p.note, p.is_active as i32, p.created_at, p.updated_at, p.deleted_at, p.default_currency],
         ).map_err(|e| AppError::Db(e.to_string()))?;
         if changed > 0 {
             partners_count += 1;
@@ -345,8 +1001,8 @@ pub fn import_json_string(pool: &DbPool, json: &str) -> Result<ImportResult, App
         }
 
         let changed = tx.execute(
-            "INSERT OR IGNORE INTO projects (id, name, product_name, description, priority, current_status, country_code, partner_id, owner_person_id, start_date, due_date, created_at, updated_at, archived_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
-            params![p.id, p.name, p.product_name, p.description, p.priority, p.current_status, p.country_code, p.partner_id, p.owner_person_id, p.start_date, p.due_date, p.created_at, p.updated_at, p.archived_at],
+            "INSERT OR IGNORE INTO projects (id, name, product_name, description, priority, current_status, country_code, partner_id, owner_person_id, start_date, due_date, created_at, updated_at, archived_at, parent_project_id, rank, size_points, deleted_at, cloned_from_id, estimated_hours, budget_amount, budget_currency) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
+            params![p.id, p.name, p.product_name, p.description, p.priority, p.current_status, p.country_code, p.partner_id, p.owner_person_id, p.start_date, p.due_date, p.created_at, p.updated_at, p.archived_at, p.parent_project_id, p.rank, p.size_points, p.deleted_at, p.cloned_from_id, p.estimated_hours, p.budget_amount, p.budget_currency],
         ).map_err(|e| AppError::Db(e.to_string()))?;
         if changed > 0 {
             projects_count += 1;
@@ -390,12 +1046,26 @@ pub fn import_json_string(pool: &DbPool, json: &str) -> Result<ImportResult, App
         }
     }
 
+    // 5b. Import size history (entries of skipped projects are skipped too)
+    let mut size_history_count = 0usize;
+    for h in &root.size_history {
+        let changed = tx.execute(
+            "INSERT OR IGNORE INTO project_size_history (id, project_id, from_points, to_points, changed_at, changed_by_person_id, note) SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7 WHERE EXISTS (SELECT 1 FROM projects WHERE id = ?2)",
+            params![h.id, h.project_id, h.from_points, h.to_points, h.changed_at, h.changed_by_person_id, h.note],
+        ).map_err(|e| AppError::Db(e.to_string()))?;
+        if changed > 0 {
+            size_history_count += 1;
+        } else {
+            skipped += 1;
+        }
+    }
+
     // 6. Import comments (schema version 2 only)
     let mut comments_count = 0usize;
     for c in &root.comments {
         let changed = tx.execute(
-            "INSERT OR IGNORE INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![c.id, c.project_id, c.person_id, c.content, c.is_pinned as i32, c.created_at, c.updated_at],
+            "INSERT OR IGNORE INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at, parent_comment_id, pin_order) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![c.id, c.project_id, c.person_id, c.content, c.is_pinned as i32, c.created_at, c.updated_at, c.parent_comment_id, c.pin_order],
         ).map_err(|e| AppError::Db(e.to_string()))?;
         if changed > 0 {
             comments_count += 1;
@@ -404,6 +1074,88 @@ pub fn import_json_string(pool: &DbPool, json: &str) -> Result<ImportResult, App
         }
     }
 
+    // 6b. Import comment reactions (reactions of skipped comments are skipped too)
+    let mut comment_reactions_count = 0usize;
+    for r in &root.comment_reactions {
+        let changed = tx.execute(
+            "INSERT OR IGNORE INTO comment_reactions (id, comment_id, project_id, person_id, emoji, created_at) SELECT ?1, ?2, ?3, ?4, ?5, ?6 WHERE EXISTS (SELECT 1 FROM project_comments WHERE id = ?2)",
+            params![r.id, r.comment_id, r.project_id, r.person_id, r.emoji, r.created_at],
+        ).map_err(|e| AppError::Db(e.to_string()))?;
+        if changed > 0 {
+            comment_reactions_count += 1;
+        } else {
+            skipped += 1;
+        }
+    }
+
+    // 6c. Import comment revisions (revisions of skipped comments are skipped too)
+    let mut comment_revisions_count = 0usize;
+    for r in &root.comment_revisions {
+        let changed = tx.execute(
+            "INSERT OR IGNORE INTO comment_revisions (id, comment_id, project_id, person_id, content, written_at, replaced_at) SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7 WHERE EXISTS (SELECT 1 FROM project_comments WHERE id = ?2)",
+            params![r.id, r.comment_id, r.project_id, r.person_id, r.content, r.written_at, r.replaced_at],
+        ).map_err(|e| AppError::Db(e.to_string()))?;
+        if changed > 0 {
+            comment_revisions_count += 1;
+        } else {
+            skipped += 1;
+        }
+    }
+
+    // 6d. Import checklist items (items of skipped projects are skipped too)
+    let mut checklist_items_count = 0usize;
+    for i in &root.checklist_items {
+        let changed = tx.execute(
+            "INSERT OR IGNORE INTO checklist_items (id, project_id, text, done, sort_order, done_at, created_at, updated_at) SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8 WHERE EXISTS (SELECT 1 FROM projects WHERE id = ?2)",
+            params![i.id, i.project_id, i.text, i.done as i32, i.sort_order, i.done_at, i.created_at, i.updated_at],
+        ).map_err(|e| AppError::Db(e.to_string()))?;
+        if changed > 0 {
+            checklist_items_count += 1;
+        } else {
+            skipped += 1;
+        }
+    }
+
+    // 7. Import custom fields (names stay unique, like project names)
+    let mut custom_field_defs_count = 0usize;
+    for d in &root.custom_field_defs {
+        let name_exists: i64 = tx
+            .query_row(
+                "SELECT COUNT(1) FROM custom_field_defs WHERE name = ?1 COLLATE NOCASE AND id <> ?2",
+                params![d.name, d.id],
+                |r| r.get(0),
+            )
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        if name_exists > 0 {
+            skipped += 1;
+            continue;
+        }
+        let options = serde_json::to_string(&d.options).unwrap_or_else(|_| "[]".to_string());
+        let changed = tx.execute(
+            "INSERT OR IGNORE INTO custom_field_defs (id, name, field_type, options, sort_order, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![d.id, d.name, d.field_type, options, d.sort_order, d.created_at, d.updated_at],
+        ).map_err(|e| AppError::Db(e.to_string()))?;
+        if changed > 0 {
+            custom_field_defs_count += 1;
+        } else {
+            skipped += 1;
+        }
+    }
+
+    // Values of skipped definitions (or missing projects) are skipped too.
+    let mut custom_field_values_count = 0usize;
+    for v in &root.custom_field_values {
+        let changed = tx.execute(
+            "INSERT OR IGNORE INTO project_custom_values (id, project_id, field_id, value, updated_at) SELECT ?1, ?2, ?3, ?4, ?5 WHERE EXISTS (SELECT 1 FROM projects WHERE id = ?2) AND EXISTS (SELECT 1 FROM custom_field_defs WHERE id = ?3)",
+            params![v.id, v.project_id, v.field_id, v.value, v.updated_at],
+        ).map_err(|e| AppError::Db(e.to_string()))?;
+        if changed > 0 {
+            custom_field_values_count += 1;
+        } else {
+            skipped += 1;
+        }
+    }
+
     tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
 
     Ok(ImportResult {
@@ -412,7 +1164,13 @@ pub fn import_json_string(pool: &DbPool, json: &str) -> Result<ImportResult, App
         projects: projects_count,
         assignments: assignments_count,
         status_history: history_count,
+        size_history: size_history_count,
         comments: comments_count,
+        comment_reactions: comment_reactions_count,
+        comment_revisions: comment_revisions_count,
+        checklist_items: checklist_items_count,
+        custom_field_defs: custom_field_defs_count,
+        custom_field_values: custom_field_values_count,
         skipped_duplicates: skipped,
     })
 }
@@ -467,9 +1225,27 @@ pub fn wipe_business_data(pool: &DbPool) -> Result<WipeResult, AppError> {
     .map_err(|e| AppError::Db(e.to_string()))?;
 
     // Delete in FK-safe order.
+    let deleted_project_custom_values = tx
+        .execute("DELETE FROM project_custom_values", [])
+        .map_err(|e| AppError::Db(e.to_string()))? as usize;
+    let deleted_custom_field_defs = tx
+        .execute("DELETE FROM custom_field_defs", [])
+        .map_err(|e| AppError::Db(e.to_string()))? as usize;
+    let deleted_comment_reactions = tx
+        .execute("DELETE FROM comment_reactions", [])
+        .map_err(|e| AppError::Db(e.to_string()))? as usize;
+    let deleted_comment_revisions = tx
+        .execute("DELETE FROM comment_revisions", [])
+        .map_err(|e| AppError::Db(e.to_string()))? as usize;
     let deleted_project_comments = tx
         .execute("DELETE FROM project_comments", [])
         .map_err(|e| AppError::Db(e.to_string()))? as usize;
+    let deleted_checklist_items = tx
+        .execute("DELETE FROM checklist_items", [])
+        .map_err(|e| AppError::Db(e.to_string()))? as usize;
+    let deleted_size_history = tx
+        .execute("DELETE FROM project_size_history", [])
+        .map_err(|e| AppError::Db(e.to_string()))? as usize;
     let deleted_status_history = tx
         .execute("DELETE FROM status_history", [])
         .map_err(|e| AppError::Db(e.to_string()))? as usize;
@@ -493,8 +1269,14 @@ pub fn wipe_business_data(pool: &DbPool) -> Result<WipeResult, AppError> {
 
     Ok(WipeResult {
         wipe_id: intent["wipe_id"].as_str().unwrap().to_string(),
+        deleted_comment_reactions,
+        deleted_comment_revisions,
         deleted_project_comments,
+        deleted_checklist_items,
+        deleted_project_custom_values,
+        deleted_custom_field_defs,
         deleted_status_history,
+        deleted_size_history,
         deleted_assignments,
         deleted_project_tags,
         deleted_projects,
@@ -527,7 +1309,7 @@ pub struct PersonImportResult {
 /// Columns: display_name, email, role, note, is_active
 /// Rows are ordered by display_name (case-insensitive).
 pub fn export_persons_csv(pool: &DbPool) -> Result<String, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     let mut stmt = conn
         .prepare(
             "SELECT display_name, email, role, note, is_active \
@@ -562,6 +1344,298 @@ pub fn export_persons_csv(pool: &DbPool) -> Result<String, AppError> {
     Ok(csv)
 }
 
+/// Columns of a CSV export: `(header name, SQL expression)`.
+type CsvColumns = &'static [(&'static str, &'static str)];
+
+/// Project CSV columns; tags are flattened into one `;`-separated field (alphabetical).
+const PROJECT_CSV_COLUMNS: CsvColumns = &[
+    ("id", "p.id"),
+    ("name", "p.name"),
+    ("status", "p.current_status"),
+    ("priority", "p.priority"),
+    ("country_code", "p.country_code"),
+    ("partner", "pt.name"),
+    ("owner", "pe.display_name"),
+    ("product_name", "p.product_name"),
+    ("start_date", "p.start_date"),
+    ("due_date", "p.due_date"),
+    (
+        "tags",
+        "(SELECT GROUP_CONCAT(tag, ';') FROM (SELECT tag FROM project_tags WHERE project_id = p.id ORDER BY tag))",
+    ),
+    ("parent_project_id", "p.parent_project_id"),
+    ("description", "p.description"),
+    ("created_at", "p.created_at"),
+    ("updated_at", "p.updated_at"),
+    ("archived_at", "p.archived_at"),
+];
+
+const ASSIGNMENT_CSV_COLUMNS: CsvColumns = &[
+    ("id", "a.id"),
+    ("project_id", "a.project_id"),
+    ("project_name", "p.name"),
+    ("person_id", "a.person_id"),
+    ("person_name", "pe.display_name"),
+    ("role", "a.role"),
+    ("start_at", "a.start_at"),
+    ("end_at", "a.end_at"),
+];
+
+const STATUS_HISTORY_CSV_COLUMNS: CsvColumns = &[
+    ("id", "h.id"),
+    ("project_id", "h.project_id"),
+    ("project_name", "p.name"),
+    ("from_status", "h.from_status"),
+    ("to_status", "h.to_status"),
+    ("changed_at", "h.changed_at"),
+    ("changed_by", "pe.display_name"),
+    ("note", "h.note"),
+];
+
+const COMMENT_CSV_COLUMNS: CsvColumns = &[
+    ("id", "c.id"),
+    ("project_id", "c.project_id"),
+    ("project_name", "p.name"),
+    ("author", "pe.display_name"),
+    ("content", "c.content"),
+    ("is_pinned", "c.is_pinned"),
+    ("created_at", "c.created_at"),
+    ("updated_at", "c.updated_at"),
+];
+
+/// Row sources (the query after `FROM`); trashed projects and their children are excluded.
+const PROJECT_CSV_FROM: &str = "projects p
+     LEFT JOIN partners pt ON pt.id = p.partner_id
+     LEFT JOIN persons pe ON pe.id = p.owner_person_id
+     WHERE p.deleted_at IS NULL
+     ORDER BY p.name COLLATE NOCASE, p.id";
+const ASSIGNMENT_CSV_FROM: &str = "assignments a
+     JOIN projects p ON p.id = a.project_id AND p.deleted_at IS NULL
+     LEFT JOIN persons pe ON pe.id = a.person_id
+     ORDER BY p.name COLLATE NOCASE, a.project_id, a.start_at, a.id";
+const STATUS_HISTORY_CSV_FROM: &str = "status_history h
+     JOIN projects p ON p.id = h.project_id AND p.deleted_at IS NULL
+     LEFT JOIN persons pe ON pe.id = h.changed_by_person_id
+     ORDER BY p.name COLLATE NOCASE, h.project_id, h.changed_at, h.rowid";
+const COMMENT_CSV_FROM: &str = "project_comments c
+     JOIN projects p ON p.id = c.project_id AND p.deleted_at IS NULL
+     LEFT JOIN persons pe ON pe.id = c.person_id
+     ORDER BY p.name COLLATE NOCASE, c.project_id, c.created_at, c.id";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvExportReq {
+    /// Column names in output order; absent or empty = every column.
+    pub columns: Option<Vec<String>>,
+}
+
+/// Export projects (not in the trash) as CSV, ordered by name.
+pub fn export_projects_csv(pool: &DbPool, req: CsvExportReq) -> Result<String, AppError> {
+    let conn = get_read_connection(pool);
+    export_csv(&conn, PROJECT_CSV_COLUMNS, PROJECT_CSV_FROM, req)
+}
+
+/// Export assignments of projects not in the trash as CSV, by project then start time.
+pub fn export_assignments_csv(pool: &DbPool, req: CsvExportReq) -> Result<String, AppError> {
+    let conn = get_read_connection(pool);
+    export_csv(&conn, ASSIGNMENT_CSV_COLUMNS, ASSIGNMENT_CSV_FROM, req)
+}
+
+/// Export status history of projects not in the trash as CSV, by project then time.
+pub fn export_status_history_csv(pool: &DbPool, req: CsvExportReq) -> Result<String, AppError> {
+    let conn = get_read_connection(pool);
+    export_csv(
+        &conn,
+        STATUS_HISTORY_CSV_COLUMNS,
+        STATUS_HISTORY_CSV_FROM,
+        req,
+    )
+}
+
+/// Export comments of projects not in the trash as CSV, by project then creation time.
+pub fn export_comments_csv(pool: &DbPool, req: CsvExportReq) -> Result<String, AppError> {
+    let conn = get_read_connection(pool);
+    export_csv(&conn, COMMENT_CSV_COLUMNS, COMMENT_CSV_FROM, req)
+}
+
+/// Select the requested columns from `from_sql` (the query after `FROM`) and render CSV.
+/// NULL becomes an empty field.
+fn export_csv(
+    conn: &Connection,
+    available: CsvColumns,
+    from_sql: &str,
+    req: CsvExportReq,
+) -> Result<String, AppError> {
+    let selected = select_columns(available, req.columns.unwrap_or_default())?;
+    let headers: Vec<&str> = selected.iter().map(|(name, _)| *name).collect();
+    let mut csv = headers.join(",");
+    csv.push('\n');
+    for row in query_columns(conn, &selected, from_sql)? {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|value| {
+                csv_escape(&match value {
+                    Value::Null => String::new(),
+                    Value::Integer(v) => v.to_string(),
+                    Value::Real(v) => v.to_string(),
+                    Value::Text(t) => t.clone(),
+                    Value::Blob(b) => format!("<{} bytes>", b.len()),
+                })
+            })
+            .collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    Ok(csv)
+}
+
+/// Resolve requested column names (case-insensitive) against `available`;
+/// an empty request selects every column.
+fn select_columns(
+    available: CsvColumns,
+    requested: Vec<String>,
+) -> Result<Vec<(&'static str, &'static str)>, AppError> {
+    let requested: Vec<String> = requested
+        .iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if requested.is_empty() {
+        return Ok(available.to_vec());
+    }
+    let mut selected = Vec::with_capacity(requested.len());
+    for name in &requested {
+        let column = available
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = available.iter().map(|(n, _)| *n).collect();
+                AppError::Validation(format!(
+                    "unknown CSV column: {} (expected one of {})",
+                    name,
+                    names.join(", ")
+                ))
+            })?;
+        if selected.contains(column) {
+            return Err(AppError::Validation(format!(
+                "duplicate CSV column: {}",
+                name
+            )));
+        }
+        selected.push(*column);
+    }
+    Ok(selected)
+}
+
+fn query_columns(
+    conn: &Connection,
+    selected: &[(&str, &str)],
+    from_sql: &str,
+) -> Result<Vec<Vec<Value>>, AppError> {
+    let exprs: Vec<&str> = selected.iter().map(|(_, expr)| *expr).collect();
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM {}", exprs.join(", "), from_sql))?;
+    let mut rows = stmt.query([])?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut values = Vec::with_capacity(selected.len());
+        for i in 0..selected.len() {
+            values.push(Value::from(row.get_ref(i)?));
+        }
+        out.push(values);
+    }
+    Ok(out)
+}
+
+const PERSON_XLSX_COLUMNS: CsvColumns = &[
+    ("id", "id"),
+    ("display_name", "display_name"),
+    ("email", "email"),
+    ("role", "role"),
+    ("note", "note"),
+    ("is_active", "is_active"),
+    ("created_at", "created_at"),
+    ("updated_at", "updated_at"),
+];
+
+const PARTNER_XLSX_COLUMNS: CsvColumns = &[
+    ("id", "id"),
+    ("name", "name"),
+    ("note", "note"),
+    ("is_active", "is_active"),
+    ("created_at", "created_at"),
+    ("updated_at", "updated_at"),
+];
+
+/// Sheets of the workspace workbook: `(sheet name, columns, query after FROM)`.
+/// Projects, assignments, status history and comments share the CSV export's columns and order.
+const XLSX_SHEETS: &[(&str, CsvColumns, &str)] = &[
+    ("Projects", PROJECT_CSV_COLUMNS, PROJECT_CSV_FROM),
+    (
+        "Persons",
+        PERSON_XLSX_COLUMNS,
+        "persons WHERE deleted_at IS NULL ORDER BY display_name COLLATE NOCASE, id",
+    ),
+    (
+        "Partners",
+        PARTNER_XLSX_COLUMNS,
+        "partners WHERE deleted_at IS NULL ORDER BY name COLLATE NOCASE, id",
+    ),
+    ("Assignments", ASSIGNMENT_CSV_COLUMNS, ASSIGNMENT_CSV_FROM),
+    (
+        "Status history",
+        STATUS_HISTORY_CSV_COLUMNS,
+        STATUS_HISTORY_CSV_FROM,
+    ),
+    ("Comments", COMMENT_CSV_COLUMNS, COMMENT_CSV_FROM),
+];
+
+/// Export the workspace (everything not in the trash) as an `.xlsx` workbook,
+/// one sheet per entity. Numbers stay numeric cells; NULL becomes an empty cell.
+pub fn export_xlsx(pool: &DbPool) -> Result<XlsxExport, AppError> {
+    let conn = get_read_connection(pool);
+    let mut workbook = XlsxWorkbook::new();
+    let mut sheets = Vec::with_capacity(XLSX_SHEETS.len());
+    for (name, columns, from_sql) in XLSX_SHEETS {
+        let rows: Vec<Vec<XlsxCell>> = query_columns(&conn, columns, from_sql)?
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|value| match value {
+                        Value::Null => XlsxCell::Empty,
+                        Value::Integer(v) => XlsxCell::Number(v as f64),
+                        Value::Real(v) => XlsxCell::Number(v),
+                        Value::Text(t) => XlsxCell::Text(t),
+                        Value::Blob(b) => XlsxCell::Text(format!("<{} bytes>", b.len())),
+                    })
+                    .collect()
+            })
+            .collect();
+        sheets.push(XlsxSheetSummary {
+            name: name.to_string(),
+            rows: rows.len(),
+        });
+        let headers: Vec<&str> = columns.iter().map(|(n, _)| *n).collect();
+        workbook.add_sheet(name, &headers, rows);
+    }
+    let bytes = workbook
+        .into_bytes()
+        .map_err(|e| AppError::Export(format!("failed to build workbook: {}", e)))?;
+    Ok(XlsxExport { bytes, sheets })
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XlsxSheetSummary {
+    pub name: String,
+    /// Data rows, excluding the header.
+    pub rows: usize,
+}
+
+pub struct XlsxExport {
+    pub bytes: Vec<u8>,
+    pub sheets: Vec<XlsxSheetSummary>,
+}
+
 /// Import persons from a UTF-8 CSV string.
 ///
 /// Expected header: display_name,email,role,note,is_active
@@ -670,6 +1744,310 @@ pub fn import_persons_csv(pool: &DbPool, csv: &str) -> Result<PersonImportResult
     })
 }
 
+/// Project fields a CSV column can map to, with the header names recognized by default.
+const PROJECT_IMPORT_FIELDS: &[(&str, &[&str])] = &[
+    ("name", &["name", "project", "project_name"]),
+    ("partner", &["partner", "partner_id", "partner_name"]),
+    ("owner", &["owner", "owner_person_id", "owner_name"]),
+    ("country_code", &["country_code", "country"]),
+    ("description", &["description"]),
+    ("priority", &["priority"]),
+    ("product_name", &["product_name", "product"]),
+    ("start_date", &["start_date"]),
+    ("due_date", &["due_date"]),
+    ("tags", &["tags"]),
+    ("parent", &["parent", "parent_project_id", "parent_project"]),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCsvImportReq {
+    pub csv: String,
+    /// Validate only and roll back (default `true`).
+    pub dry_run: Option<bool>,
+    /// CSV header → project field (see `PROJECT_IMPORT_FIELDS`); unmapped headers are matched
+    /// by their default names, the rest are ignored.
+    pub column_map: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCsvRowError {
+    /// 1-based data row (header excluded).
+    pub row: usize,
+    pub name: Option<String>,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCsvImportReport {
+    pub dry_run: bool,
+    /// `true` when the projects were written (not a dry run and no row failed).
+    pub committed: bool,
+    pub total_rows: usize,
+    pub ok_rows: usize,
+    pub errors: Vec<ProjectCsvRowError>,
+    /// Headers that map to no field.
+    pub ignored_columns: Vec<String>,
+    /// IDs of the created projects (also filled on dry runs, then rolled back).
+    pub project_ids: Vec<String>,
+}
+
+/// Import projects from CSV in one transaction.
+///
+/// Partner, owner and parent are resolved by ID or (case-insensitive) name; rows are validated
+/// in order, so a parent may be a project created by an earlier row. Every row is tried and
+/// reported; the transaction is committed only when `dry_run` is false and no row failed.
+pub fn import_projects_csv(
+    pool: &DbPool,
+    req: ProjectCsvImportReq,
+) -> Result<ProjectCsvImportReport, AppError> {
+    let dry_run = req.dry_run.unwrap_or(true);
+    let mut records = parse_csv_records(req.csv.trim_start_matches('\u{feff}')).into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| AppError::Validation("CSV is empty".into()))?;
+
+    let column_map: HashMap<String, String> = req
+        .column_map
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(column, field)| (column.trim().to_lowercase(), field.trim().to_lowercase()))
+        .collect();
+    let mut fields: Vec<Option<&'static str>> = Vec::with_capacity(header.len());
+    let mut ignored_columns = Vec::new();
+    for column in &header {
+        let key = column.trim().to_lowercase();
+        let field = match column_map.get(&key) {
+            Some(target) => Some(
+                PROJECT_IMPORT_FIELDS
+                    .iter()
+                    .map(|(field, _)| *field)
+                    .find(|field| field == target)
+                    .ok_or_else(|| {
+                        AppError::Validation(format!(
+                            "unknown project field in column map: {}",
+                            target
+                        ))
+                    })?,
+            ),
+            None => PROJECT_IMPORT_FIELDS
+                .iter()
+                .find(|(_, names)| names.contains(&key.as_str()))
+                .map(|(field, _)| *field),
+        };
+        if let Some(field) = field {
+            if fields.contains(&Some(field)) {
+                return Err(AppError::Validation(format!(
+                    "more than one column maps to {}",
+                    field
+                )));
+            }
+        } else {
+            ignored_columns.push(column.trim().to_string());
+        }
+        fields.push(field);
+    }
+    for required in ["name", "partner", "owner", "country_code"] {
+        if !fields.contains(&Some(required)) {
+            return Err(AppError::Validation(format!(
+                "missing column for required field: {}",
+                required
+            )));
+        }
+    }
+
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let mut report = ProjectCsvImportReport {
+        dry_run,
+        committed: false,
+        total_rows: 0,
+        ok_rows: 0,
+        errors: Vec::new(),
+        ignored_columns,
+        project_ids: Vec::new(),
+    };
+    for (i, record) in records.enumerate() {
+        if record.iter().all(|v| v.trim().is_empty()) {
+            continue;
+        }
+        report.total_rows += 1;
+        let value = |field: &str| {
+            fields
+                .iter()
+                .position(|f| *f == Some(field))
+                .and_then(|idx| record.get(idx))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+
+        let mut reasons = Vec::new();
+        let mut resolve = |table: &str, name_column: &str, label: &str| match resolve_reference(
+            &tx,
+            table,
+            name_column,
+            label,
+            value(label),
+        ) {
+            Ok(id) => Some(id),
+            Err(reason) => {
+                reasons.push(reason);
+                None
+            }
+        };
+        let partner_id = resolve("partners", "name", "partner");
+        let owner_id = resolve("persons", "display_name", "owner");
+        let parent_id = value("parent").and_then(|_| resolve("projects", "name", "parent"));
+        let priority = match value("priority").map(|p| p.parse::<i32>()) {
+            None => None,
+            Some(Ok(p)) if (1..=5).contains(&p) => Some(p),
+            Some(_) => {
+                reasons.push("priority must be an integer from 1 to 5".to_string());
+                None
+            }
+        };
+        for field in ["start_date", "due_date"] {
+            if let Some(date) = value(field) {
+                if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                    reasons.push(format!("{} must be YYYY-MM-DD", field));
+                }
+            }
+        }
+        let mut tags: Vec<String> = Vec::new();
+        for tag in value("tags").unwrap_or_default().split(';') {
+            let tag = tag.trim();
+            if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+
+        if reasons.is_empty() {
+            let create = ProjectCreateReq {
+                name: value("name").unwrap_or_default().to_string(),
+                description: value("description").map(str::to_string),
+                priority,
+                country_code: value("country_code").unwrap_or_default().to_string(),
+                partner_id: partner_id.unwrap_or_default(),
+                owner_person_id: owner_id.unwrap_or_default(),
+                product_name: value("product_name").map(str::to_string),
+                start_date: value("start_date").map(str::to_string),
+                due_date: value("due_date").map(str::to_string),
+                tags: Some(tags),
+                created_by_person_id: None,
+                parent_project_id: parent_id,
+            };
+            // A failed row must not leave partial writes behind.
+            tx.execute_batch("SAVEPOINT csv_row")?;
+            match project_create_tx(&tx, create) {
+                Ok(id) => {
+                    tx.execute_batch("RELEASE csv_row")?;
+                    report.project_ids.push(id);
+                    report.ok_rows += 1;
+                    continue;
+                }
+                Err(e) => {
+                    tx.execute_batch("ROLLBACK TO csv_row; RELEASE csv_row")?;
+                    reasons.push(match e {
+                        AppError::Validation(msg)
+                        | AppError::Conflict(msg)
+                        | AppError::NotFound(msg) => msg,
+                        other => other.to_string(),
+                    });
+                }
+            }
+        }
+        report.errors.push(ProjectCsvRowError {
+            row: i + 1,
+            name: value("name").map(str::to_string),
+            reasons,
+        });
+    }
+
+    if !dry_run && report.errors.is_empty() {
+        tx.commit()?;
+        report.committed = true;
+    } else {
+        tx.rollback()?;
+    }
+    Ok(report)
+}
+
+/// Resolve a row by ID, then by case-insensitive name; ambiguous names are an error.
+fn resolve_reference(
+    conn: &Connection,
+    table: &str,
+    name_column: &str,
+    label: &str,
+    value: Option<&str>,
+) -> Result<String, String> {
+    let Some(value) = value else {
+        return Err(format!("{} is required", label));
+    };
+    let lookup = |sql: &str| -> Result<Vec<String>, String> {
+        let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+        let ids = stmt
+            .query_map([value], |r| r.get::<_, String>(0))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| e.to_string())?;
+        Ok(ids)
+    };
+    let by_id = lookup(&format!(
+        "SELECT id FROM {} WHERE id = ?1 AND deleted_at IS NULL",
+        table
+    ))?;
+    if let Some(id) = by_id.into_iter().next() {
+        return Ok(id);
+    }
+    let by_name = lookup(&format!(
+        "SELECT id FROM {} WHERE {} = ?1 COLLATE NOCASE AND deleted_at IS NULL",
+        table, name_column
+    ))?;
+    match by_name.len() {
+        0 => Err(format!("{} not found: {}", label, value)),
+        1 => Ok(by_name.into_iter().next().unwrap_or_default()),
+        n => Err(format!(
+            "{} name is ambiguous ({} matches): {}",
+            label, n, value
+        )),
+    }
+}
+
+/// Parse CSV text into records; quoted fields may contain commas, quotes (`""`) and newlines.
+fn parse_csv_records(csv: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' => in_quotes = true,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            other => field.push(other),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
 /// Wrap a CSV field value in quotes if it contains commas, quotes, or newlines.
 fn csv_escape(value: &str) -> String {
     if value.contains(',') || value.contains('"') || value.contains('\n') || value.contains('\r') {
@@ -0,0 +1,646 @@
+//! Chat digests: daily / weekly summary posted to a Slack or Microsoft Teams incoming webhook.
+//!
+//! - 内容：统计周期内新建的项目、状态变更，以及当前逾期的项目（规则同 `OVERDUE` 提醒）。
+//! - 格式：Slack Block Kit 或 Teams Adaptive Card（message + attachment）；每节最多列出
+//!   `DIGEST_SECTION_LIMIT` 项，其余以 "+N more" 表示。
+//! - 由 `ReportRuntime` 每小时检查一次：daily 每个本地日期一次，weekly 距上次发送满 7 天；
+//!   周期从上次发送时间起算（首次为最近 1 / 7 天）。没有任何内容时不发送，但视为已发送。
+//! - 发送失败不推进发送时间（下个小时重试），同一错误只写一次通知（app notices）。
+//! - 配置存于 `sync_config`（本机偏好，不随同步传播）；webhook URL 含凭据，只返回是否已设置。
+
+use crate::app::{load_format_settings, notice_record, FormatSettings};
+use crate::error::AppError;
+use crate::infra::redact::{redact_message, register_secrets};
+use crate::infra::webhook::{WebhookClient, WebhookRequest};
+use crate::infra::{get_connection, DbPool};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+
+pub const DIGEST_ENABLED_KEY: &str = "digest_enabled";
+pub const DIGEST_FREQUENCY_KEY: &str = "digest_frequency";
+pub const DIGEST_FORMAT_KEY: &str = "digest_format";
+pub const DIGEST_WEBHOOK_URL_KEY: &str = "digest_webhook_url";
+pub const LAST_DIGEST_SENT_AT_KEY: &str = "last_digest_sent_at";
+pub const LAST_DIGEST_SENT_ON_KEY: &str = "last_digest_sent_on";
+pub const LAST_DIGEST_ERROR_KEY: &str = "last_digest_error";
+
+pub const NOTICE_KIND_DIGEST_FAILED: &str = "DIGEST_FAILED";
+
+/// Items listed per section; the rest is summarized as "+N more".
+pub const DIGEST_SECTION_LIMIT: usize = 20;
+
+/// Serializes scheduled runs (hourly runtime vs. commands) so a digest is never posted twice.
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "daily" => Some(Self::Daily),
+            "weekly" => Some(Self::Weekly),
+            _ => None,
+        }
+    }
+
+    fn period_days(&self) -> i64 {
+        match self {
+            Self::Daily => 1,
+            Self::Weekly => 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFormat {
+    Slack,
+    Teams,
+}
+
+impl DigestFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Slack => "slack",
+            Self::Teams => "teams",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "slack" => Some(Self::Slack),
+            "teams" => Some(Self::Teams),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestConfigDto {
+    pub enabled: bool,
+    pub frequency: DigestFrequency,
+    pub format: DigestFormat,
+    /// The URL itself is never returned (it embeds the channel credential).
+    pub has_webhook_url: bool,
+    pub last_sent_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestConfigReq {
+    /// Enabling requires a webhook URL. If omitted, keep existing value.
+    pub enabled: Option<bool>,
+    /// If omitted, keep existing value.
+    pub frequency: Option<DigestFrequency>,
+    /// If omitted, keep existing value.
+    pub format: Option<DigestFormat>,
+    /// https URL of the incoming webhook; `""` removes it. If omitted, keep existing value.
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestPreviewReq {
+    /// Default: configured frequency.
+    pub frequency: Option<DigestFrequency>,
+    /// Default: configured format.
+    pub format: Option<DigestFormat>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestProjectItem {
+    pub project_id: String,
+    pub name: String,
+    pub status: String,
+    pub due_date: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestStatusChangeItem {
+    pub project_id: String,
+    pub name: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub changed_at: String,
+    pub changed_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestDto {
+    pub frequency: DigestFrequency,
+    pub period_start: String,
+    pub period_end: String,
+    pub new_projects: Vec<DigestProjectItem>,
+    pub status_changes: Vec<DigestStatusChangeItem>,
+    pub overdue: Vec<DigestProjectItem>,
+}
+
+impl DigestDto {
+    pub fn is_empty(&self) -> bool {
+        self.new_projects.is_empty() && self.status_changes.is_empty() && self.overdue.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestPreviewDto {
+    pub digest: DigestDto,
+    /// Exact JSON body that would be posted.
+    pub payload: Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestSendResult {
+    pub period_start: String,
+    pub period_end: String,
+    /// False when there was nothing to report (nothing is posted).
+    pub posted: bool,
+}
+
+pub fn digest_get_config(pool: &DbPool) -> Result<DigestConfigDto, AppError> {
+    let conn = get_connection(pool);
+    Ok(load_config(&conn))
+}
+
+pub fn digest_update_config(
+    pool: &DbPool,
+    req: DigestConfigReq,
+) -> Result<DigestConfigDto, AppError> {
+    let webhook_url = match req.webhook_url.as_deref().map(str::trim) {
+        Some("") => Some(String::new()),
+        Some(url) => Some(validate_webhook_url(url)?),
+        None => None,
+    };
+
+    let conn = get_connection(pool);
+    let has_url = match &webhook_url {
+        Some(url) => !url.is_empty(),
+        None => load_config_value(&conn, DIGEST_WEBHOOK_URL_KEY).is_some_and(|v| !v.is_empty()),
+    };
+    let enabled = req.enabled.unwrap_or_else(|| load_digest_enabled(&conn));
+    if enabled && !has_url {
+        return Err(AppError::Validation(
+            "webhookUrl is required to enable the digest".into(),
+        ));
+    }
+
+    if let Some(url) = &webhook_url {
+        set_config_value(&conn, DIGEST_WEBHOOK_URL_KEY, url)?;
+        register_secrets([url.as_str()]);
+    }
+    if let Some(enabled) = req.enabled {
+        set_config_value(&conn, DIGEST_ENABLED_KEY, bool_str(enabled))?;
+    }
+    if let Some(frequency) = req.frequency {
+        set_config_value(&conn, DIGEST_FREQUENCY_KEY, frequency.as_str())?;
+    }
+    if let Some(format) = req.format {
+        set_config_value(&conn, DIGEST_FORMAT_KEY, format.as_str())?;
+    }
+    Ok(load_config(&conn))
+}
+
+/// Compose the digest for the last period (1 / 7 days up to `now`) without posting it.
+pub fn digest_preview(
+    pool: &DbPool,
+    req: DigestPreviewReq,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> Result<DigestPreviewDto, AppError> {
+    let conn = get_connection(pool);
+    let config = load_config(&conn);
+    let frequency = req.frequency.unwrap_or(config.frequency);
+    let format = req.format.unwrap_or(config.format);
+    let since = now - Duration::days(frequency.period_days());
+    let digest = digest_compose(&conn, frequency, since, now, today)?;
+    let payload = render_payload(&digest, format, &load_format_settings(&conn));
+    Ok(DigestPreviewDto { digest, payload })
+}
+
+/// Post the digest for the last period now (the schedule is unchanged).
+pub fn digest_send_now(
+    pool: &DbPool,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> Result<DigestSendResult, AppError> {
+    let client = WebhookClient::new().map_err(AppError::Report)?;
+    let _guard = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (url, format, digest, payload) = {
+        let conn = get_connection(pool);
+        let config = load_config(&conn);
+        let url = load_webhook_url(&conn)?;
+        let since = now - Duration::days(config.frequency.period_days());
+        let digest = digest_compose(&conn, config.frequency, since, now, today)?;
+        let payload = render_payload(&digest, config.format, &load_format_settings(&conn));
+        (url, config.format, digest, payload)
+    };
+    post(&url, format, &payload, &|req| client.post(req))?;
+    Ok(DigestSendResult {
+        period_start: digest.period_start,
+        period_end: digest.period_end,
+        posted: true,
+    })
+}
+
+/// Post the digest if enabled and due. `Ok(None)` when disabled or not due yet.
+/// Blocking: call from `spawn_blocking` in async code.
+pub fn digest_run_scheduled(
+    pool: &DbPool,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> Result<Option<DigestSendResult>, AppError> {
+    if !load_digest_enabled(&get_connection(pool)) {
+        return Ok(None);
+    }
+    let client = WebhookClient::new().map_err(AppError::Report)?;
+    digest_run_scheduled_with(pool, today, now, &|req| client.post(req))
+}
+
+/// Scheduled run with a caller-supplied sender (tests use a fake one instead of the network).
+pub fn digest_run_scheduled_with(
+    pool: &DbPool,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+    send: &dyn Fn(&WebhookRequest) -> Result<u16, String>,
+) -> Result<Option<DigestSendResult>, AppError> {
+    let _guard = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (url, format, digest, payload) = {
+        let conn = get_connection(pool);
+        let config = load_config(&conn);
+        if !config.enabled || !is_due(&conn, config.frequency, today) {
+            return Ok(None);
+        }
+        let url = load_webhook_url(&conn)?;
+        let since = load_config_value(&conn, LAST_DIGEST_SENT_AT_KEY)
+            .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or_else(|| now - Duration::days(config.frequency.period_days()));
+        let digest = digest_compose(&conn, config.frequency, since, now, today)?;
+        let payload = render_payload(&digest, config.format, &load_format_settings(&conn));
+        (url, config.format, digest, payload)
+    };
+
+    let posted = !digest.is_empty();
+    if posted {
+        // The DB lock is not held while posting.
+        if let Err(e) = post(&url, format, &payload, send) {
+            let conn = get_connection(pool);
+            let message = e.to_string();
+            if load_config_value(&conn, LAST_DIGEST_ERROR_KEY).as_deref() != Some(&message) {
+                notice_record(
+                    &conn,
+                    NOTICE_KIND_DIGEST_FAILED,
+                    "warning",
+                    &format!("Digest could not be posted: {}", message),
+                )?;
+                set_config_value(&conn, LAST_DIGEST_ERROR_KEY, &message)?;
+            }
+            return Err(e);
+        }
+    }
+
+    let conn = get_connection(pool);
+    set_config_value(&conn, LAST_DIGEST_SENT_AT_KEY, &now.to_rfc3339())?;
+    set_config_value(
+        &conn,
+        LAST_DIGEST_SENT_ON_KEY,
+        &today.format("%Y-%m-%d").to_string(),
+    )?;
+    conn.execute(
+        "DELETE FROM sync_config WHERE key = ?1",
+        [LAST_DIGEST_ERROR_KEY],
+    )?;
+    Ok(Some(DigestSendResult {
+        period_start: digest.period_start,
+        period_end: digest.period_end,
+        posted,
+    }))
+}
+
+/// Default false (needs a webhook URL).
+pub fn load_digest_enabled(conn: &Connection) -> bool {
+    load_config_value(conn, DIGEST_ENABLED_KEY).is_some_and(|v| v == "true")
+}
+
+/// Collect the digest sections: activity in `[since, now]` plus what is overdue as of `today`.
+pub fn digest_compose(
+    conn: &Connection,
+    frequency: DigestFrequency,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+    today: NaiveDate,
+) -> Result<DigestDto, AppError> {
+    let since_str = since.to_rfc3339();
+    let now_str = now.to_rfc3339();
+
+    let new_projects = conn
+        .prepare(
+            "SELECT id, name, current_status, due_date FROM projects
+             WHERE deleted_at IS NULL AND created_at >= ?1 AND created_at <= ?2
+             ORDER BY created_at, name",
+        )?
+        .query_map(params![&since_str, &now_str], map_project_item)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let status_changes = conn
+        .prepare(
+            "SELECT p.id, p.name, h.from_status, h.to_status, h.changed_at, pe.display_name
+             FROM status_history h
+             JOIN projects p ON p.id = h.project_id
+             LEFT JOIN persons pe ON pe.id = h.changed_by_person_id
+             WHERE p.deleted_at IS NULL AND h.changed_at >= ?1 AND h.changed_at <= ?2
+             ORDER BY h.changed_at, p.name",
+        )?
+        .query_map(params![&since_str, &now_str], |r| {
+            Ok(DigestStatusChangeItem {
+                project_id: r.get(0)?,
+                name: r.get(1)?,
+                from_status: r.get(2)?,
+                to_status: r.get(3)?,
+                changed_at: r.get(4)?,
+                changed_by: r.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let overdue = conn
+        .prepare(
+            "SELECT id, name, current_status, due_date FROM projects
+             WHERE archived_at IS NULL AND deleted_at IS NULL
+               AND current_status NOT IN ('DONE', 'ARCHIVED')
+               AND due_date IS NOT NULL AND due_date <> '' AND due_date < ?1
+             ORDER BY due_date, name",
+        )?
+        .query_map([today.format("%Y-%m-%d").to_string()], map_project_item)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(DigestDto {
+        frequency,
+        period_start: since_str,
+        period_end: now_str,
+        new_projects,
+        status_changes,
+        overdue,
+    })
+}
+
+/// Request body for `format`: Slack Block Kit or a Teams message with an Adaptive Card.
+pub fn render_payload(
+    digest: &DigestDto,
+    format: DigestFormat,
+    settings: &FormatSettings,
+) -> Value {
+    let title = match digest.frequency {
+        DigestFrequency::Daily => "Projex daily digest",
+        DigestFrequency::Weekly => "Projex weekly digest",
+    };
+    let period = format!(
+        "{} – {}",
+        format_instant(settings, &digest.period_start),
+        format_instant(settings, &digest.period_end)
+    );
+    let sections = [
+        (
+            format!("New projects ({})", digest.new_projects.len()),
+            digest
+                .new_projects
+                .iter()
+                .map(|p| (p.name.clone(), p.status.clone()))
+                .collect::<Vec<_>>(),
+        ),
+        (
+            format!("Status changes ({})", digest.status_changes.len()),
+            digest
+                .status_changes
+                .iter()
+                .map(|c| {
+                    let mut detail = format!(
+                        "{} → {}",
+                        c.from_status.as_deref().unwrap_or("—"),
+                        c.to_status
+                    );
+                    if let Some(by) = &c.changed_by {
+                        detail.push_str(&format!(" by {}", by));
+                    }
+                    (c.name.clone(), detail)
+                })
+                .collect(),
+        ),
+        (
+            format!("Overdue ({})", digest.overdue.len()),
+            digest
+                .overdue
+                .iter()
+                .map(|p| {
+                    let due = p.due_date.as_deref().unwrap_or_default();
+                    (
+                        p.name.clone(),
+                        format!("due {}", settings.format_date_str(due)),
+                    )
+                })
+                .collect(),
+        ),
+    ];
+
+    match format {
+        DigestFormat::Slack => {
+            let mut blocks = vec![
+                json!({ "type": "header", "text": { "type": "plain_text", "text": title } }),
+                json!({ "type": "context", "elements": [{ "type": "mrkdwn", "text": slack_escape(&period) }] }),
+            ];
+            for (heading, items) in &sections {
+                let lines = section_lines(items, |name, detail| {
+                    format!("• *{}* — {}", slack_escape(name), slack_escape(detail))
+                });
+                blocks.push(json!({ "type": "divider" }));
+                blocks.push(json!({
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": format!("*{}*\n{}", heading, lines) }
+                }));
+            }
+            json!({ "text": format!("{} ({})", title, period), "blocks": blocks })
+        }
+        DigestFormat::Teams => {
+            let mut body = vec![
+                json!({ "type": "TextBlock", "text": title, "size": "Large", "weight": "Bolder", "wrap": true }),
+                json!({ "type": "TextBlock", "text": period, "isSubtle": true, "spacing": "None", "wrap": true }),
+            ];
+            for (heading, items) in &sections {
+                let lines =
+                    section_lines(items, |name, detail| format!("- **{}** — {}", name, detail));
+                body.push(json!({
+                    "type": "TextBlock", "text": heading, "weight": "Bolder", "separator": true, "wrap": true
+                }));
+                body.push(json!({ "type": "TextBlock", "text": lines, "wrap": true }));
+            }
+            json!({
+                "type": "message",
+                "attachments": [{
+                    "contentType": "application/vnd.microsoft.card.adaptive",
+                    "contentUrl": null,
+                    "content": {
+                        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                        "type": "AdaptiveCard",
+                        "version": "1.4",
+                        "body": body
+                    }
+                }]
+            })
+        }
+    }
+}
+
+fn section_lines(items: &[(String, String)], line: impl Fn(&str, &str) -> String) -> String {
+    if items.is_empty() {
+        return "None".to_string();
+    }
+    let mut lines: Vec<String> = items
+        .iter()
+        .take(DIGEST_SECTION_LIMIT)
+        .map(|(name, detail)| line(name, detail))
+        .collect();
+    if items.len() > DIGEST_SECTION_LIMIT {
+        lines.push(format!("+{} more", items.len() - DIGEST_SECTION_LIMIT));
+    }
+    lines.join("\n")
+}
+
+/// Slack mrkdwn control characters.
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn format_instant(settings: &FormatSettings, raw: &str) -> String {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|at| settings.format_datetime(at.with_timezone(&chrono::Local).naive_local()))
+        .unwrap_or_else(|_| raw.to_string())
+}
+
+fn post(
+    url: &str,
+    format: DigestFormat,
+    payload: &Value,
+    send: &dyn Fn(&WebhookRequest) -> Result<u16, String>,
+) -> Result<(), AppError> {
+    let status = send(&WebhookRequest {
+        url: url.to_string(),
+        headers: Vec::new(),
+        body: payload.to_string(),
+    })
+    .map_err(|e| {
+        // Transport errors quote the URL, which embeds the channel credential.
+        AppError::Report(format!(
+            "{} webhook: {}",
+            format.as_str(),
+            redact_message(&e.replace(url, "<webhook url>"))
+        ))
+    })?;
+    if !(200..300).contains(&status) {
+        return Err(AppError::Report(format!(
+            "{} webhook returned HTTP {}",
+            format.as_str(),
+            status
+        )));
+    }
+    Ok(())
+}
+
+fn is_due(conn: &Connection, frequency: DigestFrequency, today: NaiveDate) -> bool {
+    let last = load_config_value(conn, LAST_DIGEST_SENT_ON_KEY)
+        .and_then(|v| NaiveDate::parse_from_str(&v, "%Y-%m-%d").ok());
+    match last {
+        None => true,
+        Some(last) => (today - last).num_days() >= frequency.period_days(),
+    }
+}
+
+fn validate_webhook_url(url: &str) -> Result<String, AppError> {
+    match url.get(..8) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("https://") && url.len() > 8 => {
+            Ok(url.to_string())
+        }
+        _ => Err(AppError::Validation(
+            "webhookUrl must be an https URL".into(),
+        )),
+    }
+}
+
+fn load_webhook_url(conn: &Connection) -> Result<String, AppError> {
+    load_config_value(conn, DIGEST_WEBHOOK_URL_KEY)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| AppError::Validation("digest webhook URL is not configured".into()))
+}
+
+fn load_config(conn: &Connection) -> DigestConfigDto {
+    DigestConfigDto {
+        enabled: load_digest_enabled(conn),
+        frequency: load_config_value(conn, DIGEST_FREQUENCY_KEY)
+            .and_then(|v| DigestFrequency::parse(&v))
+            .unwrap_or(DigestFrequency::Weekly),
+        format: load_config_value(conn, DIGEST_FORMAT_KEY)
+            .and_then(|v| DigestFormat::parse(&v))
+            .unwrap_or(DigestFormat::Slack),
+        has_webhook_url: load_config_value(conn, DIGEST_WEBHOOK_URL_KEY)
+            .is_some_and(|v| !v.is_empty()),
+        last_sent_at: load_config_value(conn, LAST_DIGEST_SENT_AT_KEY),
+        last_error: load_config_value(conn, LAST_DIGEST_ERROR_KEY),
+    }
+}
+
+fn map_project_item(r: &rusqlite::Row<'_>) -> rusqlite::Result<DigestProjectItem> {
+    Ok(DigestProjectItem {
+        project_id: r.get(0)?,
+        name: r.get(1)?,
+        status: r.get(2)?,
+        due_date: r.get(3)?,
+    })
+}
+
+fn bool_str(value: bool) -> &'static str {
+    if value {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+fn load_config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+}
+
+fn set_config_value(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,187 @@
+//! Local database encryption: status and passphrase changes (see `infra::encryption`).
+//!
+//! - 默认明文；`cmd_db_set_passphrase` 设置口令即迁移为加密库，再次调用更换口令，传空则解密回明文。
+//! - 每次变更：导出到 `app.db-rekey`（目标口令）→ 用目标口令打开并 `integrity_check` → 新口令写入
+//!   钥匙串待定条目 → 关闭连接、替换原文件并重新打开 → 待定口令转正。任一步失败时原文件不变；
+//!   替换后、转正前崩溃时，启动会用待定口令解锁。
+//! - 加密前写入的本地备份仍是明文，状态中报告其数量，由用户决定是否删除。
+
+use crate::app::backup_dir;
+use crate::error::AppError;
+use crate::infra::encryption::{
+    delete_passphrase, delete_pending_passphrase, encryption_supported, ensure_supported,
+    export_database, is_encrypted_file, open_with_key, promote_pending_passphrase,
+    store_pending_passphrase, MIN_PASSPHRASE_LEN, PLAINTEXT_HEADER,
+};
+use crate::infra::{get_connection, DbPool};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const REKEY_SUFFIX: &str = "rekey";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbEncryptionStatusDto {
+    /// This build includes SQLCipher.
+    pub supported: bool,
+    pub encrypted: bool,
+    /// Local backups that are still plaintext (written before encryption was enabled).
+    pub plaintext_backups: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbSetPassphraseReq {
+    /// New passphrase; `None`/empty decrypts the database.
+    pub passphrase: Option<String>,
+}
+
+pub fn db_encryption_status(pool: &DbPool) -> Result<DbEncryptionStatusDto, AppError> {
+    let encrypted = pool.0.is_encrypted();
+    let plaintext_backups = if encrypted {
+        backup_dir(pool)
+            .map(|dir| count_plaintext_backups(&dir))
+            .unwrap_or(0)
+    } else {
+        0
+    };
+    Ok(DbEncryptionStatusDto {
+        supported: encryption_supported(),
+        encrypted,
+        plaintext_backups,
+    })
+}
+
+/// Encrypt, re-key or decrypt the database file. Blocking and holds the writer while the file
+/// is rewritten: call with background runtimes stopped.
+pub fn db_set_passphrase(
+    pool: &DbPool,
+    req: DbSetPassphraseReq,
+) -> Result<DbEncryptionStatusDto, AppError> {
+    ensure_supported()?;
+    let passphrase = req.passphrase.filter(|p| !p.is_empty());
+    if let Some(p) = &passphrase {
+        if p.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(AppError::Validation(format!(
+                "PASSPHRASE_TOO_SHORT: at least {} characters",
+                MIN_PASSPHRASE_LEN
+            )));
+        }
+    }
+    let current_key = pool.0.key();
+    if current_key.is_none() && passphrase.is_none() {
+        return db_encryption_status(pool);
+    }
+
+    let db_path = {
+        let conn = get_connection(pool);
+        conn.path()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::Validation("in-memory database cannot be encrypted".into()))?
+    };
+    let rekey_path = sibling(&db_path, REKEY_SUFFIX);
+    let _ = fs::remove_file(&rekey_path);
+
+    let written = {
+        let conn = get_connection(pool);
+        export_database(
+            &conn,
+            &rekey_path,
+            current_key.is_some(),
+            passphrase.as_deref(),
+        )
+        .and_then(|()| verify_rewritten(&rekey_path, passphrase.as_deref()))
+    };
+    if let Err(e) = written {
+        let _ = fs::remove_file(&rekey_path);
+        return Err(e);
+    }
+    if let Some(p) = &passphrase {
+        // Pending until the swap: the current entry keeps unlocking the original file, and
+        // `open_database` falls back to the pending one if we stop after the swap.
+        if let Err(e) = store_pending_passphrase(&db_path, p) {
+            let _ = fs::remove_file(&rekey_path);
+            return Err(e);
+        }
+    }
+
+    let swapped = pool.0.reopen_with(passphrase.clone(), |path| {
+        for suffix in ["wal", "shm"] {
+            let _ = fs::remove_file(sibling(path, suffix));
+        }
+        fs::rename(&rekey_path, path).map_err(|e| {
+            AppError::DbEncryption(format!("Failed to replace {}: {}", path.display(), e))
+        })
+    });
+    if let Err(e) = swapped {
+        let _ = fs::remove_file(&rekey_path);
+        let _ = delete_pending_passphrase(&db_path);
+        return Err(e);
+    }
+    match &passphrase {
+        Some(p) => {
+            if let Err(e) = promote_pending_passphrase(&db_path, p) {
+                // Still unlockable: the next start falls back to the pending entry.
+                log::warn!("Failed to promote the new database passphrase: {}", e);
+            }
+        }
+        None => delete_passphrase(&db_path)?,
+    }
+
+    log::info!(
+        "Database {}",
+        match (&current_key, &passphrase) {
+            (None, _) => "encrypted",
+            (Some(_), Some(_)) => "re-keyed",
+            (Some(_), None) => "decrypted",
+        }
+    );
+    db_encryption_status(pool)
+}
+
+fn verify_rewritten(path: &Path, key: Option<&str>) -> Result<(), AppError> {
+    let conn = open_with_key(path, key)?;
+    let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        return Err(AppError::DbEncryption(format!(
+            "Rewritten database failed integrity check: {}",
+            integrity
+        )));
+    }
+    if key.is_some() != is_encrypted_file(path) {
+        return Err(AppError::DbEncryption(
+            "Rewritten database has the wrong encryption state".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// `app.db` -> `app.db-<suffix>` (SQLite's naming for `-wal` / `-shm`).
+fn sibling(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.as_os_str().to_os_string();
+    name.push("-");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// `.db.gz` backups whose decompressed header is the plaintext SQLite header.
+fn count_plaintext_backups(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.to_string_lossy().ends_with(".db.gz"))
+        .filter(|path| {
+            let mut header = [0u8; 16];
+            File::open(path)
+                .and_then(|file| GzDecoder::new(file).read_exact(&mut header))
+                .is_ok_and(|()| &header == PLAINTEXT_HEADER)
+        })
+        .count()
+}
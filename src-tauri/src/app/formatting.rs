@@ -0,0 +1,262 @@
+//! Date/time formatting preferences for Rust-generated report and export text.
+//!
+//! 设置存于 `sync_config`（本机偏好，不随同步传播）：`format_date`、`format_week_start`、`format_hour_cycle`。
+//! 报表、摘要、热力图等由 Rust 生成的人类可读文本统一经 `FormatSettings` 输出日期与时间，并按
+//! `week_start` 划分周；机器可读字段（JSON 导出、DTO、同步数据）仍保持 ISO 8601 / RFC 3339 不变。
+//! 时间戳以 UTC 存储，格式化时转换为本机时区。
+
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+pub const FORMAT_DATE_KEY: &str = "format_date";
+pub const FORMAT_WEEK_START_KEY: &str = "format_week_start";
+pub const FORMAT_HOUR_CYCLE_KEY: &str = "format_hour_cycle";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateFormat {
+    #[default]
+    #[serde(rename = "YYYY-MM-DD")]
+    Iso,
+    #[serde(rename = "YYYY/MM/DD")]
+    YmdSlash,
+    #[serde(rename = "DD/MM/YYYY")]
+    Dmy,
+    #[serde(rename = "MM/DD/YYYY")]
+    Mdy,
+    #[serde(rename = "DD.MM.YYYY")]
+    DmyDot,
+}
+
+impl DateFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Iso => "YYYY-MM-DD",
+            Self::YmdSlash => "YYYY/MM/DD",
+            Self::Dmy => "DD/MM/YYYY",
+            Self::Mdy => "MM/DD/YYYY",
+            Self::DmyDot => "DD.MM.YYYY",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "YYYY-MM-DD" => Some(Self::Iso),
+            "YYYY/MM/DD" => Some(Self::YmdSlash),
+            "DD/MM/YYYY" => Some(Self::Dmy),
+            "MM/DD/YYYY" => Some(Self::Mdy),
+            "DD.MM.YYYY" => Some(Self::DmyDot),
+            _ => None,
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            Self::Iso => "%Y-%m-%d",
+            Self::YmdSlash => "%Y/%m/%d",
+            Self::Dmy => "%d/%m/%Y",
+            Self::Mdy => "%m/%d/%Y",
+            Self::DmyDot => "%d.%m.%Y",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+    Saturday,
+}
+
+impl WeekStart {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Monday => "monday",
+            Self::Sunday => "sunday",
+            Self::Saturday => "saturday",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "monday" => Some(Self::Monday),
+            "sunday" => Some(Self::Sunday),
+            "saturday" => Some(Self::Saturday),
+            _ => None,
+        }
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        match self {
+            Self::Monday => Weekday::Mon,
+            Self::Sunday => Weekday::Sun,
+            Self::Saturday => Weekday::Sat,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HourCycle {
+    #[default]
+    #[serde(rename = "24h")]
+    H24,
+    #[serde(rename = "12h")]
+    H12,
+}
+
+impl HourCycle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::H24 => "24h",
+            Self::H12 => "12h",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "24h" => Some(Self::H24),
+            "12h" => Some(Self::H12),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatSettings {
+    pub date_format: DateFormat,
+    pub week_start: WeekStart,
+    pub hour_cycle: HourCycle,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatSettingsReq {
+    /// If omitted, keep existing value.
+    pub date_format: Option<DateFormat>,
+    /// If omitted, keep existing value.
+    pub week_start: Option<WeekStart>,
+    /// If omitted, keep existing value.
+    pub hour_cycle: Option<HourCycle>,
+}
+
+impl FormatSettings {
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(self.date_format.pattern()).to_string()
+    }
+
+    pub fn format_time(&self, time: NaiveTime) -> String {
+        match self.hour_cycle {
+            HourCycle::H24 => time.format("%H:%M").to_string(),
+            HourCycle::H12 => {
+                let (pm, hour) = time.hour12();
+                format!(
+                    "{}:{:02} {}",
+                    hour,
+                    time.minute(),
+                    if pm { "PM" } else { "AM" }
+                )
+            }
+        }
+    }
+
+    pub fn format_datetime(&self, at: NaiveDateTime) -> String {
+        format!(
+            "{} {}",
+            self.format_date(at.date()),
+            self.format_time(at.time())
+        )
+    }
+
+    /// Stored `YYYY-MM-DD` date; unparseable input is returned unchanged.
+    pub fn format_date_str(&self, raw: &str) -> String {
+        NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d")
+            .map(|d| self.format_date(d))
+            .unwrap_or_else(|_| raw.to_string())
+    }
+
+    /// Stored RFC 3339 timestamp rendered in local time; unparseable input is returned unchanged.
+    pub fn format_timestamp(&self, raw: &str) -> String {
+        DateTime::parse_from_rfc3339(raw.trim())
+            .map(|at| self.format_datetime(at.with_timezone(&Local).naive_local()))
+            .unwrap_or_else(|_| raw.to_string())
+    }
+
+    /// First day of the week containing `date`.
+    pub fn week_start_of(&self, date: NaiveDate) -> NaiveDate {
+        let offset = (date.weekday().num_days_from_monday() + 7
+            - self.week_start.weekday().num_days_from_monday())
+            % 7;
+        date - chrono::Duration::days(offset as i64)
+    }
+
+    /// `start – end` label for the week containing `date`.
+    pub fn week_label(&self, date: NaiveDate) -> String {
+        let start = self.week_start_of(date);
+        format!(
+            "{} – {}",
+            self.format_date(start),
+            self.format_date(start + chrono::Duration::days(6))
+        )
+    }
+}
+
+pub fn load_format_settings(conn: &Connection) -> FormatSettings {
+    FormatSettings {
+        date_format: load_config_value(conn, FORMAT_DATE_KEY)
+            .and_then(|v| DateFormat::parse(&v))
+            .unwrap_or_default(),
+        week_start: load_config_value(conn, FORMAT_WEEK_START_KEY)
+            .and_then(|v| WeekStart::parse(&v))
+            .unwrap_or_default(),
+        hour_cycle: load_config_value(conn, FORMAT_HOUR_CYCLE_KEY)
+            .and_then(|v| HourCycle::parse(&v))
+            .unwrap_or_default(),
+    }
+}
+
+pub fn format_settings_get(pool: &DbPool) -> Result<FormatSettings, AppError> {
+    let conn = get_connection(pool);
+    Ok(load_format_settings(&conn))
+}
+
+pub fn format_settings_update(
+    pool: &DbPool,
+    req: FormatSettingsReq,
+) -> Result<FormatSettings, AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    if let Some(date_format) = req.date_format {
+        set_config_value(&tx, FORMAT_DATE_KEY, date_format.as_str())?;
+    }
+    if let Some(week_start) = req.week_start {
+        set_config_value(&tx, FORMAT_WEEK_START_KEY, week_start.as_str())?;
+    }
+    if let Some(hour_cycle) = req.hour_cycle {
+        set_config_value(&tx, FORMAT_HOUR_CYCLE_KEY, hour_cycle.as_str())?;
+    }
+    tx.commit()?;
+    Ok(load_format_settings(&conn))
+}
+
+fn load_config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+}
+
+fn set_config_value(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
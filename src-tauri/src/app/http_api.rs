@@ -0,0 +1,278 @@
+//! Local read-only HTTP API: settings and request routing.
+//!
+//! - 默认关闭；开启后监听 `127.0.0.1:<port>`（只接受本机连接），供脚本、Grafana、快捷指令等读取数据。
+//! - 所有接口（`/api/v1/health` 除外）需要 `Authorization: Bearer <token>`；token 在首次开启时生成，可重新生成。
+//! - 只读：只接受 GET / HEAD；接口与 `project_list`、`project_get` 和报表命令返回相同的 JSON。
+//! - 错误：`{ "error": { code, message } }`；NOT_FOUND → 404，VALIDATION_ERROR → 400，其余 → 500。
+//! - 配置存于 `sync_config`（本机偏好，不随同步传播）。
+
+use crate::app::{
+    project_get, project_list, report_partner_summary, report_person_workload, report_summary,
+    PersonWorkloadReq, ProjectListReq, ReportSummaryReq,
+};
+use crate::error::AppError;
+use crate::infra::http_server::{HttpRequest, HttpResponse};
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+pub const HTTP_API_ENABLED_KEY: &str = "http_api_enabled";
+pub const HTTP_API_PORT_KEY: &str = "http_api_port";
+pub const HTTP_API_TOKEN_KEY: &str = "http_api_token";
+
+pub const DEFAULT_HTTP_API_PORT: u16 = 27121;
+/// Privileged ports are not allowed.
+pub const MIN_HTTP_API_PORT: u16 = 1024;
+
+const API_PREFIX: &str = "/api/v1";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiConfigDto {
+    pub enabled: bool,
+    pub port: u16,
+    /// Bearer token for requests; `None` until the API is enabled for the first time.
+    pub token: Option<String>,
+    /// e.g. `http://127.0.0.1:27121/api/v1`
+    pub base_url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiConfigReq {
+    /// If omitted, keep existing value.
+    pub enabled: Option<bool>,
+    /// 1024..=65535; if omitted, keep existing value.
+    pub port: Option<u16>,
+}
+
+pub fn http_api_get_config(pool: &DbPool) -> Result<HttpApiConfigDto, AppError> {
+    let conn = get_connection(pool);
+    Ok(load_config(&conn))
+}
+
+pub fn http_api_update_config(
+    pool: &DbPool,
+    req: HttpApiConfigReq,
+) -> Result<HttpApiConfigDto, AppError> {
+    if let Some(port) = req.port {
+        if port < MIN_HTTP_API_PORT {
+            return Err(AppError::Validation(format!(
+                "port must be between {} and 65535",
+                MIN_HTTP_API_PORT
+            )));
+        }
+    }
+    let conn = get_connection(pool);
+    if let Some(port) = req.port {
+        set_config_value(&conn, HTTP_API_PORT_KEY, &port.to_string())?;
+    }
+    if let Some(enabled) = req.enabled {
+        if enabled && load_config_value(&conn, HTTP_API_TOKEN_KEY).is_none() {
+            set_config_value(&conn, HTTP_API_TOKEN_KEY, &new_token())?;
+        }
+        set_config_value(&conn, HTTP_API_ENABLED_KEY, bool_str(enabled))?;
+    }
+    Ok(load_config(&conn))
+}
+
+/// Replace the token; requests with the old one are rejected immediately.
+pub fn http_api_regenerate_token(pool: &DbPool) -> Result<HttpApiConfigDto, AppError> {
+    let conn = get_connection(pool);
+    set_config_value(&conn, HTTP_API_TOKEN_KEY, &new_token())?;
+    Ok(load_config(&conn))
+}
+
+/// Default false.
+pub fn load_http_api_enabled(conn: &Connection) -> bool {
+    load_config_value(conn, HTTP_API_ENABLED_KEY).is_some_and(|v| v == "true")
+}
+
+pub fn load_http_api_port(conn: &Connection) -> u16 {
+    load_config_value(conn, HTTP_API_PORT_KEY)
+        .and_then(|v| v.parse::<u16>().ok())
+        .filter(|port| *port >= MIN_HTTP_API_PORT)
+        .unwrap_or(DEFAULT_HTTP_API_PORT)
+}
+
+/// Answer one API request. Blocking (reads the database): call from `spawn_blocking`.
+pub fn http_api_handle(pool: &DbPool, req: &HttpRequest, today: NaiveDate) -> HttpResponse {
+    if req.method != "GET" && req.method != "HEAD" {
+        return error_response(405, "METHOD_NOT_ALLOWED", "the API is read-only");
+    }
+    let Some(route) = req.path.strip_prefix(API_PREFIX) else {
+        return error_response(404, "NOT_FOUND", "unknown endpoint");
+    };
+    let route = route.trim_end_matches('/');
+    if route == "/health" {
+        return ok_response(json!({ "ok": true, "version": env!("CARGO_PKG_VERSION") }));
+    }
+
+    let token = load_config_value(&get_read_connection(pool), HTTP_API_TOKEN_KEY);
+    let presented = req
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    match (token.as_deref(), presented) {
+        (Some(token), Some(presented)) if constant_time_eq(token, presented) => {}
+        _ => return error_response(401, "UNAUTHORIZED", "missing or invalid bearer token"),
+    }
+
+    let result = match route {
+        "/projects" => project_list(pool, project_list_req(req)).and_then(|page| to_value(&page)),
+        "/reports/summary" => report_summary(
+            pool,
+            ReportSummaryReq {
+                from: query_value(req, "from"),
+                to: query_value(req, "to"),
+            },
+            today,
+        )
+        .and_then(|dto| to_value(&dto)),
+        "/reports/person-workload" => query_number(req, "upcomingDays")
+            .and_then(|upcoming_days| {
+                report_person_workload(pool, PersonWorkloadReq { upcoming_days }, today)
+            })
+            .and_then(|dto| to_value(&dto)),
+        "/reports/partner-summary" => report_partner_summary(pool).and_then(|dto| to_value(&dto)),
+        _ => match route.strip_prefix("/projects/") {
+            Some(id) if !id.is_empty() && !id.contains('/') => {
+                project_get(pool, id).and_then(|dto| to_value(&dto))
+            }
+            _ => return error_response(404, "NOT_FOUND", "unknown endpoint"),
+        },
+    };
+    match result {
+        Ok(body) => ok_response(body),
+        Err(e) => {
+            let status = match &e {
+                AppError::NotFound(_) => 404,
+                AppError::Validation(_) => 400,
+                _ => 500,
+            };
+            error_response(status, e.code(), &e.to_string())
+        }
+    }
+}
+
+/// Query parameters of `/projects`. List filters accept repeated keys or comma-separated values.
+fn project_list_req(req: &HttpRequest) -> ProjectListReq {
+    ProjectListReq {
+        only_unarchived: query_value(req, "onlyUnarchived").map(|v| v == "true"),
+        statuses: query_list(req, "status"),
+        country_codes: query_list(req, "countryCode"),
+        partner_ids: query_list(req, "partnerId"),
+        owner_person_ids: query_list(req, "ownerPersonId"),
+        participant_person_ids: query_list(req, "participantPersonId"),
+        tags: query_list(req, "tag"),
+        parent_project_id: query_value(req, "parentProjectId"),
+        custom_fields: None,
+        flagged: query_value(req, "flagged").map(|v| v == "true"),
+        flag_rule_ids: query_list(req, "flagRuleId"),
+        sort_by: query_value(req, "sortBy"),
+        sort_order: query_value(req, "sortOrder"),
+        limit: query_value(req, "limit").and_then(|v| v.parse().ok()),
+        offset: query_value(req, "offset").and_then(|v| v.parse().ok()),
+        after_updated_at: query_value(req, "afterUpdatedAt"),
+        after_id: query_value(req, "afterId"),
+    }
+}
+
+fn query_value(req: &HttpRequest, key: &str) -> Option<String> {
+    req.query
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.clone())
+}
+
+fn query_list(req: &HttpRequest, key: &str) -> Option<Vec<String>> {
+    let values: Vec<String> = req
+        .query
+        .iter()
+        .filter(|(k, _)| k == key)
+        .flat_map(|(_, v)| v.split(','))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!values.is_empty()).then_some(values)
+}
+
+fn query_number(req: &HttpRequest, key: &str) -> Result<Option<i64>, AppError> {
+    query_value(req, key)
+        .map(|v| {
+            v.parse::<i64>()
+                .map_err(|_| AppError::Validation(format!("{} must be a number", key)))
+        })
+        .transpose()
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, AppError> {
+    serde_json::to_value(value).map_err(|e| AppError::Db(e.to_string()))
+}
+
+fn ok_response(body: Value) -> HttpResponse {
+    HttpResponse {
+        status: 200,
+        body: body.to_string(),
+    }
+}
+
+fn error_response(status: u16, code: &str, message: &str) -> HttpResponse {
+    HttpResponse {
+        status,
+        body: json!({ "error": { "code": code, "message": message } }).to_string(),
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+fn new_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn load_config(conn: &Connection) -> HttpApiConfigDto {
+    let port = load_http_api_port(conn);
+    HttpApiConfigDto {
+        enabled: load_http_api_enabled(conn),
+        port,
+        token: load_config_value(conn, HTTP_API_TOKEN_KEY),
+        base_url: format!("http://127.0.0.1:{}{}", port, API_PREFIX),
+    }
+}
+
+fn bool_str(value: bool) -> &'static str {
+    if value {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+fn load_config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+    .filter(|v| !v.is_empty())
+}
+
+fn set_config_value(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
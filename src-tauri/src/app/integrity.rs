@@ -0,0 +1,121 @@
+//! Strict foreign-key mode: referential pre-checks in the app layer.
+//!
+//! SQLite 外键约束未开启（同步、导入会以任意顺序写入），默认行为不变。开启严格模式（`strict_fk_enabled`）后：
+//! - 创建项目校验合作方与负责人存在、未删除且未停用；更新项目时更换负责人同样校验。
+//! - 添加 / 结束成员校验项目存在且未归档；添加成员还校验人员可用。
+//! - 应用远端 delta 时，引用尚未到达的 upsert 暂存到 `sync_deferred_ops`，引用到达后自动补应用（见 `sync::deferred`）。
+//!
+//! 违反时返回 `VALIDATION_ERROR`，消息以 `FK_VIOLATION:` 开头。
+
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+pub const STRICT_FK_KEY: &str = "strict_fk_enabled";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrictFkConfigDto {
+    pub enabled: bool,
+    /// Remote operations waiting for referenced rows to arrive.
+    pub deferred_ops: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrictFkConfigReq {
+    pub enabled: bool,
+}
+
+pub fn strict_fk_get_config(pool: &DbPool) -> Result<StrictFkConfigDto, AppError> {
+    let conn = get_connection(pool);
+    load_config(&conn)
+}
+
+/// Turning strict mode off does not drop deferred operations: they are applied with the
+/// next incoming delta (or right away by the command wrapper).
+pub fn strict_fk_update_config(
+    pool: &DbPool,
+    req: StrictFkConfigReq,
+) -> Result<StrictFkConfigDto, AppError> {
+    let conn = get_connection(pool);
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [STRICT_FK_KEY, if req.enabled { "true" } else { "false" }],
+    )?;
+    load_config(&conn)
+}
+
+pub fn load_strict_fk_enabled(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [STRICT_FK_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .is_some_and(|v| v.trim() == "true")
+}
+
+fn load_config(conn: &Connection) -> Result<StrictFkConfigDto, AppError> {
+    Ok(StrictFkConfigDto {
+        enabled: load_strict_fk_enabled(conn),
+        deferred_ops: conn.query_row("SELECT COUNT(*) FROM sync_deferred_ops", [], |r| r.get(0))?,
+    })
+}
+
+/// Strict mode: the partner exists, is not in the trash and is active.
+pub(crate) fn ensure_partner_usable(conn: &Connection, partner_id: &str) -> Result<(), AppError> {
+    ensure_active(conn, "partners", "partner", partner_id)
+}
+
+/// Strict mode: the person exists, is not in the trash and is active.
+pub(crate) fn ensure_person_usable(conn: &Connection, person_id: &str) -> Result<(), AppError> {
+    ensure_active(conn, "persons", "person", person_id)
+}
+
+/// Strict mode: the project exists, is not in the trash and is not archived.
+pub(crate) fn ensure_project_open(conn: &Connection, project_id: &str) -> Result<(), AppError> {
+    let status: Option<String> = conn
+        .query_row(
+            "SELECT current_status FROM projects WHERE id = ?1 AND deleted_at IS NULL",
+            [project_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match status.as_deref() {
+        None => Err(AppError::Validation(format!(
+            "FK_VIOLATION: project {} does not exist",
+            project_id
+        ))),
+        Some("ARCHIVED") => Err(AppError::Validation(format!(
+            "FK_VIOLATION: project {} is archived",
+            project_id
+        ))),
+        Some(_) => Ok(()),
+    }
+}
+
+fn ensure_active(conn: &Connection, table: &str, label: &str, id: &str) -> Result<(), AppError> {
+    let is_active: Option<i64> = conn
+        .query_row(
+            &format!(
+                "SELECT is_active FROM {} WHERE id = ?1 AND deleted_at IS NULL",
+                table
+            ),
+            [id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match is_active {
+        None => Err(AppError::Validation(format!(
+            "FK_VIOLATION: {} {} does not exist",
+            label, id
+        ))),
+        Some(0) => Err(AppError::Validation(format!(
+            "FK_VIOLATION: {} {} is inactive",
+            label, id
+        ))),
+        Some(_) => Ok(()),
+    }
+}
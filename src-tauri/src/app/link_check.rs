@@ -0,0 +1,577 @@
+//! Link health check: find URLs in project records and report the ones that no longer resolve.
+//!
+//! - 扫描范围：项目描述、评论、文本类自定义字段（外部引用通常填在这里）。
+//! - 默认关闭（会访问外网）：`link_check_enabled` 打开后由 `LinkCheckRuntime` 每周检查一次；
+//!   手动运行（`cmd_link_check_run`）不受开关限制。配置存于 `sync_config`（本机偏好，不随同步传播）。
+//! - 同一 URL 每次只探测一次，最多 `LINK_CHECK_CONCURRENCY` 个并发请求，单次最多 `MAX_LINKS_PER_RUN` 个 URL。
+//! - 结果写入本地表 `link_check_results`（每次运行整体替换）；发现失效链接时写入通知。
+//! - 需要登录才能访问的链接（401 / 403）记为 unverified，不计入失效。
+
+use crate::app::notice_record;
+use crate::error::AppError;
+use crate::infra::link_probe::{LinkProbe, ProbeOutcome};
+use crate::infra::{get_connection, DbPool};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+pub const LINK_CHECK_ENABLED_KEY: &str = "link_check_enabled";
+pub const LAST_LINK_CHECK_AT_KEY: &str = "last_link_check_at";
+pub const NOTICE_KIND_DEAD_LINKS: &str = "DEAD_LINKS";
+
+/// Parallel HTTP probes per run.
+pub const LINK_CHECK_CONCURRENCY: usize = 4;
+/// Distinct URLs probed per run; the rest are skipped until the next run.
+pub const MAX_LINKS_PER_RUN: usize = 500;
+/// Days between scheduled checks.
+pub const LINK_CHECK_INTERVAL_DAYS: i64 = 7;
+
+/// Serializes runs (weekly runtime vs. command) so results are never interleaved.
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkSource {
+    Description,
+    Comment,
+    CustomField,
+}
+
+impl LinkSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Description => "description",
+            Self::Comment => "comment",
+            Self::CustomField => "custom_field",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "description" => Some(Self::Description),
+            "comment" => Some(Self::Comment),
+            "custom_field" => Some(Self::CustomField),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkOutcome {
+    Ok,
+    Dead,
+    Unverified,
+}
+
+impl LinkOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Dead => "dead",
+            Self::Unverified => "unverified",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ok" => Some(Self::Ok),
+            "dead" => Some(Self::Dead),
+            "unverified" => Some(Self::Unverified),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkCheckConfigDto {
+    pub enabled: bool,
+    pub last_checked_at: Option<String>,
+    /// `None` when disabled.
+    pub next_due_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkCheckConfigReq {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkCheckRunResult {
+    pub checked_at: String,
+    /// Link occurrences found (the same URL in two places counts twice).
+    pub total_links: usize,
+    /// Distinct URLs probed.
+    pub checked_urls: usize,
+    /// Distinct URLs over `MAX_LINKS_PER_RUN`, not probed this run.
+    pub skipped_urls: usize,
+    pub dead: usize,
+    pub unverified: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkIssueDto {
+    pub url: String,
+    pub source: LinkSource,
+    /// Comment id / custom field id; `None` for the description.
+    pub source_id: Option<String>,
+    pub outcome: LinkOutcome,
+    pub status_code: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectLinkHealthDto {
+    pub project_id: String,
+    pub project_name: String,
+    pub dead: usize,
+    pub unverified: usize,
+    /// Dead links first, then unverified.
+    pub links: Vec<LinkIssueDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkCheckReportDto {
+    /// `None` when no check ran yet.
+    pub checked_at: Option<String>,
+    pub total_links: usize,
+    pub dead: usize,
+    pub unverified: usize,
+    /// Projects with at least one dead or unverified link, most dead links first.
+    pub projects: Vec<ProjectLinkHealthDto>,
+}
+
+#[derive(Debug, Clone)]
+struct LinkRef {
+    project_id: String,
+    url: String,
+    source: LinkSource,
+    source_id: Option<String>,
+}
+
+/// `http(s)://` URLs in `text`, in order of appearance and without duplicates.
+///
+/// A URL ends at whitespace, quotes, angle brackets or a non-ASCII character; trailing
+/// punctuation and unbalanced closing brackets (Markdown links, parentheses) are dropped.
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let lower = text.to_ascii_lowercase();
+    let mut urls: Vec<String> = Vec::new();
+    let mut from = 0;
+    while let Some(found) = find_scheme(&lower[from..]) {
+        let start = from + found;
+        let boundary = text[..start]
+            .chars()
+            .next_back()
+            .map_or(true, |c| !c.is_ascii_alphanumeric());
+        let end = text[start..]
+            .find(|c: char| {
+                c.is_whitespace() || !c.is_ascii() || matches!(c, '"' | '\'' | '<' | '>' | '`')
+            })
+            .map_or(text.len(), |i| start + i);
+        from = end.max(start + 1);
+        if !boundary {
+            continue;
+        }
+        let url = trim_url(&text[start..end]);
+        let host = url.split_once("://").map_or("", |(_, rest)| rest);
+        if host.is_empty() || host.starts_with('/') {
+            continue;
+        }
+        if !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+fn find_scheme(lower: &str) -> Option<usize> {
+    match (lower.find("http://"), lower.find("https://")) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn trim_url(mut url: &str) -> &str {
+    loop {
+        let Some(last) = url.chars().next_back() else {
+            return url;
+        };
+        let unbalanced = |open: char, close: char| {
+            last == close && url.matches(close).count() > url.matches(open).count()
+        };
+        if matches!(last, '.' | ',' | ';' | ':' | '!' | '?')
+            || unbalanced('(', ')')
+            || unbalanced('[', ']')
+            || unbalanced('{', '}')
+        {
+            url = &url[..url.len() - last.len_utf8()];
+        } else {
+            return url;
+        }
+    }
+}
+
+/// Every link occurrence in descriptions, comments and text custom field values.
+fn collect_links(conn: &Connection) -> Result<Vec<LinkRef>, AppError> {
+    let mut texts: Vec<(String, LinkSource, Option<String>, String)> = Vec::new();
+    let mut stmt = conn.prepare(
+        "SELECT id, description FROM projects WHERE description <> '' AND deleted_at IS NULL ORDER BY created_at",
+    )?;
+    for row in stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))? {
+        let (project_id, text) = row?;
+        texts.push((project_id, LinkSource::Description, None, text));
+    }
+    let mut stmt = conn.prepare(
+        "SELECT c.project_id, c.id, c.content FROM project_comments c
+         JOIN projects p ON p.id = c.project_id AND p.deleted_at IS NULL
+         ORDER BY c.created_at",
+    )?;
+    for row in stmt.query_map([], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            r.get::<_, String>(1)?,
+            r.get::<_, String>(2)?,
+        ))
+    })? {
+        let (project_id, id, text) = row?;
+        texts.push((project_id, LinkSource::Comment, Some(id), text));
+    }
+    let mut stmt = conn.prepare(
+        "SELECT v.project_id, v.field_id, v.value FROM project_custom_values v
+         JOIN custom_field_defs d ON d.id = v.field_id
+         JOIN projects p ON p.id = v.project_id AND p.deleted_at IS NULL
+         WHERE d.field_type = 'text'
+         ORDER BY d.sort_order, d.name",
+    )?;
+    for row in stmt.query_map([], |r| {
+        Ok((
+            r.get::<_, String>(0)?,
+            r.get::<_, String>(1)?,
+            r.get::<_, String>(2)?,
+        ))
+    })? {
+        let (project_id, field_id, text) = row?;
+        texts.push((project_id, LinkSource::CustomField, Some(field_id), text));
+    }
+
+    Ok(texts
+        .into_iter()
+        .flat_map(|(project_id, source, source_id, text)| {
+            extract_urls(&text).into_iter().map(move |url| LinkRef {
+                project_id: project_id.clone(),
+                url,
+                source,
+                source_id: source_id.clone(),
+            })
+        })
+        .collect())
+}
+
+pub fn link_check_get_config(pool: &DbPool) -> Result<LinkCheckConfigDto, AppError> {
+    let conn = get_connection(pool);
+    Ok(load_config(&conn))
+}
+
+pub fn link_check_update_config(
+    pool: &DbPool,
+    req: LinkCheckConfigReq,
+) -> Result<LinkCheckConfigDto, AppError> {
+    let conn = get_connection(pool);
+    set_config_value(
+        &conn,
+        LINK_CHECK_ENABLED_KEY,
+        if req.enabled { "true" } else { "false" },
+    )?;
+    Ok(load_config(&conn))
+}
+
+pub fn load_link_check_enabled(conn: &Connection) -> bool {
+    load_config_value(conn, LINK_CHECK_ENABLED_KEY).is_some_and(|v| v == "true")
+}
+
+fn load_config(conn: &Connection) -> LinkCheckConfigDto {
+    let enabled = load_link_check_enabled(conn);
+    let last_checked_at = load_config_value(conn, LAST_LINK_CHECK_AT_KEY);
+    LinkCheckConfigDto {
+        enabled,
+        next_due_at: next_check_at(enabled, last_checked_at.as_deref()).map(|at| at.to_rfc3339()),
+        last_checked_at,
+    }
+}
+
+/// `None` when disabled; the epoch when no check ran yet (due now).
+fn next_check_at(enabled: bool, last: Option<&str>) -> Option<DateTime<Utc>> {
+    if !enabled {
+        return None;
+    }
+    let last = last
+        .and_then(|v| DateTime::parse_from_rfc3339(v.trim()).ok())
+        .map(|at| at.with_timezone(&Utc));
+    Some(match last {
+        Some(at) => at + chrono::Duration::days(LINK_CHECK_INTERVAL_DAYS),
+        None => DateTime::<Utc>::default(),
+    })
+}
+
+/// Run a check if enabled and due at `now`. `Ok(None)` when disabled or not yet due.
+pub fn link_check_run_scheduled(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+) -> Result<Option<LinkCheckRunResult>, AppError> {
+    {
+        let conn = get_connection(pool);
+        let last = load_config_value(&conn, LAST_LINK_CHECK_AT_KEY);
+        let due = next_check_at(load_link_check_enabled(&conn), last.as_deref())
+            .is_some_and(|at| at <= now);
+        if !due {
+            return Ok(None);
+        }
+    }
+    link_check_run(pool, now).map(Some)
+}
+
+/// Probe every link over HTTP. Blocking: call from `spawn_blocking` in async code.
+pub fn link_check_run(pool: &DbPool, now: DateTime<Utc>) -> Result<LinkCheckRunResult, AppError> {
+    let probe = LinkProbe::new().map_err(|e| AppError::Report(format!("link check: {}", e)))?;
+    link_check_run_with(pool, now, &|url| probe.probe(url))
+}
+
+/// Check with a caller-supplied probe (tests use a fake one instead of the network).
+pub fn link_check_run_with(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+    probe: &(dyn Fn(&str) -> ProbeOutcome + Sync),
+) -> Result<LinkCheckRunResult, AppError> {
+    let _guard = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let links = {
+        let conn = get_connection(pool);
+        collect_links(&conn)?
+    };
+
+    let mut urls: Vec<&str> = Vec::new();
+    for link in &links {
+        if !urls.contains(&link.url.as_str()) {
+            urls.push(&link.url);
+        }
+    }
+    let skipped_urls = urls.len().saturating_sub(MAX_LINKS_PER_RUN);
+    urls.truncate(MAX_LINKS_PER_RUN);
+
+    // The DB lock is not held while probing.
+    let outcomes = probe_all(&urls, probe);
+
+    let checked_at = now.to_rfc3339();
+    let mut conn = get_connection(pool);
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM link_check_results", [])?;
+    let (mut dead, mut unverified) = (0, 0);
+    let mut dead_projects: Vec<&str> = Vec::new();
+    for link in &links {
+        let Some(outcome) = outcomes.get(link.url.as_str()) else {
+            continue;
+        };
+        let (kind, status, error) = match outcome {
+            ProbeOutcome::Ok { status } => (LinkOutcome::Ok, Some(*status), None),
+            ProbeOutcome::Dead { status, error } => (LinkOutcome::Dead, *status, Some(error)),
+            ProbeOutcome::Unverified { status } => (LinkOutcome::Unverified, Some(*status), None),
+        };
+        match kind {
+            LinkOutcome::Dead => {
+                dead += 1;
+                if !dead_projects.contains(&link.project_id.as_str()) {
+                    dead_projects.push(&link.project_id);
+                }
+            }
+            LinkOutcome::Unverified => unverified += 1,
+            LinkOutcome::Ok => {}
+        }
+        tx.execute(
+            "INSERT INTO link_check_results
+                (project_id, url, source, source_id, outcome, status_code, error, checked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                link.project_id,
+                link.url,
+                link.source.as_str(),
+                link.source_id,
+                kind.as_str(),
+                status.map(i64::from),
+                error,
+                checked_at,
+            ],
+        )?;
+    }
+    set_config_value(&tx, LAST_LINK_CHECK_AT_KEY, &checked_at)?;
+    if dead > 0 {
+        notice_record(
+            &tx,
+            NOTICE_KIND_DEAD_LINKS,
+            "warning",
+            &format!(
+                "Link check found {} dead link(s) in {} project(s)",
+                dead,
+                dead_projects.len()
+            ),
+        )?;
+    }
+    tx.commit()?;
+
+    Ok(LinkCheckRunResult {
+        checked_at,
+        total_links: links.len(),
+        checked_urls: urls.len(),
+        skipped_urls,
+        dead,
+        unverified,
+    })
+}
+
+/// Probe `urls` on up to `LINK_CHECK_CONCURRENCY` worker threads.
+fn probe_all<'a>(
+    urls: &[&'a str],
+    probe: &(dyn Fn(&str) -> ProbeOutcome + Sync),
+) -> BTreeMap<&'a str, ProbeOutcome> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(BTreeMap::new());
+    std::thread::scope(|scope| {
+        for _ in 0..LINK_CHECK_CONCURRENCY.min(urls.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&url) = urls.get(i) else {
+                    break;
+                };
+                let outcome = probe(url);
+                results
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(url, outcome);
+            });
+        }
+    });
+    results.into_inner().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Dead and unverified links of the latest check, grouped by project.
+pub fn link_check_report(
+    pool: &DbPool,
+    project_id: Option<&str>,
+) -> Result<LinkCheckReportDto, AppError> {
+    let conn = get_connection(pool);
+    let checked_at = load_config_value(&conn, LAST_LINK_CHECK_AT_KEY);
+    let total_links: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM link_check_results WHERE ?1 IS NULL OR project_id = ?1",
+        params![project_id],
+        |r| r.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT r.project_id, p.name, r.url, r.source, r.source_id, r.outcome, r.status_code, r.error
+         FROM link_check_results r
+         JOIN projects p ON p.id = r.project_id
+         WHERE r.outcome <> 'ok' AND (?1 IS NULL OR r.project_id = ?1)
+         ORDER BY p.name, r.project_id, CASE r.outcome WHEN 'dead' THEN 0 ELSE 1 END, r.id",
+    )?;
+    let rows = stmt
+        .query_map(params![project_id], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, String>(5)?,
+                r.get::<_, Option<i64>>(6)?,
+                r.get::<_, Option<String>>(7)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut projects: Vec<ProjectLinkHealthDto> = Vec::new();
+    for (project_id, project_name, url, source, source_id, outcome, status_code, error) in rows {
+        let (Some(source), Some(outcome)) =
+            (LinkSource::parse(&source), LinkOutcome::parse(&outcome))
+        else {
+            continue;
+        };
+        if projects.last().map(|p| p.project_id.as_str()) != Some(project_id.as_str()) {
+            projects.push(ProjectLinkHealthDto {
+                project_id,
+                project_name,
+                dead: 0,
+                unverified: 0,
+                links: Vec::new(),
+            });
+        }
+        let project = projects.last_mut().expect("pushed above");
+        match outcome {
+            LinkOutcome::Dead => project.dead += 1,
+            _ => project.unverified += 1,
+        }
+        project.links.push(LinkIssueDto {
+            url,
+            source,
+            source_id,
+            outcome,
+            status_code,
+            error,
+        });
+    }
+    projects.sort_by(|a, b| b.dead.cmp(&a.dead));
+
+    Ok(LinkCheckReportDto {
+        checked_at,
+        total_links: total_links as usize,
+        dead: projects.iter().map(|p| p.dead).sum(),
+        unverified: projects.iter().map(|p| p.unverified).sum(),
+        projects,
+    })
+}
+
+fn load_config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+}
+
+fn set_config_value(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_urls_from_prose_markdown_and_json() {
+        let text = "See https://contoso.sharepoint.com/sites/a/Doc.docx, and \
+                    [spec](http://example.com/spec_(v2)). 见https://例子 \
+                    {\"href\":\"https://example.com/x?y=1\"} xhttps://no.example \
+                    https://contoso.sharepoint.com/sites/a/Doc.docx again; https:// alone";
+        assert_eq!(
+            extract_urls(text),
+            vec![
+                "https://contoso.sharepoint.com/sites/a/Doc.docx",
+                "http://example.com/spec_(v2)",
+                "https://example.com/x?y=1",
+            ]
+        );
+        assert!(extract_urls("no links here").is_empty());
+    }
+}
@@ -0,0 +1,296 @@
+//! Database maintenance: integrity check, statistics refresh and space reclamation.
+//!
+//! - 一次运行依次执行：`PRAGMA integrity_check` → `ANALYZE` → 增量 vacuum，并报告运行前后的库大小与索引统计。
+//! - 完整性检查失败时先执行 `REINDEX`（可修复索引损坏）再复查；仍失败则跳过 ANALYZE 与 vacuum、写入通知，
+//!   提示从备份恢复（`cmd_backup_restore`）。
+//! - 旧库的 `auto_vacuum` 为 NONE，增量 vacuum 不生效：首次运行切换为 INCREMENTAL 并执行一次完整 `VACUUM`，
+//!   之后只做 `PRAGMA incremental_vacuum`。
+//! - 默认关闭定时运行：`db_maintenance_enabled` 打开后由 `MaintenanceRuntime` 每 30 天运行一次；
+//!   手动运行（`cmd_db_maintenance`）不受开关限制。配置存于 `sync_config`（本机偏好，不随同步传播）。
+
+use crate::app::notice_record;
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+pub const DB_MAINTENANCE_ENABLED_KEY: &str = "db_maintenance_enabled";
+pub const LAST_DB_MAINTENANCE_AT_KEY: &str = "last_db_maintenance_at";
+pub const NOTICE_KIND_DB_INTEGRITY_FAILED: &str = "DB_INTEGRITY_FAILED";
+
+/// Days between scheduled runs.
+pub const DB_MAINTENANCE_INTERVAL_DAYS: i64 = 30;
+/// Problems reported by `PRAGMA integrity_check` (it stops after this many).
+pub const MAX_INTEGRITY_ERRORS: usize = 100;
+
+/// `PRAGMA auto_vacuum` value for INCREMENTAL.
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+
+/// Serializes runs (monthly runtime vs. command).
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VacuumMode {
+    /// `PRAGMA incremental_vacuum`.
+    Incremental,
+    /// Full `VACUUM`, once, to switch the file to incremental auto-vacuum.
+    Full,
+    /// Not run because the integrity check failed.
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbMaintenanceConfigDto {
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    /// `None` when disabled.
+    pub next_due_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbMaintenanceConfigReq {
+    pub enabled: bool,
+}
+
+/// `sqlite_stat1` row for one index, refreshed by `ANALYZE`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStatDto {
+    pub name: String,
+    pub table: String,
+    /// Rows in the index.
+    pub rows: i64,
+    /// Average rows per distinct value of the leading column (1 = unique).
+    pub avg_rows_per_key: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbMaintenanceReport {
+    pub started_at: String,
+    pub duration_ms: i64,
+    pub integrity_ok: bool,
+    /// Problems still reported after `REINDEX` (empty when ok).
+    pub integrity_errors: Vec<String>,
+    /// `REINDEX` ran because the first check failed.
+    pub reindexed: bool,
+    pub vacuum: VacuumMode,
+    pub size_before_bytes: i64,
+    pub size_after_bytes: i64,
+    /// Unused pages left in the file before / after.
+    pub free_pages_before: i64,
+    pub free_pages_after: i64,
+    pub indexes: Vec<IndexStatDto>,
+}
+
+pub fn db_maintenance_get_config(pool: &DbPool) -> Result<DbMaintenanceConfigDto, AppError> {
+    let conn = get_connection(pool);
+    Ok(load_config(&conn))
+}
+
+pub fn db_maintenance_update_config(
+    pool: &DbPool,
+    req: DbMaintenanceConfigReq,
+) -> Result<DbMaintenanceConfigDto, AppError> {
+    let conn = get_connection(pool);
+    set_config_value(
+        &conn,
+        DB_MAINTENANCE_ENABLED_KEY,
+        if req.enabled { "true" } else { "false" },
+    )?;
+    Ok(load_config(&conn))
+}
+
+pub fn load_db_maintenance_enabled(conn: &Connection) -> bool {
+    load_config_value(conn, DB_MAINTENANCE_ENABLED_KEY).is_some_and(|v| v == "true")
+}
+
+fn load_config(conn: &Connection) -> DbMaintenanceConfigDto {
+    let enabled = load_db_maintenance_enabled(conn);
+    let last_run_at = load_config_value(conn, LAST_DB_MAINTENANCE_AT_KEY);
+    DbMaintenanceConfigDto {
+        enabled,
+        next_due_at: next_run_at(enabled, last_run_at.as_deref()).map(|at| at.to_rfc3339()),
+        last_run_at,
+    }
+}
+
+/// `None` when disabled; the epoch when maintenance never ran (due now).
+fn next_run_at(enabled: bool, last: Option<&str>) -> Option<DateTime<Utc>> {
+    if !enabled {
+        return None;
+    }
+    let last = last
+        .and_then(|v| DateTime::parse_from_rfc3339(v.trim()).ok())
+        .map(|at| at.with_timezone(&Utc));
+    Some(match last {
+        Some(at) => at + chrono::Duration::days(DB_MAINTENANCE_INTERVAL_DAYS),
+        None => DateTime::<Utc>::default(),
+    })
+}
+
+/// Run maintenance if enabled and due at `now`. `Ok(None)` when disabled or not yet due.
+pub fn db_maintenance_run_scheduled(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+) -> Result<Option<DbMaintenanceReport>, AppError> {
+    {
+        let conn = get_connection(pool);
+        let last = load_config_value(&conn, LAST_DB_MAINTENANCE_AT_KEY);
+        let due = next_run_at(load_db_maintenance_enabled(&conn), last.as_deref())
+            .is_some_and(|at| at <= now);
+        if !due {
+            return Ok(None);
+        }
+    }
+    db_maintenance_run(pool, now).map(Some)
+}
+
+/// Check, analyze and vacuum the database. Holds the writer connection for the whole run;
+/// blocking: call from `spawn_blocking` in async code.
+pub fn db_maintenance_run(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+) -> Result<DbMaintenanceReport, AppError> {
+    let _run = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let started = std::time::Instant::now();
+    let conn = get_connection(pool);
+
+    let (size_before_bytes, free_pages_before) = db_size(&conn)?;
+    let mut integrity_errors = integrity_check(&conn)?;
+    let mut reindexed = false;
+    if !integrity_errors.is_empty() {
+        log::warn!(
+            "Integrity check failed ({} problem(s)), rebuilding indexes",
+            integrity_errors.len()
+        );
+        conn.execute_batch("REINDEX")?;
+        reindexed = true;
+        let recheck = integrity_check(&conn)?;
+        if recheck.is_empty() {
+            log::info!("Integrity restored by REINDEX");
+            integrity_errors.clear();
+        } else {
+            integrity_errors = recheck;
+        }
+    }
+    let integrity_ok = integrity_errors.is_empty();
+    if integrity_ok {
+        conn.execute_batch("ANALYZE")?;
+    }
+
+    // A damaged file is left as is for restore: rewriting it could lose more pages.
+    let vacuum = if !integrity_ok {
+        VacuumMode::Skipped
+    } else if conn.query_row("PRAGMA auto_vacuum", [], |row| row.get::<_, i64>(0))?
+        == AUTO_VACUUM_INCREMENTAL
+    {
+        conn.execute_batch("PRAGMA incremental_vacuum")?;
+        VacuumMode::Incremental
+    } else {
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM")?;
+        VacuumMode::Full
+    };
+    let (size_after_bytes, free_pages_after) = db_size(&conn)?;
+    let indexes = index_stats(&conn)?;
+
+    set_config_value(&conn, LAST_DB_MAINTENANCE_AT_KEY, &now.to_rfc3339())?;
+    if !integrity_ok {
+        notice_record(
+            &conn,
+            NOTICE_KIND_DB_INTEGRITY_FAILED,
+            "warning",
+            &format!(
+                "Database integrity check failed ({} problem(s)); restore a backup to recover",
+                integrity_errors.len()
+            ),
+        )?;
+    }
+
+    Ok(DbMaintenanceReport {
+        started_at: now.to_rfc3339(),
+        duration_ms: started.elapsed().as_millis() as i64,
+        integrity_ok,
+        integrity_errors,
+        reindexed,
+        vacuum,
+        size_before_bytes,
+        size_after_bytes,
+        free_pages_before,
+        free_pages_after,
+        indexes,
+    })
+}
+
+/// Problems found by `PRAGMA integrity_check`; empty when the database is ok.
+pub fn integrity_check(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA integrity_check({})", MAX_INTEGRITY_ERRORS))?;
+    let messages = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(if messages == ["ok"] {
+        Vec::new()
+    } else {
+        messages
+    })
+}
+
+/// `(file size in bytes, free pages)` from the page counters.
+fn db_size(conn: &Connection) -> Result<(i64, i64), AppError> {
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let free_pages: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+    Ok((page_size * page_count, free_pages))
+}
+
+/// Per-index statistics from `sqlite_stat1` (populated by `ANALYZE`), largest first.
+fn index_stats(conn: &Connection) -> Result<Vec<IndexStatDto>, AppError> {
+    let mut stmt = conn.prepare("SELECT idx, tbl, stat FROM sqlite_stat1 WHERE idx IS NOT NULL")?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stats: Vec<IndexStatDto> = rows
+        .into_iter()
+        .map(|(name, table, stat)| {
+            let mut numbers = stat.split_whitespace().map_while(|v| v.parse::<i64>().ok());
+            IndexStatDto {
+                name,
+                table,
+                rows: numbers.next().unwrap_or(0),
+                avg_rows_per_key: numbers.next(),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.rows.cmp(&a.rows).then_with(|| a.name.cmp(&b.name)));
+    Ok(stats)
+}
+
+fn load_config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+}
+
+fn set_config_value(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
@@ -1,34 +1,263 @@
 //! Application use cases and transactions.
 
+mod action_log;
+mod activity;
 mod assignment;
+mod audit;
+mod backup;
+mod calendar;
+mod checklist;
 mod comment;
+mod country;
+mod currency;
+mod custom_field;
+mod data_dir;
 mod data_transfer;
+mod digest;
+mod encryption;
+mod formatting;
+mod http_api;
+mod integrity;
+mod link_check;
+mod maintenance;
+mod notice;
+mod notification;
 mod partner;
 mod person;
+mod profile;
 mod project;
+mod project_budget;
+mod project_clone;
+mod project_markdown;
+mod project_share;
+mod project_size;
+mod project_tree;
+mod query;
+mod recurrence;
+mod report;
+mod report_schedule;
+mod saved_view;
+mod schema;
+mod sla;
+mod status_automation;
+mod tag;
+mod template;
+mod trash;
+mod webhook;
+mod workflow;
 
+pub use action_log::{redo_last, undo_last, ActionKind, ActionLogEntryDto, ACTION_LOG_MAX_ENTRIES};
+pub use activity::{activity_list, ActivityItemDto, ActivityListReq, ActivityPage, ACTIVITY_TYPES};
 pub use assignment::{
     assignment_add_member, assignment_end_member, assignment_list_by_project, AssignmentAddReq,
     AssignmentEndReq, AssignmentItemDto,
 };
+pub use audit::{audit_list, AuditEntryDto, AuditListReq, AuditPage, AUDIT_ENTITY_TYPES};
+pub use backup::{
+    backup_create, backup_dir, backup_get_config, backup_list, backup_restore,
+    backup_run_scheduled, backup_update_config, load_backup_max_count, load_backup_schedule,
+    BackupConfigDto, BackupConfigReq, BackupInfoDto, BackupRestoreReq, BackupRestoreResult,
+    BackupSchedule, BACKUP_MAX_COUNT_KEY, BACKUP_REASON_BACKUP_RESTORE, BACKUP_REASON_IMPORT_JSON,
+    BACKUP_REASON_IMPORT_PROJECTS_CSV, BACKUP_REASON_MANUAL, BACKUP_REASON_RESTORE_SNAPSHOT,
+    BACKUP_REASON_SCHEDULED, BACKUP_SCHEDULE_KEY, BACKUP_TARGET_DIR_KEY, DEFAULT_BACKUP_MAX_COUNT,
+    LAST_SCHEDULED_BACKUP_AT_KEY,
+};
+pub use calendar::export_ics;
+pub use checklist::{
+    checklist_add, checklist_delete, checklist_list, checklist_reorder, checklist_toggle,
+    ChecklistAddReq, ChecklistItemDto, ChecklistReorderReq, ChecklistToggleReq,
+    CHECKLIST_TEXT_MAX_CHARS,
+};
 pub use comment::{
-    comment_create, comment_delete, comment_list_by_project, comment_update, CommentCreateReq,
-    CommentDto, CommentUpdateReq,
+    comment_create, comment_delete, comment_history, comment_list_by_project, comment_react,
+    comment_reorder_pins, comment_reply, comment_update, person_mentions, CommentCreateReq,
+    CommentDto, CommentReactReq, CommentReactionDto, CommentReorderPinsReq, CommentReplyReq,
+    CommentRevisionDto, CommentUpdateReq, PersonMentionDto, PersonMentionsReq,
+};
+pub use country::{country_list, country_usage, CountryDto, CountryListReq, CountryUsageDto};
+pub use currency::{
+    currency_get_config, currency_rate_delete, currency_rate_list, currency_rate_set,
+    currency_rates_fetch, currency_rates_fetch_with, currency_update_config, CurrencyConfigDto,
+    CurrencyConfigReq, CurrencyRateDto, CurrencyRateSetReq, CurrencyRatesFetchResult,
+    CURRENCY_BASE_KEY, CURRENCY_RATES_URL_KEY, DEFAULT_BASE_CURRENCY, DEFAULT_RATES_URL,
+    RATE_SOURCE_FETCHED, RATE_SOURCE_MANUAL,
+};
+pub use custom_field::{
+    custom_field_create, custom_field_delete, custom_field_list, custom_field_update,
+    project_set_custom_value, CustomFieldDefCreateReq, CustomFieldDefDto, CustomFieldDefUpdateReq,
+    CustomFieldFilter, CustomFieldType, ProjectCustomValueDto, ProjectCustomValueSetReq,
+    MAX_CUSTOM_VALUE_LEN,
 };
+pub use data_dir::{
+    data_dir_info, data_dir_move, DataDirContext, DataDirInfoDto, DataDirMoveReq, DataDirMoveResult,
+};
+pub(crate) use data_transfer::import_json_conn;
 pub use data_transfer::{
-    export_json_string, export_persons_csv, import_json_string, import_persons_csv,
-    wipe_business_data, ImportResult, PersonImportResult, WipeResult,
+    export_assignments_csv, export_comments_csv, export_json_selective, export_json_string,
+    export_json_to_file, export_persons_csv, export_projects_csv, export_status_history_csv,
+    export_xlsx, import_json_string, import_persons_csv, import_projects_csv, wipe_business_data,
+    CsvExportReq, ImportResult, JsonExportFileResult, JsonExportProgress, JsonExportReq,
+    PersonImportResult, ProjectCsvImportReport, ProjectCsvImportReq, ProjectCsvRowError,
+    WipeResult, XlsxExport, XlsxSheetSummary,
+};
+pub use digest::{
+    digest_compose, digest_get_config, digest_preview, digest_run_scheduled,
+    digest_run_scheduled_with, digest_send_now, digest_update_config, load_digest_enabled,
+    render_payload, DigestConfigDto, DigestConfigReq, DigestDto, DigestFormat, DigestFrequency,
+    DigestPreviewDto, DigestPreviewReq, DigestProjectItem, DigestSendResult,
+    DigestStatusChangeItem, DIGEST_SECTION_LIMIT, NOTICE_KIND_DIGEST_FAILED,
+};
+pub use encryption::{
+    db_encryption_status, db_set_passphrase, DbEncryptionStatusDto, DbSetPassphraseReq,
+};
+pub use formatting::{
+    format_settings_get, format_settings_update, load_format_settings, DateFormat, FormatSettings,
+    FormatSettingsReq, HourCycle, WeekStart, FORMAT_DATE_KEY, FORMAT_HOUR_CYCLE_KEY,
+    FORMAT_WEEK_START_KEY,
+};
+pub use http_api::{
+    http_api_get_config, http_api_handle, http_api_regenerate_token, http_api_update_config,
+    load_http_api_enabled, load_http_api_port, HttpApiConfigDto, HttpApiConfigReq,
+    DEFAULT_HTTP_API_PORT,
+};
+pub use integrity::{
+    load_strict_fk_enabled, strict_fk_get_config, strict_fk_update_config, StrictFkConfigDto,
+    StrictFkConfigReq, STRICT_FK_KEY,
+};
+pub use link_check::{
+    extract_urls, link_check_get_config, link_check_report, link_check_run,
+    link_check_run_scheduled, link_check_run_with, link_check_update_config,
+    load_link_check_enabled, LinkCheckConfigDto, LinkCheckConfigReq, LinkCheckReportDto,
+    LinkCheckRunResult, LinkIssueDto, LinkOutcome, LinkSource, ProjectLinkHealthDto,
+    LAST_LINK_CHECK_AT_KEY, LINK_CHECK_CONCURRENCY, LINK_CHECK_ENABLED_KEY,
+    LINK_CHECK_INTERVAL_DAYS, MAX_LINKS_PER_RUN, NOTICE_KIND_DEAD_LINKS,
+};
+pub use maintenance::{
+    db_maintenance_get_config, db_maintenance_run, db_maintenance_run_scheduled,
+    db_maintenance_update_config, integrity_check, load_db_maintenance_enabled,
+    DbMaintenanceConfigDto, DbMaintenanceConfigReq, DbMaintenanceReport, IndexStatDto, VacuumMode,
+    DB_MAINTENANCE_ENABLED_KEY, DB_MAINTENANCE_INTERVAL_DAYS, LAST_DB_MAINTENANCE_AT_KEY,
+    NOTICE_KIND_DB_INTEGRITY_FAILED,
+};
+pub use notice::{
+    notice_dismiss, notice_list, notice_record, notice_record_layout_migration, AppNoticeDto,
+    NOTICE_KIND_DATA_DIR_MIGRATED, NOTICE_KIND_DATA_DIR_MOVED,
+};
+pub use notification::{
+    load_notification_desktop_enabled, load_notifications_enabled, notification_check,
+    notification_dismiss, notification_get_config, notification_list, notification_run_scheduled,
+    notification_update_config, NotificationCheckResult, NotificationConfigDto,
+    NotificationConfigReq, NotificationDto, NotificationListReq, DEFAULT_BLOCKED_DAYS,
+    DEFAULT_DUE_WINDOW_DAYS, MENTION_WINDOW_DAYS, NOTIFICATION_KIND_BLOCKED_TOO_LONG,
+    NOTIFICATION_KIND_DUE_SOON, NOTIFICATION_KIND_MENTIONED, NOTIFICATION_KIND_OVERDUE,
+    NOTIFICATION_KIND_SLA_BREACHED,
 };
 pub use partner::{
-    partner_create, partner_deactivate, partner_get, partner_list, partner_projects,
-    partner_update, PartnerCreateReq, PartnerDto, PartnerProjectItemDto, PartnerUpdateReq,
+    partner_create, partner_deactivate, partner_find_duplicates, partner_get, partner_list,
+    partner_merge, partner_projects, partner_set_default_currency, partner_update,
+    PartnerCreateReq, PartnerDto, PartnerDuplicateDto, PartnerFindDuplicatesReq, PartnerMergeReq,
+    PartnerMergeResult, PartnerProjectItemDto, PartnerSetCurrencyReq, PartnerUpdateReq,
+    PARTNER_DUPLICATE_THRESHOLD,
 };
 pub use person::{
-    person_all_projects, person_create, person_current_projects, person_deactivate, person_get,
-    person_list, person_update, PersonCreateReq, PersonDto, PersonProjectItemDto, PersonUpdateReq,
+    person_all_projects, person_create, person_current_projects, person_deactivate,
+    person_find_duplicates, person_get, person_list, person_merge, person_update, PersonCreateReq,
+    PersonDto, PersonDuplicateDto, PersonFindDuplicatesReq, PersonMergeReq, PersonMergeResult,
+    PersonProjectItemDto, PersonUpdateReq, PERSON_DUPLICATE_THRESHOLD,
+};
+pub use profile::{
+    normalize_profile_name, profile_create, profile_current, profile_delete, profile_list,
+    profile_switch_target, resolve_profile_data_dir, ProfileContext, ProfileCreateReq,
+    ProfileDeleteReq, ProfileDto, ProfileSwitchReq, DEFAULT_PROFILE,
 };
 pub use project::{
-    project_change_status, project_create, project_get, project_list, project_update,
-    ProjectChangeStatusReq, ProjectCreateReq, ProjectDetailDto, ProjectListItemDto,
-    ProjectListPage, ProjectListReq, ProjectUpdateReq,
+    project_change_status, project_create, project_get, project_list, project_reorder,
+    project_update, ProjectChangeStatusReq, ProjectCreateReq, ProjectDetailDto, ProjectListItemDto,
+    ProjectListPage, ProjectListReq, ProjectRankDto, ProjectReorderReq, ProjectUpdateReq,
+};
+pub use project_budget::{
+    budget_report, project_set_budget, BudgetReportCurrencyDto, BudgetReportDto,
+    BudgetReportPartnerDto, BudgetReportProjectDto, BudgetReportReq, ProjectSetBudgetReq,
+    MAX_BUDGET_AMOUNT, MAX_ESTIMATED_HOURS,
+};
+pub use project_clone::{project_clone, ProjectCloneReq};
+pub use project_markdown::project_export_markdown;
+pub use project_share::{
+    project_share_bundle, project_share_expiry, project_share_object_key, ProjectShareBundle,
+    ProjectShareReq, ProjectShareResult, SharedMemberDto, SharedProjectDto, SharedStatusDto,
+    DEFAULT_SHARE_EXPIRY_HOURS, MAX_SHARE_EXPIRY_HOURS, PROJECT_SHARE_FORMAT,
+    PROJECT_SHARE_VERSION,
+};
+pub use project_size::{
+    project_set_size, project_throughput, project_throughput_until, ProjectSetSizeReq,
+    SizeHistoryDto, SizeInput, ThroughputDto, ThroughputReq, ThroughputWeekDto,
+    DEFAULT_THROUGHPUT_WEEKS, MAX_THROUGHPUT_WEEKS,
+};
+pub use project_tree::{project_tree, ProjectTreeNode, ProjectTreeReq};
+pub use query::{
+    execute_query, query_schema, ExecuteQueryReq, QueryResultDto, QuerySchemaDto,
+    DEFAULT_QUERY_ROWS, MAX_QUERY_ROWS, QUERY_BLOCKED_TABLES, QUERY_TIMEOUT,
+};
+pub use recurrence::{
+    next_run_after, recurrence_rule_create, recurrence_rule_delete, recurrence_rule_get,
+    recurrence_rule_list, recurrence_rule_update, recurrence_run_due, RecurrenceRuleCreateReq,
+    RecurrenceRuleDto, RecurrenceRuleUpdateReq, RecurrenceRunItem, RecurrenceUnit,
+};
+pub use report::{
+    partner_scorecard, render_partner_scorecard_pdf, report_partner_summary,
+    report_person_workload, report_summary, PartnerScorecardDto, PartnerSummaryDto,
+    PersonWorkloadDto, PersonWorkloadReportDto, PersonWorkloadReq, ReportSummaryDto,
+    ReportSummaryReq, ScorecardProjectDto, StatusCountDto, StatusDurationDto, WeeklyThroughputDto,
+    WorkloadDueDto, SCORECARD_RECENT_DAYS, SUMMARY_DEFAULT_DAYS, WORKLOAD_UPCOMING_DAYS,
+};
+pub use report_schedule::{
+    report_run_due, report_schedule_create, report_schedule_delete, report_schedule_get,
+    report_schedule_list, report_schedule_run_now, report_schedule_update, ReportKind,
+    ReportRunItem, ReportScheduleCreateReq, ReportScheduleDto, ReportScheduleUpdateReq,
+    NOTICE_KIND_REPORT_DELIVERED, NOTICE_KIND_REPORT_FAILED,
+};
+pub use saved_view::{
+    saved_view_apply, saved_view_create, saved_view_delete, saved_view_list, SavedViewApplyReq,
+    SavedViewCreateReq, SavedViewDto,
+};
+pub use schema::{
+    db_schema_export, DbSchemaExportDto, DbSchemaExportReq, SchemaColumnDto, SchemaFormat,
+    SchemaIndexDto, SchemaRelationDto, SchemaTableDto,
+};
+pub use sla::{
+    project_flag_list, sla_evaluate, sla_rule_create, sla_rule_delete, sla_rule_list,
+    sla_rule_update, ProjectFlagDto, SlaEvaluateResult, SlaRuleCreateReq, SlaRuleDto,
+    SlaRuleUpdateReq, MAX_SLA_DAYS,
+};
+pub use status_automation::{
+    load_auto_plan_enabled, load_overdue_enabled, status_automation_get_config,
+    status_automation_run, status_automation_update_config, StatusAutomationConfigDto,
+    StatusAutomationConfigReq, StatusAutomationItem, STATUS_AUTOMATION_AUTO_PLAN_KEY,
+    STATUS_AUTOMATION_OVERDUE_KEY,
+};
+pub use tag::{
+    tag_create, tag_delete, tag_delete_unused, tag_list, tag_merge, tag_rename, tag_update,
+    TagChangeResult, TagCreateReq, TagDto, TagMergeReq, TagRenameReq, TagUpdateReq,
+};
+pub use template::{
+    project_create_from_template, project_save_as_template, render_name_pattern, template_delete,
+    template_list, ProjectCreateFromTemplateReq, ProjectSaveAsTemplateReq, ProjectTemplateDto,
+    TemplateMemberDto, TemplateMilestoneDto,
+};
+pub use trash::{
+    trash_delete, trash_list, trash_purge, trash_restore, TrashItemDto, TrashItemReq, TrashKind,
+    TrashPurgeReq, TrashPurgeResult, TrashSkippedDto,
+};
+pub(crate) use webhook::webhook_notify;
+pub use webhook::{
+    retry_delay_secs, webhook_create, webhook_delete, webhook_delivery_list, webhook_dispatch_due,
+    webhook_dispatch_due_with, webhook_list, webhook_update, WebhookCreateReq, WebhookDeliveryDto,
+    WebhookDeliveryListReq, WebhookDispatchResult, WebhookDto, WebhookUpdateReq, WEBHOOK_EVENTS,
+    WEBHOOK_EVENT_COMMENT_CREATED, WEBHOOK_EVENT_PROJECT_STATUS_CHANGED,
+    WEBHOOK_EVENT_SYNC_COMPLETED, WEBHOOK_MAX_ATTEMPTS,
+};
+pub use workflow::{
+    workflow_activate, workflow_create, workflow_delete, workflow_list, workflow_update,
+    workflow_wip_status, WipOwnerCountDto, WipStatusDto, WorkflowCreateReq, WorkflowDto,
+    WorkflowUpdateReq,
 };
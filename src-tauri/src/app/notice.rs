@@ -0,0 +1,105 @@
+//! App notice use cases: record, list, dismiss.
+
+use crate::error::AppError;
+use crate::infra::layout::LegacyLayoutMigration;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+pub const NOTICE_KIND_DATA_DIR_MIGRATED: &str = "DATA_DIR_MIGRATED";
+pub const NOTICE_KIND_DATA_DIR_MOVED: &str = "DATA_DIR_MOVED";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppNoticeDto {
+    pub id: i64,
+    pub kind: String,
+    /// "info" | "warning"
+    pub level: String,
+    pub message: String,
+    pub created_at: String,
+}
+
+/// Insert a notice shown to the user until dismissed.
+pub fn notice_record(
+    conn: &Connection,
+    kind: &str,
+    level: &str,
+    message: &str,
+) -> Result<i64, AppError> {
+    conn.execute(
+        "INSERT INTO app_notices (kind, level, message, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![kind, level, message, Utc::now().to_rfc3339()],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Record the outcome of a legacy data dir migration as a notice.
+pub fn notice_record_layout_migration(
+    conn: &Connection,
+    migration: &LegacyLayoutMigration,
+) -> Result<i64, AppError> {
+    match migration {
+        LegacyLayoutMigration::Migrated { from, to, moved } => notice_record(
+            conn,
+            NOTICE_KIND_DATA_DIR_MIGRATED,
+            "info",
+            &format!(
+                "Data moved from legacy directory {} to {} ({})",
+                from.display(),
+                to.display(),
+                moved.join(", ")
+            ),
+        ),
+        LegacyLayoutMigration::SkippedTargetExists { from, to } => notice_record(
+            conn,
+            NOTICE_KIND_DATA_DIR_MIGRATED,
+            "warning",
+            &format!(
+                "Legacy data directory {} was left untouched because {} already has a database",
+                from.display(),
+                to.display()
+            ),
+        ),
+    }
+}
+
+/// Undismissed notices, oldest first.
+pub fn notice_list(pool: &DbPool) -> Result<Vec<AppNoticeDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, level, message, created_at FROM app_notices
+         WHERE dismissed_at IS NULL ORDER BY id",
+    )?;
+    let items = stmt
+        .query_map([], |row| {
+            Ok(AppNoticeDto {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                level: row.get(2)?,
+                message: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Hide a notice. Dismissing twice is a no-op.
+pub fn notice_dismiss(pool: &DbPool, id: i64) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let exists: bool = conn
+        .query_row("SELECT 1 FROM app_notices WHERE id = ?1", [id], |_| {
+            Ok(true)
+        })
+        .unwrap_or(false);
+    if !exists {
+        return Err(AppError::NotFound(format!("notice {}", id)));
+    }
+    conn.execute(
+        "UPDATE app_notices SET dismissed_at = ?1 WHERE id = ?2 AND dismissed_at IS NULL",
+        params![Utc::now().to_rfc3339(), id],
+    )?;
+    Ok(())
+}
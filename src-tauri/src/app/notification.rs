@@ -0,0 +1,606 @@
+//! Notifications: due-date, blocked-project, @mention and SLA reminders, list and dismiss.
+//!
+//! - 检查范围：未归档、不在回收站、状态不是 DONE / ARCHIVED 的项目。
+//! - `DUE_SOON`：截止日期在今天起 `dueWindowDays` 天内；`OVERDUE`：截止日期早于今天；
+//!   `BLOCKED_TOO_LONG`：最近一次进入 BLOCKED 已超过 `blockedDays` 天。
+//! - `MENTIONED`：近 `MENTION_WINDOW_DAYS` 天内评论中 @提及了某个在职人员（`comment_mentions`），
+//!   每条评论每人一次；自己提及自己不提醒。窗口只限制新建提醒（升级后不会为旧评论补发），
+//!   提及从评论中删除（或评论被删除）后才自动关闭。
+//! - `SLA_BREACHED`：检查前先评估 SLA 规则（`app::sla`），每个项目标记（规则 + 状态周期）提醒一次。
+//! - 每个条件只提醒一次（`dedupe_key`）；条件不再成立（完成、改期、解除阻塞）时未关闭的提醒自动关闭。
+//! - 默认开启，由 `NotificationRuntime` 每小时检查一次；桌面通知默认关闭（开启后新提醒以事件推送给前端）。
+//!   配置存于 `sync_config`（本机偏好，不随同步传播）。
+
+use crate::app::load_format_settings;
+use crate::app::sla::sla_evaluate_conn;
+use crate::error::AppError;
+use crate::infra::markdown::rich_text_to_markdown;
+use crate::infra::{get_connection, DbPool};
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+pub const NOTIFICATIONS_ENABLED_KEY: &str = "notifications_enabled";
+pub const NOTIFICATION_DUE_WINDOW_DAYS_KEY: &str = "notification_due_window_days";
+pub const NOTIFICATION_BLOCKED_DAYS_KEY: &str = "notification_blocked_days";
+pub const NOTIFICATION_DESKTOP_ENABLED_KEY: &str = "notification_desktop_enabled";
+pub const LAST_NOTIFICATION_CHECK_AT_KEY: &str = "last_notification_check_at";
+
+pub const NOTIFICATION_KIND_DUE_SOON: &str = "DUE_SOON";
+pub const NOTIFICATION_KIND_OVERDUE: &str = "OVERDUE";
+pub const NOTIFICATION_KIND_BLOCKED_TOO_LONG: &str = "BLOCKED_TOO_LONG";
+pub const NOTIFICATION_KIND_MENTIONED: &str = "MENTIONED";
+pub const NOTIFICATION_KIND_SLA_BREACHED: &str = "SLA_BREACHED";
+
+pub const DEFAULT_DUE_WINDOW_DAYS: i64 = 3;
+pub const MAX_DUE_WINDOW_DAYS: i64 = 90;
+pub const DEFAULT_BLOCKED_DAYS: i64 = 14;
+pub const MAX_BLOCKED_DAYS: i64 = 365;
+/// Only mentions made in the last days produce a reminder.
+pub const MENTION_WINDOW_DAYS: i64 = 7;
+/// Longest comment excerpt in a mention reminder (in chars).
+const MENTION_EXCERPT_CHARS: usize = 120;
+/// Rows returned by `notification_list` when no limit is given.
+pub const DEFAULT_NOTIFICATION_LIST_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationDto {
+    pub id: i64,
+    /// "DUE_SOON" | "OVERDUE" | "BLOCKED_TOO_LONG" | "MENTIONED" | "SLA_BREACHED"
+    pub kind: String,
+    pub project_id: String,
+    /// Mentioned person (`MENTIONED` only).
+    pub person_id: Option<String>,
+    pub title: String,
+    pub message: String,
+    pub created_at: String,
+    pub dismissed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationConfigDto {
+    pub enabled: bool,
+    pub due_window_days: i64,
+    pub blocked_days: i64,
+    pub desktop_enabled: bool,
+    pub last_checked_at: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationConfigReq {
+    /// If omitted, keep existing value.
+    pub enabled: Option<bool>,
+    /// 0..=90; if omitted, keep existing value.
+    pub due_window_days: Option<i64>,
+    /// 1..=365; if omitted, keep existing value.
+    pub blocked_days: Option<i64>,
+    /// If omitted, keep existing value.
+    pub desktop_enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationListReq {
+    /// Default false: only notifications that are still open.
+    pub include_dismissed: Option<bool>,
+    /// Default `DEFAULT_NOTIFICATION_LIST_LIMIT`.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationCheckResult {
+    pub checked_at: String,
+    /// Reminders created by this check (already-reported conditions are not repeated).
+    pub created: Vec<NotificationDto>,
+    /// Open reminders closed because their condition no longer holds.
+    pub resolved: usize,
+}
+
+struct Reminder {
+    kind: &'static str,
+    project_id: String,
+    person_id: Option<String>,
+    dedupe_key: String,
+    title: String,
+    message: String,
+}
+
+pub fn notification_get_config(pool: &DbPool) -> Result<NotificationConfigDto, AppError> {
+    let conn = get_connection(pool);
+    Ok(load_config(&conn))
+}
+
+pub fn notification_update_config(
+    pool: &DbPool,
+    req: NotificationConfigReq,
+) -> Result<NotificationConfigDto, AppError> {
+    if let Some(days) = req.due_window_days {
+        if !(0..=MAX_DUE_WINDOW_DAYS).contains(&days) {
+            return Err(AppError::Validation(format!(
+                "dueWindowDays must be between 0 and {}",
+                MAX_DUE_WINDOW_DAYS
+            )));
+        }
+    }
+    if let Some(days) = req.blocked_days {
+        if !(1..=MAX_BLOCKED_DAYS).contains(&days) {
+            return Err(AppError::Validation(format!(
+                "blockedDays must be between 1 and {}",
+                MAX_BLOCKED_DAYS
+            )));
+        }
+    }
+
+    let conn = get_connection(pool);
+    if let Some(enabled) = req.enabled {
+        set_config_value(&conn, NOTIFICATIONS_ENABLED_KEY, bool_str(enabled))?;
+    }
+    if let Some(days) = req.due_window_days {
+        set_config_value(&conn, NOTIFICATION_DUE_WINDOW_DAYS_KEY, &days.to_string())?;
+    }
+    if let Some(days) = req.blocked_days {
+        set_config_value(&conn, NOTIFICATION_BLOCKED_DAYS_KEY, &days.to_string())?;
+    }
+    if let Some(desktop) = req.desktop_enabled {
+        set_config_value(&conn, NOTIFICATION_DESKTOP_ENABLED_KEY, bool_str(desktop))?;
+    }
+    Ok(load_config(&conn))
+}
+
+/// Default true (reminders are computed locally).
+pub fn load_notifications_enabled(conn: &Connection) -> bool {
+    load_config_value(conn, NOTIFICATIONS_ENABLED_KEY).map_or(true, |v| v != "false")
+}
+
+/// Default false.
+pub fn load_notification_desktop_enabled(conn: &Connection) -> bool {
+    load_config_value(conn, NOTIFICATION_DESKTOP_ENABLED_KEY).is_some_and(|v| v == "true")
+}
+
+fn load_config(conn: &Connection) -> NotificationConfigDto {
+    NotificationConfigDto {
+        enabled: load_notifications_enabled(conn),
+        due_window_days: load_days(
+            conn,
+            NOTIFICATION_DUE_WINDOW_DAYS_KEY,
+            DEFAULT_DUE_WINDOW_DAYS,
+            0..=MAX_DUE_WINDOW_DAYS,
+        ),
+        blocked_days: load_days(
+            conn,
+            NOTIFICATION_BLOCKED_DAYS_KEY,
+            DEFAULT_BLOCKED_DAYS,
+            1..=MAX_BLOCKED_DAYS,
+        ),
+        desktop_enabled: load_notification_desktop_enabled(conn),
+        last_checked_at: load_config_value(conn, LAST_NOTIFICATION_CHECK_AT_KEY),
+    }
+}
+
+fn load_days(
+    conn: &Connection,
+    key: &str,
+    default: i64,
+    range: std::ops::RangeInclusive<i64>,
+) -> i64 {
+    load_config_value(conn, key)
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|days| range.contains(days))
+        .unwrap_or(default)
+}
+
+/// Generate reminders as of `today` (local date), close the ones that no longer apply.
+/// Runs regardless of the enabled flag (the flag only controls the background checker).
+pub fn notification_check(
+    pool: &DbPool,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> Result<NotificationCheckResult, AppError> {
+    let conn = get_connection(pool);
+    let config = load_config(&conn);
+    sla_evaluate_conn(&conn, today, now)?;
+    let mut reminders = collect_reminders(&conn, &config, today)?;
+    reminders.extend(collect_mention_reminders(&conn, today)?);
+    reminders.extend(collect_sla_reminders(&conn, today)?);
+    let checked_at = now.to_rfc3339();
+
+    let tx = conn.unchecked_transaction()?;
+    let mut created_ids = Vec::new();
+    for reminder in &reminders {
+        let changed = tx.execute(
+            "INSERT OR IGNORE INTO notifications (kind, project_id, person_id, dedupe_key, title, message, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                reminder.kind,
+                &reminder.project_id,
+                &reminder.person_id,
+                &reminder.dedupe_key,
+                &reminder.title,
+                &reminder.message,
+                &checked_at
+            ],
+        )?;
+        if changed > 0 {
+            created_ids.push(tx.last_insert_rowid());
+        }
+    }
+
+    let mention_keys = current_mention_keys(&conn)?;
+    let current: HashSet<&str> = reminders
+        .iter()
+        .map(|r| r.dedupe_key.as_str())
+        .chain(mention_keys.iter().map(String::as_str))
+        .collect();
+    let open: Vec<(i64, String)> = tx
+        .prepare("SELECT id, dedupe_key FROM notifications WHERE dismissed_at IS NULL")?
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut resolved = 0;
+    for (id, key) in open {
+        if !current.contains(key.as_str()) {
+            tx.execute(
+                "UPDATE notifications SET dismissed_at = ?1 WHERE id = ?2",
+                params![&checked_at, id],
+            )?;
+            resolved += 1;
+        }
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        params![LAST_NOTIFICATION_CHECK_AT_KEY, &checked_at],
+    )?;
+    tx.commit()?;
+
+    let created = created_ids
+        .into_iter()
+        .map(|id| load_notification(&conn, id))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(NotificationCheckResult {
+        checked_at,
+        created,
+        resolved,
+    })
+}
+
+/// Run a check if notifications are enabled. `Ok(None)` when disabled.
+pub fn notification_run_scheduled(
+    pool: &DbPool,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> Result<Option<NotificationCheckResult>, AppError> {
+    if !load_notifications_enabled(&get_connection(pool)) {
+        return Ok(None);
+    }
+    notification_check(pool, today, now).map(Some)
+}
+
+/// Newest first.
+pub fn notification_list(
+    pool: &DbPool,
+    req: NotificationListReq,
+) -> Result<Vec<NotificationDto>, AppError> {
+    let limit = req
+        .limit
+        .unwrap_or(DEFAULT_NOTIFICATION_LIST_LIMIT)
+        .clamp(1, 1000);
+    let include_dismissed = req.include_dismissed.unwrap_or(false);
+    let conn = get_connection(pool);
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, project_id, title, message, created_at, dismissed_at, person_id FROM notifications
+         WHERE (?1 OR dismissed_at IS NULL)
+         ORDER BY created_at DESC, id DESC
+         LIMIT ?2",
+    )?;
+    let items = stmt
+        .query_map(params![include_dismissed, limit], map_notification)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Hide a notification. Dismissing twice is a no-op.
+pub fn notification_dismiss(pool: &DbPool, id: i64) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let exists: bool = conn
+        .query_row("SELECT 1 FROM notifications WHERE id = ?1", [id], |_| {
+            Ok(true)
+        })
+        .unwrap_or(false);
+    if !exists {
+        return Err(AppError::NotFound(format!("notification {}", id)));
+    }
+    conn.execute(
+        "UPDATE notifications SET dismissed_at = ?1 WHERE id = ?2 AND dismissed_at IS NULL",
+        params![Utc::now().to_rfc3339(), id],
+    )?;
+    Ok(())
+}
+
+fn collect_reminders(
+    conn: &Connection,
+    config: &NotificationConfigDto,
+    today: NaiveDate,
+) -> Result<Vec<Reminder>, AppError> {
+    let settings = load_format_settings(conn);
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.name, p.current_status, p.due_date, p.updated_at,
+                (SELECT MAX(h.changed_at) FROM status_history h
+                 WHERE h.project_id = p.id AND h.to_status = 'BLOCKED')
+         FROM projects p
+         WHERE p.archived_at IS NULL AND p.deleted_at IS NULL
+           AND p.current_status NOT IN ('DONE', 'ARCHIVED')
+         ORDER BY p.due_date, p.name",
+    )?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, Option<String>>(3)?,
+                r.get::<_, String>(4)?,
+                r.get::<_, Option<String>>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut reminders = Vec::new();
+    for (id, name, status, due_date, updated_at, blocked_at) in rows {
+        let due = due_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok());
+        if let Some(due) = due {
+            let days = (due - today).num_days();
+            let due_str = settings.format_date(due);
+            if days < 0 {
+                reminders.push(Reminder {
+                    kind: NOTIFICATION_KIND_OVERDUE,
+                    project_id: id.clone(),
+                    person_id: None,
+                    dedupe_key: format!("{}:{}:{}", NOTIFICATION_KIND_OVERDUE, id, due),
+                    title: format!("Overdue: {}", name),
+                    message: format!("Was due on {} ({} ago)", due_str, day_count(-days)),
+                });
+            } else if days <= config.due_window_days {
+                reminders.push(Reminder {
+                    kind: NOTIFICATION_KIND_DUE_SOON,
+                    project_id: id.clone(),
+                    person_id: None,
+                    dedupe_key: format!("{}:{}:{}", NOTIFICATION_KIND_DUE_SOON, id, due),
+                    title: format!("Due soon: {}", name),
+                    message: if days == 0 {
+                        format!("Due today ({})", due_str)
+                    } else {
+                        format!("Due on {} (in {})", due_str, day_count(days))
+                    },
+                });
+            }
+        }
+
+        if status == "BLOCKED" {
+            let since_raw = blocked_at.unwrap_or(updated_at);
+            let Some(since) = DateTime::parse_from_rfc3339(since_raw.trim())
+                .ok()
+                .map(|at| at.with_timezone(&Utc).date_naive())
+            else {
+                continue;
+            };
+            let days = (today - since).num_days();
+            if days >= config.blocked_days {
+                reminders.push(Reminder {
+                    kind: NOTIFICATION_KIND_BLOCKED_TOO_LONG,
+                    project_id: id.clone(),
+                    person_id: None,
+                    dedupe_key: format!(
+                        "{}:{}:{}",
+                        NOTIFICATION_KIND_BLOCKED_TOO_LONG, id, since_raw
+                    ),
+                    title: format!("Blocked: {}", name),
+                    message: format!(
+                        "Blocked since {} ({})",
+                        settings.format_date(since),
+                        day_count(days)
+                    ),
+                });
+            }
+        }
+    }
+    Ok(reminders)
+}
+
+/// One reminder per project flag; a new status period is a new reminder.
+fn collect_sla_reminders(conn: &Connection, today: NaiveDate) -> Result<Vec<Reminder>, AppError> {
+    let settings = load_format_settings(conn);
+    let rows: Vec<(String, String, String, String, String, String, i64)> = conn
+        .prepare(
+            "SELECT f.project_id, f.rule_id, f.since, p.name, p.current_status, r.name, r.max_days
+             FROM project_flags f
+             JOIN projects p ON p.id = f.project_id
+             JOIN sla_rules r ON r.id = f.rule_id
+             ORDER BY f.since, p.name",
+        )?
+        .query_map([], |r| {
+            Ok((
+                r.get(0)?,
+                r.get(1)?,
+                r.get(2)?,
+                r.get(3)?,
+                r.get(4)?,
+                r.get(5)?,
+                r.get(6)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut reminders = Vec::new();
+    for (project_id, rule_id, since_raw, name, status, rule_name, max_days) in rows {
+        let Some(since) = DateTime::parse_from_rfc3339(since_raw.trim())
+            .ok()
+            .map(|at| at.with_timezone(&Utc).date_naive())
+        else {
+            continue;
+        };
+        reminders.push(Reminder {
+            kind: NOTIFICATION_KIND_SLA_BREACHED,
+            dedupe_key: format!(
+                "{}:{}:{}:{}",
+                NOTIFICATION_KIND_SLA_BREACHED, rule_id, project_id, since_raw
+            ),
+            project_id,
+            person_id: None,
+            title: format!("{}: {}", rule_name, name),
+            message: format!(
+                "{} since {} ({}; limit {})",
+                status,
+                settings.format_date(since),
+                day_count((today - since).num_days()),
+                day_count(max_days)
+            ),
+        });
+    }
+    Ok(reminders)
+}
+
+/// Mentions that can be reminded of: active person, not the comment's author, project neither
+/// archived nor in the trash.
+const MENTION_REMINDER_FROM: &str = "FROM comment_mentions m
+     JOIN project_comments c ON c.id = m.comment_id
+     JOIN projects p ON p.id = m.project_id
+     JOIN persons mp ON mp.id = m.person_id
+     WHERE mp.is_active = 1 AND m.person_id <> COALESCE(c.person_id, '')
+       AND p.archived_at IS NULL AND p.deleted_at IS NULL";
+
+fn mention_dedupe_key(comment_id: &str, person_id: &str) -> String {
+    format!(
+        "{}:{}:{}",
+        NOTIFICATION_KIND_MENTIONED, comment_id, person_id
+    )
+}
+
+fn collect_mention_reminders(
+    conn: &Connection,
+    today: NaiveDate,
+) -> Result<Vec<Reminder>, AppError> {
+    let since = (today - chrono::Duration::days(MENTION_WINDOW_DAYS)).to_string();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT m.comment_id, m.person_id, m.project_id, p.name, mp.display_name,
+                (SELECT a.display_name FROM persons a WHERE a.id = c.person_id), c.content
+         {}
+           AND m.mentioned_at >= ?1
+         ORDER BY m.mentioned_at, m.comment_id, m.person_id",
+        MENTION_REMINDER_FROM
+    ))?;
+    let reminders = stmt
+        .query_map([&since], |r| {
+            let comment_id: String = r.get(0)?;
+            let person_id: String = r.get(1)?;
+            let project_name: String = r.get(3)?;
+            let person_name: String = r.get(4)?;
+            let author: Option<String> = r.get(5)?;
+            let content: String = r.get(6)?;
+            Ok(Reminder {
+                kind: NOTIFICATION_KIND_MENTIONED,
+                project_id: r.get(2)?,
+                dedupe_key: mention_dedupe_key(&comment_id, &person_id),
+                person_id: Some(person_id),
+                title: format!("@{} in {}", person_name, project_name),
+                message: format!(
+                    "{}: {}",
+                    author.as_deref().unwrap_or("Someone"),
+                    excerpt(&content)
+                ),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(reminders)
+}
+
+/// Dedupe keys of every mention that still qualifies, regardless of age.
+fn current_mention_keys(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT m.comment_id, m.person_id {}",
+        MENTION_REMINDER_FROM
+    ))?;
+    let keys = stmt
+        .query_map([], |r| {
+            Ok(mention_dedupe_key(
+                &r.get::<_, String>(0)?,
+                &r.get::<_, String>(1)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(keys)
+}
+
+/// Comment text on one line, cut to `MENTION_EXCERPT_CHARS`.
+fn excerpt(content: &str) -> String {
+    let text = rich_text_to_markdown(content)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.chars().count() <= MENTION_EXCERPT_CHARS {
+        return text;
+    }
+    let mut cut: String = text.chars().take(MENTION_EXCERPT_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+fn day_count(days: i64) -> String {
+    if days == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{} days", days)
+    }
+}
+
+fn load_notification(conn: &Connection, id: i64) -> Result<NotificationDto, AppError> {
+    Ok(conn.query_row(
+        "SELECT id, kind, project_id, title, message, created_at, dismissed_at, person_id FROM notifications WHERE id = ?1",
+        [id],
+        map_notification,
+    )?)
+}
+
+fn map_notification(r: &rusqlite::Row<'_>) -> rusqlite::Result<NotificationDto> {
+    Ok(NotificationDto {
+        id: r.get(0)?,
+        kind: r.get(1)?,
+        project_id: r.get(2)?,
+        title: r.get(3)?,
+        message: r.get(4)?,
+        created_at: r.get(5)?,
+        dismissed_at: r.get(6)?,
+        person_id: r.get(7)?,
+    })
+}
+
+fn bool_str(value: bool) -> &'static str {
+    if value {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+fn load_config_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+}
+
+fn set_config_value(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )?;
+    Ok(())
+}
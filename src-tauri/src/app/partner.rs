@@ -1,8 +1,11 @@
 //! Partner use cases.
 
+use crate::app::audit::{audit_capture, audit_record, AuditEntity};
+use crate::app::currency::normalize_currency;
+use crate::app::person::{name_similarity, normalize_name};
 use crate::error::AppError;
-use crate::infra::get_connection;
 use crate::infra::DbPool;
+use crate::infra::{get_connection, get_read_connection};
 use chrono::Utc;
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
@@ -15,7 +18,7 @@ pub struct PartnerCreateReq {
     pub note: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PartnerDto {
     pub id: String,
     pub name: String,
@@ -23,6 +26,8 @@ pub struct PartnerDto {
     pub is_active: bool,
     pub created_at: String,
     pub updated_at: String,
+    /// ISO 4217 code used for project budgets without their own currency (`None` = base currency).
+    pub default_currency: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,6 +36,20 @@ pub struct PartnerUpdateReq {
     pub id: String,
     pub name: Option<String>,
     pub note: Option<String>,
+    /// Recorded in the audit log.
+    #[serde(default)]
+    pub changed_by_person_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerSetCurrencyReq {
+    pub partner_id: String,
+    /// ISO 4217 code; `None` clears it (budgets fall back to the base currency).
+    pub default_currency: Option<String>,
+    /// Recorded in the audit log.
+    #[serde(default)]
+    pub changed_by_person_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -64,15 +83,16 @@ pub fn partner_create(pool: &DbPool, req: PartnerCreateReq) -> Result<PartnerDto
         is_active: true,
         created_at: now.clone(),
         updated_at: now,
+        default_currency: None,
     })
 }
 
 pub fn partner_list(pool: &DbPool, only_active: bool) -> Result<Vec<PartnerDto>, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     let sql = if only_active {
-        "SELECT id, name, note, is_active, created_at, updated_at FROM partners WHERE is_active = 1 ORDER BY name COLLATE NOCASE"
+        "SELECT id, name, note, is_active, created_at, updated_at, default_currency FROM partners WHERE is_active = 1 AND deleted_at IS NULL ORDER BY name COLLATE NOCASE"
     } else {
-        "SELECT id, name, note, is_active, created_at, updated_at FROM partners ORDER BY name COLLATE NOCASE"
+        "SELECT id, name, note, is_active, created_at, updated_at, default_currency FROM partners WHERE deleted_at IS NULL ORDER BY name COLLATE NOCASE"
     };
     let mut stmt = conn.prepare(sql).map_err(|e| AppError::Db(e.to_string()))?;
     let rows = stmt.query_map([], |row| {
@@ -83,6 +103,7 @@ pub fn partner_list(pool: &DbPool, only_active: bool) -> Result<Vec<PartnerDto>,
             is_active: row.get::<_, i32>(3)? != 0,
             created_at: row.get(4)?,
             updated_at: row.get(5)?,
+            default_currency: row.get(6)?,
         })
     })?;
     let mut out = Vec::new();
@@ -93,9 +114,9 @@ pub fn partner_list(pool: &DbPool, only_active: bool) -> Result<Vec<PartnerDto>,
 }
 
 pub fn partner_get(pool: &DbPool, id: &str) -> Result<PartnerDto, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     conn.query_row(
-        "SELECT id, name, note, is_active, created_at, updated_at FROM partners WHERE id = ?1",
+        "SELECT id, name, note, is_active, created_at, updated_at, default_currency FROM partners WHERE id = ?1 AND deleted_at IS NULL",
         [id],
         |row| {
             Ok(PartnerDto {
@@ -105,6 +126,7 @@ pub fn partner_get(pool: &DbPool, id: &str) -> Result<PartnerDto, AppError> {
                 is_active: row.get::<_, i32>(3)? != 0,
                 created_at: row.get(4)?,
                 updated_at: row.get(5)?,
+                default_currency: row.get(6)?,
             })
         },
     )
@@ -116,10 +138,12 @@ pub fn partner_update(pool: &DbPool, req: PartnerUpdateReq) -> Result<PartnerDto
 
     {
         let conn = get_connection(pool);
+        let tx = conn.unchecked_transaction()?;
+        let audit_before = audit_capture(&tx, AuditEntity::Partner, &req.id)?;
 
-        let (name, note): (String, String) = conn
+        let (name, note): (String, String) = tx
             .query_row(
-                "SELECT name, note FROM partners WHERE id = ?1",
+                "SELECT name, note FROM partners WHERE id = ?1 AND deleted_at IS NULL",
                 [&req.id],
                 |r| Ok((r.get(0)?, r.get(1)?)),
             )
@@ -137,16 +161,68 @@ pub fn partner_update(pool: &DbPool, req: PartnerUpdateReq) -> Result<PartnerDto
             return Err(AppError::Validation("name is required".into()));
         }
 
-        conn.execute(
+        tx.execute(
             "UPDATE partners SET name = ?1, note = ?2, updated_at = ?3 WHERE id = ?4",
             params![&name, &note, &now, &req.id],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
+        audit_record(
+            &tx,
+            AuditEntity::Partner,
+            &req.id,
+            audit_before,
+            req.changed_by_person_id.as_deref(),
+            &now,
+        )?;
+        tx.commit()?;
     } // release conn before calling partner_get to avoid deadlock
 
     partner_get(pool, &req.id)
 }
 
+/// Set (or clear) the default budget currency of a partner.
+pub fn partner_set_default_currency(
+    pool: &DbPool,
+    req: PartnerSetCurrencyReq,
+) -> Result<PartnerDto, AppError> {
+    let currency = req
+        .default_currency
+        .as_deref()
+        .map(normalize_currency)
+        .transpose()?;
+    let now = Utc::now().to_rfc3339();
+
+    {
+        let conn = get_connection(pool);
+        let tx = conn.unchecked_transaction()?;
+        let current: Option<String> = tx
+            .query_row(
+                "SELECT default_currency FROM partners WHERE id = ?1 AND deleted_at IS NULL",
+                [&req.partner_id],
+                |r| r.get(0),
+            )
+            .map_err(|_| AppError::NotFound(format!("partner {}", req.partner_id)))?;
+        if current != currency {
+            let audit_before = audit_capture(&tx, AuditEntity::Partner, &req.partner_id)?;
+            tx.execute(
+                "UPDATE partners SET default_currency = ?1, updated_at = ?2 WHERE id = ?3",
+                params![&currency, &now, &req.partner_id],
+            )?;
+            audit_record(
+                &tx,
+                AuditEntity::Partner,
+                &req.partner_id,
+                audit_before,
+                req.changed_by_person_id.as_deref(),
+                &now,
+            )?;
+        }
+        tx.commit()?;
+    } // release conn before calling partner_get to avoid deadlock
+
+    partner_get(pool, &req.partner_id)
+}
+
 pub fn partner_deactivate(pool: &DbPool, id: &str) -> Result<PartnerDto, AppError> {
     let now = Utc::now().to_rfc3339();
     {
@@ -164,10 +240,10 @@ pub fn partner_projects(
     pool: &DbPool,
     partner_id: &str,
 ) -> Result<Vec<PartnerProjectItemDto>, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     let mut stmt = conn
         .prepare(
-            "SELECT id, name, current_status, updated_at FROM projects WHERE partner_id = ?1 ORDER BY updated_at DESC",
+            "SELECT id, name, current_status, updated_at FROM projects WHERE partner_id = ?1 AND deleted_at IS NULL ORDER BY updated_at DESC",
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
     let rows = stmt.query_map([partner_id], |r| {
@@ -184,3 +260,191 @@ pub fn partner_projects(
     }
     Ok(out)
 }
+
+/// Minimum name similarity (0..1) reported by `partner_find_duplicates` by default.
+pub const PARTNER_DUPLICATE_THRESHOLD: f64 = 0.8;
+
+/// Legal-form words ignored when comparing partner names.
+const PARTNER_NAME_SUFFIXES: &[&str] = &[
+    "co",
+    "company",
+    "corp",
+    "corporation",
+    "gmbh",
+    "inc",
+    "llc",
+    "limited",
+    "ltd",
+    "plc",
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerFindDuplicatesReq {
+    /// 0..1, default `PARTNER_DUPLICATE_THRESHOLD`.
+    pub threshold: Option<f64>,
+    /// Also compare deactivated partners (default false).
+    pub include_inactive: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerDuplicateDto {
+    /// Suggested target: the earlier created partner.
+    pub primary: PartnerDto,
+    pub duplicate: PartnerDto,
+    /// 1.0 for the same normalized name, otherwise the name similarity.
+    pub score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerMergeReq {
+    /// Partner that receives the projects.
+    pub target_id: String,
+    /// Partner whose projects are moved; deactivated afterwards.
+    pub source_id: String,
+    /// Recorded in the audit log.
+    #[serde(default)]
+    pub changed_by_person_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerMergeResult {
+    pub target: PartnerDto,
+    pub source_id: String,
+    pub projects_moved: usize,
+}
+
+/// Pairs of partners whose names match after normalization (case, punctuation, word order,
+/// legal-form words such as "Inc" / "Ltd") or are within the similarity threshold.
+/// Sorted by score, highest first.
+pub fn partner_find_duplicates(
+    pool: &DbPool,
+    req: PartnerFindDuplicatesReq,
+) -> Result<Vec<PartnerDuplicateDto>, AppError> {
+    let threshold = req.threshold.unwrap_or(PARTNER_DUPLICATE_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(AppError::Validation(format!(
+            "threshold must be between 0 and 1: {}",
+            threshold
+        )));
+    }
+    let mut partners = partner_list(pool, !req.include_inactive.unwrap_or(false))?;
+    partners.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    let names: Vec<String> = partners
+        .iter()
+        .map(|p| {
+            normalize_name(&p.name)
+                .split(' ')
+                .filter(|w| !PARTNER_NAME_SUFFIXES.contains(w))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+
+    let mut pairs = Vec::new();
+    for i in 0..partners.len() {
+        for j in i + 1..partners.len() {
+            let score = if !names[i].is_empty() && names[i] == names[j] {
+                1.0
+            } else {
+                name_similarity(&names[i], &names[j])
+            };
+            if score >= threshold && score > 0.0 {
+                pairs.push((i, j, score));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(&b.0)).then(a.1.cmp(&b.1)));
+    Ok(pairs
+        .into_iter()
+        .map(|(i, j, score)| PartnerDuplicateDto {
+            primary: partners[i].clone(),
+            duplicate: partners[j].clone(),
+            score: (score * 1000.0).round() / 1000.0,
+        })
+        .collect())
+}
+
+/// Move every project (and template / report schedule) of `source_id` to `target_id` in one
+/// transaction, then deactivate the source. This is the only way a project's partner changes.
+/// The target keeps its fields; an empty note or default currency is filled from the source.
+/// Moved budgets that relied on the source's default currency keep it explicitly.
+pub fn partner_merge(pool: &DbPool, req: PartnerMergeReq) -> Result<PartnerMergeResult, AppError> {
+    if req.target_id == req.source_id {
+        return Err(AppError::Validation(
+            "cannot merge a partner into itself".into(),
+        ));
+    }
+    partner_get(pool, &req.target_id)?;
+    partner_get(pool, &req.source_id)?;
+    let now = Utc::now().to_rfc3339();
+    let changed_by = req.changed_by_person_id.as_deref();
+    let (target, source) = (req.target_id.as_str(), req.source_id.as_str());
+
+    let projects_moved = {
+        let conn = get_connection(pool);
+        let tx = conn.unchecked_transaction()?;
+
+        let projects: Vec<String> = tx
+            .prepare("SELECT id FROM projects WHERE partner_id = ?1")?
+            .query_map([source], |r| r.get(0))?
+            .collect::<Result<_, _>>()?;
+        for project_id in &projects {
+            let before = audit_capture(&tx, AuditEntity::Project, project_id)?;
+            tx.execute(
+                "UPDATE projects SET partner_id = ?1, updated_at = ?2, _version = _version + 1,
+                     budget_currency = COALESCE(budget_currency, (SELECT default_currency FROM partners WHERE id = ?4))
+                 WHERE id = ?3",
+                params![target, &now, project_id, source],
+            )?;
+            audit_record(
+                &tx,
+                AuditEntity::Project,
+                project_id,
+                before,
+                changed_by,
+                &now,
+            )?;
+        }
+        tx.execute(
+            "UPDATE project_templates SET partner_id = ?1 WHERE partner_id = ?2",
+            params![target, source],
+        )?;
+        tx.execute(
+            "UPDATE report_schedules SET partner_id = ?1 WHERE partner_id = ?2",
+            params![target, source],
+        )?;
+
+        let before = audit_capture(&tx, AuditEntity::Partner, target)?;
+        tx.execute(
+            "UPDATE partners SET note = (SELECT note FROM partners WHERE id = ?2), updated_at = ?3
+             WHERE id = ?1 AND note = '' AND (SELECT note FROM partners WHERE id = ?2) <> ''",
+            params![target, source, &now],
+        )?;
+        tx.execute(
+            "UPDATE partners SET default_currency = (SELECT default_currency FROM partners WHERE id = ?2), updated_at = ?3
+             WHERE id = ?1 AND default_currency IS NULL
+               AND (SELECT default_currency FROM partners WHERE id = ?2) IS NOT NULL",
+            params![target, source, &now],
+        )?;
+        audit_record(&tx, AuditEntity::Partner, target, before, changed_by, &now)?;
+
+        let before = audit_capture(&tx, AuditEntity::Partner, source)?;
+        tx.execute(
+            "UPDATE partners SET is_active = 0, updated_at = ?1 WHERE id = ?2 AND is_active = 1",
+            params![&now, source],
+        )?;
+        audit_record(&tx, AuditEntity::Partner, source, before, changed_by, &now)?;
+        tx.commit()?;
+        projects.len()
+    }; // release conn before calling partner_get to avoid deadlock
+
+    Ok(PartnerMergeResult {
+        target: partner_get(pool, target)?,
+        source_id: req.source_id.clone(),
+        projects_moved,
+    })
+}
@@ -1,8 +1,10 @@
 //! Person use cases.
 
+use crate::app::audit::{audit_capture, audit_record, AuditEntity};
+use crate::app::comment::replace_mentions;
 use crate::error::AppError;
-use crate::infra::get_connection;
 use crate::infra::DbPool;
+use crate::infra::{get_connection, get_read_connection};
 use chrono::Utc;
 use rusqlite::params;
 use serde::{Deserialize, Serialize};
@@ -17,7 +19,7 @@ pub struct PersonCreateReq {
     pub note: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PersonDto {
     pub id: String,
     pub display_name: String,
@@ -37,6 +39,9 @@ pub struct PersonUpdateReq {
     pub email: Option<String>,
     pub role: Option<String>,
     pub note: Option<String>,
+    /// Recorded in the audit log.
+    #[serde(default)]
+    pub changed_by_person_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -79,11 +84,11 @@ pub fn person_create(pool: &DbPool, req: PersonCreateReq) -> Result<PersonDto, A
 }
 
 pub fn person_list(pool: &DbPool, only_active: bool) -> Result<Vec<PersonDto>, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     let sql = if only_active {
-        "SELECT id, display_name, email, role, note, is_active, created_at, updated_at FROM persons WHERE is_active = 1 ORDER BY display_name COLLATE NOCASE"
+        "SELECT id, display_name, email, role, note, is_active, created_at, updated_at FROM persons WHERE is_active = 1 AND deleted_at IS NULL ORDER BY display_name COLLATE NOCASE"
     } else {
-        "SELECT id, display_name, email, role, note, is_active, created_at, updated_at FROM persons ORDER BY display_name COLLATE NOCASE"
+        "SELECT id, display_name, email, role, note, is_active, created_at, updated_at FROM persons WHERE deleted_at IS NULL ORDER BY display_name COLLATE NOCASE"
     };
     let mut stmt = conn.prepare(sql).map_err(|e| AppError::Db(e.to_string()))?;
     let rows = stmt.query_map([], |row| {
@@ -106,9 +111,9 @@ pub fn person_list(pool: &DbPool, only_active: bool) -> Result<Vec<PersonDto>, A
 }
 
 pub fn person_get(pool: &DbPool, id: &str) -> Result<PersonDto, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     conn.query_row(
-        "SELECT id, display_name, email, role, note, is_active, created_at, updated_at FROM persons WHERE id = ?1",
+        "SELECT id, display_name, email, role, note, is_active, created_at, updated_at FROM persons WHERE id = ?1 AND deleted_at IS NULL",
         [id],
         |row| {
             Ok(PersonDto {
@@ -131,10 +136,12 @@ pub fn person_update(pool: &DbPool, req: PersonUpdateReq) -> Result<PersonDto, A
 
     {
         let conn = get_connection(pool);
+        let tx = conn.unchecked_transaction()?;
+        let audit_before = audit_capture(&tx, AuditEntity::Person, &req.id)?;
 
-        let (display_name, email, role, note): (String, String, String, String) = conn
+        let (display_name, email, role, note): (String, String, String, String) = tx
             .query_row(
-                "SELECT display_name, email, role, note FROM persons WHERE id = ?1",
+                "SELECT display_name, email, role, note FROM persons WHERE id = ?1 AND deleted_at IS NULL",
                 [&req.id],
                 |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
             )
@@ -154,11 +161,20 @@ pub fn person_update(pool: &DbPool, req: PersonUpdateReq) -> Result<PersonDto, A
             return Err(AppError::Validation("display_name is required".into()));
         }
 
-        conn.execute(
+        tx.execute(
             "UPDATE persons SET display_name = ?1, email = ?2, role = ?3, note = ?4, updated_at = ?5 WHERE id = ?6",
             params![&display_name, &email, &role, &note, &now, &req.id],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
+        audit_record(
+            &tx,
+            AuditEntity::Person,
+            &req.id,
+            audit_before,
+            req.changed_by_person_id.as_deref(),
+            &now,
+        )?;
+        tx.commit()?;
     } // release conn before calling person_get to avoid deadlock
 
     person_get(pool, &req.id)
@@ -181,13 +197,14 @@ pub fn person_current_projects(
     pool: &DbPool,
     person_id: &str,
 ) -> Result<Vec<PersonProjectItemDto>, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     let mut stmt = conn
         .prepare(
             "SELECT p.id, p.name, p.current_status, p.updated_at
              FROM assignments a
              JOIN projects p ON p.id = a.project_id
              WHERE a.person_id = ?1 AND a.end_at IS NULL AND p.current_status <> 'ARCHIVED'
+               AND p.deleted_at IS NULL
              ORDER BY p.updated_at DESC",
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -211,14 +228,14 @@ pub fn person_all_projects(
     pool: &DbPool,
     person_id: &str,
 ) -> Result<Vec<PersonProjectItemDto>, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
     let mut stmt = conn
         .prepare(
             "SELECT p.id, p.name, p.current_status, p.updated_at,
                     MAX(COALESCE(a.end_at, a.start_at)) AS last_involved_at
              FROM assignments a
              JOIN projects p ON p.id = a.project_id
-             WHERE a.person_id = ?1
+             WHERE a.person_id = ?1 AND p.deleted_at IS NULL
              GROUP BY p.id
              ORDER BY last_involved_at DESC",
         )
@@ -238,3 +255,306 @@ pub fn person_all_projects(
     }
     Ok(out)
 }
+
+/// Minimum name similarity (0..1) reported by `person_find_duplicates` by default.
+pub const PERSON_DUPLICATE_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonFindDuplicatesReq {
+    /// 0..1, default `PERSON_DUPLICATE_THRESHOLD`.
+    pub threshold: Option<f64>,
+    /// Also compare deactivated persons (default false).
+    pub include_inactive: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonDuplicateDto {
+    /// Suggested survivor: the earlier created person.
+    pub primary: PersonDto,
+    pub duplicate: PersonDto,
+    /// 1.0 for the same email, otherwise the name similarity.
+    pub score: f64,
+    /// `"email"`, `"name"` (same after normalization) and / or `"similar_name"`.
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonMergeReq {
+    pub primary_id: String,
+    pub duplicate_id: String,
+    /// Recorded in the audit log.
+    #[serde(default)]
+    pub changed_by_person_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonMergeResult {
+    pub primary: PersonDto,
+    pub duplicate_id: String,
+    pub projects_owned: usize,
+    pub assignments: usize,
+    /// Duplicate's active assignments ended because the primary was already active on the project.
+    pub assignments_ended: usize,
+    pub comments: usize,
+    /// Reactions moved to the primary; ones the primary already made are dropped.
+    pub comment_reactions: usize,
+    /// Comments whose @mentions of the duplicate now point at the primary.
+    pub comment_mentions: usize,
+    /// Earlier comment versions authored by the duplicate.
+    pub comment_revisions: usize,
+    pub status_history: usize,
+    pub size_history: usize,
+}
+
+/// Pairs of persons that look like the same human: same email (case-insensitive), or display
+/// names that match after normalization (case, punctuation, word order) or are within the
+/// similarity threshold. Sorted by score, highest first.
+pub fn person_find_duplicates(
+    pool: &DbPool,
+    req: PersonFindDuplicatesReq,
+) -> Result<Vec<PersonDuplicateDto>, AppError> {
+    let threshold = req.threshold.unwrap_or(PERSON_DUPLICATE_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(AppError::Validation(format!(
+            "threshold must be between 0 and 1: {}",
+            threshold
+        )));
+    }
+    let mut persons = person_list(pool, !req.include_inactive.unwrap_or(false))?;
+    persons.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    let names: Vec<String> = persons
+        .iter()
+        .map(|p| normalize_name(&p.display_name))
+        .collect();
+    let emails: Vec<String> = persons
+        .iter()
+        .map(|p| p.email.trim().to_lowercase())
+        .collect();
+
+    let mut pairs = Vec::new();
+    for i in 0..persons.len() {
+        for j in i + 1..persons.len() {
+            let mut reasons = Vec::new();
+            let mut score = 0.0_f64;
+            if !emails[i].is_empty() && emails[i] == emails[j] {
+                reasons.push("email".to_string());
+                score = 1.0;
+            }
+            if !names[i].is_empty() && names[i] == names[j] {
+                reasons.push("name".to_string());
+                score = 1.0;
+            } else {
+                let similarity = name_similarity(&names[i], &names[j]);
+                if similarity >= threshold {
+                    reasons.push("similar_name".to_string());
+                    score = score.max(similarity);
+                }
+            }
+            if !reasons.is_empty() {
+                pairs.push((i, j, score, reasons));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.0.cmp(&b.0)).then(a.1.cmp(&b.1)));
+
+    Ok(pairs
+        .into_iter()
+        .map(|(i, j, score, reasons)| PersonDuplicateDto {
+            primary: persons[i].clone(),
+            duplicate: persons[j].clone(),
+            score: (score * 1000.0).round() / 1000.0,
+            reasons,
+        })
+        .collect())
+}
+
+/// Lowercase alphanumeric words, sorted, so "Wei, Li" and "li wei" compare equal.
+pub(crate) fn normalize_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_lowercase().next().unwrap_or(c)
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    words.sort_unstable();
+    words.join(" ")
+}
+
+/// 1 − Levenshtein distance / longer length, over characters.
+pub(crate) fn name_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    1.0 - prev[b.len()] as f64 / longest as f64
+}
+
+/// Move every reference from `duplicate_id` to `primary_id` in one transaction, then deactivate
+/// the duplicate. The primary keeps its fields; an empty email / role / note is filled from the
+/// duplicate. When both were active on the same project, the duplicate's assignment is ended.
+pub fn person_merge(pool: &DbPool, req: PersonMergeReq) -> Result<PersonMergeResult, AppError> {
+    if req.primary_id == req.duplicate_id {
+        return Err(AppError::Validation(
+            "cannot merge a person into itself".into(),
+        ));
+    }
+    let now = Utc::now().to_rfc3339();
+    let changed_by = req.changed_by_person_id.as_deref();
+
+    let mut result = PersonMergeResult {
+        primary: person_get(pool, &req.primary_id)?,
+        duplicate_id: req.duplicate_id.clone(),
+        projects_owned: 0,
+        assignments: 0,
+        assignments_ended: 0,
+        comments: 0,
+        comment_reactions: 0,
+        comment_mentions: 0,
+        comment_revisions: 0,
+        status_history: 0,
+        size_history: 0,
+    };
+    person_get(pool, &req.duplicate_id)?;
+
+    {
+        let conn = get_connection(pool);
+        let tx = conn.unchecked_transaction()?;
+        let (primary, duplicate) = (req.primary_id.as_str(), req.duplicate_id.as_str());
+
+        let owned: Vec<String> = tx
+            .prepare("SELECT id FROM projects WHERE owner_person_id = ?1")?
+            .query_map([duplicate], |r| r.get(0))?
+            .collect::<Result<_, _>>()?;
+        for project_id in &owned {
+            let before = audit_capture(&tx, AuditEntity::Project, project_id)?;
+            tx.execute(
+                "UPDATE projects SET owner_person_id = ?1, updated_at = ?2, _version = _version + 1 WHERE id = ?3",
+                params![primary, &now, project_id],
+            )?;
+            audit_record(
+                &tx,
+                AuditEntity::Project,
+                project_id,
+                before,
+                changed_by,
+                &now,
+            )?;
+        }
+        result.projects_owned = owned.len();
+
+        result.assignments_ended = tx.execute(
+            "UPDATE assignments SET end_at = ?1, _version = _version + 1
+             WHERE person_id = ?2 AND end_at IS NULL
+               AND project_id IN (SELECT project_id FROM assignments WHERE person_id = ?3 AND end_at IS NULL)",
+            params![&now, duplicate, primary],
+        )?;
+        result.assignments = tx.execute(
+            "UPDATE assignments SET person_id = ?1, _version = _version + 1 WHERE person_id = ?2",
+            params![primary, duplicate],
+        )?;
+        result.comments = tx.execute(
+            "UPDATE project_comments SET person_id = ?1, _version = _version + 1 WHERE person_id = ?2",
+            params![primary, duplicate],
+        )?;
+        tx.execute(
+            "DELETE FROM comment_reactions
+             WHERE person_id = ?2
+               AND EXISTS (SELECT 1 FROM comment_reactions r
+                           WHERE r.comment_id = comment_reactions.comment_id
+                             AND r.person_id = ?1 AND r.emoji = comment_reactions.emoji)",
+            params![primary, duplicate],
+        )?;
+        result.comment_reactions = tx.execute(
+            "UPDATE comment_reactions SET person_id = ?1, _version = _version + 1 WHERE person_id = ?2",
+            params![primary, duplicate],
+        )?;
+        result.comment_revisions = tx.execute(
+            "UPDATE comment_revisions SET person_id = ?1, _version = _version + 1 WHERE person_id = ?2",
+            params![primary, duplicate],
+        )?;
+        let mentioning: Vec<(String, String)> = tx
+            .prepare(
+                "SELECT id, content FROM project_comments
+                 WHERE id IN (SELECT comment_id FROM comment_mentions WHERE person_id = ?1)",
+            )?
+            .query_map([duplicate], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        for (comment_id, content) in mentioning {
+            let Some(content) =
+                replace_mentions(&content, duplicate, primary, &result.primary.display_name)
+            else {
+                continue;
+            };
+            tx.execute(
+                "UPDATE project_comments SET content = ?1, _version = _version + 1 WHERE id = ?2",
+                params![&content, &comment_id],
+            )?;
+            result.comment_mentions += 1;
+        }
+        result.status_history = tx.execute(
+            "UPDATE status_history SET changed_by_person_id = ?1, _version = _version + 1 WHERE changed_by_person_id = ?2",
+            params![primary, duplicate],
+        )?;
+        result.size_history = tx.execute(
+            "UPDATE project_size_history SET changed_by_person_id = ?1, _version = _version + 1 WHERE changed_by_person_id = ?2",
+            params![primary, duplicate],
+        )?;
+        tx.execute(
+            "UPDATE project_templates SET owner_person_id = ?1 WHERE owner_person_id = ?2",
+            params![primary, duplicate],
+        )?;
+
+        let before = audit_capture(&tx, AuditEntity::Person, primary)?;
+        tx.execute(
+            "UPDATE persons SET
+                 email = CASE WHEN email = '' THEN (SELECT email FROM persons WHERE id = ?2) ELSE email END,
+                 role = CASE WHEN role = '' THEN (SELECT role FROM persons WHERE id = ?2) ELSE role END,
+                 note = CASE WHEN note = '' THEN (SELECT note FROM persons WHERE id = ?2) ELSE note END,
+                 updated_at = ?3
+             WHERE id = ?1
+               AND ((email = '' AND (SELECT email FROM persons WHERE id = ?2) <> '')
+                 OR (role = '' AND (SELECT role FROM persons WHERE id = ?2) <> '')
+                 OR (note = '' AND (SELECT note FROM persons WHERE id = ?2) <> ''))",
+            params![primary, duplicate, &now],
+        )?;
+        audit_record(&tx, AuditEntity::Person, primary, before, changed_by, &now)?;
+
+        let before = audit_capture(&tx, AuditEntity::Person, duplicate)?;
+        tx.execute(
+            "UPDATE persons SET is_active = 0, updated_at = ?1 WHERE id = ?2 AND is_active = 1",
+            params![&now, duplicate],
+        )?;
+        audit_record(
+            &tx,
+            AuditEntity::Person,
+            duplicate,
+            before,
+            changed_by,
+            &now,
+        )?;
+        tx.commit()?;
+    } // release conn before calling person_get to avoid deadlock
+
+    result.primary = person_get(pool, &req.primary_id)?;
+    Ok(result)
+}
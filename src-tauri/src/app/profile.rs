@@ -0,0 +1,232 @@
+//! Profile use cases: list, create and delete profiles under `<base>/profiles/`.
+//!
+//! - profile = `profiles/<name>/` 目录（或 `profiles/<name>.location.json` 指向的自定义目录）；
+//!   新建只创建目录，数据库在首次以该 profile 启动时初始化。
+//! - 名称规则与 `--profile` / `PROJEX_PROFILE` 相同（`normalize_profile_name`）。
+//! - 运行时切换（`cmd_profile_switch`）只允许切到已存在、未被其它实例占用的 profile。
+//! - 删除只移除 profile 自有文件（见 `cleanup_previous_data_dir`）；当前 profile、
+//!   被其它实例加锁的 profile 以及 `default` 不可删除。
+
+use crate::error::AppError;
+use crate::infra::layout::{
+    cleanup_previous_data_dir, location_file_for, profile_dir_in_use, read_data_dir_location,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+const LOCATION_SUFFIX: &str = ".location.json";
+
+/// Base data dir and the profile this process runs.
+pub struct ProfileContext<'a> {
+    pub base_dir: &'a Path,
+    pub current_profile: &'a str,
+    /// Data dir actually in use (may come from `--data-dir`).
+    pub current_data_dir: &'a Path,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileDto {
+    pub name: String,
+    pub data_dir: String,
+    /// Data dir moved away from `profiles/<name>/`.
+    pub is_custom_dir: bool,
+    pub is_current: bool,
+    /// Locked by a running instance (always true for the current profile).
+    pub in_use: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileCreateReq {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileDeleteReq {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSwitchReq {
+    pub name: String,
+}
+
+/// Trimmed, lowercased profile name; `None` when it is not a valid directory name.
+pub fn normalize_profile_name(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() || trimmed.len() > 64 || trimmed.starts_with('-') {
+        return None;
+    }
+
+    if trimmed == "." || trimmed == ".." {
+        return None;
+    }
+
+    if !trimmed
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+
+    Some(trimmed.to_ascii_lowercase())
+}
+
+/// `<base>/profiles/<name>`
+pub fn resolve_profile_data_dir(base_dir: &Path, profile_name: &str) -> PathBuf {
+    base_dir.join("profiles").join(profile_name)
+}
+
+/// All profiles, sorted by name; the current one is always included.
+pub fn profile_list(ctx: &ProfileContext<'_>) -> Result<Vec<ProfileDto>, AppError> {
+    let mut names = BTreeSet::new();
+    names.insert(ctx.current_profile.to_string());
+    let profiles_dir = ctx.base_dir.join("profiles");
+    if profiles_dir.is_dir() {
+        let entries = fs::read_dir(&profiles_dir)
+            .map_err(|e| AppError::DataDir(format!("Failed to read {:?}: {}", profiles_dir, e)))?;
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let is_dir = entry.path().is_dir();
+            let name = match file_name.strip_suffix(LOCATION_SUFFIX) {
+                Some(name) if !is_dir => name.to_string(),
+                _ if is_dir => file_name,
+                _ => continue,
+            };
+            // Skips staging dirs (`.default.migrating`) and anything not created by us.
+            if normalize_profile_name(&name).as_deref() == Some(name.as_str()) {
+                names.insert(name);
+            }
+        }
+    }
+    names.iter().map(|name| describe(ctx, name)).collect()
+}
+
+pub fn profile_current(ctx: &ProfileContext<'_>) -> Result<ProfileDto, AppError> {
+    describe(ctx, ctx.current_profile)
+}
+
+pub fn profile_create(
+    ctx: &ProfileContext<'_>,
+    req: ProfileCreateReq,
+) -> Result<ProfileDto, AppError> {
+    let name = validate_name(&req.name)?;
+    let dir = resolve_profile_data_dir(ctx.base_dir, &name);
+    if name == ctx.current_profile || dir.exists() || location_file_for(&dir).exists() {
+        return Err(AppError::Conflict(format!(
+            "profile already exists: {}",
+            name
+        )));
+    }
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::DataDir(format!("Failed to create {:?}: {}", dir, e)))?;
+    log::info!("Profile created: {}", name);
+    describe(ctx, &name)
+}
+
+/// Normalized name to switch to; `None` when it is already the current profile.
+/// The target must exist and must not be open in another window.
+pub fn profile_switch_target(
+    ctx: &ProfileContext<'_>,
+    req: &ProfileSwitchReq,
+) -> Result<Option<String>, AppError> {
+    let name = validate_name(&req.name)?;
+    if name == ctx.current_profile {
+        return Ok(None);
+    }
+    let default_dir = resolve_profile_data_dir(ctx.base_dir, &name);
+    let location_file = location_file_for(&default_dir);
+    if !default_dir.exists() && !location_file.exists() {
+        return Err(AppError::NotFound(format!("profile {}", name)));
+    }
+    if profile_dir_in_use(&data_dir_of(&default_dir, &location_file)?) {
+        return Err(AppError::Conflict(format!(
+            "profile {} is open in another window",
+            name
+        )));
+    }
+    Ok(Some(name))
+}
+
+/// Remove the profile data (database, logs, locks) and its location pointer.
+pub fn profile_delete(ctx: &ProfileContext<'_>, req: ProfileDeleteReq) -> Result<(), AppError> {
+    let name = validate_name(&req.name)?;
+    if name == ctx.current_profile {
+        return Err(AppError::Conflict(
+            "cannot delete the profile in use".into(),
+        ));
+    }
+    if name == DEFAULT_PROFILE {
+        return Err(AppError::Validation(
+            "the default profile cannot be deleted".into(),
+        ));
+    }
+    let default_dir = resolve_profile_data_dir(ctx.base_dir, &name);
+    let location_file = location_file_for(&default_dir);
+    if !default_dir.exists() && !location_file.exists() {
+        return Err(AppError::NotFound(format!("profile {}", name)));
+    }
+    let data_dir = data_dir_of(&default_dir, &location_file)?;
+    if profile_dir_in_use(&data_dir) {
+        return Err(AppError::Conflict(format!(
+            "profile {} is open in another window",
+            name
+        )));
+    }
+
+    for dir in [&data_dir, &default_dir] {
+        if !cleanup_previous_data_dir(dir).map_err(AppError::DataDir)? {
+            return Err(AppError::Conflict(format!(
+                "profile {} is open in another window",
+                name
+            )));
+        }
+    }
+    if location_file.exists() {
+        fs::remove_file(&location_file).map_err(|e| {
+            AppError::DataDir(format!("Failed to remove {:?}: {}", location_file, e))
+        })?;
+    }
+    log::info!("Profile deleted: {}", name);
+    Ok(())
+}
+
+fn describe(ctx: &ProfileContext<'_>, name: &str) -> Result<ProfileDto, AppError> {
+    let default_dir = resolve_profile_data_dir(ctx.base_dir, name);
+    let is_current = name == ctx.current_profile;
+    let data_dir = if is_current {
+        ctx.current_data_dir.to_path_buf()
+    } else {
+        data_dir_of(&default_dir, &location_file_for(&default_dir))?
+    };
+    Ok(ProfileDto {
+        name: name.to_string(),
+        is_custom_dir: data_dir != default_dir,
+        in_use: is_current || profile_dir_in_use(&data_dir),
+        data_dir: data_dir.display().to_string(),
+        is_current,
+    })
+}
+
+fn data_dir_of(default_dir: &Path, location_file: &Path) -> Result<PathBuf, AppError> {
+    let location = read_data_dir_location(location_file).map_err(AppError::DataDir)?;
+    Ok(location
+        .data_dir
+        .unwrap_or_else(|| default_dir.to_path_buf()))
+}
+
+fn validate_name(raw: &str) -> Result<String, AppError> {
+    normalize_profile_name(raw).ok_or_else(|| {
+        AppError::Validation(format!(
+            "invalid profile name '{}': use 1-64 letters, digits, '-' or '_'",
+            raw.trim()
+        ))
+    })
+}
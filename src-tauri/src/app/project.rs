@@ -1,11 +1,23 @@
 //! Project use cases: create, list, get, change_status.
 
-use crate::domain::{ProjectStatus, StatusMachine};
+use crate::app::action_log::{journal_capture, journal_record, ActionKind, JournalScope};
+use crate::app::assignment::{end_assignments_on_archive, restore_assignments_on_unarchive};
+use crate::app::audit::{audit_capture, audit_record, AuditEntity};
+use crate::app::custom_field::{
+    custom_field_filter_condition, project_custom_values, CustomFieldFilter, ProjectCustomValueDto,
+};
+use crate::app::integrity::{ensure_partner_usable, ensure_person_usable, load_strict_fk_enabled};
+use crate::app::project_size::{project_size_history, SizeHistoryDto};
+use crate::app::sla::clear_project_flags;
+use crate::app::status_automation::{is_overdue, load_overdue_enabled};
+use crate::app::webhook::{webhook_notify, WEBHOOK_EVENT_PROJECT_STATUS_CHANGED};
+use crate::app::workflow::{load_active_workflow, wip_limit_exceeded, wip_limit_note};
+use crate::domain::{country_by_code, country_display_name, ProjectStatus};
 use crate::error::AppError;
-use crate::infra::get_connection;
 use crate::infra::DbPool;
-use chrono::Utc;
-use rusqlite::params;
+use crate::infra::{get_connection, get_read_connection};
+use chrono::{Local, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -25,8 +37,22 @@ type ProjectRawRow = (
     String,         // created_at
     String,         // updated_at
     Option<String>, // archived_at
+    Option<String>, // parent_project_id
+    Option<i64>,    // size_points
+    Option<String>, // cloned_from_id
+    Option<f64>,    // estimated_hours
+    Option<f64>,    // budget_amount
+    Option<String>, // budget_currency
 );
 
+/// Guard for malformed data (e.g. a cycle created by two devices re-parenting concurrently).
+pub(crate) const MAX_PROJECT_DEPTH: usize = 64;
+
+/// Spacing between ranks after renumbering a column (and at its ends).
+const RANK_STEP: f64 = 1024.0;
+/// Below this gap a midpoint is no longer distinguishable enough; the column is renumbered.
+const MIN_RANK_GAP: f64 = 1e-9;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectCreateReq {
@@ -41,6 +67,8 @@ pub struct ProjectCreateReq {
     pub due_date: Option<String>,
     pub tags: Option<Vec<String>>,
     pub created_by_person_id: Option<String>,
+    /// Parent project (sub-project); none = top-level.
+    pub parent_project_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -51,6 +79,8 @@ pub struct ProjectDetailDto {
     pub priority: i32,
     pub current_status: String,
     pub country_code: String,
+    /// English name from the ISO 3166 catalog (the raw code if unknown).
+    pub country_name: String,
     pub partner_id: String,
     pub owner_person_id: String,
     pub product_name: Option<String>,
@@ -60,10 +90,26 @@ pub struct ProjectDetailDto {
     pub updated_at: String,
     pub archived_at: Option<String>,
     pub tags: Vec<String>,
+    pub parent_project_id: Option<String>,
+    /// Project this one was cloned from (may no longer exist).
+    pub cloned_from_id: Option<String>,
     pub owner_name: String,
     pub partner_name: String,
     pub assignments: Vec<AssignmentDto>,
     pub status_history: Vec<StatusHistoryDto>,
+    /// Size estimate in points (`None` = not estimated).
+    pub size_points: Option<i64>,
+    pub size_history: Vec<SizeHistoryDto>,
+    /// Planned effort in hours (`None` = not estimated).
+    pub estimated_hours: Option<f64>,
+    /// Planned spend (`None` = no budget).
+    pub budget_amount: Option<f64>,
+    /// Currency of the budget as stored (`None` = the partner's default currency).
+    pub budget_currency: Option<String>,
+    /// Every defined custom field with this project's value (`None` = not set).
+    pub custom_fields: Vec<ProjectCustomValueDto>,
+    /// Past its due date and not done (always false while the overdue flag is off).
+    pub overdue: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -90,7 +136,7 @@ pub struct StatusHistoryDto {
     pub note: String,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectListReq {
     pub only_unarchived: Option<bool>,
@@ -100,10 +146,22 @@ pub struct ProjectListReq {
     pub owner_person_ids: Option<Vec<String>>,
     pub participant_person_ids: Option<Vec<String>>,
     pub tags: Option<Vec<String>>,
-    pub sort_by: Option<String>, // "updatedAt" | "priority" | "dueDate"
+    /// Direct children of this project; `""` = top-level projects only.
+    pub parent_project_id: Option<String>,
+    /// All must match (AND); values are compared in their normalized form.
+    pub custom_fields: Option<Vec<CustomFieldFilter>>,
+    /// true = only projects with an SLA flag, false = only projects without.
+    pub flagged: Option<bool>,
+    /// Flagged by any of these SLA rules.
+    pub flag_rule_ids: Option<Vec<String>>,
+    pub sort_by: Option<String>, // "updatedAt" | "priority" | "dueDate" | "rank"
     pub sort_order: Option<String>, // "asc" | "desc"
     pub limit: Option<i32>,
     pub offset: Option<i32>,
+    /// Keyset cursor (`nextAfterUpdatedAt` / `nextAfterId` of the previous page): rows after
+    /// it in `updatedAt` order. Both must be set; `offset` is ignored. `updatedAt` sort only.
+    pub after_updated_at: Option<String>,
+    pub after_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -113,19 +171,56 @@ pub struct ProjectListItemDto {
     pub current_status: String,
     pub priority: i32,
     pub country_code: String,
+    /// English name from the ISO 3166 catalog (the raw code if unknown).
+    pub country_name: String,
     pub partner_name: String,
     pub owner_name: String,
     pub due_date: Option<String>,
     pub updated_at: String,
     pub tags: Vec<String>,
+    pub parent_project_id: Option<String>,
+    /// Manual Kanban rank within the status column (`None` = never reordered).
+    pub rank: Option<f64>,
+    pub size_points: Option<i64>,
+    pub checklist_done: i64,
+    pub checklist_total: i64,
+    /// `checklist_done / checklist_total` (0.0..=1.0); `None` without checklist items.
+    pub checklist_ratio: Option<f64>,
+    /// Names of the SLA rules the project currently breaks.
+    pub flags: Vec<String>,
+    /// Past its due date and not done (always false while the overdue flag is off).
+    pub overdue: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReorderReq {
+    pub project_id: String,
+    /// The card directly above the new position; none = top of the column.
+    pub before_id: Option<String>,
+    /// The card directly below the new position; none = bottom of the column.
+    pub after_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectRankDto {
+    pub project_id: String,
+    pub current_status: String,
+    pub rank: f64,
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ProjectListPage {
     pub items: Vec<ProjectListItemDto>,
     pub total: i64,
     pub limit: i32,
+    /// Always 0 in cursor mode.
     pub offset: i32,
+    /// Cursor of the next page (`updatedAt` sort only); `None` = no more rows.
+    pub next_after_updated_at: Option<String>,
+    pub next_after_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -141,8 +236,13 @@ pub struct ProjectUpdateReq {
     pub start_date: Option<String>,
     pub due_date: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Empty string = make top-level.
+    pub parent_project_id: Option<String>,
     #[serde(default)]
     pub partner_id: Option<String>, // if present -> PARTNER_IMMUTABLE
+    /// Recorded in the audit log.
+    #[serde(default)]
+    pub changed_by_person_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -153,10 +253,8 @@ pub struct ProjectChangeStatusReq {
     pub note: Option<String>,
     pub changed_by_person_id: Option<String>,
     pub if_match_updated_at: Option<String>,
-}
-
-fn parse_status(s: &str) -> Option<ProjectStatus> {
-    s.parse::<ProjectStatus>().ok()
+    /// Change status even past a WIP limit (noted in the history).
+    pub force: Option<bool>,
 }
 
 fn ensure_project_name_unique(
@@ -186,14 +284,89 @@ fn ensure_project_name_unique(
     Ok(())
 }
 
+/// Uppercased ISO 3166-1 alpha-2 code; unknown codes are rejected.
+fn normalize_country_code(raw: &str) -> Result<String, AppError> {
+    let code = raw.trim().to_uppercase();
+    if code.is_empty() {
+        return Err(AppError::Validation("country_code is required".into()));
+    }
+    if country_by_code(&code).is_none() {
+        return Err(AppError::Validation(format!(
+            "COUNTRY_INVALID: unknown country code '{}'",
+            code
+        )));
+    }
+    Ok(code)
+}
+
+/// `parent_id` must exist (not in the trash) and must not be `project_id` itself or one of its descendants.
+fn ensure_valid_parent(
+    conn: &Connection,
+    project_id: Option<&str>,
+    parent_id: &str,
+) -> Result<(), AppError> {
+    let mut current = Some(parent_id.to_string());
+    let mut depth = 0;
+    while let Some(id) = current {
+        if Some(id.as_str()) == project_id {
+            return Err(AppError::Validation(
+                "PARENT_CYCLE: a project cannot be its own ancestor".into(),
+            ));
+        }
+        depth += 1;
+        if depth > MAX_PROJECT_DEPTH {
+            return Err(AppError::Validation(format!(
+                "PARENT_CYCLE: hierarchy deeper than {} levels",
+                MAX_PROJECT_DEPTH
+            )));
+        }
+        let row: Option<(Option<String>, bool)> = conn
+            .query_row(
+                "SELECT parent_project_id, deleted_at IS NOT NULL FROM projects WHERE id = ?1",
+                [&id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()?;
+        current = match row {
+            Some((_, true)) if id == parent_id => {
+                return Err(AppError::NotFound(format!("parent project {}", parent_id)))
+            }
+            Some((parent, _)) => parent,
+            // Only the requested parent must exist; a dangling ancestor ends the chain.
+            None if id == parent_id => {
+                return Err(AppError::NotFound(format!("parent project {}", parent_id)))
+            }
+            None => None,
+        };
+    }
+    Ok(())
+}
+
 pub fn project_create(pool: &DbPool, req: ProjectCreateReq) -> Result<ProjectDetailDto, AppError> {
+    let id = {
+        let conn = get_connection(pool);
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        let id = project_create_tx(&tx, req)?;
+        tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
+        id
+    }; // release conn before calling project_get to avoid deadlock
+
+    project_get(pool, &id)
+}
+
+/// Validate and insert a project (with owner assignment, initial history and tags) inside the
+/// caller's transaction. Returns the new project ID.
+pub(crate) fn project_create_tx(
+    tx: &rusqlite::Transaction<'_>,
+    req: ProjectCreateReq,
+) -> Result<String, AppError> {
     let name = req.name.trim();
     if name.is_empty() {
         return Err(AppError::Validation("name is required".into()));
     }
-    if req.country_code.trim().is_empty() {
-        return Err(AppError::Validation("country_code is required".into()));
-    }
+    let country_code = normalize_country_code(&req.country_code)?;
     if req.partner_id.trim().is_empty() {
         return Err(AppError::Validation("partner_id is required".into()));
     }
@@ -207,7 +380,6 @@ pub fn project_create(pool: &DbPool, req: ProjectCreateReq) -> Result<ProjectDet
     let priority = req.priority.unwrap_or(3).clamp(1, 5);
     let partner_id = req.partner_id.trim().to_string();
     let owner_person_id = req.owner_person_id.trim().to_string();
-    let country_code = req.country_code.trim().to_uppercase();
     let product_name = req
         .product_name
         .as_deref()
@@ -217,70 +389,75 @@ pub fn project_create(pool: &DbPool, req: ProjectCreateReq) -> Result<ProjectDet
     let due_date = req.due_date.filter(|s| !s.trim().is_empty());
     let tags = req.tags.unwrap_or_default();
     let created_by = req.created_by_person_id.filter(|s| !s.trim().is_empty());
+    let parent_project_id = req
+        .parent_project_id
+        .as_deref()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
 
-    {
-        let conn = get_connection(pool);
-        let tx = conn
-            .unchecked_transaction()
-            .map_err(|e| AppError::Db(e.to_string()))?;
-
-        ensure_project_name_unique(&tx, name, None)?;
-
-        tx.execute(
-            "INSERT INTO projects (id, name, description, priority, current_status, country_code, partner_id, owner_person_id, product_name, start_date, due_date, created_at, updated_at, archived_at) VALUES (?1, ?2, ?3, ?4, 'BACKLOG', ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11, NULL)",
-            params![
-                id,
-                name,
-                desc,
-                priority,
-                country_code,
-                partner_id,
-                owner_person_id,
-                product_name,
-                start_date,
-                due_date,
-                &now
-            ],
-        )
-        .map_err(|e| AppError::Db(e.to_string()))?;
-
-        let assign_id = Uuid::new_v4().to_string();
-        tx.execute(
-            "INSERT INTO assignments (id, project_id, person_id, role, start_at, end_at, created_at) VALUES (?1, ?2, ?3, 'owner', ?4, NULL, ?4)",
-            params![assign_id, &id, &owner_person_id, &now],
-        )
-        .map_err(|e| AppError::Db(e.to_string()))?;
+    if load_strict_fk_enabled(tx) {
+        ensure_partner_usable(tx, &partner_id)?;
+        ensure_person_usable(tx, &owner_person_id)?;
+    }
 
-        let hist_id = Uuid::new_v4().to_string();
-        tx.execute(
-            "INSERT INTO status_history (id, project_id, from_status, to_status, changed_at, changed_by_person_id, note) VALUES (?1, ?2, NULL, 'BACKLOG', ?3, ?4, '')",
-            params![hist_id, &id, &now, created_by],
-        )
-        .map_err(|e| AppError::Db(e.to_string()))?;
+    ensure_project_name_unique(tx, name, None)?;
+    if let Some(parent) = parent_project_id.as_deref() {
+        ensure_valid_parent(tx, None, parent)?;
+    }
 
-        for tag in &tags {
-            let tag = tag.trim();
-            if !tag.is_empty() {
-                tx.execute(
-                    "INSERT INTO project_tags (project_id, tag, created_at) VALUES (?1, ?2, ?3)",
-                    params![&id, tag, &now],
-                )
-                .map_err(|e| AppError::Db(e.to_string()))?;
-            }
+    tx.execute(
+        "INSERT INTO projects (id, name, description, priority, current_status, country_code, partner_id, owner_person_id, product_name, start_date, due_date, created_at, updated_at, archived_at, parent_project_id) VALUES (?1, ?2, ?3, ?4, 'BACKLOG', ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11, NULL, ?12)",
+        params![
+            id,
+            name,
+            desc,
+            priority,
+            country_code,
+            partner_id,
+            owner_person_id,
+            product_name,
+            start_date,
+            due_date,
+            &now,
+            parent_project_id
+        ],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let assign_id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO assignments (id, project_id, person_id, role, start_at, end_at, created_at) VALUES (?1, ?2, ?3, 'owner', ?4, NULL, ?4)",
+        params![assign_id, &id, &owner_person_id, &now],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let hist_id = Uuid::new_v4().to_string();
+    tx.execute(
+        "INSERT INTO status_history (id, project_id, from_status, to_status, changed_at, changed_by_person_id, note) VALUES (?1, ?2, NULL, 'BACKLOG', ?3, ?4, '')",
+        params![hist_id, &id, &now, created_by],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    for tag in &tags {
+        let tag = tag.trim();
+        if !tag.is_empty() {
+            tx.execute(
+                "INSERT INTO project_tags (project_id, tag, created_at) VALUES (?1, ?2, ?3)",
+                params![&id, tag, &now],
+            )
+            .map_err(|e| AppError::Db(e.to_string()))?;
         }
+    }
 
-        tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
-    } // release conn before calling project_get to avoid deadlock
-
-    project_get(pool, &id)
+    Ok(id)
 }
 
 pub fn project_get(pool: &DbPool, project_id: &str) -> Result<ProjectDetailDto, AppError> {
-    let conn = get_connection(pool);
+    let conn = get_read_connection(pool);
 
     let proj: ProjectRawRow = conn
         .query_row(
-            "SELECT id, name, description, priority, current_status, country_code, partner_id, owner_person_id, product_name, start_date, due_date, created_at, updated_at, archived_at FROM projects WHERE id = ?1",
+            "SELECT id, name, description, priority, current_status, country_code, partner_id, owner_person_id, product_name, start_date, due_date, created_at, updated_at, archived_at, parent_project_id, size_points, cloned_from_id, estimated_hours, budget_amount, budget_currency FROM projects WHERE id = ?1 AND deleted_at IS NULL",
             [project_id],
             |r| {
                 Ok((
@@ -298,6 +475,12 @@ pub fn project_get(pool: &DbPool, project_id: &str) -> Result<ProjectDetailDto,
                     r.get(11)?,
                     r.get(12)?,
                     r.get(13)?,
+                    r.get(14)?,
+                    r.get(15)?,
+                    r.get(16)?,
+                    r.get(17)?,
+                    r.get(18)?,
+                    r.get(19)?,
                 ))
             },
         )
@@ -370,12 +553,18 @@ pub fn project_get(pool: &DbPool, project_id: &str) -> Result<ProjectDetailDto,
         tags.push(r.map_err(|e| AppError::Db(e.to_string()))?);
     }
 
+    let size_history = project_size_history(&conn, project_id)?;
+    let custom_fields = project_custom_values(&conn, project_id)?;
+    let overdue = load_overdue_enabled(&conn)
+        && is_overdue(&proj.4, proj.10.as_deref(), Local::now().date_naive());
+
     Ok(ProjectDetailDto {
         id: proj.0,
         name: proj.1,
         description: proj.2,
         priority: proj.3,
         current_status: proj.4,
+        country_name: country_display_name(&proj.5),
         country_code: proj.5,
         partner_id: proj.6.clone(),
         owner_person_id: proj.7.clone(),
@@ -386,10 +575,19 @@ pub fn project_get(pool: &DbPool, project_id: &str) -> Result<ProjectDetailDto,
         updated_at: proj.12,
         archived_at: proj.13,
         tags,
+        parent_project_id: proj.14,
+        cloned_from_id: proj.16,
         owner_name,
         partner_name,
         assignments,
         status_history,
+        size_points: proj.15,
+        size_history,
+        estimated_hours: proj.17,
+        budget_amount: proj.18,
+        budget_currency: proj.19,
+        custom_fields,
+        overdue,
     })
 }
 
@@ -405,6 +603,19 @@ pub fn project_update(pool: &DbPool, req: ProjectUpdateReq) -> Result<ProjectDet
             .unchecked_transaction()
             .map_err(|e| AppError::Db(e.to_string()))?;
 
+        let scopes = [
+            JournalScope::new("projects", &["id"], "id", &req.id),
+            JournalScope::new("assignments", &["id"], "project_id", &req.id),
+            JournalScope::new(
+                "project_tags",
+                &["project_id", "tag"],
+                "project_id",
+                &req.id,
+            ),
+        ];
+        let before = journal_capture(&tx, &scopes)?;
+        let audit_before = audit_capture(&tx, AuditEntity::Project, &req.id)?;
+
         type ProjectUpdateExistingRow = (
             String,
             String,
@@ -414,24 +625,26 @@ pub fn project_update(pool: &DbPool, req: ProjectUpdateReq) -> Result<ProjectDet
             Option<String>,
             Option<String>,
             Option<String>,
+            Option<String>,
         );
 
-        let (name, desc, priority, country_code, owner_id, product_name, start_date, due_date): ProjectUpdateExistingRow = tx
+        let (name, desc, priority, country_code, owner_id, product_name, start_date, due_date, parent_id): ProjectUpdateExistingRow = tx
             .query_row(
-                "SELECT name, description, priority, country_code, owner_person_id, product_name, start_date, due_date FROM projects WHERE id = ?1",
+                "SELECT name, description, priority, country_code, owner_person_id, product_name, start_date, due_date, parent_project_id FROM projects WHERE id = ?1 AND deleted_at IS NULL",
                 [&req.id],
-                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?, r.get(6)?, r.get(7)?)),
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?, r.get(6)?, r.get(7)?, r.get(8)?)),
             )
             .map_err(|_| AppError::NotFound(format!("project {}", req.id)))?;
 
         let name = req.name.as_deref().unwrap_or(&name).trim().to_string();
         let desc = req.description.as_deref().unwrap_or(&desc).to_string();
         let priority = req.priority.unwrap_or(priority).clamp(1, 5);
-        let country_code = req
-            .country_code
-            .as_deref()
-            .map(|s| s.trim().to_uppercase())
-            .unwrap_or(country_code);
+        // Resending the stored code is not a change, even if it predates the catalog.
+        let country_code = match req.country_code.as_deref() {
+            Some(code) if code.trim() == country_code => country_code,
+            Some(code) => normalize_country_code(code)?,
+            None => country_code,
+        };
         let owner_person_id = req
             .owner_person_id
             .as_deref()
@@ -460,11 +673,25 @@ pub fn project_update(pool: &DbPool, req: ProjectUpdateReq) -> Result<ProjectDet
             .filter(|s| !s.trim().is_empty())
             .cloned();
 
+        let parent_project_id = match req.parent_project_id.as_deref().map(str::trim) {
+            Some("") => None,
+            Some(parent) => Some(parent.to_string()),
+            None => parent_id.clone(),
+        };
+
         if name.is_empty() {
             return Err(AppError::Validation("name is required".into()));
         }
 
         ensure_project_name_unique(&tx, &name, Some(&req.id))?;
+        if owner_person_id != owner_id && load_strict_fk_enabled(&tx) {
+            ensure_person_usable(&tx, &owner_person_id)?;
+        }
+        if let Some(parent) = parent_project_id.as_deref() {
+            if parent_id.as_deref() != Some(parent) {
+                ensure_valid_parent(&tx, Some(&req.id), parent)?;
+            }
+        }
 
         // If owner changed: demote old owner to member, then ensure new owner has active assignment
         if owner_person_id != owner_id {
@@ -499,7 +726,7 @@ pub fn project_update(pool: &DbPool, req: ProjectUpdateReq) -> Result<ProjectDet
         }
 
         tx.execute(
-            "UPDATE projects SET name=?1, description=?2, priority=?3, country_code=?4, owner_person_id=?5, product_name=?6, start_date=?7, due_date=?8, updated_at=?9 WHERE id=?10",
+            "UPDATE projects SET name=?1, description=?2, priority=?3, country_code=?4, owner_person_id=?5, product_name=?6, start_date=?7, due_date=?8, updated_at=?9, parent_project_id=?11 WHERE id=?10",
             params![
                 name,
                 desc,
@@ -510,45 +737,73 @@ pub fn project_update(pool: &DbPool, req: ProjectUpdateReq) -> Result<ProjectDet
                 start_date,
                 due_date,
                 &now,
-                &req.id
+                &req.id,
+                parent_project_id
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
 
         if let Some(ref tags) = req.tags {
-            tx.execute("DELETE FROM project_tags WHERE project_id = ?1", [&req.id])
-                .map_err(|e| AppError::Db(e.to_string()))?;
-            for tag in tags {
-                let tag = tag.trim();
-                if !tag.is_empty() {
-                    tx.execute(
-                        "INSERT INTO project_tags (project_id, tag, created_at) VALUES (?1, ?2, ?3)",
-                        params![&req.id, tag, &now],
-                    )
+            // Diff instead of delete-all + reinsert: unchanged tags keep their created_at and
+            // emit no sync ops, so only real adds / removals take part in the tag set merge.
+            let wanted: std::collections::BTreeSet<&str> = tags
+                .iter()
+                .map(|t| t.trim())
+                .filter(|t| !t.is_empty())
+                .collect();
+            let existing: Vec<String> = {
+                let mut stmt = tx
+                    .prepare("SELECT tag FROM project_tags WHERE project_id = ?1")
                     .map_err(|e| AppError::Db(e.to_string()))?;
-                }
+                let rows = stmt
+                    .query_map([&req.id], |r| r.get::<_, String>(0))
+                    .map_err(|e| AppError::Db(e.to_string()))?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| AppError::Db(e.to_string()))?;
+                rows
+            };
+            for tag in existing.iter().filter(|t| !wanted.contains(t.as_str())) {
+                tx.execute(
+                    "DELETE FROM project_tags WHERE project_id = ?1 AND tag = ?2",
+                    params![&req.id, tag],
+                )
+                .map_err(|e| AppError::Db(e.to_string()))?;
+            }
+            for tag in wanted.iter().filter(|t| !existing.iter().any(|e| e == *t)) {
+                tx.execute(
+                    "INSERT INTO project_tags (project_id, tag, created_at) VALUES (?1, ?2, ?3)",
+                    params![&req.id, tag, &now],
+                )
+                .map_err(|e| AppError::Db(e.to_string()))?;
             }
         }
 
+        audit_record(
+            &tx,
+            AuditEntity::Project,
+            &req.id,
+            audit_before,
+            req.changed_by_person_id.as_deref(),
+            &now,
+        )?;
+        journal_record(&tx, ActionKind::ProjectUpdate, &req.id, &scopes, before)?;
         tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
     }
     project_get(pool, &req.id)
 }
 
-pub fn project_list(pool: &DbPool, req: ProjectListReq) -> Result<ProjectListPage, AppError> {
+/// `WHERE` clause (with leading space) and bind values for the `project_list` filters of `req`;
+/// sorting and paging fields are ignored. Projects are aliased as `p`.
+pub(crate) fn project_list_filter(
+    conn: &Connection,
+    req: &ProjectListReq,
+) -> Result<(String, Vec<rusqlite::types::Value>), AppError> {
     use rusqlite::types::Value;
 
-    let only_unarchived = req.only_unarchived.unwrap_or(true);
-    let limit = req.limit.unwrap_or(50).clamp(1, 200);
-    let offset = req.offset.unwrap_or(0).max(0);
-
-    let conn = get_connection(pool);
-
-    // --- build dynamic WHERE clauses ---
-    let mut conditions: Vec<String> = Vec::new();
+    let mut conditions: Vec<String> = vec!["p.deleted_at IS NULL".to_string()];
     let mut bind_values: Vec<Value> = Vec::new();
 
-    if only_unarchived {
+    if req.only_unarchived.unwrap_or(true) {
         conditions.push("p.current_status <> 'ARCHIVED'".to_string());
     }
 
@@ -564,12 +819,17 @@ pub fn project_list(pool: &DbPool, req: ProjectListReq) -> Result<ProjectListPag
     }
 
     if let Some(ref codes) = req.country_codes {
-        let v: Vec<&String> = codes.iter().filter(|s| !s.is_empty()).collect();
+        // Compared case-insensitively so legacy lower-case codes still match.
+        let v: Vec<String> = codes
+            .iter()
+            .map(|s| s.trim().to_uppercase())
+            .filter(|s| !s.is_empty())
+            .collect();
         if !v.is_empty() {
             let ph: Vec<String> = v.iter().enumerate().map(|_| "?".to_string()).collect();
-            conditions.push(format!("p.country_code IN ({})", ph.join(",")));
+            conditions.push(format!("UPPER(TRIM(p.country_code)) IN ({})", ph.join(",")));
             for s in v {
-                bind_values.push(Value::Text(s.clone()));
+                bind_values.push(Value::Text(s));
             }
         }
     }
@@ -624,11 +884,80 @@ pub fn project_list(pool: &DbPool, req: ProjectListReq) -> Result<ProjectListPag
         }
     }
 
+    if let Some(parent) = req.parent_project_id.as_deref().map(str::trim) {
+        if parent.is_empty() {
+            conditions.push("p.parent_project_id IS NULL".to_string());
+        } else {
+            conditions.push("p.parent_project_id = ?".to_string());
+            bind_values.push(Value::Text(parent.to_string()));
+        }
+    }
+
+    if let Some(flagged) = req.flagged {
+        conditions.push(format!(
+            "p.id {} (SELECT project_id FROM project_flags)",
+            if flagged { "IN" } else { "NOT IN" }
+        ));
+    }
+
+    if let Some(ref rule_ids) = req.flag_rule_ids {
+        let v: Vec<&String> = rule_ids.iter().filter(|s| !s.is_empty()).collect();
+        if !v.is_empty() {
+            let ph: Vec<String> = v.iter().enumerate().map(|_| "?".to_string()).collect();
+            conditions.push(format!(
+                "p.id IN (SELECT project_id FROM project_flags WHERE rule_id IN ({}))",
+                ph.join(",")
+            ));
+            for s in v {
+                bind_values.push(Value::Text(s.clone()));
+            }
+        }
+    }
+
+    for filter in req.custom_fields.iter().flatten() {
+        let (condition, values) = custom_field_filter_condition(&conn, filter)?;
+        conditions.push(condition);
+        bind_values.extend(values);
+    }
+
     let where_clause = if conditions.is_empty() {
         String::new()
     } else {
         format!(" WHERE {}", conditions.join(" AND "))
     };
+    Ok((where_clause, bind_values))
+}
+
+pub fn project_list(pool: &DbPool, req: ProjectListReq) -> Result<ProjectListPage, AppError> {
+    use rusqlite::types::Value;
+
+    let limit = req.limit.unwrap_or(50).clamp(1, 200);
+    let keyset = matches!(req.sort_by.as_deref(), None | Some("updatedAt"));
+    let descending = req.sort_order.as_deref() != Some("asc");
+    let cursor = match (req.after_updated_at.as_deref(), req.after_id.as_deref()) {
+        (None, None) => None,
+        (Some(updated_at), Some(id)) if keyset => Some((updated_at, id)),
+        (Some(_), Some(_)) => {
+            return Err(AppError::Validation(
+                "cursor pagination requires sortBy updatedAt".into(),
+            ))
+        }
+        _ => {
+            return Err(AppError::Validation(
+                "afterUpdatedAt and afterId must be set together".into(),
+            ))
+        }
+    };
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        req.offset.unwrap_or(0).max(0)
+    };
+
+    let conn = get_read_connection(pool);
+    let overdue_today = load_overdue_enabled(&conn).then(|| Local::now().date_naive());
+
+    let (mut where_clause, mut bind_values) = project_list_filter(&conn, &req)?;
 
     // --- COUNT total ---
     let count_sql = format!("SELECT COUNT(*) FROM projects p{}", where_clause);
@@ -649,6 +978,17 @@ pub fn project_list(pool: &DbPool, req: ProjectListReq) -> Result<ProjectListPag
             };
             format!(" ORDER BY p.priority {}, p.updated_at DESC", dir)
         }
+        Some("rank") => {
+            // Unranked projects follow ranked ones, most recently updated first.
+            let dir = match req.sort_order.as_deref() {
+                Some("desc") => "DESC",
+                _ => "ASC",
+            };
+            format!(
+                " ORDER BY p.current_status, CASE WHEN p.rank IS NULL THEN 1 ELSE 0 END, p.rank {}, p.updated_at DESC, p.id",
+                dir
+            )
+        }
         Some("dueDate") => {
             let dir = match req.sort_order.as_deref() {
                 Some("desc") => "DESC",
@@ -658,20 +998,30 @@ pub fn project_list(pool: &DbPool, req: ProjectListReq) -> Result<ProjectListPag
             format!(" ORDER BY CASE WHEN p.due_date IS NULL THEN 1 ELSE 0 END, p.due_date {}, p.updated_at DESC", dir)
         }
         _ => {
-            // default: updatedAt DESC
-            let dir = match req.sort_order.as_deref() {
-                Some("asc") => "ASC",
-                _ => "DESC",
-            };
-            format!(" ORDER BY p.updated_at {}", dir)
+            // default: updatedAt DESC; id breaks ties so the keyset cursor is total.
+            let dir = if descending { "DESC" } else { "ASC" };
+            format!(" ORDER BY p.updated_at {}, p.id {}", dir, dir)
         }
     };
 
+    // --- keyset cursor: (updated_at, id) strictly past the previous page's last row ---
+    if let Some((updated_at, id)) = cursor {
+        let cmp = if descending { "<" } else { ">" };
+        where_clause.push_str(&format!(
+            " AND (p.updated_at {cmp} ? OR (p.updated_at = ? AND p.id {cmp} ?))"
+        ));
+        bind_values.push(Value::Text(updated_at.to_string()));
+        bind_values.push(Value::Text(updated_at.to_string()));
+        bind_values.push(Value::Text(id.to_string()));
+    }
+
     // --- main query ---
     let data_sql = format!(
         "SELECT p.id, p.name, p.current_status, p.priority, p.country_code, \
          COALESCE(pt.name, '?') AS partner_name, COALESCE(pe.display_name, '?') AS owner_name, \
-         p.due_date, p.updated_at \
+         p.due_date, p.updated_at, p.parent_project_id, p.rank, p.size_points, \
+         (SELECT COUNT(*) FROM checklist_items ci WHERE ci.project_id = p.id AND ci.done = 1), \
+         (SELECT COUNT(*) FROM checklist_items ci WHERE ci.project_id = p.id) \
          FROM projects p \
          LEFT JOIN partners pt ON pt.id = p.partner_id \
          LEFT JOIN persons pe ON pe.id = p.owner_person_id\
@@ -679,8 +1029,9 @@ pub fn project_list(pool: &DbPool, req: ProjectListReq) -> Result<ProjectListPag
         where_clause, order_clause
     );
 
-    let mut all_params = bind_values.clone();
-    all_params.push(Value::Integer(limit as i64));
+    // One extra row tells whether a next page exists.
+    let mut all_params = bind_values;
+    all_params.push(Value::Integer(limit as i64 + 1));
     all_params.push(Value::Integer(offset as i64));
 
     let all_refs: Vec<&dyn rusqlite::types::ToSql> = all_params
@@ -706,25 +1057,59 @@ pub fn project_list(pool: &DbPool, req: ProjectListReq) -> Result<ProjectListPag
                 tags.push(t);
             }
         }
+        let flags = conn
+            .prepare(
+                "SELECT r.name FROM project_flags f JOIN sla_rules r ON r.id = f.rule_id
+                 WHERE f.project_id = ?1 ORDER BY r.name COLLATE NOCASE",
+            )?
+            .query_map([&id], |r| r.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let country_code: String = row.get(4)?;
+        let current_status: String = row.get(2)?;
+        let due_date: Option<String> = row.get(7)?;
+        let checklist_done: i64 = row.get(12)?;
+        let checklist_total: i64 = row.get(13)?;
+        let overdue = overdue_today
+            .is_some_and(|today| is_overdue(&current_status, due_date.as_deref(), today));
         items.push(ProjectListItemDto {
             id,
             name: row.get(1)?,
-            current_status: row.get(2)?,
+            current_status,
             priority: row.get(3)?,
-            country_code: row.get(4)?,
+            country_name: country_display_name(&country_code),
+            country_code,
             partner_name: row.get(5)?,
             owner_name: row.get(6)?,
-            due_date: row.get(7)?,
+            due_date,
             updated_at: row.get(8)?,
             tags,
+            parent_project_id: row.get(9)?,
+            rank: row.get(10)?,
+            size_points: row.get(11)?,
+            checklist_done,
+            checklist_total,
+            checklist_ratio: (checklist_total > 0)
+                .then(|| checklist_done as f64 / checklist_total as f64),
+            flags,
+            overdue,
         });
     }
 
+    let has_more = items.len() > limit as usize;
+    items.truncate(limit as usize);
+    let next = items
+        .last()
+        .filter(|_| keyset && has_more)
+        .map(|last| (last.updated_at.clone(), last.id.clone()));
+    let (next_after_updated_at, next_after_id) = next.unzip();
+
     Ok(ProjectListPage {
         items,
         total,
         limit,
         offset,
+        next_after_updated_at,
+        next_after_id,
     })
 }
 
@@ -732,9 +1117,18 @@ pub fn project_change_status(
     pool: &DbPool,
     req: ProjectChangeStatusReq,
 ) -> Result<ProjectDetailDto, AppError> {
-    let to_status = parse_status(&req.to_status).ok_or_else(|| {
-        AppError::InvalidStatusTransition(format!("unknown status: {}", req.to_status))
-    })?;
+    project_change_status_with_history_id(pool, req, Uuid::new_v4().to_string())
+}
+
+/// `project_change_status` with a caller-chosen `status_history` id (status automation
+/// derives it so that devices moving the same project write the same row).
+pub(crate) fn project_change_status_with_history_id(
+    pool: &DbPool,
+    req: ProjectChangeStatusReq,
+    hist_id: String,
+) -> Result<ProjectDetailDto, AppError> {
+    let to_status = req.to_status.trim();
+    let archived = ProjectStatus::Archived.as_str();
 
     {
         let conn = get_connection(pool);
@@ -742,11 +1136,23 @@ pub fn project_change_status(
             .unchecked_transaction()
             .map_err(|e| AppError::Db(e.to_string()))?;
 
-        let (current_status, updated_at): (String, String) = tx
+        let scopes = [
+            JournalScope::new("projects", &["id"], "id", &req.project_id),
+            JournalScope::new("status_history", &["id"], "project_id", &req.project_id),
+            JournalScope::new("assignments", &["id"], "project_id", &req.project_id),
+        ];
+        let before = journal_capture(&tx, &scopes)?;
+
+        let (current_status, updated_at, prev_archived_at, owner_person_id): (
+            String,
+            String,
+            Option<String>,
+            String,
+        ) = tx
             .query_row(
-                "SELECT current_status, updated_at FROM projects WHERE id = ?1",
+                "SELECT current_status, updated_at, archived_at, owner_person_id FROM projects WHERE id = ?1 AND deleted_at IS NULL",
                 [&req.project_id],
-                |r| Ok((r.get(0)?, r.get(1)?)),
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
             )
             .map_err(|_| AppError::NotFound(format!("project {}", req.project_id)))?;
 
@@ -756,35 +1162,82 @@ pub fn project_change_status(
             }
         }
 
-        let from_status = parse_status(&current_status);
-
-        if !StatusMachine::can_transition(from_status, to_status) {
+        let workflow = load_active_workflow(&tx)?;
+        if !workflow.has_status(to_status) {
             return Err(AppError::InvalidStatusTransition(format!(
-                "{} -> {}",
-                current_status,
-                to_status.as_str()
+                "unknown status: {}",
+                req.to_status
             )));
         }
 
-        if StatusMachine::note_required(from_status, to_status) {
+        // A status the active workflow no longer has may move anywhere in the workflow, with a note.
+        let note_required = if workflow.has_status(&current_status) {
+            if !workflow.can_transition(&current_status, to_status) {
+                return Err(AppError::InvalidStatusTransition(format!(
+                    "{} -> {}",
+                    current_status, to_status
+                )));
+            }
+            workflow.note_required(&current_status, to_status)
+        } else {
+            true
+        };
+
+        if note_required {
             let note = req.note.as_deref().unwrap_or("").trim();
             if note.is_empty() {
                 return Err(AppError::NoteRequired);
             }
         }
 
+        clear_project_flags(&tx, &req.project_id)?;
+
+        let mut audit = Vec::new();
+        if let Some(info) =
+            wip_limit_exceeded(&tx, &workflow, to_status, &req.project_id, &owner_person_id)?
+        {
+            if !req.force.unwrap_or(false) {
+                return Err(AppError::WipLimitExceeded(info));
+            }
+            audit.push(wip_limit_note(&info));
+        }
+
         let now = Utc::now().to_rfc3339();
-        let hist_id = Uuid::new_v4().to_string();
-        let note = req.note.unwrap_or_default();
+        let mut note = req.note.unwrap_or_default();
         let changed_by = req.changed_by_person_id;
 
+        // Archive ends active assignments at archived_at; unarchive reopens them best-effort.
+        // The count is appended to the history note as an audit trail.
+        let assignments_audit = if to_status == archived {
+            let ended = end_assignments_on_archive(&tx, &req.project_id, &now)?;
+            (ended > 0).then(|| format!("[auto] ended {} active assignment(s)", ended))
+        } else if current_status == archived {
+            let restored = match prev_archived_at.as_deref() {
+                Some(archived_at) => {
+                    restore_assignments_on_unarchive(&tx, &req.project_id, archived_at)?
+                }
+                None => 0,
+            };
+            (restored > 0).then(|| format!("[auto] restored {} assignment(s)", restored))
+        } else {
+            None
+        };
+        audit.extend(assignments_audit);
+        for audit in audit {
+            note = if note.trim().is_empty() {
+                audit
+            } else {
+                format!("{}\n{}", note, audit)
+            };
+        }
+
         tx.execute(
             "INSERT INTO status_history (id, project_id, from_status, to_status, changed_at, changed_by_person_id, note) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 hist_id,
                 &req.project_id,
                 current_status,
-                to_status.as_str(),
+                to_status,
                 &now,
                 changed_by,
                 note
@@ -792,16 +1245,16 @@ pub fn project_change_status(
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
 
-        let archived_at: Option<&str> = if to_status == ProjectStatus::Archived {
+        let archived_at: Option<&str> = if to_status == archived {
             Some(&now)
         } else {
             None
         };
 
         tx.execute(
-            "UPDATE projects SET current_status = ?1, updated_at = ?2, archived_at = ?3 WHERE id = ?4",
+            "UPDATE projects SET current_status = ?1, updated_at = ?2, archived_at = ?3, rank = NULL WHERE id = ?4",
             params![
-                to_status.as_str(),
+                to_status,
                 &now,
                 archived_at,
                 &req.project_id
@@ -809,7 +1262,180 @@ pub fn project_change_status(
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
 
+        journal_record(
+            &tx,
+            ActionKind::ProjectStatus,
+            &req.project_id,
+            &scopes,
+            before,
+        )?;
+        webhook_notify(
+            &tx,
+            WEBHOOK_EVENT_PROJECT_STATUS_CHANGED,
+            serde_json::json!({
+                "projectId": &req.project_id,
+                "fromStatus": current_status,
+                "toStatus": to_status,
+                "changedAt": &now,
+                "changedByPersonId": changed_by,
+                "note": note,
+            }),
+        );
         tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
     } // release conn before project_get to avoid deadlock
     project_get(pool, &req.project_id)
 }
+
+/// Place `project_id` between `before_id` (the card above it) and `after_id` (the card below it)
+/// in its status column. Either neighbour may be omitted for the top / bottom of the column.
+pub fn project_reorder(pool: &DbPool, req: ProjectReorderReq) -> Result<ProjectRankDto, AppError> {
+    let before_id = req
+        .before_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let after_id = req
+        .after_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    if before_id.is_none() && after_id.is_none() {
+        return Err(AppError::Validation(
+            "before_id or after_id is required".into(),
+        ));
+    }
+    if before_id == Some(req.project_id.as_str()) || after_id == Some(req.project_id.as_str()) {
+        return Err(AppError::Validation(
+            "a project cannot be its own neighbour".into(),
+        ));
+    }
+
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let status: String = tx
+        .query_row(
+            "SELECT current_status FROM projects WHERE id = ?1 AND deleted_at IS NULL",
+            [&req.project_id],
+            |r| r.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("project {}", req.project_id)))?;
+    for neighbour in [before_id, after_id].into_iter().flatten() {
+        let neighbour_status: String = tx
+            .query_row(
+                "SELECT current_status FROM projects WHERE id = ?1 AND deleted_at IS NULL",
+                [neighbour],
+                |r| r.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("project {}", neighbour)))?;
+        if neighbour_status != status {
+            return Err(AppError::Validation(format!(
+                "RANK_COLUMN_MISMATCH: {} is not in the {} column",
+                neighbour, status
+            )));
+        }
+    }
+
+    let mut rank = None;
+    for attempt in 0..2 {
+        let column = load_rank_column(&tx, &status)?;
+        let column = if attempt > 0 || column.iter().any(|(_, r)| r.is_none()) {
+            renumber_rank_column(&tx, column)?
+        } else {
+            column
+        };
+        // Neighbours in the column without the moved card; a single given neighbour
+        // implies the other one (the card next to it on the open side).
+        let others: Vec<(String, f64)> = column
+            .into_iter()
+            .filter(|(id, _)| id != &req.project_id)
+            .map(|(id, r)| (id, r.unwrap_or_default()))
+            .collect();
+        let position = |id: &str| others.iter().position(|(other, _)| other == id);
+        let (low, high) = match (before_id, after_id) {
+            (Some(before), Some(after)) => (
+                position(before).map(|i| others[i].1),
+                position(after).map(|i| others[i].1),
+            ),
+            (Some(before), None) => {
+                let i = position(before);
+                (
+                    i.map(|i| others[i].1),
+                    i.and_then(|i| others.get(i + 1)).map(|(_, r)| *r),
+                )
+            }
+            (None, _) => {
+                let i = after_id.and_then(position);
+                (
+                    i.and_then(|i| i.checked_sub(1)).map(|i| others[i].1),
+                    i.map(|i| others[i].1),
+                )
+            }
+        };
+        let candidate = match (low, high) {
+            (Some(low), Some(high)) if low >= high => {
+                return Err(AppError::Validation(
+                    "before_id must be ranked above after_id".into(),
+                ))
+            }
+            (Some(low), Some(high)) => (low + high) / 2.0,
+            (Some(low), None) => low + RANK_STEP,
+            (None, Some(high)) => high - RANK_STEP,
+            (None, None) => RANK_STEP,
+        };
+        let fits = low.map_or(true, |low| candidate - low > MIN_RANK_GAP)
+            && high.map_or(true, |high| high - candidate > MIN_RANK_GAP);
+        if fits {
+            rank = Some(candidate);
+            break;
+        }
+    }
+    let rank = rank.ok_or_else(|| AppError::Db("failed to allocate a rank".into()))?;
+
+    let now = Utc::now().to_rfc3339();
+    tx.execute(
+        "UPDATE projects SET rank = ?1, updated_at = ?2 WHERE id = ?3",
+        params![rank, &now, &req.project_id],
+    )?;
+    tx.commit()?;
+    Ok(ProjectRankDto {
+        project_id: req.project_id,
+        current_status: status,
+        rank,
+    })
+}
+
+/// Column members in display order: ranked first, then unranked by most recent update.
+fn load_rank_column(
+    conn: &Connection,
+    status: &str,
+) -> Result<Vec<(String, Option<f64>)>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, rank FROM projects WHERE current_status = ?1 AND deleted_at IS NULL
+         ORDER BY CASE WHEN rank IS NULL THEN 1 ELSE 0 END, rank, updated_at DESC, id",
+    )?;
+    let rows = stmt
+        .query_map([status], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Evenly spaced ranks in the current display order (does not touch `updated_at`).
+fn renumber_rank_column(
+    conn: &Connection,
+    column: Vec<(String, Option<f64>)>,
+) -> Result<Vec<(String, Option<f64>)>, AppError> {
+    let mut renumbered = Vec::with_capacity(column.len());
+    for (i, (id, old)) in column.into_iter().enumerate() {
+        let rank = (i as f64 + 1.0) * RANK_STEP;
+        if old != Some(rank) {
+            conn.execute(
+                "UPDATE projects SET rank = ?1 WHERE id = ?2",
+                params![rank, &id],
+            )?;
+        }
+        renumbered.push((id, Some(rank)));
+    }
+    Ok(renumbered)
+}
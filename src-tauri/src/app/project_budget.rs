@@ -0,0 +1,264 @@
+//! Project estimate and budget: planned effort (hours) and planned spend per project.
+//!
+//! - 两个字段均可为空（未估算 / 无预算），必须是有限的非负数，随 `projects` 行同步并包含在 JSON 导出 / 快照中。
+//! - 每次保存同时写入全部值（`None` 表示清空）；值未变化时不更新 `updatedAt`。
+//! - 预算货币为 ISO 4217 代码，为空表示沿用合作方默认货币（再为空则为基准货币），见 `currency`。
+//! - 预算报表按本机汇率把各项目预算换算为基准货币后按合作方 / 货币汇总（金额保留两位小数）；
+//!   缺少汇率的货币单独列出，其预算不计入换算后的合计。
+//! - 复制项目不复制估算与预算（与尺寸估算一致）。
+//! - 实际工时 / 支出尚未记录，估算与实际的偏差报表待工时记录实现后提供。
+
+use crate::app::currency::{load_base_currency, load_rates, normalize_currency};
+use crate::app::{project_get, ProjectDetailDto};
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Largest accepted estimate (hours).
+pub const MAX_ESTIMATED_HOURS: f64 = 1_000_000.0;
+/// Largest accepted budget amount.
+pub const MAX_BUDGET_AMOUNT: f64 = 1_000_000_000_000.0;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSetBudgetReq {
+    pub project_id: String,
+    /// New estimate in hours; `None` clears it.
+    pub estimated_hours: Option<f64>,
+    /// New budget; `None` clears it.
+    pub budget_amount: Option<f64>,
+    /// ISO 4217 code of the budget; `None` = the partner's default currency.
+    #[serde(default)]
+    pub budget_currency: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetReportReq {
+    /// Skip archived projects (default true).
+    pub only_unarchived: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetReportProjectDto {
+    pub project_id: String,
+    pub project_name: String,
+    pub partner_id: String,
+    pub partner_name: String,
+    pub amount: f64,
+    /// Effective currency (project, else partner default, else base currency).
+    pub currency: String,
+    /// `None` when there is no rate for `currency`.
+    pub amount_base: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetReportPartnerDto {
+    pub partner_id: String,
+    pub partner_name: String,
+    pub project_count: usize,
+    /// Sum of the converted budgets.
+    pub total_base: f64,
+    /// Budgets left out of `total_base` for lack of a rate.
+    pub unconverted_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetReportCurrencyDto {
+    pub currency: String,
+    pub project_count: usize,
+    /// Sum in `currency`.
+    pub total: f64,
+    pub rate: Option<f64>,
+    pub total_base: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetReportDto {
+    pub base_currency: String,
+    /// Sum of every converted budget.
+    pub total_base: f64,
+    /// Ordered by partner name, then project name.
+    pub projects: Vec<BudgetReportProjectDto>,
+    pub partners: Vec<BudgetReportPartnerDto>,
+    /// Ordered by currency code.
+    pub currencies: Vec<BudgetReportCurrencyDto>,
+    /// Currencies in use without an exchange rate.
+    pub missing_rates: Vec<String>,
+}
+
+fn validate_amount(field: &str, value: Option<f64>, max: f64) -> Result<(), AppError> {
+    match value {
+        Some(v) if !v.is_finite() || v < 0.0 || v > max => Err(AppError::Validation(format!(
+            "{} must be between 0 and {}",
+            field, max
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Set (or clear) the estimate, the budget and its currency of a project.
+pub fn project_set_budget(
+    pool: &DbPool,
+    req: ProjectSetBudgetReq,
+) -> Result<ProjectDetailDto, AppError> {
+    validate_amount("estimatedHours", req.estimated_hours, MAX_ESTIMATED_HOURS)?;
+    validate_amount("budgetAmount", req.budget_amount, MAX_BUDGET_AMOUNT)?;
+    let budget_currency = req
+        .budget_currency
+        .as_deref()
+        .map(normalize_currency)
+        .transpose()?;
+
+    {
+        let conn = get_connection(pool);
+        let current: (Option<f64>, Option<f64>, Option<String>) = conn
+            .query_row(
+                "SELECT estimated_hours, budget_amount, budget_currency FROM projects WHERE id = ?1 AND deleted_at IS NULL",
+                [&req.project_id],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("project {}", req.project_id)))?;
+
+        let next = (req.estimated_hours, req.budget_amount, budget_currency);
+        if current != next {
+            conn.execute(
+                "UPDATE projects SET estimated_hours = ?1, budget_amount = ?2, budget_currency = ?3, updated_at = ?4 WHERE id = ?5",
+                params![
+                    next.0,
+                    next.1,
+                    next.2,
+                    chrono::Utc::now().to_rfc3339(),
+                    &req.project_id
+                ],
+            )?;
+        }
+    } // release conn before project_get to avoid deadlock
+    project_get(pool, &req.project_id)
+}
+
+/// Budgets of all projects converted to the base currency, with per-partner and per-currency totals.
+pub fn budget_report(pool: &DbPool, req: BudgetReportReq) -> Result<BudgetReportDto, AppError> {
+    let conn = get_read_connection(pool);
+    let base = load_base_currency(&conn);
+    let rates = load_rates(&conn)?;
+    let rate_of = |currency: &str| {
+        if currency == base {
+            Some(1.0)
+        } else {
+            rates.get(currency).copied()
+        }
+    };
+
+    let only_unarchived = req.only_unarchived.unwrap_or(true);
+    let rows = conn
+        .prepare(
+            "SELECT p.id, p.name, p.partner_id, pa.name, p.budget_amount,
+                    COALESCE(p.budget_currency, pa.default_currency)
+             FROM projects p
+             JOIN partners pa ON pa.id = p.partner_id
+             WHERE p.deleted_at IS NULL AND p.budget_amount IS NOT NULL
+               AND (?1 = 0 OR p.archived_at IS NULL)
+             ORDER BY pa.name COLLATE NOCASE, pa.id, p.name COLLATE NOCASE",
+        )?
+        .query_map([only_unarchived], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+                r.get::<_, f64>(4)?,
+                r.get::<_, Option<String>>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut projects = Vec::with_capacity(rows.len());
+    let mut partners: Vec<BudgetReportPartnerDto> = Vec::new();
+    let mut partner_index: HashMap<String, usize> = HashMap::new();
+    let mut currencies: BTreeMap<String, (usize, f64)> = BTreeMap::new();
+    let mut total_base = 0.0;
+    for (project_id, project_name, partner_id, partner_name, amount, currency) in rows {
+        let currency = currency
+            .map(|c| c.trim().to_ascii_uppercase())
+            .filter(|c| !c.is_empty())
+            .unwrap_or_else(|| base.clone());
+        let amount_base = rate_of(&currency).map(|rate| round_cents(amount * rate));
+
+        let index = *partner_index.entry(partner_id.clone()).or_insert_with(|| {
+            partners.push(BudgetReportPartnerDto {
+                partner_id: partner_id.clone(),
+                partner_name: partner_name.clone(),
+                project_count: 0,
+                total_base: 0.0,
+                unconverted_count: 0,
+            });
+            partners.len() - 1
+        });
+        let partner = &mut partners[index];
+        partner.project_count += 1;
+        match amount_base {
+            Some(value) => {
+                partner.total_base += value;
+                total_base += value;
+            }
+            None => partner.unconverted_count += 1,
+        }
+
+        let entry = currencies.entry(currency.clone()).or_insert((0, 0.0));
+        entry.0 += 1;
+        entry.1 += amount;
+
+        projects.push(BudgetReportProjectDto {
+            project_id,
+            project_name,
+            partner_id,
+            partner_name,
+            amount,
+            currency,
+            amount_base,
+        });
+    }
+    for partner in &mut partners {
+        partner.total_base = round_cents(partner.total_base);
+    }
+
+    let currencies: Vec<BudgetReportCurrencyDto> = currencies
+        .into_iter()
+        .map(|(currency, (project_count, total))| {
+            let rate = rate_of(&currency);
+            BudgetReportCurrencyDto {
+                total_base: rate.map(|r| round_cents(total * r)),
+                currency,
+                project_count,
+                total: round_cents(total),
+                rate,
+            }
+        })
+        .collect();
+    let missing_rates = currencies
+        .iter()
+        .filter(|c| c.rate.is_none())
+        .map(|c| c.currency.clone())
+        .collect();
+
+    Ok(BudgetReportDto {
+        base_currency: base.clone(),
+        total_base: round_cents(total_base),
+        projects,
+        partners,
+        currencies,
+        missing_rates,
+    })
+}
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
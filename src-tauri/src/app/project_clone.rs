@@ -0,0 +1,185 @@
+//! Project clone: copy a project as a new BACKLOG project linked back via `cloned_from_id`.
+//!
+//! - 复制基本信息（描述、优先级、国家、合作方、负责人、产品名、父项目）；状态重置为 BACKLOG，
+//!   状态历史、规模估算、评论不复制。
+//! - 可选复制：标签、当前成员（已停用的成员跳过）、里程碑（置顶的 `Milestones:` 评论）。
+//! - 指定新开始日期且原项目有开始日期时，里程碑日期随之平移；否则保持原日期。
+//! - 未指定名称时使用 `<原名称> (copy)`，重名则追加序号：`(copy 2)`、`(copy 3)`…
+
+use crate::app::project::project_create_tx;
+use crate::app::template::{milestone_comment, parse_milestone_comment, TemplateMilestoneDto};
+use crate::app::{project_get, ProjectCreateReq, ProjectDetailDto};
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use chrono::{NaiveDate, Utc};
+use rusqlite::params;
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCloneReq {
+    pub project_id: String,
+    /// Name of the copy; default `<name> (copy)` (numbered when taken).
+    pub name: Option<String>,
+    /// Default true.
+    pub include_tags: Option<bool>,
+    /// Default true: active non-owner members with their roles.
+    pub include_members: Option<bool>,
+    /// Default true: pinned milestone comments.
+    pub include_milestones: Option<bool>,
+    /// Start date of the copy (milestones shift with it); none = not set.
+    pub start_date: Option<String>,
+    pub due_date: Option<String>,
+    pub created_by_person_id: Option<String>,
+}
+
+/// Copy `req.project_id` (not in the trash) as a new project.
+pub fn project_clone(pool: &DbPool, req: ProjectCloneReq) -> Result<ProjectDetailDto, AppError> {
+    let source = project_get(pool, &req.project_id)?;
+    let include_tags = req.include_tags.unwrap_or(true);
+    let include_members = req.include_members.unwrap_or(true);
+    let include_milestones = req.include_milestones.unwrap_or(true);
+    let start_date = req.start_date.filter(|s| !s.trim().is_empty());
+
+    let id = {
+        let conn = get_connection(pool);
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| AppError::Db(e.to_string()))?;
+
+        let name = match req.name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+            Some(name) => name.to_string(),
+            None => clone_name(&tx, &source.name)?,
+        };
+        let id = project_create_tx(
+            &tx,
+            ProjectCreateReq {
+                name,
+                description: Some(source.description.clone()),
+                priority: Some(source.priority),
+                country_code: source.country_code.clone(),
+                partner_id: source.partner_id.clone(),
+                owner_person_id: source.owner_person_id.clone(),
+                product_name: source.product_name.clone(),
+                start_date: start_date.clone(),
+                due_date: req.due_date,
+                tags: include_tags.then(|| source.tags.clone()),
+                created_by_person_id: req.created_by_person_id.clone(),
+                parent_project_id: source.parent_project_id.clone(),
+            },
+        )?;
+        let now = Utc::now().to_rfc3339();
+        tx.execute(
+            "UPDATE projects SET cloned_from_id = ?1, _version = _version + 1 WHERE id = ?2",
+            params![&source.id, &id],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        if include_members {
+            for member in &source.assignments {
+                if member.end_at.is_some() || member.person_id == source.owner_person_id {
+                    continue;
+                }
+                let active: bool = tx
+                    .query_row(
+                        "SELECT is_active FROM persons WHERE id = ?1 AND deleted_at IS NULL",
+                        [&member.person_id],
+                        |r| r.get::<_, i32>(0),
+                    )
+                    .map(|a| a != 0)
+                    .unwrap_or(false);
+                if !active {
+                    continue;
+                }
+                tx.execute(
+                    "INSERT INTO assignments (id, project_id, person_id, role, start_at, end_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5, NULL, ?5)",
+                    params![
+                        Uuid::new_v4().to_string(),
+                        &id,
+                        &member.person_id,
+                        &member.role,
+                        &now
+                    ],
+                )
+                .map_err(|e| AppError::Db(e.to_string()))?;
+            }
+        }
+
+        if include_milestones {
+            let shift = match (
+                parse_date(source.start_date.as_deref()),
+                parse_date(start_date.as_deref()),
+            ) {
+                (Some(from), Some(to)) => to - from,
+                _ => chrono::Duration::zero(),
+            };
+            let mut stmt = tx
+                .prepare(
+                    "SELECT content FROM project_comments WHERE project_id = ?1 AND is_pinned = 1 ORDER BY created_at",
+                )
+                .map_err(|e| AppError::Db(e.to_string()))?;
+            let contents = stmt
+                .query_map([&source.id], |r| r.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            drop(stmt);
+            for content in contents {
+                let milestones = parse_milestone_comment(&content);
+                let Some(base) = milestones.iter().map(|(_, date)| *date).min() else {
+                    continue;
+                };
+                let milestones: Vec<TemplateMilestoneDto> = milestones
+                    .into_iter()
+                    .map(|(title, date)| TemplateMilestoneDto {
+                        title,
+                        offset_days: (date - base).num_days(),
+                    })
+                    .collect();
+                tx.execute(
+                    "INSERT INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at, _version)
+                     VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5, 1)",
+                    params![
+                        Uuid::new_v4().to_string(),
+                        &id,
+                        &req.created_by_person_id,
+                        milestone_comment(&milestones, base + shift),
+                        &now
+                    ],
+                )
+                .map_err(|e| AppError::Db(e.to_string()))?;
+            }
+        }
+
+        tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
+        id
+    }; // release conn before calling project_get to avoid deadlock
+
+    project_get(pool, &id)
+}
+
+/// `<name> (copy)`, or the first free `<name> (copy n)`.
+fn clone_name(tx: &rusqlite::Transaction<'_>, name: &str) -> Result<String, AppError> {
+    let mut n = 1;
+    loop {
+        let candidate = if n == 1 {
+            format!("{} (copy)", name)
+        } else {
+            format!("{} (copy {})", name, n)
+        };
+        let taken: i64 = tx
+            .query_row(
+                "SELECT COUNT(1) FROM projects WHERE name = ?1 COLLATE NOCASE",
+                [&candidate],
+                |r| r.get(0),
+            )
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        if taken == 0 {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+fn parse_date(raw: Option<&str>) -> Option<NaiveDate> {
+    raw.and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+}
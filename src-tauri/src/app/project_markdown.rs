@@ -0,0 +1,157 @@
+//! Project Markdown export: one project as a document for wikis and emails.
+//!
+//! 内容：基本信息表、成员表、状态时间线（从早到晚）、评论（从早到晚，置顶评论标注）；
+//! 评论与描述的富文本（doc JSON）转换为 Markdown。日期按用户的格式设置显示。
+
+use crate::app::{comment_list_by_project, load_format_settings, project_get};
+use crate::error::AppError;
+use crate::infra::markdown::{escape, rich_text_to_markdown, table};
+use crate::infra::{get_read_connection, DbPool};
+
+/// Render a project (not in the trash) as Markdown.
+pub fn project_export_markdown(pool: &DbPool, project_id: &str) -> Result<String, AppError> {
+    let project = project_get(pool, project_id)?;
+    let mut comments = comment_list_by_project(pool, project_id.to_string())?;
+    comments.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    let settings = load_format_settings(&get_read_connection(pool));
+    let date = |raw: &Option<String>| {
+        raw.as_deref()
+            .map(|d| settings.format_date_str(d))
+            .unwrap_or_default()
+    };
+
+    let mut sections = vec![format!("# {}", escape(&project.name))];
+
+    let mut fields: Vec<(String, String)> = vec![
+        ("Status".into(), project.current_status.clone()),
+        ("Priority".into(), project.priority.to_string()),
+        (
+            "Country".into(),
+            format!(
+                "{} ({})",
+                escape(&project.country_name),
+                project.country_code
+            ),
+        ),
+        ("Partner".into(), escape(&project.partner_name)),
+        ("Owner".into(), escape(&project.owner_name)),
+        (
+            "Product".into(),
+            escape(project.product_name.as_deref().unwrap_or("")),
+        ),
+        ("Start date".into(), date(&project.start_date)),
+        ("Due date".into(), date(&project.due_date)),
+        (
+            "Tags".into(),
+            project
+                .tags
+                .iter()
+                .map(|t| format!("`{}`", t.replace('`', "'")))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        (
+            "Size".into(),
+            project
+                .size_points
+                .map(|p| format!("{} pts", p))
+                .unwrap_or_default(),
+        ),
+    ];
+    for field in &project.custom_fields {
+        if let Some(value) = field.value.as_deref().filter(|v| !v.is_empty()) {
+            fields.push((escape(&field.name), escape(value)));
+        }
+    }
+    fields.push((
+        "Created".into(),
+        settings.format_timestamp(&project.created_at),
+    ));
+    fields.push((
+        "Updated".into(),
+        settings.format_timestamp(&project.updated_at),
+    ));
+    if let Some(archived_at) = &project.archived_at {
+        fields.push(("Archived".into(), settings.format_timestamp(archived_at)));
+    }
+    let rows: Vec<Vec<String>> = fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| vec![name, value])
+        .collect();
+    sections.push(table(&["Field".into(), "Value".into()], &rows));
+
+    let description = rich_text_to_markdown(&project.description);
+    if !description.is_empty() {
+        sections.push(format!("## Description\n\n{}", description));
+    }
+
+    let members = if project.assignments.is_empty() {
+        "_No members._".to_string()
+    } else {
+        let rows: Vec<Vec<String>> = project
+            .assignments
+            .iter()
+            .map(|a| {
+                vec![
+                    escape(&a.person_name),
+                    escape(&a.role),
+                    settings.format_timestamp(&a.start_at),
+                    a.end_at
+                        .as_deref()
+                        .map(|e| settings.format_timestamp(e))
+                        .unwrap_or_else(|| "active".to_string()),
+                ]
+            })
+            .collect();
+        table(
+            &["Person".into(), "Role".into(), "Start".into(), "End".into()],
+            &rows,
+        )
+    };
+    sections.push(format!("## Members\n\n{}", members));
+
+    let mut history: Vec<_> = project.status_history.iter().collect();
+    history.sort_by(|a, b| a.changed_at.cmp(&b.changed_at));
+    let timeline: Vec<String> = history
+        .iter()
+        .map(|h| {
+            let mut line = format!(
+                "- **{}** — {}",
+                settings.format_timestamp(&h.changed_at),
+                match &h.from_status {
+                    Some(from) => format!("{} → {}", from, h.to_status),
+                    None => h.to_status.clone(),
+                }
+            );
+            if let Some(by) = &h.changed_by_name {
+                line.push_str(&format!(" (by {})", escape(by)));
+            }
+            if !h.note.trim().is_empty() {
+                line.push_str(&format!(": {}", escape(h.note.trim()).replace('\n', " ")));
+            }
+            line
+        })
+        .collect();
+    if !timeline.is_empty() {
+        sections.push(format!("## Status history\n\n{}", timeline.join("\n")));
+    }
+
+    if !comments.is_empty() {
+        let mut text = String::from("## Comments");
+        for comment in &comments {
+            text.push_str(&format!(
+                "\n\n### {} · {}{}\n\n{}",
+                escape(comment.person_name.as_deref().unwrap_or("Unknown")),
+                settings.format_timestamp(&comment.created_at),
+                if comment.is_pinned { " (pinned)" } else { "" },
+                rich_text_to_markdown(&comment.content)
+            ));
+        }
+        sections.push(text);
+    }
+
+    let mut markdown = sections.join("\n\n");
+    markdown.push('\n');
+    Ok(markdown)
+}
@@ -0,0 +1,154 @@
+//! Project share: a redacted, read-only JSON bundle of one project for external partners.
+//!
+//! 只包含合作方可见的状态信息：项目基本字段、状态时间线（不含备注与操作人）、成员姓名与角色。
+//! 不包含任何内部 ID、邮箱、备注、评论。bundle 由 sync 命令上传到 `shares/<uuid>.json`，
+//! 并以限时预签名 GET URL 的形式交给合作方（无需安装应用）。
+
+use crate::app::project_get;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::{Deserialize, Serialize};
+
+pub const PROJECT_SHARE_FORMAT: &str = "projex-project-share";
+pub const PROJECT_SHARE_VERSION: i32 = 1;
+pub const DEFAULT_SHARE_EXPIRY_HOURS: i64 = 72;
+/// SigV4 presigned URLs are valid for at most 7 days.
+pub const MAX_SHARE_EXPIRY_HOURS: i64 = 7 * 24;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectShareReq {
+    pub project_id: String,
+    /// Link lifetime in hours (1..=168), default 72.
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectShareResult {
+    pub url: String,
+    pub object_key: String,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectShareBundle {
+    pub format: String,
+    pub version: i32,
+    pub generated_at: String,
+    pub expires_at: String,
+    pub project: SharedProjectDto,
+    pub status_timeline: Vec<SharedStatusDto>,
+    pub team: Vec<SharedMemberDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedProjectDto {
+    pub name: String,
+    pub description: String,
+    pub current_status: String,
+    pub priority: i32,
+    pub country_code: String,
+    pub product_name: Option<String>,
+    pub partner_name: String,
+    pub owner_name: String,
+    pub start_date: Option<String>,
+    pub due_date: Option<String>,
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedStatusDto {
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub changed_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedMemberDto {
+    pub name: String,
+    pub role: String,
+    pub start_at: String,
+    pub end_at: Option<String>,
+}
+
+/// Validated link lifetime for `req`.
+pub fn project_share_expiry(req: &ProjectShareReq) -> Result<chrono::Duration, AppError> {
+    let hours = req.expires_in_hours.unwrap_or(DEFAULT_SHARE_EXPIRY_HOURS);
+    if !(1..=MAX_SHARE_EXPIRY_HOURS).contains(&hours) {
+        return Err(AppError::Validation(format!(
+            "expiresInHours must be between 1 and {}",
+            MAX_SHARE_EXPIRY_HOURS
+        )));
+    }
+    Ok(chrono::Duration::hours(hours))
+}
+
+/// Unguessable object key; the project id is deliberately not part of it.
+pub fn project_share_object_key() -> String {
+    format!("shares/{}.json", uuid::Uuid::new_v4())
+}
+
+/// Build the redacted bundle for `project_id`.
+pub fn project_share_bundle(
+    pool: &DbPool,
+    project_id: &str,
+    generated_at: chrono::DateTime<chrono::Utc>,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<ProjectShareBundle, AppError> {
+    let detail = project_get(pool, project_id)?;
+
+    let mut status_timeline: Vec<SharedStatusDto> = detail
+        .status_history
+        .into_iter()
+        .map(|h| SharedStatusDto {
+            from_status: h.from_status,
+            to_status: h.to_status,
+            changed_at: h.changed_at,
+        })
+        .collect();
+    status_timeline.sort_by(|a, b| a.changed_at.cmp(&b.changed_at));
+
+    let team = detail
+        .assignments
+        .into_iter()
+        .map(|a| SharedMemberDto {
+            name: a.person_name,
+            role: a.role,
+            start_at: a.start_at,
+            end_at: a.end_at,
+        })
+        .collect();
+
+    Ok(ProjectShareBundle {
+        format: PROJECT_SHARE_FORMAT.to_string(),
+        version: PROJECT_SHARE_VERSION,
+        generated_at: generated_at.to_rfc3339(),
+        expires_at: expires_at.to_rfc3339(),
+        project: SharedProjectDto {
+            name: detail.name,
+            description: detail.description,
+            current_status: detail.current_status,
+            priority: detail.priority,
+            country_code: detail.country_code,
+            product_name: detail.product_name,
+            partner_name: detail.partner_name,
+            owner_name: detail.owner_name,
+            start_date: detail.start_date,
+            due_date: detail.due_date,
+            tags: detail.tags,
+            created_at: detail.created_at,
+            updated_at: detail.updated_at,
+            archived_at: detail.archived_at,
+        },
+        status_timeline,
+        team,
+    })
+}
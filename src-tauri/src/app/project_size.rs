@@ -0,0 +1,248 @@
+//! Project size estimates (story points) and size-weighted throughput.
+//!
+//! - 尺寸使用固定刻度：Fibonacci 0/1/2/3/5/8/13/21，T-shirt XS/S/M/L/XL 作为 1/2/3/5/8 的别名。
+//! - 每次尺寸变化写入 `project_size_history`（只追加，随同步传播）；值未变化时不记录。
+//! - 吞吐统计按 `FormatSettings.week_start` 分周，统计每个项目最近一次进入 DONE 的时间；
+//!   点数取完成时的估算（完成后才估算的项目取当前估算），未估算的项目单独计数。
+
+use crate::app::{load_format_settings, project_get, ProjectDetailDto};
+use crate::domain::{is_valid_size, parse_size, ProjectStatus};
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::{DateTime, Local, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const DEFAULT_THROUGHPUT_WEEKS: u32 = 12;
+pub const MAX_THROUGHPUT_WEEKS: u32 = 104;
+
+/// A size given as points (`5`) or as a label (`"M"`, `"13"`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SizeInput {
+    Points(i64),
+    Label(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSetSizeReq {
+    pub project_id: String,
+    /// New size; `None` clears the estimate.
+    pub size: Option<SizeInput>,
+    pub note: Option<String>,
+    pub changed_by_person_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SizeHistoryDto {
+    pub id: String,
+    pub project_id: String,
+    pub from_points: Option<i64>,
+    pub to_points: Option<i64>,
+    pub changed_at: String,
+    pub changed_by_person_id: Option<String>,
+    pub changed_by_name: Option<String>,
+    pub note: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThroughputReq {
+    /// Number of weeks ending with the current one. Default 12, max 104.
+    pub weeks: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThroughputWeekDto {
+    /// First day of the week (`YYYY-MM-DD`).
+    pub week_start: String,
+    /// Week range formatted with the user's date format.
+    pub label: String,
+    pub completed: i64,
+    pub points: i64,
+    /// Completed projects without a size estimate.
+    pub unestimated: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThroughputDto {
+    /// Oldest week first.
+    pub weeks: Vec<ThroughputWeekDto>,
+    pub total_completed: i64,
+    pub total_points: i64,
+    /// Size-weighted velocity: points completed per week over the range.
+    pub avg_points_per_week: f64,
+}
+
+fn resolve_size(input: Option<&SizeInput>) -> Result<Option<i64>, AppError> {
+    match input {
+        None => Ok(None),
+        Some(SizeInput::Points(points)) if is_valid_size(*points) => Ok(Some(*points)),
+        Some(SizeInput::Label(label)) if label.trim().is_empty() => Ok(None),
+        Some(SizeInput::Label(label)) => parse_size(label).map(Some).ok_or_else(|| {
+            AppError::Validation(format!(
+                "SIZE_INVALID: '{}' is not on the size scale",
+                label.trim()
+            ))
+        }),
+        Some(SizeInput::Points(points)) => Err(AppError::Validation(format!(
+            "SIZE_INVALID: {} is not on the size scale",
+            points
+        ))),
+    }
+}
+
+pub fn project_set_size(
+    pool: &DbPool,
+    req: ProjectSetSizeReq,
+) -> Result<ProjectDetailDto, AppError> {
+    let to_points = resolve_size(req.size.as_ref())?;
+
+    {
+        let conn = get_connection(pool);
+        let tx = conn.unchecked_transaction()?;
+
+        let from_points: Option<i64> = tx
+            .query_row(
+                "SELECT size_points FROM projects WHERE id = ?1 AND deleted_at IS NULL",
+                [&req.project_id],
+                |r| r.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("project {}", req.project_id)))?;
+
+        if from_points != to_points {
+            let now = chrono::Utc::now().to_rfc3339();
+            tx.execute(
+                "INSERT INTO project_size_history (id, project_id, from_points, to_points, changed_at, changed_by_person_id, note) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    Uuid::new_v4().to_string(),
+                    &req.project_id,
+                    from_points,
+                    to_points,
+                    &now,
+                    req.changed_by_person_id,
+                    req.note.as_deref().unwrap_or("").trim()
+                ],
+            )?;
+            tx.execute(
+                "UPDATE projects SET size_points = ?1, updated_at = ?2 WHERE id = ?3",
+                params![to_points, &now, &req.project_id],
+            )?;
+        }
+        tx.commit()?;
+    } // release conn before project_get to avoid deadlock
+    project_get(pool, &req.project_id)
+}
+
+/// Size changes of one project, newest first.
+pub(crate) fn project_size_history(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<Vec<SizeHistoryDto>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT h.id, h.project_id, h.from_points, h.to_points, h.changed_at, h.changed_by_person_id, p.display_name, h.note FROM project_size_history h LEFT JOIN persons p ON p.id = h.changed_by_person_id WHERE h.project_id = ?1 ORDER BY h.changed_at DESC",
+    )?;
+    let rows = stmt.query_map([project_id], |r| {
+        Ok(SizeHistoryDto {
+            id: r.get(0)?,
+            project_id: r.get(1)?,
+            from_points: r.get(2)?,
+            to_points: r.get(3)?,
+            changed_at: r.get(4)?,
+            changed_by_person_id: r.get(5)?,
+            changed_by_name: r.get(6)?,
+            note: r.get(7)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Completed projects and points per week, for the `weeks` weeks ending with the current one.
+pub fn project_throughput(pool: &DbPool, req: ThroughputReq) -> Result<ThroughputDto, AppError> {
+    let weeks = req
+        .weeks
+        .unwrap_or(DEFAULT_THROUGHPUT_WEEKS)
+        .clamp(1, MAX_THROUGHPUT_WEEKS);
+    let today = Local::now().date_naive();
+    project_throughput_until(pool, today, weeks)
+}
+
+/// Same as [`project_throughput`] with an explicit "today" (local date).
+pub fn project_throughput_until(
+    pool: &DbPool,
+    today: NaiveDate,
+    weeks: u32,
+) -> Result<ThroughputDto, AppError> {
+    let conn = get_read_connection(pool);
+    let settings = load_format_settings(&conn);
+    let last_week = settings.week_start_of(today);
+    let first_week = last_week - chrono::Duration::weeks(weeks as i64 - 1);
+
+    // Latest completion of each project, with the size estimated at that time.
+    let mut stmt = conn.prepare(
+        "SELECT h.changed_at,
+                COALESCE(
+                    (SELECT s.to_points FROM project_size_history s
+                     WHERE s.project_id = h.project_id AND s.changed_at <= h.changed_at
+                     ORDER BY s.changed_at DESC LIMIT 1),
+                    p.size_points
+                )
+         FROM status_history h
+         JOIN projects p ON p.id = h.project_id
+         WHERE h.to_status = ?1 AND p.deleted_at IS NULL
+           AND h.changed_at = (SELECT MAX(h2.changed_at) FROM status_history h2
+                               WHERE h2.project_id = h.project_id AND h2.to_status = ?1)",
+    )?;
+    let completions = stmt
+        .query_map([ProjectStatus::Done.as_str()], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, Option<i64>>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut buckets: HashMap<NaiveDate, (i64, i64, i64)> = HashMap::new();
+    for (changed_at, points) in completions {
+        let Ok(at) = DateTime::parse_from_rfc3339(changed_at.trim()) else {
+            continue;
+        };
+        let week = settings.week_start_of(at.with_timezone(&Local).date_naive());
+        if week < first_week || week > last_week {
+            continue;
+        }
+        let bucket = buckets.entry(week).or_default();
+        bucket.0 += 1;
+        match points {
+            Some(points) => bucket.1 += points,
+            None => bucket.2 += 1,
+        }
+    }
+
+    let weeks: Vec<ThroughputWeekDto> = (0..weeks as i64)
+        .map(|i| {
+            let week = first_week + chrono::Duration::weeks(i);
+            let (completed, points, unestimated) = buckets.get(&week).copied().unwrap_or_default();
+            ThroughputWeekDto {
+                week_start: week.format("%Y-%m-%d").to_string(),
+                label: settings.week_label(week),
+                completed,
+                points,
+                unestimated,
+            }
+        })
+        .collect();
+    let total_completed = weeks.iter().map(|w| w.completed).sum();
+    let total_points: i64 = weeks.iter().map(|w| w.points).sum();
+    let avg_points_per_week = total_points as f64 / weeks.len() as f64;
+
+    Ok(ThroughputDto {
+        weeks,
+        total_completed,
+        total_points,
+        avg_points_per_week,
+    })
+}
@@ -0,0 +1,152 @@
+//! Project hierarchy: sub-projects as a tree with rolled-up status counts.
+//!
+//! - 父项目不存在（已删除 / 尚未同步到本机）或被过滤掉（已归档）时，子项目作为顶层节点展示，不会丢失。
+//! - 两台设备并发调整父子关系可能在同步后形成环；环上的项目从其中一个节点断开后作为顶层展示。
+//! - `rollupStatusCounts` 统计节点自身及全部后代（按 `current_status` 计数）。
+
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTreeReq {
+    /// Only this project's subtree; none = the whole forest.
+    pub root_project_id: Option<String>,
+    /// Default true: archived projects are left out (their children move up to the top level).
+    pub only_unarchived: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTreeNode {
+    pub id: String,
+    pub name: String,
+    pub current_status: String,
+    pub priority: i32,
+    pub owner_name: String,
+    pub due_date: Option<String>,
+    pub parent_project_id: Option<String>,
+    /// Status -> number of projects in this subtree (the node included).
+    pub rollup_status_counts: BTreeMap<String, i64>,
+    pub descendant_count: i64,
+    pub children: Vec<ProjectTreeNode>,
+}
+
+struct TreeRow {
+    id: String,
+    name: String,
+    current_status: String,
+    priority: i32,
+    owner_name: String,
+    due_date: Option<String>,
+    parent_project_id: Option<String>,
+}
+
+pub fn project_tree(pool: &DbPool, req: ProjectTreeReq) -> Result<Vec<ProjectTreeNode>, AppError> {
+    let only_unarchived = req.only_unarchived.unwrap_or(true);
+    let root_id = req
+        .root_project_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let rows = {
+        let conn = get_read_connection(pool);
+        let mut stmt = conn.prepare(
+            "SELECT p.id, p.name, p.current_status, p.priority, COALESCE(pe.display_name, '?'), p.due_date, p.parent_project_id
+             FROM projects p LEFT JOIN persons pe ON pe.id = p.owner_person_id
+             WHERE p.deleted_at IS NULL AND (?1 = 0 OR p.current_status <> 'ARCHIVED' OR p.id = ?2)
+             ORDER BY p.name COLLATE NOCASE, p.id",
+        )?;
+        let rows = stmt
+            .query_map(rusqlite::params![only_unarchived as i32, root_id], |r| {
+                Ok(TreeRow {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    current_status: r.get(2)?,
+                    priority: r.get(3)?,
+                    owner_name: r.get(4)?,
+                    due_date: r.get(5)?,
+                    parent_project_id: r.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    let index: HashMap<&str, usize> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| (row.id.as_str(), i))
+        .collect();
+    let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        match row
+            .parent_project_id
+            .as_deref()
+            .and_then(|parent| index.get(parent))
+        {
+            Some(&parent) if parent != i => children.entry(parent).or_default().push(i),
+            _ => roots.push(i),
+        }
+    }
+
+    let mut visited = HashSet::new();
+    if let Some(root_id) = root_id {
+        let &root = index
+            .get(root_id)
+            .ok_or_else(|| AppError::NotFound(format!("project {}", root_id)))?;
+        return Ok(vec![build_node(&rows, &children, root, &mut visited)]);
+    }
+
+    let mut forest: Vec<ProjectTreeNode> = roots
+        .into_iter()
+        .map(|root| build_node(&rows, &children, root, &mut visited))
+        .collect();
+    // Whatever is left is only reachable through a cycle: break it at its first member.
+    for i in 0..rows.len() {
+        if !visited.contains(&i) {
+            forest.push(build_node(&rows, &children, i, &mut visited));
+        }
+    }
+    Ok(forest)
+}
+
+fn build_node(
+    rows: &[TreeRow],
+    children: &HashMap<usize, Vec<usize>>,
+    i: usize,
+    visited: &mut HashSet<usize>,
+) -> ProjectTreeNode {
+    visited.insert(i);
+    let row = &rows[i];
+    let mut rollup_status_counts = BTreeMap::from([(row.current_status.clone(), 1)]);
+    let mut descendant_count = 0;
+    let mut nodes = Vec::new();
+    for &child in children.get(&i).map(Vec::as_slice).unwrap_or_default() {
+        if visited.contains(&child) {
+            continue;
+        }
+        let node = build_node(rows, children, child, visited);
+        for (status, count) in &node.rollup_status_counts {
+            *rollup_status_counts.entry(status.clone()).or_insert(0) += count;
+        }
+        descendant_count += node.descendant_count + 1;
+        nodes.push(node);
+    }
+    ProjectTreeNode {
+        id: row.id.clone(),
+        name: row.name.clone(),
+        current_status: row.current_status.clone(),
+        priority: row.priority,
+        owner_name: row.owner_name.clone(),
+        due_date: row.due_date.clone(),
+        parent_project_id: row.parent_project_id.clone(),
+        rollup_status_counts,
+        descendant_count,
+        children: nodes,
+    }
+}
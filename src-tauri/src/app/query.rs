@@ -0,0 +1,363 @@
+//! Scripting surface: read-only, parameterized SQL over the local database.
+//!
+//! - 只接受单条 `SELECT` / `WITH` / `VALUES` 语句；SQLite 判定为非只读的语句一律拒绝，
+//!   执行期间连接处于 `PRAGMA query_only`。
+//! - 参数：数组为位置参数（`?` / `?1`），对象为命名参数（`:name`，键可省略前缀）；值只能是标量。
+//! - 含凭据的表（`QUERY_BLOCKED_TABLES`）不可读取：根据 `EXPLAIN` 字节码中实际打开的表判断，
+//!   视图、别名、引号写法都无法绕过。
+//! - 每次最多返回 `max_rows` 行（默认 `DEFAULT_QUERY_ROWS`，上限 `MAX_QUERY_ROWS`）；
+//!   超过 `QUERY_TIMEOUT` 的查询被中断。BLOB 以小写十六进制字符串返回。
+//! - `query_schema` 给出可查询的表、列与关系（与 `db_schema_export` 的 JSON 结构相同）。
+
+use crate::app::schema::{load_relations, load_tables};
+use crate::app::{SchemaRelationDto, SchemaTableDto};
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{Connection, Statement};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Tables holding credentials (S3 keys, webhook secrets, API tokens).
+pub const QUERY_BLOCKED_TABLES: &[&str] = &["sync_config", "webhooks"];
+pub const DEFAULT_QUERY_ROWS: usize = 200;
+pub const MAX_QUERY_ROWS: usize = 5000;
+pub const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteQueryReq {
+    pub sql: String,
+    /// Array (positional) or object (named); omitted = no parameters.
+    pub params: Option<Value>,
+    /// Default `DEFAULT_QUERY_ROWS`, at most `MAX_QUERY_ROWS`.
+    pub max_rows: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResultDto {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// More rows were available than `max_rows`.
+    pub truncated: bool,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuerySchemaDto {
+    /// Latest applied migration.
+    pub schema_version: i64,
+    /// Queryable tables (blocked tables are omitted).
+    pub tables: Vec<SchemaTableDto>,
+    pub relations: Vec<SchemaRelationDto>,
+    pub blocked_tables: Vec<String>,
+    pub max_rows: usize,
+    pub timeout_ms: u64,
+}
+
+pub fn query_schema(pool: &DbPool) -> Result<QuerySchemaDto, AppError> {
+    let conn = get_read_connection(pool);
+    let schema_version: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |r| r.get(0),
+    )?;
+    let tables: Vec<SchemaTableDto> = load_tables(&conn)?
+        .into_iter()
+        .filter(|t| !is_blocked(&t.name))
+        .collect();
+    let relations = load_relations(&conn, &tables)?
+        .into_iter()
+        .filter(|r| !is_blocked(&r.from_table) && !is_blocked(&r.to_table))
+        .collect();
+    Ok(QuerySchemaDto {
+        schema_version,
+        tables,
+        relations,
+        blocked_tables: QUERY_BLOCKED_TABLES.iter().map(|t| t.to_string()).collect(),
+        max_rows: MAX_QUERY_ROWS,
+        timeout_ms: QUERY_TIMEOUT.as_millis() as u64,
+    })
+}
+
+pub fn execute_query(pool: &DbPool, req: ExecuteQueryReq) -> Result<QueryResultDto, AppError> {
+    let sql = req.sql.trim();
+    check_single_select(sql)?;
+    let max_rows = req
+        .max_rows
+        .unwrap_or(DEFAULT_QUERY_ROWS)
+        .clamp(1, MAX_QUERY_ROWS);
+
+    let started = Instant::now();
+    let conn = get_read_connection(pool);
+    check_tables(&conn, sql)?;
+
+    // Pooled readers are already query-only; only the writer fallback needs toggling back.
+    let was_query_only: bool = conn.query_row("PRAGMA query_only", [], |row| row.get(0))?;
+    if !was_query_only {
+        conn.execute_batch("PRAGMA query_only = ON")?;
+    }
+    let result = run_with_timeout(&conn, || {
+        let mut stmt = conn.prepare(sql).map_err(invalid_sql)?;
+        if !stmt.readonly() {
+            return Err(AppError::Validation(
+                "only read-only statements are allowed".into(),
+            ));
+        }
+        bind_params(&mut stmt, req.params.as_ref())?;
+        read_rows(&mut stmt, max_rows)
+    });
+    if !was_query_only {
+        conn.execute_batch("PRAGMA query_only = OFF")?;
+    }
+    let (columns, rows, truncated) = result?;
+    Ok(QueryResultDto {
+        columns,
+        rows,
+        truncated,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Reject anything but one SELECT / WITH / VALUES statement (comments and a trailing `;` are fine).
+fn check_single_select(sql: &str) -> Result<(), AppError> {
+    let tokens = scan(sql)?;
+    let first = tokens.first().map(|t| t.to_ascii_uppercase());
+    if !matches!(first.as_deref(), Some("SELECT" | "WITH" | "VALUES")) {
+        return Err(AppError::Validation(
+            "only SELECT, WITH or VALUES statements are allowed".into(),
+        ));
+    }
+    if let Some(pos) = tokens.iter().position(|t| t == ";") {
+        if pos + 1 < tokens.len() {
+            return Err(AppError::Validation(
+                "only a single statement is allowed".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Split into coarse tokens: words, `;`, and opaque literals; comments are dropped.
+fn scan(sql: &str) -> Result<Vec<String>, AppError> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(AppError::Validation("unterminated comment".into()));
+            }
+            i += 2;
+        } else if matches!(c, '\'' | '"' | '`' | '[') {
+            let close = if c == '[' { ']' } else { c };
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(AppError::Validation("unterminated quote".into())),
+                    // Doubled quote = escaped quote.
+                    Some(&q) if q == close && close != ']' && chars.get(i + 1) == Some(&close) => {
+                        i += 2
+                    }
+                    Some(&q) if q == close => break,
+                    Some(_) => i += 1,
+                }
+            }
+            i += 1;
+            tokens.push("<literal>".to_string());
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// Inspect the compiled program: every b-tree the statement opens must belong to an allowed table.
+fn check_tables(conn: &Connection, sql: &str) -> Result<(), AppError> {
+    let root_pages: HashMap<i64, String> = conn
+        .prepare("SELECT rootpage, tbl_name FROM sqlite_master WHERE rootpage > 0")?
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let sql = sql.trim_end().trim_end_matches(';');
+    let mut stmt = conn
+        .prepare(&format!("EXPLAIN {}", sql))
+        .map_err(invalid_sql)?;
+    let mut rows = stmt.raw_query();
+    while let Some(row) = rows.next()? {
+        let opcode: String = row.get(1)?;
+        let root_page: i64 = row.get(3)?;
+        let database: i64 = row.get(4)?;
+        match opcode.as_str() {
+            "OpenWrite" => {
+                return Err(AppError::Validation(
+                    "only read-only statements are allowed".into(),
+                ))
+            }
+            "OpenRead" | "ReopenIdx" if database == 0 => {
+                if let Some(table) = root_pages.get(&root_page) {
+                    if is_blocked(table) {
+                        return Err(AppError::Validation(format!(
+                            "table {} cannot be queried",
+                            table
+                        )));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn bind_params(stmt: &mut Statement<'_>, params: Option<&Value>) -> Result<(), AppError> {
+    let expected = stmt.parameter_count();
+    match params {
+        None | Some(Value::Null) => {
+            if expected > 0 {
+                return Err(AppError::Validation(format!(
+                    "query expects {} parameter(s)",
+                    expected
+                )));
+            }
+        }
+        Some(Value::Array(values)) => {
+            if values.len() != expected {
+                return Err(AppError::Validation(format!(
+                    "query expects {} parameter(s), got {}",
+                    expected,
+                    values.len()
+                )));
+            }
+            for (i, value) in values.iter().enumerate() {
+                stmt.raw_bind_parameter(i + 1, to_sql_value(value)?)?;
+            }
+        }
+        Some(Value::Object(map)) => {
+            for (key, value) in map {
+                let name = if key.starts_with([':', '@', '$']) {
+                    key.clone()
+                } else {
+                    format!(":{}", key)
+                };
+                let index = stmt
+                    .parameter_index(&name)?
+                    .ok_or_else(|| AppError::Validation(format!("unknown parameter: {}", name)))?;
+                stmt.raw_bind_parameter(index, to_sql_value(value)?)?;
+            }
+        }
+        Some(_) => {
+            return Err(AppError::Validation(
+                "params must be an array or an object".into(),
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn to_sql_value(value: &Value) -> Result<SqlValue, AppError> {
+    Ok(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(*b as i64),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(_) | Value::Object(_) => {
+            return Err(AppError::Validation(
+                "parameter values must be scalars".into(),
+            ))
+        }
+    })
+}
+
+/// Columns, at most `max_rows` rows, and whether more rows were available.
+fn read_rows(
+    stmt: &mut Statement<'_>,
+    max_rows: usize,
+) -> Result<(Vec<String>, Vec<Vec<Value>>, bool), AppError> {
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let mut rows = stmt.raw_query();
+    let mut out = Vec::new();
+    let mut truncated = false;
+    while let Some(row) = rows.next()? {
+        if out.len() == max_rows {
+            truncated = true;
+            break;
+        }
+        let mut values = Vec::with_capacity(columns.len());
+        for i in 0..columns.len() {
+            values.push(match row.get_ref(i)? {
+                ValueRef::Null => Value::Null,
+                ValueRef::Integer(v) => Value::from(v),
+                ValueRef::Real(v) => Value::from(v),
+                ValueRef::Text(v) => Value::from(String::from_utf8_lossy(v).into_owned()),
+                ValueRef::Blob(v) => {
+                    Value::from(v.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+                }
+            });
+        }
+        out.push(values);
+    }
+    Ok((columns, out, truncated))
+}
+
+/// Run `f`, interrupting the connection if it takes longer than `QUERY_TIMEOUT`.
+fn run_with_timeout<T>(
+    conn: &Connection,
+    f: impl FnOnce() -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let interrupt = conn.get_interrupt_handle();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let watchdog = std::thread::spawn(move || {
+        if done_rx.recv_timeout(QUERY_TIMEOUT).is_err() {
+            interrupt.interrupt();
+            return true;
+        }
+        false
+    });
+    let result = f();
+    let _ = done_tx.send(());
+    let timed_out = watchdog.join().unwrap_or(false);
+    match result {
+        Err(_) if timed_out => Err(AppError::Validation(format!(
+            "query timed out after {}s",
+            QUERY_TIMEOUT.as_secs()
+        ))),
+        other => other,
+    }
+}
+
+fn invalid_sql(e: rusqlite::Error) -> AppError {
+    AppError::Validation(format!("invalid query: {}", e))
+}
+
+fn is_blocked(table: &str) -> bool {
+    QUERY_BLOCKED_TABLES
+        .iter()
+        .any(|t| t.eq_ignore_ascii_case(table))
+}
@@ -0,0 +1,336 @@
+//! Recurring projects: interval rules that instantiate a template when due.
+//!
+//! - 规则：每 N 天 / 周 / 月，从 `anchor_on` 起算；月份按锚点日计算（1/31 → 2/28 → 3/31），不累积漂移。
+//! - 到期检查由后台 `RecurrenceRuntime` 每小时执行一次（启动时立即执行一次）。
+//! - 应用未运行期间错过的多个周期只补建一个实例，`next_run_on` 直接跳到今天之后的下一个周期。
+//! - 实例化失败（例如名称冲突）记录在 `last_error`，规则照常前进，避免每小时重复失败。
+//! - 新项目初始状态历史的备注记录来源规则。
+//! - 只读副本上不执行到期检查（项目只来自同步），关闭副本模式后按上面的规则补建。
+
+use crate::app::template::{instantiate_template, load_template};
+use crate::app::{ProjectCreateFromTemplateReq, ProjectDetailDto};
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use crate::sync::replica::is_read_only_replica;
+use chrono::{Months, NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Serializes due checks (hourly runtime vs. commands) so a rule never runs twice.
+static RUN_DUE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecurrenceUnit {
+    Day,
+    Week,
+    Month,
+}
+
+impl RecurrenceUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceRuleDto {
+    pub id: String,
+    pub template_id: String,
+    pub template_name: Option<String>,
+    pub name: String,
+    pub interval_unit: RecurrenceUnit,
+    pub interval_count: i64,
+    pub anchor_on: String,
+    pub next_run_on: String,
+    pub due_offset_days: Option<i64>,
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    pub last_project_id: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceRuleCreateReq {
+    pub template_id: String,
+    pub name: String,
+    pub interval_unit: RecurrenceUnit,
+    /// >= 1
+    pub interval_count: i64,
+    /// First run date (YYYY-MM-DD); also the anchor for later runs.
+    pub start_on: String,
+    /// Due date = run date + offset (days, >= 0); none leaves the due date empty.
+    pub due_offset_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceRuleUpdateReq {
+    pub id: String,
+    pub enabled: Option<bool>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceRunItem {
+    pub rule_id: String,
+    pub run_on: String,
+    pub project_id: Option<String>,
+    pub error: Option<String>,
+}
+
+pub fn recurrence_rule_create(
+    pool: &DbPool,
+    req: RecurrenceRuleCreateReq,
+) -> Result<RecurrenceRuleDto, AppError> {
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".into()));
+    }
+    if req.interval_count < 1 {
+        return Err(AppError::Validation("intervalCount must be >= 1".into()));
+    }
+    if req.due_offset_days.is_some_and(|d| d < 0) {
+        return Err(AppError::Validation("dueOffsetDays must be >= 0".into()));
+    }
+    let start_on = parse_date(&req.start_on)?;
+
+    let conn = get_connection(pool);
+    load_template(&conn, &req.template_id)?;
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO recurrence_rules (id, template_id, name, interval_unit, interval_count, anchor_on, next_run_on, due_offset_days, enabled, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7, 1, ?8, ?8)",
+        params![
+            &id,
+            &req.template_id,
+            &name,
+            req.interval_unit.as_str(),
+            req.interval_count,
+            start_on.format("%Y-%m-%d").to_string(),
+            req.due_offset_days,
+            &now
+        ],
+    )?;
+    load_rule(&conn, &id)
+}
+
+pub fn recurrence_rule_update(
+    pool: &DbPool,
+    req: RecurrenceRuleUpdateReq,
+) -> Result<RecurrenceRuleDto, AppError> {
+    let conn = get_connection(pool);
+    load_rule(&conn, &req.id)?;
+    let now = Utc::now().to_rfc3339();
+    if let Some(name) = req.name.as_deref().map(str::trim) {
+        if name.is_empty() {
+            return Err(AppError::Validation("name is required".into()));
+        }
+        conn.execute(
+            "UPDATE recurrence_rules SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, &now, &req.id],
+        )?;
+    }
+    if let Some(enabled) = req.enabled {
+        conn.execute(
+            "UPDATE recurrence_rules SET enabled = ?1, updated_at = ?2 WHERE id = ?3",
+            params![enabled as i32, &now, &req.id],
+        )?;
+    }
+    load_rule(&conn, &req.id)
+}
+
+pub fn recurrence_rule_delete(pool: &DbPool, rule_id: &str) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let changed = conn.execute("DELETE FROM recurrence_rules WHERE id = ?1", [rule_id])?;
+    if changed == 0 {
+        return Err(AppError::NotFound(format!("recurrence rule {}", rule_id)));
+    }
+    Ok(())
+}
+
+pub fn recurrence_rule_get(pool: &DbPool, rule_id: &str) -> Result<RecurrenceRuleDto, AppError> {
+    let conn = get_read_connection(pool);
+    load_rule(&conn, rule_id)
+}
+
+pub fn recurrence_rule_list(pool: &DbPool) -> Result<Vec<RecurrenceRuleDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let ids = conn
+        .prepare("SELECT id FROM recurrence_rules ORDER BY next_run_on, name")?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    ids.iter().map(|id| load_rule(&conn, id)).collect()
+}
+
+/// Instantiate every enabled rule due on or before `today` (one instance per rule).
+pub fn recurrence_run_due(
+    pool: &DbPool,
+    today: NaiveDate,
+) -> Result<Vec<RecurrenceRunItem>, AppError> {
+    let _guard = RUN_DUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let due = {
+        let conn = get_connection(pool);
+        // Read-only replica: projects only arrive from sync; rules stay due until writable again.
+        if is_read_only_replica(&conn)? {
+            return Ok(Vec::new());
+        }
+        let ids = conn
+            .prepare(
+                "SELECT id FROM recurrence_rules WHERE enabled = 1 AND next_run_on <= ?1 ORDER BY next_run_on",
+            )?
+            .query_map([today.format("%Y-%m-%d").to_string()], |r| {
+                r.get::<_, String>(0)
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        ids.iter()
+            .map(|id| load_rule(&conn, id))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut items = Vec::new();
+    for rule in due {
+        let run_on = parse_date(&rule.next_run_on)?;
+        let result = run_rule(pool, &rule, run_on);
+        let next = next_run_after(
+            parse_date(&rule.anchor_on)?,
+            rule.interval_unit,
+            rule.interval_count,
+            today,
+        );
+        let now = Utc::now().to_rfc3339();
+        let (project_id, error) = match result {
+            Ok(project) => (Some(project.id), None),
+            Err(e) => {
+                log::warn!("Recurrence rule {} failed: {}", rule.id, e);
+                (None, Some(e.to_string()))
+            }
+        };
+        let conn = get_connection(pool);
+        conn.execute(
+            "UPDATE recurrence_rules SET next_run_on = ?1, last_run_at = ?2, last_project_id = COALESCE(?3, last_project_id), last_error = ?4, updated_at = ?2 WHERE id = ?5",
+            params![
+                next.format("%Y-%m-%d").to_string(),
+                &now,
+                &project_id,
+                &error,
+                &rule.id
+            ],
+        )?;
+        items.push(RecurrenceRunItem {
+            rule_id: rule.id,
+            run_on: run_on.format("%Y-%m-%d").to_string(),
+            project_id,
+            error,
+        });
+    }
+    Ok(items)
+}
+
+/// First occurrence `anchor + k * interval` (k >= 1) strictly after `after`.
+pub fn next_run_after(
+    anchor: NaiveDate,
+    unit: RecurrenceUnit,
+    count: i64,
+    after: NaiveDate,
+) -> NaiveDate {
+    let count = count.max(1);
+    let mut k: i64 = 1;
+    loop {
+        let candidate = occurrence(anchor, unit, count * k);
+        match candidate {
+            Some(date) if date > after => return date,
+            Some(_) => k += 1,
+            None => return NaiveDate::MAX,
+        }
+    }
+}
+
+fn occurrence(anchor: NaiveDate, unit: RecurrenceUnit, steps: i64) -> Option<NaiveDate> {
+    match unit {
+        RecurrenceUnit::Day => anchor.checked_add_signed(chrono::Duration::days(steps)),
+        RecurrenceUnit::Week => anchor.checked_add_signed(chrono::Duration::weeks(steps)),
+        RecurrenceUnit::Month => anchor.checked_add_months(Months::new(u32::try_from(steps).ok()?)),
+    }
+}
+
+fn run_rule(
+    pool: &DbPool,
+    rule: &RecurrenceRuleDto,
+    run_on: NaiveDate,
+) -> Result<ProjectDetailDto, AppError> {
+    let due_date = rule.due_offset_days.map(|days| {
+        (run_on + chrono::Duration::days(days))
+            .format("%Y-%m-%d")
+            .to_string()
+    });
+    instantiate_template(
+        pool,
+        ProjectCreateFromTemplateReq {
+            template_id: rule.template_id.clone(),
+            start_date: Some(run_on.format("%Y-%m-%d").to_string()),
+            due_date,
+            ..Default::default()
+        },
+        run_on,
+        Some(&format!("Created by recurrence rule \"{}\"", rule.name)),
+    )
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::Validation(format!("invalid date (YYYY-MM-DD): {}", value)))
+}
+
+fn load_rule(conn: &Connection, id: &str) -> Result<RecurrenceRuleDto, AppError> {
+    let rule = conn
+        .query_row(
+            "SELECT r.id, r.template_id, t.name, r.name, r.interval_unit, r.interval_count, r.anchor_on, r.next_run_on, r.due_offset_days, r.enabled, r.last_run_at, r.last_project_id, r.last_error, r.created_at, r.updated_at
+             FROM recurrence_rules r LEFT JOIN project_templates t ON t.id = r.template_id
+             WHERE r.id = ?1",
+            [id],
+            |r| {
+                let unit: String = r.get(4)?;
+                Ok(RecurrenceRuleDto {
+                    id: r.get(0)?,
+                    template_id: r.get(1)?,
+                    template_name: r.get(2)?,
+                    name: r.get(3)?,
+                    interval_unit: RecurrenceUnit::parse(&unit).unwrap_or(RecurrenceUnit::Month),
+                    interval_count: r.get(5)?,
+                    anchor_on: r.get(6)?,
+                    next_run_on: r.get(7)?,
+                    due_offset_days: r.get(8)?,
+                    enabled: r.get::<_, i32>(9)? != 0,
+                    last_run_at: r.get(10)?,
+                    last_project_id: r.get(11)?,
+                    last_error: r.get(12)?,
+                    created_at: r.get(13)?,
+                    updated_at: r.get(14)?,
+                })
+            },
+        )
+        .optional()?;
+    rule.ok_or_else(|| AppError::NotFound(format!("recurrence rule {}", id)))
+}
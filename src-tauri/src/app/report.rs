@@ -0,0 +1,708 @@
+//! Generated reports: partner scorecard (data + PDF rendering), partner engagement summary,
+//! status / throughput summary, per-person workload.
+//!
+//! - 报表数据以 DTO 返回，PDF 由 `infra::pdf` 渲染；日期按格式设置输出。
+//! - 逾期：未完成（非 DONE / ARCHIVED）且 `due_date` 早于报表日期。
+//! - 按期完成率：已完成且有截止日期的项目中，最近一次进入 DONE 的日期不晚于截止日期的比例。
+//! - 汇总：状态按生效流程（`workflow_definitions`）的顺序列出，不在流程中的状态只计入总数；
+//!   状态停留时间取 status_history 相邻两条记录的间隔，只统计结束日期落在区间内的停留；
+//!   吞吐量按周（周一开始）统计进入 DONE 的次数。日期均按本地时区计算，区间两端都包含。
+//! - 合作方汇总：项目时长为创建到最近一次进入 DONE 的天数（只统计进入过 DONE 的项目）；
+//!   最近活动取项目更新时间与状态变更时间中最晚的一个。
+//! - 工作量：每个启用的成员参与（进行中的 assignment）或负责的未归档项目；回收站中的项目不计入。
+
+use crate::app::workflow::load_active_workflow;
+use crate::app::{load_format_settings, FormatSettings};
+use crate::domain::ProjectStatus;
+use crate::error::AppError;
+use crate::infra::pdf::PdfText;
+use crate::infra::{get_read_connection, DbPool};
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Window for "completed recently" in the scorecard.
+pub const SCORECARD_RECENT_DAYS: i64 = 90;
+
+/// Default summary range: the last 12 weeks up to today.
+pub const SUMMARY_DEFAULT_DAYS: i64 = 84;
+
+/// Default look-ahead for "upcoming due dates" in the workload report.
+pub const WORKLOAD_UPCOMING_DAYS: i64 = 14;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScorecardProjectDto {
+    pub id: String,
+    pub name: String,
+    pub current_status: String,
+    pub owner_name: String,
+    pub due_date: Option<String>,
+    /// Local date of the latest move to DONE.
+    pub completed_on: Option<String>,
+    pub overdue: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerScorecardDto {
+    pub partner_id: String,
+    pub partner_name: String,
+    /// Report date (`YYYY-MM-DD`).
+    pub as_of: String,
+    pub total: i64,
+    pub active: i64,
+    pub done: i64,
+    pub archived: i64,
+    pub overdue: i64,
+    /// Moved to DONE within the last `SCORECARD_RECENT_DAYS` days.
+    pub completed_recently: i64,
+    /// Share of completed projects (with a due date) finished on time; `None` if there are none.
+    pub on_time_rate: Option<f64>,
+    /// All projects of the partner except archived ones, by due date.
+    pub projects: Vec<ScorecardProjectDto>,
+}
+
+pub fn partner_scorecard(
+    pool: &DbPool,
+    partner_id: &str,
+    today: NaiveDate,
+) -> Result<PartnerScorecardDto, AppError> {
+    let conn = get_read_connection(pool);
+    partner_scorecard_conn(&conn, partner_id, today)
+}
+
+pub(crate) fn partner_scorecard_conn(
+    conn: &Connection,
+    partner_id: &str,
+    today: NaiveDate,
+) -> Result<PartnerScorecardDto, AppError> {
+    let partner_name: String = conn
+        .query_row(
+            "SELECT name FROM partners WHERE id = ?1 AND deleted_at IS NULL",
+            [partner_id],
+            |r| r.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("partner {}", partner_id)))?;
+
+    let mut stmt = conn.prepare(
+        "SELECT p.id, p.name, p.current_status, COALESCE(pe.display_name, '?'), p.due_date,
+                (SELECT MAX(h.changed_at) FROM status_history h
+                 WHERE h.project_id = p.id AND h.to_status = ?2)
+         FROM projects p
+         LEFT JOIN persons pe ON pe.id = p.owner_person_id
+         WHERE p.partner_id = ?1 AND p.deleted_at IS NULL
+         ORDER BY CASE WHEN p.due_date IS NULL THEN 1 ELSE 0 END, p.due_date, p.name",
+    )?;
+    let rows = stmt
+        .query_map([partner_id, ProjectStatus::Done.as_str()], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, Option<String>>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let recent_from = today - chrono::Duration::days(SCORECARD_RECENT_DAYS);
+    let mut card = PartnerScorecardDto {
+        partner_id: partner_id.to_string(),
+        partner_name,
+        as_of: today.format("%Y-%m-%d").to_string(),
+        total: 0,
+        active: 0,
+        done: 0,
+        archived: 0,
+        overdue: 0,
+        completed_recently: 0,
+        on_time_rate: None,
+        projects: Vec::new(),
+    };
+    let (mut with_due, mut on_time) = (0i64, 0i64);
+    for (id, name, status, owner_name, due_date, done_at) in rows {
+        card.total += 1;
+        let due = due_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok());
+        let completed_on = done_at
+            .as_deref()
+            .and_then(|at| DateTime::parse_from_rfc3339(at.trim()).ok())
+            .map(|at| at.with_timezone(&Local).date_naive());
+        let is_done = status == ProjectStatus::Done.as_str();
+        let is_archived = status == ProjectStatus::Archived.as_str();
+        let overdue = !is_done && !is_archived && due.is_some_and(|d| d < today);
+
+        if is_done {
+            card.done += 1;
+            if let (Some(due), Some(completed_on)) = (due, completed_on) {
+                with_due += 1;
+                if completed_on <= due {
+                    on_time += 1;
+                }
+            }
+        } else if is_archived {
+            card.archived += 1;
+        } else {
+            card.active += 1;
+        }
+        if overdue {
+            card.overdue += 1;
+        }
+        if is_done && completed_on.is_some_and(|d| d > recent_from && d <= today) {
+            card.completed_recently += 1;
+        }
+        if !is_archived {
+            card.projects.push(ScorecardProjectDto {
+                id,
+                name,
+                current_status: status,
+                owner_name,
+                due_date,
+                completed_on: completed_on.map(|d| d.format("%Y-%m-%d").to_string()),
+                overdue,
+            });
+        }
+    }
+    if with_due > 0 {
+        card.on_time_rate = Some(on_time as f64 / with_due as f64);
+    }
+    Ok(card)
+}
+
+/// Render a scorecard as a PDF document.
+pub fn render_partner_scorecard_pdf(
+    card: &PartnerScorecardDto,
+    settings: &FormatSettings,
+) -> Vec<u8> {
+    let mut doc = PdfText::new();
+    doc.heading(&format!("Partner scorecard: {}", card.partner_name));
+    doc.line(&format!("As of {}", settings.format_date_str(&card.as_of)));
+
+    doc.subheading("Summary");
+    doc.line(&format!(
+        "Projects: {}   Active: {}   Done: {}   Archived: {}",
+        card.total, card.active, card.done, card.archived
+    ));
+    doc.line(&format!("Overdue: {}", card.overdue));
+    doc.line(&format!(
+        "Completed in the last {} days: {}",
+        SCORECARD_RECENT_DAYS, card.completed_recently
+    ));
+    doc.line(&format!(
+        "On-time completion: {}",
+        card.on_time_rate
+            .map(|r| format!("{:.0}%", r * 100.0))
+            .unwrap_or_else(|| "n/a".to_string())
+    ));
+
+    doc.subheading("Projects");
+    if card.projects.is_empty() {
+        doc.line("No projects.");
+    }
+    for p in &card.projects {
+        let due = p
+            .due_date
+            .as_deref()
+            .map(|d| settings.format_date_str(d))
+            .unwrap_or_else(|| "-".to_string());
+        let mut line = format!(
+            "{} | {} | {} | due {}",
+            p.name, p.current_status, p.owner_name, due
+        );
+        if let Some(completed_on) = &p.completed_on {
+            line.push_str(&format!(
+                " | done {}",
+                settings.format_date_str(completed_on)
+            ));
+        }
+        if p.overdue {
+            line.push_str(" | OVERDUE");
+        }
+        doc.line(&line);
+    }
+    doc.into_bytes()
+}
+
+/// Scorecard for `partner_id` rendered with the saved format settings.
+pub(crate) fn partner_scorecard_pdf(
+    conn: &Connection,
+    partner_id: &str,
+    today: NaiveDate,
+) -> Result<(PartnerScorecardDto, Vec<u8>), AppError> {
+    let card = partner_scorecard_conn(conn, partner_id, today)?;
+    let pdf = render_partner_scorecard_pdf(&card, &load_format_settings(conn));
+    Ok((card, pdf))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerSummaryDto {
+    pub partner_id: String,
+    pub partner_name: String,
+    pub is_active: bool,
+    /// Not DONE or ARCHIVED.
+    pub active: i64,
+    pub done: i64,
+    pub archived: i64,
+    /// Average days from creation to the latest move to DONE; `None` if no project got there.
+    pub avg_duration_days: Option<f64>,
+    /// Latest project update or status change (RFC3339); `None` without projects.
+    pub last_activity_at: Option<String>,
+}
+
+/// Engagement overview of every partner (not in the trash), by name.
+pub fn report_partner_summary(pool: &DbPool) -> Result<Vec<PartnerSummaryDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let mut stmt = conn.prepare(
+        "SELECT pa.id, pa.name, pa.is_active, p.current_status, p.created_at, p.updated_at,
+                (SELECT MAX(h.changed_at) FROM status_history h
+                 WHERE h.project_id = p.id AND h.to_status = ?1),
+                (SELECT MAX(h.changed_at) FROM status_history h WHERE h.project_id = p.id)
+         FROM partners pa
+         LEFT JOIN projects p ON p.partner_id = pa.id AND p.deleted_at IS NULL
+         WHERE pa.deleted_at IS NULL
+         ORDER BY pa.name COLLATE NOCASE, pa.id",
+    )?;
+    let rows = stmt
+        .query_map([ProjectStatus::Done.as_str()], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, bool>(2)?,
+                r.get::<_, Option<String>>(3)?,
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, Option<String>>(5)?,
+                r.get::<_, Option<String>>(6)?,
+                r.get::<_, Option<String>>(7)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let parse = |at: Option<&str>| at.and_then(|at| DateTime::parse_from_rfc3339(at.trim()).ok());
+    let mut summaries: Vec<PartnerSummaryDto> = Vec::new();
+    let mut durations: Vec<(f64, i64)> = Vec::new();
+    for (
+        partner_id,
+        partner_name,
+        is_active,
+        status,
+        created_at,
+        updated_at,
+        done_at,
+        changed_at,
+    ) in rows
+    {
+        if summaries.last().map(|s| &s.partner_id) != Some(&partner_id) {
+            summaries.push(PartnerSummaryDto {
+                partner_id,
+                partner_name,
+                is_active,
+                active: 0,
+                done: 0,
+                archived: 0,
+                avg_duration_days: None,
+                last_activity_at: None,
+            });
+            durations.push((0.0, 0));
+        }
+        let (Some(summary), Some(duration)) = (summaries.last_mut(), durations.last_mut()) else {
+            continue;
+        };
+        let Some(status) = status else {
+            continue;
+        };
+        if status == ProjectStatus::Done.as_str() {
+            summary.done += 1;
+        } else if status == ProjectStatus::Archived.as_str() {
+            summary.archived += 1;
+        } else {
+            summary.active += 1;
+        }
+        if let (Some(created), Some(done)) =
+            (parse(created_at.as_deref()), parse(done_at.as_deref()))
+        {
+            duration.0 += (done - created).num_seconds().max(0) as f64 / 86_400.0;
+            duration.1 += 1;
+        }
+        for at in [updated_at, changed_at].into_iter().flatten() {
+            let Some(parsed) = parse(Some(&at)) else {
+                continue;
+            };
+            let later = summary
+                .last_activity_at
+                .as_deref()
+                .and_then(|prev| parse(Some(prev)))
+                .map_or(true, |prev| parsed > prev);
+            if later {
+                summary.last_activity_at = Some(at);
+            }
+        }
+    }
+    for (summary, (days, samples)) in summaries.iter_mut().zip(durations) {
+        summary.avg_duration_days = (samples > 0).then(|| days / samples as f64);
+    }
+    Ok(summaries)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSummaryReq {
+    /// Inclusive start date (`YYYY-MM-DD`, local); default `to` minus `SUMMARY_DEFAULT_DAYS`.
+    pub from: Option<String>,
+    /// Inclusive end date (`YYYY-MM-DD`, local); default today.
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCountDto {
+    pub status: String,
+    /// Projects currently in this status.
+    pub count: i64,
+    /// Of those, overdue as of the report end date.
+    pub overdue: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusDurationDto {
+    pub status: String,
+    /// Average days spent in the status; `None` if no stay ended in the range.
+    pub avg_days: Option<f64>,
+    /// Number of stays that ended in the range.
+    pub samples: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyThroughputDto {
+    /// Monday of the week (`YYYY-MM-DD`).
+    pub week_start: String,
+    /// Moves to DONE during the week (within the range).
+    pub done: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSummaryDto {
+    pub from: String,
+    pub to: String,
+    pub total: i64,
+    /// Every status, in workflow order.
+    pub status_counts: Vec<StatusCountDto>,
+    pub avg_time_in_status: Vec<StatusDurationDto>,
+    /// Every week overlapping the range, oldest first.
+    pub throughput: Vec<WeeklyThroughputDto>,
+    pub overdue: i64,
+}
+
+/// Status counts, time in status, weekly throughput and overdue counts for a date range.
+pub fn report_summary(
+    pool: &DbPool,
+    req: ReportSummaryReq,
+    today: NaiveDate,
+) -> Result<ReportSummaryDto, AppError> {
+    let to = parse_report_date(req.to.as_deref(), "to")?.unwrap_or(today);
+    let from = parse_report_date(req.from.as_deref(), "from")?
+        .unwrap_or(to - chrono::Duration::days(SUMMARY_DEFAULT_DAYS));
+    if from > to {
+        return Err(AppError::Validation("from must not be after to".into()));
+    }
+    let overdue_as_of = to.min(today);
+
+    let conn = get_read_connection(pool);
+    let statuses: Vec<String> = load_active_workflow(&conn)?
+        .statuses
+        .into_iter()
+        .map(|s| s.key)
+        .collect();
+    let mut status_counts: Vec<StatusCountDto> = statuses
+        .iter()
+        .map(|s| StatusCountDto {
+            status: s.clone(),
+            count: 0,
+            overdue: 0,
+        })
+        .collect();
+    let mut total = 0;
+    let mut overdue = 0;
+    {
+        let mut stmt =
+            conn.prepare("SELECT current_status, due_date FROM projects WHERE deleted_at IS NULL")?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok((r.get::<_, String>(0)?, r.get::<_, Option<String>>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (status, due_date) in rows {
+            total += 1;
+            let Some(entry) = status_counts.iter_mut().find(|c| c.status == status) else {
+                continue;
+            };
+            entry.count += 1;
+            let open = status != ProjectStatus::Done.as_str()
+                && status != ProjectStatus::Archived.as_str();
+            let due = due_date
+                .as_deref()
+                .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok());
+            if open && due.is_some_and(|d| d < overdue_as_of) {
+                entry.overdue += 1;
+                overdue += 1;
+            }
+        }
+    }
+
+    // Status history of live projects, per project in time order.
+    let mut stmt = conn.prepare(
+        "SELECT h.project_id, h.to_status, h.changed_at
+         FROM status_history h
+         JOIN projects p ON p.id = h.project_id AND p.deleted_at IS NULL
+         ORDER BY h.project_id, h.changed_at, h.rowid",
+    )?;
+    let history = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let in_range = |at: &DateTime<Local>| {
+        let d = at.date_naive();
+        d >= from && d <= to
+    };
+    let mut durations: Vec<(f64, i64)> = vec![(0.0, 0); statuses.len()];
+    let first_week = week_start(from);
+    let weeks = (week_start(to) - first_week).num_days() / 7 + 1;
+    let mut throughput = vec![0i64; weeks as usize];
+    let mut prev: Option<(&str, &str, DateTime<Local>)> = None;
+    for (project_id, status, changed_at) in &history {
+        let Some(at) = DateTime::parse_from_rfc3339(changed_at.trim())
+            .ok()
+            .map(|at| at.with_timezone(&Local))
+        else {
+            continue;
+        };
+        if let Some((prev_project, prev_status, prev_at)) = prev {
+            if prev_project == project_id && in_range(&at) {
+                if let Some(i) = statuses.iter().position(|s| s == prev_status) {
+                    durations[i].0 += (at - prev_at).num_seconds().max(0) as f64 / 86_400.0;
+                    durations[i].1 += 1;
+                }
+            }
+        }
+        if status == ProjectStatus::Done.as_str() && in_range(&at) {
+            let week = (week_start(at.date_naive()) - first_week).num_days() / 7;
+            throughput[week as usize] += 1;
+        }
+        prev = Some((project_id, status, at));
+    }
+
+    Ok(ReportSummaryDto {
+        from: from.format("%Y-%m-%d").to_string(),
+        to: to.format("%Y-%m-%d").to_string(),
+        total,
+        status_counts,
+        avg_time_in_status: statuses
+            .into_iter()
+            .zip(durations)
+            .map(|(status, (days, samples))| StatusDurationDto {
+                status,
+                avg_days: (samples > 0).then(|| days / samples as f64),
+                samples,
+            })
+            .collect(),
+        throughput: throughput
+            .into_iter()
+            .enumerate()
+            .map(|(i, done)| WeeklyThroughputDto {
+                week_start: (first_week + chrono::Duration::weeks(i as i64))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                done,
+            })
+            .collect(),
+        overdue,
+    })
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonWorkloadReq {
+    /// Due within this many days from today (overdue ones included); default `WORKLOAD_UPCOMING_DAYS`.
+    pub upcoming_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkloadDueDto {
+    pub project_id: String,
+    pub project_name: String,
+    pub current_status: String,
+    pub due_date: String,
+    pub overdue: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonWorkloadDto {
+    pub person_id: String,
+    pub display_name: String,
+    pub role: Option<String>,
+    pub active_assignments: i64,
+    pub projects_owned: i64,
+    /// Projects the person is on or owns, by current status (every status except ARCHIVED).
+    pub status_counts: Vec<StatusCountDto>,
+    /// Open projects due by the look-ahead date, earliest first.
+    pub upcoming_due: Vec<WorkloadDueDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonWorkloadReportDto {
+    /// Report date (`YYYY-MM-DD`).
+    pub as_of: String,
+    /// Last day counted as upcoming (`YYYY-MM-DD`).
+    pub upcoming_until: String,
+    /// Every active person, by name.
+    pub persons: Vec<PersonWorkloadDto>,
+}
+
+/// Active assignments, owned projects, projects by status and upcoming due dates per active person.
+pub fn report_person_workload(
+    pool: &DbPool,
+    req: PersonWorkloadReq,
+    today: NaiveDate,
+) -> Result<PersonWorkloadReportDto, AppError> {
+    let upcoming_days = req.upcoming_days.unwrap_or(WORKLOAD_UPCOMING_DAYS);
+    if upcoming_days < 0 {
+        return Err(AppError::Validation(
+            "upcomingDays must not be negative".into(),
+        ));
+    }
+    let until = today + chrono::Duration::days(upcoming_days);
+    let statuses: Vec<ProjectStatus> = ProjectStatus::all()
+        .iter()
+        .copied()
+        .filter(|s| *s != ProjectStatus::Archived)
+        .collect();
+
+    let conn = get_read_connection(pool);
+    let mut stmt = conn.prepare(
+        "SELECT id, display_name, role FROM persons
+         WHERE is_active = 1 AND deleted_at IS NULL
+         ORDER BY display_name COLLATE NOCASE",
+    )?;
+    let mut persons = stmt
+        .query_map([], |r| {
+            Ok(PersonWorkloadDto {
+                person_id: r.get(0)?,
+                display_name: r.get(1)?,
+                role: r.get(2)?,
+                active_assignments: 0,
+                projects_owned: 0,
+                status_counts: statuses
+                    .iter()
+                    .map(|s| StatusCountDto {
+                        status: s.as_str().to_string(),
+                        count: 0,
+                        overdue: 0,
+                    })
+                    .collect(),
+                upcoming_due: Vec::new(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let index: HashMap<String, usize> = persons
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.person_id.clone(), i))
+        .collect();
+
+    // One row per (person, project) the person is on or owns.
+    let mut stmt = conn.prepare(
+        "WITH involved AS (
+             SELECT person_id, project_id FROM assignments WHERE end_at IS NULL
+             UNION
+             SELECT owner_person_id, id FROM projects
+         )
+         SELECT i.person_id, p.id, p.name, p.current_status, p.due_date,
+                p.owner_person_id = i.person_id,
+                (SELECT COUNT(*) FROM assignments a
+                 WHERE a.project_id = p.id AND a.person_id = i.person_id AND a.end_at IS NULL)
+         FROM involved i
+         JOIN projects p ON p.id = i.project_id
+         WHERE p.deleted_at IS NULL AND p.current_status <> 'ARCHIVED'
+         ORDER BY p.due_date, p.name COLLATE NOCASE",
+    )?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, String>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+                r.get::<_, Option<String>>(4)?,
+                r.get::<_, bool>(5)?,
+                r.get::<_, i64>(6)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (person_id, project_id, name, status, due_date, owned, assignments) in rows {
+        let Some(person) = index.get(&person_id).map(|&i| &mut persons[i]) else {
+            continue;
+        };
+        person.active_assignments += assignments;
+        if owned {
+            person.projects_owned += 1;
+        }
+        let open = status != ProjectStatus::Done.as_str();
+        let due = due_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok());
+        let overdue = open && due.is_some_and(|d| d < today);
+        if let Some(entry) = person.status_counts.iter_mut().find(|c| c.status == status) {
+            entry.count += 1;
+            if overdue {
+                entry.overdue += 1;
+            }
+        }
+        if let Some(due) = due.filter(|d| open && *d <= until) {
+            person.upcoming_due.push(WorkloadDueDto {
+                project_id,
+                project_name: name,
+                current_status: status,
+                due_date: due.format("%Y-%m-%d").to_string(),
+                overdue,
+            });
+        }
+    }
+
+    Ok(PersonWorkloadReportDto {
+        as_of: today.format("%Y-%m-%d").to_string(),
+        upcoming_until: until.format("%Y-%m-%d").to_string(),
+        persons,
+    })
+}
+
+fn parse_report_date(value: Option<&str>, field: &str) -> Result<Option<NaiveDate>, AppError> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| AppError::Validation(format!("{} must be YYYY-MM-DD", field))),
+    }
+}
+
+/// Monday of the week containing `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+}
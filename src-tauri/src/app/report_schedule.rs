@@ -0,0 +1,441 @@
+//! Report schedules: generate report files into a folder every N days / weeks / months.
+//!
+//! - 目前支持的报表：合作方 scorecard（PDF）。
+//! - 周期规则与循环项目相同（`anchor_on` 起算，错过的多个周期只补生成一次）；到期检查由后台
+//!   `ReportRuntime` 每小时执行一次（启动时立即执行一次）。
+//! - 输出目录：未设置时为数据目录下的 `reports/`；`~/` 开头的路径展开为用户主目录；其他路径必须为绝对路径。
+//! - 文件名为 `<计划名>-<运行日期>.pdf`，同一天重复运行覆盖同名文件。
+//! - 每次运行的成功 / 失败都会写入通知（app notices），失败原因同时记录在 `last_error`。
+
+use crate::app::report::partner_scorecard_pdf;
+use crate::app::{next_run_after, notice_record, RecurrenceUnit};
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+pub const NOTICE_KIND_REPORT_DELIVERED: &str = "REPORT_DELIVERED";
+pub const NOTICE_KIND_REPORT_FAILED: &str = "REPORT_FAILED";
+
+const REPORT_DIR_NAME: &str = "reports";
+
+/// Serializes due checks (hourly runtime vs. commands) so a schedule never runs twice.
+static RUN_DUE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportKind {
+    PartnerScorecard,
+}
+
+impl ReportKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PartnerScorecard => "partner_scorecard",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "partner_scorecard" => Some(Self::PartnerScorecard),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportScheduleDto {
+    pub id: String,
+    pub name: String,
+    pub report_kind: ReportKind,
+    pub partner_id: Option<String>,
+    pub partner_name: Option<String>,
+    pub interval_unit: RecurrenceUnit,
+    pub interval_count: i64,
+    pub anchor_on: String,
+    pub next_run_on: String,
+    /// Configured folder (`None` = `<data dir>/reports`).
+    pub output_dir: Option<String>,
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    pub last_output_path: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportScheduleCreateReq {
+    pub name: String,
+    pub report_kind: ReportKind,
+    /// Required for `partner_scorecard`.
+    pub partner_id: Option<String>,
+    pub interval_unit: RecurrenceUnit,
+    /// >= 1
+    pub interval_count: i64,
+    /// First run date (YYYY-MM-DD); also the anchor for later runs (a Monday for "every Monday").
+    pub start_on: String,
+    /// Absolute path or `~/...`; empty / omitted = `<data dir>/reports`.
+    pub output_dir: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportScheduleUpdateReq {
+    pub id: String,
+    pub name: Option<String>,
+    pub enabled: Option<bool>,
+    /// Empty string resets to `<data dir>/reports`.
+    pub output_dir: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRunItem {
+    pub schedule_id: String,
+    pub run_on: String,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+}
+
+pub fn report_schedule_create(
+    pool: &DbPool,
+    req: ReportScheduleCreateReq,
+) -> Result<ReportScheduleDto, AppError> {
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".into()));
+    }
+    if req.interval_count < 1 {
+        return Err(AppError::Validation("intervalCount must be >= 1".into()));
+    }
+    let start_on = parse_date(&req.start_on)?;
+    let output_dir = normalize_output_dir(req.output_dir.as_deref())?;
+    let partner_id = req
+        .partner_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+
+    let conn = get_connection(pool);
+    match req.report_kind {
+        ReportKind::PartnerScorecard => {
+            let Some(partner_id) = partner_id.as_deref() else {
+                return Err(AppError::Validation(
+                    "partnerId is required for partner_scorecard".into(),
+                ));
+            };
+            let exists: i64 = conn.query_row(
+                "SELECT COUNT(1) FROM partners WHERE id = ?1",
+                [partner_id],
+                |r| r.get(0),
+            )?;
+            if exists == 0 {
+                return Err(AppError::NotFound(format!("partner {}", partner_id)));
+            }
+        }
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO report_schedules (id, name, report_kind, partner_id, interval_unit, interval_count, anchor_on, next_run_on, output_dir, enabled, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7, ?8, 1, ?9, ?9)",
+        params![
+            &id,
+            &name,
+            req.report_kind.as_str(),
+            &partner_id,
+            req.interval_unit.as_str(),
+            req.interval_count,
+            start_on.format("%Y-%m-%d").to_string(),
+            &output_dir,
+            &now
+        ],
+    )?;
+    load_schedule(&conn, &id)
+}
+
+pub fn report_schedule_update(
+    pool: &DbPool,
+    req: ReportScheduleUpdateReq,
+) -> Result<ReportScheduleDto, AppError> {
+    let conn = get_connection(pool);
+    load_schedule(&conn, &req.id)?;
+    let now = Utc::now().to_rfc3339();
+    if let Some(name) = req.name.as_deref().map(str::trim) {
+        if name.is_empty() {
+            return Err(AppError::Validation("name is required".into()));
+        }
+        conn.execute(
+            "UPDATE report_schedules SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, &now, &req.id],
+        )?;
+    }
+    if let Some(enabled) = req.enabled {
+        conn.execute(
+            "UPDATE report_schedules SET enabled = ?1, updated_at = ?2 WHERE id = ?3",
+            params![enabled as i32, &now, &req.id],
+        )?;
+    }
+    if let Some(raw) = req.output_dir.as_deref() {
+        let output_dir = normalize_output_dir(Some(raw))?;
+        conn.execute(
+            "UPDATE report_schedules SET output_dir = ?1, updated_at = ?2 WHERE id = ?3",
+            params![output_dir, &now, &req.id],
+        )?;
+    }
+    load_schedule(&conn, &req.id)
+}
+
+pub fn report_schedule_delete(pool: &DbPool, schedule_id: &str) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let changed = conn.execute("DELETE FROM report_schedules WHERE id = ?1", [schedule_id])?;
+    if changed == 0 {
+        return Err(AppError::NotFound(format!(
+            "report schedule {}",
+            schedule_id
+        )));
+    }
+    Ok(())
+}
+
+pub fn report_schedule_get(
+    pool: &DbPool,
+    schedule_id: &str,
+) -> Result<ReportScheduleDto, AppError> {
+    let conn = get_read_connection(pool);
+    load_schedule(&conn, schedule_id)
+}
+
+pub fn report_schedule_list(pool: &DbPool) -> Result<Vec<ReportScheduleDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let ids = conn
+        .prepare("SELECT id FROM report_schedules ORDER BY next_run_on, name")?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    ids.iter().map(|id| load_schedule(&conn, id)).collect()
+}
+
+/// Generate every enabled schedule due on or before `today` (one file per schedule).
+pub fn report_run_due(pool: &DbPool, today: NaiveDate) -> Result<Vec<ReportRunItem>, AppError> {
+    let _guard = RUN_DUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let conn = get_connection(pool);
+    let ids = conn
+        .prepare(
+            "SELECT id FROM report_schedules WHERE enabled = 1 AND next_run_on <= ?1 ORDER BY next_run_on",
+        )?
+        .query_map([today.format("%Y-%m-%d").to_string()], |r| {
+            r.get::<_, String>(0)
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut items = Vec::new();
+    for id in ids {
+        let schedule = load_schedule(&conn, &id)?;
+        let next = next_run_after(
+            parse_date(&schedule.anchor_on)?,
+            schedule.interval_unit,
+            schedule.interval_count,
+            today,
+        );
+        items.push(run_and_record(&conn, &schedule, today, Some(next))?);
+    }
+    Ok(items)
+}
+
+/// Generate one schedule's report now without moving its next run date.
+pub fn report_schedule_run_now(
+    pool: &DbPool,
+    schedule_id: &str,
+    today: NaiveDate,
+) -> Result<ReportRunItem, AppError> {
+    let _guard = RUN_DUE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let conn = get_connection(pool);
+    let schedule = load_schedule(&conn, schedule_id)?;
+    run_and_record(&conn, &schedule, today, None)
+}
+
+fn run_and_record(
+    conn: &Connection,
+    schedule: &ReportScheduleDto,
+    run_on: NaiveDate,
+    next_run_on: Option<NaiveDate>,
+) -> Result<ReportRunItem, AppError> {
+    let result = generate(conn, schedule, run_on);
+    let now = Utc::now().to_rfc3339();
+    let (output_path, error) = match result {
+        Ok(path) => {
+            let path = path.display().to_string();
+            notice_record(
+                conn,
+                NOTICE_KIND_REPORT_DELIVERED,
+                "info",
+                &format!("Report \"{}\" saved to {}", schedule.name, path),
+            )?;
+            (Some(path), None)
+        }
+        Err(e) => {
+            log::warn!("Report schedule {} failed: {}", schedule.id, e);
+            notice_record(
+                conn,
+                NOTICE_KIND_REPORT_FAILED,
+                "warning",
+                &format!("Report \"{}\" failed: {}", schedule.name, e),
+            )?;
+            (None, Some(e.to_string()))
+        }
+    };
+    conn.execute(
+        "UPDATE report_schedules SET next_run_on = COALESCE(?1, next_run_on), last_run_at = ?2, last_output_path = COALESCE(?3, last_output_path), last_error = ?4, updated_at = ?2 WHERE id = ?5",
+        params![
+            next_run_on.map(|d| d.format("%Y-%m-%d").to_string()),
+            &now,
+            &output_path,
+            &error,
+            &schedule.id
+        ],
+    )?;
+    Ok(ReportRunItem {
+        schedule_id: schedule.id.clone(),
+        run_on: run_on.format("%Y-%m-%d").to_string(),
+        output_path,
+        error,
+    })
+}
+
+fn generate(
+    conn: &Connection,
+    schedule: &ReportScheduleDto,
+    run_on: NaiveDate,
+) -> Result<PathBuf, AppError> {
+    let bytes = match schedule.report_kind {
+        ReportKind::PartnerScorecard => {
+            let partner_id = schedule
+                .partner_id
+                .as_deref()
+                .ok_or_else(|| AppError::Validation("partnerId is required".into()))?;
+            partner_scorecard_pdf(conn, partner_id, run_on)?.1
+        }
+    };
+    let dir = resolve_output_dir(conn, schedule.output_dir.as_deref())?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::Report(format!("Failed to create {:?}: {}", dir, e)))?;
+    let path = dir.join(format!(
+        "{}-{}.pdf",
+        file_stem(&schedule.name),
+        run_on.format("%Y-%m-%d")
+    ));
+    fs::write(&path, bytes)
+        .map_err(|e| AppError::Report(format!("Failed to write {:?}: {}", path, e)))?;
+    Ok(path)
+}
+
+/// Configured folder, or `<data dir>/reports` next to the database file.
+fn resolve_output_dir(conn: &Connection, configured: Option<&str>) -> Result<PathBuf, AppError> {
+    if let Some(dir) = configured {
+        return expand_home(dir);
+    }
+    let db_path = conn.path().filter(|p| !p.is_empty()).ok_or_else(|| {
+        AppError::Validation("outputDir is required for an in-memory database".into())
+    })?;
+    Path::new(db_path)
+        .parent()
+        .map(|dir| dir.join(REPORT_DIR_NAME))
+        .ok_or_else(|| AppError::Validation("cannot resolve the data directory".into()))
+}
+
+fn normalize_output_dir(raw: Option<&str>) -> Result<Option<String>, AppError> {
+    let Some(raw) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    expand_home(raw)?;
+    Ok(Some(raw.to_string()))
+}
+
+fn expand_home(raw: &str) -> Result<PathBuf, AppError> {
+    let path = match raw
+        .strip_prefix("~/")
+        .or_else(|| (raw == "~").then_some(""))
+    {
+        Some(rest) => dirs::home_dir()
+            .ok_or_else(|| AppError::Validation("cannot resolve the home directory".into()))?
+            .join(rest),
+        None => PathBuf::from(raw),
+    };
+    if !path.is_absolute() {
+        return Err(AppError::Validation(format!(
+            "outputDir must be an absolute path or start with ~/: {}",
+            raw
+        )));
+    }
+    Ok(path)
+}
+
+/// Schedule name as a file name: path separators and reserved characters become `-`.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || "/\\:*?\"<>|".contains(c) || c.is_control() {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches(|c| c == '-' || c == '.');
+    if stem.is_empty() {
+        "report".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::Validation(format!("invalid date (YYYY-MM-DD): {}", value)))
+}
+
+fn load_schedule(conn: &Connection, id: &str) -> Result<ReportScheduleDto, AppError> {
+    let schedule = conn
+        .query_row(
+            "SELECT s.id, s.name, s.report_kind, s.partner_id, p.name, s.interval_unit, s.interval_count, s.anchor_on, s.next_run_on, s.output_dir, s.enabled, s.last_run_at, s.last_output_path, s.last_error, s.created_at, s.updated_at
+             FROM report_schedules s LEFT JOIN partners p ON p.id = s.partner_id
+             WHERE s.id = ?1",
+            [id],
+            |r| {
+                let kind: String = r.get(2)?;
+                let unit: String = r.get(5)?;
+                Ok(ReportScheduleDto {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    report_kind: ReportKind::parse(&kind).unwrap_or(ReportKind::PartnerScorecard),
+                    partner_id: r.get(3)?,
+                    partner_name: r.get(4)?,
+                    interval_unit: RecurrenceUnit::parse(&unit).unwrap_or(RecurrenceUnit::Week),
+                    interval_count: r.get(6)?,
+                    anchor_on: r.get(7)?,
+                    next_run_on: r.get(8)?,
+                    output_dir: r.get(9)?,
+                    enabled: r.get::<_, i32>(10)? != 0,
+                    last_run_at: r.get(11)?,
+                    last_output_path: r.get(12)?,
+                    last_error: r.get(13)?,
+                    created_at: r.get(14)?,
+                    updated_at: r.get(15)?,
+                })
+            },
+        )
+        .optional()?;
+    schedule.ok_or_else(|| AppError::NotFound(format!("report schedule {}", id)))
+}
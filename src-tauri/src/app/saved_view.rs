@@ -0,0 +1,137 @@
+//! Saved views: named `project_list` filter + sort combinations.
+//!
+//! - 视图保存完整的 `ProjectListReq`（筛选 + 排序），分页参数不保存，应用时由调用方传入。
+//! - 视图名称大小写不敏感唯一；视图表仅本机保存，不参与同步。
+//! - 视图引用的人员 / 合作方 / 自定义字段被删除后，应用时按原值筛选（匹配不到即为空结果），
+//!   自定义字段不存在时返回 `NOT_FOUND`，由前端提示用户修改视图。
+
+use crate::app::{project_list, ProjectListPage, ProjectListReq};
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedViewDto {
+    pub id: String,
+    pub name: String,
+    /// Stored filters and sort (paging fields are never stored).
+    pub filters: ProjectListReq,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedViewCreateReq {
+    /// Unique view name (case-insensitive).
+    pub name: String,
+    pub filters: ProjectListReq,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedViewApplyReq {
+    pub id: String,
+    pub limit: Option<i32>,
+    pub offset: Option<i32>,
+    pub after_updated_at: Option<String>,
+    pub after_id: Option<String>,
+}
+
+pub fn saved_view_create(pool: &DbPool, req: SavedViewCreateReq) -> Result<SavedViewDto, AppError> {
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".into()));
+    }
+    let mut filters = req.filters;
+    filters.limit = None;
+    filters.offset = None;
+    filters.after_updated_at = None;
+    filters.after_id = None;
+    let filters_json = serde_json::to_string(&filters)
+        .map_err(|e| AppError::Validation(format!("invalid filters: {}", e)))?;
+
+    let conn = get_connection(pool);
+    let exists: i64 = conn.query_row(
+        "SELECT COUNT(1) FROM saved_views WHERE name = ?1 COLLATE NOCASE",
+        [&name],
+        |r| r.get(0),
+    )?;
+    if exists > 0 {
+        return Err(AppError::Conflict("saved view name must be unique".into()));
+    }
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO saved_views (id, name, filters, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+        params![&id, &name, &filters_json, &now],
+    )?;
+    load_saved_view(&conn, &id)
+}
+
+pub fn saved_view_list(pool: &DbPool) -> Result<Vec<SavedViewDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let ids = conn
+        .prepare("SELECT id FROM saved_views ORDER BY name COLLATE NOCASE")?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    ids.iter().map(|id| load_saved_view(&conn, id)).collect()
+}
+
+pub fn saved_view_delete(pool: &DbPool, view_id: &str) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let changed = conn.execute("DELETE FROM saved_views WHERE id = ?1", [view_id])?;
+    if changed == 0 {
+        return Err(AppError::NotFound(format!("saved view {}", view_id)));
+    }
+    Ok(())
+}
+
+/// Run `project_list` with the view's filters and the caller's paging.
+pub fn saved_view_apply(
+    pool: &DbPool,
+    req: SavedViewApplyReq,
+) -> Result<ProjectListPage, AppError> {
+    let mut filters = {
+        let conn = get_connection(pool);
+        load_saved_view(&conn, &req.id)?.filters
+    };
+    filters.limit = req.limit;
+    filters.offset = req.offset;
+    filters.after_updated_at = req.after_updated_at;
+    filters.after_id = req.after_id;
+    project_list(pool, filters)
+}
+
+fn load_saved_view(conn: &Connection, id: &str) -> Result<SavedViewDto, AppError> {
+    let row = conn
+        .query_row(
+            "SELECT id, name, filters, created_at, updated_at FROM saved_views WHERE id = ?1",
+            [id],
+            |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, String>(1)?,
+                    r.get::<_, String>(2)?,
+                    r.get::<_, String>(3)?,
+                    r.get::<_, String>(4)?,
+                ))
+            },
+        )
+        .optional()?;
+    let (id, name, filters, created_at, updated_at) =
+        row.ok_or_else(|| AppError::NotFound(format!("saved view {}", id)))?;
+    // Views saved by a newer build may carry unknown fields; serde ignores them.
+    let filters = serde_json::from_str(&filters).unwrap_or_default();
+    Ok(SavedViewDto {
+        id,
+        name,
+        filters,
+        created_at,
+        updated_at,
+    })
+}
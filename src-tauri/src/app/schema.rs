@@ -0,0 +1,321 @@
+//! Schema introspection: machine-readable description and ERD of the current database.
+//!
+//! 从 `sqlite_master` 与 `pragma_table_info` / `pragma_index_list` / `pragma_foreign_key_list` 读取实际结构，
+//! 反映本构建已执行的全部迁移。关系包括声明的外键，以及同步严格模式使用的隐式引用（`declared = false`）。
+//! 输出格式：`json`（结构描述）、`mermaid`（erDiagram）、`graphviz`（DOT）。
+
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use crate::sync::deferred::references;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemaFormat {
+    #[default]
+    Json,
+    Mermaid,
+    Graphviz,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbSchemaExportReq {
+    pub format: Option<SchemaFormat>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaColumnDto {
+    pub name: String,
+    /// Declared type (may be empty).
+    pub data_type: String,
+    pub not_null: bool,
+    pub default_value: Option<String>,
+    /// Position in the primary key (1-based); 0 = not part of it.
+    pub primary_key: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaIndexDto {
+    pub name: String,
+    pub unique: bool,
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaRelationDto {
+    pub from_table: String,
+    pub from_column: String,
+    pub to_table: String,
+    pub to_column: String,
+    /// `FOREIGN KEY` in the table definition; otherwise enforced by the app layer only.
+    pub declared: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaTableDto {
+    pub name: String,
+    pub columns: Vec<SchemaColumnDto>,
+    pub indexes: Vec<SchemaIndexDto>,
+    pub triggers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbSchemaExportDto {
+    pub format: SchemaFormat,
+    /// Latest applied migration.
+    pub schema_version: i64,
+    pub tables: Vec<SchemaTableDto>,
+    pub relations: Vec<SchemaRelationDto>,
+    /// Rendered output in `format` (pretty JSON of `tables` + `relations` for `json`).
+    pub content: String,
+}
+
+pub fn db_schema_export(
+    pool: &DbPool,
+    req: DbSchemaExportReq,
+) -> Result<DbSchemaExportDto, AppError> {
+    let format = req.format.unwrap_or_default();
+    let conn = get_read_connection(pool);
+    let schema_version: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |r| r.get(0),
+    )?;
+    let tables = load_tables(&conn)?;
+    let relations = load_relations(&conn, &tables)?;
+
+    let content = match format {
+        SchemaFormat::Json => serde_json::to_string_pretty(&serde_json::json!({
+            "schemaVersion": schema_version,
+            "tables": tables,
+            "relations": relations,
+        }))
+        .map_err(|e| AppError::Db(format!("Serialize schema failed: {}", e)))?,
+        SchemaFormat::Mermaid => render_mermaid(&tables, &relations),
+        SchemaFormat::Graphviz => render_graphviz(&tables, &relations),
+    };
+
+    Ok(DbSchemaExportDto {
+        format,
+        schema_version,
+        tables,
+        relations,
+        content,
+    })
+}
+
+pub(crate) fn load_tables(conn: &Connection) -> Result<Vec<SchemaTableDto>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+         ORDER BY name",
+    )?;
+    let names = stmt
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut tables = Vec::with_capacity(names.len());
+    for name in names {
+        let mut stmt = conn.prepare(
+            "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1) ORDER BY cid",
+        )?;
+        let columns = stmt
+            .query_map([&name], |r| {
+                Ok(SchemaColumnDto {
+                    name: r.get(0)?,
+                    data_type: r.get(1)?,
+                    not_null: r.get(2)?,
+                    default_value: r.get(3)?,
+                    primary_key: r.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT name, \"unique\" FROM pragma_index_list(?1)
+             WHERE origin <> 'pk' ORDER BY name",
+        )?;
+        let index_names = stmt
+            .query_map([&name], |r| {
+                Ok((r.get::<_, String>(0)?, r.get::<_, bool>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut indexes = Vec::with_capacity(index_names.len());
+        for (index, unique) in index_names {
+            let mut stmt = conn.prepare("SELECT name FROM pragma_index_info(?1) ORDER BY seqno")?;
+            let columns = stmt
+                .query_map([&index], |r| r.get::<_, Option<String>>(0))?
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .map(|c| c.unwrap_or_else(|| "<expr>".to_string()))
+                .collect();
+            indexes.push(SchemaIndexDto {
+                name: index,
+                unique,
+                columns,
+            });
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'trigger' AND tbl_name = ?1 ORDER BY name",
+        )?;
+        let triggers = stmt
+            .query_map([&name], |r| r.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        tables.push(SchemaTableDto {
+            name,
+            columns,
+            indexes,
+            triggers,
+        });
+    }
+    Ok(tables)
+}
+
+pub(crate) fn load_relations(
+    conn: &Connection,
+    tables: &[SchemaTableDto],
+) -> Result<Vec<SchemaRelationDto>, AppError> {
+    let mut relations: Vec<SchemaRelationDto> = Vec::new();
+    for table in tables {
+        let mut stmt = conn.prepare(
+            "SELECT \"from\", \"table\", COALESCE(\"to\", 'id') FROM pragma_foreign_key_list(?1)
+             ORDER BY id, seq",
+        )?;
+        let declared = stmt
+            .query_map([&table.name], |r| {
+                Ok(SchemaRelationDto {
+                    from_table: table.name.clone(),
+                    from_column: r.get(0)?,
+                    to_table: r.get(1)?,
+                    to_column: r.get(2)?,
+                    declared: true,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        relations.extend(declared);
+
+        for (column, to_table, _) in references(&table.name) {
+            let known = relations
+                .iter()
+                .any(|r| r.from_table == table.name && r.from_column == *column);
+            let has_column = table.columns.iter().any(|c| c.name == *column);
+            if !known && has_column && tables.iter().any(|t| t.name == *to_table) {
+                relations.push(SchemaRelationDto {
+                    from_table: table.name.clone(),
+                    from_column: column.to_string(),
+                    to_table: to_table.to_string(),
+                    to_column: "id".to_string(),
+                    declared: false,
+                });
+            }
+        }
+    }
+    Ok(relations)
+}
+
+fn render_mermaid(tables: &[SchemaTableDto], relations: &[SchemaRelationDto]) -> String {
+    let mut out = String::from("erDiagram\n");
+    for table in tables {
+        out.push_str(&format!("    {} {{\n", table.name));
+        for column in &table.columns {
+            let mut keys = Vec::new();
+            if column.primary_key > 0 {
+                keys.push("PK");
+            }
+            if relations
+                .iter()
+                .any(|r| r.from_table == table.name && r.from_column == column.name)
+            {
+                keys.push("FK");
+            }
+            out.push_str(&format!(
+                "        {} {}{}\n",
+                mermaid_type(&column.data_type),
+                column.name,
+                if keys.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}", keys.join(","))
+                }
+            ));
+        }
+        out.push_str("    }\n");
+    }
+    for r in relations {
+        // Many rows of `from_table` point at one row of `to_table`.
+        out.push_str(&format!(
+            "    {} {} {} : \"{}\"\n",
+            r.to_table,
+            if r.declared { "||--o{" } else { "||..o{" },
+            r.from_table,
+            r.from_column
+        ));
+    }
+    out
+}
+
+/// Mermaid attribute types must be a single word.
+fn mermaid_type(data_type: &str) -> String {
+    let t: String = data_type
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if t.is_empty() {
+        "ANY".to_string()
+    } else {
+        t
+    }
+}
+
+fn render_graphviz(tables: &[SchemaTableDto], relations: &[SchemaRelationDto]) -> String {
+    let mut out = String::from(
+        "digraph schema {\n    rankdir=LR;\n    node [shape=plaintext, fontname=\"Helvetica\"];\n",
+    );
+    for table in tables {
+        out.push_str(&format!(
+            "    \"{0}\" [label=<<TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\"><TR><TD BGCOLOR=\"lightgrey\"><B>{0}</B></TD></TR>",
+            table.name
+        ));
+        for column in &table.columns {
+            let name = if column.primary_key > 0 {
+                format!("<U>{}</U>", column.name)
+            } else {
+                column.name.clone()
+            };
+            out.push_str(&format!(
+                "<TR><TD PORT=\"{}\" ALIGN=\"LEFT\">{} {}</TD></TR>",
+                column.name,
+                name,
+                html_escape(&column.data_type)
+            ));
+        }
+        out.push_str("</TABLE>>];\n");
+    }
+    for r in relations {
+        out.push_str(&format!(
+            "    \"{}\":\"{}\" -> \"{}\":\"{}\"{};\n",
+            r.from_table,
+            r.from_column,
+            r.to_table,
+            r.to_column,
+            if r.declared { "" } else { " [style=dashed]" }
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,352 @@
+//! SLA / staleness rules and the project flags derived from them.
+//!
+//! - 规则 = 状态 + 天数：项目停留在当前状态超过 `maxDays` 天即违反（如 “BLOCKED 超过 14 天”）；
+//!   `status` 为空时适用于所有未完成状态（非 DONE / ARCHIVED），即 “30 天没有状态变更”。
+//! - 停留起点取最近一次状态变更（status_history），没有历史时取创建时间；天数按日期计算。
+//! - 评估范围：未归档、不在回收站的项目。每次评估把 `project_flags` 更新为当前违反的 (项目, 规则)，
+//!   不再违反的标记删除；进入新的状态周期时标记重新生成。状态变更时立即清除该项目的标记。
+//! - 评估随提醒检查（`NotificationRuntime`，每小时）进行，并为每个标记生成 `SLA_BREACHED` 提醒；
+//!   增删改规则后立即重新评估，也可通过 `sla_evaluate` 手动触发。
+//! - 规则与标记仅本机保存，不参与同步（同提醒）；`project_list` 可按标记筛选（`flagged` / `flagRuleIds`）。
+
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Longest accepted threshold.
+pub const MAX_SLA_DAYS: i64 = 365;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaRuleDto {
+    pub id: String,
+    pub name: String,
+    /// `None` = any open status.
+    pub status: Option<String>,
+    pub max_days: i64,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaRuleCreateReq {
+    pub name: String,
+    /// Omitted or empty = any open status.
+    pub status: Option<String>,
+    /// 1..=365
+    pub max_days: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaRuleUpdateReq {
+    pub id: String,
+    pub name: Option<String>,
+    /// Empty string = any open status; omitted = keep.
+    pub status: Option<String>,
+    pub max_days: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFlagDto {
+    pub project_id: String,
+    pub project_name: String,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub current_status: String,
+    /// When the project entered its current status.
+    pub since: String,
+    pub max_days: i64,
+    pub flagged_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaEvaluateResult {
+    /// Flags that did not exist before this evaluation.
+    pub flagged: usize,
+    /// Flags removed because the project no longer breaks the rule.
+    pub cleared: usize,
+}
+
+const RULE_COLUMNS: &str = "id, name, status, max_days, enabled, created_at, updated_at";
+
+/// Rules by name.
+pub fn sla_rule_list(pool: &DbPool) -> Result<Vec<SlaRuleDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let rules = conn
+        .prepare(&format!(
+            "SELECT {} FROM sla_rules ORDER BY name COLLATE NOCASE",
+            RULE_COLUMNS
+        ))?
+        .query_map([], rule_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rules)
+}
+
+pub fn sla_rule_create(pool: &DbPool, req: SlaRuleCreateReq) -> Result<SlaRuleDto, AppError> {
+    let name = validate_name(&req.name)?;
+    let status = normalize_status(req.status.as_deref());
+    validate_max_days(req.max_days)?;
+
+    let conn = get_connection(pool);
+    ensure_name_unique(&conn, name, None)?;
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    conn.execute(
+        "INSERT INTO sla_rules (id, name, status, max_days, enabled, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5)",
+        params![&id, name, status, req.max_days, now.to_rfc3339()],
+    )?;
+    sla_evaluate_conn(&conn, Local::now().date_naive(), now)?;
+    load_rule(&conn, &id)
+}
+
+pub fn sla_rule_update(pool: &DbPool, req: SlaRuleUpdateReq) -> Result<SlaRuleDto, AppError> {
+    let conn = get_connection(pool);
+    let current = load_rule(&conn, &req.id)?;
+    let name = match req.name.as_deref() {
+        Some(name) => {
+            let name = validate_name(name)?;
+            ensure_name_unique(&conn, name, Some(&req.id))?;
+            name.to_string()
+        }
+        None => current.name,
+    };
+    let status = match req.status.as_deref() {
+        Some(status) => normalize_status(Some(status)),
+        None => current.status,
+    };
+    let max_days = req.max_days.unwrap_or(current.max_days);
+    validate_max_days(max_days)?;
+    let enabled = req.enabled.unwrap_or(current.enabled);
+
+    let now = Utc::now();
+    conn.execute(
+        "UPDATE sla_rules SET name = ?1, status = ?2, max_days = ?3, enabled = ?4, updated_at = ?5
+         WHERE id = ?6",
+        params![
+            &name,
+            status,
+            max_days,
+            enabled as i32,
+            now.to_rfc3339(),
+            &req.id
+        ],
+    )?;
+    sla_evaluate_conn(&conn, Local::now().date_naive(), now)?;
+    load_rule(&conn, &req.id)
+}
+
+/// Delete a rule and its flags (open reminders close at the next check).
+pub fn sla_rule_delete(pool: &DbPool, rule_id: &str) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let rows = tx.execute("DELETE FROM sla_rules WHERE id = ?1", [rule_id])?;
+    if rows == 0 {
+        return Err(AppError::NotFound(format!("SLA rule {}", rule_id)));
+    }
+    tx.execute("DELETE FROM project_flags WHERE rule_id = ?1", [rule_id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Current flags, optionally of one project; longest-standing first.
+pub fn project_flag_list(
+    pool: &DbPool,
+    project_id: Option<String>,
+) -> Result<Vec<ProjectFlagDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let flags = conn
+        .prepare(
+            "SELECT f.project_id, p.name, f.rule_id, r.name, p.current_status, f.since, r.max_days, f.flagged_at
+             FROM project_flags f
+             JOIN projects p ON p.id = f.project_id
+             JOIN sla_rules r ON r.id = f.rule_id
+             WHERE (?1 IS NULL OR f.project_id = ?1)
+             ORDER BY f.since, p.name COLLATE NOCASE, r.name COLLATE NOCASE",
+        )?
+        .query_map([project_id], |r| {
+            Ok(ProjectFlagDto {
+                project_id: r.get(0)?,
+                project_name: r.get(1)?,
+                rule_id: r.get(2)?,
+                rule_name: r.get(3)?,
+                current_status: r.get(4)?,
+                since: r.get(5)?,
+                max_days: r.get(6)?,
+                flagged_at: r.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(flags)
+}
+
+/// Re-evaluate every enabled rule as of `today`.
+pub fn sla_evaluate(
+    pool: &DbPool,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> Result<SlaEvaluateResult, AppError> {
+    let conn = get_connection(pool);
+    sla_evaluate_conn(&conn, today, now)
+}
+
+/// Bring `project_flags` in line with the enabled rules.
+pub(crate) fn sla_evaluate_conn(
+    conn: &Connection,
+    today: NaiveDate,
+    now: DateTime<Utc>,
+) -> Result<SlaEvaluateResult, AppError> {
+    let rules: Vec<SlaRuleDto> = conn
+        .prepare(&format!(
+            "SELECT {} FROM sla_rules WHERE enabled = 1",
+            RULE_COLUMNS
+        ))?
+        .query_map([], rule_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    let projects: Vec<(String, String, String)> = conn
+        .prepare(
+            "SELECT p.id, p.current_status,
+                    COALESCE((SELECT MAX(h.changed_at) FROM status_history h WHERE h.project_id = p.id),
+                             p.created_at)
+             FROM projects p
+             WHERE p.archived_at IS NULL AND p.deleted_at IS NULL",
+        )?
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut breaches: HashMap<(String, String), String> = HashMap::new();
+    for (project_id, status, since_raw) in &projects {
+        let Some(since) = DateTime::parse_from_rfc3339(since_raw.trim())
+            .ok()
+            .map(|at| at.with_timezone(&Utc).date_naive())
+        else {
+            continue;
+        };
+        let days = (today - since).num_days();
+        for rule in &rules {
+            let applies = match rule.status.as_deref() {
+                Some(rule_status) => rule_status == status,
+                None => status != "DONE" && status != "ARCHIVED",
+            };
+            if applies && days > rule.max_days {
+                breaches.insert((project_id.clone(), rule.id.clone()), since_raw.clone());
+            }
+        }
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let existing: Vec<(String, String, String)> = tx
+        .prepare("SELECT project_id, rule_id, since FROM project_flags")?
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut cleared = 0;
+    for (project_id, rule_id, since) in existing {
+        let key = (project_id, rule_id);
+        if breaches.get(&key) == Some(&since) {
+            breaches.remove(&key);
+        } else {
+            tx.execute(
+                "DELETE FROM project_flags WHERE project_id = ?1 AND rule_id = ?2",
+                params![&key.0, &key.1],
+            )?;
+            // A new status period is a new flag, not a cleared one.
+            if !breaches.contains_key(&key) {
+                cleared += 1;
+            }
+        }
+    }
+    let flagged_at = now.to_rfc3339();
+    for ((project_id, rule_id), since) in &breaches {
+        tx.execute(
+            "INSERT INTO project_flags (project_id, rule_id, since, flagged_at) VALUES (?1, ?2, ?3, ?4)",
+            params![project_id, rule_id, since, &flagged_at],
+        )?;
+    }
+    tx.commit()?;
+    Ok(SlaEvaluateResult {
+        flagged: breaches.len(),
+        cleared,
+    })
+}
+
+/// A status change starts a new period: the project's flags no longer apply.
+pub(crate) fn clear_project_flags(conn: &Connection, project_id: &str) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM project_flags WHERE project_id = ?1",
+        [project_id],
+    )?;
+    Ok(())
+}
+
+fn validate_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".into()));
+    }
+    Ok(name)
+}
+
+fn normalize_status(status: Option<&str>) -> Option<String> {
+    status
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn validate_max_days(max_days: i64) -> Result<(), AppError> {
+    if !(1..=MAX_SLA_DAYS).contains(&max_days) {
+        return Err(AppError::Validation(format!(
+            "maxDays must be between 1 and {}",
+            MAX_SLA_DAYS
+        )));
+    }
+    Ok(())
+}
+
+fn ensure_name_unique(
+    conn: &Connection,
+    name: &str,
+    exclude_id: Option<&str>,
+) -> Result<(), AppError> {
+    let exists: i64 = conn.query_row(
+        "SELECT COUNT(1) FROM sla_rules WHERE name = ?1 COLLATE NOCASE AND id <> ?2",
+        params![name, exclude_id.unwrap_or("")],
+        |r| r.get(0),
+    )?;
+    if exists > 0 {
+        return Err(AppError::Conflict("SLA rule name must be unique".into()));
+    }
+    Ok(())
+}
+
+fn load_rule(conn: &Connection, id: &str) -> Result<SlaRuleDto, AppError> {
+    conn.query_row(
+        &format!("SELECT {} FROM sla_rules WHERE id = ?1", RULE_COLUMNS),
+        [id],
+        rule_from_row,
+    )
+    .optional()?
+    .ok_or_else(|| AppError::NotFound(format!("SLA rule {}", id)))
+}
+
+fn rule_from_row(row: &Row<'_>) -> rusqlite::Result<SlaRuleDto> {
+    Ok(SlaRuleDto {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        status: row.get(2)?,
+        max_days: row.get(3)?,
+        enabled: row.get::<_, i32>(4)? != 0,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
@@ -0,0 +1,208 @@
+//! Status automation: date-based overdue flag and automatic BACKLOG → PLANNED.
+//!
+//! - 两个开关均默认关闭，存于 `sync_config`（按工作区 / profile 保存，不随同步传播）。
+//! - 逾期标记（`status_automation_overdue_enabled`）：`due_date` 早于今天且状态不是 DONE / ARCHIVED
+//!   的项目在 `project_list` / `project_get` 中返回 `overdue = true`（规则同 `OVERDUE` 提醒）；
+//!   读取时计算，不写入项目，不产生同步变更。
+//! - 自动计划（`status_automation_auto_plan_enabled`）：`start_date` 已到（早于或等于今天）的 BACKLOG
+//!   项目由后台 `RecurrenceRuntime` 每小时转为 PLANNED（启动时立即执行一次）。
+//! - 自动流转走 `project_change_status`（流程、WIP 上限、Webhook、撤销日志照常生效），
+//!   状态历史记为系统操作：无操作人，备注以 `[auto]` 开头。
+//! - 幂等：同一项目、同一开始日期的自动流转只做一次。状态历史 id 由项目 id 与开始日期推导，
+//!   已存在该行（本机或同步而来）则跳过；两台设备离线时同时流转，同步后合并为同一行。
+//! - 生效流程不允许 BACKLOG → PLANNED 时不做任何流转；单个项目失败（如超出 WIP 上限）只记录，下次重试。
+//! - 只读副本上不执行自动流转（状态只来自同步）。
+
+use crate::app::project::project_change_status_with_history_id;
+use crate::app::workflow::load_active_workflow;
+use crate::app::ProjectChangeStatusReq;
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use crate::sync::replica::is_read_only_replica;
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+pub const STATUS_AUTOMATION_OVERDUE_KEY: &str = "status_automation_overdue_enabled";
+pub const STATUS_AUTOMATION_AUTO_PLAN_KEY: &str = "status_automation_auto_plan_enabled";
+
+/// Serializes runs (hourly runtime vs. command) so a project is never moved twice.
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusAutomationConfigDto {
+    /// Compute the `overdue` flag of projects.
+    pub overdue_enabled: bool,
+    /// Move BACKLOG projects to PLANNED once their start date is reached.
+    pub auto_plan_enabled: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusAutomationConfigReq {
+    pub overdue_enabled: Option<bool>,
+    pub auto_plan_enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusAutomationItem {
+    pub project_id: String,
+    pub start_date: String,
+    pub to_status: String,
+    /// Why the project was not moved; it is retried on the next run.
+    pub error: Option<String>,
+}
+
+pub fn status_automation_get_config(pool: &DbPool) -> Result<StatusAutomationConfigDto, AppError> {
+    let conn = get_read_connection(pool);
+    Ok(load_config(&conn))
+}
+
+pub fn status_automation_update_config(
+    pool: &DbPool,
+    req: StatusAutomationConfigReq,
+) -> Result<StatusAutomationConfigDto, AppError> {
+    let conn = get_connection(pool);
+    for (key, value) in [
+        (STATUS_AUTOMATION_OVERDUE_KEY, req.overdue_enabled),
+        (STATUS_AUTOMATION_AUTO_PLAN_KEY, req.auto_plan_enabled),
+    ] {
+        if let Some(value) = value {
+            conn.execute(
+                "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+                [key, if value { "true" } else { "false" }],
+            )?;
+        }
+    }
+    Ok(load_config(&conn))
+}
+
+pub fn load_overdue_enabled(conn: &Connection) -> bool {
+    load_flag(conn, STATUS_AUTOMATION_OVERDUE_KEY)
+}
+
+pub fn load_auto_plan_enabled(conn: &Connection) -> bool {
+    load_flag(conn, STATUS_AUTOMATION_AUTO_PLAN_KEY)
+}
+
+/// Past its due date and neither DONE nor ARCHIVED.
+pub(crate) fn is_overdue(status: &str, due_date: Option<&str>, today: NaiveDate) -> bool {
+    status != "DONE"
+        && status != "ARCHIVED"
+        && due_date
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+            .is_some_and(|due| due < today)
+}
+
+/// Move every BACKLOG project whose start date is on or before `today` to PLANNED.
+/// Does nothing while auto-plan is off, on a read-only replica or when the active workflow
+/// has no BACKLOG → PLANNED transition.
+pub fn status_automation_run(
+    pool: &DbPool,
+    today: NaiveDate,
+) -> Result<Vec<StatusAutomationItem>, AppError> {
+    let _guard = RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let due = {
+        let conn = get_connection(pool);
+        if !load_auto_plan_enabled(&conn) || is_read_only_replica(&conn)? {
+            return Ok(Vec::new());
+        }
+        if !load_active_workflow(&conn)?.can_transition("BACKLOG", "PLANNED") {
+            return Ok(Vec::new());
+        }
+        let rows = conn
+            .prepare(
+                "SELECT id, start_date FROM projects
+                 WHERE current_status = 'BACKLOG' AND archived_at IS NULL AND deleted_at IS NULL
+                   AND start_date IS NOT NULL AND TRIM(start_date) <> ''
+                 ORDER BY start_date, name",
+            )?
+            .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut due = Vec::new();
+        for (project_id, start_date) in rows {
+            if !NaiveDate::parse_from_str(start_date.trim(), "%Y-%m-%d").is_ok_and(|d| d <= today) {
+                continue;
+            }
+            // Already planned for this start date (here or on a synced device): a project
+            // moved back to BACKLOG by hand stays there.
+            let history_id = auto_plan_history_id(&project_id, start_date.trim());
+            let planned = conn
+                .query_row(
+                    "SELECT 1 FROM status_history WHERE id = ?1",
+                    [&history_id],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+            if !planned {
+                due.push((project_id, start_date, history_id));
+            }
+        }
+        due
+    };
+
+    let mut items = Vec::new();
+    for (project_id, start_date, history_id) in due {
+        let result = project_change_status_with_history_id(
+            pool,
+            ProjectChangeStatusReq {
+                project_id: project_id.clone(),
+                to_status: "PLANNED".to_string(),
+                note: Some(format!("[auto] start date {} reached", start_date.trim())),
+                changed_by_person_id: None,
+                if_match_updated_at: None,
+                force: None,
+            },
+            history_id,
+        );
+        let error = match result {
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("Auto-plan of project {} failed: {}", project_id, e);
+                Some(e.to_string())
+            }
+        };
+        items.push(StatusAutomationItem {
+            project_id,
+            start_date,
+            to_status: "PLANNED".to_string(),
+            error,
+        });
+    }
+    Ok(items)
+}
+
+/// Same id on every device for one project and start date, so concurrent auto-plans on
+/// two devices merge into a single `status_history` row when they sync.
+fn auto_plan_history_id(project_id: &str, start_date: &str) -> String {
+    let digest = Sha256::digest(format!("auto-plan:{}:{}", project_id, start_date).as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    // RFC 9562 version 8 (custom) with the RFC variant.
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    Uuid::from_bytes(bytes).to_string()
+}
+
+fn load_config(conn: &Connection) -> StatusAutomationConfigDto {
+    StatusAutomationConfigDto {
+        overdue_enabled: load_overdue_enabled(conn),
+        auto_plan_enabled: load_auto_plan_enabled(conn),
+    }
+}
+
+fn load_flag(conn: &Connection, key: &str) -> bool {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .is_some_and(|v| v.trim() == "true")
+}
@@ -0,0 +1,391 @@
+//! Tag catalog and bulk tag maintenance (rename / merge / delete).
+//!
+//! - 标签本身仍是 `project_tags` 上的字符串（去首尾空格、区分大小写）；目录表 `tags` 只为标签名
+//!   附加颜色（`#rrggbb`）与说明。没有目录行的标签照常列出，颜色为空。
+//! - 目录仅本机保存，不参与同步（同模板、保存的视图）；改名、合并改写的是 `project_tags`，
+//!   照常通过标签集合合并同步到其他设备。
+//! - 改名 / 合并覆盖所有带该标签的项目（包括回收站中的项目），在一个事务内逐个项目删除旧标签、
+//!   加入新标签，并记入项目审计日志；已带目标标签的项目只删除旧标签。
+//! - 改名的目标已存在时拒绝（应改用合并）；合并保留目标的颜色与说明，目标为空的字段取自源标签。
+//! - 使用计数只统计未删除的项目；仍被项目使用的标签不能删除，`tag_delete_unused` 一次清理
+//!   所有未被使用的目录行。
+
+use crate::app::audit::{audit_capture, audit_record, AuditEntity};
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagDto {
+    pub name: String,
+    /// `#rrggbb`; `None` when unset or the tag has no catalog entry.
+    pub color: Option<String>,
+    pub description: String,
+    /// Projects (not in the trash) carrying the tag.
+    pub project_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagCreateReq {
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagUpdateReq {
+    pub name: String,
+    /// Empty string clears the color; omitted = keep.
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRenameReq {
+    pub from: String,
+    /// Must not exist yet (use `tag_merge` to combine two tags).
+    pub to: String,
+    /// Recorded in the audit log.
+    #[serde(default)]
+    pub changed_by_person_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMergeReq {
+    /// Tag that disappears.
+    pub source: String,
+    /// Tag that receives the projects; keeps its color and description.
+    pub target: String,
+    /// Recorded in the audit log.
+    #[serde(default)]
+    pub changed_by_person_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagChangeResult {
+    pub tag: TagDto,
+    /// Projects whose tags changed (including projects in the trash).
+    pub projects_updated: usize,
+}
+
+/// Catalog entries plus every tag in use, with color, description and usage count.
+const TAG_SELECT: &str = "SELECT n.name, t.color, COALESCE(t.description, ''),
+        (SELECT COUNT(*) FROM project_tags pt JOIN projects p ON p.id = pt.project_id
+         WHERE pt.tag = n.name AND p.deleted_at IS NULL)
+     FROM (SELECT name FROM tags
+           UNION
+           SELECT pt.tag FROM project_tags pt JOIN projects p ON p.id = pt.project_id
+           WHERE p.deleted_at IS NULL) n
+     LEFT JOIN tags t ON t.name = n.name";
+
+/// All tags by name (case-insensitive).
+pub fn tag_list(pool: &DbPool) -> Result<Vec<TagDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let tags = conn
+        .prepare(&format!(
+            "{} ORDER BY n.name COLLATE NOCASE, n.name",
+            TAG_SELECT
+        ))?
+        .query_map([], tag_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(tags)
+}
+
+/// Add a catalog entry (the tag does not have to be in use yet).
+pub fn tag_create(pool: &DbPool, req: TagCreateReq) -> Result<TagDto, AppError> {
+    let name = validate_name(&req.name)?;
+    let color = normalize_color(req.color.as_deref())?;
+    let description = req.description.as_deref().unwrap_or("").trim();
+
+    let conn = get_connection(pool);
+    if catalog_has(&conn, name)? {
+        return Err(AppError::Conflict(format!("tag already exists: {}", name)));
+    }
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO tags (name, color, description, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+        params![name, color, description, &now],
+    )?;
+    load_tag(&conn, name)
+}
+
+/// Set the color / description of a catalog entry or of a tag in use (adding its catalog entry).
+pub fn tag_update(pool: &DbPool, req: TagUpdateReq) -> Result<TagDto, AppError> {
+    let name = req.name.trim();
+    let color = req
+        .color
+        .as_deref()
+        .map(|c| normalize_color(Some(c)))
+        .transpose()?;
+
+    let conn = get_connection(pool);
+    if !tag_exists(&conn, name)? {
+        return Err(AppError::NotFound(format!("tag {}", name)));
+    }
+    let now = Utc::now().to_rfc3339();
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT OR IGNORE INTO tags (name, color, description, created_at, updated_at) VALUES (?1, NULL, '', ?2, ?2)",
+        params![name, &now],
+    )?;
+    if let Some(color) = color {
+        tx.execute(
+            "UPDATE tags SET color = ?1, updated_at = ?2 WHERE name = ?3",
+            params![color, &now, name],
+        )?;
+    }
+    if let Some(description) = req.description.as_deref() {
+        tx.execute(
+            "UPDATE tags SET description = ?1, updated_at = ?2 WHERE name = ?3",
+            params![description.trim(), &now, name],
+        )?;
+    }
+    tx.commit()?;
+    load_tag(&conn, name)
+}
+
+/// Rename a tag on every project and in the catalog.
+pub fn tag_rename(pool: &DbPool, req: TagRenameReq) -> Result<TagChangeResult, AppError> {
+    let from = req.from.trim();
+    let to = validate_name(&req.to)?;
+    if from == to {
+        return Err(AppError::Validation(
+            "new tag name is the same as the old one".into(),
+        ));
+    }
+
+    let conn = get_connection(pool);
+    if !tag_exists(&conn, from)? {
+        return Err(AppError::NotFound(format!("tag {}", from)));
+    }
+    if tag_exists(&conn, to)? {
+        return Err(AppError::Conflict(format!(
+            "tag already exists: {} (merge the tags instead)",
+            to
+        )));
+    }
+    let now = Utc::now().to_rfc3339();
+    let tx = conn.unchecked_transaction()?;
+    let projects_updated = retag(&tx, from, to, req.changed_by_person_id.as_deref(), &now)?;
+    tx.execute(
+        "UPDATE tags SET name = ?1, updated_at = ?2 WHERE name = ?3",
+        params![to, &now, from],
+    )?;
+    tx.commit()?;
+    Ok(TagChangeResult {
+        tag: load_tag(&conn, to)?,
+        projects_updated,
+    })
+}
+
+/// Replace `source` with `target` on every project, then drop `source` from the catalog.
+/// The target keeps its color and description; empty ones are filled from the source.
+pub fn tag_merge(pool: &DbPool, req: TagMergeReq) -> Result<TagChangeResult, AppError> {
+    let (source, target) = (req.source.trim(), req.target.trim());
+    if source == target {
+        return Err(AppError::Validation(
+            "cannot merge a tag into itself".into(),
+        ));
+    }
+
+    let conn = get_connection(pool);
+    for name in [source, target] {
+        if !tag_exists(&conn, name)? {
+            return Err(AppError::NotFound(format!("tag {}", name)));
+        }
+    }
+    let now = Utc::now().to_rfc3339();
+    let tx = conn.unchecked_transaction()?;
+    let projects_updated = retag(
+        &tx,
+        source,
+        target,
+        req.changed_by_person_id.as_deref(),
+        &now,
+    )?;
+    tx.execute(
+        "UPDATE tags SET
+             color = COALESCE(color, (SELECT color FROM tags WHERE name = ?2)),
+             description = CASE WHEN description = '' THEN (SELECT description FROM tags WHERE name = ?2)
+                                ELSE description END,
+             updated_at = ?3
+         WHERE name = ?1 AND EXISTS (SELECT 1 FROM tags WHERE name = ?2)",
+        params![target, source, &now],
+    )?;
+    // No target entry yet: the source entry becomes the target's.
+    tx.execute(
+        "UPDATE OR IGNORE tags SET name = ?1, updated_at = ?2 WHERE name = ?3",
+        params![target, &now, source],
+    )?;
+    tx.execute("DELETE FROM tags WHERE name = ?1", [source])?;
+    tx.commit()?;
+    Ok(TagChangeResult {
+        tag: load_tag(&conn, target)?,
+        projects_updated,
+    })
+}
+
+/// Delete the catalog entry of a tag no project uses.
+pub fn tag_delete(pool: &DbPool, name: &str) -> Result<(), AppError> {
+    let name = name.trim();
+    let conn = get_connection(pool);
+    let used = usage_count(&conn, name)?;
+    if used > 0 {
+        return Err(AppError::Conflict(format!(
+            "tag {} is used by {} project(s); remove it from them or merge it into another tag",
+            name, used
+        )));
+    }
+    if conn.execute("DELETE FROM tags WHERE name = ?1", [name])? == 0 {
+        return Err(AppError::NotFound(format!("tag {}", name)));
+    }
+    Ok(())
+}
+
+/// Delete every catalog entry no project uses; returns the deleted names.
+pub fn tag_delete_unused(pool: &DbPool) -> Result<Vec<String>, AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let unused: Vec<String> = tx
+        .prepare(
+            "SELECT name FROM tags t
+             WHERE NOT EXISTS (SELECT 1 FROM project_tags pt JOIN projects p ON p.id = pt.project_id
+                               WHERE pt.tag = t.name AND p.deleted_at IS NULL)
+             ORDER BY name COLLATE NOCASE, name",
+        )?
+        .query_map([], |r| r.get(0))?
+        .collect::<Result<_, _>>()?;
+    for name in &unused {
+        tx.execute("DELETE FROM tags WHERE name = ?1", [name])?;
+    }
+    tx.commit()?;
+    Ok(unused)
+}
+
+/// Move every `from` tag row to `to` (sync triggers emit the removal and the addition).
+/// Projects that already carry `to` only lose `from`.
+fn retag(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    changed_by: Option<&str>,
+    now: &str,
+) -> Result<usize, AppError> {
+    let projects: Vec<String> = conn
+        .prepare("SELECT project_id FROM project_tags WHERE tag = ?1 ORDER BY project_id")?
+        .query_map([from], |r| r.get(0))?
+        .collect::<Result<_, _>>()?;
+    for project_id in &projects {
+        let before = audit_capture(conn, AuditEntity::Project, project_id)?;
+        conn.execute(
+            "DELETE FROM project_tags WHERE project_id = ?1 AND tag = ?2",
+            params![project_id, from],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO project_tags (project_id, tag, created_at) VALUES (?1, ?2, ?3)",
+            params![project_id, to, now],
+        )?;
+        conn.execute(
+            "UPDATE projects SET updated_at = ?1, _version = _version + 1 WHERE id = ?2",
+            params![now, project_id],
+        )?;
+        audit_record(
+            conn,
+            AuditEntity::Project,
+            project_id,
+            before,
+            changed_by,
+            now,
+        )?;
+    }
+    Ok(projects.len())
+}
+
+/// A tag left only on projects in the trash is returned without color and with no usage.
+fn load_tag(conn: &Connection, name: &str) -> Result<TagDto, AppError> {
+    let tag = conn
+        .query_row(
+            &format!("{} WHERE n.name = ?1", TAG_SELECT),
+            [name],
+            tag_from_row,
+        )
+        .optional()?;
+    Ok(tag.unwrap_or_else(|| TagDto {
+        name: name.to_string(),
+        color: None,
+        description: String::new(),
+        project_count: 0,
+    }))
+}
+
+fn tag_from_row(row: &Row<'_>) -> rusqlite::Result<TagDto> {
+    Ok(TagDto {
+        name: row.get(0)?,
+        color: row.get(1)?,
+        description: row.get(2)?,
+        project_count: row.get(3)?,
+    })
+}
+
+/// In the catalog or on any project (including projects in the trash).
+fn tag_exists(conn: &Connection, name: &str) -> Result<bool, AppError> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM tags WHERE name = ?1)
+             OR EXISTS(SELECT 1 FROM project_tags WHERE tag = ?1)",
+        [name],
+        |r| r.get(0),
+    )?)
+}
+
+fn catalog_has(conn: &Connection, name: &str) -> Result<bool, AppError> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM tags WHERE name = ?1)",
+        [name],
+        |r| r.get(0),
+    )?)
+}
+
+fn usage_count(conn: &Connection, name: &str) -> Result<i64, AppError> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*) FROM project_tags pt JOIN projects p ON p.id = pt.project_id
+         WHERE pt.tag = ?1 AND p.deleted_at IS NULL",
+        [name],
+        |r| r.get(0),
+    )?)
+}
+
+fn validate_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("tag name must not be empty".into()));
+    }
+    Ok(name)
+}
+
+/// `#rgb` / `#rrggbb` (case-insensitive) stored as lowercase `#rrggbb`; empty = no color.
+fn normalize_color(color: Option<&str>) -> Result<Option<String>, AppError> {
+    let Some(color) = color.map(str::trim).filter(|c| !c.is_empty()) else {
+        return Ok(None);
+    };
+    let hex = color
+        .strip_prefix('#')
+        .filter(|h| (h.len() == 3 || h.len() == 6) && h.bytes().all(|b| b.is_ascii_hexdigit()))
+        .ok_or_else(|| {
+            AppError::Validation(format!("invalid tag color: {:?} (expected #rrggbb)", color))
+        })?
+        .to_ascii_lowercase();
+    let hex = if hex.len() == 3 {
+        hex.chars().flat_map(|c| [c, c]).collect()
+    } else {
+        hex
+    };
+    Ok(Some(format!("#{}", hex)))
+}
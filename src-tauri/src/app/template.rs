@@ -0,0 +1,384 @@
+//! Project template use cases: save a project as a template, instantiate, list, delete.
+//!
+//! 模板保存名称模式、默认优先级/国家/合作方/负责人、标签、默认成员与初始里程碑。
+//! - 名称模式占位符：`{date}`（YYYY-MM-DD）、`{month}`（YYYY-MM）、`{year}`、`{n}`（第 n 个实例）。
+//! - 里程碑没有独立实体：实例化时写成一条置顶评论（标题 + 由开始日期偏移得到的日期）。
+//! - 模板表仅本机保存，不参与同步；删除模板同时删除引用它的周期规则。
+
+use crate::app::{
+    assignment_add_member, comment_create, project_create, project_get, AssignmentAddReq,
+    CommentCreateReq, ProjectCreateReq, ProjectDetailDto,
+};
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const DEFAULT_NAME_PATTERN_SUFFIX: &str = " {date}";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateMemberDto {
+    pub person_id: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateMilestoneDto {
+    pub title: String,
+    /// Days after the project start date (or creation date when no start date).
+    pub offset_days: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTemplateDto {
+    pub id: String,
+    pub name: String,
+    pub name_pattern: String,
+    pub description: String,
+    pub priority: i32,
+    pub country_code: String,
+    pub partner_id: String,
+    pub owner_person_id: String,
+    pub product_name: Option<String>,
+    pub tags: Vec<String>,
+    pub members: Vec<TemplateMemberDto>,
+    pub milestones: Vec<TemplateMilestoneDto>,
+    pub instance_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSaveAsTemplateReq {
+    pub project_id: String,
+    /// Unique template name.
+    pub template_name: String,
+    /// Default: `<project name> {date}`.
+    pub name_pattern: Option<String>,
+    pub milestones: Option<Vec<TemplateMilestoneDto>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectCreateFromTemplateReq {
+    pub template_id: String,
+    /// Overrides the rendered name pattern.
+    pub name: Option<String>,
+    pub start_date: Option<String>,
+    pub due_date: Option<String>,
+    pub created_by_person_id: Option<String>,
+}
+
+/// Capture `project_id` (fields, tags, active non-owner members) as a new template.
+pub fn project_save_as_template(
+    pool: &DbPool,
+    req: ProjectSaveAsTemplateReq,
+) -> Result<ProjectTemplateDto, AppError> {
+    let template_name = req.template_name.trim().to_string();
+    if template_name.is_empty() {
+        return Err(AppError::Validation("template_name is required".into()));
+    }
+    let milestones = req.milestones.unwrap_or_default();
+    validate_milestones(&milestones)?;
+
+    let project = project_get(pool, &req.project_id)?;
+    let name_pattern = req
+        .name_pattern
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| format!("{}{}", project.name, DEFAULT_NAME_PATTERN_SUFFIX));
+    let members: Vec<TemplateMemberDto> = project
+        .assignments
+        .iter()
+        .filter(|a| a.end_at.is_none() && a.person_id != project.owner_person_id)
+        .map(|a| TemplateMemberDto {
+            person_id: a.person_id.clone(),
+            role: a.role.clone(),
+        })
+        .collect();
+
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let conn = get_connection(pool);
+    let exists: i64 = conn.query_row(
+        "SELECT COUNT(1) FROM project_templates WHERE name = ?1",
+        [&template_name],
+        |r| r.get(0),
+    )?;
+    if exists > 0 {
+        return Err(AppError::Conflict("template name must be unique".into()));
+    }
+    conn.execute(
+        "INSERT INTO project_templates (id, name, name_pattern, description, priority, country_code, partner_id, owner_person_id, product_name, tags, members, milestones, instance_count, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, 0, ?13, ?13)",
+        params![
+            &id,
+            &template_name,
+            &name_pattern,
+            &project.description,
+            project.priority,
+            &project.country_code,
+            &project.partner_id,
+            &project.owner_person_id,
+            &project.product_name,
+            to_json(&project.tags)?,
+            to_json(&members)?,
+            to_json(&milestones)?,
+            &now
+        ],
+    )?;
+    load_template(&conn, &id)
+}
+
+/// Create a project from a template: members are added (inactive persons skipped) and
+/// milestones are written as a pinned comment. Increments the template's instance count.
+pub fn project_create_from_template(
+    pool: &DbPool,
+    req: ProjectCreateFromTemplateReq,
+) -> Result<ProjectDetailDto, AppError> {
+    instantiate_template(pool, req, Utc::now().date_naive(), None)
+}
+
+/// `today`: date used for name placeholders and milestone offsets (when no start date).
+/// `history_note`: note on the initial status history entry.
+pub(crate) fn instantiate_template(
+    pool: &DbPool,
+    req: ProjectCreateFromTemplateReq,
+    today: NaiveDate,
+    history_note: Option<&str>,
+) -> Result<ProjectDetailDto, AppError> {
+    let template = {
+        let conn = get_connection(pool);
+        let template = load_template(&conn, &req.template_id)?;
+        conn.execute(
+            "UPDATE project_templates SET instance_count = instance_count + 1 WHERE id = ?1",
+            [&template.id],
+        )?;
+        template
+    };
+    let name = req
+        .name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| {
+            render_name_pattern(&template.name_pattern, today, template.instance_count + 1)
+        });
+    let start_date = req.start_date.filter(|s| !s.trim().is_empty());
+
+    let created = project_create(
+        pool,
+        ProjectCreateReq {
+            name,
+            description: Some(template.description.clone()),
+            priority: Some(template.priority),
+            country_code: template.country_code.clone(),
+            partner_id: template.partner_id.clone(),
+            owner_person_id: template.owner_person_id.clone(),
+            product_name: template.product_name.clone(),
+            start_date: start_date.clone(),
+            due_date: req.due_date,
+            tags: Some(template.tags.clone()),
+            created_by_person_id: req.created_by_person_id.clone(),
+            parent_project_id: None,
+        },
+    );
+    let project = match created {
+        Ok(project) => project,
+        Err(e) => {
+            let conn = get_connection(pool);
+            conn.execute(
+                "UPDATE project_templates SET instance_count = instance_count - 1 WHERE id = ?1",
+                [&template.id],
+            )?;
+            return Err(e);
+        }
+    };
+
+    if let Some(note) = history_note {
+        let conn = get_connection(pool);
+        conn.execute(
+            "UPDATE status_history SET note = ?1 WHERE project_id = ?2 AND from_status IS NULL",
+            params![note, &project.id],
+        )?;
+    }
+
+    for member in &template.members {
+        if member.person_id == template.owner_person_id
+            || !person_is_active(pool, &member.person_id)?
+        {
+            continue;
+        }
+        assignment_add_member(
+            pool,
+            AssignmentAddReq {
+                project_id: project.id.clone(),
+                person_id: member.person_id.clone(),
+                role: Some(member.role.clone()),
+                start_at: None,
+            },
+        )?;
+    }
+
+    if !template.milestones.is_empty() {
+        let base = start_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+            .unwrap_or(today);
+        comment_create(
+            pool,
+            CommentCreateReq {
+                project_id: project.id.clone(),
+                person_id: req.created_by_person_id,
+                content: milestone_comment(&template.milestones, base),
+                is_pinned: Some(true),
+            },
+        )?;
+    }
+
+    project_get(pool, &project.id)
+}
+
+pub fn template_list(pool: &DbPool) -> Result<Vec<ProjectTemplateDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let ids = conn
+        .prepare("SELECT id FROM project_templates ORDER BY name")?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    ids.iter().map(|id| load_template(&conn, id)).collect()
+}
+
+pub fn template_delete(pool: &DbPool, template_id: &str) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let changed = tx.execute("DELETE FROM project_templates WHERE id = ?1", [template_id])?;
+    if changed == 0 {
+        return Err(AppError::NotFound(format!("template {}", template_id)));
+    }
+    tx.execute(
+        "DELETE FROM recurrence_rules WHERE template_id = ?1",
+        [template_id],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Replace `{date}`, `{month}`, `{year}` and `{n}` in `pattern`.
+pub fn render_name_pattern(pattern: &str, date: NaiveDate, instance: i64) -> String {
+    pattern
+        .replace("{date}", &date.format("%Y-%m-%d").to_string())
+        .replace("{month}", &date.format("%Y-%m").to_string())
+        .replace("{year}", &date.format("%Y").to_string())
+        .replace("{n}", &instance.to_string())
+}
+
+pub(crate) fn load_template(conn: &Connection, id: &str) -> Result<ProjectTemplateDto, AppError> {
+    let row = conn
+        .query_row(
+            "SELECT id, name, name_pattern, description, priority, country_code, partner_id, owner_person_id, product_name, tags, members, milestones, instance_count, created_at, updated_at FROM project_templates WHERE id = ?1",
+            [id],
+            |r| {
+                Ok((
+                    ProjectTemplateDto {
+                        id: r.get(0)?,
+                        name: r.get(1)?,
+                        name_pattern: r.get(2)?,
+                        description: r.get(3)?,
+                        priority: r.get(4)?,
+                        country_code: r.get(5)?,
+                        partner_id: r.get(6)?,
+                        owner_person_id: r.get(7)?,
+                        product_name: r.get(8)?,
+                        tags: Vec::new(),
+                        members: Vec::new(),
+                        milestones: Vec::new(),
+                        instance_count: r.get(12)?,
+                        created_at: r.get(13)?,
+                        updated_at: r.get(14)?,
+                    },
+                    r.get::<_, String>(9)?,
+                    r.get::<_, String>(10)?,
+                    r.get::<_, String>(11)?,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((mut template, tags, members, milestones)) = row else {
+        return Err(AppError::NotFound(format!("template {}", id)));
+    };
+    template.tags = from_json(&tags)?;
+    template.members = from_json(&members)?;
+    template.milestones = from_json(&milestones)?;
+    Ok(template)
+}
+
+fn validate_milestones(milestones: &[TemplateMilestoneDto]) -> Result<(), AppError> {
+    for milestone in milestones {
+        if milestone.title.trim().is_empty() {
+            return Err(AppError::Validation("milestone title is required".into()));
+        }
+        if milestone.offset_days < 0 {
+            return Err(AppError::Validation(
+                "milestone offsetDays must be >= 0".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn milestone_comment(milestones: &[TemplateMilestoneDto], base: NaiveDate) -> String {
+    let mut sorted: Vec<&TemplateMilestoneDto> = milestones.iter().collect();
+    sorted.sort_by_key(|m| m.offset_days);
+    let mut content = String::from("Milestones:");
+    for milestone in sorted {
+        let due = base + chrono::Duration::days(milestone.offset_days);
+        content.push_str(&format!(
+            "\n- {} ({})",
+            milestone.title.trim(),
+            due.format("%Y-%m-%d")
+        ));
+    }
+    content
+}
+
+/// Milestones listed in a comment written by `milestone_comment`: `(title, date)` pairs.
+/// Other comments (or edited lines that no longer match) yield nothing.
+pub(crate) fn parse_milestone_comment(content: &str) -> Vec<(String, NaiveDate)> {
+    let mut lines = content.lines();
+    if lines.next().map(str::trim) != Some("Milestones:") {
+        return Vec::new();
+    }
+    lines
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("- ")?.strip_suffix(')')?;
+            let (title, date) = rest.rsplit_once(" (")?;
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+            Some((title.trim().to_string(), date)).filter(|(t, _)| !t.is_empty())
+        })
+        .collect()
+}
+
+fn person_is_active(pool: &DbPool, person_id: &str) -> Result<bool, AppError> {
+    let conn = get_connection(pool);
+    let active: Option<i32> = conn
+        .query_row(
+            "SELECT is_active FROM persons WHERE id = ?1",
+            [person_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    Ok(active.is_some_and(|a| a != 0))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, AppError> {
+    serde_json::to_string(value).map_err(|e| AppError::Db(e.to_string()))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(raw: &str) -> Result<Vec<T>, AppError> {
+    serde_json::from_str(raw).map_err(|e| AppError::Db(format!("corrupt template column: {}", e)))
+}
@@ -0,0 +1,365 @@
+//! Trash bin: soft delete, restore and purge for projects, persons and partners.
+//!
+//! - 删除只设置 `deleted_at`（并递增 `_version`）：行从列表、详情与报表中隐藏，可随时恢复；
+//!   同步时作为普通 UPDATE 传播，其它设备同样进入回收站。
+//! - 清空（purge）才真正 DELETE：生成同步 DELETE 操作与墓碑；项目连同其评论、成员、状态历史、
+//!   规模历史、标签与自定义字段值一起删除。
+//! - 仍被引用的条目不能删除 / 清除：有子项目的项目、仍是项目负责人或成员的人员、仍有项目的合作方。
+//! - 项目名称在清除前仍然占用（回收站中的项目恢复时不会出现重名）。
+
+use crate::error::AppError;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Project children removed together with a purged project.
+const PROJECT_CHILD_TABLES: &[&str] = &[
+    "project_custom_values",
+    "comment_reactions",
+    "comment_revisions",
+    "project_comments",
+    "checklist_items",
+    "project_tags",
+    "assignments",
+    "status_history",
+    "project_size_history",
+    "link_check_results",
+    "project_flags",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrashKind {
+    Project,
+    Person,
+    Partner,
+}
+
+impl TrashKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Project => "project",
+            Self::Person => "person",
+            Self::Partner => "partner",
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Project => "projects",
+            Self::Person => "persons",
+            Self::Partner => "partners",
+        }
+    }
+
+    fn name_column(&self) -> &'static str {
+        match self {
+            Self::Person => "display_name",
+            Self::Project | Self::Partner => "name",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItemReq {
+    pub kind: TrashKind,
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashPurgeReq {
+    /// Purge items deleted at least this many days ago; 0 = empty the whole trash.
+    pub older_than_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItemDto {
+    pub kind: TrashKind,
+    pub id: String,
+    pub name: String,
+    pub deleted_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashSkippedDto {
+    pub kind: TrashKind,
+    pub id: String,
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashPurgeResult {
+    pub purged: Vec<TrashItemDto>,
+    /// Old enough but still referenced; they stay in the trash.
+    pub skipped: Vec<TrashSkippedDto>,
+}
+
+/// Move a project, person or partner to the trash.
+pub fn trash_delete(pool: &DbPool, req: TrashItemReq) -> Result<TrashItemDto, AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let name = live_name(&tx, req.kind, &req.id)?
+        .ok_or_else(|| AppError::NotFound(format!("{} {}", req.kind.as_str(), req.id)))?;
+    if let Some(reason) = live_references(&tx, req.kind, &req.id)? {
+        return Err(AppError::Validation(format!(
+            "TRASH_IN_USE: {} cannot be deleted: {}",
+            name, reason
+        )));
+    }
+
+    let now = Utc::now().to_rfc3339();
+    tx.execute(
+        &format!(
+            "UPDATE {} SET deleted_at = ?1, updated_at = ?1, _version = _version + 1 WHERE id = ?2",
+            req.kind.table()
+        ),
+        params![now, req.id],
+    )?;
+    tx.commit()?;
+    Ok(TrashItemDto {
+        kind: req.kind,
+        id: req.id,
+        name,
+        deleted_at: now,
+    })
+}
+
+/// Everything in the trash, most recently deleted first.
+pub fn trash_list(pool: &DbPool) -> Result<Vec<TrashItemDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let mut items = Vec::new();
+    for kind in [TrashKind::Project, TrashKind::Person, TrashKind::Partner] {
+        items.extend(trashed(&conn, kind, None)?);
+    }
+    items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(items)
+}
+
+/// Take an item out of the trash. A project needs its partner, owner and parent restored first.
+pub fn trash_restore(pool: &DbPool, req: TrashItemReq) -> Result<TrashItemDto, AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let item = trashed(&tx, req.kind, None)?
+        .into_iter()
+        .find(|item| item.id == req.id)
+        .ok_or_else(|| AppError::NotFound(format!("{} {} in trash", req.kind.as_str(), req.id)))?;
+
+    if req.kind == TrashKind::Project {
+        let blockers: Vec<String> = tx
+            .prepare(
+                "SELECT 'partner ' || pt.name FROM projects p
+                   JOIN partners pt ON pt.id = p.partner_id AND pt.deleted_at IS NOT NULL
+                 WHERE p.id = ?1
+                 UNION ALL
+                 SELECT 'person ' || pe.display_name FROM projects p
+                   JOIN persons pe ON pe.id = p.owner_person_id AND pe.deleted_at IS NOT NULL
+                 WHERE p.id = ?1
+                 UNION ALL
+                 SELECT 'parent project ' || pp.name FROM projects p
+                   JOIN projects pp ON pp.id = p.parent_project_id AND pp.deleted_at IS NOT NULL
+                 WHERE p.id = ?1",
+            )?
+            .query_map([&req.id], |r| r.get(0))?
+            .collect::<Result<_, _>>()?;
+        if !blockers.is_empty() {
+            return Err(AppError::Validation(format!(
+                "TRASH_RESTORE_BLOCKED: restore {} first",
+                blockers.join(", ")
+            )));
+        }
+    }
+
+    tx.execute(
+        &format!(
+            "UPDATE {} SET deleted_at = NULL, updated_at = ?1, _version = _version + 1 WHERE id = ?2",
+            req.kind.table()
+        ),
+        params![Utc::now().to_rfc3339(), req.id],
+    )?;
+    tx.commit()?;
+    Ok(item)
+}
+
+/// Permanently delete items that have been in the trash for at least `older_than_days` days.
+pub fn trash_purge(
+    pool: &DbPool,
+    req: TrashPurgeReq,
+    now: DateTime<Utc>,
+) -> Result<TrashPurgeResult, AppError> {
+    if req.older_than_days < 0 {
+        return Err(AppError::Validation("older_than_days must be >= 0".into()));
+    }
+    let cutoff = now - Duration::days(req.older_than_days);
+
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    let mut result = TrashPurgeResult {
+        purged: Vec::new(),
+        skipped: Vec::new(),
+    };
+    // Projects first: purging them can free their owners and partners.
+    for kind in [TrashKind::Project, TrashKind::Person, TrashKind::Partner] {
+        let mut pending = trashed(&tx, kind, Some(cutoff))?;
+        // Repeat while progress is made: a purged sub-project unblocks its parent.
+        loop {
+            let mut blocked = Vec::new();
+            let count = pending.len();
+            for item in pending {
+                if let Some(reason) = purge_blocker(&tx, kind, &item.id)? {
+                    blocked.push((item, reason));
+                    continue;
+                }
+                if kind == TrashKind::Project {
+                    for table in PROJECT_CHILD_TABLES {
+                        tx.execute(
+                            &format!("DELETE FROM {} WHERE project_id = ?1", table),
+                            [&item.id],
+                        )?;
+                    }
+                }
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE id = ?1", kind.table()),
+                    [&item.id],
+                )?;
+                result.purged.push(item);
+            }
+            if blocked.is_empty() || blocked.len() == count {
+                result
+                    .skipped
+                    .extend(blocked.into_iter().map(|(item, reason)| TrashSkippedDto {
+                        kind,
+                        id: item.id,
+                        name: item.name,
+                        reason,
+                    }));
+                break;
+            }
+            pending = blocked.into_iter().map(|(item, _)| item).collect();
+        }
+    }
+    tx.commit()?;
+    Ok(result)
+}
+
+/// Name of a row that is not in the trash.
+fn live_name(conn: &Connection, kind: TrashKind, id: &str) -> Result<Option<String>, AppError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM {} WHERE id = ?1 AND deleted_at IS NULL",
+                kind.name_column(),
+                kind.table()
+            ),
+            [id],
+            |r| r.get(0),
+        )
+        .optional()?)
+}
+
+/// Trashed rows of one kind, optionally only those deleted at or before `cutoff`.
+fn trashed(
+    conn: &Connection,
+    kind: TrashKind,
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<Vec<TrashItemDto>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, {}, deleted_at FROM {} WHERE deleted_at IS NOT NULL ORDER BY deleted_at",
+        kind.name_column(),
+        kind.table()
+    ))?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok(TrashItemDto {
+                kind,
+                id: r.get(0)?,
+                name: r.get(1)?,
+                deleted_at: r.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows
+        .into_iter()
+        .filter(|item| {
+            cutoff.map_or(true, |cutoff| {
+                DateTime::parse_from_rfc3339(item.deleted_at.trim())
+                    .is_ok_and(|at| at.with_timezone(&Utc) <= cutoff)
+            })
+        })
+        .collect())
+}
+
+/// Why a live row cannot go to the trash (live projects still depend on it).
+fn live_references(
+    conn: &Connection,
+    kind: TrashKind,
+    id: &str,
+) -> Result<Option<String>, AppError> {
+    let checks: &[(&str, &str)] = match kind {
+        TrashKind::Project => &[(
+            "SELECT COUNT(1) FROM projects WHERE parent_project_id = ?1 AND deleted_at IS NULL",
+            "it has sub-projects",
+        )],
+        TrashKind::Person => &[
+            (
+                "SELECT COUNT(1) FROM projects WHERE owner_person_id = ?1 AND deleted_at IS NULL",
+                "owner of projects",
+            ),
+            (
+                "SELECT COUNT(1) FROM assignments a JOIN projects p ON p.id = a.project_id
+                 WHERE a.person_id = ?1 AND a.end_at IS NULL AND p.deleted_at IS NULL",
+                "active member of projects",
+            ),
+        ],
+        TrashKind::Partner => &[(
+            "SELECT COUNT(1) FROM projects WHERE partner_id = ?1 AND deleted_at IS NULL",
+            "it has projects",
+        )],
+    };
+    first_reference(conn, checks, id)
+}
+
+/// Why a trashed row cannot be purged (any remaining row still points at it).
+fn purge_blocker(conn: &Connection, kind: TrashKind, id: &str) -> Result<Option<String>, AppError> {
+    let checks: &[(&str, &str)] = match kind {
+        TrashKind::Project => &[(
+            "SELECT COUNT(1) FROM projects WHERE parent_project_id = ?1",
+            "sub-projects still in the trash",
+        )],
+        TrashKind::Person => &[
+            (
+                "SELECT COUNT(1) FROM projects WHERE owner_person_id = ?1",
+                "still owner of projects",
+            ),
+            (
+                "SELECT COUNT(1) FROM assignments WHERE person_id = ?1",
+                "still referenced by project memberships",
+            ),
+        ],
+        TrashKind::Partner => &[(
+            "SELECT COUNT(1) FROM projects WHERE partner_id = ?1",
+            "still has projects",
+        )],
+    };
+    first_reference(conn, checks, id)
+}
+
+fn first_reference(
+    conn: &Connection,
+    checks: &[(&str, &str)],
+    id: &str,
+) -> Result<Option<String>, AppError> {
+    for (sql, reason) in checks {
+        let count: i64 = conn.query_row(sql, [id], |r| r.get(0))?;
+        if count > 0 {
+            return Ok(Some(reason.to_string()));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,461 @@
+//! Outbound webhooks: register endpoints, queue events, deliver with retry / backoff.
+//!
+//! - 事件：`project.status_changed`、`comment.created`、`sync.completed`；webhook 的 `events` 为空表示订阅全部。
+//! - 事件在产生它的事务内写入 `webhook_deliveries`（outbox），由 `WebhookRuntime` 异步发送，
+//!   不阻塞业务操作；发送时不持有数据库锁。
+//! - 请求体：`{ id, event, occurredAt, data }`；配置了 secret 时带 `X-Projex-Signature`（HMAC-SHA256）。
+//! - 2xx 视为成功；其余状态码或网络错误按指数退避重试（30s 起，每次翻倍，最长 1h），
+//!   共 `WEBHOOK_MAX_ATTEMPTS` 次后标记为 failed。投递记录即投递日志，保留最近 `WEBHOOK_DELIVERY_MAX_ROWS` 条。
+//! - 停用的 webhook 不发送（待发送的投递保留，重新启用后继续）；删除 webhook 同时删除其投递记录。
+
+use crate::error::AppError;
+use crate::infra::webhook::{
+    signature, WebhookClient, WebhookRequest, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER,
+};
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+pub const WEBHOOK_EVENT_PROJECT_STATUS_CHANGED: &str = "project.status_changed";
+pub const WEBHOOK_EVENT_COMMENT_CREATED: &str = "comment.created";
+pub const WEBHOOK_EVENT_SYNC_COMPLETED: &str = "sync.completed";
+pub const WEBHOOK_EVENTS: &[&str] = &[
+    WEBHOOK_EVENT_PROJECT_STATUS_CHANGED,
+    WEBHOOK_EVENT_COMMENT_CREATED,
+    WEBHOOK_EVENT_SYNC_COMPLETED,
+];
+
+/// Attempts per delivery before it is marked failed.
+pub const WEBHOOK_MAX_ATTEMPTS: i64 = 6;
+/// Delay before the first retry; doubles with each further attempt.
+pub const WEBHOOK_RETRY_BASE_SECS: i64 = 30;
+pub const WEBHOOK_RETRY_MAX_SECS: i64 = 60 * 60;
+/// Deliveries sent per dispatch round; the rest wait for the next round.
+pub const WEBHOOK_DISPATCH_BATCH: i64 = 50;
+/// Keep at most this many finished (delivered / failed) deliveries.
+pub const WEBHOOK_DELIVERY_MAX_ROWS: i64 = 1000;
+
+/// Serializes dispatch rounds (runtime vs. command) so a delivery is never sent twice.
+static DISPATCH_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDto {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    /// The secret itself is never returned.
+    pub has_secret: bool,
+    /// Empty = all events.
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookCreateReq {
+    pub name: String,
+    pub url: String,
+    pub secret: Option<String>,
+    /// Default: all events.
+    pub events: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookUpdateReq {
+    pub id: String,
+    pub name: Option<String>,
+    pub url: Option<String>,
+    /// `""` removes the secret; omitted keeps it.
+    pub secret: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryDto {
+    pub id: i64,
+    pub webhook_id: String,
+    pub event: String,
+    pub payload: String,
+    /// "pending" | "delivered" | "failed"
+    pub status: String,
+    pub attempts: i64,
+    pub next_attempt_at: Option<String>,
+    pub last_status_code: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryListReq {
+    pub webhook_id: Option<String>,
+    /// "pending" | "delivered" | "failed"
+    pub status: Option<String>,
+    /// Default 100, max 500.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDispatchResult {
+    pub delivered: usize,
+    /// Attempts that failed and will be retried.
+    pub retrying: usize,
+    /// Deliveries that gave up in this round.
+    pub failed: usize,
+}
+
+pub fn webhook_create(pool: &DbPool, req: WebhookCreateReq) -> Result<WebhookDto, AppError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".into()));
+    }
+    let url = validate_url(&req.url)?;
+    let events = validate_events(req.events.unwrap_or_default())?;
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    let conn = get_connection(pool);
+    conn.execute(
+        "INSERT INTO webhooks (id, name, url, secret, events, enabled, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+        params![
+            &id,
+            name,
+            url,
+            req.secret.unwrap_or_default().trim(),
+            events_json(&events)?,
+            req.enabled.unwrap_or(true) as i32,
+            &now
+        ],
+    )?;
+    load_webhook(&conn, &id)
+}
+
+pub fn webhook_update(pool: &DbPool, req: WebhookUpdateReq) -> Result<WebhookDto, AppError> {
+    let conn = get_connection(pool);
+    load_webhook(&conn, &req.id)?;
+    let now = Utc::now().to_rfc3339();
+    if let Some(name) = req.name.as_deref().map(str::trim) {
+        if name.is_empty() {
+            return Err(AppError::Validation("name is required".into()));
+        }
+        conn.execute(
+            "UPDATE webhooks SET name = ?1, updated_at = ?2 WHERE id = ?3",
+            params![name, &now, &req.id],
+        )?;
+    }
+    if let Some(url) = req.url.as_deref() {
+        conn.execute(
+            "UPDATE webhooks SET url = ?1, updated_at = ?2 WHERE id = ?3",
+            params![validate_url(url)?, &now, &req.id],
+        )?;
+    }
+    if let Some(secret) = req.secret.as_deref() {
+        conn.execute(
+            "UPDATE webhooks SET secret = ?1, updated_at = ?2 WHERE id = ?3",
+            params![secret.trim(), &now, &req.id],
+        )?;
+    }
+    if let Some(events) = req.events {
+        let events = validate_events(events)?;
+        conn.execute(
+            "UPDATE webhooks SET events = ?1, updated_at = ?2 WHERE id = ?3",
+            params![events_json(&events)?, &now, &req.id],
+        )?;
+    }
+    if let Some(enabled) = req.enabled {
+        conn.execute(
+            "UPDATE webhooks SET enabled = ?1, updated_at = ?2 WHERE id = ?3",
+            params![enabled as i32, &now, &req.id],
+        )?;
+    }
+    load_webhook(&conn, &req.id)
+}
+
+pub fn webhook_delete(pool: &DbPool, webhook_id: &str) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "DELETE FROM webhook_deliveries WHERE webhook_id = ?1",
+        [webhook_id],
+    )?;
+    let changed = tx.execute("DELETE FROM webhooks WHERE id = ?1", [webhook_id])?;
+    if changed == 0 {
+        return Err(AppError::NotFound(format!("webhook {}", webhook_id)));
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn webhook_list(pool: &DbPool) -> Result<Vec<WebhookDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let ids = conn
+        .prepare("SELECT id FROM webhooks ORDER BY name")?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    ids.iter().map(|id| load_webhook(&conn, id)).collect()
+}
+
+/// Delivery log, newest first.
+pub fn webhook_delivery_list(
+    pool: &DbPool,
+    req: WebhookDeliveryListReq,
+) -> Result<Vec<WebhookDeliveryDto>, AppError> {
+    if let Some(status) = req.status.as_deref() {
+        if !matches!(status, "pending" | "delivered" | "failed") {
+            return Err(AppError::Validation(format!(
+                "unknown delivery status: {}",
+                status
+            )));
+        }
+    }
+    let limit = req.limit.unwrap_or(100).clamp(1, 500);
+    let conn = get_read_connection(pool);
+    let mut stmt = conn.prepare(
+        "SELECT id, webhook_id, event, payload, status, attempts, next_attempt_at, last_status_code, last_error, created_at, delivered_at
+         FROM webhook_deliveries
+         WHERE (?1 IS NULL OR webhook_id = ?1) AND (?2 IS NULL OR status = ?2)
+         ORDER BY id DESC LIMIT ?3",
+    )?;
+    let items = stmt
+        .query_map(params![req.webhook_id, req.status, limit], |r| {
+            Ok(WebhookDeliveryDto {
+                id: r.get(0)?,
+                webhook_id: r.get(1)?,
+                event: r.get(2)?,
+                payload: r.get(3)?,
+                status: r.get(4)?,
+                attempts: r.get(5)?,
+                next_attempt_at: r.get(6)?,
+                last_status_code: r.get(7)?,
+                last_error: r.get(8)?,
+                created_at: r.get(9)?,
+                delivered_at: r.get(10)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Queue `event` for every enabled webhook subscribed to it. Call inside the writing
+/// transaction; a no-op when no webhook is configured.
+pub(crate) fn webhook_enqueue(conn: &Connection, event: &str, data: Value) -> Result<(), AppError> {
+    let mut targets = Vec::new();
+    let mut stmt = conn.prepare("SELECT id, events FROM webhooks WHERE enabled = 1")?;
+    for row in stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?)))? {
+        let (id, events) = row?;
+        let events: Vec<String> = serde_json::from_str(&events).unwrap_or_default();
+        if events.is_empty() || events.iter().any(|e| e == event) {
+            targets.push(id);
+        }
+    }
+    if targets.is_empty() {
+        return Ok(());
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let payload = json!({
+        "id": Uuid::new_v4().to_string(),
+        "event": event,
+        "occurredAt": &now,
+        "data": data,
+    })
+    .to_string();
+    for webhook_id in targets {
+        conn.execute(
+            "INSERT INTO webhook_deliveries (webhook_id, event, payload, status, attempts, next_attempt_at, created_at)
+             VALUES (?1, ?2, ?3, 'pending', 0, ?4, ?4)",
+            params![webhook_id, event, &payload, &now],
+        )?;
+    }
+    conn.execute(
+        "DELETE FROM webhook_deliveries WHERE status <> 'pending' AND id NOT IN (
+             SELECT id FROM webhook_deliveries WHERE status <> 'pending' ORDER BY id DESC LIMIT ?1)",
+        [WEBHOOK_DELIVERY_MAX_ROWS],
+    )?;
+    Ok(())
+}
+
+/// Best-effort `webhook_enqueue`: a webhook problem must never fail the business operation.
+pub(crate) fn webhook_notify(conn: &Connection, event: &str, data: Value) {
+    if let Err(e) = webhook_enqueue(conn, event, data) {
+        log::warn!("Failed to queue webhook event {}: {}", event, e);
+    }
+}
+
+/// Send due deliveries over HTTP. Blocking: call from `spawn_blocking` in async code.
+pub fn webhook_dispatch_due(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+) -> Result<WebhookDispatchResult, AppError> {
+    let client = WebhookClient::new().map_err(AppError::Sync)?;
+    webhook_dispatch_due_with(pool, now, &|req| client.post(req))
+}
+
+/// Dispatch with a caller-supplied sender (tests use a fake one instead of the network).
+pub fn webhook_dispatch_due_with(
+    pool: &DbPool,
+    now: DateTime<Utc>,
+    send: &dyn Fn(&WebhookRequest) -> Result<u16, String>,
+) -> Result<WebhookDispatchResult, AppError> {
+    let _guard = DISPATCH_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let due: Vec<(i64, String, String, String, String, i64)> = {
+        let conn = get_connection(pool);
+        let mut stmt = conn.prepare(
+            "SELECT d.id, d.event, d.payload, w.url, w.secret, d.attempts
+             FROM webhook_deliveries d JOIN webhooks w ON w.id = d.webhook_id
+             WHERE d.status = 'pending' AND w.enabled = 1
+               AND (d.next_attempt_at IS NULL OR d.next_attempt_at <= ?1)
+             ORDER BY d.id LIMIT ?2",
+        )?;
+        let rows = stmt
+            .query_map(params![now.to_rfc3339(), WEBHOOK_DISPATCH_BATCH], |r| {
+                Ok((
+                    r.get(0)?,
+                    r.get(1)?,
+                    r.get(2)?,
+                    r.get(3)?,
+                    r.get(4)?,
+                    r.get(5)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    let mut result = WebhookDispatchResult::default();
+    for (id, event, payload, url, secret, attempts) in due {
+        let mut headers = vec![
+            (EVENT_HEADER.to_string(), event),
+            (DELIVERY_HEADER.to_string(), id.to_string()),
+        ];
+        if !secret.is_empty() {
+            headers.push((SIGNATURE_HEADER.to_string(), signature(&secret, &payload)));
+        }
+        // The DB lock is not held while sending.
+        let outcome = send(&WebhookRequest {
+            url,
+            headers,
+            body: payload,
+        });
+
+        let attempts = attempts + 1;
+        let (status_code, error) = match outcome {
+            Ok(code) if (200..300).contains(&code) => (Some(code), None),
+            Ok(code) => (Some(code), Some(format!("HTTP {}", code))),
+            Err(e) => (None, Some(e)),
+        };
+        let conn = get_connection(pool);
+        match error {
+            None => {
+                conn.execute(
+                    "UPDATE webhook_deliveries SET status = 'delivered', attempts = ?1, next_attempt_at = NULL,
+                         last_status_code = ?2, last_error = NULL, delivered_at = ?3 WHERE id = ?4",
+                    params![attempts, status_code, now.to_rfc3339(), id],
+                )?;
+                result.delivered += 1;
+            }
+            Some(error) if attempts >= WEBHOOK_MAX_ATTEMPTS => {
+                conn.execute(
+                    "UPDATE webhook_deliveries SET status = 'failed', attempts = ?1, next_attempt_at = NULL,
+                         last_status_code = ?2, last_error = ?3 WHERE id = ?4",
+                    params![attempts, status_code, error, id],
+                )?;
+                result.failed += 1;
+            }
+            Some(error) => {
+                let next = now + chrono::Duration::seconds(retry_delay_secs(attempts));
+                conn.execute(
+                    "UPDATE webhook_deliveries SET attempts = ?1, next_attempt_at = ?2,
+                         last_status_code = ?3, last_error = ?4 WHERE id = ?5",
+                    params![attempts, next.to_rfc3339(), status_code, error, id],
+                )?;
+                result.retrying += 1;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Delay after the `attempts`-th failed attempt.
+pub fn retry_delay_secs(attempts: i64) -> i64 {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    (WEBHOOK_RETRY_BASE_SECS * 2i64.pow(exponent)).min(WEBHOOK_RETRY_MAX_SECS)
+}
+
+fn validate_url(raw: &str) -> Result<String, AppError> {
+    let url = raw.trim();
+    let lower = url.to_ascii_lowercase();
+    let host = lower
+        .strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"));
+    match host {
+        Some(rest) if !rest.is_empty() && !rest.starts_with('/') => Ok(url.to_string()),
+        _ => Err(AppError::Validation(
+            "url must be an http(s) URL".to_string(),
+        )),
+    }
+}
+
+fn validate_events(events: Vec<String>) -> Result<Vec<String>, AppError> {
+    let mut out: Vec<String> = Vec::new();
+    for event in events {
+        let event = event.trim();
+        if !WEBHOOK_EVENTS.contains(&event) {
+            return Err(AppError::Validation(format!(
+                "unknown webhook event: {}",
+                event
+            )));
+        }
+        if !out.iter().any(|e| e == event) {
+            out.push(event.to_string());
+        }
+    }
+    Ok(out)
+}
+
+fn events_json(events: &[String]) -> Result<String, AppError> {
+    serde_json::to_string(events).map_err(|e| AppError::Db(e.to_string()))
+}
+
+fn load_webhook(conn: &Connection, id: &str) -> Result<WebhookDto, AppError> {
+    let row = conn
+        .query_row(
+            "SELECT id, name, url, secret, events, enabled, created_at, updated_at FROM webhooks WHERE id = ?1",
+            [id],
+            |r| {
+                Ok((
+                    WebhookDto {
+                        id: r.get(0)?,
+                        name: r.get(1)?,
+                        url: r.get(2)?,
+                        has_secret: !r.get::<_, String>(3)?.is_empty(),
+                        events: Vec::new(),
+                        enabled: r.get::<_, i32>(5)? != 0,
+                        created_at: r.get(6)?,
+                        updated_at: r.get(7)?,
+                    },
+                    r.get::<_, String>(4)?,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((mut webhook, events)) = row else {
+        return Err(AppError::NotFound(format!("webhook {}", id)));
+    };
+    webhook.events = serde_json::from_str(&events)
+        .map_err(|e| AppError::Db(format!("corrupt webhook events: {}", e)))?;
+    Ok(webhook)
+}
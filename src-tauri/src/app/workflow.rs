@@ -0,0 +1,348 @@
+//! Status workflow use cases: list, create, update, activate, delete.
+//!
+//! - 流程定义状态（看板顺序 + 显示名）、允许的流转以及哪些流转必须填写备注；同一时间只有一个生效流程，
+//!   `project_change_status` 按生效流程校验。内置状态机作为 `default` 流程预置（migration 0041）。
+//! - 每个流程必须包含 BACKLOG（新项目的初始状态）、DONE（报表按完成统计）与 ARCHIVED（归档时结束成员参与），
+//!   其余状态可增删、改名、自定义。
+//! - 项目当前状态不在生效流程中（切换或修改流程后遗留）时，可转到流程中的任意状态，但必须填写备注。
+//! - 流程定义仅本机保存，不参与同步（同模板、保存的视图）；项目状态本身照常同步，远端状态不做流程校验。
+//! - 生效流程不可删除；修改流程不改动已有项目的状态。
+//! - WIP 上限按状态配置（`wipLimit` 全局、`ownerWipLimit` 每个负责人）：`project_change_status` 转入该状态后
+//!   超出上限时返回 `WIP_LIMIT_EXCEEDED`（details 带当前计数）；`force` 时照常变更，并在历史备注中记录超限计数。
+//!   只在状态变更时检查，新建项目、同步下来的状态与调低上限都不会被拦截；`workflow_wip_status` 列出当前计数。
+
+use crate::domain::{Workflow, WorkflowStatus, WorkflowTransition};
+use crate::error::{AppError, WipLimitInfo};
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowDto {
+    pub id: String,
+    pub name: String,
+    /// Board order.
+    pub statuses: Vec<WorkflowStatus>,
+    pub transitions: Vec<WorkflowTransition>,
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowCreateReq {
+    pub name: String,
+    pub statuses: Vec<WorkflowStatus>,
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowUpdateReq {
+    pub id: String,
+    pub name: Option<String>,
+    /// Replaces the status list.
+    pub statuses: Option<Vec<WorkflowStatus>>,
+    /// Replaces the transition list.
+    pub transitions: Option<Vec<WorkflowTransition>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipOwnerCountDto {
+    pub person_id: String,
+    pub display_name: String,
+    pub count: i64,
+    pub over_limit: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipStatusDto {
+    pub status: String,
+    pub label: String,
+    /// Live (not trashed) projects in this status.
+    pub count: i64,
+    pub wip_limit: Option<u32>,
+    pub owner_wip_limit: Option<u32>,
+    pub over_limit: bool,
+    /// Per-owner counts, most loaded first; empty without `owner_wip_limit`.
+    pub owners: Vec<WipOwnerCountDto>,
+}
+
+/// All workflows, the active one first, then by name.
+pub fn workflow_list(pool: &DbPool) -> Result<Vec<WorkflowDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let ids = conn
+        .prepare(
+            "SELECT id FROM workflow_definitions ORDER BY is_active DESC, name COLLATE NOCASE",
+        )?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    ids.iter().map(|id| load_workflow(&conn, id)).collect()
+}
+
+/// Create an (inactive) workflow.
+pub fn workflow_create(pool: &DbPool, req: WorkflowCreateReq) -> Result<WorkflowDto, AppError> {
+    let name = req.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::Validation("name is required".into()));
+    }
+    let workflow = Workflow::new(req.statuses, req.transitions)
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let conn = get_connection(pool);
+    ensure_name_unique(&conn, &name, None)?;
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO workflow_definitions (id, name, statuses, transitions, is_active, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, 0, ?5, ?5)",
+        params![
+            &id,
+            &name,
+            to_json(&workflow.statuses)?,
+            to_json(&workflow.transitions)?,
+            &now
+        ],
+    )?;
+    load_workflow(&conn, &id)
+}
+
+pub fn workflow_update(pool: &DbPool, req: WorkflowUpdateReq) -> Result<WorkflowDto, AppError> {
+    let conn = get_connection(pool);
+    let current = load_workflow(&conn, &req.id)?;
+    let name = match req.name.as_deref().map(str::trim) {
+        Some("") => return Err(AppError::Validation("name is required".into())),
+        Some(name) => {
+            ensure_name_unique(&conn, name, Some(&req.id))?;
+            name.to_string()
+        }
+        None => current.name,
+    };
+    let workflow = Workflow::new(
+        req.statuses.unwrap_or(current.statuses),
+        req.transitions.unwrap_or(current.transitions),
+    )
+    .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    conn.execute(
+        "UPDATE workflow_definitions SET name = ?1, statuses = ?2, transitions = ?3, updated_at = ?4
+         WHERE id = ?5",
+        params![
+            &name,
+            to_json(&workflow.statuses)?,
+            to_json(&workflow.transitions)?,
+            Utc::now().to_rfc3339(),
+            &req.id
+        ],
+    )?;
+    load_workflow(&conn, &req.id)
+}
+
+/// Make a workflow the one `project_change_status` checks.
+pub fn workflow_activate(pool: &DbPool, workflow_id: &str) -> Result<WorkflowDto, AppError> {
+    let conn = get_connection(pool);
+    load_workflow(&conn, workflow_id)?;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "UPDATE workflow_definitions SET is_active = 0 WHERE is_active = 1 AND id <> ?1",
+        [workflow_id],
+    )?;
+    tx.execute(
+        "UPDATE workflow_definitions SET is_active = 1 WHERE id = ?1",
+        [workflow_id],
+    )?;
+    tx.commit()?;
+    load_workflow(&conn, workflow_id)
+}
+
+pub fn workflow_delete(pool: &DbPool, workflow_id: &str) -> Result<(), AppError> {
+    let conn = get_connection(pool);
+    if load_workflow(&conn, workflow_id)?.is_active {
+        return Err(AppError::Validation(
+            "the active workflow cannot be deleted".into(),
+        ));
+    }
+    conn.execute(
+        "DELETE FROM workflow_definitions WHERE id = ?1",
+        [workflow_id],
+    )?;
+    Ok(())
+}
+
+/// Current counts against the WIP limits of the active workflow, in board order.
+pub fn workflow_wip_status(pool: &DbPool) -> Result<Vec<WipStatusDto>, AppError> {
+    let conn = get_read_connection(pool);
+    let workflow = load_active_workflow(&conn)?;
+    let mut out = Vec::with_capacity(workflow.statuses.len());
+    for status in workflow.statuses {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM projects WHERE current_status = ?1 AND deleted_at IS NULL",
+            [&status.key],
+            |r| r.get(0),
+        )?;
+        let owners = match status.owner_wip_limit {
+            Some(limit) => conn
+                .prepare(
+                    "SELECT p.owner_person_id, COALESCE(pe.display_name, p.owner_person_id) AS name, COUNT(*) AS n
+                     FROM projects p LEFT JOIN persons pe ON pe.id = p.owner_person_id
+                     WHERE p.current_status = ?1 AND p.deleted_at IS NULL
+                     GROUP BY p.owner_person_id
+                     ORDER BY n DESC, name COLLATE NOCASE",
+                )?
+                .query_map([&status.key], |r| {
+                    let count: i64 = r.get(2)?;
+                    Ok(WipOwnerCountDto {
+                        person_id: r.get(0)?,
+                        display_name: r.get(1)?,
+                        count,
+                        over_limit: count > i64::from(limit),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        out.push(WipStatusDto {
+            over_limit: status.wip_limit.is_some_and(|l| count > i64::from(l))
+                || owners.iter().any(|o| o.over_limit),
+            status: status.key,
+            label: status.label,
+            count,
+            wip_limit: status.wip_limit,
+            owner_wip_limit: status.owner_wip_limit,
+            owners,
+        });
+    }
+    Ok(out)
+}
+
+/// Counts after moving `project_id` (owned by `owner_person_id`) into `status`, when the move
+/// takes the status past one of its WIP limits.
+pub(crate) fn wip_limit_exceeded(
+    conn: &Connection,
+    workflow: &Workflow,
+    status: &str,
+    project_id: &str,
+    owner_person_id: &str,
+) -> Result<Option<WipLimitInfo>, AppError> {
+    let Some(limits) = workflow.status(status) else {
+        return Ok(None);
+    };
+    if limits.wip_limit.is_none() && limits.owner_wip_limit.is_none() {
+        return Ok(None);
+    }
+    let (others, owner_others): (i64, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(owner_person_id = ?2), 0) FROM projects
+         WHERE current_status = ?1 AND deleted_at IS NULL AND id <> ?3",
+        params![status, owner_person_id, project_id],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )?;
+    let (count, owner_count) = (others + 1, owner_others + 1);
+    let over = |count: i64, limit: Option<u32>| limit.is_some_and(|l| count > i64::from(l));
+    if !over(count, limits.wip_limit) && !over(owner_count, limits.owner_wip_limit) {
+        return Ok(None);
+    }
+    Ok(Some(WipLimitInfo {
+        status: status.to_string(),
+        count,
+        limit: limits.wip_limit,
+        owner_person_id: owner_person_id.to_string(),
+        owner_count,
+        owner_limit: limits.owner_wip_limit,
+    }))
+}
+
+/// History note line for a forced change past a WIP limit.
+pub(crate) fn wip_limit_note(info: &WipLimitInfo) -> String {
+    let mut counts = Vec::new();
+    if let Some(limit) = info.limit {
+        counts.push(format!("{}/{}", info.count, limit));
+    }
+    if let Some(limit) = info.owner_limit {
+        counts.push(format!("{}/{} for the owner", info.owner_count, limit));
+    }
+    format!(
+        "[auto] over WIP limit in {}: {}",
+        info.status,
+        counts.join(", ")
+    )
+}
+
+/// The active workflow; the built-in state machine when none is active.
+pub(crate) fn load_active_workflow(conn: &Connection) -> Result<Workflow, AppError> {
+    let row: Option<(String, String)> = conn
+        .query_row(
+            "SELECT statuses, transitions FROM workflow_definitions WHERE is_active = 1",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?;
+    match row {
+        Some((statuses, transitions)) => Ok(Workflow {
+            statuses: from_json(&statuses)?,
+            transitions: from_json(&transitions)?,
+        }),
+        None => Ok(Workflow::builtin()),
+    }
+}
+
+fn load_workflow(conn: &Connection, id: &str) -> Result<WorkflowDto, AppError> {
+    let row = conn
+        .query_row(
+            "SELECT id, name, statuses, transitions, is_active, created_at, updated_at
+             FROM workflow_definitions WHERE id = ?1",
+            [id],
+            |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, String>(1)?,
+                    r.get::<_, String>(2)?,
+                    r.get::<_, String>(3)?,
+                    r.get::<_, i64>(4)?,
+                    r.get::<_, String>(5)?,
+                    r.get::<_, String>(6)?,
+                ))
+            },
+        )
+        .optional()?;
+    let (id, name, statuses, transitions, is_active, created_at, updated_at) =
+        row.ok_or_else(|| AppError::NotFound(format!("workflow {}", id)))?;
+    Ok(WorkflowDto {
+        id,
+        name,
+        statuses: from_json(&statuses)?,
+        transitions: from_json(&transitions)?,
+        is_active: is_active != 0,
+        created_at,
+        updated_at,
+    })
+}
+
+fn ensure_name_unique(
+    conn: &Connection,
+    name: &str,
+    exclude_id: Option<&str>,
+) -> Result<(), AppError> {
+    let exists: i64 = conn.query_row(
+        "SELECT COUNT(1) FROM workflow_definitions WHERE name = ?1 COLLATE NOCASE AND id <> ?2",
+        params![name, exclude_id.unwrap_or("")],
+        |r| r.get(0),
+    )?;
+    if exists > 0 {
+        return Err(AppError::Conflict("workflow name must be unique".into()));
+    }
+    Ok(())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, AppError> {
+    serde_json::to_string(value).map_err(|e| AppError::Db(format!("workflow encode failed: {}", e)))
+}
+
+fn from_json<T: for<'de> Deserialize<'de>>(value: &str) -> Result<T, AppError> {
+    serde_json::from_str(value).map_err(|e| AppError::Db(format!("workflow decode failed: {}", e)))
+}
@@ -0,0 +1,16 @@
+use crate::app::{redo_last, undo_last, ActionLogEntryDto};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+/// Undo the latest journaled edit; `null` when there is nothing to undo.
+#[tauri::command]
+pub fn cmd_undo_last(pool: State<'_, DbPool>) -> Result<Option<ActionLogEntryDto>, AppError> {
+    undo_last(&pool)
+}
+
+/// Redo the latest undone edit; `null` when there is nothing to redo.
+#[tauri::command]
+pub fn cmd_redo_last(pool: State<'_, DbPool>) -> Result<Option<ActionLogEntryDto>, AppError> {
+    redo_last(&pool)
+}
@@ -0,0 +1,17 @@
+use crate::app::{activity_list, ActivityListReq, ActivityPage};
+use crate::commands::run_blocking;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+/// Workspace activity feed (newest first), filtered by project / person / date range / type.
+#[tauri::command]
+pub async fn cmd_activity_list(
+    pool: State<'_, DbPool>,
+    req: Option<ActivityListReq>,
+) -> Result<ActivityPage, AppError> {
+    run_blocking(&pool, move |pool| {
+        activity_list(pool, req.unwrap_or_default())
+    })
+    .await
+}
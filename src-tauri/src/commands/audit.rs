@@ -0,0 +1,14 @@
+use crate::app::{audit_list, AuditListReq, AuditPage};
+use crate::commands::run_blocking;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+/// Field-level audit trail (newest first), filtered by entity type / id.
+#[tauri::command]
+pub async fn cmd_audit_list(
+    pool: State<'_, DbPool>,
+    req: Option<AuditListReq>,
+) -> Result<AuditPage, AppError> {
+    run_blocking(&pool, move |pool| audit_list(pool, req.unwrap_or_default())).await
+}
@@ -0,0 +1,98 @@
+use crate::app::{
+    backup_create, backup_get_config, backup_list, backup_restore, backup_run_scheduled,
+    backup_update_config, load_backup_schedule, BackupConfigDto, BackupConfigReq, BackupInfoDto,
+    BackupRestoreReq, BackupRestoreResult, BackupSchedule, BACKUP_REASON_MANUAL,
+};
+use crate::commands::run_blocking;
+use crate::commands::runtime::{PeriodicTask, ProfileRuntime, RuntimeFuture};
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use tauri::State;
+use tokio::time::Duration;
+
+/// How often the scheduler checks whether a daily/weekly backup is due.
+const BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Timer for scheduled local backups (independent of sync).
+#[derive(Clone, Default)]
+pub struct BackupRuntime {
+    task: PeriodicTask,
+}
+
+impl BackupRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stop_scheduler(&self) {
+        self.task.stop().await;
+    }
+
+    pub async fn refresh_scheduler(&self, pool: DbPool) {
+        let schedule = load_backup_schedule(&get_read_connection(&pool));
+        if schedule == BackupSchedule::Off {
+            self.task.stop().await;
+            return;
+        }
+
+        self.task
+            .start("Scheduled backup", BACKUP_CHECK_INTERVAL, pool, |pool| {
+                match backup_run_scheduled(pool, chrono::Utc::now()) {
+                    Ok(Some(backup)) => {
+                        log::info!("Scheduled backup written: {}", backup.file_name)
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::error!("Scheduled backup failed: {}", e),
+                }
+            })
+            .await;
+    }
+}
+
+impl ProfileRuntime for BackupRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_scheduler())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(self.refresh_scheduler(pool))
+    }
+}
+
+/// Local DB backups, newest first.
+#[tauri::command]
+pub fn cmd_backup_list(pool: State<'_, DbPool>) -> Result<Vec<BackupInfoDto>, AppError> {
+    backup_list(&pool)
+}
+
+/// Replace business data with a backup; the current state is backed up first.
+#[tauri::command]
+pub async fn cmd_backup_restore(
+    pool: State<'_, DbPool>,
+    req: BackupRestoreReq,
+) -> Result<BackupRestoreResult, AppError> {
+    run_blocking(&pool, move |pool| backup_restore(pool, req)).await
+}
+
+/// Write a backup now (reason "manual").
+#[tauri::command]
+pub async fn cmd_backup_create(pool: State<'_, DbPool>) -> Result<Option<BackupInfoDto>, AppError> {
+    run_blocking(&pool, |pool| backup_create(pool, BACKUP_REASON_MANUAL)).await
+}
+
+#[tauri::command]
+pub fn cmd_backup_get_config(pool: State<'_, DbPool>) -> Result<BackupConfigDto, AppError> {
+    backup_get_config(&pool)
+}
+
+/// Save schedule / target dir / retention and restart the backup scheduler.
+#[tauri::command]
+pub async fn cmd_backup_update_config(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, BackupRuntime>,
+    req: BackupConfigReq,
+) -> Result<BackupConfigDto, AppError> {
+    let config = backup_update_config(&pool, req)?;
+    runtime.refresh_scheduler(pool.inner().clone()).await;
+    Ok(config)
+}
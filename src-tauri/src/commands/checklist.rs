@@ -0,0 +1,58 @@
+use crate::app::{
+    checklist_add, checklist_delete, checklist_list, checklist_reorder, checklist_toggle,
+    ChecklistAddReq, ChecklistItemDto, ChecklistReorderReq, ChecklistToggleReq,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistListReq {
+    pub project_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistDeleteReq {
+    pub id: String,
+}
+
+#[tauri::command]
+pub fn cmd_checklist_list(
+    pool: State<DbPool>,
+    req: ChecklistListReq,
+) -> Result<Vec<ChecklistItemDto>, AppError> {
+    checklist_list(&pool, req.project_id)
+}
+
+#[tauri::command]
+pub fn cmd_checklist_add(
+    pool: State<DbPool>,
+    req: ChecklistAddReq,
+) -> Result<ChecklistItemDto, AppError> {
+    checklist_add(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_checklist_toggle(
+    pool: State<DbPool>,
+    req: ChecklistToggleReq,
+) -> Result<ChecklistItemDto, AppError> {
+    checklist_toggle(&pool, req)
+}
+
+/// Set the order of a project's checklist.
+#[tauri::command]
+pub fn cmd_checklist_reorder(
+    pool: State<DbPool>,
+    req: ChecklistReorderReq,
+) -> Result<Vec<ChecklistItemDto>, AppError> {
+    checklist_reorder(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_checklist_delete(pool: State<DbPool>, req: ChecklistDeleteReq) -> Result<(), AppError> {
+    checklist_delete(&pool, req.id)
+}
@@ -1,6 +1,7 @@
 use crate::app::{
-    comment_create, comment_delete, comment_list_by_project, comment_update, CommentCreateReq,
-    CommentDto, CommentUpdateReq,
+    comment_create, comment_delete, comment_history, comment_list_by_project, comment_react,
+    comment_reorder_pins, comment_reply, comment_update, CommentCreateReq, CommentDto,
+    CommentReactReq, CommentReorderPinsReq, CommentReplyReq, CommentRevisionDto, CommentUpdateReq,
 };
 use crate::error::AppError;
 use crate::infra::DbPool;
@@ -19,6 +20,12 @@ pub struct CommentDeleteReq {
     pub id: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentHistoryReq {
+    pub comment_id: String,
+}
+
 #[tauri::command]
 pub fn cmd_comment_create(
     pool: State<DbPool>,
@@ -27,6 +34,22 @@ pub fn cmd_comment_create(
     comment_create(&pool, req)
 }
 
+#[tauri::command]
+pub fn cmd_comment_reply(
+    pool: State<DbPool>,
+    req: CommentReplyReq,
+) -> Result<CommentDto, AppError> {
+    comment_reply(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_comment_react(
+    pool: State<DbPool>,
+    req: CommentReactReq,
+) -> Result<CommentDto, AppError> {
+    comment_react(&pool, req)
+}
+
 #[tauri::command]
 pub fn cmd_comment_update(
     pool: State<DbPool>,
@@ -35,6 +58,24 @@ pub fn cmd_comment_update(
     comment_update(&pool, req)
 }
 
+/// Earlier versions of an edited comment, most recent edit first.
+#[tauri::command]
+pub fn cmd_comment_history(
+    pool: State<DbPool>,
+    req: CommentHistoryReq,
+) -> Result<Vec<CommentRevisionDto>, AppError> {
+    comment_history(&pool, req.comment_id)
+}
+
+/// Set the order of a project's pinned comments.
+#[tauri::command]
+pub fn cmd_comment_reorder_pins(
+    pool: State<DbPool>,
+    req: CommentReorderPinsReq,
+) -> Result<Vec<CommentDto>, AppError> {
+    comment_reorder_pins(&pool, req)
+}
+
 #[tauri::command]
 pub fn cmd_comment_delete(pool: State<DbPool>, req: CommentDeleteReq) -> Result<(), AppError> {
     comment_delete(&pool, req.id)
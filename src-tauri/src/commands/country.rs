@@ -0,0 +1,18 @@
+use crate::app::{country_list, country_usage, CountryDto, CountryListReq, CountryUsageDto};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+#[tauri::command]
+pub fn cmd_countries_list(req: Option<CountryListReq>) -> Result<Vec<CountryDto>, AppError> {
+    Ok(country_list(req.unwrap_or_default()))
+}
+
+/// Codes stored on projects with their project counts (for list filters).
+#[tauri::command]
+pub fn cmd_countries_in_use(
+    pool: State<'_, DbPool>,
+    req: Option<CountryListReq>,
+) -> Result<Vec<CountryUsageDto>, AppError> {
+    country_usage(&pool, req.unwrap_or_default())
+}
@@ -0,0 +1,72 @@
+use crate::app::{
+    budget_report, currency_get_config, currency_rate_delete, currency_rate_list,
+    currency_rate_set, currency_rates_fetch, currency_update_config, BudgetReportDto,
+    BudgetReportReq, CurrencyConfigDto, CurrencyConfigReq, CurrencyRateDto, CurrencyRateSetReq,
+    CurrencyRatesFetchResult,
+};
+use crate::commands::run_blocking;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyRateDeleteReq {
+    pub currency: String,
+}
+
+#[tauri::command]
+pub fn cmd_currency_get_config(pool: State<'_, DbPool>) -> Result<CurrencyConfigDto, AppError> {
+    currency_get_config(&pool)
+}
+
+/// Change the base currency (existing rates are converted) or the rates URL.
+#[tauri::command]
+pub fn cmd_currency_update_config(
+    pool: State<'_, DbPool>,
+    req: CurrencyConfigReq,
+) -> Result<CurrencyConfigDto, AppError> {
+    currency_update_config(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_currency_rate_list(pool: State<'_, DbPool>) -> Result<Vec<CurrencyRateDto>, AppError> {
+    currency_rate_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_currency_rate_set(
+    pool: State<'_, DbPool>,
+    req: CurrencyRateSetReq,
+) -> Result<CurrencyRateDto, AppError> {
+    currency_rate_set(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_currency_rate_delete(
+    pool: State<'_, DbPool>,
+    req: CurrencyRateDeleteReq,
+) -> Result<(), AppError> {
+    currency_rate_delete(&pool, &req.currency)
+}
+
+/// Download current rates from the configured URL.
+#[tauri::command]
+pub async fn cmd_currency_rates_fetch(
+    pool: State<'_, DbPool>,
+) -> Result<CurrencyRatesFetchResult, AppError> {
+    run_blocking(&pool, currency_rates_fetch).await
+}
+
+/// Project budgets converted to the base currency, per partner and per currency.
+#[tauri::command]
+pub async fn cmd_budget_report(
+    pool: State<'_, DbPool>,
+    req: Option<BudgetReportReq>,
+) -> Result<BudgetReportDto, AppError> {
+    run_blocking(&pool, move |pool| {
+        budget_report(pool, req.unwrap_or_default())
+    })
+    .await
+}
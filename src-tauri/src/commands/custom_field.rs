@@ -0,0 +1,52 @@
+use crate::app::{
+    custom_field_create, custom_field_delete, custom_field_list, custom_field_update,
+    project_set_custom_value, CustomFieldDefCreateReq, CustomFieldDefDto, CustomFieldDefUpdateReq,
+    ProjectCustomValueDto, ProjectCustomValueSetReq,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldDeleteReq {
+    pub id: String,
+}
+
+#[tauri::command]
+pub fn cmd_custom_field_list(pool: State<DbPool>) -> Result<Vec<CustomFieldDefDto>, AppError> {
+    custom_field_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_custom_field_create(
+    pool: State<DbPool>,
+    req: CustomFieldDefCreateReq,
+) -> Result<CustomFieldDefDto, AppError> {
+    custom_field_create(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_custom_field_update(
+    pool: State<DbPool>,
+    req: CustomFieldDefUpdateReq,
+) -> Result<CustomFieldDefDto, AppError> {
+    custom_field_update(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_custom_field_delete(
+    pool: State<DbPool>,
+    req: CustomFieldDeleteReq,
+) -> Result<(), AppError> {
+    custom_field_delete(&pool, &req.id)
+}
+
+#[tauri::command]
+pub fn cmd_project_set_custom_value(
+    pool: State<DbPool>,
+    req: ProjectCustomValueSetReq,
+) -> Result<Vec<ProjectCustomValueDto>, AppError> {
+    project_set_custom_value(&pool, req)
+}
@@ -0,0 +1,86 @@
+use crate::app::{
+    data_dir_info, data_dir_move, DataDirContext, DataDirInfoDto, DataDirMoveReq, DataDirMoveResult,
+};
+use crate::commands::profile::{refresh_runtimes, stop_runtimes};
+use crate::commands::run_blocking;
+use crate::commands::sync::SyncRuntime;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use crate::{
+    activate_profile, finish_runtime_data_dir_move, open_profile, ActiveProfile, AppRuntimeState,
+};
+use tauri::{AppHandle, State};
+
+fn context(runtime: &AppRuntimeState) -> DataDirContext<'_> {
+    DataDirContext {
+        current_dir: runtime.data_dir(),
+        default_dir: runtime.default_data_dir(),
+        location_file: runtime.location_file(),
+    }
+}
+
+#[tauri::command]
+pub fn cmd_data_dir_get(profile: State<'_, ActiveProfile>) -> Result<DataDirInfoDto, AppError> {
+    data_dir_info(&context(&profile.current()))
+}
+
+/// Copy + verify the profile data into a new dir, then switch to it without restarting
+/// (same steps as a profile switch). If the switch fails the copy stays configured for the
+/// next start and `requires_restart` is set. The frontend must reload all data.
+#[tauri::command]
+pub async fn cmd_data_dir_move(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    profile: State<'_, ActiveProfile>,
+    sync_runtime: State<'_, SyncRuntime>,
+    req: DataDirMoveReq,
+) -> Result<DataDirMoveResult, AppError> {
+    let current = profile.current();
+    let sync_guard = sync_runtime.hold_sync_lock().await;
+    stop_runtimes(&app).await;
+
+    let source = current.clone();
+    let moved = run_blocking(&pool, move |pool| {
+        data_dir_move(pool, &context(&source), req)
+    })
+    .await;
+    let mut result = match moved {
+        Ok(result) => result,
+        Err(e) => {
+            drop(sync_guard);
+            refresh_runtimes(&app, pool.inner().clone()).await;
+            return Err(e);
+        }
+    };
+
+    // The pointer file now names the new dir: reopen the profile from there.
+    let reopened = open_profile(current.base_data_dir(), current.profile_name())
+        .map_err(AppError::DataDir)
+        .and_then(|(state, lock_file, new_pool)| {
+            pool.0.replace_with(new_pool)?;
+            Ok((state, lock_file))
+        });
+    match reopened {
+        Ok((state, lock_file)) => {
+            activate_profile(&state);
+            profile.replace(state, lock_file);
+            // The old dir is unlocked now: remove it right away instead of at next start.
+            finish_runtime_data_dir_move(&pool, &profile.current());
+            result.requires_restart = false;
+            log::info!(
+                "Data dir switched: {} -> {}",
+                result.from_dir,
+                result.to_dir
+            );
+        }
+        Err(e) => log::error!(
+            "Data copied to {} but switching failed, restart required: {}",
+            result.to_dir,
+            e
+        ),
+    }
+    drop(sync_guard);
+
+    refresh_runtimes(&app, pool.inner().clone()).await;
+    Ok(result)
+}
@@ -1,18 +1,57 @@
 //! Export / Import command handlers.
 
 use crate::app::{
-    export_json_string, export_persons_csv, import_json_string, import_persons_csv,
-    wipe_business_data, ImportResult, PersonImportResult, WipeResult,
+    backup_create, export_assignments_csv, export_comments_csv, export_ics, export_json_selective,
+    export_json_to_file, export_persons_csv, export_projects_csv, export_status_history_csv,
+    export_xlsx, import_json_string, import_persons_csv, import_projects_csv, wipe_business_data,
+    CsvExportReq, ImportResult, JsonExportFileResult, JsonExportReq, PersonImportResult,
+    ProjectCsvImportReport, ProjectCsvImportReq, ProjectListReq, WipeResult, XlsxSheetSummary,
+    BACKUP_REASON_IMPORT_JSON, BACKUP_REASON_IMPORT_PROJECTS_CSV,
+};
+use crate::commands::run_blocking;
+use crate::commands::sync::{
+    ensure_operation_allowed, publish_import_snapshot_for_pool, DISABLE_REMOTE_CLEANUP_KEY,
 };
 use crate::error::AppError;
-use crate::infra::DbPool;
-use serde::Deserialize;
-use tauri::State;
+use crate::infra::{get_connection, DbPool};
+use crate::sync::import_snapshot::max_sync_metadata_id;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+
+/// Event carrying `JsonExportProgress` while `cmd_export_json_file` runs.
+pub const EXPORT_JSON_PROGRESS_EVENT: &str = "export-json-progress";
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportJsonReq {
     pub schema_version: Option<i32>,
+    /// Project selection, comment / history exclusion and `changedSince`.
+    #[serde(flatten)]
+    pub selection: JsonExportReq,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJsonFileReq {
+    /// Absolute destination path (picked with the save dialog); overwritten if present.
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportXlsxReq {
+    /// Absolute destination path (picked with the save dialog); overwritten if present.
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportXlsxResult {
+    pub path: String,
+    pub bytes: usize,
+    pub sheets: Vec<XlsxSheetSummary>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -28,33 +67,188 @@ pub struct ImportPersonsCsvReq {
 }
 
 #[tauri::command]
-pub fn cmd_export_json(
-    pool: State<DbPool>,
+pub async fn cmd_export_json(
+    pool: State<'_, DbPool>,
     req: Option<ExportJsonReq>,
 ) -> Result<String, AppError> {
-    let schema_version = req.and_then(|r| r.schema_version);
-    export_json_string(&pool, schema_version)
+    run_blocking(&pool, move |pool| {
+        export_json_selective(pool, req.map(|r| r.selection).unwrap_or_default())
+    })
+    .await
+}
+
+/// Whole workspace as JSON streamed to `req.path` (same format as `cmd_export_json`) for
+/// databases too large to build in memory; progress is emitted as `export-json-progress`.
+#[tauri::command]
+pub async fn cmd_export_json_file(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    req: ExportJsonFileReq,
+) -> Result<JsonExportFileResult, AppError> {
+    if !Path::new(&req.path).is_absolute() {
+        return Err(AppError::Validation(format!(
+            "export path must be absolute: {}",
+            req.path
+        )));
+    }
+    run_blocking(&pool, move |pool| {
+        export_json_to_file(pool, Path::new(&req.path), |progress| {
+            if let Err(e) = app.emit(EXPORT_JSON_PROGRESS_EVENT, progress) {
+                log::warn!("Failed to emit export progress: {}", e);
+            }
+        })
+    })
+    .await
+}
+
+/// Large imports (>= `auto_snapshot_import_threshold` rows) are published as a snapshot.
+#[tauri::command]
+pub async fn cmd_import_json(
+    pool: State<'_, DbPool>,
+    req: ImportJsonReq,
+) -> Result<ImportResult, AppError> {
+    let (before_id, result) = run_blocking(&pool, move |pool| {
+        backup_create(pool, BACKUP_REASON_IMPORT_JSON)?;
+        let before_id = max_sync_metadata_id(&get_connection(pool))?;
+        Ok((before_id, import_json_string(pool, &req.json)?))
+    })
+    .await?;
+    publish_import_snapshot_for_pool(pool.inner(), before_id, result.imported_rows()).await;
+    Ok(result)
+}
+
+/// iCalendar (.ics) text with the due dates, start dates and milestones of the projects
+/// matching the `project_list` filters (default: all unarchived projects).
+#[tauri::command]
+pub async fn cmd_export_ics(
+    pool: State<'_, DbPool>,
+    req: Option<ProjectListReq>,
+) -> Result<String, AppError> {
+    run_blocking(&pool, move |pool| {
+        export_ics(pool, req.unwrap_or_default(), chrono::Utc::now())
+    })
+    .await
+}
+
+/// Whole workspace as an Excel workbook (projects, persons, partners, assignments,
+/// status history, comments), written to `req.path`.
+#[tauri::command]
+pub async fn cmd_export_xlsx(
+    pool: State<'_, DbPool>,
+    req: ExportXlsxReq,
+) -> Result<ExportXlsxResult, AppError> {
+    if !Path::new(&req.path).is_absolute() {
+        return Err(AppError::Validation(format!(
+            "export path must be absolute: {}",
+            req.path
+        )));
+    }
+    run_blocking(&pool, move |pool| {
+        let export = export_xlsx(pool)?;
+        let path = Path::new(&req.path);
+        fs::write(path, &export.bytes)
+            .map_err(|e| AppError::Export(format!("Failed to write {:?}: {}", path, e)))?;
+        Ok(ExportXlsxResult {
+            path: req.path,
+            bytes: export.bytes.len(),
+            sheets: export.sheets,
+        })
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn cmd_import_json(pool: State<DbPool>, req: ImportJsonReq) -> Result<ImportResult, AppError> {
-    import_json_string(&pool, &req.json)
+pub async fn cmd_export_persons_csv(pool: State<'_, DbPool>) -> Result<String, AppError> {
+    run_blocking(&pool, export_persons_csv).await
 }
 
+/// Projects as CSV (tags flattened, owner / partner names resolved); `req.columns` picks
+/// and orders the columns.
 #[tauri::command]
-pub fn cmd_export_persons_csv(pool: State<DbPool>) -> Result<String, AppError> {
-    export_persons_csv(&pool)
+pub async fn cmd_export_projects_csv(
+    pool: State<'_, DbPool>,
+    req: Option<CsvExportReq>,
+) -> Result<String, AppError> {
+    run_blocking(&pool, move |pool| {
+        export_projects_csv(pool, req.unwrap_or_default())
+    })
+    .await
 }
 
 #[tauri::command]
-pub fn cmd_import_persons_csv(
-    pool: State<DbPool>,
+pub async fn cmd_export_assignments_csv(
+    pool: State<'_, DbPool>,
+    req: Option<CsvExportReq>,
+) -> Result<String, AppError> {
+    run_blocking(&pool, move |pool| {
+        export_assignments_csv(pool, req.unwrap_or_default())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn cmd_export_status_history_csv(
+    pool: State<'_, DbPool>,
+    req: Option<CsvExportReq>,
+) -> Result<String, AppError> {
+    run_blocking(&pool, move |pool| {
+        export_status_history_csv(pool, req.unwrap_or_default())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn cmd_export_comments_csv(
+    pool: State<'_, DbPool>,
+    req: Option<CsvExportReq>,
+) -> Result<String, AppError> {
+    run_blocking(&pool, move |pool| {
+        export_comments_csv(pool, req.unwrap_or_default())
+    })
+    .await
+}
+
+/// Large imports (>= `auto_snapshot_import_threshold` new persons) are published as a snapshot.
+#[tauri::command]
+pub async fn cmd_import_persons_csv(
+    pool: State<'_, DbPool>,
     req: ImportPersonsCsvReq,
 ) -> Result<PersonImportResult, AppError> {
-    import_persons_csv(&pool, &req.csv)
+    let (before_id, result) = run_blocking(&pool, move |pool| {
+        let before_id = max_sync_metadata_id(&get_connection(pool))?;
+        Ok((before_id, import_persons_csv(pool, &req.csv)?))
+    })
+    .await?;
+    publish_import_snapshot_for_pool(pool.inner(), before_id, result.created).await;
+    Ok(result)
+}
+
+/// Dry run by default; a committed import takes a backup first and large imports
+/// (>= `auto_snapshot_import_threshold` projects) are published as a snapshot.
+#[tauri::command]
+pub async fn cmd_import_projects_csv(
+    pool: State<'_, DbPool>,
+    req: ProjectCsvImportReq,
+) -> Result<ProjectCsvImportReport, AppError> {
+    let (before_id, report) = run_blocking(&pool, move |pool| {
+        if req.dry_run == Some(false) {
+            backup_create(pool, BACKUP_REASON_IMPORT_PROJECTS_CSV)?;
+        }
+        let before_id = max_sync_metadata_id(&get_connection(pool))?;
+        Ok((before_id, import_projects_csv(pool, req)?))
+    })
+    .await?;
+    if report.committed {
+        publish_import_snapshot_for_pool(pool.inner(), before_id, report.ok_rows).await;
+    }
+    Ok(report)
 }
 
 #[tauri::command]
 pub fn cmd_wipe_business_data(pool: State<DbPool>) -> Result<WipeResult, AppError> {
+    {
+        let conn = get_connection(&pool);
+        ensure_operation_allowed(&conn, DISABLE_REMOTE_CLEANUP_KEY, "remote cleanup")?;
+    } // Release lock before wipe_business_data acquires it again.
     wipe_business_data(&pool)
 }
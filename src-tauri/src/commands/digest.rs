@@ -0,0 +1,46 @@
+use crate::app::{
+    digest_get_config, digest_preview, digest_send_now, digest_update_config, DigestConfigDto,
+    DigestConfigReq, DigestPreviewDto, DigestPreviewReq, DigestSendResult,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+#[tauri::command]
+pub fn cmd_digest_get_config(pool: State<'_, DbPool>) -> Result<DigestConfigDto, AppError> {
+    digest_get_config(&pool)
+}
+
+/// Update digest settings; the hourly report runtime picks them up on its next tick.
+#[tauri::command]
+pub fn cmd_digest_update_config(
+    pool: State<'_, DbPool>,
+    req: DigestConfigReq,
+) -> Result<DigestConfigDto, AppError> {
+    digest_update_config(&pool, req)
+}
+
+/// Compose the digest for the last period and return it with the JSON that would be posted.
+#[tauri::command]
+pub fn cmd_digest_preview(
+    pool: State<'_, DbPool>,
+    req: Option<DigestPreviewReq>,
+) -> Result<DigestPreviewDto, AppError> {
+    digest_preview(
+        &pool,
+        req.unwrap_or_default(),
+        chrono::Local::now().date_naive(),
+        chrono::Utc::now(),
+    )
+}
+
+/// Post the digest for the last period now; the schedule is unchanged.
+#[tauri::command]
+pub async fn cmd_digest_send(pool: State<'_, DbPool>) -> Result<DigestSendResult, AppError> {
+    let pool = pool.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        digest_send_now(&pool, chrono::Local::now().date_naive(), chrono::Utc::now())
+    })
+    .await
+    .map_err(|e| AppError::Report(format!("digest task failed: {}", e)))?
+}
@@ -0,0 +1,33 @@
+use crate::app::{
+    db_encryption_status, db_set_passphrase, DbEncryptionStatusDto, DbSetPassphraseReq,
+};
+use crate::commands::profile::{refresh_runtimes, stop_runtimes};
+use crate::commands::run_blocking;
+use crate::commands::sync::SyncRuntime;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn cmd_db_encryption_status(
+    pool: State<'_, DbPool>,
+) -> Result<DbEncryptionStatusDto, AppError> {
+    run_blocking(&pool, db_encryption_status).await
+}
+
+/// Encrypt, re-key or decrypt the local database. Waits for a running sync and stops the
+/// schedulers while the file is rewritten.
+#[tauri::command]
+pub async fn cmd_db_set_passphrase(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    sync_runtime: State<'_, SyncRuntime>,
+    req: DbSetPassphraseReq,
+) -> Result<DbEncryptionStatusDto, AppError> {
+    let sync_guard = sync_runtime.hold_sync_lock().await;
+    stop_runtimes(&app).await;
+    let result = run_blocking(&pool, move |pool| db_set_passphrase(pool, req)).await;
+    drop(sync_guard);
+    refresh_runtimes(&app, pool.inner().clone()).await;
+    result
+}
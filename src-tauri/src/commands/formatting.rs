@@ -0,0 +1,17 @@
+use crate::app::{format_settings_get, format_settings_update, FormatSettings, FormatSettingsReq};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+#[tauri::command]
+pub fn cmd_format_settings_get(pool: State<DbPool>) -> Result<FormatSettings, AppError> {
+    format_settings_get(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_format_settings_update(
+    pool: State<DbPool>,
+    req: FormatSettingsReq,
+) -> Result<FormatSettings, AppError> {
+    format_settings_update(&pool, req)
+}
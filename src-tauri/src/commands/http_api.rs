@@ -0,0 +1,110 @@
+use crate::app::{
+    http_api_get_config, http_api_handle, http_api_regenerate_token, http_api_update_config,
+    load_http_api_enabled, load_http_api_port, HttpApiConfigDto, HttpApiConfigReq,
+};
+use crate::commands::runtime::{PeriodicTask, ProfileRuntime, RuntimeFuture};
+use crate::error::AppError;
+use crate::infra::http_server::{serve, HttpRequest, HttpResponse};
+use crate::infra::{get_read_connection, DbPool};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tauri::State;
+use tokio::net::TcpListener;
+
+/// Embedded read-only HTTP API; runs only while enabled in settings.
+#[derive(Clone, Default)]
+pub struct HttpApiRuntime {
+    server: PeriodicTask,
+}
+
+impl HttpApiRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stop_server(&self) {
+        self.server.stop().await;
+    }
+
+    /// (Re)start the server from the stored settings. Errors when the port cannot be bound.
+    pub async fn refresh_server(&self, pool: DbPool) -> Result<(), String> {
+        // Always stop first to ensure only one server is alive.
+        self.stop_server().await;
+
+        let (enabled, port) = {
+            let conn = get_read_connection(&pool);
+            (load_http_api_enabled(&conn), load_http_api_port(&conn))
+        };
+        if !enabled {
+            return Ok(());
+        }
+
+        // Loopback only: the API is for scripts and dashboards on this machine.
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| format!("cannot listen on {}: {}", addr, e))?;
+        log::info!("HTTP API listening on http://{}", addr);
+
+        let handler = Arc::new(move |request: HttpRequest| {
+            let pool = pool.clone();
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    http_api_handle(&pool, &request, chrono::Local::now().date_naive())
+                })
+                .await
+                .unwrap_or_else(|e| HttpResponse {
+                    status: 500,
+                    body: serde_json::json!({
+                        "error": { "code": "INTERNAL_ERROR", "message": e.to_string() }
+                    })
+                    .to_string(),
+                })
+            }
+        });
+        self.server.spawn(serve(listener, handler)).await;
+        Ok(())
+    }
+}
+
+impl ProfileRuntime for HttpApiRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_server())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(async move {
+            if let Err(e) = self.refresh_server(pool).await {
+                log::error!("HTTP API not started: {}", e);
+            }
+        })
+    }
+}
+
+#[tauri::command]
+pub fn cmd_http_api_get_config(pool: State<'_, DbPool>) -> Result<HttpApiConfigDto, AppError> {
+    http_api_get_config(&pool)
+}
+
+/// Update settings and restart the server (fails if the port is already in use).
+#[tauri::command]
+pub async fn cmd_http_api_update_config(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, HttpApiRuntime>,
+    req: HttpApiConfigReq,
+) -> Result<HttpApiConfigDto, AppError> {
+    let config = http_api_update_config(&pool, req)?;
+    runtime
+        .refresh_server(pool.inner().clone())
+        .await
+        .map_err(AppError::Validation)?;
+    Ok(config)
+}
+
+/// New bearer token; the running server checks it per request, so no restart is needed.
+#[tauri::command]
+pub fn cmd_http_api_regenerate_token(
+    pool: State<'_, DbPool>,
+) -> Result<HttpApiConfigDto, AppError> {
+    http_api_regenerate_token(&pool)
+}
@@ -0,0 +1,41 @@
+use crate::app::{
+    strict_fk_get_config, strict_fk_update_config, StrictFkConfigDto, StrictFkConfigReq,
+};
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use crate::sync::DeltaSyncEngine;
+use tauri::State;
+
+#[tauri::command]
+pub fn cmd_strict_fk_get_config(pool: State<'_, DbPool>) -> Result<StrictFkConfigDto, AppError> {
+    strict_fk_get_config(&pool)
+}
+
+/// Turning strict mode off applies the operations it had deferred right away.
+#[tauri::command]
+pub fn cmd_strict_fk_update_config(
+    pool: State<'_, DbPool>,
+    req: StrictFkConfigReq,
+) -> Result<StrictFkConfigDto, AppError> {
+    strict_fk_update_config_for_pool(&pool, req)
+}
+
+pub fn strict_fk_update_config_for_pool(
+    pool: &DbPool,
+    req: StrictFkConfigReq,
+) -> Result<StrictFkConfigDto, AppError> {
+    let config = strict_fk_update_config(pool, req)?;
+    if config.enabled || config.deferred_ops == 0 {
+        return Ok(config);
+    }
+    let device_id = {
+        let conn = get_connection(pool);
+        DeltaSyncEngine::get_device_id(&conn)?
+    };
+    let stats = DeltaSyncEngine::new(pool, device_id).apply_deferred()?;
+    log::info!(
+        "Applied {} deferred operations after disabling strict FK mode",
+        stats.applied_ops
+    );
+    strict_fk_get_config(pool)
+}
@@ -0,0 +1,105 @@
+use crate::app::{
+    link_check_get_config, link_check_report, link_check_run, link_check_run_scheduled,
+    link_check_update_config, load_link_check_enabled, LinkCheckConfigDto, LinkCheckConfigReq,
+    LinkCheckReportDto, LinkCheckRunResult,
+};
+use crate::commands::runtime::{PeriodicTask, ProfileRuntime, RuntimeFuture};
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use serde::Deserialize;
+use tauri::State;
+use tokio::time::Duration;
+
+/// How often the runtime checks whether the weekly link check is due.
+const LINK_CHECK_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkCheckReportReq {
+    /// Only this project; all projects if omitted.
+    pub project_id: Option<String>,
+}
+
+/// Timer for the opt-in weekly link check.
+#[derive(Clone, Default)]
+pub struct LinkCheckRuntime {
+    task: PeriodicTask,
+}
+
+impl LinkCheckRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stop_scheduler(&self) {
+        self.task.stop().await;
+    }
+
+    pub async fn refresh_scheduler(&self, pool: DbPool) {
+        let enabled = load_link_check_enabled(&get_read_connection(&pool));
+        if !enabled {
+            self.task.stop().await;
+            return;
+        }
+
+        self.task
+            .start("Link check", LINK_CHECK_POLL_INTERVAL, pool, |pool| {
+                match link_check_run_scheduled(pool, chrono::Utc::now()) {
+                    Ok(Some(result)) => log::info!(
+                        "Link check finished: {} links, {} dead, {} unverified",
+                        result.total_links,
+                        result.dead,
+                        result.unverified
+                    ),
+                    Ok(None) => {}
+                    Err(e) => log::error!("Link check failed: {}", e),
+                }
+            })
+            .await;
+    }
+}
+
+impl ProfileRuntime for LinkCheckRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_scheduler())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(self.refresh_scheduler(pool))
+    }
+}
+
+#[tauri::command]
+pub fn cmd_link_check_get_config(pool: State<'_, DbPool>) -> Result<LinkCheckConfigDto, AppError> {
+    link_check_get_config(&pool)
+}
+
+/// Turn the weekly check on/off and restart the scheduler.
+#[tauri::command]
+pub async fn cmd_link_check_update_config(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, LinkCheckRuntime>,
+    req: LinkCheckConfigReq,
+) -> Result<LinkCheckConfigDto, AppError> {
+    let config = link_check_update_config(&pool, req)?;
+    runtime.refresh_scheduler(pool.inner().clone()).await;
+    Ok(config)
+}
+
+/// Check all links now (works while the weekly check is off).
+#[tauri::command]
+pub async fn cmd_link_check_run(pool: State<'_, DbPool>) -> Result<LinkCheckRunResult, AppError> {
+    let pool = pool.inner().clone();
+    tokio::task::spawn_blocking(move || link_check_run(&pool, chrono::Utc::now()))
+        .await
+        .map_err(|e| AppError::Report(format!("link check task failed: {}", e)))?
+}
+
+/// Dead and unverified links of the latest check, per project.
+#[tauri::command]
+pub fn cmd_link_check_report(
+    pool: State<'_, DbPool>,
+    req: LinkCheckReportReq,
+) -> Result<LinkCheckReportDto, AppError> {
+    link_check_report(&pool, req.project_id.as_deref())
+}
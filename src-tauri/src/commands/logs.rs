@@ -1,8 +1,8 @@
 //! Tauri commands for log viewing.
 
 use crate::error::AppError;
-use crate::infra::DbPool;
-use crate::AppRuntimeState;
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use crate::{ActiveProfile, AppRuntimeState};
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
@@ -145,10 +145,9 @@ fn redact_content(content: &str, patterns: &[String]) -> String {
 
 /// List all log files in the app log directory.
 #[tauri::command]
-pub fn cmd_log_list_files(
-    runtime: State<'_, AppRuntimeState>,
-) -> Result<Vec<LogFileDto>, AppError> {
-    let log_dir = get_log_dir(runtime.inner())?;
+pub fn cmd_log_list_files(profile: State<'_, ActiveProfile>) -> Result<Vec<LogFileDto>, AppError> {
+    let runtime = profile.current();
+    let log_dir = get_log_dir(&runtime)?;
     let profile_name = runtime.profile_name();
 
     if !log_dir.exists() {
@@ -206,16 +205,17 @@ pub fn cmd_log_list_files(
 #[tauri::command]
 pub fn cmd_log_tail(
     pool: State<DbPool>,
-    runtime: State<'_, AppRuntimeState>,
+    profile: State<'_, ActiveProfile>,
     req: LogTailReq,
 ) -> Result<LogTailResp, AppError> {
+    let runtime = profile.current();
     // 白名单校验
     validate_log_file_name(&req.file_name, runtime.profile_name())?;
 
     // 限制 max_bytes
     let max_bytes = req.max_bytes.min(MAX_TAIL_BYTES);
 
-    let log_dir = get_log_dir(runtime.inner())?;
+    let log_dir = get_log_dir(&runtime)?;
     let file_path = log_dir.join(&req.file_name);
 
     if !file_path.exists() {
@@ -248,11 +248,7 @@ pub fn cmd_log_tail(
 
     // 脱敏处理
     if req.redact {
-        let conn = pool
-            .inner()
-            .0
-            .lock()
-            .map_err(|e| AppError::Db(e.to_string()))?;
+        let conn = get_read_connection(&pool);
         let patterns = get_redaction_patterns(&conn);
         if !patterns.is_empty() {
             content = redact_content(&content, &patterns);
@@ -278,13 +274,14 @@ pub fn cmd_log_tail(
 /// Clear (truncate) a log file.
 #[tauri::command]
 pub fn cmd_log_clear(
-    runtime: State<'_, AppRuntimeState>,
+    profile: State<'_, ActiveProfile>,
     req: LogClearReq,
 ) -> Result<String, AppError> {
+    let runtime = profile.current();
     // 白名单校验
     validate_log_file_name(&req.file_name, runtime.profile_name())?;
 
-    let log_dir = get_log_dir(runtime.inner())?;
+    let log_dir = get_log_dir(&runtime)?;
     let file_path = log_dir.join(&req.file_name);
 
     if !file_path.exists() {
@@ -310,11 +307,7 @@ pub struct LogLevelResp {
 /// Get current log level setting
 #[tauri::command]
 pub fn cmd_log_get_level(pool: State<DbPool>) -> Result<LogLevelResp, AppError> {
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e| AppError::Db(e.to_string()))?;
+    let conn = get_read_connection(&pool);
 
     let level = get_config_value(&conn, "log_level")
         .unwrap_or_else(|_| {
@@ -346,11 +339,7 @@ pub fn cmd_log_set_level(pool: State<DbPool>, level: String) -> Result<String, A
         )));
     }
 
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e| AppError::Db(e.to_string()))?;
+    let conn = get_connection(&pool);
 
     // 保存到数据库
     conn.execute(
@@ -0,0 +1,91 @@
+use crate::app::{
+    db_maintenance_get_config, db_maintenance_run, db_maintenance_run_scheduled,
+    db_maintenance_update_config, load_db_maintenance_enabled, DbMaintenanceConfigDto,
+    DbMaintenanceConfigReq, DbMaintenanceReport,
+};
+use crate::commands::run_blocking;
+use crate::commands::runtime::{PeriodicTask, ProfileRuntime, RuntimeFuture};
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use tauri::State;
+use tokio::time::Duration;
+
+/// How often the runtime checks whether monthly maintenance is due.
+const DB_MAINTENANCE_POLL_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Timer for the opt-in monthly database maintenance.
+#[derive(Clone, Default)]
+pub struct MaintenanceRuntime {
+    task: PeriodicTask,
+}
+
+impl MaintenanceRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stop_scheduler(&self) {
+        self.task.stop().await;
+    }
+
+    pub async fn refresh_scheduler(&self, pool: DbPool) {
+        let enabled = load_db_maintenance_enabled(&get_read_connection(&pool));
+        if !enabled {
+            self.task.stop().await;
+            return;
+        }
+
+        self.task
+            .start(
+                "DB maintenance",
+                DB_MAINTENANCE_POLL_INTERVAL,
+                pool,
+                |pool| match db_maintenance_run_scheduled(pool, chrono::Utc::now()) {
+                    Ok(Some(report)) => log::info!(
+                        "DB maintenance finished: integrity {}, {} -> {} bytes",
+                        if report.integrity_ok { "ok" } else { "FAILED" },
+                        report.size_before_bytes,
+                        report.size_after_bytes
+                    ),
+                    Ok(None) => {}
+                    Err(e) => log::error!("DB maintenance failed: {}", e),
+                },
+            )
+            .await;
+    }
+}
+
+impl ProfileRuntime for MaintenanceRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_scheduler())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(self.refresh_scheduler(pool))
+    }
+}
+
+/// Integrity check, ANALYZE and vacuum now (works while the monthly run is off).
+#[tauri::command]
+pub async fn cmd_db_maintenance(pool: State<'_, DbPool>) -> Result<DbMaintenanceReport, AppError> {
+    run_blocking(&pool, |pool| db_maintenance_run(pool, chrono::Utc::now())).await
+}
+
+#[tauri::command]
+pub fn cmd_db_maintenance_get_config(
+    pool: State<'_, DbPool>,
+) -> Result<DbMaintenanceConfigDto, AppError> {
+    db_maintenance_get_config(&pool)
+}
+
+/// Turn the monthly run on/off and restart the scheduler.
+#[tauri::command]
+pub async fn cmd_db_maintenance_update_config(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, MaintenanceRuntime>,
+    req: DbMaintenanceConfigReq,
+) -> Result<DbMaintenanceConfigDto, AppError> {
+    let config = db_maintenance_update_config(&pool, req)?;
+    runtime.refresh_scheduler(pool.inner().clone()).await;
+    Ok(config)
+}
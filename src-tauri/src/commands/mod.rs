@@ -1,10 +1,58 @@
 //! Tauri command handlers (DTO boundary).
 
+pub mod action_log;
+pub mod activity;
 pub mod assignment;
+pub mod audit;
+pub mod backup;
+pub mod checklist;
 pub mod comment;
+pub mod country;
+pub mod currency;
+pub mod custom_field;
+pub mod data_dir;
 pub mod data_transfer;
+pub mod digest;
+pub mod encryption;
+pub mod formatting;
+pub mod http_api;
+pub mod integrity;
+pub mod link_check;
 pub mod logs;
+pub mod maintenance;
+pub mod notice;
+pub mod notification;
 pub mod partner;
 pub mod person;
+pub mod profile;
 pub mod project;
+pub mod query;
+pub mod recurrence;
+pub mod report;
+pub mod runtime;
+pub mod saved_view;
+pub mod schema;
+pub mod sla;
+pub mod status_automation;
 pub mod sync;
+pub mod tag;
+pub mod template;
+pub mod trash;
+pub mod webhook;
+pub mod workflow;
+
+use crate::error::AppError;
+use crate::infra::DbPool;
+
+/// Run synchronous SQLite work on the blocking thread pool so large lists, exports and
+/// snapshots never stall the async runtime (and the UI).
+pub(crate) async fn run_blocking<T, F>(pool: &DbPool, f: F) -> Result<T, AppError>
+where
+    F: FnOnce(&DbPool) -> Result<T, AppError> + Send + 'static,
+    T: Send + 'static,
+{
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || f(&pool))
+        .await
+        .map_err(|e| AppError::Db(format!("database task failed: {}", e)))?
+}
@@ -0,0 +1,21 @@
+use crate::app::{notice_dismiss, notice_list, AppNoticeDto};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoticeDismissReq {
+    pub id: i64,
+}
+
+#[tauri::command]
+pub fn cmd_notice_list(pool: State<DbPool>) -> Result<Vec<AppNoticeDto>, AppError> {
+    notice_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_notice_dismiss(pool: State<DbPool>, req: NoticeDismissReq) -> Result<(), AppError> {
+    notice_dismiss(&pool, req.id)
+}
@@ -0,0 +1,147 @@
+use crate::app::{
+    load_notification_desktop_enabled, load_notifications_enabled, notification_check,
+    notification_dismiss, notification_get_config, notification_list, notification_run_scheduled,
+    notification_update_config, NotificationCheckResult, NotificationConfigDto,
+    NotificationConfigReq, NotificationDto, NotificationListReq,
+};
+use crate::commands::runtime::{PeriodicTask, ProfileRuntime, RuntimeFuture};
+use crate::error::AppError;
+use crate::infra::{get_read_connection, DbPool};
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter, State};
+use tokio::time::Duration;
+
+/// How often the runtime looks for due, overdue and long-blocked projects.
+const NOTIFICATION_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Event carrying newly created notifications (`NotificationDto[]`) when desktop
+/// notifications are enabled; the frontend shows them as system notifications.
+pub const NOTIFICATION_CREATED_EVENT: &str = "notification-created";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationDismissReq {
+    pub id: i64,
+}
+
+/// Timer that generates reminders; runs alongside the other schedulers.
+#[derive(Clone)]
+pub struct NotificationRuntime {
+    app: AppHandle,
+    task: PeriodicTask,
+}
+
+impl NotificationRuntime {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            task: PeriodicTask::new(),
+        }
+    }
+
+    pub async fn stop_scheduler(&self) {
+        self.task.stop().await;
+    }
+
+    pub async fn refresh_scheduler(&self, pool: DbPool) {
+        let enabled = load_notifications_enabled(&get_read_connection(&pool));
+        if !enabled {
+            self.task.stop().await;
+            return;
+        }
+
+        let app = self.app.clone();
+        self.task
+            .start(
+                "Notification",
+                NOTIFICATION_CHECK_INTERVAL,
+                pool,
+                move |pool| match notification_run_scheduled(
+                    pool,
+                    chrono::Local::now().date_naive(),
+                    chrono::Utc::now(),
+                ) {
+                    Ok(Some(result)) => {
+                        if !result.created.is_empty() {
+                            log::info!(
+                                "Notification check: {} new, {} resolved",
+                                result.created.len(),
+                                result.resolved
+                            );
+                            emit_desktop(&app, pool, &result.created);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::error!("Notification check failed: {}", e),
+                },
+            )
+            .await;
+    }
+}
+
+impl ProfileRuntime for NotificationRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_scheduler())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(self.refresh_scheduler(pool))
+    }
+}
+
+/// Push new notifications to the frontend if desktop notifications are enabled (best-effort).
+fn emit_desktop(app: &AppHandle, pool: &DbPool, created: &[NotificationDto]) {
+    let desktop = load_notification_desktop_enabled(&get_read_connection(pool));
+    if !desktop || created.is_empty() {
+        return;
+    }
+    if let Err(e) = app.emit(NOTIFICATION_CREATED_EVENT, created) {
+        log::warn!("Failed to emit desktop notifications: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn cmd_notification_get_config(
+    pool: State<'_, DbPool>,
+) -> Result<NotificationConfigDto, AppError> {
+    notification_get_config(&pool)
+}
+
+/// Update reminder settings and restart the scheduler.
+#[tauri::command]
+pub async fn cmd_notification_update_config(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, NotificationRuntime>,
+    req: NotificationConfigReq,
+) -> Result<NotificationConfigDto, AppError> {
+    let config = notification_update_config(&pool, req)?;
+    runtime.refresh_scheduler(pool.inner().clone()).await;
+    Ok(config)
+}
+
+/// Check now (works while the background checker is off).
+#[tauri::command]
+pub fn cmd_notification_check(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+) -> Result<NotificationCheckResult, AppError> {
+    let result = notification_check(&pool, chrono::Local::now().date_naive(), chrono::Utc::now())?;
+    emit_desktop(&app, &pool, &result.created);
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn cmd_notification_list(
+    pool: State<'_, DbPool>,
+    req: Option<NotificationListReq>,
+) -> Result<Vec<NotificationDto>, AppError> {
+    notification_list(&pool, req.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn cmd_notification_dismiss(
+    pool: State<'_, DbPool>,
+    req: NotificationDismissReq,
+) -> Result<(), AppError> {
+    notification_dismiss(&pool, req.id)
+}
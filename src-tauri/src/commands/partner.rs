@@ -1,7 +1,10 @@
 use crate::app::{
-    partner_create, partner_deactivate, partner_get, partner_list, partner_projects,
-    partner_update, PartnerCreateReq, PartnerDto, PartnerProjectItemDto, PartnerUpdateReq,
+    partner_create, partner_deactivate, partner_find_duplicates, partner_get, partner_list,
+    partner_merge, partner_projects, partner_set_default_currency, partner_update,
+    PartnerCreateReq, PartnerDto, PartnerDuplicateDto, PartnerFindDuplicatesReq, PartnerMergeReq,
+    PartnerMergeResult, PartnerProjectItemDto, PartnerSetCurrencyReq, PartnerUpdateReq,
 };
+use crate::commands::run_blocking;
 use crate::error::AppError;
 use crate::infra::DbPool;
 use serde::Deserialize;
@@ -40,6 +43,15 @@ pub fn cmd_partner_update(
     partner_update(&pool, req)
 }
 
+/// Set or clear the default budget currency of a partner.
+#[tauri::command]
+pub fn cmd_partner_set_default_currency(
+    pool: State<DbPool>,
+    req: PartnerSetCurrencyReq,
+) -> Result<PartnerDto, AppError> {
+    partner_set_default_currency(&pool, req)
+}
+
 #[tauri::command]
 pub fn cmd_partner_deactivate(
     pool: State<DbPool>,
@@ -63,3 +75,21 @@ pub fn cmd_partner_projects(
 ) -> Result<Vec<PartnerProjectItemDto>, AppError> {
     partner_projects(&pool, &req.id)
 }
+
+/// Likely duplicate partners (same or similar name), best matches first.
+#[tauri::command]
+pub fn cmd_partner_find_duplicates(
+    pool: State<DbPool>,
+    req: Option<PartnerFindDuplicatesReq>,
+) -> Result<Vec<PartnerDuplicateDto>, AppError> {
+    partner_find_duplicates(&pool, req.unwrap_or_default())
+}
+
+/// Move all projects from the source partner to the target, then deactivate the source.
+#[tauri::command]
+pub async fn cmd_partner_merge(
+    pool: State<'_, DbPool>,
+    req: PartnerMergeReq,
+) -> Result<PartnerMergeResult, AppError> {
+    run_blocking(&pool, move |pool| partner_merge(pool, req)).await
+}
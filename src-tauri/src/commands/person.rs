@@ -1,7 +1,10 @@
 use crate::app::{
-    person_all_projects, person_create, person_current_projects, person_deactivate, person_get,
-    person_list, person_update, PersonCreateReq, PersonDto, PersonProjectItemDto, PersonUpdateReq,
+    person_all_projects, person_create, person_current_projects, person_deactivate,
+    person_find_duplicates, person_get, person_list, person_mentions, person_merge, person_update,
+    PersonCreateReq, PersonDto, PersonDuplicateDto, PersonFindDuplicatesReq, PersonMentionDto,
+    PersonMentionsReq, PersonMergeReq, PersonMergeResult, PersonProjectItemDto, PersonUpdateReq,
 };
+use crate::commands::run_blocking;
 use crate::error::AppError;
 use crate::infra::DbPool;
 use serde::Deserialize;
@@ -65,3 +68,30 @@ pub fn cmd_person_all_projects(
 ) -> Result<Vec<PersonProjectItemDto>, AppError> {
     person_all_projects(&pool, &req.id)
 }
+
+/// Comments that @mention the person, newest first.
+#[tauri::command]
+pub fn cmd_person_mentions(
+    pool: State<DbPool>,
+    req: PersonMentionsReq,
+) -> Result<Vec<PersonMentionDto>, AppError> {
+    person_mentions(&pool, req)
+}
+
+/// Likely duplicate persons (same email, same or similar display name), best matches first.
+#[tauri::command]
+pub fn cmd_person_find_duplicates(
+    pool: State<DbPool>,
+    req: Option<PersonFindDuplicatesReq>,
+) -> Result<Vec<PersonDuplicateDto>, AppError> {
+    person_find_duplicates(&pool, req.unwrap_or_default())
+}
+
+/// Move all references from the duplicate to the primary, then deactivate the duplicate.
+#[tauri::command]
+pub async fn cmd_person_merge(
+    pool: State<'_, DbPool>,
+    req: PersonMergeReq,
+) -> Result<PersonMergeResult, AppError> {
+    run_blocking(&pool, move |pool| person_merge(pool, req)).await
+}
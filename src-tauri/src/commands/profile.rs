@@ -0,0 +1,97 @@
+use crate::app::{
+    profile_create, profile_current, profile_delete, profile_list, profile_switch_target,
+    ProfileContext, ProfileCreateReq, ProfileDeleteReq, ProfileDto, ProfileSwitchReq,
+};
+use crate::commands::runtime::RuntimeRegistry;
+use crate::commands::sync::SyncRuntime;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use crate::{activate_profile, open_profile, ActiveProfile, AppRuntimeState};
+use tauri::{AppHandle, Manager, State};
+
+fn context(runtime: &AppRuntimeState) -> ProfileContext<'_> {
+    ProfileContext {
+        base_dir: runtime.base_data_dir(),
+        current_profile: runtime.profile_name(),
+        current_data_dir: runtime.data_dir(),
+    }
+}
+
+#[tauri::command]
+pub fn cmd_profile_list(profile: State<'_, ActiveProfile>) -> Result<Vec<ProfileDto>, AppError> {
+    profile_list(&context(&profile.current()))
+}
+
+#[tauri::command]
+pub fn cmd_profile_current(profile: State<'_, ActiveProfile>) -> Result<ProfileDto, AppError> {
+    profile_current(&context(&profile.current()))
+}
+
+/// Create an empty profile dir; its database is initialized on first use.
+#[tauri::command]
+pub fn cmd_profile_create(
+    profile: State<'_, ActiveProfile>,
+    req: ProfileCreateReq,
+) -> Result<ProfileDto, AppError> {
+    profile_create(&context(&profile.current()), req)
+}
+
+/// Delete a profile's data; the current profile and profiles open elsewhere are refused.
+#[tauri::command]
+pub fn cmd_profile_delete(
+    profile: State<'_, ActiveProfile>,
+    req: ProfileDeleteReq,
+) -> Result<(), AppError> {
+    profile_delete(&context(&profile.current()), req)
+}
+
+/// Switch to another profile without restarting: waits for a running sync, stops the
+/// schedulers, swaps the database behind the shared pool, moves file logging and the
+/// profile lock, then restarts the schedulers. The frontend must reload all data.
+#[tauri::command]
+pub async fn cmd_profile_switch(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    profile: State<'_, ActiveProfile>,
+    sync_runtime: State<'_, SyncRuntime>,
+    req: ProfileSwitchReq,
+) -> Result<ProfileDto, AppError> {
+    let current = profile.current();
+    let Some(name) = profile_switch_target(&context(&current), &req)? else {
+        return profile_current(&context(&current));
+    };
+
+    let sync_guard = sync_runtime.hold_sync_lock().await;
+    stop_runtimes(&app).await;
+    // Runtimes and commands all hold clones of the managed pool: swap the database in place.
+    let opened = open_profile(current.base_data_dir(), &name)
+        .map_err(AppError::DataDir)
+        .and_then(|(state, lock_file, new_pool)| {
+            pool.0.replace_with(new_pool)?;
+            Ok((state, lock_file))
+        });
+    let (state, lock_file) = match opened {
+        Ok(opened) => opened,
+        Err(e) => {
+            drop(sync_guard);
+            refresh_runtimes(&app, pool.inner().clone()).await;
+            return Err(e);
+        }
+    };
+    activate_profile(&state);
+    profile.replace(state, lock_file);
+    log::info!("Switched profile: {} -> {}", current.profile_name(), name);
+    drop(sync_guard);
+
+    refresh_runtimes(&app, pool.inner().clone()).await;
+    profile_current(&context(&profile.current()))
+}
+
+pub(crate) async fn stop_runtimes(app: &AppHandle) {
+    app.state::<RuntimeRegistry>().stop_all().await;
+}
+
+/// Restart schedulers from the (new) profile's settings.
+pub(crate) async fn refresh_runtimes(app: &AppHandle, pool: DbPool) {
+    app.state::<RuntimeRegistry>().refresh_all(pool).await;
+}
@@ -1,8 +1,13 @@
 use crate::app::{
-    project_change_status, project_create, project_get, project_list, project_update,
-    ProjectChangeStatusReq, ProjectCreateReq, ProjectDetailDto, ProjectListPage, ProjectListReq,
-    ProjectUpdateReq,
+    project_change_status, project_clone, project_create, project_export_markdown, project_get,
+    project_list, project_reorder, project_set_budget, project_set_size, project_throughput,
+    project_tree, project_update, ProjectChangeStatusReq, ProjectCloneReq, ProjectCreateReq,
+    ProjectDetailDto, ProjectListPage, ProjectListReq, ProjectRankDto, ProjectReorderReq,
+    ProjectSetBudgetReq, ProjectSetSizeReq, ProjectShareReq, ProjectShareResult, ProjectTreeNode,
+    ProjectTreeReq, ProjectUpdateReq, ThroughputDto, ThroughputReq,
 };
+use crate::commands::run_blocking;
+use crate::commands::sync::project_share_for_pool;
 use crate::error::AppError;
 use crate::infra::DbPool;
 use serde::Deserialize;
@@ -23,11 +28,38 @@ pub fn cmd_project_create(
 }
 
 #[tauri::command]
-pub fn cmd_project_get(
-    pool: State<DbPool>,
+pub async fn cmd_project_get(
+    pool: State<'_, DbPool>,
     req: ProjectGetReq,
 ) -> Result<ProjectDetailDto, AppError> {
-    project_get(&pool, &req.id)
+    run_blocking(&pool, move |pool| project_get(pool, &req.id)).await
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectExportMarkdownReq {
+    pub project_id: String,
+}
+
+/// One project as Markdown (details, members, status timeline, comments) for wikis and emails.
+#[tauri::command]
+pub async fn cmd_project_export_markdown(
+    pool: State<'_, DbPool>,
+    req: ProjectExportMarkdownReq,
+) -> Result<String, AppError> {
+    run_blocking(&pool, move |pool| {
+        project_export_markdown(pool, &req.project_id)
+    })
+    .await
+}
+
+/// Copy a project as a new BACKLOG project (optionally with tags, members and milestones).
+#[tauri::command]
+pub async fn cmd_project_clone(
+    pool: State<'_, DbPool>,
+    req: ProjectCloneReq,
+) -> Result<ProjectDetailDto, AppError> {
+    run_blocking(&pool, move |pool| project_clone(pool, req)).await
 }
 
 #[tauri::command]
@@ -39,11 +71,65 @@ pub fn cmd_project_update(
 }
 
 #[tauri::command]
-pub fn cmd_project_list(
-    pool: State<DbPool>,
+pub async fn cmd_project_list(
+    pool: State<'_, DbPool>,
     req: Option<ProjectListReq>,
 ) -> Result<ProjectListPage, AppError> {
-    project_list(&pool, req.unwrap_or_default())
+    run_blocking(&pool, move |pool| {
+        project_list(pool, req.unwrap_or_default())
+    })
+    .await
+}
+
+/// Persist a manual Kanban position within the project's status column.
+#[tauri::command]
+pub fn cmd_project_reorder(
+    pool: State<DbPool>,
+    req: ProjectReorderReq,
+) -> Result<ProjectRankDto, AppError> {
+    project_reorder(&pool, req)
+}
+
+/// Set or clear the size estimate (points or XS–XL); changes are recorded in size history.
+#[tauri::command]
+pub fn cmd_project_set_size(
+    pool: State<DbPool>,
+    req: ProjectSetSizeReq,
+) -> Result<ProjectDetailDto, AppError> {
+    project_set_size(&pool, req)
+}
+
+/// Set or clear the estimate (hours), the budget and its currency of a project.
+#[tauri::command]
+pub fn cmd_project_set_budget(
+    pool: State<DbPool>,
+    req: ProjectSetBudgetReq,
+) -> Result<ProjectDetailDto, AppError> {
+    project_set_budget(&pool, req)
+}
+
+/// Weekly completed projects and size-weighted velocity.
+#[tauri::command]
+pub async fn cmd_project_throughput(
+    pool: State<'_, DbPool>,
+    req: Option<ThroughputReq>,
+) -> Result<ThroughputDto, AppError> {
+    run_blocking(&pool, move |pool| {
+        project_throughput(pool, req.unwrap_or_default())
+    })
+    .await
+}
+
+/// Project hierarchy (sub-projects) with rolled-up status counts.
+#[tauri::command]
+pub async fn cmd_project_tree(
+    pool: State<'_, DbPool>,
+    req: Option<ProjectTreeReq>,
+) -> Result<Vec<ProjectTreeNode>, AppError> {
+    run_blocking(&pool, move |pool| {
+        project_tree(pool, req.unwrap_or_default())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -53,3 +139,12 @@ pub fn cmd_project_change_status(
 ) -> Result<ProjectDetailDto, AppError> {
     project_change_status(&pool, req)
 }
+
+/// Upload a redacted read-only bundle of one project and return a time-limited link.
+#[tauri::command]
+pub async fn cmd_project_share(
+    pool: State<'_, DbPool>,
+    req: ProjectShareReq,
+) -> Result<ProjectShareResult, AppError> {
+    project_share_for_pool(pool.inner(), req).await
+}
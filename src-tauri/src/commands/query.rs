@@ -0,0 +1,20 @@
+use crate::app::{execute_query, query_schema, ExecuteQueryReq, QueryResultDto, QuerySchemaDto};
+use crate::commands::run_blocking;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+/// Run one read-only, parameterized SQL query (for scripts and automations).
+#[tauri::command]
+pub async fn cmd_execute_query(
+    pool: State<'_, DbPool>,
+    req: ExecuteQueryReq,
+) -> Result<QueryResultDto, AppError> {
+    run_blocking(&pool, move |pool| execute_query(pool, req)).await
+}
+
+/// Machine-readable description of the queryable tables, columns and relations.
+#[tauri::command]
+pub fn cmd_query_schema(pool: State<'_, DbPool>) -> Result<QuerySchemaDto, AppError> {
+    query_schema(&pool)
+}
@@ -0,0 +1,119 @@
+use crate::app::{
+    recurrence_rule_create, recurrence_rule_delete, recurrence_rule_get, recurrence_rule_list,
+    recurrence_rule_update, recurrence_run_due, status_automation_run, RecurrenceRuleCreateReq,
+    RecurrenceRuleDto, RecurrenceRuleUpdateReq, RecurrenceRunItem,
+};
+use crate::commands::runtime::{PeriodicTask, ProfileRuntime, RuntimeFuture};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+use tokio::time::Duration;
+
+/// How often the runtime checks for due recurrence rules and start dates.
+const RECURRENCE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurrenceRuleDeleteReq {
+    pub id: String,
+}
+
+/// Timer that creates projects from due recurrence rules and applies status automation
+/// (BACKLOG → PLANNED at the start date).
+#[derive(Clone, Default)]
+pub struct RecurrenceRuntime {
+    task: PeriodicTask,
+}
+
+impl RecurrenceRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stop_scheduler(&self) {
+        self.task.stop().await;
+    }
+
+    pub async fn refresh_scheduler(&self, pool: DbPool) {
+        self.task
+            .start("Recurrence", RECURRENCE_CHECK_INTERVAL, pool, |pool| {
+                let today = chrono::Local::now().date_naive();
+                match recurrence_run_due(pool, today) {
+                    Ok(items) => {
+                        for item in items {
+                            log::info!(
+                                "Recurrence rule {} ran for {}: {:?}",
+                                item.rule_id,
+                                item.run_on,
+                                item.project_id
+                            );
+                        }
+                    }
+                    Err(e) => log::error!("Recurrence check failed: {}", e),
+                }
+                match status_automation_run(pool, today) {
+                    Ok(items) => {
+                        for item in items.iter().filter(|item| item.error.is_none()) {
+                            log::info!(
+                                "Project {} moved to {} (start date {})",
+                                item.project_id,
+                                item.to_status,
+                                item.start_date
+                            );
+                        }
+                    }
+                    Err(e) => log::error!("Status automation failed: {}", e),
+                }
+            })
+            .await;
+    }
+}
+
+impl ProfileRuntime for RecurrenceRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_scheduler())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(self.refresh_scheduler(pool))
+    }
+}
+
+#[tauri::command]
+pub fn cmd_recurrence_list(pool: State<'_, DbPool>) -> Result<Vec<RecurrenceRuleDto>, AppError> {
+    recurrence_rule_list(&pool)
+}
+
+/// Create a rule; a rule already due (start date today or earlier) runs immediately.
+#[tauri::command]
+pub fn cmd_recurrence_create(
+    pool: State<'_, DbPool>,
+    req: RecurrenceRuleCreateReq,
+) -> Result<RecurrenceRuleDto, AppError> {
+    let rule = recurrence_rule_create(&pool, req)?;
+    recurrence_run_due(&pool, chrono::Local::now().date_naive())?;
+    recurrence_rule_get(&pool, &rule.id)
+}
+
+#[tauri::command]
+pub fn cmd_recurrence_update(
+    pool: State<'_, DbPool>,
+    req: RecurrenceRuleUpdateReq,
+) -> Result<RecurrenceRuleDto, AppError> {
+    recurrence_rule_update(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_recurrence_delete(
+    pool: State<'_, DbPool>,
+    req: RecurrenceRuleDeleteReq,
+) -> Result<(), AppError> {
+    recurrence_rule_delete(&pool, &req.id)
+}
+
+/// Run due rules now (same as the hourly check).
+#[tauri::command]
+pub fn cmd_recurrence_run_due(pool: State<'_, DbPool>) -> Result<Vec<RecurrenceRunItem>, AppError> {
+    recurrence_run_due(&pool, chrono::Local::now().date_naive())
+}
@@ -0,0 +1,198 @@
+use crate::app::{
+    digest_run_scheduled, partner_scorecard, report_partner_summary, report_person_workload,
+    report_run_due, report_schedule_create, report_schedule_delete, report_schedule_get,
+    report_schedule_list, report_schedule_run_now, report_schedule_update, report_summary,
+    PartnerScorecardDto, PartnerSummaryDto, PersonWorkloadReportDto, PersonWorkloadReq,
+    ReportRunItem, ReportScheduleCreateReq, ReportScheduleDto, ReportScheduleUpdateReq,
+    ReportSummaryDto, ReportSummaryReq,
+};
+use crate::commands::run_blocking;
+use crate::commands::runtime::{PeriodicTask, ProfileRuntime, RuntimeFuture};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+use tokio::time::Duration;
+
+/// How often the runtime checks for due report schedules.
+const REPORT_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportScheduleDeleteReq {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportScheduleRunReq {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerScorecardReq {
+    pub partner_id: String,
+}
+
+/// Timer that generates reports from due schedules and posts due chat digests.
+#[derive(Clone, Default)]
+pub struct ReportRuntime {
+    task: PeriodicTask,
+}
+
+impl ReportRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stop_scheduler(&self) {
+        self.task.stop().await;
+    }
+
+    pub async fn refresh_scheduler(&self, pool: DbPool) {
+        self.task
+            .start("Report schedule", REPORT_CHECK_INTERVAL, pool, |pool| {
+                match report_run_due(pool, chrono::Local::now().date_naive()) {
+                    Ok(items) => {
+                        for item in items {
+                            log::info!(
+                                "Report schedule {} ran for {}: {:?}",
+                                item.schedule_id,
+                                item.run_on,
+                                item.output_path
+                            );
+                        }
+                    }
+                    Err(e) => log::error!("Report schedule check failed: {}", e),
+                }
+
+                // Chat digests share the hourly tick (they are reports posted to a channel).
+                match digest_run_scheduled(
+                    pool,
+                    chrono::Local::now().date_naive(),
+                    chrono::Utc::now(),
+                ) {
+                    Ok(Some(result)) => log::info!(
+                        "Digest for {} – {}: posted = {}",
+                        result.period_start,
+                        result.period_end,
+                        result.posted
+                    ),
+                    Ok(None) => {}
+                    Err(e) => log::warn!("Digest failed: {}", e),
+                }
+            })
+            .await;
+    }
+}
+
+impl ProfileRuntime for ReportRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_scheduler())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(self.refresh_scheduler(pool))
+    }
+}
+
+#[tauri::command]
+pub fn cmd_report_schedule_list(
+    pool: State<'_, DbPool>,
+) -> Result<Vec<ReportScheduleDto>, AppError> {
+    report_schedule_list(&pool)
+}
+
+/// Create a schedule; one already due (start date today or earlier) runs immediately.
+#[tauri::command]
+pub async fn cmd_report_schedule_create(
+    pool: State<'_, DbPool>,
+    req: ReportScheduleCreateReq,
+) -> Result<ReportScheduleDto, AppError> {
+    run_blocking(&pool, move |pool| {
+        let schedule = report_schedule_create(pool, req)?;
+        report_run_due(pool, chrono::Local::now().date_naive())?;
+        report_schedule_get(pool, &schedule.id)
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn cmd_report_schedule_update(
+    pool: State<'_, DbPool>,
+    req: ReportScheduleUpdateReq,
+) -> Result<ReportScheduleDto, AppError> {
+    report_schedule_update(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_report_schedule_delete(
+    pool: State<'_, DbPool>,
+    req: ReportScheduleDeleteReq,
+) -> Result<(), AppError> {
+    report_schedule_delete(&pool, &req.id)
+}
+
+/// Generate a schedule's report now; the next scheduled run is unchanged.
+#[tauri::command]
+pub async fn cmd_report_schedule_run(
+    pool: State<'_, DbPool>,
+    req: ReportScheduleRunReq,
+) -> Result<ReportRunItem, AppError> {
+    run_blocking(&pool, move |pool| {
+        report_schedule_run_now(pool, &req.id, chrono::Local::now().date_naive())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn cmd_report_partner_scorecard(
+    pool: State<'_, DbPool>,
+    req: PartnerScorecardReq,
+) -> Result<PartnerScorecardDto, AppError> {
+    run_blocking(&pool, move |pool| {
+        partner_scorecard(pool, &req.partner_id, chrono::Local::now().date_naive())
+    })
+    .await
+}
+
+/// Per partner: project counts by stage, average duration and latest activity.
+#[tauri::command]
+pub async fn cmd_report_partner_summary(
+    pool: State<'_, DbPool>,
+) -> Result<Vec<PartnerSummaryDto>, AppError> {
+    run_blocking(&pool, report_partner_summary).await
+}
+
+/// Status counts, average time in status, weekly throughput and overdue counts.
+#[tauri::command]
+pub async fn cmd_report_summary(
+    pool: State<'_, DbPool>,
+    req: Option<ReportSummaryReq>,
+) -> Result<ReportSummaryDto, AppError> {
+    run_blocking(&pool, move |pool| {
+        report_summary(
+            pool,
+            req.unwrap_or_default(),
+            chrono::Local::now().date_naive(),
+        )
+    })
+    .await
+}
+
+/// Per active person: active assignments, owned projects, projects by status and upcoming due dates.
+#[tauri::command]
+pub async fn cmd_report_person_workload(
+    pool: State<'_, DbPool>,
+    req: Option<PersonWorkloadReq>,
+) -> Result<PersonWorkloadReportDto, AppError> {
+    run_blocking(&pool, move |pool| {
+        report_person_workload(
+            pool,
+            req.unwrap_or_default(),
+            chrono::Local::now().date_naive(),
+        )
+    })
+    .await
+}
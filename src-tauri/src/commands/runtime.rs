@@ -0,0 +1,104 @@
+//! Background runtimes bound to the active database.
+//!
+//! 设计要点：
+//! - 定时任务（备份、报表、提醒、Webhook 等）共用 `PeriodicTask`：一个可中止的循环，
+//!   每轮在阻塞线程池上执行一次 tick，再等待固定间隔；重新启动时先中止旧循环，保证只有一个存活。
+//! - 每个运行时实现 `ProfileRuntime`（停止 / 按当前设置重启），并在启动时登记到
+//!   `RuntimeRegistry`；切换 profile、加密、迁移数据目录时只遍历注册表，不再逐个列举。
+//! - 按登记顺序停止、按相反顺序重启：同步调度最先登记，因此最先停止、最后重启。
+//! - 方法返回装箱的 `Send` future，以便以 `Arc<dyn ProfileRuntime>` 存放（不引入 async-trait）。
+
+use crate::infra::DbPool;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Duration};
+
+pub type RuntimeFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// A background runtime that reads its settings from the active database.
+pub trait ProfileRuntime: Send + Sync {
+    fn stop(&self) -> RuntimeFuture<'_>;
+
+    /// (Re)start from the settings stored in `pool`; stays stopped when disabled.
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_>;
+}
+
+/// Abortable background task; `start` runs a blocking tick every `interval`.
+#[derive(Clone, Default)]
+pub struct PeriodicTask {
+    handle: Arc<AsyncMutex<Option<JoinHandle<()>>>>,
+}
+
+impl PeriodicTask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stop(&self) {
+        let mut guard = self.handle.lock().await;
+        if let Some(handle) = guard.take() {
+            handle.abort();
+        }
+    }
+
+    /// Replace the running task with `task` (the HTTP API uses this for its accept loop).
+    pub async fn spawn<T>(&self, task: T)
+    where
+        T: Future<Output = ()> + Send + 'static,
+    {
+        let mut guard = self.handle.lock().await;
+        // Abort under the same lock so only one task is ever alive.
+        if let Some(handle) = guard.take() {
+            handle.abort();
+        }
+        *guard = Some(tokio::spawn(task));
+    }
+
+    /// Replace the running loop. `tick` runs on the blocking pool and logs its own result;
+    /// a panic is logged under `name` and the loop keeps going.
+    pub async fn start<F>(&self, name: &'static str, interval: Duration, pool: DbPool, tick: F)
+    where
+        F: Fn(&DbPool) + Send + Sync + 'static,
+    {
+        let tick = Arc::new(tick);
+        self.spawn(async move {
+            loop {
+                let task_tick = tick.clone();
+                let task_pool = pool.clone();
+                if let Err(e) = tokio::task::spawn_blocking(move || task_tick(&task_pool)).await {
+                    log::error!("{} task panicked: {}", name, e);
+                }
+                sleep(interval).await;
+            }
+        })
+        .await;
+    }
+}
+
+/// Every runtime that has to follow the active database, in registration order.
+#[derive(Clone, Default)]
+pub struct RuntimeRegistry {
+    runtimes: Vec<Arc<dyn ProfileRuntime>>,
+}
+
+impl RuntimeRegistry {
+    pub fn register<R: ProfileRuntime + 'static>(&mut self, runtime: R) {
+        self.runtimes.push(Arc::new(runtime));
+    }
+
+    pub async fn stop_all(&self) {
+        for runtime in &self.runtimes {
+            runtime.stop().await;
+        }
+    }
+
+    /// Restart from the (new) database's settings, in reverse registration order.
+    pub async fn refresh_all(&self, pool: DbPool) {
+        for runtime in self.runtimes.iter().rev() {
+            runtime.refresh(pool.clone()).await;
+        }
+    }
+}
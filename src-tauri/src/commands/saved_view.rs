@@ -0,0 +1,40 @@
+use crate::app::{
+    saved_view_apply, saved_view_create, saved_view_delete, saved_view_list, ProjectListPage,
+    SavedViewApplyReq, SavedViewCreateReq, SavedViewDto,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedViewDeleteReq {
+    pub id: String,
+}
+
+#[tauri::command]
+pub fn cmd_saved_view_list(pool: State<DbPool>) -> Result<Vec<SavedViewDto>, AppError> {
+    saved_view_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_saved_view_create(
+    pool: State<DbPool>,
+    req: SavedViewCreateReq,
+) -> Result<SavedViewDto, AppError> {
+    saved_view_create(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_saved_view_delete(pool: State<DbPool>, req: SavedViewDeleteReq) -> Result<(), AppError> {
+    saved_view_delete(&pool, &req.id)
+}
+
+#[tauri::command]
+pub fn cmd_saved_view_apply(
+    pool: State<DbPool>,
+    req: SavedViewApplyReq,
+) -> Result<ProjectListPage, AppError> {
+    saved_view_apply(&pool, req)
+}
@@ -0,0 +1,17 @@
+use crate::app::{db_schema_export, DbSchemaExportDto, DbSchemaExportReq};
+use crate::commands::run_blocking;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+/// Current database schema as JSON, a Mermaid ERD or a Graphviz ERD (default JSON).
+#[tauri::command]
+pub async fn cmd_db_schema_export(
+    pool: State<'_, DbPool>,
+    req: Option<DbSchemaExportReq>,
+) -> Result<DbSchemaExportDto, AppError> {
+    run_blocking(&pool, move |pool| {
+        db_schema_export(pool, req.unwrap_or_default())
+    })
+    .await
+}
@@ -0,0 +1,62 @@
+use crate::app::{
+    project_flag_list, sla_evaluate, sla_rule_create, sla_rule_delete, sla_rule_list,
+    sla_rule_update, ProjectFlagDto, SlaEvaluateResult, SlaRuleCreateReq, SlaRuleDto,
+    SlaRuleUpdateReq,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlaRuleDeleteReq {
+    pub id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFlagListReq {
+    /// Omitted = flags of every project.
+    pub project_id: Option<String>,
+}
+
+#[tauri::command]
+pub fn cmd_sla_rule_list(pool: State<DbPool>) -> Result<Vec<SlaRuleDto>, AppError> {
+    sla_rule_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_sla_rule_create(
+    pool: State<DbPool>,
+    req: SlaRuleCreateReq,
+) -> Result<SlaRuleDto, AppError> {
+    sla_rule_create(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_sla_rule_update(
+    pool: State<DbPool>,
+    req: SlaRuleUpdateReq,
+) -> Result<SlaRuleDto, AppError> {
+    sla_rule_update(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_sla_rule_delete(pool: State<DbPool>, req: SlaRuleDeleteReq) -> Result<(), AppError> {
+    sla_rule_delete(&pool, &req.id)
+}
+
+/// Re-evaluate the rules now (the notification checker also does this every hour).
+#[tauri::command]
+pub fn cmd_sla_evaluate(pool: State<DbPool>) -> Result<SlaEvaluateResult, AppError> {
+    sla_evaluate(&pool, chrono::Local::now().date_naive(), chrono::Utc::now())
+}
+
+#[tauri::command]
+pub fn cmd_project_flag_list(
+    pool: State<DbPool>,
+    req: Option<ProjectFlagListReq>,
+) -> Result<Vec<ProjectFlagDto>, AppError> {
+    project_flag_list(&pool, req.unwrap_or_default().project_id)
+}
@@ -0,0 +1,33 @@
+use crate::app::{
+    status_automation_get_config, status_automation_run, status_automation_update_config,
+    StatusAutomationConfigDto, StatusAutomationConfigReq, StatusAutomationItem,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+#[tauri::command]
+pub fn cmd_status_automation_get_config(
+    pool: State<'_, DbPool>,
+) -> Result<StatusAutomationConfigDto, AppError> {
+    status_automation_get_config(&pool)
+}
+
+/// Turning auto-plan on moves projects whose start date has passed right away.
+#[tauri::command]
+pub fn cmd_status_automation_update_config(
+    pool: State<'_, DbPool>,
+    req: StatusAutomationConfigReq,
+) -> Result<StatusAutomationConfigDto, AppError> {
+    let config = status_automation_update_config(&pool, req)?;
+    status_automation_run(&pool, chrono::Local::now().date_naive())?;
+    Ok(config)
+}
+
+/// Apply automatic status changes now (same as the hourly run).
+#[tauri::command]
+pub fn cmd_status_automation_run(
+    pool: State<'_, DbPool>,
+) -> Result<Vec<StatusAutomationItem>, AppError> {
+    status_automation_run(&pool, chrono::Local::now().date_naive())
+}
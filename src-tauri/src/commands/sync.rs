@@ -1,22 +1,121 @@
 //! Tauri commands for sync operations
 
+use crate::app::{
+    backup_create, load_backup_max_count, project_share_bundle, project_share_expiry,
+    project_share_object_key, webhook_notify, ImportResult, ProjectShareReq, ProjectShareResult,
+    BACKUP_MAX_COUNT_KEY, BACKUP_REASON_RESTORE_SNAPSHOT, WEBHOOK_EVENT_SYNC_COMPLETED,
+};
+use crate::commands::run_blocking;
+use crate::commands::runtime::{ProfileRuntime, RuntimeFuture};
 use crate::error::{AppError, PendingWipeInfo};
-use crate::infra::DbPool;
-use crate::sync::{Delta, DeltaSyncEngine, S3ObjectSummary, S3SyncClient, SnapshotManager};
+use crate::infra::db::open_memory_db;
+use crate::infra::redact::{redact_message, refresh_from_sync_config, register_secrets};
+use crate::infra::{get_connection, get_read_connection, DbPool};
+use crate::sync::activity::{
+    record_user_activity, wait_for_user_idle, MAX_APPLY_DEFER, USER_IDLE_WINDOW,
+};
+use crate::sync::backend::{BackendKind, SyncBackend, SYNC_BACKEND_KEY};
+use crate::sync::compaction::{
+    device_cursors_key, load_local_cursors, parse_device_cursors_key, plan_compaction,
+    DeviceCursors, RemoteDeltaRef, AUTO_COMPACT_KEY, COMPACT_OLDER_THAN_DAYS_KEY,
+    DEFAULT_COMPACT_OLDER_THAN_DAYS, LAST_COMPACT_AT_KEY, LAST_PUBLISHED_CURSORS_KEY,
+};
+use crate::sync::config_check::{
+    check_config_shape, has_failures, probe_object_key, CheckStatus, ConfigCheck, ConfigShape,
+};
+use crate::sync::conflicts::{
+    list_sync_conflicts, resolve_sync_conflict, SyncConflictDto, SyncConflictListReq,
+    SyncResolveConflictReq,
+};
+use crate::sync::delta_sync::{DELTA_CHUNK_MAX_MB_KEY, DELTA_CHUNK_MAX_OPS_KEY};
+use crate::sync::device_health::{build_device_health, DeviceSyncHealth};
+use crate::sync::device_registry::{
+    device_object_prefixes, is_device_revoked, list_devices, load_revoked_devices,
+    rename_local_device, revoke_device, touch_local_device, validate_device_id, DeviceInfo,
+};
+use crate::sync::error_log::{
+    list_sync_errors, record_sync_error, SyncErrorListReq, SyncErrorPage, DEFAULT_SYNC_PHASE,
+};
+use crate::sync::gdrive::{
+    begin_authorization, finish_authorization, revoke_token, GoogleDriveBackend,
+    GoogleDriveCredentials, GoogleDriveError, AUTHORIZATION_TIMEOUT,
+};
+use crate::sync::history::{list_sync_history, record_sync_history};
+use crate::sync::import_snapshot::{
+    extract_snapshot_import, load_auto_snapshot_import_threshold, mark_import_inserts_synced,
+    max_sync_metadata_id, merge_import_snapshot, should_publish_import_snapshot, SnapshotImportRef,
+    AUTO_SNAPSHOT_IMPORT_THRESHOLD_KEY,
+};
+use crate::sync::namespace_claim::{
+    check_namespace_claim, is_namespace_verified, local_namespace_claim, mark_namespace_verified,
+    namespace_claim_key, namespace_token, reset_device_id, NamespaceClaim,
+};
+use crate::sync::offline::{probe_connectivity, OfflineQueue, OfflineQueueStatus};
+use crate::sync::pause::{
+    SyncPause, SyncPauseState, WriteDebounce, DEFAULT_SYNC_ON_WRITE_DEBOUNCE_SECS,
+    SYNC_ON_WRITE_DEBOUNCE_SECS_KEY, SYNC_ON_WRITE_KEY, WRITE_POLL_INTERVAL,
+};
+use crate::sync::presence::{
+    claim_presence_slot, load_presence_config, local_heartbeat, presence_key, recent_presence,
+    reset_presence_throttle, resolve_project_names, save_presence_config, PresenceConfig,
+    PresenceEntry, PresenceHeartbeat, PRESENCE_ACTIVE_WINDOW_SECS, PRESENCE_PREFIX,
+};
+use crate::sync::protocol::{
+    check_remote_schema, local_schema_meta, SchemaMeta, SCHEMA_META_KEY, SYNC_FORMAT_VERSION,
+};
+use crate::sync::repair::{
+    build_manifest, compress_manifest, decompress_manifest, parse_row_hashes_key, plan_repair,
+    repair_request_key, repair_request_prefix, requeue_rows, row_hashes_key, RepairRequest,
+};
+use crate::sync::replica::{is_read_only_replica, READ_ONLY_REPLICA_KEY};
+use crate::sync::s3_client::{
+    normalize_key_prefix, presign_expiry, validate_presign_key, validate_role_arn, ObjectOptions,
+    RetryPolicy, S3AuthSettings, TransferConfig, MIN_PART_SIZE_MB, S3_EXTERNAL_ID_KEY,
+    S3_KEY_PREFIX_KEY, S3_PART_RETRIES_KEY, S3_PART_SIZE_MB_KEY, S3_PROFILE_KEY,
+    S3_RETRY_BASE_DELAY_MS_KEY, S3_RETRY_MAX_ATTEMPTS_KEY, S3_ROLE_ARN_KEY, S3_SESSION_TOKEN_KEY,
+};
+use crate::sync::snapshot::{Snapshot, TableRestoreDiff};
+use crate::sync::snapshot_catalog::{
+    describe_snapshots, new_snapshot_key, snapshots_to_prune, SnapshotInfo,
+    DEFAULT_SNAPSHOT_RETENTION_COUNT, SNAPSHOT_RETENTION_COUNT_KEY,
+};
+use crate::sync::staging::{
+    apply_staged_deltas, discard_staged_deltas, list_staged_deltas, should_stage_remote_deltas,
+    stage_remote_delta, StagedApplyResult, StagedDeltaDto, REVIEW_BEFORE_APPLY_KEY,
+};
+use crate::sync::storage_class::{
+    format_object_tags, normalize_storage_class, parse_object_tags, tagging_header,
+    DeltaLifecycleRule, DEFAULT_STORAGE_CLASS, SNAPSHOT_STORAGE_CLASS_KEY, SNAPSHOT_TAGS_KEY,
+};
+use crate::sync::table_filter::{
+    load_exclude_archived_projects, load_ignored_tables, set_exclude_archived_projects,
+    set_ignored_tables, PROJECT_DETAIL_TABLES, SYNCABLE_TABLES,
+};
+use crate::sync::throttle::{
+    kbps_to_bytes_per_sec, MAX_DOWNLOAD_KBPS_KEY, MAX_UPLOAD_KBPS_KEY, PAUSE_ON_METERED_KEY,
+};
+use crate::sync::verify::{compare_databases, verify_tables, SyncVerifyReport};
+use crate::sync::{
+    Delta, DeltaChunkLimits, DeltaSyncEngine, PresignMethod, S3ObjectSummary, S3SyncClient,
+    SnapshotManager, SyncHistoryPage, SyncHistoryReq, SyncRunResult, SyncRunStats,
+};
 use aws_sdk_s3::error::ProvideErrorMetadata;
 use aws_sdk_s3::error::SdkError;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error as StdError;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex as AsyncMutex;
 use tokio::task::JoinHandle;
 use tokio::time::{sleep, Duration};
 use uuid::Uuid;
 
 const PENDING_WIPE_KEY: &str = "pending_wipe";
+pub(crate) const DISABLE_SNAPSHOT_RESTORE_KEY: &str = "disable_snapshot_restore";
+pub(crate) const DISABLE_REMOTE_CLEANUP_KEY: &str = "disable_remote_cleanup";
 
 /// Injected S3 credentials for Android (from Keystore).
 /// On desktop the credentials are read from SQLite sync_config directly.
@@ -63,17 +162,12 @@ pub async fn android_run_sync_once(pool_ref: &DbPool) -> AndroidSyncResult {
 
     // 1. Check sync_enabled in SQLite
     let sync_enabled = {
-        match pool_ref.0.lock() {
-            Ok(conn) => {
-                get_config_value(&conn, "sync_enabled")
-                    .ok()
-                    .as_deref()
-                    .unwrap_or("0")
-                    .trim()
-                    == "1"
-            }
-            Err(_) => false,
-        }
+        get_config_value(&get_read_connection(pool_ref), "sync_enabled")
+            .ok()
+            .as_deref()
+            .unwrap_or("0")
+            .trim()
+            == "1"
     };
     if !sync_enabled {
         log::info!("[android_sync] sync_enabled=0, skipping");
@@ -84,13 +178,8 @@ pub async fn android_run_sync_once(pool_ref: &DbPool) -> AndroidSyncResult {
     }
 
     // 3. Acquire file lock (sync.lock) for cross-process mutual exclusion.
-    //    data_dir is derived from the same dirs crate path as the Tauri app.
-    let lock_path = {
-        let base = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
-        base.join("com.nickdu.projex")
-            .join("default")
-            .join("sync.lock")
-    };
+    //    data_dir is the default profile dir under the same dirs crate path as the Tauri app.
+    let lock_path = crate::android_profile_data_dir().join("sync.lock");
     if let Some(parent) = lock_path.parent() {
         let _ = std::fs::create_dir_all(parent);
     }
@@ -119,22 +208,13 @@ pub async fn android_run_sync_once(pool_ref: &DbPool) -> AndroidSyncResult {
 
     // 4. Read config from SQLite (including credentials, same as desktop)
     let (device_id_opt, bucket_opt, endpoint, access_key, secret_key) = {
-        match pool_ref.0.lock() {
-            Ok(conn) => {
-                let device_id = get_config_value(&conn, "device_id").ok();
-                let bucket = get_config_value(&conn, "s3_bucket").ok();
-                let endpoint = get_config_value(&conn, "s3_endpoint").ok();
-                let access_key = get_config_value(&conn, "s3_access_key").ok();
-                let secret_key = get_config_value(&conn, "s3_secret_key").ok();
-                (device_id, bucket, endpoint, access_key, secret_key)
-            }
-            Err(_) => {
-                return AndroidSyncResult {
-                    status: "failed".to_string(),
-                    message: "db lock poisoned".to_string(),
-                };
-            }
-        }
+        let conn = get_read_connection(pool_ref);
+        let device_id = get_config_value(&conn, "device_id").ok();
+        let bucket = get_config_value(&conn, "s3_bucket").ok();
+        let endpoint = get_config_value(&conn, "s3_endpoint").ok();
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
+        (device_id, bucket, endpoint, access_key, secret_key)
     };
 
     let device_id = match device_id_opt {
@@ -168,9 +248,7 @@ pub async fn android_run_sync_once(pool_ref: &DbPool) -> AndroidSyncResult {
     // 5. HTTPS-only enforcement for Android
     if let Err(e) = validate_endpoint_https(&endpoint) {
         log::error!("[android_sync] {}", e);
-        if let Ok(conn) = pool_ref.0.lock() {
-            let _ = set_config_value(&conn, "last_sync_error", &e.to_string());
-        }
+        let _ = set_config_value(&get_connection(pool_ref), "last_sync_error", &e.to_string());
         return AndroidSyncResult {
             status: "failed".to_string(),
             message: e.to_string(),
@@ -192,9 +270,9 @@ pub async fn android_run_sync_once(pool_ref: &DbPool) -> AndroidSyncResult {
     .await;
 
     match result {
-        Ok(msg) => AndroidSyncResult {
+        Ok(result) => AndroidSyncResult {
             status: "ok".to_string(),
-            message: msg,
+            message: result.message,
         },
         Err(e) => AndroidSyncResult {
             status: "failed".to_string(),
@@ -211,6 +289,12 @@ pub struct SyncRuntime {
 struct SyncRuntimeInner {
     sync_lock: AsyncMutex<()>,
     is_syncing: AtomicBool,
+    /// Reported by the frontend (Network Information API); not persisted.
+    network_metered: AtomicBool,
+    offline: std::sync::Mutex<OfflineQueue>,
+    pause: std::sync::Mutex<SyncPause>,
+    /// Wakes the scheduler early (e.g. the frontend saw the network come back).
+    wake: tokio::sync::Notify,
     scheduler_handle: AsyncMutex<Option<JoinHandle<()>>>,
 }
 
@@ -220,6 +304,10 @@ impl SyncRuntime {
             inner: Arc::new(SyncRuntimeInner {
                 sync_lock: AsyncMutex::new(()),
                 is_syncing: AtomicBool::new(false),
+                network_metered: AtomicBool::new(false),
+                offline: std::sync::Mutex::new(OfflineQueue::default()),
+                pause: std::sync::Mutex::new(SyncPause::default()),
+                wake: tokio::sync::Notify::new(),
                 scheduler_handle: AsyncMutex::new(None),
             }),
         }
@@ -229,6 +317,66 @@ impl SyncRuntime {
         self.inner.is_syncing.load(Ordering::Relaxed)
     }
 
+    pub fn offline_status(&self) -> OfflineQueueStatus {
+        self.offline_queue().status()
+    }
+
+    fn offline_queue(&self) -> std::sync::MutexGuard<'_, OfflineQueue> {
+        self.inner.offline.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn mark_online(&self) {
+        if self.offline_queue().mark_online() {
+            log::info!("S3 endpoint reachable again; leaving offline mode");
+        }
+    }
+
+    fn pause_guard(&self) -> std::sync::MutexGuard<'_, SyncPause> {
+        self.inner.pause.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Pause scheduled and write-triggered syncs for `duration` (`None` = until resumed).
+    /// Manual syncs still run.
+    pub fn pause(&self, duration: Option<Duration>) -> SyncPauseState {
+        let now = chrono::Utc::now();
+        let pause = match duration.and_then(|d| chrono::Duration::from_std(d).ok()) {
+            Some(d) => SyncPause::Until(now + d),
+            None => SyncPause::Indefinite,
+        };
+        *self.pause_guard() = pause;
+        log::info!("Sync paused: {:?}", pause);
+        pause.state(now)
+    }
+
+    pub fn resume(&self) -> SyncPauseState {
+        *self.pause_guard() = SyncPause::Running;
+        log::info!("Sync resumed");
+        self.wake_scheduler();
+        SyncPauseState::default()
+    }
+
+    pub fn pause_state(&self) -> SyncPauseState {
+        self.pause_guard().state(chrono::Utc::now())
+    }
+
+    /// Run the scheduler's next tick now instead of waiting for its timer.
+    pub fn wake_scheduler(&self) {
+        self.inner.wake.notify_one();
+    }
+
+    pub fn is_network_metered(&self) -> bool {
+        self.inner.network_metered.load(Ordering::Relaxed)
+    }
+
+    pub fn set_network_metered(&self, metered: bool) {
+        self.inner.network_metered.store(metered, Ordering::Relaxed);
+    }
+
+    /// Wait for a running sync to finish and keep new ones out while the guard lives.
+    pub async fn hold_sync_lock(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.inner.sync_lock.lock().await
+    }
+
     pub async fn stop_scheduler(&self) {
         let mut guard = self.inner.scheduler_handle.lock().await;
         if let Some(handle) = guard.take() {
@@ -243,13 +391,7 @@ impl SyncRuntime {
         self.stop_scheduler().await;
 
         let enabled = {
-            let conn = match pool.0.lock() {
-                Ok(c) => c,
-                Err(poisoned) => {
-                    log::error!("DB lock poisoned when refreshing scheduler: {}", poisoned);
-                    return;
-                }
-            };
+            let conn = get_read_connection(&pool);
             get_config_value(&conn, "sync_enabled").ok().as_deref() == Some("1")
         };
 
@@ -261,22 +403,19 @@ impl SyncRuntime {
         let mut guard = self.inner.scheduler_handle.lock().await;
         *guard = Some(tokio::spawn(async move {
             loop {
-                let (enabled, minutes) = match pool.0.lock() {
-                    Ok(conn) => {
-                        let enabled =
-                            get_config_value(&conn, "sync_enabled").ok().as_deref() == Some("1");
-                        let minutes = get_config_value(&conn, "auto_sync_interval_minutes")
-                            .ok()
-                            .and_then(|v| v.trim().parse::<i64>().ok())
-                            .filter(|v| *v >= 1)
-                            .unwrap_or(1);
-                        (enabled, minutes)
-                    }
-                    Err(poisoned) => {
-                        log::error!("DB lock poisoned in scheduler loop: {}", poisoned);
-                        // Backoff; keep the scheduler alive.
-                        (false, 1)
-                    }
+                let (enabled, minutes, pause_on_metered, endpoint) = {
+                    let conn = get_read_connection(&pool);
+                    let enabled =
+                        get_config_value(&conn, "sync_enabled").ok().as_deref() == Some("1");
+                    let minutes = get_config_value(&conn, "auto_sync_interval_minutes")
+                        .ok()
+                        .and_then(|v| v.trim().parse::<i64>().ok())
+                        .filter(|v| *v >= 1)
+                        .unwrap_or(1);
+                    let pause_on_metered =
+                        is_config_flag_set(&conn, PAUSE_ON_METERED_KEY).unwrap_or(false);
+                    let endpoint = get_config_value(&conn, "s3_endpoint").ok();
+                    (enabled, minutes, pause_on_metered, endpoint)
                 };
 
                 if !enabled {
@@ -284,24 +423,154 @@ impl SyncRuntime {
                     break;
                 }
 
-                let res = sync_full_with_runtime_for_pool(&pool, &runtime).await;
-                if let Err(e) = res {
-                    log::error!("Scheduled sync failed: {}", e);
+                let interval = Duration::from_secs((minutes.max(1) as u64) * 60);
+                let mut delay = interval;
+                let pause = *runtime.pause_guard();
+                if pause.is_paused(chrono::Utc::now()) {
+                    log::info!("Scheduled sync skipped (paused)");
+                    if let Some(remaining) = pause.remaining(chrono::Utc::now()) {
+                        delay = delay.min(remaining);
+                    }
+                } else if pause_on_metered && runtime.is_network_metered() {
+                    log::info!("Scheduled sync skipped (metered connection)");
+                } else if runtime.offline_status().offline
+                    && !probe_connectivity(endpoint.as_deref()).await
+                {
+                    // Still offline: keep changes queued, probe again after a longer delay.
+                    delay = runtime
+                        .offline_queue()
+                        .mark_offline(chrono::Utc::now(), interval);
+                } else {
+                    let res = sync_full_with_runtime_for_pool(&pool, &runtime).await;
+                    match res {
+                        Ok(_) => auto_compact_if_due(&pool, &runtime).await,
+                        Err(e) => {
+                            log::error!("Scheduled sync failed: {}", e);
+                            if !probe_connectivity(endpoint.as_deref()).await {
+                                delay = runtime
+                                    .offline_queue()
+                                    .mark_offline(chrono::Utc::now(), interval);
+                                log::warn!(
+                                    "S3 endpoint unreachable; pending changes stay queued, retrying in {:?}",
+                                    delay
+                                );
+                            }
+                        }
+                    }
                 }
 
-                let secs = (minutes.max(1) as u64) * 60;
-                sleep(Duration::from_secs(secs)).await;
+                wait_for_next_tick(&pool, &runtime, delay).await;
             }
         }));
     }
 }
 
+/// Sleep until the next scheduled tick, an explicit wake-up, or (with `sync_on_write`)
+/// until a burst of local changes has settled for the debounce period.
+async fn wait_for_next_tick(pool: &DbPool, runtime: &SyncRuntime, delay: Duration) {
+    let deadline = tokio::time::Instant::now() + delay;
+    let mut debounce = {
+        let conn = get_read_connection(pool);
+        load_write_debounce(&conn).map(|d| WriteDebounce::new(d, max_unsynced_id(&conn)))
+    };
+
+    loop {
+        let step = match debounce {
+            Some(_) => WRITE_POLL_INTERVAL,
+            None => delay,
+        };
+        let wake_at = deadline.min(tokio::time::Instant::now() + step);
+        tokio::select! {
+            _ = tokio::time::sleep_until(wake_at) => {}
+            _ = runtime.inner.wake.notified() => return,
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return;
+        }
+        if let Some(debounce) = debounce.as_mut() {
+            let current = max_unsynced_id(&get_read_connection(pool));
+            if debounce.observe(current, std::time::Instant::now()) && !runtime.pause_state().paused
+            {
+                log::info!("Local changes settled; syncing now");
+                return;
+            }
+        }
+    }
+}
+
+/// Debounce period when `sync_on_write` is enabled.
+fn load_write_debounce(conn: &Connection) -> Option<Duration> {
+    if !is_config_flag_set(conn, SYNC_ON_WRITE_KEY).unwrap_or(false) {
+        return None;
+    }
+    Some(Duration::from_secs(load_write_debounce_secs(conn)))
+}
+
+fn load_write_debounce_secs(conn: &Connection) -> u64 {
+    get_optional_config_value(conn, SYNC_ON_WRITE_DEBOUNCE_SECS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|v| *v >= 1)
+        .unwrap_or(DEFAULT_SYNC_ON_WRITE_DEBOUNCE_SECS)
+}
+
+fn max_unsynced_id(conn: &Connection) -> Option<i64> {
+    conn.query_row(
+        "SELECT MAX(id) FROM sync_metadata WHERE synced = 0",
+        [],
+        |row| row.get(0),
+    )
+    .ok()
+    .flatten()
+}
+
 impl Default for SyncRuntime {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl ProfileRuntime for SyncRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_scheduler())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(self.refresh_scheduler(pool))
+    }
+}
+
+/// AWS auth fields of a config draft. If omitted, keep the saved value; an empty string clears it.
+#[derive(Debug, Default, Deserialize)]
+pub struct S3AuthReq {
+    /// Named profile from the shared AWS config files; replaces the static keys.
+    pub profile: Option<String>,
+    /// Session token of temporary (STS) credentials, sent with the static keys.
+    pub session_token: Option<String>,
+    /// IAM role assumed through STS on top of the base credentials.
+    pub role_arn: Option<String>,
+    /// External ID required by the role's trust policy.
+    pub external_id: Option<String>,
+}
+
+impl S3AuthReq {
+    /// `saved` with the draft values applied.
+    fn merge_into(&self, mut saved: S3AuthSettings) -> S3AuthSettings {
+        for (target, draft) in [
+            (&mut saved.profile, &self.profile),
+            (&mut saved.session_token, &self.session_token),
+            (&mut saved.role_arn, &self.role_arn),
+            (&mut saved.external_id, &self.external_id),
+        ] {
+            if let Some(value) = draft {
+                *target = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            }
+        }
+        saved
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SyncConfigReq {
     pub enabled: bool,
@@ -309,8 +578,58 @@ pub struct SyncConfigReq {
     pub endpoint: Option<String>,
     pub access_key: Option<String>,
     pub secret_key: Option<String>,
+    #[serde(flatten)]
+    pub auth: S3AuthReq,
     /// Auto sync interval in minutes. If omitted, keep existing value.
     pub auto_sync_interval_minutes: Option<i64>,
+    /// Device-local guard for snapshot restore. If omitted, keep existing value.
+    pub disable_snapshot_restore: Option<bool>,
+    /// Device-local guard for remote cleanup (wipe propagation). If omitted, keep existing value.
+    pub disable_remote_cleanup: Option<bool>,
+    /// Stage downloaded remote deltas for review instead of applying. If omitted, keep existing value.
+    pub review_before_apply: Option<bool>,
+    /// Apply remote deltas but never record local changes. If omitted, keep existing value.
+    pub read_only_replica: Option<bool>,
+    /// Run delta compaction after scheduled syncs (at most daily). If omitted, keep existing value.
+    pub auto_compact: Option<bool>,
+    /// Minimum age (days) of deltas removed by compaction. If omitted, keep existing value.
+    pub compact_older_than_days: Option<i64>,
+    /// Number of own snapshots kept remotely (>= 1). If omitted, keep existing value.
+    pub snapshot_retention_count: Option<i64>,
+    /// Storage class of uploaded snapshots (`""` = STANDARD). If omitted, keep existing value.
+    pub snapshot_storage_class: Option<String>,
+    /// Object tags of uploaded snapshots, `k=v,k2=v2` (`""` = none). If omitted, keep existing value.
+    pub snapshot_tags: Option<String>,
+    /// Number of local DB backups kept in `<data_dir>/backups` (>= 1). If omitted, keep existing value.
+    pub backup_max_count: Option<i64>,
+    /// Tables this device stops syncing (replaces the current set; `[]` syncs all). If omitted, keep existing value.
+    pub ignored_tables: Option<Vec<String>>,
+    /// Keep details of archived projects local-only. If omitted, keep existing value.
+    pub exclude_archived_projects: Option<bool>,
+    /// Imports adding at least this many rows are published as a snapshot (0 = never). If omitted, keep existing value.
+    pub auto_snapshot_import_threshold: Option<i64>,
+    /// Max operations per uploaded delta (>= 1). If omitted, keep existing value.
+    pub delta_chunk_max_ops: Option<i64>,
+    /// Max size (MB, before compression) per uploaded delta (>= 1). If omitted, keep existing value.
+    pub delta_chunk_max_mb: Option<i64>,
+    /// Multipart part size in MB for large uploads (>= 5). If omitted, keep existing value.
+    pub s3_part_size_mb: Option<i64>,
+    /// Retries per multipart part / interrupted download (>= 0). If omitted, keep existing value.
+    pub s3_part_retries: Option<i64>,
+    /// Attempts per S3 request on transient errors, first one included (>= 1). If omitted, keep existing value.
+    pub s3_retry_max_attempts: Option<i64>,
+    /// Base backoff (ms) between S3 retries, doubled per attempt (>= 0). If omitted, keep existing value.
+    pub s3_retry_base_delay_ms: Option<i64>,
+    /// Upload cap in kilobits per second (0 = unlimited). If omitted, keep existing value.
+    pub max_upload_kbps: Option<i64>,
+    /// Download cap in kilobits per second (0 = unlimited). If omitted, keep existing value.
+    pub max_download_kbps: Option<i64>,
+    /// Skip scheduled syncs on metered connections. If omitted, keep existing value.
+    pub pause_on_metered: Option<bool>,
+    /// Sync shortly after local writes instead of only on the interval. If omitted, keep existing value.
+    pub sync_on_write: Option<bool>,
+    /// Quiet period (seconds) after the last local write before syncing (>= 1). If omitted, keep existing value.
+    pub sync_on_write_debounce_secs: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -318,12 +637,45 @@ pub struct SyncEnableReq {
     pub enabled: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct SyncTestConnectionReq {
     pub bucket: Option<String>,
     pub endpoint: Option<String>,
     pub access_key: Option<String>,
     pub secret_key: Option<String>,
+    #[serde(flatten)]
+    pub auth: S3AuthReq,
+    /// Also PUT / GET / DELETE a canary object under `probes/` (default false: list only).
+    pub probe_write: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncTestConnectionResp {
+    /// All performed checks passed.
+    pub ok: bool,
+    /// e.g. "Connection OK" or "Connection OK; missing permissions: write"
+    pub message: String,
+    /// list / write / read / delete; skipped ones when `probe_write` is off.
+    pub checks: Vec<ConfigCheck>,
+}
+
+/// Draft config for `cmd_sync_validate_config`; omitted fields fall back to saved values.
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncValidateConfigReq {
+    pub bucket: Option<String>,
+    pub endpoint: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    #[serde(flatten)]
+    pub auth: S3AuthReq,
+    pub auto_sync_interval_minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncValidateConfigResp {
+    /// No check failed (warnings allowed).
+    pub ok: bool,
+    pub checks: Vec<ConfigCheck>,
 }
 
 #[derive(Debug, Serialize)]
@@ -334,10 +686,77 @@ pub struct SyncConfigResp {
     pub access_key: Option<String>,
     pub has_secret_key: bool,
     pub secret_key_masked: Option<String>,
+    /// Named AWS profile used instead of the static keys.
+    pub profile: Option<String>,
+    /// A session token is stored (it is never returned).
+    pub has_session_token: bool,
+    /// IAM role assumed through STS.
+    pub role_arn: Option<String>,
+    pub external_id: Option<String>,
+    /// Prefix of every sync object in the bucket (`""` = bucket root).
+    pub key_prefix: String,
     pub device_id: String,
     pub last_sync: Option<String>,
     /// Auto sync interval in minutes (>= 1).
     pub auto_sync_interval_minutes: i64,
+    /// When true, `cmd_sync_restore_snapshot` is rejected on this device.
+    pub disable_snapshot_restore: bool,
+    /// When true, remote cleanup operations (e.g. wipe propagation) are rejected on this device.
+    pub disable_remote_cleanup: bool,
+    /// When true, downloaded remote deltas wait in staging until `cmd_sync_apply_staged`.
+    pub review_before_apply: bool,
+    /// When true, this device only applies remote changes; business writes return `READ_ONLY_REPLICA`.
+    pub read_only_replica: bool,
+    /// When true, scheduled syncs also compact old deltas (at most once a day).
+    pub auto_compact: bool,
+    /// Minimum age (days) of deltas removed by compaction (>= 1).
+    pub compact_older_than_days: i64,
+    /// Last successful compaction on this device.
+    pub last_compact_at: Option<String>,
+    /// Number of own snapshots kept remotely (>= 1); older ones are pruned after upload.
+    pub snapshot_retention_count: i64,
+    /// Storage class new snapshots are uploaded with.
+    pub snapshot_storage_class: String,
+    /// Tags set on new snapshots (`k=v,k2=v2`, empty = none).
+    pub snapshot_tags: String,
+    /// Remote store: `s3` or `gdrive`.
+    pub backend: String,
+    /// A Google Drive authorization is saved.
+    pub gdrive_connected: bool,
+    /// Number of local DB backups kept (>= 1), automatic and scheduled alike.
+    pub backup_max_count: i64,
+    /// Tables that can be toggled per device.
+    pub syncable_tables: Vec<String>,
+    /// Tables this device ignores: local edits are not recorded for upload (not even after
+    /// re-enabling) and remote operations on them are dropped at apply time.
+    /// Snapshots leave them out and snapshot restore keeps the local rows.
+    pub ignored_tables: Vec<String>,
+    /// When true, assignments, history, tags, comments and custom values of archived projects
+    /// stay local; the project rows themselves still sync.
+    pub exclude_archived_projects: bool,
+    /// Imports adding at least this many rows upload one snapshot instead of per-row deltas (0 = never).
+    pub auto_snapshot_import_threshold: i64,
+    /// Max operations per uploaded delta; larger local change sets are split.
+    pub delta_chunk_max_ops: usize,
+    /// Max size (MB, before compression) per uploaded delta.
+    pub delta_chunk_max_mb: usize,
+    /// Objects larger than this (MB) are uploaded in parts of this size.
+    pub s3_part_size_mb: usize,
+    /// Retries per multipart part / interrupted download.
+    pub s3_part_retries: u32,
+    /// Attempts per S3 request on transient errors (throttling, 5xx, timeouts).
+    pub s3_retry_max_attempts: u32,
+    /// Base backoff (ms) between S3 retries, doubled per attempt, with jitter.
+    pub s3_retry_base_delay_ms: u64,
+    /// Upload cap in kilobits per second (0 = unlimited).
+    pub max_upload_kbps: u64,
+    /// Download cap in kilobits per second (0 = unlimited).
+    pub max_download_kbps: u64,
+    /// Scheduled syncs are skipped while the connection is reported as metered.
+    pub pause_on_metered: bool,
+    /// Sync once local writes have settled for `sync_on_write_debounce_secs`.
+    pub sync_on_write: bool,
+    pub sync_on_write_debounce_secs: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -346,50 +765,18 @@ pub struct SyncStatusResp {
     pub pending_changes: i64,
     pub last_sync: Option<String>,
     pub last_error: Option<String>,
+    /// Offline mode: set while the S3 endpoint is unreachable; `pending_changes` is the queue.
+    pub offline_queue: OfflineQueueStatus,
+    /// Background syncing paused via `cmd_sync_pause`.
+    pub pause: SyncPauseState,
 }
 
 /// Get current sync configuration
 #[tauri::command]
 pub fn cmd_sync_get_config(pool: State<DbPool>) -> Result<SyncConfigResp, AppError> {
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
-
-    let device_id = get_config_value(&conn, "device_id")?;
-    let enabled = get_config_value(&conn, "sync_enabled")? == "1";
-    let bucket = get_config_value(&conn, "s3_bucket").ok();
-    let endpoint = get_config_value(&conn, "s3_endpoint").ok();
-    let access_key = get_config_value(&conn, "s3_access_key").ok();
-    let secret_key = get_config_value(&conn, "s3_secret_key").ok();
-    let has_secret_key = secret_key
-        .as_deref()
-        .map(|v| !v.trim().is_empty())
-        .unwrap_or(false);
-    let secret_key_masked = secret_key
-        .as_deref()
-        .map(str::trim)
-        .filter(|v| !v.is_empty())
-        .map(mask_credential);
-    let last_sync = get_config_value(&conn, "last_sync").ok();
-    let auto_sync_interval_minutes = get_config_value(&conn, "auto_sync_interval_minutes")
-        .ok()
-        .and_then(|v| v.trim().parse::<i64>().ok())
-        .filter(|v| *v >= 1)
-        .unwrap_or(1);
+    let conn = get_connection(&pool);
 
-    Ok(SyncConfigResp {
-        enabled,
-        bucket,
-        endpoint,
-        access_key,
-        has_secret_key,
-        secret_key_masked,
-        device_id,
-        last_sync,
-        auto_sync_interval_minutes,
-    })
+    load_sync_config_resp(&conn)
 }
 
 /// Update sync configuration
@@ -399,13 +786,33 @@ pub async fn cmd_sync_update_config(
     runtime: State<'_, SyncRuntime>,
     req: SyncConfigReq,
 ) -> Result<String, AppError> {
+    if req.enabled {
+        verify_namespace_before_save(pool.inner(), &req).await?;
+    }
     {
-        let conn = pool
-            .inner()
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(&pool);
 
+        // First: the fields that can be rejected, so nothing is half-saved.
+        let auth = req.auth.merge_into(load_s3_auth(&conn)?);
+        validate_s3_auth(&auth)?;
+        let snapshot_storage_class = req
+            .snapshot_storage_class
+            .as_deref()
+            .map(normalize_storage_class)
+            .transpose()
+            .map_err(AppError::Validation)?;
+        let snapshot_tags = req
+            .snapshot_tags
+            .as_deref()
+            .map(parse_object_tags)
+            .transpose()
+            .map_err(AppError::Validation)?;
+        if let Some(tables) = req.ignored_tables.as_deref() {
+            set_ignored_tables(&conn, tables)?;
+        }
+        if let Some(exclude) = req.exclude_archived_projects {
+            set_exclude_archived_projects(&conn, exclude)?;
+        }
         set_config_value(&conn, "sync_enabled", if req.enabled { "1" } else { "0" })?;
         set_config_value(&conn, "s3_bucket", &req.bucket)?;
 
@@ -437,11 +844,132 @@ pub async fn cmd_sync_update_config(
         {
             set_config_value(&conn, "s3_secret_key", secret_key)?;
         }
+        // Auth settings may be cleared (e.g. switching from a profile back to static keys).
+        for (key, value) in [
+            (S3_PROFILE_KEY, &auth.profile),
+            (S3_SESSION_TOKEN_KEY, &auth.session_token),
+            (S3_ROLE_ARN_KEY, &auth.role_arn),
+            (S3_EXTERNAL_ID_KEY, &auth.external_id),
+        ] {
+            set_config_value(&conn, key, value.as_deref().unwrap_or_default())?;
+        }
 
         if let Some(minutes) = req.auto_sync_interval_minutes {
             let minutes = minutes.max(1);
             set_config_value(&conn, "auto_sync_interval_minutes", &minutes.to_string())?;
         }
+
+        if let Some(disabled) = req.disable_snapshot_restore {
+            set_config_value(
+                &conn,
+                DISABLE_SNAPSHOT_RESTORE_KEY,
+                if disabled { "1" } else { "0" },
+            )?;
+        }
+        if let Some(disabled) = req.disable_remote_cleanup {
+            set_config_value(
+                &conn,
+                DISABLE_REMOTE_CLEANUP_KEY,
+                if disabled { "1" } else { "0" },
+            )?;
+        }
+        if let Some(review) = req.review_before_apply {
+            set_config_value(
+                &conn,
+                REVIEW_BEFORE_APPLY_KEY,
+                if review { "1" } else { "0" },
+            )?;
+        }
+        if let Some(replica) = req.read_only_replica {
+            set_config_value(
+                &conn,
+                READ_ONLY_REPLICA_KEY,
+                if replica { "1" } else { "0" },
+            )?;
+        }
+        if let Some(auto_compact) = req.auto_compact {
+            set_config_value(
+                &conn,
+                AUTO_COMPACT_KEY,
+                if auto_compact { "1" } else { "0" },
+            )?;
+        }
+        if let Some(days) = req.compact_older_than_days {
+            set_config_value(&conn, COMPACT_OLDER_THAN_DAYS_KEY, &days.max(1).to_string())?;
+        }
+        if let Some(count) = req.snapshot_retention_count {
+            set_config_value(
+                &conn,
+                SNAPSHOT_RETENTION_COUNT_KEY,
+                &count.max(1).to_string(),
+            )?;
+        }
+        if let Some(class) = snapshot_storage_class {
+            set_config_value(&conn, SNAPSHOT_STORAGE_CLASS_KEY, &class)?;
+        }
+        if let Some(tags) = snapshot_tags {
+            set_config_value(&conn, SNAPSHOT_TAGS_KEY, &format_object_tags(&tags))?;
+        }
+        if let Some(count) = req.backup_max_count {
+            set_config_value(&conn, BACKUP_MAX_COUNT_KEY, &count.max(1).to_string())?;
+        }
+        if let Some(threshold) = req.auto_snapshot_import_threshold {
+            set_config_value(
+                &conn,
+                AUTO_SNAPSHOT_IMPORT_THRESHOLD_KEY,
+                &threshold.max(0).to_string(),
+            )?;
+        }
+        if let Some(ops) = req.delta_chunk_max_ops {
+            set_config_value(&conn, DELTA_CHUNK_MAX_OPS_KEY, &ops.max(1).to_string())?;
+        }
+        if let Some(mb) = req.delta_chunk_max_mb {
+            set_config_value(&conn, DELTA_CHUNK_MAX_MB_KEY, &mb.max(1).to_string())?;
+        }
+        if let Some(mb) = req.s3_part_size_mb {
+            set_config_value(
+                &conn,
+                S3_PART_SIZE_MB_KEY,
+                &mb.max(MIN_PART_SIZE_MB as i64).to_string(),
+            )?;
+        }
+        if let Some(retries) = req.s3_part_retries {
+            set_config_value(&conn, S3_PART_RETRIES_KEY, &retries.max(0).to_string())?;
+        }
+        if let Some(attempts) = req.s3_retry_max_attempts {
+            set_config_value(
+                &conn,
+                S3_RETRY_MAX_ATTEMPTS_KEY,
+                &attempts.max(1).to_string(),
+            )?;
+        }
+        if let Some(delay_ms) = req.s3_retry_base_delay_ms {
+            set_config_value(
+                &conn,
+                S3_RETRY_BASE_DELAY_MS_KEY,
+                &delay_ms.max(0).to_string(),
+            )?;
+        }
+        if let Some(kbps) = req.max_upload_kbps {
+            set_config_value(&conn, MAX_UPLOAD_KBPS_KEY, &kbps.max(0).to_string())?;
+        }
+        if let Some(kbps) = req.max_download_kbps {
+            set_config_value(&conn, MAX_DOWNLOAD_KBPS_KEY, &kbps.max(0).to_string())?;
+        }
+        if let Some(pause) = req.pause_on_metered {
+            set_config_value(&conn, PAUSE_ON_METERED_KEY, if pause { "1" } else { "0" })?;
+        }
+        if let Some(on_write) = req.sync_on_write {
+            set_config_value(&conn, SYNC_ON_WRITE_KEY, if on_write { "1" } else { "0" })?;
+        }
+        if let Some(secs) = req.sync_on_write_debounce_secs {
+            set_config_value(
+                &conn,
+                SYNC_ON_WRITE_DEBOUNCE_SECS_KEY,
+                &secs.max(1).to_string(),
+            )?;
+        }
+        refresh_from_sync_config(&conn);
     } // Drop DB lock before await (Tauri commands require Send futures).
 
     // Backend timer: restart scheduler to apply new interval / enabled flag.
@@ -450,8 +978,162 @@ pub async fn cmd_sync_update_config(
     Ok("Sync configuration updated".to_string())
 }
 
+/// Refuse to save a config whose bucket is already claimed by another profile for this
+/// device id. S3 being unreachable does not block saving: the sync pipeline checks again.
+async fn verify_namespace_before_save(
+    pool_ref: &DbPool,
+    req: &SyncConfigReq,
+) -> Result<(), AppError> {
+    let (device_id, endpoint, access_key, secret_key, auth, key_prefix) = {
+        let conn = get_connection(pool_ref);
+        let pick = |value: Option<&String>, key: &str| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .or_else(|| get_config_value(&conn, key).ok())
+        };
+        (
+            get_config_value(&conn, "device_id")?,
+            pick(req.endpoint.as_ref(), "s3_endpoint"),
+            pick(req.access_key.as_ref(), "s3_access_key"),
+            pick(req.secret_key.as_ref(), "s3_secret_key"),
+            req.auth.merge_into(load_s3_auth(&conn)?),
+            load_key_prefix(&conn)?,
+        )
+    };
+    let bucket = req.bucket.trim().to_string();
+    if bucket.is_empty() {
+        return Ok(());
+    }
+    let has_credentials = endpoint.is_none()
+        || auth.replaces_static_keys()
+        || (access_key.is_some() && secret_key.is_some());
+    if !has_credentials {
+        return Ok(());
+    }
+
+    let s3_client = S3SyncClient::connect(
+        bucket.clone(),
+        device_id.clone(),
+        endpoint.clone(),
+        access_key,
+        secret_key,
+        &auth,
+    )
+    .await;
+    let s3_client = match s3_client {
+        Ok(client) => client.with_key_prefix(key_prefix),
+        Err(e) => {
+            log::warn!("Namespace claim check skipped: S3 client error: {}", e);
+            return Ok(());
+        }
+    };
+
+    match ensure_namespace_claim(pool_ref, &s3_client, endpoint.as_deref(), &device_id).await {
+        Err(AppError::Validation(msg)) => Err(AppError::Validation(msg)),
+        Err(e) => {
+            log::warn!("Namespace claim check skipped: {}", e);
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Make sure `claims/<device_id>.json` in the client's bucket belongs to this profile,
+/// writing it if absent. Verified namespaces are cached locally.
+async fn ensure_namespace_claim(
+    pool_ref: &DbPool,
+    backend: &dyn SyncBackend,
+    endpoint: Option<&str>,
+    device_id: &str,
+) -> Result<(), AppError> {
+    let (claim, token) = {
+        let conn = get_connection(pool_ref);
+        let claim = local_namespace_claim(&conn, device_id);
+        let token = namespace_token(endpoint, backend.namespace(), &claim);
+        if is_namespace_verified(&conn, &token) {
+            return Ok(());
+        }
+        (claim, token)
+    };
+
+    let key = namespace_claim_key(device_id);
+    let remote = if backend.exists(&key).await.unwrap_or(false) {
+        let data = backend
+            .download(&key)
+            .await
+            .map_err(|e| map_s3_error("download", e))?;
+        serde_json::from_slice::<NamespaceClaim>(&data).ok()
+    } else {
+        None
+    };
+    if check_namespace_claim(remote.as_ref(), &claim)? {
+        let body = serde_json::to_vec(&claim)
+            .map_err(|e| AppError::Sync(format!("Serialize namespace claim failed: {}", e)))?;
+        backend
+            .upload(&key, body)
+            .await
+            .map_err(|e| map_s3_error("upload", e))?;
+        log::info!(
+            "Namespace claimed: device {} for profile '{}'",
+            device_id,
+            claim.profile
+        );
+    }
+
+    let conn = get_connection(pool_ref);
+    mark_namespace_verified(&conn, &token)
+}
+
+/// Read `meta/schema.json` and refuse buckets written by a newer app; record this build's
+/// format when the marker is missing or older.
+async fn ensure_remote_schema(backend: &dyn SyncBackend, device_id: &str) -> Result<(), AppError> {
+    let remote = if backend.exists(SCHEMA_META_KEY).await.unwrap_or(false) {
+        let data = backend
+            .download(SCHEMA_META_KEY)
+            .await
+            .map_err(|e| map_s3_error("download", e))?;
+        match serde_json::from_slice::<SchemaMeta>(&data) {
+            Ok(meta) => Some(meta),
+            Err(e) => {
+                log::warn!("Ignoring unreadable {}: {}", SCHEMA_META_KEY, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if check_remote_schema(remote.as_ref())? {
+        let body = serde_json::to_vec(&local_schema_meta(device_id))
+            .map_err(|e| AppError::Sync(format!("Serialize schema marker failed: {}", e)))?;
+        backend
+            .upload(SCHEMA_META_KEY, body)
+            .await
+            .map_err(|e| map_s3_error("upload", e))?;
+        log::info!(
+            "Recorded sync format {} in {}",
+            SYNC_FORMAT_VERSION,
+            SCHEMA_META_KEY
+        );
+    }
+    Ok(())
+}
+
+/// Assign this profile a new device id, e.g. after `NAMESPACE_CLAIMED`.
+#[tauri::command]
+pub async fn cmd_sync_reset_device_id(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+) -> Result<String, AppError> {
+    let _lock = runtime.inner.sync_lock.lock().await;
+    let conn = get_connection(&pool);
+    let device_id = reset_device_id(&conn)?;
+    log::warn!("Device id reset to {}", device_id);
+    Ok(device_id)
+}
+
 /// Enable/disable sync (independent from config editing).
-/// When enabling, validate required S3 config exists.
+/// When enabling, validate required S3 config (or the Google Drive authorization) exists.
 #[tauri::command]
 pub async fn cmd_sync_set_enabled(
     pool: State<'_, DbPool>,
@@ -459,13 +1141,13 @@ pub async fn cmd_sync_set_enabled(
     req: SyncEnableReq,
 ) -> Result<String, AppError> {
     {
-        let conn = pool
-            .inner()
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(&pool);
 
-        if req.enabled {
+        if req.enabled && load_backend_kind(&conn)? == BackendKind::GoogleDrive {
+            if GoogleDriveCredentials::load(&conn)?.is_none() {
+                return Err(AppError::SyncConfigIncomplete);
+            }
+        } else if req.enabled {
             let bucket_ok = get_config_value(&conn, "s3_bucket")
                 .ok()
                 .map(|v| !v.trim().is_empty())
@@ -479,7 +1161,11 @@ pub async fn cmd_sync_set_enabled(
                 .map(|v| !v.trim().is_empty())
                 .unwrap_or(false);
 
-            if !bucket_ok || !access_ok || !secret_ok {
+            // A profile or role supplies its own credentials.
+            let credentials_ok =
+                (access_ok && secret_ok) || load_s3_auth(&conn)?.replaces_static_keys();
+
+            if !bucket_ok || !credentials_ok {
                 return Err(AppError::SyncConfigIncomplete);
             }
         }
@@ -496,28 +1182,24 @@ pub async fn cmd_sync_set_enabled(
 pub async fn cmd_sync_test_connection(
     pool: State<'_, DbPool>,
     req: Option<SyncTestConnectionReq>,
-) -> Result<String, AppError> {
+) -> Result<SyncTestConnectionResp, AppError> {
     let pool_ref = pool.inner();
-    let req = req.unwrap_or(SyncTestConnectionReq {
-        bucket: None,
-        endpoint: None,
-        access_key: None,
-        secret_key: None,
-    });
+    let req = req.unwrap_or_default();
+    let probe_write = req.probe_write.unwrap_or(false);
 
     // Get config
-    let (saved_bucket, saved_endpoint, saved_access_key, saved_secret_key) = {
-        let conn = pool_ref
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+    let (saved_bucket, saved_endpoint, saved_access_key, saved_secret_key, auth, key_prefix) = {
+        let conn = get_connection(pool_ref);
         (
             get_config_value(&conn, "s3_bucket").ok(),
             get_config_value(&conn, "s3_endpoint").ok(),
             get_config_value(&conn, "s3_access_key").ok(),
             get_config_value(&conn, "s3_secret_key").ok(),
+            req.auth.merge_into(load_s3_auth(&conn)?),
+            load_key_prefix(&conn)?,
         )
     };
+    validate_s3_auth(&auth)?;
 
     // Prefer request draft values (for unsaved form testing), fallback to persisted values.
     let bucket = req
@@ -579,63 +1261,296 @@ pub async fn cmd_sync_test_connection(
         })
         .unwrap_or_default();
 
-    if bucket.is_empty() || access_key.is_empty() || secret_key.is_empty() {
+    let has_static_keys = !access_key.is_empty() && !secret_key.is_empty();
+    if bucket.is_empty() || !(has_static_keys || auth.replaces_static_keys()) {
         return Err(AppError::SyncConfigIncomplete);
     }
-
-    // Reuse device_id only for namespacing; not required for the test itself.
+    // Draft values may not be saved yet; keep them out of logs all the same.
+    register_secrets([
+        bucket.as_str(),
+        endpoint.as_deref().unwrap_or_default(),
+        access_key.as_str(),
+        secret_key.as_str(),
+        auth.session_token.as_deref().unwrap_or_default(),
+        auth.external_id.as_deref().unwrap_or_default(),
+    ]);
+
+    // Reuse device_id for namespacing and the canary key.
     let device_id = {
-        let conn = pool_ref
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(pool_ref);
         get_config_value(&conn, "device_id")?
     };
 
-    let s3_client = if let Some(endpoint_url) = endpoint {
-        S3SyncClient::new_with_endpoint(
-            bucket.clone(),
-            device_id,
-            endpoint_url,
-            access_key,
-            secret_key,
-        )
-        .await
-        .map_err(|e| AppError::Sync(format!("S3 client error: {}", e)))?
+    let (access_key, secret_key) = if has_static_keys {
+        (Some(access_key), Some(secret_key))
     } else {
-        // No custom endpoint: rely on environment credentials.
-        S3SyncClient::new(bucket.clone(), device_id)
-            .await
-            .map_err(|e| AppError::Sync(format!("S3 client error: {}", e)))?
+        (None, None)
     };
+    let s3_client = S3SyncClient::connect(
+        bucket.clone(),
+        device_id.clone(),
+        endpoint,
+        access_key,
+        secret_key,
+        &auth,
+    )
+    .await
+    .map_err(|e| AppError::Sync(format!("S3 client error: {}", e)))?
+    .with_key_prefix(key_prefix);
 
+    // Listing failures keep surfacing as errors (stable codes for the settings page).
     s3_client
         .test_connection()
         .await
         .map_err(|e| map_s3_error("test", e))?;
 
-    Ok("Connection OK".to_string())
+    let mut checks = vec![ConfigCheck::pass("list", "bucket listed")];
+    if probe_write {
+        checks.extend(probe_write_access(&s3_client, &device_id).await);
+    } else {
+        for name in &PROBE_CHECKS[1..] {
+            checks.push(ConfigCheck::skip(name, "not requested"));
+        }
+    }
+    let missing: Vec<&str> = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .map(|c| c.name.as_str())
+        .collect();
+    let message = if missing.is_empty() {
+        "Connection OK".to_string()
+    } else {
+        format!("Connection OK; missing permissions: {}", missing.join(", "))
+    };
+    Ok(SyncTestConnectionResp {
+        ok: missing.is_empty(),
+        message,
+        checks,
+    })
 }
 
-/// Get sync status
+/// Bucket permission probes, in the order they run.
+const PROBE_CHECKS: [&str; 4] = ["list", "write", "read", "delete"];
+
+/// Validate the full sync config without saving it: shape checks first, then (only if none
+/// failed) connectivity and a write/delete probe of a canary object. One result per check.
 #[tauri::command]
-pub fn cmd_sync_get_status(
-    pool: State<DbPool>,
-    runtime: State<SyncRuntime>,
-) -> Result<SyncStatusResp, AppError> {
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+pub async fn cmd_sync_validate_config(
+    pool: State<'_, DbPool>,
+    req: Option<SyncValidateConfigReq>,
+) -> Result<SyncValidateConfigResp, AppError> {
+    sync_validate_config_for_pool(pool.inner(), req.unwrap_or_default()).await
+}
 
-    let pending_changes: i64 = conn
-        .query_row(
-            "SELECT COUNT(*) FROM sync_metadata WHERE synced = 0",
-            [],
-            |row: &rusqlite::Row<'_>| row.get(0),
+pub async fn sync_validate_config_for_pool(
+    pool: &DbPool,
+    req: SyncValidateConfigReq,
+) -> Result<SyncValidateConfigResp, AppError> {
+    let (bucket, endpoint, access_key, secret_key, auth, key_prefix, interval, device_id) = {
+        let conn = get_connection(pool);
+        let saved_interval = get_config_value(&conn, "auto_sync_interval_minutes")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok());
+        (
+            draft_or_saved(req.bucket, get_config_value(&conn, "s3_bucket").ok()),
+            draft_or_saved(req.endpoint, get_config_value(&conn, "s3_endpoint").ok()),
+            draft_or_saved(
+                req.access_key,
+                get_config_value(&conn, "s3_access_key").ok(),
+            ),
+            draft_or_saved(
+                req.secret_key,
+                get_config_value(&conn, "s3_secret_key").ok(),
+            ),
+            req.auth.merge_into(load_s3_auth(&conn)?),
+            load_key_prefix(&conn)?,
+            req.auto_sync_interval_minutes.or(saved_interval),
+            get_config_value(&conn, "device_id")?,
         )
-        .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
+    };
+    register_secrets([
+        bucket.as_deref().unwrap_or_default(),
+        endpoint.as_deref().unwrap_or_default(),
+        access_key.as_deref().unwrap_or_default(),
+        secret_key.as_deref().unwrap_or_default(),
+        auth.session_token.as_deref().unwrap_or_default(),
+        auth.external_id.as_deref().unwrap_or_default(),
+    ]);
+
+    let mut checks = check_config_shape(&ConfigShape {
+        bucket: bucket.as_deref().unwrap_or_default(),
+        endpoint: endpoint.as_deref(),
+        access_key: access_key.as_deref().unwrap_or_default(),
+        secret_key: secret_key.as_deref().unwrap_or_default(),
+        profile: auth.profile.as_deref(),
+        session_token: auth.session_token.as_deref(),
+        role_arn: auth.role_arn.as_deref(),
+        external_id: auth.external_id.as_deref(),
+        auto_sync_interval_minutes: interval,
+        require_https: cfg!(target_os = "android"),
+    });
+
+    if has_failures(&checks) {
+        for name in PROBE_CHECKS {
+            checks.push(ConfigCheck::skip(name, "fix the failed checks first"));
+        }
+    } else {
+        let client = S3SyncClient::connect(
+            bucket.unwrap_or_default(),
+            device_id.clone(),
+            endpoint,
+            access_key,
+            secret_key,
+            &auth,
+        )
+        .await;
+        match client {
+            Ok(client) => {
+                let client = client.with_key_prefix(key_prefix);
+                checks.extend(probe_bucket(&client, &device_id).await)
+            }
+            Err(e) => {
+                checks.push(ConfigCheck::fail("list", format!("S3 client error: {}", e)));
+                for name in &PROBE_CHECKS[1..] {
+                    checks.push(ConfigCheck::skip(name, "connection failed"));
+                }
+            }
+        }
+    }
+
+    Ok(SyncValidateConfigResp {
+        ok: !has_failures(&checks),
+        checks,
+    })
+}
+
+/// List, then write / read back / delete a canary object. A failed step skips the ones that
+/// depend on it.
+async fn probe_bucket(client: &S3SyncClient, device_id: &str) -> Vec<ConfigCheck> {
+    if let Err(e) = client.test_connection().await {
+        let mut checks = vec![ConfigCheck::fail(
+            "list",
+            map_s3_error("test", e).to_string(),
+        )];
+        for name in &PROBE_CHECKS[1..] {
+            checks.push(ConfigCheck::skip(name, "listing failed"));
+        }
+        return checks;
+    }
+    let mut checks = vec![ConfigCheck::pass("list", "bucket listed")];
+    checks.extend(probe_write_access(client, device_id).await);
+    checks
+}
+
+/// PUT a canary under `probes/`, GET it back, DELETE it; one check per permission.
+async fn probe_write_access(client: &S3SyncClient, device_id: &str) -> Vec<ConfigCheck> {
+    const CANARY: &[u8] = b"projex permission probe";
+    let mut checks = Vec::new();
+    let key = probe_object_key(device_id);
+    if let Err(e) = client.upload(&key, CANARY.to_vec()).await {
+        checks.push(ConfigCheck::fail(
+            "write",
+            map_s3_error("upload", e).to_string(),
+        ));
+        checks.push(ConfigCheck::skip("read", "nothing was written"));
+        checks.push(ConfigCheck::skip("delete", "nothing was written"));
+        return checks;
+    }
+    checks.push(ConfigCheck::pass("write", format!("wrote {}", key)));
+
+    checks.push(match client.download(&key).await {
+        Ok(data) if data == CANARY => ConfigCheck::pass("read", "canary read back"),
+        Ok(_) => ConfigCheck::fail("read", "canary read back with different content"),
+        Err(e) => ConfigCheck::fail("read", map_s3_error("download", e).to_string()),
+    });
+
+    checks.push(match client.delete(&key).await {
+        Ok(()) => ConfigCheck::pass("delete", "canary removed"),
+        Err(e) => ConfigCheck::fail(
+            "delete",
+            format!(
+                "{} (canary {} left in the bucket)",
+                map_s3_error("delete", e),
+                key
+            ),
+        ),
+    });
+    checks
+}
+
+/// Request draft value if non-blank, else the saved value if non-blank.
+fn draft_or_saved(draft: Option<String>, saved: Option<String>) -> Option<String> {
+    [draft, saved]
+        .into_iter()
+        .flatten()
+        .map(|v| v.trim().to_string())
+        .find(|v| !v.is_empty())
+}
+
+/// Get sync status
+/// Frontend reports whether the current connection is metered (cellular / tethered /
+/// data saver), so the scheduler can honor `pause_on_metered`.
+#[tauri::command]
+pub fn cmd_sync_set_network_metered(runtime: State<SyncRuntime>, metered: bool) {
+    if runtime.is_network_metered() != metered {
+        log::info!("Network metered: {}", metered);
+    }
+    runtime.set_network_metered(metered);
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncPauseReq {
+    /// Pause length in minutes (>= 1); omit to pause until `cmd_sync_resume`.
+    pub duration_minutes: Option<i64>,
+}
+
+/// Pause scheduled and write-triggered syncs. Manual `cmd_sync_full` still runs.
+#[tauri::command]
+pub fn cmd_sync_pause(
+    runtime: State<SyncRuntime>,
+    req: SyncPauseReq,
+) -> Result<SyncPauseState, AppError> {
+    let duration = match req.duration_minutes {
+        Some(minutes) if minutes < 1 => {
+            return Err(AppError::Validation(
+                "duration_minutes must be at least 1".to_string(),
+            ))
+        }
+        Some(minutes) => Some(Duration::from_secs(minutes as u64 * 60)),
+        None => None,
+    };
+    Ok(runtime.pause(duration))
+}
+
+#[tauri::command]
+pub fn cmd_sync_resume(runtime: State<SyncRuntime>) -> SyncPauseState {
+    runtime.resume()
+}
+
+/// Frontend saw the network come back (`online` event): retry queued changes now if the
+/// scheduler is in offline mode.
+#[tauri::command]
+pub fn cmd_sync_network_online(runtime: State<SyncRuntime>) {
+    if runtime.offline_status().offline {
+        log::info!("Network back online; waking sync scheduler");
+        runtime.wake_scheduler();
+    }
+}
+
+#[tauri::command]
+pub fn cmd_sync_get_status(
+    pool: State<DbPool>,
+    runtime: State<SyncRuntime>,
+) -> Result<SyncStatusResp, AppError> {
+    let conn = get_connection(&pool);
+
+    let pending_changes: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sync_metadata WHERE synced = 0",
+            [],
+            |row: &rusqlite::Row<'_>| row.get(0),
+        )
+        .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
 
     let last_sync = get_config_value(&conn, "last_sync").ok();
     let last_error = get_config_value(&conn, "last_sync_error").ok();
@@ -645,16 +1560,14 @@ pub fn cmd_sync_get_status(
         pending_changes,
         last_sync,
         last_error,
+        offline_queue: runtime.offline_status(),
+        pause: runtime.pause_state(),
     })
 }
 
 #[tauri::command]
 pub fn cmd_sync_get_pending_wipe(pool: State<DbPool>) -> Result<Option<PendingWipeInfo>, AppError> {
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+    let conn = get_connection(&pool);
     get_pending_wipe_info(&conn)
 }
 
@@ -670,10 +1583,13 @@ pub async fn cmd_sync_confirm_wipe(
     pool: State<'_, DbPool>,
     runtime: State<'_, SyncRuntime>,
     req: SyncConfirmWipeReq,
-) -> Result<String, AppError> {
+) -> Result<SyncRunResult, AppError> {
     let _lock = runtime.inner.sync_lock.lock().await;
     runtime.inner.is_syncing.store(true, Ordering::Relaxed);
-    let res = confirm_pending_wipe_and_sync(pool.inner(), req).await;
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let mut stats = SyncRunStats::default();
+    let res = confirm_pending_wipe_and_sync(pool.inner(), req, &mut stats).await;
+    record_sync_attempt(pool.inner(), &started_at, &stats, &res);
     runtime.inner.is_syncing.store(false, Ordering::Relaxed);
     res
 }
@@ -689,11 +1605,7 @@ pub fn cmd_sync_reject_wipe(
     pool: State<'_, DbPool>,
     req: SyncRejectWipeReq,
 ) -> Result<String, AppError> {
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+    let conn = get_connection(&pool);
     let pending = get_pending_wipe_info(&conn)?.ok_or_else(|| {
         AppError::Validation("NO_PENDING_WIPE: there is no pending wipe to reject".to_string())
     })?;
@@ -709,7 +1621,9 @@ pub fn cmd_sync_reject_wipe(
 async fn confirm_pending_wipe_and_sync(
     pool_ref: &DbPool,
     req: SyncConfirmWipeReq,
-) -> Result<String, AppError> {
+    stats: &mut SyncRunStats,
+) -> Result<SyncRunResult, AppError> {
+    stats.phase = Some("wipe_confirm");
     let phrase = req.phrase.trim();
     if phrase != "CLEAR" {
         return Err(AppError::Validation(
@@ -718,10 +1632,7 @@ async fn confirm_pending_wipe_and_sync(
     }
 
     let (pending, device_id, bucket, endpoint, access_key, secret_key) = {
-        let conn = pool_ref
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(pool_ref);
         let pending = get_pending_wipe_info(&conn)?.ok_or_else(|| {
             AppError::Validation("NO_PENDING_WIPE: there is no pending wipe to confirm".to_string())
         })?;
@@ -729,34 +1640,30 @@ async fn confirm_pending_wipe_and_sync(
             return Err(AppError::Validation("WIPE_ID_MISMATCH".to_string()));
         }
         let device_id = get_config_value(&conn, "device_id")?;
-        let bucket = get_config_value(&conn, "s3_bucket")?;
+        let bucket = load_bucket(&conn)?;
         let endpoint = get_config_value(&conn, "s3_endpoint").ok();
-        let access_key = get_config_value(&conn, "s3_access_key")?;
-        let secret_key = get_config_value(&conn, "s3_secret_key")?;
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
         (pending, device_id, bucket, endpoint, access_key, secret_key)
     };
+    stats.delta_key = Some(pending.delta_key.clone());
 
     // Download and apply the specific delta that contains wipe intent.
-    let s3_client = if let Some(endpoint_url) = endpoint.clone() {
-        S3SyncClient::new_with_endpoint(
-            bucket.clone(),
-            device_id.clone(),
-            endpoint_url,
-            access_key.clone(),
-            secret_key.clone(),
-        )
-        .await
-        .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?
-    } else {
-        S3SyncClient::new(bucket.clone(), device_id.clone())
-            .await
-            .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?
-    };
+    let backend = connect_backend(
+        pool_ref,
+        bucket.clone(),
+        device_id.clone(),
+        endpoint.clone(),
+        access_key.clone(),
+        secret_key.clone(),
+    )
+    .await?;
 
-    let delta_data = s3_client.download(&pending.delta_key).await.map_err(|e| {
+    let delta_data = backend.download(&pending.delta_key).await.map_err(|e| {
         log::error!("S3 download error for {}: {:?}", pending.delta_key, e);
         map_s3_error("download", e)
     })?;
+    stats.bytes_downloaded += delta_data.len() as i64;
     let delta = Delta::decompress(&delta_data)?;
     if let Some((wipe_id, _created_at)) = extract_wipe_intent(&delta) {
         if wipe_id != pending.wipe_id {
@@ -770,15 +1677,14 @@ async fn confirm_pending_wipe_and_sync(
 
     let delta_engine = DeltaSyncEngine::new(pool_ref, device_id.clone());
     let before_apply_sync_meta_id = delta_engine.current_max_sync_metadata_id()?;
-    delta_engine.apply_delta(&delta)?;
+    let applied = delta_engine.apply_delta(&delta)?;
+    stats.add_applied(&applied);
     let _marked = delta_engine
         .mark_remote_applied_operations_synced(before_apply_sync_meta_id, &delta.operations)?;
+    stats.ops_downloaded += delta.operations.len() as i64;
 
     {
-        let conn = pool_ref
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(pool_ref);
         set_remote_delta_cursor_timestamp(
             &conn,
             &pending.source_device_id,
@@ -786,10 +1692,11 @@ async fn confirm_pending_wipe_and_sync(
         )?;
         clear_pending_wipe(&conn)?;
     }
+    stats.delta_key = None;
 
     // Continue with a normal full sync now that wipe has been applied and cursor advanced.
     sync_full_pipeline(
-        pool_ref, device_id, bucket, endpoint, access_key, secret_key,
+        pool_ref, device_id, bucket, endpoint, access_key, secret_key, stats,
     )
     .await
 }
@@ -799,7 +1706,7 @@ async fn confirm_pending_wipe_and_sync(
 pub async fn cmd_sync_full(
     pool: State<'_, DbPool>,
     runtime: State<'_, SyncRuntime>,
-) -> Result<String, AppError> {
+) -> Result<SyncRunResult, AppError> {
     sync_full_with_runtime_for_pool(pool.inner(), runtime.inner()).await
 }
 
@@ -808,17 +1715,20 @@ pub async fn cmd_sync_full(
 pub async fn sync_full_with_runtime_for_pool(
     pool_ref: &DbPool,
     runtime: &SyncRuntime,
-) -> Result<String, AppError> {
+) -> Result<SyncRunResult, AppError> {
     let _lock = runtime.inner.sync_lock.lock().await;
     runtime.inner.is_syncing.store(true, Ordering::Relaxed);
     let res = sync_full_impl(pool_ref).await;
     runtime.inner.is_syncing.store(false, Ordering::Relaxed);
+    if res.is_ok() {
+        runtime.mark_online();
+    }
     res
 }
 
 /// Execute full sync pipeline for a database pool.
 /// This entry is used by command runtime and integration tests.
-pub async fn sync_full_for_pool(pool_ref: &DbPool) -> Result<String, AppError> {
+pub async fn sync_full_for_pool(pool_ref: &DbPool) -> Result<SyncRunResult, AppError> {
     sync_full_impl(pool_ref).await
 }
 
@@ -831,16 +1741,15 @@ pub async fn sync_hold_lock_for_test(runtime: &SyncRuntime, hold_for: Duration)
     runtime.inner.is_syncing.store(false, Ordering::Relaxed);
 }
 
-async fn sync_full_impl(pool_ref: &DbPool) -> Result<String, AppError> {
-    let res: Result<String, AppError> = (async {
+async fn sync_full_impl(pool_ref: &DbPool) -> Result<SyncRunResult, AppError> {
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let mut stats = SyncRunStats::default();
+    let res: Result<SyncRunResult, AppError> = (async {
         log::info!("Starting full sync...");
 
         // Get config
         let (device_id, bucket, endpoint, access_key, secret_key) = {
-            let conn = pool_ref
-                .0
-                .lock()
-                .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+            let conn = get_connection(pool_ref);
 
             // Block early if a wipe is pending confirmation.
             if let Some(pending) = get_pending_wipe_info(&conn)? {
@@ -848,25 +1757,24 @@ async fn sync_full_impl(pool_ref: &DbPool) -> Result<String, AppError> {
             }
 
             let device_id = get_config_value(&conn, "device_id")?;
-            let bucket = get_config_value(&conn, "s3_bucket")?;
+            let bucket = load_bucket(&conn)?;
             let endpoint = get_config_value(&conn, "s3_endpoint").ok();
-            let access_key = get_config_value(&conn, "s3_access_key")?;
-            let secret_key = get_config_value(&conn, "s3_secret_key")?;
+            let access_key = get_config_value(&conn, "s3_access_key").ok();
+            let secret_key = get_config_value(&conn, "s3_secret_key").ok();
             (device_id, bucket, endpoint, access_key, secret_key)
         };
 
         sync_full_pipeline(
-            pool_ref, device_id, bucket, endpoint, access_key, secret_key,
+            pool_ref, device_id, bucket, endpoint, access_key, secret_key, &mut stats,
         )
         .await
     })
     .await;
 
     if let Err(e) = &res {
-        if let Ok(conn) = pool_ref.0.lock() {
-            let _ = set_config_value(&conn, "last_sync_error", &e.to_string());
-        }
+        let _ = set_config_value(&get_connection(pool_ref), "last_sync_error", &e.to_string());
     }
+    record_sync_attempt(pool_ref, &started_at, &stats, &res);
 
     res
 }
@@ -879,26 +1787,65 @@ async fn sync_full_impl_with_creds(
     bucket: String,
     endpoint: Option<String>,
     creds: SyncCredentials,
-) -> Result<String, AppError> {
+) -> Result<SyncRunResult, AppError> {
+    let started_at = chrono::Utc::now().to_rfc3339();
+    let mut stats = SyncRunStats::default();
     let res = sync_full_pipeline(
         pool_ref,
         device_id,
         bucket,
         endpoint,
-        creds.access_key,
-        creds.secret_key,
+        Some(creds.access_key),
+        Some(creds.secret_key),
+        &mut stats,
     )
     .await;
 
     if let Err(e) = &res {
-        if let Ok(conn) = pool_ref.0.lock() {
-            let _ = set_config_value(&conn, "last_sync_error", &e.to_string());
-        }
+        let _ = set_config_value(&get_connection(pool_ref), "last_sync_error", &e.to_string());
     }
+    record_sync_attempt(pool_ref, &started_at, &stats, &res);
 
     res
 }
 
+/// Best-effort: persist one row into sync_history (and sync_error_log on failure);
+/// failures only get logged.
+fn record_sync_attempt(
+    pool_ref: &DbPool,
+    started_at: &str,
+    stats: &SyncRunStats,
+    res: &Result<SyncRunResult, AppError>,
+) {
+    let error = res.as_ref().err().map(|e| e.to_string());
+    let conn = get_connection(pool_ref);
+    if let Err(e) = record_sync_history(&conn, started_at, stats, error.as_deref()) {
+        log::warn!("Failed to record sync history: {}", e);
+    }
+    if let Err(err) = res {
+        let phase = stats.phase.unwrap_or(DEFAULT_SYNC_PHASE);
+        if let Err(e) = record_sync_error(&conn, phase, err, stats.delta_key.as_deref()) {
+            log::warn!("Failed to record sync error: {}", e);
+        }
+    }
+    if let Ok(result) = res {
+        webhook_notify(
+            &conn,
+            WEBHOOK_EVENT_SYNC_COMPLETED,
+            serde_json::json!({
+                "startedAt": started_at,
+                "direction": &result.direction,
+                "opsUploaded": result.ops_uploaded,
+                "opsDownloaded": result.ops_downloaded,
+                "deltasUploaded": result.deltas_uploaded,
+                "deltasApplied": result.deltas_applied,
+                "deltasStaged": result.deltas_staged,
+                "conflictsDetected": result.conflicts_detected,
+            }),
+        );
+    }
+}
+
 /// Core sync pipeline: upload local delta, bootstrap snapshot, download & apply remote deltas.
 /// Called by both the desktop path (credentials from SQLite) and the Android path (credentials injected).
 async fn sync_full_pipeline(
@@ -906,73 +1853,101 @@ async fn sync_full_pipeline(
     device_id: String,
     bucket: String,
     endpoint: Option<String>,
-    access_key: String,
-    secret_key: String,
-) -> Result<String, AppError> {
-    let res: Result<String, AppError> = (async {
+    access_key: Option<String>,
+    secret_key: Option<String>,
+    stats: &mut SyncRunStats,
+) -> Result<SyncRunResult, AppError> {
+    // Android injects credentials from the Keystore: make sure they are redacted too.
+    register_secrets([
+        bucket.as_str(),
+        endpoint.as_deref().unwrap_or_default(),
+        access_key.as_deref().unwrap_or_default(),
+        secret_key.as_deref().unwrap_or_default(),
+    ]);
+
+    let res: Result<SyncRunResult, AppError> = (async {
         log::info!("Starting full sync...");
+        stats.phase = Some("prepare");
 
         // Block all sync if a remote wipe is pending confirmation on this device.
         {
-            let conn = pool_ref
-                .0
-                .lock()
-                .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+            let conn = get_connection(pool_ref);
             if let Some(pending) = get_pending_wipe_info(&conn)? {
                 return Err(AppError::SyncWipeConfirmRequired(pending));
             }
+            if is_device_revoked(&conn, &device_id)? {
+                return Err(AppError::Sync(
+                    "This device has been revoked on another device".to_string(),
+                ));
+            }
+            // Before collecting local changes, so a refreshed registry row goes out in this run.
+            touch_local_device(&conn, &device_id, chrono::Utc::now())?;
         }
 
         // Create S3 client
-        let s3_client = if let Some(endpoint_url) = endpoint {
-            S3SyncClient::new_with_endpoint(
-                bucket.clone(),
-                device_id.clone(),
-                endpoint_url,
-                access_key,
-                secret_key,
-            )
-            .await
-            .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?
-        } else {
-            S3SyncClient::new(bucket.clone(), device_id.clone())
-                .await
-                .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?
-        };
+        stats.phase = Some("connect");
+        let claim_endpoint = endpoint.clone();
+        let backend = connect_backend(
+            pool_ref,
+            bucket.clone(),
+            device_id.clone(),
+            endpoint,
+            access_key,
+            secret_key,
+        )
+        .await?;
+
+        ensure_namespace_claim(pool_ref, &backend, claim_endpoint.as_deref(), &device_id).await?;
+        ensure_remote_schema(&backend, &device_id).await?;
+
+        // Rows other devices asked for in repair mode go out with this run's upload.
+        if let Err(e) = serve_repair_requests(pool_ref, &backend, &device_id).await {
+            log::warn!("Failed to serve repair requests: {}", e);
+        }
 
         // Step 1: Upload local delta
+        stats.phase = Some("upload");
         let delta_engine = DeltaSyncEngine::new(pool_ref, device_id.clone());
-        let local_collected = delta_engine.collect_local_delta()?;
-        let has_local_delta = !local_collected.delta.operations.is_empty();
+        let chunk_limits = DeltaChunkLimits::load(&get_read_connection(pool_ref));
+        let local_chunks = delta_engine.collect_local_delta_chunks(chunk_limits)?;
+        let has_local_delta = !local_chunks.is_empty();
 
         if has_local_delta {
+            let chunk_count = local_chunks.len();
             log::info!(
-                "Uploading {} local changes",
-                local_collected.delta.operations.len()
+                "Uploading {} local changes in {} delta(s)",
+                local_chunks
+                    .iter()
+                    .map(|c| c.delta.operations.len())
+                    .sum::<usize>(),
+                chunk_count
             );
 
-            let delta_data = local_collected.delta.compress()?;
-            let delta_key = format!(
-                "deltas/{}/delta-{}-{}.gz",
-                device_id,
-                chrono::Utc::now()
-                    .timestamp_nanos_opt()
-                    .unwrap_or_else(|| chrono::Utc::now().timestamp_micros() * 1_000),
-                Uuid::new_v4()
-            );
+            // In order, one object per chunk: a failed upload leaves the earlier chunks
+            // synced and the rest pending for the next run.
+            for (index, local_collected) in local_chunks.into_iter().enumerate() {
+                let delta_data = local_collected.delta.compress()?;
+                let delta_bytes = delta_data.len() as i64;
+                let delta_key = new_delta_key(&device_id);
 
-            s3_client
-                .upload(&delta_key, delta_data)
-                .await
-                .map_err(|e| {
+                backend.upload(&delta_key, delta_data).await.map_err(|e| {
                     // Log full debug info, but return a concise message to the UI.
-                    log::error!("S3 upload error: {:?}", e);
+                    log::error!(
+                        "S3 upload error (delta {}/{}): {:?}",
+                        index + 1,
+                        chunk_count,
+                        e
+                    );
                     map_s3_error("upload", e)
                 })?;
 
-            // Mark as synced (by sync_metadata.id, not by count)
-            if let Some(max_id) = local_collected.max_sync_meta_id {
-                delta_engine.mark_synced(max_id)?;
+                // Mark as synced (by sync_metadata.id, not by count)
+                if let Some(max_id) = local_collected.max_sync_meta_id {
+                    delta_engine.mark_synced(max_id)?;
+                }
+                stats.deltas_uploaded += 1;
+                stats.ops_uploaded += local_collected.delta.operations.len() as i64;
+                stats.bytes_uploaded += delta_bytes;
             }
         } else {
             log::info!("No local delta changes to upload");
@@ -981,30 +1956,25 @@ async fn sync_full_pipeline(
         // Bootstrap: if there are no deltas to upload and remote is empty, upload a snapshot once.
         // This avoids the confusing "sync succeeded but bucket is empty" experience.
         if !has_local_delta {
-            let remote_snapshots = s3_client.list("snapshots/").await.map_err(|e| {
+            stats.phase = Some("bootstrap");
+            let remote_snapshots = backend.list("snapshots/").await.map_err(|e| {
                 log::error!("S3 list snapshots error: {:?}", e);
                 map_s3_error("list", e)
             })?;
 
-            let remote_deltas = s3_client.list("deltas/").await.map_err(|e| {
+            let remote_deltas = backend.list("deltas/").await.map_err(|e| {
                 log::error!("S3 list deltas error: {:?}", e);
                 map_s3_error("list", e)
             })?;
 
             if remote_snapshots.is_empty() && remote_deltas.is_empty() {
                 log::info!("Remote empty, uploading initial snapshot for bootstrap");
-                let snapshot_mgr = SnapshotManager::new(pool_ref, device_id.clone());
-                let snapshot = snapshot_mgr.create_snapshot()?;
+                let snapshot = create_snapshot_blocking(pool_ref, &device_id).await?;
                 let snapshot_data = snapshot.compress()?;
-                let snapshot_key = format!("snapshots/latest-{}.gz", device_id);
-
-                s3_client
-                    .upload(&snapshot_key, snapshot_data)
-                    .await
-                    .map_err(|e| {
-                        log::error!("S3 snapshot upload error: {:?}", e);
-                        map_s3_error("upload", e)
-                    })?;
+                let snapshot_bytes = snapshot_data.len() as i64;
+                let snapshot_key =
+                    upload_snapshot(pool_ref, &backend, &device_id, snapshot_data).await?;
+                stats.bytes_uploaded += snapshot_bytes;
 
                 log::info!(
                     "Bootstrap snapshot uploaded: {} (checksum {})",
@@ -1021,17 +1991,16 @@ async fn sync_full_pipeline(
         }
 
         // Step 2: Download and apply remote deltas
-        let remote_delta_keys = s3_client.list("deltas/").await.map_err(|e| {
+        stats.phase = Some("list");
+        let remote_delta_keys = backend.list("deltas/").await.map_err(|e| {
             log::error!("S3 list error: {:?}", e);
             map_s3_error("list", e)
         })?;
 
         let mut remote_delta_candidates = Vec::new();
-        {
-            let conn = pool_ref
-                .0
-                .lock()
-                .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let stage_remote = {
+            let conn = get_connection(pool_ref);
+            let revoked = load_revoked_devices(&conn)?;
 
             for key in remote_delta_keys {
                 match parse_remote_delta_object(&key) {
@@ -1039,6 +2008,10 @@ async fn sync_full_pipeline(
                         if remote_delta.source_device_id == device_id {
                             continue;
                         }
+                        if revoked.contains(&remote_delta.source_device_id) {
+                            log::info!("Skip delta from revoked device: {}", key);
+                            continue;
+                        }
 
                         let cursor_ts = get_remote_delta_cursor_timestamp(
                             &conn,
@@ -1051,10 +2024,13 @@ async fn sync_full_pipeline(
                     }
                     None => {
                         log::warn!("Skip unsupported delta key format: {}", key);
+                        stats.quarantined_objects.push(key);
                     }
                 }
             }
-        }
+
+            should_stage_remote_deltas(&conn)?
+        };
 
         remote_delta_candidates.sort_by(|a, b| {
             a.source_device_id
@@ -1069,12 +2045,18 @@ async fn sync_full_pipeline(
         );
 
         let mut applied_remote_delta_count = 0usize;
+        let mut staged_remote_delta_count = 0usize;
+        let mut apply_defer_budget = MAX_APPLY_DEFER;
         for remote in remote_delta_candidates {
-            let delta_data = s3_client.download(&remote.key).await.map_err(|e| {
+            stats.phase = Some("download");
+            stats.delta_key = Some(remote.key.clone());
+            let delta_data = backend.download(&remote.key).await.map_err(|e| {
                 log::error!("S3 download error for {}: {:?}", remote.key, e);
                 map_s3_error("download", e)
             })?;
 
+            stats.bytes_downloaded += delta_data.len() as i64;
+            stats.phase = Some("verify");
             let delta = Delta::decompress(&delta_data)?;
             let calculated_checksum = Delta::calculate_checksum(&delta.operations);
             if calculated_checksum != delta.checksum {
@@ -1094,27 +2076,75 @@ async fn sync_full_pipeline(
                     created_at,
                 };
                 {
-                    let conn = pool_ref
-                        .0
-                        .lock()
-                        .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+                    let conn = get_connection(pool_ref);
                     set_pending_wipe_info(&conn, &pending)?;
                 }
                 return Err(AppError::SyncWipeConfirmRequired(pending));
             }
 
+            // Bulk import published as a snapshot: insert-only merge, so it bypasses review.
+            if let Some(import) = extract_snapshot_import(&delta.operations) {
+                stats.phase = Some("merge_import");
+                let merged = merge_remote_import_snapshot(
+                    pool_ref,
+                    &backend,
+                    &remote.source_device_id,
+                    &import,
+                )
+                .await?;
+                match merged {
+                    Some(merged) => stats.ops_downloaded += merged.imported_rows() as i64,
+                    None => log::warn!(
+                        "Import snapshot {} not found; restore a snapshot to pick up the import",
+                        import.snapshot_key
+                    ),
+                }
+            }
+
+            // Review mode: park the verified delta and advance the cursor; apply happens on approval.
+            if stage_remote {
+                stats.phase = Some("stage");
+                let conn = get_connection(pool_ref);
+                stage_remote_delta(
+                    &conn,
+                    &remote.key,
+                    &remote.source_device_id,
+                    remote.timestamp,
+                    &delta,
+                )?;
+                set_remote_delta_cursor_timestamp(
+                    &conn,
+                    &remote.source_device_id,
+                    remote.timestamp,
+                )?;
+                staged_remote_delta_count += 1;
+                stats.deltas_staged += 1;
+                log::info!("Staged remote delta {} for review", remote.key);
+                continue;
+            }
+
+            // Yield the DB mutex to the UI while the user is typing (bounded per sync run).
+            let deferred = wait_for_user_idle(USER_IDLE_WINDOW, apply_defer_budget).await;
+            if !deferred.is_zero() {
+                apply_defer_budget = apply_defer_budget.saturating_sub(deferred);
+                log::info!(
+                    "Deferred applying {} by {:?} while user was active",
+                    remote.key,
+                    deferred
+                );
+            }
+
+            stats.phase = Some("apply");
             let before_apply_sync_meta_id = delta_engine.current_max_sync_metadata_id()?;
-            delta_engine.apply_delta(&delta)?;
+            let applied = delta_engine.apply_delta(&delta)?;
+            stats.add_applied(&applied);
             let marked = delta_engine.mark_remote_applied_operations_synced(
                 before_apply_sync_meta_id,
                 &delta.operations,
             )?;
 
             {
-                let conn = pool_ref
-                    .0
-                    .lock()
-                    .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+                let conn = get_connection(pool_ref);
                 set_remote_delta_cursor_timestamp(
                     &conn,
                     &remote.source_device_id,
@@ -1123,6 +2153,7 @@ async fn sync_full_pipeline(
             }
 
             applied_remote_delta_count += 1;
+            stats.ops_downloaded += delta.operations.len() as i64;
             log::info!(
                 "Applied remote delta {} from {}, marked {} local metadata rows as synced",
                 remote.key,
@@ -1131,14 +2162,18 @@ async fn sync_full_pipeline(
             );
         }
 
+        stats.delta_key = None;
         log::info!("Applied {} remote delta files", applied_remote_delta_count);
 
+        // Let other devices know how far we have read (input for their compaction safety check).
+        if let Err(e) = publish_device_cursors(pool_ref, &backend, &device_id).await {
+            log::warn!("Failed to publish device cursors: {}", e);
+        }
+
         // Step 3: Update last sync time
+        stats.phase = Some("finalize");
         {
-            let conn = pool_ref
-                .0
-                .lock()
-                .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+            let conn = get_connection(pool_ref);
             set_config_value(&conn, "last_sync", &chrono::Utc::now().to_rfc3339())?;
             // Clear error
             conn.execute("DELETE FROM sync_config WHERE key = 'last_sync_error'", [])
@@ -1147,128 +2182,1946 @@ async fn sync_full_pipeline(
 
         log::info!("Sync completed successfully");
 
-        Ok("Sync completed".to_string())
+        if staged_remote_delta_count > 0 {
+            return Ok(stats.to_result(format!(
+                "Sync completed ({} remote deltas staged for review)",
+                staged_remote_delta_count
+            )));
+        }
+        Ok(stats.to_result("Sync completed"))
     })
     .await;
 
     res
 }
 
-/// Create and upload snapshot
+/// Frontend heartbeat while the user is editing; defers the sync apply phase briefly.
 #[tauri::command]
-pub async fn cmd_sync_create_snapshot(pool: State<'_, DbPool>) -> Result<String, AppError> {
-    sync_create_snapshot_for_pool(pool.inner()).await
+pub fn cmd_user_activity() {
+    record_user_activity();
 }
 
-/// Execute snapshot creation/upload pipeline for a database pool.
-/// This entry is used by command runtime and integration tests.
-pub async fn sync_create_snapshot_for_pool(pool_ref: &DbPool) -> Result<String, AppError> {
-    sync_create_snapshot_impl(pool_ref).await
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncPresenceHeartbeatReq {
+    /// Project currently open in the UI, if any.
+    pub project_id: Option<String>,
 }
 
-async fn sync_create_snapshot_impl(pool_ref: &DbPool) -> Result<String, AppError> {
-    log::info!("Creating snapshot...");
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncPresenceListReq {
+    /// Only devices active on this project ("someone else is editing this" hints).
+    pub project_id: Option<String>,
+}
 
-    // Get config
-    let (device_id, bucket, endpoint, access_key, secret_key) = {
-        let conn = pool_ref
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
-        let device_id = get_config_value(&conn, "device_id")?;
-        let bucket = get_config_value(&conn, "s3_bucket")?;
-        let endpoint = get_config_value(&conn, "s3_endpoint").ok();
-        let access_key = get_config_value(&conn, "s3_access_key")?;
-        let secret_key = get_config_value(&conn, "s3_secret_key")?;
-        (device_id, bucket, endpoint, access_key, secret_key)
+/// Presence settings (device-local, off by default).
+#[tauri::command]
+pub fn cmd_sync_presence_get_config(pool: State<DbPool>) -> Result<PresenceConfig, AppError> {
+    let conn = get_connection(&pool);
+    load_presence_config(&conn)
+}
+
+/// Save presence settings; switching presence off removes this device's heartbeat (best-effort).
+#[tauri::command]
+pub async fn cmd_sync_presence_update_config(
+    pool: State<'_, DbPool>,
+    req: PresenceConfig,
+) -> Result<PresenceConfig, AppError> {
+    sync_presence_update_config_for_pool(pool.inner(), req).await
+}
+
+pub async fn sync_presence_update_config_for_pool(
+    pool_ref: &DbPool,
+    req: PresenceConfig,
+) -> Result<PresenceConfig, AppError> {
+    let config = {
+        let conn = get_connection(pool_ref);
+        save_presence_config(&conn, &req)?;
+        load_presence_config(&conn)?
+    };
+    reset_presence_throttle();
+    if !config.enabled {
+        if let Ok(Some(backend)) = configured_sync_backend(pool_ref).await {
+            if let Err(e) = backend.delete(&presence_key(backend.device_id())).await {
+                log::warn!("Failed to remove presence heartbeat: {}", e);
+            }
+        }
+    }
+    Ok(config)
+}
+
+/// Frontend heartbeat carrying the open project. Counts as user activity and, with presence on,
+/// uploads `presence/<device_id>.json` at most once a minute (immediately on a project change).
+/// Upload failures are only logged.
+#[tauri::command]
+pub async fn cmd_sync_presence_heartbeat(
+    pool: State<'_, DbPool>,
+    req: Option<SyncPresenceHeartbeatReq>,
+) -> Result<(), AppError> {
+    record_user_activity();
+    sync_presence_heartbeat_for_pool(pool.inner(), req.unwrap_or_default().project_id).await
+}
+
+pub async fn sync_presence_heartbeat_for_pool(
+    pool_ref: &DbPool,
+    project_id: Option<String>,
+) -> Result<(), AppError> {
+    let project_id = project_id
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    let config = {
+        let conn = get_connection(pool_ref);
+        load_presence_config(&conn)?
+    };
+    let now = chrono::Utc::now();
+    if !config.enabled || !claim_presence_slot(project_id.as_deref(), now.timestamp()) {
+        return Ok(());
+    }
+
+    let backend = match configured_sync_backend(pool_ref).await {
+        Ok(Some(client)) => client,
+        Ok(None) => {
+            reset_presence_throttle();
+            return Ok(());
+        }
+        Err(e) => {
+            reset_presence_throttle();
+            log::warn!("Presence heartbeat skipped: {}", e);
+            return Ok(());
+        }
+    };
+    let beat = local_heartbeat(backend.device_id(), &config, project_id.as_deref(), now);
+    let body = serde_json::to_vec(&beat)
+        .map_err(|e| AppError::Sync(format!("Serialize presence heartbeat failed: {}", e)))?;
+    if let Err(e) = backend
+        .upload(&presence_key(backend.device_id()), body)
+        .await
+    {
+        reset_presence_throttle();
+        log::warn!("Presence heartbeat upload failed: {}", e);
+    }
+    Ok(())
+}
+
+/// Devices (this one included, flagged `is_self`) active within the last few minutes.
+/// Empty while presence is off or sync is not enabled.
+#[tauri::command]
+pub async fn cmd_sync_presence_list(
+    pool: State<'_, DbPool>,
+    req: Option<SyncPresenceListReq>,
+) -> Result<Vec<PresenceEntry>, AppError> {
+    sync_presence_list_for_pool(pool.inner(), req.unwrap_or_default()).await
+}
+
+pub async fn sync_presence_list_for_pool(
+    pool_ref: &DbPool,
+    req: SyncPresenceListReq,
+) -> Result<Vec<PresenceEntry>, AppError> {
+    let enabled = {
+        let conn = get_connection(pool_ref);
+        load_presence_config(&conn)?.enabled
+    };
+    if !enabled {
+        return Ok(Vec::new());
+    }
+    let Some(backend) = configured_sync_backend(pool_ref).await? else {
+        return Ok(Vec::new());
+    };
+
+    let now = chrono::Utc::now();
+    let cutoff = now.timestamp() - PRESENCE_ACTIVE_WINDOW_SECS;
+    let objects = backend
+        .list_with_metadata(PRESENCE_PREFIX)
+        .await
+        .map_err(|e| map_s3_error("list", e))?;
+    let mut beats = Vec::new();
+    for object in objects {
+        // Stale heartbeats are not worth a download.
+        if object.last_modified_unix.is_some_and(|at| at < cutoff) {
+            continue;
+        }
+        match backend.download(&object.key).await {
+            Ok(data) => match serde_json::from_slice::<PresenceHeartbeat>(&data) {
+                Ok(beat) => beats.push((beat, object.last_modified_unix)),
+                Err(e) => log::warn!("Ignoring malformed presence object {}: {}", object.key, e),
+            },
+            Err(e) => log::warn!("Failed to download presence object {}: {}", object.key, e),
+        }
+    }
+
+    let mut entries = recent_presence(beats, backend.device_id(), now);
+    if let Some(project_id) = req.project_id.as_deref().map(str::trim) {
+        entries.retain(|entry| entry.project_id.as_deref() == Some(project_id));
+    }
+    let conn = get_connection(pool_ref);
+    resolve_project_names(&conn, &mut entries)?;
+    Ok(entries)
+}
+
+/// S3 client for the saved config, or `None` when sync is disabled or not configured.
+async fn configured_sync_client(pool_ref: &DbPool) -> Result<Option<S3SyncClient>, AppError> {
+    let (device_id, bucket, endpoint, access_key, secret_key, auth, key_prefix) = {
+        let conn = get_connection(pool_ref);
+        if !is_config_flag_set(&conn, "sync_enabled")?
+            || load_backend_kind(&conn)? != BackendKind::S3
+        {
+            return Ok(None);
+        }
+        let Some(bucket) = get_optional_config_value(&conn, "s3_bucket")?
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+        else {
+            return Ok(None);
+        };
+        (
+            get_config_value(&conn, "device_id")?,
+            bucket,
+            get_config_value(&conn, "s3_endpoint").ok(),
+            get_optional_config_value(&conn, "s3_access_key")?,
+            get_optional_config_value(&conn, "s3_secret_key")?,
+            load_s3_auth(&conn)?,
+            load_key_prefix(&conn)?,
+        )
+    };
+
+    let has_credentials = endpoint.is_none()
+        || auth.replaces_static_keys()
+        || (access_key.is_some() && secret_key.is_some());
+    if !has_credentials {
+        return Ok(None);
+    }
+    let client =
+        S3SyncClient::connect(bucket, device_id, endpoint, access_key, secret_key, &auth).await;
+    let client = client.map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?;
+    configure_s3_client(pool_ref, client.with_key_prefix(key_prefix)).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncSetKeyPrefixReq {
+    /// e.g. `teams/alpha/`; empty = bucket root.
+    pub key_prefix: String,
+    /// Move the existing sync objects to the new prefix (default true).
+    pub migrate: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncSetKeyPrefixResult {
+    /// Normalized prefix now in use.
+    pub key_prefix: String,
+    pub moved_objects: usize,
+}
+
+/// Change the key prefix under which this workspace stores its sync objects.
+#[tauri::command]
+pub async fn cmd_sync_set_key_prefix(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+    req: SyncSetKeyPrefixReq,
+) -> Result<SyncSetKeyPrefixResult, AppError> {
+    let _lock = runtime.inner.sync_lock.lock().await;
+    sync_set_key_prefix_for_pool(pool.inner(), req).await
+}
+
+/// Set the key prefix; with `migrate`, first move the sync folders from the current prefix
+/// (only when sync is enabled and configured). The new prefix is saved only after every
+/// object was moved, so an interrupted move keeps the old prefix and can simply be re-run.
+pub async fn sync_set_key_prefix_for_pool(
+    pool_ref: &DbPool,
+    req: SyncSetKeyPrefixReq,
+) -> Result<SyncSetKeyPrefixResult, AppError> {
+    let key_prefix = normalize_key_prefix(&req.key_prefix).map_err(AppError::Validation)?;
+    let current = {
+        let conn = get_connection(pool_ref);
+        let current = load_key_prefix(&conn)?;
+        if req.migrate.unwrap_or(true) && current != key_prefix {
+            ensure_operation_allowed(&conn, DISABLE_REMOTE_CLEANUP_KEY, "key prefix migration")?;
+        }
+        current
     };
+    if current == key_prefix {
+        return Ok(SyncSetKeyPrefixResult {
+            key_prefix,
+            moved_objects: 0,
+        });
+    }
+
+    let mut moved_objects = 0;
+    if req.migrate.unwrap_or(true) {
+        if let Some(client) = configured_sync_client(pool_ref).await? {
+            let client = client.with_key_prefix(key_prefix.clone());
+            moved_objects = client
+                .move_from_prefix(&current)
+                .await
+                .map_err(|e| map_s3_error("move", e))?;
+        }
+    }
+
+    let conn = get_connection(pool_ref);
+    set_config_value(&conn, S3_KEY_PREFIX_KEY, &key_prefix)?;
+    log::info!(
+        "Sync key prefix changed from '{}' to '{}' ({} object(s) moved)",
+        current,
+        key_prefix,
+        moved_objects
+    );
+    Ok(SyncSetKeyPrefixResult {
+        key_prefix,
+        moved_objects,
+    })
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncInstallLifecycleReq {
+    /// Age (days) after which deltas move to `storage_class` (default 30).
+    pub transition_after_days: Option<i32>,
+    /// Target class for old deltas (default STANDARD_IA).
+    pub storage_class: Option<String>,
+    /// Abort multipart uploads left unfinished for this many days (default 7).
+    pub abort_multipart_after_days: Option<i32>,
+    /// Only return the rule, do not touch the bucket (default false).
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncInstallLifecycleResult {
+    /// The rule was written to the bucket.
+    pub installed: bool,
+    pub rule_id: String,
+    /// Rule in the `put-bucket-lifecycle-configuration` JSON format.
+    pub lifecycle_json: String,
+    /// Equivalent AWS CLI command (for buckets this device cannot configure).
+    pub cli_command: String,
+}
+
+/// Install the lifecycle rule that moves old deltas to a cheaper storage class.
+#[tauri::command]
+pub async fn cmd_sync_install_lifecycle(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+    req: SyncInstallLifecycleReq,
+) -> Result<SyncInstallLifecycleResult, AppError> {
+    let _lock = runtime.inner.sync_lock.lock().await;
+    sync_install_lifecycle_for_pool(pool.inner(), req).await
+}
+
+/// Build the delta lifecycle rule for the saved key prefix and, unless `dry_run` or sync is
+/// not configured, install it on the bucket. The JSON and CLI command are always returned so
+/// the rule can be applied by hand when the credentials lack `s3:PutLifecycleConfiguration`.
+pub async fn sync_install_lifecycle_for_pool(
+    pool_ref: &DbPool,
+    req: SyncInstallLifecycleReq,
+) -> Result<SyncInstallLifecycleResult, AppError> {
+    let (bucket, key_prefix) = {
+        let conn = get_read_connection(pool_ref);
+        (
+            get_optional_config_value(&conn, "s3_bucket")?.unwrap_or_default(),
+            load_key_prefix(&conn)?,
+        )
+    };
+    let rule = DeltaLifecycleRule::new(
+        &key_prefix,
+        req.transition_after_days,
+        req.storage_class.as_deref(),
+        req.abort_multipart_after_days,
+    )
+    .map_err(AppError::Validation)?;
+    let lifecycle_json = serde_json::to_string_pretty(&rule.to_json())
+        .map_err(|e| AppError::Validation(format!("Failed to serialize lifecycle: {}", e)))?;
+    let cli_command = format!(
+        "aws s3api put-bucket-lifecycle-configuration --bucket {} --lifecycle-configuration file://lifecycle.json",
+        if bucket.trim().is_empty() { "<bucket>" } else { bucket.trim() }
+    );
+
+    let mut installed = false;
+    if !req.dry_run.unwrap_or(false) {
+        if let Some(client) = configured_sync_client(pool_ref).await? {
+            client
+                .install_lifecycle_rule(&rule)
+                .await
+                .map_err(|e| map_s3_error("put lifecycle", e))?;
+            installed = true;
+        }
+    }
+    Ok(SyncInstallLifecycleResult {
+        installed,
+        rule_id: rule.id,
+        lifecycle_json,
+        cli_command,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncGdriveBeginAuthReq {
+    /// OAuth client id of a "Desktop app" client in the user's Google Cloud project.
+    pub client_id: String,
+    /// Google issues a secret for desktop clients too; sent along with the PKCE verifier.
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncGdriveBeginAuthResp {
+    /// Open in the system browser, then call `cmd_sync_gdrive_finish_auth`.
+    pub auth_url: String,
+}
+
+/// Start the Google Drive authorization (loopback redirect + PKCE).
+#[tauri::command]
+pub async fn cmd_sync_gdrive_begin_auth(
+    req: SyncGdriveBeginAuthReq,
+) -> Result<SyncGdriveBeginAuthResp, AppError> {
+    let auth_url = begin_authorization(&req.client_id, req.client_secret.as_deref()).await?;
+    Ok(SyncGdriveBeginAuthResp { auth_url })
+}
+
+/// Wait for the browser to return, save the tokens and switch sync to Google Drive.
+#[tauri::command]
+pub async fn cmd_sync_gdrive_finish_auth(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+) -> Result<SyncConfigResp, AppError> {
+    let credentials = finish_authorization(AUTHORIZATION_TIMEOUT).await?;
+    let resp = {
+        let _lock = runtime.inner.sync_lock.lock().await;
+        sync_gdrive_connect_for_pool(pool.inner(), &credentials)?
+    };
+    runtime.refresh_scheduler(pool.inner().clone()).await;
+    Ok(resp)
+}
+
+/// Save a Google Drive authorization and select the Drive backend. S3 settings are kept, so
+/// switching back only needs `cmd_sync_gdrive_disconnect`.
+pub fn sync_gdrive_connect_for_pool(
+    pool_ref: &DbPool,
+    credentials: &GoogleDriveCredentials,
+) -> Result<SyncConfigResp, AppError> {
+    let conn = get_connection(pool_ref);
+    credentials.save(&conn)?;
+    set_config_value(&conn, SYNC_BACKEND_KEY, BackendKind::GoogleDrive.as_str())?;
+    refresh_from_sync_config(&conn);
+    log::info!("Sync backend switched to Google Drive");
+    load_sync_config_resp(&conn)
+}
+
+/// Revoke and forget the Google Drive authorization; sync falls back to S3.
+#[tauri::command]
+pub async fn cmd_sync_gdrive_disconnect(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+) -> Result<SyncConfigResp, AppError> {
+    let resp = {
+        let _lock = runtime.inner.sync_lock.lock().await;
+        sync_gdrive_disconnect_for_pool(pool.inner()).await?
+    };
+    runtime.refresh_scheduler(pool.inner().clone()).await;
+    Ok(resp)
+}
+
+/// Revocation at Google is best effort; the local tokens are always removed. When Drive was
+/// the active backend, sync is disabled until S3 is configured and enabled again.
+pub async fn sync_gdrive_disconnect_for_pool(
+    pool_ref: &DbPool,
+) -> Result<SyncConfigResp, AppError> {
+    let credentials = {
+        let conn = get_read_connection(pool_ref);
+        GoogleDriveCredentials::load(&conn)?
+    };
+    if let Some(credentials) = credentials {
+        revoke_token(&credentials.refresh_token).await;
+    }
+    let conn = get_connection(pool_ref);
+    GoogleDriveCredentials::clear(&conn)?;
+    if load_backend_kind(&conn)? == BackendKind::GoogleDrive {
+        set_config_value(&conn, "sync_enabled", "0")?;
+    }
+    set_config_value(&conn, SYNC_BACKEND_KEY, BackendKind::S3.as_str())?;
+    log::info!("Google Drive disconnected, sync backend is S3");
+    load_sync_config_resp(&conn)
+}
+
+/// Paginated sync attempt history (newest first).
+#[tauri::command]
+pub fn cmd_sync_get_history(
+    pool: State<DbPool>,
+    req: Option<SyncHistoryReq>,
+) -> Result<SyncHistoryPage, AppError> {
+    list_sync_history(&pool, req.unwrap_or_default())
+}
+
+/// Paginated sync error log (newest first); `last_sync_error` keeps only the latest.
+#[tauri::command]
+pub fn cmd_sync_errors_list(
+    pool: State<DbPool>,
+    req: Option<SyncErrorListReq>,
+) -> Result<SyncErrorPage, AppError> {
+    list_sync_errors(&pool, req.unwrap_or_default())
+}
+
+/// List remote deltas waiting for review (review-before-apply mode).
+#[tauri::command]
+pub fn cmd_sync_list_staged(pool: State<DbPool>) -> Result<Vec<StagedDeltaDto>, AppError> {
+    list_staged_deltas(&pool)
+}
+
+/// Approve and apply all staged remote deltas in source order.
+#[tauri::command]
+pub async fn cmd_sync_apply_staged(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+) -> Result<StagedApplyResult, AppError> {
+    // Share the sync lock so approval never interleaves with a running pipeline.
+    let _lock = runtime.inner.sync_lock.lock().await;
+    apply_staged_deltas(pool.inner())
+}
+
+/// Reject all staged remote deltas (they will not be downloaded again).
+#[tauri::command]
+pub async fn cmd_sync_discard_staged(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+) -> Result<i64, AppError> {
+    let _lock = runtime.inner.sync_lock.lock().await;
+    discard_staged_deltas(pool.inner())
+}
+
+/// List journaled sync conflicts (unresolved only by default).
+#[tauri::command]
+pub fn cmd_sync_list_conflicts(
+    pool: State<DbPool>,
+    req: Option<SyncConflictListReq>,
+) -> Result<Vec<SyncConflictDto>, AppError> {
+    list_sync_conflicts(&pool, req.unwrap_or_default())
+}
+
+/// Override an automatic conflict resolution (keep local | remote | merged JSON).
+#[tauri::command]
+pub fn cmd_sync_resolve_conflict(
+    pool: State<DbPool>,
+    req: SyncResolveConflictReq,
+) -> Result<SyncConflictDto, AppError> {
+    resolve_sync_conflict(&pool, req)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncCompactReq {
+    /// Only deltas older than this many days are removed; defaults to the configured value.
+    pub older_than_days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncCompactResult {
+    /// Snapshot uploaded before deleting (None when nothing was compacted).
+    pub snapshot_key: Option<String>,
+    pub deleted_deltas: usize,
+    pub failed_deletes: usize,
+    pub retained_deltas: usize,
+    /// Devices whose unpublished or lagging cursors kept deltas alive.
+    pub blocked_by_devices: Vec<String>,
+    pub older_than_days: i64,
+}
+
+/// Merge old deltas into a fresh snapshot and delete the ones every known device has read.
+#[tauri::command]
+pub async fn cmd_sync_compact(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+    req: Option<SyncCompactReq>,
+) -> Result<SyncCompactResult, AppError> {
+    let _lock = runtime.inner.sync_lock.lock().await;
+    sync_compact_for_pool(pool.inner(), req.unwrap_or_default()).await
+}
+
+/// Execute delta compaction for a database pool.
+/// This entry is used by command runtime and integration tests.
+pub async fn sync_compact_for_pool(
+    pool_ref: &DbPool,
+    req: SyncCompactReq,
+) -> Result<SyncCompactResult, AppError> {
+    sync_compact_impl(pool_ref, req).await
+}
+
+/// Scheduled mode: compact after a successful scheduled sync, at most once a day.
+async fn auto_compact_if_due(pool_ref: &DbPool, runtime: &SyncRuntime) {
+    let due = {
+        let conn = get_read_connection(pool_ref);
+        let enabled = is_config_flag_set(&conn, AUTO_COMPACT_KEY).unwrap_or(false);
+        let cleanup_disabled =
+            is_config_flag_set(&conn, DISABLE_REMOTE_CLEANUP_KEY).unwrap_or(true);
+        let last = get_optional_config_value(&conn, LAST_COMPACT_AT_KEY)
+            .ok()
+            .flatten()
+            .and_then(|v| chrono::DateTime::parse_from_rfc3339(v.trim()).ok());
+        let recently = last.is_some_and(|t| {
+            chrono::Utc::now().signed_duration_since(t) < chrono::Duration::hours(24)
+        });
+        enabled && !cleanup_disabled && !recently
+    };
+    if !due {
+        return;
+    }
+
+    let _lock = runtime.inner.sync_lock.lock().await;
+    match sync_compact_impl(pool_ref, SyncCompactReq::default()).await {
+        Ok(result) => log::info!("Scheduled compaction finished: {:?}", result),
+        Err(e) => log::warn!("Scheduled compaction failed: {}", e),
+    }
+}
+
+async fn sync_compact_impl(
+    pool_ref: &DbPool,
+    req: SyncCompactReq,
+) -> Result<SyncCompactResult, AppError> {
+    log::info!("Starting delta compaction...");
+
+    let (device_id, bucket, endpoint, access_key, secret_key, older_than_days) = {
+        let conn = get_connection(pool_ref);
+        ensure_operation_allowed(&conn, DISABLE_REMOTE_CLEANUP_KEY, "delta compaction")?;
+        if let Some(pending) = get_pending_wipe_info(&conn)? {
+            return Err(AppError::SyncWipeConfirmRequired(pending));
+        }
+        // Staged deltas advanced our cursors without being applied: the snapshot would miss them.
+        let staged: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sync_staged_deltas", [], |row| {
+                row.get(0)
+            })
+            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
+        if staged > 0 {
+            return Err(AppError::Validation(format!(
+                "STAGED_DELTAS_PENDING: apply or discard {} staged remote deltas before compacting",
+                staged
+            )));
+        }
+        let older_than_days = match req.older_than_days {
+            Some(days) if days < 1 => {
+                return Err(AppError::Validation(
+                    "older_than_days must be >= 1".to_string(),
+                ))
+            }
+            Some(days) => days,
+            None => load_compact_older_than_days(&conn)?,
+        };
+        let device_id = get_config_value(&conn, "device_id")?;
+        let bucket = load_bucket(&conn)?;
+        let endpoint = get_config_value(&conn, "s3_endpoint").ok();
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
+        (
+            device_id,
+            bucket,
+            endpoint,
+            access_key,
+            secret_key,
+            older_than_days,
+        )
+    };
+
+    // Create S3 client
+    let backend = connect_backend(
+        pool_ref,
+        bucket.clone(),
+        device_id.clone(),
+        endpoint,
+        access_key,
+        secret_key,
+    )
+    .await?;
+
+    // Our own cursors come straight from SQLite; other devices' from their published files.
+    publish_device_cursors(pool_ref, &backend, &device_id).await?;
+    let (local_cursors, revoked) = {
+        let conn = get_connection(pool_ref);
+        (
+            load_local_cursors(&conn, &device_id)?,
+            load_revoked_devices(&conn)?,
+        )
+    };
+    let mut device_cursors = vec![local_cursors];
+    // Invalid files are treated as unpublished: that device then blocks compaction.
+    device_cursors.extend(fetch_published_cursors(&backend, &device_id).await?);
+
+    // Revoked devices neither hold back compaction nor have their deltas compacted (purge them).
+    device_cursors.retain(|c| !revoked.contains(&c.device_id));
+    let mut deltas = list_remote_delta_refs(&backend).await?;
+    deltas.retain(|d| !revoked.contains(&d.source_device_id));
+
+    let cutoff = chrono::Utc::now().timestamp() - older_than_days * 86_400;
+    let plan = plan_compaction(&deltas, &device_cursors, cutoff);
+    log::info!(
+        "Compaction plan: {} deletable, {} retained, blocked by {:?}",
+        plan.deletable.len(),
+        plan.retained,
+        plan.blocked_by
+    );
+
+    let mut result = SyncCompactResult {
+        snapshot_key: None,
+        deleted_deltas: 0,
+        failed_deletes: 0,
+        retained_deltas: plan.retained,
+        blocked_by_devices: plan.blocked_by,
+        older_than_days,
+    };
+    if plan.deletable.is_empty() {
+        return Ok(result);
+    }
+
+    // Snapshot first: it must hold everything the deleted deltas carried.
+    let snapshot = create_snapshot_blocking(pool_ref, &device_id).await?;
+    let snapshot_key =
+        upload_snapshot(pool_ref, &backend, &device_id, snapshot.compress()?).await?;
+    result.snapshot_key = Some(snapshot_key);
+
+    for key in &plan.deletable {
+        match backend.delete(key).await {
+            Ok(()) => result.deleted_deltas += 1,
+            Err(e) => {
+                log::warn!("Failed to delete compacted delta {}: {:?}", key, e);
+                result.failed_deletes += 1;
+            }
+        }
+    }
+
+    {
+        let conn = get_connection(pool_ref);
+        set_config_value(&conn, LAST_COMPACT_AT_KEY, &chrono::Utc::now().to_rfc3339())?;
+    }
+    log::info!(
+        "Compaction removed {} deltas ({} failed)",
+        result.deleted_deltas,
+        result.failed_deletes
+    );
+    Ok(result)
+}
+
+/// Cursors published by other devices under `devices/` (malformed files are skipped).
+async fn fetch_published_cursors(
+    backend: &dyn SyncBackend,
+    local_device_id: &str,
+) -> Result<Vec<DeviceCursors>, AppError> {
+    let cursor_keys = backend.list("devices/").await.map_err(|e| {
+        log::error!("S3 list devices error: {:?}", e);
+        map_s3_error("list", e)
+    })?;
+    let mut published = Vec::new();
+    for key in cursor_keys {
+        if !parse_device_cursors_key(&key).is_some_and(|id| id != local_device_id) {
+            continue;
+        }
+        let data = backend.download(&key).await.map_err(|e| {
+            log::error!("S3 download error for {}: {:?}", key, e);
+            map_s3_error("download", e)
+        })?;
+        match serde_json::from_slice::<DeviceCursors>(&data) {
+            Ok(cursors) => published.push(cursors),
+            Err(e) => log::warn!("Skip invalid device cursors {}: {}", key, e),
+        }
+    }
+    Ok(published)
+}
+
+/// All `deltas/` objects with their source device and key timestamp.
+async fn list_remote_delta_refs(
+    backend: &dyn SyncBackend,
+) -> Result<Vec<RemoteDeltaRef>, AppError> {
+    Ok(backend
+        .list_with_metadata("deltas/")
+        .await
+        .map_err(|e| {
+            log::error!("S3 list deltas error: {:?}", e);
+            map_s3_error("list", e)
+        })?
+        .into_iter()
+        .filter_map(|summary| {
+            let parsed = parse_remote_delta_object(&summary.key)?;
+            Some(RemoteDeltaRef {
+                key: parsed.key,
+                source_device_id: parsed.source_device_id,
+                timestamp: parsed.timestamp,
+                last_modified_unix: summary.last_modified_unix,
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn cmd_sync_list_devices(
+    pool: State<'_, DbPool>,
+) -> Result<Vec<DeviceSyncHealth>, AppError> {
+    sync_list_devices_for_pool(pool.inner()).await
+}
+
+/// Every device seen in the bucket with its last upload, what this device has applied
+/// from it and what is still pending, so a device that stopped syncing stands out.
+pub async fn sync_list_devices_for_pool(
+    pool_ref: &DbPool,
+) -> Result<Vec<DeviceSyncHealth>, AppError> {
+    let Some(backend) = configured_sync_backend(pool_ref).await? else {
+        return Err(AppError::Sync("sync is not configured".to_string()));
+    };
+    let device_id = backend.device_id().to_string();
+    let (local_cursors, local_pending) = {
+        let conn = get_read_connection(pool_ref);
+        let pending: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sync_metadata WHERE synced = 0",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        (load_local_cursors(&conn, &device_id)?, pending)
+    };
+
+    let deltas = list_remote_delta_refs(&backend).await?;
+    let snapshots = describe_snapshots(
+        &backend
+            .list_with_metadata("snapshots/")
+            .await
+            .map_err(|e| map_s3_error("list", e))?,
+    );
+    let published = fetch_published_cursors(&backend, &device_id).await?;
+
+    let mut health = build_device_health(
+        &device_id,
+        local_pending,
+        &deltas,
+        &snapshots,
+        &local_cursors,
+        &published,
+    );
+    let registry = {
+        let conn = get_read_connection(pool_ref);
+        list_devices(&conn, &device_id)?
+    };
+    for device in &mut health {
+        if let Some(info) = registry.iter().find(|d| d.id == device.device_id) {
+            device.name = Some(info.name.clone());
+            device.revoked_at = info.revoked_at.clone();
+        }
+    }
+    Ok(health)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncRevokeDeviceReq {
+    pub device_id: String,
+    /// Also delete the device's deltas, snapshots, cursors and presence from the bucket.
+    pub purge_remote: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncRevokeDeviceResult {
+    pub device: DeviceInfo,
+    pub purged_objects: usize,
+    pub failed_deletes: usize,
+}
+
+/// Device registry as known locally (works without S3 access).
+#[tauri::command]
+pub fn cmd_sync_list_registered_devices(pool: State<DbPool>) -> Result<Vec<DeviceInfo>, AppError> {
+    let conn = get_read_connection(&pool);
+    let device_id = get_config_value(&conn, "device_id")?;
+    list_devices(&conn, &device_id)
+}
+
+/// Rename this device; the new name reaches other devices with the next sync.
+#[tauri::command]
+pub fn cmd_sync_rename_device(pool: State<DbPool>, name: String) -> Result<DeviceInfo, AppError> {
+    sync_rename_device_for_pool(pool.inner(), &name)
+}
+
+pub fn sync_rename_device_for_pool(pool_ref: &DbPool, name: &str) -> Result<DeviceInfo, AppError> {
+    let conn = get_connection(pool_ref);
+    let device_id = get_config_value(&conn, "device_id")?;
+    rename_local_device(&conn, &device_id, name)
+}
+
+/// Revoke another device: its deltas are no longer applied anywhere once the revocation
+/// has synced. Optionally purges its objects from the bucket.
+#[tauri::command]
+pub async fn cmd_sync_revoke_device(
+    pool: State<'_, DbPool>,
+    req: SyncRevokeDeviceReq,
+) -> Result<SyncRevokeDeviceResult, AppError> {
+    sync_revoke_device_for_pool(pool.inner(), req).await
+}
+
+pub async fn sync_revoke_device_for_pool(
+    pool_ref: &DbPool,
+    req: SyncRevokeDeviceReq,
+) -> Result<SyncRevokeDeviceResult, AppError> {
+    let purge = req.purge_remote.unwrap_or(false);
+    validate_device_id(&req.device_id)?;
+    let device = {
+        let conn = get_connection(pool_ref);
+        if purge {
+            ensure_operation_allowed(&conn, DISABLE_REMOTE_CLEANUP_KEY, "remote purge")?;
+        }
+        let local_device_id = get_config_value(&conn, "device_id")?;
+        revoke_device(&conn, &local_device_id, &req.device_id)?
+    };
+    log::warn!("Device {} ({}) revoked", device.id, device.name);
+
+    let mut result = SyncRevokeDeviceResult {
+        device,
+        purged_objects: 0,
+        failed_deletes: 0,
+    };
+    if !purge {
+        return Ok(result);
+    }
+
+    let Some(backend) = configured_sync_backend(pool_ref).await? else {
+        return Err(AppError::Sync("sync is not configured".to_string()));
+    };
+    for prefix in device_object_prefixes(&result.device.id) {
+        let keys = backend
+            .list(&prefix)
+            .await
+            .map_err(|e| map_s3_error("list", e))?;
+        for key in keys {
+            match backend.delete(&key).await {
+                Ok(()) => result.purged_objects += 1,
+                Err(e) => {
+                    log::warn!("Failed to purge {}: {:?}", key, e);
+                    result.failed_deletes += 1;
+                }
+            }
+        }
+    }
+    log::info!(
+        "Purged {} objects of revoked device {} ({} failed)",
+        result.purged_objects,
+        result.device.id,
+        result.failed_deletes
+    );
+    Ok(result)
+}
+
+/// Rebuild the remote state (latest snapshot + all deltas) and compare it with the local tables.
+#[tauri::command]
+pub async fn cmd_sync_verify(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+) -> Result<SyncVerifyReport, AppError> {
+    let _lock = runtime.inner.sync_lock.lock().await;
+    sync_verify_for_pool(pool.inner()).await
+}
+
+pub async fn sync_verify_for_pool(pool_ref: &DbPool) -> Result<SyncVerifyReport, AppError> {
+    let Some(backend) = configured_sync_backend(pool_ref).await? else {
+        return Err(AppError::Sync("sync is not configured".to_string()));
+    };
+    let device_id = backend.device_id().to_string();
+    let (revoked, ignored_tables, pending_local_changes, staged_deltas) = {
+        let conn = get_read_connection(pool_ref);
+        let count = |sql: &str| -> Result<i64, AppError> {
+            conn.query_row(sql, [], |row| row.get(0))
+                .map_err(|e| AppError::Db(e.to_string()))
+        };
+        // Archived-project details are out of scope: their tables may differ as expected.
+        let mut ignored_tables = load_ignored_tables(&conn)?;
+        if load_exclude_archived_projects(&conn)? {
+            for table in PROJECT_DETAIL_TABLES {
+                if !ignored_tables.iter().any(|t| t == table) {
+                    ignored_tables.push(table.to_string());
+                }
+            }
+        }
+        (
+            load_revoked_devices(&conn)?,
+            ignored_tables,
+            count("SELECT COUNT(*) FROM sync_metadata WHERE synced = 0")?,
+            count("SELECT COUNT(*) FROM sync_staged_deltas")?,
+        )
+    };
+    log::info!("Verifying local data against the remote state...");
+
+    let scratch = open_memory_db()?;
+    let snapshots = backend
+        .list_with_metadata("snapshots/")
+        .await
+        .map_err(|e| map_s3_error("list", e))?;
+    let snapshot_key = select_latest_snapshot(&snapshots).map(|s| s.key.clone());
+    if let Some(key) = &snapshot_key {
+        let data = backend.download(key).await.map_err(|e| {
+            log::error!("S3 download error for {}: {:?}", key, e);
+            map_s3_error("download", e)
+        })?;
+        let snapshot = Snapshot::decompress(&data)?;
+        let scratch_device_id = device_id.clone();
+        run_blocking(&scratch, move |pool| {
+            SnapshotManager::new(pool, scratch_device_id).restore_snapshot(&snapshot)
+        })
+        .await?;
+    }
+
+    let mut deltas = list_remote_delta_refs(&backend).await?;
+    deltas.retain(|d| !revoked.contains(&d.source_device_id));
+    deltas.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.key.cmp(&b.key)));
+
+    let engine = DeltaSyncEngine::new(&scratch, device_id.clone());
+    let mut deltas_applied = 0;
+    let mut deltas_failed = Vec::new();
+    for remote in deltas {
+        let data = backend.download(&remote.key).await.map_err(|e| {
+            log::error!("S3 download error for {}: {:?}", remote.key, e);
+            map_s3_error("download", e)
+        })?;
+        let delta = match Delta::decompress(&data) {
+            Ok(delta) if Delta::calculate_checksum(&delta.operations) == delta.checksum => delta,
+            _ => {
+                log::warn!("Verify: skip corrupt delta {}", remote.key);
+                deltas_failed.push(remote.key);
+                continue;
+            }
+        };
+        if let Some(import) = extract_snapshot_import(&delta.operations) {
+            merge_remote_import_snapshot(&scratch, &backend, &remote.source_device_id, &import)
+                .await?;
+        }
+        match engine.apply_delta(&delta) {
+            Ok(_) => deltas_applied += 1,
+            Err(e) => {
+                log::warn!("Verify: failed to apply {}: {}", remote.key, e);
+                deltas_failed.push(remote.key);
+            }
+        }
+    }
+
+    let tables = {
+        let local = get_read_connection(pool_ref);
+        let remote = get_read_connection(&scratch);
+        compare_databases(&local, &remote, &ignored_tables)?
+    };
+    let in_sync = !tables.iter().any(|t| !t.ignored && t.is_diverged());
+    log::info!(
+        "Verify finished: in_sync={}, {} deltas applied, {} failed",
+        in_sync,
+        deltas_applied,
+        deltas_failed.len()
+    );
+    Ok(SyncVerifyReport {
+        checked_at: chrono::Utc::now().to_rfc3339(),
+        snapshot_key,
+        deltas_applied,
+        deltas_failed,
+        pending_local_changes,
+        staged_deltas,
+        in_sync,
+        tables,
+    })
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TableRepairSummary {
+    pub table: String,
+    /// Rows (or deletes) this device wins, queued for upload.
+    pub requeued: usize,
+    /// Rows requested from other devices.
+    pub requested: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncRepairResult {
+    pub manifest_key: String,
+    /// Devices whose row hashes were compared.
+    pub peers: Vec<String>,
+    pub rows_requeued: usize,
+    pub rows_requested: usize,
+    pub tables: Vec<TableRepairSummary>,
+}
+
+/// Repair mode: publish row hashes, compare with other devices' and queue only the differing rows.
+/// Requeued rows go out with the next sync; requested rows arrive after the peer's next sync.
+#[tauri::command]
+pub async fn cmd_sync_repair(
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+) -> Result<SyncRepairResult, AppError> {
+    let _lock = runtime.inner.sync_lock.lock().await;
+    sync_repair_for_pool(pool.inner()).await
+}
+
+pub async fn sync_repair_for_pool(pool_ref: &DbPool) -> Result<SyncRepairResult, AppError> {
+    let Some(backend) = configured_sync_backend(pool_ref).await? else {
+        return Err(AppError::Sync("sync is not configured".to_string()));
+    };
+    let device_id = backend.device_id().to_string();
+    let manifest_device_id = device_id.clone();
+    let (manifest, revoked) = run_blocking(pool_ref, move |pool| {
+        let conn = get_read_connection(pool);
+        let ignored = load_ignored_tables(&conn)?;
+        let tables: Vec<&str> = verify_tables()
+            .into_iter()
+            .filter(|t| !ignored.iter().any(|i| i == t))
+            .collect();
+        Ok((
+            build_manifest(&conn, &manifest_device_id, &tables)?,
+            load_revoked_devices(&conn)?,
+        ))
+    })
+    .await?;
+
+    let manifest_key = row_hashes_key(&device_id);
+    backend
+        .upload(&manifest_key, compress_manifest(&manifest)?)
+        .await
+        .map_err(|e| {
+            log::error!("S3 row hashes upload error: {:?}", e);
+            map_s3_error("upload", e)
+        })?;
+
+    let keys = backend
+        .list("devices/")
+        .await
+        .map_err(|e| map_s3_error("list", e))?;
+    let mut peers = Vec::new();
+    let mut requeue: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut summaries: BTreeMap<String, TableRepairSummary> = BTreeMap::new();
+    for key in keys {
+        let Some(peer_id) = parse_row_hashes_key(&key) else {
+            continue;
+        };
+        if peer_id == device_id || revoked.contains(peer_id) {
+            continue;
+        }
+        let data = backend.download(&key).await.map_err(|e| {
+            log::error!("S3 download error for {}: {:?}", key, e);
+            map_s3_error("download", e)
+        })?;
+        let peer = match decompress_manifest(&data) {
+            Ok(peer) => peer,
+            Err(e) => {
+                log::warn!("Skip invalid row hashes {}: {}", key, e);
+                continue;
+            }
+        };
+        let plan = plan_repair(&manifest, &peer);
+        for (table, ids) in plan.upload {
+            requeue.entry(table).or_default().extend(ids);
+        }
+        if !plan.request.is_empty() {
+            for (table, ids) in &plan.request {
+                summaries
+                    .entry(table.clone())
+                    .or_insert_with(|| TableRepairSummary {
+                        table: table.clone(),
+                        ..Default::default()
+                    })
+                    .requested += ids.len();
+            }
+            let request = RepairRequest {
+                requester_device_id: device_id.clone(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+                tables: plan.request,
+            };
+            let body = serde_json::to_vec(&request).map_err(|e| AppError::Db(e.to_string()))?;
+            backend
+                .upload(&repair_request_key(peer_id, &device_id), body)
+                .await
+                .map_err(|e| {
+                    log::error!("S3 repair request upload error: {:?}", e);
+                    map_s3_error("upload", e)
+                })?;
+        }
+        peers.push(peer_id.to_string());
+    }
+
+    {
+        let conn = get_connection(pool_ref);
+        for (table, ids) in requeue {
+            let ids: Vec<String> = ids.into_iter().collect();
+            let queued = requeue_rows(&conn, &device_id, &table, &ids)?;
+            summaries
+                .entry(table.clone())
+                .or_insert_with(|| TableRepairSummary {
+                    table,
+                    ..Default::default()
+                })
+                .requeued += queued;
+        }
+    }
+
+    let tables: Vec<TableRepairSummary> = summaries.into_values().collect();
+    let result = SyncRepairResult {
+        manifest_key,
+        peers,
+        rows_requeued: tables.iter().map(|t| t.requeued).sum(),
+        rows_requested: tables.iter().map(|t| t.requested).sum(),
+        tables,
+    };
+    log::info!(
+        "Repair prepared: {} rows requeued, {} rows requested from {:?}",
+        result.rows_requeued,
+        result.rows_requested,
+        result.peers
+    );
+    Ok(result)
+}
+
+/// Answer `repair/<device_id>/` requests by queueing the requested rows, then drop the requests.
+async fn serve_repair_requests(
+    pool_ref: &DbPool,
+    backend: &dyn SyncBackend,
+    device_id: &str,
+) -> Result<usize, AppError> {
+    // A replica never queues rows; requests stay until replica mode is turned off.
+    if is_read_only_replica(&get_read_connection(pool_ref))? {
+        return Ok(0);
+    }
+    let keys = backend
+        .list(&repair_request_prefix(device_id))
+        .await
+        .map_err(|e| map_s3_error("list", e))?;
+    let mut queued = 0;
+    for key in keys {
+        let data = backend
+            .download(&key)
+            .await
+            .map_err(|e| map_s3_error("download", e))?;
+        match serde_json::from_slice::<RepairRequest>(&data) {
+            Ok(request) => {
+                let conn = get_connection(pool_ref);
+                if is_device_revoked(&conn, &request.requester_device_id)? {
+                    log::info!("Drop repair request of revoked device: {}", key);
+                } else {
+                    let ignored = load_ignored_tables(&conn)?;
+                    for (table, ids) in &request.tables {
+                        if verify_tables().contains(&table.as_str()) && !ignored.contains(table) {
+                            queued += requeue_rows(&conn, device_id, table, ids)?;
+                        }
+                    }
+                    log::info!(
+                        "Queued rows for repair request of {}",
+                        request.requester_device_id
+                    );
+                }
+            }
+            Err(e) => log::warn!("Drop invalid repair request {}: {}", key, e),
+        }
+        if let Err(e) = backend.delete(&key).await {
+            log::warn!("Failed to delete repair request {}: {:?}", key, e);
+        }
+    }
+    Ok(queued)
+}
+
+/// Upload `devices/<device_id>/cursors.json` when our cursors changed since the last upload.
+async fn publish_device_cursors(
+    pool_ref: &DbPool,
+    backend: &dyn SyncBackend,
+    device_id: &str,
+) -> Result<(), AppError> {
+    let (cursors, last_published) = {
+        let conn = get_connection(pool_ref);
+        (
+            load_local_cursors(&conn, device_id)?,
+            get_optional_config_value(&conn, LAST_PUBLISHED_CURSORS_KEY)?,
+        )
+    };
+    let fingerprint =
+        serde_json::to_string(&cursors.cursors).map_err(|e| AppError::Db(e.to_string()))?;
+    if last_published.as_deref() == Some(fingerprint.as_str()) {
+        return Ok(());
+    }
+
+    let body = serde_json::to_vec(&cursors).map_err(|e| AppError::Db(e.to_string()))?;
+    backend
+        .upload(&device_cursors_key(device_id), body)
+        .await
+        .map_err(|e| {
+            log::error!("S3 cursors upload error: {:?}", e);
+            map_s3_error("upload", e)
+        })?;
+
+    let conn = get_connection(pool_ref);
+    set_config_value(&conn, LAST_PUBLISHED_CURSORS_KEY, &fingerprint)
+}
+
+/// Create and upload snapshot
+#[tauri::command]
+pub async fn cmd_sync_create_snapshot(pool: State<'_, DbPool>) -> Result<String, AppError> {
+    sync_create_snapshot_for_pool(pool.inner()).await
+}
+
+/// Execute snapshot creation/upload pipeline for a database pool.
+/// This entry is used by command runtime and integration tests.
+pub async fn sync_create_snapshot_for_pool(pool_ref: &DbPool) -> Result<String, AppError> {
+    sync_create_snapshot_impl(pool_ref).await
+}
+
+async fn sync_create_snapshot_impl(pool_ref: &DbPool) -> Result<String, AppError> {
+    log::info!("Creating snapshot...");
+
+    // Get config
+    let (device_id, bucket, endpoint, access_key, secret_key) = {
+        let conn = get_connection(pool_ref);
+        let device_id = get_config_value(&conn, "device_id")?;
+        let bucket = load_bucket(&conn)?;
+        let endpoint = get_config_value(&conn, "s3_endpoint").ok();
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
+        (device_id, bucket, endpoint, access_key, secret_key)
+    };
+
+    // Create S3 client
+    let backend = connect_backend(
+        pool_ref,
+        bucket.clone(),
+        device_id.clone(),
+        endpoint,
+        access_key,
+        secret_key,
+    )
+    .await?;
+
+    //Create snapshot
+    let snapshot = create_snapshot_blocking(pool_ref, &device_id).await?;
+
+    // Upload snapshot
+    let snapshot_data = snapshot.compress()?;
+    let snapshot_key = upload_snapshot(pool_ref, &backend, &device_id, snapshot_data).await?;
+
+    log::info!("Snapshot uploaded: {}", snapshot_key);
+
+    Ok(format!("Snapshot created: {}", snapshot.checksum))
+}
+
+/// Upload a redacted bundle of one project to `shares/` and return a presigned GET link.
+/// The object stays in the bucket after the link expires.
+pub async fn project_share_for_pool(
+    pool_ref: &DbPool,
+    req: ProjectShareReq,
+) -> Result<ProjectShareResult, AppError> {
+    let expiry = project_share_expiry(&req)?;
+    let generated_at = chrono::Utc::now();
+    let expires_at = generated_at + expiry;
+    let bundle = project_share_bundle(pool_ref, &req.project_id, generated_at, expires_at)?;
+    let body = serde_json::to_vec_pretty(&bundle)
+        .map_err(|e| AppError::Sync(format!("Serialize share bundle failed: {}", e)))?;
+
+    let (device_id, bucket, endpoint, access_key, secret_key) = {
+        let conn = get_connection(pool_ref);
+        ensure_s3_backend(&conn, "project share links")?;
+        let device_id = get_config_value(&conn, "device_id")?;
+        let bucket = get_config_value(&conn, "s3_bucket")?;
+        let endpoint = get_config_value(&conn, "s3_endpoint").ok();
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
+        (device_id, bucket, endpoint, access_key, secret_key)
+    };
+
+    let s3_client = connect_s3(
+        pool_ref, bucket, device_id, endpoint, access_key, secret_key,
+    )
+    .await
+    .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?;
+    let s3_client = configure_s3_client(pool_ref, s3_client)?;
+
+    let object_key = project_share_object_key();
+    s3_client
+        .upload_with_content_type(&object_key, body, "application/json; charset=utf-8")
+        .await
+        .map_err(|e| map_s3_error("upload", e))?;
+    let url = s3_client
+        .presign_get(
+            &object_key,
+            Duration::from_secs(expiry.num_seconds() as u64),
+        )
+        .await
+        .map_err(|e| map_s3_error("presign", e))?;
+
+    log::info!(
+        "Project shared: {} -> {} (expires {})",
+        req.project_id,
+        object_key,
+        expires_at.to_rfc3339()
+    );
+    Ok(ProjectShareResult {
+        url,
+        object_key,
+        expires_at: expires_at.to_rfc3339(),
+    })
+}
+
+/// Publish a large local import as a snapshot plus a `SNAPSHOT_IMPORT` control delta, then mark
+/// the import's INSERT metadata as synced. Best effort: on failure the rows upload as normal deltas.
+/// `after_id`: max `sync_metadata.id` before the import ran.
+pub async fn publish_import_snapshot_for_pool(
+    pool_ref: &DbPool,
+    after_id: i64,
+    imported_rows: usize,
+) -> Option<String> {
+    match publish_import_snapshot_impl(pool_ref, after_id, imported_rows).await {
+        Ok(key) => key,
+        Err(e) => {
+            log::warn!(
+                "Publishing import snapshot failed, falling back to deltas: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+async fn publish_import_snapshot_impl(
+    pool_ref: &DbPool,
+    after_id: i64,
+    imported_rows: usize,
+) -> Result<Option<String>, AppError> {
+    let (device_id, bucket, endpoint, access_key, secret_key, up_to_id) = {
+        let conn = get_connection(pool_ref);
+        if !should_publish_import_snapshot(&conn, imported_rows) {
+            return Ok(None);
+        }
+        let device_id = get_config_value(&conn, "device_id")?;
+        let bucket = load_bucket(&conn)?;
+        let endpoint = get_config_value(&conn, "s3_endpoint").ok();
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
+        let up_to_id = max_sync_metadata_id(&conn)?;
+        (
+            device_id, bucket, endpoint, access_key, secret_key, up_to_id,
+        )
+    };
+    log::info!(
+        "Large import ({} rows): publishing a snapshot instead of deltas",
+        imported_rows
+    );
+
+    let backend = connect_backend(
+        pool_ref,
+        bucket.clone(),
+        device_id.clone(),
+        endpoint,
+        access_key,
+        secret_key,
+    )
+    .await?;
+
+    let snapshot = create_snapshot_blocking(pool_ref, &device_id).await?;
+    let snapshot_key =
+        upload_snapshot(pool_ref, &backend, &device_id, snapshot.compress()?).await?;
+
+    let delta_engine = DeltaSyncEngine::new(pool_ref, device_id.clone());
+    let control = delta_engine.build_control_delta(vec![snapshot_import_operation(
+        &snapshot_key,
+        &snapshot.checksum,
+        imported_rows,
+    )])?;
+    backend
+        .upload(&new_delta_key(&device_id), control.compress()?)
+        .await
+        .map_err(|e| {
+            log::error!("S3 upload error: {:?}", e);
+            map_s3_error("upload", e)
+        })?;
+
+    let marked = {
+        let conn = get_connection(pool_ref);
+        mark_import_inserts_synced(&conn, after_id, up_to_id)?
+    };
+    log::info!(
+        "Import snapshot published: {} ({} metadata rows covered)",
+        snapshot_key,
+        marked
+    );
+    Ok(Some(snapshot_key))
+}
+
+/// Receiving side of `SNAPSHOT_IMPORT`: download the referenced snapshot (or the source
+/// device's newest one if it was pruned) and merge the rows missing locally.
+async fn merge_remote_import_snapshot(
+    pool_ref: &DbPool,
+    backend: &dyn SyncBackend,
+    source_device_id: &str,
+    import: &SnapshotImportRef,
+) -> Result<Option<ImportResult>, AppError> {
+    let objects = backend
+        .list_with_metadata("snapshots/")
+        .await
+        .map_err(|e| {
+            log::error!("S3 list snapshots error: {:?}", e);
+            map_s3_error("list", e)
+        })?;
+    let key = if objects.iter().any(|o| o.key == import.snapshot_key) {
+        import.snapshot_key.clone()
+    } else {
+        match describe_snapshots(&objects)
+            .into_iter()
+            .find(|s| s.device_id == source_device_id)
+        {
+            Some(newest) => {
+                log::warn!(
+                    "Import snapshot {} was pruned, merging {} instead",
+                    import.snapshot_key,
+                    newest.key
+                );
+                newest.key
+            }
+            None => return Ok(None),
+        }
+    };
+
+    let data = backend.download(&key).await.map_err(|e| {
+        log::error!("S3 download error for {}: {:?}", key, e);
+        map_s3_error("download", e)
+    })?;
+    let snapshot = Snapshot::decompress(&data)?;
+    if key == import.snapshot_key
+        && !import.checksum.is_empty()
+        && snapshot.checksum != import.checksum
+    {
+        return Err(AppError::Sync(format!(
+            "Checksum mismatch for import snapshot {}",
+            key
+        )));
+    }
+    let conn = get_connection(pool_ref);
+    let merged = merge_import_snapshot(&conn, &snapshot)?;
+    log::info!(
+        "Merged import snapshot {}: {} new rows, {} already present",
+        key,
+        merged.imported_rows(),
+        merged.skipped_duplicates
+    );
+    Ok(Some(merged))
+}
+
+/// Upload a snapshot under a fresh timestamped key, then prune this device's old ones.
+/// Build (and verify) a full snapshot on the blocking pool: it exports every table.
+async fn create_snapshot_blocking(
+    pool_ref: &DbPool,
+    device_id: &str,
+) -> Result<Snapshot, AppError> {
+    let device_id = device_id.to_string();
+    run_blocking(pool_ref, move |pool| {
+        SnapshotManager::new(pool, device_id).create_snapshot()
+    })
+    .await
+}
+
+async fn upload_snapshot(
+    pool_ref: &DbPool,
+    backend: &dyn SyncBackend,
+    device_id: &str,
+    snapshot_data: Vec<u8>,
+) -> Result<String, AppError> {
+    let snapshot_key = new_snapshot_key(device_id);
+    let options = {
+        let conn = get_read_connection(pool_ref);
+        load_snapshot_object_options(&conn)?
+    };
+    backend
+        .upload_with_options(&snapshot_key, snapshot_data, &options)
+        .await
+        .map_err(|e| {
+            log::error!("S3 snapshot upload error: {:?}", e);
+            map_s3_error("upload", e)
+        })?;
+    prune_old_snapshots(pool_ref, backend, device_id).await;
+    Ok(snapshot_key)
+}
+
+/// Best-effort retention: keep the newest `snapshot_retention_count` own snapshots.
+/// Skipped when remote cleanup is disabled on this device; failures only get logged.
+async fn prune_old_snapshots(pool_ref: &DbPool, backend: &dyn SyncBackend, device_id: &str) {
+    let keep = {
+        let conn = get_read_connection(pool_ref);
+        if is_config_flag_set(&conn, DISABLE_REMOTE_CLEANUP_KEY).unwrap_or(true) {
+            log::info!("Remote cleanup disabled, keeping old snapshots");
+            return;
+        }
+        load_snapshot_retention_count(&conn).unwrap_or(DEFAULT_SNAPSHOT_RETENTION_COUNT)
+    };
+
+    let objects = match backend.list_with_metadata("snapshots/").await {
+        Ok(objects) => objects,
+        Err(e) => {
+            log::warn!("Failed to list snapshots for pruning: {:?}", e);
+            return;
+        }
+    };
+    let snapshots = describe_snapshots(&objects);
+    for key in snapshots_to_prune(&snapshots, device_id, keep as usize) {
+        match backend.delete(&key).await {
+            Ok(()) => log::info!("Pruned old snapshot: {}", key),
+            Err(e) => log::warn!("Failed to prune snapshot {}: {:?}", key, e),
+        }
+    }
+}
+
+/// List remote snapshots with metadata (newest first).
+#[tauri::command]
+pub async fn cmd_sync_list_snapshots(
+    pool: State<'_, DbPool>,
+) -> Result<Vec<SnapshotInfo>, AppError> {
+    sync_list_snapshots_for_pool(pool.inner()).await
+}
+
+/// Execute remote snapshot listing for a database pool.
+/// This entry is used by command runtime and integration tests.
+pub async fn sync_list_snapshots_for_pool(
+    pool_ref: &DbPool,
+) -> Result<Vec<SnapshotInfo>, AppError> {
+    let (device_id, bucket, endpoint, access_key, secret_key) = {
+        let conn = get_connection(pool_ref);
+        let device_id = get_config_value(&conn, "device_id")?;
+        let bucket = load_bucket(&conn)?;
+        let endpoint = get_config_value(&conn, "s3_endpoint").ok();
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
+        (device_id, bucket, endpoint, access_key, secret_key)
+    };
+
+    let backend = connect_backend(
+        pool_ref, bucket, device_id, endpoint, access_key, secret_key,
+    )
+    .await?;
+
+    let objects = backend
+        .list_with_metadata("snapshots/")
+        .await
+        .map_err(|e| {
+            log::error!("S3 list error: {:?}", e);
+            map_s3_error("list", e)
+        })?;
+    Ok(describe_snapshots(&objects))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncPresignReq {
+    pub key: String,
+    pub method: PresignMethod,
+    /// Link lifetime in seconds (1..=604800), default 3600.
+    pub expires_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncPresignResp {
+    pub url: String,
+    pub method: PresignMethod,
+    pub key: String,
+    pub expires_at: String,
+}
+
+/// Presigned GET/PUT URL for one object in the sync bucket, for use without credentials
+/// (browser downloads, debugging, offloaded uploads).
+#[tauri::command]
+pub async fn cmd_sync_presign_object(
+    pool: State<'_, DbPool>,
+    req: SyncPresignReq,
+) -> Result<SyncPresignResp, AppError> {
+    sync_presign_object_for_pool(pool.inner(), req).await
+}
+
+pub async fn sync_presign_object_for_pool(
+    pool_ref: &DbPool,
+    req: SyncPresignReq,
+) -> Result<SyncPresignResp, AppError> {
+    let key = req.key.trim().to_string();
+    validate_presign_key(&key).map_err(AppError::Validation)?;
+    let expires_in = presign_expiry(req.expires_in_secs).map_err(AppError::Validation)?;
+
+    let (device_id, bucket, endpoint, access_key, secret_key) = {
+        let conn = get_connection(pool_ref);
+        ensure_s3_backend(&conn, "presigned URLs")?;
+        let device_id = get_config_value(&conn, "device_id")?;
+        let bucket = get_config_value(&conn, "s3_bucket")?;
+        let endpoint = get_config_value(&conn, "s3_endpoint").ok();
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
+        (device_id, bucket, endpoint, access_key, secret_key)
+    };
+
+    let s3_client = connect_s3(
+        pool_ref, bucket, device_id, endpoint, access_key, secret_key,
+    )
+    .await
+    .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?;
+
+    let url = s3_client
+        .presign(req.method, &key, expires_in)
+        .await
+        .map_err(|e| map_s3_error("presign", e))?;
+    let expires_at = chrono::Utc::now()
+        + chrono::Duration::from_std(expires_in).unwrap_or_else(|_| chrono::Duration::hours(1));
+    Ok(SyncPresignResp {
+        url,
+        method: req.method,
+        key,
+        expires_at: expires_at.to_rfc3339(),
+    })
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncRestoreSnapshotReq {
+    /// Snapshot to restore (from `cmd_sync_list_snapshots`); `None` restores the newest.
+    pub key: Option<String>,
+}
+
+/// Download and restore a snapshot (the given key, or the newest one)
+#[tauri::command]
+pub async fn cmd_sync_restore_snapshot(
+    pool: State<'_, DbPool>,
+    req: Option<SyncRestoreSnapshotReq>,
+) -> Result<String, AppError> {
+    let req = req.unwrap_or_default();
+    sync_restore_snapshot_for_pool(pool.inner(), req.key.as_deref()).await
+}
+
+/// Execute snapshot restore pipeline for a database pool.
+/// This entry is used by command runtime and integration tests.
+pub async fn sync_restore_snapshot_for_pool(
+    pool_ref: &DbPool,
+    snapshot_key: Option<&str>,
+) -> Result<String, AppError> {
+    sync_restore_snapshot_impl(pool_ref, snapshot_key).await
+}
+
+async fn sync_restore_snapshot_impl(
+    pool_ref: &DbPool,
+    snapshot_key: Option<&str>,
+) -> Result<String, AppError> {
+    log::info!("Restoring from snapshot...");
 
-    // Create S3 client
-    let s3_client = if let Some(endpoint_url) = endpoint {
-        S3SyncClient::new_with_endpoint(
-            bucket.clone(),
-            device_id.clone(),
-            endpoint_url,
-            access_key,
-            secret_key,
-        )
-        .await
-        .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?
-    } else {
-        S3SyncClient::new(bucket.clone(), device_id.clone())
-            .await
-            .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?
-    };
+    {
+        let conn = get_connection(pool_ref);
+        ensure_operation_allowed(&conn, DISABLE_SNAPSHOT_RESTORE_KEY, "snapshot restore")?;
+    }
 
-    //Create snapshot
-    let snapshot_mgr = SnapshotManager::new(pool_ref, device_id.clone());
-    let snapshot = snapshot_mgr.create_snapshot()?;
+    let (device_id, selected_key, snapshot) = fetch_snapshot(pool_ref, snapshot_key).await?;
 
-    // Upload snapshot
-    let snapshot_data = snapshot.compress()?;
-    let snapshot_key = format!("snapshots/latest-{}.gz", device_id);
+    let checksum = snapshot.checksum.clone();
+    run_blocking(pool_ref, move |pool| {
+        // Keep an undo point: a bad restore can be reverted with cmd_backup_restore.
+        backup_create(pool, BACKUP_REASON_RESTORE_SNAPSHOT)?;
+        SnapshotManager::new(pool, device_id).restore_snapshot(&snapshot)
+    })
+    .await?;
 
-    s3_client
-        .upload(&snapshot_key, snapshot_data)
-        .await
-        .map_err(|e| {
-            log::error!("S3 upload error: {:?}", e);
-            map_s3_error("upload", e)
-        })?;
+    log::info!("Snapshot restored successfully");
 
-    log::info!("Snapshot uploaded: {}", snapshot_key);
+    Ok(format!(
+        "Restored from snapshot {}: {}",
+        selected_key, checksum
+    ))
+}
 
-    Ok(format!("Snapshot created: {}", snapshot.checksum))
+#[derive(Debug, Serialize)]
+pub struct SnapshotRestorePreview {
+    pub snapshot_key: String,
+    /// Device that created the snapshot.
+    pub device_id: String,
+    pub created_at: String,
+    pub checksum: String,
+    /// Per-table counts relative to the current local data.
+    pub tables: Vec<TableRestoreDiff>,
 }
 
-/// Download and restore from latest snapshot
+/// Dry run of `cmd_sync_restore_snapshot`: what restoring would add/update/delete per table
 #[tauri::command]
-pub async fn cmd_sync_restore_snapshot(pool: State<'_, DbPool>) -> Result<String, AppError> {
-    sync_restore_snapshot_for_pool(pool.inner()).await
+pub async fn cmd_sync_preview_restore_snapshot(
+    pool: State<'_, DbPool>,
+    req: Option<SyncRestoreSnapshotReq>,
+) -> Result<SnapshotRestorePreview, AppError> {
+    let req = req.unwrap_or_default();
+    sync_preview_restore_snapshot_for_pool(pool.inner(), req.key.as_deref()).await
 }
 
-/// Execute snapshot restore pipeline for a database pool.
+/// Execute the snapshot restore preview for a database pool (no local writes).
 /// This entry is used by command runtime and integration tests.
-pub async fn sync_restore_snapshot_for_pool(pool_ref: &DbPool) -> Result<String, AppError> {
-    sync_restore_snapshot_impl(pool_ref).await
+pub async fn sync_preview_restore_snapshot_for_pool(
+    pool_ref: &DbPool,
+    snapshot_key: Option<&str>,
+) -> Result<SnapshotRestorePreview, AppError> {
+    let (device_id, selected_key, snapshot) = fetch_snapshot(pool_ref, snapshot_key).await?;
+    let (tables, snapshot) = run_blocking(pool_ref, move |pool| {
+        let tables = SnapshotManager::new(pool, device_id).diff_snapshot(&snapshot)?;
+        Ok((tables, snapshot))
+    })
+    .await?;
+    Ok(SnapshotRestorePreview {
+        snapshot_key: selected_key,
+        device_id: snapshot.device_id,
+        created_at: snapshot.created_at,
+        checksum: snapshot.checksum,
+        tables,
+    })
 }
 
-async fn sync_restore_snapshot_impl(pool_ref: &DbPool) -> Result<String, AppError> {
-    log::info!("Restoring from snapshot...");
+/// Event carrying `SyncBootstrapProgress` while `cmd_sync_bootstrap_from_remote` runs.
+pub const SYNC_BOOTSTRAP_PROGRESS_EVENT: &str = "sync-bootstrap-progress";
+
+/// Stages of `cmd_sync_bootstrap_from_remote`, in order.
+const BOOTSTRAP_STAGES: [&str; 4] = [
+    "download_snapshot",
+    "restore_snapshot",
+    "apply_deltas",
+    "done",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncBootstrapProgress {
+    /// `download_snapshot` | `restore_snapshot` | `apply_deltas` | `done`.
+    pub stage: String,
+    /// 1-based position of `stage` out of `total_steps`.
+    pub step: usize,
+    pub total_steps: usize,
+    /// Known once the newest snapshot is selected.
+    pub snapshot_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncBootstrapResult {
+    pub snapshot_key: String,
+    /// Device that created the snapshot.
+    pub source_device_id: String,
+    pub snapshot_created_at: String,
+    /// The sync run that applied the deltas newer than the snapshot.
+    pub sync: SyncRunResult,
+}
+
+/// First run on a new device: fill the empty local workspace from the newest remote snapshot,
+/// then catch up with the deltas written since. Progress is emitted as `sync-bootstrap-progress`.
+#[tauri::command]
+pub async fn cmd_sync_bootstrap_from_remote(
+    app: AppHandle,
+    pool: State<'_, DbPool>,
+    runtime: State<'_, SyncRuntime>,
+) -> Result<SyncBootstrapResult, AppError> {
+    let _lock = runtime.inner.sync_lock.lock().await;
+    runtime.inner.is_syncing.store(true, Ordering::Relaxed);
+    let res = sync_bootstrap_from_remote_for_pool(pool.inner(), |progress| {
+        if let Err(e) = app.emit(SYNC_BOOTSTRAP_PROGRESS_EVENT, progress) {
+            log::warn!("Failed to emit bootstrap progress: {}", e);
+        }
+    })
+    .await;
+    runtime.inner.is_syncing.store(false, Ordering::Relaxed);
+    if res.is_ok() {
+        runtime.mark_online();
+    }
+    res
+}
+
+/// Refuses a workspace that already has data (`LOCAL_NOT_EMPTY`); replacing local data is
+/// `cmd_sync_restore_snapshot`. Restored rows are marked as synced so they are not uploaded
+/// back, and the cursor of the snapshot's device skips the deltas the snapshot already holds.
+pub async fn sync_bootstrap_from_remote_for_pool(
+    pool_ref: &DbPool,
+    mut on_progress: impl FnMut(&SyncBootstrapProgress),
+) -> Result<SyncBootstrapResult, AppError> {
+    let mut report = |stage: &str, snapshot_key: Option<&str>| {
+        on_progress(&SyncBootstrapProgress {
+            stage: stage.to_string(),
+            step: BOOTSTRAP_STAGES
+                .iter()
+                .position(|s| *s == stage)
+                .unwrap_or(0)
+                + 1,
+            total_steps: BOOTSTRAP_STAGES.len(),
+            snapshot_key: snapshot_key.map(ToString::to_string),
+        })
+    };
+    {
+        let conn = get_connection(pool_ref);
+        ensure_operation_allowed(&conn, DISABLE_SNAPSHOT_RESTORE_KEY, "bootstrap from remote")?;
+        ensure_local_workspace_empty(&conn)?;
+    }
+
+    report("download_snapshot", None);
+    let (device_id, snapshot_key, snapshot) = fetch_snapshot(pool_ref, None).await?;
+    let source_device_id = snapshot.device_id.clone();
+    let snapshot_created_at = snapshot.created_at.clone();
+
+    report("restore_snapshot", Some(snapshot_key.as_str()));
+    let local_device_id = device_id.clone();
+    run_blocking(pool_ref, move |pool| {
+        // Re-checked under the blocking task: the UI may have written since the first check.
+        ensure_local_workspace_empty(&get_connection(pool))?;
+        SnapshotManager::new(pool, local_device_id.clone()).restore_snapshot(&snapshot)?;
+        let delta_engine = DeltaSyncEngine::new(pool, local_device_id);
+        delta_engine.mark_synced(delta_engine.current_max_sync_metadata_id()?)
+    })
+    .await?;
+    if source_device_id != device_id {
+        if let Ok(created_at) = chrono::DateTime::parse_from_rfc3339(&snapshot_created_at) {
+            // One second earlier: a delta written in the snapshot's second is applied again
+            // (harmless) rather than skipped.
+            let covered_until = created_at.timestamp() - 1;
+            let conn = get_connection(pool_ref);
+            let cursor = get_remote_delta_cursor_timestamp(&conn, &source_device_id)?.unwrap_or(0);
+            if cursor < covered_until {
+                set_remote_delta_cursor_timestamp(&conn, &source_device_id, covered_until)?;
+            }
+        }
+    }
+    log::info!(
+        "Bootstrapped from snapshot {} of device {}",
+        snapshot_key,
+        source_device_id
+    );
+
+    report("apply_deltas", Some(snapshot_key.as_str()));
+    let sync = sync_full_impl(pool_ref).await?;
+
+    report("done", Some(snapshot_key.as_str()));
+    Ok(SyncBootstrapResult {
+        snapshot_key,
+        source_device_id,
+        snapshot_created_at,
+        sync,
+    })
+}
+
+/// `LOCAL_NOT_EMPTY` when the workspace has projects, persons, partners or custom fields.
+fn ensure_local_workspace_empty(conn: &Connection) -> Result<(), AppError> {
+    let has_data: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM projects)
+             OR EXISTS (SELECT 1 FROM persons)
+             OR EXISTS (SELECT 1 FROM partners)
+             OR EXISTS (SELECT 1 FROM custom_field_defs)",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_data {
+        return Err(AppError::Validation(
+            "LOCAL_NOT_EMPTY: the local workspace already has data; restore a snapshot to replace it"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
 
+/// Download and decompress a snapshot: the given key, or the newest one.
+/// Returns `(local device_id, snapshot key, snapshot)`.
+async fn fetch_snapshot(
+    pool_ref: &DbPool,
+    snapshot_key: Option<&str>,
+) -> Result<(String, String, Snapshot), AppError> {
     // Get config
     let (device_id, bucket, endpoint, access_key, secret_key) = {
-        let conn = pool_ref
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(pool_ref);
         let device_id = get_config_value(&conn, "device_id")?;
-        let bucket = get_config_value(&conn, "s3_bucket")?;
+        let bucket = load_bucket(&conn)?;
         let endpoint = get_config_value(&conn, "s3_endpoint").ok();
-        let access_key = get_config_value(&conn, "s3_access_key")?;
-        let secret_key = get_config_value(&conn, "s3_secret_key")?;
+        let access_key = get_config_value(&conn, "s3_access_key").ok();
+        let secret_key = get_config_value(&conn, "s3_secret_key").ok();
         (device_id, bucket, endpoint, access_key, secret_key)
     };
 
     // Create S3 client
-    let s3_client = if let Some(endpoint_url) = endpoint {
-        S3SyncClient::new_with_endpoint(
-            bucket.clone(),
-            device_id.clone(),
-            endpoint_url,
-            access_key,
-            secret_key,
-        )
-        .await
-        .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?
-    } else {
-        S3SyncClient::new(bucket.clone(), device_id.clone())
-            .await
-            .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?
-    };
+    let backend = connect_backend(
+        pool_ref,
+        bucket.clone(),
+        device_id.clone(),
+        endpoint,
+        access_key,
+        secret_key,
+    )
+    .await?;
 
-    // List snapshots with metadata and choose latest explicitly.
-    let snapshots = s3_client
+    // List snapshots with metadata; use the requested key or choose latest explicitly.
+    let snapshots = backend
         .list_with_metadata("snapshots/")
         .await
         .map_err(|e| {
@@ -1280,40 +4133,33 @@ async fn sync_restore_snapshot_impl(pool_ref: &DbPool) -> Result<String, AppErro
         return Err(AppError::Db("No snapshots found".to_string()));
     }
 
-    let latest = select_latest_snapshot(&snapshots)
-        .ok_or_else(|| AppError::Db("No valid snapshots found".to_string()))?;
-    let latest_key = latest.key.as_str();
+    let selected = match snapshot_key.map(str::trim) {
+        Some(key) => snapshots
+            .iter()
+            .find(|s| s.key == key)
+            .ok_or_else(|| AppError::Validation(format!("SNAPSHOT_NOT_FOUND: {}", key)))?,
+        None => select_latest_snapshot(&snapshots)
+            .ok_or_else(|| AppError::Db("No valid snapshots found".to_string()))?,
+    };
     log::info!(
-        "Downloading latest snapshot: {} (last_modified_unix={:?})",
-        latest_key,
-        latest.last_modified_unix
+        "Downloading snapshot: {} (last_modified_unix={:?})",
+        selected.key,
+        selected.last_modified_unix
     );
 
-    let snapshot_data = s3_client.download(latest_key).await.map_err(|e| {
+    let snapshot_data = backend.download(&selected.key).await.map_err(|e| {
         log::error!("S3 download error: {:?}", e);
         map_s3_error("download", e)
     })?;
-
-    // Decompress and restore
-    use crate::sync::snapshot::Snapshot;
     let snapshot = Snapshot::decompress(&snapshot_data)?;
 
-    let snapshot_mgr = SnapshotManager::new(pool_ref, device_id);
-    snapshot_mgr.restore_snapshot(&snapshot)?;
-
-    log::info!("Snapshot restored successfully");
-
-    Ok(format!("Restored from snapshot: {}", snapshot.checksum))
+    Ok((device_id, selected.key.clone(), snapshot))
 }
 
 /// Reveal the stored secret key (use with caution).
 #[tauri::command]
 pub fn cmd_sync_reveal_secret_key(pool: State<DbPool>) -> Result<String, AppError> {
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+    let conn = get_connection(&pool);
 
     let secret_key = get_config_value(&conn, "s3_secret_key")?;
     let secret_key = secret_key.trim().to_string();
@@ -1364,6 +4210,317 @@ fn get_optional_config_value(conn: &Connection, key: &str) -> Result<Option<Stri
     }
 }
 
+fn load_sync_config_resp(conn: &Connection) -> Result<SyncConfigResp, AppError> {
+    let device_id = get_config_value(conn, "device_id")?;
+    let enabled = get_config_value(conn, "sync_enabled")? == "1";
+    let bucket = get_config_value(conn, "s3_bucket").ok();
+    let endpoint = get_config_value(conn, "s3_endpoint").ok();
+    let access_key = get_config_value(conn, "s3_access_key").ok();
+    let secret_key = get_config_value(conn, "s3_secret_key").ok();
+    let has_secret_key = secret_key
+        .as_deref()
+        .map(|v| !v.trim().is_empty())
+        .unwrap_or(false);
+    let secret_key_masked = secret_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(mask_credential);
+    let auth = load_s3_auth(conn)?;
+    let last_sync = get_config_value(conn, "last_sync").ok();
+    let auto_sync_interval_minutes = get_config_value(conn, "auto_sync_interval_minutes")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|v| *v >= 1)
+        .unwrap_or(1);
+    let disable_snapshot_restore = is_config_flag_set(conn, DISABLE_SNAPSHOT_RESTORE_KEY)?;
+    let disable_remote_cleanup = is_config_flag_set(conn, DISABLE_REMOTE_CLEANUP_KEY)?;
+    let review_before_apply = is_config_flag_set(conn, REVIEW_BEFORE_APPLY_KEY)?;
+    let read_only_replica = is_read_only_replica(conn)?;
+    let auto_compact = is_config_flag_set(conn, AUTO_COMPACT_KEY)?;
+    let compact_older_than_days = load_compact_older_than_days(conn)?;
+    let last_compact_at = get_optional_config_value(conn, LAST_COMPACT_AT_KEY)?;
+    let snapshot_retention_count = load_snapshot_retention_count(conn)?;
+    let snapshot_options = load_snapshot_object_options(conn)?;
+    let backup_max_count = load_backup_max_count(conn);
+    let ignored_tables = load_ignored_tables(conn)?;
+    let exclude_archived_projects = load_exclude_archived_projects(conn)?;
+    let auto_snapshot_import_threshold = load_auto_snapshot_import_threshold(conn);
+    let chunk_limits = DeltaChunkLimits::load(conn);
+    let transfer = load_transfer_config(conn)?;
+    let retry = load_retry_policy(conn)?;
+    let pause_on_metered = is_config_flag_set(conn, PAUSE_ON_METERED_KEY)?;
+    let sync_on_write = is_config_flag_set(conn, SYNC_ON_WRITE_KEY)?;
+
+    Ok(SyncConfigResp {
+        enabled,
+        bucket,
+        endpoint,
+        access_key,
+        has_secret_key,
+        secret_key_masked,
+        profile: auth.profile,
+        has_session_token: auth.session_token.is_some(),
+        role_arn: auth.role_arn,
+        external_id: auth.external_id,
+        key_prefix: load_key_prefix(conn)?,
+        device_id,
+        last_sync,
+        auto_sync_interval_minutes,
+        disable_snapshot_restore,
+        disable_remote_cleanup,
+        review_before_apply,
+        read_only_replica,
+        auto_compact,
+        compact_older_than_days,
+        last_compact_at,
+        snapshot_retention_count,
+        snapshot_storage_class: snapshot_options
+            .storage_class
+            .unwrap_or_else(|| DEFAULT_STORAGE_CLASS.to_string()),
+        snapshot_tags: get_optional_config_value(conn, SNAPSHOT_TAGS_KEY)?.unwrap_or_default(),
+        backend: load_backend_kind(conn)?.as_str().to_string(),
+        gdrive_connected: GoogleDriveCredentials::load(conn)?.is_some(),
+        backup_max_count,
+        syncable_tables: SYNCABLE_TABLES.iter().map(|t| t.to_string()).collect(),
+        ignored_tables,
+        exclude_archived_projects,
+        auto_snapshot_import_threshold,
+        delta_chunk_max_ops: chunk_limits.max_ops,
+        delta_chunk_max_mb: chunk_limits.max_bytes / (1024 * 1024),
+        s3_part_size_mb: transfer.part_size_bytes / (1024 * 1024),
+        s3_part_retries: transfer.part_retries,
+        s3_retry_max_attempts: retry.max_attempts,
+        s3_retry_base_delay_ms: retry.base_delay.as_millis() as u64,
+        max_upload_kbps: load_kbps(conn, MAX_UPLOAD_KBPS_KEY)?,
+        max_download_kbps: load_kbps(conn, MAX_DOWNLOAD_KBPS_KEY)?,
+        pause_on_metered,
+        sync_on_write,
+        sync_on_write_debounce_secs: load_write_debounce_secs(conn),
+    })
+}
+
+fn load_compact_older_than_days(conn: &Connection) -> Result<i64, AppError> {
+    Ok(
+        get_optional_config_value(conn, COMPACT_OLDER_THAN_DAYS_KEY)?
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| *v >= 1)
+            .unwrap_or(DEFAULT_COMPACT_OLDER_THAN_DAYS),
+    )
+}
+
+/// Apply this device's transfer and retry settings to a freshly built client.
+fn configure_s3_client(pool_ref: &DbPool, client: S3SyncClient) -> Result<S3SyncClient, AppError> {
+    let conn = get_read_connection(pool_ref);
+    Ok(client
+        .with_transfer_config(load_transfer_config(&conn)?)
+        .with_retry_policy(load_retry_policy(&conn)?)
+        .with_bandwidth_limits(
+            kbps_to_bytes_per_sec(load_kbps(&conn, MAX_UPLOAD_KBPS_KEY)?),
+            kbps_to_bytes_per_sec(load_kbps(&conn, MAX_DOWNLOAD_KBPS_KEY)?),
+        ))
+}
+
+/// Saved AWS auth settings (profile / session token / role); empty values count as unset.
+fn load_s3_auth(conn: &Connection) -> Result<S3AuthSettings, AppError> {
+    let load = |key: &str| -> Result<Option<String>, AppError> {
+        Ok(get_optional_config_value(conn, key)?
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty()))
+    };
+    Ok(S3AuthSettings {
+        profile: load(S3_PROFILE_KEY)?,
+        session_token: load(S3_SESSION_TOKEN_KEY)?,
+        role_arn: load(S3_ROLE_ARN_KEY)?,
+        external_id: load(S3_EXTERNAL_ID_KEY)?,
+    })
+}
+
+/// Reject auth settings that cannot work: a malformed role ARN, or an external ID without a role.
+fn validate_s3_auth(auth: &S3AuthSettings) -> Result<(), AppError> {
+    if let Some(role_arn) = auth.role_arn.as_deref() {
+        validate_role_arn(role_arn).map_err(AppError::Validation)?;
+    } else if auth.external_id.is_some() {
+        return Err(AppError::Validation(
+            "EXTERNAL_ID_WITHOUT_ROLE: external ID is only used with a role ARN".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Saved key prefix, normalized (`""` = bucket root).
+fn load_key_prefix(conn: &Connection) -> Result<String, AppError> {
+    let raw = get_optional_config_value(conn, S3_KEY_PREFIX_KEY)?.unwrap_or_default();
+    normalize_key_prefix(&raw).map_err(AppError::Validation)
+}
+
+/// Build the S3 client with the saved auth settings (profile / session token / role),
+/// scoped to the saved key prefix.
+async fn connect_s3(
+    pool_ref: &DbPool,
+    bucket: String,
+    device_id: String,
+    endpoint: Option<String>,
+    access_key: Option<String>,
+    secret_key: Option<String>,
+) -> Result<S3SyncClient, Box<dyn StdError>> {
+    let (auth, key_prefix) = {
+        let conn = get_read_connection(pool_ref);
+        (load_s3_auth(&conn)?, load_key_prefix(&conn)?)
+    };
+    let client =
+        S3SyncClient::connect(bucket, device_id, endpoint, access_key, secret_key, &auth).await?;
+    Ok(client.with_key_prefix(key_prefix))
+}
+
+fn load_backend_kind(conn: &Connection) -> Result<BackendKind, AppError> {
+    Ok(BackendKind::parse(
+        get_optional_config_value(conn, SYNC_BACKEND_KEY)?.as_deref(),
+    ))
+}
+
+/// Saved bucket; empty for Google Drive, which needs none.
+fn load_bucket(conn: &Connection) -> Result<String, AppError> {
+    match load_backend_kind(conn)? {
+        BackendKind::GoogleDrive => Ok(String::new()),
+        BackendKind::S3 => get_config_value(conn, "s3_bucket"),
+    }
+}
+
+/// Reject S3-only operations (presigned URLs, key prefixes, lifecycle rules) on other backends.
+fn ensure_s3_backend(conn: &Connection, operation: &str) -> Result<(), AppError> {
+    match load_backend_kind(conn)? {
+        BackendKind::S3 => Ok(()),
+        other => Err(AppError::Validation(format!(
+            "S3_ONLY: {} is not available with the {} backend",
+            operation,
+            other.as_str()
+        ))),
+    }
+}
+
+/// Client for the selected backend: Google Drive with the saved authorization, or S3 with
+/// the given settings and this device's transfer settings.
+async fn connect_backend(
+    pool_ref: &DbPool,
+    bucket: String,
+    device_id: String,
+    endpoint: Option<String>,
+    access_key: Option<String>,
+    secret_key: Option<String>,
+) -> Result<Box<dyn SyncBackend>, AppError> {
+    let (kind, retry) = {
+        let conn = get_read_connection(pool_ref);
+        (load_backend_kind(&conn)?, load_retry_policy(&conn)?)
+    };
+    if kind == BackendKind::GoogleDrive {
+        let backend = GoogleDriveBackend::from_pool(pool_ref, device_id)?.with_retry_policy(retry);
+        return Ok(Box::new(backend));
+    }
+    let client = connect_s3(
+        pool_ref, bucket, device_id, endpoint, access_key, secret_key,
+    )
+    .await
+    .map_err(|e| AppError::Db(format!("S3 client error: {}", e)))?;
+    Ok(Box::new(configure_s3_client(pool_ref, client)?))
+}
+
+/// Backend for the saved config, or `None` when sync is disabled or not configured.
+async fn configured_sync_backend(
+    pool_ref: &DbPool,
+) -> Result<Option<Box<dyn SyncBackend>>, AppError> {
+    let (kind, device_id, retry) = {
+        let conn = get_connection(pool_ref);
+        if !is_config_flag_set(&conn, "sync_enabled")? {
+            return Ok(None);
+        }
+        (
+            load_backend_kind(&conn)?,
+            get_config_value(&conn, "device_id")?,
+            load_retry_policy(&conn)?,
+        )
+    };
+    match kind {
+        BackendKind::GoogleDrive => match GoogleDriveBackend::from_pool(pool_ref, device_id) {
+            Ok(backend) => Ok(Some(Box::new(backend.with_retry_policy(retry)))),
+            Err(AppError::SyncConfigIncomplete) => Ok(None),
+            Err(e) => Err(e),
+        },
+        BackendKind::S3 => Ok(configured_sync_client(pool_ref)
+            .await?
+            .map(|client| Box::new(client) as Box<dyn SyncBackend>)),
+    }
+}
+
+fn load_kbps(conn: &Connection, key: &str) -> Result<u64, AppError> {
+    Ok(get_optional_config_value(conn, key)?
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(0))
+}
+
+fn load_retry_policy(conn: &Connection) -> Result<RetryPolicy, AppError> {
+    let max_attempts = get_optional_config_value(conn, S3_RETRY_MAX_ATTEMPTS_KEY)?
+        .and_then(|v| v.trim().parse::<u32>().ok());
+    let base_delay_ms = get_optional_config_value(conn, S3_RETRY_BASE_DELAY_MS_KEY)?
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    Ok(RetryPolicy::from_settings(max_attempts, base_delay_ms))
+}
+
+fn load_transfer_config(conn: &Connection) -> Result<TransferConfig, AppError> {
+    let part_size_mb = get_optional_config_value(conn, S3_PART_SIZE_MB_KEY)?
+        .and_then(|v| v.trim().parse::<usize>().ok());
+    let part_retries = get_optional_config_value(conn, S3_PART_RETRIES_KEY)?
+        .and_then(|v| v.trim().parse::<u32>().ok());
+    Ok(TransferConfig::from_settings(part_size_mb, part_retries))
+}
+
+/// Storage class and tags for snapshot uploads; STANDARD without tags by default.
+fn load_snapshot_object_options(conn: &Connection) -> Result<ObjectOptions, AppError> {
+    let storage_class = get_optional_config_value(conn, SNAPSHOT_STORAGE_CLASS_KEY)?
+        .map(|v| normalize_storage_class(&v))
+        .transpose()
+        .map_err(AppError::Validation)?
+        .filter(|class| class != DEFAULT_STORAGE_CLASS);
+    let tags =
+        parse_object_tags(&get_optional_config_value(conn, SNAPSHOT_TAGS_KEY)?.unwrap_or_default())
+            .map_err(AppError::Validation)?;
+    Ok(ObjectOptions {
+        storage_class,
+        tagging: Some(tagging_header(&tags)).filter(|t| !t.is_empty()),
+    })
+}
+
+fn load_snapshot_retention_count(conn: &Connection) -> Result<i64, AppError> {
+    Ok(
+        get_optional_config_value(conn, SNAPSHOT_RETENTION_COUNT_KEY)?
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| *v >= 1)
+            .unwrap_or(DEFAULT_SNAPSHOT_RETENTION_COUNT),
+    )
+}
+
+fn is_config_flag_set(conn: &Connection, key: &str) -> Result<bool, AppError> {
+    Ok(get_optional_config_value(conn, key)?
+        .as_deref()
+        .map(str::trim)
+        == Some("1"))
+}
+
+/// Reject a destructive operation when the matching device-local guard flag is set.
+///
+/// 这些开关只存在于本机 sync_config，不随 export_config 导出：
+/// 典型场景是手机端永远不允许 restore / wipe，即使 UI 被绕过也要在命令层拦截。
+pub(crate) fn ensure_operation_allowed(
+    conn: &Connection,
+    flag_key: &str,
+    operation: &str,
+) -> Result<(), AppError> {
+    if is_config_flag_set(conn, flag_key)? {
+        return Err(AppError::SyncOperationDisabled(operation.to_string()));
+    }
+    Ok(())
+}
+
 fn get_pending_wipe_info(conn: &Connection) -> Result<Option<PendingWipeInfo>, AppError> {
     let raw = get_optional_config_value(conn, PENDING_WIPE_KEY)?;
     let Some(raw) = raw else {
@@ -1413,6 +4570,18 @@ fn extract_wipe_intent(delta: &Delta) -> Option<(String, String)> {
     None
 }
 
+/// `deltas/<device_id>/delta-<unix_nanos>-<uuid>.gz`
+fn new_delta_key(device_id: &str) -> String {
+    format!(
+        "deltas/{}/delta-{}-{}.gz",
+        device_id,
+        chrono::Utc::now()
+            .timestamp_nanos_opt()
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_micros() * 1_000),
+        Uuid::new_v4()
+    )
+}
+
 fn parse_remote_delta_object(key: &str) -> Option<RemoteDeltaObject> {
     let rest = key.strip_prefix("deltas/")?;
     let (source_device_id, file_name) = rest.split_once('/')?;
@@ -1461,17 +4630,21 @@ fn set_remote_delta_cursor_timestamp(
     set_config_value(conn, &key, &timestamp.to_string())
 }
 
+/// Messages end up in the UI, `last_sync_error` and `sync_history`: redact them like logs.
 fn map_s3_error(op: &str, err: Box<dyn StdError>) -> AppError {
-    if let Some((code, message)) = extract_s3_error_code_message(err.as_ref()) {
+    let message = if let Some((code, message)) = extract_s3_error_code_message(err.as_ref()) {
         let msg = message.trim();
         if !msg.is_empty() {
             // Return server message directly for UI display.
-            return AppError::Sync(format!("[{}] {}", code, msg));
+            format!("[{}] {}", code, msg)
+        } else {
+            format!("[{}] {}", code, err)
         }
-        return AppError::Sync(format!("[{}] {}", code, err));
-    }
+    } else {
+        format!("S3 {} failed: {}", op, err)
+    };
 
-    AppError::Sync(format!("S3 {} failed: {}", op, err))
+    AppError::Sync(redact_message(&message).into_owned())
 }
 
 fn extract_s3_error_code_message(err: &(dyn StdError + 'static)) -> Option<(String, String)> {
@@ -1505,6 +4678,9 @@ fn extract_s3_error_code_message(err: &(dyn StdError + 'static)) -> Option<(Stri
     if let Some(e) = err.downcast_ref::<SdkError<GetObjectError>>() {
         return from_sdk_error(e);
     }
+    if let Some(e) = err.downcast_ref::<GoogleDriveError>() {
+        return Some((e.code.clone(), e.message.clone()));
+    }
 
     None
 }
@@ -1516,16 +4692,15 @@ fn extract_s3_error_code_message(err: &(dyn StdError + 'static)) -> Option<(Stri
 /// 不导出：device_id / sync_enabled / last_sync / local_version（这些是设备运行时状态）
 #[tauri::command]
 pub fn cmd_sync_export_config(pool: State<DbPool>) -> Result<String, AppError> {
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+    let conn = get_connection(&pool);
 
     let bucket = get_optional_config_value(&conn, "s3_bucket")?;
     let endpoint = get_optional_config_value(&conn, "s3_endpoint")?;
     let access_key = get_optional_config_value(&conn, "s3_access_key")?;
     let secret_key = get_optional_config_value(&conn, "s3_secret_key")?;
+    // The session token is short-lived: not exported.
+    let auth = load_s3_auth(&conn)?;
+    let key_prefix = load_key_prefix(&conn)?;
     let auto_sync_interval_minutes = get_config_value(&conn, "auto_sync_interval_minutes")
         .ok()
         .and_then(|v| v.trim().parse::<i64>().ok())
@@ -1542,6 +4717,10 @@ pub fn cmd_sync_export_config(pool: State<DbPool>) -> Result<String, AppError> {
             "endpoint": endpoint.unwrap_or_default(),
             "access_key": access_key.unwrap_or_default(),
             "secret_key": secret_key.unwrap_or_default(),
+            "profile": auth.profile.unwrap_or_default(),
+            "role_arn": auth.role_arn.unwrap_or_default(),
+            "external_id": auth.external_id.unwrap_or_default(),
+            "key_prefix": key_prefix,
             "auto_sync_interval_minutes": auto_sync_interval_minutes,
         }
     });
@@ -1584,11 +4763,25 @@ pub async fn cmd_sync_import_config(
         .ok_or_else(|| AppError::Validation("MISSING_FIELD: sync_config".to_string()))?;
 
     {
-        let conn = pool
-            .inner()
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(&pool);
+        let field = |name: &str| {
+            cfg.get(name)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        let imported_auth = S3AuthSettings {
+            profile: field("profile").map(ToString::to_string),
+            session_token: None,
+            role_arn: field("role_arn").map(ToString::to_string),
+            external_id: field("external_id").map(ToString::to_string),
+        };
+        validate_s3_auth(&imported_auth)?;
+        // Joining an existing namespace: set as is, nothing to move.
+        let key_prefix = field("key_prefix")
+            .map(normalize_key_prefix)
+            .transpose()
+            .map_err(AppError::Validation)?;
 
         if let Some(bucket) = cfg
             .get("bucket")
@@ -1620,6 +4813,18 @@ pub async fn cmd_sync_import_config(
         {
             set_config_value(&conn, "s3_secret_key", secret_key.trim())?;
         }
+        for (key, value) in [
+            (S3_PROFILE_KEY, &imported_auth.profile),
+            (S3_ROLE_ARN_KEY, &imported_auth.role_arn),
+            (S3_EXTERNAL_ID_KEY, &imported_auth.external_id),
+        ] {
+            if let Some(value) = value {
+                set_config_value(&conn, key, value)?;
+            }
+        }
+        if let Some(key_prefix) = key_prefix {
+            set_config_value(&conn, S3_KEY_PREFIX_KEY, &key_prefix)?;
+        }
         if let Some(interval) = cfg
             .get("auto_sync_interval_minutes")
             .and_then(|v| v.as_i64())
@@ -1627,51 +4832,16 @@ pub async fn cmd_sync_import_config(
         {
             set_config_value(&conn, "auto_sync_interval_minutes", &interval.to_string())?;
         }
+        refresh_from_sync_config(&conn);
     }
 
     // Refresh scheduler in case interval changed (sync_enabled state unchanged).
     runtime.refresh_scheduler(pool.inner().clone()).await;
 
     // Return updated config so the frontend can refresh its state.
-    let conn = pool
-        .inner()
-        .0
-        .lock()
-        .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
-
-    let device_id = get_config_value(&conn, "device_id")?;
-    let enabled = get_config_value(&conn, "sync_enabled")? == "1";
-    let bucket = get_config_value(&conn, "s3_bucket").ok();
-    let endpoint = get_config_value(&conn, "s3_endpoint").ok();
-    let access_key = get_config_value(&conn, "s3_access_key").ok();
-    let secret_key = get_config_value(&conn, "s3_secret_key").ok();
-    let has_secret_key = secret_key
-        .as_deref()
-        .map(|v| !v.trim().is_empty())
-        .unwrap_or(false);
-    let secret_key_masked = secret_key
-        .as_deref()
-        .map(str::trim)
-        .filter(|v| !v.is_empty())
-        .map(mask_credential);
-    let last_sync = get_config_value(&conn, "last_sync").ok();
-    let auto_sync_interval_minutes = get_config_value(&conn, "auto_sync_interval_minutes")
-        .ok()
-        .and_then(|v| v.trim().parse::<i64>().ok())
-        .filter(|v| *v >= 1)
-        .unwrap_or(1);
+    let conn = get_connection(&pool);
 
-    Ok(SyncConfigResp {
-        enabled,
-        bucket,
-        endpoint,
-        access_key,
-        has_secret_key,
-        secret_key_masked,
-        device_id,
-        last_sync,
-        auto_sync_interval_minutes,
-    })
+    load_sync_config_resp(&conn)
 }
 
 fn mask_credential(value: &str) -> String {
@@ -1701,7 +4871,13 @@ fn mask_credential(value: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{extract_wipe_intent, parse_remote_delta_object, select_latest_snapshot};
+    use super::{
+        ensure_operation_allowed, extract_wipe_intent, load_sync_config_resp,
+        parse_remote_delta_object, select_latest_snapshot, set_config_value,
+        DISABLE_REMOTE_CLEANUP_KEY, DISABLE_SNAPSHOT_RESTORE_KEY, READ_ONLY_REPLICA_KEY,
+    };
+    use crate::infra::db::init_test_db;
+    use crate::sync::protocol::SYNC_FORMAT_VERSION;
     use crate::sync::{Delta, Operation, OperationType, S3ObjectSummary, VectorClock};
 
     #[test]
@@ -1735,14 +4911,17 @@ mod tests {
             S3ObjectSummary {
                 key: "snapshots/latest-b.gz".to_string(),
                 last_modified_unix: Some(100),
+                size_bytes: None,
             },
             S3ObjectSummary {
                 key: "snapshots/latest-a.gz".to_string(),
                 last_modified_unix: Some(100),
+                size_bytes: None,
             },
             S3ObjectSummary {
                 key: "snapshots/latest-c.gz".to_string(),
                 last_modified_unix: Some(101),
+                size_bytes: None,
             },
         ];
 
@@ -1762,6 +4941,7 @@ mod tests {
             vector_clock: VectorClock::new("test-device".to_string()),
             created_at: "2026-03-03T00:00:00Z".to_string(),
             checksum,
+            format_version: SYNC_FORMAT_VERSION,
         }
     }
 
@@ -1875,4 +5055,42 @@ mod tests {
         }]);
         assert!(extract_wipe_intent(&delta).is_none());
     }
+
+    #[test]
+    fn destructive_ops_are_allowed_by_default() {
+        let pool = init_test_db();
+        let conn = pool.0.lock().unwrap();
+        assert!(ensure_operation_allowed(&conn, DISABLE_SNAPSHOT_RESTORE_KEY, "restore").is_ok());
+        assert!(ensure_operation_allowed(&conn, DISABLE_REMOTE_CLEANUP_KEY, "wipe").is_ok());
+
+        let resp = load_sync_config_resp(&conn).unwrap();
+        assert!(!resp.disable_snapshot_restore);
+        assert!(!resp.disable_remote_cleanup);
+    }
+
+    #[test]
+    fn disabled_flag_rejects_operation_and_is_reflected_in_config() {
+        let pool = init_test_db();
+        let conn = pool.0.lock().unwrap();
+        set_config_value(&conn, DISABLE_REMOTE_CLEANUP_KEY, "1").unwrap();
+
+        let err = ensure_operation_allowed(&conn, DISABLE_REMOTE_CLEANUP_KEY, "wipe").unwrap_err();
+        assert_eq!(err.code(), "SYNC_OPERATION_DISABLED");
+        // Other guard stays independent.
+        assert!(ensure_operation_allowed(&conn, DISABLE_SNAPSHOT_RESTORE_KEY, "restore").is_ok());
+
+        let resp = load_sync_config_resp(&conn).unwrap();
+        assert!(!resp.disable_snapshot_restore);
+        assert!(resp.disable_remote_cleanup);
+    }
+
+    #[test]
+    fn read_only_replica_is_reflected_in_config() {
+        let pool = init_test_db();
+        let conn = pool.0.lock().unwrap();
+        assert!(!load_sync_config_resp(&conn).unwrap().read_only_replica);
+
+        set_config_value(&conn, READ_ONLY_REPLICA_KEY, "1").unwrap();
+        assert!(load_sync_config_resp(&conn).unwrap().read_only_replica);
+    }
 }
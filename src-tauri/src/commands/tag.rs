@@ -0,0 +1,60 @@
+use crate::app::{
+    tag_create, tag_delete, tag_delete_unused, tag_list, tag_merge, tag_rename, tag_update,
+    TagChangeResult, TagCreateReq, TagDto, TagMergeReq, TagRenameReq, TagUpdateReq,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagDeleteReq {
+    pub name: String,
+}
+
+/// Catalog entries and tags in use, with usage counts.
+#[tauri::command]
+pub fn cmd_tag_list(pool: State<'_, DbPool>) -> Result<Vec<TagDto>, AppError> {
+    tag_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_tag_create(pool: State<'_, DbPool>, req: TagCreateReq) -> Result<TagDto, AppError> {
+    tag_create(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_tag_update(pool: State<'_, DbPool>, req: TagUpdateReq) -> Result<TagDto, AppError> {
+    tag_update(&pool, req)
+}
+
+/// Rename a tag on every project.
+#[tauri::command]
+pub fn cmd_tag_rename(
+    pool: State<'_, DbPool>,
+    req: TagRenameReq,
+) -> Result<TagChangeResult, AppError> {
+    tag_rename(&pool, req)
+}
+
+/// Replace one tag with another on every project.
+#[tauri::command]
+pub fn cmd_tag_merge(
+    pool: State<'_, DbPool>,
+    req: TagMergeReq,
+) -> Result<TagChangeResult, AppError> {
+    tag_merge(&pool, req)
+}
+
+/// Delete an unused tag from the catalog.
+#[tauri::command]
+pub fn cmd_tag_delete(pool: State<'_, DbPool>, req: TagDeleteReq) -> Result<(), AppError> {
+    tag_delete(&pool, &req.name)
+}
+
+/// Delete every unused tag from the catalog; returns the deleted names.
+#[tauri::command]
+pub fn cmd_tag_delete_unused(pool: State<'_, DbPool>) -> Result<Vec<String>, AppError> {
+    tag_delete_unused(&pool)
+}
@@ -0,0 +1,40 @@
+use crate::app::{
+    project_create_from_template, project_save_as_template, template_delete, template_list,
+    ProjectCreateFromTemplateReq, ProjectDetailDto, ProjectSaveAsTemplateReq, ProjectTemplateDto,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateDeleteReq {
+    pub id: String,
+}
+
+#[tauri::command]
+pub fn cmd_template_list(pool: State<DbPool>) -> Result<Vec<ProjectTemplateDto>, AppError> {
+    template_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_template_delete(pool: State<DbPool>, req: TemplateDeleteReq) -> Result<(), AppError> {
+    template_delete(&pool, &req.id)
+}
+
+#[tauri::command]
+pub fn cmd_project_save_as_template(
+    pool: State<DbPool>,
+    req: ProjectSaveAsTemplateReq,
+) -> Result<ProjectTemplateDto, AppError> {
+    project_save_as_template(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_project_create_from_template(
+    pool: State<DbPool>,
+    req: ProjectCreateFromTemplateReq,
+) -> Result<ProjectDetailDto, AppError> {
+    project_create_from_template(&pool, req)
+}
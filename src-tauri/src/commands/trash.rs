@@ -0,0 +1,42 @@
+use crate::app::{
+    trash_delete, trash_list, trash_purge, trash_restore, TrashItemDto, TrashItemReq,
+    TrashPurgeReq, TrashPurgeResult,
+};
+use crate::commands::run_blocking;
+use crate::error::AppError;
+use crate::infra::DbPool;
+use tauri::State;
+
+/// Move a project / person / partner to the trash (soft delete).
+#[tauri::command]
+pub fn cmd_trash_delete(
+    pool: State<'_, DbPool>,
+    req: TrashItemReq,
+) -> Result<TrashItemDto, AppError> {
+    trash_delete(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_trash_list(pool: State<'_, DbPool>) -> Result<Vec<TrashItemDto>, AppError> {
+    trash_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_trash_restore(
+    pool: State<'_, DbPool>,
+    req: TrashItemReq,
+) -> Result<TrashItemDto, AppError> {
+    trash_restore(&pool, req)
+}
+
+/// Permanently delete items trashed at least `olderThanDays` days ago.
+#[tauri::command]
+pub async fn cmd_trash_purge(
+    pool: State<'_, DbPool>,
+    req: TrashPurgeReq,
+) -> Result<TrashPurgeResult, AppError> {
+    run_blocking(&pool, move |pool| {
+        trash_purge(pool, req, chrono::Utc::now())
+    })
+    .await
+}
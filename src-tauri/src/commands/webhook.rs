@@ -0,0 +1,116 @@
+use crate::app::{
+    webhook_create, webhook_delete, webhook_delivery_list, webhook_dispatch_due, webhook_list,
+    webhook_update, WebhookCreateReq, WebhookDeliveryDto, WebhookDeliveryListReq,
+    WebhookDispatchResult, WebhookDto, WebhookUpdateReq,
+};
+use crate::commands::runtime::{PeriodicTask, ProfileRuntime, RuntimeFuture};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+use tokio::time::Duration;
+
+/// How often the dispatcher looks for due deliveries (retries are scheduled in `next_attempt_at`).
+const WEBHOOK_DISPATCH_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeleteReq {
+    pub id: String,
+}
+
+/// Background dispatcher for queued webhook deliveries; always running (a round with
+/// nothing due is a single query).
+#[derive(Clone, Default)]
+pub struct WebhookRuntime {
+    task: PeriodicTask,
+}
+
+impl WebhookRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stop_scheduler(&self) {
+        self.task.stop().await;
+    }
+
+    pub async fn refresh_scheduler(&self, pool: DbPool) {
+        self.task
+            .start(
+                "Webhook dispatch",
+                WEBHOOK_DISPATCH_INTERVAL,
+                pool,
+                |pool| match webhook_dispatch_due(pool, chrono::Utc::now()) {
+                    Ok(result) => {
+                        if result.delivered + result.retrying + result.failed > 0 {
+                            log::info!(
+                                "Webhook dispatch: {} delivered, {} retrying, {} failed",
+                                result.delivered,
+                                result.retrying,
+                                result.failed
+                            );
+                        }
+                    }
+                    Err(e) => log::error!("Webhook dispatch failed: {}", e),
+                },
+            )
+            .await;
+    }
+}
+
+impl ProfileRuntime for WebhookRuntime {
+    fn stop(&self) -> RuntimeFuture<'_> {
+        Box::pin(self.stop_scheduler())
+    }
+
+    fn refresh(&self, pool: DbPool) -> RuntimeFuture<'_> {
+        Box::pin(self.refresh_scheduler(pool))
+    }
+}
+
+#[tauri::command]
+pub fn cmd_webhook_create(
+    pool: State<'_, DbPool>,
+    req: WebhookCreateReq,
+) -> Result<WebhookDto, AppError> {
+    webhook_create(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_webhook_update(
+    pool: State<'_, DbPool>,
+    req: WebhookUpdateReq,
+) -> Result<WebhookDto, AppError> {
+    webhook_update(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_webhook_delete(pool: State<'_, DbPool>, req: WebhookDeleteReq) -> Result<(), AppError> {
+    webhook_delete(&pool, &req.id)
+}
+
+#[tauri::command]
+pub fn cmd_webhook_list(pool: State<'_, DbPool>) -> Result<Vec<WebhookDto>, AppError> {
+    webhook_list(&pool)
+}
+
+/// Delivery log, newest first.
+#[tauri::command]
+pub fn cmd_webhook_delivery_list(
+    pool: State<'_, DbPool>,
+    req: Option<WebhookDeliveryListReq>,
+) -> Result<Vec<WebhookDeliveryDto>, AppError> {
+    webhook_delivery_list(&pool, req.unwrap_or_default())
+}
+
+/// Send due deliveries now instead of waiting for the next dispatcher round.
+#[tauri::command]
+pub async fn cmd_webhook_dispatch(
+    pool: State<'_, DbPool>,
+) -> Result<WebhookDispatchResult, AppError> {
+    let pool = pool.inner().clone();
+    tokio::task::spawn_blocking(move || webhook_dispatch_due(&pool, chrono::Utc::now()))
+        .await
+        .map_err(|e| AppError::Sync(format!("webhook dispatch task failed: {}", e)))?
+}
@@ -0,0 +1,55 @@
+use crate::app::{
+    workflow_activate, workflow_create, workflow_delete, workflow_list, workflow_update,
+    workflow_wip_status, WipStatusDto, WorkflowCreateReq, WorkflowDto, WorkflowUpdateReq,
+};
+use crate::error::AppError;
+use crate::infra::DbPool;
+use serde::Deserialize;
+use tauri::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowIdReq {
+    pub id: String,
+}
+
+#[tauri::command]
+pub fn cmd_workflow_list(pool: State<DbPool>) -> Result<Vec<WorkflowDto>, AppError> {
+    workflow_list(&pool)
+}
+
+#[tauri::command]
+pub fn cmd_workflow_create(
+    pool: State<DbPool>,
+    req: WorkflowCreateReq,
+) -> Result<WorkflowDto, AppError> {
+    workflow_create(&pool, req)
+}
+
+#[tauri::command]
+pub fn cmd_workflow_update(
+    pool: State<DbPool>,
+    req: WorkflowUpdateReq,
+) -> Result<WorkflowDto, AppError> {
+    workflow_update(&pool, req)
+}
+
+/// Make a workflow the one status changes are checked against.
+#[tauri::command]
+pub fn cmd_workflow_activate(
+    pool: State<DbPool>,
+    req: WorkflowIdReq,
+) -> Result<WorkflowDto, AppError> {
+    workflow_activate(&pool, &req.id)
+}
+
+#[tauri::command]
+pub fn cmd_workflow_delete(pool: State<DbPool>, req: WorkflowIdReq) -> Result<(), AppError> {
+    workflow_delete(&pool, &req.id)
+}
+
+/// Project counts against the active workflow's WIP limits.
+#[tauri::command]
+pub fn cmd_workflow_wip_status(pool: State<DbPool>) -> Result<Vec<WipStatusDto>, AppError> {
+    workflow_wip_status(&pool)
+}
@@ -0,0 +1,309 @@
+//! ISO 3166-1 country catalog embedded in the binary (alpha-2 code -> names).
+//!
+//! - 项目的 `country_code` 在创建 / 修改时必须是表中的 alpha-2 代码（大小写不敏感，存储为大写）。
+//! - 同步、快照恢复与 JSON 导入不做校验：旧数据或其他设备写入的未知代码原样保留，展示时回退为代码本身。
+//! - 名称：英文取常用名，中文取简体常用名；国旗 emoji 由代码按区域指示符计算。
+
+/// One ISO 3166-1 entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Country {
+    pub code: &'static str,
+    pub alpha3: &'static str,
+    pub name_en: &'static str,
+    pub name_zh: &'static str,
+}
+
+impl Country {
+    /// Name for a UI locale (`zh*` -> Chinese, anything else -> English).
+    pub fn localized_name(&self, locale: &str) -> &'static str {
+        if locale.to_ascii_lowercase().starts_with("zh") {
+            self.name_zh
+        } else {
+            self.name_en
+        }
+    }
+
+    pub fn flag(&self) -> String {
+        flag_emoji(self.code)
+    }
+}
+
+/// Look up an alpha-2 code (case-insensitive, surrounding whitespace ignored).
+pub fn country_by_code(code: &str) -> Option<&'static Country> {
+    let code = code.trim().to_ascii_uppercase();
+    COUNTRIES
+        .binary_search_by(|c| c.code.cmp(code.as_str()))
+        .ok()
+        .map(|i| &COUNTRIES[i])
+}
+
+/// English display name, falling back to the raw code for unknown values.
+pub fn country_display_name(code: &str) -> String {
+    country_by_code(code).map_or_else(|| code.to_string(), |c| c.name_en.to_string())
+}
+
+/// Regional-indicator flag for a two-letter code; empty for anything else.
+pub fn flag_emoji(code: &str) -> String {
+    let code = code.trim();
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return String::new();
+    }
+    code.to_ascii_uppercase()
+        .chars()
+        .filter_map(|c| char::from_u32(0x1F1E6 + (c as u32 - 'A' as u32)))
+        .collect()
+}
+
+/// Sorted by `code` (binary search relies on it).
+#[rustfmt::skip]
+pub static COUNTRIES: &[Country] = &[
+    Country { code: "AD", alpha3: "AND", name_en: "Andorra", name_zh: "安道尔" },
+    Country { code: "AE", alpha3: "ARE", name_en: "United Arab Emirates", name_zh: "阿联酋" },
+    Country { code: "AF", alpha3: "AFG", name_en: "Afghanistan", name_zh: "阿富汗" },
+    Country { code: "AG", alpha3: "ATG", name_en: "Antigua and Barbuda", name_zh: "安提瓜和巴布达" },
+    Country { code: "AI", alpha3: "AIA", name_en: "Anguilla", name_zh: "安圭拉" },
+    Country { code: "AL", alpha3: "ALB", name_en: "Albania", name_zh: "阿尔巴尼亚" },
+    Country { code: "AM", alpha3: "ARM", name_en: "Armenia", name_zh: "亚美尼亚" },
+    Country { code: "AO", alpha3: "AGO", name_en: "Angola", name_zh: "安哥拉" },
+    Country { code: "AQ", alpha3: "ATA", name_en: "Antarctica", name_zh: "南极洲" },
+    Country { code: "AR", alpha3: "ARG", name_en: "Argentina", name_zh: "阿根廷" },
+    Country { code: "AS", alpha3: "ASM", name_en: "American Samoa", name_zh: "美属萨摩亚" },
+    Country { code: "AT", alpha3: "AUT", name_en: "Austria", name_zh: "奥地利" },
+    Country { code: "AU", alpha3: "AUS", name_en: "Australia", name_zh: "澳大利亚" },
+    Country { code: "AW", alpha3: "ABW", name_en: "Aruba", name_zh: "阿鲁巴" },
+    Country { code: "AX", alpha3: "ALA", name_en: "Åland Islands", name_zh: "奥兰群岛" },
+    Country { code: "AZ", alpha3: "AZE", name_en: "Azerbaijan", name_zh: "阿塞拜疆" },
+    Country { code: "BA", alpha3: "BIH", name_en: "Bosnia and Herzegovina", name_zh: "波斯尼亚和黑塞哥维那" },
+    Country { code: "BB", alpha3: "BRB", name_en: "Barbados", name_zh: "巴巴多斯" },
+    Country { code: "BD", alpha3: "BGD", name_en: "Bangladesh", name_zh: "孟加拉" },
+    Country { code: "BE", alpha3: "BEL", name_en: "Belgium", name_zh: "比利时" },
+    Country { code: "BF", alpha3: "BFA", name_en: "Burkina Faso", name_zh: "布基纳法索" },
+    Country { code: "BG", alpha3: "BGR", name_en: "Bulgaria", name_zh: "保加利亚" },
+    Country { code: "BH", alpha3: "BHR", name_en: "Bahrain", name_zh: "巴林" },
+    Country { code: "BI", alpha3: "BDI", name_en: "Burundi", name_zh: "布隆迪" },
+    Country { code: "BJ", alpha3: "BEN", name_en: "Benin", name_zh: "贝宁" },
+    Country { code: "BL", alpha3: "BLM", name_en: "Saint Barthélemy", name_zh: "圣巴泰勒米岛" },
+    Country { code: "BM", alpha3: "BMU", name_en: "Bermuda", name_zh: "百慕大" },
+    Country { code: "BN", alpha3: "BRN", name_en: "Brunei", name_zh: "文莱" },
+    Country { code: "BO", alpha3: "BOL", name_en: "Bolivia", name_zh: "玻利维亚" },
+    Country { code: "BQ", alpha3: "BES", name_en: "Caribbean Netherlands", name_zh: "博奈尔、圣尤斯特歇斯岛和萨巴" },
+    Country { code: "BR", alpha3: "BRA", name_en: "Brazil", name_zh: "巴西" },
+    Country { code: "BS", alpha3: "BHS", name_en: "Bahamas", name_zh: "巴哈马" },
+    Country { code: "BT", alpha3: "BTN", name_en: "Bhutan", name_zh: "不丹" },
+    Country { code: "BV", alpha3: "BVT", name_en: "Bouvet Island", name_zh: "布维群岛" },
+    Country { code: "BW", alpha3: "BWA", name_en: "Botswana", name_zh: "博兹瓦那" },
+    Country { code: "BY", alpha3: "BLR", name_en: "Belarus", name_zh: "白俄罗斯" },
+    Country { code: "BZ", alpha3: "BLZ", name_en: "Belize", name_zh: "伯利兹" },
+    Country { code: "CA", alpha3: "CAN", name_en: "Canada", name_zh: "加拿大" },
+    Country { code: "CC", alpha3: "CCK", name_en: "Cocos (Keeling) Islands", name_zh: "科科斯群岛" },
+    Country { code: "CD", alpha3: "COD", name_en: "Congo (DRC)", name_zh: "刚果（金）" },
+    Country { code: "CF", alpha3: "CAF", name_en: "Central African Republic", name_zh: "中非" },
+    Country { code: "CG", alpha3: "COG", name_en: "Congo", name_zh: "刚果（布）" },
+    Country { code: "CH", alpha3: "CHE", name_en: "Switzerland", name_zh: "瑞士" },
+    Country { code: "CI", alpha3: "CIV", name_en: "Côte d'Ivoire", name_zh: "科特迪瓦" },
+    Country { code: "CK", alpha3: "COK", name_en: "Cook Islands", name_zh: "库克群岛" },
+    Country { code: "CL", alpha3: "CHL", name_en: "Chile", name_zh: "智利" },
+    Country { code: "CM", alpha3: "CMR", name_en: "Cameroon", name_zh: "喀麦隆" },
+    Country { code: "CN", alpha3: "CHN", name_en: "China", name_zh: "中国" },
+    Country { code: "CO", alpha3: "COL", name_en: "Colombia", name_zh: "哥伦比亚" },
+    Country { code: "CR", alpha3: "CRI", name_en: "Costa Rica", name_zh: "哥斯达黎加" },
+    Country { code: "CU", alpha3: "CUB", name_en: "Cuba", name_zh: "古巴" },
+    Country { code: "CV", alpha3: "CPV", name_en: "Cabo Verde", name_zh: "佛得角" },
+    Country { code: "CW", alpha3: "CUW", name_en: "Curaçao", name_zh: "库拉索" },
+    Country { code: "CX", alpha3: "CXR", name_en: "Christmas Island", name_zh: "圣诞岛" },
+    Country { code: "CY", alpha3: "CYP", name_en: "Cyprus", name_zh: "塞浦路斯" },
+    Country { code: "CZ", alpha3: "CZE", name_en: "Czechia", name_zh: "捷克" },
+    Country { code: "DE", alpha3: "DEU", name_en: "Germany", name_zh: "德国" },
+    Country { code: "DJ", alpha3: "DJI", name_en: "Djibouti", name_zh: "吉布提" },
+    Country { code: "DK", alpha3: "DNK", name_en: "Denmark", name_zh: "丹麦" },
+    Country { code: "DM", alpha3: "DMA", name_en: "Dominica", name_zh: "多米尼克" },
+    Country { code: "DO", alpha3: "DOM", name_en: "Dominican Republic", name_zh: "多米尼加共和国" },
+    Country { code: "DZ", alpha3: "DZA", name_en: "Algeria", name_zh: "阿尔及利亚" },
+    Country { code: "EC", alpha3: "ECU", name_en: "Ecuador", name_zh: "厄瓜多尔" },
+    Country { code: "EE", alpha3: "EST", name_en: "Estonia", name_zh: "爱沙尼亚" },
+    Country { code: "EG", alpha3: "EGY", name_en: "Egypt", name_zh: "埃及" },
+    Country { code: "EH", alpha3: "ESH", name_en: "Western Sahara", name_zh: "西撒哈拉" },
+    Country { code: "ER", alpha3: "ERI", name_en: "Eritrea", name_zh: "厄立特里亚" },
+    Country { code: "ES", alpha3: "ESP", name_en: "Spain", name_zh: "西班牙" },
+    Country { code: "ET", alpha3: "ETH", name_en: "Ethiopia", name_zh: "埃塞俄比亚" },
+    Country { code: "FI", alpha3: "FIN", name_en: "Finland", name_zh: "芬兰" },
+    Country { code: "FJ", alpha3: "FJI", name_en: "Fiji", name_zh: "斐济" },
+    Country { code: "FK", alpha3: "FLK", name_en: "Falkland Islands", name_zh: "福克兰群岛(马尔维纳斯)" },
+    Country { code: "FM", alpha3: "FSM", name_en: "Micronesia", name_zh: "密克罗尼西亚" },
+    Country { code: "FO", alpha3: "FRO", name_en: "Faroe Islands", name_zh: "法罗群岛" },
+    Country { code: "FR", alpha3: "FRA", name_en: "France", name_zh: "法国" },
+    Country { code: "GA", alpha3: "GAB", name_en: "Gabon", name_zh: "加蓬" },
+    Country { code: "GB", alpha3: "GBR", name_en: "United Kingdom", name_zh: "英国" },
+    Country { code: "GD", alpha3: "GRD", name_en: "Grenada", name_zh: "格林纳达" },
+    Country { code: "GE", alpha3: "GEO", name_en: "Georgia", name_zh: "格鲁吉亚" },
+    Country { code: "GF", alpha3: "GUF", name_en: "French Guiana", name_zh: "法属圭亚那" },
+    Country { code: "GG", alpha3: "GGY", name_en: "Guernsey", name_zh: "根西岛" },
+    Country { code: "GH", alpha3: "GHA", name_en: "Ghana", name_zh: "加纳" },
+    Country { code: "GI", alpha3: "GIB", name_en: "Gibraltar", name_zh: "直布罗陀" },
+    Country { code: "GL", alpha3: "GRL", name_en: "Greenland", name_zh: "格陵兰" },
+    Country { code: "GM", alpha3: "GMB", name_en: "Gambia", name_zh: "冈比亚" },
+    Country { code: "GN", alpha3: "GIN", name_en: "Guinea", name_zh: "几内亚" },
+    Country { code: "GP", alpha3: "GLP", name_en: "Guadeloupe", name_zh: "瓜德罗普" },
+    Country { code: "GQ", alpha3: "GNQ", name_en: "Equatorial Guinea", name_zh: "赤道几内亚" },
+    Country { code: "GR", alpha3: "GRC", name_en: "Greece", name_zh: "希腊" },
+    Country { code: "GS", alpha3: "SGS", name_en: "South Georgia and the South Sandwich Islands", name_zh: "南乔治亚岛和南桑德韦奇岛" },
+    Country { code: "GT", alpha3: "GTM", name_en: "Guatemala", name_zh: "瓜地马拉" },
+    Country { code: "GU", alpha3: "GUM", name_en: "Guam", name_zh: "关岛" },
+    Country { code: "GW", alpha3: "GNB", name_en: "Guinea-Bissau", name_zh: "几内亚比绍" },
+    Country { code: "GY", alpha3: "GUY", name_en: "Guyana", name_zh: "圭亚那" },
+    Country { code: "HK", alpha3: "HKG", name_en: "Hong Kong", name_zh: "香港" },
+    Country { code: "HM", alpha3: "HMD", name_en: "Heard Island and McDonald Islands", name_zh: "赫德岛与麦克唐纳群岛" },
+    Country { code: "HN", alpha3: "HND", name_en: "Honduras", name_zh: "洪都拉斯" },
+    Country { code: "HR", alpha3: "HRV", name_en: "Croatia", name_zh: "克罗地亚" },
+    Country { code: "HT", alpha3: "HTI", name_en: "Haiti", name_zh: "海地" },
+    Country { code: "HU", alpha3: "HUN", name_en: "Hungary", name_zh: "匈牙利" },
+    Country { code: "ID", alpha3: "IDN", name_en: "Indonesia", name_zh: "印度尼西亚" },
+    Country { code: "IE", alpha3: "IRL", name_en: "Ireland", name_zh: "爱尔兰" },
+    Country { code: "IL", alpha3: "ISR", name_en: "Israel", name_zh: "以色列" },
+    Country { code: "IM", alpha3: "IMN", name_en: "Isle of Man", name_zh: "曼岛" },
+    Country { code: "IN", alpha3: "IND", name_en: "India", name_zh: "印度" },
+    Country { code: "IO", alpha3: "IOT", name_en: "British Indian Ocean Territory", name_zh: "英属印度洋领地" },
+    Country { code: "IQ", alpha3: "IRQ", name_en: "Iraq", name_zh: "伊拉克" },
+    Country { code: "IR", alpha3: "IRN", name_en: "Iran", name_zh: "伊朗" },
+    Country { code: "IS", alpha3: "ISL", name_en: "Iceland", name_zh: "冰岛" },
+    Country { code: "IT", alpha3: "ITA", name_en: "Italy", name_zh: "意大利" },
+    Country { code: "JE", alpha3: "JEY", name_en: "Jersey", name_zh: "泽西岛" },
+    Country { code: "JM", alpha3: "JAM", name_en: "Jamaica", name_zh: "牙买加" },
+    Country { code: "JO", alpha3: "JOR", name_en: "Jordan", name_zh: "约旦" },
+    Country { code: "JP", alpha3: "JPN", name_en: "Japan", name_zh: "日本" },
+    Country { code: "KE", alpha3: "KEN", name_en: "Kenya", name_zh: "肯尼亚" },
+    Country { code: "KG", alpha3: "KGZ", name_en: "Kyrgyzstan", name_zh: "吉尔吉斯坦" },
+    Country { code: "KH", alpha3: "KHM", name_en: "Cambodia", name_zh: "柬埔塞" },
+    Country { code: "KI", alpha3: "KIR", name_en: "Kiribati", name_zh: "基里巴斯" },
+    Country { code: "KM", alpha3: "COM", name_en: "Comoros", name_zh: "科摩罗" },
+    Country { code: "KN", alpha3: "KNA", name_en: "Saint Kitts and Nevis", name_zh: "圣基茨和尼维斯" },
+    Country { code: "KP", alpha3: "PRK", name_en: "North Korea", name_zh: "朝鲜" },
+    Country { code: "KR", alpha3: "KOR", name_en: "South Korea", name_zh: "韩国" },
+    Country { code: "KW", alpha3: "KWT", name_en: "Kuwait", name_zh: "科威特" },
+    Country { code: "KY", alpha3: "CYM", name_en: "Cayman Islands", name_zh: "开曼群岛" },
+    Country { code: "KZ", alpha3: "KAZ", name_en: "Kazakhstan", name_zh: "哈萨克斯坦" },
+    Country { code: "LA", alpha3: "LAO", name_en: "Laos", name_zh: "老挝" },
+    Country { code: "LB", alpha3: "LBN", name_en: "Lebanon", name_zh: "黎巴嫩" },
+    Country { code: "LC", alpha3: "LCA", name_en: "Saint Lucia", name_zh: "圣路西亚" },
+    Country { code: "LI", alpha3: "LIE", name_en: "Liechtenstein", name_zh: "列支敦士登" },
+    Country { code: "LK", alpha3: "LKA", name_en: "Sri Lanka", name_zh: "斯里兰卡" },
+    Country { code: "LR", alpha3: "LBR", name_en: "Liberia", name_zh: "利比里亚" },
+    Country { code: "LS", alpha3: "LSO", name_en: "Lesotho", name_zh: "莱索托" },
+    Country { code: "LT", alpha3: "LTU", name_en: "Lithuania", name_zh: "立陶宛" },
+    Country { code: "LU", alpha3: "LUX", name_en: "Luxembourg", name_zh: "卢森堡" },
+    Country { code: "LV", alpha3: "LVA", name_en: "Latvia", name_zh: "拉脱维亚" },
+    Country { code: "LY", alpha3: "LBY", name_en: "Libya", name_zh: "利比亚" },
+    Country { code: "MA", alpha3: "MAR", name_en: "Morocco", name_zh: "摩洛哥" },
+    Country { code: "MC", alpha3: "MCO", name_en: "Monaco", name_zh: "摩纳哥" },
+    Country { code: "MD", alpha3: "MDA", name_en: "Moldova", name_zh: "摩尔多瓦" },
+    Country { code: "ME", alpha3: "MNE", name_en: "Montenegro", name_zh: "黑山" },
+    Country { code: "MF", alpha3: "MAF", name_en: "Saint Martin (French part)", name_zh: "法属圣马丁" },
+    Country { code: "MG", alpha3: "MDG", name_en: "Madagascar", name_zh: "马达加斯加" },
+    Country { code: "MH", alpha3: "MHL", name_en: "Marshall Islands", name_zh: "马绍尔群岛" },
+    Country { code: "MK", alpha3: "MKD", name_en: "North Macedonia", name_zh: "北马其顿" },
+    Country { code: "ML", alpha3: "MLI", name_en: "Mali", name_zh: "马里" },
+    Country { code: "MM", alpha3: "MMR", name_en: "Myanmar", name_zh: "缅甸" },
+    Country { code: "MN", alpha3: "MNG", name_en: "Mongolia", name_zh: "蒙古" },
+    Country { code: "MO", alpha3: "MAC", name_en: "Macao", name_zh: "澳门" },
+    Country { code: "MP", alpha3: "MNP", name_en: "Northern Mariana Islands", name_zh: "北马里亚纳群岛" },
+    Country { code: "MQ", alpha3: "MTQ", name_en: "Martinique", name_zh: "马提尼克" },
+    Country { code: "MR", alpha3: "MRT", name_en: "Mauritania", name_zh: "毛里塔尼亚" },
+    Country { code: "MS", alpha3: "MSR", name_en: "Montserrat", name_zh: "蒙塞拉特岛" },
+    Country { code: "MT", alpha3: "MLT", name_en: "Malta", name_zh: "马尔他" },
+    Country { code: "MU", alpha3: "MUS", name_en: "Mauritius", name_zh: "毛里求斯" },
+    Country { code: "MV", alpha3: "MDV", name_en: "Maldives", name_zh: "马尔代夫" },
+    Country { code: "MW", alpha3: "MWI", name_en: "Malawi", name_zh: "马拉维" },
+    Country { code: "MX", alpha3: "MEX", name_en: "Mexico", name_zh: "墨西哥" },
+    Country { code: "MY", alpha3: "MYS", name_en: "Malaysia", name_zh: "马来西亚" },
+    Country { code: "MZ", alpha3: "MOZ", name_en: "Mozambique", name_zh: "莫桑比克" },
+    Country { code: "NA", alpha3: "NAM", name_en: "Namibia", name_zh: "纳米比亚" },
+    Country { code: "NC", alpha3: "NCL", name_en: "New Caledonia", name_zh: "新喀里多尼亚" },
+    Country { code: "NE", alpha3: "NER", name_en: "Niger", name_zh: "尼日尔" },
+    Country { code: "NF", alpha3: "NFK", name_en: "Norfolk Island", name_zh: "诺福克岛" },
+    Country { code: "NG", alpha3: "NGA", name_en: "Nigeria", name_zh: "尼日利亚" },
+    Country { code: "NI", alpha3: "NIC", name_en: "Nicaragua", name_zh: "尼加拉瓜" },
+    Country { code: "NL", alpha3: "NLD", name_en: "Netherlands", name_zh: "荷兰" },
+    Country { code: "NO", alpha3: "NOR", name_en: "Norway", name_zh: "挪威" },
+    Country { code: "NP", alpha3: "NPL", name_en: "Nepal", name_zh: "尼泊尔" },
+    Country { code: "NR", alpha3: "NRU", name_en: "Nauru", name_zh: "瑙鲁" },
+    Country { code: "NU", alpha3: "NIU", name_en: "Niue", name_zh: "纽埃" },
+    Country { code: "NZ", alpha3: "NZL", name_en: "New Zealand", name_zh: "新西兰" },
+    Country { code: "OM", alpha3: "OMN", name_en: "Oman", name_zh: "阿曼" },
+    Country { code: "PA", alpha3: "PAN", name_en: "Panama", name_zh: "巴拿马" },
+    Country { code: "PE", alpha3: "PER", name_en: "Peru", name_zh: "秘鲁" },
+    Country { code: "PF", alpha3: "PYF", name_en: "French Polynesia", name_zh: "法属玻利尼西亚" },
+    Country { code: "PG", alpha3: "PNG", name_en: "Papua New Guinea", name_zh: "巴布亚新几内亚" },
+    Country { code: "PH", alpha3: "PHL", name_en: "Philippines", name_zh: "菲律宾" },
+    Country { code: "PK", alpha3: "PAK", name_en: "Pakistan", name_zh: "巴基斯坦" },
+    Country { code: "PL", alpha3: "POL", name_en: "Poland", name_zh: "波兰" },
+    Country { code: "PM", alpha3: "SPM", name_en: "Saint Pierre and Miquelon", name_zh: "圣皮埃尔和密克隆" },
+    Country { code: "PN", alpha3: "PCN", name_en: "Pitcairn", name_zh: "皮特克恩" },
+    Country { code: "PR", alpha3: "PRI", name_en: "Puerto Rico", name_zh: "波多黎各" },
+    Country { code: "PS", alpha3: "PSE", name_en: "Palestine", name_zh: "巴勒斯坦" },
+    Country { code: "PT", alpha3: "PRT", name_en: "Portugal", name_zh: "葡萄牙" },
+    Country { code: "PW", alpha3: "PLW", name_en: "Palau", name_zh: "帕劳" },
+    Country { code: "PY", alpha3: "PRY", name_en: "Paraguay", name_zh: "巴拉圭" },
+    Country { code: "QA", alpha3: "QAT", name_en: "Qatar", name_zh: "卡塔尔" },
+    Country { code: "RE", alpha3: "REU", name_en: "Réunion", name_zh: "留尼汪" },
+    Country { code: "RO", alpha3: "ROU", name_en: "Romania", name_zh: "罗马尼亚" },
+    Country { code: "RS", alpha3: "SRB", name_en: "Serbia", name_zh: "塞尔维亚" },
+    Country { code: "RU", alpha3: "RUS", name_en: "Russia", name_zh: "俄罗斯" },
+    Country { code: "RW", alpha3: "RWA", name_en: "Rwanda", name_zh: "卢旺达" },
+    Country { code: "SA", alpha3: "SAU", name_en: "Saudi Arabia", name_zh: "沙特阿拉伯" },
+    Country { code: "SB", alpha3: "SLB", name_en: "Solomon Islands", name_zh: "所罗门群岛" },
+    Country { code: "SC", alpha3: "SYC", name_en: "Seychelles", name_zh: "塞舌尔" },
+    Country { code: "SD", alpha3: "SDN", name_en: "Sudan", name_zh: "苏丹" },
+    Country { code: "SE", alpha3: "SWE", name_en: "Sweden", name_zh: "瑞典" },
+    Country { code: "SG", alpha3: "SGP", name_en: "Singapore", name_zh: "新加坡" },
+    Country { code: "SH", alpha3: "SHN", name_en: "Saint Helena, Ascension and Tristan da Cunha", name_zh: "圣赫勒拿-阿森松-特里斯坦达库尼亚" },
+    Country { code: "SI", alpha3: "SVN", name_en: "Slovenia", name_zh: "斯洛文尼亚" },
+    Country { code: "SJ", alpha3: "SJM", name_en: "Svalbard and Jan Mayen", name_zh: "斯瓦尔巴特和扬马延岛" },
+    Country { code: "SK", alpha3: "SVK", name_en: "Slovakia", name_zh: "斯洛伐克" },
+    Country { code: "SL", alpha3: "SLE", name_en: "Sierra Leone", name_zh: "塞拉利昂" },
+    Country { code: "SM", alpha3: "SMR", name_en: "San Marino", name_zh: "圣马力诺市" },
+    Country { code: "SN", alpha3: "SEN", name_en: "Senegal", name_zh: "塞内加尔" },
+    Country { code: "SO", alpha3: "SOM", name_en: "Somalia", name_zh: "索马里" },
+    Country { code: "SR", alpha3: "SUR", name_en: "Suriname", name_zh: "苏里南" },
+    Country { code: "SS", alpha3: "SSD", name_en: "South Sudan", name_zh: "南苏丹" },
+    Country { code: "ST", alpha3: "STP", name_en: "Sao Tome and Principe", name_zh: "圣多美和普林西比" },
+    Country { code: "SV", alpha3: "SLV", name_en: "El Salvador", name_zh: "萨尔瓦多" },
+    Country { code: "SX", alpha3: "SXM", name_en: "Sint Maarten (Dutch part)", name_zh: "荷属圣马丁" },
+    Country { code: "SY", alpha3: "SYR", name_en: "Syria", name_zh: "叙利亚" },
+    Country { code: "SZ", alpha3: "SWZ", name_en: "Eswatini", name_zh: "斯威士兰" },
+    Country { code: "TC", alpha3: "TCA", name_en: "Turks and Caicos Islands", name_zh: "特克斯和凯科斯群岛" },
+    Country { code: "TD", alpha3: "TCD", name_en: "Chad", name_zh: "乍得" },
+    Country { code: "TF", alpha3: "ATF", name_en: "French Southern Territories", name_zh: "法属南半球领地" },
+    Country { code: "TG", alpha3: "TGO", name_en: "Togo", name_zh: "多哥" },
+    Country { code: "TH", alpha3: "THA", name_en: "Thailand", name_zh: "泰国" },
+    Country { code: "TJ", alpha3: "TJK", name_en: "Tajikistan", name_zh: "塔吉克斯坦" },
+    Country { code: "TK", alpha3: "TKL", name_en: "Tokelau", name_zh: "托克劳" },
+    Country { code: "TL", alpha3: "TLS", name_en: "Timor-Leste", name_zh: "东帝汶" },
+    Country { code: "TM", alpha3: "TKM", name_en: "Turkmenistan", name_zh: "土库曼斯坦" },
+    Country { code: "TN", alpha3: "TUN", name_en: "Tunisia", name_zh: "突尼斯" },
+    Country { code: "TO", alpha3: "TON", name_en: "Tonga", name_zh: "汤加" },
+    Country { code: "TR", alpha3: "TUR", name_en: "Türkiye", name_zh: "土耳其" },
+    Country { code: "TT", alpha3: "TTO", name_en: "Trinidad and Tobago", name_zh: "特里尼达和多巴哥" },
+    Country { code: "TV", alpha3: "TUV", name_en: "Tuvalu", name_zh: "图瓦卢" },
+    Country { code: "TW", alpha3: "TWN", name_en: "Taiwan", name_zh: "中国台湾" },
+    Country { code: "TZ", alpha3: "TZA", name_en: "Tanzania", name_zh: "坦桑尼亚" },
+    Country { code: "UA", alpha3: "UKR", name_en: "Ukraine", name_zh: "乌克兰" },
+    Country { code: "UG", alpha3: "UGA", name_en: "Uganda", name_zh: "乌干达" },
+    Country { code: "UM", alpha3: "UMI", name_en: "United States Minor Outlying Islands", name_zh: "美国本土外小岛屿" },
+    Country { code: "US", alpha3: "USA", name_en: "United States", name_zh: "美国" },
+    Country { code: "UY", alpha3: "URY", name_en: "Uruguay", name_zh: "乌拉圭" },
+    Country { code: "UZ", alpha3: "UZB", name_en: "Uzbekistan", name_zh: "乌兹别克斯坦" },
+    Country { code: "VA", alpha3: "VAT", name_en: "Vatican City", name_zh: "梵蒂冈" },
+    Country { code: "VC", alpha3: "VCT", name_en: "Saint Vincent and the Grenadines", name_zh: "圣文森特和格林纳丁斯" },
+    Country { code: "VE", alpha3: "VEN", name_en: "Venezuela", name_zh: "委内瑞拉" },
+    Country { code: "VG", alpha3: "VGB", name_en: "British Virgin Islands", name_zh: "英属维尔京群岛" },
+    Country { code: "VI", alpha3: "VIR", name_en: "U.S. Virgin Islands", name_zh: "美属维尔京群岛" },
+    Country { code: "VN", alpha3: "VNM", name_en: "Vietnam", name_zh: "越南" },
+    Country { code: "VU", alpha3: "VUT", name_en: "Vanuatu", name_zh: "瓦努阿图" },
+    Country { code: "WF", alpha3: "WLF", name_en: "Wallis and Futuna", name_zh: "瓦利斯和富图纳" },
+    Country { code: "WS", alpha3: "WSM", name_en: "Samoa", name_zh: "萨摩亚" },
+    Country { code: "YE", alpha3: "YEM", name_en: "Yemen", name_zh: "也门" },
+    Country { code: "YT", alpha3: "MYT", name_en: "Mayotte", name_zh: "马约特" },
+    Country { code: "ZA", alpha3: "ZAF", name_en: "South Africa", name_zh: "南非" },
+    Country { code: "ZM", alpha3: "ZMB", name_en: "Zambia", name_zh: "赞比亚" },
+    Country { code: "ZW", alpha3: "ZWE", name_en: "Zimbabwe", name_zh: "津巴布韦" },
+];
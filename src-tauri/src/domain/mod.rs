@@ -1,5 +1,14 @@
 //! Domain layer: status machine, invariants.
 
+mod country;
+mod size;
 mod status;
+mod workflow;
 
+pub use country::{country_by_code, country_display_name, flag_emoji, Country, COUNTRIES};
+pub use size::{is_valid_size, parse_size, size_label, SIZE_LABELS, SIZE_POINTS};
 pub use status::{ProjectStatus, StatusMachine};
+pub use workflow::{
+    InvalidWorkflow, Workflow, WorkflowStatus, WorkflowTransition, MAX_STATUS_KEY_LEN,
+    MAX_STATUS_LABEL_LEN, MAX_WIP_LIMIT, MAX_WORKFLOW_STATUSES, REQUIRED_STATUSES,
+};
@@ -0,0 +1,54 @@
+//! Project size scale: Fibonacci story points with T-shirt aliases.
+
+/// Allowed `size_points` values, ascending.
+pub const SIZE_POINTS: &[i64] = &[0, 1, 2, 3, 5, 8, 13, 21];
+
+/// T-shirt labels and the points they map to.
+pub const SIZE_LABELS: &[(&str, i64)] = &[("XS", 1), ("S", 2), ("M", 3), ("L", 5), ("XL", 8)];
+
+pub fn is_valid_size(points: i64) -> bool {
+    SIZE_POINTS.contains(&points)
+}
+
+/// Points for a T-shirt label (case-insensitive) or a numeric string on the scale.
+pub fn parse_size(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    if let Some((_, points)) = SIZE_LABELS
+        .iter()
+        .find(|(label, _)| label.eq_ignore_ascii_case(raw))
+    {
+        return Some(*points);
+    }
+    raw.parse::<i64>().ok().filter(|p| is_valid_size(*p))
+}
+
+/// T-shirt label for `points`, if one maps to it.
+pub fn size_label(points: i64) -> Option<&'static str> {
+    SIZE_LABELS
+        .iter()
+        .find(|(_, p)| *p == points)
+        .map(|(label, _)| *label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_labels_and_points() {
+        assert_eq!(parse_size("xl"), Some(8));
+        assert_eq!(parse_size(" M "), Some(3));
+        assert_eq!(parse_size("13"), Some(13));
+        assert_eq!(parse_size("4"), None);
+        assert_eq!(parse_size("XXL"), None);
+    }
+
+    #[test]
+    fn labels_are_on_the_scale() {
+        for (label, points) in SIZE_LABELS {
+            assert!(is_valid_size(*points));
+            assert_eq!(size_label(*points), Some(*label));
+        }
+        assert_eq!(size_label(21), None);
+    }
+}
@@ -65,7 +65,8 @@ impl ProjectStatus {
     }
 }
 
-/// State machine: valid transitions and note requirements.
+/// Built-in state machine: valid transitions and note requirements. Seeded as the default
+/// workflow (`Workflow::builtin`); `project_change_status` checks the active workflow.
 pub struct StatusMachine;
 
 impl StatusMachine {
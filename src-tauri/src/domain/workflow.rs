@@ -0,0 +1,314 @@
+//! Configurable status workflow: statuses, allowed transitions and note requirements.
+
+use super::status::{ProjectStatus, StatusMachine};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Statuses every workflow must keep: new projects start in BACKLOG, DONE counts as completed
+/// and ARCHIVED archives the project.
+pub const REQUIRED_STATUSES: [ProjectStatus; 3] = [
+    ProjectStatus::Backlog,
+    ProjectStatus::Done,
+    ProjectStatus::Archived,
+];
+
+pub const MAX_WORKFLOW_STATUSES: usize = 20;
+/// Longest status key (`[A-Z][A-Z0-9_]*`).
+pub const MAX_STATUS_KEY_LEN: usize = 32;
+pub const MAX_STATUS_LABEL_LEN: usize = 64;
+/// Largest accepted WIP limit.
+pub const MAX_WIP_LIMIT: u32 = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStatus {
+    /// Stored in `projects.current_status`.
+    pub key: String,
+    /// Display name; empty = the key.
+    #[serde(default)]
+    pub label: String,
+    /// Most projects allowed in this status; `None` = unlimited.
+    #[serde(default)]
+    pub wip_limit: Option<u32>,
+    /// Most projects one owner may have in this status; `None` = unlimited.
+    #[serde(default)]
+    pub owner_wip_limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowTransition {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub note_required: bool,
+}
+
+/// Statuses in board order and the transitions allowed between them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workflow {
+    pub statuses: Vec<WorkflowStatus>,
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+/// Error returned when a workflow definition is inconsistent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidWorkflow(pub String);
+
+impl fmt::Display for InvalidWorkflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid workflow: {}", self.0)
+    }
+}
+
+impl Workflow {
+    /// Trim keys and labels (an empty label becomes the key) and check the definition.
+    pub fn new(
+        statuses: Vec<WorkflowStatus>,
+        transitions: Vec<WorkflowTransition>,
+    ) -> Result<Self, InvalidWorkflow> {
+        let statuses: Vec<WorkflowStatus> = statuses
+            .into_iter()
+            .map(|s| {
+                let key = s.key.trim().to_string();
+                let label = match s.label.trim() {
+                    "" => key.clone(),
+                    label => label.to_string(),
+                };
+                WorkflowStatus { key, label, ..s }
+            })
+            .collect();
+        let transitions = transitions
+            .into_iter()
+            .map(|t| WorkflowTransition {
+                from: t.from.trim().to_string(),
+                to: t.to.trim().to_string(),
+                note_required: t.note_required,
+            })
+            .collect();
+        let workflow = Self {
+            statuses,
+            transitions,
+        };
+        workflow.validate()?;
+        Ok(workflow)
+    }
+
+    /// The built-in state machine (`StatusMachine`), seeded as the default workflow.
+    pub fn builtin() -> Self {
+        let all = ProjectStatus::all();
+        let statuses = all
+            .iter()
+            .map(|s| WorkflowStatus {
+                key: s.as_str().to_string(),
+                label: builtin_label(*s).to_string(),
+                wip_limit: None,
+                owner_wip_limit: None,
+            })
+            .collect();
+        let transitions = all
+            .iter()
+            .flat_map(|from| all.iter().map(move |to| (*from, *to)))
+            .filter(|(from, to)| StatusMachine::can_transition(Some(*from), *to))
+            .map(|(from, to)| WorkflowTransition {
+                from: from.as_str().to_string(),
+                to: to.as_str().to_string(),
+                note_required: StatusMachine::note_required(Some(from), to),
+            })
+            .collect();
+        Self {
+            statuses,
+            transitions,
+        }
+    }
+
+    pub fn has_status(&self, key: &str) -> bool {
+        self.status(key).is_some()
+    }
+
+    pub fn status(&self, key: &str) -> Option<&WorkflowStatus> {
+        self.statuses.iter().find(|s| s.key == key)
+    }
+
+    pub fn can_transition(&self, from: &str, to: &str) -> bool {
+        self.transition(from, to).is_some()
+    }
+
+    /// Returns true if this transition requires a non-empty note.
+    pub fn note_required(&self, from: &str, to: &str) -> bool {
+        self.transition(from, to).is_some_and(|t| t.note_required)
+    }
+
+    fn transition(&self, from: &str, to: &str) -> Option<&WorkflowTransition> {
+        self.transitions
+            .iter()
+            .find(|t| t.from == from && t.to == to)
+    }
+
+    fn validate(&self) -> Result<(), InvalidWorkflow> {
+        let invalid = |message: String| Err(InvalidWorkflow(message));
+        if self.statuses.is_empty() || self.statuses.len() > MAX_WORKFLOW_STATUSES {
+            return invalid(format!(
+                "a workflow has 1 to {} statuses",
+                MAX_WORKFLOW_STATUSES
+            ));
+        }
+        let mut keys = HashSet::new();
+        for status in &self.statuses {
+            if !is_valid_status_key(&status.key) {
+                return invalid(format!(
+                    "status key '{}' must be A-Z, 0-9 or _, start with a letter and have at most {} characters",
+                    status.key, MAX_STATUS_KEY_LEN
+                ));
+            }
+            if status.label.chars().count() > MAX_STATUS_LABEL_LEN {
+                return invalid(format!(
+                    "label of {} is longer than {} characters",
+                    status.key, MAX_STATUS_LABEL_LEN
+                ));
+            }
+            for limit in [status.wip_limit, status.owner_wip_limit]
+                .into_iter()
+                .flatten()
+            {
+                if !(1..=MAX_WIP_LIMIT).contains(&limit) {
+                    return invalid(format!(
+                        "WIP limit of {} must be between 1 and {}",
+                        status.key, MAX_WIP_LIMIT
+                    ));
+                }
+            }
+            if !keys.insert(status.key.as_str()) {
+                return invalid(format!("status {} is listed twice", status.key));
+            }
+        }
+        for required in REQUIRED_STATUSES {
+            if !keys.contains(required.as_str()) {
+                return invalid(format!("status {} is required", required.as_str()));
+            }
+        }
+
+        let mut pairs = HashSet::new();
+        for t in &self.transitions {
+            for key in [&t.from, &t.to] {
+                if !keys.contains(key.as_str()) {
+                    return invalid(format!("transition uses unknown status {}", key));
+                }
+            }
+            if t.from == t.to {
+                return invalid(format!("transition {} -> {} goes nowhere", t.from, t.to));
+            }
+            if !pairs.insert((t.from.as_str(), t.to.as_str())) {
+                return invalid(format!("transition {} -> {} is listed twice", t.from, t.to));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn builtin_label(status: ProjectStatus) -> &'static str {
+    match status {
+        ProjectStatus::Backlog => "Backlog",
+        ProjectStatus::Planned => "Planned",
+        ProjectStatus::InProgress => "In progress",
+        ProjectStatus::Blocked => "Blocked",
+        ProjectStatus::Done => "Done",
+        ProjectStatus::Archived => "Archived",
+    }
+}
+
+fn is_valid_status_key(key: &str) -> bool {
+    key.len() <= MAX_STATUS_KEY_LEN
+        && key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(key: &str) -> WorkflowStatus {
+        WorkflowStatus {
+            key: key.to_string(),
+            label: String::new(),
+            wip_limit: None,
+            owner_wip_limit: None,
+        }
+    }
+
+    fn transition(from: &str, to: &str) -> WorkflowTransition {
+        WorkflowTransition {
+            from: from.to_string(),
+            to: to.to_string(),
+            note_required: false,
+        }
+    }
+
+    #[test]
+    fn builtin_matches_status_machine() {
+        let workflow = Workflow::builtin();
+        for from in ProjectStatus::all() {
+            for to in ProjectStatus::all() {
+                assert_eq!(
+                    workflow.can_transition(from.as_str(), to.as_str()),
+                    StatusMachine::can_transition(Some(*from), *to)
+                );
+                assert_eq!(
+                    workflow.note_required(from.as_str(), to.as_str()),
+                    StatusMachine::note_required(Some(*from), *to)
+                );
+            }
+        }
+        assert_eq!(
+            Workflow::new(workflow.statuses.clone(), workflow.transitions.clone()),
+            Ok(workflow)
+        );
+    }
+
+    #[test]
+    fn new_trims_and_defaults_labels() {
+        let workflow = Workflow::new(
+            vec![
+                status(" BACKLOG "),
+                status("REVIEW"),
+                status("DONE"),
+                status("ARCHIVED"),
+            ],
+            vec![transition("BACKLOG", " REVIEW")],
+        )
+        .unwrap();
+        assert_eq!(workflow.statuses[0].key, "BACKLOG");
+        assert_eq!(workflow.statuses[1].label, "REVIEW");
+        assert!(workflow.can_transition("BACKLOG", "REVIEW"));
+        assert!(!workflow.can_transition("REVIEW", "BACKLOG"));
+    }
+
+    #[test]
+    fn new_rejects_inconsistent_definitions() {
+        let base = || vec![status("BACKLOG"), status("DONE"), status("ARCHIVED")];
+        assert!(Workflow::new(vec![status("BACKLOG"), status("DONE")], vec![]).is_err());
+        assert!(Workflow::new(
+            base().into_iter().chain([status("in_review")]).collect(),
+            vec![]
+        )
+        .is_err());
+        assert!(
+            Workflow::new(base().into_iter().chain([status("DONE")]).collect(), vec![]).is_err()
+        );
+        assert!(Workflow::new(base(), vec![transition("BACKLOG", "REVIEW")]).is_err());
+        assert!(Workflow::new(base(), vec![transition("DONE", "DONE")]).is_err());
+        let zero_limit = WorkflowStatus {
+            wip_limit: Some(0),
+            ..status("REVIEW")
+        };
+        assert!(Workflow::new(base().into_iter().chain([zero_limit]).collect(), vec![]).is_err());
+        assert!(Workflow::new(
+            base(),
+            vec![transition("BACKLOG", "DONE"), transition("BACKLOG", "DONE")]
+        )
+        .is_err());
+    }
+}
@@ -13,6 +13,19 @@ pub struct PendingWipeInfo {
     pub created_at: String,
 }
 
+/// Counts behind a rejected status change (`WIP_LIMIT_EXCEEDED`), including the moved project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipLimitInfo {
+    pub status: String,
+    pub count: i64,
+    pub limit: Option<u32>,
+    pub owner_person_id: String,
+    /// Of `count`, projects owned by the moved project's owner.
+    pub owner_count: i64,
+    pub owner_limit: Option<u32>,
+}
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("{0}")]
@@ -36,6 +49,9 @@ pub enum AppError {
     #[error("Note is required for this transition")]
     NoteRequired,
 
+    #[error("WIP limit exceeded for {}", .0.status)]
+    WipLimitExceeded(WipLimitInfo),
+
     #[error("Assignment already active for this person on this project")]
     AssignmentAlreadyActive,
 
@@ -54,6 +70,30 @@ pub enum AppError {
     #[error("Sync blocked: wipe confirmation required")]
     SyncWipeConfirmRequired(PendingWipeInfo),
 
+    #[error("Operation disabled on this device: {0}")]
+    SyncOperationDisabled(String),
+
+    #[error("Unsupported sync format: {0}")]
+    SyncFormatUnsupported(String),
+
+    #[error("Read-only replica: {0} is not allowed on this device")]
+    ReadOnlyReplica(String),
+
+    #[error("Database encryption error: {0}")]
+    DbEncryption(String),
+
+    #[error("Data dir error: {0}")]
+    DataDir(String),
+
+    #[error("Backup error: {0}")]
+    Backup(String),
+
+    #[error("Report error: {0}")]
+    Report(String),
+
+    #[error("Export error: {0}")]
+    Export(String),
+
     #[error("Log file error: {0}")]
     LogFile(String),
 
@@ -71,12 +111,21 @@ impl AppError {
             Self::PartnerImmutable => "PARTNER_IMMUTABLE",
             Self::InvalidStatusTransition(_) => "INVALID_STATUS_TRANSITION",
             Self::NoteRequired => "NOTE_REQUIRED",
+            Self::WipLimitExceeded(_) => "WIP_LIMIT_EXCEEDED",
             Self::AssignmentAlreadyActive => "ASSIGNMENT_ALREADY_ACTIVE",
             Self::AssignmentNotActive => "ASSIGNMENT_NOT_ACTIVE",
             Self::SyncConfigIncomplete => "SYNC_CONFIG_INCOMPLETE",
             Self::SyncBucketNotOwned => "SYNC_BUCKET_NOT_OWNED",
             Self::Sync(_) => "SYNC_ERROR",
             Self::SyncWipeConfirmRequired(_) => "SYNC_WIPE_CONFIRM_REQUIRED",
+            Self::SyncOperationDisabled(_) => "SYNC_OPERATION_DISABLED",
+            Self::SyncFormatUnsupported(_) => "SYNC_FORMAT_UNSUPPORTED",
+            Self::ReadOnlyReplica(_) => "READ_ONLY_REPLICA",
+            Self::DbEncryption(_) => "DB_ENCRYPTION_ERROR",
+            Self::DataDir(_) => "DATA_DIR_ERROR",
+            Self::Backup(_) => "BACKUP_ERROR",
+            Self::Report(_) => "REPORT_ERROR",
+            Self::Export(_) => "EXPORT_ERROR",
             Self::LogFile(_) => "LOG_INVALID_FILE",
             Self::LogIo(_) => "LOG_IO_ERROR",
         }
@@ -85,6 +134,7 @@ impl AppError {
     pub fn to_serde(&self) -> AppErrorDto {
         let details = match self {
             Self::SyncWipeConfirmRequired(info) => serde_json::to_value(info).ok(),
+            Self::WipLimitExceeded(info) => serde_json::to_value(info).ok(),
             _ => None,
         };
         AppErrorDto {
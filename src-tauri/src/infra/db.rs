@@ -1,34 +1,286 @@
-//! SQLite connection and migrations.
+//! SQLite connection pool and migrations.
+//!
+//! 连接池：一个写连接 + 按需打开的只读连接（最多 `MAX_READERS` 个）。
+//! - 写连接独占（`DbPool::lock` / `get_connection`），所有写入与需要读己之写的流程都走它；
+//! - 只读连接（`get_read_connection`）在 WAL 模式下与写连接并发，列表、报表、导出不再排队等写锁；
+//! - 内存数据库（测试）没有只读连接，读请求回落到写连接；
+//! - 持锁期间 panic 不会让连接池失效：锁中毒被忽略（未提交的事务在 `Transaction` drop 时回滚）；
+//! - 加密库（见 `infra::encryption`）的口令随连接池保存，只读连接与导出副本使用同一口令。
 
-use rusqlite::Connection;
-use std::path::Path;
-use std::sync::Arc;
-use std::sync::Mutex;
+use crate::infra::encryption::{apply_key, open_database, open_with_key};
+use rusqlite::{Connection, OpenFlags};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, LockResult, Mutex, MutexGuard, PoisonError};
 use std::time::Duration;
 
+/// Upper bound of concurrently open read-only connections.
+pub const MAX_READERS: usize = 4;
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Clone)]
-pub struct DbPool(pub Arc<Mutex<Connection>>);
+pub struct DbPool(pub Arc<ConnectionPool>);
+
+pub struct ConnectionPool {
+    writer: Mutex<Connection>,
+    readers: Mutex<ReaderSlots>,
+    reader_returned: Condvar,
+}
+
+struct ReaderSlots {
+    /// Database file for read-only connections; `None` = in-memory (no readers).
+    path: Option<PathBuf>,
+    idle: Vec<Connection>,
+    open: usize,
+    /// Bumped when the database is replaced; stale readers are closed on return.
+    generation: u64,
+    /// SQLCipher passphrase; `None` = plaintext.
+    key: Option<String>,
+}
+
+impl ConnectionPool {
+    fn new(writer: Connection, path: Option<PathBuf>, key: Option<String>) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+            readers: Mutex::new(ReaderSlots {
+                path,
+                idle: Vec::new(),
+                open: 0,
+                generation: 0,
+                key,
+            }),
+            reader_returned: Condvar::new(),
+        }
+    }
+
+    /// Exclusive write connection. Never poisoned (see module docs); the `LockResult`
+    /// keeps the `Mutex` signature callers match on.
+    pub fn lock(&self) -> LockResult<MutexGuard<'_, Connection>> {
+        Ok(self.writer.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Read-only connection; falls back to the write connection for in-memory databases
+    /// or when a reader cannot be opened.
+    pub fn read(&self) -> ReadConnection<'_> {
+        match self.acquire_reader() {
+            Ok(Some((conn, generation))) => ReadConnection::Reader {
+                pool: self,
+                conn: Some(conn),
+                generation,
+            },
+            Ok(None) => ReadConnection::Writer(self.writer_guard()),
+            Err(e) => {
+                log::warn!("Read-only connection unavailable, using writer: {}", e);
+                ReadConnection::Writer(self.writer_guard())
+            }
+        }
+    }
+
+    /// Point the pool at another database (profile switch): `other`'s write connection
+    /// replaces ours and idle readers are closed. Errors if `other` is still shared.
+    pub fn replace_with(&self, other: DbPool) -> Result<(), crate::error::AppError> {
+        let other = Arc::try_unwrap(other.0).map_err(|_| {
+            crate::error::AppError::Db("replacement database is still in use".into())
+        })?;
+        let readers = other
+            .readers
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        let writer = other
+            .writer
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let mut guard = self.writer_guard();
+        let previous = std::mem::replace(&mut *guard, writer);
+        {
+            let mut slots = self.reader_slots();
+            slots.path = readers.path;
+            slots.key = readers.key;
+        }
+        self.close_idle_readers();
+        drop(guard);
+        drop(previous);
+        self.reader_returned.notify_all();
+        Ok(())
+    }
+
+    /// Close the database file, run `swap` on it while no pooled connection has it open, then
+    /// reopen it with `key` (the previous key if `swap` fails). Writers wait meanwhile; readers
+    /// still in use are closed on return. For in-place rewrites such as changing the passphrase.
+    pub fn reopen_with(
+        &self,
+        key: Option<String>,
+        swap: impl FnOnce(&Path) -> Result<(), crate::error::AppError>,
+    ) -> Result<(), crate::error::AppError> {
+        let mut guard = self.writer_guard();
+        let (path, previous_key) = {
+            let slots = self.reader_slots();
+            let path = slots.path.clone().ok_or_else(|| {
+                crate::error::AppError::Validation("in-memory database has no file".into())
+            })?;
+            (path, slots.key.clone())
+        };
+        let placeholder =
+            Connection::open_in_memory().map_err(|e| crate::error::AppError::Db(e.to_string()))?;
+        drop(std::mem::replace(&mut *guard, placeholder));
+        self.close_idle_readers();
+
+        let swapped = swap(&path);
+        let key = if swapped.is_ok() { key } else { previous_key };
+        let conn = open_with_key(&path, key.as_deref())?;
+        configure_connection(&conn)?;
+        *guard = conn;
+        self.reader_slots().key = key;
+        drop(guard);
+        self.reader_returned.notify_all();
+        swapped
+    }
+
+    /// The database is encrypted (opened with a passphrase).
+    pub fn is_encrypted(&self) -> bool {
+        self.reader_slots().key.is_some()
+    }
+
+    /// Passphrase of the open database, for copies that must stay encrypted.
+    pub(crate) fn key(&self) -> Option<String> {
+        self.reader_slots().key.clone()
+    }
+
+    /// Close idle readers; readers in use are closed on return (generation bump).
+    fn close_idle_readers(&self) {
+        let mut slots = self.reader_slots();
+        let idle = std::mem::take(&mut slots.idle);
+        slots.open -= idle.len();
+        slots.generation += 1;
+        drop(slots);
+        drop(idle);
+    }
+
+    fn writer_guard(&self) -> MutexGuard<'_, Connection> {
+        self.writer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn reader_slots(&self) -> MutexGuard<'_, ReaderSlots> {
+        self.readers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn acquire_reader(&self) -> Result<Option<(Connection, u64)>, crate::error::AppError> {
+        let mut slots = self.reader_slots();
+        loop {
+            let Some(path) = slots.path.clone() else {
+                return Ok(None);
+            };
+            let key = slots.key.clone();
+            if let Some(conn) = slots.idle.pop() {
+                return Ok(Some((conn, slots.generation)));
+            }
+            if slots.open < MAX_READERS {
+                slots.open += 1;
+                let generation = slots.generation;
+                drop(slots);
+                return match open_reader(&path, key.as_deref()) {
+                    Ok(conn) => Ok(Some((conn, generation))),
+                    Err(e) => {
+                        self.reader_slots().open -= 1;
+                        Err(e)
+                    }
+                };
+            }
+            slots = self
+                .reader_returned
+                .wait(slots)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn release_reader(&self, conn: Connection, generation: u64) {
+        let mut slots = self.reader_slots();
+        if generation == slots.generation {
+            slots.idle.push(conn);
+        } else {
+            // Opened against a replaced database: close it and free the slot.
+            slots.open -= 1;
+            drop(conn);
+        }
+        drop(slots);
+        self.reader_returned.notify_one();
+    }
+}
+
+/// Connection handed out by `get_read_connection`; derefs to `Connection`.
+pub enum ReadConnection<'a> {
+    Reader {
+        pool: &'a ConnectionPool,
+        conn: Option<Connection>,
+        generation: u64,
+    },
+    Writer(MutexGuard<'a, Connection>),
+}
+
+impl Deref for ReadConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            ReadConnection::Reader { conn, .. } => conn.as_ref().expect("reader present"),
+            ReadConnection::Writer(guard) => guard,
+        }
+    }
+}
+
+impl Drop for ReadConnection<'_> {
+    fn drop(&mut self) {
+        if let ReadConnection::Reader {
+            pool,
+            conn,
+            generation,
+        } = self
+        {
+            if let Some(conn) = conn.take() {
+                pool.release_reader(conn, *generation);
+            }
+        }
+    }
+}
 
 /// Initialize DB at path, run migrations, return managed pool.
 pub fn init_db(db_path: &Path) -> Result<DbPool, crate::error::AppError> {
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| crate::error::AppError::Db(e.to_string()))?;
     }
-    let mut conn =
-        Connection::open(db_path).map_err(|e| crate::error::AppError::Db(e.to_string()))?;
+    let (mut conn, key) = open_database(db_path)?;
     configure_connection(&conn)?;
     run_migrations(&mut conn)?;
-    Ok(DbPool(Arc::new(Mutex::new(conn))))
+    Ok(DbPool(Arc::new(ConnectionPool::new(
+        conn,
+        Some(db_path.to_path_buf()),
+        key,
+    ))))
 }
 
 fn configure_connection(conn: &Connection) -> Result<(), crate::error::AppError> {
     conn.pragma_update(None, "journal_mode", "WAL")
         .map_err(|e| crate::error::AppError::Db(e.to_string()))?;
-    conn.busy_timeout(Duration::from_secs(5))
+    conn.busy_timeout(BUSY_TIMEOUT)
         .map_err(|e| crate::error::AppError::Db(e.to_string()))?;
     Ok(())
 }
 
+fn open_reader(db_path: &Path, key: Option<&str>) -> Result<Connection, crate::error::AppError> {
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| crate::error::AppError::Db(e.to_string()))?;
+    apply_key(&conn, key)?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| crate::error::AppError::Db(e.to_string()))?;
+    conn.pragma_update(None, "query_only", true)
+        .map_err(|e| crate::error::AppError::Db(e.to_string()))?;
+    Ok(conn)
+}
+
 fn run_migrations(conn: &mut Connection) -> Result<(), crate::error::AppError> {
     let tx = conn
         .transaction()
@@ -71,6 +323,147 @@ fn run_migrations(conn: &mut Connection) -> Result<(), crate::error::AppError> {
             6,
             include_str!("../../migrations/0006_add_project_product_name.sql"),
         ),
+        (
+            7,
+            include_str!("../../migrations/0007_add_sync_history.sql"),
+        ),
+        (
+            8,
+            include_str!("../../migrations/0008_add_sync_staging.sql"),
+        ),
+        (
+            9,
+            include_str!("../../migrations/0009_add_sync_conflicts.sql"),
+        ),
+        (
+            10,
+            include_str!("../../migrations/0010_add_app_notices.sql"),
+        ),
+        (
+            11,
+            include_str!("../../migrations/0011_add_sync_record_base.sql"),
+        ),
+        (
+            12,
+            include_str!("../../migrations/0012_add_sync_tombstones.sql"),
+        ),
+        (
+            13,
+            include_str!("../../migrations/0013_add_sync_error_log.sql"),
+        ),
+        (
+            14,
+            include_str!("../../migrations/0014_add_sync_history_outcome.sql"),
+        ),
+        (
+            15,
+            include_str!("../../migrations/0015_add_sync_table_toggles.sql"),
+        ),
+        (
+            16,
+            include_str!("../../migrations/0016_add_project_templates.sql"),
+        ),
+        (
+            17,
+            include_str!("../../migrations/0017_add_recurrence_rules.sql"),
+        ),
+        (
+            18,
+            include_str!("../../migrations/0018_add_project_parent.sql"),
+        ),
+        (
+            19,
+            include_str!("../../migrations/0019_add_custom_fields.sql"),
+        ),
+        (
+            20,
+            include_str!("../../migrations/0020_add_saved_views.sql"),
+        ),
+        (
+            21,
+            include_str!("../../migrations/0021_add_project_rank.sql"),
+        ),
+        (
+            22,
+            include_str!("../../migrations/0022_add_project_size.sql"),
+        ),
+        (
+            23,
+            include_str!("../../migrations/0023_add_report_schedules.sql"),
+        ),
+        (
+            24,
+            include_str!("../../migrations/0024_add_link_check_results.sql"),
+        ),
+        (
+            25,
+            include_str!("../../migrations/0025_add_soft_delete.sql"),
+        ),
+        (26, include_str!("../../migrations/0026_add_action_log.sql")),
+        (
+            27,
+            include_str!("../../migrations/0027_add_project_tag_tombstones.sql"),
+        ),
+        (28, include_str!("../../migrations/0028_add_audit_log.sql")),
+        (
+            29,
+            include_str!("../../migrations/0029_add_sync_deferred_ops.sql"),
+        ),
+        (
+            30,
+            include_str!("../../migrations/0030_add_history_update_triggers.sql"),
+        ),
+        (
+            31,
+            include_str!("../../migrations/0031_add_project_cloned_from.sql"),
+        ),
+        (
+            32,
+            include_str!("../../migrations/0032_add_notifications.sql"),
+        ),
+        (33, include_str!("../../migrations/0033_add_webhooks.sql")),
+        (
+            34,
+            include_str!("../../migrations/0034_add_project_updated_index.sql"),
+        ),
+        (35, include_str!("../../migrations/0035_add_devices.sql")),
+        (
+            36,
+            include_str!("../../migrations/0036_add_read_only_replica.sql"),
+        ),
+        (
+            37,
+            include_str!("../../migrations/0037_add_comment_threads_and_reactions.sql"),
+        ),
+        (
+            38,
+            include_str!("../../migrations/0038_add_comment_mentions.sql"),
+        ),
+        (
+            39,
+            include_str!("../../migrations/0039_add_comment_revisions_and_pin_order.sql"),
+        ),
+        (
+            40,
+            include_str!("../../migrations/0040_add_checklist_items.sql"),
+        ),
+        (
+            41,
+            include_str!("../../migrations/0041_add_workflow_definitions.sql"),
+        ),
+        (
+            42,
+            include_str!("../../migrations/0042_add_sla_rules_and_project_flags.sql"),
+        ),
+        (
+            43,
+            include_str!("../../migrations/0043_add_project_estimate_and_budget.sql"),
+        ),
+        (
+            44,
+            include_str!("../../migrations/0044_add_budget_currency.sql"),
+        ),
+        (45, include_str!("../../migrations/0045_add_tags.sql")),
     ];
 
     for (version, sql) in MIGRATIONS {
@@ -103,14 +496,27 @@ fn run_migrations(conn: &mut Connection) -> Result<(), crate::error::AppError> {
     Ok(())
 }
 
-/// Get connection from pool (for use in commands).
-pub fn get_connection(pool: &DbPool) -> std::sync::MutexGuard<'_, Connection> {
-    pool.0.lock().expect("db lock")
+/// Get the write connection from the pool (for use in commands).
+pub fn get_connection(pool: &DbPool) -> MutexGuard<'_, Connection> {
+    pool.0.writer_guard()
+}
+
+/// Get a read-only connection (list, report and export use cases).
+pub fn get_read_connection(pool: &DbPool) -> ReadConnection<'_> {
+    pool.0.read()
+}
+
+/// In-memory database with all migrations applied, as scratch space (e.g. sync verification).
+pub fn open_memory_db() -> Result<DbPool, crate::error::AppError> {
+    let mut conn =
+        Connection::open_in_memory().map_err(|e| crate::error::AppError::Db(e.to_string()))?;
+    run_migrations(&mut conn)?;
+    Ok(DbPool(Arc::new(ConnectionPool::new(conn, None, None))))
 }
 
 /// Create an in-memory database with all migrations applied (for testing).
 pub fn init_test_db() -> DbPool {
     let mut conn = Connection::open_in_memory().expect("open in-memory DB");
     run_migrations(&mut conn).expect("run migrations");
-    DbPool(Arc::new(Mutex::new(conn)))
+    DbPool(Arc::new(ConnectionPool::new(conn, None, None)))
 }
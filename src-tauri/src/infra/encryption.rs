@@ -0,0 +1,261 @@
+//! At-rest encryption of the local database (SQLCipher, opt-in Cargo feature `encryption`).
+//!
+//! 设计要点：
+//! - 加密与否由文件头判断：明文库以 `SQLite format 3\0` 开头，SQLCipher 库整页加密、无此文件头。
+//! - 口令存于系统钥匙串（service = `projex`，account = 数据库文件路径），启动时自动解锁；
+//!   环境变量 `PROJEX_DB_PASSPHRASE` 优先（钥匙串不可用时的恢复手段）。
+//! - 更换口令时新口令先写入待定条目（account 加 `#pending`），文件替换完成后才转正；
+//!   中途崩溃时旧口令解不开新文件，启动会回退到待定条目并转正。
+//! - 加密 / 解密 / 更换口令都通过导出整库完成（`sqlcipher_export` 到新文件），不在原文件上 rekey：
+//!   WAL 模式下 rekey 不可靠，导出失败时原文件保持不变。
+//! - 未启用 feature 的构建：明文库照常工作，打开加密库返回 `DB_ENCRYPTION_ERROR`。
+
+use crate::error::AppError;
+use rusqlite::Connection;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+pub const PASSPHRASE_ENV: &str = "PROJEX_DB_PASSPHRASE";
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// First 16 bytes of every plaintext SQLite database.
+pub const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+#[cfg(feature = "encryption")]
+const KEYCHAIN_SERVICE: &str = "projex";
+/// Appended to the keychain account of a passphrase whose re-keyed file is not in place yet.
+const PENDING_ACCOUNT_SUFFIX: &str = "#pending";
+
+/// Whether this build can open and write encrypted databases.
+pub fn encryption_supported() -> bool {
+    cfg!(feature = "encryption")
+}
+
+/// The file exists, is not empty and lacks the plaintext SQLite header.
+pub fn is_encrypted_file(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut header = [0u8; 16];
+    match file.read_exact(&mut header) {
+        Ok(()) => &header != PLAINTEXT_HEADER,
+        // Shorter than a header: empty or just created, SQLite treats it as a new database.
+        Err(_) => false,
+    }
+}
+
+/// Open `path`, unlocking it with the stored passphrase when it is encrypted.
+pub fn open_database(path: &Path) -> Result<(Connection, Option<String>), AppError> {
+    if !is_encrypted_file(path) {
+        let conn = open_with_key(path, None)?;
+        return Ok((conn, None));
+    }
+    let failed = match load_passphrase(path)? {
+        Some(key) => match open_with_key(path, Some(&key)) {
+            Ok(conn) => return Ok((conn, Some(key))),
+            Err(e) => e,
+        },
+        None => AppError::DbEncryption(format!(
+            "{} is encrypted but no passphrase is stored; set {} to unlock it",
+            path.display(),
+            PASSPHRASE_ENV
+        )),
+    };
+    // A passphrase change stopped between the file swap and the keychain update: the new
+    // key is still in the pending entry.
+    let Ok(Some(key)) = load_pending_passphrase(path) else {
+        return Err(failed);
+    };
+    let conn = open_with_key(path, Some(&key)).map_err(|_| failed)?;
+    if let Err(e) = promote_pending_passphrase(path, &key) {
+        log::warn!("Failed to store the recovered database passphrase: {}", e);
+    }
+    Ok((conn, Some(key)))
+}
+
+/// Open `path` with an explicit key (`None` = plaintext) and check that it is readable.
+pub fn open_with_key(path: &Path, key: Option<&str>) -> Result<Connection, AppError> {
+    let conn = Connection::open(path).map_err(|e| AppError::Db(e.to_string()))?;
+    apply_key(&conn, key)?;
+    Ok(conn)
+}
+
+/// Set the key on a freshly opened connection; a no-op for plaintext databases.
+pub fn apply_key(conn: &Connection, key: Option<&str>) -> Result<(), AppError> {
+    let Some(key) = key else {
+        return Ok(());
+    };
+    if !encryption_supported() {
+        return Err(unsupported());
+    }
+    conn.pragma_update(None, "key", key)
+        .map_err(|e| AppError::DbEncryption(e.to_string()))?;
+    // The key is only checked on first access.
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map_err(|_| AppError::DbEncryption("wrong passphrase or damaged database".into()))?;
+    Ok(())
+}
+
+/// Write a consistent copy of `conn`'s main database to `target`, encrypted with
+/// `target_key` (`None` = plaintext). `source_encrypted` selects the export path:
+/// `VACUUM INTO` for plaintext → plaintext, `sqlcipher_export` otherwise.
+pub fn export_database(
+    conn: &Connection,
+    target: &Path,
+    source_encrypted: bool,
+    target_key: Option<&str>,
+) -> Result<(), AppError> {
+    let target_path = target.to_string_lossy();
+    if !source_encrypted && target_key.is_none() {
+        // VACUUM INTO writes a consistent, defragmented copy (WAL content included).
+        conn.execute("VACUUM INTO ?1", [target_path.as_ref()])?;
+        return Ok(());
+    }
+    if !encryption_supported() {
+        return Err(unsupported());
+    }
+    // An empty key attaches the target as plaintext.
+    conn.execute(
+        "ATTACH DATABASE ?1 AS export_target KEY ?2",
+        [target_path.as_ref(), target_key.unwrap_or("")],
+    )?;
+    let exported = conn
+        .query_row("SELECT sqlcipher_export('export_target')", [], |_| Ok(()))
+        .map_err(|e| AppError::DbEncryption(format!("Export failed: {}", e)));
+    let _ = conn.execute("DETACH DATABASE export_target", []);
+    exported
+}
+
+/// `ATTACH` a database file (e.g. a backup) that may be plaintext while `main` is encrypted.
+pub fn attach_database(conn: &Connection, path: &Path, alias: &str) -> Result<(), AppError> {
+    let path_str = path.to_string_lossy();
+    if encryption_supported() && !is_encrypted_file(path) {
+        // Without KEY, SQLCipher would reuse the main key on a plaintext file.
+        conn.execute(
+            &format!("ATTACH DATABASE ?1 AS {} KEY ''", alias),
+            [path_str.as_ref()],
+        )?;
+    } else {
+        conn.execute(
+            &format!("ATTACH DATABASE ?1 AS {}", alias),
+            [path_str.as_ref()],
+        )?;
+    }
+    Ok(())
+}
+
+/// The passphrase for `db_path`: `PROJEX_DB_PASSPHRASE`, else the keychain entry.
+pub fn load_passphrase(db_path: &Path) -> Result<Option<String>, AppError> {
+    if let Some(value) = std::env::var(PASSPHRASE_ENV).ok().filter(|v| !v.is_empty()) {
+        return Ok(Some(value));
+    }
+    keychain::load(&account(db_path))
+}
+
+/// Remember the passphrase for `db_path` on this device.
+pub fn store_passphrase(db_path: &Path, passphrase: &str) -> Result<(), AppError> {
+    keychain::store(&account(db_path), passphrase)
+}
+
+/// Forget the passphrase for `db_path`; missing entries are ignored.
+pub fn delete_passphrase(db_path: &Path) -> Result<(), AppError> {
+    keychain::delete(&account(db_path))
+}
+
+/// Keep a new passphrase next to the current one until the re-keyed file is in place.
+pub fn store_pending_passphrase(db_path: &Path, passphrase: &str) -> Result<(), AppError> {
+    keychain::store(&pending_account(db_path), passphrase)
+}
+
+pub fn load_pending_passphrase(db_path: &Path) -> Result<Option<String>, AppError> {
+    keychain::load(&pending_account(db_path))
+}
+
+pub fn delete_pending_passphrase(db_path: &Path) -> Result<(), AppError> {
+    keychain::delete(&pending_account(db_path))
+}
+
+/// The re-keyed file is in place: make the pending passphrase the current one.
+pub fn promote_pending_passphrase(db_path: &Path, passphrase: &str) -> Result<(), AppError> {
+    store_passphrase(db_path, passphrase)?;
+    delete_pending_passphrase(db_path)
+}
+
+/// Keychain account of `db_path`'s passphrase (the file path).
+fn account(db_path: &Path) -> String {
+    db_path.to_string_lossy().into_owned()
+}
+
+fn pending_account(db_path: &Path) -> String {
+    format!("{}{}", account(db_path), PENDING_ACCOUNT_SUFFIX)
+}
+
+/// `DB_ENCRYPTION_ERROR` unless this build includes SQLCipher.
+pub fn ensure_supported() -> Result<(), AppError> {
+    if encryption_supported() {
+        Ok(())
+    } else {
+        Err(unsupported())
+    }
+}
+
+fn unsupported() -> AppError {
+    AppError::DbEncryption("database encryption is not available in this build".into())
+}
+
+#[cfg(feature = "encryption")]
+mod keychain {
+    use super::KEYCHAIN_SERVICE;
+    use crate::error::AppError;
+
+    fn entry(account: &str) -> Result<keyring::Entry, AppError> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, account)
+            .map_err(|e| AppError::DbEncryption(format!("Keychain unavailable: {}", e)))
+    }
+
+    pub fn load(account: &str) -> Result<Option<String>, AppError> {
+        match entry(account)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(AppError::DbEncryption(format!(
+                "Keychain read failed: {}",
+                e
+            ))),
+        }
+    }
+
+    pub fn store(account: &str, passphrase: &str) -> Result<(), AppError> {
+        entry(account)?
+            .set_password(passphrase)
+            .map_err(|e| AppError::DbEncryption(format!("Keychain write failed: {}", e)))
+    }
+
+    pub fn delete(account: &str) -> Result<(), AppError> {
+        match entry(account)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(AppError::DbEncryption(format!(
+                "Keychain delete failed: {}",
+                e
+            ))),
+        }
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+mod keychain {
+    use crate::error::AppError;
+
+    pub fn load(_account: &str) -> Result<Option<String>, AppError> {
+        Ok(None)
+    }
+
+    pub fn store(_account: &str, _passphrase: &str) -> Result<(), AppError> {
+        Err(super::unsupported())
+    }
+
+    pub fn delete(_account: &str) -> Result<(), AppError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+//! HTTP client and response parsing for the optional exchange-rate fetch.
+//!
+//! 响应为常见的 JSON 格式 `{"base": "USD", "rates": {"EUR": 0.92, ...}}`（frankfurter、
+//! exchangerate.host 等均兼容），`rates` 表示 1 单位基准货币可兑换的各货币数量。
+//! 单个请求超时 `FETCH_TIMEOUT`，非 2xx 状态视为失败。
+
+use std::time::Duration;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+const USER_AGENT: &str = concat!("Projex/", env!("CARGO_PKG_VERSION"), " exchange-rates");
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatesResponse {
+    /// `base` of the response, if present.
+    pub base: Option<String>,
+    /// Units of each currency per 1 unit of the base currency.
+    pub rates: Vec<(String, f64)>,
+}
+
+/// Parse a rates response; entries that are not numbers are skipped.
+pub fn parse_rates_response(body: &str) -> Result<RatesResponse, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("invalid JSON: {}", e))?;
+    let rates = value["rates"]
+        .as_object()
+        .ok_or_else(|| "response has no \"rates\" object".to_string())?
+        .iter()
+        .filter_map(|(code, rate)| rate.as_f64().map(|r| (code.clone(), r)))
+        .collect();
+    Ok(RatesResponse {
+        base: value["base"].as_str().map(str::to_string),
+        rates,
+    })
+}
+
+/// Blocking HTTP client for one fetch.
+pub struct RatesClient {
+    client: reqwest::blocking::Client,
+}
+
+impl RatesClient {
+    pub fn new() -> Result<Self, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { client })
+    }
+
+    /// GET the response body. Must not be called from an async context (use `spawn_blocking`).
+    pub fn get(&self, url: &str) -> Result<String, String> {
+        let resp = self
+            .client
+            .get(url)
+            .header("Accept", "application/json")
+            .send()
+            .map_err(|e| {
+                if e.is_timeout() {
+                    "timed out".to_string()
+                } else {
+                    e.to_string()
+                }
+            })?;
+        let status = resp.status().as_u16();
+        if !(200..300).contains(&status) {
+            return Err(format!("HTTP {}", status));
+        }
+        resp.text().map_err(|e| e.to_string())
+    }
+}
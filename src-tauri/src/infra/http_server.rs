@@ -0,0 +1,250 @@
+//! Minimal HTTP/1.1 server for the local read-only API.
+//!
+//! 只实现只读 JSON API 所需的子集：解析请求行、请求头和查询参数（不读取请求体），
+//! 每个连接处理一个请求后关闭（`Connection: close`）。只应绑定在回环地址上。
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Request line + headers larger than this are rejected.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+/// Clients must send the request head within this time.
+pub const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    /// Decoded path without the query string.
+    pub path: String,
+    /// Decoded query parameters in order (repeated keys are kept).
+    pub query: Vec<(String, String)>,
+    /// Header names are lowercased.
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// JSON text.
+    pub body: String,
+}
+
+/// Parse a request head (everything before the blank line).
+pub fn parse_request_head(head: &str) -> Result<HttpRequest, String> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err("malformed request line".into());
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(format!("unsupported version: {}", version));
+    }
+    let (raw_path, raw_query) = target.split_once('?').unwrap_or((target, ""));
+    let path = percent_decode(raw_path).ok_or("malformed path")?;
+    let mut query = Vec::new();
+    for pair in raw_query.split('&').filter(|p| !p.is_empty()) {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        let k = percent_decode(&k.replace('+', " ")).ok_or("malformed query")?;
+        let v = percent_decode(&v.replace('+', " ")).ok_or("malformed query")?;
+        query.push((k, v));
+    }
+    let mut headers = Vec::new();
+    for line in lines.filter(|l| !l.is_empty()) {
+        let (name, value) = line.split_once(':').ok_or("malformed header")?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+    Ok(HttpRequest {
+        method: method.to_string(),
+        path,
+        query,
+        headers,
+    })
+}
+
+/// `%XX` decoding; `None` for invalid escapes or non-UTF-8 results.
+pub fn percent_decode(raw: &str) -> Option<String> {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = raw.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+pub fn render_response(response: &HttpResponse, head_only: bool) -> Vec<u8> {
+    let mut out = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.body.len()
+    )
+    .into_bytes();
+    if !head_only {
+        out.extend_from_slice(response.body.as_bytes());
+    }
+    out
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Accept connections until the task is aborted; `handle` runs for every parsed request.
+pub async fn serve<F, Fut>(listener: TcpListener, handle: Arc<F>)
+where
+    F: Fn(HttpRequest) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = HttpResponse> + Send + 'static,
+{
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let handle = handle.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(stream, handle.as_ref()).await {
+                        log::debug!("HTTP API connection error: {}", e);
+                    }
+                });
+            }
+            Err(e) => {
+                log::warn!("HTTP API accept failed: {}", e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+async fn serve_connection<F, Fut>(mut stream: TcpStream, handle: &F) -> std::io::Result<()>
+where
+    F: Fn(HttpRequest) -> Fut,
+    Fut: Future<Output = HttpResponse>,
+{
+    let head = match tokio::time::timeout(READ_TIMEOUT, read_head(&mut stream)).await {
+        Ok(head) => head?,
+        Err(_) => {
+            let response = error_response(408, "request head not received in time");
+            stream.write_all(&render_response(&response, false)).await?;
+            return stream.shutdown().await;
+        }
+    };
+    let (response, head_only) = match head {
+        None => (error_response(431, "request head too large"), false),
+        Some(head) => match parse_request_head(&head) {
+            Ok(request) => {
+                let head_only = request.method == "HEAD";
+                (handle(request).await, head_only)
+            }
+            Err(e) => (error_response(400, &e), false),
+        },
+    };
+    stream
+        .write_all(&render_response(&response, head_only))
+        .await?;
+    stream.shutdown().await
+}
+
+/// Read up to the blank line. `Ok(None)` when the head exceeds `MAX_HEAD_BYTES`.
+pub async fn read_head(stream: &mut TcpStream) -> std::io::Result<Option<String>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            buf.truncate(end);
+            return Ok(Some(String::from_utf8_lossy(&buf).into_owned()));
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Ok(None);
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+fn error_response(status: u16, message: &str) -> HttpResponse {
+    HttpResponse {
+        status,
+        body: serde_json::json!({ "error": { "code": error_code(status), "message": message } })
+            .to_string(),
+    }
+}
+
+/// Error code for failures the server answers itself (before a request reaches the API).
+fn error_code(status: u16) -> &'static str {
+    match status {
+        400 => "BAD_REQUEST",
+        408 => "REQUEST_TIMEOUT",
+        431 => "REQUEST_HEADER_TOO_LARGE",
+        _ => "INTERNAL_ERROR",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_path_query_and_headers() {
+        let req = parse_request_head(
+            "GET /api/v1/projects?status=DONE&status=BLOCKED&q=a+b%26c HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer abc",
+        )
+        .unwrap();
+        assert_eq!(req.method, "GET");
+        assert_eq!(req.path, "/api/v1/projects");
+        assert_eq!(
+            req.query,
+            vec![
+                ("status".to_string(), "DONE".to_string()),
+                ("status".to_string(), "BLOCKED".to_string()),
+                ("q".to_string(), "a b&c".to_string()),
+            ]
+        );
+        assert_eq!(req.header("authorization"), Some("Bearer abc"));
+        assert!(parse_request_head("GET /").is_err());
+        assert!(parse_request_head("GET /%zz HTTP/1.1").is_err());
+    }
+
+    #[test]
+    fn error_code_follows_status() {
+        let code = |status| {
+            let body: serde_json::Value =
+                serde_json::from_str(&error_response(status, "x").body).unwrap();
+            body["error"]["code"].as_str().unwrap().to_string()
+        };
+        assert_eq!(code(400), "BAD_REQUEST");
+        assert_eq!(code(408), "REQUEST_TIMEOUT");
+        assert_eq!(code(431), "REQUEST_HEADER_TOO_LARGE");
+    }
+}
@@ -0,0 +1,93 @@
+//! Minimal iCalendar (RFC 5545) writer for all-day events.
+//!
+//! 输出 `VCALENDAR` + 全天 `VEVENT`（`DTSTART;VALUE=DATE`，`DTEND` 为次日）；行以 CRLF 结尾，
+//! 超过 75 字节的内容行按 RFC 折行（不拆分 UTF-8 字符）；文本值转义 `\ ; , 换行`。
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+const PRODID: &str = "-//Projex//Projex//EN";
+const MAX_LINE_OCTETS: usize = 75;
+
+/// One all-day event.
+pub struct IcsEvent {
+    /// Stable across exports so calendar clients update events instead of duplicating them.
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: Option<String>,
+    pub categories: Vec<String>,
+}
+
+/// Render a calendar; `stamp` is written as every event's `DTSTAMP`.
+pub fn render_calendar(name: &str, events: &[IcsEvent], stamp: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, &format!("PRODID:{}", PRODID));
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "METHOD:PUBLISH");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape_text(name)));
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", escape_text(&event.uid)));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(
+            &mut out,
+            &format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")),
+        );
+        let end = event.date.succ_opt().unwrap_or(event.date);
+        push_line(
+            &mut out,
+            &format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+        );
+        push_line(
+            &mut out,
+            &format!("SUMMARY:{}", escape_text(&event.summary)),
+        );
+        if let Some(description) = event.description.as_deref() {
+            push_line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape_text(description)),
+            );
+        }
+        if !event.categories.is_empty() {
+            let categories: Vec<String> = event.categories.iter().map(|c| escape_text(c)).collect();
+            push_line(&mut out, &format!("CATEGORIES:{}", categories.join(",")));
+        }
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Append a content line, folding it into 75-octet chunks (continuations start with a space).
+fn push_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if octets + len > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            octets = 1;
+        }
+        out.push(c);
+        octets += len;
+    }
+    out.push_str("\r\n");
+}
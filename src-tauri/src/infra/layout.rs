@@ -0,0 +1,281 @@
+//! Data directory layout: legacy layout migration and custom data dir locations.
+//!
+//! ## Legacy `default/` layout
+//!
+//! 旧版 Android 后台同步把数据放在 `com.nickdu.projex/default/projex.db`，
+//! 桌面端统一使用 `profiles/<name>/app.db`。启动时（打开数据库之前）检测旧布局并迁移：
+//! - 先把文件逐个 rename 进同级暂存目录 `profiles/.default.migrating`（同一文件系统内 rename 是原子的）；
+//! - 全部就位后再把暂存目录整体 rename 为目标目录，目标要么完整出现、要么不存在；
+//! - 中途崩溃时暂存目录保留，下次启动继续迁移剩余文件。
+//!
+//! 迁移期间持有旧目录的 `sync.lock`，与 Android 后台 Worker 互斥。
+//!
+//! ## Custom data dir
+//!
+//! profile 数据目录可以指向任意路径（加密卷、其它磁盘）。指针文件放在默认位置旁：
+//! `profiles/<name>.location.json`，因为数据库本身随目录移动，不能存配置。
+//! 移动后旧目录在下次启动（新目录已加锁）时清理，只删除已知文件。
+
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Legacy entry name -> name inside the profile data dir.
+const LEGACY_ENTRIES: &[(&str, &str)] = &[
+    ("projex.db", "app.db"),
+    ("projex.db-wal", "app.db-wal"),
+    ("projex.db-shm", "app.db-shm"),
+    ("projex.db-journal", "app.db-journal"),
+    ("app.lock", "app.lock"),
+    ("logs", "logs"),
+];
+
+const LEGACY_LOCK: &str = "sync.lock";
+
+/// Files and dirs owned by a profile data dir (removed when cleaning up after a move).
+const PROFILE_ENTRIES: &[&str] = &[
+    "app.db",
+    "app.db-wal",
+    "app.db-shm",
+    "app.db-journal",
+    "app.lock",
+    "sync.lock",
+    "logs",
+    "backups",
+];
+
+/// Sub-directories copied along with the database when the data dir moves.
+pub const PROFILE_DATA_SUBDIRS: &[&str] = &["logs", "backups"];
+
+/// Contents of `profiles/<name>.location.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataDirLocation {
+    /// Custom data dir; `None` means the default `profiles/<name>/`.
+    pub data_dir: Option<PathBuf>,
+    /// Previous data dir to remove on next startup after a move.
+    pub pending_cleanup: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LegacyLayoutMigration {
+    /// Legacy files were moved into the profile data dir.
+    Migrated {
+        from: PathBuf,
+        to: PathBuf,
+        moved: Vec<String>,
+    },
+    /// Both layouts hold a database; nothing was touched.
+    SkippedTargetExists { from: PathBuf, to: PathBuf },
+}
+
+/// Move a legacy `default/` layout into `target_dir`. Returns `Ok(None)` when there is
+/// nothing to migrate. Blocks while another process holds the legacy lock.
+pub fn migrate_legacy_layout(
+    legacy_dir: &Path,
+    target_dir: &Path,
+) -> Result<Option<LegacyLayoutMigration>, String> {
+    let staging_dir = staging_dir_for(target_dir);
+    let legacy_db = legacy_dir.join("projex.db");
+    if !legacy_db.exists() && !staging_dir.exists() {
+        return Ok(None);
+    }
+
+    if target_dir.join("app.db").exists() {
+        return Ok(Some(LegacyLayoutMigration::SkippedTargetExists {
+            from: legacy_dir.to_path_buf(),
+            to: target_dir.to_path_buf(),
+        }));
+    }
+
+    let lock_path = legacy_dir.join(LEGACY_LOCK);
+    let lock_file = if legacy_dir.exists() {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| format!("Failed to open legacy lock {:?}: {}", lock_path, e))?;
+        // Wait for an in-flight background sync instead of starting on an empty database.
+        file.lock_exclusive()
+            .map_err(|e| format!("Failed to lock legacy dir {:?}: {}", legacy_dir, e))?;
+        Some(file)
+    } else {
+        None
+    };
+
+    fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("Failed to create staging dir {:?}: {}", staging_dir, e))?;
+
+    let mut moved = Vec::new();
+    for (legacy_name, new_name) in LEGACY_ENTRIES {
+        let src = legacy_dir.join(legacy_name);
+        let dst = staging_dir.join(new_name);
+        if !src.exists() || dst.exists() {
+            continue;
+        }
+        fs::rename(&src, &dst)
+            .map_err(|e| format!("Failed to move {:?} to {:?}: {}", src, dst, e))?;
+        moved.push((*legacy_name).to_string());
+    }
+
+    activate_staging_dir(&staging_dir, target_dir)?;
+
+    // The lock file itself goes last, after it has been released.
+    drop(lock_file);
+    if lock_path.exists() {
+        let dst = target_dir.join(LEGACY_LOCK);
+        let result = if dst.exists() {
+            fs::remove_file(&lock_path)
+        } else {
+            fs::rename(&lock_path, &dst)
+        };
+        if let Err(e) = result {
+            log::warn!("Failed to move legacy lock {:?}: {}", lock_path, e);
+        }
+    }
+    // Only removes the legacy dir when empty; leftovers stay for manual inspection.
+    let _ = fs::remove_dir(legacy_dir);
+
+    Ok(Some(LegacyLayoutMigration::Migrated {
+        from: legacy_dir.to_path_buf(),
+        to: target_dir.to_path_buf(),
+        moved,
+    }))
+}
+
+/// Swap the staging dir into place. If the target dir already exists without a database
+/// (e.g. only logs), merge entries into it with the database moved last.
+fn activate_staging_dir(staging_dir: &Path, target_dir: &Path) -> Result<(), String> {
+    if !target_dir.exists() {
+        return fs::rename(staging_dir, target_dir).map_err(|e| {
+            format!(
+                "Failed to activate migrated data dir {:?}: {}",
+                target_dir, e
+            )
+        });
+    }
+
+    for (_, name) in LEGACY_ENTRIES.iter().rev() {
+        let src = staging_dir.join(name);
+        if src.exists() {
+            merge_entry(&src, &target_dir.join(name))?;
+        }
+    }
+    let _ = fs::remove_dir_all(staging_dir);
+    Ok(())
+}
+
+/// Move `src` to `dst`; directories (logs) are merged one level deep, keeping existing files.
+fn merge_entry(src: &Path, dst: &Path) -> Result<(), String> {
+    if !dst.exists() {
+        return fs::rename(src, dst)
+            .map_err(|e| format!("Failed to move {:?} to {:?}: {}", src, dst, e));
+    }
+    if src.is_dir() && dst.is_dir() {
+        let entries = fs::read_dir(src).map_err(|e| format!("Failed to read {:?}: {}", src, e))?;
+        for entry in entries.flatten() {
+            let target = dst.join(entry.file_name());
+            if !target.exists() {
+                fs::rename(entry.path(), &target).map_err(|e| {
+                    format!("Failed to move {:?} to {:?}: {}", entry.path(), target, e)
+                })?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn staging_dir_for(target_dir: &Path) -> PathBuf {
+    let name = target_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target_dir.with_file_name(format!(".{}.migrating", name))
+}
+
+/// `profiles/<name>/` -> `profiles/<name>.location.json`.
+pub fn location_file_for(default_profile_dir: &Path) -> PathBuf {
+    let name = default_profile_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    default_profile_dir.with_file_name(format!("{}.location.json", name))
+}
+
+/// Missing file means the default location.
+pub fn read_data_dir_location(path: &Path) -> Result<DataDirLocation, String> {
+    match fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .map_err(|e| format!("Invalid data dir location file {:?}: {}", path, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DataDirLocation::default()),
+        Err(e) => Err(format!("Failed to read {:?}: {}", path, e)),
+    }
+}
+
+/// Write via temp file + rename so a crash never leaves a half-written pointer.
+pub fn write_data_dir_location(path: &Path, location: &DataDirLocation) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let raw = serde_json::to_string_pretty(location)
+        .map_err(|e| format!("Failed to serialize data dir location: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(|e| format!("Failed to write {:?}: {}", tmp, e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {:?}: {}", path, e))
+}
+
+/// Whether another process (or this one) holds the profile lock of `dir`.
+pub fn profile_dir_in_use(dir: &Path) -> bool {
+    let lock_path = dir.join("app.lock");
+    if !lock_path.exists() {
+        return false;
+    }
+    match OpenOptions::new().read(true).write(true).open(&lock_path) {
+        Ok(lock) => lock.try_lock_exclusive().is_err(),
+        Err(_) => true,
+    }
+}
+
+/// Remove a previous profile data dir after a move. Only known entries are deleted and
+/// the dir itself only when empty. Skips (returns `Ok(false)`) while it is still locked.
+pub fn cleanup_previous_data_dir(dir: &Path) -> Result<bool, String> {
+    if !dir.exists() {
+        return Ok(true);
+    }
+    let lock_path = dir.join("app.lock");
+    let lock = if lock_path.exists() {
+        let lock = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| format!("Failed to open {:?}: {}", lock_path, e))?;
+        if lock.try_lock_exclusive().is_err() {
+            return Ok(false);
+        }
+        Some(lock)
+    } else {
+        None
+    };
+
+    // The lock file goes last, once released.
+    for name in PROFILE_ENTRIES.iter().filter(|n| **n != "app.lock") {
+        let path = dir.join(name);
+        let result = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else if path.exists() {
+            fs::remove_file(&path)
+        } else {
+            continue;
+        };
+        result.map_err(|e| format!("Failed to remove {:?}: {}", path, e))?;
+    }
+    drop(lock);
+    if lock_path.exists() {
+        fs::remove_file(&lock_path)
+            .map_err(|e| format!("Failed to remove {:?}: {}", lock_path, e))?;
+    }
+    let _ = fs::remove_dir(dir);
+    Ok(true)
+}
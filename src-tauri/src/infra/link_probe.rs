@@ -0,0 +1,68 @@
+//! HTTP reachability probe for the link health check.
+//!
+//! 先发 HEAD，服务器不支持（405 / 501）时改用 GET；跟随重定向，单个请求超时 `PROBE_TIMEOUT`。
+//! 结果分三类：可达、失效（连接失败 / 超时 / 404 / 410 / 5xx）、无法验证（401 / 403 等需要登录，
+//! 例如未登录访问 SharePoint）。
+
+use std::time::Duration;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+const USER_AGENT: &str = concat!("Projex/", env!("CARGO_PKG_VERSION"), " link-check");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    Ok { status: u16 },
+    Dead { status: Option<u16>, error: String },
+    Unverified { status: u16 },
+}
+
+/// Classify a final HTTP status code.
+pub fn classify_status(status: u16) -> ProbeOutcome {
+    match status {
+        200..=399 => ProbeOutcome::Ok { status },
+        401 | 403 | 407 | 429 => ProbeOutcome::Unverified { status },
+        404 | 410 | 500..=599 => ProbeOutcome::Dead {
+            status: Some(status),
+            error: format!("HTTP {}", status),
+        },
+        _ => ProbeOutcome::Unverified { status },
+    }
+}
+
+/// Blocking HTTP client shared by the probes of one run.
+pub struct LinkProbe {
+    client: reqwest::blocking::Client,
+}
+
+impl LinkProbe {
+    pub fn new() -> Result<Self, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { client })
+    }
+
+    /// Must not be called from an async context (use `spawn_blocking`).
+    pub fn probe(&self, url: &str) -> ProbeOutcome {
+        let status = match self.client.head(url).send() {
+            Ok(resp) if matches!(resp.status().as_u16(), 405 | 501) => {
+                self.client.get(url).send().map(|r| r.status().as_u16())
+            }
+            Ok(resp) => Ok(resp.status().as_u16()),
+            Err(e) => Err(e),
+        };
+        match status {
+            Ok(status) => classify_status(status),
+            Err(e) => ProbeOutcome::Dead {
+                status: None,
+                error: if e.is_timeout() {
+                    "timed out".to_string()
+                } else {
+                    e.to_string()
+                },
+            },
+        }
+    }
+}
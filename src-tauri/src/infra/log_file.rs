@@ -0,0 +1,153 @@
+//! Profile log files that follow the active profile.
+//!
+//! 日志插件的 Folder target 在初始化时固定路径，无法随运行时切换 profile 变化；
+//! 因此文件日志经由 `ProfileLogWriter` 写出：每次写入按当前 profile 解析目标文件，切换后自动改写到新目录。
+//! 轮转策略与原来一致：单个文件最大 `MAX_LOG_FILE_BYTES`，超过后依次改名为 `<name>.log.1` … `.log.N`，
+//! 保留最近 `KEEP_ROTATED_FILES` 个。
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+pub const KEEP_ROTATED_FILES: usize = 5;
+
+/// Which of the two per-profile log files a writer feeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogStream {
+    Webview,
+    Rust,
+}
+
+#[derive(Debug, Clone)]
+struct ActiveLogFiles {
+    dir: PathBuf,
+    /// File names without extension, e.g. `webview-default`.
+    webview: String,
+    rust: String,
+}
+
+static ACTIVE_LOG_FILES: RwLock<Option<ActiveLogFiles>> = RwLock::new(None);
+
+/// Point all writers at `log_dir`; later lines go to `<name>.log` there.
+pub fn set_active_log_files(log_dir: &Path, webview_name: &str, rust_name: &str) {
+    let files = ActiveLogFiles {
+        dir: log_dir.to_path_buf(),
+        webview: webview_name.to_string(),
+        rust: rust_name.to_string(),
+    };
+    match ACTIVE_LOG_FILES.write() {
+        Ok(mut guard) => *guard = Some(files),
+        Err(poisoned) => *poisoned.into_inner() = Some(files),
+    }
+}
+
+fn active_path(stream: LogStream) -> Option<PathBuf> {
+    let guard = ACTIVE_LOG_FILES.read().ok()?;
+    let files = guard.as_ref()?;
+    let name = match stream {
+        LogStream::Webview => &files.webview,
+        LogStream::Rust => &files.rust,
+    };
+    Some(files.dir.join(format!("{}.log", name)))
+}
+
+/// `io::Write` sink for the log dispatcher. Lines are dropped until a profile is active.
+pub struct ProfileLogWriter {
+    stream: LogStream,
+    open: Option<(PathBuf, File, u64)>,
+}
+
+impl ProfileLogWriter {
+    pub fn new(stream: LogStream) -> Self {
+        Self { stream, open: None }
+    }
+
+    fn file_for(&mut self, path: PathBuf, incoming: u64) -> io::Result<&mut File> {
+        let reopen = match &self.open {
+            Some((open_path, _, size)) => {
+                *open_path != path || size + incoming > MAX_LOG_FILE_BYTES
+            }
+            None => true,
+        };
+        if reopen {
+            self.open = None;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if size > 0 && size + incoming > MAX_LOG_FILE_BYTES {
+                rotate(&path, KEEP_ROTATED_FILES)?;
+            }
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+            self.open = Some((path, file, size));
+        }
+        Ok(&mut self.open.as_mut().expect("log file opened above").1)
+    }
+}
+
+impl Write for ProfileLogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Some(path) = active_path(self.stream) else {
+            return Ok(buf.len());
+        };
+        let written = self.file_for(path, buf.len() as u64)?.write(buf)?;
+        if let Some((_, _, size)) = self.open.as_mut() {
+            *size += written as u64;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.open.as_mut() {
+            Some((_, file, _)) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `x.log` -> `x.log.1`, `x.log.1` -> `x.log.2`, …; the oldest beyond `keep` is removed.
+fn rotate(path: &Path, keep: usize) -> io::Result<()> {
+    let rotated = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+    let oldest = rotated(keep);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+    for n in (1..keep).rev() {
+        let from = rotated(n);
+        if from.exists() {
+            fs::rename(&from, rotated(n + 1))?;
+        }
+    }
+    fs::rename(path, rotated(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_shifts_files_and_drops_the_oldest() {
+        let dir = std::env::temp_dir().join(format!("projex-logrotate-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rust-default.log");
+        for (name, content) in [
+            ("rust-default.log", "current"),
+            ("rust-default.log.1", "one"),
+            ("rust-default.log.2", "two"),
+        ] {
+            fs::write(dir.join(name), content).unwrap();
+        }
+
+        rotate(&path, 2).unwrap();
+
+        assert!(!path.exists());
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("rust-default.log.1"), "current");
+        assert_eq!(read("rust-default.log.2"), "one");
+        assert!(!dir.join("rust-default.log.3").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
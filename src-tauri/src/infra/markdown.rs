@@ -0,0 +1,252 @@
+//! Markdown helpers: rich-text doc JSON (TipTap / ProseMirror) → GitHub-flavored Markdown.
+//!
+//! 支持编辑器用到的节点：段落、标题、列表（含任务列表）、引用、代码块、分隔线、图片、表格、
+//! 提及（`@名称`）；行内标记支持粗体、斜体、删除线、行内代码、链接。下划线与高亮没有 Markdown
+//! 对应写法，只保留文字。不是 doc JSON 的内容（旧数据、模板生成的纯文本评论）原样输出。
+
+use serde_json::Value;
+
+/// Render stored comment / description content as Markdown.
+pub fn rich_text_to_markdown(content: &str) -> String {
+    match serde_json::from_str::<Value>(content) {
+        Ok(doc) if node_type(&doc) == "doc" => blocks(children(&doc)),
+        _ => content.trim().to_string(),
+    }
+}
+
+/// Escape characters with inline Markdown meaning (including `|`, so text is safe in tables).
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// GFM table; cells are inserted verbatim (escape them first), newlines become `<br>`.
+pub fn table(header: &[String], rows: &[Vec<String>]) -> String {
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain(std::iter::once(header.len()))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let line = |cells: &[String]| {
+        let mut out = String::from("|");
+        for i in 0..columns {
+            let cell = cells.get(i).map(String::as_str).unwrap_or("");
+            out.push(' ');
+            out.push_str(&cell.trim().replace('\n', "<br>"));
+            out.push_str(" |");
+        }
+        out
+    };
+    let mut lines = vec![line(header), format!("|{}", " --- |".repeat(columns))];
+    lines.extend(rows.iter().map(|r| line(r)));
+    lines.join("\n")
+}
+
+fn node_type(node: &Value) -> &str {
+    node.get("type").and_then(Value::as_str).unwrap_or("")
+}
+
+fn children(node: &Value) -> &[Value] {
+    node.get("content")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+fn attr<'a>(node: &'a Value, name: &str) -> Option<&'a Value> {
+    node.get("attrs").and_then(|a| a.get(name))
+}
+
+fn attr_str<'a>(node: &'a Value, name: &str) -> &'a str {
+    attr(node, name).and_then(Value::as_str).unwrap_or("")
+}
+
+/// Block nodes separated by blank lines.
+fn blocks(nodes: &[Value]) -> String {
+    nodes
+        .iter()
+        .map(block)
+        .filter(|b| !b.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn block(node: &Value) -> String {
+    match node_type(node) {
+        "paragraph" => inline(children(node)),
+        "heading" => {
+            let level = attr(node, "level")
+                .and_then(Value::as_u64)
+                .unwrap_or(1)
+                .clamp(1, 6) as usize;
+            format!("{} {}", "#".repeat(level), inline(children(node)))
+        }
+        "blockquote" => prefix_lines(&blocks(children(node)), "> ", "> "),
+        "codeBlock" => {
+            let code: String = children(node)
+                .iter()
+                .filter_map(|t| t.get("text").and_then(Value::as_str))
+                .collect();
+            let fence = if code.contains("```") { "~~~~" } else { "```" };
+            format!(
+                "{}{}\n{}\n{}",
+                fence,
+                attr_str(node, "language"),
+                code.trim_end_matches('\n'),
+                fence
+            )
+        }
+        "horizontalRule" => "---".to_string(),
+        "bulletList" => list(children(node), |_, _| "- ".to_string()),
+        "orderedList" => {
+            let start = attr(node, "start").and_then(Value::as_u64).unwrap_or(1) as usize;
+            list(children(node), |i, _| format!("{}. ", start + i))
+        }
+        "taskList" => list(children(node), |_, item| {
+            let checked = attr(item, "checked")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            if checked { "- [x] " } else { "- [ ] " }.to_string()
+        }),
+        "table" => {
+            let mut rows: Vec<Vec<String>> = children(node)
+                .iter()
+                .map(|row| {
+                    children(row)
+                        .iter()
+                        .map(|cell| {
+                            blocks(children(cell))
+                                .replace("\\\n", "\n")
+                                .replace("\n\n", "\n")
+                        })
+                        .collect()
+                })
+                .collect();
+            if rows.is_empty() {
+                return String::new();
+            }
+            // GFM needs a header row; the editor's first row is the header by default.
+            let header = rows.remove(0);
+            table(&header, &rows)
+        }
+        "image" => format!(
+            "![{}]({})",
+            escape(attr_str(node, "alt")),
+            attr_str(node, "src")
+        ),
+        _ if node.get("text").is_some() || node_type(node) == "mention" => {
+            inline(std::slice::from_ref(node))
+        }
+        _ => blocks(children(node)),
+    }
+}
+
+/// List items: the first line gets the marker, continuation lines are indented to match.
+fn list(items: &[Value], marker: impl Fn(usize, &Value) -> String) -> String {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let marker = marker(i, item);
+            let body = blocks(children(item));
+            prefix_lines(&body, &marker, &" ".repeat(marker.len()))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+    if text.is_empty() {
+        return first.trim_end().to_string();
+    }
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 { first } else { rest };
+            if line.is_empty() {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn inline(nodes: &[Value]) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node_type(node) {
+            "text" => {
+                let text = node.get("text").and_then(Value::as_str).unwrap_or("");
+                out.push_str(&marked_text(text, node.get("marks")));
+            }
+            "hardBreak" => out.push_str("\\\n"),
+            "mention" => {
+                let label = attr(node, "label")
+                    .and_then(Value::as_str)
+                    .filter(|l| !l.is_empty())
+                    .unwrap_or_else(|| attr_str(node, "id"));
+                out.push('@');
+                out.push_str(&escape(label));
+            }
+            "image" => out.push_str(&block(node)),
+            _ => out.push_str(&inline(children(node))),
+        }
+    }
+    out
+}
+
+/// Apply marks; surrounding whitespace is moved outside the delimiters so emphasis still parses.
+fn marked_text(text: &str, marks: Option<&Value>) -> String {
+    let marks: Vec<&Value> = marks
+        .and_then(Value::as_array)
+        .map(|m| m.iter().collect())
+        .unwrap_or_default();
+    let has = |name: &str| marks.iter().any(|m| node_type(m) == name);
+    if has("code") {
+        let fence = if text.contains('`') { "``" } else { "`" };
+        return wrap_link(format!("{0}{1}{0}", fence, text), &marks);
+    }
+
+    let core = text.trim();
+    if core.is_empty() {
+        return text.to_string();
+    }
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    let mut md = escape(core);
+    if has("strike") {
+        md = format!("~~{}~~", md);
+    }
+    if has("italic") {
+        md = format!("*{}*", md);
+    }
+    if has("bold") {
+        md = format!("**{}**", md);
+    }
+    format!("{}{}{}", leading, wrap_link(md, &marks), trailing)
+}
+
+fn wrap_link(md: String, marks: &[&Value]) -> String {
+    match marks
+        .iter()
+        .find(|m| node_type(m) == "link")
+        .map(|m| attr_str(m, "href"))
+        .filter(|href| !href.is_empty())
+    {
+        Some(href) => format!("[{}](<{}>)", md, href.replace('>', "%3E")),
+        None => md,
+    }
+}
@@ -1,6 +1,18 @@
 //! Infrastructure: SQLite connection, migrations, repositories.
 
 pub mod db;
+pub mod encryption;
+pub mod exchange_rates;
+pub mod http_server;
+pub mod ics;
+pub mod layout;
+pub mod link_probe;
+pub mod log_file;
+pub mod markdown;
+pub mod pdf;
+pub mod redact;
+pub mod webhook;
+pub mod xlsx;
 
-pub(crate) use db::get_connection;
+pub(crate) use db::{get_connection, get_read_connection};
 pub use db::{init_db, DbPool};
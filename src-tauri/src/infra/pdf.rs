@@ -0,0 +1,247 @@
+//! Minimal PDF writer for generated reports: wrapped text lines on A4 pages.
+//!
+//! 文本使用 PDF 预定义的中文字体 `STSong-Light`（Adobe-GB1，UTF-16 编码，不嵌入字体文件），
+//! 中英文均可显示；阅读器需支持亚洲字体（Acrobat、浏览器内置阅读器、macOS 预览均支持）。
+//! 只支持左对齐文本，不支持图片和表格线。
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const LINE_SPACING: f32 = 1.4;
+
+pub const HEADING_SIZE: f32 = 16.0;
+pub const SUBHEADING_SIZE: f32 = 12.5;
+pub const BODY_SIZE: f32 = 10.0;
+
+struct PlacedLine {
+    text: String,
+    size: f32,
+    y: f32,
+}
+
+/// Text document laid out top to bottom; a new page starts when the current one is full.
+pub struct PdfText {
+    pages: Vec<Vec<PlacedLine>>,
+    y: f32,
+}
+
+impl Default for PdfText {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfText {
+    pub fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    pub fn heading(&mut self, text: &str) {
+        self.push(text, HEADING_SIZE);
+    }
+
+    pub fn subheading(&mut self, text: &str) {
+        self.blank();
+        self.push(text, SUBHEADING_SIZE);
+    }
+
+    pub fn line(&mut self, text: &str) {
+        self.push(text, BODY_SIZE);
+    }
+
+    pub fn blank(&mut self) {
+        self.y -= BODY_SIZE * 0.6;
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Append `text` at `size`, wrapping to the printable width.
+    pub fn push(&mut self, text: &str, size: f32) {
+        for line in wrap(text, size, PAGE_WIDTH - 2.0 * MARGIN) {
+            let height = size * LINE_SPACING;
+            if self.y - height < MARGIN {
+                self.pages.push(Vec::new());
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+            self.y -= height;
+            let y = self.y;
+            self.pages
+                .last_mut()
+                .expect("at least one page")
+                .push(PlacedLine {
+                    text: line,
+                    size,
+                    y,
+                });
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        let page_count = self.pages.len();
+        let kids: Vec<String> = (0..page_count)
+            .map(|i| format!("{} 0 R", 6 + 2 * i))
+            .collect();
+
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                page_count
+            )
+            .into_bytes(),
+        );
+        objects.push(
+            b"<< /Type /Font /Subtype /Type0 /BaseFont /STSong-Light /Encoding /UniGB-UTF16-H /DescendantFonts [4 0 R] >>"
+                .to_vec(),
+        );
+        objects.push(
+            b"<< /Type /Font /Subtype /CIDFontType0 /BaseFont /STSong-Light /CIDSystemInfo << /Registry (Adobe) /Ordering (GB1) /Supplement 4 >> /FontDescriptor 5 0 R /DW 1000 /W [1 95 500] >>"
+                .to_vec(),
+        );
+        objects.push(
+            b"<< /Type /FontDescriptor /FontName /STSong-Light /Flags 6 /FontBBox [-25 -254 1000 880] /ItalicAngle 0 /Ascent 880 /Descent -120 /CapHeight 880 /StemV 93 >>"
+                .to_vec(),
+        );
+
+        for (i, page) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    7 + 2 * i
+                )
+                .into_bytes(),
+            );
+            let mut content = String::new();
+            for line in page {
+                content.push_str(&format!(
+                    "BT /F1 {} Tf 1 0 0 1 {} {:.2} Tm <{}> Tj ET\n",
+                    line.size,
+                    MARGIN,
+                    line.y,
+                    utf16_hex(&line.text)
+                ));
+            }
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend_from_slice(content.as_bytes());
+            stream.extend_from_slice(b"endstream");
+            objects.push(stream);
+        }
+
+        let mut out = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, body) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref_at = out.len();
+        out.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref_at
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+/// Approximate advance width: full-width glyphs take 1em, everything else half.
+fn char_width(c: char, size: f32) -> f32 {
+    if c as u32 >= 0x2E80 {
+        size
+    } else {
+        size * 0.5
+    }
+}
+
+/// Break `text` into lines no wider than `max_width`, preferring spaces as break points.
+fn wrap(text: &str, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut current = String::new();
+        let mut width = 0.0;
+        for c in paragraph.chars() {
+            let w = char_width(c, size);
+            if width + w > max_width && !current.is_empty() {
+                match current.rfind(' ').filter(|_| !c.is_whitespace()) {
+                    Some(at) if at > 0 => {
+                        let rest = current.split_off(at + 1);
+                        lines.push(current.trim_end().to_string());
+                        current = rest;
+                    }
+                    _ => lines.push(std::mem::take(&mut current)),
+                }
+                width = current.chars().map(|c| char_width(c, size)).sum();
+                if c == ' ' && current.is_empty() {
+                    continue;
+                }
+            }
+            current.push(c);
+            width += w;
+        }
+        lines.push(current);
+    }
+    lines
+}
+
+fn utf16_hex(text: &str) -> String {
+    text.encode_utf16().map(|u| format!("{:04X}", u)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_a_parseable_skeleton() {
+        let mut doc = PdfText::new();
+        doc.heading("合作方报告 Scorecard");
+        doc.line("Projects: 3");
+        let bytes = doc.into_bytes();
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Count 1"));
+        assert!(text.contains(&utf16_hex("Projects: 3")));
+        assert!(text.trim_end().ends_with("%%EOF"));
+
+        let xref_at: usize = text
+            .lines()
+            .rev()
+            .nth(1)
+            .and_then(|l| l.parse().ok())
+            .unwrap();
+        assert!(text[xref_at..].starts_with("xref"));
+    }
+
+    #[test]
+    fn wraps_long_lines_and_paginates() {
+        let long = "word ".repeat(60);
+        let lines = wrap(long.trim(), BODY_SIZE, 100.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| !l.starts_with(' ')));
+
+        let mut doc = PdfText::new();
+        for i in 0..120 {
+            doc.line(&format!("line {}", i));
+        }
+        assert!(doc.page_count() > 1);
+    }
+}
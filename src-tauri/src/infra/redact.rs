@@ -0,0 +1,174 @@
+//! Log redaction: strip credentials and endpoints before anything reaches a log sink.
+//!
+//! - 模式匹配：AWS 风格 access key（`AKIA`/`ASIA`… + 16 位大写字母数字）、
+//!   预签名 URL 中的 `X-Amz-Credential` / `X-Amz-Signature` / `X-Amz-Security-Token` 取值。
+//! - 字面量：`sync_config` 中的 endpoint（含 host）、bucket、access key、secret key、session token、external ID，
+//!   启动时与配置变更时登记（`refresh_from_sync_config` / `register_secrets`）。
+//! - 日志插件的 formatter 对所有 target（文件 / webview）统一调用 `redact_message`；
+//!   返回给 UI 的 S3 错误信息也经过同一函数。
+
+use rusqlite::Connection;
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Config values treated as secrets (endpoint host is derived from `s3_endpoint`).
+const SECRET_CONFIG_KEYS: &[&str] = &[
+    "s3_endpoint",
+    "s3_bucket",
+    "s3_access_key",
+    "s3_secret_key",
+    "s3_session_token",
+    "s3_external_id",
+    "gdrive_client_secret",
+    "gdrive_refresh_token",
+    "gdrive_access_token",
+    "digest_webhook_url",
+    "http_api_token",
+];
+/// AWS access key id prefixes (IAM user, STS, group, role, ...).
+const ACCESS_KEY_PREFIXES: &[&str] = &[
+    "AKIA", "ASIA", "AGPA", "AIDA", "AROA", "ANPA", "ANVA", "AIPA",
+];
+const ACCESS_KEY_LEN: usize = 20;
+const SIGNED_QUERY_PARAMS: &[&str] = &[
+    "X-Amz-Credential=",
+    "X-Amz-Signature=",
+    "X-Amz-Security-Token=",
+];
+/// Shorter literals are too likely to collide with ordinary words.
+const MIN_SECRET_LEN: usize = 4;
+
+static SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Add literal secrets (empty / too short values are ignored). For URLs the host is added too.
+pub fn register_secrets<'a>(values: impl IntoIterator<Item = &'a str>) {
+    let mut new_values = Vec::new();
+    for value in values {
+        let value = value.trim();
+        if value.len() < MIN_SECRET_LEN {
+            continue;
+        }
+        if let Some(host) = url_host(value) {
+            if host.len() >= MIN_SECRET_LEN {
+                new_values.push(host.to_string());
+            }
+        }
+        new_values.push(value.to_string());
+    }
+    if new_values.is_empty() {
+        return;
+    }
+
+    let Ok(mut secrets) = SECRETS.write() else {
+        return;
+    };
+    for value in new_values {
+        if !secrets.contains(&value) {
+            secrets.push(value);
+        }
+    }
+    // Longest first so a URL is replaced before its host.
+    secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+}
+
+/// (Re)load secret literals from `sync_config`. Old values stay registered:
+/// they may still appear in messages about the previous configuration.
+pub fn refresh_from_sync_config(conn: &Connection) {
+    let mut values = Vec::new();
+    for key in SECRET_CONFIG_KEYS {
+        if let Ok(value) = conn.query_row(
+            "SELECT value FROM sync_config WHERE key = ?1",
+            [key],
+            |row| row.get::<_, String>(0),
+        ) {
+            values.push(value);
+        }
+    }
+    register_secrets(values.iter().map(String::as_str));
+}
+
+/// Redact registered literals and key-like patterns from `message`.
+pub fn redact_message(message: &str) -> Cow<'_, str> {
+    let mut out = Cow::Borrowed(message);
+
+    if let Ok(secrets) = SECRETS.read() {
+        for secret in secrets.iter() {
+            if out.contains(secret.as_str()) {
+                out = Cow::Owned(out.replace(secret.as_str(), REDACTED));
+            }
+        }
+    }
+    if let Some(replaced) = redact_signed_query_params(&out) {
+        out = Cow::Owned(replaced);
+    }
+    if let Some(replaced) = redact_access_keys(&out) {
+        out = Cow::Owned(replaced);
+    }
+    out
+}
+
+fn url_host(value: &str) -> Option<&str> {
+    let rest = value
+        .strip_prefix("https://")
+        .or_else(|| value.strip_prefix("http://"))?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority.rsplit('@').next()?;
+    let host = match host_port.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => host_port,
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+fn redact_access_keys(message: &str) -> Option<String> {
+    let bytes = message.as_bytes();
+    let mut out = String::new();
+    let mut last = 0;
+    let mut i = 0;
+    while i + ACCESS_KEY_LEN <= bytes.len() {
+        let is_key = ACCESS_KEY_PREFIXES
+            .iter()
+            .any(|p| bytes[i..].starts_with(p.as_bytes()))
+            && bytes[i + 4..i + ACCESS_KEY_LEN]
+                .iter()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric())
+            && bytes
+                .get(i + ACCESS_KEY_LEN)
+                .map_or(true, |b| !b.is_ascii_alphanumeric());
+        if is_key {
+            out.push_str(&message[last..i]);
+            out.push_str(REDACTED);
+            i += ACCESS_KEY_LEN;
+            last = i;
+        } else {
+            i += 1;
+        }
+    }
+    (last > 0).then(|| {
+        out.push_str(&message[last..]);
+        out
+    })
+}
+
+fn redact_signed_query_params(message: &str) -> Option<String> {
+    let mut out = message.to_string();
+    let mut changed = false;
+    for param in SIGNED_QUERY_PARAMS {
+        let mut search_from = 0;
+        while let Some(pos) = out[search_from..].find(param) {
+            let start = search_from + pos + param.len();
+            let end = out[start..]
+                .find(|c: char| c == '&' || c == '"' || c == '\'' || c.is_whitespace())
+                .map_or(out.len(), |offset| start + offset);
+            if &out[start..end] != REDACTED {
+                out.replace_range(start..end, REDACTED);
+                changed = true;
+            }
+            search_from = start + REDACTED.len();
+        }
+    }
+    changed.then_some(out)
+}
@@ -0,0 +1,110 @@
+//! Outbound webhook HTTP client and payload signing.
+//!
+//! 签名：`X-Projex-Signature: sha256=<hex>`，为以 webhook secret 为密钥对请求体计算的 HMAC-SHA256
+//! （与 GitHub webhook 相同的写法，接收方可直接复用校验代码）。单个请求超时 `POST_TIMEOUT`，不跟随重定向。
+
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+const USER_AGENT: &str = concat!("Projex/", env!("CARGO_PKG_VERSION"), " webhook");
+const BLOCK_SIZE: usize = 64;
+
+pub const SIGNATURE_HEADER: &str = "X-Projex-Signature";
+pub const EVENT_HEADER: &str = "X-Projex-Event";
+pub const DELIVERY_HEADER: &str = "X-Projex-Delivery";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// HMAC-SHA256 of `body` keyed with `secret`, as lowercase hex.
+pub fn hmac_sha256_hex(secret: &[u8], body: &[u8]) -> String {
+    let mut key = [0u8; BLOCK_SIZE];
+    if secret.len() > BLOCK_SIZE {
+        key[..32].copy_from_slice(&Sha256::digest(secret));
+    } else {
+        key[..secret.len()].copy_from_slice(secret);
+    }
+    let pad = |byte: u8| key.iter().map(|k| k ^ byte).collect::<Vec<u8>>();
+
+    let mut inner = Sha256::new();
+    inner.update(pad(0x36));
+    inner.update(body);
+    let mut outer = Sha256::new();
+    outer.update(pad(0x5c));
+    outer.update(inner.finalize());
+    format!("{:x}", outer.finalize())
+}
+
+/// Header value for `SIGNATURE_HEADER`.
+pub fn signature(secret: &str, body: &str) -> String {
+    format!(
+        "sha256={}",
+        hmac_sha256_hex(secret.as_bytes(), body.as_bytes())
+    )
+}
+
+/// Blocking HTTP client shared by the deliveries of one dispatch round.
+pub struct WebhookClient {
+    client: reqwest::blocking::Client,
+}
+
+impl WebhookClient {
+    pub fn new() -> Result<Self, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(POST_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { client })
+    }
+
+    /// POST the JSON body; returns the HTTP status. Must not be called from an async context.
+    pub fn post(&self, req: &WebhookRequest) -> Result<u16, String> {
+        let mut builder = self
+            .client
+            .post(&req.url)
+            .header("Content-Type", "application/json")
+            .body(req.body.clone());
+        for (name, value) in &req.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        builder
+            .send()
+            .map(|resp| resp.status().as_u16())
+            .map_err(|e| {
+                if e.is_timeout() {
+                    "timed out".to_string()
+                } else {
+                    e.to_string()
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_matches_rfc4231_vectors() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Test case 6: key longer than the block size.
+        assert_eq!(
+            hmac_sha256_hex(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! Minimal XLSX (Office Open XML spreadsheet) writer: plain tables, one per sheet.
+//!
+//! 单元格为数字或行内字符串（`inlineStr`，不生成 sharedStrings），首行为加粗表头并冻结；
+//! 不支持公式、日期格式与列宽自适应。ZIP 容器由本模块写出（deflate 压缩、CRC32 均来自 `flate2`），
+//! 文件时间固定为 1980-01-01，同样的数据生成同样的字节。
+
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::io::Write;
+
+/// Excel's limit on characters in one cell.
+const MAX_CELL_CHARS: usize = 32_767;
+/// Excel's limit on sheet name length.
+const MAX_SHEET_NAME_CHARS: usize = 31;
+
+pub enum XlsxCell {
+    Empty,
+    Number(f64),
+    Text(String),
+}
+
+struct Sheet {
+    name: String,
+    header: Vec<String>,
+    rows: Vec<Vec<XlsxCell>>,
+}
+
+#[derive(Default)]
+pub struct XlsxWorkbook {
+    sheets: Vec<Sheet>,
+}
+
+impl XlsxWorkbook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sheet; names are trimmed to 31 characters with `[]:*?/\` replaced.
+    pub fn add_sheet(&mut self, name: &str, header: &[&str], rows: Vec<Vec<XlsxCell>>) {
+        let name: String = name
+            .chars()
+            .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+            .take(MAX_SHEET_NAME_CHARS)
+            .collect();
+        self.sheets.push(Sheet {
+            name,
+            header: header.iter().map(|h| h.to_string()).collect(),
+            rows,
+        });
+    }
+
+    pub fn sheet_count(&self) -> usize {
+        self.sheets.len()
+    }
+
+    pub fn into_bytes(self) -> std::io::Result<Vec<u8>> {
+        let mut zip = ZipWriter::default();
+        zip.add(
+            "[Content_Types].xml",
+            content_types(self.sheets.len()).as_bytes(),
+        )?;
+        zip.add("_rels/.rels", ROOT_RELS.as_bytes())?;
+        zip.add("xl/workbook.xml", workbook_xml(&self.sheets).as_bytes())?;
+        zip.add(
+            "xl/_rels/workbook.xml.rels",
+            workbook_rels(self.sheets.len()).as_bytes(),
+        )?;
+        zip.add("xl/styles.xml", STYLES.as_bytes())?;
+        for (i, sheet) in self.sheets.iter().enumerate() {
+            zip.add(
+                &format!("xl/worksheets/sheet{}.xml", i + 1),
+                sheet_xml(sheet).as_bytes(),
+            )?;
+        }
+        Ok(zip.finish())
+    }
+}
+
+const ROOT_RELS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+    r#"</Relationships>"#
+);
+
+/// Style 0: default; style 1: bold (header row).
+const STYLES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    r#"<fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts>"#,
+    r#"<fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills>"#,
+    r#"<borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>"#,
+    r#"<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>"#,
+    r#"<cellXfs count="2"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs>"#,
+    r#"<cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles>"#,
+    r#"</styleSheet>"#
+);
+
+fn content_types(sheets: usize) -> String {
+    let mut xml = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+        r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+        r#"<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+    ));
+    for i in 1..=sheets {
+        xml.push_str(&format!(
+            r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+            i
+        ));
+    }
+    xml.push_str("</Types>");
+    xml
+}
+
+fn workbook_xml(sheets: &[Sheet]) -> String {
+    let mut xml = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+    ));
+    for (i, sheet) in sheets.iter().enumerate() {
+        xml.push_str(&format!(
+            r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+            escape_xml(&sheet.name),
+            i + 1,
+            i + 1
+        ));
+    }
+    xml.push_str("</sheets></workbook>");
+    xml
+}
+
+fn workbook_rels(sheets: usize) -> String {
+    let mut xml = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    ));
+    for i in 1..=sheets {
+        xml.push_str(&format!(
+            r#"<Relationship Id="rId{0}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{0}.xml"/>"#,
+            i
+        ));
+    }
+    xml.push_str(&format!(
+        r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#,
+        sheets + 1
+    ));
+    xml.push_str("</Relationships>");
+    xml
+}
+
+fn sheet_xml(sheet: &Sheet) -> String {
+    let mut xml = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+        r#"<sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews>"#,
+        r#"<sheetData>"#,
+    ));
+    xml.push_str(r#"<row r="1">"#);
+    for (c, title) in sheet.header.iter().enumerate() {
+        xml.push_str(&format!(
+            r#"<c r="{}1" s="1" t="inlineStr"><is><t>{}</t></is></c>"#,
+            column_name(c),
+            escape_xml(title)
+        ));
+    }
+    xml.push_str("</row>");
+    for (r, row) in sheet.rows.iter().enumerate() {
+        let row_number = r + 2;
+        xml.push_str(&format!(r#"<row r="{}">"#, row_number));
+        for (c, cell) in row.iter().enumerate() {
+            let reference = format!("{}{}", column_name(c), row_number);
+            match cell {
+                XlsxCell::Empty => {}
+                XlsxCell::Number(n) if n.is_finite() => {
+                    xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, n));
+                }
+                XlsxCell::Number(n) => xml.push_str(&text_cell(&reference, &n.to_string())),
+                XlsxCell::Text(t) => xml.push_str(&text_cell(&reference, t)),
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+fn text_cell(reference: &str, text: &str) -> String {
+    let text: String = text.chars().take(MAX_CELL_CHARS).collect();
+    format!(
+        r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+        reference,
+        escape_xml(&text)
+    )
+}
+
+/// 0 → A, 25 → Z, 26 → AA.
+fn column_name(index: usize) -> String {
+    let mut n = index + 1;
+    let mut name = Vec::new();
+    while n > 0 {
+        let rem = (n - 1) % 26;
+        name.push(b'A' + rem as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Escape markup and drop characters XML 1.0 does not allow.
+fn escape_xml(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 || c == '\u{fffe}' || c == '\u{ffff}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// ZIP archive with deflated entries (no ZIP64: workbooks stay far below 4 GiB).
+#[derive(Default)]
+struct ZipWriter {
+    out: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+/// DOS date for 1980-01-01, time 00:00.
+const DOS_DATE: u16 = 0x0021;
+const DOS_TIME: u16 = 0;
+
+impl ZipWriter {
+    fn add(&mut self, name: &str, data: &[u8]) -> std::io::Result<()> {
+        let mut crc = Crc::new();
+        crc.update(data);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+        let offset = self.out.len() as u32;
+        let name = name.as_bytes();
+
+        let mut local = Vec::with_capacity(30 + name.len());
+        local.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        local.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        local.extend_from_slice(&0x0800u16.to_le_bytes()); // UTF-8 names
+        local.extend_from_slice(&8u16.to_le_bytes()); // deflate
+        local.extend_from_slice(&DOS_TIME.to_le_bytes());
+        local.extend_from_slice(&DOS_DATE.to_le_bytes());
+        local.extend_from_slice(&crc.sum().to_le_bytes());
+        local.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        local.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        local.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        local.extend_from_slice(&0u16.to_le_bytes()); // extra length
+        local.extend_from_slice(name);
+        self.out.extend_from_slice(&local);
+        self.out.extend_from_slice(&compressed);
+
+        self.central
+            .extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        self.central.extend_from_slice(&local[4..30]);
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        self.central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name);
+        self.entries += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.out.len() as u32;
+        let central_size = self.central.len() as u32;
+        self.out.extend_from_slice(&self.central);
+        self.out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // this disk
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // central directory disk
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&central_size.to_le_bytes());
+        self.out.extend_from_slice(&central_offset.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.out
+    }
+}
@@ -7,26 +7,53 @@ pub mod error;
 pub mod infra;
 pub mod sync;
 pub use crate::commands::sync::{
-    sync_create_snapshot_for_pool, sync_full_for_pool, sync_full_with_runtime_for_pool,
-    sync_hold_lock_for_test, sync_restore_snapshot_for_pool, SyncRuntime,
+    sync_bootstrap_from_remote_for_pool, sync_compact_for_pool, sync_create_snapshot_for_pool,
+    sync_full_for_pool, sync_full_with_runtime_for_pool, sync_gdrive_connect_for_pool,
+    sync_gdrive_disconnect_for_pool, sync_hold_lock_for_test, sync_install_lifecycle_for_pool,
+    sync_list_devices_for_pool, sync_list_snapshots_for_pool,
+    sync_preview_restore_snapshot_for_pool, sync_restore_snapshot_for_pool,
+    sync_set_key_prefix_for_pool, sync_verify_for_pool, SnapshotRestorePreview,
+    SyncBootstrapProgress, SyncBootstrapResult, SyncCompactReq, SyncCompactResult, SyncConfigResp,
+    SyncInstallLifecycleReq, SyncInstallLifecycleResult, SyncRuntime, SyncSetKeyPrefixReq,
+    SyncSetKeyPrefixResult,
 };
 
+use app::{normalize_profile_name, resolve_profile_data_dir, DEFAULT_PROFILE};
+use commands::backup::BackupRuntime;
+use commands::http_api::HttpApiRuntime;
+use commands::link_check::LinkCheckRuntime;
+use commands::maintenance::MaintenanceRuntime;
+use commands::notification::NotificationRuntime;
+use commands::recurrence::RecurrenceRuntime;
+use commands::report::ReportRuntime;
+use commands::runtime::RuntimeRegistry;
+use commands::webhook::WebhookRuntime;
 use fs2::FileExt;
-use infra::init_db;
+use infra::layout::{
+    cleanup_previous_data_dir, location_file_for, migrate_legacy_layout, read_data_dir_location,
+    write_data_dir_location, DataDirLocation, LegacyLayoutMigration,
+};
+use infra::log_file::{LogStream, ProfileLogWriter};
+use infra::{get_connection, get_read_connection, init_db, DbPool};
 use std::fs::{File, OpenOptions};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use tauri::Manager;
 use tauri_plugin_log::{Target, TargetKind};
 
-const DEFAULT_PROFILE: &str = "default";
+/// Pre-profiles layout used by the Android background sync path.
+const LEGACY_DEFAULT_DIR: &str = "default";
 const PROFILE_ARG: &str = "--profile";
 const PROFILE_ENV: &str = "PROJEX_PROFILE";
+const DATA_DIR_ARG: &str = "--data-dir";
+const DATA_DIR_ENV: &str = "PROJEX_DATA_DIR";
 
 pub struct AppRuntimeState {
     profile_name: String,
+    base_data_dir: PathBuf,
     data_dir: PathBuf,
-    #[allow(dead_code)]
-    lock_file: File,
+    default_data_dir: PathBuf,
+    location_file: PathBuf,
 }
 
 impl AppRuntimeState {
@@ -34,6 +61,10 @@ impl AppRuntimeState {
         &self.profile_name
     }
 
+    pub fn base_data_dir(&self) -> &Path {
+        &self.base_data_dir
+    }
+
     pub fn data_dir(&self) -> &Path {
         &self.data_dir
     }
@@ -41,6 +72,50 @@ impl AppRuntimeState {
     pub fn log_dir(&self) -> PathBuf {
         self.data_dir.join("logs")
     }
+
+    pub fn default_data_dir(&self) -> &Path {
+        &self.default_data_dir
+    }
+
+    pub fn location_file(&self) -> &Path {
+        &self.location_file
+    }
+}
+
+/// Managed state for the running profile; replaced in place by `cmd_profile_switch`.
+pub struct ActiveProfile {
+    state: RwLock<Arc<AppRuntimeState>>,
+    /// Exclusive profile lock, held while the profile is active.
+    lock_file: Mutex<Option<File>>,
+}
+
+impl ActiveProfile {
+    fn new(state: AppRuntimeState, lock_file: File) -> Self {
+        Self {
+            state: RwLock::new(Arc::new(state)),
+            lock_file: Mutex::new(Some(lock_file)),
+        }
+    }
+
+    pub fn current(&self) -> Arc<AppRuntimeState> {
+        match self.state.read() {
+            Ok(state) => state.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Make `state` the active profile and release the previous profile lock.
+    pub(crate) fn replace(&self, state: AppRuntimeState, lock_file: File) {
+        match self.state.write() {
+            Ok(mut guard) => *guard = Arc::new(state),
+            Err(poisoned) => *poisoned.into_inner() = Arc::new(state),
+        }
+        let previous = match self.lock_file.lock() {
+            Ok(mut guard) => guard.replace(lock_file),
+            Err(poisoned) => poisoned.into_inner().replace(lock_file),
+        };
+        drop(previous);
+    }
 }
 
 fn app_data_dir() -> PathBuf {
@@ -48,13 +123,13 @@ fn app_data_dir() -> PathBuf {
     base.join("com.nickdu.projex")
 }
 
-fn parse_profile_arg(args: &[String]) -> Option<String> {
+fn parse_flag_arg(args: &[String], flag: &str) -> Option<String> {
     for (index, arg) in args.iter().enumerate() {
-        if let Some(value) = arg.strip_prefix(&format!("{PROFILE_ARG}=")) {
+        if let Some(value) = arg.strip_prefix(&format!("{flag}=")) {
             return Some(value.to_string());
         }
 
-        if arg == PROFILE_ARG {
+        if arg == flag {
             return Some(args.get(index + 1).cloned().unwrap_or_default());
         }
     }
@@ -62,24 +137,43 @@ fn parse_profile_arg(args: &[String]) -> Option<String> {
     None
 }
 
-fn normalize_profile_name(raw: &str) -> Option<String> {
+fn parse_profile_arg(args: &[String]) -> Option<String> {
+    parse_flag_arg(args, PROFILE_ARG)
+}
+
+fn normalize_data_dir(raw: &str) -> Option<PathBuf> {
     let trimmed = raw.trim();
-    if trimmed.is_empty() || trimmed.len() > 64 || trimmed.starts_with('-') {
+    if trimmed.is_empty() {
         return None;
     }
+    let path = PathBuf::from(trimmed);
+    path.is_absolute().then_some(path)
+}
 
-    if trimmed == "." || trimmed == ".." {
-        return None;
+/// One-off data dir override (not persisted): `--data-dir` first, then `PROJEX_DATA_DIR`.
+fn resolve_data_dir_override() -> Option<PathBuf> {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(raw) = parse_flag_arg(&args, DATA_DIR_ARG) {
+        if let Some(dir) = normalize_data_dir(&raw) {
+            return Some(dir);
+        }
+        eprintln!(
+            "Invalid data dir from {DATA_DIR_ARG}: '{}', must be absolute",
+            raw
+        );
     }
 
-    if !trimmed
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
-    {
-        return None;
+    if let Ok(raw) = std::env::var(DATA_DIR_ENV) {
+        if let Some(dir) = normalize_data_dir(&raw) {
+            return Some(dir);
+        }
+        eprintln!(
+            "Invalid data dir from {DATA_DIR_ENV}: '{}', must be absolute",
+            raw
+        );
     }
 
-    Some(trimmed.to_ascii_lowercase())
+    None
 }
 
 fn resolve_profile_name() -> String {
@@ -107,13 +201,110 @@ fn resolve_profile_name() -> String {
     DEFAULT_PROFILE.to_string()
 }
 
-fn resolve_profile_data_dir(base_data_dir: &Path, profile_name: &str) -> PathBuf {
-    base_data_dir.join("profiles").join(profile_name)
+/// Default profile dir used by the Android background Worker (no AppHandle there).
+#[cfg(target_os = "android")]
+fn android_profile_data_dir() -> PathBuf {
+    resolve_profile_data_dir(&app_data_dir(), DEFAULT_PROFILE)
+}
+
+fn resolve_legacy_default_dir(base_data_dir: &Path) -> PathBuf {
+    base_data_dir.join(LEGACY_DEFAULT_DIR)
+}
+
+fn resolve_base_data_dir(app: &tauri::AppHandle) -> PathBuf {
+    app.path().app_data_dir().unwrap_or_else(|_| app_data_dir())
+}
+
+/// Only the default profile can have a legacy layout to migrate.
+fn migrate_legacy_layout_for_profile(
+    base_data_dir: &Path,
+    profile_name: &str,
+    data_dir: &Path,
+) -> Result<Option<LegacyLayoutMigration>, String> {
+    if profile_name != DEFAULT_PROFILE {
+        return Ok(None);
+    }
+    migrate_legacy_layout(&resolve_legacy_default_dir(base_data_dir), data_dir)
+}
+
+/// Remove the previous data dir left behind by a move, now that the new one is locked.
+fn finish_data_dir_move(
+    pool: &DbPool,
+    location_file: &Path,
+    location: &DataDirLocation,
+    data_dir: &Path,
+) {
+    let Some(previous) = location.pending_cleanup.as_deref() else {
+        return;
+    };
+    if previous == data_dir {
+        return;
+    }
+
+    let (level, message) = match cleanup_previous_data_dir(previous) {
+        Ok(true) => {
+            let cleared = DataDirLocation {
+                pending_cleanup: None,
+                ..location.clone()
+            };
+            if let Err(e) = write_data_dir_location(location_file, &cleared) {
+                log::warn!("Failed to clear pending data dir cleanup: {}", e);
+            }
+            (
+                "info",
+                format!(
+                    "Data moved to {}; previous directory {} was removed",
+                    data_dir.display(),
+                    previous.display()
+                ),
+            )
+        }
+        Ok(false) => {
+            log::info!(
+                "Previous data dir {:?} still in use, cleanup postponed",
+                previous
+            );
+            return;
+        }
+        Err(e) => (
+            "warning",
+            format!(
+                "Data moved to {}, but previous directory {} could not be removed: {}",
+                data_dir.display(),
+                previous.display(),
+                e
+            ),
+        ),
+    };
+    log::info!("{}", message);
+    let result = app::notice_record(
+        &get_connection(pool),
+        app::NOTICE_KIND_DATA_DIR_MOVED,
+        level,
+        &message,
+    );
+    if let Err(e) = result {
+        log::warn!("Failed to record data dir move notice: {}", e);
+    }
+}
+
+/// Run the cleanup left by `cmd_data_dir_move` once the running profile holds its new dir.
+pub(crate) fn finish_runtime_data_dir_move(pool: &DbPool, state: &AppRuntimeState) {
+    match read_data_dir_location(&state.location_file) {
+        Ok(location) => {
+            finish_data_dir_move(pool, &state.location_file, &location, &state.data_dir)
+        }
+        Err(e) => log::warn!("Failed to read data dir location: {}", e),
+    }
 }
 
-fn resolve_data_dir(app: &tauri::AppHandle, profile_name: &str) -> PathBuf {
-    let base_data_dir = app.path().app_data_dir().unwrap_or_else(|_| app_data_dir());
-    resolve_profile_data_dir(&base_data_dir, profile_name)
+/// Log the migration and leave a notice for the UI (best-effort).
+fn record_layout_migration(pool: &DbPool, migration: &LegacyLayoutMigration) {
+    log::info!("Legacy data dir migration: {:?}", migration);
+    let result = app::notice_record_layout_migration(&get_connection(pool), migration);
+    if let Err(e) = result {
+        log::warn!("Failed to record data dir migration notice: {}", e);
+    }
 }
 
 fn resolve_log_target_names(profile_name: &str) -> (String, String) {
@@ -123,6 +314,64 @@ fn resolve_log_target_names(profile_name: &str) -> (String, String) {
     )
 }
 
+/// Route file logs to `log_dir` (see `infra::log_file`).
+fn activate_profile_logs(log_dir: &Path, profile_name: &str) {
+    let (webview_log_target, rust_log_target) = resolve_log_target_names(profile_name);
+    infra::log_file::set_active_log_files(log_dir, &webview_log_target, &rust_log_target);
+}
+
+/// Per-profile bookkeeping once its database is open (startup and profile switch).
+fn prepare_profile_db(pool: &DbPool, profile_name: &str) {
+    let conn = get_connection(pool);
+    infra::redact::refresh_from_sync_config(&conn);
+    // Copied databases keep the old value: rewrite so namespace claims see this profile.
+    if let Err(e) = sync::namespace_claim::record_local_profile(&conn, profile_name) {
+        log::warn!("Failed to record profile name: {}", e);
+    }
+}
+
+/// Lock and open another profile for a runtime switch. Resolves the data dir like startup
+/// does, except that `--data-dir` only ever applies to the launch profile.
+pub(crate) fn open_profile(
+    base_data_dir: &Path,
+    profile_name: &str,
+) -> Result<(AppRuntimeState, File, DbPool), String> {
+    let default_data_dir = resolve_profile_data_dir(base_data_dir, profile_name);
+    let location_file = location_file_for(&default_data_dir);
+    let location = read_data_dir_location(&location_file)?;
+    let data_dir = location
+        .data_dir
+        .clone()
+        .unwrap_or_else(|| default_data_dir.clone());
+    let layout_migration =
+        migrate_legacy_layout_for_profile(base_data_dir, profile_name, &data_dir)?;
+    let lock_file = acquire_profile_lock(&data_dir, profile_name)?;
+    let log_dir = data_dir.join("logs");
+    std::fs::create_dir_all(&log_dir)
+        .map_err(|e| format!("Failed to create log dir {:?}: {}", log_dir, e))?;
+    let pool = init_db(&data_dir.join("app.db")).map_err(|e| e.to_string())?;
+
+    prepare_profile_db(&pool, profile_name);
+    if let Some(migration) = &layout_migration {
+        record_layout_migration(&pool, migration);
+    }
+    finish_data_dir_move(&pool, &location_file, &location, &data_dir);
+
+    let state = AppRuntimeState {
+        profile_name: profile_name.to_string(),
+        base_data_dir: base_data_dir.to_path_buf(),
+        data_dir,
+        default_data_dir,
+        location_file,
+    };
+    Ok((state, lock_file, pool))
+}
+
+/// Make the switched-to profile's logs the active log files.
+pub(crate) fn activate_profile(state: &AppRuntimeState) {
+    activate_profile_logs(&state.log_dir(), &state.profile_name);
+}
+
 fn acquire_profile_lock(data_dir: &Path, profile_name: &str) -> Result<File, String> {
     std::fs::create_dir_all(data_dir).map_err(|e| {
         format!(
@@ -150,6 +399,12 @@ fn acquire_profile_lock(data_dir: &Path, profile_name: &str) -> Result<File, Str
     Ok(lock_file)
 }
 
+/// File log target whose path follows the active profile.
+fn profile_log_dispatch(stream: LogStream) -> tauri_plugin_log::fern::Dispatch {
+    let writer: Box<dyn std::io::Write + Send> = Box::new(ProfileLogWriter::new(stream));
+    tauri_plugin_log::fern::Dispatch::new().chain(writer)
+}
+
 fn parse_log_level(level: &str) -> Option<log::LevelFilter> {
     match level.to_uppercase().as_str() {
         "OFF" => Some(log::LevelFilter::Off),
@@ -162,6 +417,26 @@ fn parse_log_level(level: &str) -> Option<log::LevelFilter> {
     }
 }
 
+/// Read-only replica: reject business writes before they reach the command.
+fn reject_replica_writes(
+    handler: impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command();
+        if sync::replica::blocks_command(command) {
+            let blocked =
+                invoke.message.state_ref().try_get::<DbPool>().map(|pool| {
+                    sync::replica::ensure_writable(&get_read_connection(&pool), command)
+                });
+            if let Some(Err(e)) = blocked {
+                invoke.resolver.reject(e);
+                return true;
+            }
+        }
+        handler(invoke)
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -171,7 +446,17 @@ pub fn run() {
             let profile_name = resolve_profile_name();
 
             // Get data directory early to read log level config
-            let data_dir = resolve_data_dir(app.handle(), &profile_name);
+            let base_data_dir = resolve_base_data_dir(app.handle());
+            let default_data_dir = resolve_profile_data_dir(&base_data_dir, &profile_name);
+            let location_file = location_file_for(&default_data_dir);
+            let location = read_data_dir_location(&location_file).map_err(std::io::Error::other)?;
+            let data_dir = resolve_data_dir_override()
+                .or_else(|| location.data_dir.clone())
+                .unwrap_or_else(|| default_data_dir.clone());
+            // Must run before anything creates files in data_dir (lock, logs, db).
+            let layout_migration =
+                migrate_legacy_layout_for_profile(&base_data_dir, &profile_name, &data_dir)
+                    .map_err(std::io::Error::other)?;
             let db_path = data_dir.join("app.db");
             let log_dir = data_dir.join("logs");
             let lock_file =
@@ -187,8 +472,10 @@ pub fn run() {
                 };
 
                 // Try to read saved log level from database
-                match rusqlite::Connection::open(&db_path) {
-                    Ok(conn) => {
+                match infra::encryption::open_database(&db_path) {
+                    Ok((conn, _)) => {
+                        // Register configured secrets before the first line is logged.
+                        infra::redact::refresh_from_sync_config(&conn);
                         let saved_level: Result<String, _> = conn.query_row(
                             "SELECT value FROM sync_config WHERE key = 'log_level'",
                             [],
@@ -202,46 +489,58 @@ pub fn run() {
                     Err(_) => default_level,
                 }
             };
-            let (webview_log_target, rust_log_target) = resolve_log_target_names(&profile_name);
+            activate_profile_logs(&log_dir, &profile_name);
 
             // Configure log targets:
             // - Webview: for displaying logs in dev console
-            // - Folder (webview-<profile>.log): for frontend logs
-            // - Folder (rust-<profile>.log): for backend logs
+            // - File (webview-<profile>.log): for frontend logs
+            // - File (rust-<profile>.log): for backend logs
+            // 文件目标跟随当前 profile（运行时切换 profile 后写入新目录），见 `infra::log_file`。
             // 文件轮转策略：单个文件最大 10MB，保留最近 5 个文件。
+            // All targets share one formatter that redacts credentials / endpoints.
             app.handle().plugin(
                 tauri_plugin_log::Builder::default()
                     .level(log_level)
-                    .max_file_size(10 * 1024 * 1024) // 10 MB per file
+                    .format(|out, message, record| {
+                        let message = message.to_string();
+                        out.finish(format_args!(
+                            "{}[{}][{}] {}",
+                            chrono::Local::now().format("[%Y-%m-%d][%H:%M:%S]"),
+                            record.level(),
+                            record.target(),
+                            infra::redact::redact_message(&message)
+                        ))
+                    })
                     .targets([
                         Target::new(TargetKind::Webview),
-                        Target::new(TargetKind::Folder {
-                            path: log_dir.clone(),
-                            file_name: Some(webview_log_target),
-                        })
+                        Target::new(TargetKind::Dispatch(profile_log_dispatch(
+                            LogStream::Webview,
+                        )))
                         .filter(|metadata| {
                             metadata
                                 .target()
                                 .starts_with(tauri_plugin_log::WEBVIEW_TARGET)
                         }),
-                        Target::new(TargetKind::Folder {
-                            path: log_dir.clone(),
-                            file_name: Some(rust_log_target),
-                        })
-                        .filter(|metadata| {
-                            !metadata
-                                .target()
-                                .starts_with(tauri_plugin_log::WEBVIEW_TARGET)
-                        }),
+                        Target::new(TargetKind::Dispatch(profile_log_dispatch(LogStream::Rust)))
+                            .filter(|metadata| {
+                                !metadata
+                                    .target()
+                                    .starts_with(tauri_plugin_log::WEBVIEW_TARGET)
+                            }),
                     ])
                     .build(),
             )?;
 
-            app.manage(AppRuntimeState {
-                profile_name: profile_name.clone(),
-                data_dir: data_dir.clone(),
+            app.manage(ActiveProfile::new(
+                AppRuntimeState {
+                    profile_name: profile_name.clone(),
+                    base_data_dir: base_data_dir.clone(),
+                    data_dir: data_dir.clone(),
+                    default_data_dir,
+                    location_file: location_file.clone(),
+                },
                 lock_file,
-            });
+            ));
 
             log::info!("Profile: {}", profile_name);
             log::info!("DB path: {:?}", db_path);
@@ -252,44 +551,153 @@ pub fn run() {
                 e
             })?;
             app.manage(pool.clone());
+            prepare_profile_db(&pool, &profile_name);
+            if let Some(migration) = &layout_migration {
+                record_layout_migration(&pool, migration);
+            }
+            finish_data_dir_move(&pool, &location_file, &location, &data_dir);
 
             // Register pool for Android background Worker (JNI path).
             #[cfg(target_os = "android")]
             crate::android_jni::register_pool(pool.clone());
 
-            // Backend auto-sync scheduler (timer lives in Rust).
-            let runtime = SyncRuntime::new();
-            app.manage(runtime.clone());
+            // Runtimes that follow the active database; profile switches and re-keying
+            // stop and restart them through the registry.
+            let mut runtimes = RuntimeRegistry::default();
+
+            // Backend auto-sync scheduler (timer lives in Rust); stopped first, restarted last.
+            let sync_runtime = SyncRuntime::new();
+            app.manage(sync_runtime.clone());
+            runtimes.register(sync_runtime);
+
+            // Scheduled local backups (independent of sync).
+            let backup_runtime = BackupRuntime::new();
+            app.manage(backup_runtime.clone());
+            runtimes.register(backup_runtime);
+
+            // Recurring projects: create instances from due rules.
+            let recurrence_runtime = RecurrenceRuntime::new();
+            app.manage(recurrence_runtime.clone());
+            runtimes.register(recurrence_runtime);
+
+            // Scheduled reports: write report files from due schedules.
+            let report_runtime = ReportRuntime::new();
+            app.manage(report_runtime.clone());
+            runtimes.register(report_runtime);
+
+            // Opt-in weekly link health check.
+            let link_check_runtime = LinkCheckRuntime::new();
+            app.manage(link_check_runtime.clone());
+            runtimes.register(link_check_runtime);
+
+            // Opt-in monthly database maintenance (integrity check, ANALYZE, vacuum).
+            let maintenance_runtime = MaintenanceRuntime::new();
+            app.manage(maintenance_runtime.clone());
+            runtimes.register(maintenance_runtime);
+
+            // Reminders for due, overdue and long-blocked projects.
+            let notification_runtime = NotificationRuntime::new(app.handle().clone());
+            app.manage(notification_runtime.clone());
+            runtimes.register(notification_runtime);
+
+            // Outbound webhook deliveries (queued by status changes, comments and sync).
+            let webhook_runtime = WebhookRuntime::new();
+            app.manage(webhook_runtime.clone());
+            runtimes.register(webhook_runtime);
+
+            // Optional read-only HTTP API on localhost.
+            let http_api_runtime = HttpApiRuntime::new();
+            app.manage(http_api_runtime.clone());
+            runtimes.register(http_api_runtime);
+
+            app.manage(runtimes.clone());
             tauri::async_runtime::spawn(async move {
-                runtime.refresh_scheduler(pool).await;
+                runtimes.refresh_all(pool).await;
             });
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(reject_replica_writes(tauri::generate_handler![
             commands::assignment::cmd_assignment_add_member,
             commands::assignment::cmd_assignment_end_member,
             commands::assignment::cmd_assignment_list_by_project,
             commands::comment::cmd_comment_create,
+            commands::comment::cmd_comment_reply,
+            commands::comment::cmd_comment_react,
             commands::comment::cmd_comment_update,
+            commands::comment::cmd_comment_history,
+            commands::comment::cmd_comment_reorder_pins,
             commands::comment::cmd_comment_delete,
             commands::comment::cmd_comment_list,
+            commands::checklist::cmd_checklist_list,
+            commands::checklist::cmd_checklist_add,
+            commands::checklist::cmd_checklist_toggle,
+            commands::checklist::cmd_checklist_reorder,
+            commands::checklist::cmd_checklist_delete,
+            commands::country::cmd_countries_list,
+            commands::country::cmd_countries_in_use,
+            commands::digest::cmd_digest_get_config,
+            commands::digest::cmd_digest_update_config,
+            commands::digest::cmd_digest_preview,
+            commands::digest::cmd_digest_send,
+            commands::formatting::cmd_format_settings_get,
+            commands::formatting::cmd_format_settings_update,
+            commands::http_api::cmd_http_api_get_config,
+            commands::http_api::cmd_http_api_update_config,
+            commands::http_api::cmd_http_api_regenerate_token,
+            commands::custom_field::cmd_custom_field_list,
+            commands::custom_field::cmd_custom_field_create,
+            commands::custom_field::cmd_custom_field_update,
+            commands::custom_field::cmd_custom_field_delete,
+            commands::custom_field::cmd_project_set_custom_value,
             commands::data_transfer::cmd_export_json,
+            commands::data_transfer::cmd_export_json_file,
+            commands::data_transfer::cmd_export_ics,
             commands::data_transfer::cmd_import_json,
             commands::data_transfer::cmd_export_persons_csv,
+            commands::data_transfer::cmd_export_projects_csv,
+            commands::data_transfer::cmd_export_assignments_csv,
+            commands::data_transfer::cmd_export_status_history_csv,
+            commands::data_transfer::cmd_export_comments_csv,
+            commands::data_transfer::cmd_export_xlsx,
             commands::data_transfer::cmd_import_persons_csv,
+            commands::data_transfer::cmd_import_projects_csv,
             commands::data_transfer::cmd_wipe_business_data,
+            commands::data_dir::cmd_data_dir_get,
+            commands::data_dir::cmd_data_dir_move,
+            commands::encryption::cmd_db_encryption_status,
+            commands::encryption::cmd_db_set_passphrase,
+            commands::profile::cmd_profile_list,
+            commands::profile::cmd_profile_current,
+            commands::profile::cmd_profile_create,
+            commands::profile::cmd_profile_delete,
+            commands::profile::cmd_profile_switch,
+            commands::backup::cmd_backup_list,
+            commands::backup::cmd_backup_restore,
+            commands::backup::cmd_backup_create,
+            commands::backup::cmd_backup_get_config,
+            commands::backup::cmd_backup_update_config,
             commands::logs::cmd_log_list_files,
             commands::logs::cmd_log_tail,
             commands::logs::cmd_log_clear,
             commands::logs::cmd_log_get_level,
             commands::logs::cmd_log_set_level,
+            commands::notice::cmd_notice_list,
+            commands::notice::cmd_notice_dismiss,
+            commands::notification::cmd_notification_get_config,
+            commands::notification::cmd_notification_update_config,
+            commands::notification::cmd_notification_check,
+            commands::notification::cmd_notification_list,
+            commands::notification::cmd_notification_dismiss,
             commands::partner::cmd_partner_create,
             commands::partner::cmd_partner_get,
             commands::partner::cmd_partner_list,
             commands::partner::cmd_partner_update,
+            commands::partner::cmd_partner_set_default_currency,
             commands::partner::cmd_partner_deactivate,
             commands::partner::cmd_partner_projects,
+            commands::partner::cmd_partner_find_duplicates,
+            commands::partner::cmd_partner_merge,
             commands::person::cmd_person_create,
             commands::person::cmd_person_get,
             commands::person::cmd_person_list,
@@ -297,26 +705,148 @@ pub fn run() {
             commands::person::cmd_person_deactivate,
             commands::person::cmd_person_current_projects,
             commands::person::cmd_person_all_projects,
+            commands::person::cmd_person_mentions,
+            commands::person::cmd_person_find_duplicates,
+            commands::person::cmd_person_merge,
             commands::project::cmd_project_create,
             commands::project::cmd_project_get,
+            commands::project::cmd_project_export_markdown,
+            commands::project::cmd_project_clone,
             commands::project::cmd_project_update,
             commands::project::cmd_project_list,
+            commands::project::cmd_project_tree,
+            commands::project::cmd_project_reorder,
+            commands::project::cmd_project_set_size,
+            commands::project::cmd_project_set_budget,
+            commands::currency::cmd_budget_report,
+            commands::currency::cmd_currency_get_config,
+            commands::currency::cmd_currency_update_config,
+            commands::currency::cmd_currency_rate_list,
+            commands::currency::cmd_currency_rate_set,
+            commands::currency::cmd_currency_rate_delete,
+            commands::currency::cmd_currency_rates_fetch,
+            commands::project::cmd_project_throughput,
             commands::project::cmd_project_change_status,
+            commands::project::cmd_project_share,
+            commands::template::cmd_template_list,
+            commands::template::cmd_template_delete,
+            commands::template::cmd_project_save_as_template,
+            commands::template::cmd_project_create_from_template,
+            commands::recurrence::cmd_recurrence_list,
+            commands::recurrence::cmd_recurrence_create,
+            commands::recurrence::cmd_recurrence_update,
+            commands::recurrence::cmd_recurrence_delete,
+            commands::recurrence::cmd_recurrence_run_due,
+            commands::report::cmd_report_schedule_list,
+            commands::report::cmd_report_schedule_create,
+            commands::report::cmd_report_schedule_update,
+            commands::report::cmd_report_schedule_delete,
+            commands::report::cmd_report_schedule_run,
+            commands::report::cmd_report_partner_scorecard,
+            commands::report::cmd_report_partner_summary,
+            commands::report::cmd_report_summary,
+            commands::report::cmd_report_person_workload,
+            commands::link_check::cmd_link_check_get_config,
+            commands::link_check::cmd_link_check_update_config,
+            commands::link_check::cmd_link_check_run,
+            commands::link_check::cmd_link_check_report,
+            commands::maintenance::cmd_db_maintenance,
+            commands::maintenance::cmd_db_maintenance_get_config,
+            commands::maintenance::cmd_db_maintenance_update_config,
+            commands::trash::cmd_trash_delete,
+            commands::trash::cmd_trash_list,
+            commands::trash::cmd_trash_restore,
+            commands::trash::cmd_trash_purge,
+            commands::webhook::cmd_webhook_create,
+            commands::webhook::cmd_webhook_update,
+            commands::webhook::cmd_webhook_delete,
+            commands::webhook::cmd_webhook_list,
+            commands::webhook::cmd_webhook_delivery_list,
+            commands::webhook::cmd_webhook_dispatch,
+            commands::action_log::cmd_undo_last,
+            commands::action_log::cmd_redo_last,
+            commands::activity::cmd_activity_list,
+            commands::audit::cmd_audit_list,
+            commands::integrity::cmd_strict_fk_get_config,
+            commands::integrity::cmd_strict_fk_update_config,
+            commands::schema::cmd_db_schema_export,
+            commands::query::cmd_execute_query,
+            commands::query::cmd_query_schema,
+            commands::saved_view::cmd_saved_view_list,
+            commands::saved_view::cmd_saved_view_create,
+            commands::saved_view::cmd_saved_view_delete,
+            commands::saved_view::cmd_saved_view_apply,
+            commands::workflow::cmd_workflow_list,
+            commands::workflow::cmd_workflow_create,
+            commands::workflow::cmd_workflow_update,
+            commands::workflow::cmd_workflow_activate,
+            commands::workflow::cmd_workflow_delete,
+            commands::workflow::cmd_workflow_wip_status,
+            commands::sla::cmd_sla_rule_list,
+            commands::sla::cmd_sla_rule_create,
+            commands::sla::cmd_sla_rule_update,
+            commands::sla::cmd_sla_rule_delete,
+            commands::sla::cmd_sla_evaluate,
+            commands::sla::cmd_project_flag_list,
+            commands::tag::cmd_tag_list,
+            commands::tag::cmd_tag_create,
+            commands::tag::cmd_tag_update,
+            commands::tag::cmd_tag_rename,
+            commands::tag::cmd_tag_merge,
+            commands::tag::cmd_tag_delete,
+            commands::tag::cmd_tag_delete_unused,
+            commands::status_automation::cmd_status_automation_get_config,
+            commands::status_automation::cmd_status_automation_update_config,
+            commands::status_automation::cmd_status_automation_run,
             commands::sync::cmd_sync_get_config,
             commands::sync::cmd_sync_update_config,
             commands::sync::cmd_sync_set_enabled,
             commands::sync::cmd_sync_reveal_secret_key,
             commands::sync::cmd_sync_test_connection,
+            commands::sync::cmd_sync_validate_config,
             commands::sync::cmd_sync_get_status,
+            commands::sync::cmd_sync_set_network_metered,
+            commands::sync::cmd_sync_network_online,
+            commands::sync::cmd_sync_pause,
+            commands::sync::cmd_sync_resume,
+            commands::sync::cmd_sync_list_devices,
+            commands::sync::cmd_sync_list_registered_devices,
+            commands::sync::cmd_sync_rename_device,
+            commands::sync::cmd_sync_revoke_device,
+            commands::sync::cmd_sync_verify,
+            commands::sync::cmd_sync_repair,
+            commands::sync::cmd_sync_get_history,
+            commands::sync::cmd_sync_errors_list,
+            commands::sync::cmd_user_activity,
+            commands::sync::cmd_sync_presence_get_config,
+            commands::sync::cmd_sync_presence_update_config,
+            commands::sync::cmd_sync_presence_heartbeat,
+            commands::sync::cmd_sync_presence_list,
+            commands::sync::cmd_sync_list_staged,
+            commands::sync::cmd_sync_apply_staged,
+            commands::sync::cmd_sync_discard_staged,
+            commands::sync::cmd_sync_list_conflicts,
+            commands::sync::cmd_sync_resolve_conflict,
             commands::sync::cmd_sync_get_pending_wipe,
             commands::sync::cmd_sync_confirm_wipe,
             commands::sync::cmd_sync_reject_wipe,
             commands::sync::cmd_sync_full,
             commands::sync::cmd_sync_create_snapshot,
+            commands::sync::cmd_sync_list_snapshots,
+            commands::sync::cmd_sync_preview_restore_snapshot,
             commands::sync::cmd_sync_restore_snapshot,
+            commands::sync::cmd_sync_bootstrap_from_remote,
+            commands::sync::cmd_sync_compact,
+            commands::sync::cmd_sync_set_key_prefix,
+            commands::sync::cmd_sync_install_lifecycle,
+            commands::sync::cmd_sync_gdrive_begin_auth,
+            commands::sync::cmd_sync_gdrive_finish_auth,
+            commands::sync::cmd_sync_gdrive_disconnect,
             commands::sync::cmd_sync_export_config,
             commands::sync::cmd_sync_import_config,
-        ])
+            commands::sync::cmd_sync_presign_object,
+            commands::sync::cmd_sync_reset_device_id,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -324,8 +854,9 @@ pub fn run() {
 #[cfg(test)]
 mod tests {
     use super::{
-        normalize_profile_name, parse_profile_arg, resolve_log_target_names,
-        resolve_profile_data_dir,
+        migrate_legacy_layout_for_profile, normalize_data_dir, normalize_profile_name,
+        open_profile, parse_flag_arg, parse_profile_arg, resolve_legacy_default_dir,
+        resolve_log_target_names, resolve_profile_data_dir, DATA_DIR_ARG,
     };
     use std::path::Path;
 
@@ -345,6 +876,32 @@ mod tests {
         assert_eq!(parse_profile_arg(&args), Some("work".to_string()));
     }
 
+    #[test]
+    fn parse_data_dir_flag() {
+        let args = vec![
+            "projex".to_string(),
+            "--profile=work".to_string(),
+            "--data-dir".to_string(),
+            "/mnt/secure/projex".to_string(),
+        ];
+        assert_eq!(
+            parse_flag_arg(&args, DATA_DIR_ARG),
+            Some("/mnt/secure/projex".to_string())
+        );
+        assert_eq!(parse_profile_arg(&args), Some("work".to_string()));
+    }
+
+    #[test]
+    fn normalize_data_dir_requires_absolute_path() {
+        assert_eq!(normalize_data_dir("relative/dir"), None);
+        assert_eq!(normalize_data_dir("  "), None);
+        let absolute = std::env::temp_dir().join("projex-data");
+        assert_eq!(
+            normalize_data_dir(absolute.to_str().unwrap()),
+            Some(absolute.clone())
+        );
+    }
+
     #[test]
     fn normalize_profile_name_rejects_invalid_characters() {
         assert_eq!(normalize_profile_name("../prod"), None);
@@ -379,6 +936,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resolve_legacy_default_dir_is_sibling_of_profiles() {
+        let base = Path::new("/tmp/projex");
+        assert_eq!(resolve_legacy_default_dir(base), base.join("default"));
+    }
+
+    #[test]
+    fn legacy_layout_migration_only_applies_to_default_profile() {
+        let base = std::env::temp_dir().join(format!("projex-layout-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(base.join("default")).unwrap();
+        std::fs::write(base.join("default").join("projex.db"), b"db").unwrap();
+
+        let work_dir = resolve_profile_data_dir(&base, "work");
+        assert_eq!(
+            migrate_legacy_layout_for_profile(&base, "work", &work_dir).unwrap(),
+            None
+        );
+        assert!(base.join("default").join("projex.db").exists());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn open_profile_locks_and_initializes_the_target() {
+        let base =
+            std::env::temp_dir().join(format!("projex-open-profile-{}", uuid::Uuid::new_v4()));
+        let work_dir = resolve_profile_data_dir(&base, "work");
+        std::fs::create_dir_all(&work_dir).unwrap();
+
+        let (state, lock_file, pool) = open_profile(&base, "work").unwrap();
+        assert_eq!(state.profile_name(), "work");
+        assert_eq!(state.data_dir(), work_dir.as_path());
+        assert!(work_dir.join("app.db").exists());
+        assert!(work_dir.join("logs").is_dir());
+        let recorded: String = pool
+            .0
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT value FROM sync_config WHERE key = 'local_profile_name'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(recorded, "work");
+
+        // A second window cannot open the same profile while the lock is held.
+        assert!(open_profile(&base, "work").is_err());
+        drop(lock_file);
+        drop(pool);
+        assert!(open_profile(&base, "work").is_ok());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
     #[test]
     fn resolve_log_target_names_for_profiles() {
         assert_eq!(
@@ -0,0 +1,43 @@
+//! User activity tracking for the sync apply phase.
+//!
+//! 前端在用户输入时发送心跳（`cmd_user_activity`）。应用远端 delta 需要长时间持有 DB 互斥锁，
+//! 若恰逢用户正在输入评论，会导致界面卡顿；因此在用户活跃期间推迟 apply，
+//! 但每次同步的推迟总时长有上限，避免持续输入时同步永远无法完成。
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use tokio::time::{sleep, Duration};
+
+/// The user counts as "active" if a heartbeat arrived within this window.
+pub const USER_IDLE_WINDOW: Duration = Duration::from_secs(3);
+/// Upper bound on how long one sync run may defer its apply phase in total.
+pub const MAX_APPLY_DEFER: Duration = Duration::from_secs(30);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+static LAST_USER_ACTIVITY_MS: AtomicI64 = AtomicI64::new(0);
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Record a heartbeat from the frontend.
+pub fn record_user_activity() {
+    LAST_USER_ACTIVITY_MS.store(now_ms(), Ordering::Relaxed);
+}
+
+pub fn is_user_active(idle_window: Duration) -> bool {
+    let last = LAST_USER_ACTIVITY_MS.load(Ordering::Relaxed);
+    last > 0 && now_ms() - last < idle_window.as_millis() as i64
+}
+
+/// Wait until the user has been idle for `idle_window`, or `max_wait` elapses.
+/// Returns how long we actually waited.
+pub async fn wait_for_user_idle(idle_window: Duration, max_wait: Duration) -> Duration {
+    let mut waited = Duration::ZERO;
+    while waited < max_wait && is_user_active(idle_window) {
+        let step = POLL_INTERVAL.min(max_wait - waited);
+        sleep(step).await;
+        waited += step;
+    }
+    waited
+}
@@ -0,0 +1,140 @@
+//! Storage backends behind the sync pipeline.
+//!
+//! 设计要点：
+//! - 同步只依赖一个扁平的对象存储：按键上传 / 下载 / 删除、按前缀列举（带修改时间与大小）。
+//!   `SyncBackend` 只包含这些操作，S3 与 Google Drive 各自实现；预签名、键前缀迁移、
+//!   生命周期规则等 S3 专有功能仍直接使用 `S3SyncClient`。
+//! - 方法返回装箱的 `Send` future，以便以 `&dyn SyncBackend` 传入同步流程（不引入 async-trait）。
+//! - 后端由 `sync_config.sync_backend` 选择：`s3`（默认）或 `gdrive`。
+
+use crate::sync::s3_client::{ObjectOptions, S3ObjectSummary, S3SyncClient};
+use std::error::Error as StdError;
+use std::future::Future;
+use std::pin::Pin;
+
+pub const SYNC_BACKEND_KEY: &str = "sync_backend";
+
+pub type BackendResult<T> = Result<T, Box<dyn StdError>>;
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = BackendResult<T>> + Send + 'a>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    S3,
+    GoogleDrive,
+}
+
+impl BackendKind {
+    /// Unknown or empty values fall back to S3 (configs written before backends existed).
+    pub fn parse(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some("gdrive") => Self::GoogleDrive,
+            _ => Self::S3,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::S3 => "s3",
+            Self::GoogleDrive => "gdrive",
+        }
+    }
+}
+
+/// Object store used by the sync pipeline. Keys are relative (`deltas/...`, `snapshots/...`).
+pub trait SyncBackend: Send + Sync {
+    fn kind(&self) -> BackendKind;
+
+    fn device_id(&self) -> &str;
+
+    /// Identifies the remote store in namespace claims (bucket name for S3).
+    fn namespace(&self) -> &str;
+
+    /// Create or replace the object at `key`.
+    fn upload<'a>(&'a self, key: &'a str, data: Vec<u8>) -> BackendFuture<'a, ()>;
+
+    /// `upload` with per-object settings; backends without storage classes ignore them.
+    fn upload_with_options<'a>(
+        &'a self,
+        key: &'a str,
+        data: Vec<u8>,
+        options: &'a ObjectOptions,
+    ) -> BackendFuture<'a, ()>;
+
+    fn download<'a>(&'a self, key: &'a str) -> BackendFuture<'a, Vec<u8>>;
+
+    /// Deleting a missing object succeeds.
+    fn delete<'a>(&'a self, key: &'a str) -> BackendFuture<'a, ()>;
+
+    fn exists<'a>(&'a self, key: &'a str) -> BackendFuture<'a, bool>;
+
+    /// All objects whose key starts with `prefix`.
+    fn list_with_metadata<'a>(&'a self, prefix: &'a str)
+        -> BackendFuture<'a, Vec<S3ObjectSummary>>;
+
+    fn list<'a>(&'a self, prefix: &'a str) -> BackendFuture<'a, Vec<String>> {
+        Box::pin(async move {
+            Ok(self
+                .list_with_metadata(prefix)
+                .await?
+                .into_iter()
+                .map(|object| object.key)
+                .collect())
+        })
+    }
+
+    fn test_connection(&self) -> BackendFuture<'_, ()>;
+}
+
+impl SyncBackend for S3SyncClient {
+    fn kind(&self) -> BackendKind {
+        BackendKind::S3
+    }
+
+    fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    fn namespace(&self) -> &str {
+        &self.bucket
+    }
+
+    fn upload<'a>(&'a self, key: &'a str, data: Vec<u8>) -> BackendFuture<'a, ()> {
+        Box::pin(S3SyncClient::upload(self, key, data))
+    }
+
+    fn upload_with_options<'a>(
+        &'a self,
+        key: &'a str,
+        data: Vec<u8>,
+        options: &'a ObjectOptions,
+    ) -> BackendFuture<'a, ()> {
+        Box::pin(S3SyncClient::upload_with_options(self, key, data, options))
+    }
+
+    fn download<'a>(&'a self, key: &'a str) -> BackendFuture<'a, Vec<u8>> {
+        Box::pin(S3SyncClient::download(self, key))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BackendFuture<'a, ()> {
+        Box::pin(S3SyncClient::delete(self, key))
+    }
+
+    fn exists<'a>(&'a self, key: &'a str) -> BackendFuture<'a, bool> {
+        Box::pin(S3SyncClient::exists(self, key))
+    }
+
+    fn list_with_metadata<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> BackendFuture<'a, Vec<S3ObjectSummary>> {
+        Box::pin(S3SyncClient::list_with_metadata(self, prefix))
+    }
+
+    fn list<'a>(&'a self, prefix: &'a str) -> BackendFuture<'a, Vec<String>> {
+        Box::pin(S3SyncClient::list(self, prefix))
+    }
+
+    fn test_connection(&self) -> BackendFuture<'_, ()> {
+        Box::pin(S3SyncClient::test_connection(self))
+    }
+}
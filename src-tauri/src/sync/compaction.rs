@@ -0,0 +1,164 @@
+//! Delta compaction: garbage-collect old `deltas/` objects behind a fresh snapshot.
+//!
+//! 设计要点：
+//! - 每台设备在同步后把自己的远端游标（`last_remote_delta_ts::<source>`）发布到
+//!   `devices/<device_id>/cursors.json`，供其它设备判断“谁已经读到哪里”。
+//! - 来源设备 S 的 delta 只有在所有其它已知设备（出现在 `deltas/` 或 `devices/` 下）的
+//!   游标都已越过它、且对象早于保留期时才可删除；任一设备未发布游标即阻止 S 的压缩。
+//! - 执行压缩的设备先上传包含全部已应用数据的快照，再删除可删除的 delta，
+//!   新设备通过快照恢复即可补齐被删除的历史。
+
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub const AUTO_COMPACT_KEY: &str = "auto_compact";
+pub const COMPACT_OLDER_THAN_DAYS_KEY: &str = "compact_older_than_days";
+pub const LAST_COMPACT_AT_KEY: &str = "last_compact_at";
+pub const DEFAULT_COMPACT_OLDER_THAN_DAYS: i64 = 30;
+/// Local cache of the last cursors JSON uploaded, to skip redundant PUTs.
+pub const LAST_PUBLISHED_CURSORS_KEY: &str = "last_published_cursors";
+
+const CURSOR_KEY_PREFIX: &str = "last_remote_delta_ts::";
+
+/// Remote progress of one device, stored at `devices/<device_id>/cursors.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceCursors {
+    pub device_id: String,
+    pub updated_at: String,
+    /// Last applied (or staged) delta timestamp per source device.
+    pub cursors: BTreeMap<String, i64>,
+}
+
+/// A parsed `deltas/<source>/delta-<ts>[-<uuid>].gz` object.
+#[derive(Debug, Clone)]
+pub struct RemoteDeltaRef {
+    pub key: String,
+    pub source_device_id: String,
+    pub timestamp: i64,
+    pub last_modified_unix: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompactionPlan {
+    /// Delta keys safe to delete.
+    pub deletable: Vec<String>,
+    /// Number of delta objects kept.
+    pub retained: usize,
+    /// Devices whose missing/lagging cursors held back compaction.
+    pub blocked_by: Vec<String>,
+}
+
+pub fn device_cursors_key(device_id: &str) -> String {
+    format!("devices/{}/cursors.json", device_id)
+}
+
+/// `devices/<device_id>/cursors.json` -> device id.
+pub fn parse_device_cursors_key(key: &str) -> Option<&str> {
+    key.strip_prefix("devices/")?
+        .strip_suffix("/cursors.json")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+/// This device's remote delta cursors as stored in `sync_config`.
+pub fn load_local_cursors(conn: &Connection, device_id: &str) -> Result<DeviceCursors, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM sync_config WHERE key LIKE ?1")
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let rows = stmt
+        .query_map(params![format!("{}%", CURSOR_KEY_PREFIX)], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let cursors = rows
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let source = key.strip_prefix(CURSOR_KEY_PREFIX)?.to_string();
+            let ts = value.trim().parse::<i64>().ok()?;
+            Some((source, ts))
+        })
+        .collect();
+
+    Ok(DeviceCursors {
+        device_id: device_id.to_string(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+        cursors,
+    })
+}
+
+/// Decide which deltas can go. `cutoff_unix`: only objects last modified at or before it.
+/// `device_cursors` must include the compacting device's own (fresh) cursors.
+pub fn plan_compaction(
+    deltas: &[RemoteDeltaRef],
+    device_cursors: &[DeviceCursors],
+    cutoff_unix: i64,
+) -> CompactionPlan {
+    let by_device: BTreeMap<&str, &DeviceCursors> = device_cursors
+        .iter()
+        .map(|c| (c.device_id.as_str(), c))
+        .collect();
+    let known_devices: BTreeSet<&str> = deltas
+        .iter()
+        .map(|d| d.source_device_id.as_str())
+        .chain(by_device.keys().copied())
+        .collect();
+
+    // Highest timestamp of each source that every other known device has consumed.
+    let mut safe_ts: BTreeMap<&str, i64> = BTreeMap::new();
+    let mut blocked_by = BTreeSet::new();
+    for source in deltas.iter().map(|d| d.source_device_id.as_str()) {
+        if safe_ts.contains_key(source) {
+            continue;
+        }
+        let mut min_ts = i64::MAX;
+        for consumer in known_devices.iter().filter(|d| **d != source) {
+            let consumed = by_device
+                .get(consumer)
+                .map(|c| c.cursors.get(source).copied().unwrap_or(0));
+            match consumed {
+                Some(ts) => min_ts = min_ts.min(ts),
+                None => {
+                    blocked_by.insert(consumer.to_string());
+                    min_ts = i64::MIN;
+                }
+            }
+        }
+        safe_ts.insert(source, min_ts);
+    }
+
+    let mut plan = CompactionPlan::default();
+    for delta in deltas {
+        let old_enough = delta
+            .last_modified_unix
+            .is_some_and(|modified| modified <= cutoff_unix);
+        let consumed = safe_ts
+            .get(delta.source_device_id.as_str())
+            .is_some_and(|safe| delta.timestamp <= *safe);
+        if old_enough && consumed {
+            plan.deletable.push(delta.key.clone());
+        } else {
+            if old_enough {
+                // Old but still unread somewhere: name who is lagging behind.
+                for consumer in known_devices
+                    .iter()
+                    .filter(|d| **d != delta.source_device_id)
+                {
+                    let lagging = by_device.get(consumer).is_some_and(|c| {
+                        c.cursors.get(&delta.source_device_id).copied().unwrap_or(0)
+                            < delta.timestamp
+                    });
+                    if lagging {
+                        blocked_by.insert(consumer.to_string());
+                    }
+                }
+            }
+            plan.retained += 1;
+        }
+    }
+    plan.blocked_by = blocked_by.into_iter().collect();
+    plan
+}
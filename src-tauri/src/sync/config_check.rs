@@ -0,0 +1,313 @@
+//! Sync config validation: offline shape checks used by `cmd_sync_validate_config`.
+//!
+//! 每项检查独立给出 pass / warn / fail / skip，不因前一项失败而中断，便于 UI 一次列出所有问题；
+//! 网络探测（连接、写入、删除）由命令层在所有形状检查都未失败时执行。
+//! 消息中不回显凭据本身。
+
+use crate::sync::s3_client::validate_role_arn;
+use serde::Serialize;
+
+pub const MIN_AUTO_SYNC_INTERVAL_MINUTES: i64 = 1;
+/// Longer intervals are allowed but flagged: other devices see changes a day late.
+pub const MAX_RECOMMENDED_AUTO_SYNC_INTERVAL_MINUTES: i64 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl ConfigCheck {
+    pub fn new(name: &str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn pass(name: &str, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, message)
+    }
+
+    pub fn warn(name: &str, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, message)
+    }
+
+    pub fn fail(name: &str, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, message)
+    }
+
+    pub fn skip(name: &str, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Skip, message)
+    }
+}
+
+/// Values under test (draft merged over saved config by the caller).
+#[derive(Debug, Default)]
+pub struct ConfigShape<'a> {
+    pub bucket: &'a str,
+    pub endpoint: Option<&'a str>,
+    pub access_key: &'a str,
+    pub secret_key: &'a str,
+    /// Named AWS profile; replaces the static keys.
+    pub profile: Option<&'a str>,
+    pub session_token: Option<&'a str>,
+    /// IAM role assumed through STS; replaces the static keys.
+    pub role_arn: Option<&'a str>,
+    pub external_id: Option<&'a str>,
+    pub auto_sync_interval_minutes: Option<i64>,
+    /// Android: plain http endpoints are rejected.
+    pub require_https: bool,
+}
+
+pub fn check_config_shape(shape: &ConfigShape<'_>) -> Vec<ConfigCheck> {
+    // With a profile or role the keys are optional: checked only when given.
+    let keys_optional = shape.profile.is_some() || shape.role_arn.is_some();
+    let credential = |name: &str, value: &str, min_len: usize| {
+        if keys_optional && value.is_empty() {
+            ConfigCheck::skip(name, "credentials come from the AWS profile / role")
+        } else {
+            check_credential(name, value, min_len)
+        }
+    };
+    vec![
+        check_bucket_name(shape.bucket),
+        check_endpoint(shape.endpoint),
+        check_https_policy(shape.endpoint, shape.require_https),
+        check_interval(shape.auto_sync_interval_minutes),
+        credential("access_key", shape.access_key, 3),
+        credential("secret_key", shape.secret_key, 8),
+        check_auth(shape),
+    ]
+}
+
+/// Profile / session token / role combination. Values other than the role ARN are never echoed.
+pub fn check_auth(shape: &ConfigShape<'_>) -> ConfigCheck {
+    const NAME: &str = "auth";
+    if let Some(role_arn) = shape.role_arn {
+        if let Err(msg) = validate_role_arn(role_arn) {
+            return ConfigCheck::fail(NAME, msg);
+        }
+    } else if shape.external_id.is_some() {
+        return ConfigCheck::fail(NAME, "external ID is only used with a role ARN");
+    }
+    if shape.session_token.is_some() {
+        if shape.profile.is_some() {
+            return ConfigCheck::warn(NAME, "session token is ignored while a profile is set");
+        }
+        if shape.access_key.is_empty() || shape.secret_key.is_empty() {
+            return ConfigCheck::fail(NAME, "session token requires access key and secret key");
+        }
+    }
+    match (shape.profile, shape.role_arn) {
+        (Some(profile), Some(_)) => {
+            ConfigCheck::pass(NAME, format!("profile {}, then assume role", profile))
+        }
+        (Some(profile), None) => ConfigCheck::pass(NAME, format!("profile {}", profile)),
+        (None, Some(_)) => ConfigCheck::pass(NAME, "assume role"),
+        (None, None) if shape.session_token.is_some() => {
+            ConfigCheck::pass(NAME, "temporary credentials (session token)")
+        }
+        (None, None) => ConfigCheck::skip(NAME, "static keys"),
+    }
+}
+
+/// S3 bucket naming rules (shared by AWS, R2, OSS and MinIO).
+pub fn check_bucket_name(bucket: &str) -> ConfigCheck {
+    const NAME: &str = "bucket";
+    let bucket = bucket.trim();
+    if bucket.is_empty() {
+        return ConfigCheck::fail(NAME, "bucket is required");
+    }
+    if !(3..=63).contains(&bucket.len()) {
+        return ConfigCheck::fail(NAME, "bucket name must be 3-63 characters");
+    }
+    if !bucket
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.' || c == '-')
+    {
+        return ConfigCheck::fail(
+            NAME,
+            "bucket name may only contain lowercase letters, digits, '.' and '-'",
+        );
+    }
+    let edge_ok = |c: Option<char>| c.is_some_and(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    if !edge_ok(bucket.chars().next()) || !edge_ok(bucket.chars().last()) {
+        return ConfigCheck::fail(
+            NAME,
+            "bucket name must start and end with a letter or digit",
+        );
+    }
+    if bucket.contains("..") || bucket.contains(".-") || bucket.contains("-.") {
+        return ConfigCheck::fail(NAME, "bucket name has an empty or malformed label");
+    }
+    let labels: Vec<&str> = bucket.split('.').collect();
+    if labels.len() == 4 && labels.iter().all(|l| l.parse::<u8>().is_ok()) {
+        return ConfigCheck::fail(NAME, "bucket name must not look like an IP address");
+    }
+    if bucket.starts_with("xn--") || bucket.ends_with("-s3alias") || bucket.ends_with("--ol-s3") {
+        return ConfigCheck::fail(NAME, "bucket name uses a reserved prefix or suffix");
+    }
+    if bucket.contains('.') {
+        return ConfigCheck::warn(
+            NAME,
+            "dotted bucket names break virtual-hosted TLS certificates on some providers",
+        );
+    }
+    ConfigCheck::pass(NAME, "valid bucket name")
+}
+
+/// `scheme://host[:port][/path]` with http/https.
+pub fn check_endpoint(endpoint: Option<&str>) -> ConfigCheck {
+    const NAME: &str = "endpoint";
+    let Some(endpoint) = endpoint.map(str::trim).filter(|e| !e.is_empty()) else {
+        return ConfigCheck::skip(
+            NAME,
+            "no endpoint: AWS default endpoint and credentials chain",
+        );
+    };
+    let Some((scheme, rest)) = endpoint.split_once("://") else {
+        return ConfigCheck::fail(NAME, "endpoint must start with https:// or http://");
+    };
+    if !matches!(scheme.to_ascii_lowercase().as_str(), "http" | "https") {
+        return ConfigCheck::fail(NAME, format!("unsupported scheme: {}", scheme));
+    }
+    if endpoint
+        .chars()
+        .any(|c| c.is_whitespace() || c.is_control())
+    {
+        return ConfigCheck::fail(NAME, "endpoint must not contain whitespace");
+    }
+    let (authority, path) = match rest.find(['/', '?', '#']) {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+    if authority.contains('@') {
+        return ConfigCheck::fail(NAME, "endpoint must not embed credentials");
+    }
+    let Some(host) = endpoint_host(authority) else {
+        return ConfigCheck::fail(NAME, "endpoint has no valid host[:port]");
+    };
+    if path.trim_matches('/').is_empty() {
+        ConfigCheck::pass(NAME, format!("host {}", host))
+    } else {
+        ConfigCheck::warn(
+            NAME,
+            "endpoint has a path or query; S3 endpoints are usually just scheme://host[:port]",
+        )
+    }
+}
+
+pub fn check_https_policy(endpoint: Option<&str>, require_https: bool) -> ConfigCheck {
+    const NAME: &str = "https_policy";
+    let Some(endpoint) = endpoint.map(str::trim).filter(|e| !e.is_empty()) else {
+        return ConfigCheck::pass(NAME, "AWS default endpoint uses https");
+    };
+    let lower = endpoint.to_ascii_lowercase();
+    if lower.starts_with("https://") {
+        return ConfigCheck::pass(NAME, "https");
+    }
+    if require_https {
+        return ConfigCheck::fail(
+            NAME,
+            "ENDPOINT_NOT_HTTPS: endpoint must use https:// on this platform",
+        );
+    }
+    let authority = lower
+        .split_once("://")
+        .map_or("", |(_, rest)| rest)
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or("");
+    let is_local = endpoint_host(authority).is_some_and(|host| {
+        host == "localhost" || host == "127.0.0.1" || host == "::1" || host.ends_with(".local")
+    });
+    if is_local {
+        ConfigCheck::pass(NAME, "plain http to a local endpoint")
+    } else {
+        ConfigCheck::warn(
+            NAME,
+            "plain http to a remote endpoint: credentials and data travel unencrypted",
+        )
+    }
+}
+
+pub fn check_interval(minutes: Option<i64>) -> ConfigCheck {
+    const NAME: &str = "auto_sync_interval";
+    match minutes {
+        None => ConfigCheck::skip(NAME, "not set (default 1 minute)"),
+        Some(m) if m < MIN_AUTO_SYNC_INTERVAL_MINUTES => ConfigCheck::fail(
+            NAME,
+            format!(
+                "interval must be >= {} minute",
+                MIN_AUTO_SYNC_INTERVAL_MINUTES
+            ),
+        ),
+        Some(m) if m > MAX_RECOMMENDED_AUTO_SYNC_INTERVAL_MINUTES => ConfigCheck::warn(
+            NAME,
+            format!(
+                "{} minutes: other devices see changes more than a day late",
+                m
+            ),
+        ),
+        Some(m) => ConfigCheck::pass(NAME, format!("{} minutes", m)),
+    }
+}
+
+/// Shape only: present, no whitespace/control chars, sane length. The value is never echoed.
+pub fn check_credential(name: &str, value: &str, min_len: usize) -> ConfigCheck {
+    if value.is_empty() {
+        return ConfigCheck::fail(name, format!("{} is required", name));
+    }
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return ConfigCheck::fail(name, format!("{} contains whitespace", name));
+    }
+    if value.len() < min_len || value.len() > 128 {
+        return ConfigCheck::fail(name, format!("{} must be {}-128 characters", name, min_len));
+    }
+    ConfigCheck::pass(name, format!("{} characters", value.len()))
+}
+
+/// Canary written and deleted by the write probe; unique per run so probes never collide.
+pub fn probe_object_key(device_id: &str) -> String {
+    format!("probes/{}-{}.txt", device_id, uuid::Uuid::new_v4().simple())
+}
+
+pub fn has_failures(checks: &[ConfigCheck]) -> bool {
+    checks.iter().any(|c| c.status == CheckStatus::Fail)
+}
+
+/// `host[:port]` or `[v6]:port` -> host, rejecting empty hosts and non-numeric ports.
+fn endpoint_host(authority: &str) -> Option<&str> {
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        let port_ok = after.is_empty()
+            || after
+                .strip_prefix(':')
+                .is_some_and(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()));
+        return (!host.is_empty() && port_ok).then_some(host);
+    }
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+    if port.is_some_and(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    let valid_host = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_');
+    valid_host.then_some(host)
+}
@@ -0,0 +1,286 @@
+//! Sync conflict journal and manual resolution.
+//!
+//! `apply_upsert` 自动解决冲突（LWW）时会把双方版本写入 `sync_conflicts`；
+//! 用户之后可以审阅并改判：保留本地 / 保留远端 / 提交合并后的 JSON。
+//! 改判结果作为一次普通的本地写入（`_version` 递增），经由同步触发器传播到其它设备。
+
+use super::delta_sync::DeltaSyncEngine;
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Tables whose rows are keyed by `id` and carry `_version`; only these are journaled.
+const JOURNALED_TABLES: &[&str] = &[
+    "projects",
+    "persons",
+    "partners",
+    "assignments",
+    "status_history",
+    "project_size_history",
+    "project_comments",
+    "comment_reactions",
+    "comment_revisions",
+    "checklist_items",
+    "custom_field_defs",
+    "project_custom_values",
+];
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncConflictListReq {
+    /// Include conflicts that were already resolved manually. Default: false.
+    pub include_resolved: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncResolveConflictReq {
+    pub id: i64,
+    /// "local" | "remote" | "merged"
+    pub keep: String,
+    /// Required when `keep == "merged"`: full row JSON to write.
+    pub merged_json: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncConflictDto {
+    pub id: i64,
+    pub table_name: String,
+    pub record_id: String,
+    pub local_data: Option<Value>,
+    pub local_version: Option<i64>,
+    pub remote_data: Value,
+    pub remote_version: i64,
+    pub remote_device_id: String,
+    /// Side picked by automatic LWW: "local" | "remote"
+    pub auto_resolution: String,
+    pub detected_at: String,
+    pub resolved_at: Option<String>,
+    /// Manual override: "local" | "remote" | "merged"
+    pub resolution: Option<String>,
+}
+
+/// Journal an automatic resolution. No-op when there is no local row to conflict with.
+pub(crate) fn record_conflict(
+    conn: &Connection,
+    table: &str,
+    record_id: &str,
+    remote_data: &Value,
+    remote_version: i64,
+    remote_device_id: &str,
+    auto_resolution: &str,
+) -> Result<(), AppError> {
+    if !JOURNALED_TABLES.contains(&table) {
+        return Ok(());
+    }
+    let Some(local) = load_local_row_json(conn, table, record_id)? else {
+        return Ok(());
+    };
+    let local_version = local.get("_version").and_then(|v| v.as_i64());
+
+    conn.execute(
+        "INSERT INTO sync_conflicts (table_name, record_id, local_data, local_version, remote_data, remote_version, remote_device_id, auto_resolution, detected_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            table,
+            record_id,
+            local.to_string(),
+            local_version,
+            remote_data.to_string(),
+            remote_version,
+            remote_device_id,
+            auto_resolution,
+            chrono::Utc::now().to_rfc3339(),
+        ],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Newest first. Unresolved only unless `include_resolved`.
+pub fn list_sync_conflicts(
+    pool: &DbPool,
+    req: SyncConflictListReq,
+) -> Result<Vec<SyncConflictDto>, AppError> {
+    let conn = get_connection(pool);
+    let sql = if req.include_resolved.unwrap_or(false) {
+        format!("{} ORDER BY id DESC", CONFLICT_SELECT)
+    } else {
+        format!(
+            "{} WHERE resolved_at IS NULL ORDER BY id DESC",
+            CONFLICT_SELECT
+        )
+    };
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let items = stmt
+        .query_map([], map_conflict_row)
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(items)
+}
+
+/// Override an automatic resolution. The chosen row is written locally with a bumped
+/// `_version`, so it wins LWW on other devices after the next sync.
+pub fn resolve_sync_conflict(
+    pool: &DbPool,
+    req: SyncResolveConflictReq,
+) -> Result<SyncConflictDto, AppError> {
+    let conn = get_connection(pool);
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let conflict = get_conflict(&tx, req.id)?
+        .ok_or_else(|| AppError::NotFound(format!("sync conflict {}", req.id)))?;
+    if conflict.resolved_at.is_some() {
+        return Err(AppError::Validation(format!(
+            "CONFLICT_ALREADY_RESOLVED: sync conflict {} is already resolved",
+            req.id
+        )));
+    }
+
+    let keep = req.keep.trim();
+    let to_write: Option<Value> = match keep {
+        "local" if conflict.auto_resolution == "local" => None,
+        "local" => Some(conflict.local_data.clone().ok_or_else(|| {
+            AppError::Validation("local_data is missing for this conflict".into())
+        })?),
+        "remote" if conflict.auto_resolution == "remote" => None,
+        "remote" => Some(conflict.remote_data.clone()),
+        "merged" => {
+            let raw = req
+                .merged_json
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| {
+                    AppError::Validation("merged_json is required when keep=merged".into())
+                })?;
+            let merged: Value = serde_json::from_str(raw)
+                .map_err(|e| AppError::Validation(format!("INVALID_JSON: {}", e)))?;
+            if !merged.is_object() {
+                return Err(AppError::Validation(
+                    "merged_json must be a JSON object".into(),
+                ));
+            }
+            if merged.get("id").and_then(|v| v.as_str()) != Some(conflict.record_id.as_str()) {
+                return Err(AppError::Validation(
+                    "merged_json.id must match the conflicting record".into(),
+                ));
+            }
+            Some(merged)
+        }
+        _ => {
+            return Err(AppError::Validation(
+                "keep must be one of local|remote|merged".into(),
+            ))
+        }
+    };
+
+    if let Some(mut data) = to_write {
+        let current_version: i64 = tx
+            .query_row(
+                &format!("SELECT _version FROM {} WHERE id = ?1", conflict.table_name),
+                [&conflict.record_id],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Db(e.to_string()))?
+            .flatten()
+            .unwrap_or(0);
+        let version = current_version.max(conflict.remote_version) + 1;
+        if let Some(obj) = data.as_object_mut() {
+            if obj.contains_key("updated_at") {
+                obj.insert(
+                    "updated_at".into(),
+                    Value::String(chrono::Utc::now().to_rfc3339()),
+                );
+            }
+        }
+
+        let device_id = DeltaSyncEngine::get_device_id(&tx)?;
+        let engine = DeltaSyncEngine::new(pool, device_id);
+        engine.upsert_record(&tx, &conflict.table_name, &data, version)?;
+    }
+
+    tx.execute(
+        "UPDATE sync_conflicts SET resolved_at = ?1, resolution = ?2 WHERE id = ?3",
+        params![chrono::Utc::now().to_rfc3339(), keep, req.id],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let resolved = get_conflict(&tx, req.id)?
+        .ok_or_else(|| AppError::NotFound(format!("sync conflict {}", req.id)))?;
+    tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(resolved)
+}
+
+const CONFLICT_SELECT: &str = "SELECT id, table_name, record_id, local_data, local_version, remote_data, remote_version, remote_device_id, auto_resolution, detected_at, resolved_at, resolution FROM sync_conflicts";
+
+fn get_conflict(conn: &Connection, id: i64) -> Result<Option<SyncConflictDto>, AppError> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1", CONFLICT_SELECT),
+        [id],
+        map_conflict_row,
+    )
+    .optional()
+    .map_err(|e| AppError::Db(e.to_string()))
+}
+
+fn map_conflict_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SyncConflictDto> {
+    let local_data: Option<String> = row.get(3)?;
+    let remote_data: String = row.get(5)?;
+    Ok(SyncConflictDto {
+        id: row.get(0)?,
+        table_name: row.get(1)?,
+        record_id: row.get(2)?,
+        local_data: local_data.and_then(|s| serde_json::from_str(&s).ok()),
+        local_version: row.get(4)?,
+        remote_data: serde_json::from_str(&remote_data).unwrap_or(Value::Null),
+        remote_version: row.get(6)?,
+        remote_device_id: row.get(7)?,
+        auto_resolution: row.get(8)?,
+        detected_at: row.get(9)?,
+        resolved_at: row.get(10)?,
+        resolution: row.get(11)?,
+    })
+}
+
+/// Read a row as a JSON object keyed by column name.
+pub(crate) fn load_local_row_json(
+    conn: &Connection,
+    table: &str,
+    record_id: &str,
+) -> Result<Option<Value>, AppError> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT * FROM {} WHERE id = ?1", table))
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let columns: Vec<String> = stmt
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let mut rows = stmt
+        .query([record_id])
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? else {
+        return Ok(None);
+    };
+
+    let mut obj = serde_json::Map::new();
+    for (i, name) in columns.iter().enumerate() {
+        let value = match row.get_ref(i).map_err(|e| AppError::Db(e.to_string()))? {
+            ValueRef::Null | ValueRef::Blob(_) => Value::Null,
+            ValueRef::Integer(n) => Value::from(n),
+            ValueRef::Real(f) => Value::from(f),
+            ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+        };
+        obj.insert(name.clone(), value);
+    }
+    Ok(Some(Value::Object(obj)))
+}
@@ -0,0 +1,170 @@
+//! Deferred remote operations (`sync_deferred_ops`) for strict foreign-key mode.
+//!
+//! 严格模式下，远端 upsert 引用的行（合作方 / 负责人 / 项目 / 自定义字段等）本地尚不存在时，
+//! 不写入业务表，而是连同来源 delta 的设备与向量时钟暂存；每次应用 delta 后按暂存顺序重试，
+//! 引用齐全即走正常的 upsert 流程（LWW / tombstone 判断照旧）。
+//! 被引用的行已在本地删除（有 tombstone）时，该操作作为过期数据丢弃。关闭严格模式后，剩余操作全部直接应用。
+//! 来源设备被吊销后，其暂存操作在吊销时删除，重试时也会跳过（吊销可能经同步到达）。
+
+use super::delta_sync::Operation;
+use super::tombstone::load_tombstone_version;
+use super::vector_clock::VectorClock;
+use crate::error::AppError;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Result of checking an upsert's references.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RefCheck {
+    Present,
+    /// Not here yet: `table:id`.
+    Missing(String),
+    /// Deleted locally: `table:id`.
+    Deleted(String),
+}
+
+/// An operation waiting for its references.
+pub(crate) struct DeferredOp {
+    pub id: i64,
+    pub op: Operation,
+    pub source_device_id: String,
+    pub vector_clock: VectorClock,
+}
+
+/// `(column, referenced table, nullable)` for each synced table.
+pub(crate) fn references(table: &str) -> &'static [(&'static str, &'static str, bool)] {
+    match table {
+        "projects" => &[
+            ("partner_id", "partners", false),
+            ("owner_person_id", "persons", false),
+            ("parent_project_id", "projects", true),
+        ],
+        "assignments" => &[
+            ("project_id", "projects", false),
+            ("person_id", "persons", false),
+        ],
+        "status_history" | "project_size_history" | "project_tags" | "checklist_items" => {
+            &[("project_id", "projects", false)]
+        }
+        "project_comments" => &[
+            ("project_id", "projects", false),
+            ("parent_comment_id", "project_comments", true),
+        ],
+        "comment_reactions" | "comment_revisions" => &[
+            ("comment_id", "project_comments", false),
+            ("project_id", "projects", false),
+        ],
+        "project_custom_values" => &[
+            ("project_id", "projects", false),
+            ("field_id", "custom_field_defs", false),
+        ],
+        _ => &[],
+    }
+}
+
+/// Check that every row referenced by an upsert's data exists locally.
+pub(crate) fn check_references(
+    conn: &Connection,
+    table: &str,
+    data: &serde_json::Value,
+) -> Result<RefCheck, AppError> {
+    for (column, ref_table, nullable) in references(table) {
+        let Some(ref_id) = data[*column]
+            .as_str()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        else {
+            if *nullable {
+                continue;
+            }
+            return Ok(RefCheck::Missing(format!("{}:?", ref_table)));
+        };
+        let exists = conn
+            .query_row(
+                &format!("SELECT 1 FROM {} WHERE id = ?1", ref_table),
+                [ref_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if exists {
+            continue;
+        }
+        let reference = format!("{}:{}", ref_table, ref_id);
+        if load_tombstone_version(conn, ref_table, ref_id)?.is_some() {
+            return Ok(RefCheck::Deleted(reference));
+        }
+        return Ok(RefCheck::Missing(reference));
+    }
+    Ok(RefCheck::Present)
+}
+
+pub(crate) fn defer_operation(
+    conn: &Connection,
+    op: &Operation,
+    source_device_id: &str,
+    vector_clock: &VectorClock,
+    missing_ref: &str,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO sync_deferred_ops (table_name, record_id, operation, source_device_id, vector_clock, missing_ref, deferred_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            op.table_name,
+            op.record_id,
+            serde_json::to_string(op)
+                .map_err(|e| AppError::Db(format!("Serialize deferred op failed: {}", e)))?,
+            source_device_id,
+            serde_json::to_string(vector_clock)
+                .map_err(|e| AppError::Db(format!("Serialize vector clock failed: {}", e)))?,
+            missing_ref,
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    Ok(())
+}
+
+/// All deferred operations, oldest first.
+pub(crate) fn load_deferred_ops(conn: &Connection) -> Result<Vec<DeferredOp>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT id, operation, source_device_id, vector_clock FROM sync_deferred_ops ORDER BY id",
+    )?;
+    let rows = stmt
+        .query_map([], |r| {
+            Ok((
+                r.get::<_, i64>(0)?,
+                r.get::<_, String>(1)?,
+                r.get::<_, String>(2)?,
+                r.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    rows.into_iter()
+        .map(|(id, op, source_device_id, vector_clock)| {
+            Ok(DeferredOp {
+                id,
+                op: serde_json::from_str(&op)
+                    .map_err(|e| AppError::Db(format!("Deserialize deferred op failed: {}", e)))?,
+                source_device_id,
+                vector_clock: serde_json::from_str(&vector_clock)
+                    .map_err(|e| AppError::Db(format!("Deserialize vector clock failed: {}", e)))?,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn delete_deferred_op(conn: &Connection, id: i64) -> Result<(), AppError> {
+    conn.execute("DELETE FROM sync_deferred_ops WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// Drop every operation parked from `device_id` (the device was revoked).
+pub(crate) fn delete_deferred_ops_from(
+    conn: &Connection,
+    device_id: &str,
+) -> Result<usize, AppError> {
+    Ok(conn.execute(
+        "DELETE FROM sync_deferred_ops WHERE source_device_id = ?1",
+        params![device_id],
+    )?)
+}
@@ -1,8 +1,22 @@
 //! Delta sync engine with conflict resolution
 
+use super::conflicts::{load_local_row_json, record_conflict};
+use super::deferred::{
+    check_references, defer_operation, delete_deferred_op, load_deferred_ops, RefCheck,
+};
+use super::device_registry::is_device_revoked;
+use super::field_merge::{
+    delete_record_base, is_field_merge_table, load_record_base, merge_fields, save_record_base,
+    supersede_pending_snapshots, update_bases_from_uploaded,
+};
+use super::protocol::{legacy_format_version, upgrade_delta_json, SYNC_FORMAT_VERSION};
+use super::table_filter::SyncScope;
+use super::tag_set::{apply_remote_tag_add, apply_remote_tag_remove};
+use super::tombstone::{load_tombstone_version, record_tombstone};
 use super::vector_clock::VectorClock;
+use crate::app::load_strict_fk_enabled;
 use crate::error::AppError;
-use crate::infra::DbPool;
+use crate::infra::{get_connection, DbPool};
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
@@ -27,6 +41,41 @@ pub struct Operation {
     pub version: i64,
 }
 
+/// Per-operation outcome counters of one `apply_delta` call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApplyDeltaStats {
+    /// Operations that changed local rows (upserts, merges, deletes).
+    pub applied_ops: usize,
+    /// Remote upserts rejected by LWW (older version or deleted locally), and tag removals
+    /// superseded by a later local re-add.
+    pub skipped_stale_upserts: usize,
+    /// Automatic resolutions journaled to `sync_conflicts`.
+    pub conflicts_detected: usize,
+    /// Operations dropped because this device ignores their table.
+    pub ignored_table_ops: usize,
+    /// Detail operations dropped because their project is archived here and archived projects
+    /// are out of this device's sync scope.
+    pub archived_project_ops: usize,
+    /// Strict FK mode: upserts parked in `sync_deferred_ops` until their references arrive.
+    pub deferred_ops: usize,
+}
+
+/// What happened to one remote upsert.
+#[derive(Debug, Clone, Copy, Default)]
+struct UpsertOutcome {
+    applied: bool,
+    skipped_stale: bool,
+    conflict: bool,
+}
+
+impl ApplyDeltaStats {
+    fn record(&mut self, outcome: UpsertOutcome) {
+        self.applied_ops += usize::from(outcome.applied);
+        self.skipped_stale_upserts += usize::from(outcome.skipped_stale);
+        self.conflicts_detected += usize::from(outcome.conflict);
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Delta {
     pub id: i64,
@@ -35,6 +84,9 @@ pub struct Delta {
     pub vector_clock: VectorClock,
     pub created_at: String,
     pub checksum: String,
+    /// Payload format; objects written before the field existed read as format 1.
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
 }
 
 /// Local delta collected from `sync_metadata`.
@@ -44,6 +96,52 @@ pub struct CollectedLocalDelta {
     pub max_sync_meta_id: Option<i64>,
 }
 
+pub const DELTA_CHUNK_MAX_OPS_KEY: &str = "delta_chunk_max_ops";
+pub const DELTA_CHUNK_MAX_MB_KEY: &str = "delta_chunk_max_mb";
+pub const DEFAULT_DELTA_CHUNK_MAX_OPS: usize = 5_000;
+pub const DEFAULT_DELTA_CHUNK_MAX_MB: usize = 8;
+
+/// Size caps of one uploaded delta (`collect_local_delta_chunks`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeltaChunkLimits {
+    pub max_ops: usize,
+    /// Serialized (uncompressed) operation bytes.
+    pub max_bytes: usize,
+}
+
+impl Default for DeltaChunkLimits {
+    fn default() -> Self {
+        Self {
+            max_ops: DEFAULT_DELTA_CHUNK_MAX_OPS,
+            max_bytes: DEFAULT_DELTA_CHUNK_MAX_MB * 1024 * 1024,
+        }
+    }
+}
+
+impl DeltaChunkLimits {
+    /// Limits from `sync_config` (`delta_chunk_max_ops`, `delta_chunk_max_mb`); missing or
+    /// invalid values fall back to the defaults.
+    pub fn load(conn: &Connection) -> Self {
+        let read = |key: &str| {
+            conn.query_row(
+                "SELECT value FROM sync_config WHERE key = ?1",
+                [key],
+                |row| row.get::<_, String>(0),
+            )
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|v| *v >= 1)
+        };
+        let defaults = Self::default();
+        Self {
+            max_ops: read(DELTA_CHUNK_MAX_OPS_KEY).unwrap_or(defaults.max_ops),
+            max_bytes: read(DELTA_CHUNK_MAX_MB_KEY)
+                .map(|mb| mb.saturating_mul(1024 * 1024))
+                .unwrap_or(defaults.max_bytes),
+        }
+    }
+}
+
 impl Delta {
     /// Calculate checksum of operations
     pub fn calculate_checksum(operations: &[Operation]) -> String {
@@ -68,7 +166,8 @@ impl Delta {
             .map_err(|e| AppError::Db(format!("Compress finish failed: {}", e)))
     }
 
-    /// Decompress delta from bytes
+    /// Decompress delta from bytes, upgrading older payload formats.
+    /// Fails with `SYNC_FORMAT_UNSUPPORTED` for payloads written by a newer app.
     pub fn decompress(data: &[u8]) -> Result<Self, AppError> {
         let mut decoder = GzDecoder::new(data);
         let mut json = String::new();
@@ -76,11 +175,79 @@ impl Delta {
             .read_to_string(&mut json)
             .map_err(|e| AppError::Db(format!("Decompress failed: {}", e)))?;
 
-        serde_json::from_str(&json)
+        let mut value: serde_json::Value = serde_json::from_str(&json)
+            .map_err(|e| AppError::Db(format!("Deserialize delta failed: {}", e)))?;
+        upgrade_delta_json(&mut value)?;
+        serde_json::from_value(value)
             .map_err(|e| AppError::Db(format!("Deserialize delta failed: {}", e)))
     }
 }
 
+/// Unsynced `sync_metadata` rows as `(id, operation)`, oldest first.
+fn load_unsynced_operations(conn: &Connection) -> Result<Vec<(i64, Operation)>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, table_name, record_id, operation, data_snapshot, version, created_at 
+             FROM sync_metadata 
+             WHERE synced = 0 
+             ORDER BY id ASC",
+        )
+        .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
+
+    stmt.query_map([], |row: &rusqlite::Row<'_>| {
+        let op_type = match row.get::<_, String>(3)?.as_str() {
+            "INSERT" => OperationType::Insert,
+            "UPDATE" => OperationType::Update,
+            "DELETE" => OperationType::Delete,
+            _ => OperationType::Update,
+        };
+
+        let data_json: Option<String> = row.get(4)?;
+        let data = data_json.and_then(|s: String| serde_json::from_str(&s).ok());
+
+        Ok((
+            row.get(0)?,
+            Operation {
+                table_name: row.get(1)?,
+                record_id: row.get(2)?,
+                op_type,
+                data,
+                version: row.get(5)?,
+            },
+        ))
+    })
+    .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))
+}
+
+/// Unsynced operations inside this device's sync scope. Operations outside it (recorded before
+/// a table was ignored, or details of archived projects) are marked synced and never uploaded.
+fn load_operations_in_scope(conn: &Connection) -> Result<Vec<(i64, Operation)>, AppError> {
+    let scope = SyncScope::load(conn)?;
+    let mut kept = Vec::new();
+    let mut dropped = 0;
+    for (meta_id, op) in load_unsynced_operations(conn)? {
+        if scope.ignores_table(&op.table_name) || scope.excludes_archived_detail(conn, &op)? {
+            conn.execute(
+                "UPDATE sync_metadata SET synced = 1 WHERE id = ?1",
+                params![meta_id],
+            )
+            .map_err(|e| AppError::Db(e.to_string()))?;
+            dropped += 1;
+        } else {
+            kept.push((meta_id, op));
+        }
+    }
+    if dropped > 0 {
+        log::info!(
+            "Dropped {} local operations outside the sync scope",
+            dropped
+        );
+    }
+    Ok(kept)
+}
+
 pub struct DeltaSyncEngine<'a> {
     pool: &'a DbPool,
     device_id: String,
@@ -103,64 +270,85 @@ impl<'a> DeltaSyncEngine<'a> {
 
     /// Collect local changes into delta
     pub fn collect_local_delta(&self) -> Result<CollectedLocalDelta, AppError> {
-        let conn = self
-            .pool
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
-
-        // Get unsynced metadata
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, table_name, record_id, operation, data_snapshot, version, created_at 
-                 FROM sync_metadata 
-                 WHERE synced = 0 
-                 ORDER BY id ASC",
-            )
-            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
-
-        let mut max_sync_meta_id: Option<i64> = None;
-        let operations: Vec<Operation> = stmt
-            .query_map([], |row: &rusqlite::Row<'_>| {
-                let meta_id: i64 = row.get(0)?;
-                max_sync_meta_id = Some(max_sync_meta_id.map_or(meta_id, |m| m.max(meta_id)));
-                let op_type = match row.get::<_, String>(3)?.as_str() {
-                    "INSERT" => OperationType::Insert,
-                    "UPDATE" => OperationType::Update,
-                    "DELETE" => OperationType::Delete,
-                    _ => OperationType::Update,
-                };
-
-                let data_json: Option<String> = row.get(4)?;
-                let data = data_json.and_then(|s: String| serde_json::from_str(&s).ok());
-
-                Ok(Operation {
-                    table_name: row.get(1)?,
-                    record_id: row.get(2)?,
-                    op_type,
-                    data,
-                    version: row.get(5)?,
-                })
-            })
-            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
+        let conn = get_connection(&self.pool);
+        let pending = load_operations_in_scope(&conn)?;
+        let max_sync_meta_id = pending.iter().map(|(id, _)| *id).max();
+        let operations = pending.into_iter().map(|(_, op)| op).collect();
+        let vector_clock = self.get_vector_clock(&conn)?;
+        Ok(CollectedLocalDelta {
+            delta: self.local_delta(operations, vector_clock),
+            max_sync_meta_id,
+        })
+    }
 
-        // Get current vector clock
+    /// Collect local changes split into deltas of at most `limits.max_ops` operations and
+    /// `limits.max_bytes` serialized bytes (an operation larger than that travels alone).
+    /// Chunks follow `sync_metadata.id` order, so after uploading them in order each chunk's
+    /// `max_sync_meta_id` can be marked synced on its own. Empty when nothing is pending.
+    pub fn collect_local_delta_chunks(
+        &self,
+        limits: DeltaChunkLimits,
+    ) -> Result<Vec<CollectedLocalDelta>, AppError> {
+        let conn = get_connection(&self.pool);
+        let pending = load_operations_in_scope(&conn)?;
         let vector_clock = self.get_vector_clock(&conn)?;
+        drop(conn);
+
+        let mut chunks = Vec::new();
+        let mut operations: Vec<Operation> = Vec::new();
+        let mut bytes = 0;
+        let mut max_id = None;
+        for (meta_id, op) in pending {
+            let op_bytes = serde_json::to_vec(&op).map(|v| v.len()).unwrap_or(0);
+            if !operations.is_empty()
+                && (operations.len() >= limits.max_ops || bytes + op_bytes > limits.max_bytes)
+            {
+                chunks.push(CollectedLocalDelta {
+                    delta: self.local_delta(std::mem::take(&mut operations), vector_clock.clone()),
+                    max_sync_meta_id: max_id,
+                });
+                bytes = 0;
+            }
+            operations.push(op);
+            bytes += op_bytes;
+            max_id = Some(meta_id);
+        }
+        if !operations.is_empty() {
+            chunks.push(CollectedLocalDelta {
+                delta: self.local_delta(operations, vector_clock),
+                max_sync_meta_id: max_id,
+            });
+        }
+        Ok(chunks)
+    }
 
+    fn local_delta(&self, operations: Vec<Operation>, vector_clock: VectorClock) -> Delta {
         let checksum = Delta::calculate_checksum(&operations);
+        Delta {
+            id: 0, // Will be assigned by sync manager
+            operations,
+            device_id: self.device_id.clone(),
+            vector_clock,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            checksum,
+            format_version: SYNC_FORMAT_VERSION,
+        }
+    }
 
-        Ok(CollectedLocalDelta {
-            delta: Delta {
-                id: 0, // Will be assigned by sync manager
-                operations,
-                device_id: self.device_id.clone(),
-                vector_clock,
-                created_at: chrono::Utc::now().to_rfc3339(),
-                checksum,
-            },
-            max_sync_meta_id,
+    /// Build a delta carrying only `operations` (e.g. `_control` ops), stamped with the
+    /// current vector clock; `sync_metadata` is not touched.
+    pub fn build_control_delta(&self, operations: Vec<Operation>) -> Result<Delta, AppError> {
+        let conn = get_connection(&self.pool);
+        let vector_clock = self.get_vector_clock(&conn)?;
+        let checksum = Delta::calculate_checksum(&operations);
+        Ok(Delta {
+            id: 0,
+            operations,
+            device_id: self.device_id.clone(),
+            vector_clock,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            checksum,
+            format_version: SYNC_FORMAT_VERSION,
         })
     }
 
@@ -180,44 +368,217 @@ impl<'a> DeltaSyncEngine<'a> {
     }
 
     /// Apply remote delta to local database
-    pub fn apply_delta(&self, delta: &Delta) -> Result<(), AppError> {
-        let mut conn = self
-            .pool
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+    pub fn apply_delta(&self, delta: &Delta) -> Result<ApplyDeltaStats, AppError> {
+        let mut conn = get_connection(&self.pool);
 
         let tx = conn
             .transaction()
             .map_err(|e| AppError::Db(e.to_string()))?;
 
+        if is_device_revoked(&tx, &delta.device_id)? {
+            return Err(AppError::Sync(format!(
+                "Rejected delta from revoked device {}",
+                delta.device_id
+            )));
+        }
+
+        let scope = SyncScope::load(&tx)?;
+        let strict_fk = load_strict_fk_enabled(&tx);
+        let mut stats = ApplyDeltaStats::default();
         for op in &delta.operations {
+            if scope.ignores_table(&op.table_name) {
+                stats.ignored_table_ops += 1;
+                continue;
+            }
+            if scope.excludes_archived_detail(&tx, op)? {
+                stats.archived_project_ops += 1;
+                continue;
+            }
             match op.op_type {
                 OperationType::Insert | OperationType::Update => {
                     if let Some(data) = &op.data {
-                        self.apply_upsert(
+                        if strict_fk {
+                            match check_references(&tx, &op.table_name, data)? {
+                                RefCheck::Present => {}
+                                RefCheck::Missing(reference) => {
+                                    defer_operation(
+                                        &tx,
+                                        op,
+                                        &delta.device_id,
+                                        &delta.vector_clock,
+                                        &reference,
+                                    )?;
+                                    stats.deferred_ops += 1;
+                                    continue;
+                                }
+                                RefCheck::Deleted(reference) => {
+                                    log::info!(
+                                        "Skip remote upsert of {}:{} referencing deleted {}",
+                                        op.table_name,
+                                        op.record_id,
+                                        reference
+                                    );
+                                    stats.skipped_stale_upserts += 1;
+                                    continue;
+                                }
+                            }
+                        }
+                        let outcome = self.apply_upsert(
                             &tx,
                             &op.table_name,
                             &op.record_id,
                             data,
                             op.version,
-                            &delta.vector_clock,
+                            delta,
                         )?;
+                        stats.record(outcome);
                     }
                 }
                 OperationType::Delete => {
-                    self.apply_delete(&tx, &op.table_name, &op.record_id)?;
+                    if op.table_name == "project_tags" {
+                        let (project_id, tag) = parse_project_tag_record_id(&op.record_id)?;
+                        let deleted_at = op.data.as_ref().and_then(|d| d["deleted_at"].as_str());
+                        let applied = apply_remote_tag_remove(&tx, project_id, tag, deleted_at)?;
+                        stats.record(UpsertOutcome {
+                            applied,
+                            skipped_stale: !applied,
+                            conflict: false,
+                        });
+                        continue;
+                    }
+                    self.apply_delete(&tx, &op.table_name, &op.record_id, op.version)?;
+                    stats.applied_ops += 1;
                 }
             }
         }
 
+        self.apply_ready_deferred(&tx, strict_fk, &mut stats)?;
+        if stats.deferred_ops > 0 {
+            log::info!(
+                "Deferred {} operations waiting for referenced rows",
+                stats.deferred_ops
+            );
+        }
+
+        if stats.ignored_table_ops > 0 {
+            log::info!(
+                "Ignored {} operations on tables not synced by this device: {:?}",
+                stats.ignored_table_ops,
+                scope.ignored_tables
+            );
+        }
+        if stats.archived_project_ops > 0 {
+            log::info!(
+                "Ignored {} operations on details of archived projects",
+                stats.archived_project_ops
+            );
+        }
+
         // Update vector clock
         self.update_vector_clock(&tx, &delta.vector_clock)?;
 
         tx.commit()
             .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
 
-        Ok(())
+        Ok(stats)
+    }
+
+    /// Apply deferred operations left over from earlier deltas (e.g. after strict FK mode
+    /// was turned off) without waiting for a new delta.
+    pub fn apply_deferred(&self) -> Result<ApplyDeltaStats, AppError> {
+        let mut conn = get_connection(&self.pool);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        let strict_fk = load_strict_fk_enabled(&tx);
+        let mut stats = ApplyDeltaStats::default();
+        self.apply_ready_deferred(&tx, strict_fk, &mut stats)?;
+        tx.commit()
+            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
+        Ok(stats)
+    }
+
+    /// Retry deferred operations until no more become applicable: those whose references
+    /// now exist (every one when strict mode is off). Operations referencing rows deleted
+    /// locally, or parked from a device revoked since, are dropped.
+    fn apply_ready_deferred(
+        &self,
+        tx: &rusqlite::Transaction,
+        strict_fk: bool,
+        stats: &mut ApplyDeltaStats,
+    ) -> Result<(), AppError> {
+        let scope = SyncScope::load(tx)?;
+        loop {
+            let mut progressed = false;
+            for deferred in load_deferred_ops(tx)? {
+                let op = &deferred.op;
+                let Some(data) = &op.data else {
+                    delete_deferred_op(tx, deferred.id)?;
+                    continue;
+                };
+                // The revocation may have arrived after the operation was parked.
+                if is_device_revoked(tx, &deferred.source_device_id)? {
+                    log::info!(
+                        "Drop deferred upsert of {}:{} from revoked device {}",
+                        op.table_name,
+                        op.record_id,
+                        deferred.source_device_id
+                    );
+                    delete_deferred_op(tx, deferred.id)?;
+                    progressed = true;
+                    continue;
+                }
+                if strict_fk && !scope.ignores_table(&op.table_name) {
+                    match check_references(tx, &op.table_name, data)? {
+                        RefCheck::Present => {}
+                        RefCheck::Missing(_) => continue,
+                        RefCheck::Deleted(reference) => {
+                            log::info!(
+                                "Drop deferred upsert of {}:{} referencing deleted {}",
+                                op.table_name,
+                                op.record_id,
+                                reference
+                            );
+                            delete_deferred_op(tx, deferred.id)?;
+                            stats.skipped_stale_upserts += 1;
+                            progressed = true;
+                            continue;
+                        }
+                    }
+                }
+                delete_deferred_op(tx, deferred.id)?;
+                progressed = true;
+                if scope.ignores_table(&op.table_name) {
+                    stats.ignored_table_ops += 1;
+                    continue;
+                }
+                if scope.excludes_archived_detail(tx, op)? {
+                    stats.archived_project_ops += 1;
+                    continue;
+                }
+                let origin = Delta {
+                    id: 0,
+                    operations: Vec::new(),
+                    device_id: deferred.source_device_id.clone(),
+                    vector_clock: deferred.vector_clock.clone(),
+                    created_at: String::new(),
+                    checksum: String::new(),
+                    format_version: SYNC_FORMAT_VERSION,
+                };
+                let outcome = self.apply_upsert(
+                    tx,
+                    &op.table_name,
+                    &op.record_id,
+                    data,
+                    op.version,
+                    &origin,
+                )?;
+                stats.record(outcome);
+            }
+            if !progressed {
+                return Ok(());
+            }
+        }
     }
 
     /// Apply upsert operation
@@ -228,12 +589,30 @@ impl<'a> DeltaSyncEngine<'a> {
         record_id: &str,
         data: &serde_json::Value,
         version: i64,
-        remote_vc: &VectorClock,
-    ) -> Result<(), AppError> {
+        delta: &Delta,
+    ) -> Result<UpsertOutcome, AppError> {
+        // Tags merge as a set (see `tag_set`), not per-row LWW.
+        if table == "project_tags" {
+            let (project_id, tag) = parse_project_tag_record_id(record_id)?;
+            let applied = apply_remote_tag_add(tx, project_id, tag, data["created_at"].as_str())?;
+            return Ok(UpsertOutcome {
+                applied,
+                skipped_stale: !applied,
+                conflict: false,
+            });
+        }
+
+        if let Some(outcome) =
+            self.try_apply_field_merge(tx, table, record_id, data, version, delta)?
+        {
+            return Ok(outcome);
+        }
+
         // Check for conflicts using vector clock
         let local_vc = self.get_record_vector_clock(tx, table, record_id)?;
+        let clock_conflict = local_vc.conflicts_with(&delta.vector_clock);
 
-        if local_vc.conflicts_with(remote_vc) {
+        if clock_conflict {
             // Conflict! Use LWW resolution
             log::warn!("Conflict detected for {}:{}, using LWW", table, record_id);
             // For now, remote wins (can be improved with timestamp comparison)
@@ -242,10 +621,115 @@ impl<'a> DeltaSyncEngine<'a> {
         // LWW minimal guard: avoid stale remote upsert overriding newer local row.
         // 复杂说明：当前版本号是每行增量计数，跨设备不是全局时钟。
         // 这里先做保守保护：仅拦截 remote_version < local_version。
-        if !self.should_apply_upsert_lww(tx, table, record_id, version)? {
-            return Ok(());
+        let remote_wins = self.should_apply_upsert_lww(tx, table, record_id, version)?;
+
+        // Journal every automatic resolution so the user can audit / override it later.
+        // Must run before the upsert so the local side is captured as it was.
+        if clock_conflict || !remote_wins {
+            record_conflict(
+                tx,
+                table,
+                record_id,
+                data,
+                version,
+                &delta.device_id,
+                if remote_wins { "remote" } else { "local" },
+            )?;
         }
+        let conflict = clock_conflict || !remote_wins;
+
+        if !remote_wins {
+            return Ok(UpsertOutcome {
+                skipped_stale: true,
+                conflict,
+                ..Default::default()
+            });
+        }
+
+        self.upsert_record(tx, table, data, version)?;
+        if is_field_merge_table(table) {
+            save_record_base(tx, table, record_id, data)?;
+        }
+        Ok(UpsertOutcome {
+            applied: true,
+            conflict,
+            ..Default::default()
+        })
+    }
 
+    /// Per-column merge of a remote upsert into an existing local row (see `field_merge`).
+    /// Returns `None` when the table is not merged per column or no base is known yet.
+    fn try_apply_field_merge(
+        &self,
+        tx: &rusqlite::Transaction,
+        table: &str,
+        record_id: &str,
+        data: &serde_json::Value,
+        version: i64,
+        delta: &Delta,
+    ) -> Result<Option<UpsertOutcome>, AppError> {
+        if !is_field_merge_table(table) {
+            return Ok(None);
+        }
+        let Some(local) = load_local_row_json(tx, table, record_id)? else {
+            return Ok(None);
+        };
+        let Some(base) = load_record_base(tx, table, record_id)? else {
+            return Ok(None);
+        };
+
+        let local_version = local.get("_version").and_then(|v| v.as_i64()).unwrap_or(0);
+        let remote_wins = version >= local_version;
+        let merge = merge_fields(&base, &local, data, remote_wins);
+
+        if !merge.conflicting_columns.is_empty() {
+            log::warn!(
+                "Field conflict for {}:{} on {:?}, {} wins",
+                table,
+                record_id,
+                merge.conflicting_columns,
+                if remote_wins { "remote" } else { "local" }
+            );
+            record_conflict(
+                tx,
+                table,
+                record_id,
+                data,
+                version,
+                &delta.device_id,
+                if remote_wins { "remote" } else { "local" },
+            )?;
+        }
+
+        if !merge.remote_applied.is_empty() {
+            if merge.keeps_local_changes {
+                // Bump past both sides so the merged row wins LWW elsewhere; its
+                // trigger snapshot stays unsynced and replaces older pending ones.
+                let merged_version = local_version.max(version) + 1;
+                self.upsert_record(tx, table, &merge.merged, merged_version)?;
+                supersede_pending_snapshots(tx, table, record_id, merged_version)?;
+            } else {
+                self.upsert_record(tx, table, &merge.merged, version.max(local_version))?;
+            }
+        }
+
+        save_record_base(tx, table, record_id, data)?;
+        Ok(Some(UpsertOutcome {
+            applied: !merge.remote_applied.is_empty(),
+            // Remote edits lost to newer local ones on every column it touched.
+            skipped_stale: merge.remote_applied.is_empty() && !merge.conflicting_columns.is_empty(),
+            conflict: !merge.conflicting_columns.is_empty(),
+        }))
+    }
+
+    /// Write a full row for `table`, stamping `_version = version`.
+    pub(crate) fn upsert_record(
+        &self,
+        tx: &rusqlite::Transaction,
+        table: &str,
+        data: &serde_json::Value,
+        version: i64,
+    ) -> Result<(), AppError> {
         // Build SQL dynamically based on table
         match table {
             "projects" => self.upsert_project(tx, data, version)?,
@@ -253,8 +737,15 @@ impl<'a> DeltaSyncEngine<'a> {
             "partners" => self.upsert_partner(tx, data, version)?,
             "assignments" => self.upsert_assignment(tx, data, version)?,
             "status_history" => self.upsert_status_history(tx, data, version)?,
+            "project_size_history" => self.upsert_project_size_history(tx, data, version)?,
             "project_tags" => self.upsert_project_tag(tx, data)?,
             "project_comments" => self.upsert_project_comment(tx, data, version)?,
+            "custom_field_defs" => self.upsert_custom_field_def(tx, data, version)?,
+            "project_custom_values" => self.upsert_project_custom_value(tx, data, version)?,
+            "comment_reactions" => self.upsert_comment_reaction(tx, data, version)?,
+            "comment_revisions" => self.upsert_comment_revision(tx, data, version)?,
+            "checklist_items" => self.upsert_checklist_item(tx, data, version)?,
+            "devices" => self.upsert_device(tx, data, version)?,
             _ => {
                 log::warn!("Unknown table for upsert: {}", table);
             }
@@ -273,8 +764,9 @@ impl<'a> DeltaSyncEngine<'a> {
             "INSERT OR REPLACE INTO projects (
                 id, name, description, priority, current_status, country_code,
                 partner_id, owner_person_id, product_name, start_date, due_date,
-                created_at, updated_at, archived_at, _version
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                created_at, updated_at, archived_at, _version, parent_project_id, rank,
+                size_points, deleted_at, cloned_from_id, estimated_hours, budget_amount, budget_currency
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
             params![
                 data["id"].as_str(),
                 data["name"].as_str(),
@@ -291,6 +783,14 @@ impl<'a> DeltaSyncEngine<'a> {
                 data["updated_at"].as_str(),
                 data["archived_at"].as_str(),
                 version,
+                data["parent_project_id"].as_str(),
+                data["rank"].as_f64(),
+                data["size_points"].as_i64(),
+                data["deleted_at"].as_str(),
+                data["cloned_from_id"].as_str(),
+                data["estimated_hours"].as_f64(),
+                data["budget_amount"].as_f64(),
+                data["budget_currency"].as_str(),
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -307,8 +807,8 @@ impl<'a> DeltaSyncEngine<'a> {
         tx.execute(
             "INSERT OR REPLACE INTO persons (
                 id, display_name, email, role, note, is_active, 
-                created_at, updated_at, _version
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                created_at, updated_at, _version, deleted_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 data["id"].as_str(),
                 data["display_name"].as_str(),
@@ -319,6 +819,7 @@ impl<'a> DeltaSyncEngine<'a> {
                 data["created_at"].as_str(),
                 data["updated_at"].as_str(),
                 version,
+                data["deleted_at"].as_str(),
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -334,8 +835,8 @@ impl<'a> DeltaSyncEngine<'a> {
     ) -> Result<(), AppError> {
         tx.execute(
             "INSERT OR REPLACE INTO partners (
-                id, name, note, is_active, created_at, updated_at, _version
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                id, name, note, is_active, created_at, updated_at, _version, deleted_at, default_currency
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 data["id"].as_str(),
                 data["name"].as_str(),
@@ -344,6 +845,8 @@ impl<'a> DeltaSyncEngine<'a> {
                 data["created_at"].as_str(),
                 data["updated_at"].as_str(),
                 version,
+                data["deleted_at"].as_str(),
+                data["default_currency"].as_str(),
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -404,6 +907,33 @@ impl<'a> DeltaSyncEngine<'a> {
         Ok(())
     }
 
+    fn upsert_project_size_history(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+        version: i64,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT OR REPLACE INTO project_size_history (
+                id, project_id, from_points, to_points, changed_at,
+                changed_by_person_id, note, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                data["id"].as_str(),
+                data["project_id"].as_str(),
+                data["from_points"].as_i64(),
+                data["to_points"].as_i64(),
+                data["changed_at"].as_str(),
+                data["changed_by_person_id"].as_str(),
+                data["note"].as_str().unwrap_or(""),
+                version,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
     fn upsert_project_tag(
         &self,
         tx: &rusqlite::Transaction,
@@ -441,8 +971,9 @@ impl<'a> DeltaSyncEngine<'a> {
 
         tx.execute(
             "INSERT OR REPLACE INTO project_comments (
-                id, project_id, person_id, content, is_pinned, created_at, updated_at, _version
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                id, project_id, person_id, content, is_pinned, created_at, updated_at, _version,
+                parent_comment_id, pin_order
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 data["id"].as_str(),
                 data["project_id"].as_str(),
@@ -452,6 +983,178 @@ impl<'a> DeltaSyncEngine<'a> {
                 data["created_at"].as_str(),
                 data["updated_at"].as_str(),
                 version,
+                data["parent_comment_id"].as_str(),
+                data["pin_order"].as_i64(),
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn upsert_custom_field_def(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+        version: i64,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT OR REPLACE INTO custom_field_defs (
+                id, name, field_type, options, sort_order, created_at, updated_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                data["id"].as_str(),
+                data["name"].as_str(),
+                data["field_type"].as_str(),
+                data["options"].as_str().unwrap_or("[]"),
+                data["sort_order"].as_i64().unwrap_or(0),
+                data["created_at"].as_str(),
+                data["updated_at"].as_str(),
+                version,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn upsert_project_custom_value(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+        version: i64,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT OR REPLACE INTO project_custom_values (
+                id, project_id, field_id, value, updated_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                data["id"].as_str(),
+                data["project_id"].as_str(),
+                data["field_id"].as_str(),
+                data["value"].as_str(),
+                data["updated_at"].as_str(),
+                version,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// A reaction added on two devices gets two ids; the unique (comment, person, emoji)
+    /// index makes the later one replace the earlier.
+    fn upsert_comment_reaction(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+        version: i64,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT OR REPLACE INTO comment_reactions (
+                id, comment_id, project_id, person_id, emoji, created_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                data["id"].as_str(),
+                data["comment_id"].as_str(),
+                data["project_id"].as_str(),
+                data["person_id"].as_str(),
+                data["emoji"].as_str(),
+                data["created_at"].as_str(),
+                version,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn upsert_comment_revision(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+        version: i64,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT OR REPLACE INTO comment_revisions (
+                id, comment_id, project_id, person_id, content, written_at, replaced_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                data["id"].as_str(),
+                data["comment_id"].as_str(),
+                data["project_id"].as_str(),
+                data["person_id"].as_str(),
+                data["content"].as_str(),
+                data["written_at"].as_str(),
+                data["replaced_at"].as_str(),
+                version,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn upsert_checklist_item(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+        version: i64,
+    ) -> Result<(), AppError> {
+        let done = match data["done"].as_bool() {
+            Some(done) => done as i64,
+            None => data["done"].as_i64().unwrap_or(0),
+        };
+
+        tx.execute(
+            "INSERT OR REPLACE INTO checklist_items (
+                id, project_id, text, done, sort_order, done_at, created_at, updated_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                data["id"].as_str(),
+                data["project_id"].as_str(),
+                data["text"].as_str(),
+                done,
+                data["sort_order"].as_i64().unwrap_or(0),
+                data["done_at"].as_str(),
+                data["created_at"].as_str(),
+                data["updated_at"].as_str(),
+                version,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Revocation is sticky: a remote row never clears a local `revoked_at`.
+    fn upsert_device(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+        version: i64,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO devices (
+                id, name, platform, last_seen_at, revoked_at, created_at, updated_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                platform = excluded.platform,
+                last_seen_at = excluded.last_seen_at,
+                revoked_at = COALESCE(devices.revoked_at, excluded.revoked_at),
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at,
+                _version = excluded._version",
+            params![
+                data["id"].as_str(),
+                data["name"].as_str(),
+                data["platform"].as_str().unwrap_or(""),
+                data["last_seen_at"].as_str(),
+                data["revoked_at"].as_str(),
+                data["created_at"].as_str(),
+                data["updated_at"].as_str(),
+                version,
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -478,7 +1181,14 @@ impl<'a> DeltaSyncEngine<'a> {
                 | "partners"
                 | "assignments"
                 | "status_history"
+                | "project_size_history"
                 | "project_comments"
+                | "custom_field_defs"
+                | "project_custom_values"
+                | "comment_reactions"
+                | "comment_revisions"
+                | "checklist_items"
+                | "devices"
         );
         if !supports_version {
             return Ok(true);
@@ -502,7 +1212,22 @@ impl<'a> DeltaSyncEngine<'a> {
                     Ok(true)
                 }
             }
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(true),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                // Row is gone: only a strictly newer version may bring it back.
+                match load_tombstone_version(tx, table, record_id)? {
+                    Some(deleted_version) if remote_version <= deleted_version => {
+                        log::info!(
+                            "Skip remote upsert of deleted {}:{} (remote_version={}, deleted_version={})",
+                            table,
+                            record_id,
+                            remote_version,
+                            deleted_version
+                        );
+                        Ok(false)
+                    }
+                    _ => Ok(true),
+                }
+            }
             Err(e) => Err(AppError::Db(e.to_string())),
         }
     }
@@ -513,6 +1238,7 @@ impl<'a> DeltaSyncEngine<'a> {
         tx: &rusqlite::Transaction,
         table: &str,
         record_id: &str,
+        version: i64,
     ) -> Result<(), AppError> {
         match table {
             "project_tags" => {
@@ -527,6 +1253,10 @@ impl<'a> DeltaSyncEngine<'a> {
                 let sql = format!("DELETE FROM {} WHERE id = ?1", table);
                 tx.execute(&sql, params![record_id])
                     .map_err(|e| AppError::Db(e.to_string()))?;
+                if is_field_merge_table(table) {
+                    delete_record_base(tx, table, record_id)?;
+                }
+                record_tombstone(tx, table, record_id, version)?;
             }
         }
 
@@ -575,28 +1305,26 @@ impl<'a> DeltaSyncEngine<'a> {
 
     /// Mark local changes as synced
     pub fn mark_synced(&self, up_to_id: i64) -> Result<(), AppError> {
-        let conn = self
-            .pool
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(&self.pool);
 
-        conn.execute(
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
+        update_bases_from_uploaded(&tx, up_to_id)?;
+        tx.execute(
             "UPDATE sync_metadata SET synced = 1 WHERE id <= ?1 AND synced = 0",
             params![up_to_id],
         )
         .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
+        tx.commit()
+            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
 
         Ok(())
     }
 
     /// Get current max sync_metadata id.
     pub fn current_max_sync_metadata_id(&self) -> Result<i64, AppError> {
-        let conn = self
-            .pool
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let conn = get_connection(&self.pool);
 
         conn.query_row(
             "SELECT COALESCE(MAX(id), 0) FROM sync_metadata",
@@ -613,11 +1341,7 @@ impl<'a> DeltaSyncEngine<'a> {
         min_exclusive_id: i64,
         operations: &[Operation],
     ) -> Result<usize, AppError> {
-        let mut conn = self
-            .pool
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let mut conn = get_connection(&self.pool);
 
         let tx = conn
             .transaction()
@@ -664,7 +1388,7 @@ fn parse_project_tag_record_id(record_id: &str) -> Result<(&str, &str), AppError
     })
 }
 
-fn operation_type_to_sql_name(op_type: &OperationType) -> &'static str {
+pub(crate) fn operation_type_to_sql_name(op_type: &OperationType) -> &'static str {
     match op_type {
         OperationType::Insert => "INSERT",
         OperationType::Update => "UPDATE",
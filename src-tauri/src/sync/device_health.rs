@@ -0,0 +1,100 @@
+//! Per-device sync health, assembled from the bucket listing and this device's cursors.
+
+use crate::sync::compaction::{DeviceCursors, RemoteDeltaRef};
+use crate::sync::snapshot_catalog::SnapshotInfo;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeviceSyncHealth {
+    pub device_id: String,
+    /// Friendly name from the device registry, once that device has synced its row.
+    pub name: Option<String>,
+    pub revoked_at: Option<String>,
+    pub is_local: bool,
+    /// Delta objects of this device still in the bucket.
+    pub delta_count: usize,
+    /// Upload time (object last-modified, RFC3339) of the newest delta.
+    pub last_delta_at: Option<String>,
+    pub last_snapshot_at: Option<String>,
+    /// Newest delta timestamp of this device already applied (or staged) here.
+    pub applied_cursor_ts: Option<i64>,
+    /// Remote deltas of this device not yet applied here (always 0 for this device).
+    pub pending_deltas: usize,
+    /// Local changes not uploaded yet (this device only).
+    pub pending_local_changes: Option<i64>,
+    /// When the device last published its cursors, i.e. finished a sync.
+    pub last_sync_at: Option<String>,
+    #[serde(skip)]
+    last_activity_unix: Option<i64>,
+}
+
+/// One entry per device seen in `deltas/`, `snapshots/` or `devices/`, this device first,
+/// then most recently active first.
+pub fn build_device_health(
+    local_device_id: &str,
+    local_pending_changes: i64,
+    deltas: &[RemoteDeltaRef],
+    snapshots: &[SnapshotInfo],
+    local_cursors: &DeviceCursors,
+    published: &[DeviceCursors],
+) -> Vec<DeviceSyncHealth> {
+    let mut devices: BTreeMap<String, DeviceSyncHealth> = BTreeMap::new();
+    let mut entry = |device_id: &str| -> &mut DeviceSyncHealth {
+        devices
+            .entry(device_id.to_string())
+            .or_insert_with(|| DeviceSyncHealth {
+                device_id: device_id.to_string(),
+                is_local: device_id == local_device_id,
+                applied_cursor_ts: local_cursors.cursors.get(device_id).copied(),
+                ..Default::default()
+            })
+    };
+
+    entry(local_device_id).pending_local_changes = Some(local_pending_changes);
+
+    for delta in deltas {
+        let device = entry(&delta.source_device_id);
+        device.delta_count += 1;
+        if delta.last_modified_unix > device.last_activity_unix {
+            device.last_activity_unix = delta.last_modified_unix;
+            device.last_delta_at = delta.last_modified_unix.and_then(unix_to_rfc3339);
+        }
+        if !device.is_local
+            && !device
+                .applied_cursor_ts
+                .is_some_and(|ts| delta.timestamp <= ts)
+        {
+            device.pending_deltas += 1;
+        }
+    }
+
+    // `snapshots` is newest first: keep the first one per device.
+    for snapshot in snapshots {
+        let device = entry(&snapshot.device_id);
+        if device.last_snapshot_at.is_none() {
+            device.last_snapshot_at = snapshot
+                .created_at
+                .clone()
+                .or_else(|| snapshot.last_modified_unix.and_then(unix_to_rfc3339));
+        }
+        device.last_activity_unix = device.last_activity_unix.max(snapshot.last_modified_unix);
+    }
+
+    for cursors in published {
+        entry(&cursors.device_id).last_sync_at = Some(cursors.updated_at.clone());
+    }
+
+    let mut list: Vec<DeviceSyncHealth> = devices.into_values().collect();
+    list.sort_by(|a, b| {
+        b.is_local
+            .cmp(&a.is_local)
+            .then(b.last_activity_unix.cmp(&a.last_activity_unix))
+            .then(a.device_id.cmp(&b.device_id))
+    });
+    list
+}
+
+fn unix_to_rfc3339(secs: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(secs, 0).map(|t| t.to_rfc3339())
+}
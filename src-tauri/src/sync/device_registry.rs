@@ -0,0 +1,284 @@
+//! Device registry: friendly names for sync device ids, and revocation.
+//!
+//! 设计要点：
+//! - `devices` 表经由普通增量管道同步（不受按表开关影响）；每台设备在同步时维护自己的一行：
+//!   名称、平台、`last_seen_at`（最多每 `LAST_SEEN_REFRESH_SECS` 秒刷新一次，空闲设备不会每轮都产生 delta）。
+//! - 吊销是单向的：`revoked_at` 一旦写入，远端 upsert 不会清除。被吊销设备的 delta 在拉取时跳过、
+//!   `apply_delta` 拒绝，且不参与压缩的安全判断；其 S3 前缀可按需清理。
+//! - 吊销时删除该设备已暂存（review 队列）与延迟（严格外键）的操作，避免它们卡住后续应用。
+//! - 注册表不在快照中：从快照恢复的设备通过之后的 delta 获得注册表。
+
+use crate::error::AppError;
+use crate::sync::deferred::delete_deferred_ops_from;
+use crate::sync::presence::{presence_key, PRESENCE_DISPLAY_NAME_KEY};
+use crate::sync::repair::repair_request_prefix;
+use crate::sync::snapshot_catalog::SNAPSHOT_PREFIX;
+use crate::sync::staging::discard_staged_deltas_from;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Minimum gap between two `last_seen_at` updates of the local row.
+pub const LAST_SEEN_REFRESH_SECS: i64 = 3600;
+const MAX_DEVICE_NAME_CHARS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeviceInfo {
+    pub id: String,
+    pub name: String,
+    pub platform: String,
+    pub last_seen_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_local: bool,
+}
+
+/// Platform recorded for this build (`macos`, `windows`, `linux`, `android`, ...).
+pub fn current_platform() -> &'static str {
+    std::env::consts::OS
+}
+
+/// Name used until the user renames the device: presence display name, else platform + short id.
+pub fn default_device_name(conn: &Connection, device_id: &str) -> Result<String, AppError> {
+    let display_name: Option<String> = conn
+        .query_row(
+            "SELECT value FROM sync_config WHERE key = ?1",
+            params![PRESENCE_DISPLAY_NAME_KEY],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    if let Some(name) = display_name.map(|n| n.trim().to_string()) {
+        if !name.is_empty() {
+            return Ok(name);
+        }
+    }
+    let short_id: String = device_id.chars().take(8).collect();
+    Ok(format!("{} {}", current_platform(), short_id))
+}
+
+/// Device ids end up in S3 prefixes: reject anything that could address another device's objects.
+pub fn validate_device_id(device_id: &str) -> Result<(), AppError> {
+    let device_id = device_id.trim();
+    if device_id.is_empty() || device_id.contains('/') {
+        return Err(AppError::Validation(format!(
+            "Invalid device id: {:?}",
+            device_id
+        )));
+    }
+    Ok(())
+}
+
+fn normalize_device_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Validation(
+            "Device name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_DEVICE_NAME_CHARS {
+        return Err(AppError::Validation(format!(
+            "Device name must be at most {} characters",
+            MAX_DEVICE_NAME_CHARS
+        )));
+    }
+    Ok(name.to_string())
+}
+
+const DEVICE_COLUMNS: &str = "id, name, platform, last_seen_at, revoked_at, created_at, updated_at";
+
+fn device_from_row(row: &rusqlite::Row<'_>, local_device_id: &str) -> rusqlite::Result<DeviceInfo> {
+    let id: String = row.get(0)?;
+    Ok(DeviceInfo {
+        is_local: id == local_device_id,
+        id,
+        name: row.get(1)?,
+        platform: row.get(2)?,
+        last_seen_at: row.get(3)?,
+        revoked_at: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+/// Registered devices: this device first, then active ones by last seen, revoked last.
+pub fn list_devices(conn: &Connection, local_device_id: &str) -> Result<Vec<DeviceInfo>, AppError> {
+    let sql = format!(
+        "SELECT {} FROM devices
+         ORDER BY id = ?1 DESC, revoked_at IS NOT NULL, last_seen_at DESC, name COLLATE NOCASE",
+        DEVICE_COLUMNS
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let rows = stmt
+        .query_map(params![local_device_id], |row| {
+            device_from_row(row, local_device_id)
+        })
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(rows)
+}
+
+pub fn get_device(
+    conn: &Connection,
+    device_id: &str,
+    local_device_id: &str,
+) -> Result<Option<DeviceInfo>, AppError> {
+    let sql = format!("SELECT {} FROM devices WHERE id = ?1", DEVICE_COLUMNS);
+    conn.query_row(&sql, params![device_id], |row| {
+        device_from_row(row, local_device_id)
+    })
+    .optional()
+    .map_err(|e| AppError::Db(e.to_string()))
+}
+
+pub fn is_device_revoked(conn: &Connection, device_id: &str) -> Result<bool, AppError> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM devices WHERE id = ?1 AND revoked_at IS NOT NULL)",
+        params![device_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::Db(e.to_string()))
+}
+
+pub fn load_revoked_devices(conn: &Connection) -> Result<BTreeSet<String>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM devices WHERE revoked_at IS NOT NULL")
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<BTreeSet<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(ids)
+}
+
+/// Register this device or refresh its platform / last seen. Returns true if the row changed.
+pub fn touch_local_device(
+    conn: &Connection,
+    device_id: &str,
+    now: DateTime<Utc>,
+) -> Result<bool, AppError> {
+    let now_str = now.to_rfc3339();
+    let platform = current_platform();
+    let existing: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT platform, last_seen_at FROM devices WHERE id = ?1",
+            params![device_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let Some((stored_platform, last_seen_at)) = existing else {
+        let name = default_device_name(conn, device_id)?;
+        conn.execute(
+            "INSERT INTO devices (id, name, platform, last_seen_at, created_at, updated_at, _version)
+             VALUES (?1, ?2, ?3, ?4, ?4, ?4, 1)",
+            params![device_id, name, platform, now_str],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+        return Ok(true);
+    };
+
+    let seen_recently = last_seen_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| (now - t.with_timezone(&Utc)).num_seconds() < LAST_SEEN_REFRESH_SECS);
+    if stored_platform == platform && seen_recently {
+        return Ok(false);
+    }
+    conn.execute(
+        "UPDATE devices SET platform = ?1, last_seen_at = ?2, updated_at = ?2, _version = _version + 1
+         WHERE id = ?3",
+        params![platform, now_str, device_id],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(true)
+}
+
+/// Rename this device (registering it first if needed).
+pub fn rename_local_device(
+    conn: &Connection,
+    device_id: &str,
+    name: &str,
+) -> Result<DeviceInfo, AppError> {
+    let name = normalize_device_name(name)?;
+    touch_local_device(conn, device_id, Utc::now())?;
+    conn.execute(
+        "UPDATE devices SET name = ?1, updated_at = ?2, _version = _version + 1
+         WHERE id = ?3 AND name <> ?1",
+        params![name, Utc::now().to_rfc3339(), device_id],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    get_device(conn, device_id, device_id)?
+        .ok_or_else(|| AppError::Db(format!("Device {} not registered", device_id)))
+}
+
+/// Mark another device as revoked. Unknown ids are registered as revoked so that deltas
+/// from a device that never synced its registry row are rejected too.
+pub fn revoke_device(
+    conn: &Connection,
+    local_device_id: &str,
+    device_id: &str,
+) -> Result<DeviceInfo, AppError> {
+    let device_id = device_id.trim();
+    validate_device_id(device_id)?;
+    if device_id == local_device_id {
+        return Err(AppError::Validation(
+            "Cannot revoke this device".to_string(),
+        ));
+    }
+
+    let now = Utc::now().to_rfc3339();
+    match get_device(conn, device_id, local_device_id)? {
+        Some(device) if device.revoked_at.is_some() => return Ok(device),
+        Some(_) => {
+            conn.execute(
+                "UPDATE devices SET revoked_at = ?1, updated_at = ?1, _version = _version + 1
+                 WHERE id = ?2",
+                params![now, device_id],
+            )
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        }
+        None => {
+            let short_id: String = device_id.chars().take(8).collect();
+            conn.execute(
+                "INSERT INTO devices (id, name, platform, revoked_at, created_at, updated_at, _version)
+                 VALUES (?1, ?2, '', ?3, ?3, ?3, 1)",
+                params![device_id, short_id, now],
+            )
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        }
+    }
+    // Its parked deltas would otherwise be rejected on every apply.
+    let staged = discard_staged_deltas_from(conn, device_id)?;
+    let deferred = delete_deferred_ops_from(conn, device_id)?;
+    if staged + deferred > 0 {
+        log::info!(
+            "Dropped {} staged delta(s) and {} deferred op(s) from revoked device {}",
+            staged,
+            deferred,
+            device_id
+        );
+    }
+    get_device(conn, device_id, local_device_id)?
+        .ok_or_else(|| AppError::Db(format!("Device {} not registered", device_id)))
+}
+
+/// S3 prefixes holding a device's objects: deltas, snapshots, cursors / row hashes, repair
+/// requests addressed to it, presence.
+pub fn device_object_prefixes(device_id: &str) -> Vec<String> {
+    vec![
+        format!("deltas/{}/", device_id),
+        format!("{}{}/", SNAPSHOT_PREFIX, device_id),
+        format!("{}latest-{}.gz", SNAPSHOT_PREFIX, device_id),
+        format!("devices/{}/", device_id),
+        repair_request_prefix(device_id),
+        presence_key(device_id),
+    ]
+}
@@ -0,0 +1,108 @@
+//! Rolling sync error log (one row per failed attempt, local-only diagnostics)
+
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Keep at most this many error rows; older rows are pruned on insert.
+const SYNC_ERROR_LOG_MAX_ROWS: i64 = 500;
+
+/// Phase recorded when a failure happens before the pipeline reports one.
+pub const DEFAULT_SYNC_PHASE: &str = "prepare";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncErrorListReq {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncErrorEntry {
+    pub id: i64,
+    pub occurred_at: String,
+    /// "prepare" | "connect" | "upload" | "bootstrap" | "list" | "download" | "verify"
+    /// | "stage" | "apply" | "wipe_confirm" | "finalize"
+    pub phase: String,
+    /// Stable `AppError` code, e.g. "SYNC_ERROR".
+    pub error_code: String,
+    pub message: String,
+    /// Remote delta being processed when the error happened, if any.
+    pub delta_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncErrorPage {
+    pub items: Vec<SyncErrorEntry>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Persist one sync failure and prune rows beyond the retention cap.
+pub fn record_sync_error(
+    conn: &Connection,
+    phase: &str,
+    error: &AppError,
+    delta_key: Option<&str>,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO sync_error_log (occurred_at, phase, error_code, message, delta_key)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            chrono::Utc::now().to_rfc3339(),
+            phase,
+            error.code(),
+            error.to_string(),
+            delta_key,
+        ],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    conn.execute(
+        "DELETE FROM sync_error_log WHERE id NOT IN (SELECT id FROM sync_error_log ORDER BY id DESC LIMIT ?1)",
+        [SYNC_ERROR_LOG_MAX_ROWS],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Paginated error log, newest first.
+pub fn list_sync_errors(pool: &DbPool, req: SyncErrorListReq) -> Result<SyncErrorPage, AppError> {
+    let limit = req.limit.unwrap_or(50).clamp(1, 200);
+    let offset = req.offset.unwrap_or(0).max(0);
+
+    let conn = get_connection(pool);
+    let total: i64 = conn
+        .query_row("SELECT COUNT(*) FROM sync_error_log", [], |row| row.get(0))
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, occurred_at, phase, error_code, message, delta_key
+             FROM sync_error_log ORDER BY id DESC LIMIT ?1 OFFSET ?2",
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let items = stmt
+        .query_map([limit, offset], |row| {
+            Ok(SyncErrorEntry {
+                id: row.get(0)?,
+                occurred_at: row.get(1)?,
+                phase: row.get(2)?,
+                error_code: row.get(3)?,
+                message: row.get(4)?,
+                delta_key: row.get(5)?,
+            })
+        })
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    Ok(SyncErrorPage {
+        items,
+        total,
+        limit,
+        offset,
+    })
+}
@@ -0,0 +1,199 @@
+//! Field-level (three-way) merge for concurrently edited rows.
+//!
+//! 设计要点：
+//! - 对 `FIELD_MERGE_TABLES` 中的每一行，在 `sync_record_base` 保存“最近一次与其它设备一致”的整行快照（base）：
+//!   应用远端 upsert 后 base = 远端数据；本地变更上传成功后 base = 上传的快照。
+//! - 收到远端 upsert 时按列比较 base / local / remote：只有远端改过的列才覆盖本地；
+//!   两边都改且值不同的列按 `_version` 做列级 LWW（平局远端胜），并记入冲突日志。
+//! - 没有 base（升级前的数据、首次同步）时退回整行 LWW。
+
+use crate::error::AppError;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+
+/// Tables merged column by column instead of whole-row LWW.
+pub const FIELD_MERGE_TABLES: &[&str] = &["projects", "persons"];
+
+/// Bookkeeping columns never merged on their own.
+const IGNORED_COLUMNS: &[&str] = &["id", "created_at", "updated_at", "_version"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldMerge {
+    /// Local row with remote column changes applied.
+    pub merged: Value,
+    /// Columns taken from the remote side.
+    pub remote_applied: Vec<String>,
+    /// Columns changed on both sides to different values.
+    pub conflicting_columns: Vec<String>,
+    /// The merged row still carries local edits the remote device does not have yet.
+    pub keeps_local_changes: bool,
+}
+
+pub fn is_field_merge_table(table: &str) -> bool {
+    FIELD_MERGE_TABLES.contains(&table)
+}
+
+/// Three-way merge of `remote` into `local` against their common `base`.
+/// `remote_wins_conflicts` decides columns edited on both sides.
+pub fn merge_fields(
+    base: &Value,
+    local: &Value,
+    remote: &Value,
+    remote_wins_conflicts: bool,
+) -> FieldMerge {
+    let mut merged = local.clone();
+    let mut remote_applied = Vec::new();
+    let mut conflicting_columns = Vec::new();
+
+    if let (Some(remote_obj), Some(merged_obj)) = (remote.as_object(), merged.as_object_mut()) {
+        for (column, remote_value) in remote_obj {
+            if IGNORED_COLUMNS.contains(&column.as_str()) {
+                continue;
+            }
+            let base_value = base.get(column).unwrap_or(&Value::Null);
+            let local_value = merged_obj.get(column).unwrap_or(&Value::Null);
+            if remote_value == base_value || remote_value == local_value {
+                continue;
+            }
+            if local_value != base_value {
+                conflicting_columns.push(column.clone());
+                if !remote_wins_conflicts {
+                    continue;
+                }
+            }
+            merged_obj.insert(column.clone(), remote_value.clone());
+            remote_applied.push(column.clone());
+        }
+
+        if !remote_applied.is_empty() {
+            // Keep the newer of the two timestamps (RFC 3339 strings sort chronologically).
+            let local_updated = merged_obj.get("updated_at").and_then(Value::as_str);
+            let remote_updated = remote_obj.get("updated_at").and_then(Value::as_str);
+            if let Some(remote_updated) = remote_updated {
+                if local_updated.map_or(true, |l| remote_updated > l) {
+                    merged_obj.insert(
+                        "updated_at".into(),
+                        Value::String(remote_updated.to_string()),
+                    );
+                }
+            }
+        }
+    }
+
+    let keeps_local_changes = remote.as_object().is_some_and(|remote_obj| {
+        remote_obj.iter().any(|(column, remote_value)| {
+            !IGNORED_COLUMNS.contains(&column.as_str())
+                && merged.get(column).unwrap_or(&Value::Null) != remote_value
+        })
+    });
+
+    FieldMerge {
+        merged,
+        remote_applied,
+        conflicting_columns,
+        keeps_local_changes,
+    }
+}
+
+pub(crate) fn load_record_base(
+    conn: &Connection,
+    table: &str,
+    record_id: &str,
+) -> Result<Option<Value>, AppError> {
+    let raw: Option<String> = conn
+        .query_row(
+            "SELECT data FROM sync_record_base WHERE table_name = ?1 AND record_id = ?2",
+            params![table, record_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(raw.and_then(|s| serde_json::from_str(&s).ok()))
+}
+
+pub(crate) fn save_record_base(
+    conn: &Connection,
+    table: &str,
+    record_id: &str,
+    data: &Value,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_record_base (table_name, record_id, data, updated_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            table,
+            record_id,
+            data.to_string(),
+            chrono::Utc::now().to_rfc3339()
+        ],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(())
+}
+
+pub(crate) fn delete_record_base(
+    conn: &Connection,
+    table: &str,
+    record_id: &str,
+) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM sync_record_base WHERE table_name = ?1 AND record_id = ?2",
+        params![table, record_id],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(())
+}
+
+/// Promote snapshots of uploaded local changes (`sync_metadata.id <= up_to_id`, not yet
+/// marked synced) to merge bases. Must run before those rows are marked synced.
+pub(crate) fn update_bases_from_uploaded(conn: &Connection, up_to_id: i64) -> Result<(), AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT table_name, record_id, operation, data_snapshot FROM sync_metadata
+             WHERE id <= ?1 AND synced = 0 AND table_name IN ('projects', 'persons')
+             ORDER BY id ASC",
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let rows = stmt
+        .query_map([up_to_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    for (table, record_id, operation, snapshot) in rows {
+        let data = snapshot.and_then(|s| serde_json::from_str::<Value>(&s).ok());
+        match (operation.as_str(), data) {
+            ("DELETE", _) | (_, None) => delete_record_base(conn, &table, &record_id)?,
+            (_, Some(data)) => save_record_base(conn, &table, &record_id, &data)?,
+        }
+    }
+    Ok(())
+}
+
+/// Drop older unsynced snapshots of a row once a newer merged snapshot (at `version`)
+/// is queued; the merged row already contains those local edits.
+pub(crate) fn supersede_pending_snapshots(
+    conn: &Connection,
+    table: &str,
+    record_id: &str,
+    version: i64,
+) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE sync_metadata SET synced = 1
+         WHERE synced = 0 AND table_name = ?1 AND record_id = ?2
+           AND id < (
+             SELECT MAX(id) FROM sync_metadata
+             WHERE synced = 0 AND table_name = ?1 AND record_id = ?2 AND version = ?3
+           )",
+        params![table, record_id, version],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,767 @@
+//! Google Drive sync backend (Drive API v3, OAuth 2.0 handled in Rust).
+//!
+//! 设计要点：
+//! - 只申请 `drive.appdata` 权限：同步对象存于用户 Drive 的应用专属隐藏目录（appDataFolder），
+//!   用户看不到也不会误删，应用也读不到用户的其他文件。对象键直接作为文件名，目录结构是扁平的。
+//! - 授权：桌面端 OAuth 2.0 授权码 + PKCE。`begin_authorization` 在 127.0.0.1 的随机端口监听并返回授权链接，
+//!   `finish_authorization` 等待浏览器回跳、校验 state，再用授权码换取令牌。client_id 由用户在 Google Cloud
+//!   创建（桌面应用类型）；client_secret 对桌面应用不保密，可选。
+//! - 令牌存于 `sync_config`（与 S3 密钥一致，日志中脱敏）：refresh token 长期有效，access token 约一小时。
+//!   请求前临近过期即刷新，收到 401 时强制刷新并重试一次，刷新结果写回 `sync_config`。
+//!   refresh token 被撤销时返回 `[invalid_grant]`，需重新授权。
+//! - Drive 允许同名文件：上传时覆盖已有文件；若出现多个同名文件，读写最近修改的一个。
+//! - 列举：分页列出应用目录中的全部文件再按前缀过滤（Drive 查询不支持前缀匹配）。
+
+use crate::error::AppError;
+use crate::infra::http_server::{parse_request_head, read_head, READ_TIMEOUT};
+use crate::infra::redact::register_secrets;
+use crate::infra::{get_connection, DbPool};
+use crate::sync::backend::{BackendFuture, BackendKind, BackendResult, SyncBackend};
+use crate::sync::s3_client::{
+    is_transient_error_code, random_jitter, ObjectOptions, RetryPolicy, S3ObjectSummary,
+};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+
+pub const GDRIVE_CLIENT_ID_KEY: &str = "gdrive_client_id";
+pub const GDRIVE_CLIENT_SECRET_KEY: &str = "gdrive_client_secret";
+pub const GDRIVE_REFRESH_TOKEN_KEY: &str = "gdrive_refresh_token";
+pub const GDRIVE_ACCESS_TOKEN_KEY: &str = "gdrive_access_token";
+pub const GDRIVE_TOKEN_EXPIRES_AT_KEY: &str = "gdrive_token_expires_at";
+
+pub const GDRIVE_SCOPE: &str = "https://www.googleapis.com/auth/drive.appdata";
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const REVOKE_URL: &str = "https://oauth2.googleapis.com/revoke";
+const FILES_URL: &str = "https://www.googleapis.com/drive/v3/files";
+const UPLOAD_URL: &str = "https://www.googleapis.com/upload/drive/v3/files";
+const APP_DATA_FOLDER: &str = "appDataFolder";
+/// Namespace claims use this in place of a bucket name.
+const NAMESPACE: &str = "gdrive:appDataFolder";
+const FILE_FIELDS: &str = "id,name,size,modifiedTime";
+
+/// Refresh this long before the access token expires.
+const TOKEN_EXPIRY_MARGIN_SECS: i64 = 60;
+/// How long `finish_authorization` waits for the browser to come back.
+pub const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Authorization started by `begin_authorization`, completed by `finish_authorization`.
+static PENDING_AUTHORIZATION: Mutex<Option<PendingAuthorization>> = Mutex::new(None);
+
+struct PendingAuthorization {
+    listener: TcpListener,
+    redirect_uri: String,
+    state: String,
+    code_verifier: String,
+    client_id: String,
+    client_secret: Option<String>,
+}
+
+/// Error returned by the Drive or OAuth endpoints.
+#[derive(Debug)]
+pub struct GoogleDriveError {
+    pub status: Option<u16>,
+    /// Drive `reason` (e.g. `notFound`) or OAuth `error` (e.g. `invalid_grant`).
+    pub code: String,
+    pub message: String,
+}
+
+impl fmt::Display for GoogleDriveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "Google Drive {} {}: {}", status, self.code, self.message),
+            None => write!(f, "Google Drive {}: {}", self.code, self.message),
+        }
+    }
+}
+
+impl std::error::Error for GoogleDriveError {}
+
+impl GoogleDriveError {
+    fn not_found(key: &str) -> Self {
+        Self {
+            status: Some(404),
+            code: "notFound".to_string(),
+            message: format!("File not found: {}", key),
+        }
+    }
+
+    async fn from_response(response: Response) -> Self {
+        let status = response.status().as_u16();
+        let body = response.bytes().await.unwrap_or_default();
+        let (code, message) = parse_error_body(&body);
+        Self {
+            status: Some(status),
+            code: code.unwrap_or_else(|| format!("http_{}", status)),
+            message: message.unwrap_or_default(),
+        }
+    }
+}
+
+/// Code and message from a Drive (`{"error": {"message", "errors": [{"reason"}]}}`) or
+/// OAuth (`{"error": "...", "error_description": "..."}`) error body.
+pub fn parse_error_body(body: &[u8]) -> (Option<String>, Option<String>) {
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return (None, None);
+    };
+    match &value["error"] {
+        serde_json::Value::String(code) => (
+            Some(code.clone()),
+            value["error_description"].as_str().map(ToString::to_string),
+        ),
+        serde_json::Value::Object(_) => (
+            value["error"]["errors"][0]["reason"]
+                .as_str()
+                .or_else(|| value["error"]["status"].as_str())
+                .map(ToString::to_string),
+            value["error"]["message"].as_str().map(ToString::to_string),
+        ),
+        _ => (None, None),
+    }
+}
+
+/// OAuth client and tokens as stored in `sync_config`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoogleDriveCredentials {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub refresh_token: String,
+    pub access_token: String,
+    /// Unix seconds.
+    pub expires_at: i64,
+}
+
+impl GoogleDriveCredentials {
+    /// Saved credentials, `None` until an authorization has completed.
+    pub fn load(conn: &Connection) -> Result<Option<Self>, AppError> {
+        let value = |key: &str| -> Result<Option<String>, AppError> {
+            Ok(conn
+                .query_row(
+                    "SELECT value FROM sync_config WHERE key = ?1",
+                    [key],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()?
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()))
+        };
+        let (Some(client_id), Some(refresh_token)) = (
+            value(GDRIVE_CLIENT_ID_KEY)?,
+            value(GDRIVE_REFRESH_TOKEN_KEY)?,
+        ) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            client_id,
+            client_secret: value(GDRIVE_CLIENT_SECRET_KEY)?,
+            refresh_token,
+            access_token: value(GDRIVE_ACCESS_TOKEN_KEY)?.unwrap_or_default(),
+            expires_at: value(GDRIVE_TOKEN_EXPIRES_AT_KEY)?
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+        }))
+    }
+
+    pub fn save(&self, conn: &Connection) -> Result<(), AppError> {
+        for (key, value) in [
+            (GDRIVE_CLIENT_ID_KEY, self.client_id.as_str()),
+            (
+                GDRIVE_CLIENT_SECRET_KEY,
+                self.client_secret.as_deref().unwrap_or_default(),
+            ),
+            (GDRIVE_REFRESH_TOKEN_KEY, self.refresh_token.as_str()),
+        ] {
+            conn.execute(
+                "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )?;
+        }
+        self.save_access_token(conn)
+    }
+
+    fn save_access_token(&self, conn: &Connection) -> Result<(), AppError> {
+        for (key, value) in [
+            (GDRIVE_ACCESS_TOKEN_KEY, self.access_token.clone()),
+            (GDRIVE_TOKEN_EXPIRES_AT_KEY, self.expires_at.to_string()),
+        ] {
+            conn.execute(
+                "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Forget the client and tokens (the client id is kept for the next authorization).
+    pub fn clear(conn: &Connection) -> Result<(), AppError> {
+        for key in [
+            GDRIVE_REFRESH_TOKEN_KEY,
+            GDRIVE_ACCESS_TOKEN_KEY,
+            GDRIVE_TOKEN_EXPIRES_AT_KEY,
+        ] {
+            conn.execute("DELETE FROM sync_config WHERE key = ?1", [key])?;
+        }
+        Ok(())
+    }
+
+    /// The access token is usable for at least `TOKEN_EXPIRY_MARGIN_SECS` more.
+    pub fn access_token_valid(&self, now_unix: i64) -> bool {
+        !self.access_token.is_empty() && self.expires_at - TOKEN_EXPIRY_MARGIN_SECS > now_unix
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: i64,
+    refresh_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DriveFile {
+    id: String,
+    name: String,
+    /// int64 encoded as a string.
+    size: Option<String>,
+    modified_time: Option<String>,
+}
+
+impl DriveFile {
+    fn summary(self) -> S3ObjectSummary {
+        S3ObjectSummary {
+            last_modified_unix: self
+                .modified_time
+                .as_deref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.timestamp()),
+            size_bytes: self.size.and_then(|s| s.parse().ok()),
+            key: self.name,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileList {
+    #[serde(default)]
+    files: Vec<DriveFile>,
+    next_page_token: Option<String>,
+}
+
+pub struct GoogleDriveBackend {
+    http: Client,
+    pool: DbPool,
+    pub device_id: String,
+    credentials: tokio::sync::Mutex<GoogleDriveCredentials>,
+    retry: RetryPolicy,
+}
+
+impl GoogleDriveBackend {
+    /// Backend for the saved authorization; `SyncConfigIncomplete` until Drive is connected.
+    pub fn from_pool(pool: &DbPool, device_id: String) -> Result<Self, AppError> {
+        let credentials = {
+            let conn = get_connection(pool);
+            GoogleDriveCredentials::load(&conn)?
+        }
+        .ok_or(AppError::SyncConfigIncomplete)?;
+        Ok(Self {
+            http: Client::new(),
+            pool: pool.clone(),
+            device_id,
+            credentials: tokio::sync::Mutex::new(credentials),
+            retry: RetryPolicy::default(),
+        })
+    }
+
+    /// Use a custom retry policy for transient Drive failures.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Current access token, refreshed (and saved) when it is about to expire or `force`d.
+    async fn access_token(&self, force: bool) -> BackendResult<String> {
+        let mut credentials = self.credentials.lock().await;
+        let now = chrono::Utc::now().timestamp();
+        if !force && credentials.access_token_valid(now) {
+            return Ok(credentials.access_token.clone());
+        }
+        let mut form = vec![
+            ("grant_type", "refresh_token"),
+            ("client_id", credentials.client_id.as_str()),
+            ("refresh_token", credentials.refresh_token.as_str()),
+        ];
+        if let Some(secret) = credentials.client_secret.as_deref() {
+            form.push(("client_secret", secret));
+        }
+        let token = request_token(&self.http, &form).await?;
+        credentials.access_token = token.access_token;
+        credentials.expires_at = now + token.expires_in;
+        register_secrets([credentials.access_token.as_str()]);
+        {
+            let conn = get_connection(&self.pool);
+            credentials.save_access_token(&conn)?;
+        }
+        log::info!("Google Drive access token refreshed");
+        Ok(credentials.access_token.clone())
+    }
+
+    /// Send an authorized request, refreshing the token once on 401 and retrying transient
+    /// failures with backoff. `build` creates a fresh request for every attempt.
+    async fn send<F>(&self, op: &str, build: F) -> BackendResult<Response>
+    where
+        F: Fn(&Client, &str) -> RequestBuilder,
+    {
+        let mut attempt = 1;
+        let mut refreshed = false;
+        loop {
+            let token = self.access_token(false).await?;
+            let retry_reason = match build(&self.http, &token).send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) if response.status() == StatusCode::UNAUTHORIZED && !refreshed => {
+                    refreshed = true;
+                    self.access_token(true).await?;
+                    continue;
+                }
+                Ok(response)
+                    if attempt < self.retry.max_attempts
+                        && is_transient_error_code(None, Some(response.status().as_u16())) =>
+                {
+                    format!("HTTP {}", response.status())
+                }
+                Ok(response) => {
+                    return Err(Box::new(GoogleDriveError::from_response(response).await))
+                }
+                Err(e)
+                    if attempt < self.retry.max_attempts
+                        && (e.is_timeout() || e.is_connect() || e.is_request()) =>
+                {
+                    e.to_string()
+                }
+                Err(e) => return Err(Box::new(e)),
+            };
+            let delay = self.retry.backoff(attempt, random_jitter());
+            log::warn!(
+                "Google Drive {} failed (attempt {}/{}), retrying in {:.2?}: {}",
+                op,
+                attempt,
+                self.retry.max_attempts,
+                delay,
+                retry_reason
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Most recently modified file named `key`.
+    async fn find_file(&self, key: &str) -> BackendResult<Option<DriveFile>> {
+        let query = format!(
+            "name = '{}' and trashed = false",
+            key.replace('\\', "\\\\").replace('\'', "\\'")
+        );
+        let fields = format!("files({})", FILE_FIELDS);
+        let response = self
+            .send("find", |http, token| {
+                http.get(FILES_URL).bearer_auth(token).query(&[
+                    ("spaces", APP_DATA_FOLDER),
+                    ("q", query.as_str()),
+                    ("orderBy", "modifiedTime desc"),
+                    ("pageSize", "10"),
+                    ("fields", fields.as_str()),
+                ])
+            })
+            .await?;
+        let list: FileList = serde_json::from_slice(&response.bytes().await?)?;
+        Ok(list.files.into_iter().next())
+    }
+
+    async fn upload_file(&self, key: &str, data: Vec<u8>) -> BackendResult<()> {
+        let data_len = data.len();
+        match self.find_file(key).await? {
+            Some(file) => {
+                let url = format!("{}/{}", UPLOAD_URL, file.id);
+                self.send("update", |http, token| {
+                    http.patch(&url)
+                        .bearer_auth(token)
+                        .query(&[("uploadType", "media")])
+                        .header("Content-Type", "application/octet-stream")
+                        .body(data.clone())
+                })
+                .await?;
+            }
+            None => {
+                let boundary = format!("projex-{}", uuid::Uuid::new_v4().simple());
+                let metadata = serde_json::json!({ "name": key, "parents": [APP_DATA_FOLDER] });
+                let body = multipart_related_body(&boundary, &metadata, &data);
+                let content_type = format!("multipart/related; boundary={}", boundary);
+                self.send("create", |http, token| {
+                    http.post(UPLOAD_URL)
+                        .bearer_auth(token)
+                        .query(&[("uploadType", "multipart"), ("fields", "id")])
+                        .header("Content-Type", content_type.as_str())
+                        .body(body.clone())
+                })
+                .await?;
+            }
+        }
+        log::info!("Google Drive upload: {} ({} bytes)", key, data_len);
+        Ok(())
+    }
+
+    async fn download_file(&self, key: &str) -> BackendResult<Vec<u8>> {
+        let file = self
+            .find_file(key)
+            .await?
+            .ok_or_else(|| GoogleDriveError::not_found(key))?;
+        let url = format!("{}/{}", FILES_URL, file.id);
+        let response = self
+            .send("download", |http, token| {
+                http.get(&url).bearer_auth(token).query(&[("alt", "media")])
+            })
+            .await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn delete_file(&self, key: &str) -> BackendResult<()> {
+        let Some(file) = self.find_file(key).await? else {
+            return Ok(());
+        };
+        let url = format!("{}/{}", FILES_URL, file.id);
+        match self
+            .send("delete", |http, token| http.delete(&url).bearer_auth(token))
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e)
+                if e.downcast_ref::<GoogleDriveError>()
+                    .is_some_and(|e| e.status == Some(404)) =>
+            {
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn list_files(&self, prefix: &str) -> BackendResult<Vec<S3ObjectSummary>> {
+        let fields = format!("nextPageToken,files({})", FILE_FIELDS);
+        let mut objects = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let response = self
+                .send("list", |http, token| {
+                    let request = http.get(FILES_URL).bearer_auth(token).query(&[
+                        ("spaces", APP_DATA_FOLDER),
+                        ("q", "trashed = false"),
+                        ("pageSize", "1000"),
+                        ("fields", fields.as_str()),
+                    ]);
+                    match page_token.as_deref() {
+                        Some(page) => request.query(&[("pageToken", page)]),
+                        None => request,
+                    }
+                })
+                .await?;
+            let list: FileList = serde_json::from_slice(&response.bytes().await?)?;
+            objects.extend(
+                list.files
+                    .into_iter()
+                    .filter(|file| file.name.starts_with(prefix))
+                    .map(DriveFile::summary),
+            );
+            match list.next_page_token {
+                Some(next) => page_token = Some(next),
+                None => break,
+            }
+        }
+        // Same order as S3 listings; duplicate names are listed once.
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        objects.dedup_by(|a, b| a.key == b.key);
+        Ok(objects)
+    }
+
+    async fn check_access(&self) -> BackendResult<()> {
+        self.send("test", |http, token| {
+            http.get(FILES_URL).bearer_auth(token).query(&[
+                ("spaces", APP_DATA_FOLDER),
+                ("pageSize", "1"),
+                ("fields", "files(id)"),
+            ])
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+impl SyncBackend for GoogleDriveBackend {
+    fn kind(&self) -> BackendKind {
+        BackendKind::GoogleDrive
+    }
+
+    fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    fn namespace(&self) -> &str {
+        NAMESPACE
+    }
+
+    fn upload<'a>(&'a self, key: &'a str, data: Vec<u8>) -> BackendFuture<'a, ()> {
+        Box::pin(self.upload_file(key, data))
+    }
+
+    fn upload_with_options<'a>(
+        &'a self,
+        key: &'a str,
+        data: Vec<u8>,
+        _options: &'a ObjectOptions,
+    ) -> BackendFuture<'a, ()> {
+        Box::pin(self.upload_file(key, data))
+    }
+
+    fn download<'a>(&'a self, key: &'a str) -> BackendFuture<'a, Vec<u8>> {
+        Box::pin(self.download_file(key))
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BackendFuture<'a, ()> {
+        Box::pin(self.delete_file(key))
+    }
+
+    fn exists<'a>(&'a self, key: &'a str) -> BackendFuture<'a, bool> {
+        Box::pin(async move { Ok(self.find_file(key).await?.is_some()) })
+    }
+
+    fn list_with_metadata<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> BackendFuture<'a, Vec<S3ObjectSummary>> {
+        Box::pin(self.list_files(prefix))
+    }
+
+    fn test_connection(&self) -> BackendFuture<'_, ()> {
+        Box::pin(self.check_access())
+    }
+}
+
+/// `multipart/related` body for a Drive upload: JSON metadata, then the content.
+pub fn multipart_related_body(
+    boundary: &str,
+    metadata: &serde_json::Value,
+    data: &[u8],
+) -> Vec<u8> {
+    let mut body = format!(
+        "--{b}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{m}\r\n--{b}\r\nContent-Type: application/octet-stream\r\n\r\n",
+        b = boundary,
+        m = metadata
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+async fn request_token(http: &Client, form: &[(&str, &str)]) -> BackendResult<TokenResponse> {
+    let response = http.post(TOKEN_URL).form(form).send().await?;
+    if !response.status().is_success() {
+        return Err(Box::new(GoogleDriveError::from_response(response).await));
+    }
+    Ok(serde_json::from_slice(&response.bytes().await?)?)
+}
+
+/// RFC 7636 `S256` code challenge.
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    base64_url(&Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Unpadded base64url.
+fn base64_url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | ((*b as u32) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+/// Start an authorization: listen for the redirect on a loopback port and return the URL to
+/// open in the browser. Replaces an authorization that was started but not finished.
+pub async fn begin_authorization(
+    client_id: &str,
+    client_secret: Option<&str>,
+) -> Result<String, AppError> {
+    let client_id = client_id.trim();
+    if client_id.is_empty() {
+        return Err(AppError::Validation(
+            "GDRIVE_CLIENT_ID_REQUIRED: an OAuth client id is required".into(),
+        ));
+    }
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| AppError::Sync(format!("Failed to listen for the OAuth redirect: {}", e)))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| AppError::Sync(format!("Failed to listen for the OAuth redirect: {}", e)))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}", port);
+    // 64 characters from [0-9a-f], within the 43..=128 range of RFC 7636.
+    let code_verifier = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let auth_url = Url::parse_with_params(
+        AUTH_URL,
+        &[
+            ("client_id", client_id),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", GDRIVE_SCOPE),
+            ("code_challenge", pkce_challenge(&code_verifier).as_str()),
+            ("code_challenge_method", "S256"),
+            ("state", state.as_str()),
+            // Offline access returns a refresh token; consent makes Google issue a new one.
+            ("access_type", "offline"),
+            ("prompt", "consent"),
+        ],
+    )
+    .map_err(|e| AppError::Sync(format!("Failed to build the authorization URL: {}", e)))?;
+
+    *PENDING_AUTHORIZATION
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = Some(PendingAuthorization {
+        listener,
+        redirect_uri,
+        state,
+        code_verifier,
+        client_id: client_id.to_string(),
+        client_secret: client_secret
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(ToString::to_string),
+    });
+    Ok(auth_url.to_string())
+}
+
+/// Wait for the browser redirect of the pending authorization and exchange the code for
+/// tokens. The caller saves the returned credentials.
+pub async fn finish_authorization(timeout: Duration) -> Result<GoogleDriveCredentials, AppError> {
+    let pending = PENDING_AUTHORIZATION
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+        .ok_or_else(|| {
+            AppError::Validation("GDRIVE_AUTH_NOT_STARTED: start the authorization first".into())
+        })?;
+    let query = tokio::time::timeout(timeout, wait_for_redirect(&pending.listener))
+        .await
+        .map_err(|_| AppError::Sync("GDRIVE_AUTH_TIMEOUT: no answer from the browser".into()))?;
+    let param = |name: &str| {
+        query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    };
+    if param("state") != Some(pending.state.as_str()) {
+        return Err(AppError::Validation(
+            "GDRIVE_AUTH_STATE_MISMATCH: the redirect does not belong to this authorization".into(),
+        ));
+    }
+    if let Some(error) = param("error") {
+        return Err(AppError::Validation(format!(
+            "GDRIVE_AUTH_DENIED: {}",
+            error
+        )));
+    }
+    let code = param("code")
+        .ok_or_else(|| AppError::Validation("GDRIVE_AUTH_DENIED: no authorization code".into()))?;
+
+    let http = Client::new();
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("client_id", pending.client_id.as_str()),
+        ("redirect_uri", pending.redirect_uri.as_str()),
+        ("code_verifier", pending.code_verifier.as_str()),
+    ];
+    if let Some(secret) = pending.client_secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+    let token = request_token(&http, &form)
+        .await
+        .map_err(|e| AppError::Sync(format!("Google Drive authorization failed: {}", e)))?;
+    let refresh_token = token.refresh_token.ok_or_else(|| {
+        AppError::Sync("Google Drive authorization failed: no refresh token returned".into())
+    })?;
+    Ok(GoogleDriveCredentials {
+        client_id: pending.client_id,
+        client_secret: pending.client_secret,
+        refresh_token,
+        access_token: token.access_token,
+        expires_at: chrono::Utc::now().timestamp() + token.expires_in,
+    })
+}
+
+/// Query parameters of the first request that carries `code` or `error`; other requests
+/// (favicon, preconnects) get a 404.
+async fn wait_for_redirect(listener: &TcpListener) -> Vec<(String, String)> {
+    loop {
+        let Ok((mut stream, _)) = listener.accept().await else {
+            continue;
+        };
+        // Bounded: a preconnect that never sends a request would block the redirect forever.
+        let request = match tokio::time::timeout(READ_TIMEOUT, read_head(&mut stream)).await {
+            Ok(Ok(Some(head))) => parse_request_head(&head).ok(),
+            _ => None,
+        };
+        let query = request
+            .map(|r| r.query)
+            .filter(|q| q.iter().any(|(k, _)| k == "code" || k == "error"));
+        let (status, body) = match query {
+            Some(_) => (
+                "200 OK",
+                "<html><body>Projex: authorization received, you can close this window.</body></html>",
+            ),
+            None => ("404 Not Found", ""),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+        if let Some(query) = query {
+            return query;
+        }
+    }
+}
+
+/// Best-effort revocation of a refresh token at Google.
+pub async fn revoke_token(refresh_token: &str) {
+    let result = Client::new()
+        .post(REVOKE_URL)
+        .form(&[("token", refresh_token)])
+        .send()
+        .await;
+    match result {
+        Ok(response) if response.status().is_success() => {}
+        Ok(response) => log::warn!("Google Drive token revocation: HTTP {}", response.status()),
+        Err(e) => log::warn!("Google Drive token revocation failed: {}", e),
+    }
+}
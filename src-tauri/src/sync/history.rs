@@ -0,0 +1,214 @@
+//! Sync history log (one row per sync attempt, local-only diagnostics)
+
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use crate::sync::ApplyDeltaStats;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Keep at most this many history rows; older rows are pruned on insert.
+const SYNC_HISTORY_MAX_ROWS: i64 = 1000;
+
+/// Counters accumulated by the sync pipeline during one attempt.
+#[derive(Debug, Clone, Default)]
+pub struct SyncRunStats {
+    pub ops_uploaded: i64,
+    pub ops_downloaded: i64,
+    pub bytes_uploaded: i64,
+    pub bytes_downloaded: i64,
+    pub deltas_uploaded: i64,
+    pub deltas_applied: i64,
+    /// Remote deltas parked for review (review-before-apply mode).
+    pub deltas_staged: i64,
+    pub skipped_stale_upserts: i64,
+    pub conflicts_detected: i64,
+    /// Remote objects under `deltas/` set aside because their key is not understood.
+    pub quarantined_objects: Vec<String>,
+    /// Pipeline phase currently running (reported with errors).
+    pub phase: Option<&'static str>,
+    /// Remote delta currently being processed (reported with errors).
+    pub delta_key: Option<String>,
+}
+
+impl SyncRunStats {
+    /// Derive the transfer direction from what actually moved.
+    pub fn direction(&self) -> &'static str {
+        let up = self.ops_uploaded > 0 || self.bytes_uploaded > 0;
+        let down = self.ops_downloaded > 0 || self.bytes_downloaded > 0;
+        match (up, down) {
+            (true, true) => "both",
+            (true, false) => "up",
+            (false, true) => "down",
+            (false, false) => "none",
+        }
+    }
+
+    /// Fold one `apply_delta` outcome into the run counters.
+    pub fn add_applied(&mut self, applied: &ApplyDeltaStats) {
+        self.deltas_applied += 1;
+        self.skipped_stale_upserts += applied.skipped_stale_upserts as i64;
+        self.conflicts_detected += applied.conflicts_detected as i64;
+    }
+
+    pub fn to_result(&self, message: impl Into<String>) -> SyncRunResult {
+        SyncRunResult {
+            message: message.into(),
+            direction: self.direction().to_string(),
+            ops_uploaded: self.ops_uploaded,
+            ops_downloaded: self.ops_downloaded,
+            deltas_uploaded: self.deltas_uploaded,
+            deltas_applied: self.deltas_applied,
+            deltas_staged: self.deltas_staged,
+            skipped_stale_upserts: self.skipped_stale_upserts,
+            conflicts_detected: self.conflicts_detected,
+            quarantined_objects: self.quarantined_objects.clone(),
+        }
+    }
+}
+
+/// Outcome of a successful sync run (returned by `cmd_sync_full` / `cmd_sync_confirm_wipe`).
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncRunResult {
+    pub message: String,
+    /// "none" | "up" | "down" | "both"
+    pub direction: String,
+    pub ops_uploaded: i64,
+    pub ops_downloaded: i64,
+    pub deltas_uploaded: i64,
+    pub deltas_applied: i64,
+    pub deltas_staged: i64,
+    /// Remote upserts not applied because the local row is newer or was deleted.
+    pub skipped_stale_upserts: i64,
+    /// Automatic resolutions journaled to `sync_conflicts` (see `cmd_sync_list_conflicts`).
+    pub conflicts_detected: i64,
+    /// Remote object keys ignored because they are not valid delta keys.
+    pub quarantined_objects: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SyncHistoryReq {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncHistoryEntry {
+    pub id: i64,
+    pub started_at: String,
+    pub finished_at: String,
+    /// "none" | "up" | "down" | "both"
+    pub direction: String,
+    pub ops_uploaded: i64,
+    pub ops_downloaded: i64,
+    pub bytes_uploaded: i64,
+    pub bytes_downloaded: i64,
+    /// "success" | "failed"
+    pub result: String,
+    pub error: Option<String>,
+    pub deltas_uploaded: i64,
+    pub deltas_applied: i64,
+    pub deltas_staged: i64,
+    pub skipped_stale_upserts: i64,
+    pub conflicts_detected: i64,
+    pub quarantined_objects: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncHistoryPage {
+    pub items: Vec<SyncHistoryEntry>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Persist one sync attempt and prune rows beyond the retention cap.
+pub fn record_sync_history(
+    conn: &Connection,
+    started_at: &str,
+    stats: &SyncRunStats,
+    error: Option<&str>,
+) -> Result<(), AppError> {
+    let finished_at = chrono::Utc::now().to_rfc3339();
+    let result = if error.is_some() { "failed" } else { "success" };
+    conn.execute(
+        "INSERT INTO sync_history (started_at, finished_at, direction, ops_uploaded, ops_downloaded, bytes_uploaded, bytes_downloaded, result, error,
+             deltas_uploaded, deltas_applied, deltas_staged, skipped_stale_upserts, conflicts_detected, quarantined_objects)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            started_at,
+            finished_at,
+            stats.direction(),
+            stats.ops_uploaded,
+            stats.ops_downloaded,
+            stats.bytes_uploaded,
+            stats.bytes_downloaded,
+            result,
+            error,
+            stats.deltas_uploaded,
+            stats.deltas_applied,
+            stats.deltas_staged,
+            stats.skipped_stale_upserts,
+            stats.conflicts_detected,
+            stats.quarantined_objects.len() as i64,
+        ],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    conn.execute(
+        "DELETE FROM sync_history WHERE id NOT IN (SELECT id FROM sync_history ORDER BY id DESC LIMIT ?1)",
+        [SYNC_HISTORY_MAX_ROWS],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Paginated history, newest first.
+pub fn list_sync_history(pool: &DbPool, req: SyncHistoryReq) -> Result<SyncHistoryPage, AppError> {
+    let limit = req.limit.unwrap_or(50).clamp(1, 200);
+    let offset = req.offset.unwrap_or(0).max(0);
+
+    let conn = get_connection(pool);
+    let total: i64 = conn
+        .query_row("SELECT COUNT(*) FROM sync_history", [], |row| row.get(0))
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, started_at, finished_at, direction, ops_uploaded, ops_downloaded, bytes_uploaded, bytes_downloaded, result, error,
+                    deltas_uploaded, deltas_applied, deltas_staged, skipped_stale_upserts, conflicts_detected, quarantined_objects
+             FROM sync_history ORDER BY id DESC LIMIT ?1 OFFSET ?2",
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let items = stmt
+        .query_map([limit, offset], |row| {
+            Ok(SyncHistoryEntry {
+                id: row.get(0)?,
+                started_at: row.get(1)?,
+                finished_at: row.get(2)?,
+                direction: row.get(3)?,
+                ops_uploaded: row.get(4)?,
+                ops_downloaded: row.get(5)?,
+                bytes_uploaded: row.get(6)?,
+                bytes_downloaded: row.get(7)?,
+                result: row.get(8)?,
+                error: row.get(9)?,
+                deltas_uploaded: row.get(10)?,
+                deltas_applied: row.get(11)?,
+                deltas_staged: row.get(12)?,
+                skipped_stale_upserts: row.get(13)?,
+                conflicts_detected: row.get(14)?,
+                quarantined_objects: row.get(15)?,
+            })
+        })
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    Ok(SyncHistoryPage {
+        items,
+        total,
+        limit,
+        offset,
+    })
+}
@@ -0,0 +1,186 @@
+//! Large local imports published as a snapshot instead of row-by-row deltas.
+//!
+//! 导入端：`import_json_string` / 人员 CSV 导入新增行数 ≥ `auto_snapshot_import_threshold`
+//! （`sync_config`，默认 500，0 = 关闭）且已启用同步时，上传一份新快照，再上传只含一个
+//! `_control` 操作（`SNAPSHOT_IMPORT`，指向快照 key）的小 delta，并把本次导入产生的 INSERT 元数据标记为已同步；
+//! 导入产生的 UPDATE（如 CSV 更新已有人员）仍按普通 delta 上传。任一步失败则不标记，全部回退为普通 delta。
+//!
+//! 接收端：遇到 `SNAPSHOT_IMPORT` 时下载该快照（已被保留策略裁剪时改用来源设备最新的快照），
+//! 按导入语义 `INSERT OR IGNORE` 合并：只补齐本地缺失的行，不覆盖本地已有行；
+//! 跳过本地有墓碑的行与本机同步范围之外的行（忽略的表、已归档项目的明细）；合并产生的元数据直接标记为已同步。
+//! 只新增不覆盖，因此审阅模式下也直接合并，不进入暂存区。
+
+use super::snapshot::{Snapshot, RESTORE_DIFF_SECTIONS};
+use super::table_filter::{archived_project_ids, strip_export_rows, SyncScope};
+use super::tombstone::load_tombstone_version;
+use super::Operation;
+use super::OperationType;
+use crate::app::{import_json_conn, ImportResult};
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+
+pub const AUTO_SNAPSHOT_IMPORT_THRESHOLD_KEY: &str = "auto_snapshot_import_threshold";
+pub const DEFAULT_AUTO_SNAPSHOT_IMPORT_THRESHOLD: i64 = 500;
+
+const CONTROL_TABLE: &str = "_control";
+const SNAPSHOT_IMPORT_TYPE: &str = "SNAPSHOT_IMPORT";
+
+/// Snapshot referenced by a `SNAPSHOT_IMPORT` control operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotImportRef {
+    pub snapshot_key: String,
+    pub checksum: String,
+}
+
+/// Configured threshold; `0` means never publish imports as snapshots.
+pub fn load_auto_snapshot_import_threshold(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [AUTO_SNAPSHOT_IMPORT_THRESHOLD_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| v.trim().parse::<i64>().ok())
+    .filter(|v| *v >= 0)
+    .unwrap_or(DEFAULT_AUTO_SNAPSHOT_IMPORT_THRESHOLD)
+}
+
+/// Whether an import of `imported_rows` new rows should be published as a snapshot.
+pub fn should_publish_import_snapshot(conn: &Connection, imported_rows: usize) -> bool {
+    let sync_enabled = conn
+        .query_row(
+            "SELECT value FROM sync_config WHERE key = 'sync_enabled'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .is_ok_and(|v| v.trim() == "1");
+    let threshold = load_auto_snapshot_import_threshold(conn);
+    sync_enabled && threshold > 0 && imported_rows as i64 >= threshold
+}
+
+pub fn snapshot_import_operation(snapshot_key: &str, checksum: &str, rows: usize) -> Operation {
+    Operation {
+        table_name: CONTROL_TABLE.to_string(),
+        record_id: snapshot_key.to_string(),
+        op_type: OperationType::Insert,
+        data: Some(serde_json::json!({
+            "type": SNAPSHOT_IMPORT_TYPE,
+            "snapshot_key": snapshot_key,
+            "checksum": checksum,
+            "rows": rows,
+            "created_at": chrono::Utc::now().to_rfc3339(),
+        })),
+        version: 1,
+    }
+}
+
+pub fn extract_snapshot_import(operations: &[Operation]) -> Option<SnapshotImportRef> {
+    operations.iter().find_map(|op| {
+        if op.table_name != CONTROL_TABLE {
+            return None;
+        }
+        let data = op.data.as_ref()?;
+        if data.get("type")?.as_str()? != SNAPSHOT_IMPORT_TYPE {
+            return None;
+        }
+        Some(SnapshotImportRef {
+            snapshot_key: data
+                .get("snapshot_key")
+                .and_then(|v| v.as_str())
+                .unwrap_or(op.record_id.as_str())
+                .to_string(),
+            checksum: data
+                .get("checksum")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        })
+    })
+}
+
+/// Highest `sync_metadata.id` (0 when empty); taken before an import to delimit its rows.
+pub fn max_sync_metadata_id(conn: &Connection) -> Result<i64, AppError> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(id), 0) FROM sync_metadata",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::Db(e.to_string()))
+}
+
+/// Importing side: mark the INSERT metadata written by the import (`after_id < id <= up_to_id`)
+/// as synced, since the published snapshot carries those rows.
+pub fn mark_import_inserts_synced(
+    conn: &Connection,
+    after_id: i64,
+    up_to_id: i64,
+) -> Result<usize, AppError> {
+    conn.execute(
+        "UPDATE sync_metadata SET synced = 1
+         WHERE id > ?1 AND id <= ?2 AND synced = 0 AND operation = 'INSERT'
+           AND table_name <> ?3",
+        params![after_id, up_to_id, CONTROL_TABLE],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))
+}
+
+/// Receiving side: insert the snapshot rows missing locally (never overwrite) and keep the
+/// resulting metadata out of the next upload.
+pub fn merge_import_snapshot(
+    conn: &Connection,
+    snapshot: &Snapshot,
+) -> Result<ImportResult, AppError> {
+    if !snapshot.verify() {
+        return Err(AppError::Sync(
+            "Import snapshot integrity check failed".to_string(),
+        ));
+    }
+    let mut data: serde_json::Value = serde_json::from_str(&snapshot.data)
+        .map_err(|e| AppError::Sync(format!("Invalid import snapshot data: {}", e)))?;
+    let scope = SyncScope::load(conn)?;
+    let archived = if scope.exclude_archived_projects {
+        let mut archived = archived_project_ids(&data);
+        archived.extend(load_archived_project_ids(conn)?);
+        archived
+    } else {
+        Default::default()
+    };
+    strip_export_rows(&mut data, &scope.ignored_tables, &archived);
+
+    for (section, table) in RESTORE_DIFF_SECTIONS {
+        let Some(rows) = data.get_mut(*section).and_then(|v| v.as_array_mut()) else {
+            continue;
+        };
+        let mut kept = Vec::with_capacity(rows.len());
+        for row in rows.drain(..) {
+            let id = row.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+            // Deleted here after the snapshot was taken elsewhere: do not resurrect.
+            if load_tombstone_version(conn, table, id)?.is_none() {
+                kept.push(row);
+            }
+        }
+        *rows = kept;
+    }
+
+    let before_id = max_sync_metadata_id(conn)?;
+    let result = import_json_conn(conn, &data.to_string())?;
+    conn.execute(
+        "UPDATE sync_metadata SET synced = 1 WHERE id > ?1 AND synced = 0",
+        params![before_id],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(result)
+}
+
+/// Projects archived locally: merged rows never overwrite them, so their details stay local.
+fn load_archived_project_ids(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM projects WHERE current_status = 'ARCHIVED'")
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(ids)
+}
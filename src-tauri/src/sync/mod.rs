@@ -1,11 +1,46 @@
 //! S3 sync module
 
+pub mod activity;
+pub mod backend;
+pub mod compaction;
+pub mod config_check;
+pub mod conflicts;
+pub mod deferred;
 pub mod delta_sync;
+pub mod device_health;
+pub mod device_registry;
+pub mod error_log;
+pub mod field_merge;
+pub mod gdrive;
+pub mod history;
+pub mod import_snapshot;
+pub mod namespace_claim;
+pub mod offline;
+pub mod pause;
+pub mod presence;
+pub mod protocol;
+pub mod repair;
+pub mod replica;
 pub mod s3_client;
 pub mod snapshot;
+pub mod snapshot_catalog;
+pub mod staging;
+pub mod storage_class;
+pub mod table_filter;
+pub mod tag_set;
+pub mod throttle;
+pub mod tombstone;
 pub mod vector_clock;
+pub mod verify;
 
-pub use delta_sync::{Delta, DeltaSyncEngine, Operation, OperationType};
-pub use s3_client::{S3ObjectSummary, S3SyncClient};
+pub use backend::{BackendKind, SyncBackend};
+pub use delta_sync::{
+    ApplyDeltaStats, Delta, DeltaChunkLimits, DeltaSyncEngine, Operation, OperationType,
+};
+pub use error_log::{SyncErrorEntry, SyncErrorListReq, SyncErrorPage};
+pub use history::{SyncHistoryEntry, SyncHistoryPage, SyncHistoryReq, SyncRunResult, SyncRunStats};
+pub use s3_client::{
+    ObjectOptions, PresignMethod, RetryPolicy, S3ObjectSummary, S3SyncClient, TransferConfig,
+};
 pub use snapshot::SnapshotManager;
 pub use vector_clock::VectorClock;
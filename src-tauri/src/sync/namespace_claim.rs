@@ -0,0 +1,118 @@
+//! Namespace claim: one profile per (bucket, device_id).
+//!
+//! 两个 profile 指向同一个桶且 device_id 相同（例如复制了数据目录 / 导入了备份）时，
+//! 二者会写入同一个 `deltas/<device_id>/` 与同一组游标，互相破坏。
+//! - 每个 device_id 在远端登记 `claims/<device_id>.json`（device_id + profile）。
+//! - 保存配置（启用同步时）与每次同步前检查：登记属于其它 profile 则拒绝
+//!   （`VALIDATION_ERROR` + `NAMESPACE_CLAIMED:`），提示重置 device_id 或换桶。
+//! - 检查通过后把 `<endpoint>|<bucket>|<device_id>|<profile>` 缓存到本地，之后的同步跳过远端读取。
+
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Profile that owns this database, written at every startup.
+pub const LOCAL_PROFILE_KEY: &str = "local_profile_name";
+/// Last namespace verified (or claimed) by this device.
+pub const NAMESPACE_CLAIM_VERIFIED_KEY: &str = "namespace_claim_verified";
+/// Profile assumed when none was recorded (Android / tests).
+pub const DEFAULT_CLAIM_PROFILE: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceClaim {
+    pub device_id: String,
+    pub profile: String,
+    pub claimed_at: String,
+}
+
+pub fn namespace_claim_key(device_id: &str) -> String {
+    format!("claims/{}.json", device_id)
+}
+
+pub fn record_local_profile(conn: &Connection, profile: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        params![LOCAL_PROFILE_KEY, profile],
+    )?;
+    Ok(())
+}
+
+pub fn local_profile(conn: &Connection) -> String {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [LOCAL_PROFILE_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .map(|v| v.trim().to_string())
+    .filter(|v| !v.is_empty())
+    .unwrap_or_else(|| DEFAULT_CLAIM_PROFILE.to_string())
+}
+
+/// The claim this database would write for `device_id`.
+pub fn local_namespace_claim(conn: &Connection, device_id: &str) -> NamespaceClaim {
+    NamespaceClaim {
+        device_id: device_id.to_string(),
+        profile: local_profile(conn),
+        claimed_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Cache token for a verified namespace.
+pub fn namespace_token(endpoint: Option<&str>, bucket: &str, claim: &NamespaceClaim) -> String {
+    format!(
+        "{}|{}|{}|{}",
+        endpoint.unwrap_or_default(),
+        bucket,
+        claim.device_id,
+        claim.profile
+    )
+}
+
+pub fn is_namespace_verified(conn: &Connection, token: &str) -> bool {
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [NAMESPACE_CLAIM_VERIFIED_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .is_ok_and(|v| v == token)
+}
+
+pub fn mark_namespace_verified(conn: &Connection, token: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        params![NAMESPACE_CLAIM_VERIFIED_KEY, token],
+    )?;
+    Ok(())
+}
+
+/// Compare the remote claim with ours. `Ok(true)`: no claim yet, ours must be written.
+pub fn check_namespace_claim(
+    remote: Option<&NamespaceClaim>,
+    ours: &NamespaceClaim,
+) -> Result<bool, AppError> {
+    match remote {
+        None => Ok(true),
+        Some(claim) if claim.profile == ours.profile => Ok(false),
+        Some(claim) => Err(AppError::Validation(format!(
+            "NAMESPACE_CLAIMED: device id {} is already used in this bucket by profile '{}' \
+             (claimed {}); reset this profile's device id or use another bucket",
+            claim.device_id, claim.profile, claim.claimed_at
+        ))),
+    }
+}
+
+/// Give this database a fresh device id (the way out of a collision). Objects written under the
+/// old id are then read like any other device's; the local claim cache is cleared.
+pub fn reset_device_id(conn: &Connection) -> Result<String, AppError> {
+    let device_id = uuid::Uuid::new_v4().simple().to_string();
+    conn.execute(
+        "UPDATE sync_config SET value = ?1 WHERE key = 'device_id'",
+        [&device_id],
+    )?;
+    conn.execute(
+        "DELETE FROM sync_config WHERE key = ?1",
+        [NAMESPACE_CLAIM_VERIFIED_KEY],
+    )?;
+    Ok(device_id)
+}
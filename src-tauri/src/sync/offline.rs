@@ -0,0 +1,123 @@
+//! Offline detection and retry backoff for the sync scheduler
+//!
+//! A failed scheduled sync probes the S3 endpoint with a plain TCP connect. If it is
+//! unreachable the device is marked offline: pending changes stay queued in
+//! `sync_metadata` and the scheduler retries on a short, growing delay (instead of the
+//! regular interval) until the endpoint answers again.
+
+use serde::Serialize;
+use std::time::Duration;
+
+/// First retry after going offline; doubled per failed retry up to the sync interval.
+pub const OFFLINE_RETRY_BASE: Duration = Duration::from_secs(5);
+pub const CONNECTIVITY_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_S3_HOST: &str = "s3.amazonaws.com";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OfflineQueueStatus {
+    pub offline: bool,
+    /// When the endpoint was first found unreachable (RFC 3339).
+    pub offline_since: Option<String>,
+    /// Failed retries since going offline.
+    pub retry_attempt: u32,
+    /// Next scheduled retry (RFC 3339), while offline.
+    pub next_retry_at: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct OfflineQueue {
+    status: OfflineQueueStatus,
+}
+
+impl OfflineQueue {
+    pub fn status(&self) -> OfflineQueueStatus {
+        self.status.clone()
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.status.offline
+    }
+
+    /// Record an unreachable endpoint and return the delay before the next attempt.
+    pub fn mark_offline(
+        &mut self,
+        now: chrono::DateTime<chrono::Utc>,
+        interval: Duration,
+    ) -> Duration {
+        if self.status.offline {
+            self.status.retry_attempt += 1;
+        } else {
+            self.status = OfflineQueueStatus {
+                offline: true,
+                offline_since: Some(now.to_rfc3339()),
+                retry_attempt: 0,
+                next_retry_at: None,
+            };
+        }
+        let delay = offline_retry_delay(self.status.retry_attempt, interval);
+        self.status.next_retry_at = chrono::Duration::from_std(delay)
+            .ok()
+            .map(|d| (now + d).to_rfc3339());
+        delay
+    }
+
+    /// The endpoint answered again; returns true if the device was offline.
+    pub fn mark_online(&mut self) -> bool {
+        let was_offline = self.status.offline;
+        self.status = OfflineQueueStatus::default();
+        was_offline
+    }
+}
+
+/// 5s, 10s, 20s, ... never longer than the regular sync interval.
+pub fn offline_retry_delay(retry_attempt: u32, interval: Duration) -> Duration {
+    OFFLINE_RETRY_BASE
+        .saturating_mul(1 << retry_attempt.min(16))
+        .min(interval.max(OFFLINE_RETRY_BASE))
+}
+
+/// `host:port` to probe for the configured endpoint (AWS S3 when none is set).
+pub fn probe_target(endpoint: Option<&str>) -> String {
+    let Some(endpoint) = endpoint.map(str::trim).filter(|e| !e.is_empty()) else {
+        return format!("{}:443", DEFAULT_S3_HOST);
+    };
+    let (scheme, rest) = endpoint.split_once("://").unwrap_or(("https", endpoint));
+    let authority = rest.split('/').next().unwrap_or("");
+    let default_port = if scheme.eq_ignore_ascii_case("http") {
+        80
+    } else {
+        443
+    };
+
+    if let Some(v6) = authority.strip_prefix('[') {
+        let (host, after) = v6.split_once(']').unwrap_or((v6, ""));
+        let port = after.strip_prefix(':').unwrap_or("");
+        let port = port.parse::<u16>().unwrap_or(default_port);
+        return format!("[{}]:{}", host, port);
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if port.parse::<u16>().is_ok() => format!("{}:{}", host, port),
+        _ => format!("{}:{}", authority, default_port),
+    }
+}
+
+/// Whether a TCP connection to the endpoint can be opened (DNS + connect).
+pub async fn probe_connectivity(endpoint: Option<&str>) -> bool {
+    let target = probe_target(endpoint);
+    match tokio::time::timeout(
+        CONNECTIVITY_PROBE_TIMEOUT,
+        tokio::net::TcpStream::connect(target.as_str()),
+    )
+    .await
+    {
+        Ok(Ok(_)) => true,
+        Ok(Err(e)) => {
+            log::info!("Connectivity probe failed: {} - {}", target, e);
+            false
+        }
+        Err(_) => {
+            log::info!("Connectivity probe timed out: {}", target);
+            false
+        }
+    }
+}
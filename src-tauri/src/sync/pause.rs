@@ -0,0 +1,92 @@
+//! Scheduler controls: user pause/resume and debounced "sync after local writes"
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+pub const SYNC_ON_WRITE_KEY: &str = "sync_on_write";
+pub const SYNC_ON_WRITE_DEBOUNCE_SECS_KEY: &str = "sync_on_write_debounce_secs";
+pub const DEFAULT_SYNC_ON_WRITE_DEBOUNCE_SECS: u64 = 10;
+/// How often the scheduler looks for new local changes while waiting.
+pub const WRITE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SyncPauseState {
+    pub paused: bool,
+    /// RFC 3339; `None` while paused means "until resumed".
+    pub paused_until: Option<String>,
+}
+
+/// User-requested pause of background syncing (in memory, cleared on restart).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncPause {
+    #[default]
+    Running,
+    Until(DateTime<Utc>),
+    Indefinite,
+}
+
+impl SyncPause {
+    pub fn is_paused(&self, now: DateTime<Utc>) -> bool {
+        match self {
+            SyncPause::Running => false,
+            SyncPause::Until(until) => *until > now,
+            SyncPause::Indefinite => true,
+        }
+    }
+
+    /// Time left until an expiring pause ends.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<Duration> {
+        match self {
+            SyncPause::Until(until) if *until > now => (*until - now).to_std().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn state(&self, now: DateTime<Utc>) -> SyncPauseState {
+        SyncPauseState {
+            paused: self.is_paused(now),
+            paused_until: match self {
+                SyncPause::Until(until) if *until > now => Some(until.to_rfc3339()),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Debounce for write-triggered syncs: fires once local changes stop arriving for
+/// `debounce`, so a burst of edits is pushed as one sync.
+#[derive(Debug)]
+pub struct WriteDebounce {
+    debounce: Duration,
+    /// Highest unsynced `sync_metadata.id` seen so far.
+    last_seen: Option<i64>,
+    changed_at: Option<Instant>,
+}
+
+impl WriteDebounce {
+    pub fn new(debounce: Duration, last_seen: Option<i64>) -> Self {
+        Self {
+            debounce,
+            last_seen,
+            changed_at: None,
+        }
+    }
+
+    /// Feed the current highest unsynced id; returns true when a sync should start.
+    pub fn observe(&mut self, max_unsynced_id: Option<i64>, now: Instant) -> bool {
+        if max_unsynced_id > self.last_seen {
+            self.last_seen = max_unsynced_id;
+            self.changed_at = Some(now);
+            return false;
+        }
+        // Synced meanwhile (manual sync): nothing left to push.
+        if max_unsynced_id.is_none() {
+            self.last_seen = None;
+            self.changed_at = None;
+            return false;
+        }
+        self.changed_at
+            .is_some_and(|at| now.saturating_duration_since(at) >= self.debounce)
+    }
+}
@@ -0,0 +1,228 @@
+//! Presence: who else is active right now, and on which project.
+//!
+//! 设计要点（默认关闭，需在同步设置中开启）：
+//! - 用户活跃期间，每台设备最多每 `PRESENCE_HEARTBEAT_INTERVAL_SECS` 秒覆盖写一次
+//!   `presence/<device_id>.json`（显示名 + 当前项目 + 时间）；切换项目时立即写入。
+//! - 列表读取全部心跳，仅保留 `PRESENCE_ACTIVE_WINDOW_SECS` 内的条目；
+//!   时间优先取 S3 的 LastModified（不受对方本机时钟影响）。
+//! - 关闭 presence 时尽力删除本机心跳对象。心跳失败只记日志，不影响同步。
+
+use crate::error::AppError;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+pub const PRESENCE_ENABLED_KEY: &str = "presence_enabled";
+/// Name shown to other devices; empty = show the device id.
+pub const PRESENCE_DISPLAY_NAME_KEY: &str = "presence_display_name";
+/// Minimum gap between two heartbeat uploads while staying on the same project.
+pub const PRESENCE_HEARTBEAT_INTERVAL_SECS: i64 = 60;
+/// A device counts as present if its heartbeat is at most this old.
+pub const PRESENCE_ACTIVE_WINDOW_SECS: i64 = 300;
+
+pub const PRESENCE_PREFIX: &str = "presence/";
+const MAX_DISPLAY_NAME_CHARS: usize = 64;
+
+static PRESENCE_THROTTLE: Mutex<PresenceThrottle> = Mutex::new(PresenceThrottle::new());
+
+/// Remote heartbeat object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceHeartbeat {
+    pub device_id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PresenceEntry {
+    pub device_id: String,
+    pub display_name: String,
+    pub project_id: Option<String>,
+    /// Resolved from the local database; `None` if the project is unknown here.
+    pub project_name: Option<String>,
+    pub last_seen_at: String,
+    pub is_self: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceConfig {
+    pub enabled: bool,
+    pub display_name: String,
+}
+
+pub fn presence_key(device_id: &str) -> String {
+    format!("{}{}.json", PRESENCE_PREFIX, device_id)
+}
+
+pub fn load_presence_config(conn: &Connection) -> Result<PresenceConfig, AppError> {
+    let value = |key: &str| -> Result<Option<String>, AppError> {
+        match conn.query_row(
+            "SELECT value FROM sync_config WHERE key = ?1",
+            [key],
+            |row| row.get::<_, String>(0),
+        ) {
+            Ok(v) => Ok(Some(v)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    };
+    Ok(PresenceConfig {
+        enabled: value(PRESENCE_ENABLED_KEY)?.as_deref().map(str::trim) == Some("1"),
+        display_name: value(PRESENCE_DISPLAY_NAME_KEY)?
+            .map(|v| v.trim().to_string())
+            .unwrap_or_default(),
+    })
+}
+
+pub fn save_presence_config(conn: &Connection, config: &PresenceConfig) -> Result<(), AppError> {
+    let display_name = config.display_name.trim();
+    if display_name.chars().count() > MAX_DISPLAY_NAME_CHARS {
+        return Err(AppError::Validation(format!(
+            "display_name must be at most {} characters",
+            MAX_DISPLAY_NAME_CHARS
+        )));
+    }
+    for (key, value) in [
+        (PRESENCE_ENABLED_KEY, if config.enabled { "1" } else { "0" }),
+        (PRESENCE_DISPLAY_NAME_KEY, display_name),
+    ] {
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+    }
+    Ok(())
+}
+
+/// The heartbeat this device would write now.
+pub fn local_heartbeat(
+    device_id: &str,
+    config: &PresenceConfig,
+    project_id: Option<&str>,
+    now: DateTime<Utc>,
+) -> PresenceHeartbeat {
+    PresenceHeartbeat {
+        device_id: device_id.to_string(),
+        display_name: Some(config.display_name.clone()).filter(|n| !n.is_empty()),
+        project_id: project_id.map(str::to_string),
+        updated_at: now.to_rfc3339(),
+    }
+}
+
+/// Heartbeats seen within the active window, most recent first.
+///
+/// `last_modified_unix` is the object's S3 timestamp; the heartbeat's own `updated_at` is only
+/// used when the listing did not report one.
+pub fn recent_presence(
+    beats: Vec<(PresenceHeartbeat, Option<i64>)>,
+    self_device_id: &str,
+    now: DateTime<Utc>,
+) -> Vec<PresenceEntry> {
+    let cutoff = now - Duration::seconds(PRESENCE_ACTIVE_WINDOW_SECS);
+    let mut entries: Vec<(DateTime<Utc>, PresenceEntry)> = beats
+        .into_iter()
+        .filter_map(|(beat, last_modified_unix)| {
+            let seen = last_modified_unix
+                .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+                .or_else(|| {
+                    DateTime::parse_from_rfc3339(beat.updated_at.trim())
+                        .ok()
+                        .map(|at| at.with_timezone(&Utc))
+                })?;
+            if seen < cutoff {
+                return None;
+            }
+            let display_name = beat
+                .display_name
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| beat.device_id.clone());
+            Some((
+                seen,
+                PresenceEntry {
+                    is_self: beat.device_id == self_device_id,
+                    device_id: beat.device_id,
+                    display_name,
+                    project_id: beat.project_id,
+                    project_name: None,
+                    last_seen_at: seen.to_rfc3339(),
+                },
+            ))
+        })
+        .collect();
+    entries.sort_by(|a, b| b.0.cmp(&a.0));
+    entries.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Fill in project names from the local database (trashed or unknown projects stay `None`).
+pub fn resolve_project_names(
+    conn: &Connection,
+    entries: &mut [PresenceEntry],
+) -> Result<(), AppError> {
+    for entry in entries.iter_mut() {
+        if let Some(project_id) = &entry.project_id {
+            entry.project_name = conn
+                .query_row(
+                    "SELECT name FROM projects WHERE id = ?1 AND deleted_at IS NULL",
+                    [project_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+        }
+    }
+    Ok(())
+}
+
+/// Decides when a heartbeat is worth uploading.
+#[derive(Debug, Default)]
+pub struct PresenceThrottle {
+    last: Option<(i64, Option<String>)>,
+}
+
+impl PresenceThrottle {
+    pub const fn new() -> Self {
+        Self { last: None }
+    }
+
+    /// Due when nothing was published yet, the interval elapsed, or the project changed.
+    pub fn is_due(&self, project_id: Option<&str>, now_unix: i64) -> bool {
+        match &self.last {
+            None => true,
+            Some((at, last_project)) => {
+                last_project.as_deref() != project_id
+                    || now_unix - at >= PRESENCE_HEARTBEAT_INTERVAL_SECS
+            }
+        }
+    }
+
+    pub fn mark_published(&mut self, project_id: Option<&str>, now_unix: i64) {
+        self.last = Some((now_unix, project_id.map(str::to_string)));
+    }
+
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Reserve the next heartbeat upload for this process; `false` if one is not due yet.
+pub fn claim_presence_slot(project_id: Option<&str>, now_unix: i64) -> bool {
+    let Ok(mut throttle) = PRESENCE_THROTTLE.lock() else {
+        return false;
+    };
+    if !throttle.is_due(project_id, now_unix) {
+        return false;
+    }
+    throttle.mark_published(project_id, now_unix);
+    true
+}
+
+/// Forget the last upload (after a failed upload or when presence is switched off).
+pub fn reset_presence_throttle() {
+    if let Ok(mut throttle) = PRESENCE_THROTTLE.lock() {
+        throttle.reset();
+    }
+}
@@ -0,0 +1,106 @@
+//! Sync payload format versioning: refuse payloads written by newer apps, upgrade older ones.
+//!
+//! 设计要点：
+//! - `Delta` / `Snapshot` 携带 `format_version`；字段出现之前写入的对象按 1 解析（`serde(default)`）。
+//! - 桶内 `meta/schema.json` 记录写入过该桶的最高格式版本：每次同步读取，缺失或低于本机时由本机
+//!   覆盖写入；高于本机时拒绝同步（`SYNC_FORMAT_UNSUPPORTED`），提示升级应用，避免旧版本误读新格式。
+//! - 单个 delta / 快照解码时同样检查：格式更新则拒绝，格式更旧则逐级升级到当前格式后再应用。
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+
+/// Format written by this build. Bump when `Delta` / `Snapshot` payloads change shape, and add an
+/// upgrade step below for the previous format.
+pub const SYNC_FORMAT_VERSION: u32 = 2;
+/// Payloads written before `format_version` existed.
+pub const LEGACY_FORMAT_VERSION: u32 = 1;
+pub const SCHEMA_META_KEY: &str = "meta/schema.json";
+
+pub fn legacy_format_version() -> u32 {
+    LEGACY_FORMAT_VERSION
+}
+
+/// `meta/schema.json`: the newest payload format any device has written to the bucket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaMeta {
+    pub format_version: u32,
+    pub app_version: String,
+    pub updated_by: String,
+    pub updated_at: String,
+}
+
+/// The schema marker this build would write.
+pub fn local_schema_meta(device_id: &str) -> SchemaMeta {
+    SchemaMeta {
+        format_version: SYNC_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        updated_by: device_id.to_string(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Refuse the bucket when a newer app already writes to it. Returns whether this device should
+/// (re)write `meta/schema.json` (missing or older marker).
+pub fn check_remote_schema(remote: Option<&SchemaMeta>) -> Result<bool, AppError> {
+    match remote {
+        None => Ok(true),
+        Some(meta) if meta.format_version > SYNC_FORMAT_VERSION => {
+            Err(AppError::SyncFormatUnsupported(format!(
+                "bucket uses sync format {} (written by app {}), this app supports up to {}; \
+                 update the app before syncing",
+                meta.format_version, meta.app_version, SYNC_FORMAT_VERSION
+            )))
+        }
+        Some(meta) => Ok(meta.format_version < SYNC_FORMAT_VERSION),
+    }
+}
+
+/// Refuse a single payload written in a newer format.
+pub fn ensure_readable_format(kind: &str, format_version: u32) -> Result<(), AppError> {
+    if format_version > SYNC_FORMAT_VERSION {
+        return Err(AppError::SyncFormatUnsupported(format!(
+            "{} uses sync format {}, this app supports up to {}; update the app before syncing",
+            kind, format_version, SYNC_FORMAT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+/// Upgrade a decoded delta payload (JSON) to `SYNC_FORMAT_VERSION`, one format at a time.
+/// Returns the format it was written in.
+pub fn upgrade_delta_json(value: &mut serde_json::Value) -> Result<u32, AppError> {
+    upgrade_payload("delta", value)
+}
+
+/// Same as `upgrade_delta_json` for snapshots.
+pub fn upgrade_snapshot_json(value: &mut serde_json::Value) -> Result<u32, AppError> {
+    upgrade_payload("snapshot", value)
+}
+
+fn upgrade_payload(kind: &str, value: &mut serde_json::Value) -> Result<u32, AppError> {
+    let original = value
+        .get("format_version")
+        .and_then(|v| v.as_u64())
+        .map(|v| u32::try_from(v).unwrap_or(u32::MAX))
+        .unwrap_or(LEGACY_FORMAT_VERSION);
+    ensure_readable_format(kind, original)?;
+
+    let mut format = original;
+    while format < SYNC_FORMAT_VERSION {
+        match format {
+            // Format 2 only introduced the `format_version` field itself.
+            LEGACY_FORMAT_VERSION => {}
+            other => {
+                return Err(AppError::Sync(format!(
+                    "No upgrade path for {} format {}",
+                    kind, other
+                )))
+            }
+        }
+        format += 1;
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("format_version".to_string(), SYNC_FORMAT_VERSION.into());
+    }
+    Ok(original)
+}
@@ -0,0 +1,307 @@
+//! Repair sync: fix divergent rows by exchanging per-record hashes instead of restoring a snapshot.
+//!
+//! 设计要点：
+//! - 每台设备把行哈希清单上传到 `devices/<device_id>/row-hashes.json.gz`：每表按行 ID 的哈希首字节
+//!   分成 256 个桶，记录根哈希、桶哈希、逐行 `(hash, _version)` 以及删除 tombstone。
+//! - 与其它设备的清单比较时先比根哈希、再比桶哈希，只逐行比较不同的桶（Merkle 风格）。
+//! - 每个不同的行按确定规则选出胜者（`_version` 大者；相同则哈希字典序大者；删除 tombstone 版本
+//!   不低于对方行版本时删除胜出），双方独立计算结论一致：
+//!   - 本机胜出：把当前行（或删除）重新写入 `sync_metadata`，下次同步作为普通 delta 上传；
+//!   - 对方胜出：写入 `repair/<peer>/<device_id>.json` 请求，对方在下次同步开始时重新发出这些行，
+//!     本机随后按普通 delta 拉取。
+//! - 本机忽略的表不参与；`project_tags` 无版本，只按存在与否补齐（集合语义由 `tag_set` 处理）。
+
+use crate::error::AppError;
+use crate::sync::conflicts::load_local_row_json;
+use crate::sync::tombstone::load_tombstone_version;
+use crate::sync::verify::table_row_hashes;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Write};
+
+pub const REPAIR_PREFIX: &str = "repair/";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowDigest {
+    pub hash: String,
+    /// `_version` (0 for `project_tags`).
+    pub version: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableManifest {
+    pub root: String,
+    /// Bucket ("00".."ff") -> hash of its rows; empty buckets are omitted.
+    pub buckets: BTreeMap<String, String>,
+    pub rows: BTreeMap<String, RowDigest>,
+    /// Deleted row id -> deleted version (versioned tables only).
+    pub tombstones: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowHashManifest {
+    pub device_id: String,
+    pub created_at: String,
+    pub tables: BTreeMap<String, TableManifest>,
+}
+
+/// Rows a device asks a peer to send again, stored at `repair/<peer>/<requester>.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepairRequest {
+    pub requester_device_id: String,
+    pub created_at: String,
+    pub tables: BTreeMap<String, Vec<String>>,
+}
+
+/// Outcome of comparing this device's manifest with one peer's.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairPlan {
+    /// Rows (or deletes) this device wins: re-upload.
+    pub upload: BTreeMap<String, Vec<String>>,
+    /// Rows the peer wins: request them.
+    pub request: BTreeMap<String, Vec<String>>,
+}
+
+pub fn row_hashes_key(device_id: &str) -> String {
+    format!("devices/{}/row-hashes.json.gz", device_id)
+}
+
+/// `devices/<device_id>/row-hashes.json.gz` -> device id.
+pub fn parse_row_hashes_key(key: &str) -> Option<&str> {
+    key.strip_prefix("devices/")?
+        .strip_suffix("/row-hashes.json.gz")
+        .filter(|id| !id.is_empty() && !id.contains('/'))
+}
+
+pub fn repair_request_prefix(device_id: &str) -> String {
+    format!("{}{}/", REPAIR_PREFIX, device_id)
+}
+
+pub fn repair_request_key(target_device_id: &str, requester_device_id: &str) -> String {
+    format!(
+        "{}{}.json",
+        repair_request_prefix(target_device_id),
+        requester_device_id
+    )
+}
+
+fn bucket_of(record_id: &str) -> String {
+    format!("{:02x}", Sha256::digest(record_id.as_bytes())[0])
+}
+
+/// Hash every row of `tables` into a manifest.
+pub fn build_manifest(
+    conn: &Connection,
+    device_id: &str,
+    tables: &[&str],
+) -> Result<RowHashManifest, AppError> {
+    let mut manifest = RowHashManifest {
+        device_id: device_id.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        tables: BTreeMap::new(),
+    };
+    for table in tables {
+        manifest
+            .tables
+            .insert(table.to_string(), build_table_manifest(conn, table)?);
+    }
+    Ok(manifest)
+}
+
+fn build_table_manifest(conn: &Connection, table: &str) -> Result<TableManifest, AppError> {
+    let versioned = table != "project_tags";
+    let versions: BTreeMap<String, i64> = if versioned {
+        let mut stmt = conn
+            .prepare(&format!("SELECT id, COALESCE(_version, 0) FROM {}", table))
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::Db(e.to_string()))?
+            .collect::<Result<_, _>>()
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        rows
+    } else {
+        BTreeMap::new()
+    };
+
+    let mut manifest = TableManifest::default();
+    for (id, hash) in table_row_hashes(conn, table)? {
+        let version = versions.get(&id).copied().unwrap_or(0);
+        manifest.rows.insert(id, RowDigest { hash, version });
+    }
+
+    let mut by_bucket: BTreeMap<String, Sha256> = BTreeMap::new();
+    for (id, digest) in &manifest.rows {
+        let hasher = by_bucket.entry(bucket_of(id)).or_default();
+        hasher.update(id.as_bytes());
+        hasher.update([0x1f]);
+        hasher.update(digest.hash.as_bytes());
+        hasher.update([0x1e]);
+    }
+    let mut root = Sha256::new();
+    for (bucket, hasher) in by_bucket {
+        let hash = format!("{:x}", hasher.finalize());
+        root.update(bucket.as_bytes());
+        root.update(hash.as_bytes());
+        manifest.buckets.insert(bucket, hash);
+    }
+    manifest.root = format!("{:x}", root.finalize());
+
+    if versioned {
+        let mut stmt = conn
+            .prepare("SELECT record_id, version FROM sync_tombstones WHERE table_name = ?1")
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        manifest.tombstones = stmt
+            .query_map(params![table], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::Db(e.to_string()))?
+            .collect::<Result<_, _>>()
+            .map_err(|e| AppError::Db(e.to_string()))?;
+    }
+    Ok(manifest)
+}
+
+pub fn compress_manifest(manifest: &RowHashManifest) -> Result<Vec<u8>, AppError> {
+    let json = serde_json::to_vec(manifest)
+        .map_err(|e| AppError::Db(format!("Serialize row hashes failed: {}", e)))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&json)
+        .map_err(|e| AppError::Db(format!("Compress failed: {}", e)))?;
+    encoder
+        .finish()
+        .map_err(|e| AppError::Db(format!("Compress finish failed: {}", e)))
+}
+
+pub fn decompress_manifest(data: &[u8]) -> Result<RowHashManifest, AppError> {
+    let mut json = Vec::new();
+    GzDecoder::new(data)
+        .read_to_end(&mut json)
+        .map_err(|e| AppError::Db(format!("Decompress failed: {}", e)))?;
+    serde_json::from_slice(&json)
+        .map_err(|e| AppError::Db(format!("Deserialize row hashes failed: {}", e)))
+}
+
+/// Which side should resend each differing row. Tables missing from either manifest are skipped.
+pub fn plan_repair(local: &RowHashManifest, peer: &RowHashManifest) -> RepairPlan {
+    let mut plan = RepairPlan::default();
+    for (table, ours) in &local.tables {
+        let Some(theirs) = peer.tables.get(table) else {
+            continue;
+        };
+        if ours.root == theirs.root {
+            continue;
+        }
+        let buckets: BTreeSet<&String> = ours
+            .buckets
+            .keys()
+            .chain(theirs.buckets.keys())
+            .filter(|b| ours.buckets.get(*b) != theirs.buckets.get(*b))
+            .collect();
+        let ids: BTreeSet<&String> = ours
+            .rows
+            .keys()
+            .chain(theirs.rows.keys())
+            .filter(|id| buckets.contains(&bucket_of(id)))
+            .collect();
+
+        for id in ids {
+            let upload = match (ours.rows.get(id), theirs.rows.get(id)) {
+                (Some(l), Some(p)) if l.hash == p.hash => continue,
+                (Some(l), Some(p)) => (l.version, &l.hash) > (p.version, &p.hash),
+                // The peer's delete wins unless our row is newer.
+                (Some(l), None) => !theirs.tombstones.get(id).is_some_and(|v| *v >= l.version),
+                (None, Some(p)) => ours.tombstones.get(id).is_some_and(|v| *v >= p.version),
+                (None, None) => continue,
+            };
+            let target = if upload {
+                &mut plan.upload
+            } else {
+                &mut plan.request
+            };
+            target.entry(table.clone()).or_default().push(id.clone());
+        }
+    }
+    plan
+}
+
+/// Queue the current state of the given rows for upload: the row itself, or a delete when it is
+/// tombstoned. Rows with neither are skipped. Returns the number of queued operations.
+pub fn requeue_rows(
+    conn: &Connection,
+    device_id: &str,
+    table: &str,
+    ids: &[String],
+) -> Result<usize, AppError> {
+    let mut queued = 0;
+    for id in ids {
+        let op = if table == "project_tags" {
+            requeued_tag_op(conn, id)?
+        } else {
+            match load_local_row_json(conn, table, id)? {
+                Some(row) => {
+                    let version = row["_version"].as_i64().unwrap_or(1);
+                    Some(("UPDATE", Some(row), version))
+                }
+                None => load_tombstone_version(conn, table, id)?
+                    .map(|version| ("DELETE", None, version)),
+            }
+        };
+        let Some((operation, data, version)) = op else {
+            continue;
+        };
+        conn.execute(
+            "INSERT INTO sync_metadata (table_name, record_id, operation, data_snapshot, device_id, version, created_at, synced)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime('now'), 0)",
+            params![
+                table,
+                id,
+                operation,
+                data.map(|d| d.to_string()),
+                device_id,
+                version
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+        queued += 1;
+    }
+    Ok(queued)
+}
+
+type RequeuedOp = (&'static str, Option<serde_json::Value>, i64);
+
+fn requeued_tag_op(conn: &Connection, record_id: &str) -> Result<Option<RequeuedOp>, AppError> {
+    let Some((project_id, tag)) = record_id.split_once(':') else {
+        return Ok(None);
+    };
+    let created_at: Option<String> = conn
+        .query_row(
+            "SELECT created_at FROM project_tags WHERE project_id = ?1 AND tag = ?2",
+            params![project_id, tag],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    if let Some(created_at) = created_at {
+        let data =
+            serde_json::json!({"project_id": project_id, "tag": tag, "created_at": created_at});
+        return Ok(Some(("INSERT", Some(data), 1)));
+    }
+    let deleted_at: Option<String> = conn
+        .query_row(
+            "SELECT deleted_at FROM sync_tombstones WHERE table_name = 'project_tags' AND record_id = ?1",
+            params![record_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(deleted_at.map(|deleted_at| {
+        let data =
+            serde_json::json!({"project_id": project_id, "tag": tag, "deleted_at": deleted_at});
+        ("DELETE", Some(data), 1)
+    }))
+}
@@ -0,0 +1,93 @@
+//! Read-only replica mode (`sync_config` key `read_only_replica` = '1').
+//!
+//! 设计要点：
+//! - 开关存在本机 sync_config（每个 profile 一个库，即按 profile 生效），不随 export_config 导出。
+//! - 远端修改照常应用：同步、暂存审核、快照恢复、从远端初始化都不受影响。
+//! - 本地不产生变更：业务表的变更跟踪触发器（migration 0036）在开关打开时不写 `sync_metadata`；
+//!   开启之前已记录、尚未上传的变更仍会上传。devices 触发器不受影响，副本照常发布自己的设备行，
+//!   可以像其他设备一样被改名、吊销。
+//! - 业务写命令在 invoke 层统一拦截（`REPLICA_BLOCKED_COMMANDS`），返回 `READ_ONLY_REPLICA`，
+//!   避免写入只落在本机、永远不上传而与其他设备分叉。修复同步（会把本机行重新排队上传）同样被拒绝，
+//!   也不响应其他设备的修复请求。
+//! - 后台的循环项目检查在副本上跳过（到期规则保持到期，关闭副本模式后补建），自动计划（BACKLOG → PLANNED）同样跳过。
+
+use crate::error::AppError;
+use rusqlite::{Connection, OptionalExtension};
+
+pub const READ_ONLY_REPLICA_KEY: &str = "read_only_replica";
+
+/// Commands that change business data (or would queue local rows for upload).
+pub const REPLICA_BLOCKED_COMMANDS: &[&str] = &[
+    "cmd_assignment_add_member",
+    "cmd_assignment_end_member",
+    "cmd_comment_create",
+    "cmd_comment_reply",
+    "cmd_comment_react",
+    "cmd_comment_update",
+    "cmd_comment_reorder_pins",
+    "cmd_comment_delete",
+    "cmd_checklist_add",
+    "cmd_checklist_toggle",
+    "cmd_checklist_reorder",
+    "cmd_checklist_delete",
+    "cmd_custom_field_create",
+    "cmd_custom_field_update",
+    "cmd_custom_field_delete",
+    "cmd_project_set_custom_value",
+    "cmd_import_json",
+    "cmd_import_persons_csv",
+    "cmd_import_projects_csv",
+    "cmd_wipe_business_data",
+    "cmd_backup_restore",
+    "cmd_partner_create",
+    "cmd_partner_update",
+    "cmd_partner_set_default_currency",
+    "cmd_partner_deactivate",
+    "cmd_partner_merge",
+    "cmd_person_create",
+    "cmd_person_update",
+    "cmd_person_deactivate",
+    "cmd_person_merge",
+    "cmd_project_create",
+    "cmd_project_clone",
+    "cmd_project_update",
+    "cmd_project_reorder",
+    "cmd_project_set_size",
+    "cmd_project_set_budget",
+    "cmd_project_change_status",
+    "cmd_project_create_from_template",
+    "cmd_recurrence_run_due",
+    "cmd_status_automation_run",
+    "cmd_tag_rename",
+    "cmd_tag_merge",
+    "cmd_trash_delete",
+    "cmd_trash_restore",
+    "cmd_trash_purge",
+    "cmd_undo_last",
+    "cmd_redo_last",
+    "cmd_sync_resolve_conflict",
+    "cmd_sync_repair",
+];
+
+pub fn is_read_only_replica(conn: &Connection) -> Result<bool, AppError> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM sync_config WHERE key = ?1",
+            [READ_ONLY_REPLICA_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.as_deref().map(str::trim) == Some("1"))
+}
+
+/// Reject a local write on a read-only replica.
+pub fn ensure_writable(conn: &Connection, operation: &str) -> Result<(), AppError> {
+    if is_read_only_replica(conn)? {
+        return Err(AppError::ReadOnlyReplica(operation.to_string()));
+    }
+    Ok(())
+}
+
+pub fn blocks_command(command: &str) -> bool {
+    REPLICA_BLOCKED_COMMANDS.contains(&command)
+}
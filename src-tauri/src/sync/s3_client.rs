@@ -1,20 +1,286 @@
 //! S3 client wrapper for sync operations
 
+use crate::sync::storage_class::DeltaLifecycleRule;
+use crate::sync::throttle::BandwidthLimiter;
 use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_s3::config::retry::RetryConfig;
 use aws_sdk_s3::config::Region;
+use aws_sdk_s3::error::{ProvideErrorMetadata, SdkError};
+use aws_sdk_s3::presigning::PresigningConfig;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{
+    AbortIncompleteMultipartUpload, BucketLifecycleConfiguration, CompletedMultipartUpload,
+    CompletedPart, ExpirationStatus, LifecycleRule, LifecycleRuleFilter, StorageClass, Transition,
+    TransitionStorageClass,
+};
 use aws_sdk_s3::Client;
-use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// SigV4 presigned URLs are valid for at most 7 days.
+pub const MAX_PRESIGN_EXPIRY_SECS: u64 = 7 * 24 * 60 * 60;
+pub const DEFAULT_PRESIGN_EXPIRY_SECS: u64 = 60 * 60;
+
+pub const S3_PART_SIZE_MB_KEY: &str = "s3_part_size_mb";
+pub const S3_PART_RETRIES_KEY: &str = "s3_part_retries";
+pub const DEFAULT_PART_SIZE_MB: usize = 8;
+pub const DEFAULT_PART_RETRIES: u32 = 3;
+/// S3 rejects multipart parts smaller than 5 MiB (except the last one).
+pub const MIN_PART_SIZE_MB: usize = 5;
+/// S3 allows at most 10,000 parts per multipart upload.
+pub const MAX_UPLOAD_PARTS: usize = 10_000;
+
+/// Per-object settings for uploads; the default writes STANDARD objects without tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectOptions {
+    /// S3 storage class name (see `storage_class::normalize_storage_class`).
+    pub storage_class: Option<String>,
+    /// `x-amz-tagging` value (see `storage_class::tagging_header`).
+    pub tagging: Option<String>,
+}
+
+impl ObjectOptions {
+    fn storage_class(&self) -> Option<StorageClass> {
+        self.storage_class.as_deref().map(StorageClass::from)
+    }
+}
+
+/// Multipart / resume settings for large objects (snapshots, big deltas).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferConfig {
+    /// Objects larger than this are uploaded in parts of this size.
+    pub part_size_bytes: usize,
+    /// Extra attempts per part, and per interrupted download, before giving up.
+    pub part_retries: u32,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            part_size_bytes: DEFAULT_PART_SIZE_MB * 1024 * 1024,
+            part_retries: DEFAULT_PART_RETRIES,
+        }
+    }
+}
+
+impl TransferConfig {
+    /// Build from stored settings; missing values use the defaults and the part size is
+    /// raised to the S3 minimum.
+    pub fn from_settings(part_size_mb: Option<usize>, part_retries: Option<u32>) -> Self {
+        let defaults = Self::default();
+        Self {
+            part_size_bytes: part_size_mb
+                .map(|mb| mb.max(MIN_PART_SIZE_MB).saturating_mul(1024 * 1024))
+                .unwrap_or(defaults.part_size_bytes),
+            part_retries: part_retries.unwrap_or(defaults.part_retries),
+        }
+    }
+}
+
+pub const S3_RETRY_MAX_ATTEMPTS_KEY: &str = "s3_retry_max_attempts";
+pub const S3_RETRY_BASE_DELAY_MS_KEY: &str = "s3_retry_base_delay_ms";
+pub const DEFAULT_RETRY_MAX_ATTEMPTS: u32 = 4;
+pub const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 200;
+/// Upper bound for a single backoff, whatever the attempt number.
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Retry policy for transient S3 failures (throttling, 5xx, timeouts, dropped connections).
+/// The SDK client is built with its own retries disabled, so this is the only request-level
+/// retry layer (multipart parts add `TransferConfig::part_retries` on top).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first one (1 = no retry).
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_RETRY_MAX_ATTEMPTS,
+            base_delay: Duration::from_millis(DEFAULT_RETRY_BASE_DELAY_MS),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Build from stored settings; missing values use the defaults.
+    pub fn from_settings(max_attempts: Option<u32>, base_delay_ms: Option<u64>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_attempts: max_attempts
+                .map(|n| n.max(1))
+                .unwrap_or(defaults.max_attempts),
+            base_delay: base_delay_ms
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.base_delay),
+        }
+    }
+
+    /// Exponential backoff with jitter: half of `base * 2^(attempt - 1)` (capped at
+    /// `MAX_RETRY_DELAY`) plus a random share of the other half, so devices hitting the
+    /// same throttled bucket don't retry in lockstep. `jitter` is in `0.0..=1.0`.
+    pub fn backoff(&self, attempt: u32, jitter: f64) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+            .min(MAX_RETRY_DELAY);
+        exp / 2 + (exp / 2).mul_f64(jitter.clamp(0.0, 1.0))
+    }
+}
+
+pub const S3_PROFILE_KEY: &str = "s3_profile";
+pub const S3_SESSION_TOKEN_KEY: &str = "s3_session_token";
+pub const S3_ROLE_ARN_KEY: &str = "s3_role_arn";
+pub const S3_EXTERNAL_ID_KEY: &str = "s3_external_id";
+/// STS limit for `RoleSessionName`.
+const MAX_ROLE_SESSION_NAME_LEN: usize = 64;
+
+/// How the client obtains credentials beyond plain access / secret keys.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct S3AuthSettings {
+    /// Named profile from `~/.aws/config` / `~/.aws/credentials` (SSO profiles included).
+    /// Takes precedence over static keys.
+    pub profile: Option<String>,
+    /// STS session token paired with the static keys (temporary credentials).
+    pub session_token: Option<String>,
+    /// IAM role assumed with the base credentials.
+    pub role_arn: Option<String>,
+    /// External ID required by the role's trust policy.
+    pub external_id: Option<String>,
+}
+
+impl S3AuthSettings {
+    /// Credentials come from somewhere other than the saved access / secret keys.
+    pub fn replaces_static_keys(&self) -> bool {
+        self.profile.is_some() || self.role_arn.is_some()
+    }
+}
+
+/// `arn:<partition>:iam::<account>:role/<name>`.
+pub fn validate_role_arn(arn: &str) -> Result<(), String> {
+    let parts: Vec<&str> = arn.splitn(6, ':').collect();
+    let valid = parts.len() == 6
+        && parts[0] == "arn"
+        && parts[1].starts_with("aws")
+        && parts[2] == "iam"
+        && parts[3].is_empty()
+        && parts[4].len() == 12
+        && parts[4].chars().all(|c| c.is_ascii_digit())
+        && parts[5]
+            .strip_prefix("role/")
+            .is_some_and(|name| !name.is_empty());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "INVALID_ROLE_ARN: expected arn:aws:iam::<account>:role/<name>, got {}",
+            arn
+        ))
+    }
+}
+
+/// `projex-<device id>`, restricted to the characters and length STS accepts.
+pub fn role_session_name(device_id: &str) -> String {
+    format!("projex-{}", device_id)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "+=,.@-_".contains(c) {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(MAX_ROLE_SESSION_NAME_LEN)
+        .collect()
+}
+
+pub const S3_KEY_PREFIX_KEY: &str = "s3_key_prefix";
+pub const MAX_KEY_PREFIX_LEN: usize = 256;
+/// Top-level folders written by sync. A key prefix moves all of them; other objects in the
+/// bucket (e.g. other workspaces' prefixes) are never touched.
+pub const SYNC_KEY_ROOTS: [&str; 8] = [
+    "deltas/",
+    "snapshots/",
+    "devices/",
+    "claims/",
+    "meta/",
+    "repair/",
+    "presence/",
+    "probes/",
+];
+
+/// Normalize a user-entered key prefix to `""` or `seg/seg/`. Rejects empty / dot segments,
+/// characters that need escaping in URLs, and segments named like a sync folder
+/// (`deltas`, ...), which would nest one namespace inside another.
+pub fn normalize_key_prefix(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return Ok(String::new());
+    }
+    let normalized = format!("{}/", trimmed);
+    if normalized.len() > MAX_KEY_PREFIX_LEN {
+        return Err(format!(
+            "INVALID_KEY_PREFIX: at most {} characters",
+            MAX_KEY_PREFIX_LEN
+        ));
+    }
+    for segment in trimmed.split('/') {
+        if segment.is_empty() || segment == "." || segment == ".." {
+            return Err(format!(
+                "INVALID_KEY_PREFIX: empty or relative segment in {}",
+                raw.trim()
+            ));
+        }
+        if !segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+        {
+            return Err(format!(
+                "INVALID_KEY_PREFIX: segments may only contain letters, digits, '-', '_' and '.' ({})",
+                segment
+            ));
+        }
+        if SYNC_KEY_ROOTS
+            .iter()
+            .any(|root| root.trim_end_matches('/') == segment)
+        {
+            return Err(format!(
+                "INVALID_KEY_PREFIX: '{}' is reserved for sync data",
+                segment
+            ));
+        }
+    }
+    Ok(normalized)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PresignMethod {
+    Get,
+    Put,
+}
 
 #[derive(Debug, Clone)]
 pub struct S3ObjectSummary {
     pub key: String,
     pub last_modified_unix: Option<i64>,
+    pub size_bytes: Option<i64>,
 }
 
 pub struct S3SyncClient {
     client: Client,
     pub bucket: String,
     pub device_id: String,
+    transfer: TransferConfig,
+    retry: RetryPolicy,
+    upload_limit: BandwidthLimiter,
+    download_limit: BandwidthLimiter,
+    /// Normalized (`""` or `seg/`), prepended to every key; keys passed in and returned
+    /// by `list` are relative to it.
+    key_prefix: String,
 }
 
 impl S3SyncClient {
@@ -23,18 +289,15 @@ impl S3SyncClient {
         bucket: String,
         device_id: String,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let region_provider = RegionProviderChain::default_provider().or_else("us-east-1");
-        let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(region_provider)
-            .load()
-            .await;
-        let client = Client::new(&config);
-
-        Ok(Self {
-            client,
+        Self::connect(
             bucket,
             device_id,
-        })
+            None,
+            None,
+            None,
+            &S3AuthSettings::default(),
+        )
+        .await
     }
 
     /// Create client with custom endpoint (for MinIO, R2, etc.)
@@ -45,51 +308,201 @@ impl S3SyncClient {
         access_key: String,
         secret_key: String,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        use aws_credential_types::Credentials;
+        Self::connect(
+            bucket,
+            device_id,
+            Some(endpoint),
+            Some(access_key),
+            Some(secret_key),
+            &S3AuthSettings::default(),
+        )
+        .await
+    }
 
-        let creds = Credentials::new(access_key, secret_key, None, None, "custom");
+    /// Create client from the full connection settings. Base credentials, first match wins:
+    /// named profile → static keys (custom endpoint, or any endpoint with a session token) →
+    /// default chain (environment, `~/.aws`, instance role). `auth.role_arn` is then assumed
+    /// with those base credentials through STS (refreshed before expiry).
+    pub async fn connect(
+        bucket: String,
+        device_id: String,
+        endpoint: Option<String>,
+        access_key: Option<String>,
+        secret_key: Option<String>,
+        auth: &S3AuthSettings,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        use aws_config::default_provider::region::DefaultRegionChain;
+        use aws_config::sts::AssumeRoleProvider;
+        use aws_credential_types::provider::SharedCredentialsProvider;
+        use aws_credential_types::Credentials;
 
-        let region_provider = if let Some(region) = infer_region_from_endpoint(&endpoint) {
-            RegionProviderChain::first_try(Region::new(region)).or_else("us-east-1")
-        } else {
-            RegionProviderChain::default_provider().or_else("us-east-1")
+        let access_key = access_key.filter(|v| !v.trim().is_empty());
+        let secret_key = secret_key.filter(|v| !v.trim().is_empty());
+        let inferred_region = endpoint.as_deref().and_then(infer_region_from_endpoint);
+        let region_provider = match inferred_region {
+            Some(region) => {
+                RegionProviderChain::first_try(Region::new(region)).or_else("us-east-1")
+            }
+            None => {
+                let mut chain = DefaultRegionChain::builder();
+                if let Some(profile) = &auth.profile {
+                    chain = chain.profile_name(profile);
+                }
+                RegionProviderChain::first_try(chain.build()).or_else("us-east-1")
+            }
         };
 
-        let force_path_style = should_force_path_style_for_endpoint(&endpoint);
+        let mut loader =
+            aws_config::defaults(aws_config::BehaviorVersion::latest()).region(region_provider);
+        if let Some(profile) = &auth.profile {
+            loader = loader.profile_name(profile);
+        } else if let (Some(access_key), Some(secret_key)) = (access_key, secret_key) {
+            if endpoint.is_some() || auth.session_token.is_some() {
+                loader = loader.credentials_provider(Credentials::new(
+                    access_key,
+                    secret_key,
+                    auth.session_token.clone(),
+                    None,
+                    "custom",
+                ));
+            }
+        }
+        // The custom endpoint is S3-only: STS (assume-role) keeps the AWS endpoint.
+        let mut config = loader.load().await;
 
-        let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(region_provider)
-            .endpoint_url(endpoint)
-            .credentials_provider(creds)
-            .load()
-            .await;
+        if let Some(role_arn) = &auth.role_arn {
+            let mut role = AssumeRoleProvider::builder(role_arn)
+                .session_name(role_session_name(&device_id))
+                .configure(&config);
+            if let Some(external_id) = &auth.external_id {
+                role = role.external_id(external_id);
+            }
+            let provider = role.build().await;
+            config = config
+                .into_builder()
+                .credentials_provider(SharedCredentialsProvider::new(provider))
+                .build();
+        }
 
-        // For local MinIO-style endpoints, use path-style to avoid bucket-subdomain parsing issues.
-        // For cloud providers (AWS S3 / R2 / OSS), keep virtual-hosted style by default.
-        let s3_config = aws_sdk_s3::config::Builder::from(&config)
-            .force_path_style(force_path_style)
-            .build();
-        let client = Client::from_conf(s3_config);
+        let client = Client::from_conf(s3_config(&config, endpoint));
 
         Ok(Self {
             client,
             bucket,
             device_id,
+            transfer: TransferConfig::default(),
+            retry: RetryPolicy::default(),
+            upload_limit: BandwidthLimiter::unlimited(),
+            download_limit: BandwidthLimiter::unlimited(),
+            key_prefix: String::new(),
         })
     }
 
-    /// Upload object to S3
+    /// Scope all keys under `prefix` (already normalized, see `normalize_key_prefix`).
+    pub fn with_key_prefix(mut self, prefix: String) -> Self {
+        self.key_prefix = prefix;
+        self
+    }
+
+    pub fn key_prefix(&self) -> &str {
+        &self.key_prefix
+    }
+
+    fn full_key(&self, key: &str) -> String {
+        format!("{}{}", self.key_prefix, key)
+    }
+
+    /// Use custom part size / retry settings for large transfers.
+    pub fn with_transfer_config(mut self, transfer: TransferConfig) -> Self {
+        self.transfer = transfer;
+        self
+    }
+
+    /// Use a custom retry policy for transient S3 failures.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Cap upload / download throughput (bytes per second, `None` = unlimited).
+    pub fn with_bandwidth_limits(
+        mut self,
+        upload_bytes_per_sec: Option<u64>,
+        download_bytes_per_sec: Option<u64>,
+    ) -> Self {
+        self.upload_limit = BandwidthLimiter::new(upload_bytes_per_sec);
+        self.download_limit = BandwidthLimiter::new(download_bytes_per_sec);
+        self
+    }
+
+    /// Send a request, retrying transient failures with backoff. `send` builds a fresh
+    /// request for every attempt.
+    async fn send_with_retry<T, E, F, Fut>(
+        &self,
+        op: &str,
+        key: &str,
+        mut send: F,
+    ) -> Result<T, SdkError<E>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, SdkError<E>>>,
+        E: ProvideErrorMetadata + std::fmt::Debug,
+    {
+        let mut attempt = 1;
+        loop {
+            match send().await {
+                Err(e) if attempt < self.retry.max_attempts && is_transient_s3_error(&e) => {
+                    let delay = self.retry.backoff(attempt, random_jitter());
+                    log::warn!(
+                        "S3 {} {} failed (attempt {}/{}), retrying in {:.2?}: {:?}",
+                        op,
+                        key,
+                        attempt,
+                        self.retry.max_attempts,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Upload object to S3 (multipart when larger than the configured part size)
     pub async fn upload(&self, key: &str, data: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+        self.upload_with_options(key, data, &ObjectOptions::default())
+            .await
+    }
+
+    /// `upload` with a storage class and tags applied to the new object.
+    pub async fn upload_with_options(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        options: &ObjectOptions,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let key = &self.full_key(key);
+        if data.len() > self.transfer.part_size_bytes {
+            return self.upload_multipart(key, &data, options).await;
+        }
+
         let start = Instant::now();
         let data_len = data.len();
+        self.upload_limit.consume(data_len).await;
 
         let result = self
-            .client
-            .put_object()
-            .bucket(&self.bucket)
-            .key(key)
-            .body(data.into())
-            .send()
+            .send_with_retry("upload", key, || {
+                self.client
+                    .put_object()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .body(ByteStream::from(data.clone()))
+                    .set_storage_class(options.storage_class())
+                    .set_tagging(options.tagging.clone())
+                    .send()
+            })
             .await;
 
         let elapsed = start.elapsed();
@@ -103,26 +516,282 @@ impl S3SyncClient {
         Ok(())
     }
 
-    /// Download object from S3
-    pub async fn download(&self, key: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    /// Multipart upload: each part is retried on its own, so a flaky connection only
+    /// re-sends the failed part. The upload is aborted if any part runs out of retries.
+    async fn upload_multipart(
+        &self,
+        key: &str,
+        data: &[u8],
+        options: &ObjectOptions,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let start = Instant::now();
+        let created = self
+            .send_with_retry("create multipart upload", key, || {
+                self.client
+                    .create_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .set_storage_class(options.storage_class())
+                    .set_tagging(options.tagging.clone())
+                    .send()
+            })
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        let upload_id = created
+            .upload_id()
+            .ok_or("multipart upload returned no upload id")?
+            .to_string();
+
+        let ranges = part_ranges(data.len(), self.transfer.part_size_bytes);
+        let parts_total = ranges.len();
+        let mut completed = Vec::with_capacity(parts_total);
+        for (index, range) in ranges.into_iter().enumerate() {
+            let part_number = (index + 1) as i32;
+            match self
+                .upload_part_with_retry(key, &upload_id, part_number, &data[range])
+                .await
+            {
+                Ok(part) => completed.push(part),
+                Err(e) => {
+                    log::error!(
+                        "S3 multipart upload failed: {} part {}/{} - {:?}",
+                        key,
+                        part_number,
+                        parts_total,
+                        e
+                    );
+                    self.abort_multipart(key, &upload_id).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        let completed = CompletedMultipartUpload::builder()
+            .set_parts(Some(completed))
+            .build();
+        let result = self
+            .send_with_retry("complete multipart upload", key, || {
+                self.client
+                    .complete_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .upload_id(&upload_id)
+                    .multipart_upload(completed.clone())
+                    .send()
+            })
+            .await;
+        if let Err(e) = result {
+            log::error!("S3 multipart complete failed: {} - {:?}", key, e);
+            self.abort_multipart(key, &upload_id).await;
+            return Err(Box::new(e));
+        }
 
-        let resp = self
+        log::info!(
+            "S3 multipart upload: {} ({:.2?}, {} bytes, {} parts)",
+            key,
+            start.elapsed(),
+            data.len(),
+            parts_total
+        );
+        Ok(())
+    }
+
+    async fn upload_part_with_retry(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        chunk: &[u8],
+    ) -> Result<CompletedPart, Box<dyn std::error::Error>> {
+        self.upload_limit.consume(chunk.len()).await;
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(chunk.to_vec()))
+                .send()
+                .await;
+            match result {
+                Ok(resp) => {
+                    return Ok(CompletedPart::builder()
+                        .set_e_tag(resp.e_tag().map(ToString::to_string))
+                        .part_number(part_number)
+                        .build());
+                }
+                Err(e) if attempt >= self.transfer.part_retries => return Err(Box::new(e)),
+                Err(e) => {
+                    attempt += 1;
+                    log::warn!(
+                        "S3 upload part {} of {} failed, retrying ({}/{}): {:?}",
+                        part_number,
+                        key,
+                        attempt,
+                        self.transfer.part_retries,
+                        e
+                    );
+                    tokio::time::sleep(transfer_retry_delay(attempt)).await;
+                }
+            }
+        }
+    }
+
+    /// Best effort: an orphaned upload only costs storage until the bucket lifecycle clears it.
+    async fn abort_multipart(&self, key: &str, upload_id: &str) {
+        if let Err(e) = self
             .client
-            .get_object()
+            .abort_multipart_upload()
             .bucket(&self.bucket)
             .key(key)
+            .upload_id(upload_id)
             .send()
             .await
+        {
+            log::warn!("S3 multipart abort failed: {} - {:?}", key, e);
+        }
+    }
+
+    /// Upload object with an explicit `Content-Type` (e.g. JSON opened in a browser).
+    pub async fn upload_with_content_type(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let key = &self.full_key(key);
+        let data_len = data.len();
+        self.upload_limit.consume(data_len).await;
+        self.send_with_retry("upload", key, || {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .content_type(content_type)
+                .body(ByteStream::from(data.clone()))
+                .send()
+        })
+        .await
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+
+        log::info!("S3 upload: {} ({} bytes, {})", key, data_len, content_type);
+        Ok(())
+    }
+
+    /// Presigned GET URL for `key`, valid for `expires_in` (SigV4 caps this at 7 days).
+    pub async fn presign_get(
+        &self,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let key = &self.full_key(key);
+        let config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        let request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .presigned(config)
+            .await
             .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
 
-        let data = resp
-            .body
-            .collect()
+        log::info!("S3 presigned GET: {} (expires in {:?})", key, expires_in);
+        Ok(request.uri().to_string())
+    }
+
+    /// Presigned PUT URL for `key`: the holder can upload exactly this object until expiry.
+    pub async fn presign_put(
+        &self,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let key = &self.full_key(key);
+        let config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        let request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .presigned(config)
             .await
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?
-            .into_bytes()
-            .to_vec();
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+
+        log::info!("S3 presigned PUT: {} (expires in {:?})", key, expires_in);
+        Ok(request.uri().to_string())
+    }
+
+    pub async fn presign(
+        &self,
+        method: PresignMethod,
+        key: &str,
+        expires_in: Duration,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        match method {
+            PresignMethod::Get => self.presign_get(key, expires_in).await,
+            PresignMethod::Put => self.presign_put(key, expires_in).await,
+        }
+    }
+
+    /// Download object from S3. A body stream that breaks mid-transfer is resumed with a
+    /// range request from the last received byte instead of starting over.
+    pub async fn download(&self, key: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let key = &self.full_key(key);
+        let start = Instant::now();
+        let mut data: Vec<u8> = Vec::new();
+        let mut expected_len: Option<usize> = None;
+        let mut attempt = 0;
+
+        loop {
+            let offset = data.len();
+            let sent = self
+                .send_with_retry("download", key, || {
+                    let mut req = self.client.get_object().bucket(&self.bucket).key(key);
+                    if offset > 0 {
+                        req = req.range(resume_range(offset));
+                    }
+                    req.send()
+                })
+                .await;
+
+            let failure: Box<dyn std::error::Error> = match sent {
+                Ok(resp) => {
+                    if expected_len.is_none() {
+                        expected_len = resp
+                            .content_length()
+                            .and_then(|len| usize::try_from(len).ok());
+                    }
+                    match read_body(resp.body, &mut data, &self.download_limit).await {
+                        Ok(()) if !expected_len.is_some_and(|len| data.len() < len) => break,
+                        Ok(()) => "download ended before the full object was received".into(),
+                        Err(e) => e,
+                    }
+                }
+                // Missing object, bad credentials, ...: nothing to resume.
+                Err(e) if attempt == 0 => return Err(Box::new(e)),
+                Err(e) => Box::new(e),
+            };
+
+            if attempt >= self.transfer.part_retries {
+                log::error!("S3 download failed: {} - {:?}", key, failure);
+                return Err(failure);
+            }
+            attempt += 1;
+            log::warn!(
+                "S3 download interrupted: {} at {} bytes, resuming ({}/{}): {}",
+                key,
+                data.len(),
+                attempt,
+                self.transfer.part_retries,
+                failure
+            );
+            tokio::time::sleep(transfer_retry_delay(attempt)).await;
+        }
+
         let elapsed = start.elapsed();
 
         log::info!(
@@ -141,27 +810,41 @@ impl S3SyncClient {
         Ok(summaries.into_iter().map(|s| s.key).collect())
     }
 
-    /// List objects with metadata (paginated).
+    /// List objects with metadata (paginated). Keys are relative to the key prefix.
     pub async fn list_with_metadata(
         &self,
         prefix: &str,
+    ) -> Result<Vec<S3ObjectSummary>, Box<dyn std::error::Error>> {
+        let mut objects = self.list_raw(&self.full_key(prefix)).await?;
+        for object in &mut objects {
+            if let Some(relative) = object.key.strip_prefix(&self.key_prefix) {
+                object.key = relative.to_string();
+            }
+        }
+        Ok(objects)
+    }
+
+    /// List full bucket keys under `prefix`, ignoring the key prefix.
+    async fn list_raw(
+        &self,
+        prefix: &str,
     ) -> Result<Vec<S3ObjectSummary>, Box<dyn std::error::Error>> {
         let mut continuation_token: Option<String> = None;
         let mut objects = Vec::new();
 
         loop {
-            let mut req = self
-                .client
-                .list_objects_v2()
-                .bucket(&self.bucket)
-                .prefix(prefix);
-
-            if let Some(token) = &continuation_token {
-                req = req.continuation_token(token);
-            }
-
-            let resp = req
-                .send()
+            let resp = self
+                .send_with_retry("list", prefix, || {
+                    let mut req = self
+                        .client
+                        .list_objects_v2()
+                        .bucket(&self.bucket)
+                        .prefix(prefix);
+                    if let Some(token) = &continuation_token {
+                        req = req.continuation_token(token);
+                    }
+                    req.send()
+                })
                 .await
                 .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
 
@@ -170,6 +853,7 @@ impl S3SyncClient {
                     objects.push(S3ObjectSummary {
                         key: key.to_string(),
                         last_modified_unix: obj.last_modified().map(|dt| dt.secs()),
+                        size_bytes: obj.size(),
                     });
                 }
             }
@@ -188,11 +872,13 @@ impl S3SyncClient {
     }
 
     /// Test connection to bucket with minimal request.
+    /// Lists under the key prefix: a policy scoped to the prefix is enough.
     pub async fn test_connection(&self) -> Result<(), Box<dyn std::error::Error>> {
-        self.client
-            .list_objects_v2()
-            .bucket(&self.bucket)
-            .max_keys(1)
+        let mut req = self.client.list_objects_v2().bucket(&self.bucket);
+        if !self.key_prefix.is_empty() {
+            req = req.prefix(&self.key_prefix);
+        }
+        req.max_keys(1)
             .send()
             .await
             .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
@@ -201,13 +887,16 @@ impl S3SyncClient {
 
     /// Delete object from S3
     pub async fn delete(&self, key: &str) -> Result<(), Box<dyn std::error::Error>> {
-        self.client
-            .delete_object()
-            .bucket(&self.bucket)
-            .key(key)
-            .send()
-            .await
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        let key = &self.full_key(key);
+        self.send_with_retry("delete", key, || {
+            self.client
+                .delete_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .send()
+        })
+        .await
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
 
         log::info!("S3 deleted: {}", key);
 
@@ -220,7 +909,7 @@ impl S3SyncClient {
             .client
             .head_object()
             .bucket(&self.bucket)
-            .key(key)
+            .key(self.full_key(key))
             .send()
             .await
         {
@@ -228,6 +917,235 @@ impl S3SyncClient {
             Err(_) => Ok(false),
         }
     }
+
+    /// Move the sync folders (`SYNC_KEY_ROOTS`) stored under `from_prefix` to this client's
+    /// key prefix: server-side copy, then delete of the source, one object at a time.
+    /// Safe to re-run after an interruption: moved objects are no longer listed under
+    /// `from_prefix` and a repeated copy overwrites the same content. Returns the number
+    /// of objects moved.
+    pub async fn move_from_prefix(
+        &self,
+        from_prefix: &str,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        if from_prefix == self.key_prefix {
+            return Ok(0);
+        }
+        let mut moved = 0;
+        for root in SYNC_KEY_ROOTS {
+            for object in self.list_raw(&format!("{}{}", from_prefix, root)).await? {
+                let Some(relative) = object.key.strip_prefix(from_prefix) else {
+                    continue;
+                };
+                let target = self.full_key(relative);
+                let copy_source = format!("{}/{}", self.bucket, encode_copy_source(&object.key));
+                self.send_with_retry("copy", &object.key, || {
+                    self.client
+                        .copy_object()
+                        .bucket(&self.bucket)
+                        .copy_source(&copy_source)
+                        .key(&target)
+                        .send()
+                })
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+                self.send_with_retry("delete", &object.key, || {
+                    self.client
+                        .delete_object()
+                        .bucket(&self.bucket)
+                        .key(&object.key)
+                        .send()
+                })
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+                moved += 1;
+            }
+        }
+        log::info!(
+            "S3 moved {} object(s) from prefix '{}' to '{}'",
+            moved,
+            from_prefix,
+            self.key_prefix
+        );
+        Ok(moved)
+    }
+
+    /// Add `rule` to the bucket lifecycle configuration, replacing a rule with the same ID.
+    /// S3 only accepts the whole configuration, so the other rules are read and written back.
+    pub async fn install_lifecycle_rule(
+        &self,
+        rule: &DeltaLifecycleRule,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut rules: Vec<LifecycleRule> = match self
+            .send_with_retry("get lifecycle", &self.bucket, || {
+                self.client
+                    .get_bucket_lifecycle_configuration()
+                    .bucket(&self.bucket)
+                    .send()
+            })
+            .await
+        {
+            Ok(output) => output.rules().to_vec(),
+            Err(SdkError::ServiceError(se))
+                if se.err().meta().code() == Some("NoSuchLifecycleConfiguration") =>
+            {
+                Vec::new()
+            }
+            Err(e) => return Err(Box::new(e)),
+        };
+        rules.retain(|existing| existing.id() != Some(rule.id.as_str()));
+        rules.push(sdk_lifecycle_rule(rule)?);
+
+        let configuration = BucketLifecycleConfiguration::builder()
+            .set_rules(Some(rules))
+            .build()?;
+        self.send_with_retry("put lifecycle", &self.bucket, || {
+            self.client
+                .put_bucket_lifecycle_configuration()
+                .bucket(&self.bucket)
+                .lifecycle_configuration(configuration.clone())
+                .send()
+        })
+        .await
+        .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        log::info!(
+            "S3 lifecycle rule {} installed on {} ({} -> {} after {} days)",
+            rule.id,
+            self.bucket,
+            rule.prefix,
+            rule.storage_class,
+            rule.transition_after_days
+        );
+        Ok(())
+    }
+}
+
+/// Convert to the SDK type; the rule was validated by `DeltaLifecycleRule::new`.
+fn sdk_lifecycle_rule(
+    rule: &DeltaLifecycleRule,
+) -> Result<LifecycleRule, Box<dyn std::error::Error>> {
+    Ok(LifecycleRule::builder()
+        .id(&rule.id)
+        .status(ExpirationStatus::Enabled)
+        .filter(LifecycleRuleFilter::builder().prefix(&rule.prefix).build())
+        .transitions(
+            Transition::builder()
+                .days(rule.transition_after_days)
+                .storage_class(TransitionStorageClass::from(rule.storage_class.as_str()))
+                .build(),
+        )
+        .abort_incomplete_multipart_upload(
+            AbortIncompleteMultipartUpload::builder()
+                .days_after_initiation(rule.abort_multipart_after_days)
+                .build(),
+        )
+        .build()?)
+}
+
+/// `x-amz-copy-source` is URL-encoded; `/` separators stay as they are.
+pub fn encode_copy_source(key: &str) -> String {
+    let mut encoded = String::with_capacity(key.len());
+    for byte in key.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Throttling, server-side failures and network errors are worth retrying; client errors
+/// (403, 404, bad request) fail immediately. Codes come from the structured error metadata.
+pub fn is_transient_s3_error<E: ProvideErrorMetadata>(err: &SdkError<E>) -> bool {
+    match err {
+        SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) | SdkError::ResponseError(_) => {
+            true
+        }
+        SdkError::ServiceError(se) => {
+            is_transient_error_code(se.err().meta().code(), Some(se.raw().status().as_u16()))
+        }
+        _ => false,
+    }
+}
+
+pub fn is_transient_error_code(code: Option<&str>, http_status: Option<u16>) -> bool {
+    matches!(
+        code,
+        Some(
+            "SlowDown"
+                | "ServiceUnavailable"
+                | "InternalError"
+                | "RequestTimeout"
+                | "Throttling"
+                | "ThrottlingException"
+                | "RequestLimitExceeded"
+                | "TooManyRequests"
+        )
+    ) || matches!(http_status, Some(429 | 500 | 502 | 503 | 504))
+}
+
+/// Uniform value in `0.0..=1.0` (random bits of a v4 UUID; no extra RNG dependency).
+pub(crate) fn random_jitter() -> f64 {
+    uuid::Uuid::new_v4().as_u128() as u32 as f64 / u32::MAX as f64
+}
+
+async fn read_body(
+    mut body: ByteStream,
+    out: &mut Vec<u8>,
+    limit: &BandwidthLimiter,
+) -> Result<(), Box<dyn std::error::Error>> {
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        limit.consume(chunk.len()).await;
+        out.extend_from_slice(&chunk);
+    }
+    Ok(())
+}
+
+/// Byte ranges of each multipart part. The part size grows when needed to stay within
+/// `MAX_UPLOAD_PARTS`.
+pub fn part_ranges(len: usize, part_size: usize) -> Vec<Range<usize>> {
+    let part_size = part_size.max(1).max(len.div_ceil(MAX_UPLOAD_PARTS));
+    (0..len)
+        .step_by(part_size)
+        .map(|start| start..(start + part_size).min(len))
+        .collect()
+}
+
+/// `Range` header continuing a download after `offset` received bytes.
+pub fn resume_range(offset: usize) -> String {
+    format!("bytes={}-", offset)
+}
+
+/// 0.5s, 1s, 2s, ... capped at 8s.
+fn transfer_retry_delay(attempt: u32) -> Duration {
+    Duration::from_millis(500u64.saturating_mul(1 << attempt.saturating_sub(1).min(4)))
+}
+
+/// Reject keys that are empty, absolute or contain empty / `.` / `..` segments.
+pub fn validate_presign_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.starts_with('/') {
+        return Err(format!("invalid object key: {:?}", key));
+    }
+    if key
+        .split('/')
+        .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        return Err(format!("invalid object key: {:?}", key));
+    }
+    Ok(())
+}
+
+/// Requested lifetime (default 1 hour), bounded to 1s..=7 days.
+pub fn presign_expiry(expires_in_secs: Option<u64>) -> Result<Duration, String> {
+    let secs = expires_in_secs.unwrap_or(DEFAULT_PRESIGN_EXPIRY_SECS);
+    if !(1..=MAX_PRESIGN_EXPIRY_SECS).contains(&secs) {
+        return Err(format!(
+            "expires_in_secs must be between 1 and {}",
+            MAX_PRESIGN_EXPIRY_SECS
+        ));
+    }
+    Ok(Duration::from_secs(secs))
 }
 
 fn infer_region_from_endpoint(endpoint: &str) -> Option<String> {
@@ -258,6 +1176,22 @@ fn infer_region_from_endpoint(endpoint: &str) -> Option<String> {
     None
 }
 
+/// S3 client settings on top of the shared SDK config.
+fn s3_config(config: &aws_config::SdkConfig, endpoint: Option<String>) -> aws_sdk_s3::Config {
+    // `RetryPolicy` is the only retry layer: the SDK's own retries would multiply the
+    // configured attempts and stack a second backoff schedule.
+    let mut s3_config =
+        aws_sdk_s3::config::Builder::from(config).retry_config(RetryConfig::disabled());
+    // For local MinIO-style endpoints, use path-style to avoid bucket-subdomain parsing issues.
+    // For cloud providers (AWS S3 / R2 / OSS), keep virtual-hosted style by default.
+    if let Some(endpoint) = endpoint {
+        s3_config = s3_config
+            .force_path_style(should_force_path_style_for_endpoint(&endpoint))
+            .endpoint_url(endpoint);
+    }
+    s3_config.build()
+}
+
 fn should_force_path_style_for_endpoint(endpoint: &str) -> bool {
     let host = extract_endpoint_host(endpoint);
     let host_lc = host.to_ascii_lowercase();
@@ -294,7 +1228,26 @@ fn extract_endpoint_host(endpoint: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::should_force_path_style_for_endpoint;
+    use super::{
+        encode_copy_source, is_transient_error_code, normalize_key_prefix, part_ranges,
+        presign_expiry, resume_range, role_session_name, s3_config,
+        should_force_path_style_for_endpoint, transfer_retry_delay, validate_presign_key,
+        validate_role_arn, RetryPolicy, TransferConfig, MAX_RETRY_DELAY, MAX_UPLOAD_PARTS,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn sdk_retries_are_disabled() {
+        let config = aws_config::SdkConfig::builder()
+            .behavior_version(aws_config::BehaviorVersion::latest())
+            .region(aws_sdk_s3::config::Region::new("us-east-1"))
+            .build();
+        for endpoint in [None, Some("http://localhost:9000".to_string())] {
+            let s3 = s3_config(&config, endpoint);
+            let retry = s3.retry_config().expect("retry config is set");
+            assert_eq!(retry.max_attempts(), 1);
+        }
+    }
 
     #[test]
     fn should_force_path_style_for_local_endpoints() {
@@ -322,4 +1275,142 @@ mod tests {
             "https://oss-cn-shanghai.aliyuncs.com"
         ));
     }
+
+    #[test]
+    fn presign_key_must_be_relative_and_normalized() {
+        assert!(validate_presign_key("snapshots/device/snap.gz").is_ok());
+        assert!(validate_presign_key("").is_err());
+        assert!(validate_presign_key("/deltas/a.gz").is_err());
+        assert!(validate_presign_key("deltas/../secret").is_err());
+        assert!(validate_presign_key("deltas//a.gz").is_err());
+    }
+
+    #[test]
+    fn presign_expiry_is_bounded() {
+        assert_eq!(presign_expiry(None).unwrap().as_secs(), 3600);
+        assert!(presign_expiry(Some(7 * 24 * 3600)).is_ok());
+        assert!(presign_expiry(Some(0)).is_err());
+        assert!(presign_expiry(Some(7 * 24 * 3600 + 1)).is_err());
+    }
+
+    #[test]
+    fn part_ranges_cover_object_in_order() {
+        assert_eq!(part_ranges(10, 4), vec![0..4, 4..8, 8..10]);
+        assert_eq!(part_ranges(8, 4), vec![0..4, 4..8]);
+        assert!(part_ranges(0, 4).is_empty());
+    }
+
+    #[test]
+    fn part_ranges_stay_within_part_limit() {
+        let len = MAX_UPLOAD_PARTS * 3 + 1;
+        let ranges = part_ranges(len, 1);
+        assert!(ranges.len() <= MAX_UPLOAD_PARTS);
+        assert_eq!(ranges.last().unwrap().end, len);
+    }
+
+    #[test]
+    fn transfer_config_enforces_minimum_part_size() {
+        let config = TransferConfig::from_settings(Some(1), Some(0));
+        assert_eq!(config.part_size_bytes, 5 * 1024 * 1024);
+        assert_eq!(config.part_retries, 0);
+        assert_eq!(
+            TransferConfig::from_settings(None, None),
+            TransferConfig::default()
+        );
+    }
+
+    #[test]
+    fn resume_and_retry_helpers() {
+        assert_eq!(resume_range(1024), "bytes=1024-");
+        assert_eq!(transfer_retry_delay(1).as_millis(), 500);
+        assert_eq!(transfer_retry_delay(2).as_millis(), 1000);
+        assert_eq!(transfer_retry_delay(20).as_millis(), 8000);
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        assert!(is_transient_error_code(Some("SlowDown"), Some(503)));
+        assert!(is_transient_error_code(Some("InternalError"), None));
+        assert!(is_transient_error_code(None, Some(502)));
+        assert!(is_transient_error_code(Some("Whatever"), Some(429)));
+        assert!(!is_transient_error_code(Some("AccessDenied"), Some(403)));
+        assert!(!is_transient_error_code(Some("NoSuchKey"), Some(404)));
+        assert!(!is_transient_error_code(None, None));
+    }
+
+    #[test]
+    fn backoff_grows_with_jitter_and_is_capped() {
+        let policy = RetryPolicy::from_settings(Some(5), Some(200));
+        assert_eq!(policy.backoff(1, 0.0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1, 1.0), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3, 0.0), Duration::from_millis(400));
+        assert_eq!(policy.backoff(3, 1.0), Duration::from_millis(800));
+        assert_eq!(policy.backoff(30, 1.0), MAX_RETRY_DELAY);
+        let mid = policy.backoff(2, 0.5);
+        assert!(mid > Duration::from_millis(200) && mid < Duration::from_millis(400));
+    }
+
+    #[test]
+    fn retry_policy_keeps_at_least_one_attempt() {
+        assert_eq!(RetryPolicy::from_settings(Some(0), None).max_attempts, 1);
+        assert_eq!(
+            RetryPolicy::from_settings(None, None),
+            RetryPolicy::default()
+        );
+    }
+
+    #[test]
+    fn role_arn_shape() {
+        assert!(validate_role_arn("arn:aws:iam::123456789012:role/ProjexSync").is_ok());
+        assert!(validate_role_arn("arn:aws-cn:iam::123456789012:role/team/Sync").is_ok());
+        for bad in [
+            "",
+            "ProjexSync",
+            "arn:aws:iam::123456789012:user/alice",
+            "arn:aws:iam::12345:role/Sync",
+            "arn:aws:s3:::bucket",
+            "arn:aws:iam::123456789012:role/",
+        ] {
+            assert!(validate_role_arn(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn role_session_name_is_sts_safe() {
+        assert_eq!(role_session_name("dev-1"), "projex-dev-1");
+        assert_eq!(role_session_name("my laptop/1"), "projex-my-laptop-1");
+        assert_eq!(role_session_name(&"x".repeat(100)).len(), 64);
+    }
+
+    #[test]
+    fn key_prefix_is_normalized_and_cannot_nest_sync_folders() {
+        assert_eq!(normalize_key_prefix("").unwrap(), "");
+        assert_eq!(normalize_key_prefix(" / ").unwrap(), "");
+        assert_eq!(normalize_key_prefix("teams/alpha").unwrap(), "teams/alpha/");
+        assert_eq!(
+            normalize_key_prefix("/teams/alpha/").unwrap(),
+            "teams/alpha/"
+        );
+        for bad in [
+            "teams//alpha",
+            "../alpha",
+            "teams/./a",
+            "a b",
+            "team%20",
+            "deltas",
+            "x/snapshots/",
+        ] {
+            assert!(normalize_key_prefix(bad).is_err(), "{}", bad);
+        }
+        assert!(normalize_key_prefix(&"a".repeat(300)).is_err());
+    }
+
+    #[test]
+    fn copy_source_keeps_separators() {
+        assert_eq!(
+            encode_copy_source("teams/alpha/deltas/dev-1/000001.json.gz"),
+            "teams/alpha/deltas/dev-1/000001.json.gz"
+        );
+        assert_eq!(encode_copy_source("a b+c"), "a%20b%2Bc");
+    }
 }
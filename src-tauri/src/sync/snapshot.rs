@@ -1,15 +1,36 @@
 //! Snapshot manager for full sync
 
+use super::protocol::{legacy_format_version, upgrade_snapshot_json, SYNC_FORMAT_VERSION};
+use super::table_filter::{
+    archived_project_ids, strip_export_rows, SyncScope, PROJECT_DETAIL_TABLES,
+};
 use crate::app::export_json_string;
 use crate::error::AppError;
-use crate::infra::DbPool;
+use crate::infra::{get_connection, get_read_connection, DbPool};
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::io::{Read, Write};
 
+/// Export sections compared by the restore preview, with the table each one replaces.
+pub(crate) const RESTORE_DIFF_SECTIONS: &[(&str, &str)] = &[
+    ("persons", "persons"),
+    ("partners", "partners"),
+    ("projects", "projects"),
+    ("assignments", "assignments"),
+    ("statusHistory", "status_history"),
+    ("sizeHistory", "project_size_history"),
+    ("comments", "project_comments"),
+    ("commentReactions", "comment_reactions"),
+    ("commentRevisions", "comment_revisions"),
+    ("checklistItems", "checklist_items"),
+    ("customFieldDefs", "custom_field_defs"),
+    ("customFieldValues", "project_custom_values"),
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Snapshot {
     pub version: i32,
@@ -17,12 +38,30 @@ pub struct Snapshot {
     pub device_id: String,
     pub data: String, // JSON string from export
     pub checksum: String,
+    /// Payload format; objects written before the field existed read as format 1.
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
+    /// Rows the creating device kept out of sync (absent = nothing excluded).
+    #[serde(default)]
+    pub scope: SyncScope,
 }
 
 impl Snapshot {
-    /// Create a new snapshot from current database
+    /// Create a new snapshot from current database, without the rows outside this device's sync scope
     pub fn create(pool: &DbPool, device_id: String) -> Result<Self, AppError> {
-        let data = export_json_string(pool, None)?;
+        let scope = SyncScope::load(&get_read_connection(pool))?;
+        let mut data = export_json_string(pool, None)?;
+        if !scope.is_full() {
+            let mut value: serde_json::Value = serde_json::from_str(&data)
+                .map_err(|e| AppError::Db(format!("Invalid export data: {}", e)))?;
+            let archived = if scope.exclude_archived_projects {
+                archived_project_ids(&value)
+            } else {
+                Default::default()
+            };
+            strip_export_rows(&mut value, &scope.ignored_tables, &archived);
+            data = value.to_string();
+        }
         let checksum = Self::calculate_checksum(&data);
 
         Ok(Self {
@@ -31,6 +70,8 @@ impl Snapshot {
             device_id,
             data,
             checksum,
+            format_version: SYNC_FORMAT_VERSION,
+            scope,
         })
     }
 
@@ -62,7 +103,8 @@ impl Snapshot {
             .map_err(|e| AppError::Db(format!("Compress finish failed: {}", e)))
     }
 
-    /// Decompress snapshot from bytes
+    /// Decompress snapshot from bytes, upgrading older payload formats.
+    /// Fails with `SYNC_FORMAT_UNSUPPORTED` for snapshots written by a newer app.
     pub fn decompress(data: &[u8]) -> Result<Self, AppError> {
         let mut decoder = GzDecoder::new(data);
         let mut json = String::new();
@@ -70,11 +112,44 @@ impl Snapshot {
             .read_to_string(&mut json)
             .map_err(|e| AppError::Db(format!("Decompress failed: {}", e)))?;
 
-        serde_json::from_str(&json)
+        let mut value: serde_json::Value = serde_json::from_str(&json)
+            .map_err(|e| AppError::Db(format!("Deserialize snapshot failed: {}", e)))?;
+        upgrade_snapshot_json(&mut value)?;
+        serde_json::from_value(value)
             .map_err(|e| AppError::Db(format!("Deserialize snapshot failed: {}", e)))
     }
 }
 
+/// Per-table effect of restoring a snapshot over the current local data.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TableRestoreDiff {
+    pub table: String,
+    /// Rows only in the snapshot.
+    pub added: usize,
+    /// Rows in both whose content differs.
+    pub updated: usize,
+    /// Local rows missing from the snapshot (lost on restore).
+    pub deleted: usize,
+    pub unchanged: usize,
+}
+
+/// Business tables in the order a full restore clears them.
+const RESTORE_CLEAR_ORDER: &[&str] = &[
+    "project_custom_values",
+    "custom_field_defs",
+    "comment_reactions",
+    "comment_revisions",
+    "project_comments",
+    "checklist_items",
+    "project_size_history",
+    "status_history",
+    "assignments",
+    "project_tags",
+    "projects",
+    "persons",
+    "partners",
+];
+
 pub struct SnapshotManager<'a> {
     pool: &'a DbPool,
     device_id: String,
@@ -104,6 +179,38 @@ impl<'a> SnapshotManager<'a> {
         Ok(snapshot)
     }
 
+    /// Dry run of `restore_snapshot`: compare snapshot rows with the current data by id.
+    pub fn diff_snapshot(&self, snapshot: &Snapshot) -> Result<Vec<TableRestoreDiff>, AppError> {
+        if !snapshot.verify() {
+            return Err(AppError::Db("Snapshot integrity check failed".to_string()));
+        }
+
+        let mut incoming: serde_json::Value = serde_json::from_str(&snapshot.data)
+            .map_err(|e| AppError::Db(format!("Invalid snapshot data: {}", e)))?;
+        // Same export format on both sides, so rows compare field by field.
+        let mut current: serde_json::Value =
+            serde_json::from_str(&export_json_string(self.pool, None)?)
+                .map_err(|e| AppError::Db(format!("Invalid export data: {}", e)))?;
+
+        // Rows outside either scope are left alone by the restore.
+        let keep = self.restore_scope(snapshot)?;
+        let archived = kept_archived_projects(&keep, &incoming);
+        strip_export_rows(&mut incoming, &keep.ignored_tables, &archived);
+        strip_export_rows(&mut current, &keep.ignored_tables, &archived);
+
+        Ok(RESTORE_DIFF_SECTIONS
+            .iter()
+            .filter(|(_, table)| !keep.ignores_table(table))
+            .map(|(section, table)| diff_section(table, &current[*section], &incoming[*section]))
+            .collect())
+    }
+
+    /// This device's scope combined with the one the snapshot was taken with.
+    fn restore_scope(&self, snapshot: &Snapshot) -> Result<SyncScope, AppError> {
+        let local = SyncScope::load(&get_read_connection(self.pool))?;
+        Ok(local.union(&snapshot.scope))
+    }
+
     /// Restore from snapshot (full restore)
     pub fn restore_snapshot(&self, snapshot: &Snapshot) -> Result<(), AppError> {
         log::info!("Restoring snapshot: {}", &snapshot.checksum[..8]);
@@ -114,34 +221,43 @@ impl<'a> SnapshotManager<'a> {
         }
 
         // Parse snapshot data
-        let export_data: serde_json::Value = serde_json::from_str(&snapshot.data)
+        let mut export_data: serde_json::Value = serde_json::from_str(&snapshot.data)
             .map_err(|e| AppError::Db(format!("Invalid snapshot data: {}", e)))?;
 
+        // Rows outside either scope keep their local state.
+        let keep = self.restore_scope(snapshot)?;
+        let archived = kept_archived_projects(&keep, &export_data);
+        strip_export_rows(&mut export_data, &keep.ignored_tables, &archived);
+        let archived_json = serde_json::to_string(&archived)
+            .map_err(|e| AppError::Db(format!("Serialize archived projects failed: {}", e)))?;
+
         // Restore to database
-        let mut conn = self
-            .pool
-            .0
-            .lock()
-            .map_err(|e: std::sync::PoisonError<_>| AppError::Db(e.to_string()))?;
+        let mut conn = get_connection(&self.pool);
 
         let tx = conn
             .transaction()
             .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
 
         // Clear existing data
-        tx.execute("DELETE FROM project_comments", [])
-            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
-        tx.execute("DELETE FROM status_history", [])
-            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
-        tx.execute("DELETE FROM assignments", [])
-            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
-        tx.execute("DELETE FROM project_tags", [])
-            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
-        tx.execute("DELETE FROM projects", [])
-            .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
-        tx.execute("DELETE FROM persons", [])
+        for table in RESTORE_CLEAR_ORDER {
+            if keep.ignores_table(table) {
+                continue;
+            }
+            if PROJECT_DETAIL_TABLES.contains(table) && !archived.is_empty() {
+                tx.execute(
+                    &format!(
+                        "DELETE FROM {} WHERE project_id NOT IN (SELECT value FROM json_each(?1))",
+                        table
+                    ),
+                    [&archived_json],
+                )
+            } else {
+                tx.execute(&format!("DELETE FROM {}", table), [])
+            }
             .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
-        tx.execute("DELETE FROM partners", [])
+        }
+        // Merge bases describe the pre-restore rows; fall back to LWW until re-established.
+        tx.execute("DELETE FROM sync_record_base", [])
             .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
 
         // Restore persons
@@ -179,6 +295,13 @@ impl<'a> SnapshotManager<'a> {
             }
         }
 
+        // Restore size history (absent in older snapshots)
+        if let Some(history) = export_data["sizeHistory"].as_array() {
+            for entry in history {
+                self.restore_size_history(&tx, entry)?;
+            }
+        }
+
         // Restore comments (schema version 2)
         if let Some(comments) = export_data["comments"].as_array() {
             for comment in comments {
@@ -186,6 +309,39 @@ impl<'a> SnapshotManager<'a> {
             }
         }
 
+        // Restore comment reactions (absent in older snapshots)
+        if let Some(reactions) = export_data["commentReactions"].as_array() {
+            for reaction in reactions {
+                self.restore_comment_reaction(&tx, reaction)?;
+            }
+        }
+
+        // Restore comment revisions (absent in older snapshots)
+        if let Some(revisions) = export_data["commentRevisions"].as_array() {
+            for revision in revisions {
+                self.restore_comment_revision(&tx, revision)?;
+            }
+        }
+
+        // Restore checklist items (absent in older snapshots)
+        if let Some(items) = export_data["checklistItems"].as_array() {
+            for item in items {
+                self.restore_checklist_item(&tx, item)?;
+            }
+        }
+
+        // Restore custom fields (absent in older snapshots)
+        if let Some(defs) = export_data["customFieldDefs"].as_array() {
+            for def in defs {
+                self.restore_custom_field_def(&tx, def)?;
+            }
+        }
+        if let Some(values) = export_data["customFieldValues"].as_array() {
+            for value in values {
+                self.restore_custom_value(&tx, value)?;
+            }
+        }
+
         tx.commit()
             .map_err(|e: rusqlite::Error| AppError::Db(e.to_string()))?;
 
@@ -200,8 +356,8 @@ impl<'a> SnapshotManager<'a> {
         data: &serde_json::Value,
     ) -> Result<(), AppError> {
         tx.execute(
-            "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at, _version)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at, _version, deleted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             rusqlite::params![
                 data["id"].as_str(),
                 data["displayName"].as_str(),
@@ -212,6 +368,7 @@ impl<'a> SnapshotManager<'a> {
                 data["createdAt"].as_str(),
                 data["updatedAt"].as_str(),
                 data["version"].as_i64().unwrap_or(1),
+                data["deletedAt"].as_str(),
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -225,8 +382,8 @@ impl<'a> SnapshotManager<'a> {
         data: &serde_json::Value,
     ) -> Result<(), AppError> {
         tx.execute(
-            "INSERT INTO partners (id, name, note, is_active, created_at, updated_at, _version)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO partners (id, name, note, is_active, created_at, updated_at, _version, deleted_at, default_currency)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             rusqlite::params![
                 data["id"].as_str(),
                 data["name"].as_str(),
@@ -239,6 +396,8 @@ impl<'a> SnapshotManager<'a> {
                 data["createdAt"].as_str(),
                 data["updatedAt"].as_str(),
                 data["version"].as_i64().unwrap_or(1),
+                data["deletedAt"].as_str(),
+                data["defaultCurrency"].as_str(),
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -254,8 +413,9 @@ impl<'a> SnapshotManager<'a> {
         tx.execute(
             "INSERT INTO projects (id, name, product_name, description, priority, current_status, country_code, 
                                    partner_id, owner_person_id, start_date, due_date, 
-                                   created_at, updated_at, archived_at, _version)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                                   created_at, updated_at, archived_at, _version, parent_project_id, rank, size_points, deleted_at, cloned_from_id,
+                                   estimated_hours, budget_amount, budget_currency)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
             rusqlite::params![
                 data["id"].as_str(),
                 data["name"].as_str(),
@@ -272,6 +432,14 @@ impl<'a> SnapshotManager<'a> {
                 data["updatedAt"].as_str(),
                 data["archivedAt"].as_str(),
                 data["version"].as_i64().unwrap_or(1),
+                data["parentProjectId"].as_str(),
+                data["rank"].as_f64(),
+                data["sizePoints"].as_i64(),
+                data["deletedAt"].as_str(),
+                data["clonedFromId"].as_str(),
+                data["estimatedHours"].as_f64(),
+                data["budgetAmount"].as_f64(),
+                data["budgetCurrency"].as_str(),
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -341,6 +509,31 @@ impl<'a> SnapshotManager<'a> {
         Ok(())
     }
 
+    fn restore_size_history(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO project_size_history (id, project_id, from_points, to_points, changed_at,
+                                               changed_by_person_id, note, _version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                data["id"].as_str(),
+                data["projectId"].as_str(),
+                data["fromPoints"].as_i64(),
+                data["toPoints"].as_i64(),
+                data["changedAt"].as_str(),
+                data["changedByPersonId"].as_str(),
+                data["note"].as_str().unwrap_or(""),
+                data["version"].as_i64().unwrap_or(1),
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
     fn restore_comment(
         &self,
         tx: &rusqlite::Transaction,
@@ -348,8 +541,9 @@ impl<'a> SnapshotManager<'a> {
     ) -> Result<(), AppError> {
         tx.execute(
             "INSERT INTO project_comments (
-                id, project_id, person_id, content, is_pinned, created_at, updated_at, _version
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                id, project_id, person_id, content, is_pinned, created_at, updated_at, _version,
+                parent_comment_id, pin_order
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             rusqlite::params![
                 data["id"].as_str(),
                 data["projectId"].as_str(),
@@ -363,6 +557,138 @@ impl<'a> SnapshotManager<'a> {
                 data["createdAt"].as_str(),
                 data["updatedAt"].as_str(),
                 1i64,
+                data["parentCommentId"].as_str(),
+                data["pinOrder"].as_i64(),
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn restore_comment_reaction(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO comment_reactions (
+                id, comment_id, project_id, person_id, emoji, created_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                data["id"].as_str(),
+                data["commentId"].as_str(),
+                data["projectId"].as_str(),
+                data["personId"].as_str(),
+                data["emoji"].as_str(),
+                data["createdAt"].as_str(),
+                1i64,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn restore_comment_revision(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO comment_revisions (
+                id, comment_id, project_id, person_id, content, written_at, replaced_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                data["id"].as_str(),
+                data["commentId"].as_str(),
+                data["projectId"].as_str(),
+                data["personId"].as_str(),
+                data["content"].as_str(),
+                data["writtenAt"].as_str(),
+                data["replacedAt"].as_str(),
+                1i64,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn restore_checklist_item(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO checklist_items (
+                id, project_id, text, done, sort_order, done_at, created_at, updated_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                data["id"].as_str(),
+                data["projectId"].as_str(),
+                data["text"].as_str(),
+                if data["done"].as_bool().unwrap_or(false) {
+                    1
+                } else {
+                    0
+                },
+                data["sortOrder"].as_i64().unwrap_or(0),
+                data["doneAt"].as_str(),
+                data["createdAt"].as_str(),
+                data["updatedAt"].as_str(),
+                1i64,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn restore_custom_field_def(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        let options =
+            serde_json::to_string(&data["options"].as_array().cloned().unwrap_or_default())
+                .map_err(|e| AppError::Db(e.to_string()))?;
+        tx.execute(
+            "INSERT INTO custom_field_defs (
+                id, name, field_type, options, sort_order, created_at, updated_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                data["id"].as_str(),
+                data["name"].as_str(),
+                data["fieldType"].as_str(),
+                options,
+                data["sortOrder"].as_i64().unwrap_or(0),
+                data["createdAt"].as_str(),
+                data["updatedAt"].as_str(),
+                1i64,
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn restore_custom_value(
+        &self,
+        tx: &rusqlite::Transaction,
+        data: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        tx.execute(
+            "INSERT INTO project_custom_values (
+                id, project_id, field_id, value, updated_at, _version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                data["id"].as_str(),
+                data["projectId"].as_str(),
+                data["fieldId"].as_str(),
+                data["value"].as_str(),
+                data["updatedAt"].as_str(),
+                1i64,
             ],
         )
         .map_err(|e| AppError::Db(e.to_string()))?;
@@ -370,3 +696,58 @@ impl<'a> SnapshotManager<'a> {
         Ok(())
     }
 }
+
+/// Projects archived in the snapshot whose details stay local on restore.
+fn kept_archived_projects(keep: &SyncScope, incoming: &serde_json::Value) -> HashSet<String> {
+    if keep.exclude_archived_projects {
+        archived_project_ids(incoming)
+    } else {
+        HashSet::new()
+    }
+}
+
+fn diff_section(
+    table: &str,
+    current: &serde_json::Value,
+    incoming: &serde_json::Value,
+) -> TableRestoreDiff {
+    let index = |rows: &serde_json::Value| -> HashMap<String, serde_json::Value> {
+        rows.as_array()
+            .map(|rows| {
+                rows.iter()
+                    .filter_map(|row| Some((row["id"].as_str()?.to_string(), row.clone())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let current = index(current);
+    let incoming = index(incoming);
+
+    let mut diff = TableRestoreDiff {
+        table: table.to_string(),
+        ..Default::default()
+    };
+    for (id, row) in &incoming {
+        match current.get(id) {
+            None => diff.added += 1,
+            Some(local) if rows_equal(local, row) => diff.unchanged += 1,
+            Some(_) => diff.updated += 1,
+        }
+    }
+    diff.deleted = current
+        .keys()
+        .filter(|id| !incoming.contains_key(*id))
+        .count();
+    diff
+}
+
+/// Field-wise equality; a field missing on one side (older export schema) equals null.
+fn rows_equal(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    match (a.as_object(), b.as_object()) {
+        (Some(a), Some(b)) => a.keys().chain(b.keys()).all(|key| {
+            a.get(key).unwrap_or(&serde_json::Value::Null)
+                == b.get(key).unwrap_or(&serde_json::Value::Null)
+        }),
+        _ => a == b,
+    }
+}
@@ -0,0 +1,108 @@
+//! Remote snapshot catalog: timestamped keys, listing metadata and retention.
+//!
+//! 设计要点：
+//! - 新快照写入 `snapshots/<device_id>/snapshot-<unix_nanos>.gz`，不再覆盖同一对象；
+//!   旧格式 `snapshots/latest-<device_id>.gz` 仍可列出与恢复。
+//! - 每台设备只裁剪自己上传的快照，保留最新 `snapshot_retention_count` 份（含旧格式对象）。
+
+use crate::sync::S3ObjectSummary;
+use serde::Serialize;
+
+pub const SNAPSHOT_RETENTION_COUNT_KEY: &str = "snapshot_retention_count";
+pub const DEFAULT_SNAPSHOT_RETENTION_COUNT: i64 = 5;
+
+pub const SNAPSHOT_PREFIX: &str = "snapshots/";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotInfo {
+    pub key: String,
+    pub device_id: String,
+    /// Creation time from the key (RFC3339); `None` for legacy `latest-<device>` objects.
+    pub created_at: Option<String>,
+    pub last_modified_unix: Option<i64>,
+    pub size_bytes: Option<i64>,
+    /// Overwritten `snapshots/latest-<device_id>.gz` object from older versions.
+    pub legacy: bool,
+    #[serde(skip)]
+    sort_nanos: i64,
+}
+
+pub fn snapshot_key(device_id: &str, created_at_nanos: i64) -> String {
+    format!(
+        "{}{}/snapshot-{}.gz",
+        SNAPSHOT_PREFIX, device_id, created_at_nanos
+    )
+}
+
+/// Key for a snapshot created now.
+pub fn new_snapshot_key(device_id: &str) -> String {
+    let nanos = chrono::Utc::now()
+        .timestamp_nanos_opt()
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_micros() * 1_000);
+    snapshot_key(device_id, nanos)
+}
+
+/// `(device_id, created_at_nanos)`; the timestamp is `None` for legacy keys.
+pub fn parse_snapshot_key(key: &str) -> Option<(&str, Option<i64>)> {
+    let rest = key.strip_prefix(SNAPSHOT_PREFIX)?;
+    if let Some(device_id) = rest
+        .strip_prefix("latest-")
+        .and_then(|r| r.strip_suffix(".gz"))
+    {
+        return (!device_id.is_empty() && !device_id.contains('/')).then_some((device_id, None));
+    }
+    let (device_id, file) = rest.split_once('/')?;
+    let nanos = file
+        .strip_prefix("snapshot-")?
+        .strip_suffix(".gz")?
+        .parse::<i64>()
+        .ok()?;
+    (!device_id.is_empty()).then_some((device_id, Some(nanos)))
+}
+
+/// Recognised snapshots, newest first (key time, then last-modified, then key).
+pub fn describe_snapshots(objects: &[S3ObjectSummary]) -> Vec<SnapshotInfo> {
+    let mut snapshots: Vec<SnapshotInfo> = objects
+        .iter()
+        .filter_map(|obj| {
+            let (device_id, nanos) = parse_snapshot_key(&obj.key)?;
+            let sort_nanos = nanos.unwrap_or_else(|| {
+                obj.last_modified_unix
+                    .map_or(i64::MIN, |secs| secs.saturating_mul(1_000_000_000))
+            });
+            Some(SnapshotInfo {
+                key: obj.key.clone(),
+                device_id: device_id.to_string(),
+                created_at: nanos
+                    .and_then(|n| {
+                        chrono::DateTime::from_timestamp(
+                            n.div_euclid(1_000_000_000),
+                            n.rem_euclid(1_000_000_000) as u32,
+                        )
+                    })
+                    .map(|t| t.to_rfc3339()),
+                last_modified_unix: obj.last_modified_unix,
+                size_bytes: obj.size_bytes,
+                legacy: nanos.is_none(),
+                sort_nanos,
+            })
+        })
+        .collect();
+    snapshots.sort_by(|a, b| {
+        b.sort_nanos
+            .cmp(&a.sort_nanos)
+            .then(b.last_modified_unix.cmp(&a.last_modified_unix))
+            .then(b.key.cmp(&a.key))
+    });
+    snapshots
+}
+
+/// Keys of `device_id`'s own snapshots beyond the newest `keep` (`keep` >= 1).
+pub fn snapshots_to_prune(snapshots: &[SnapshotInfo], device_id: &str, keep: usize) -> Vec<String> {
+    snapshots
+        .iter()
+        .filter(|s| s.device_id == device_id)
+        .skip(keep.max(1))
+        .map(|s| s.key.clone())
+        .collect()
+}
@@ -0,0 +1,324 @@
+//! Staging area for "review before apply" sync mode.
+//!
+//! 设计要点：
+//! - 开启 review 模式后，下载的远端 delta 不直接应用，而是整包存入 `sync_staged_deltas`，
+//!   并按操作拆出可读摘要写入 `sync_staged_changes` 供 UI 审阅。
+//! - 源设备游标在暂存时即推进（避免重复下载），因此暂存队列就是这些 delta 的唯一副本：
+//!   批准时按 (source_device_id, source_timestamp) 顺序整体应用；丢弃即放弃这些远端变更。
+//! - 只要队列非空，后续下载的 delta 也继续暂存（即使已关闭 review 模式），保证应用顺序不乱。
+//! - 吊销设备时删除其暂存 delta；批准时仍逐条检查来源（吊销可能经队列中较早的 delta 到达），
+//!   被吊销来源的 delta 直接丢弃，不阻塞其余队列。
+
+use super::delta_sync::{
+    operation_type_to_sql_name, Delta, DeltaSyncEngine, Operation, OperationType,
+};
+use super::device_registry::is_device_revoked;
+use crate::error::AppError;
+use crate::infra::{get_connection, DbPool};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+pub const REVIEW_BEFORE_APPLY_KEY: &str = "review_before_apply";
+
+#[derive(Debug, Serialize)]
+pub struct StagedChangeDto {
+    pub table_name: String,
+    pub record_id: String,
+    /// "INSERT" | "UPDATE" | "DELETE"
+    pub operation: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StagedDeltaDto {
+    pub id: i64,
+    pub delta_key: String,
+    pub source_device_id: String,
+    pub source_timestamp: i64,
+    pub staged_at: String,
+    pub changes: Vec<StagedChangeDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StagedApplyResult {
+    pub applied_deltas: i64,
+    pub applied_operations: i64,
+    /// Deltas dropped because their source device has been revoked.
+    pub revoked_deltas: i64,
+}
+
+/// Whether newly downloaded remote deltas must go to staging instead of being applied.
+pub fn should_stage_remote_deltas(conn: &Connection) -> Result<bool, AppError> {
+    let review_mode = conn
+        .query_row(
+            "SELECT value FROM sync_config WHERE key = ?1",
+            [REVIEW_BEFORE_APPLY_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .map(|v| v.trim() == "1")
+        .unwrap_or(false);
+    if review_mode {
+        return Ok(true);
+    }
+    let staged: i64 = conn
+        .query_row("SELECT COUNT(*) FROM sync_staged_deltas", [], |row| {
+            row.get(0)
+        })
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(staged > 0)
+}
+
+/// Store a verified remote delta for later review. Returns false if the key was already staged.
+pub fn stage_remote_delta(
+    conn: &Connection,
+    delta_key: &str,
+    source_device_id: &str,
+    source_timestamp: i64,
+    delta: &Delta,
+) -> Result<bool, AppError> {
+    let payload = serde_json::to_string(delta)
+        .map_err(|e| AppError::Db(format!("Serialize delta failed: {}", e)))?;
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let inserted = tx
+        .execute(
+            "INSERT OR IGNORE INTO sync_staged_deltas (delta_key, source_device_id, source_timestamp, payload, staged_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                delta_key,
+                source_device_id,
+                source_timestamp,
+                payload,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    if inserted == 0 {
+        return Ok(false);
+    }
+    let staged_delta_id = tx.last_insert_rowid();
+
+    for op in &delta.operations {
+        tx.execute(
+            "INSERT INTO sync_staged_changes (staged_delta_id, table_name, record_id, operation, summary)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                staged_delta_id,
+                op.table_name,
+                op.record_id,
+                operation_type_to_sql_name(&op.op_type),
+                summarize_operation(op)
+            ],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    }
+
+    tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(true)
+}
+
+/// List staged deltas in apply order, each with its per-operation summaries.
+pub fn list_staged_deltas(pool: &DbPool) -> Result<Vec<StagedDeltaDto>, AppError> {
+    let conn = get_connection(pool);
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, delta_key, source_device_id, source_timestamp, staged_at
+             FROM sync_staged_deltas
+             ORDER BY source_device_id, source_timestamp, id",
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let mut deltas = stmt
+        .query_map([], |row| {
+            Ok(StagedDeltaDto {
+                id: row.get(0)?,
+                delta_key: row.get(1)?,
+                source_device_id: row.get(2)?,
+                source_timestamp: row.get(3)?,
+                staged_at: row.get(4)?,
+                changes: Vec::new(),
+            })
+        })
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    let mut change_stmt = conn
+        .prepare(
+            "SELECT table_name, record_id, operation, summary
+             FROM sync_staged_changes WHERE staged_delta_id = ?1 ORDER BY id",
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    for delta in &mut deltas {
+        delta.changes = change_stmt
+            .query_map([delta.id], |row| {
+                Ok(StagedChangeDto {
+                    table_name: row.get(0)?,
+                    record_id: row.get(1)?,
+                    operation: row.get(2)?,
+                    summary: row.get(3)?,
+                })
+            })
+            .map_err(|e| AppError::Db(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Db(e.to_string()))?;
+    }
+
+    Ok(deltas)
+}
+
+/// Apply every staged delta in source order, removing each one once applied. Deltas from
+/// a revoked device (possibly revoked by an earlier delta in the queue) are dropped.
+pub fn apply_staged_deltas(pool: &DbPool) -> Result<StagedApplyResult, AppError> {
+    let (device_id, staged) = {
+        let conn = get_connection(pool);
+        let device_id = DeltaSyncEngine::get_device_id(&conn)?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, source_device_id, payload FROM sync_staged_deltas
+                 ORDER BY source_device_id, source_timestamp, id",
+            )
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        let staged = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(|e| AppError::Db(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        (device_id, staged)
+    }; // release conn before apply_delta locks the pool again
+
+    let engine = DeltaSyncEngine::new(pool, device_id);
+    let mut result = StagedApplyResult {
+        applied_deltas: 0,
+        applied_operations: 0,
+        revoked_deltas: 0,
+    };
+    for (staged_id, source_device_id, payload) in staged {
+        {
+            let conn = get_connection(pool);
+            if is_device_revoked(&conn, &source_device_id)? {
+                log::warn!(
+                    "Dropped staged delta {} from revoked device {}",
+                    staged_id,
+                    source_device_id
+                );
+                delete_staged_delta(&conn, staged_id)?;
+                result.revoked_deltas += 1;
+                continue;
+            }
+        }
+        let delta: Delta = serde_json::from_str(&payload)
+            .map_err(|e| AppError::Db(format!("Deserialize staged delta failed: {}", e)))?;
+        let before_apply_sync_meta_id = engine.current_max_sync_metadata_id()?;
+        engine.apply_delta(&delta)?;
+        engine
+            .mark_remote_applied_operations_synced(before_apply_sync_meta_id, &delta.operations)?;
+        {
+            let conn = get_connection(pool);
+            delete_staged_delta(&conn, staged_id)?;
+        }
+        result.applied_deltas += 1;
+        result.applied_operations += delta.operations.len() as i64;
+    }
+
+    Ok(result)
+}
+
+/// Drop all staged deltas without applying them. Returns the number of deltas discarded.
+pub fn discard_staged_deltas(pool: &DbPool) -> Result<i64, AppError> {
+    let conn = get_connection(pool);
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    tx.execute("DELETE FROM sync_staged_changes", [])
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let discarded = tx
+        .execute("DELETE FROM sync_staged_deltas", [])
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    tx.commit().map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(discarded as i64)
+}
+
+/// Drop the staged deltas of `source_device_id` (the device was revoked). Returns the
+/// number of deltas dropped.
+pub(crate) fn discard_staged_deltas_from(
+    conn: &Connection,
+    source_device_id: &str,
+) -> Result<usize, AppError> {
+    conn.execute(
+        "DELETE FROM sync_staged_changes WHERE staged_delta_id IN
+           (SELECT id FROM sync_staged_deltas WHERE source_device_id = ?1)",
+        [source_device_id],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    conn.execute(
+        "DELETE FROM sync_staged_deltas WHERE source_device_id = ?1",
+        [source_device_id],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))
+}
+
+fn delete_staged_delta(conn: &Connection, staged_id: i64) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM sync_staged_changes WHERE staged_delta_id = ?1",
+        [staged_id],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    conn.execute("DELETE FROM sync_staged_deltas WHERE id = ?1", [staged_id])
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(())
+}
+
+/// Human-readable one-liner for a remote operation, e.g. `Update project "Apollo"`.
+pub fn summarize_operation(op: &Operation) -> String {
+    let verb = match op.op_type {
+        OperationType::Insert => "Create",
+        OperationType::Update => "Update",
+        OperationType::Delete => "Delete",
+    };
+    let noun = match op.table_name.as_str() {
+        "projects" => "project",
+        "persons" => "person",
+        "partners" => "partner",
+        "assignments" => "assignment",
+        "status_history" => "status change",
+        "project_size_history" => "size change",
+        "project_tags" => "project tag",
+        "project_comments" => "comment",
+        "comment_reactions" => "comment reaction",
+        "comment_revisions" => "comment revision",
+        "checklist_items" => "checklist item",
+        "custom_field_defs" => "custom field",
+        "project_custom_values" => "custom field value",
+        other => other,
+    };
+
+    let data = op.data.as_ref();
+    let field = |key: &str| {
+        data.and_then(|d| d.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let label = if op.table_name == "status_history" {
+        field("to_status").map(|to| match field("from_status") {
+            Some(from) => format!("{} -> {}", from, to),
+            None => to,
+        })
+    } else {
+        field("name")
+            .or_else(|| field("display_name"))
+            .or_else(|| field("tag"))
+    };
+
+    match label {
+        Some(label) => format!("{} {} \"{}\"", verb, noun, label),
+        None => format!("{} {} {}", verb, noun, op.record_id),
+    }
+}
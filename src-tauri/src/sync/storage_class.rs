@@ -0,0 +1,198 @@
+//! Storage class, object tags and lifecycle rules that keep the S3 bill down.
+//!
+//! - 快照上传可指定存储类别（`STANDARD_IA` / `GLACIER_IR` …）与对象标签，配置存于 `sync_config`；
+//!   只允许可立即读取的类别，恢复快照不需要先取回（不支持 `GLACIER` / `DEEP_ARCHIVE`）。
+//! - 旧 delta 通过桶生命周期规则转存到低频类别：`cmd_sync_install_lifecycle` 写入（或只返回）一条
+//!   作用于 `<key_prefix>deltas/` 的规则，并清理超时未完成的分片上传。delta 仍由压缩（compaction）删除，
+//!   规则不设置过期：按游标判断哪些 delta 可删只能由客户端完成。
+//! - 写入时只替换同 ID 的规则，桶上已有的其他规则保持不变。
+
+use serde_json::json;
+
+pub const SNAPSHOT_STORAGE_CLASS_KEY: &str = "s3_snapshot_storage_class";
+pub const SNAPSHOT_TAGS_KEY: &str = "s3_snapshot_tags";
+
+pub const DEFAULT_STORAGE_CLASS: &str = "STANDARD";
+/// Classes whose objects can be read back immediately.
+pub const INSTANT_STORAGE_CLASSES: [&str; 5] = [
+    "STANDARD",
+    "STANDARD_IA",
+    "ONEZONE_IA",
+    "INTELLIGENT_TIERING",
+    "GLACIER_IR",
+];
+/// S3 limit for tags per object.
+pub const MAX_OBJECT_TAGS: usize = 10;
+
+pub const DEFAULT_DELTA_TRANSITION_DAYS: i32 = 30;
+pub const DEFAULT_DELTA_TRANSITION_CLASS: &str = "STANDARD_IA";
+pub const DEFAULT_ABORT_MULTIPART_DAYS: i32 = 7;
+/// S3 rejects transitions to the IA classes before this age.
+pub const MIN_IA_TRANSITION_DAYS: i32 = 30;
+
+/// Upper-case storage class name, limited to `INSTANT_STORAGE_CLASSES`.
+pub fn normalize_storage_class(raw: &str) -> Result<String, String> {
+    let class = raw.trim().to_ascii_uppercase();
+    if class.is_empty() {
+        return Ok(DEFAULT_STORAGE_CLASS.to_string());
+    }
+    if INSTANT_STORAGE_CLASSES.contains(&class.as_str()) {
+        Ok(class)
+    } else {
+        Err(format!(
+            "INVALID_STORAGE_CLASS: {} (expected one of {})",
+            raw.trim(),
+            INSTANT_STORAGE_CLASSES.join(", ")
+        ))
+    }
+}
+
+/// `key=value` pairs separated by `,` or `&`. Keys are required and unique; both sides follow
+/// the S3 tag rules (letters, digits, spaces and `+ - = . _ : / @`, key <= 128, value <= 256).
+pub fn parse_object_tags(raw: &str) -> Result<Vec<(String, String)>, String> {
+    let mut tags: Vec<(String, String)> = Vec::new();
+    for pair in raw
+        .split([',', '&'])
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (key, value) = (key.trim(), value.trim());
+        let valid_chars = |s: &str| {
+            s.chars()
+                .all(|c| c.is_alphanumeric() || c == ' ' || "+-=._:/@".contains(c))
+        };
+        if key.is_empty() || key.chars().count() > 128 || !valid_chars(key) {
+            return Err(format!("INVALID_OBJECT_TAGS: bad tag key in '{}'", pair));
+        }
+        if value.chars().count() > 256 || !valid_chars(value) {
+            return Err(format!("INVALID_OBJECT_TAGS: bad tag value in '{}'", pair));
+        }
+        if key.to_ascii_lowercase().starts_with("aws:") {
+            return Err(format!(
+                "INVALID_OBJECT_TAGS: '{}' uses the reserved aws: prefix",
+                key
+            ));
+        }
+        if tags.iter().any(|(k, _)| k == key) {
+            return Err(format!("INVALID_OBJECT_TAGS: duplicate key '{}'", key));
+        }
+        tags.push((key.to_string(), value.to_string()));
+    }
+    if tags.len() > MAX_OBJECT_TAGS {
+        return Err(format!(
+            "INVALID_OBJECT_TAGS: at most {} tags per object",
+            MAX_OBJECT_TAGS
+        ));
+    }
+    Ok(tags)
+}
+
+/// Canonical form saved in `sync_config` and shown in the UI: `k=v,k2=v2`.
+pub fn format_object_tags(tags: &[(String, String)]) -> String {
+    tags.iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// `x-amz-tagging` header value: URL-encoded query string.
+pub fn tagging_header(tags: &[(String, String)]) -> String {
+    tags.iter()
+        .map(|(k, v)| format!("{}={}", url_encode(k), url_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn url_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Lifecycle rule for the deltas of one key prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeltaLifecycleRule {
+    pub id: String,
+    /// `<key_prefix>deltas/`.
+    pub prefix: String,
+    pub transition_after_days: i32,
+    pub storage_class: String,
+    pub abort_multipart_after_days: i32,
+}
+
+impl DeltaLifecycleRule {
+    /// Validated rule; `None` arguments take the defaults.
+    pub fn new(
+        key_prefix: &str,
+        transition_after_days: Option<i32>,
+        storage_class: Option<&str>,
+        abort_multipart_after_days: Option<i32>,
+    ) -> Result<Self, String> {
+        let storage_class = storage_class
+            .map(normalize_storage_class)
+            .transpose()?
+            .unwrap_or_else(|| DEFAULT_DELTA_TRANSITION_CLASS.to_string());
+        if storage_class == DEFAULT_STORAGE_CLASS {
+            return Err("INVALID_STORAGE_CLASS: deltas already start in STANDARD".to_string());
+        }
+        let transition_after_days = transition_after_days.unwrap_or(DEFAULT_DELTA_TRANSITION_DAYS);
+        let min_days = if storage_class.ends_with("_IA") {
+            MIN_IA_TRANSITION_DAYS
+        } else {
+            1
+        };
+        if transition_after_days < min_days {
+            return Err(format!(
+                "transition_after_days must be >= {} for {}",
+                min_days, storage_class
+            ));
+        }
+        let abort_multipart_after_days =
+            abort_multipart_after_days.unwrap_or(DEFAULT_ABORT_MULTIPART_DAYS);
+        if abort_multipart_after_days < 1 {
+            return Err("abort_multipart_after_days must be >= 1".to_string());
+        }
+        Ok(Self {
+            id: lifecycle_rule_id(key_prefix),
+            prefix: format!("{}deltas/", key_prefix),
+            transition_after_days,
+            storage_class,
+            abort_multipart_after_days,
+        })
+    }
+
+    /// The rule as `aws s3api put-bucket-lifecycle-configuration` JSON.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "Rules": [{
+                "ID": self.id,
+                "Status": "Enabled",
+                "Filter": { "Prefix": self.prefix },
+                "Transitions": [{
+                    "Days": self.transition_after_days,
+                    "StorageClass": self.storage_class,
+                }],
+                "AbortIncompleteMultipartUpload": {
+                    "DaysAfterInitiation": self.abort_multipart_after_days,
+                },
+            }]
+        })
+    }
+}
+
+/// `projex-deltas` at the bucket root, `projex-teams-alpha-deltas` under `teams/alpha/`.
+pub fn lifecycle_rule_id(key_prefix: &str) -> String {
+    let scope = key_prefix.trim_matches('/').replace('/', "-");
+    if scope.is_empty() {
+        "projex-deltas".to_string()
+    } else {
+        format!("projex-{}-deltas", scope)
+    }
+}
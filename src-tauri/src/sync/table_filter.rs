@@ -0,0 +1,263 @@
+//! Sync scope: per-table toggles (`sync_config` key `sync_table_disabled::<table>` = '1') and
+//! archived-project exclusion (`sync_exclude_archived_projects` = '1').
+//!
+//! 本机忽略某表时：
+//! - 本地修改：变更跟踪触发器的 WHEN 条件不满足，不写 `sync_metadata`，因此永不上传（重新启用后也不会补传）；
+//!   关闭之前已记录、尚未上传的变更在收集 delta 时丢弃（标记为已同步）。
+//! - 远端修改：`apply_delta` 丢弃该表的操作（计入 `ignored_table_ops`），游标照常前进，重新启用后不会补拉，
+//!   需要完整数据时用快照恢复。
+//! - 快照：创建时不含该表的行；恢复时保留本地行，不写入快照中的行（快照记录创建时的范围，
+//!   恢复按双方范围的并集处理，因此不会用不完整的快照清空其他设备的数据）。
+//!
+//! 排除已归档项目时：
+//! - 范围按项目划分：状态为 `ARCHIVED` 的项目，其明细（`PROJECT_DETAIL_TABLES`：参与、状态/规模历史、标签、
+//!   评论（含表情回应与编辑历史）、自定义字段值）只留在本机；项目行本身照常同步，归档 / 取消归档仍会传播到其他设备。
+//! - 判断以操作发生时本机项目的当前状态为准：收集时丢弃已归档项目的明细变更，应用时丢弃落在本机已归档项目上的
+//!   远端明细（计入 `archived_project_ops`）。取消归档后只同步之后的修改，归档期间的修改不补传。
+//! - 快照同样不含已归档项目的明细，恢复时保留这些项目的本地明细。
+
+use super::delta_sync::Operation;
+use super::snapshot::RESTORE_DIFF_SECTIONS;
+use crate::error::AppError;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Tables that can be toggled (all business tables tracked by sync triggers).
+pub const SYNCABLE_TABLES: &[&str] = &[
+    "persons",
+    "partners",
+    "projects",
+    "assignments",
+    "status_history",
+    "project_size_history",
+    "project_tags",
+    "project_comments",
+    "comment_reactions",
+    "comment_revisions",
+    "checklist_items",
+    "custom_field_defs",
+    "project_custom_values",
+];
+
+/// Tables whose rows belong to one project (`project_id`); the unit of archived-project exclusion.
+pub const PROJECT_DETAIL_TABLES: &[&str] = &[
+    "assignments",
+    "status_history",
+    "project_size_history",
+    "project_tags",
+    "project_comments",
+    "comment_reactions",
+    "comment_revisions",
+    "checklist_items",
+    "project_custom_values",
+];
+
+pub const EXCLUDE_ARCHIVED_PROJECTS_KEY: &str = "sync_exclude_archived_projects";
+
+const TABLE_DISABLED_KEY_PREFIX: &str = "sync_table_disabled::";
+
+pub fn table_disabled_key(table: &str) -> String {
+    format!("{}{}", TABLE_DISABLED_KEY_PREFIX, table)
+}
+
+/// Tables this device ignores, in `SYNCABLE_TABLES` order.
+pub fn load_ignored_tables(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT key FROM sync_config WHERE key LIKE ?1 AND TRIM(value) = '1'")
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let keys = stmt
+        .query_map(params![format!("{}%", TABLE_DISABLED_KEY_PREFIX)], |row| {
+            row.get::<_, String>(0)
+        })
+        .map_err(|e| AppError::Db(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Db(e.to_string()))?;
+
+    Ok(SYNCABLE_TABLES
+        .iter()
+        .filter(|table| keys.contains(&table_disabled_key(table)))
+        .map(|table| table.to_string())
+        .collect())
+}
+
+/// Replace the ignored set. Unknown table names are rejected before anything is written.
+pub fn set_ignored_tables(conn: &Connection, tables: &[String]) -> Result<(), AppError> {
+    if let Some(unknown) = tables.iter().find(|t| !SYNCABLE_TABLES.contains(&t.trim())) {
+        return Err(AppError::Validation(format!(
+            "Unknown sync table: {} (expected one of {})",
+            unknown,
+            SYNCABLE_TABLES.join(", ")
+        )));
+    }
+
+    for table in SYNCABLE_TABLES {
+        let ignored = tables.iter().any(|t| t.trim() == *table);
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+            params![table_disabled_key(table), if ignored { "1" } else { "0" }],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    }
+    Ok(())
+}
+
+pub fn load_exclude_archived_projects(conn: &Connection) -> Result<bool, AppError> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM sync_config WHERE key = ?1",
+            [EXCLUDE_ARCHIVED_PROJECTS_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.as_deref().map(str::trim) == Some("1"))
+}
+
+pub fn set_exclude_archived_projects(conn: &Connection, exclude: bool) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        params![
+            EXCLUDE_ARCHIVED_PROJECTS_KEY,
+            if exclude { "1" } else { "0" }
+        ],
+    )?;
+    Ok(())
+}
+
+/// What a device keeps out of sync. Snapshots carry the scope they were taken with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncScope {
+    /// In `SYNCABLE_TABLES` order.
+    #[serde(default)]
+    pub ignored_tables: Vec<String>,
+    #[serde(default)]
+    pub exclude_archived_projects: bool,
+}
+
+impl SyncScope {
+    pub fn load(conn: &Connection) -> Result<Self, AppError> {
+        Ok(Self {
+            ignored_tables: load_ignored_tables(conn)?,
+            exclude_archived_projects: load_exclude_archived_projects(conn)?,
+        })
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.ignored_tables.is_empty() && !self.exclude_archived_projects
+    }
+
+    /// Everything excluded by either scope.
+    pub fn union(&self, other: &SyncScope) -> SyncScope {
+        SyncScope {
+            ignored_tables: SYNCABLE_TABLES
+                .iter()
+                .filter(|t| self.ignores_table(t) || other.ignores_table(t))
+                .map(|t| t.to_string())
+                .collect(),
+            exclude_archived_projects: self.exclude_archived_projects
+                || other.exclude_archived_projects,
+        }
+    }
+
+    pub fn ignores_table(&self, table: &str) -> bool {
+        self.ignored_tables.iter().any(|t| t == table)
+    }
+
+    /// Detail operation of a project that is archived here (when archived projects are excluded).
+    pub fn excludes_archived_detail(
+        &self,
+        conn: &Connection,
+        op: &Operation,
+    ) -> Result<bool, AppError> {
+        if !self.exclude_archived_projects
+            || !PROJECT_DETAIL_TABLES.contains(&op.table_name.as_str())
+        {
+            return Ok(false);
+        }
+        let Some(project_id) = operation_project_id(conn, op)? else {
+            return Ok(false);
+        };
+        let status: Option<String> = conn
+            .query_row(
+                "SELECT current_status FROM projects WHERE id = ?1",
+                [&project_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(status.as_deref() == Some("ARCHIVED"))
+    }
+}
+
+/// Project of a detail operation: from the payload, the tag record id, or (deletes) the local row.
+fn operation_project_id(conn: &Connection, op: &Operation) -> Result<Option<String>, AppError> {
+    if op.table_name == "project_tags" {
+        return Ok(op
+            .record_id
+            .split_once(':')
+            .map(|(project_id, _)| project_id.to_string()));
+    }
+    if let Some(project_id) = op
+        .data
+        .as_ref()
+        .and_then(|d| d.get("project_id"))
+        .and_then(|v| v.as_str())
+    {
+        return Ok(Some(project_id.to_string()));
+    }
+    // Table names come from PROJECT_DETAIL_TABLES, never from the payload.
+    Ok(conn
+        .query_row(
+            &format!("SELECT project_id FROM {} WHERE id = ?1", op.table_name),
+            [&op.record_id],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Ids of the archived projects in an export JSON (`export_json_string` format).
+pub fn archived_project_ids(data: &serde_json::Value) -> HashSet<String> {
+    data["projects"]
+        .as_array()
+        .map(|projects| {
+            projects
+                .iter()
+                .filter(|p| p["currentStatus"].as_str() == Some("ARCHIVED"))
+                .filter_map(|p| p["id"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Drop from an export JSON the rows of `ignored_tables` and the detail rows (including tags)
+/// of `archived_projects`.
+pub fn strip_export_rows(
+    data: &mut serde_json::Value,
+    ignored_tables: &[String],
+    archived_projects: &HashSet<String>,
+) {
+    for (section, table) in RESTORE_DIFF_SECTIONS {
+        let Some(rows) = data.get_mut(*section).and_then(|v| v.as_array_mut()) else {
+            continue;
+        };
+        if ignored_tables.iter().any(|t| t == table) {
+            rows.clear();
+        } else if PROJECT_DETAIL_TABLES.contains(table) && !archived_projects.is_empty() {
+            rows.retain(|row| {
+                row["projectId"]
+                    .as_str()
+                    .map_or(true, |id| !archived_projects.contains(id))
+            });
+        }
+    }
+    let strip_all_tags = ignored_tables.iter().any(|t| t == "project_tags");
+    if let Some(projects) = data.get_mut("projects").and_then(|v| v.as_array_mut()) {
+        for project in projects {
+            let archived = project["id"]
+                .as_str()
+                .is_some_and(|id| archived_projects.contains(id));
+            if strip_all_tags || archived {
+                project["tags"] = serde_json::json!([]);
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Set-union merge for `project_tags`.
+//!
+//! 标签按集合合并（LWW-element-set，同时刻 add 优先）而不是逐行 LWW：
+//! - 远端 add：若本地 tombstone 的 `deleted_at` 晚于该标签的 `created_at`，说明本地在其之后移除过，跳过；
+//!   否则插入（已存在则保留本地行）。
+//! - 远端 remove：DELETE op 携带 `deleted_at`（0027 起）；本地行的 `created_at` 晚于它时说明本地之后重新添加过，
+//!   保留；否则删除，并把 tombstone 时间记为远端删除时间。
+//! - 旧客户端的 DELETE op 不带 `deleted_at`，退化为直接删除（与之前行为一致）。
+//!
+//! 时间比较依赖各设备时钟；同一标签在两端的 add / remove 通常相隔远大于时钟偏差。
+
+use crate::error::AppError;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+
+const TABLE: &str = "project_tags";
+
+/// Apply a remote tag add. Returns `false` when a later local removal wins.
+pub(crate) fn apply_remote_tag_add(
+    conn: &Connection,
+    project_id: &str,
+    tag: &str,
+    created_at: Option<&str>,
+) -> Result<bool, AppError> {
+    let record_id = format!("{}:{}", project_id, tag);
+    if let (Some(deleted_at), Some(added)) = (
+        load_tag_tombstone(conn, &record_id)?,
+        created_at.and_then(parse_timestamp),
+    ) {
+        if parse_timestamp(&deleted_at).is_some_and(|deleted| deleted > added) {
+            log::info!(
+                "Skip remote tag add {} (added {}, removed locally {})",
+                record_id,
+                created_at.unwrap_or_default(),
+                deleted_at
+            );
+            return Ok(false);
+        }
+    }
+
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT OR IGNORE INTO project_tags (project_id, tag, created_at) VALUES (?1, ?2, ?3)",
+        params![project_id, tag, created_at.unwrap_or(&now)],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(true)
+}
+
+/// Apply a remote tag removal. Returns `false` when a later local add wins.
+pub(crate) fn apply_remote_tag_remove(
+    conn: &Connection,
+    project_id: &str,
+    tag: &str,
+    deleted_at: Option<&str>,
+) -> Result<bool, AppError> {
+    let record_id = format!("{}:{}", project_id, tag);
+    let removed = deleted_at.and_then(parse_timestamp);
+
+    if let Some(removed) = removed {
+        let local_created: Option<String> = conn
+            .query_row(
+                "SELECT created_at FROM project_tags WHERE project_id = ?1 AND tag = ?2",
+                params![project_id, tag],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| AppError::Db(e.to_string()))?;
+        if let Some(created) = local_created.as_deref().and_then(parse_timestamp) {
+            if created >= removed {
+                log::info!(
+                    "Keep tag {} re-added locally after remote removal ({})",
+                    record_id,
+                    deleted_at.unwrap_or_default()
+                );
+                return Ok(false);
+            }
+        }
+    }
+
+    let existed = conn
+        .execute(
+            "DELETE FROM project_tags WHERE project_id = ?1 AND tag = ?2",
+            params![project_id, tag],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?
+        > 0;
+
+    // The delete trigger stamps "now"; keep the remote removal time instead so that
+    // adds made between the two stay newer. For tags that were already gone, keep the
+    // later of the two removals.
+    if let Some(deleted_at) = deleted_at.filter(|_| removed.is_some()) {
+        conn.execute(
+            "INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+             VALUES (?1, ?2, ?3, 0)
+             ON CONFLICT(table_name, record_id) DO UPDATE SET
+                 deleted_at = CASE WHEN ?4 THEN excluded.deleted_at
+                                   ELSE MAX(deleted_at, excluded.deleted_at) END",
+            params![TABLE, record_id, deleted_at, existed],
+        )
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    }
+    Ok(true)
+}
+
+fn load_tag_tombstone(conn: &Connection, record_id: &str) -> Result<Option<String>, AppError> {
+    conn.query_row(
+        "SELECT deleted_at FROM sync_tombstones WHERE table_name = ?1 AND record_id = ?2",
+        params![TABLE, record_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| AppError::Db(e.to_string()))
+}
+
+/// RFC3339 or SQLite `datetime('now')` (UTC) timestamps.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|t| t.and_utc())
+        })
+}
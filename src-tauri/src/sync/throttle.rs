@@ -0,0 +1,83 @@
+//! Bandwidth limiting for S3 transfers (token bucket)
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const MAX_UPLOAD_KBPS_KEY: &str = "max_upload_kbps";
+pub const MAX_DOWNLOAD_KBPS_KEY: &str = "max_download_kbps";
+/// Skip scheduled syncs while the frontend reports a metered connection.
+pub const PAUSE_ON_METERED_KEY: &str = "pause_on_metered";
+
+/// Kilobits per second to bytes per second (`0` = unlimited).
+pub fn kbps_to_bytes_per_sec(kbps: u64) -> Option<u64> {
+    (kbps > 0).then(|| (kbps.saturating_mul(1000) / 8).max(1))
+}
+
+/// Token bucket holding at most one second worth of bytes. A transfer larger than the
+/// bucket goes into debt and the next caller waits it off, so the long-run average never
+/// exceeds the rate.
+#[derive(Debug)]
+pub struct BandwidthLimiter {
+    bytes_per_sec: Option<u64>,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl BandwidthLimiter {
+    pub fn new(bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            bytes_per_sec,
+            state: Mutex::new(BucketState {
+                tokens: bytes_per_sec.unwrap_or(0) as f64,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(None)
+    }
+
+    pub fn bytes_per_sec(&self) -> Option<u64> {
+        self.bytes_per_sec
+    }
+
+    /// Wait until `bytes` may be sent or received.
+    pub async fn consume(&self, bytes: usize) {
+        let wait = self.reserve(bytes, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take `bytes` from the bucket at `now` and return how long the caller must wait.
+    pub fn reserve(&self, bytes: usize, now: Instant) -> Duration {
+        let Some(rate) = self.bytes_per_sec else {
+            return Duration::ZERO;
+        };
+        let rate = rate as f64;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let elapsed = now
+            .saturating_duration_since(state.refilled_at)
+            .as_secs_f64();
+        state.tokens = (state.tokens + elapsed * rate).min(rate);
+        state.refilled_at = state.refilled_at.max(now);
+        state.tokens -= bytes as f64;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / rate)
+        }
+    }
+}
+
+impl Default for BandwidthLimiter {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
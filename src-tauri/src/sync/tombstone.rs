@@ -0,0 +1,44 @@
+//! Delete tombstones (`sync_tombstones`).
+//!
+//! 删除（本地或远端）时由触发器记录 `(table, record_id, version)`；记录重新插入时触发器清除。
+//! 应用远端 upsert 时若本地行不存在且 `remote_version <= tombstone.version`，视为删除之前的旧数据，跳过，
+//! 避免乱序/迟到的 delta 让已删除的记录“复活”。
+//!
+//! `project_tags` 没有 `_version`，其 tombstone 按 `deleted_at` 比较（`version = 0`），见 `tag_set`。
+
+use crate::error::AppError;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Version at which `table:record_id` was deleted, if it is tombstoned.
+pub(crate) fn load_tombstone_version(
+    conn: &Connection,
+    table: &str,
+    record_id: &str,
+) -> Result<Option<i64>, AppError> {
+    conn.query_row(
+        "SELECT version FROM sync_tombstones WHERE table_name = ?1 AND record_id = ?2",
+        params![table, record_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| AppError::Db(e.to_string()))
+}
+
+/// Record (or raise) a tombstone for a remote delete; covers rows that never existed
+/// locally, where the delete trigger does not fire.
+pub(crate) fn record_tombstone(
+    conn: &Connection,
+    table: &str,
+    record_id: &str,
+    version: i64,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+         VALUES (?1, ?2, datetime('now'), ?3)
+         ON CONFLICT(table_name, record_id) DO UPDATE SET
+             version = MAX(version, excluded.version)",
+        params![table, record_id, version],
+    )
+    .map_err(|e| AppError::Db(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! Sync integrity check: compare local tables with the remote state rebuilt elsewhere.
+//!
+//! 设计要点：
+//! - 远端状态在内存临时库中重建：恢复最新快照，再按时间顺序应用桶中全部 delta（含本机上传的、
+//!   跳过已吊销设备的），与正常同步走同一套 apply 逻辑。
+//! - 按表比较行数与逐行哈希（主键 → 全部列值的 SHA-256），报告仅本地 / 仅远端 / 内容不同的行。
+//! - 本机忽略的表（排除已归档项目时还包括项目明细表）照常比较但标记为 `ignored`，不计入 `in_sync`；
+//!   尚未上传的本地变更与待审阅的 delta 会造成预期内的差异，报告中一并给出数量。
+
+use crate::error::AppError;
+use crate::sync::table_filter::SYNCABLE_TABLES;
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Row ids listed per table and kind of divergence.
+pub const MAX_SAMPLE_IDS: usize = 20;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TableVerifyResult {
+    pub table: String,
+    pub local_rows: usize,
+    pub remote_rows: usize,
+    pub only_local: usize,
+    pub only_remote: usize,
+    /// Rows present on both sides with different content.
+    pub differing: usize,
+    /// This device ignores the table (differences are expected).
+    pub ignored: bool,
+    /// Up to `MAX_SAMPLE_IDS` ids of each kind.
+    pub only_local_ids: Vec<String>,
+    pub only_remote_ids: Vec<String>,
+    pub differing_ids: Vec<String>,
+}
+
+impl TableVerifyResult {
+    pub fn is_diverged(&self) -> bool {
+        self.only_local + self.only_remote + self.differing > 0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncVerifyReport {
+    pub checked_at: String,
+    /// Snapshot the remote state was rebuilt from (`None`: deltas only).
+    pub snapshot_key: Option<String>,
+    pub deltas_applied: usize,
+    /// Deltas that could not be applied to the rebuilt state (corrupt, rejected).
+    pub deltas_failed: Vec<String>,
+    pub pending_local_changes: i64,
+    pub staged_deltas: i64,
+    /// No divergence in any table this device syncs.
+    pub in_sync: bool,
+    pub tables: Vec<TableVerifyResult>,
+}
+
+/// Tables compared by the check: every syncable table plus the device registry.
+pub fn verify_tables() -> Vec<&'static str> {
+    SYNCABLE_TABLES
+        .iter()
+        .copied()
+        .chain(std::iter::once("devices"))
+        .collect()
+}
+
+/// Primary key -> hash of all column values, for one table.
+pub fn table_row_hashes(
+    conn: &Connection,
+    table: &str,
+) -> Result<BTreeMap<String, String>, AppError> {
+    let key_expr = if table == "project_tags" {
+        "project_id || ':' || tag"
+    } else {
+        "id"
+    };
+    let mut stmt = conn
+        .prepare(&format!("SELECT {}, * FROM {}", key_expr, table))
+        .map_err(|e| AppError::Db(e.to_string()))?;
+    let columns: Vec<String> = stmt
+        .column_names()
+        .into_iter()
+        .skip(1)
+        .map(str::to_string)
+        .collect();
+    let mut rows = stmt.query([]).map_err(|e| AppError::Db(e.to_string()))?;
+
+    let mut hashes = BTreeMap::new();
+    while let Some(row) = rows.next().map_err(|e| AppError::Db(e.to_string()))? {
+        let key: String = row.get(0).map_err(|e| AppError::Db(e.to_string()))?;
+        let mut hasher = Sha256::new();
+        for (i, name) in columns.iter().enumerate() {
+            hasher.update(name.as_bytes());
+            hasher.update([0x1f]);
+            match row
+                .get_ref(i + 1)
+                .map_err(|e| AppError::Db(e.to_string()))?
+            {
+                ValueRef::Null => hasher.update(b"n"),
+                ValueRef::Integer(n) => hasher.update(format!("i{}", n)),
+                ValueRef::Real(f) => hasher.update(format!("r{}", f)),
+                ValueRef::Text(t) => {
+                    hasher.update(b"t");
+                    hasher.update(t);
+                }
+                ValueRef::Blob(b) => {
+                    hasher.update(b"b");
+                    hasher.update(b);
+                }
+            }
+            hasher.update([0x1e]);
+        }
+        hashes.insert(key, format!("{:x}", hasher.finalize()));
+    }
+    Ok(hashes)
+}
+
+pub fn compare_table(
+    table: &str,
+    local: &BTreeMap<String, String>,
+    remote: &BTreeMap<String, String>,
+    ignored: bool,
+) -> TableVerifyResult {
+    let mut result = TableVerifyResult {
+        table: table.to_string(),
+        local_rows: local.len(),
+        remote_rows: remote.len(),
+        ignored,
+        ..Default::default()
+    };
+    for (id, hash) in local {
+        match remote.get(id) {
+            None => push_sample(&mut result.only_local, &mut result.only_local_ids, id),
+            Some(other) if other != hash => {
+                push_sample(&mut result.differing, &mut result.differing_ids, id)
+            }
+            Some(_) => {}
+        }
+    }
+    for id in remote.keys().filter(|id| !local.contains_key(*id)) {
+        push_sample(&mut result.only_remote, &mut result.only_remote_ids, id);
+    }
+    result
+}
+
+fn push_sample(count: &mut usize, ids: &mut Vec<String>, id: &str) {
+    *count += 1;
+    if ids.len() < MAX_SAMPLE_IDS {
+        ids.push(id.to_string());
+    }
+}
+
+/// Compare every verified table between the local database and the rebuilt remote state.
+pub fn compare_databases(
+    local: &Connection,
+    remote: &Connection,
+    ignored_tables: &[String],
+) -> Result<Vec<TableVerifyResult>, AppError> {
+    verify_tables()
+        .into_iter()
+        .map(|table| {
+            Ok(compare_table(
+                table,
+                &table_row_hashes(local, table)?,
+                &table_row_hashes(remote, table)?,
+                ignored_tables.iter().any(|t| t == table),
+            ))
+        })
+        .collect()
+}
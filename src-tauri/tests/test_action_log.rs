@@ -0,0 +1,271 @@
+//! Undo/redo journal tests
+
+use app_lib::app::{
+    assignment_add_member, assignment_end_member, assignment_list_by_project, comment_create,
+    comment_delete, comment_list_by_project, partner_create, person_create, project_change_status,
+    project_create, project_get, project_update, redo_last, undo_last, ActionKind,
+    AssignmentAddReq, AssignmentEndReq, CommentCreateReq, PartnerCreateReq, PersonCreateReq,
+    ProjectChangeStatusReq, ProjectCreateReq, ProjectUpdateReq, ACTION_LOG_MAX_ENTRIES,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+struct Seed {
+    person_id: String,
+    project_id: String,
+}
+
+fn person(pool: &DbPool, name: &str) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn seed(pool: &DbPool) -> Seed {
+    let person_id = person(pool, "Owner");
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Alpha".to_string(),
+            description: Some("first".to_string()),
+            priority: Some(3),
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person_id.clone(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(vec!["a".to_string()]),
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+    Seed {
+        person_id,
+        project_id: project.id,
+    }
+}
+
+fn rename(pool: &DbPool, id: &str, name: &str, tags: Vec<&str>) {
+    project_update(
+        pool,
+        ProjectUpdateReq {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            description: None,
+            priority: None,
+            country_code: None,
+            owner_person_id: None,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(tags.into_iter().map(str::to_string).collect()),
+            parent_project_id: None,
+            partner_id: None,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap();
+}
+
+fn sorted_tags(pool: &DbPool, id: &str) -> Vec<String> {
+    let mut tags = project_get(pool, id).unwrap().tags;
+    tags.sort();
+    tags
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn undo_and_redo_project_update_with_tags() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+    assert!(undo_last(&pool).unwrap().is_none());
+
+    rename(&pool, &seed.project_id, "Beta", vec!["b", "c"]);
+    let undone = undo_last(&pool).unwrap().unwrap();
+    assert_eq!(undone.action, ActionKind::ProjectUpdate);
+    assert_eq!(undone.entity_id, seed.project_id);
+    assert!(undone.undone_at.is_some());
+
+    let project = project_get(&pool, &seed.project_id).unwrap();
+    assert_eq!(project.name, "Alpha");
+    assert_eq!(sorted_tags(&pool, &seed.project_id), vec!["a"]);
+    assert!(undo_last(&pool).unwrap().is_none());
+
+    let redone = redo_last(&pool).unwrap().unwrap();
+    assert_eq!(redone.undone_at, None);
+    assert_eq!(project_get(&pool, &seed.project_id).unwrap().name, "Beta");
+    assert_eq!(sorted_tags(&pool, &seed.project_id), vec!["b", "c"]);
+    assert!(redo_last(&pool).unwrap().is_none());
+
+    // Undo bumps the sync version like any other edit.
+    let conn = pool.0.lock().unwrap();
+    let version: i64 = conn
+        .query_row(
+            "SELECT _version FROM projects WHERE id = ?1",
+            [&seed.project_id],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert!(version >= 3);
+}
+
+#[test]
+fn undo_status_change_removes_history_entry() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+    project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: seed.project_id.clone(),
+            to_status: "PLANNED".to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        project_get(&pool, &seed.project_id)
+            .unwrap()
+            .status_history
+            .len(),
+        2
+    );
+
+    assert_eq!(
+        undo_last(&pool).unwrap().unwrap().action,
+        ActionKind::ProjectStatus
+    );
+    let project = project_get(&pool, &seed.project_id).unwrap();
+    assert_eq!(project.current_status, "BACKLOG");
+    assert_eq!(project.status_history.len(), 1);
+
+    redo_last(&pool).unwrap().unwrap();
+    let project = project_get(&pool, &seed.project_id).unwrap();
+    assert_eq!(project.current_status, "PLANNED");
+    assert_eq!(project.status_history.len(), 2);
+}
+
+#[test]
+fn undo_assignment_end_and_comment_delete() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+    let member = person(&pool, "Member");
+    assignment_add_member(
+        &pool,
+        AssignmentAddReq {
+            project_id: seed.project_id.clone(),
+            person_id: member.clone(),
+            role: None,
+            start_at: None,
+        },
+    )
+    .unwrap();
+    let comment = comment_create(
+        &pool,
+        CommentCreateReq {
+            project_id: seed.project_id.clone(),
+            person_id: Some(seed.person_id.clone()),
+            content: "keep me".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+
+    assignment_end_member(
+        &pool,
+        AssignmentEndReq {
+            project_id: seed.project_id.clone(),
+            person_id: member.clone(),
+            end_at: None,
+        },
+    )
+    .unwrap();
+    comment_delete(&pool, comment.id.clone()).unwrap();
+    assert!(comment_list_by_project(&pool, seed.project_id.clone())
+        .unwrap()
+        .is_empty());
+
+    // Stack order: the comment delete is undone first.
+    assert_eq!(
+        undo_last(&pool).unwrap().unwrap().action,
+        ActionKind::CommentDelete
+    );
+    let comments = comment_list_by_project(&pool, seed.project_id.clone()).unwrap();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].content, "keep me");
+
+    assert_eq!(
+        undo_last(&pool).unwrap().unwrap().action,
+        ActionKind::AssignmentEnd
+    );
+    let active = assignment_list_by_project(&pool, &seed.project_id)
+        .unwrap()
+        .into_iter()
+        .filter(|a| a.person_id == member && a.end_at.is_none())
+        .count();
+    assert_eq!(active, 1);
+}
+
+#[test]
+fn stale_undo_is_rejected_and_new_action_clears_redo() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+    rename(&pool, &seed.project_id, "Beta", vec!["a"]);
+
+    // Someone (or a sync) changes the project outside the journal.
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE projects SET name = 'Gamma' WHERE id = ?1",
+            [&seed.project_id],
+        )
+        .unwrap();
+    }
+    let err = undo_last(&pool).unwrap_err();
+    assert_eq!(err.code(), "CONFLICT");
+    assert!(err.to_string().contains("UNDO_STALE"));
+    assert_eq!(project_get(&pool, &seed.project_id).unwrap().name, "Gamma");
+
+    rename(&pool, &seed.project_id, "Delta", vec!["a"]);
+    undo_last(&pool).unwrap().unwrap();
+    assert_eq!(project_get(&pool, &seed.project_id).unwrap().name, "Gamma");
+    rename(&pool, &seed.project_id, "Epsilon", vec!["a"]);
+    assert!(redo_last(&pool).unwrap().is_none());
+}
+
+#[test]
+fn journal_is_capped() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+    for i in 0..(ACTION_LOG_MAX_ENTRIES + 5) {
+        rename(&pool, &seed.project_id, &format!("Name {}", i), vec!["a"]);
+    }
+    let conn = pool.0.lock().unwrap();
+    let count: i64 = conn
+        .query_row("SELECT COUNT(1) FROM action_log", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, ACTION_LOG_MAX_ENTRIES);
+}
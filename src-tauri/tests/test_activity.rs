@@ -0,0 +1,330 @@
+//! Activity feed tests: merged timeline, filters and pagination
+
+use app_lib::app::{
+    activity_list, assignment_add_member, assignment_end_member, comment_create, partner_create,
+    person_create, project_change_status, project_create, trash_delete, ActivityListReq,
+    AssignmentAddReq, AssignmentEndReq, CommentCreateReq, PartnerCreateReq, PersonCreateReq,
+    ProjectChangeStatusReq, ProjectCreateReq, TrashItemReq, TrashKind,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+struct Seed {
+    owner_id: String,
+    member_id: String,
+    alpha_id: String,
+    beta_id: String,
+}
+
+fn person(pool: &DbPool, name: &str) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn project(pool: &DbPool, name: &str, partner_id: &str, owner_id: &str) -> String {
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner_id.to_string(),
+            owner_person_id: owner_id.to_string(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: Some(owner_id.to_string()),
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+/// Alpha: created, moved to PLANNED, one comment, member joined and left. Beta: created.
+fn seed(pool: &DbPool) -> Seed {
+    let owner_id = person(pool, "Owner");
+    let member_id = person(pool, "Member");
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let alpha_id = project(pool, "Alpha", &partner.id, &owner_id);
+    let beta_id = project(pool, "Beta", &partner.id, &owner_id);
+
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: alpha_id.clone(),
+            to_status: "PLANNED".to_string(),
+            note: None,
+            changed_by_person_id: Some(owner_id.clone()),
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: alpha_id.clone(),
+            person_id: Some(member_id.clone()),
+            content: "Kickoff done".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    assignment_add_member(
+        pool,
+        AssignmentAddReq {
+            project_id: alpha_id.clone(),
+            person_id: member_id.clone(),
+            role: None,
+            start_at: None,
+        },
+    )
+    .unwrap();
+    assignment_end_member(
+        pool,
+        AssignmentEndReq {
+            project_id: alpha_id.clone(),
+            person_id: member_id.clone(),
+            end_at: None,
+        },
+    )
+    .unwrap();
+
+    Seed {
+        owner_id,
+        member_id,
+        alpha_id,
+        beta_id,
+    }
+}
+
+fn types_of(pool: &DbPool, req: ActivityListReq) -> Vec<String> {
+    let mut types: Vec<String> = activity_list(pool, req)
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|item| item.activity_type)
+        .collect();
+    types.sort();
+    types
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn feed_merges_all_sources_newest_first() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+
+    let page = activity_list(&pool, ActivityListReq::default()).unwrap();
+    // 2 projects created (+ owner assignments), status change, comment, member start / end.
+    assert_eq!(page.total, 8);
+    let ats: Vec<&str> = page.items.iter().map(|i| i.at.as_str()).collect();
+    let mut sorted = ats.clone();
+    sorted.sort_by(|a, b| b.cmp(a));
+    assert_eq!(ats, sorted);
+    assert!(page.items.iter().all(|i| i.at.ends_with('Z')));
+
+    let created = page
+        .items
+        .iter()
+        .find(|i| {
+            i.activity_type == "project_created" && i.project_id.as_deref() == Some(&seed.beta_id)
+        })
+        .unwrap();
+    assert_eq!(created.project_name.as_deref(), Some("Beta"));
+    assert_eq!(created.person_id.as_deref(), Some(seed.owner_id.as_str()));
+
+    let status = page
+        .items
+        .iter()
+        .find(|i| i.activity_type == "status_change")
+        .unwrap();
+    assert_eq!(status.detail.as_deref(), Some("BACKLOG → PLANNED"));
+    assert_eq!(status.person_name.as_deref(), Some("Owner"));
+}
+
+#[test]
+fn feed_filters_by_project_person_type_and_date() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+
+    let alpha = types_of(
+        &pool,
+        ActivityListReq {
+            project_id: Some(seed.alpha_id.clone()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        alpha,
+        vec![
+            "assignment_end",
+            "assignment_start",
+            "assignment_start",
+            "comment",
+            "project_created",
+            "status_change"
+        ]
+    );
+
+    let member = types_of(
+        &pool,
+        ActivityListReq {
+            person_id: Some(seed.member_id.clone()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        member,
+        vec!["assignment_end", "assignment_start", "comment"]
+    );
+
+    let comments = types_of(
+        &pool,
+        ActivityListReq {
+            types: Some(vec!["comment".to_string()]),
+            ..Default::default()
+        },
+    );
+    assert_eq!(comments, vec!["comment"]);
+
+    let future = activity_list(
+        &pool,
+        ActivityListReq {
+            from: Some("2999-01-01".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(future.total, 0);
+    let past = activity_list(
+        &pool,
+        ActivityListReq {
+            to: Some("2000-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(past.total, 0);
+
+    let err = activity_list(
+        &pool,
+        ActivityListReq {
+            types: Some(vec!["nope".to_string()]),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err = activity_list(
+        &pool,
+        ActivityListReq {
+            from: Some("last week".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
+
+#[test]
+fn feed_paginates_and_hides_trashed_projects() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+
+    let first = activity_list(
+        &pool,
+        ActivityListReq {
+            limit: Some(3),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let second = activity_list(
+        &pool,
+        ActivityListReq {
+            limit: Some(3),
+            offset: Some(3),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(first.items.len(), 3);
+    assert_eq!(second.items.len(), 3);
+    assert_eq!(first.total, second.total);
+    assert!(first.items.iter().all(|a| second
+        .items
+        .iter()
+        .all(|b| a.entity_id != b.entity_id || a.activity_type != b.activity_type)));
+
+    trash_delete(
+        &pool,
+        TrashItemReq {
+            kind: TrashKind::Project,
+            id: seed.beta_id.clone(),
+        },
+    )
+    .unwrap();
+    let page = activity_list(&pool, ActivityListReq::default()).unwrap();
+    assert_eq!(page.total, 6);
+    assert!(page
+        .items
+        .iter()
+        .all(|i| i.project_id.as_deref() != Some(seed.beta_id.as_str())));
+}
+
+#[test]
+fn edits_come_from_sync_change_log() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_config (key, value) VALUES ('sync_enabled', '1')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE persons SET role = 'PM' WHERE id = ?1",
+            [&seed.owner_id],
+        )
+        .unwrap();
+    }
+
+    let page = activity_list(
+        &pool,
+        ActivityListReq {
+            types: Some(vec!["edit".to_string()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(page.total, 1);
+    let edit = &page.items[0];
+    assert_eq!(edit.person_id.as_deref(), Some(seed.owner_id.as_str()));
+    assert_eq!(edit.person_name.as_deref(), Some("Owner"));
+    assert_eq!(edit.detail.as_deref(), Some("persons"));
+    assert_eq!(edit.project_id, None);
+}
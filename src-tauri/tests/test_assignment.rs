@@ -49,6 +49,7 @@ fn seed(pool: &app_lib::infra::DbPool) -> TestSeedIds {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -0,0 +1,237 @@
+//! Audit log tests: per-field records for project / person / partner updates
+
+use app_lib::app::{
+    audit_list, partner_create, partner_update, person_create, person_update, project_create,
+    project_update, AuditListReq, PartnerCreateReq, PartnerUpdateReq, PersonCreateReq,
+    PersonUpdateReq, ProjectCreateReq, ProjectUpdateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+struct Seed {
+    person_id: String,
+    partner_id: String,
+    project_id: String,
+}
+
+fn seed(pool: &DbPool) -> Seed {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Alpha".to_string(),
+            description: None,
+            priority: Some(3),
+            country_code: "CN".to_string(),
+            partner_id: partner.id.clone(),
+            owner_person_id: person.id.clone(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(vec!["b".to_string()]),
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+    Seed {
+        person_id: person.id,
+        partner_id: partner.id,
+        project_id: project.id,
+    }
+}
+
+fn project_req(id: &str) -> ProjectUpdateReq {
+    ProjectUpdateReq {
+        id: id.to_string(),
+        name: None,
+        description: None,
+        priority: None,
+        country_code: None,
+        owner_person_id: None,
+        product_name: None,
+        start_date: None,
+        due_date: None,
+        tags: None,
+        parent_project_id: None,
+        partner_id: None,
+        changed_by_person_id: None,
+    }
+}
+
+fn entity(entity_type: &str, entity_id: &str) -> AuditListReq {
+    AuditListReq {
+        entity_type: Some(entity_type.to_string()),
+        entity_id: Some(entity_id.to_string()),
+        ..Default::default()
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn project_update_records_changed_fields_only() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+
+    project_update(
+        &pool,
+        ProjectUpdateReq {
+            name: Some("Beta".to_string()),
+            priority: Some(3),
+            tags: Some(vec!["a".to_string(), "b".to_string()]),
+            changed_by_person_id: Some(seed.person_id.clone()),
+            ..project_req(&seed.project_id)
+        },
+    )
+    .unwrap();
+
+    let page = audit_list(&pool, entity("project", &seed.project_id)).unwrap();
+    assert_eq!(page.total, 2);
+    let mut fields: Vec<&str> = page.items.iter().map(|e| e.field.as_str()).collect();
+    fields.sort();
+    assert_eq!(fields, vec!["name", "tags"]);
+
+    let name = page.items.iter().find(|e| e.field == "name").unwrap();
+    assert_eq!(name.old_value.as_deref(), Some("Alpha"));
+    assert_eq!(name.new_value.as_deref(), Some("Beta"));
+    assert_eq!(
+        name.changed_by_person_id.as_deref(),
+        Some(seed.person_id.as_str())
+    );
+    assert_eq!(name.changed_by_name.as_deref(), Some("Owner"));
+    let tags = page.items.iter().find(|e| e.field == "tags").unwrap();
+    assert_eq!(tags.old_value.as_deref(), Some("b"));
+    assert_eq!(tags.new_value.as_deref(), Some("a, b"));
+
+    // A no-op update leaves no trail.
+    project_update(&pool, project_req(&seed.project_id)).unwrap();
+    assert_eq!(
+        audit_list(&pool, entity("project", &seed.project_id))
+            .unwrap()
+            .total,
+        2
+    );
+}
+
+#[test]
+fn person_and_partner_updates_are_audited() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+
+    person_update(
+        &pool,
+        PersonUpdateReq {
+            id: seed.person_id.clone(),
+            display_name: None,
+            email: Some("owner@example.com".to_string()),
+            role: Some("PM".to_string()),
+            note: None,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap();
+    partner_update(
+        &pool,
+        PartnerUpdateReq {
+            id: seed.partner_id.clone(),
+            name: Some("Acme Corp".to_string()),
+            note: None,
+            changed_by_person_id: Some(seed.person_id.clone()),
+        },
+    )
+    .unwrap();
+
+    let person = audit_list(&pool, entity("person", &seed.person_id)).unwrap();
+    let mut fields: Vec<(&str, Option<&str>)> = person
+        .items
+        .iter()
+        .map(|e| (e.field.as_str(), e.new_value.as_deref()))
+        .collect();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec![("email", Some("owner@example.com")), ("role", Some("PM"))]
+    );
+    assert!(person
+        .items
+        .iter()
+        .all(|e| e.changed_by_person_id.is_none()));
+
+    let partner = audit_list(&pool, entity("partner", &seed.partner_id)).unwrap();
+    assert_eq!(partner.total, 1);
+    assert_eq!(partner.items[0].old_value.as_deref(), Some("Acme"));
+    assert_eq!(partner.items[0].new_value.as_deref(), Some("Acme Corp"));
+}
+
+#[test]
+fn audit_list_filters_paginates_and_validates() {
+    let pool = init_test_db();
+    let seed = seed(&pool);
+    for name in ["One", "Two", "Three"] {
+        project_update(
+            &pool,
+            ProjectUpdateReq {
+                name: Some(name.to_string()),
+                ..project_req(&seed.project_id)
+            },
+        )
+        .unwrap();
+    }
+    partner_update(
+        &pool,
+        PartnerUpdateReq {
+            id: seed.partner_id.clone(),
+            name: Some("Other".to_string()),
+            note: None,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(audit_list(&pool, AuditListReq::default()).unwrap().total, 4);
+    let page = audit_list(
+        &pool,
+        AuditListReq {
+            entity_type: Some("project".to_string()),
+            limit: Some(2),
+            offset: Some(0),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(page.total, 3);
+    assert_eq!(page.items.len(), 2);
+    // Newest first.
+    assert_eq!(page.items[0].new_value.as_deref(), Some("Three"));
+    assert_eq!(page.items[1].new_value.as_deref(), Some("Two"));
+
+    let err = audit_list(
+        &pool,
+        AuditListReq {
+            entity_type: Some("comment".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
@@ -0,0 +1,199 @@
+//! Local DB backup (auto backup before destructive operations) tests
+
+use app_lib::app::{
+    backup_create, backup_get_config, backup_list, backup_restore, backup_run_scheduled,
+    backup_update_config, BackupConfigReq, BackupRestoreReq, BackupSchedule, BACKUP_MAX_COUNT_KEY,
+    BACKUP_REASON_BACKUP_RESTORE, BACKUP_REASON_IMPORT_JSON, BACKUP_REASON_SCHEDULED,
+};
+use app_lib::infra::db::{init_db, init_test_db};
+use app_lib::infra::DbPool;
+use std::fs;
+use std::path::PathBuf;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn file_db() -> (DbPool, PathBuf) {
+    let base = std::env::temp_dir().join(format!("projex-backup-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&base).unwrap();
+    let pool = init_db(&base.join("app.db")).unwrap();
+    (pool, base)
+}
+
+fn insert_person(pool: &DbPool, id: &str, name: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at)
+         VALUES (?1, ?2, '', '', '', 1, datetime('now'), datetime('now'))",
+        [id, name],
+    )
+    .unwrap();
+}
+
+fn person_names(pool: &DbPool) -> Vec<String> {
+    let conn = pool.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare("SELECT display_name FROM persons ORDER BY id")
+        .unwrap();
+    stmt.query_map([], |row: &rusqlite::Row<'_>| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn in_memory_db_skips_backup() {
+    let pool = init_test_db();
+    assert!(backup_create(&pool, BACKUP_REASON_IMPORT_JSON)
+        .unwrap()
+        .is_none());
+    assert!(backup_list(&pool).unwrap().is_empty());
+}
+
+#[test]
+fn restore_reverts_changes_and_keeps_undo_point() {
+    let (pool, base) = file_db();
+    insert_person(&pool, "p1", "Alice");
+
+    let backup = backup_create(&pool, BACKUP_REASON_IMPORT_JSON)
+        .unwrap()
+        .unwrap();
+    assert_eq!(backup.reason, BACKUP_REASON_IMPORT_JSON);
+    assert!(base.join("backups").join(&backup.file_name).is_file());
+
+    insert_person(&pool, "p2", "Bob");
+    let result = backup_restore(
+        &pool,
+        BackupRestoreReq {
+            file_name: backup.file_name.clone(),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(person_names(&pool), vec!["Alice".to_string()]);
+    assert_eq!(result.restored_from, backup.file_name);
+    let safety = result.safety_backup.unwrap();
+    let listed = backup_list(&pool).unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].file_name, safety);
+    assert_eq!(listed[0].reason, BACKUP_REASON_BACKUP_RESTORE);
+
+    // Undo the restore.
+    backup_restore(&pool, BackupRestoreReq { file_name: safety }).unwrap();
+    assert_eq!(
+        person_names(&pool),
+        vec!["Alice".to_string(), "Bob".to_string()]
+    );
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn rotation_keeps_configured_max_count() {
+    let (pool, base) = file_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, '2')",
+            [BACKUP_MAX_COUNT_KEY],
+        )
+        .unwrap();
+    }
+
+    let mut names = Vec::new();
+    for _ in 0..3 {
+        names.push(backup_create(&pool, "manual").unwrap().unwrap().file_name);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    let listed: Vec<String> = backup_list(&pool)
+        .unwrap()
+        .into_iter()
+        .map(|b| b.file_name)
+        .collect();
+    assert_eq!(listed, vec![names[2].clone(), names[1].clone()]);
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn restore_rejects_path_like_names() {
+    let (pool, base) = file_db();
+    let err = backup_restore(
+        &pool,
+        BackupRestoreReq {
+            file_name: "../app.db".into(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn scheduled_backup_runs_once_per_period() {
+    let (pool, base) = file_db();
+    let now = chrono::Utc::now();
+    // Off by default.
+    assert!(backup_run_scheduled(&pool, now).unwrap().is_none());
+
+    backup_update_config(
+        &pool,
+        BackupConfigReq {
+            schedule: Some(BackupSchedule::Daily),
+            target_dir: None,
+            max_count: None,
+        },
+    )
+    .unwrap();
+
+    let first = backup_run_scheduled(&pool, now).unwrap().unwrap();
+    assert_eq!(first.reason, BACKUP_REASON_SCHEDULED);
+    assert!(
+        backup_run_scheduled(&pool, now + chrono::Duration::hours(23))
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        backup_run_scheduled(&pool, now + chrono::Duration::hours(24))
+            .unwrap()
+            .is_some()
+    );
+
+    let config = backup_get_config(&pool).unwrap();
+    assert_eq!(config.schedule, BackupSchedule::Daily);
+    assert!(config.next_due_at.is_some());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn backups_go_to_user_chosen_dir() {
+    let (pool, base) = file_db();
+    let target = base.join("elsewhere");
+    let config = backup_update_config(
+        &pool,
+        BackupConfigReq {
+            schedule: None,
+            target_dir: Some(target.display().to_string()),
+            max_count: Some(3),
+        },
+    )
+    .unwrap();
+    assert_eq!(config.effective_dir, Some(target.display().to_string()));
+    assert_eq!(config.max_count, 3);
+
+    let backup = backup_create(&pool, "manual").unwrap().unwrap();
+    assert!(target.join(&backup.file_name).is_file());
+    assert_eq!(backup_list(&pool).unwrap().len(), 1);
+
+    let err = backup_update_config(
+        &pool,
+        BackupConfigReq {
+            schedule: None,
+            target_dir: Some("relative/dir".into()),
+            max_count: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let _ = fs::remove_dir_all(&base);
+}
@@ -0,0 +1,262 @@
+//! Project checklist integration tests
+
+use app_lib::app::{
+    checklist_add, checklist_delete, checklist_list, checklist_reorder, checklist_toggle,
+    partner_create, person_create, project_create, project_list, ChecklistAddReq,
+    ChecklistReorderReq, ChecklistToggleReq, PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+    ProjectListReq, CHECKLIST_TEXT_MAX_CHARS,
+};
+use app_lib::infra::db::init_test_db;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn seed_project(pool: &app_lib::infra::DbPool, name: &str) -> String {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner of {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner of {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: Some(3),
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn add(pool: &app_lib::infra::DbPool, project_id: &str, text: &str) -> String {
+    checklist_add(
+        pool,
+        ChecklistAddReq {
+            project_id: project_id.to_string(),
+            text: text.to_string(),
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn toggle(pool: &app_lib::infra::DbPool, id: &str, done: Option<bool>) -> bool {
+    checklist_toggle(
+        pool,
+        ChecklistToggleReq {
+            id: id.to_string(),
+            done,
+        },
+    )
+    .unwrap()
+    .done
+}
+
+fn listed_texts(pool: &app_lib::infra::DbPool, project_id: &str) -> Vec<String> {
+    checklist_list(pool, project_id.to_string())
+        .unwrap()
+        .into_iter()
+        .map(|item| item.text)
+        .collect()
+}
+
+fn item_version(pool: &app_lib::infra::DbPool, id: &str) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT _version FROM checklist_items WHERE id = ?1",
+        [id],
+        |r| r.get(0),
+    )
+    .unwrap()
+}
+
+// ══════════════════════════════════════════════════════════
+//  checklist_add / checklist_list
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn items_are_appended_in_order() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, "Checklist");
+
+    add(&pool, &project_id, "  Sign contract ");
+    add(&pool, &project_id, "Kick-off call");
+
+    let items = checklist_list(&pool, project_id.clone()).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].text, "Sign contract");
+    assert_eq!(items[0].sort_order, 1);
+    assert_eq!(items[1].sort_order, 2);
+    assert!(!items[0].done);
+    assert!(items[0].done_at.is_none());
+}
+
+#[test]
+fn add_validates_text_and_project() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, "Checklist");
+
+    for text in ["   ".to_string(), "x".repeat(CHECKLIST_TEXT_MAX_CHARS + 1)] {
+        let err = checklist_add(
+            &pool,
+            ChecklistAddReq {
+                project_id: project_id.clone(),
+                text,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "VALIDATION_ERROR");
+    }
+
+    let err = checklist_add(
+        &pool,
+        ChecklistAddReq {
+            project_id: "missing".to_string(),
+            text: "Anything".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+    let err = checklist_list(&pool, "missing".to_string()).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
+
+// ══════════════════════════════════════════════════════════
+//  checklist_toggle
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn toggle_flips_and_records_done_at() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, "Checklist");
+    let id = add(&pool, &project_id, "Sign contract");
+
+    assert!(toggle(&pool, &id, None));
+    let item = &checklist_list(&pool, project_id.clone()).unwrap()[0];
+    assert!(item.done_at.is_some());
+
+    // Already done: no write.
+    let version = item_version(&pool, &id);
+    assert!(toggle(&pool, &id, Some(true)));
+    assert_eq!(item_version(&pool, &id), version);
+
+    assert!(!toggle(&pool, &id, None));
+    let item = &checklist_list(&pool, project_id).unwrap()[0];
+    assert!(item.done_at.is_none());
+
+    let err = checklist_toggle(
+        &pool,
+        ChecklistToggleReq {
+            id: "missing".to_string(),
+            done: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
+
+// ══════════════════════════════════════════════════════════
+//  checklist_reorder / checklist_delete
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn reorder_puts_listed_items_first() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, "Checklist");
+    let other_project_id = seed_project(&pool, "Other");
+    add(&pool, &project_id, "a");
+    let b = add(&pool, &project_id, "b");
+    let c = add(&pool, &project_id, "c");
+    let foreign = add(&pool, &other_project_id, "x");
+
+    let items = checklist_reorder(
+        &pool,
+        ChecklistReorderReq {
+            project_id: project_id.clone(),
+            item_ids: vec![c],
+        },
+    )
+    .unwrap();
+    let order: Vec<(&str, i64)> = items
+        .iter()
+        .map(|item| (item.text.as_str(), item.sort_order))
+        .collect();
+    assert_eq!(order, vec![("c", 1), ("a", 2), ("b", 3)]);
+
+    for item_ids in [vec![b.clone(), b], vec![foreign]] {
+        let err = checklist_reorder(
+            &pool,
+            ChecklistReorderReq {
+                project_id: project_id.clone(),
+                item_ids,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "VALIDATION_ERROR");
+    }
+    assert_eq!(listed_texts(&pool, &project_id), vec!["c", "a", "b"]);
+
+    // New items still go to the end.
+    add(&pool, &project_id, "d");
+    assert_eq!(listed_texts(&pool, &project_id), vec!["c", "a", "b", "d"]);
+}
+
+#[test]
+fn delete_removes_the_item() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, "Checklist");
+    let a = add(&pool, &project_id, "a");
+    add(&pool, &project_id, "b");
+
+    checklist_delete(&pool, a.clone()).unwrap();
+    assert_eq!(listed_texts(&pool, &project_id), vec!["b"]);
+
+    let err = checklist_delete(&pool, a).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
+
+// ══════════════════════════════════════════════════════════
+//  project_list completion
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn project_list_reports_checklist_completion() {
+    let pool = init_test_db();
+    let with_items = seed_project(&pool, "With checklist");
+    let without_items = seed_project(&pool, "Without checklist");
+    let ids: Vec<String> = (0..4)
+        .map(|i| add(&pool, &with_items, &format!("step {}", i)))
+        .collect();
+    toggle(&pool, &ids[0], None);
+
+    let page = project_list(&pool, ProjectListReq::default()).unwrap();
+    let item = page.items.iter().find(|p| p.id == with_items).unwrap();
+    assert_eq!((item.checklist_done, item.checklist_total), (1, 4));
+    assert_eq!(item.checklist_ratio, Some(0.25));
+
+    let item = page.items.iter().find(|p| p.id == without_items).unwrap();
+    assert_eq!((item.checklist_done, item.checklist_total), (0, 0));
+    assert_eq!(item.checklist_ratio, None);
+}
@@ -1,9 +1,11 @@
 //! Comment CRUD integration tests
 
 use app_lib::app::{
-    comment_create, comment_delete, comment_list_by_project, comment_update, partner_create,
-    person_create, project_create, CommentCreateReq, CommentUpdateReq, PartnerCreateReq,
-    PersonCreateReq, ProjectCreateReq,
+    comment_create, comment_delete, comment_history, comment_list_by_project, comment_react,
+    comment_reorder_pins, comment_reply, comment_update, partner_create, person_create,
+    person_mentions, project_create, undo_last, CommentCreateReq, CommentReactReq,
+    CommentReorderPinsReq, CommentReplyReq, CommentUpdateReq, PartnerCreateReq, PersonCreateReq,
+    PersonMentionsReq, ProjectCreateReq,
 };
 use app_lib::infra::db::init_test_db;
 
@@ -51,6 +53,7 @@ fn seed(pool: &app_lib::infra::DbPool) -> TestSeedIds {
             due_date: None,
             tags: None,
             created_by_person_id: Some(person.id.clone()),
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -62,6 +65,130 @@ fn seed(pool: &app_lib::infra::DbPool) -> TestSeedIds {
     }
 }
 
+fn create_top_level(pool: &app_lib::infra::DbPool, project_id: &str, content: &str) -> String {
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: project_id.to_string(),
+            person_id: None,
+            content: content.to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn create_pinned(pool: &app_lib::infra::DbPool, project_id: &str, content: &str) -> String {
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: project_id.to_string(),
+            person_id: None,
+            content: content.to_string(),
+            is_pinned: Some(true),
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn set_content(pool: &app_lib::infra::DbPool, id: &str, content: &str) {
+    comment_update(
+        pool,
+        CommentUpdateReq {
+            id: id.to_string(),
+            content: Some(content.to_string()),
+            person_id: None,
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+}
+
+fn set_pinned(pool: &app_lib::infra::DbPool, id: &str, pinned: bool) {
+    comment_update(
+        pool,
+        CommentUpdateReq {
+            id: id.to_string(),
+            content: None,
+            person_id: None,
+            is_pinned: Some(pinned),
+        },
+    )
+    .unwrap();
+}
+
+fn listed_ids(pool: &app_lib::infra::DbPool, project_id: &str) -> Vec<String> {
+    comment_list_by_project(pool, project_id.to_string())
+        .unwrap()
+        .into_iter()
+        .map(|c| c.id)
+        .collect()
+}
+
+fn reply(pool: &app_lib::infra::DbPool, comment_id: &str, content: &str) -> String {
+    comment_reply(
+        pool,
+        CommentReplyReq {
+            comment_id: comment_id.to_string(),
+            person_id: None,
+            content: content.to_string(),
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn react(
+    pool: &app_lib::infra::DbPool,
+    comment_id: &str,
+    person_id: &str,
+    emoji: &str,
+) -> Result<app_lib::app::CommentDto, app_lib::error::AppError> {
+    comment_react(
+        pool,
+        CommentReactReq {
+            comment_id: comment_id.to_string(),
+            person_id: person_id.to_string(),
+            emoji: emoji.to_string(),
+        },
+    )
+}
+
+/// TipTap doc with one paragraph mentioning `person_ids` in order.
+fn mention_doc(text: &str, person_ids: &[&str]) -> String {
+    let mut nodes = vec![serde_json::json!({"type": "text", "text": text})];
+    for id in person_ids {
+        nodes.push(serde_json::json!({"type": "mention", "attrs": {"id": id, "label": id}}));
+    }
+    serde_json::json!({
+        "type": "doc",
+        "content": [{"type": "paragraph", "content": nodes}]
+    })
+    .to_string()
+}
+
+fn mentioned_comments(pool: &app_lib::infra::DbPool, person_id: &str) -> Vec<String> {
+    person_mentions(
+        pool,
+        PersonMentionsReq {
+            person_id: person_id.to_string(),
+            limit: None,
+        },
+    )
+    .unwrap()
+    .into_iter()
+    .map(|m| m.comment_id)
+    .collect()
+}
+
+fn count_rows(pool: &app_lib::infra::DbPool, table: &str) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0))
+        .unwrap()
+}
+
 // ══════════════════════════════════════════════════════════
 //  comment_create
 // ══════════════════════════════════════════════════════════
@@ -482,3 +609,300 @@ fn list_comments_filters_by_project() {
     assert_eq!(comments1[0].content, "project 1 comment");
     assert_eq!(comments2[0].content, "project 2 comment");
 }
+
+// ══════════════════════════════════════════════════════════
+//  threads & reactions
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn replies_are_nested_under_their_top_level_comment() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+
+    let root = create_top_level(&pool, &ids.project_id, "root");
+    let first = reply(&pool, &root, "first reply");
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    // Replying to a reply joins the same thread.
+    let second = reply(&pool, &first, "second reply");
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    create_top_level(&pool, &ids.project_id, "newer");
+
+    let comments = comment_list_by_project(&pool, ids.project_id.clone()).unwrap();
+    assert_eq!(comments.len(), 2);
+    assert_eq!(comments[0].content, "newer");
+    assert!(comments[0].replies.is_empty());
+
+    let thread = &comments[1];
+    assert_eq!(thread.id, root);
+    assert_eq!(thread.parent_comment_id, None);
+    let reply_ids: Vec<&str> = thread.replies.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(reply_ids, vec![first.as_str(), second.as_str()]);
+    assert!(thread
+        .replies
+        .iter()
+        .all(|r| r.parent_comment_id.as_deref() == Some(root.as_str())));
+}
+
+#[test]
+fn reply_to_unknown_comment_is_not_found() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let err = comment_reply(
+        &pool,
+        CommentReplyReq {
+            comment_id: "missing".to_string(),
+            person_id: None,
+            content: "hello".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
+
+#[test]
+fn reacting_twice_toggles_and_reactions_are_summarized() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let other = person_create(
+        &pool,
+        PersonCreateReq {
+            display_name: "Other User".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let root = create_top_level(&pool, &ids.project_id, "root");
+
+    react(&pool, &root, &ids.person_id, "👍").unwrap();
+    react(&pool, &root, &other.id, "🎉").unwrap();
+    let comment = react(&pool, &root, &other.id, "👍").unwrap();
+
+    assert_eq!(comment.reactions.len(), 2);
+    assert_eq!(comment.reactions[0].emoji, "👍");
+    assert_eq!(comment.reactions[0].count, 2);
+    assert_eq!(
+        comment.reactions[0].person_names,
+        vec!["Test User".to_string(), "Other User".to_string()]
+    );
+    assert_eq!(comment.reactions[1].emoji, "🎉");
+    assert_eq!(comment.reactions[1].count, 1);
+
+    // Same person, same emoji again: removed.
+    let comment = react(&pool, &root, &ids.person_id, "👍").unwrap();
+    assert_eq!(comment.reactions[0].count, 1);
+    assert_eq!(comment.reactions[0].person_ids, vec![other.id.clone()]);
+    assert_eq!(count_rows(&pool, "comment_reactions"), 2);
+}
+
+#[test]
+fn react_validates_emoji_comment_and_person() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let root = create_top_level(&pool, &ids.project_id, "root");
+
+    let err = react(&pool, &root, &ids.person_id, "  ").unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err = react(&pool, &root, &ids.person_id, "thumbs up").unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err = react(&pool, "missing", &ids.person_id, "👍").unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+    let err = react(&pool, &root, "missing", "👍").unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+    assert_eq!(count_rows(&pool, "comment_reactions"), 0);
+}
+
+#[test]
+fn deleting_a_thread_removes_replies_and_reactions_and_can_be_undone() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let root = create_top_level(&pool, &ids.project_id, "root");
+    let answer = reply(&pool, &root, "answer");
+    react(&pool, &root, &ids.person_id, "👍").unwrap();
+    react(&pool, &answer, &ids.person_id, "👀").unwrap();
+
+    comment_delete(&pool, root.clone()).unwrap();
+    assert!(comment_list_by_project(&pool, ids.project_id.clone())
+        .unwrap()
+        .is_empty());
+    assert_eq!(count_rows(&pool, "project_comments"), 0);
+    assert_eq!(count_rows(&pool, "comment_reactions"), 0);
+
+    undo_last(&pool).unwrap().unwrap();
+    let comments = comment_list_by_project(&pool, ids.project_id.clone()).unwrap();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].reactions[0].emoji, "👍");
+    assert_eq!(comments[0].replies.len(), 1);
+    assert_eq!(comments[0].replies[0].reactions[0].emoji, "👀");
+}
+
+// ══════════════════════════════════════════════════════════
+//  mentions
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn mentions_are_indexed_from_comment_content() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let other = person_create(
+        &pool,
+        PersonCreateReq {
+            display_name: "Other".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id;
+
+    let first = create_top_level(
+        &pool,
+        &ids.project_id,
+        &mention_doc("hi ", &[&ids.person_id, &ids.person_id, &other]),
+    );
+    let answer = reply(&pool, &first, &mention_doc("thanks ", &[&ids.person_id]));
+    create_top_level(&pool, &ids.project_id, "plain text @Test User");
+
+    assert_eq!(count_rows(&pool, "comment_mentions"), 3);
+    let mentions = person_mentions(
+        &pool,
+        PersonMentionsReq {
+            person_id: ids.person_id.clone(),
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(mentions.len(), 2);
+    assert_eq!(mentions[0].comment_id, answer);
+    assert_eq!(
+        mentions[0].parent_comment_id.as_deref(),
+        Some(first.as_str())
+    );
+    assert_eq!(mentions[1].comment_id, first);
+    assert!(mentions[1].project_name.starts_with("Test Project-"));
+    assert_eq!(mentioned_comments(&pool, &other), vec![first.clone()]);
+
+    // Editing away a mention drops it; deleting the thread drops the rest.
+    comment_update(
+        &pool,
+        CommentUpdateReq {
+            id: first.clone(),
+            content: Some(mention_doc("hi ", &[&ids.person_id])),
+            person_id: None,
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    assert!(mentioned_comments(&pool, &other).is_empty());
+    assert_eq!(mentioned_comments(&pool, &ids.person_id).len(), 2);
+
+    comment_delete(&pool, first).unwrap();
+    assert!(mentioned_comments(&pool, &ids.person_id).is_empty());
+    assert_eq!(count_rows(&pool, "comment_mentions"), 0);
+}
+
+#[test]
+fn person_mentions_unknown_person_is_not_found() {
+    let pool = init_test_db();
+    let err = person_mentions(
+        &pool,
+        PersonMentionsReq {
+            person_id: "missing".to_string(),
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
+
+// ══════════════════════════════════════════════════════════
+//  edit history & pin order
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn content_edits_are_kept_as_revisions() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let id = create_top_level(&pool, &ids.project_id, "v1");
+
+    set_content(&pool, &id, "v2");
+    set_content(&pool, &id, "v2");
+    set_pinned(&pool, &id, true);
+    set_content(&pool, &id, "v3");
+
+    let history = comment_history(&pool, id.clone()).unwrap();
+    let contents: Vec<&str> = history.iter().map(|r| r.content.as_str()).collect();
+    assert_eq!(contents, vec!["v2", "v1"]);
+    assert!(history[0].written_at >= history[1].replaced_at);
+    let comments = comment_list_by_project(&pool, ids.project_id.clone()).unwrap();
+    assert_eq!(comments[0].content, "v3");
+    assert_eq!(comments[0].revision_count, 2);
+
+    let err = comment_history(&pool, "missing".to_string()).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+
+    comment_delete(&pool, id).unwrap();
+    assert_eq!(count_rows(&pool, "comment_revisions"), 0);
+}
+
+#[test]
+fn pinned_comments_follow_pin_order() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let plain = create_top_level(&pool, &ids.project_id, "plain");
+    let a = create_pinned(&pool, &ids.project_id, "a");
+    let b = create_pinned(&pool, &ids.project_id, "b");
+    let c = create_pinned(&pool, &ids.project_id, "c");
+    // Newly pinned comments go first.
+    assert_eq!(
+        listed_ids(&pool, &ids.project_id),
+        vec![c.clone(), b.clone(), a.clone(), plain.clone()]
+    );
+
+    let comments = comment_reorder_pins(
+        &pool,
+        CommentReorderPinsReq {
+            project_id: ids.project_id.clone(),
+            comment_ids: vec![a.clone()],
+        },
+    )
+    .unwrap();
+    let order: Vec<&str> = comments.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(
+        order,
+        vec![a.as_str(), c.as_str(), b.as_str(), plain.as_str()]
+    );
+    assert_eq!(comments[0].pin_order, Some(1));
+    assert_eq!(comments[3].pin_order, None);
+
+    set_pinned(&pool, &b, false);
+    set_pinned(&pool, &plain, true);
+    assert_eq!(
+        listed_ids(&pool, &ids.project_id),
+        vec![plain.clone(), a.clone(), c.clone(), b.clone()]
+    );
+
+    for comment_ids in [vec![b.clone()], vec![a.clone(), a.clone()]] {
+        let err = comment_reorder_pins(
+            &pool,
+            CommentReorderPinsReq {
+                project_id: ids.project_id.clone(),
+                comment_ids,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "VALIDATION_ERROR");
+    }
+    let err = comment_reorder_pins(
+        &pool,
+        CommentReorderPinsReq {
+            project_id: "missing".to_string(),
+            comment_ids: Vec::new(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
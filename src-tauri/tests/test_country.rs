@@ -0,0 +1,174 @@
+//! Country catalog tests: lookup, flags and localized listing
+
+use app_lib::app::{
+    country_list, country_usage, partner_create, person_create, project_create, project_list,
+    project_update, CountryListReq, PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+    ProjectListReq, ProjectUpdateReq,
+};
+use app_lib::domain::{country_by_code, country_display_name, flag_emoji};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn project(pool: &DbPool, name: &str, country_code: &str) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: country_code.to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+/// Stored as-is, the way sync or a JSON import may leave it.
+fn store_raw_country(pool: &DbPool, project_id: &str, code: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "UPDATE projects SET country_code = ?1 WHERE id = ?2",
+        [code, project_id],
+    )
+    .unwrap();
+}
+
+fn filter(pool: &DbPool, codes: &[&str]) -> usize {
+    project_list(
+        pool,
+        ProjectListReq {
+            country_codes: Some(codes.iter().map(|c| c.to_string()).collect()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .items
+    .len()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn lookup_is_case_insensitive_and_falls_back_to_code() {
+    let cn = country_by_code(" cn ").unwrap();
+    assert_eq!(cn.code, "CN");
+    assert_eq!(cn.alpha3, "CHN");
+    assert_eq!(cn.name_zh, "中国");
+    assert!(country_by_code("XX").is_none());
+    assert!(country_by_code("").is_none());
+
+    assert_eq!(country_display_name("US"), "United States");
+    assert_eq!(country_display_name("XX"), "XX");
+    assert_eq!(flag_emoji("jp"), "🇯🇵");
+    assert_eq!(flag_emoji("J1"), "");
+}
+
+#[test]
+fn list_returns_every_code_with_localized_names() {
+    let en = country_list(CountryListReq::default());
+    assert_eq!(en.len(), 249);
+    assert!(en.windows(2).all(|w| w[0].code < w[1].code));
+    let de = en.iter().find(|c| c.code == "DE").unwrap();
+    assert_eq!(de.name, "Germany");
+    assert_eq!(de.flag, "🇩🇪");
+
+    let zh = country_list(CountryListReq {
+        locale: Some("zh-CN".to_string()),
+    });
+    let de = zh.iter().find(|c| c.code == "DE").unwrap();
+    assert_eq!(de.name, "德国");
+    assert_eq!(de.name_en, "Germany");
+}
+
+#[test]
+fn usage_lists_stored_codes_including_unknown_ones() {
+    let pool = init_test_db();
+    project(&pool, "Alpha", "CN");
+    let beta = project(&pool, "Beta", "JP");
+    let gamma = project(&pool, "Gamma", "US");
+    store_raw_country(&pool, &beta, " cn");
+    store_raw_country(&pool, &gamma, "xx");
+
+    let usage = country_usage(
+        &pool,
+        CountryListReq {
+            locale: Some("zh".to_string()),
+        },
+    )
+    .unwrap();
+    let summary: Vec<(&str, &str, bool, i64)> = usage
+        .iter()
+        .map(|c| (c.code.as_str(), c.name.as_str(), c.known, c.project_count))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![("CN", "中国", true, 2), ("XX", "XX", false, 1)]
+    );
+    assert_eq!(usage[0].flag, "🇨🇳");
+
+    // Filters match stored codes regardless of case and whitespace.
+    assert_eq!(filter(&pool, &["cn"]), 2);
+    assert_eq!(filter(&pool, &["XX"]), 1);
+    assert_eq!(filter(&pool, &["JP"]), 0);
+}
+
+#[test]
+fn update_keeps_an_unchanged_legacy_code() {
+    let pool = init_test_db();
+    let id = project(&pool, "Alpha", "CN");
+    store_raw_country(&pool, &id, "XX");
+    let update = |code: &str| {
+        project_update(
+            &pool,
+            ProjectUpdateReq {
+                id: id.clone(),
+                name: Some("Alpha 2".to_string()),
+                description: None,
+                priority: None,
+                country_code: Some(code.to_string()),
+                owner_person_id: None,
+                product_name: None,
+                start_date: None,
+                due_date: None,
+                tags: None,
+                partner_id: None,
+                parent_project_id: None,
+                changed_by_person_id: None,
+            },
+        )
+    };
+
+    let detail = update("XX").unwrap();
+    assert_eq!(detail.name, "Alpha 2");
+    assert_eq!(detail.country_code, "XX");
+    assert_eq!(update("ZZ").unwrap_err().code(), "VALIDATION_ERROR");
+    assert_eq!(update("de").unwrap().country_code, "DE");
+}
@@ -0,0 +1,120 @@
+//! CSV exports of projects, assignments, status history and comments
+
+use app_lib::app::{
+    comment_create, export_assignments_csv, export_comments_csv, export_projects_csv,
+    export_status_history_csv, partner_create, person_create, project_create, CommentCreateReq,
+    CsvExportReq, PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn seed(pool: &DbPool) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Alice".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme, Inc.".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let id = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Alpha".to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id.clone(),
+            product_name: None,
+            start_date: None,
+            due_date: Some("2026-04-30".to_string()),
+            tags: Some(vec!["web".to_string(), "api".to_string()]),
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id;
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: id.clone(),
+            person_id: Some(owner.id),
+            content: "Line one\n\"quoted\"".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    id
+}
+
+fn columns(names: &[&str]) -> CsvExportReq {
+    CsvExportReq {
+        columns: Some(names.iter().map(|c| c.to_string()).collect()),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn projects_csv_resolves_names_and_flattens_tags() {
+    let pool = init_test_db();
+    let id = seed(&pool);
+
+    let csv = export_projects_csv(&pool, CsvExportReq::default()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("id,name,status,priority,country_code,partner,owner,"));
+    assert!(lines[1].starts_with(&format!("{},Alpha,BACKLOG,", id)));
+    assert!(lines[1].contains(",\"Acme, Inc.\",Alice,"));
+    assert!(lines[1].contains(",api;web,"));
+}
+
+#[test]
+fn column_selection_picks_and_orders_columns() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let csv = export_projects_csv(&pool, columns(&["due_date", "name", "tags"])).unwrap();
+    assert_eq!(csv, "due_date,name,tags\n2026-04-30,Alpha,api;web\n");
+
+    let err = export_projects_csv(&pool, columns(&["name", "nope"])).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err = export_projects_csv(&pool, columns(&["name", "NAME"])).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
+
+#[test]
+fn assignments_history_and_comments_csv() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let csv = export_assignments_csv(
+        &pool,
+        columns(&["project_name", "person_name", "role", "end_at"]),
+    )
+    .unwrap();
+    assert_eq!(
+        csv,
+        "project_name,person_name,role,end_at\nAlpha,Alice,owner,\n"
+    );
+
+    let csv = export_status_history_csv(&pool, columns(&["project_name", "to_status"])).unwrap();
+    assert_eq!(csv, "project_name,to_status\nAlpha,BACKLOG\n");
+
+    let csv = export_comments_csv(&pool, columns(&["author", "content"])).unwrap();
+    assert_eq!(csv, "author,content\nAlice,\"Line one\n\"\"quoted\"\"\"\n");
+}
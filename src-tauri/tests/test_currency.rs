@@ -0,0 +1,416 @@
+//! Budget currency tests: rates, base currency, partner defaults and the budget report
+
+use app_lib::app::{
+    budget_report, currency_get_config, currency_rate_delete, currency_rate_list,
+    currency_rate_set, currency_rates_fetch_with, currency_update_config, export_json_string,
+    import_json_string, partner_create, partner_get, partner_merge, partner_set_default_currency,
+    person_create, project_create, project_get, project_set_budget, BudgetReportReq,
+    CurrencyConfigReq, CurrencyRateSetReq, PartnerCreateReq, PartnerMergeReq,
+    PartnerSetCurrencyReq, PersonCreateReq, ProjectCreateReq, ProjectSetBudgetReq,
+    RATE_SOURCE_FETCHED,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use std::cell::RefCell;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn partner(pool: &DbPool, name: &str, currency: Option<&str>) -> String {
+    let id = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: name.to_string(),
+            note: None,
+        },
+    )
+    .unwrap()
+    .id;
+    if currency.is_some() {
+        set_partner_currency(pool, &id, currency).unwrap();
+    }
+    id
+}
+
+fn set_partner_currency(
+    pool: &DbPool,
+    partner_id: &str,
+    currency: Option<&str>,
+) -> Result<Option<String>, String> {
+    partner_set_default_currency(
+        pool,
+        PartnerSetCurrencyReq {
+            partner_id: partner_id.to_string(),
+            default_currency: currency.map(str::to_string),
+            changed_by_person_id: None,
+        },
+    )
+    .map(|p| p.default_currency)
+    .map_err(|e| e.code().to_string())
+}
+
+fn project(pool: &DbPool, partner_id: &str, name: &str) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "DE".to_string(),
+            partner_id: partner_id.to_string(),
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn set_budget(pool: &DbPool, id: &str, amount: f64, currency: Option<&str>) {
+    project_set_budget(
+        pool,
+        ProjectSetBudgetReq {
+            project_id: id.to_string(),
+            estimated_hours: None,
+            budget_amount: Some(amount),
+            budget_currency: currency.map(str::to_string),
+        },
+    )
+    .unwrap();
+}
+
+fn set_rate(pool: &DbPool, currency: &str, rate: f64) -> Result<(), String> {
+    currency_rate_set(
+        pool,
+        CurrencyRateSetReq {
+            currency: currency.to_string(),
+            rate,
+        },
+    )
+    .map(|_| ())
+    .map_err(|e| e.code().to_string())
+}
+
+fn rates(pool: &DbPool) -> Vec<(String, f64)> {
+    currency_rate_list(pool)
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.currency, (r.rate * 1e6).round() / 1e6))
+        .collect()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn manual_rates_validate_codes_and_values() {
+    let pool = init_test_db();
+    assert_eq!(currency_get_config(&pool).unwrap().base_currency, "USD");
+
+    set_rate(&pool, " eur ", 1.1).unwrap();
+    set_rate(&pool, "GBP", 1.25).unwrap();
+    set_rate(&pool, "EUR", 1.08).unwrap();
+    assert_eq!(
+        rates(&pool),
+        vec![("EUR".to_string(), 1.08), ("GBP".to_string(), 1.25)]
+    );
+
+    for (code, rate) in [
+        ("EURO", 1.0),
+        ("E1R", 1.0),
+        ("", 1.0),
+        ("JPY", 0.0),
+        ("JPY", -1.0),
+        ("JPY", f64::NAN),
+        ("usd", 1.0),
+    ] {
+        assert_eq!(
+            set_rate(&pool, code, rate),
+            Err("VALIDATION_ERROR".to_string()),
+            "{} {}",
+            code,
+            rate
+        );
+    }
+
+    currency_rate_delete(&pool, "gbp").unwrap();
+    assert_eq!(
+        currency_rate_delete(&pool, "GBP").unwrap_err().code(),
+        "NOT_FOUND"
+    );
+    assert_eq!(rates(&pool), vec![("EUR".to_string(), 1.08)]);
+}
+
+#[test]
+fn budgets_take_project_or_partner_currency() {
+    let pool = init_test_db();
+    let acme = partner(&pool, "Acme", None);
+    let id = project(&pool, &acme, "Alpha");
+
+    assert_eq!(
+        set_partner_currency(&pool, &acme, Some("eur")),
+        Ok(Some("EUR".to_string()))
+    );
+    assert_eq!(
+        set_partner_currency(&pool, &acme, Some("EURO")),
+        Err("VALIDATION_ERROR".to_string())
+    );
+    assert_eq!(
+        set_partner_currency(&pool, "missing", Some("EUR")),
+        Err("NOT_FOUND".to_string())
+    );
+
+    set_budget(&pool, &id, 1_000.0, Some("gbp"));
+    assert_eq!(
+        project_get(&pool, &id).unwrap().budget_currency.as_deref(),
+        Some("GBP")
+    );
+    set_budget(&pool, &id, 1_000.0, None);
+    assert_eq!(project_get(&pool, &id).unwrap().budget_currency, None);
+    let err = project_set_budget(
+        &pool,
+        ProjectSetBudgetReq {
+            project_id: id.clone(),
+            estimated_hours: None,
+            budget_amount: Some(1.0),
+            budget_currency: Some("12".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+
+    // The report resolves the partner default.
+    let report = budget_report(&pool, BudgetReportReq::default()).unwrap();
+    assert_eq!(report.projects[0].currency, "EUR");
+}
+
+#[test]
+fn budget_report_converts_to_base_currency() {
+    let pool = init_test_db();
+    let acme = partner(&pool, "Acme", Some("EUR"));
+    let beta = partner(&pool, "Beta", None);
+    let a1 = project(&pool, &acme, "A1");
+    let a2 = project(&pool, &acme, "A2");
+    let b1 = project(&pool, &beta, "B1");
+    let b2 = project(&pool, &beta, "B2");
+    project(&pool, &beta, "No budget");
+    set_budget(&pool, &a1, 1_000.0, None);
+    set_budget(&pool, &a2, 500.0, Some("GBP"));
+    set_budget(&pool, &b1, 200.0, None);
+    set_budget(&pool, &b2, 30_000.0, Some("JPY"));
+    set_rate(&pool, "EUR", 1.1).unwrap();
+    set_rate(&pool, "GBP", 1.25).unwrap();
+
+    let report = budget_report(&pool, BudgetReportReq::default()).unwrap();
+    assert_eq!(report.base_currency, "USD");
+    let converted: Vec<(&str, &str, Option<f64>)> = report
+        .projects
+        .iter()
+        .map(|p| (p.project_name.as_str(), p.currency.as_str(), p.amount_base))
+        .collect();
+    assert_eq!(
+        converted,
+        vec![
+            ("A1", "EUR", Some(1_100.0)),
+            ("A2", "GBP", Some(625.0)),
+            ("B1", "USD", Some(200.0)),
+            ("B2", "JPY", None),
+        ]
+    );
+    assert_eq!(report.total_base, 1_925.0);
+    assert_eq!(report.missing_rates, vec!["JPY"]);
+
+    let partners: Vec<(&str, usize, f64, usize)> = report
+        .partners
+        .iter()
+        .map(|p| {
+            (
+                p.partner_name.as_str(),
+                p.project_count,
+                p.total_base,
+                p.unconverted_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        partners,
+        vec![("Acme", 2, 1_725.0, 0), ("Beta", 2, 200.0, 1)]
+    );
+
+    let currencies: Vec<(&str, f64, Option<f64>)> = report
+        .currencies
+        .iter()
+        .map(|c| (c.currency.as_str(), c.total, c.total_base))
+        .collect();
+    assert_eq!(
+        currencies,
+        vec![
+            ("EUR", 1_000.0, Some(1_100.0)),
+            ("GBP", 500.0, Some(625.0)),
+            ("JPY", 30_000.0, None),
+            ("USD", 200.0, Some(200.0)),
+        ]
+    );
+}
+
+#[test]
+fn switching_base_currency_converts_rates() {
+    let pool = init_test_db();
+    set_rate(&pool, "EUR", 1.25).unwrap();
+    set_rate(&pool, "GBP", 1.5).unwrap();
+
+    let err = currency_update_config(
+        &pool,
+        CurrencyConfigReq {
+            base_currency: Some("CHF".to_string()),
+            rates_url: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+
+    let config = currency_update_config(
+        &pool,
+        CurrencyConfigReq {
+            base_currency: Some("eur".to_string()),
+            rates_url: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(config.base_currency, "EUR");
+    assert_eq!(
+        rates(&pool),
+        vec![("GBP".to_string(), 1.2), ("USD".to_string(), 0.8)]
+    );
+
+    // A 100 USD budget is now 80 EUR.
+    let acme = partner(&pool, "Acme", Some("USD"));
+    let id = project(&pool, &acme, "Alpha");
+    set_budget(&pool, &id, 100.0, None);
+    let report = budget_report(&pool, BudgetReportReq::default()).unwrap();
+    assert_eq!(report.total_base, 80.0);
+}
+
+#[test]
+fn fetch_stores_rates_from_the_response() {
+    let pool = init_test_db();
+    set_rate(&pool, "CHF", 1.1).unwrap();
+    let requested = RefCell::new(Vec::new());
+    let result = currency_rates_fetch_with(&pool, &|url| {
+        requested.borrow_mut().push(url.to_string());
+        Ok(r#"{"base":"USD","date":"2026-03-02","rates":{"EUR":0.8,"GBP":0.5,"USD":1,"BAD1":2,"JPY":"x"}}"#.to_string())
+    })
+    .unwrap();
+    assert_eq!(
+        requested.borrow().as_slice(),
+        ["https://api.frankfurter.app/latest?from=USD"]
+    );
+    assert_eq!(result.base_currency, "USD");
+    assert_eq!(result.updated, 2);
+
+    let list = currency_rate_list(&pool).unwrap();
+    assert_eq!(list.len(), 3);
+    assert_eq!(list[1].currency, "EUR");
+    assert_eq!(list[1].rate, 1.25);
+    assert_eq!(list[1].source, RATE_SOURCE_FETCHED);
+    assert_eq!(list[2].rate, 2.0);
+
+    // Configured URL, wrong base and network errors.
+    currency_update_config(
+        &pool,
+        CurrencyConfigReq {
+            base_currency: None,
+            rates_url: Some("https://rates.example.com/{base}.json".to_string()),
+        },
+    )
+    .unwrap();
+    let err = currency_rates_fetch_with(&pool, &|url| {
+        assert_eq!(url, "https://rates.example.com/USD.json");
+        Ok(r#"{"base":"EUR","rates":{"USD":1.25}}"#.to_string())
+    })
+    .unwrap_err();
+    assert_eq!(err.code(), "REPORT_ERROR");
+    let err = currency_rates_fetch_with(&pool, &|_| Err("HTTP 503".to_string())).unwrap_err();
+    assert_eq!(err.code(), "REPORT_ERROR");
+    assert_eq!(currency_rate_list(&pool).unwrap().len(), 3);
+
+    let err = currency_update_config(
+        &pool,
+        CurrencyConfigReq {
+            base_currency: None,
+            rates_url: Some("ftp://rates".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
+
+#[test]
+fn merge_keeps_the_source_currency_of_moved_budgets() {
+    let pool = init_test_db();
+    let target = partner(&pool, "Target", None);
+    let source = partner(&pool, "Source", Some("EUR"));
+    let id = project(&pool, &source, "Alpha");
+    set_budget(&pool, &id, 100.0, None);
+
+    partner_merge(
+        &pool,
+        PartnerMergeReq {
+            target_id: target.clone(),
+            source_id: source,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        project_get(&pool, &id).unwrap().budget_currency.as_deref(),
+        Some("EUR")
+    );
+    assert_eq!(
+        partner_get(&pool, &target)
+            .unwrap()
+            .default_currency
+            .as_deref(),
+        Some("EUR")
+    );
+}
+
+#[test]
+fn currencies_survive_json_round_trip() {
+    let pool = init_test_db();
+    let acme = partner(&pool, "Acme", Some("EUR"));
+    let id = project(&pool, &acme, "Alpha");
+    set_budget(&pool, &id, 100.0, Some("GBP"));
+
+    let json = export_json_string(&pool, None).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["partners"][0]["defaultCurrency"], "EUR");
+    assert_eq!(value["projects"][0]["budgetCurrency"], "GBP");
+
+    let other = init_test_db();
+    import_json_string(&other, &json).unwrap();
+    assert_eq!(
+        partner_get(&other, &acme)
+            .unwrap()
+            .default_currency
+            .as_deref(),
+        Some("EUR")
+    );
+    assert_eq!(
+        project_get(&other, &id).unwrap().budget_currency.as_deref(),
+        Some("GBP")
+    );
+}
@@ -0,0 +1,239 @@
+//! Custom field tests: definitions, typed values, project detail and list filters
+
+use app_lib::app::{
+    custom_field_create, custom_field_delete, custom_field_list, custom_field_update,
+    partner_create, person_create, project_create, project_get, project_list,
+    project_set_custom_value, CustomFieldDefCreateReq, CustomFieldDefUpdateReq, CustomFieldFilter,
+    CustomFieldType, PartnerCreateReq, PersonCreateReq, ProjectCreateReq, ProjectCustomValueSetReq,
+    ProjectDetailDto, ProjectListReq,
+};
+use app_lib::error::AppError;
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn create_project(pool: &DbPool, name: &str) -> ProjectDetailDto {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+}
+
+fn define(
+    pool: &DbPool,
+    name: &str,
+    field_type: CustomFieldType,
+    options: Option<Vec<&str>>,
+) -> Result<String, AppError> {
+    custom_field_create(
+        pool,
+        CustomFieldDefCreateReq {
+            name: name.to_string(),
+            field_type,
+            options: options.map(|o| o.into_iter().map(str::to_string).collect()),
+            sort_order: None,
+        },
+    )
+    .map(|def| def.id)
+}
+
+fn set(
+    pool: &DbPool,
+    project_id: &str,
+    field_id: &str,
+    value: Option<&str>,
+) -> Result<(), AppError> {
+    project_set_custom_value(
+        pool,
+        ProjectCustomValueSetReq {
+            project_id: project_id.to_string(),
+            field_id: field_id.to_string(),
+            value: value.map(str::to_string),
+        },
+    )
+    .map(|_| ())
+}
+
+fn list_ids(pool: &DbPool, filters: Vec<(&str, &str)>) -> Vec<String> {
+    let page = project_list(
+        pool,
+        ProjectListReq {
+            custom_fields: Some(
+                filters
+                    .into_iter()
+                    .map(|(field_id, value)| CustomFieldFilter {
+                        field_id: field_id.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    page.items.into_iter().map(|p| p.id).collect()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn define_fields_and_validate_values() {
+    let pool = init_test_db();
+    let project = create_project(&pool, "Alpha");
+
+    let budget = define(&pool, "Budget", CustomFieldType::Number, None).unwrap();
+    let launch = define(&pool, "Launch", CustomFieldType::Date, None).unwrap();
+    let tier = define(
+        &pool,
+        "Tier",
+        CustomFieldType::Select,
+        Some(vec![" Gold ", "Silver", "Gold", ""]),
+    )
+    .unwrap();
+
+    // Names are unique case-insensitively; select needs options.
+    assert!(matches!(
+        define(&pool, "budget", CustomFieldType::Text, None),
+        Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+        define(&pool, "Empty", CustomFieldType::Select, Some(vec![" "])),
+        Err(AppError::Validation(_))
+    ));
+
+    let defs = custom_field_list(&pool).unwrap();
+    assert_eq!(defs.len(), 3);
+    assert_eq!(defs[2].options, vec!["Gold", "Silver"]);
+
+    // Values are normalized per type.
+    set(&pool, &project.id, &budget, Some(" 1200.0 ")).unwrap();
+    set(&pool, &project.id, &launch, Some("2026-03-01")).unwrap();
+    set(&pool, &project.id, &tier, Some("Gold")).unwrap();
+    assert!(matches!(
+        set(&pool, &project.id, &budget, Some("lots")),
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        set(&pool, &project.id, &launch, Some("2026-02-30")),
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        set(&pool, &project.id, &tier, Some("Bronze")),
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        set(&pool, "missing", &tier, Some("Gold")),
+        Err(AppError::NotFound(_))
+    ));
+
+    // The type is fixed; options can only be edited on select fields.
+    assert!(custom_field_update(
+        &pool,
+        CustomFieldDefUpdateReq {
+            id: budget.clone(),
+            name: None,
+            options: Some(vec!["1".into()]),
+            sort_order: None,
+        },
+    )
+    .is_err());
+
+    let detail = project_get(&pool, &project.id).unwrap();
+    let values: Vec<(&str, Option<&str>)> = detail
+        .custom_fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.value.as_deref()))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            ("Budget", Some("1200")),
+            ("Launch", Some("2026-03-01")),
+            ("Tier", Some("Gold")),
+        ]
+    );
+}
+
+#[test]
+fn clearing_a_value_leaves_the_field_unset() {
+    let pool = init_test_db();
+    let project = create_project(&pool, "Alpha");
+    let note = define(&pool, "Note", CustomFieldType::Text, None).unwrap();
+
+    set(&pool, &project.id, &note, Some("first")).unwrap();
+    set(&pool, &project.id, &note, Some("second")).unwrap();
+    let detail = project_get(&pool, &project.id).unwrap();
+    assert_eq!(detail.custom_fields[0].value.as_deref(), Some("second"));
+
+    set(&pool, &project.id, &note, Some("  ")).unwrap();
+    let detail = project_get(&pool, &project.id).unwrap();
+    assert_eq!(detail.custom_fields.len(), 1);
+    assert_eq!(detail.custom_fields[0].value, None);
+}
+
+#[test]
+fn list_filters_by_custom_values_and_delete_drops_values() {
+    let pool = init_test_db();
+    let alpha = create_project(&pool, "Alpha");
+    let beta = create_project(&pool, "Beta");
+    let region = define(&pool, "Region", CustomFieldType::Text, None).unwrap();
+    let budget = define(&pool, "Budget", CustomFieldType::Number, None).unwrap();
+
+    set(&pool, &alpha.id, &region, Some("EMEA")).unwrap();
+    set(&pool, &alpha.id, &budget, Some("10")).unwrap();
+    set(&pool, &beta.id, &region, Some("APAC")).unwrap();
+    set(&pool, &beta.id, &budget, Some("10")).unwrap();
+
+    // Text matches case-insensitively, numbers by normalized value, filters AND together.
+    assert_eq!(
+        list_ids(&pool, vec![(&region, "emea")]),
+        vec![alpha.id.clone()]
+    );
+    assert_eq!(list_ids(&pool, vec![(&budget, "10.0")]).len(), 2);
+    assert_eq!(
+        list_ids(&pool, vec![(&region, "APAC"), (&budget, "10")]),
+        vec![beta.id.clone()]
+    );
+
+    custom_field_delete(&pool, &region).unwrap();
+    let detail = project_get(&pool, &alpha.id).unwrap();
+    assert_eq!(detail.custom_fields.len(), 1);
+    assert_eq!(detail.custom_fields[0].name, "Budget");
+    assert!(matches!(
+        custom_field_delete(&pool, &region),
+        Err(AppError::NotFound(_))
+    ));
+}
@@ -0,0 +1,185 @@
+//! Custom data dir location + guided move tests
+
+use app_lib::app::{data_dir_info, data_dir_move, DataDirContext, DataDirMoveReq};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::layout::{
+    cleanup_previous_data_dir, location_file_for, read_data_dir_location, DataDirLocation,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn temp_base() -> PathBuf {
+    let base = std::env::temp_dir().join(format!("projex-datadir-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&base).unwrap();
+    base
+}
+
+/// `<base>/profiles/default` with a log file, plus its pointer file path.
+fn seed_profile(base: &Path) -> (PathBuf, PathBuf) {
+    let current = base.join("profiles").join("default");
+    fs::create_dir_all(current.join("logs")).unwrap();
+    fs::write(current.join("logs").join("rust-default.log"), b"log line").unwrap();
+    let location_file = location_file_for(&current);
+    (current, location_file)
+}
+
+fn move_req(target: &Path) -> DataDirMoveReq {
+    DataDirMoveReq {
+        target_dir: Some(target.display().to_string()),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn location_file_sits_next_to_profile_dir() {
+    let file = location_file_for(Path::new("/data/profiles/work"));
+    assert_eq!(file, PathBuf::from("/data/profiles/work.location.json"));
+}
+
+#[test]
+fn missing_location_file_means_default_dir() {
+    let base = temp_base();
+    let location = read_data_dir_location(&base.join("none.location.json")).unwrap();
+    assert_eq!(location, DataDirLocation::default());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn move_copies_verifies_and_points_next_start_at_target() {
+    let base = temp_base();
+    let (current, location_file) = seed_profile(&base);
+    let target = base.join("secure").join("projex");
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at)
+             VALUES ('p1', 'Alice', '', '', '', 1, datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+    }
+    let ctx = DataDirContext {
+        current_dir: &current,
+        default_dir: &current,
+        location_file: &location_file,
+    };
+
+    let result = data_dir_move(&pool, &ctx, move_req(&target)).unwrap();
+    assert!(result.requires_restart);
+    assert!(result.copied_files.contains(&"app.db".to_string()));
+    assert!(result
+        .copied_files
+        .contains(&"logs/rust-default.log".to_string()));
+    assert!(target.join("logs").join("rust-default.log").exists());
+
+    let copy = rusqlite::Connection::open(target.join("app.db")).unwrap();
+    let persons: i64 = copy
+        .query_row("SELECT COUNT(*) FROM persons", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(persons, 1);
+
+    let location = read_data_dir_location(&location_file).unwrap();
+    assert_eq!(location.data_dir, Some(target.clone()));
+    assert_eq!(location.pending_cleanup, Some(current.clone()));
+
+    let info = data_dir_info(&ctx).unwrap();
+    assert!(info.is_custom);
+    assert_eq!(info.configured_dir, target.display().to_string());
+    // Still running from the old dir until restart.
+    assert_eq!(info.current_dir, current.display().to_string());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn move_rejects_relative_and_non_empty_targets() {
+    let base = temp_base();
+    let (current, location_file) = seed_profile(&base);
+    let pool = init_test_db();
+    let ctx = DataDirContext {
+        current_dir: &current,
+        default_dir: &current,
+        location_file: &location_file,
+    };
+
+    let err = data_dir_move(&pool, &ctx, move_req(Path::new("relative/dir"))).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+
+    let occupied = base.join("occupied");
+    fs::create_dir_all(&occupied).unwrap();
+    fs::write(occupied.join("keep.txt"), b"user file").unwrap();
+    let err = data_dir_move(&pool, &ctx, move_req(&occupied)).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert!(err.to_string().contains("DATA_DIR_NOT_EMPTY"));
+    assert!(occupied.join("keep.txt").exists());
+
+    let err = data_dir_move(&pool, &ctx, move_req(&current.join("nested"))).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert!(!location_file.exists());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn cleanup_removes_only_known_entries() {
+    let base = temp_base();
+    let old = base.join("old");
+    fs::create_dir_all(old.join("logs")).unwrap();
+    fs::write(old.join("app.db"), b"db").unwrap();
+    fs::write(old.join("app.lock"), b"").unwrap();
+    fs::write(old.join("logs").join("rust-default.log"), b"log").unwrap();
+
+    assert!(cleanup_previous_data_dir(&old).unwrap());
+    assert!(!old.exists());
+
+    fs::create_dir_all(&old).unwrap();
+    fs::write(old.join("app.db"), b"db").unwrap();
+    fs::write(old.join("notes.txt"), b"user file").unwrap();
+    assert!(cleanup_previous_data_dir(&old).unwrap());
+    assert!(!old.join("app.db").exists());
+    assert!(old.join("notes.txt").exists());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn move_copies_default_backups_and_freezes_the_old_database() {
+    let base = temp_base();
+    let (current, location_file) = seed_profile(&base);
+    fs::create_dir_all(current.join("backups")).unwrap();
+    fs::write(
+        current
+            .join("backups")
+            .join("backup-20260101T000000000Z-manual.db.gz"),
+        b"backup",
+    )
+    .unwrap();
+    let target = base.join("other-drive");
+    let pool = init_test_db();
+    let ctx = DataDirContext {
+        current_dir: &current,
+        default_dir: &current,
+        location_file: &location_file,
+    };
+
+    let result = data_dir_move(&pool, &ctx, move_req(&target)).unwrap();
+    assert!(result
+        .copied_files
+        .contains(&"backups/backup-20260101T000000000Z-manual.db.gz".to_string()));
+    assert!(target
+        .join("backups")
+        .join("backup-20260101T000000000Z-manual.db.gz")
+        .exists());
+
+    // Edits after the copy would be lost with the old dir: it is read-only now.
+    let conn = pool.0.lock().unwrap();
+    assert!(conn
+        .execute(
+            "INSERT INTO partners (id, name, created_at, updated_at) VALUES ('pt1', 'Acme', datetime('now'), datetime('now'))",
+            [],
+        )
+        .is_err());
+    drop(conn);
+    let _ = fs::remove_dir_all(&base);
+}
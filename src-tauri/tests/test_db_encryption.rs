@@ -0,0 +1,117 @@
+//! Database encryption tests: file detection, status and passphrase validation
+
+use app_lib::app::{db_encryption_status, db_set_passphrase, DbSetPassphraseReq};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::encryption::{encryption_supported, is_encrypted_file};
+use app_lib::infra::init_db;
+use std::fs;
+use std::path::PathBuf;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("projex-encryption-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn passphrase(value: &str) -> DbSetPassphraseReq {
+    DbSetPassphraseReq {
+        passphrase: Some(value.to_string()),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn plaintext_and_empty_files_are_not_encrypted() {
+    let dir = temp_dir();
+    let db_path = dir.join("app.db");
+    let pool = init_db(&db_path).unwrap();
+    assert!(!is_encrypted_file(&db_path));
+    assert!(!pool.0.is_encrypted());
+
+    let empty = dir.join("empty.db");
+    fs::write(&empty, b"").unwrap();
+    assert!(!is_encrypted_file(&empty));
+    assert!(!is_encrypted_file(&dir.join("missing.db")));
+
+    let random = dir.join("random.db");
+    fs::write(&random, [0x5au8; 4096]).unwrap();
+    assert!(is_encrypted_file(&random));
+
+    drop(pool);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn status_reports_plaintext_database() {
+    let pool = init_test_db();
+    let status = db_encryption_status(&pool).unwrap();
+    assert_eq!(status.supported, encryption_supported());
+    assert!(!status.encrypted);
+    assert_eq!(status.plaintext_backups, 0);
+}
+
+#[test]
+fn clearing_the_passphrase_of_a_plaintext_database_is_a_no_op_or_unsupported() {
+    let pool = init_test_db();
+    let result = db_set_passphrase(&pool, DbSetPassphraseReq { passphrase: None });
+    if encryption_supported() {
+        assert!(!result.unwrap().encrypted);
+    } else {
+        assert_eq!(result.unwrap_err().code(), "DB_ENCRYPTION_ERROR");
+    }
+}
+
+#[test]
+fn short_passphrase_and_in_memory_database_are_rejected() {
+    let pool = init_test_db();
+    let short = db_set_passphrase(&pool, passphrase("short")).unwrap_err();
+    let in_memory = db_set_passphrase(&pool, passphrase("long enough passphrase")).unwrap_err();
+    if encryption_supported() {
+        assert_eq!(short.code(), "VALIDATION_ERROR");
+        assert_eq!(in_memory.code(), "VALIDATION_ERROR");
+    } else {
+        assert_eq!(short.code(), "DB_ENCRYPTION_ERROR");
+        assert_eq!(in_memory.code(), "DB_ENCRYPTION_ERROR");
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+#[test]
+fn encrypted_file_cannot_be_opened_without_sqlcipher() {
+    let dir = temp_dir();
+    let db_path = dir.join("app.db");
+    fs::write(&db_path, [0x5au8; 4096]).unwrap();
+    let err = init_db(&db_path)
+        .err()
+        .expect("encrypted file must not open");
+    assert_eq!(err.code(), "DB_ENCRYPTION_ERROR");
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn export_roundtrip_requires_the_passphrase() {
+    use app_lib::infra::encryption::{export_database, open_with_key};
+
+    let dir = temp_dir();
+    let plain = rusqlite::Connection::open(dir.join("plain.db")).unwrap();
+    plain
+        .execute_batch("CREATE TABLE t (v TEXT); INSERT INTO t VALUES ('secret')")
+        .unwrap();
+
+    let encrypted = dir.join("encrypted.db");
+    export_database(&plain, &encrypted, false, Some("correct horse")).unwrap();
+    assert!(is_encrypted_file(&encrypted));
+    assert!(open_with_key(&encrypted, Some("wrong passphrase")).is_err());
+    let conn = open_with_key(&encrypted, Some("correct horse")).unwrap();
+    let value: String = conn.query_row("SELECT v FROM t", [], |r| r.get(0)).unwrap();
+    assert_eq!(value, "secret");
+
+    let decrypted = dir.join("decrypted.db");
+    export_database(&conn, &decrypted, true, None).unwrap();
+    assert!(!is_encrypted_file(&decrypted));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,105 @@
+//! Database maintenance tests: integrity check, vacuum mode, index stats and scheduling
+
+use app_lib::app::{
+    db_maintenance_get_config, db_maintenance_run, db_maintenance_run_scheduled,
+    db_maintenance_update_config, integrity_check, DbMaintenanceConfigReq, VacuumMode,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::{DateTime, Duration, Utc};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn at(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+fn add_partners(pool: &DbPool, count: usize) {
+    let conn = pool.0.lock().unwrap();
+    for i in 0..count {
+        conn.execute(
+            "INSERT INTO partners (id, name, note, created_at, updated_at) VALUES (?1, ?2, ?3, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            rusqlite::params![format!("pt{}", i), format!("Partner {}", i), "x".repeat(2000)],
+        )
+        .unwrap();
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn healthy_database_passes_integrity_check() {
+    let pool = init_test_db();
+    let conn = pool.0.lock().unwrap();
+    assert!(integrity_check(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn first_run_switches_to_incremental_vacuum_and_reclaims_space() {
+    let pool = init_test_db();
+    add_partners(&pool, 200);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute("DELETE FROM partners", []).unwrap();
+    }
+
+    let now = at("2026-03-01T00:00:00Z");
+    let first = db_maintenance_run(&pool, now).unwrap();
+    assert!(first.integrity_ok);
+    assert!(!first.reindexed);
+    assert_eq!(first.vacuum, VacuumMode::Full);
+    assert!(first.free_pages_before > 0);
+    assert_eq!(first.free_pages_after, 0);
+    assert!(first.size_after_bytes < first.size_before_bytes);
+
+    let second = db_maintenance_run(&pool, now).unwrap();
+    assert_eq!(second.vacuum, VacuumMode::Incremental);
+}
+
+#[test]
+fn analyze_reports_index_stats() {
+    let pool = init_test_db();
+    add_partners(&pool, 10);
+
+    let report = db_maintenance_run(&pool, Utc::now()).unwrap();
+    assert!(!report.indexes.is_empty());
+    let partner_index = report
+        .indexes
+        .iter()
+        .find(|i| i.table == "partners")
+        .expect("partners index stats");
+    assert_eq!(partner_index.rows, 10);
+    // Largest first.
+    assert!(report.indexes.windows(2).all(|w| w[0].rows >= w[1].rows));
+}
+
+#[test]
+fn scheduled_run_is_opt_in_and_monthly() {
+    let pool = init_test_db();
+    let now = at("2026-03-01T00:00:00Z");
+    assert!(db_maintenance_run_scheduled(&pool, now).unwrap().is_none());
+    assert!(db_maintenance_get_config(&pool)
+        .unwrap()
+        .next_due_at
+        .is_none());
+
+    db_maintenance_update_config(&pool, DbMaintenanceConfigReq { enabled: true }).unwrap();
+    assert!(db_maintenance_run_scheduled(&pool, now).unwrap().is_some());
+    assert!(
+        db_maintenance_run_scheduled(&pool, now + Duration::days(29))
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        db_maintenance_run_scheduled(&pool, now + Duration::days(30))
+            .unwrap()
+            .is_some()
+    );
+
+    let config = db_maintenance_get_config(&pool).unwrap();
+    assert!(config.enabled);
+    assert_eq!(
+        config.next_due_at.as_deref(),
+        Some((now + Duration::days(60)).to_rfc3339().as_str())
+    );
+}
@@ -0,0 +1,121 @@
+//! Connection pool tests (write connection, read-only connections, poisoning)
+
+use app_lib::infra::db::{get_connection, get_read_connection, init_db, init_test_db, MAX_READERS};
+use app_lib::infra::DbPool;
+use std::fs;
+use std::path::PathBuf;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn file_db() -> (DbPool, PathBuf) {
+    let base = std::env::temp_dir().join(format!("projex-pool-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&base).unwrap();
+    let pool = init_db(&base.join("app.db")).unwrap();
+    (pool, base)
+}
+
+fn insert_person(pool: &DbPool, id: &str) {
+    get_connection(pool)
+        .execute(
+            "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at)
+             VALUES (?1, ?1, '', '', '', 1, datetime('now'), datetime('now'))",
+            [id],
+        )
+        .unwrap();
+}
+
+fn count_persons(conn: &rusqlite::Connection) -> i64 {
+    conn.query_row("SELECT COUNT(*) FROM persons", [], |r| r.get(0))
+        .unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn test_reader_sees_committed_writes() {
+    let (pool, base) = file_db();
+    insert_person(&pool, "p1");
+    assert_eq!(count_persons(&get_read_connection(&pool)), 1);
+    fs::remove_dir_all(base).ok();
+}
+
+#[test]
+fn test_reader_does_not_wait_for_writer() {
+    let (pool, base) = file_db();
+    insert_person(&pool, "p1");
+
+    let writer = get_connection(&pool);
+    let readers: Vec<_> = (0..MAX_READERS)
+        .map(|_| {
+            let pool = pool.clone();
+            std::thread::spawn(move || count_persons(&get_read_connection(&pool)))
+        })
+        .collect();
+    for handle in readers {
+        assert_eq!(handle.join().unwrap(), 1);
+    }
+    drop(writer);
+    fs::remove_dir_all(base).ok();
+}
+
+#[test]
+fn test_reader_is_read_only() {
+    let (pool, base) = file_db();
+    let conn = get_read_connection(&pool);
+    let result = conn.execute(
+        "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at)
+         VALUES ('p1', 'p1', '', '', '', 1, datetime('now'), datetime('now'))",
+        [],
+    );
+    assert!(result.is_err());
+    drop(conn);
+    assert_eq!(count_persons(&get_connection(&pool)), 0);
+    fs::remove_dir_all(base).ok();
+}
+
+#[test]
+fn test_in_memory_reads_use_writer() {
+    let pool = init_test_db();
+    insert_person(&pool, "p1");
+    assert_eq!(count_persons(&get_read_connection(&pool)), 1);
+}
+
+#[test]
+fn test_panic_while_writing_does_not_poison_pool() {
+    let pool = init_test_db();
+    let cloned = pool.clone();
+    let result = std::thread::spawn(move || {
+        let _conn = get_connection(&cloned);
+        panic!("boom");
+    })
+    .join();
+    assert!(result.is_err());
+
+    assert!(pool.0.lock().is_ok());
+    insert_person(&pool, "p1");
+    assert_eq!(count_persons(&get_read_connection(&pool)), 1);
+}
+
+#[test]
+fn test_replace_with_switches_database() {
+    let (pool, base) = file_db();
+    insert_person(&pool, "p1");
+    assert_eq!(count_persons(&get_read_connection(&pool)), 1);
+
+    let (other, other_base) = file_db();
+    pool.0.replace_with(other).unwrap();
+    assert_eq!(count_persons(&get_read_connection(&pool)), 0);
+    assert_eq!(count_persons(&get_connection(&pool)), 0);
+
+    fs::remove_dir_all(base).ok();
+    fs::remove_dir_all(other_base).ok();
+}
+
+#[test]
+fn test_replace_with_rejects_shared_pool() {
+    let pool = init_test_db();
+    let other = init_test_db();
+    let kept = other.clone();
+    assert!(pool.0.replace_with(other).is_err());
+    drop(kept);
+}
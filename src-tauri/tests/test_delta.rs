@@ -1,5 +1,6 @@
 //! Delta compress / decompress / checksum tests
 
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
 use app_lib::sync::VectorClock;
 use app_lib::sync::{Delta, Operation, OperationType};
 
@@ -45,6 +46,7 @@ fn make_delta() -> Delta {
         vector_clock: VectorClock::new("test-device".into()),
         created_at: "2026-01-01T00:00:00Z".into(),
         checksum,
+        format_version: SYNC_FORMAT_VERSION,
     }
 }
 
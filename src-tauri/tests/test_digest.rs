@@ -0,0 +1,264 @@
+//! Chat digest (Slack / Teams) tests
+
+use app_lib::app::{
+    digest_get_config, digest_preview, digest_run_scheduled_with, digest_update_config,
+    notice_list, partner_create, person_create, project_change_status, project_create,
+    DigestConfigReq, DigestFormat, DigestFrequency, DigestPreviewReq, PartnerCreateReq,
+    PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq, NOTICE_KIND_DIGEST_FAILED,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::webhook::WebhookRequest;
+use app_lib::infra::DbPool;
+use chrono::{Duration, NaiveDate, Utc};
+use std::cell::RefCell;
+
+// ──────────────────────── Helper ────────────────────────
+
+const SLACK_URL: &str = "https://hooks.slack.com/services/T000/B000/XXXX";
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn project(pool: &DbPool, name: &str, due: Option<&str>) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("{} owner", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("{} partner", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "US".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: due.map(str::to_string),
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn change_status(pool: &DbPool, project_id: &str, to: &str) {
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: project_id.to_string(),
+            to_status: to.to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+}
+
+fn enable(pool: &DbPool, frequency: DigestFrequency, format: DigestFormat) {
+    digest_update_config(
+        pool,
+        DigestConfigReq {
+            enabled: Some(true),
+            frequency: Some(frequency),
+            format: Some(format),
+            webhook_url: Some(SLACK_URL.to_string()),
+        },
+    )
+    .unwrap();
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn preview_renders_slack_and_teams() {
+    let pool = init_test_db();
+    let p = project(&pool, "Relaunch <beta>", None);
+    change_status(&pool, &p, "PLANNED");
+    project(&pool, "Late", Some("2026-01-05"));
+
+    let today = day("2026-03-10");
+    let preview = digest_preview(
+        &pool,
+        DigestPreviewReq {
+            frequency: Some(DigestFrequency::Daily),
+            format: Some(DigestFormat::Slack),
+        },
+        today,
+        Utc::now(),
+    )
+    .unwrap();
+    assert_eq!(preview.digest.new_projects.len(), 2);
+    assert_eq!(preview.digest.status_changes.len(), 1);
+    assert_eq!(preview.digest.status_changes[0].to_status, "PLANNED");
+    assert_eq!(preview.digest.overdue.len(), 1);
+    assert_eq!(preview.digest.overdue[0].name, "Late");
+
+    let blocks = preview.payload["blocks"].as_array().unwrap();
+    assert_eq!(blocks[0]["type"], "header");
+    let text = preview.payload.to_string();
+    assert!(text.contains("New projects (2)"));
+    assert!(text.contains("Relaunch &lt;beta&gt;"));
+    assert!(preview.payload["text"]
+        .as_str()
+        .unwrap()
+        .starts_with("Projex daily digest"));
+
+    let teams = digest_preview(
+        &pool,
+        DigestPreviewReq {
+            frequency: Some(DigestFrequency::Weekly),
+            format: Some(DigestFormat::Teams),
+        },
+        today,
+        Utc::now(),
+    )
+    .unwrap();
+    let card = &teams.payload["attachments"][0];
+    assert_eq!(
+        card["contentType"],
+        "application/vnd.microsoft.card.adaptive"
+    );
+    assert_eq!(card["content"]["type"], "AdaptiveCard");
+    assert!(teams.payload.to_string().contains("Overdue (1)"));
+}
+
+#[test]
+fn scheduled_run_posts_once_per_period() {
+    let pool = init_test_db();
+    project(&pool, "New", None);
+
+    let sent: RefCell<Vec<WebhookRequest>> = RefCell::new(Vec::new());
+    let send = |req: &WebhookRequest| -> Result<u16, String> {
+        sent.borrow_mut().push(req.clone());
+        Ok(200)
+    };
+    let now = Utc::now();
+
+    // Disabled by default.
+    assert!(
+        digest_run_scheduled_with(&pool, day("2026-03-10"), now, &send)
+            .unwrap()
+            .is_none()
+    );
+
+    enable(&pool, DigestFrequency::Weekly, DigestFormat::Slack);
+    let first = digest_run_scheduled_with(&pool, day("2026-03-10"), now, &send)
+        .unwrap()
+        .unwrap();
+    assert!(first.posted);
+    assert_eq!(sent.borrow().len(), 1);
+    assert_eq!(sent.borrow()[0].url, SLACK_URL);
+    assert!(digest_get_config(&pool).unwrap().last_sent_at.is_some());
+
+    // Not due again within the week.
+    let later = now + Duration::days(3);
+    assert!(
+        digest_run_scheduled_with(&pool, day("2026-03-13"), later, &send)
+            .unwrap()
+            .is_none()
+    );
+
+    // A week later: due, but nothing happened since the last digest and nothing is overdue.
+    let next_week = now + Duration::days(7);
+    let quiet = digest_run_scheduled_with(&pool, day("2026-03-17"), next_week, &send)
+        .unwrap()
+        .unwrap();
+    assert!(!quiet.posted);
+    assert_eq!(sent.borrow().len(), 1);
+}
+
+#[test]
+fn failures_are_reported_once_and_retried() {
+    let pool = init_test_db();
+    project(&pool, "New", None);
+    enable(&pool, DigestFrequency::Daily, DigestFormat::Teams);
+
+    let now = Utc::now();
+    let failing = |_: &WebhookRequest| -> Result<u16, String> {
+        Err(format!("error sending request for url ({})", SLACK_URL))
+    };
+    for _ in 0..2 {
+        let err = digest_run_scheduled_with(&pool, day("2026-03-10"), now, &failing).unwrap_err();
+        assert!(!err.to_string().contains("XXXX"));
+    }
+    let notices: Vec<_> = notice_list(&pool)
+        .unwrap()
+        .into_iter()
+        .filter(|n| n.kind == NOTICE_KIND_DIGEST_FAILED)
+        .collect();
+    assert_eq!(notices.len(), 1);
+    let config = digest_get_config(&pool).unwrap();
+    assert!(config.last_sent_at.is_none());
+    assert!(config.last_error.is_some());
+
+    // Still due: the next successful run clears the error.
+    let ok = digest_run_scheduled_with(&pool, day("2026-03-10"), now, &|_| Ok(202))
+        .unwrap()
+        .unwrap();
+    assert!(ok.posted);
+    assert!(digest_get_config(&pool).unwrap().last_error.is_none());
+}
+
+#[test]
+fn config_validation() {
+    let pool = init_test_db();
+    let config = digest_get_config(&pool).unwrap();
+    assert!(!config.enabled);
+    assert!(!config.has_webhook_url);
+    assert_eq!(config.frequency, DigestFrequency::Weekly);
+    assert_eq!(config.format, DigestFormat::Slack);
+
+    let no_url = digest_update_config(
+        &pool,
+        DigestConfigReq {
+            enabled: Some(true),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(no_url.code(), "VALIDATION_ERROR");
+    let http = digest_update_config(
+        &pool,
+        DigestConfigReq {
+            webhook_url: Some("http://hooks.slack.com/x".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(http.code(), "VALIDATION_ERROR");
+
+    enable(&pool, DigestFrequency::Daily, DigestFormat::Slack);
+    let config = digest_get_config(&pool).unwrap();
+    assert!(config.enabled && config.has_webhook_url);
+    // Removing the URL of an enabled digest is rejected.
+    let err = digest_update_config(
+        &pool,
+        DigestConfigReq {
+            webhook_url: Some(String::new()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
@@ -74,6 +74,7 @@ fn export_with_data_contains_all_entities() {
             due_date: None,
             tags: Some(vec!["export".to_string(), "test".to_string()]),
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -99,6 +100,7 @@ fn export_with_data_contains_all_entities() {
             note: Some("Starting planning".to_string()),
             changed_by_person_id: Some(owner.id.clone()),
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -122,6 +124,7 @@ fn export_with_data_contains_all_entities() {
     assert_eq!(proj["priority"], 4);
     assert_eq!(proj["currentStatus"], "PLANNED");
     assert_eq!(proj["countryCode"], "US");
+    assert_eq!(proj["countryName"], "United States");
     assert_eq!(proj["partnerId"], partner.id);
     assert_eq!(proj["ownerPersonId"], owner.id);
     let tags = proj["tags"].as_array().unwrap();
@@ -188,6 +191,7 @@ fn export_uses_camel_case_keys() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -249,6 +253,7 @@ fn import_into_empty_db_succeeds() {
             due_date: None,
             tags: Some(vec!["imported".to_string()]),
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -306,6 +311,7 @@ fn import_duplicate_ids_are_skipped() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -375,6 +381,7 @@ fn import_export_roundtrip_preserves_data() {
             due_date: Some("2026-12-31".to_string()),
             tags: Some(vec!["alpha".to_string(), "beta".to_string()]),
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -386,6 +393,7 @@ fn import_export_roundtrip_preserves_data() {
             note: Some("planning".to_string()),
             changed_by_person_id: Some(owner.id.clone()),
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -0,0 +1,263 @@
+//! Selective JSON export: project selection, comment / history exclusion, changedSince
+
+use app_lib::app::{
+    comment_create, export_json_selective, import_json_string, partner_create, person_create,
+    project_create, CommentCreateReq, JsonExportReq, PartnerCreateReq, PersonCreateReq,
+    ProjectCreateReq, ProjectListReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use serde_json::Value;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn person(pool: &DbPool, name: &str) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn partner(pool: &DbPool, name: &str) -> String {
+    partner_create(
+        pool,
+        PartnerCreateReq {
+            name: name.to_string(),
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn project(
+    pool: &DbPool,
+    name: &str,
+    partner_id: &str,
+    owner_id: &str,
+    parent: Option<&str>,
+) -> String {
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner_id.to_string(),
+            owner_person_id: owner_id.to_string(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(vec![name.to_lowercase()]),
+            created_by_person_id: None,
+            parent_project_id: parent.map(str::to_string),
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn export(pool: &DbPool, req: JsonExportReq) -> Value {
+    serde_json::from_str(&export_json_selective(pool, req).unwrap()).unwrap()
+}
+
+fn ids(json: &Value, key: &str) -> Vec<String> {
+    let mut ids: Vec<String> = json[key]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Two unrelated projects: (alpha, beta, alice, bob, acme, globex); alpha has a comment by carol.
+fn seed(pool: &DbPool) -> [String; 7] {
+    let alice = person(pool, "Alice");
+    let bob = person(pool, "Bob");
+    let carol = person(pool, "Carol");
+    let acme = partner(pool, "Acme");
+    let globex = partner(pool, "Globex");
+    let alpha = project(pool, "Alpha", &acme, &alice, None);
+    let beta = project(pool, "Beta", &globex, &bob, Some(&alpha));
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: alpha.clone(),
+            person_id: Some(carol.clone()),
+            content: "hello".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    [alpha, beta, alice, bob, carol, acme, globex]
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn default_request_exports_everything() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let json = export(&pool, JsonExportReq::default());
+    assert_eq!(json["projects"].as_array().unwrap().len(), 2);
+    assert_eq!(json["persons"].as_array().unwrap().len(), 3);
+    assert_eq!(json["comments"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn project_selection_exports_only_referenced_rows() {
+    let pool = init_test_db();
+    let [alpha, beta, _alice, bob, _carol, _acme, globex] = seed(&pool);
+
+    let json = export(
+        &pool,
+        JsonExportReq {
+            project_ids: Some(vec![beta.clone()]),
+            ..Default::default()
+        },
+    );
+    assert_eq!(ids(&json, "projects"), vec![beta.clone()]);
+    assert_eq!(ids(&json, "persons"), vec![bob]);
+    assert_eq!(ids(&json, "partners"), vec![globex]);
+    assert_eq!(json["assignments"].as_array().unwrap().len(), 1);
+    assert_eq!(json["statusHistory"].as_array().unwrap().len(), 1);
+    assert!(json["comments"].as_array().unwrap().is_empty());
+    // The parent is not exported, so the link is dropped.
+    assert!(json["projects"][0]["parentProjectId"].is_null());
+
+    // Parent and child together keep the link.
+    let json = export(
+        &pool,
+        JsonExportReq {
+            project_ids: Some(vec![beta.clone(), alpha.clone()]),
+            ..Default::default()
+        },
+    );
+    let child = json["projects"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["id"] == beta.as_str())
+        .unwrap();
+    assert_eq!(child["parentProjectId"], alpha.as_str());
+}
+
+#[test]
+fn filter_selection_and_exclusions() {
+    let pool = init_test_db();
+    let [alpha, _beta, alice, _bob, carol, acme, _globex] = seed(&pool);
+
+    let json = export(
+        &pool,
+        JsonExportReq {
+            filter: Some(ProjectListReq {
+                partner_ids: Some(vec![acme.clone()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    );
+    assert_eq!(ids(&json, "projects"), vec![alpha.clone()]);
+    let mut expected = vec![alice.clone(), carol];
+    expected.sort();
+    assert_eq!(ids(&json, "persons"), expected);
+
+    // Without comments the commenter is no longer referenced.
+    let json = export(
+        &pool,
+        JsonExportReq {
+            project_ids: Some(vec![alpha]),
+            include_comments: Some(false),
+            include_history: Some(false),
+            ..Default::default()
+        },
+    );
+    assert!(json["comments"].as_array().unwrap().is_empty());
+    assert!(json["statusHistory"].as_array().unwrap().is_empty());
+    assert_eq!(ids(&json, "persons"), vec![alice]);
+}
+
+#[test]
+fn changed_since_keeps_recent_rows() {
+    let pool = init_test_db();
+    let [alpha, _beta, ..] = seed(&pool);
+    pool.0
+        .lock()
+        .unwrap()
+        .execute(
+            "UPDATE projects SET updated_at = '2020-01-01T00:00:00+00:00' WHERE id <> ?1",
+            [&alpha],
+        )
+        .unwrap();
+
+    let json = export(
+        &pool,
+        JsonExportReq {
+            changed_since: Some("2021-06-01".to_string()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(ids(&json, "projects"), vec![alpha]);
+
+    let json = export(
+        &pool,
+        JsonExportReq {
+            changed_since: Some("2999-01-01T00:00:00+08:00".to_string()),
+            ..Default::default()
+        },
+    );
+    assert!(json["projects"].as_array().unwrap().is_empty());
+    assert!(json["persons"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn selective_export_rejects_bad_input_and_imports_cleanly() {
+    let pool = init_test_db();
+    let [_alpha, beta, ..] = seed(&pool);
+
+    let err = export_json_selective(
+        &pool,
+        JsonExportReq {
+            project_ids: Some(vec!["missing".to_string()]),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+
+    let err = export_json_selective(
+        &pool,
+        JsonExportReq {
+            changed_since: Some("last week".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+
+    // A single-project export is self-contained.
+    let json = export_json_selective(
+        &pool,
+        JsonExportReq {
+            project_ids: Some(vec![beta]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let target = init_test_db();
+    let result = import_json_string(&target, &json).unwrap();
+    assert_eq!(result.projects, 1);
+    assert_eq!(result.persons, 1);
+    assert_eq!(result.partners, 1);
+}
@@ -0,0 +1,179 @@
+//! Streaming JSON export (export_json_to_file) tests
+
+use app_lib::app::{
+    export_json_string, export_json_to_file, import_json_string, partner_create, person_create,
+    project_change_status, project_create, JsonExportProgress, PartnerCreateReq, PersonCreateReq,
+    ProjectChangeStatusReq, ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use std::fs;
+use std::path::PathBuf;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("projex-export-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn seed(pool: &DbPool, projects: usize) {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Alice".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    for i in 0..projects {
+        let project = project_create(
+            pool,
+            ProjectCreateReq {
+                name: format!("P{}", i),
+                description: None,
+                priority: Some(3),
+                country_code: "US".to_string(),
+                partner_id: partner.id.clone(),
+                owner_person_id: owner.id.clone(),
+                product_name: None,
+                start_date: None,
+                due_date: None,
+                tags: Some(vec!["a".to_string(), "b".to_string()]),
+                created_by_person_id: None,
+                parent_project_id: None,
+            },
+        )
+        .unwrap();
+        project_change_status(
+            pool,
+            ProjectChangeStatusReq {
+                project_id: project.id,
+                to_status: "PLANNED".to_string(),
+                note: None,
+                changed_by_person_id: None,
+                if_match_updated_at: None,
+                force: None,
+            },
+        )
+        .unwrap();
+    }
+}
+
+fn without_exported_at(json: &str) -> serde_json::Value {
+    let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+    value.as_object_mut().unwrap().remove("exportedAt");
+    value
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn streamed_file_matches_in_memory_export() {
+    let pool = init_test_db();
+    seed(&pool, 3);
+    let dir = temp_dir();
+    let path = dir.join("workspace.json");
+
+    let result = export_json_to_file(&pool, &path, |_| {}).unwrap();
+
+    let streamed = fs::read_to_string(&path).unwrap();
+    assert_eq!(result.bytes, streamed.len() as u64);
+    // 1 person + 1 partner + 3 projects + 6 status rows (CREATE + PLANNED each)
+    assert_eq!(result.rows, 11);
+    assert_eq!(
+        without_exported_at(&streamed),
+        without_exported_at(&export_json_string(&pool, None).unwrap())
+    );
+    assert!(!dir.join("workspace.json.part").exists());
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn streamed_file_can_be_imported() {
+    let pool = init_test_db();
+    seed(&pool, 2);
+    let dir = temp_dir();
+    let path = dir.join("workspace.json");
+    export_json_to_file(&pool, &path, |_| {}).unwrap();
+
+    let target = init_test_db();
+    let result = import_json_string(&target, &fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(result.persons, 1);
+    assert_eq!(result.partners, 1);
+    assert_eq!(result.projects, 2);
+    assert_eq!(result.status_history, 4);
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn progress_reaches_total_for_every_section() {
+    let pool = init_test_db();
+    seed(&pool, 2);
+    let dir = temp_dir();
+    let path = dir.join("workspace.json");
+
+    let mut events: Vec<JsonExportProgress> = Vec::new();
+    let result = export_json_to_file(&pool, &path, |p| events.push(p.clone())).unwrap();
+
+    let sections: Vec<&str> = events.iter().map(|p| p.section).collect();
+    assert_eq!(
+        sections,
+        vec![
+            "persons",
+            "partners",
+            "projects",
+            "assignments",
+            "statusHistory",
+            "sizeHistory",
+            "comments",
+            "customFieldDefs",
+            "customFieldValues",
+        ]
+    );
+    let last = events.last().unwrap();
+    assert_eq!(last.rows_written, result.rows);
+    assert_eq!(last.total_rows, result.rows);
+    assert!(events
+        .windows(2)
+        .all(|w| w[0].rows_written <= w[1].rows_written));
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn empty_database_streams_valid_json() {
+    let pool = init_test_db();
+    let dir = temp_dir();
+    let path = dir.join("empty.json");
+
+    let result = export_json_to_file(&pool, &path, |_| {}).unwrap();
+    assert_eq!(result.rows, 0);
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(json["schemaVersion"], 3);
+    assert_eq!(json["projects"].as_array().unwrap().len(), 0);
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn failed_export_leaves_no_file() {
+    let pool = init_test_db();
+    let dir = temp_dir();
+    let path = dir.join("missing").join("workspace.json");
+
+    let err = export_json_to_file(&pool, &path, |_| {}).unwrap_err();
+    assert_eq!(err.code(), "EXPORT_ERROR");
+    assert!(!path.exists());
+    fs::remove_dir_all(dir).ok();
+}
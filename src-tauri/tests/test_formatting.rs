@@ -0,0 +1,98 @@
+//! Formatting preference tests: persistence, date/time rendering, week boundaries
+
+use app_lib::app::{
+    format_settings_get, format_settings_update, DateFormat, FormatSettings, FormatSettingsReq,
+    HourCycle, WeekStart,
+};
+use app_lib::infra::db::init_test_db;
+use chrono::NaiveDate;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn defaults_to_iso_monday_24h_and_persists_partial_updates() {
+    let pool = init_test_db();
+    assert_eq!(
+        format_settings_get(&pool).unwrap(),
+        FormatSettings::default()
+    );
+
+    let updated = format_settings_update(
+        &pool,
+        FormatSettingsReq {
+            date_format: Some(DateFormat::Mdy),
+            week_start: Some(WeekStart::Sunday),
+            hour_cycle: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(updated.date_format, DateFormat::Mdy);
+    assert_eq!(updated.week_start, WeekStart::Sunday);
+    assert_eq!(updated.hour_cycle, HourCycle::H24);
+
+    format_settings_update(
+        &pool,
+        FormatSettingsReq {
+            hour_cycle: Some(HourCycle::H12),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let stored = format_settings_get(&pool).unwrap();
+    assert_eq!(stored.date_format, DateFormat::Mdy);
+    assert_eq!(stored.hour_cycle, HourCycle::H12);
+}
+
+#[test]
+fn formats_dates_and_times_per_settings() {
+    let at = date(2026, 3, 1).and_hms_opt(14, 5, 0).unwrap();
+    let iso = FormatSettings::default();
+    assert_eq!(iso.format_datetime(at), "2026-03-01 14:05");
+
+    let us = FormatSettings {
+        date_format: DateFormat::Mdy,
+        hour_cycle: HourCycle::H12,
+        ..Default::default()
+    };
+    assert_eq!(us.format_datetime(at), "03/01/2026 2:05 PM");
+    assert_eq!(
+        us.format_time(date(2026, 3, 1).and_hms_opt(0, 30, 0).unwrap().time()),
+        "12:30 AM"
+    );
+    assert_eq!(us.format_date_str("2026-12-24"), "12/24/2026");
+    assert_eq!(us.format_date_str("not a date"), "not a date");
+
+    let de = FormatSettings {
+        date_format: DateFormat::DmyDot,
+        ..Default::default()
+    };
+    assert_eq!(de.format_date(date(2026, 3, 1)), "01.03.2026");
+}
+
+#[test]
+fn week_start_controls_week_boundaries() {
+    // 2026-03-04 is a Wednesday.
+    let wed = date(2026, 3, 4);
+    let monday = FormatSettings::default();
+    assert_eq!(monday.week_start_of(wed), date(2026, 3, 2));
+    assert_eq!(monday.week_label(wed), "2026-03-02 – 2026-03-08");
+
+    let sunday = FormatSettings {
+        week_start: WeekStart::Sunday,
+        ..Default::default()
+    };
+    assert_eq!(sunday.week_start_of(wed), date(2026, 3, 1));
+    assert_eq!(sunday.week_start_of(date(2026, 3, 1)), date(2026, 3, 1));
+
+    let saturday = FormatSettings {
+        week_start: WeekStart::Saturday,
+        ..Default::default()
+    };
+    assert_eq!(saturday.week_start_of(wed), date(2026, 2, 28));
+}
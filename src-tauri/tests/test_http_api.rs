@@ -0,0 +1,196 @@
+//! Local read-only HTTP API tests (routing, auth, settings)
+
+use app_lib::app::{
+    http_api_get_config, http_api_handle, http_api_regenerate_token, http_api_update_config,
+    partner_create, person_create, project_create, HttpApiConfigReq, PartnerCreateReq,
+    PersonCreateReq, ProjectCreateReq, DEFAULT_HTTP_API_PORT,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::http_server::{parse_request_head, HttpResponse};
+use app_lib::infra::DbPool;
+use chrono::NaiveDate;
+use serde_json::Value;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, 10).unwrap()
+}
+
+fn setup(pool: &DbPool) -> (String, String) {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Dashboard".to_string(),
+            description: None,
+            priority: None,
+            country_code: "US".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+    let token = http_api_update_config(
+        pool,
+        HttpApiConfigReq {
+            enabled: Some(true),
+            port: None,
+        },
+    )
+    .unwrap()
+    .token
+    .unwrap();
+    (project.id, token)
+}
+
+fn get(pool: &DbPool, target: &str, token: Option<&str>) -> (u16, Value) {
+    let mut head = format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1", target);
+    if let Some(token) = token {
+        head.push_str(&format!("\r\nAuthorization: Bearer {}", token));
+    }
+    let HttpResponse { status, body } =
+        http_api_handle(pool, &parse_request_head(&head).unwrap(), today());
+    (status, serde_json::from_str(&body).unwrap())
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn endpoints_mirror_commands() {
+    let pool = init_test_db();
+    let (project_id, token) = setup(&pool);
+
+    let (status, body) = get(&pool, "/api/v1/health", None);
+    assert_eq!(status, 200);
+    assert_eq!(body["ok"], true);
+
+    let (status, body) = get(&pool, "/api/v1/projects?status=BACKLOG,DONE", Some(&token));
+    assert_eq!(status, 200);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["id"], project_id.as_str());
+    let (_, body) = get(&pool, "/api/v1/projects?status=DONE", Some(&token));
+    assert_eq!(body["total"], 0);
+
+    let (status, body) = get(
+        &pool,
+        &format!("/api/v1/projects/{}", project_id),
+        Some(&token),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["name"], "Dashboard");
+
+    for path in [
+        "/api/v1/reports/summary",
+        "/api/v1/reports/person-workload?upcomingDays=30",
+        "/api/v1/reports/partner-summary",
+    ] {
+        assert_eq!(get(&pool, path, Some(&token)).0, 200, "{}", path);
+    }
+}
+
+#[test]
+fn auth_errors_and_read_only() {
+    let pool = init_test_db();
+    let (_, token) = setup(&pool);
+
+    let (status, body) = get(&pool, "/api/v1/projects", None);
+    assert_eq!(status, 401);
+    assert_eq!(body["error"]["code"], "UNAUTHORIZED");
+    assert_eq!(get(&pool, "/api/v1/projects", Some("wrong")).0, 401);
+
+    let (status, body) = get(&pool, "/api/v1/projects/missing", Some(&token));
+    assert_eq!(status, 404);
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+    assert_eq!(get(&pool, "/api/v1/nope", Some(&token)).0, 404);
+    assert_eq!(
+        get(
+            &pool,
+            "/api/v1/reports/person-workload?upcomingDays=x",
+            Some(&token)
+        )
+        .0,
+        400
+    );
+
+    let post = parse_request_head(&format!(
+        "POST /api/v1/projects HTTP/1.1\r\nAuthorization: Bearer {}",
+        token
+    ))
+    .unwrap();
+    assert_eq!(http_api_handle(&pool, &post, today()).status, 405);
+
+    // The old token stops working as soon as a new one is generated.
+    let new_token = http_api_regenerate_token(&pool).unwrap().token.unwrap();
+    assert_ne!(new_token, token);
+    assert_eq!(get(&pool, "/api/v1/projects", Some(&token)).0, 401);
+    assert_eq!(get(&pool, "/api/v1/projects", Some(&new_token)).0, 200);
+}
+
+#[test]
+fn settings_defaults_and_validation() {
+    let pool = init_test_db();
+    let config = http_api_get_config(&pool).unwrap();
+    assert!(!config.enabled);
+    assert!(config.token.is_none());
+    assert_eq!(config.port, DEFAULT_HTTP_API_PORT);
+
+    let err = http_api_update_config(
+        &pool,
+        HttpApiConfigReq {
+            enabled: None,
+            port: Some(80),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+
+    let config = http_api_update_config(
+        &pool,
+        HttpApiConfigReq {
+            enabled: Some(true),
+            port: Some(28000),
+        },
+    )
+    .unwrap();
+    assert!(config.enabled);
+    assert_eq!(config.base_url, "http://127.0.0.1:28000/api/v1");
+    let token = config.token.unwrap();
+    assert_eq!(token.len(), 64);
+
+    // Disabling and re-enabling keeps the token.
+    for enabled in [false, true] {
+        http_api_update_config(
+            &pool,
+            HttpApiConfigReq {
+                enabled: Some(enabled),
+                port: None,
+            },
+        )
+        .unwrap();
+    }
+    assert_eq!(http_api_get_config(&pool).unwrap().token, Some(token));
+}
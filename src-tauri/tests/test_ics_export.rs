@@ -0,0 +1,145 @@
+//! iCalendar export: due / start dates and template milestones, filtered like project_list
+
+use app_lib::app::{
+    comment_create, export_ics, partner_create, person_create, project_create, CommentCreateReq,
+    PartnerCreateReq, PersonCreateReq, ProjectCreateReq, ProjectListReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::{TimeZone, Utc};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn project(
+    pool: &DbPool,
+    name: &str,
+    start_date: Option<&str>,
+    due_date: Option<&str>,
+) -> (String, String) {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("{} owner", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("{} partner", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    let id = project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id.clone(),
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: start_date.map(str::to_string),
+            due_date: due_date.map(str::to_string),
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id;
+    (id, partner.id)
+}
+
+fn export(pool: &DbPool, req: ProjectListReq) -> String {
+    export_ics(
+        pool,
+        req,
+        Utc.with_ymd_and_hms(2026, 3, 1, 8, 30, 0).unwrap(),
+    )
+    .unwrap()
+}
+
+/// Unfolded content lines.
+fn lines(ics: &str) -> Vec<String> {
+    ics.replace("\r\n ", "")
+        .split("\r\n")
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn ics_contains_due_start_and_milestone_events() {
+    let pool = init_test_db();
+    let (id, _) = project(
+        &pool,
+        "Alpha, Phase 1",
+        Some("2026-03-02"),
+        Some("2026-04-30"),
+    );
+    comment_create(
+        &pool,
+        CommentCreateReq {
+            project_id: id.clone(),
+            person_id: None,
+            content: "Milestones:\n- Kickoff (2026-03-05)\n- Beta (2026-04-01)".to_string(),
+            is_pinned: Some(true),
+        },
+    )
+    .unwrap();
+
+    let ics = export(&pool, ProjectListReq::default());
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(ics
+        .split("\r\n")
+        .all(|line| line.len() <= 75 || line.is_empty()));
+
+    let lines = lines(&ics);
+    assert_eq!(lines.iter().filter(|l| *l == "BEGIN:VEVENT").count(), 4);
+    assert!(lines.contains(&format!("UID:{}-due@projex", id)));
+    assert!(lines.contains(&"DTSTAMP:20260301T083000Z".to_string()));
+    assert!(lines.contains(&"SUMMARY:Due: Alpha\\, Phase 1".to_string()));
+    assert!(lines.contains(&"SUMMARY:Start: Alpha\\, Phase 1".to_string()));
+    assert!(lines.contains(&"SUMMARY:Alpha\\, Phase 1: Kickoff".to_string()));
+
+    // Events are ordered by date; all-day events end the next day.
+    let starts: Vec<&str> = lines
+        .iter()
+        .filter_map(|l| l.strip_prefix("DTSTART;VALUE=DATE:"))
+        .collect();
+    assert_eq!(starts, vec!["20260302", "20260305", "20260401", "20260430"]);
+    assert!(lines.contains(&"DTEND;VALUE=DATE:20260501".to_string()));
+}
+
+#[test]
+fn ics_uses_project_list_filters() {
+    let pool = init_test_db();
+    let (_, partner_a) = project(&pool, "Alpha", None, Some("2026-04-30"));
+    project(&pool, "Beta", None, Some("2026-05-31"));
+    project(&pool, "Undated", None, None);
+
+    let all = lines(&export(&pool, ProjectListReq::default()));
+    assert_eq!(all.iter().filter(|l| *l == "BEGIN:VEVENT").count(), 2);
+
+    let only_a = lines(&export(
+        &pool,
+        ProjectListReq {
+            partner_ids: Some(vec![partner_a]),
+            ..Default::default()
+        },
+    ));
+    let summaries: Vec<&str> = only_a
+        .iter()
+        .filter_map(|l| l.strip_prefix("SUMMARY:"))
+        .collect();
+    assert_eq!(summaries, vec!["Due: Alpha"]);
+}
@@ -0,0 +1,152 @@
+//! Large imports published as a snapshot: threshold, metadata coverage, receiver merge
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::import_snapshot::{
+    extract_snapshot_import, mark_import_inserts_synced, max_sync_metadata_id,
+    merge_import_snapshot, should_publish_import_snapshot, snapshot_import_operation,
+    AUTO_SNAPSHOT_IMPORT_THRESHOLD_KEY,
+};
+use app_lib::sync::snapshot::Snapshot;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn setup() -> DbPool {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE sync_config SET value = '1' WHERE key = 'sync_enabled'",
+            [],
+        )
+        .unwrap();
+    }
+    pool
+}
+
+fn insert_person(pool: &DbPool, id: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO persons (id, display_name, created_at, updated_at) VALUES (?1, ?1, datetime('now'), datetime('now'))",
+        [id],
+    )
+    .unwrap();
+}
+
+fn pending_count(pool: &DbPool) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT COUNT(*) FROM sync_metadata WHERE synced = 0",
+        [],
+        |row: &rusqlite::Row<'_>| row.get(0),
+    )
+    .unwrap()
+}
+
+fn person_ids(pool: &DbPool) -> Vec<String> {
+    let conn = pool.0.lock().unwrap();
+    let mut stmt = conn.prepare("SELECT id FROM persons ORDER BY id").unwrap();
+    stmt.query_map([], |row: &rusqlite::Row<'_>| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn threshold_controls_publishing() {
+    let pool = setup();
+    let conn = pool.0.lock().unwrap();
+    assert!(!should_publish_import_snapshot(&conn, 499));
+    assert!(should_publish_import_snapshot(&conn, 500));
+
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, '0')",
+        [AUTO_SNAPSHOT_IMPORT_THRESHOLD_KEY],
+    )
+    .unwrap();
+    assert!(!should_publish_import_snapshot(&conn, 10_000));
+
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, '2')",
+        [AUTO_SNAPSHOT_IMPORT_THRESHOLD_KEY],
+    )
+    .unwrap();
+    conn.execute(
+        "UPDATE sync_config SET value = '0' WHERE key = 'sync_enabled'",
+        [],
+    )
+    .unwrap();
+    assert!(!should_publish_import_snapshot(&conn, 10));
+}
+
+#[test]
+fn only_import_inserts_are_marked_synced() {
+    let pool = setup();
+    insert_person(&pool, "before");
+    let after_id = max_sync_metadata_id(&pool.0.lock().unwrap()).unwrap();
+
+    insert_person(&pool, "imported-1");
+    insert_person(&pool, "imported-2");
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute("UPDATE persons SET note = 'edited' WHERE id = 'before'", [])
+            .unwrap();
+    }
+    let up_to_id = max_sync_metadata_id(&pool.0.lock().unwrap()).unwrap();
+
+    let marked = mark_import_inserts_synced(&pool.0.lock().unwrap(), after_id, up_to_id).unwrap();
+    assert_eq!(marked, 2);
+    // The earlier insert and the update still upload as deltas.
+    assert_eq!(pending_count(&pool), 2);
+}
+
+#[test]
+fn control_operation_round_trips() {
+    let op = snapshot_import_operation("snapshots/dev-a/snapshot-1.gz", "abc", 600);
+    let found = extract_snapshot_import(std::slice::from_ref(&op)).unwrap();
+    assert_eq!(found.snapshot_key, "snapshots/dev-a/snapshot-1.gz");
+    assert_eq!(found.checksum, "abc");
+}
+
+#[test]
+fn merge_inserts_missing_rows_without_resurrecting_deleted_ones() {
+    let source = setup();
+    insert_person(&source, "kept");
+    insert_person(&source, "deleted-here");
+    insert_person(&source, "new");
+    let snapshot = Snapshot::create(&source, "dev-a".into()).unwrap();
+
+    let receiver = setup();
+    insert_person(&receiver, "kept");
+    insert_person(&receiver, "deleted-here");
+    {
+        let conn = receiver.0.lock().unwrap();
+        conn.execute(
+            "UPDATE persons SET display_name = 'Local' WHERE id = 'kept'",
+            [],
+        )
+        .unwrap();
+        conn.execute("DELETE FROM persons WHERE id = 'deleted-here'", [])
+            .unwrap();
+    }
+    let pending_before = pending_count(&receiver);
+
+    let merged = merge_import_snapshot(&receiver.0.lock().unwrap(), &snapshot).unwrap();
+    assert_eq!(merged.persons, 1);
+    assert_eq!(person_ids(&receiver), vec!["kept", "new"]);
+    let name: String = receiver
+        .0
+        .lock()
+        .unwrap()
+        .query_row(
+            "SELECT display_name FROM persons WHERE id = 'kept'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(name, "Local");
+    // Merged rows are not echoed back as local changes.
+    assert_eq!(pending_count(&receiver), pending_before);
+}
@@ -0,0 +1,142 @@
+//! Legacy `default/` data dir migration + app notice tests
+
+use app_lib::app::{notice_dismiss, notice_list, notice_record_layout_migration};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::layout::{migrate_legacy_layout, LegacyLayoutMigration};
+use std::fs;
+use std::path::PathBuf;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn temp_base() -> PathBuf {
+    let base = std::env::temp_dir().join(format!("projex-layout-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&base).unwrap();
+    base
+}
+
+fn seed_legacy(base: &std::path::Path) -> PathBuf {
+    let legacy = base.join("default");
+    fs::create_dir_all(legacy.join("logs")).unwrap();
+    fs::write(legacy.join("projex.db"), b"legacy-db").unwrap();
+    fs::write(legacy.join("projex.db-wal"), b"legacy-wal").unwrap();
+    fs::write(legacy.join("sync.lock"), b"").unwrap();
+    fs::write(legacy.join("logs").join("rust-default.log"), b"old log").unwrap();
+    legacy
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn no_legacy_layout_is_noop() {
+    let base = temp_base();
+    let target = base.join("profiles").join("default");
+    assert_eq!(
+        migrate_legacy_layout(&base.join("default"), &target).unwrap(),
+        None
+    );
+    assert!(!target.exists());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn legacy_layout_is_moved_into_profile_dir() {
+    let base = temp_base();
+    let legacy = seed_legacy(&base);
+    let target = base.join("profiles").join("default");
+
+    let result = migrate_legacy_layout(&legacy, &target).unwrap().unwrap();
+    match result {
+        LegacyLayoutMigration::Migrated { moved, .. } => {
+            assert!(moved.contains(&"projex.db".to_string()));
+            assert!(moved.contains(&"logs".to_string()));
+        }
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+
+    assert_eq!(fs::read(target.join("app.db")).unwrap(), b"legacy-db");
+    assert_eq!(fs::read(target.join("app.db-wal")).unwrap(), b"legacy-wal");
+    assert!(target.join("logs").join("rust-default.log").exists());
+    assert!(target.join("sync.lock").exists());
+    assert!(!legacy.exists());
+    assert!(!base.join("profiles").join(".default.migrating").exists());
+
+    // Second run has nothing left to do.
+    assert_eq!(migrate_legacy_layout(&legacy, &target).unwrap(), None);
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn existing_target_database_is_never_overwritten() {
+    let base = temp_base();
+    let legacy = seed_legacy(&base);
+    let target = base.join("profiles").join("default");
+    fs::create_dir_all(&target).unwrap();
+    fs::write(target.join("app.db"), b"current-db").unwrap();
+
+    let result = migrate_legacy_layout(&legacy, &target).unwrap().unwrap();
+    assert!(matches!(
+        result,
+        LegacyLayoutMigration::SkippedTargetExists { .. }
+    ));
+    assert_eq!(fs::read(target.join("app.db")).unwrap(), b"current-db");
+    assert!(legacy.join("projex.db").exists());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn interrupted_migration_resumes_from_staging_dir() {
+    let base = temp_base();
+    let legacy = seed_legacy(&base);
+    let target = base.join("profiles").join("default");
+    // Simulate a crash after the database was staged but before activation.
+    let staging = base.join("profiles").join(".default.migrating");
+    fs::create_dir_all(&staging).unwrap();
+    fs::rename(legacy.join("projex.db"), staging.join("app.db")).unwrap();
+
+    migrate_legacy_layout(&legacy, &target).unwrap().unwrap();
+    assert_eq!(fs::read(target.join("app.db")).unwrap(), b"legacy-db");
+    assert!(target.join("app.db-wal").exists());
+    assert!(!staging.exists());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn existing_target_logs_are_merged() {
+    let base = temp_base();
+    let legacy = seed_legacy(&base);
+    let target = base.join("profiles").join("default");
+    fs::create_dir_all(target.join("logs")).unwrap();
+    fs::write(target.join("logs").join("webview-default.log"), b"new log").unwrap();
+
+    migrate_legacy_layout(&legacy, &target).unwrap().unwrap();
+    assert!(target.join("app.db").exists());
+    assert!(target.join("logs").join("rust-default.log").exists());
+    assert!(target.join("logs").join("webview-default.log").exists());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn migration_notice_is_listed_until_dismissed() {
+    let pool = init_test_db();
+    let migration = LegacyLayoutMigration::Migrated {
+        from: PathBuf::from("/data/com.nickdu.projex/default"),
+        to: PathBuf::from("/data/com.nickdu.projex/profiles/default"),
+        moved: vec!["projex.db".into(), "logs".into()],
+    };
+    {
+        let conn = pool.0.lock().unwrap();
+        notice_record_layout_migration(&conn, &migration).unwrap();
+    }
+
+    let notices = notice_list(&pool).unwrap();
+    assert_eq!(notices.len(), 1);
+    assert_eq!(notices[0].kind, "DATA_DIR_MIGRATED");
+    assert_eq!(notices[0].level, "info");
+    assert!(notices[0].message.contains("projex.db, logs"));
+
+    notice_dismiss(&pool, notices[0].id).unwrap();
+    assert!(notice_list(&pool).unwrap().is_empty());
+
+    let err = notice_dismiss(&pool, 999).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
@@ -0,0 +1,207 @@
+//! Link health check tests: URL collection, classification, report and notices
+
+use app_lib::app::{
+    comment_create, custom_field_create, link_check_get_config, link_check_report,
+    link_check_run_scheduled, link_check_run_with, link_check_update_config, notice_list,
+    partner_create, person_create, project_create, project_set_custom_value, CommentCreateReq,
+    CustomFieldDefCreateReq, CustomFieldType, LinkCheckConfigReq, LinkOutcome, LinkSource,
+    PartnerCreateReq, PersonCreateReq, ProjectCreateReq, ProjectCustomValueSetReq,
+    NOTICE_KIND_DEAD_LINKS,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::link_probe::ProbeOutcome;
+use app_lib::infra::DbPool;
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn at(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+fn create_project(pool: &DbPool, name: &str, description: &str) -> String {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+/// `/gone` is dead, `/login` needs auth, everything else is fine.
+fn fake_probe(url: &str) -> ProbeOutcome {
+    if url.contains("/gone") {
+        ProbeOutcome::Dead {
+            status: Some(404),
+            error: "HTTP 404".to_string(),
+        }
+    } else if url.contains("/login") {
+        ProbeOutcome::Unverified { status: 403 }
+    } else {
+        ProbeOutcome::Ok { status: 200 }
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn run_collects_links_from_all_sources_and_reports_dead_ones() {
+    let pool = init_test_db();
+    let alpha = create_project(
+        &pool,
+        "Alpha",
+        "Spec: https://share.example.com/gone/spec.docx. Home https://example.com",
+    );
+    let beta = create_project(&pool, "Beta", "No links");
+    comment_create(
+        &pool,
+        CommentCreateReq {
+            project_id: beta.clone(),
+            person_id: None,
+            content: "Minutes at (https://share.example.com/login/minutes)".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    let field = custom_field_create(
+        &pool,
+        CustomFieldDefCreateReq {
+            name: "Tracker".to_string(),
+            field_type: CustomFieldType::Text,
+            options: None,
+            sort_order: None,
+        },
+    )
+    .unwrap();
+    project_set_custom_value(
+        &pool,
+        ProjectCustomValueSetReq {
+            project_id: beta.clone(),
+            field_id: field.id.clone(),
+            value: Some("https://share.example.com/gone/tracker".to_string()),
+        },
+    )
+    .unwrap();
+
+    // Each distinct URL is probed once.
+    let probed = Mutex::new(Vec::new());
+    let result = link_check_run_with(&pool, at("2026-03-02T08:00:00Z"), &|url| {
+        probed.lock().unwrap().push(url.to_string());
+        fake_probe(url)
+    })
+    .unwrap();
+    assert_eq!(result.total_links, 4);
+    assert_eq!(result.checked_urls, 4);
+    assert_eq!((result.dead, result.unverified), (2, 1));
+    assert_eq!(probed.lock().unwrap().len(), 4);
+
+    let report = link_check_report(&pool, None).unwrap();
+    assert_eq!(report.total_links, 4);
+    assert_eq!(report.projects.len(), 2);
+    let beta_health = report
+        .projects
+        .iter()
+        .find(|p| p.project_id == beta)
+        .unwrap();
+    assert_eq!((beta_health.dead, beta_health.unverified), (1, 1));
+    assert_eq!(beta_health.links[0].outcome, LinkOutcome::Dead);
+    assert_eq!(beta_health.links[0].source, LinkSource::CustomField);
+    assert_eq!(
+        beta_health.links[0].source_id.as_deref(),
+        Some(field.id.as_str())
+    );
+    assert_eq!(beta_health.links[1].source, LinkSource::Comment);
+
+    let alpha_only = link_check_report(&pool, Some(&alpha)).unwrap();
+    assert_eq!(alpha_only.projects.len(), 1);
+    assert_eq!(
+        alpha_only.projects[0].links[0].url,
+        "https://share.example.com/gone/spec.docx"
+    );
+    assert_eq!(alpha_only.projects[0].links[0].status_code, Some(404));
+
+    let notices = notice_list(&pool).unwrap();
+    assert!(notices.iter().any(|n| n.kind == NOTICE_KIND_DEAD_LINKS
+        && n.level == "warning"
+        && n.message.contains("2 dead link(s) in 2 project(s)")));
+}
+
+#[test]
+fn rerun_replaces_results_and_healthy_run_records_no_notice() {
+    let pool = init_test_db();
+    create_project(&pool, "Alpha", "https://example.com/gone");
+    link_check_run_with(&pool, at("2026-03-02T08:00:00Z"), &fake_probe).unwrap();
+    assert_eq!(link_check_report(&pool, None).unwrap().dead, 1);
+
+    let before = notice_list(&pool).unwrap().len();
+    link_check_run_with(&pool, at("2026-03-03T08:00:00Z"), &|_| ProbeOutcome::Ok {
+        status: 200,
+    })
+    .unwrap();
+    let report = link_check_report(&pool, None).unwrap();
+    assert_eq!((report.total_links, report.dead), (1, 0));
+    assert!(report.projects.is_empty());
+    assert_eq!(
+        report.checked_at.as_deref(),
+        Some(at("2026-03-03T08:00:00Z").to_rfc3339().as_str())
+    );
+    assert_eq!(notice_list(&pool).unwrap().len(), before);
+}
+
+#[test]
+fn scheduled_check_is_opt_in() {
+    let pool = init_test_db();
+    let config = link_check_get_config(&pool).unwrap();
+    assert!(!config.enabled);
+    assert!(config.next_due_at.is_none());
+    assert!(link_check_run_scheduled(&pool, at("2026-03-02T08:00:00Z"))
+        .unwrap()
+        .is_none());
+
+    let config = link_check_update_config(&pool, LinkCheckConfigReq { enabled: true }).unwrap();
+    assert!(config.enabled);
+    assert!(config.next_due_at.is_some());
+
+    // Not due again within a week of the last check (no links: nothing goes to the network).
+    link_check_run_with(&pool, at("2026-03-02T08:00:00Z"), &fake_probe).unwrap();
+    let config = link_check_get_config(&pool).unwrap();
+    assert_eq!(
+        config.next_due_at.as_deref(),
+        Some(at("2026-03-09T08:00:00Z").to_rfc3339().as_str())
+    );
+    assert!(link_check_run_scheduled(&pool, at("2026-03-05T08:00:00Z"))
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,316 @@
+//! Notification (due-date / blocked / mention reminder) tests
+
+use app_lib::app::{
+    comment_create, comment_update, notification_check, notification_dismiss,
+    notification_get_config, notification_list, notification_run_scheduled,
+    notification_update_config, partner_create, person_create, project_change_status,
+    project_create, CommentCreateReq, CommentUpdateReq, NotificationConfigReq, NotificationListReq,
+    PartnerCreateReq, PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq,
+    DEFAULT_BLOCKED_DAYS, DEFAULT_DUE_WINDOW_DAYS, MENTION_WINDOW_DAYS,
+    NOTIFICATION_KIND_BLOCKED_TOO_LONG, NOTIFICATION_KIND_DUE_SOON, NOTIFICATION_KIND_MENTIONED,
+    NOTIFICATION_KIND_OVERDUE,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::{NaiveDate, TimeZone, Utc};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn setup(pool: &DbPool) -> (String, String) {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    (owner.id, partner.id)
+}
+
+fn project(pool: &DbPool, owner: &str, partner: &str, name: &str, due: Option<&str>) -> String {
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "US".to_string(),
+            partner_id: partner.to_string(),
+            owner_person_id: owner.to_string(),
+            product_name: None,
+            start_date: None,
+            due_date: due.map(str::to_string),
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn change_status(pool: &DbPool, project_id: &str, to: &str) {
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: project_id.to_string(),
+            to_status: to.to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+}
+
+fn open_kinds(pool: &DbPool, project_id: &str) -> Vec<String> {
+    notification_list(pool, NotificationListReq::default())
+        .unwrap()
+        .into_iter()
+        .filter(|n| n.project_id == project_id)
+        .map(|n| n.kind)
+        .collect()
+}
+
+fn mention_doc(person_ids: &[&str]) -> String {
+    let nodes: Vec<_> = person_ids
+        .iter()
+        .map(|id| serde_json::json!({"type": "mention", "attrs": {"id": id, "label": id}}))
+        .collect();
+    serde_json::json!({"type": "doc", "content": [{"type": "paragraph", "content": nodes}]})
+        .to_string()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn check_creates_due_soon_and_overdue_once() {
+    let pool = init_test_db();
+    let (owner, partner) = setup(&pool);
+    let soon = project(&pool, &owner, &partner, "Soon", Some("2026-03-12"));
+    let late = project(&pool, &owner, &partner, "Late", Some("2026-03-01"));
+    let later = project(&pool, &owner, &partner, "Later", Some("2026-06-01"));
+    let done = project(&pool, &owner, &partner, "Done", Some("2026-03-01"));
+    change_status(&pool, &done, "PLANNED");
+    change_status(&pool, &done, "IN_PROGRESS");
+    change_status(&pool, &done, "DONE");
+
+    let now = Utc::now();
+    let result = notification_check(&pool, day("2026-03-10"), now).unwrap();
+    assert_eq!(result.created.len(), 2);
+    assert_eq!(open_kinds(&pool, &soon), vec![NOTIFICATION_KIND_DUE_SOON]);
+    assert_eq!(open_kinds(&pool, &late), vec![NOTIFICATION_KIND_OVERDUE]);
+    assert!(open_kinds(&pool, &later).is_empty());
+    assert!(open_kinds(&pool, &done).is_empty());
+    let overdue = result
+        .created
+        .iter()
+        .find(|n| n.project_id == late)
+        .unwrap();
+    assert_eq!(overdue.title, "Overdue: Late");
+    assert!(overdue.message.contains("9 days ago"));
+
+    // Same conditions: nothing new.
+    let again = notification_check(&pool, day("2026-03-10"), now).unwrap();
+    assert!(again.created.is_empty());
+    assert_eq!(again.resolved, 0);
+
+    // Two days later "Soon" is overdue: the due-soon reminder is closed, a new one appears.
+    let later_check = notification_check(&pool, day("2026-03-13"), now).unwrap();
+    assert_eq!(later_check.created.len(), 1);
+    assert_eq!(later_check.resolved, 1);
+    assert_eq!(open_kinds(&pool, &soon), vec![NOTIFICATION_KIND_OVERDUE]);
+}
+
+#[test]
+fn blocked_too_long_uses_last_blocked_transition() {
+    let pool = init_test_db();
+    let (owner, partner) = setup(&pool);
+    let stuck = project(&pool, &owner, &partner, "Stuck", None);
+    change_status(&pool, &stuck, "PLANNED");
+    change_status(&pool, &stuck, "IN_PROGRESS");
+    change_status(&pool, &stuck, "BLOCKED");
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE status_history SET changed_at = '2026-01-01T09:00:00+00:00' WHERE project_id = ?1 AND to_status = 'BLOCKED'",
+            [&stuck],
+        )
+        .unwrap();
+    }
+
+    let now = Utc.with_ymd_and_hms(2026, 1, 10, 9, 0, 0).unwrap();
+    let early = notification_check(&pool, day("2026-01-10"), now).unwrap();
+    assert!(early.created.is_empty());
+
+    let result = notification_check(&pool, day("2026-01-20"), now).unwrap();
+    assert_eq!(result.created.len(), 1);
+    assert_eq!(result.created[0].kind, NOTIFICATION_KIND_BLOCKED_TOO_LONG);
+    assert!(result.created[0].message.contains("19 days"));
+
+    // Unblocked: the reminder is closed automatically.
+    change_status(&pool, &stuck, "IN_PROGRESS");
+    let resolved = notification_check(&pool, day("2026-01-21"), now).unwrap();
+    assert_eq!(resolved.resolved, 1);
+    assert!(open_kinds(&pool, &stuck).is_empty());
+}
+
+#[test]
+fn dismiss_config_and_scheduled_run() {
+    let pool = init_test_db();
+    let (owner, partner) = setup(&pool);
+    let p = project(&pool, &owner, &partner, "P", Some("2026-03-15"));
+
+    let config = notification_get_config(&pool).unwrap();
+    assert!(config.enabled);
+    assert!(!config.desktop_enabled);
+    assert_eq!(config.due_window_days, DEFAULT_DUE_WINDOW_DAYS);
+    assert_eq!(config.blocked_days, DEFAULT_BLOCKED_DAYS);
+    assert!(config.last_checked_at.is_none());
+
+    // Outside the default 3-day window; inside a 7-day window.
+    let now = Utc::now();
+    assert!(notification_check(&pool, day("2026-03-10"), now)
+        .unwrap()
+        .created
+        .is_empty());
+    notification_update_config(
+        &pool,
+        NotificationConfigReq {
+            due_window_days: Some(7),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = notification_check(&pool, day("2026-03-10"), now).unwrap();
+    assert_eq!(result.created.len(), 1);
+
+    let id = result.created[0].id;
+    notification_dismiss(&pool, id).unwrap();
+    notification_dismiss(&pool, id).unwrap();
+    assert!(open_kinds(&pool, &p).is_empty());
+    let all = notification_list(
+        &pool,
+        NotificationListReq {
+            include_dismissed: Some(true),
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert!(all[0].dismissed_at.is_some());
+    // A dismissed condition is not reported again.
+    assert!(notification_check(&pool, day("2026-03-11"), now)
+        .unwrap()
+        .created
+        .is_empty());
+    assert_eq!(
+        notification_dismiss(&pool, 9999).unwrap_err().code(),
+        "NOT_FOUND"
+    );
+
+    let err = notification_update_config(
+        &pool,
+        NotificationConfigReq {
+            blocked_days: Some(0),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+
+    notification_update_config(
+        &pool,
+        NotificationConfigReq {
+            enabled: Some(false),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(notification_run_scheduled(&pool, day("2026-03-10"), now)
+        .unwrap()
+        .is_none());
+    assert!(notification_get_config(&pool)
+        .unwrap()
+        .last_checked_at
+        .is_some());
+}
+
+#[test]
+fn mentions_remind_the_mentioned_person_until_the_mention_is_removed() {
+    let pool = init_test_db();
+    let (owner, partner) = setup(&pool);
+    let bob = person_create(
+        &pool,
+        PersonCreateReq {
+            display_name: "Bob".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id;
+    let p = project(&pool, &owner, &partner, "P", None);
+    // The author mentioning themself is not reminded.
+    let comment = comment_create(
+        &pool,
+        CommentCreateReq {
+            project_id: p.clone(),
+            person_id: Some(owner.clone()),
+            content: mention_doc(&[&bob, &owner]),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+
+    let now = Utc::now();
+    let today = now.date_naive();
+    let result = notification_check(&pool, today, now).unwrap();
+    assert_eq!(result.created.len(), 1);
+    let created = &result.created[0];
+    assert_eq!(created.kind, NOTIFICATION_KIND_MENTIONED);
+    assert_eq!(created.person_id.as_deref(), Some(bob.as_str()));
+    assert_eq!(created.title, "@Bob in P");
+    assert!(created.message.starts_with("Owner: "));
+    assert!(notification_check(&pool, today, now)
+        .unwrap()
+        .created
+        .is_empty());
+
+    // Past the window the open reminder stays; nothing new for old mentions.
+    let later = today + chrono::Duration::days(MENTION_WINDOW_DAYS + 1);
+    let result = notification_check(&pool, later, now).unwrap();
+    assert!(result.created.is_empty());
+    assert_eq!(result.resolved, 0);
+    assert_eq!(open_kinds(&pool, &p), vec![NOTIFICATION_KIND_MENTIONED]);
+
+    comment_update(
+        &pool,
+        CommentUpdateReq {
+            id: comment.id,
+            content: Some(mention_doc(&[&owner])),
+            person_id: None,
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    let result = notification_check(&pool, today, now).unwrap();
+    assert_eq!(result.resolved, 1);
+    assert!(open_kinds(&pool, &p).is_empty());
+}
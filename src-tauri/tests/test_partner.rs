@@ -155,6 +155,7 @@ fn update_partner_partial_fields() {
             id: created.id.clone(),
             name: Some("Updated Name".to_string()),
             note: None, // keep original
+            changed_by_person_id: None,
         },
     )
     .unwrap();
@@ -172,6 +173,7 @@ fn update_partner_not_found() {
             id: "ghost".to_string(),
             name: Some("X".to_string()),
             note: None,
+            changed_by_person_id: None,
         },
     );
     assert!(err.is_err());
@@ -189,6 +191,7 @@ fn update_partner_empty_name_keeps_original() {
             id: created.id.clone(),
             name: Some("  ".to_string()),
             note: None,
+            changed_by_person_id: None,
         },
     )
     .unwrap();
@@ -250,6 +253,7 @@ fn partner_projects_returns_associated_projects() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -267,6 +271,7 @@ fn partner_projects_returns_associated_projects() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -309,6 +314,7 @@ fn partner_projects_does_not_include_other_partners() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -326,6 +332,7 @@ fn partner_projects_does_not_include_other_partners() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
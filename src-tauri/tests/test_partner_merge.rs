@@ -0,0 +1,128 @@
+//! Partner duplicate detection and merge
+
+use app_lib::app::{
+    partner_create, partner_deactivate, partner_find_duplicates, partner_get, partner_merge,
+    partner_projects, person_create, project_create, project_get, PartnerCreateReq,
+    PartnerFindDuplicatesReq, PartnerMergeReq, PersonCreateReq, ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn partner(pool: &DbPool, name: &str, note: Option<&str>) -> String {
+    partner_create(
+        pool,
+        PartnerCreateReq {
+            name: name.to_string(),
+            note: note.map(str::to_string),
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn project(pool: &DbPool, name: &str, partner_id: &str) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("{} owner", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner_id.to_string(),
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn merge_req(target: &str, source: &str) -> PartnerMergeReq {
+    PartnerMergeReq {
+        target_id: target.to_string(),
+        source_id: source.to_string(),
+        changed_by_person_id: None,
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn find_duplicates_ignores_case_punctuation_and_legal_form() {
+    let pool = init_test_db();
+    let acme = partner(&pool, "Acme", None);
+    let acme_inc = partner(&pool, "ACME, Inc.", None);
+    let globex = partner(&pool, "Globex Corp", None);
+    let globexx = partner(&pool, "Globexx", None);
+    partner(&pool, "Initech", None);
+
+    let pairs = partner_find_duplicates(&pool, PartnerFindDuplicatesReq::default()).unwrap();
+    let found: Vec<(&str, &str)> = pairs
+        .iter()
+        .map(|p| (p.primary.id.as_str(), p.duplicate.id.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (acme.as_str(), acme_inc.as_str()),
+            (globex.as_str(), globexx.as_str())
+        ]
+    );
+    assert_eq!(pairs[0].score, 1.0);
+    assert!(pairs[1].score < 1.0);
+
+    partner_deactivate(&pool, &acme_inc).unwrap();
+    let pairs = partner_find_duplicates(&pool, PartnerFindDuplicatesReq::default()).unwrap();
+    assert_eq!(pairs.len(), 1);
+}
+
+#[test]
+fn merge_moves_projects_and_deactivates_source() {
+    let pool = init_test_db();
+    let target = partner(&pool, "Acme", None);
+    let source = partner(&pool, "Acme Inc", Some("billing via HQ"));
+    let a = project(&pool, "Alpha", &source);
+    let b = project(&pool, "Beta", &source);
+    project(&pool, "Gamma", &target);
+
+    let result = partner_merge(&pool, merge_req(&target, &source)).unwrap();
+    assert_eq!(result.projects_moved, 2);
+    assert_eq!(result.target.note, "billing via HQ");
+    assert_eq!(project_get(&pool, &a).unwrap().partner_id, target);
+    assert_eq!(project_get(&pool, &b).unwrap().partner_id, target);
+    assert_eq!(partner_projects(&pool, &target).unwrap().len(), 3);
+    assert!(partner_projects(&pool, &source).unwrap().is_empty());
+    assert!(!partner_get(&pool, &source).unwrap().is_active);
+    assert!(partner_get(&pool, &target).unwrap().is_active);
+}
+
+#[test]
+fn merge_rejects_self_and_missing_partners() {
+    let pool = init_test_db();
+    let acme = partner(&pool, "Acme", None);
+    let a = project(&pool, "Alpha", &acme);
+
+    let err = partner_merge(&pool, merge_req(&acme, &acme)).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err = partner_merge(&pool, merge_req("missing", &acme)).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+    assert_eq!(project_get(&pool, &a).unwrap().partner_id, acme);
+    assert!(partner_get(&pool, &acme).unwrap().is_active);
+}
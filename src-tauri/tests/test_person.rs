@@ -164,6 +164,7 @@ fn update_person_partial_fields() {
             email: None, // keep original
             role: Some("lead".to_string()),
             note: None,
+            changed_by_person_id: None,
         },
     )
     .unwrap();
@@ -184,6 +185,7 @@ fn update_person_not_found() {
             email: None,
             role: None,
             note: None,
+            changed_by_person_id: None,
         },
     );
     assert!(err.is_err());
@@ -204,6 +206,7 @@ fn update_person_empty_name_keeps_original() {
             email: None,
             role: None,
             note: None,
+            changed_by_person_id: None,
         },
     )
     .unwrap();
@@ -270,6 +273,7 @@ fn seed_project_for_person(pool: &app_lib::infra::DbPool, owner_id: &str) -> Str
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -306,6 +310,7 @@ fn current_projects_excludes_archived() {
             note: Some("done".to_string()),
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -0,0 +1,282 @@
+//! Person duplicate detection and merge
+
+use app_lib::app::{
+    assignment_add_member, comment_create, partner_create, person_create, person_deactivate,
+    person_find_duplicates, person_get, person_merge, project_change_status, project_create,
+    AssignmentAddReq, CommentCreateReq, PartnerCreateReq, PersonCreateReq, PersonFindDuplicatesReq,
+    PersonMergeReq, ProjectChangeStatusReq, ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn person(pool: &DbPool, name: &str, email: Option<&str>) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: email.map(str::to_string),
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn project(pool: &DbPool, name: &str, owner: &str) -> String {
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("{} partner", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.to_string(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn merge_req(primary: &str, duplicate: &str) -> PersonMergeReq {
+    PersonMergeReq {
+        primary_id: primary.to_string(),
+        duplicate_id: duplicate.to_string(),
+        changed_by_person_id: None,
+    }
+}
+
+fn count(pool: &DbPool, sql: &str, id: &str) -> i64 {
+    pool.0
+        .lock()
+        .unwrap()
+        .query_row(sql, [id], |r| r.get(0))
+        .unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn find_duplicates_matches_email_and_names() {
+    let pool = init_test_db();
+    let li = person(&pool, "Li Wei", None);
+    let li2 = person(&pool, "wei, LI", None);
+    let jon = person(&pool, "Jon Smith", None);
+    let john = person(&pool, "John Smith", None);
+    let ann = person(&pool, "Ann", Some("ann@example.com"));
+    let anna = person(&pool, "A. Berg", Some(" ANN@example.com"));
+    person(&pool, "Zed", None);
+
+    let pairs = person_find_duplicates(&pool, PersonFindDuplicatesReq::default()).unwrap();
+    let found: Vec<(&str, &str, &Vec<String>)> = pairs
+        .iter()
+        .map(|p| (p.primary.id.as_str(), p.duplicate.id.as_str(), &p.reasons))
+        .collect();
+    assert_eq!(found.len(), 3, "{:?}", found);
+    assert!(found.contains(&(li.as_str(), li2.as_str(), &vec!["name".to_string()])));
+    assert!(found.contains(&(ann.as_str(), anna.as_str(), &vec!["email".to_string()])));
+    let similar = pairs.iter().find(|p| p.primary.id == jon).unwrap();
+    assert_eq!(similar.duplicate.id, john);
+    assert_eq!(similar.reasons, vec!["similar_name".to_string()]);
+    assert!(similar.score < 1.0 && similar.score >= 0.8);
+    assert_eq!(pairs.last().unwrap().primary.id, jon, "sorted by score");
+
+    // Deactivated persons are skipped unless requested; the threshold is validated.
+    person_deactivate(&pool, &john).unwrap();
+    let pairs = person_find_duplicates(&pool, PersonFindDuplicatesReq::default()).unwrap();
+    assert_eq!(pairs.len(), 2);
+    let pairs = person_find_duplicates(
+        &pool,
+        PersonFindDuplicatesReq {
+            threshold: Some(0.95),
+            include_inactive: Some(true),
+        },
+    )
+    .unwrap();
+    assert_eq!(pairs.len(), 2);
+    let err = person_find_duplicates(
+        &pool,
+        PersonFindDuplicatesReq {
+            threshold: Some(1.5),
+            include_inactive: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
+
+#[test]
+fn merge_moves_references_and_deactivates_duplicate() {
+    let pool = init_test_db();
+    let primary = person(&pool, "Alice", None);
+    let duplicate = person(&pool, "alice", Some("alice@example.com"));
+    let shared = project(&pool, "Shared", &primary);
+    let owned = project(&pool, "Owned", &duplicate);
+    assignment_add_member(
+        &pool,
+        AssignmentAddReq {
+            project_id: shared.clone(),
+            person_id: duplicate.clone(),
+            role: None,
+            start_at: None,
+        },
+    )
+    .unwrap();
+    comment_create(
+        &pool,
+        CommentCreateReq {
+            project_id: shared.clone(),
+            person_id: Some(duplicate.clone()),
+            content: "hi".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: owned.clone(),
+            to_status: "PLANNED".to_string(),
+            note: None,
+            changed_by_person_id: Some(duplicate.clone()),
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+
+    let result = person_merge(&pool, merge_req(&primary, &duplicate)).unwrap();
+    assert_eq!(result.projects_owned, 1);
+    // Owner assignment on "Owned" + member assignment on "Shared".
+    assert_eq!(result.assignments, 2);
+    assert_eq!(result.assignments_ended, 1);
+    assert_eq!(result.comments, 1);
+    assert_eq!(result.status_history, 1);
+    assert_eq!(result.primary.email, "alice@example.com");
+    assert_eq!(result.primary.display_name, "Alice");
+
+    for sql in [
+        "SELECT COUNT(*) FROM projects WHERE owner_person_id = ?1",
+        "SELECT COUNT(*) FROM assignments WHERE person_id = ?1",
+        "SELECT COUNT(*) FROM project_comments WHERE person_id = ?1",
+        "SELECT COUNT(*) FROM status_history WHERE changed_by_person_id = ?1",
+    ] {
+        assert_eq!(count(&pool, sql, &duplicate), 0, "{}", sql);
+    }
+    assert_eq!(
+        count(
+            &pool,
+            "SELECT COUNT(*) FROM assignments WHERE person_id = ?1 AND end_at IS NULL",
+            &primary
+        ),
+        2
+    );
+    assert!(!person_get(&pool, &duplicate).unwrap().is_active);
+}
+
+#[test]
+fn merge_points_mentions_at_primary() {
+    let pool = init_test_db();
+    let primary = person(&pool, "Alice", None);
+    let duplicate = person(&pool, "alice", None);
+    let id = project(&pool, "Alpha", &primary);
+    let content = format!(
+        r#"{{"type":"doc","content":[{{"type":"paragraph","content":[{{"type":"mention","attrs":{{"id":"{}","label":"alice"}}}}]}}]}}"#,
+        duplicate
+    );
+    let comment = comment_create(
+        &pool,
+        CommentCreateReq {
+            project_id: id,
+            person_id: None,
+            content,
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+
+    let result = person_merge(&pool, merge_req(&primary, &duplicate)).unwrap();
+    assert_eq!(result.comment_mentions, 1);
+    let content: String = pool
+        .0
+        .lock()
+        .unwrap()
+        .query_row(
+            "SELECT content FROM project_comments WHERE id = ?1",
+            [&comment.id],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert!(content.contains(&format!(r#""id":"{}""#, primary)));
+    assert!(content.contains(r#""label":"Alice""#));
+    let sql = "SELECT COUNT(*) FROM comment_mentions WHERE person_id = ?1";
+    assert_eq!(count(&pool, sql, &duplicate), 0);
+    assert_eq!(count(&pool, sql, &primary), 1);
+}
+
+#[test]
+fn merge_rejects_self_and_missing_persons() {
+    let pool = init_test_db();
+    let alice = person(&pool, "Alice", None);
+
+    let err = person_merge(&pool, merge_req(&alice, &alice)).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err = person_merge(&pool, merge_req(&alice, "missing")).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+    assert!(person_get(&pool, &alice).unwrap().is_active);
+}
+
+#[test]
+fn merge_records_history_updates_for_sync() {
+    let pool = init_test_db();
+    let primary = person(&pool, "Alice", None);
+    let duplicate = person(&pool, "Alice B", None);
+    let id = project(&pool, "Alpha", &primary);
+    project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: id,
+            to_status: "PLANNED".to_string(),
+            note: None,
+            changed_by_person_id: Some(duplicate.clone()),
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+    pool.0
+        .lock()
+        .unwrap()
+        .execute(
+            "INSERT OR REPLACE INTO sync_config (key, value) VALUES ('sync_enabled', '1')",
+            [],
+        )
+        .unwrap();
+
+    person_merge(&pool, merge_req(&primary, &duplicate)).unwrap();
+    let updates = count(
+        &pool,
+        "SELECT COUNT(*) FROM sync_metadata
+         WHERE table_name = 'status_history' AND operation = 'UPDATE'
+           AND json_extract(data_snapshot, '$.changed_by_person_id') = ?1",
+        &primary,
+    );
+    assert_eq!(updates, 1);
+}
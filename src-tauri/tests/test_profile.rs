@@ -0,0 +1,171 @@
+//! Profile management tests (list, create, delete guards)
+
+use app_lib::app::{
+    profile_create, profile_current, profile_delete, profile_list, profile_switch_target,
+    ProfileContext, ProfileCreateReq, ProfileDeleteReq, ProfileSwitchReq,
+};
+use app_lib::infra::layout::{location_file_for, write_data_dir_location, DataDirLocation};
+use fs2::FileExt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn temp_base() -> PathBuf {
+    let base = std::env::temp_dir().join(format!("projex-profile-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(base.join("profiles").join("default")).unwrap();
+    base
+}
+
+fn ctx<'a>(base: &'a Path, current_dir: &'a Path) -> ProfileContext<'a> {
+    ProfileContext {
+        base_dir: base,
+        current_profile: "default",
+        current_data_dir: current_dir,
+    }
+}
+
+fn create(ctx: &ProfileContext<'_>, name: &str) -> Result<String, String> {
+    profile_create(
+        ctx,
+        ProfileCreateReq {
+            name: name.to_string(),
+        },
+    )
+    .map(|p| p.name)
+    .map_err(|e| e.code().to_string())
+}
+
+fn delete(ctx: &ProfileContext<'_>, name: &str) -> Result<(), String> {
+    profile_delete(
+        ctx,
+        ProfileDeleteReq {
+            name: name.to_string(),
+        },
+    )
+    .map_err(|e| e.code().to_string())
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn list_and_create_profiles() {
+    let base = temp_base();
+    let current_dir = base.join("profiles").join("default");
+    let ctx = ctx(&base, &current_dir);
+    // Staging dirs and stray files are not profiles.
+    fs::create_dir_all(base.join("profiles").join(".default.migrating")).unwrap();
+    fs::write(base.join("profiles").join("notes.txt"), b"x").unwrap();
+
+    assert_eq!(create(&ctx, " Work_1 "), Ok("work_1".to_string()));
+    assert_eq!(create(&ctx, "work_1"), Err("CONFLICT".to_string()));
+    assert_eq!(create(&ctx, "default"), Err("CONFLICT".to_string()));
+    for invalid in ["", "../prod", "-x", "a b"] {
+        assert_eq!(create(&ctx, invalid), Err("VALIDATION_ERROR".to_string()));
+    }
+
+    // A profile moved to a custom dir is known only by its pointer file.
+    let custom = base.join("elsewhere");
+    write_data_dir_location(
+        &location_file_for(&base.join("profiles").join("moved")),
+        &DataDirLocation {
+            data_dir: Some(custom.clone()),
+            pending_cleanup: None,
+        },
+    )
+    .unwrap();
+
+    let profiles = profile_list(&ctx).unwrap();
+    let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["default", "moved", "work_1"]);
+    assert!(profiles[0].is_current && profiles[0].in_use);
+    assert!(profiles[1].is_custom_dir);
+    assert_eq!(profiles[1].data_dir, custom.display().to_string());
+    assert!(!profiles[2].in_use);
+
+    let current = profile_current(&ctx).unwrap();
+    assert_eq!(current.name, "default");
+    assert_eq!(current.data_dir, current_dir.display().to_string());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn delete_guards_current_default_and_locked_profiles() {
+    let base = temp_base();
+    let current_dir = base.join("profiles").join("default");
+    let ctx = ctx(&base, &current_dir);
+    create(&ctx, "work").unwrap();
+    let work_dir = base.join("profiles").join("work");
+    fs::create_dir_all(work_dir.join("logs")).unwrap();
+    fs::write(work_dir.join("app.db"), b"db").unwrap();
+
+    assert_eq!(delete(&ctx, "default"), Err("CONFLICT".to_string()));
+    assert_eq!(delete(&ctx, "missing"), Err("NOT_FOUND".to_string()));
+
+    let lock = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .read(true)
+        .write(true)
+        .open(work_dir.join("app.lock"))
+        .unwrap();
+    lock.try_lock_exclusive().unwrap();
+    assert_eq!(delete(&ctx, "work"), Err("CONFLICT".to_string()));
+    assert!(work_dir.join("app.db").exists());
+    drop(lock);
+
+    delete(&ctx, "work").unwrap();
+    assert!(!work_dir.exists());
+    let names: Vec<String> = profile_list(&ctx)
+        .unwrap()
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    assert_eq!(names, vec!["default"]);
+
+    // The default profile is the launch fallback even when another one is running.
+    let other = ProfileContext {
+        current_profile: "work",
+        ..ctx
+    };
+    assert_eq!(
+        delete(&other, "default"),
+        Err("VALIDATION_ERROR".to_string())
+    );
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn switch_target_must_exist_and_be_free() {
+    let base = temp_base();
+    let current_dir = base.join("profiles").join("default");
+    let ctx = ctx(&base, &current_dir);
+    let target = |name: &str| {
+        profile_switch_target(
+            &ctx,
+            &ProfileSwitchReq {
+                name: name.to_string(),
+            },
+        )
+        .map_err(|e| e.code().to_string())
+    };
+
+    assert_eq!(target("Default"), Ok(None));
+    assert_eq!(target("work"), Err("NOT_FOUND".to_string()));
+    assert_eq!(target("../x"), Err("VALIDATION_ERROR".to_string()));
+
+    create(&ctx, "work").unwrap();
+    assert_eq!(target(" WORK "), Ok(Some("work".to_string())));
+
+    let lock = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .read(true)
+        .write(true)
+        .open(base.join("profiles").join("work").join("app.lock"))
+        .unwrap();
+    lock.try_lock_exclusive().unwrap();
+    assert_eq!(target("work"), Err("CONFLICT".to_string()));
+    drop(lock);
+    let _ = fs::remove_dir_all(&base);
+}
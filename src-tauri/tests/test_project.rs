@@ -1,9 +1,10 @@
 //! Project CRUD + status machine integration tests
 
 use app_lib::app::{
-    partner_create, person_create, project_change_status, project_create, project_get,
-    project_list, project_update, PartnerCreateReq, PersonCreateReq, ProjectChangeStatusReq,
-    ProjectCreateReq, ProjectListReq, ProjectUpdateReq,
+    assignment_add_member, assignment_end_member, assignment_list_by_project, partner_create,
+    person_create, project_change_status, project_create, project_get, project_list,
+    project_update, AssignmentAddReq, AssignmentEndReq, PartnerCreateReq, PersonCreateReq,
+    ProjectChangeStatusReq, ProjectCreateReq, ProjectListReq, ProjectUpdateReq,
 };
 use app_lib::infra::db::init_test_db;
 
@@ -52,6 +53,7 @@ fn make_project_req(ids: &TestSeedIds, name: &str) -> ProjectCreateReq {
         due_date: Some("2026-12-31".to_string()),
         tags: Some(vec!["tag1".to_string(), "tag2".to_string()]),
         created_by_person_id: Some(ids.person_id.clone()),
+        parent_project_id: None,
     }
 }
 
@@ -147,6 +149,43 @@ fn create_project_country_code_uppercased() {
     req.country_code = "jp".to_string();
     let proj = project_create(&pool, req).unwrap();
     assert_eq!(proj.country_code, "JP");
+    assert_eq!(proj.country_name, "Japan");
+
+    let page = project_list(&pool, ProjectListReq::default()).unwrap();
+    assert_eq!(page.items[0].country_name, "Japan");
+}
+
+#[test]
+fn create_and_update_reject_unknown_country_code() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let mut req = make_project_req(&ids, "Nowhere");
+    req.country_code = "XX".to_string();
+    let err = project_create(&pool, req).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert!(err.to_string().contains("COUNTRY_INVALID"));
+
+    let proj = project_create(&pool, make_project_req(&ids, "Somewhere")).unwrap();
+    let err = project_update(
+        &pool,
+        ProjectUpdateReq {
+            id: proj.id.clone(),
+            name: None,
+            description: None,
+            priority: None,
+            country_code: Some("ZZ".to_string()),
+            owner_person_id: None,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            partner_id: None,
+            parent_project_id: None,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("COUNTRY_INVALID"));
 }
 
 #[test]
@@ -194,6 +233,8 @@ fn update_project_partial_fields() {
             due_date: None,
             tags: None,
             partner_id: None,
+            parent_project_id: None,
+            changed_by_person_id: None,
         },
     )
     .unwrap();
@@ -224,6 +265,8 @@ fn update_project_partner_id_immutable() {
             due_date: None,
             tags: None,
             partner_id: Some("new-partner-id".to_string()),
+            parent_project_id: None,
+            changed_by_person_id: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "PARTNER_IMMUTABLE");
@@ -261,6 +304,8 @@ fn update_project_owner_change_demotes_old_owner() {
             due_date: None,
             tags: None,
             partner_id: None,
+            parent_project_id: None,
+            changed_by_person_id: None,
         },
     )
     .unwrap();
@@ -303,6 +348,8 @@ fn update_project_tags_replaced() {
             due_date: None,
             tags: Some(vec!["new-tag".to_string()]),
             partner_id: None,
+            parent_project_id: None,
+            changed_by_person_id: None,
         },
     )
     .unwrap();
@@ -342,6 +389,7 @@ fn list_projects_excludes_archived_by_default() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -353,6 +401,7 @@ fn list_projects_excludes_archived_by_default() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -364,6 +413,7 @@ fn list_projects_excludes_archived_by_default() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -375,6 +425,7 @@ fn list_projects_excludes_archived_by_default() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -426,6 +477,7 @@ fn change_status_normal_flow() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -440,6 +492,7 @@ fn change_status_normal_flow() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -454,6 +507,7 @@ fn change_status_normal_flow() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -468,6 +522,7 @@ fn change_status_normal_flow() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -490,6 +545,7 @@ fn change_status_invalid_transition_rejected() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "INVALID_STATUS_TRANSITION");
@@ -509,6 +565,7 @@ fn change_status_unknown_status_rejected() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "INVALID_STATUS_TRANSITION");
@@ -529,6 +586,7 @@ fn change_status_note_required_for_abandon() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "NOTE_REQUIRED");
@@ -542,6 +600,7 @@ fn change_status_note_required_for_abandon() {
             note: Some("Abandoned due to budget cuts".to_string()),
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -563,6 +622,7 @@ fn change_status_note_required_for_unarchive() {
             note: Some("archive reason".to_string()),
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -576,6 +636,7 @@ fn change_status_note_required_for_unarchive() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "NOTE_REQUIRED");
@@ -589,6 +650,7 @@ fn change_status_note_required_for_unarchive() {
             note: Some("Reviving project".to_string()),
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -610,6 +672,7 @@ fn change_status_note_required_for_rework() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -621,6 +684,7 @@ fn change_status_note_required_for_rework() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -632,6 +696,7 @@ fn change_status_note_required_for_rework() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -645,6 +710,7 @@ fn change_status_note_required_for_rework() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "NOTE_REQUIRED");
@@ -658,6 +724,7 @@ fn change_status_note_required_for_rework() {
             note: Some("Found bugs".to_string()),
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -679,6 +746,7 @@ fn change_status_blocked_and_unblocked() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -690,6 +758,7 @@ fn change_status_blocked_and_unblocked() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -703,6 +772,7 @@ fn change_status_blocked_and_unblocked() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -717,6 +787,7 @@ fn change_status_blocked_and_unblocked() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -737,6 +808,7 @@ fn change_status_optimistic_lock_conflict() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: Some("1970-01-01T00:00:00Z".to_string()), // stale
+            force: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "CONFLICT");
@@ -756,6 +828,7 @@ fn change_status_records_history() {
             note: Some("Planning started".to_string()),
             changed_by_person_id: Some(ids.person_id.clone()),
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -782,11 +855,128 @@ fn change_status_not_found_project() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "NOT_FOUND");
 }
 
+#[test]
+fn archive_ends_active_assignments_and_unarchive_restores_them() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let project = project_create(&pool, make_project_req(&ids, "Sunset")).unwrap();
+    let member = person_create(
+        &pool,
+        PersonCreateReq {
+            display_name: "Member".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let former = person_create(
+        &pool,
+        PersonCreateReq {
+            display_name: "Former".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    for person_id in [&member.id, &former.id] {
+        assignment_add_member(
+            &pool,
+            AssignmentAddReq {
+                project_id: project.id.clone(),
+                person_id: person_id.clone(),
+                role: None,
+                start_at: None,
+            },
+        )
+        .unwrap();
+    }
+    assignment_end_member(
+        &pool,
+        AssignmentEndReq {
+            project_id: project.id.clone(),
+            person_id: former.id.clone(),
+            end_at: Some("2026-02-01T00:00:00Z".to_string()),
+        },
+    )
+    .unwrap();
+
+    let archived = project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: project.id.clone(),
+            to_status: "ARCHIVED".to_string(),
+            note: Some("abandoned".to_string()),
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+    let archived_at = archived.archived_at.clone().unwrap();
+    let assignments = assignment_list_by_project(&pool, &project.id).unwrap();
+    assert!(assignments.iter().all(|a| a.end_at.is_some()));
+    let auto_ended = assignments
+        .iter()
+        .filter(|a| a.end_at.as_deref() == Some(archived_at.as_str()))
+        .count();
+    assert_eq!(auto_ended, 2); // owner + member; "former" keeps its own end date
+    let entry = archived
+        .status_history
+        .iter()
+        .find(|h| h.to_status == "ARCHIVED")
+        .unwrap();
+    assert_eq!(entry.note, "abandoned\n[auto] ended 2 active assignment(s)");
+
+    // Meanwhile the member is re-added: unarchive must not duplicate that assignment.
+    assignment_add_member(
+        &pool,
+        AssignmentAddReq {
+            project_id: project.id.clone(),
+            person_id: member.id.clone(),
+            role: None,
+            start_at: None,
+        },
+    )
+    .unwrap();
+
+    let restored = project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: project.id.clone(),
+            to_status: "BACKLOG".to_string(),
+            note: Some("revived".to_string()),
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+    let active: Vec<String> = assignment_list_by_project(&pool, &project.id)
+        .unwrap()
+        .into_iter()
+        .filter(|a| a.end_at.is_none())
+        .map(|a| a.person_id)
+        .collect();
+    assert_eq!(active.len(), 2);
+    assert!(active.contains(&ids.person_id));
+    assert!(active.contains(&member.id));
+    assert!(!active.contains(&former.id));
+    let entry = restored
+        .status_history
+        .iter()
+        .find(|h| h.to_status == "BACKLOG" && h.from_status.is_some())
+        .unwrap();
+    assert_eq!(entry.note, "revived\n[auto] restored 1 assignment(s)");
+}
+
 // ══════════════════════════════════════════════════════════
 //  project_update — 补充
 // ══════════════════════════════════════════════════════════
@@ -808,6 +998,8 @@ fn update_project_not_found() {
             due_date: None,
             tags: None,
             partner_id: None,
+            parent_project_id: None,
+            changed_by_person_id: None,
         },
     );
     assert_eq!(err.unwrap_err().code(), "NOT_FOUND");
@@ -889,6 +1081,123 @@ fn list_projects_limit_clamped() {
     assert_eq!(page.limit, 200); // clamped
 }
 
+#[test]
+fn list_projects_with_cursor_walks_all_pages() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    for i in 0..5 {
+        project_create(&pool, make_project_req(&ids, &format!("C{}", i))).unwrap();
+    }
+    let all: Vec<String> = project_list(&pool, ProjectListReq::default())
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|p| p.id)
+        .collect();
+
+    let mut seen = Vec::new();
+    let mut cursor: (Option<String>, Option<String>) = (None, None);
+    loop {
+        let page = project_list(
+            &pool,
+            ProjectListReq {
+                limit: Some(2),
+                after_updated_at: cursor.0.clone(),
+                after_id: cursor.1.clone(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(page.offset, 0);
+        seen.extend(page.items.into_iter().map(|p| p.id));
+        if page.next_after_id.is_none() {
+            assert!(page.next_after_updated_at.is_none());
+            break;
+        }
+        cursor = (page.next_after_updated_at, page.next_after_id);
+    }
+    assert_eq!(seen, all);
+}
+
+#[test]
+fn list_projects_cursor_is_stable_under_updates() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    for i in 0..4 {
+        project_create(&pool, make_project_req(&ids, &format!("S{}", i))).unwrap();
+    }
+    let first = project_list(
+        &pool,
+        ProjectListReq {
+            limit: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Touching an already-seen project moves it to the top, not into the next page.
+    let seen = first.items[1].id.clone();
+    project_update(
+        &pool,
+        ProjectUpdateReq {
+            id: seen.clone(),
+            name: Some("Renamed".to_string()),
+            description: None,
+            priority: None,
+            country_code: None,
+            owner_person_id: None,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            partner_id: None,
+            parent_project_id: None,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap();
+
+    let second = project_list(
+        &pool,
+        ProjectListReq {
+            limit: Some(2),
+            after_updated_at: first.next_after_updated_at,
+            after_id: first.next_after_id,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(second.items.len(), 2);
+    assert!(second.items.iter().all(|p| p.id != seen));
+    assert!(second.next_after_id.is_none());
+}
+
+#[test]
+fn list_projects_cursor_validation() {
+    let pool = init_test_db();
+
+    let half = project_list(
+        &pool,
+        ProjectListReq {
+            after_id: Some("x".to_string()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(half.unwrap_err().code(), "VALIDATION_ERROR");
+
+    let wrong_sort = project_list(
+        &pool,
+        ProjectListReq {
+            sort_by: Some("priority".to_string()),
+            after_updated_at: Some("2026-01-01T00:00:00Z".to_string()),
+            after_id: Some("x".to_string()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(wrong_sort.unwrap_err().code(), "VALIDATION_ERROR");
+}
+
 // ══════════════════════════════════════════════════════════
 //  project_get — 孤立 FK (owner/partner 被删除)
 // ══════════════════════════════════════════════════════════
@@ -935,6 +1244,7 @@ fn list_filter_by_statuses() {
             note: None,
             changed_by_person_id: None,
             if_match_updated_at: None,
+            force: None,
         },
     )
     .unwrap();
@@ -997,6 +1307,7 @@ fn list_filter_by_country_codes() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1041,6 +1352,7 @@ fn list_filter_by_partner_ids() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1087,6 +1399,7 @@ fn list_filter_by_owner_person_ids() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1170,6 +1483,7 @@ fn list_filter_by_tags() {
             start_date: None,
             due_date: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1218,6 +1532,7 @@ fn list_combined_filters() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1259,6 +1574,7 @@ fn list_sort_by_priority() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1276,6 +1592,7 @@ fn list_sort_by_priority() {
             due_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1312,6 +1629,7 @@ fn list_sort_by_due_date() {
             start_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1329,6 +1647,7 @@ fn list_sort_by_due_date() {
             start_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
@@ -1346,6 +1665,7 @@ fn list_sort_by_due_date() {
             start_date: None,
             tags: None,
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
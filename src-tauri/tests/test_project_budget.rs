@@ -0,0 +1,144 @@
+//! Project estimate / budget tests: validation, updates and JSON round trip
+
+use app_lib::app::{
+    export_json_string, import_json_string, partner_create, person_create, project_create,
+    project_get, project_set_budget, PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+    ProjectSetBudgetReq, MAX_ESTIMATED_HOURS,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn create(pool: &DbPool, name: &str) -> String {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn set_budget(
+    pool: &DbPool,
+    id: &str,
+    estimated_hours: Option<f64>,
+    budget_amount: Option<f64>,
+) -> Result<(Option<f64>, Option<f64>), String> {
+    project_set_budget(
+        pool,
+        ProjectSetBudgetReq {
+            project_id: id.to_string(),
+            estimated_hours,
+            budget_amount,
+            budget_currency: None,
+        },
+    )
+    .map(|p| (p.estimated_hours, p.budget_amount))
+    .map_err(|e| e.code().to_string())
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn set_and_clear_estimate_and_budget() {
+    let pool = init_test_db();
+    let id = create(&pool, "Alpha");
+    let detail = project_get(&pool, &id).unwrap();
+    assert_eq!(detail.estimated_hours, None);
+    assert_eq!(detail.budget_amount, None);
+
+    assert_eq!(
+        set_budget(&pool, &id, Some(120.5), Some(25_000.0)),
+        Ok((Some(120.5), Some(25_000.0)))
+    );
+    let updated_at = project_get(&pool, &id).unwrap().updated_at;
+
+    // Same values: nothing written.
+    set_budget(&pool, &id, Some(120.5), Some(25_000.0)).unwrap();
+    assert_eq!(project_get(&pool, &id).unwrap().updated_at, updated_at);
+
+    // Both values are replaced: None clears.
+    assert_eq!(
+        set_budget(&pool, &id, None, Some(30_000.0)),
+        Ok((None, Some(30_000.0)))
+    );
+    assert_eq!(set_budget(&pool, &id, None, None), Ok((None, None)));
+}
+
+#[test]
+fn set_budget_rejects_invalid_values() {
+    let pool = init_test_db();
+    let id = create(&pool, "Alpha");
+    for (hours, budget) in [
+        (Some(-1.0), None),
+        (None, Some(-0.01)),
+        (Some(f64::NAN), None),
+        (None, Some(f64::INFINITY)),
+        (Some(MAX_ESTIMATED_HOURS + 1.0), None),
+    ] {
+        assert_eq!(
+            set_budget(&pool, &id, hours, budget),
+            Err("VALIDATION_ERROR".to_string())
+        );
+    }
+    assert_eq!(
+        set_budget(&pool, "missing", Some(1.0), None),
+        Err("NOT_FOUND".to_string())
+    );
+    // Zero is a valid estimate.
+    assert_eq!(
+        set_budget(&pool, &id, Some(0.0), Some(0.0)),
+        Ok((Some(0.0), Some(0.0)))
+    );
+}
+
+#[test]
+fn estimate_and_budget_survive_json_round_trip() {
+    let pool = init_test_db();
+    let id = create(&pool, "Alpha");
+    set_budget(&pool, &id, Some(40.0), Some(9_999.99)).unwrap();
+
+    let json = export_json_string(&pool, None).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let project = &value["projects"][0];
+    assert_eq!(project["estimatedHours"], 40.0);
+    assert_eq!(project["budgetAmount"], 9_999.99);
+
+    let other = init_test_db();
+    import_json_string(&other, &json).unwrap();
+    let detail = project_get(&other, &id).unwrap();
+    assert_eq!(detail.estimated_hours, Some(40.0));
+    assert_eq!(detail.budget_amount, Some(9_999.99));
+}
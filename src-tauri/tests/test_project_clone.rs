@@ -0,0 +1,223 @@
+//! Project clone tests
+
+use app_lib::app::{
+    assignment_add_member, comment_create, comment_list_by_project, partner_create, person_create,
+    person_deactivate, project_change_status, project_clone, project_create, AssignmentAddReq,
+    CommentCreateReq, PartnerCreateReq, PersonCreateReq, ProjectChangeStatusReq, ProjectCloneReq,
+    ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn person(pool: &DbPool, name: &str) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+/// Project "Launch" (starts 2026-03-01, in PLANNED) owned by Owner, with Member as "qa",
+/// a tag and a pinned milestone comment. Returns (project_id, member_id).
+fn seed_project(pool: &DbPool) -> (String, String) {
+    let owner = person(pool, "Owner");
+    let member = person(pool, "Member");
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Launch".to_string(),
+            description: Some("go live".to_string()),
+            priority: Some(2),
+            country_code: "DE".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner,
+            product_name: Some("Widget".to_string()),
+            start_date: Some("2026-03-01".to_string()),
+            due_date: Some("2026-04-01".to_string()),
+            tags: Some(vec!["launch".to_string()]),
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+    assignment_add_member(
+        pool,
+        AssignmentAddReq {
+            project_id: project.id.clone(),
+            person_id: member.clone(),
+            role: Some("qa".to_string()),
+            start_at: None,
+        },
+    )
+    .unwrap();
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: project.id.clone(),
+            person_id: None,
+            content: "Milestones:\n- Beta (2026-03-10)\n- GA (2026-03-20)".to_string(),
+            is_pinned: Some(true),
+        },
+    )
+    .unwrap();
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: project.id.clone(),
+            person_id: None,
+            content: "discussion".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: project.id.clone(),
+            to_status: "PLANNED".to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+    (project.id, member)
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn clone_copies_fields_tags_members_and_milestones() {
+    let pool = init_test_db();
+    let (project_id, member) = seed_project(&pool);
+
+    let copy = project_clone(
+        &pool,
+        ProjectCloneReq {
+            project_id: project_id.clone(),
+            start_date: Some("2026-05-01".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_ne!(copy.id, project_id);
+    assert_eq!(copy.name, "Launch (copy)");
+    assert_eq!(copy.current_status, "BACKLOG");
+    assert_eq!(copy.status_history.len(), 1);
+    assert_eq!(copy.cloned_from_id.as_deref(), Some(project_id.as_str()));
+    assert_eq!(copy.description, "go live");
+    assert_eq!(copy.priority, 2);
+    assert_eq!(copy.product_name.as_deref(), Some("Widget"));
+    assert_eq!(copy.start_date.as_deref(), Some("2026-05-01"));
+    assert_eq!(copy.due_date, None);
+    assert_eq!(copy.tags, vec!["launch".to_string()]);
+    assert!(copy
+        .assignments
+        .iter()
+        .any(|a| a.person_id == member && a.role == "qa" && a.end_at.is_none()));
+    assert!(copy.assignments.iter().any(|a| a.role == "owner"));
+
+    // Only the milestone comment is copied, shifted with the start date.
+    let comments = comment_list_by_project(&pool, copy.id.clone()).unwrap();
+    assert_eq!(comments.len(), 1);
+    assert!(comments[0].is_pinned);
+    assert_eq!(
+        comments[0].content,
+        "Milestones:\n- Beta (2026-05-10)\n- GA (2026-05-20)"
+    );
+
+    // A second default clone gets a numbered name.
+    let again = project_clone(
+        &pool,
+        ProjectCloneReq {
+            project_id,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(again.name, "Launch (copy 2)");
+    let comments = comment_list_by_project(&pool, again.id).unwrap();
+    assert_eq!(
+        comments[0].content,
+        "Milestones:\n- Beta (2026-03-10)\n- GA (2026-03-20)"
+    );
+}
+
+#[test]
+fn clone_options_exclude_tags_members_and_milestones() {
+    let pool = init_test_db();
+    let (project_id, member) = seed_project(&pool);
+
+    let copy = project_clone(
+        &pool,
+        ProjectCloneReq {
+            project_id,
+            name: Some("Relaunch".to_string()),
+            include_tags: Some(false),
+            include_members: Some(false),
+            include_milestones: Some(false),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(copy.name, "Relaunch");
+    assert!(copy.tags.is_empty());
+    assert_eq!(copy.assignments.len(), 1);
+    assert!(!copy.assignments.iter().any(|a| a.person_id == member));
+    assert!(comment_list_by_project(&pool, copy.id).unwrap().is_empty());
+}
+
+#[test]
+fn clone_skips_inactive_members_and_rejects_bad_input() {
+    let pool = init_test_db();
+    let (project_id, member) = seed_project(&pool);
+    person_deactivate(&pool, &member).unwrap();
+
+    let copy = project_clone(
+        &pool,
+        ProjectCloneReq {
+            project_id: project_id.clone(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(!copy.assignments.iter().any(|a| a.person_id == member));
+
+    let err = project_clone(
+        &pool,
+        ProjectCloneReq {
+            project_id: project_id.clone(),
+            name: Some("launch".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "CONFLICT");
+
+    let err = project_clone(
+        &pool,
+        ProjectCloneReq {
+            project_id: "missing".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
@@ -0,0 +1,174 @@
+//! Project CSV import: column mapping, reference resolution, dry run and all-or-nothing commit
+
+use app_lib::app::{
+    export_projects_csv, import_projects_csv, partner_create, person_create, project_get,
+    CsvExportReq, PartnerCreateReq, PersonCreateReq, ProjectCsvImportReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use std::collections::HashMap;
+
+// ──────────────────────── Helper ────────────────────────
+
+/// Partner "Acme" and person "Alice"; returns (partner_id, person_id).
+fn seed(pool: &DbPool) -> (String, String) {
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Alice".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    (partner.id, person.id)
+}
+
+fn import(pool: &DbPool, csv: &str, dry_run: bool) -> app_lib::app::ProjectCsvImportReport {
+    import_projects_csv(
+        pool,
+        ProjectCsvImportReq {
+            csv: csv.to_string(),
+            dry_run: Some(dry_run),
+            column_map: None,
+        },
+    )
+    .unwrap()
+}
+
+fn project_count(pool: &DbPool) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row("SELECT COUNT(*) FROM projects", [], |r| r.get(0))
+        .unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn dry_run_validates_without_writing() {
+    let pool = init_test_db();
+    let (partner_id, _) = seed(&pool);
+    let csv = format!(
+        "name,partner,owner,country_code,priority,due_date,tags\n\
+         Alpha,{},alice,CN,2,2026-04-30,web; api\n\
+         Beta,Nobody,Alice,CN,9,30/04/2026,\n",
+        partner_id
+    );
+
+    let report = import(&pool, &csv, true);
+    assert!(report.dry_run);
+    assert!(!report.committed);
+    assert_eq!(report.total_rows, 2);
+    assert_eq!(report.ok_rows, 1);
+    assert_eq!(report.errors.len(), 1);
+    let error = &report.errors[0];
+    assert_eq!(error.row, 2);
+    assert_eq!(error.name.as_deref(), Some("Beta"));
+    assert_eq!(error.reasons.len(), 3);
+    assert!(error.reasons[0].contains("partner not found"));
+    assert_eq!(project_count(&pool), 0);
+
+    // With errors nothing is committed either.
+    let report = import(&pool, &csv, false);
+    assert!(!report.committed);
+    assert_eq!(project_count(&pool), 0);
+}
+
+#[test]
+fn commit_creates_projects_in_one_transaction() {
+    let pool = init_test_db();
+    seed(&pool);
+    let csv = "name,partner,owner,country_code,tags,parent,status\n\
+               Parent,Acme,Alice,CN,web;api;web,,DONE\n\
+               Child,acme,ALICE,CN,,Parent,\n";
+
+    let report = import(&pool, csv, false);
+    assert!(report.committed);
+    assert_eq!(report.ok_rows, 2);
+    assert_eq!(report.ignored_columns, vec!["status"]);
+    assert_eq!(project_count(&pool), 2);
+
+    let parent = project_get(&pool, &report.project_ids[0]).unwrap();
+    assert_eq!(parent.current_status, "BACKLOG");
+    let mut tags = parent.tags.clone();
+    tags.sort();
+    assert_eq!(tags, vec!["api", "web"]);
+    let child = project_get(&pool, &report.project_ids[1]).unwrap();
+    assert_eq!(child.parent_project_id.as_deref(), Some(parent.id.as_str()));
+}
+
+#[test]
+fn duplicate_names_and_column_map() {
+    let pool = init_test_db();
+    seed(&pool);
+    let csv = "Title,Client,Lead,Land\n\
+               \"Alpha, \"\"v2\"\"\",Acme,Alice,CN\n\
+               \"Alpha, \"\"v2\"\"\",Acme,Alice,CN\n";
+    let column_map: HashMap<String, String> = [
+        ("Title", "name"),
+        ("Client", "partner"),
+        ("Lead", "owner"),
+        ("Land", "country_code"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+
+    let report = import_projects_csv(
+        &pool,
+        ProjectCsvImportReq {
+            csv: csv.to_string(),
+            dry_run: None,
+            column_map: Some(column_map),
+        },
+    )
+    .unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.ok_rows, 1);
+    // The second row collides with the first one inside the same transaction.
+    assert_eq!(report.errors[0].row, 2);
+    assert_eq!(report.errors[0].name.as_deref(), Some("Alpha, \"v2\""));
+
+    let err = import_projects_csv(
+        &pool,
+        ProjectCsvImportReq {
+            csv: "name,owner,country_code\nA,Alice,CN\n".to_string(),
+            dry_run: None,
+            column_map: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
+
+#[test]
+fn exported_csv_round_trips() {
+    let pool = init_test_db();
+    seed(&pool);
+    import(
+        &pool,
+        "name,partner,owner,country_code,description\nAlpha,Acme,Alice,CN,\"multi\nline\"\n",
+        false,
+    );
+    let exported = export_projects_csv(&pool, CsvExportReq::default()).unwrap();
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute("UPDATE projects SET name = 'Renamed'", [])
+            .unwrap();
+    }
+
+    let report = import(&pool, &exported, false);
+    assert!(report.committed, "{:?}", report.errors);
+    let project = project_get(&pool, &report.project_ids[0]).unwrap();
+    assert_eq!(project.name, "Alpha");
+    assert_eq!(project.description, "multi\nline");
+}
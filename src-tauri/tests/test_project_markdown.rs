@@ -0,0 +1,154 @@
+//! Project Markdown export
+
+use app_lib::app::{
+    comment_create, partner_create, person_create, project_create, project_export_markdown,
+    CommentCreateReq, PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use serde_json::json;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn seed(pool: &DbPool) -> (String, String) {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Alice".to_string(),
+            email: None,
+            role: Some("PM".to_string()),
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme | Co".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let id = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Alpha *beta*".to_string(),
+            description: Some("Ship the thing".to_string()),
+            priority: Some(2),
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id.clone(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(vec!["web".to_string()]),
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id;
+    (id, owner.id)
+}
+
+fn comment(pool: &DbPool, project_id: &str, person_id: &str, content: String) {
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: project_id.to_string(),
+            person_id: Some(person_id.to_string()),
+            content,
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn markdown_has_metadata_members_and_timeline() {
+    let pool = init_test_db();
+    let (id, _) = seed(&pool);
+
+    let md = project_export_markdown(&pool, &id).unwrap();
+    assert!(md.starts_with("# Alpha \\*beta\\*\n"));
+    assert!(md.contains("| Field | Value |\n| --- | --- |\n| Status | BACKLOG |"));
+    assert!(md.contains("| Partner | Acme \\| Co |"));
+    assert!(md.contains("| Tags | `web` |"));
+    assert!(md.contains("## Description\n\nShip the thing"));
+    assert!(md.contains("## Members\n\n| Person | Role | Start | End |"));
+    assert!(md.contains("| active |"));
+    assert!(md.contains("## Status history\n\n- **"));
+    assert!(md.contains("BACKLOG"));
+    assert!(!md.contains("## Comments"));
+    assert!(md.ends_with('\n'));
+}
+
+#[test]
+fn markdown_converts_rich_text_comments() {
+    let pool = init_test_db();
+    let (id, owner) = seed(&pool);
+    let doc = json!({
+        "type": "doc",
+        "content": [
+            { "type": "heading", "attrs": { "level": 2 }, "content": [{ "type": "text", "text": "Plan" }] },
+            { "type": "paragraph", "content": [
+                { "type": "text", "text": "Ping " },
+                { "type": "mention", "attrs": { "id": owner, "label": "Alice" } },
+                { "type": "text", "text": " about " },
+                { "type": "text", "text": "the spec ", "marks": [{ "type": "bold" }] },
+                { "type": "text", "text": "here", "marks": [{ "type": "link", "attrs": { "href": "https://example.com/a" } }] },
+                { "type": "hardBreak" },
+                { "type": "text", "text": "run it", "marks": [{ "type": "code" }] }
+            ]},
+            { "type": "bulletList", "content": [
+                { "type": "listItem", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "one" }] }] },
+                { "type": "listItem", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "two" }] }] }
+            ]},
+            { "type": "taskList", "content": [
+                { "type": "taskItem", "attrs": { "checked": true }, "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "done" }] }] },
+                { "type": "taskItem", "attrs": { "checked": false }, "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "todo" }] }] }
+            ]},
+            { "type": "table", "content": [
+                { "type": "tableRow", "content": [
+                    { "type": "tableHeader", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "k" }] }] },
+                    { "type": "tableHeader", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "v" }] }] }
+                ]},
+                { "type": "tableRow", "content": [
+                    { "type": "tableCell", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "a" }] }] },
+                    { "type": "tableCell", "content": [{ "type": "paragraph" }] }
+                ]}
+            ]}
+        ]
+    });
+    comment(&pool, &id, &owner, doc.to_string());
+    comment(
+        &pool,
+        &id,
+        &owner,
+        "Milestones:\n- Kickoff (2026-05-01)".to_string(),
+    );
+
+    let md = project_export_markdown(&pool, &id).unwrap();
+    let comments = &md[md.find("## Comments").unwrap()..];
+    assert!(comments.contains("### Alice · "));
+    assert!(comments.contains("## Plan"));
+    assert!(comments
+        .contains("Ping @Alice about **the spec** [here](<https://example.com/a>)\\\n`run it`"));
+    assert!(comments.contains("- one\n- two"));
+    assert!(comments.contains("- [x] done\n- [ ] todo"));
+    assert!(comments.contains("| k | v |\n| --- | --- |\n| a |  |"));
+    // Plain-text comments are kept as written, after the rich-text one.
+    let plain = comments
+        .find("Milestones:\n- Kickoff (2026-05-01)")
+        .unwrap();
+    assert!(plain > comments.find("## Plan").unwrap());
+}
+
+#[test]
+fn markdown_unknown_project_is_not_found() {
+    let pool = init_test_db();
+    let err = project_export_markdown(&pool, "missing").unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
@@ -0,0 +1,206 @@
+//! Kanban rank tests: manual ordering within a status column
+
+use app_lib::app::{
+    partner_create, person_create, project_change_status, project_create, project_list,
+    project_reorder, PartnerCreateReq, PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq,
+    ProjectListReq, ProjectReorderReq,
+};
+use app_lib::error::AppError;
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn create(pool: &DbPool, name: &str) -> String {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn reorder(
+    pool: &DbPool,
+    id: &str,
+    before: Option<&str>,
+    after: Option<&str>,
+) -> Result<f64, AppError> {
+    project_reorder(
+        pool,
+        ProjectReorderReq {
+            project_id: id.to_string(),
+            before_id: before.map(str::to_string),
+            after_id: after.map(str::to_string),
+        },
+    )
+    .map(|r| r.rank)
+}
+
+fn backlog_ids(pool: &DbPool) -> Vec<String> {
+    project_list(
+        pool,
+        ProjectListReq {
+            statuses: Some(vec!["BACKLOG".to_string()]),
+            sort_by: Some("rank".to_string()),
+            limit: Some(200),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .items
+    .into_iter()
+    .map(|p| p.id)
+    .collect()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn reorder_moves_cards_to_top_bottom_and_between() {
+    let pool = init_test_db();
+    let a = create(&pool, "A");
+    let b = create(&pool, "B");
+    let c = create(&pool, "C");
+
+    // Pin an explicit order: A, B, C.
+    reorder(&pool, &a, None, Some(&backlog_ids(&pool)[0])).unwrap();
+    reorder(&pool, &b, Some(&a), None).unwrap();
+    reorder(&pool, &c, Some(&b), None).unwrap();
+    assert_eq!(backlog_ids(&pool), vec![a.clone(), b.clone(), c.clone()]);
+
+    // C to the top.
+    reorder(&pool, &c, None, Some(&a)).unwrap();
+    assert_eq!(backlog_ids(&pool), vec![c.clone(), a.clone(), b.clone()]);
+
+    // C between A and B.
+    reorder(&pool, &c, Some(&a), Some(&b)).unwrap();
+    assert_eq!(backlog_ids(&pool), vec![a.clone(), c.clone(), b.clone()]);
+
+    // A below B (only the card above is given).
+    reorder(&pool, &a, Some(&b), None).unwrap();
+    assert_eq!(backlog_ids(&pool), vec![c, b, a]);
+}
+
+#[test]
+fn reorder_validates_neighbours_and_status_change_clears_rank() {
+    let pool = init_test_db();
+    let a = create(&pool, "A");
+    let b = create(&pool, "B");
+    let planned = create(&pool, "Planned");
+    project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: planned.clone(),
+            to_status: "PLANNED".to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+
+    assert!(matches!(
+        reorder(&pool, &a, None, None),
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        reorder(&pool, &a, Some(&a), None),
+        Err(AppError::Validation(_))
+    ));
+    let err = reorder(&pool, &a, Some(&planned), None).unwrap_err();
+    assert!(err.to_string().contains("RANK_COLUMN_MISMATCH"));
+    assert!(matches!(
+        reorder(&pool, &a, Some("missing"), None),
+        Err(AppError::NotFound(_))
+    ));
+
+    reorder(&pool, &a, Some(&b), None).unwrap();
+    project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: a.clone(),
+            to_status: "PLANNED".to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+    let page = project_list(
+        &pool,
+        ProjectListReq {
+            statuses: Some(vec!["PLANNED".to_string()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let moved = page.items.iter().find(|p| p.id == a).unwrap();
+    assert_eq!(moved.rank, None);
+}
+
+#[test]
+fn exhausted_gaps_renumber_the_column() {
+    let pool = init_test_db();
+    let top = create(&pool, "Top");
+    let bottom = create(&pool, "Bottom");
+    create(&pool, "Moving");
+    reorder(&pool, &top, None, Some(&backlog_ids(&pool)[0])).unwrap();
+    reorder(&pool, &bottom, Some(&top), None).unwrap();
+
+    // Repeatedly move the last card directly under `top`; halving the gap forces a renumber.
+    for _ in 0..80 {
+        let ids = backlog_ids(&pool);
+        reorder(&pool, &ids[2], Some(&ids[0]), Some(&ids[1])).unwrap();
+    }
+    let ids = backlog_ids(&pool);
+    assert_eq!(ids.len(), 3);
+    assert_eq!(ids[0], top);
+
+    let ranks: Vec<f64> = project_list(
+        &pool,
+        ProjectListReq {
+            sort_by: Some("rank".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .items
+    .into_iter()
+    .map(|p| p.rank.unwrap())
+    .collect();
+    assert!(ranks.windows(2).all(|w| w[0] < w[1]));
+}
@@ -0,0 +1,163 @@
+//! Project share bundle (redaction) tests
+
+use app_lib::app::{
+    assignment_add_member, partner_create, person_create, project_change_status, project_create,
+    project_share_bundle, project_share_expiry, project_share_object_key, AssignmentAddReq,
+    PartnerCreateReq, PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq, ProjectShareReq,
+    PROJECT_SHARE_FORMAT,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+struct Seeded {
+    project_id: String,
+    person_id: String,
+    partner_id: String,
+}
+
+fn seed(pool: &DbPool) -> Seeded {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: Some("owner@secret.test".to_string()),
+            role: Some("PM".to_string()),
+            note: Some("internal person note".to_string()),
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: Some("internal partner note".to_string()),
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Shared".to_string(),
+            description: Some("desc".to_string()),
+            priority: Some(2),
+            country_code: "CN".to_string(),
+            partner_id: partner.id.clone(),
+            owner_person_id: person.id.clone(),
+            product_name: None,
+            start_date: None,
+            due_date: Some("2026-12-31".to_string()),
+            tags: Some(vec!["pilot".to_string()]),
+            created_by_person_id: Some(person.id.clone()),
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+    Seeded {
+        project_id: project.id,
+        person_id: person.id,
+        partner_id: partner.id,
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn bundle_contains_status_but_no_internal_fields() {
+    let pool = init_test_db();
+    let ids = seed(&pool);
+    let member = person_create(
+        &pool,
+        PersonCreateReq {
+            display_name: "Member".to_string(),
+            email: Some("member@secret.test".to_string()),
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    assignment_add_member(
+        &pool,
+        AssignmentAddReq {
+            project_id: ids.project_id.clone(),
+            person_id: member.id.clone(),
+            role: Some("dev".to_string()),
+            start_at: None,
+        },
+    )
+    .unwrap();
+    project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: ids.project_id.clone(),
+            to_status: "PLANNED".to_string(),
+            note: Some("confidential status note".to_string()),
+            changed_by_person_id: Some(ids.person_id.clone()),
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+
+    let now = chrono::Utc::now();
+    let bundle = project_share_bundle(
+        &pool,
+        &ids.project_id,
+        now,
+        now + chrono::Duration::hours(1),
+    )
+    .unwrap();
+    assert_eq!(bundle.format, PROJECT_SHARE_FORMAT);
+    assert_eq!(bundle.project.name, "Shared");
+    assert_eq!(bundle.project.partner_name, "Acme");
+    assert_eq!(bundle.project.current_status, "PLANNED");
+    assert!(bundle.team.iter().any(|m| m.name == "Member"));
+    assert!(bundle
+        .status_timeline
+        .iter()
+        .any(|s| s.from_status.as_deref() == Some("BACKLOG") && s.to_status == "PLANNED"));
+
+    let json = serde_json::to_string(&bundle).unwrap();
+    for secret in [
+        ids.project_id.as_str(),
+        ids.person_id.as_str(),
+        ids.partner_id.as_str(),
+        member.id.as_str(),
+        "secret.test",
+        "internal",
+        "confidential",
+    ] {
+        assert!(!json.contains(secret), "bundle leaks {}", secret);
+    }
+}
+
+#[test]
+fn unknown_project_is_not_found() {
+    let pool = init_test_db();
+    let now = chrono::Utc::now();
+    let err = project_share_bundle(&pool, "missing", now, now).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
+
+#[test]
+fn expiry_is_bounded_and_key_hides_project_id() {
+    let req = |hours| ProjectShareReq {
+        project_id: "p1".to_string(),
+        expires_in_hours: hours,
+    };
+    assert_eq!(
+        project_share_expiry(&req(None)).unwrap(),
+        chrono::Duration::hours(72)
+    );
+    assert!(project_share_expiry(&req(Some(168))).is_ok());
+    assert_eq!(
+        project_share_expiry(&req(Some(0))).unwrap_err().code(),
+        "VALIDATION_ERROR"
+    );
+    assert!(project_share_expiry(&req(Some(169))).is_err());
+
+    let key = project_share_object_key();
+    assert!(key.starts_with("shares/") && key.ends_with(".json"));
+    assert!(!key.contains("p1"));
+}
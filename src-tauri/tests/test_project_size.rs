@@ -0,0 +1,184 @@
+//! Project size tests: scale validation, size history and size-weighted throughput
+
+use app_lib::app::{
+    format_settings_update, partner_create, person_create, project_change_status, project_create,
+    project_get, project_list, project_set_size, project_throughput_until, FormatSettingsReq,
+    PartnerCreateReq, PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq, ProjectListReq,
+    ProjectSetSizeReq, SizeInput, WeekStart,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::{Datelike, Local, Weekday};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn create(pool: &DbPool, name: &str) -> String {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn set_size(pool: &DbPool, id: &str, size: Option<SizeInput>) -> Option<i64> {
+    project_set_size(
+        pool,
+        ProjectSetSizeReq {
+            project_id: id.to_string(),
+            size,
+            note: None,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap()
+    .size_points
+}
+
+fn complete(pool: &DbPool, id: &str) {
+    for status in ["PLANNED", "IN_PROGRESS", "DONE"] {
+        project_change_status(
+            pool,
+            ProjectChangeStatusReq {
+                project_id: id.to_string(),
+                to_status: status.to_string(),
+                note: None,
+                changed_by_person_id: None,
+                if_match_updated_at: None,
+                force: None,
+            },
+        )
+        .unwrap();
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn set_size_records_history_only_on_change() {
+    let pool = init_test_db();
+    let id = create(&pool, "Apollo");
+
+    assert_eq!(
+        set_size(&pool, &id, Some(SizeInput::Label("m".into()))),
+        Some(3)
+    );
+    assert_eq!(set_size(&pool, &id, Some(SizeInput::Points(3))), Some(3));
+    assert_eq!(set_size(&pool, &id, Some(SizeInput::Points(13))), Some(13));
+    assert_eq!(set_size(&pool, &id, None), None);
+
+    let detail = project_get(&pool, &id).unwrap();
+    let changes: Vec<_> = detail
+        .size_history
+        .iter()
+        .map(|h| (h.from_points, h.to_points))
+        .collect();
+    assert_eq!(changes.len(), 3);
+    assert!(changes.contains(&(None, Some(3))));
+    assert!(changes.contains(&(Some(3), Some(13))));
+    assert!(changes.contains(&(Some(13), None)));
+
+    set_size(&pool, &id, Some(SizeInput::Label("XL".into())));
+    let page = project_list(&pool, ProjectListReq::default()).unwrap();
+    assert_eq!(page.items[0].size_points, Some(8));
+}
+
+#[test]
+fn set_size_rejects_values_off_the_scale() {
+    let pool = init_test_db();
+    let id = create(&pool, "Apollo");
+
+    for size in [SizeInput::Points(4), SizeInput::Label("XXL".into())] {
+        let err = project_set_size(
+            &pool,
+            ProjectSetSizeReq {
+                project_id: id.clone(),
+                size: Some(size),
+                note: None,
+                changed_by_person_id: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "VALIDATION_ERROR");
+        assert!(err.to_string().contains("SIZE_INVALID"));
+    }
+
+    let err = project_set_size(
+        &pool,
+        ProjectSetSizeReq {
+            project_id: "missing".into(),
+            size: Some(SizeInput::Points(5)),
+            note: None,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+    assert!(project_get(&pool, &id).unwrap().size_history.is_empty());
+}
+
+#[test]
+fn throughput_weights_completed_projects_by_size() {
+    let pool = init_test_db();
+    format_settings_update(
+        &pool,
+        FormatSettingsReq {
+            week_start: Some(WeekStart::Sunday),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let sized = create(&pool, "Sized");
+    let unestimated = create(&pool, "Unestimated");
+    let open = create(&pool, "Open");
+    set_size(&pool, &sized, Some(SizeInput::Points(5)));
+    set_size(&pool, &open, Some(SizeInput::Points(8)));
+    complete(&pool, &sized);
+    complete(&pool, &unestimated);
+
+    let today = Local::now().date_naive();
+    let stats = project_throughput_until(&pool, today, 2).unwrap();
+    assert_eq!(stats.weeks.len(), 2);
+    let current = &stats.weeks[1];
+    let week_start = chrono::NaiveDate::parse_from_str(&current.week_start, "%Y-%m-%d").unwrap();
+    assert_eq!(week_start.weekday(), Weekday::Sun);
+    assert_eq!(
+        (current.completed, current.points, current.unestimated),
+        (2, 5, 1)
+    );
+    assert_eq!(stats.weeks[0].completed, 0);
+    assert_eq!((stats.total_completed, stats.total_points), (2, 5));
+    assert_eq!(stats.avg_points_per_week, 2.5);
+}
@@ -0,0 +1,175 @@
+//! Sub-project hierarchy tests: parent validation, list filter, tree rollup
+
+use app_lib::app::{
+    partner_create, person_create, project_change_status, project_create, project_list,
+    project_tree, project_update, PartnerCreateReq, PersonCreateReq, ProjectChangeStatusReq,
+    ProjectCreateReq, ProjectDetailDto, ProjectListReq, ProjectTreeReq, ProjectUpdateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn create(pool: &DbPool, name: &str, parent: Option<&str>) -> ProjectDetailDto {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: parent.map(str::to_string),
+        },
+    )
+    .unwrap()
+}
+
+fn set_parent(pool: &DbPool, id: &str, parent: &str) -> Result<ProjectDetailDto, String> {
+    project_update(
+        pool,
+        ProjectUpdateReq {
+            id: id.to_string(),
+            name: None,
+            description: None,
+            priority: None,
+            country_code: None,
+            owner_person_id: None,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            parent_project_id: Some(parent.to_string()),
+            partner_id: None,
+            changed_by_person_id: None,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn parent_must_exist_and_not_create_cycles() {
+    let pool = init_test_db();
+    let root = create(&pool, "Root", None);
+    let child = create(&pool, "Child", Some(&root.id));
+    let grandchild = create(&pool, "Grandchild", Some(&child.id));
+    assert_eq!(child.parent_project_id.as_deref(), Some(root.id.as_str()));
+
+    for (id, parent) in [
+        (&root.id, &root.id),
+        (&root.id, &child.id),
+        (&root.id, &grandchild.id),
+    ] {
+        let err = set_parent(&pool, id, parent).unwrap_err();
+        assert!(err.contains("PARENT_CYCLE"), "{}", err);
+    }
+    assert!(set_parent(&pool, &root.id, "missing").is_err());
+
+    // Re-parenting to a sibling branch and back to top-level are fine.
+    let other = create(&pool, "Other", None);
+    let moved = set_parent(&pool, &grandchild.id, &other.id).unwrap();
+    assert_eq!(moved.parent_project_id.as_deref(), Some(other.id.as_str()));
+    let top = set_parent(&pool, &grandchild.id, "").unwrap();
+    assert_eq!(top.parent_project_id, None);
+}
+
+#[test]
+fn list_filters_by_parent() {
+    let pool = init_test_db();
+    let root = create(&pool, "Root", None);
+    let a = create(&pool, "A", Some(&root.id));
+    create(&pool, "B", Some(&root.id));
+    create(&pool, "A1", Some(&a.id));
+
+    let children = project_list(
+        &pool,
+        ProjectListReq {
+            parent_project_id: Some(root.id.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut names: Vec<String> = children.items.into_iter().map(|p| p.name).collect();
+    names.sort();
+    assert_eq!(names, ["A", "B"]);
+
+    let top = project_list(
+        &pool,
+        ProjectListReq {
+            parent_project_id: Some(String::new()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(top.total, 1);
+    assert_eq!(top.items[0].id, root.id);
+}
+
+#[test]
+fn tree_rolls_up_status_counts() {
+    let pool = init_test_db();
+    let root = create(&pool, "Root", None);
+    let a = create(&pool, "A", Some(&root.id));
+    let a1 = create(&pool, "A1", Some(&a.id));
+    create(&pool, "B", Some(&root.id));
+    create(&pool, "Standalone", None);
+    project_change_status(
+        &pool,
+        ProjectChangeStatusReq {
+            project_id: a1.id.clone(),
+            to_status: "PLANNED".to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+
+    let forest = project_tree(&pool, ProjectTreeReq::default()).unwrap();
+    assert_eq!(forest.len(), 2);
+    let tree = forest.iter().find(|n| n.id == root.id).unwrap();
+    assert_eq!(tree.descendant_count, 3);
+    assert_eq!(tree.rollup_status_counts["BACKLOG"], 3);
+    assert_eq!(tree.rollup_status_counts["PLANNED"], 1);
+    let node_a = tree.children.iter().find(|n| n.id == a.id).unwrap();
+    assert_eq!(node_a.children.len(), 1);
+    assert_eq!(node_a.rollup_status_counts["PLANNED"], 1);
+
+    let subtree = project_tree(
+        &pool,
+        ProjectTreeReq {
+            root_project_id: Some(a.id.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(subtree.len(), 1);
+    assert_eq!(subtree[0].descendant_count, 1);
+}
@@ -0,0 +1,173 @@
+//! Read-only query surface tests (statement checks, parameters, blocked tables, schema)
+
+use app_lib::app::{
+    execute_query, person_create, query_schema, ExecuteQueryReq, PersonCreateReq,
+    QUERY_BLOCKED_TABLES,
+};
+use app_lib::infra::db::{get_read_connection, init_db, init_test_db};
+use app_lib::infra::DbPool;
+use serde_json::{json, Value};
+use std::fs;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn add_person(pool: &DbPool, name: &str) {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+}
+
+fn query(sql: &str, params: Option<Value>, max_rows: Option<usize>) -> ExecuteQueryReq {
+    ExecuteQueryReq {
+        sql: sql.to_string(),
+        params,
+        max_rows,
+    }
+}
+
+fn rejected(pool: &DbPool, sql: &str) -> String {
+    let err = execute_query(pool, query(sql, None, None)).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR", "{}", sql);
+    err.to_string()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn select_with_positional_and_named_params() {
+    let pool = init_test_db();
+    for name in ["Alice", "Bob", "Carol"] {
+        add_person(&pool, name);
+    }
+
+    let result = execute_query(
+        &pool,
+        query(
+            "SELECT display_name AS name, 1.5 AS x, NULL AS n FROM persons \
+             WHERE display_name <> ?1 ORDER BY display_name;",
+            Some(json!(["Bob"])),
+            None,
+        ),
+    )
+    .unwrap();
+    assert_eq!(result.columns, vec!["name", "x", "n"]);
+    assert_eq!(result.rows.len(), 2);
+    assert_eq!(
+        result.rows[0],
+        vec![json!("Alice"), json!(1.5), Value::Null]
+    );
+    assert!(!result.truncated);
+
+    let result = execute_query(
+        &pool,
+        query(
+            "-- people by name\nSELECT COUNT(*) FROM persons WHERE display_name = :name",
+            Some(json!({ "name": "Carol" })),
+            None,
+        ),
+    )
+    .unwrap();
+    assert_eq!(result.rows, vec![vec![json!(1)]]);
+
+    let result = execute_query(
+        &pool,
+        query("SELECT display_name FROM persons", None, Some(2)),
+    )
+    .unwrap();
+    assert_eq!(result.rows.len(), 2);
+    assert!(result.truncated);
+
+    let err = execute_query(&pool, query("SELECT ?1, ?2", Some(json!([1])), None)).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err = execute_query(&pool, query("SELECT :a", Some(json!({ "b": 1 })), None)).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
+
+#[test]
+fn writes_and_multiple_statements_are_rejected() {
+    let pool = init_test_db();
+    add_person(&pool, "Alice");
+
+    for sql in [
+        "DELETE FROM persons",
+        "UPDATE persons SET display_name = 'x'",
+        "PRAGMA query_only = OFF",
+        "SELECT 1; DELETE FROM persons",
+        "WITH x AS (SELECT 1) DELETE FROM persons",
+        "ATTACH DATABASE ':memory:' AS other",
+        "SELECT * FROM no_such_table",
+        "SELECT 'unterminated",
+    ] {
+        rejected(&pool, sql);
+    }
+    // Semicolons inside literals and comments are not statement breaks.
+    let result = execute_query(&pool, query("SELECT 'a;b' /* ; */ AS v;", None, None)).unwrap();
+    assert_eq!(result.rows, vec![vec![json!("a;b")]]);
+
+    let conn = pool.0.lock().unwrap();
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM persons", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, 1);
+    // The connection is writable again after a query.
+    let query_only: i64 = conn
+        .query_row("PRAGMA query_only", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(query_only, 0);
+}
+
+#[test]
+fn pooled_readers_stay_query_only() {
+    let base = std::env::temp_dir().join(format!("projex-query-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&base).unwrap();
+    let pool = init_db(&base.join("app.db")).unwrap();
+    add_person(&pool, "Alice");
+
+    let result = execute_query(&pool, query("SELECT COUNT(*) FROM persons", None, None)).unwrap();
+    assert_eq!(result.rows, vec![vec![json!(1)]]);
+
+    let conn = get_read_connection(&pool);
+    let query_only: i64 = conn
+        .query_row("PRAGMA query_only", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(query_only, 1);
+    drop(conn);
+    drop(pool);
+    fs::remove_dir_all(base).ok();
+}
+
+#[test]
+fn credential_tables_cannot_be_read() {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute_batch("CREATE VIEW config_view AS SELECT * FROM sync_config")
+            .unwrap();
+    }
+
+    let message = rejected(&pool, "SELECT * FROM sync_config");
+    assert!(message.contains("sync_config"), "{}", message);
+    rejected(&pool, "SELECT value FROM \"SYNC_CONFIG\"");
+    rejected(&pool, "SELECT * FROM config_view");
+    rejected(&pool, "SELECT (SELECT COUNT(*) FROM webhooks)");
+    rejected(&pool, "SELECT * FROM main.webhooks w");
+
+    let schema = query_schema(&pool).unwrap();
+    assert!(schema.schema_version > 0);
+    assert!(schema.tables.iter().any(|t| t.name == "projects"));
+    for blocked in QUERY_BLOCKED_TABLES {
+        assert!(schema.tables.iter().all(|t| t.name != *blocked));
+        assert!(schema
+            .relations
+            .iter()
+            .all(|r| r.from_table != *blocked && r.to_table != *blocked));
+    }
+    assert_eq!(schema.blocked_tables.len(), QUERY_BLOCKED_TABLES.len());
+}
@@ -0,0 +1,203 @@
+//! Recurring project rules tests
+
+use app_lib::app::{
+    next_run_after, partner_create, person_create, project_create, project_get,
+    project_save_as_template, recurrence_rule_create, recurrence_rule_list, recurrence_rule_update,
+    recurrence_run_due, template_delete, PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+    ProjectSaveAsTemplateReq, RecurrenceRuleCreateReq, RecurrenceRuleUpdateReq, RecurrenceUnit,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::NaiveDate;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn seed_template(pool: &DbPool) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Regulator".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Compliance".to_string(),
+            description: None,
+            priority: None,
+            country_code: "US".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+    project_save_as_template(
+        pool,
+        ProjectSaveAsTemplateReq {
+            project_id: project.id,
+            template_name: "Compliance".to_string(),
+            name_pattern: Some("Compliance {month}".to_string()),
+            milestones: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn monthly_rule(pool: &DbPool, template_id: &str, start_on: &str) -> String {
+    recurrence_rule_create(
+        pool,
+        RecurrenceRuleCreateReq {
+            template_id: template_id.to_string(),
+            name: "Monthly compliance".to_string(),
+            interval_unit: RecurrenceUnit::Month,
+            interval_count: 1,
+            start_on: start_on.to_string(),
+            due_offset_days: Some(14),
+        },
+    )
+    .unwrap()
+    .id
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn next_run_is_anchored_without_drift() {
+    let anchor = date("2026-01-31");
+    let feb = next_run_after(anchor, RecurrenceUnit::Month, 1, anchor);
+    assert_eq!(feb, date("2026-02-28"));
+    assert_eq!(
+        next_run_after(anchor, RecurrenceUnit::Month, 1, feb),
+        date("2026-03-31")
+    );
+    assert_eq!(
+        next_run_after(
+            date("2026-01-01"),
+            RecurrenceUnit::Week,
+            2,
+            date("2026-01-20")
+        ),
+        date("2026-01-29")
+    );
+    assert_eq!(
+        next_run_after(
+            date("2026-01-01"),
+            RecurrenceUnit::Day,
+            3,
+            date("2026-01-01")
+        ),
+        date("2026-01-04")
+    );
+}
+
+#[test]
+fn due_rule_creates_one_project_and_advances() {
+    let pool = init_test_db();
+    let template_id = seed_template(&pool);
+    let rule_id = monthly_rule(&pool, &template_id, "2026-01-01");
+
+    assert!(recurrence_run_due(&pool, date("2025-12-31"))
+        .unwrap()
+        .is_empty());
+
+    // Three missed periods: only one instance, next run after "today".
+    let items = recurrence_run_due(&pool, date("2026-04-15")).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].rule_id, rule_id);
+    assert_eq!(items[0].run_on, "2026-01-01");
+    let project = project_get(&pool, items[0].project_id.as_deref().unwrap()).unwrap();
+    assert_eq!(project.name, "Compliance 2026-01");
+    assert_eq!(project.start_date.as_deref(), Some("2026-01-01"));
+    assert_eq!(project.due_date.as_deref(), Some("2026-01-15"));
+    assert!(project
+        .status_history
+        .iter()
+        .any(|h| h.from_status.is_none() && h.note.contains("Monthly compliance")));
+
+    let rule = &recurrence_rule_list(&pool).unwrap()[0];
+    assert_eq!(rule.next_run_on, "2026-05-01");
+    assert_eq!(rule.last_project_id, items[0].project_id);
+    assert!(rule.last_error.is_none());
+    assert!(recurrence_run_due(&pool, date("2026-04-30"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn failures_are_recorded_and_disabled_rules_skip() {
+    let pool = init_test_db();
+    let template_id = seed_template(&pool);
+    let rule_id = monthly_rule(&pool, &template_id, "2026-01-01");
+    // Second rule on the same template renders the same name: the later one conflicts.
+    monthly_rule(&pool, &template_id, "2026-01-01");
+
+    let items = recurrence_run_due(&pool, date("2026-01-01")).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items.iter().filter(|i| i.error.is_some()).count(), 1);
+    assert!(recurrence_rule_list(&pool)
+        .unwrap()
+        .iter()
+        .all(|r| r.next_run_on == "2026-02-01"));
+
+    recurrence_rule_update(
+        &pool,
+        RecurrenceRuleUpdateReq {
+            id: rule_id,
+            enabled: Some(false),
+            name: None,
+        },
+    )
+    .unwrap();
+    let items = recurrence_run_due(&pool, date("2026-02-01")).unwrap();
+    assert_eq!(items.len(), 1);
+
+    template_delete(&pool, &template_id).unwrap();
+    assert!(recurrence_rule_list(&pool).unwrap().is_empty());
+}
+
+#[test]
+fn read_only_replica_skips_due_rules() {
+    let pool = init_test_db();
+    let template_id = seed_template(&pool);
+    monthly_rule(&pool, &template_id, "2026-01-01");
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO sync_config (key, value) VALUES ('read_only_replica', '1')",
+            [],
+        )
+        .unwrap();
+    }
+
+    assert!(recurrence_run_due(&pool, date("2026-01-15"))
+        .unwrap()
+        .is_empty());
+    // Still due once the device is writable again.
+    assert_eq!(
+        recurrence_rule_list(&pool).unwrap()[0].next_run_on,
+        "2026-01-01"
+    );
+}
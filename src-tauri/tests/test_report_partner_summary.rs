@@ -0,0 +1,182 @@
+//! Partner engagement report: stage counts, average duration and latest activity
+
+use app_lib::app::{
+    partner_create, person_create, project_create, report_partner_summary, PartnerCreateReq,
+    PersonCreateReq, ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn partner(pool: &DbPool, name: &str) -> String {
+    partner_create(
+        pool,
+        PartnerCreateReq {
+            name: name.to_string(),
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+/// Create a project created at `created_at` with a given status and `(status, changed_at)` history.
+fn project(
+    pool: &DbPool,
+    name: &str,
+    partner_id: &str,
+    created_at: &str,
+    history: &[(&str, &str)],
+) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("{} owner", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let id = project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner_id.to_string(),
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id;
+
+    let conn = pool.0.lock().unwrap();
+    conn.execute("DELETE FROM status_history WHERE project_id = ?1", [&id])
+        .unwrap();
+    for (i, (status, at)) in history.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO status_history (id, project_id, from_status, to_status, changed_at, note)
+             VALUES (?1, ?2, NULL, ?3, ?4, '')",
+            rusqlite::params![format!("{}-{}", id, i), id, status, at],
+        )
+        .unwrap();
+    }
+    let last = history
+        .last()
+        .map(|(status, _)| *status)
+        .unwrap_or("BACKLOG");
+    conn.execute(
+        "UPDATE projects SET current_status = ?1, created_at = ?2, updated_at = ?2 WHERE id = ?3",
+        rusqlite::params![last, created_at, id],
+    )
+    .unwrap();
+    id
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn partner_summary_aggregates_projects_per_partner() {
+    let pool = init_test_db();
+    let acme = partner(&pool, "Acme");
+    let beta = partner(&pool, "Beta");
+    let idle = partner(&pool, "Idle");
+
+    project(
+        &pool,
+        "Fast",
+        &acme,
+        "2026-01-01T00:00:00Z",
+        &[
+            ("BACKLOG", "2026-01-01T00:00:00Z"),
+            ("DONE", "2026-01-03T00:00:00Z"),
+        ],
+    );
+    project(
+        &pool,
+        "Slow",
+        &acme,
+        "2026-01-01T00:00:00Z",
+        &[
+            ("BACKLOG", "2026-01-01T00:00:00Z"),
+            ("DONE", "2026-01-07T00:00:00Z"),
+            ("ARCHIVED", "2026-02-10T00:00:00Z"),
+        ],
+    );
+    project(
+        &pool,
+        "Open",
+        &acme,
+        "2026-01-05T00:00:00Z",
+        &[("IN_PROGRESS", "2026-01-06T00:00:00Z")],
+    );
+    project(
+        &pool,
+        "Fresh",
+        &beta,
+        "2026-03-01T00:00:00Z",
+        &[("BACKLOG", "2026-03-01T00:00:00Z")],
+    );
+
+    let summary = report_partner_summary(&pool).unwrap();
+    let names: Vec<&str> = summary.iter().map(|s| s.partner_name.as_str()).collect();
+    assert_eq!(names, vec!["Acme", "Beta", "Idle"]);
+
+    let a = &summary[0];
+    assert_eq!((a.active, a.done, a.archived), (1, 1, 1));
+    // Creation → latest DONE: 2 and 6 days.
+    assert_eq!(a.avg_duration_days, Some(4.0));
+    assert_eq!(a.last_activity_at.as_deref(), Some("2026-02-10T00:00:00Z"));
+
+    let b = &summary[1];
+    assert_eq!((b.active, b.done, b.archived), (1, 0, 0));
+    assert_eq!(b.avg_duration_days, None);
+    assert_eq!(b.last_activity_at.as_deref(), Some("2026-03-01T00:00:00Z"));
+
+    let i = &summary[2];
+    assert_eq!(i.partner_id, idle);
+    assert!(i.is_active);
+    assert_eq!((i.active, i.done, i.archived), (0, 0, 0));
+    assert_eq!(i.last_activity_at, None);
+}
+
+#[test]
+fn partner_summary_skips_trashed_projects_and_partners() {
+    let pool = init_test_db();
+    let acme = partner(&pool, "Acme");
+    let gone = partner(&pool, "Gone");
+    let trashed = project(
+        &pool,
+        "Trashed",
+        &acme,
+        "2026-01-01T00:00:00Z",
+        &[("BACKLOG", "2026-01-01T00:00:00Z")],
+    );
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE projects SET deleted_at = '2026-01-02T00:00:00Z' WHERE id = ?1",
+            [&trashed],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE partners SET deleted_at = '2026-01-02T00:00:00Z' WHERE id = ?1",
+            [&gone],
+        )
+        .unwrap();
+    }
+
+    let summary = report_partner_summary(&pool).unwrap();
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].active, 0);
+    assert_eq!(summary[0].last_activity_at, None);
+}
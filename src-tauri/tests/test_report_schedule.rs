@@ -0,0 +1,195 @@
+//! Report schedule tests: partner scorecard data, PDF delivery and notices
+
+use app_lib::app::{
+    notice_list, partner_create, partner_scorecard, person_create, project_change_status,
+    project_create, report_run_due, report_schedule_create, report_schedule_get, PartnerCreateReq,
+    PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq, RecurrenceUnit, ReportKind,
+    ReportScheduleCreateReq, NOTICE_KIND_REPORT_DELIVERED, NOTICE_KIND_REPORT_FAILED,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::{Local, NaiveDate};
+use std::path::PathBuf;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("projex-{}-{}", name, uuid::Uuid::new_v4()))
+}
+
+/// Partner "Acme" with one overdue, one done and one archived project.
+fn seed_partner(pool: &DbPool) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let mut ids = Vec::new();
+    for (name, due) in [
+        ("Late", Some("2020-01-01")),
+        ("Shipped", Some("2099-12-31")),
+        ("Shelved", None),
+    ] {
+        let project = project_create(
+            pool,
+            ProjectCreateReq {
+                name: name.to_string(),
+                description: None,
+                priority: None,
+                country_code: "US".to_string(),
+                partner_id: partner.id.clone(),
+                owner_person_id: owner.id.clone(),
+                product_name: None,
+                start_date: None,
+                due_date: due.map(str::to_string),
+                tags: None,
+                created_by_person_id: None,
+                parent_project_id: None,
+            },
+        )
+        .unwrap();
+        ids.push(project.id);
+    }
+    let moves = [
+        (&ids[1], "PLANNED"),
+        (&ids[1], "IN_PROGRESS"),
+        (&ids[1], "DONE"),
+        (&ids[2], "ARCHIVED"),
+    ];
+    for (id, status) in moves {
+        project_change_status(
+            pool,
+            ProjectChangeStatusReq {
+                project_id: id.clone(),
+                to_status: status.to_string(),
+                note: None,
+                changed_by_person_id: None,
+                if_match_updated_at: None,
+                force: None,
+            },
+        )
+        .unwrap();
+    }
+    partner.id
+}
+
+fn schedule_req(partner_id: &str, output_dir: &str) -> ReportScheduleCreateReq {
+    ReportScheduleCreateReq {
+        name: "Acme weekly".to_string(),
+        report_kind: ReportKind::PartnerScorecard,
+        partner_id: Some(partner_id.to_string()),
+        interval_unit: RecurrenceUnit::Week,
+        interval_count: 1,
+        start_on: "2026-01-05".to_string(),
+        output_dir: Some(output_dir.to_string()),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn scorecard_counts_status_overdue_and_on_time() {
+    let pool = init_test_db();
+    let partner_id = seed_partner(&pool);
+
+    let card = partner_scorecard(&pool, &partner_id, Local::now().date_naive()).unwrap();
+    assert_eq!(card.partner_name, "Acme");
+    assert_eq!(
+        (card.total, card.active, card.done, card.archived),
+        (3, 1, 1, 1)
+    );
+    assert_eq!(card.overdue, 1);
+    assert_eq!(card.completed_recently, 1);
+    assert_eq!(card.on_time_rate, Some(1.0));
+    let names: Vec<_> = card.projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["Late", "Shipped"]);
+    assert!(card.projects[0].overdue);
+
+    let err = partner_scorecard(&pool, "missing", Local::now().date_naive()).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
+
+#[test]
+fn due_schedule_writes_pdf_and_records_notice() {
+    let pool = init_test_db();
+    let partner_id = seed_partner(&pool);
+    let dir = temp_dir("reports");
+    let schedule =
+        report_schedule_create(&pool, schedule_req(&partner_id, dir.to_str().unwrap())).unwrap();
+
+    assert!(report_run_due(&pool, date("2026-01-04"))
+        .unwrap()
+        .is_empty());
+    let items = report_run_due(&pool, date("2026-01-07")).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].error, None);
+    let path = PathBuf::from(items[0].output_path.as_deref().unwrap());
+    assert_eq!(path, dir.join("Acme-weekly-2026-01-07.pdf"));
+    assert!(std::fs::read(&path).unwrap().starts_with(b"%PDF-"));
+
+    let schedule = report_schedule_get(&pool, &schedule.id).unwrap();
+    assert_eq!(schedule.next_run_on, "2026-01-12");
+    assert_eq!(schedule.last_error, None);
+    assert!(report_run_due(&pool, date("2026-01-07"))
+        .unwrap()
+        .is_empty());
+
+    let notices = notice_list(&pool).unwrap();
+    assert!(notices
+        .iter()
+        .any(|n| n.kind == NOTICE_KIND_REPORT_DELIVERED && n.message.contains("Acme weekly")));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn failed_run_records_error_and_invalid_schedules_are_rejected() {
+    let pool = init_test_db();
+    let partner_id = seed_partner(&pool);
+
+    // A regular file where the output folder should be.
+    let blocker = temp_dir("blocker");
+    std::fs::write(&blocker, b"not a dir").unwrap();
+    let schedule =
+        report_schedule_create(&pool, schedule_req(&partner_id, blocker.to_str().unwrap()))
+            .unwrap();
+    let items = report_run_due(&pool, date("2026-01-05")).unwrap();
+    assert!(items[0].output_path.is_none());
+    assert!(items[0].error.is_some());
+    let schedule = report_schedule_get(&pool, &schedule.id).unwrap();
+    assert!(schedule.last_error.is_some());
+    assert_eq!(schedule.next_run_on, "2026-01-12");
+    assert!(notice_list(&pool)
+        .unwrap()
+        .iter()
+        .any(|n| n.kind == NOTICE_KIND_REPORT_FAILED && n.level == "warning"));
+    std::fs::remove_file(&blocker).ok();
+
+    let err =
+        report_schedule_create(&pool, schedule_req(&partner_id, "relative/reports")).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let mut req = schedule_req(&partner_id, "/tmp");
+    req.partner_id = None;
+    assert_eq!(
+        report_schedule_create(&pool, req).unwrap_err().code(),
+        "VALIDATION_ERROR"
+    );
+    let err = report_schedule_create(&pool, schedule_req("missing", "/tmp")).unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
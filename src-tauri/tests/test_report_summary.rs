@@ -0,0 +1,238 @@
+//! Report summary tests: status counts, time in status, throughput and overdue
+
+use app_lib::app::{
+    partner_create, person_create, project_create, report_summary, PartnerCreateReq,
+    PersonCreateReq, ProjectCreateReq, ReportSummaryReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::NaiveDate;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+/// Create a project and replace its history with `(status, changed_at)` rows.
+fn project_with_history(
+    pool: &DbPool,
+    name: &str,
+    due_date: Option<&str>,
+    history: &[(&str, &str)],
+) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("{} owner", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("{} partner", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: due_date.map(str::to_string),
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "DELETE FROM status_history WHERE project_id = ?1",
+        [&project.id],
+    )
+    .unwrap();
+    let mut from: Option<&str> = None;
+    for (i, (status, at)) in history.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO status_history (id, project_id, from_status, to_status, changed_at, note)
+             VALUES (?1, ?2, ?3, ?4, ?5, '')",
+            rusqlite::params![
+                format!("{}-{}", project.id, i),
+                project.id,
+                from,
+                status,
+                at
+            ],
+        )
+        .unwrap();
+        from = Some(*status);
+    }
+    conn.execute(
+        "UPDATE projects SET current_status = ?1 WHERE id = ?2",
+        rusqlite::params![history.last().unwrap().0, project.id],
+    )
+    .unwrap();
+    project.id
+}
+
+fn seed(pool: &DbPool) {
+    project_with_history(
+        pool,
+        "Shipped",
+        None,
+        &[
+            ("BACKLOG", "2026-03-01T12:00:00Z"),
+            ("PLANNED", "2026-03-03T12:00:00Z"),
+            ("IN_PROGRESS", "2026-03-05T12:00:00Z"),
+            ("DONE", "2026-03-09T12:00:00Z"),
+        ],
+    );
+    project_with_history(
+        pool,
+        "Late",
+        Some("2026-03-10"),
+        &[("BACKLOG", "2026-03-02T12:00:00Z")],
+    );
+    project_with_history(
+        pool,
+        "Old",
+        None,
+        &[
+            ("BACKLOG", "2026-01-30T12:00:00Z"),
+            ("PLANNED", "2026-01-31T12:00:00Z"),
+            ("IN_PROGRESS", "2026-02-01T12:00:00Z"),
+            ("DONE", "2026-02-02T12:00:00Z"),
+        ],
+    );
+}
+
+fn range(from: &str, to: &str) -> ReportSummaryReq {
+    ReportSummaryReq {
+        from: Some(from.to_string()),
+        to: Some(to.to_string()),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn summary_counts_statuses_and_overdue() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let summary =
+        report_summary(&pool, range("2026-03-01", "2026-03-15"), date("2026-03-15")).unwrap();
+    assert_eq!(summary.from, "2026-03-01");
+    assert_eq!(summary.to, "2026-03-15");
+    assert_eq!(summary.total, 3);
+    assert_eq!(summary.overdue, 1);
+
+    let statuses: Vec<&str> = summary
+        .status_counts
+        .iter()
+        .map(|c| c.status.as_str())
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            "BACKLOG",
+            "PLANNED",
+            "IN_PROGRESS",
+            "BLOCKED",
+            "DONE",
+            "ARCHIVED"
+        ]
+    );
+    let count = |s: &str| {
+        summary
+            .status_counts
+            .iter()
+            .find(|c| c.status == s)
+            .unwrap()
+    };
+    assert_eq!(count("BACKLOG").count, 1);
+    assert_eq!(count("BACKLOG").overdue, 1);
+    assert_eq!(count("DONE").count, 2);
+    assert_eq!(count("DONE").overdue, 0);
+
+    // Overdue is judged at the range end: before the due date nothing is late.
+    let earlier =
+        report_summary(&pool, range("2026-03-01", "2026-03-08"), date("2026-03-15")).unwrap();
+    assert_eq!(earlier.overdue, 0);
+}
+
+#[test]
+fn summary_averages_time_in_status_and_weekly_throughput() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let summary =
+        report_summary(&pool, range("2026-03-01", "2026-03-15"), date("2026-03-15")).unwrap();
+    let avg = |s: &str| {
+        let d = summary
+            .avg_time_in_status
+            .iter()
+            .find(|d| d.status == s)
+            .unwrap();
+        (d.avg_days, d.samples)
+    };
+    // Only stays ending inside the range count ("Old" finished in February).
+    assert_eq!(avg("BACKLOG"), (Some(2.0), 1));
+    assert_eq!(avg("PLANNED"), (Some(2.0), 1));
+    assert_eq!(avg("IN_PROGRESS"), (Some(4.0), 1));
+    assert_eq!(avg("DONE"), (None, 0));
+
+    let weeks: Vec<(&str, i64)> = summary
+        .throughput
+        .iter()
+        .map(|w| (w.week_start.as_str(), w.done))
+        .collect();
+    assert_eq!(
+        weeks,
+        vec![("2026-02-23", 0), ("2026-03-02", 0), ("2026-03-09", 1)]
+    );
+
+    let all_time =
+        report_summary(&pool, range("2026-01-01", "2026-03-15"), date("2026-03-15")).unwrap();
+    assert_eq!(all_time.throughput.iter().map(|w| w.done).sum::<i64>(), 2);
+    assert_eq!(
+        all_time
+            .avg_time_in_status
+            .iter()
+            .find(|d| d.status == "IN_PROGRESS")
+            .unwrap()
+            .avg_days,
+        Some(2.5)
+    );
+}
+
+#[test]
+fn summary_defaults_and_validation() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let summary = report_summary(&pool, ReportSummaryReq::default(), date("2026-03-15")).unwrap();
+    assert_eq!(summary.to, "2026-03-15");
+    assert_eq!(summary.from, "2025-12-21");
+    assert_eq!(summary.throughput.len(), 13);
+
+    let err =
+        report_summary(&pool, range("2026-03-15", "2026-03-01"), date("2026-03-15")).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err =
+        report_summary(&pool, range("last month", "2026-03-01"), date("2026-03-15")).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
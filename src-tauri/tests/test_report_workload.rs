@@ -0,0 +1,179 @@
+//! Per-person workload report: assignments, ownership, status counts and upcoming due dates
+
+use app_lib::app::{
+    assignment_add_member, partner_create, person_create, project_create, report_person_workload,
+    AssignmentAddReq, PartnerCreateReq, PersonCreateReq, PersonWorkloadDto, PersonWorkloadReq,
+    ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::NaiveDate;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn person(pool: &DbPool, name: &str) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: None,
+            role: Some("engineer".to_string()),
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn project(pool: &DbPool, name: &str, owner: &str, due_date: Option<&str>) -> String {
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("{} partner", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.to_string(),
+            product_name: None,
+            start_date: None,
+            due_date: due_date.map(str::to_string),
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn add_member(pool: &DbPool, project_id: &str, person_id: &str) {
+    assignment_add_member(
+        pool,
+        AssignmentAddReq {
+            project_id: project_id.to_string(),
+            person_id: person_id.to_string(),
+            role: None,
+            start_at: None,
+        },
+    )
+    .unwrap();
+}
+
+fn set_status(pool: &DbPool, project_id: &str, status: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "UPDATE projects SET current_status = ?1 WHERE id = ?2",
+        [status, project_id],
+    )
+    .unwrap();
+}
+
+fn status_count(row: &PersonWorkloadDto, status: &str) -> (i64, i64) {
+    let c = row
+        .status_counts
+        .iter()
+        .find(|c| c.status == status)
+        .unwrap();
+    (c.count, c.overdue)
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn workload_counts_assignments_ownership_and_statuses() {
+    let pool = init_test_db();
+    let alice = person(&pool, "Alice");
+    let bob = person(&pool, "Bob");
+    project(&pool, "Alpha", &alice, Some("2026-03-05"));
+    let beta = project(&pool, "Beta", &bob, Some("2026-03-20"));
+    let gamma = project(&pool, "Gamma", &alice, None);
+    add_member(&pool, &beta, &alice);
+    set_status(&pool, &gamma, "IN_PROGRESS");
+    // Archived projects are not workload.
+    let old = project(&pool, "Old", &alice, Some("2026-01-01"));
+    set_status(&pool, &old, "ARCHIVED");
+
+    let report =
+        report_person_workload(&pool, PersonWorkloadReq::default(), date("2026-03-10")).unwrap();
+    assert_eq!(report.as_of, "2026-03-10");
+    assert_eq!(report.upcoming_until, "2026-03-24");
+    let names: Vec<&str> = report
+        .persons
+        .iter()
+        .map(|p| p.display_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Alice", "Bob"]);
+
+    let a = &report.persons[0];
+    assert_eq!(a.role.as_deref(), Some("engineer"));
+    // Owners get an assignment on create: Alpha, Gamma and the Beta membership.
+    assert_eq!(a.active_assignments, 3);
+    assert_eq!(a.projects_owned, 2);
+    assert_eq!(status_count(a, "BACKLOG"), (2, 1));
+    assert_eq!(status_count(a, "IN_PROGRESS"), (1, 0));
+    assert!(a.status_counts.iter().all(|c| c.status != "ARCHIVED"));
+
+    let due: Vec<(&str, bool)> = a
+        .upcoming_due
+        .iter()
+        .map(|d| (d.project_name.as_str(), d.overdue))
+        .collect();
+    assert_eq!(due, vec![("Alpha", true), ("Beta", false)]);
+
+    let b = &report.persons[1];
+    assert_eq!(b.active_assignments, 1);
+    assert_eq!(b.projects_owned, 1);
+    assert_eq!(status_count(b, "BACKLOG"), (1, 0));
+}
+
+#[test]
+fn workload_respects_look_ahead_and_skips_inactive_people() {
+    let pool = init_test_db();
+    let alice = person(&pool, "Alice");
+    let carol = person(&pool, "Carol");
+    project(&pool, "Later", &alice, Some("2026-03-20"));
+    let done = project(&pool, "Done", &alice, Some("2026-03-01"));
+    set_status(&pool, &done, "DONE");
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute("UPDATE persons SET is_active = 0 WHERE id = ?1", [&carol])
+            .unwrap();
+    }
+
+    let report = report_person_workload(
+        &pool,
+        PersonWorkloadReq {
+            upcoming_days: Some(5),
+        },
+        date("2026-03-10"),
+    )
+    .unwrap();
+    assert_eq!(report.persons.len(), 1);
+    let a = &report.persons[0];
+    // Finished projects are never overdue or upcoming; "Later" is beyond the window.
+    assert!(a.upcoming_due.is_empty());
+    assert_eq!(status_count(a, "DONE"), (1, 0));
+
+    let err = report_person_workload(
+        &pool,
+        PersonWorkloadReq {
+            upcoming_days: Some(-1),
+        },
+        date("2026-03-10"),
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
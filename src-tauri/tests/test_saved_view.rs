@@ -0,0 +1,146 @@
+//! Saved view tests: create/list/delete and applying stored filters
+
+use app_lib::app::{
+    partner_create, person_create, project_create, saved_view_apply, saved_view_create,
+    saved_view_delete, saved_view_list, PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+    ProjectListReq, SavedViewApplyReq, SavedViewCreateReq,
+};
+use app_lib::error::AppError;
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn create_project(pool: &DbPool, name: &str, country: &str, priority: i32) -> String {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: Some(priority),
+            country_code: country.to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn apply(pool: &DbPool, id: &str, limit: Option<i32>) -> Vec<String> {
+    saved_view_apply(
+        pool,
+        SavedViewApplyReq {
+            id: id.to_string(),
+            limit,
+            offset: None,
+            after_updated_at: None,
+            after_id: None,
+        },
+    )
+    .unwrap()
+    .items
+    .into_iter()
+    .map(|p| p.name)
+    .collect()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn apply_runs_stored_filters_and_sort_with_caller_paging() {
+    let pool = init_test_db();
+    create_project(&pool, "US low", "US", 1);
+    create_project(&pool, "US high", "US", 5);
+    create_project(&pool, "CN", "CN", 3);
+
+    let view = saved_view_create(
+        &pool,
+        SavedViewCreateReq {
+            name: "US by priority".to_string(),
+            filters: ProjectListReq {
+                country_codes: Some(vec!["US".to_string()]),
+                sort_by: Some("priority".to_string()),
+                sort_order: Some("desc".to_string()),
+                limit: Some(1),
+                ..Default::default()
+            },
+        },
+    )
+    .unwrap();
+    // Paging is never stored.
+    assert_eq!(view.filters.limit, None);
+    assert_eq!(view.filters.country_codes, Some(vec!["US".to_string()]));
+
+    assert_eq!(apply(&pool, &view.id, None), vec!["US high", "US low"]);
+    assert_eq!(apply(&pool, &view.id, Some(1)), vec!["US high"]);
+}
+
+#[test]
+fn names_are_unique_and_views_can_be_deleted() {
+    let pool = init_test_db();
+    let create = |name: &str| {
+        saved_view_create(
+            &pool,
+            SavedViewCreateReq {
+                name: name.to_string(),
+                filters: ProjectListReq::default(),
+            },
+        )
+    };
+    let b = create("Beta").unwrap();
+    create("alpha").unwrap();
+    assert!(matches!(create(" BETA "), Err(AppError::Conflict(_))));
+    assert!(matches!(create("  "), Err(AppError::Validation(_))));
+
+    let names: Vec<String> = saved_view_list(&pool)
+        .unwrap()
+        .into_iter()
+        .map(|v| v.name)
+        .collect();
+    assert_eq!(names, vec!["alpha", "Beta"]);
+
+    saved_view_delete(&pool, &b.id).unwrap();
+    assert_eq!(saved_view_list(&pool).unwrap().len(), 1);
+    assert!(matches!(
+        saved_view_delete(&pool, &b.id),
+        Err(AppError::NotFound(_))
+    ));
+    assert!(matches!(
+        saved_view_apply(
+            &pool,
+            SavedViewApplyReq {
+                id: b.id,
+                limit: None,
+                offset: None,
+                after_updated_at: None,
+                after_id: None,
+            },
+        ),
+        Err(AppError::NotFound(_))
+    ));
+}
@@ -0,0 +1,69 @@
+//! Schema export: introspected tables, relations and rendered ERDs
+
+use app_lib::app::{db_schema_export, DbSchemaExportReq, SchemaFormat};
+use app_lib::infra::db::init_test_db;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn export(format: Option<SchemaFormat>) -> app_lib::app::DbSchemaExportDto {
+    let pool = init_test_db();
+    db_schema_export(&pool, DbSchemaExportReq { format }).unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn json_export_describes_current_tables() {
+    let schema = export(None);
+    assert_eq!(schema.format, SchemaFormat::Json);
+    assert!(schema.schema_version >= 29);
+
+    let projects = schema.tables.iter().find(|t| t.name == "projects").unwrap();
+    let id = projects.columns.iter().find(|c| c.name == "id").unwrap();
+    assert_eq!(id.primary_key, 1);
+    assert!(projects.columns.iter().any(|c| c.name == "deleted_at"));
+    assert!(!projects.triggers.is_empty());
+    assert!(schema.tables.iter().all(|t| !t.name.starts_with("sqlite_")));
+
+    let content: serde_json::Value = serde_json::from_str(&schema.content).unwrap();
+    assert_eq!(content["schemaVersion"], schema.schema_version);
+    assert_eq!(
+        content["tables"].as_array().unwrap().len(),
+        schema.tables.len()
+    );
+}
+
+#[test]
+fn relations_include_declared_and_implicit_references() {
+    let schema = export(None);
+    let relation = |from: &str, column: &str| {
+        schema
+            .relations
+            .iter()
+            .find(|r| r.from_table == from && r.from_column == column)
+    };
+    let owner = relation("projects", "owner_person_id").unwrap();
+    assert_eq!(owner.to_table, "persons");
+    assert_eq!(owner.to_column, "id");
+    let field = relation("project_custom_values", "field_id").unwrap();
+    assert_eq!(field.to_table, "custom_field_defs");
+    assert!(!field.declared);
+}
+
+#[test]
+fn mermaid_and_graphviz_render_erds() {
+    let mermaid = export(Some(SchemaFormat::Mermaid));
+    assert!(mermaid.content.starts_with("erDiagram\n"));
+    assert!(mermaid.content.contains("    projects {\n"));
+    assert!(mermaid.content.contains("TEXT id PK"));
+    assert!(mermaid
+        .content
+        .contains("persons ||--o{ assignments : \"person_id\""));
+
+    let dot = export(Some(SchemaFormat::Graphviz));
+    assert!(dot.content.starts_with("digraph schema {"));
+    assert!(dot
+        .content
+        .contains("\"assignments\":\"project_id\" -> \"projects\":\"id\";"));
+    assert!(dot.content.trim_end().ends_with('}'));
+}
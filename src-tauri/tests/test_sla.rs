@@ -0,0 +1,337 @@
+//! SLA / staleness rule tests
+
+use app_lib::app::{
+    notification_check, notification_list, partner_create, person_create, project_change_status,
+    project_create, project_flag_list, project_list, sla_evaluate, sla_rule_create,
+    sla_rule_delete, sla_rule_list, sla_rule_update, NotificationListReq, PartnerCreateReq,
+    PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq, ProjectListReq, SlaRuleCreateReq,
+    SlaRuleUpdateReq, MAX_SLA_DAYS, NOTIFICATION_KIND_SLA_BREACHED,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::{NaiveDate, TimeZone, Utc};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn setup(pool: &DbPool) -> (String, String) {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    (owner.id, partner.id)
+}
+
+fn project(pool: &DbPool, owner: &str, partner: &str, name: &str) -> String {
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "US".to_string(),
+            partner_id: partner.to_string(),
+            owner_person_id: owner.to_string(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn change_status(pool: &DbPool, project_id: &str, to: &str) {
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: project_id.to_string(),
+            to_status: to.to_string(),
+            note: Some("moving on".to_string()),
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+}
+
+fn rule(pool: &DbPool, name: &str, status: Option<&str>, max_days: i64) -> String {
+    sla_rule_create(
+        pool,
+        SlaRuleCreateReq {
+            name: name.to_string(),
+            status: status.map(str::to_string),
+            max_days,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+/// Move every status change and the creation of a project to `at`.
+fn backdate(pool: &DbPool, project_id: &str, at: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "UPDATE status_history SET changed_at = ?2 WHERE project_id = ?1",
+        [project_id, at],
+    )
+    .unwrap();
+    conn.execute(
+        "UPDATE projects SET created_at = ?2 WHERE id = ?1",
+        [project_id, at],
+    )
+    .unwrap();
+}
+
+fn flagged_rules(pool: &DbPool, project_id: &str) -> Vec<String> {
+    project_flag_list(pool, Some(project_id.to_string()))
+        .unwrap()
+        .into_iter()
+        .map(|f| f.rule_name)
+        .collect()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn rule_crud_and_validation() {
+    let pool = init_test_db();
+    let id = rule(&pool, "Blocked > 14 days", Some(" BLOCKED "), 14);
+    let rules = sla_rule_list(&pool).unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].status.as_deref(), Some("BLOCKED"));
+    assert!(rules[0].enabled);
+
+    let bad = |name: &str, max_days: i64| {
+        sla_rule_create(
+            &pool,
+            SlaRuleCreateReq {
+                name: name.to_string(),
+                status: None,
+                max_days,
+            },
+        )
+        .unwrap_err()
+        .code()
+        .to_string()
+    };
+    assert_eq!(bad("  ", 5), "VALIDATION_ERROR");
+    assert_eq!(bad("Stale", 0), "VALIDATION_ERROR");
+    assert_eq!(bad("Stale", MAX_SLA_DAYS + 1), "VALIDATION_ERROR");
+    assert_eq!(bad("blocked > 14 DAYS", 5), "CONFLICT");
+
+    // An empty status means "any open status".
+    let updated = sla_rule_update(
+        &pool,
+        SlaRuleUpdateReq {
+            id: id.clone(),
+            name: None,
+            status: Some(String::new()),
+            max_days: Some(30),
+            enabled: Some(false),
+        },
+    )
+    .unwrap();
+    assert_eq!(updated.status, None);
+    assert_eq!(updated.max_days, 30);
+    assert!(!updated.enabled);
+
+    sla_rule_delete(&pool, &id).unwrap();
+    assert!(sla_rule_list(&pool).unwrap().is_empty());
+    assert_eq!(sla_rule_delete(&pool, &id).unwrap_err().code(), "NOT_FOUND");
+}
+
+#[test]
+fn evaluate_flags_projects_past_the_limit() {
+    let pool = init_test_db();
+    let (owner, partner) = setup(&pool);
+    let blocked_rule = rule(&pool, "Blocked > 14 days", Some("BLOCKED"), 14);
+    let stale_rule = rule(&pool, "No change in 30 days", None, 30);
+
+    let stuck = project(&pool, &owner, &partner, "Stuck");
+    change_status(&pool, &stuck, "PLANNED");
+    change_status(&pool, &stuck, "IN_PROGRESS");
+    change_status(&pool, &stuck, "BLOCKED");
+    backdate(&pool, &stuck, "2026-01-01T09:00:00+00:00");
+    let idle = project(&pool, &owner, &partner, "Idle");
+    backdate(&pool, &idle, "2026-01-10T09:00:00+00:00");
+    let done = project(&pool, &owner, &partner, "Done");
+    change_status(&pool, &done, "PLANNED");
+    change_status(&pool, &done, "IN_PROGRESS");
+    change_status(&pool, &done, "DONE");
+    backdate(&pool, &done, "2025-06-01T09:00:00+00:00");
+
+    let now = Utc.with_ymd_and_hms(2026, 1, 15, 9, 0, 0).unwrap();
+    let early = sla_evaluate(&pool, day("2026-01-15"), now).unwrap();
+    assert_eq!(early.flagged, 0);
+
+    // 16 days blocked, 7 days idle.
+    let result = sla_evaluate(&pool, day("2026-01-17"), now).unwrap();
+    assert_eq!(result.flagged, 1);
+    assert_eq!(flagged_rules(&pool, &stuck), vec!["Blocked > 14 days"]);
+
+    // 39 days blocked, 30 days idle: the idle project is exactly at the limit.
+    sla_evaluate(&pool, day("2026-02-09"), now).unwrap();
+    assert_eq!(
+        flagged_rules(&pool, &stuck),
+        vec!["Blocked > 14 days", "No change in 30 days"]
+    );
+    assert!(flagged_rules(&pool, &idle).is_empty());
+    sla_evaluate(&pool, day("2026-02-10"), now).unwrap();
+    assert_eq!(flagged_rules(&pool, &idle), vec!["No change in 30 days"]);
+    assert!(flagged_rules(&pool, &done).is_empty());
+
+    let flags = project_flag_list(&pool, None).unwrap();
+    assert_eq!(flags.len(), 3);
+    assert_eq!(flags[0].project_name, "Stuck");
+    assert_eq!(flags[0].current_status, "BLOCKED");
+    assert_eq!(flags[0].since, "2026-01-01T09:00:00+00:00");
+
+    // Same day again: nothing changes.
+    let again = sla_evaluate(&pool, day("2026-02-10"), now).unwrap();
+    assert_eq!((again.flagged, again.cleared), (0, 0));
+
+    // Disabling a rule clears its flags at the next evaluation.
+    sla_rule_update(
+        &pool,
+        SlaRuleUpdateReq {
+            id: stale_rule,
+            name: None,
+            status: None,
+            max_days: None,
+            enabled: Some(false),
+        },
+    )
+    .unwrap();
+    sla_evaluate(&pool, day("2026-02-10"), now).unwrap();
+    assert_eq!(flagged_rules(&pool, &stuck), vec!["Blocked > 14 days"]);
+    assert!(flagged_rules(&pool, &idle).is_empty());
+
+    // Deleting a rule removes its flags.
+    sla_rule_delete(&pool, &blocked_rule).unwrap();
+    assert!(project_flag_list(&pool, None).unwrap().is_empty());
+}
+
+#[test]
+fn status_change_clears_flags_and_list_filters() {
+    let pool = init_test_db();
+    let (owner, partner) = setup(&pool);
+    let blocked_rule = rule(&pool, "Blocked > 14 days", Some("BLOCKED"), 14);
+    let stuck = project(&pool, &owner, &partner, "Stuck");
+    change_status(&pool, &stuck, "PLANNED");
+    change_status(&pool, &stuck, "IN_PROGRESS");
+    change_status(&pool, &stuck, "BLOCKED");
+    backdate(&pool, &stuck, "2026-01-01T09:00:00+00:00");
+    let fine = project(&pool, &owner, &partner, "Fine");
+
+    let now = Utc.with_ymd_and_hms(2026, 1, 20, 9, 0, 0).unwrap();
+    sla_evaluate(&pool, day("2026-01-20"), now).unwrap();
+
+    let flagged = project_list(
+        &pool,
+        ProjectListReq {
+            flagged: Some(true),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(flagged.total, 1);
+    assert_eq!(flagged.items[0].id, stuck);
+    assert_eq!(flagged.items[0].flags, vec!["Blocked > 14 days"]);
+
+    let unflagged = project_list(
+        &pool,
+        ProjectListReq {
+            flagged: Some(false),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(unflagged.total, 1);
+    assert_eq!(unflagged.items[0].id, fine);
+    assert!(unflagged.items[0].flags.is_empty());
+
+    let by_rule = project_list(
+        &pool,
+        ProjectListReq {
+            flag_rule_ids: Some(vec![blocked_rule.clone()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(by_rule.total, 1);
+    let other_rule = project_list(
+        &pool,
+        ProjectListReq {
+            flag_rule_ids: Some(vec!["missing".to_string()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(other_rule.total, 0);
+
+    // Unblocked: the flag goes away right away.
+    change_status(&pool, &stuck, "IN_PROGRESS");
+    assert!(flagged_rules(&pool, &stuck).is_empty());
+    let after = project_list(
+        &pool,
+        ProjectListReq {
+            flagged: Some(true),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(after.total, 0);
+}
+
+#[test]
+fn notification_check_reports_breaches() {
+    let pool = init_test_db();
+    let (owner, partner) = setup(&pool);
+    rule(&pool, "In progress > 30 days", Some("IN_PROGRESS"), 30);
+    let slow = project(&pool, &owner, &partner, "Slow");
+    change_status(&pool, &slow, "PLANNED");
+    change_status(&pool, &slow, "IN_PROGRESS");
+    backdate(&pool, &slow, "2026-01-01T09:00:00+00:00");
+
+    let now = Utc.with_ymd_and_hms(2026, 2, 10, 9, 0, 0).unwrap();
+    let result = notification_check(&pool, day("2026-02-10"), now).unwrap();
+    assert_eq!(result.created.len(), 1);
+    let reminder = &result.created[0];
+    assert_eq!(reminder.kind, NOTIFICATION_KIND_SLA_BREACHED);
+    assert_eq!(reminder.project_id, slow);
+    assert_eq!(reminder.title, "In progress > 30 days: Slow");
+    assert!(reminder.message.contains("40 days"));
+
+    let again = notification_check(&pool, day("2026-02-11"), now).unwrap();
+    assert!(again.created.is_empty());
+
+    // Back to planning: the flag and the reminder are closed.
+    change_status(&pool, &slow, "PLANNED");
+    let resolved = notification_check(&pool, day("2026-02-12"), now).unwrap();
+    assert_eq!(resolved.resolved, 1);
+    assert!(notification_list(&pool, NotificationListReq::default())
+        .unwrap()
+        .is_empty());
+}
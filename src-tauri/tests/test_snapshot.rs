@@ -1,6 +1,7 @@
 //! Snapshot manager integration tests (in-memory SQLite)
 
 use app_lib::infra::db::init_test_db;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
 use app_lib::sync::snapshot::Snapshot;
 use app_lib::sync::SnapshotManager;
 
@@ -73,6 +74,8 @@ fn snapshot_verify_passes_for_valid() {
         device_id: "d1".into(),
         data: "test data".into(),
         checksum: Snapshot::calculate_checksum("test data"),
+        format_version: SYNC_FORMAT_VERSION,
+        scope: Default::default(),
     };
     assert!(snap.verify());
 }
@@ -85,6 +88,8 @@ fn snapshot_verify_fails_for_tampered_data() {
         device_id: "d1".into(),
         data: "tampered data".into(),
         checksum: Snapshot::calculate_checksum("original data"),
+        format_version: SYNC_FORMAT_VERSION,
+        scope: Default::default(),
     };
     assert!(!snap.verify());
 }
@@ -97,6 +102,8 @@ fn snapshot_compress_decompress_roundtrip() {
         device_id: "test-device".into(),
         data: r#"{"persons":[],"partners":[],"projects":[]}"#.into(),
         checksum: Snapshot::calculate_checksum(r#"{"persons":[],"partners":[],"projects":[]}"#),
+        format_version: SYNC_FORMAT_VERSION,
+        scope: Default::default(),
     };
 
     let compressed = snap.compress().unwrap();
@@ -215,8 +222,32 @@ fn seed_full_data(pool: &app_lib::infra::DbPool) {
         [],
     ).unwrap();
     conn.execute(
-        "INSERT INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at, _version)
-         VALUES ('c1', 'proj1', 'p1', '{\"type\":\"doc\",\"content\":[]}', 1, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z', 1)",
+        "INSERT INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at, _version, pin_order)
+         VALUES ('c1', 'proj1', 'p1', '{\"type\":\"doc\",\"content\":[]}', 1, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z', 1, 2)",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at, _version, parent_comment_id)
+         VALUES ('c2', 'proj1', 'p1', 'reply', 0, '2026-01-02T00:00:00Z', '2026-01-02T00:00:00Z', 1, 'c1')",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO comment_reactions (id, comment_id, project_id, person_id, emoji, created_at, _version)
+         VALUES ('r1', 'c1', 'proj1', 'p1', '👍', '2026-01-02T00:00:00Z', 1)",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO comment_revisions (id, comment_id, project_id, person_id, content, written_at, replaced_at, _version)
+         VALUES ('rv1', 'c1', 'proj1', 'p1', 'first draft', '2025-12-31T00:00:00Z', '2026-01-01T00:00:00Z', 1)",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO checklist_items (id, project_id, text, done, sort_order, done_at, created_at, updated_at, _version)
+         VALUES ('ck1', 'proj1', 'Sign contract', 1, 1, '2026-01-03T00:00:00Z', '2026-01-01T00:00:00Z', '2026-01-03T00:00:00Z', 1)",
         [],
     )
     .unwrap();
@@ -251,7 +282,10 @@ fn restore_snapshot_full_flow() {
     assert_eq!(count_table(&pool, "projects"), 1);
     assert_eq!(count_table(&pool, "assignments"), 1);
     assert_eq!(count_table(&pool, "status_history"), 1);
-    assert_eq!(count_table(&pool, "project_comments"), 1);
+    assert_eq!(count_table(&pool, "project_comments"), 2);
+    assert_eq!(count_table(&pool, "comment_reactions"), 1);
+    assert_eq!(count_table(&pool, "comment_revisions"), 1);
+    assert_eq!(count_table(&pool, "checklist_items"), 1);
 
     // Verify content
     let conn = pool.0.lock().unwrap();
@@ -272,6 +306,34 @@ fn restore_snapshot_full_flow() {
         )
         .unwrap();
     assert_eq!(proj_name, "Demo");
+
+    let parent: Option<String> = conn
+        .query_row(
+            "SELECT parent_comment_id FROM project_comments WHERE id = 'c2'",
+            [],
+            |r: &rusqlite::Row<'_>| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(parent.as_deref(), Some("c1"));
+
+    let pin_order: Option<i64> = conn
+        .query_row(
+            "SELECT pin_order FROM project_comments WHERE id = 'c1'",
+            [],
+            |r: &rusqlite::Row<'_>| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(pin_order, Some(2));
+
+    let (done, done_at): (i64, Option<String>) = conn
+        .query_row(
+            "SELECT done, done_at FROM checklist_items WHERE id = 'ck1'",
+            [],
+            |r: &rusqlite::Row<'_>| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(done, 1);
+    assert_eq!(done_at.as_deref(), Some("2026-01-03T00:00:00Z"));
 }
 
 #[test]
@@ -294,6 +356,9 @@ fn restore_snapshot_clears_all_tables() {
     assert_eq!(count_table(&pool, "assignments"), 0);
     assert_eq!(count_table(&pool, "status_history"), 0);
     assert_eq!(count_table(&pool, "project_comments"), 0);
+    assert_eq!(count_table(&pool, "comment_reactions"), 0);
+    assert_eq!(count_table(&pool, "comment_revisions"), 0);
+    assert_eq!(count_table(&pool, "checklist_items"), 0);
 }
 
 #[test]
@@ -307,6 +372,8 @@ fn restore_snapshot_fails_for_tampered_checksum() {
         device_id: "d1".into(),
         data: r#"{"schemaVersion":1,"exportedAt":"","persons":[],"partners":[],"projects":[],"assignments":[],"statusHistory":[]}"#.into(),
         checksum: "wrong_checksum".into(),
+        format_version: SYNC_FORMAT_VERSION,
+        scope: Default::default(),
     };
 
     let err = mgr.restore_snapshot(&snap);
@@ -326,6 +393,8 @@ fn restore_snapshot_fails_for_invalid_json() {
         device_id: "d1".into(),
         data: bad_data.into(),
         checksum: Snapshot::calculate_checksum(bad_data),
+        format_version: SYNC_FORMAT_VERSION,
+        scope: Default::default(),
     };
 
     let err = mgr.restore_snapshot(&snap);
@@ -335,3 +404,94 @@ fn restore_snapshot_fails_for_invalid_json() {
         .to_string()
         .contains("Invalid snapshot data"));
 }
+
+// ──────────────────────── Restore preview (diff) ────────────────────────
+
+fn diff_for<'a>(
+    diffs: &'a [app_lib::sync::snapshot::TableRestoreDiff],
+    table: &str,
+) -> &'a app_lib::sync::snapshot::TableRestoreDiff {
+    diffs.iter().find(|d| d.table == table).unwrap()
+}
+
+#[test]
+fn diff_snapshot_counts_added_updated_deleted() {
+    let (pool, device_id) = setup();
+    seed_data(&pool);
+    let mgr = SnapshotManager::new(&pool, device_id);
+    let snapshot = mgr.create_snapshot().unwrap();
+
+    {
+        let conn = pool.0.lock().unwrap();
+        // Updated locally after the snapshot.
+        conn.execute(
+            "UPDATE persons SET email = 'new@test.com' WHERE id = 'p1'",
+            [],
+        )
+        .unwrap();
+        // Created locally after the snapshot → would be deleted by restore.
+        conn.execute(
+            "INSERT INTO partners (id, name, note, is_active, created_at, updated_at, _version)
+             VALUES ('pt2', 'Globex', '', 1, '2026-01-02', '2026-01-02', 1)",
+            [],
+        )
+        .unwrap();
+        // Deleted locally after the snapshot → would be added back by restore.
+        conn.execute("DELETE FROM projects WHERE id = 'proj1'", [])
+            .unwrap();
+    }
+
+    let diffs = mgr.diff_snapshot(&snapshot).unwrap();
+    assert_eq!(diffs.len(), 12);
+
+    let persons = diff_for(&diffs, "persons");
+    assert_eq!((persons.added, persons.updated, persons.deleted), (0, 1, 0));
+    let partners = diff_for(&diffs, "partners");
+    assert_eq!(
+        (partners.unchanged, partners.added, partners.deleted),
+        (1, 0, 1)
+    );
+    let projects = diff_for(&diffs, "projects");
+    assert_eq!((projects.added, projects.deleted), (1, 0));
+
+    // Dry run: nothing changed locally.
+    assert_eq!(count_table(&pool, "partners"), 2);
+    assert_eq!(count_table(&pool, "projects"), 0);
+}
+
+#[test]
+fn diff_snapshot_of_current_state_is_all_unchanged() {
+    let (pool, device_id) = setup();
+    seed_full_data(&pool);
+    let mgr = SnapshotManager::new(&pool, device_id);
+    let snapshot = mgr.create_snapshot().unwrap();
+
+    let diffs = mgr.diff_snapshot(&snapshot).unwrap();
+    for diff in &diffs {
+        assert_eq!(
+            (diff.added, diff.updated, diff.deleted),
+            (0, 0, 0),
+            "{}",
+            diff.table
+        );
+    }
+    assert!(diffs.iter().any(|d| d.unchanged > 0));
+}
+
+#[test]
+fn diff_snapshot_rejects_tampered_checksum() {
+    let (pool, device_id) = setup();
+    let mgr = SnapshotManager::new(&pool, device_id);
+    let snap = Snapshot {
+        version: 1,
+        created_at: "2026-01-01".into(),
+        device_id: "d1".into(),
+        data: r#"{"persons":[]}"#.into(),
+        checksum: "wrong_checksum".into(),
+        format_version: SYNC_FORMAT_VERSION,
+        scope: Default::default(),
+    };
+
+    let err = mgr.diff_snapshot(&snap).unwrap_err();
+    assert!(err.to_string().contains("integrity"));
+}
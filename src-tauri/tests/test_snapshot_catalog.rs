@@ -0,0 +1,94 @@
+//! Snapshot key / retention tests (pure functions, no S3)
+
+use app_lib::sync::snapshot_catalog::{
+    describe_snapshots, parse_snapshot_key, snapshot_key, snapshots_to_prune,
+};
+use app_lib::sync::S3ObjectSummary;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn object(key: &str, last_modified_unix: i64) -> S3ObjectSummary {
+    S3ObjectSummary {
+        key: key.to_string(),
+        last_modified_unix: Some(last_modified_unix),
+        size_bytes: Some(128),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn parses_timestamped_and_legacy_keys() {
+    let key = snapshot_key("device-a", 1_700_000_000_123_456_789);
+    assert_eq!(key, "snapshots/device-a/snapshot-1700000000123456789.gz");
+    assert_eq!(
+        parse_snapshot_key(&key),
+        Some(("device-a", Some(1_700_000_000_123_456_789)))
+    );
+    assert_eq!(
+        parse_snapshot_key("snapshots/latest-device-b.gz"),
+        Some(("device-b", None))
+    );
+    assert!(parse_snapshot_key("snapshots/device-a/not-a-snapshot.gz").is_none());
+    assert!(parse_snapshot_key("deltas/device-a/delta-1.gz").is_none());
+}
+
+#[test]
+fn describe_orders_newest_first_and_skips_unknown_keys() {
+    let objects = vec![
+        object("snapshots/latest-device-a.gz", 1_600_000_000),
+        object(
+            &snapshot_key("device-a", 1_700_000_000_000_000_000),
+            1_700_000_000,
+        ),
+        object(
+            &snapshot_key("device-b", 1_700_000_100_000_000_000),
+            1_700_000_100,
+        ),
+        object("snapshots/readme.txt", 1_800_000_000),
+    ];
+
+    let snapshots = describe_snapshots(&objects);
+    let keys: Vec<&str> = snapshots.iter().map(|s| s.key.as_str()).collect();
+    assert_eq!(
+        keys,
+        vec![
+            "snapshots/device-b/snapshot-1700000100000000000.gz",
+            "snapshots/device-a/snapshot-1700000000000000000.gz",
+            "snapshots/latest-device-a.gz",
+        ]
+    );
+    assert_eq!(
+        snapshots[0].created_at.as_deref(),
+        Some("2023-11-14T22:15:00+00:00")
+    );
+    assert!(snapshots[2].legacy);
+    assert_eq!(snapshots[2].created_at, None);
+    assert_eq!(snapshots[0].size_bytes, Some(128));
+}
+
+#[test]
+fn prune_keeps_newest_own_snapshots_only() {
+    let objects: Vec<S3ObjectSummary> = (1..=4)
+        .map(|i| object(&snapshot_key("device-a", i * 1_000_000_000), i))
+        .chain(std::iter::once(object(
+            &snapshot_key("device-b", 1_000_000_000),
+            1,
+        )))
+        .chain(std::iter::once(object("snapshots/latest-device-a.gz", 0)))
+        .collect();
+    let snapshots = describe_snapshots(&objects);
+
+    let pruned = snapshots_to_prune(&snapshots, "device-a", 2);
+    assert_eq!(
+        pruned,
+        vec![
+            "snapshots/device-a/snapshot-2000000000.gz".to_string(),
+            "snapshots/device-a/snapshot-1000000000.gz".to_string(),
+            "snapshots/latest-device-a.gz".to_string(),
+        ]
+    );
+
+    // A retention of 0 is treated as 1: the newest snapshot always survives.
+    assert_eq!(snapshots_to_prune(&snapshots, "device-a", 0).len(), 4);
+}
@@ -0,0 +1,309 @@
+//! Status automation tests: overdue flag and BACKLOG → PLANNED at the start date
+
+use app_lib::app::{
+    partner_create, person_create, project_change_status, project_create, project_get,
+    project_list, status_automation_get_config, status_automation_run,
+    status_automation_update_config, workflow_list, workflow_update, PartnerCreateReq,
+    PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq, ProjectListReq,
+    StatusAutomationConfigReq, WorkflowUpdateReq,
+};
+use app_lib::domain::WorkflowStatus;
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::NaiveDate;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn day(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn setup(pool: &DbPool) -> (String, String) {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    (owner.id, partner.id)
+}
+
+fn project(
+    pool: &DbPool,
+    (owner, partner): &(String, String),
+    name: &str,
+    start: Option<&str>,
+    due: Option<&str>,
+) -> String {
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "US".to_string(),
+            partner_id: partner.clone(),
+            owner_person_id: owner.clone(),
+            product_name: None,
+            start_date: start.map(str::to_string),
+            due_date: due.map(str::to_string),
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn change_status(pool: &DbPool, project_id: &str, to: &str) {
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: project_id.to_string(),
+            to_status: to.to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+}
+
+fn configure(pool: &DbPool, overdue: Option<bool>, auto_plan: Option<bool>) {
+    status_automation_update_config(
+        pool,
+        StatusAutomationConfigReq {
+            overdue_enabled: overdue,
+            auto_plan_enabled: auto_plan,
+        },
+    )
+    .unwrap();
+}
+
+fn overdue_names(pool: &DbPool) -> Vec<String> {
+    let mut names: Vec<String> = project_list(pool, ProjectListReq::default())
+        .unwrap()
+        .items
+        .into_iter()
+        .filter(|p| p.overdue)
+        .map(|p| p.name)
+        .collect();
+    names.sort();
+    names
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn config_defaults_off_and_updates_independently() {
+    let pool = init_test_db();
+    let config = status_automation_get_config(&pool).unwrap();
+    assert!(!config.overdue_enabled);
+    assert!(!config.auto_plan_enabled);
+
+    configure(&pool, Some(true), None);
+    configure(&pool, None, Some(true));
+    let config = status_automation_get_config(&pool).unwrap();
+    assert!(config.overdue_enabled);
+    assert!(config.auto_plan_enabled);
+
+    configure(&pool, Some(false), None);
+    let config = status_automation_get_config(&pool).unwrap();
+    assert!(!config.overdue_enabled);
+    assert!(config.auto_plan_enabled);
+}
+
+#[test]
+fn overdue_flag_marks_open_projects_past_due() {
+    let pool = init_test_db();
+    let people = setup(&pool);
+    let late = project(&pool, &people, "Late", None, Some("2000-01-01"));
+    project(&pool, &people, "Future", None, Some("2999-12-31"));
+    project(&pool, &people, "Undated", None, None);
+    let done = project(&pool, &people, "Done", None, Some("2000-01-01"));
+    change_status(&pool, &done, "PLANNED");
+    change_status(&pool, &done, "IN_PROGRESS");
+    change_status(&pool, &done, "DONE");
+
+    // Off by default.
+    assert!(overdue_names(&pool).is_empty());
+    assert!(!project_get(&pool, &late).unwrap().overdue);
+
+    configure(&pool, Some(true), None);
+    assert_eq!(overdue_names(&pool), vec!["Late"]);
+    assert!(project_get(&pool, &late).unwrap().overdue);
+    assert!(!project_get(&pool, &done).unwrap().overdue);
+}
+
+#[test]
+fn auto_plan_moves_backlog_projects_at_their_start_date() {
+    let pool = init_test_db();
+    let people = setup(&pool);
+    let starting = project(&pool, &people, "Starting", Some("2026-03-01"), None);
+    let later = project(&pool, &people, "Later", Some("2026-03-10"), None);
+    let undated = project(&pool, &people, "Undated", None, None);
+    let planned = project(&pool, &people, "Planned", Some("2026-02-01"), None);
+    change_status(&pool, &planned, "PLANNED");
+
+    // Off by default.
+    assert!(status_automation_run(&pool, day("2026-03-05"))
+        .unwrap()
+        .is_empty());
+
+    configure(&pool, None, Some(true));
+    assert!(status_automation_run(&pool, day("2026-02-28"))
+        .unwrap()
+        .is_empty());
+
+    let items = status_automation_run(&pool, day("2026-03-01")).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].project_id, starting);
+    assert_eq!(items[0].to_status, "PLANNED");
+    assert!(items[0].error.is_none());
+
+    let detail = project_get(&pool, &starting).unwrap();
+    assert_eq!(detail.current_status, "PLANNED");
+    let entry = &detail.status_history[0];
+    assert_eq!(entry.from_status.as_deref(), Some("BACKLOG"));
+    assert_eq!(entry.to_status, "PLANNED");
+    assert!(entry.changed_by_person_id.is_none());
+    assert_eq!(entry.note, "[auto] start date 2026-03-01 reached");
+
+    assert_eq!(
+        project_get(&pool, &later).unwrap().current_status,
+        "BACKLOG"
+    );
+    assert_eq!(
+        project_get(&pool, &undated).unwrap().current_status,
+        "BACKLOG"
+    );
+    assert_eq!(
+        project_get(&pool, &planned).unwrap().status_history.len(),
+        2
+    );
+
+    // Already moved: nothing left to do on the same day.
+    assert!(status_automation_run(&pool, day("2026-03-01"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn auto_plan_runs_once_per_start_date() {
+    let pool = init_test_db();
+    let people = setup(&pool);
+    let starting = project(&pool, &people, "Starting", Some("2026-03-01"), None);
+    configure(&pool, None, Some(true));
+    assert_eq!(
+        status_automation_run(&pool, day("2026-03-01"))
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // The history row arrived (e.g. from another device) but the project is still in
+    // BACKLOG here: no second transition, no duplicate history row.
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE projects SET current_status = 'BACKLOG' WHERE id = ?1",
+            [&starting],
+        )
+        .unwrap();
+    }
+    assert!(status_automation_run(&pool, day("2026-03-02"))
+        .unwrap()
+        .is_empty());
+    let detail = project_get(&pool, &starting).unwrap();
+    assert_eq!(detail.current_status, "BACKLOG");
+    assert_eq!(detail.status_history.len(), 2);
+}
+
+#[test]
+fn auto_plan_respects_wip_limits_and_retries() {
+    let pool = init_test_db();
+    let people = setup(&pool);
+    let default = workflow_list(&pool).unwrap().remove(0);
+    let statuses = default
+        .statuses
+        .into_iter()
+        .map(|s| match s.key.as_str() {
+            "PLANNED" => WorkflowStatus {
+                wip_limit: Some(1),
+                ..s
+            },
+            _ => s,
+        })
+        .collect();
+    workflow_update(
+        &pool,
+        WorkflowUpdateReq {
+            id: default.id,
+            name: None,
+            statuses: Some(statuses),
+            transitions: None,
+        },
+    )
+    .unwrap();
+    let first = project(&pool, &people, "First", Some("2026-03-01"), None);
+    let second = project(&pool, &people, "Second", Some("2026-03-02"), None);
+    configure(&pool, None, Some(true));
+
+    let items = status_automation_run(&pool, day("2026-03-02")).unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(items[0].error.is_none());
+    assert_eq!(items[1].project_id, second);
+    assert!(items[1].error.is_some());
+    assert_eq!(
+        project_get(&pool, &second).unwrap().current_status,
+        "BACKLOG"
+    );
+
+    // Room again: the next run picks it up.
+    change_status(&pool, &first, "IN_PROGRESS");
+    let retry = status_automation_run(&pool, day("2026-03-03")).unwrap();
+    assert_eq!(retry.len(), 1);
+    assert!(retry[0].error.is_none());
+    assert_eq!(
+        project_get(&pool, &second).unwrap().current_status,
+        "PLANNED"
+    );
+}
+
+#[test]
+fn read_only_replica_skips_auto_plan() {
+    let pool = init_test_db();
+    let people = setup(&pool);
+    let starting = project(&pool, &people, "Starting", Some("2026-03-01"), None);
+    configure(&pool, None, Some(true));
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO sync_config (key, value) VALUES ('read_only_replica', '1')",
+            [],
+        )
+        .unwrap();
+    }
+
+    assert!(status_automation_run(&pool, day("2026-03-05"))
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        project_get(&pool, &starting).unwrap().current_status,
+        "BACKLOG"
+    );
+}
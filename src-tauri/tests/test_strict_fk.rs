@@ -0,0 +1,295 @@
+//! Strict foreign-key mode: referential pre-checks and deferred remote operations
+
+use app_lib::app::{
+    assignment_add_member, partner_create, person_create, project_create, strict_fk_get_config,
+    strict_fk_update_config, AssignmentAddReq, PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+    StrictFkConfigReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::{Delta, DeltaSyncEngine, Operation, OperationType, VectorClock};
+use serde_json::json;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn device_id(pool: &DbPool) -> String {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = 'device_id'",
+        [],
+        |row: &rusqlite::Row<'_>| row.get::<_, String>(0),
+    )
+    .unwrap()
+}
+
+fn set_strict(pool: &DbPool, enabled: bool) {
+    strict_fk_update_config(pool, StrictFkConfigReq { enabled }).unwrap();
+}
+
+fn seed_person(pool: &DbPool) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn seed_partner(pool: &DbPool) -> String {
+    partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn create_req(name: &str, partner_id: &str, owner_person_id: &str) -> ProjectCreateReq {
+    ProjectCreateReq {
+        name: name.to_string(),
+        description: None,
+        priority: None,
+        country_code: "CN".to_string(),
+        partner_id: partner_id.to_string(),
+        owner_person_id: owner_person_id.to_string(),
+        product_name: None,
+        start_date: None,
+        due_date: None,
+        tags: None,
+        created_by_person_id: None,
+        parent_project_id: None,
+    }
+}
+
+fn exec(pool: &DbPool, sql: &str, id: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(sql, [id]).unwrap();
+}
+
+fn count(pool: &DbPool, sql: &str) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(sql, [], |row: &rusqlite::Row<'_>| row.get(0))
+        .unwrap()
+}
+
+fn make_delta(operations: Vec<Operation>) -> Delta {
+    let checksum = Delta::calculate_checksum(&operations);
+    Delta {
+        id: 1,
+        operations,
+        device_id: "remote-device".into(),
+        vector_clock: VectorClock::new("remote-device".into()),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        checksum,
+        format_version: SYNC_FORMAT_VERSION,
+    }
+}
+
+fn remote_project(id: &str, partner_id: &str, owner_person_id: &str) -> Operation {
+    Operation {
+        table_name: "projects".into(),
+        record_id: id.into(),
+        op_type: OperationType::Insert,
+        data: Some(json!({
+            "id": id,
+            "name": "Remote Project",
+            "description": null,
+            "priority": 3,
+            "current_status": "BACKLOG",
+            "country_code": "US",
+            "partner_id": partner_id,
+            "owner_person_id": owner_person_id,
+            "start_date": null,
+            "due_date": null,
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+            "archived_at": null
+        })),
+        version: 1,
+    }
+}
+
+fn remote_assignment(id: &str, project_id: &str, person_id: &str) -> Operation {
+    Operation {
+        table_name: "assignments".into(),
+        record_id: id.into(),
+        op_type: OperationType::Insert,
+        data: Some(json!({
+            "id": id,
+            "project_id": project_id,
+            "person_id": person_id,
+            "role": "member",
+            "start_at": "2026-01-01T00:00:00Z",
+            "end_at": null,
+            "created_at": "2026-01-01T00:00:00Z"
+        })),
+        version: 1,
+    }
+}
+
+fn assert_fk_violation(err: app_lib::error::AppError, needle: &str) {
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let message = err.to_string();
+    assert!(message.contains("FK_VIOLATION"), "{}", message);
+    assert!(message.contains(needle), "{}", message);
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn strict_mode_is_off_by_default_and_toggles() {
+    let pool = init_test_db();
+    let config = strict_fk_get_config(&pool).unwrap();
+    assert!(!config.enabled);
+    assert_eq!(config.deferred_ops, 0);
+
+    set_strict(&pool, true);
+    assert!(strict_fk_get_config(&pool).unwrap().enabled);
+    set_strict(&pool, false);
+    assert!(!strict_fk_get_config(&pool).unwrap().enabled);
+}
+
+#[test]
+fn strict_mode_rejects_missing_or_inactive_references_on_create() {
+    let pool = init_test_db();
+    let person = seed_person(&pool);
+    let partner = seed_partner(&pool);
+
+    // Default mode keeps the old behavior.
+    project_create(&pool, create_req("Loose", "no-such-partner", &person)).unwrap();
+
+    set_strict(&pool, true);
+    let err = project_create(&pool, create_req("Strict", "no-such-partner", &person)).unwrap_err();
+    assert_fk_violation(err, "does not exist");
+
+    exec(
+        &pool,
+        "UPDATE persons SET is_active = 0 WHERE id = ?1",
+        &person,
+    );
+    let err = project_create(&pool, create_req("Strict", &partner, &person)).unwrap_err();
+    assert_fk_violation(err, "inactive");
+
+    let active = seed_person(&pool);
+    project_create(&pool, create_req("Strict", &partner, &active)).unwrap();
+}
+
+#[test]
+fn strict_mode_rejects_members_on_archived_project() {
+    let pool = init_test_db();
+    let person = seed_person(&pool);
+    let partner = seed_partner(&pool);
+    let project = project_create(&pool, create_req("Alpha", &partner, &person))
+        .unwrap()
+        .id;
+    exec(
+        &pool,
+        "UPDATE projects SET current_status = 'ARCHIVED' WHERE id = ?1",
+        &project,
+    );
+    set_strict(&pool, true);
+
+    let member = seed_person(&pool);
+    let err = assignment_add_member(
+        &pool,
+        AssignmentAddReq {
+            project_id: project,
+            person_id: member,
+            role: None,
+            start_at: None,
+        },
+    )
+    .unwrap_err();
+    assert_fk_violation(err, "archived");
+}
+
+#[test]
+fn remote_op_with_missing_reference_is_deferred_until_it_arrives() {
+    let pool = init_test_db();
+    let person = seed_person(&pool);
+    let partner = seed_partner(&pool);
+    set_strict(&pool, true);
+    let engine = DeltaSyncEngine::new(&pool, device_id(&pool));
+
+    let stats = engine
+        .apply_delta(&make_delta(vec![remote_assignment(
+            "ra-1", "rproj-1", &person,
+        )]))
+        .unwrap();
+    assert_eq!(stats.deferred_ops, 1);
+    assert_eq!(stats.applied_ops, 0);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM assignments"), 0);
+    assert_eq!(strict_fk_get_config(&pool).unwrap().deferred_ops, 1);
+
+    let stats = engine
+        .apply_delta(&make_delta(vec![remote_project(
+            "rproj-1", &partner, &person,
+        )]))
+        .unwrap();
+    assert_eq!(stats.applied_ops, 2);
+    assert_eq!(
+        count(
+            &pool,
+            "SELECT COUNT(*) FROM assignments WHERE id = 'ra-1' AND project_id = 'rproj-1'"
+        ),
+        1
+    );
+    assert_eq!(strict_fk_get_config(&pool).unwrap().deferred_ops, 0);
+}
+
+#[test]
+fn remote_op_referencing_deleted_row_is_skipped() {
+    let pool = init_test_db();
+    let person = seed_person(&pool);
+    set_strict(&pool, true);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO sync_tombstones (table_name, record_id, deleted_at, version)
+             VALUES ('projects', 'gone-1', '2026-01-01T00:00:00Z', 1)",
+            [],
+        )
+        .unwrap();
+    }
+    let engine = DeltaSyncEngine::new(&pool, device_id(&pool));
+
+    let stats = engine
+        .apply_delta(&make_delta(vec![remote_assignment(
+            "ra-1", "gone-1", &person,
+        )]))
+        .unwrap();
+    assert_eq!(stats.deferred_ops, 0);
+    assert_eq!(stats.skipped_stale_upserts, 1);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM assignments"), 0);
+}
+
+#[test]
+fn disabling_strict_mode_flushes_deferred_ops() {
+    let pool = init_test_db();
+    let person = seed_person(&pool);
+    set_strict(&pool, true);
+    let engine = DeltaSyncEngine::new(&pool, device_id(&pool));
+    engine
+        .apply_delta(&make_delta(vec![remote_assignment(
+            "ra-1", "rproj-1", &person,
+        )]))
+        .unwrap();
+    assert_eq!(strict_fk_get_config(&pool).unwrap().deferred_ops, 1);
+
+    set_strict(&pool, false);
+    // Still queued until the next apply.
+    assert_eq!(strict_fk_get_config(&pool).unwrap().deferred_ops, 1);
+    let stats = engine.apply_deferred().unwrap();
+    assert_eq!(stats.applied_ops, 1);
+    assert_eq!(strict_fk_get_config(&pool).unwrap().deferred_ops, 0);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM assignments"), 1);
+}
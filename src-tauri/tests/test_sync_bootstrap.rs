@@ -0,0 +1,53 @@
+//! Bootstrap from a remote snapshot: local preconditions (no remote needed)
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync_bootstrap_from_remote_for_pool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn set_config(pool: &DbPool, key: &str, value: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )
+    .unwrap();
+}
+
+fn insert_person(pool: &DbPool, id: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at, _version)
+         VALUES (?1, 'Alice', '', '', '', 1, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z', 1)",
+        [id],
+    )
+    .unwrap();
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[tokio::test]
+async fn bootstrap_refuses_a_workspace_with_data() {
+    let pool = init_test_db();
+    insert_person(&pool, "p1");
+
+    let mut stages = Vec::new();
+    let err = sync_bootstrap_from_remote_for_pool(&pool, |p| stages.push(p.stage.clone()))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert!(err.to_string().contains("LOCAL_NOT_EMPTY"));
+    assert!(stages.is_empty());
+}
+
+#[tokio::test]
+async fn bootstrap_respects_disabled_snapshot_restore() {
+    let pool = init_test_db();
+    set_config(&pool, "disable_snapshot_restore", "1");
+
+    let err = sync_bootstrap_from_remote_for_pool(&pool, |_| {})
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "SYNC_OPERATION_DISABLED");
+}
@@ -0,0 +1,107 @@
+//! Delta compaction planning: only deltas every known device has read may be deleted
+
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::compaction::{
+    device_cursors_key, load_local_cursors, parse_device_cursors_key, plan_compaction,
+    DeviceCursors, RemoteDeltaRef,
+};
+use std::collections::BTreeMap;
+
+// ──────────────────────── Helper ────────────────────────
+
+const CUTOFF: i64 = 1_000;
+
+fn delta(source: &str, ts: i64, modified: i64) -> RemoteDeltaRef {
+    RemoteDeltaRef {
+        key: format!("deltas/{}/delta-{}.gz", source, ts),
+        source_device_id: source.to_string(),
+        timestamp: ts,
+        last_modified_unix: Some(modified),
+    }
+}
+
+fn cursors(device: &str, entries: &[(&str, i64)]) -> DeviceCursors {
+    DeviceCursors {
+        device_id: device.to_string(),
+        updated_at: "2026-01-01T00:00:00Z".to_string(),
+        cursors: entries
+            .iter()
+            .map(|(source, ts)| (source.to_string(), *ts))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn deletes_only_old_deltas_read_by_every_other_device() {
+    let deltas = vec![
+        delta("dev-a", 10, 100),
+        delta("dev-a", 20, 200),
+        delta("dev-a", 30, 5_000), // too recent
+        delta("dev-b", 15, 100),
+    ];
+    let devices = vec![
+        cursors("dev-a", &[("dev-b", 15)]),
+        cursors("dev-b", &[("dev-a", 30)]),
+        cursors("dev-c", &[("dev-a", 10), ("dev-b", 15)]),
+    ];
+
+    let plan = plan_compaction(&deltas, &devices, CUTOFF);
+    assert_eq!(
+        plan.deletable,
+        vec![
+            "deltas/dev-a/delta-10.gz".to_string(),
+            "deltas/dev-b/delta-15.gz".to_string()
+        ]
+    );
+    assert_eq!(plan.retained, 2);
+    // dev-c has not read dev-a@20 yet.
+    assert_eq!(plan.blocked_by, vec!["dev-c".to_string()]);
+}
+
+#[test]
+fn device_without_published_cursors_blocks_compaction() {
+    let deltas = vec![delta("dev-a", 10, 100), delta("dev-b", 10, 100)];
+    // dev-b wrote deltas but never published how far it has read.
+    let devices = vec![cursors("dev-a", &[("dev-b", 10)])];
+
+    let plan = plan_compaction(&deltas, &devices, CUTOFF);
+    assert_eq!(plan.deletable, vec!["deltas/dev-b/delta-10.gz".to_string()]);
+    assert_eq!(plan.blocked_by, vec!["dev-b".to_string()]);
+}
+
+#[test]
+fn single_device_can_compact_its_own_history() {
+    let deltas = vec![delta("dev-a", 10, 100)];
+    let devices = vec![cursors("dev-a", &[])];
+
+    let plan = plan_compaction(&deltas, &devices, CUTOFF);
+    assert_eq!(plan.deletable.len(), 1);
+    assert!(plan.blocked_by.is_empty());
+}
+
+#[test]
+fn device_cursors_key_roundtrip() {
+    let key = device_cursors_key("dev-a");
+    assert_eq!(key, "devices/dev-a/cursors.json");
+    assert_eq!(parse_device_cursors_key(&key), Some("dev-a"));
+    assert_eq!(parse_device_cursors_key("devices/dev-a/other.json"), None);
+    assert_eq!(parse_device_cursors_key("devices//cursors.json"), None);
+}
+
+#[test]
+fn local_cursors_come_from_sync_config() {
+    let pool = init_test_db();
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES ('last_remote_delta_ts::dev-b', '42')",
+        [],
+    )
+    .unwrap();
+
+    let local = load_local_cursors(&conn, "dev-a").unwrap();
+    assert_eq!(local.device_id, "dev-a");
+    assert_eq!(local.cursors.get("dev-b"), Some(&42));
+    assert_eq!(local.cursors.len(), 1);
+}
@@ -0,0 +1,177 @@
+//! Sync config validation (offline shape checks) tests
+
+use app_lib::sync::config_check::{
+    check_auth, check_bucket_name, check_config_shape, check_endpoint, check_https_policy,
+    check_interval, has_failures, probe_object_key, CheckStatus, ConfigShape,
+};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn valid_shape() -> ConfigShape<'static> {
+    ConfigShape {
+        bucket: "projex-sync",
+        endpoint: Some("https://s3.example.com"),
+        access_key: "AKIAEXAMPLE",
+        secret_key: "secret-key-123",
+        auto_sync_interval_minutes: Some(5),
+        require_https: false,
+        ..Default::default()
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn bucket_naming_rules() {
+    for ok in ["abc", "projex-sync-01", "a1b2c3"] {
+        assert_eq!(check_bucket_name(ok).status, CheckStatus::Pass, "{}", ok);
+    }
+    assert_eq!(check_bucket_name("my.bucket").status, CheckStatus::Warn);
+    let too_long = "a".repeat(64);
+    for bad in [
+        "",
+        "ab",
+        "Projex",
+        "projex_sync",
+        "-projex",
+        "projex-",
+        "a..b",
+        "192.168.1.10",
+        "xn--projex",
+        "projex-s3alias",
+        too_long.as_str(),
+    ] {
+        assert_eq!(check_bucket_name(bad).status, CheckStatus::Fail, "{}", bad);
+    }
+}
+
+#[test]
+fn endpoint_parse_and_https_policy() {
+    assert_eq!(check_endpoint(None).status, CheckStatus::Skip);
+    assert_eq!(
+        check_endpoint(Some("https://s3.example.com")).status,
+        CheckStatus::Pass
+    );
+    assert_eq!(
+        check_endpoint(Some("http://127.0.0.1:9000/")).status,
+        CheckStatus::Pass
+    );
+    assert_eq!(
+        check_endpoint(Some("https://host/bucket")).status,
+        CheckStatus::Warn
+    );
+    for bad in [
+        "s3.example.com",
+        "ftp://s3.example.com",
+        "https://",
+        "https://host:port",
+        "https://user:pw@host",
+        "https://ho st",
+    ] {
+        assert_eq!(
+            check_endpoint(Some(bad)).status,
+            CheckStatus::Fail,
+            "{}",
+            bad
+        );
+    }
+
+    assert_eq!(check_https_policy(None, true).status, CheckStatus::Pass);
+    assert_eq!(
+        check_https_policy(Some("https://h"), true).status,
+        CheckStatus::Pass
+    );
+    let android = check_https_policy(Some("http://localhost:9000"), true);
+    assert_eq!(android.status, CheckStatus::Fail);
+    assert!(android.message.starts_with("ENDPOINT_NOT_HTTPS"));
+    assert_eq!(
+        check_https_policy(Some("http://localhost:9000"), false).status,
+        CheckStatus::Pass
+    );
+    assert_eq!(
+        check_https_policy(Some("http://s3.example.com"), false).status,
+        CheckStatus::Warn
+    );
+}
+
+#[test]
+fn full_shape_reports_every_check_without_echoing_secrets() {
+    let checks = check_config_shape(&valid_shape());
+    assert_eq!(checks.len(), 7);
+    assert!(!has_failures(&checks));
+
+    let shape = ConfigShape {
+        bucket: "Bad_Bucket",
+        secret_key: "has space",
+        auto_sync_interval_minutes: Some(0),
+        ..valid_shape()
+    };
+    let checks = check_config_shape(&shape);
+    let failed: Vec<&str> = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(failed, ["bucket", "auto_sync_interval", "secret_key"]);
+    assert!(checks.iter().all(|c| !c.message.contains("has space")));
+
+    assert_eq!(check_interval(Some(2000)).status, CheckStatus::Warn);
+    assert!(probe_object_key("dev-1").starts_with("probes/dev-1-"));
+}
+
+#[test]
+fn profile_or_role_makes_static_keys_optional() {
+    let shape = ConfigShape {
+        access_key: "",
+        secret_key: "",
+        profile: Some("projex"),
+        ..valid_shape()
+    };
+    let checks = check_config_shape(&shape);
+    assert!(!has_failures(&checks));
+    let status = |name: &str| checks.iter().find(|c| c.name == name).unwrap().status;
+    assert_eq!(status("access_key"), CheckStatus::Skip);
+    assert_eq!(status("secret_key"), CheckStatus::Skip);
+    assert_eq!(status("auth"), CheckStatus::Pass);
+
+    let role = ConfigShape {
+        access_key: "",
+        secret_key: "",
+        role_arn: Some("arn:aws:iam::123456789012:role/projex-sync"),
+        external_id: Some("ext-1"),
+        ..valid_shape()
+    };
+    assert!(!has_failures(&check_config_shape(&role)));
+}
+
+#[test]
+fn auth_combinations_that_cannot_work_fail() {
+    let bad_arn = ConfigShape {
+        role_arn: Some("arn:aws:iam::123:user/alice"),
+        ..valid_shape()
+    };
+    assert_eq!(check_auth(&bad_arn).status, CheckStatus::Fail);
+    assert!(check_auth(&bad_arn).message.starts_with("INVALID_ROLE_ARN"));
+
+    let orphan_external_id = ConfigShape {
+        external_id: Some("ext-1"),
+        ..valid_shape()
+    };
+    assert_eq!(check_auth(&orphan_external_id).status, CheckStatus::Fail);
+
+    let token_without_keys = ConfigShape {
+        access_key: "",
+        session_token: Some("FwoGZXIvYXdzEXAMPLE"),
+        ..valid_shape()
+    };
+    let check = check_auth(&token_without_keys);
+    assert_eq!(check.status, CheckStatus::Fail);
+    assert!(!check.message.contains("FwoGZXIvYXdzEXAMPLE"));
+
+    let token_with_keys = ConfigShape {
+        session_token: Some("FwoGZXIvYXdzEXAMPLE"),
+        ..valid_shape()
+    };
+    assert_eq!(check_auth(&token_with_keys).status, CheckStatus::Pass);
+    assert_eq!(check_auth(&valid_shape()).status, CheckStatus::Skip);
+}
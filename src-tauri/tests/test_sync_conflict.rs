@@ -2,6 +2,7 @@
 
 use app_lib::error::AppError;
 use app_lib::infra::db::init_test_db;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
 use app_lib::sync::{Delta, DeltaSyncEngine, Operation, OperationType, VectorClock};
 use serde_json::json;
 
@@ -45,6 +46,7 @@ fn make_delta(operations: Vec<Operation>) -> Delta {
         vector_clock: VectorClock::new("remote-device".into()),
         created_at: "2026-01-01T00:00:00Z".into(),
         checksum,
+        format_version: SYNC_FORMAT_VERSION,
     }
 }
 
@@ -165,7 +167,10 @@ fn apply_delta_upsert_person_stale_version_is_ignored() {
         version: 4,
     }]);
 
-    engine.apply_delta(&delta).unwrap();
+    let applied = engine.apply_delta(&delta).unwrap();
+    assert_eq!(applied.applied_ops, 0);
+    assert_eq!(applied.skipped_stale_upserts, 1);
+    assert_eq!(applied.conflicts_detected, 1);
 
     let conn = pool.0.lock().unwrap();
     let (name, version): (String, i64) = conn
@@ -563,6 +568,7 @@ fn apply_delta_updates_vector_clock() {
         vector_clock: vc,
         created_at: "2026-01-01T00:00:00Z".into(),
         checksum: "ignored".into(),
+        format_version: SYNC_FORMAT_VERSION,
     };
 
     engine.apply_delta(&delta).unwrap();
@@ -0,0 +1,222 @@
+//! Sync conflict journal + manual resolution tests (in-memory SQLite)
+
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::conflicts::{
+    list_sync_conflicts, resolve_sync_conflict, SyncConflictListReq, SyncResolveConflictReq,
+};
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::{Delta, DeltaSyncEngine, Operation, OperationType, VectorClock};
+use serde_json::json;
+
+// ──────────────────────── Helper ────────────────────────
+
+/// Local person at `_version = 5`, then a stale remote update (version 4) that LWW skips.
+fn setup_stale_remote_conflict() -> app_lib::infra::DbPool {
+    let pool = init_test_db();
+    let device_id = {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at, _version)
+             VALUES ('person-1', 'Alice Local', 'a@t.com', 'dev', '', 1, datetime('now'), datetime('now'), 5)",
+            [],
+        )
+        .unwrap();
+        conn.query_row(
+            "SELECT value FROM sync_config WHERE key = 'device_id'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get::<_, String>(0),
+        )
+        .unwrap()
+    };
+
+    let operations = vec![Operation {
+        table_name: "persons".into(),
+        record_id: "person-1".into(),
+        op_type: OperationType::Update,
+        data: Some(json!({
+            "id": "person-1",
+            "display_name": "Alice Remote",
+            "email": "remote@t.com",
+            "role": "lead",
+            "note": "",
+            "is_active": 1,
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z"
+        })),
+        version: 4,
+    }];
+    let checksum = Delta::calculate_checksum(&operations);
+    let delta = Delta {
+        id: 1,
+        operations,
+        device_id: "remote-device".into(),
+        vector_clock: VectorClock::new("remote-device".into()),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        checksum,
+        format_version: SYNC_FORMAT_VERSION,
+    };
+    DeltaSyncEngine::new(&pool, device_id)
+        .apply_delta(&delta)
+        .unwrap();
+    pool
+}
+
+fn person_name_and_version(pool: &app_lib::infra::DbPool) -> (String, i64) {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT display_name, _version FROM persons WHERE id = 'person-1'",
+        [],
+        |r: &rusqlite::Row<'_>| Ok((r.get(0)?, r.get(1)?)),
+    )
+    .unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn stale_remote_upsert_is_journaled_as_local_win() {
+    let pool = setup_stale_remote_conflict();
+
+    let conflicts = list_sync_conflicts(&pool, SyncConflictListReq::default()).unwrap();
+    assert_eq!(conflicts.len(), 1);
+    let c = &conflicts[0];
+    assert_eq!(c.table_name, "persons");
+    assert_eq!(c.record_id, "person-1");
+    assert_eq!(c.auto_resolution, "local");
+    assert_eq!(c.local_version, Some(5));
+    assert_eq!(c.remote_version, 4);
+    assert_eq!(c.remote_device_id, "remote-device");
+    assert_eq!(
+        c.local_data.as_ref().unwrap()["display_name"],
+        "Alice Local"
+    );
+    assert_eq!(c.remote_data["display_name"], "Alice Remote");
+    assert!(c.resolved_at.is_none());
+    assert_eq!(person_name_and_version(&pool), ("Alice Local".into(), 5));
+}
+
+#[test]
+fn resolve_keep_remote_overrides_local_with_bumped_version() {
+    let pool = setup_stale_remote_conflict();
+    let id = list_sync_conflicts(&pool, SyncConflictListReq::default()).unwrap()[0].id;
+
+    let resolved = resolve_sync_conflict(
+        &pool,
+        SyncResolveConflictReq {
+            id,
+            keep: "remote".into(),
+            merged_json: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(resolved.resolution.as_deref(), Some("remote"));
+    assert!(resolved.resolved_at.is_some());
+    assert_eq!(person_name_and_version(&pool), ("Alice Remote".into(), 6));
+
+    // Resolved conflicts drop out of the default listing.
+    assert!(list_sync_conflicts(&pool, SyncConflictListReq::default())
+        .unwrap()
+        .is_empty());
+    let all = list_sync_conflicts(
+        &pool,
+        SyncConflictListReq {
+            include_resolved: Some(true),
+        },
+    )
+    .unwrap();
+    assert_eq!(all.len(), 1);
+}
+
+#[test]
+fn resolve_keep_local_matching_auto_resolution_writes_nothing() {
+    let pool = setup_stale_remote_conflict();
+    let id = list_sync_conflicts(&pool, SyncConflictListReq::default()).unwrap()[0].id;
+
+    let resolved = resolve_sync_conflict(
+        &pool,
+        SyncResolveConflictReq {
+            id,
+            keep: "local".into(),
+            merged_json: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(resolved.resolution.as_deref(), Some("local"));
+    assert_eq!(person_name_and_version(&pool), ("Alice Local".into(), 5));
+}
+
+#[test]
+fn resolve_merged_writes_merged_row() {
+    let pool = setup_stale_remote_conflict();
+    let id = list_sync_conflicts(&pool, SyncConflictListReq::default()).unwrap()[0].id;
+
+    let merged = json!({
+        "id": "person-1",
+        "display_name": "Alice Merged",
+        "email": "a@t.com",
+        "role": "lead",
+        "note": "",
+        "is_active": 1,
+        "created_at": "2026-01-01T00:00:00Z",
+        "updated_at": "2026-01-01T00:00:00Z"
+    });
+    resolve_sync_conflict(
+        &pool,
+        SyncResolveConflictReq {
+            id,
+            keep: "merged".into(),
+            merged_json: Some(merged.to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(person_name_and_version(&pool), ("Alice Merged".into(), 6));
+}
+
+#[test]
+fn resolve_merged_with_mismatched_id_is_rejected() {
+    let pool = setup_stale_remote_conflict();
+    let id = list_sync_conflicts(&pool, SyncConflictListReq::default()).unwrap()[0].id;
+
+    let err = resolve_sync_conflict(
+        &pool,
+        SyncResolveConflictReq {
+            id,
+            keep: "merged".into(),
+            merged_json: Some(json!({"id": "someone-else"}).to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert_eq!(person_name_and_version(&pool), ("Alice Local".into(), 5));
+}
+
+#[test]
+fn resolve_twice_is_rejected() {
+    let pool = setup_stale_remote_conflict();
+    let id = list_sync_conflicts(&pool, SyncConflictListReq::default()).unwrap()[0].id;
+    let req = || SyncResolveConflictReq {
+        id,
+        keep: "local".into(),
+        merged_json: None,
+    };
+
+    resolve_sync_conflict(&pool, req()).unwrap();
+    let err = resolve_sync_conflict(&pool, req()).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert!(err.to_string().contains("CONFLICT_ALREADY_RESOLVED"));
+}
+
+#[test]
+fn resolve_unknown_conflict_is_not_found() {
+    let pool = init_test_db();
+    let err = resolve_sync_conflict(
+        &pool,
+        SyncResolveConflictReq {
+            id: 999,
+            keep: "local".into(),
+            merged_json: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "NOT_FOUND");
+}
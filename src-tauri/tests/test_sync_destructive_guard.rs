@@ -0,0 +1,78 @@
+//! Device-local guards for destructive sync operations
+
+use app_lib::infra::db::init_test_db;
+
+// ── helpers ────────────────────────────────────────────────────────────────────
+
+fn set_config(pool: &app_lib::infra::db::DbPool, key: &str, value: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )
+    .unwrap();
+}
+
+// ── snapshot restore ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn restore_snapshot_is_rejected_when_disabled_on_device() {
+    let pool = init_test_db();
+    set_config(&pool, "disable_snapshot_restore", "1");
+
+    let err = app_lib::sync_restore_snapshot_for_pool(&pool, None)
+        .await
+        .unwrap_err();
+
+    // Guard fires before any S3 config is read, so no bucket is needed.
+    assert_eq!(err.code(), "SYNC_OPERATION_DISABLED");
+}
+
+#[tokio::test]
+async fn restore_snapshot_guard_off_falls_through_to_config_check() {
+    let pool = init_test_db();
+    set_config(&pool, "disable_snapshot_restore", "0");
+
+    let err = app_lib::sync_restore_snapshot_for_pool(&pool, None)
+        .await
+        .unwrap_err();
+
+    // No bucket configured → the pipeline proceeds past the guard and fails on config lookup.
+    assert_eq!(err.code(), "DB_ERROR");
+}
+
+// ── delta compaction ───────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn compaction_is_rejected_when_remote_cleanup_disabled() {
+    let pool = init_test_db();
+    set_config(&pool, "disable_remote_cleanup", "1");
+
+    let err = app_lib::sync_compact_for_pool(&pool, app_lib::SyncCompactReq::default())
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.code(), "SYNC_OPERATION_DISABLED");
+}
+
+#[tokio::test]
+async fn compaction_is_rejected_while_deltas_are_staged() {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO sync_staged_deltas (delta_key, source_device_id, source_timestamp, payload, staged_at)
+             VALUES ('deltas/dev-b/delta-1.gz', 'dev-b', 1, '{}', datetime('now'))",
+            [],
+        )
+        .unwrap();
+    }
+
+    let err = app_lib::sync_compact_for_pool(&pool, app_lib::SyncCompactReq::default())
+        .await
+        .unwrap_err();
+
+    // The snapshot would miss staged-but-unapplied data.
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert!(err.to_string().contains("STAGED_DELTAS_PENDING"));
+}
@@ -0,0 +1,111 @@
+//! Per-device sync health (cmd_sync_list_devices) tests
+
+use app_lib::sync::compaction::{DeviceCursors, RemoteDeltaRef};
+use app_lib::sync::device_health::build_device_health;
+use app_lib::sync::snapshot_catalog::describe_snapshots;
+use app_lib::sync::S3ObjectSummary;
+use std::collections::BTreeMap;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn delta(device: &str, ts: i64, modified: i64) -> RemoteDeltaRef {
+    RemoteDeltaRef {
+        key: format!("deltas/{}/delta-{}-x.gz", device, ts),
+        source_device_id: device.to_string(),
+        timestamp: ts,
+        last_modified_unix: Some(modified),
+    }
+}
+
+fn cursors(device: &str, entries: &[(&str, i64)]) -> DeviceCursors {
+    DeviceCursors {
+        device_id: device.to_string(),
+        updated_at: "2026-01-01T00:00:00+00:00".to_string(),
+        cursors: entries
+            .iter()
+            .map(|(d, ts)| (d.to_string(), *ts))
+            .collect::<BTreeMap<_, _>>(),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn counts_pending_deltas_against_local_cursor() {
+    let deltas = vec![
+        delta("laptop", 10, 1_700_000_010),
+        delta("laptop", 20, 1_700_000_020),
+        delta("laptop", 30, 1_700_000_030),
+        delta("me", 5, 1_700_000_005),
+    ];
+    let local = cursors("me", &[("laptop", 20)]);
+
+    let list = build_device_health("me", 3, &deltas, &[], &local, &[]);
+    assert_eq!(list.len(), 2);
+
+    let me = &list[0];
+    assert!(me.is_local);
+    assert_eq!(me.pending_local_changes, Some(3));
+    assert_eq!(me.pending_deltas, 0);
+    assert_eq!(me.delta_count, 1);
+
+    let laptop = &list[1];
+    assert_eq!(laptop.device_id, "laptop");
+    assert_eq!(laptop.delta_count, 3);
+    assert_eq!(laptop.applied_cursor_ts, Some(20));
+    assert_eq!(laptop.pending_deltas, 1);
+    assert_eq!(laptop.pending_local_changes, None);
+    assert_eq!(
+        laptop.last_delta_at.as_deref(),
+        Some("2023-11-14T22:13:50+00:00")
+    );
+}
+
+#[test]
+fn device_without_cursor_has_everything_pending() {
+    let deltas = vec![delta("phone", 1, 100), delta("phone", 2, 200)];
+    let list = build_device_health("me", 0, &deltas, &[], &cursors("me", &[]), &[]);
+    let phone = list.iter().find(|d| d.device_id == "phone").unwrap();
+    assert_eq!(phone.applied_cursor_ts, None);
+    assert_eq!(phone.pending_deltas, 2);
+}
+
+#[test]
+fn includes_devices_seen_only_in_snapshots_or_cursors() {
+    let snapshots = describe_snapshots(&[
+        S3ObjectSummary {
+            key: "snapshots/old/snapshot-1000000000000000000.gz".to_string(),
+            last_modified_unix: Some(1_000_000_000),
+            size_bytes: Some(10),
+        },
+        S3ObjectSummary {
+            key: "snapshots/old/snapshot-1100000000000000000.gz".to_string(),
+            last_modified_unix: Some(1_100_000_000),
+            size_bytes: Some(10),
+        },
+    ]);
+    let published = vec![cursors("tablet", &[])];
+
+    let list = build_device_health("me", 0, &[], &snapshots, &cursors("me", &[]), &published);
+    let ids: Vec<&str> = list.iter().map(|d| d.device_id.as_str()).collect();
+    assert_eq!(ids, vec!["me", "old", "tablet"]);
+
+    let old = &list[1];
+    assert_eq!(old.delta_count, 0);
+    assert_eq!(
+        old.last_snapshot_at.as_deref(),
+        Some("2004-11-09T11:33:20+00:00")
+    );
+    assert_eq!(
+        list[2].last_sync_at.as_deref(),
+        Some("2026-01-01T00:00:00+00:00")
+    );
+}
+
+#[test]
+fn most_recently_active_devices_come_first() {
+    let deltas = vec![delta("a", 1, 100), delta("b", 1, 300), delta("c", 1, 200)];
+    let list = build_device_health("me", 0, &deltas, &[], &cursors("me", &[]), &[]);
+    let ids: Vec<&str> = list.iter().map(|d| d.device_id.as_str()).collect();
+    assert_eq!(ids, vec!["me", "b", "c", "a"]);
+}
@@ -0,0 +1,239 @@
+//! Device registry tests: local row maintenance, rename, revocation and delta rejection
+
+use app_lib::error::AppError;
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::device_registry::{
+    device_object_prefixes, is_device_revoked, list_devices, load_revoked_devices,
+    rename_local_device, revoke_device, touch_local_device, LAST_SEEN_REFRESH_SECS,
+};
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::{Delta, DeltaSyncEngine, Operation, OperationType, VectorClock};
+use chrono::{Duration, Utc};
+use serde_json::json;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn setup() -> (DbPool, String) {
+    let pool = init_test_db();
+    let device_id = {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE sync_config SET value = '1' WHERE key = 'sync_enabled'",
+            [],
+        )
+        .unwrap();
+        conn.query_row(
+            "SELECT value FROM sync_config WHERE key = 'device_id'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get::<_, String>(0),
+        )
+        .unwrap()
+    };
+    (pool, device_id)
+}
+
+fn pending_device_ops(pool: &DbPool) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT COUNT(*) FROM sync_metadata WHERE synced = 0 AND table_name = 'devices'",
+        [],
+        |row: &rusqlite::Row<'_>| row.get(0),
+    )
+    .unwrap()
+}
+
+fn remote_delta(source: &str, operations: Vec<Operation>) -> Delta {
+    let checksum = Delta::calculate_checksum(&operations);
+    Delta {
+        id: 1,
+        operations,
+        device_id: source.into(),
+        vector_clock: VectorClock::new(source.into()),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        checksum,
+        format_version: SYNC_FORMAT_VERSION,
+    }
+}
+
+fn device_op(data: serde_json::Value, version: i64) -> Operation {
+    Operation {
+        table_name: "devices".into(),
+        record_id: data["id"].as_str().unwrap().into(),
+        op_type: OperationType::Update,
+        data: Some(data),
+        version,
+    }
+}
+
+fn device_row(id: &str, name: &str, revoked_at: Option<&str>, version: i64) -> serde_json::Value {
+    json!({
+        "id": id,
+        "name": name,
+        "platform": "linux",
+        "last_seen_at": "2026-01-01T00:00:00+00:00",
+        "revoked_at": revoked_at,
+        "created_at": "2026-01-01T00:00:00+00:00",
+        "updated_at": "2026-01-01T00:00:00+00:00",
+        "_version": version,
+    })
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn touch_registers_local_device_and_throttles_last_seen() {
+    let (pool, device_id) = setup();
+    let conn = pool.0.lock().unwrap();
+    let now = Utc::now();
+
+    assert!(touch_local_device(&conn, &device_id, now).unwrap());
+    let devices = list_devices(&conn, &device_id).unwrap();
+    assert_eq!(devices.len(), 1);
+    assert!(devices[0].is_local);
+    assert_eq!(devices[0].platform, std::env::consts::OS);
+    assert!(devices[0]
+        .name
+        .ends_with(&device_id.chars().take(8).collect::<String>()));
+
+    // Within the refresh window nothing is written (no delta per sync run).
+    assert!(!touch_local_device(&conn, &device_id, now + Duration::minutes(5)).unwrap());
+    assert!(touch_local_device(
+        &conn,
+        &device_id,
+        now + Duration::seconds(LAST_SEEN_REFRESH_SECS + 1)
+    )
+    .unwrap());
+    drop(conn);
+    assert_eq!(pending_device_ops(&pool), 2);
+}
+
+#[test]
+fn rename_validates_and_is_tracked_for_sync() {
+    let (pool, device_id) = setup();
+    let conn = pool.0.lock().unwrap();
+
+    let device = rename_local_device(&conn, &device_id, "  Work laptop ").unwrap();
+    assert_eq!(device.name, "Work laptop");
+    assert!(device.is_local);
+
+    assert!(matches!(
+        rename_local_device(&conn, &device_id, "   "),
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        rename_local_device(&conn, &device_id, &"x".repeat(65)),
+        Err(AppError::Validation(_))
+    ));
+    drop(conn);
+    // Insert + rename.
+    assert_eq!(pending_device_ops(&pool), 2);
+}
+
+#[test]
+fn revoke_rejects_self_and_registers_unknown_devices() {
+    let (pool, device_id) = setup();
+    let conn = pool.0.lock().unwrap();
+
+    assert!(matches!(
+        revoke_device(&conn, &device_id, &device_id),
+        Err(AppError::Validation(_))
+    ));
+    assert!(matches!(
+        revoke_device(&conn, &device_id, "../other"),
+        Err(AppError::Validation(_))
+    ));
+
+    let revoked = revoke_device(&conn, &device_id, "lost-phone").unwrap();
+    assert!(revoked.revoked_at.is_some());
+    assert!(is_device_revoked(&conn, "lost-phone").unwrap());
+    // Idempotent: revoking again keeps the original time.
+    let again = revoke_device(&conn, &device_id, "lost-phone").unwrap();
+    assert_eq!(again.revoked_at, revoked.revoked_at);
+    assert_eq!(
+        load_revoked_devices(&conn)
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec!["lost-phone".to_string()]
+    );
+}
+
+#[test]
+fn remote_registry_rows_apply_and_revocation_is_sticky() {
+    let (pool, device_id) = setup();
+    let engine = DeltaSyncEngine::new(&pool, device_id.clone());
+
+    engine
+        .apply_delta(&remote_delta(
+            "laptop",
+            vec![device_op(device_row("laptop", "Laptop", None, 1), 1)],
+        ))
+        .unwrap();
+    {
+        let conn = pool.0.lock().unwrap();
+        revoke_device(&conn, &device_id, "tablet").unwrap();
+    }
+
+    // Another device's older view of "tablet" must not un-revoke it.
+    engine
+        .apply_delta(&remote_delta(
+            "laptop",
+            vec![device_op(device_row("tablet", "Tablet", None, 5), 5)],
+        ))
+        .unwrap();
+
+    let conn = pool.0.lock().unwrap();
+    let devices = list_devices(&conn, &device_id).unwrap();
+    let laptop = devices.iter().find(|d| d.id == "laptop").unwrap();
+    assert_eq!(laptop.name, "Laptop");
+    let tablet = devices.iter().find(|d| d.id == "tablet").unwrap();
+    assert_eq!(tablet.name, "Tablet");
+    assert!(tablet.revoked_at.is_some());
+    // Revoked devices sort last.
+    assert_eq!(devices.last().unwrap().id, "tablet");
+}
+
+#[test]
+fn deltas_from_revoked_devices_are_rejected() {
+    let (pool, device_id) = setup();
+    {
+        let conn = pool.0.lock().unwrap();
+        revoke_device(&conn, &device_id, "stolen").unwrap();
+    }
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    let delta = remote_delta(
+        "stolen",
+        vec![Operation {
+            table_name: "partners".into(),
+            record_id: "pt1".into(),
+            op_type: OperationType::Insert,
+            data: Some(json!({
+                "id": "pt1",
+                "name": "Acme",
+                "note": "",
+                "is_active": 1,
+                "created_at": "2026-01-01T00:00:00Z",
+                "updated_at": "2026-01-01T00:00:00Z",
+                "_version": 1,
+            })),
+            version: 1,
+        }],
+    );
+
+    assert!(matches!(engine.apply_delta(&delta), Err(AppError::Sync(_))));
+    let conn = pool.0.lock().unwrap();
+    let partners: i64 = conn
+        .query_row("SELECT COUNT(*) FROM partners", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(partners, 0);
+}
+
+#[test]
+fn purge_prefixes_are_scoped_to_the_device() {
+    let prefixes = device_object_prefixes("abc");
+    assert!(prefixes.contains(&"deltas/abc/".to_string()));
+    assert!(prefixes.contains(&"snapshots/abc/".to_string()));
+    assert!(prefixes.contains(&"devices/abc/".to_string()));
+    assert!(prefixes.iter().all(|p| p.contains("abc")));
+}
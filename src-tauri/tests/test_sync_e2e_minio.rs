@@ -4,8 +4,9 @@
 use app_lib::infra::{db::init_test_db, DbPool};
 use app_lib::sync::{Delta, S3SyncClient};
 use app_lib::{
-    sync_create_snapshot_for_pool, sync_full_for_pool, sync_full_with_runtime_for_pool,
-    sync_hold_lock_for_test, sync_restore_snapshot_for_pool, SyncRuntime,
+    sync_bootstrap_from_remote_for_pool, sync_create_snapshot_for_pool, sync_full_for_pool,
+    sync_full_with_runtime_for_pool, sync_hold_lock_for_test, sync_list_snapshots_for_pool,
+    sync_restore_snapshot_for_pool, sync_set_key_prefix_for_pool, SyncRuntime, SyncSetKeyPrefixReq,
 };
 use aws_config::meta::region::RegionProviderChain;
 use aws_credential_types::Credentials;
@@ -602,7 +603,17 @@ fn snapshot_create_restore_end_to_end_minio() {
         assert_eq!(person_count(&pool, &person_id), 1);
         assert_eq!(person_count(&pool, &extra_person_id), 1);
 
-        sync_restore_snapshot_for_pool(&pool)
+        let snapshots = sync_list_snapshots_for_pool(&pool)
+            .await
+            .expect("snapshot list should succeed");
+        let own = snapshots
+            .iter()
+            .find(|s| s.device_id == device_id)
+            .expect("own snapshot should be listed");
+        assert!(!own.legacy);
+        assert!(own.created_at.is_some());
+
+        sync_restore_snapshot_for_pool(&pool, Some(&own.key))
             .await
             .expect("snapshot restore should succeed");
 
@@ -945,3 +956,128 @@ fn sync_runtime_lock_blocks_manual_sync_until_scheduler_slot_released() {
         assert_eq!(unsynced_meta_count(&pool, "persons", &person_id), 0);
     });
 }
+
+#[test]
+fn key_prefix_migration_moves_objects_for_other_devices() {
+    let Some(cfg) = MinioE2eConfig::from_env() else {
+        eprintln!(
+            "skip key_prefix_migration_moves_objects_for_other_devices: SYNC_MINIO_TEST != 1"
+        );
+        return;
+    };
+
+    let rt = tokio::runtime::Runtime::new().expect("create tokio runtime");
+    rt.block_on(async {
+        let bucket = create_isolated_bucket(&cfg).await;
+        let pool_a = init_test_db();
+        let pool_b = init_test_db();
+        let device_a = format!("e2e-device-a-{}", random_suffix(6));
+        let device_b = format!("e2e-device-b-{}", random_suffix(6));
+        configure_pool(&pool_a, &cfg, &bucket, &device_a);
+        configure_pool(&pool_b, &cfg, &bucket, &device_b);
+
+        let person_id = format!("e2e-person-{}", random_suffix(8));
+        insert_person(&pool_a, &person_id, "Prefixed");
+        sync_full_for_pool(&pool_a)
+            .await
+            .expect("device A should upload at the bucket root");
+
+        let moved = sync_set_key_prefix_for_pool(
+            &pool_a,
+            SyncSetKeyPrefixReq {
+                key_prefix: "/teams/alpha".to_string(),
+                migrate: Some(true),
+            },
+        )
+        .await
+        .expect("move objects under the prefix");
+        assert_eq!(moved.key_prefix, "teams/alpha/");
+        assert!(moved.moved_objects > 0);
+
+        let root = make_bucket_client(&cfg, &bucket, &device_b).await;
+        assert!(root.list("deltas/").await.unwrap().is_empty());
+        assert!(!root.list("teams/alpha/deltas/").await.unwrap().is_empty());
+
+        // Device B joins the prefixed namespace without moving anything.
+        let joined = sync_set_key_prefix_for_pool(
+            &pool_b,
+            SyncSetKeyPrefixReq {
+                key_prefix: "teams/alpha/".to_string(),
+                migrate: Some(true),
+            },
+        )
+        .await
+        .expect("join the prefixed namespace");
+        assert_eq!(joined.moved_objects, 0);
+        sync_full_for_pool(&pool_b)
+            .await
+            .expect("device B should pull from the prefix");
+        assert_eq!(
+            get_person_display_name(&pool_b, &person_id).as_deref(),
+            Some("Prefixed")
+        );
+    });
+}
+
+#[test]
+fn bootstrap_from_remote_restores_snapshot_then_newer_deltas() {
+    let Some(cfg) = MinioE2eConfig::from_env() else {
+        eprintln!(
+            "skip bootstrap_from_remote_restores_snapshot_then_newer_deltas: SYNC_MINIO_TEST != 1"
+        );
+        return;
+    };
+
+    let rt = tokio::runtime::Runtime::new().expect("create tokio runtime");
+    rt.block_on(async {
+        let bucket = create_isolated_bucket(&cfg).await;
+        let pool_a = init_test_db();
+        let pool_b = init_test_db();
+        let device_a = format!("e2e-device-a-{}", random_suffix(6));
+        let device_b = format!("e2e-device-b-{}", random_suffix(6));
+        configure_pool(&pool_a, &cfg, &bucket, &device_a);
+        configure_pool(&pool_b, &cfg, &bucket, &device_b);
+
+        let in_snapshot = format!("e2e-person-{}", random_suffix(8));
+        insert_person(&pool_a, &in_snapshot, "In snapshot");
+        sync_full_for_pool(&pool_a)
+            .await
+            .expect("device A initial sync");
+        // Delta keys have second precision: keep each delta out of the snapshot's second.
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        sync_create_snapshot_for_pool(&pool_a)
+            .await
+            .expect("device A snapshot");
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let after_snapshot = format!("e2e-person-{}", random_suffix(8));
+        insert_person(&pool_a, &after_snapshot, "After snapshot");
+        sync_full_for_pool(&pool_a)
+            .await
+            .expect("device A second sync");
+
+        let mut stages = Vec::new();
+        let result = sync_bootstrap_from_remote_for_pool(&pool_b, |p| stages.push(p.stage.clone()))
+            .await
+            .expect("device B bootstrap");
+        assert_eq!(
+            stages,
+            [
+                "download_snapshot",
+                "restore_snapshot",
+                "apply_deltas",
+                "done"
+            ]
+        );
+        assert_eq!(result.source_device_id, device_a);
+        // Only the delta written after the snapshot is applied.
+        assert_eq!(result.sync.deltas_applied, 1);
+        assert_eq!(person_count(&pool_b, &in_snapshot), 1);
+        assert_eq!(person_count(&pool_b, &after_snapshot), 1);
+        assert_eq!(unsynced_meta_count(&pool_b, "persons", &in_snapshot), 0);
+
+        let err = sync_bootstrap_from_remote_for_pool(&pool_b, |_| {})
+            .await
+            .expect_err("a filled workspace is not bootstrapped again");
+        assert!(err.to_string().contains("LOCAL_NOT_EMPTY"));
+    });
+}
@@ -1,7 +1,8 @@
 //! DeltaSyncEngine integration tests (in-memory SQLite)
 
 use app_lib::infra::db::init_test_db;
-use app_lib::sync::DeltaSyncEngine;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::{Delta, DeltaChunkLimits, DeltaSyncEngine};
 
 // ──────────────────────── Helper ────────────────────────
 
@@ -136,6 +137,129 @@ fn delta_checksum_matches_operations() {
     assert_eq!(delta.checksum, recalculated);
 }
 
+#[test]
+fn chunks_split_by_operation_count() {
+    let (pool, device_id) = setup();
+    for i in 1..=5 {
+        insert_sync_metadata(
+            &pool,
+            "persons",
+            &format!("p-{:03}", i),
+            "INSERT",
+            &device_id,
+        );
+    }
+
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    let chunks = engine
+        .collect_local_delta_chunks(DeltaChunkLimits {
+            max_ops: 2,
+            max_bytes: usize::MAX,
+        })
+        .unwrap();
+
+    let sizes: Vec<usize> = chunks.iter().map(|c| c.delta.operations.len()).collect();
+    assert_eq!(sizes, vec![2, 2, 1]);
+    let max_ids: Vec<Option<i64>> = chunks.iter().map(|c| c.max_sync_meta_id).collect();
+    assert_eq!(max_ids, vec![Some(2), Some(4), Some(5)]);
+    for chunk in &chunks {
+        assert_eq!(
+            chunk.delta.checksum,
+            Delta::calculate_checksum(&chunk.delta.operations)
+        );
+    }
+}
+
+#[test]
+fn chunks_split_by_size_and_keep_oversized_operations_alone() {
+    let (pool, device_id) = setup();
+    for i in 1..=3 {
+        insert_sync_metadata(
+            &pool,
+            "persons",
+            &format!("p-{:03}", i),
+            "INSERT",
+            &device_id,
+        );
+    }
+
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    let chunks = engine
+        .collect_local_delta_chunks(DeltaChunkLimits {
+            max_ops: 100,
+            max_bytes: 1,
+        })
+        .unwrap();
+    let sizes: Vec<usize> = chunks.iter().map(|c| c.delta.operations.len()).collect();
+    assert_eq!(sizes, vec![1, 1, 1]);
+}
+
+#[test]
+fn chunks_empty_when_nothing_pending() {
+    let (pool, device_id) = setup();
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    assert!(engine
+        .collect_local_delta_chunks(DeltaChunkLimits::default())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn marking_a_chunk_leaves_later_chunks_pending() {
+    let (pool, device_id) = setup();
+    for i in 1..=4 {
+        insert_sync_metadata(
+            &pool,
+            "persons",
+            &format!("p-{:03}", i),
+            "INSERT",
+            &device_id,
+        );
+    }
+    let limits = DeltaChunkLimits {
+        max_ops: 3,
+        max_bytes: usize::MAX,
+    };
+
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    let chunks = engine.collect_local_delta_chunks(limits).unwrap();
+    engine
+        .mark_synced(chunks[0].max_sync_meta_id.unwrap())
+        .unwrap();
+
+    let remaining = engine.collect_local_delta_chunks(limits).unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].delta.operations.len(), 1);
+    assert_eq!(remaining[0].delta.operations[0].record_id, "p-004");
+}
+
+#[test]
+fn chunk_limits_load_from_sync_config() {
+    let (pool, _) = setup();
+    let conn = pool.0.lock().unwrap();
+    assert_eq!(DeltaChunkLimits::load(&conn), DeltaChunkLimits::default());
+
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES ('delta_chunk_max_ops', '10'), ('delta_chunk_max_mb', '2')",
+        [],
+    )
+    .unwrap();
+    assert_eq!(
+        DeltaChunkLimits::load(&conn),
+        DeltaChunkLimits {
+            max_ops: 10,
+            max_bytes: 2 * 1024 * 1024,
+        }
+    );
+
+    conn.execute(
+        "UPDATE sync_config SET value = '0' WHERE key = 'delta_chunk_max_ops'",
+        [],
+    )
+    .unwrap();
+    assert_eq!(DeltaChunkLimits::load(&conn).max_ops, 5_000);
+}
+
 #[test]
 fn device_id_is_consistent() {
     let (pool, _) = setup();
@@ -173,6 +297,7 @@ fn apply_remote_delta_inserts_person() {
         vector_clock: app_lib::sync::VectorClock::new("remote-device".into()),
         created_at: "2026-01-01T00:00:00Z".into(),
         checksum: "ignored".into(),
+        format_version: SYNC_FORMAT_VERSION,
     };
 
     engine.apply_delta(&delta).unwrap();
@@ -211,6 +336,7 @@ fn apply_remote_delta_deletes_person() {
         vector_clock: app_lib::sync::VectorClock::new("remote-device".into()),
         created_at: "2026-01-01T00:00:00Z".into(),
         checksum: "ignored".into(),
+        format_version: SYNC_FORMAT_VERSION,
     };
 
     engine.apply_delta(&delta).unwrap();
@@ -264,6 +390,7 @@ fn mark_remote_applied_operations_synced_marks_trigger_rows() {
         vector_clock: app_lib::sync::VectorClock::new("remote-device".into()),
         created_at: "2026-01-01T00:00:00Z".into(),
         checksum: "ignored".into(),
+        format_version: SYNC_FORMAT_VERSION,
     };
 
     engine.apply_delta(&delta).unwrap();
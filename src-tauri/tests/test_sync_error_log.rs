@@ -0,0 +1,86 @@
+//! Sync error log tests
+
+use app_lib::error::AppError;
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::error_log::{list_sync_errors, record_sync_error};
+use app_lib::sync::SyncErrorListReq;
+
+// ──────────────────────── Tests ────────────────────────
+
+#[tokio::test]
+async fn failed_sync_attempt_logs_phase_and_code() {
+    let pool = init_test_db();
+
+    // No S3 config → pipeline fails while preparing.
+    let err = app_lib::sync_full_for_pool(&pool).await.unwrap_err();
+
+    let page = list_sync_errors(&pool, SyncErrorListReq::default()).unwrap();
+    assert_eq!(page.total, 1);
+    let entry = &page.items[0];
+    assert_eq!(entry.phase, "prepare");
+    assert_eq!(entry.error_code, err.code());
+    assert_eq!(entry.message, err.to_string());
+    assert!(entry.delta_key.is_none());
+
+    // The single last_sync_error is kept for compatibility.
+    let conn = pool.0.lock().unwrap();
+    let last_error: String = conn
+        .query_row(
+            "SELECT value FROM sync_config WHERE key = 'last_sync_error'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(last_error, err.to_string());
+}
+
+#[test]
+fn errors_are_paginated_newest_first() {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        for i in 0..4 {
+            let key = format!("deltas/dev-b/delta-{}.gz", i);
+            record_sync_error(
+                &conn,
+                "download",
+                &AppError::Sync(format!("boom {}", i)),
+                Some(&key),
+            )
+            .unwrap();
+        }
+    }
+
+    let page = list_sync_errors(
+        &pool,
+        SyncErrorListReq {
+            limit: Some(2),
+            offset: Some(1),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(page.total, 4);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[0].message, "Sync error: boom 2");
+    assert_eq!(page.items[0].error_code, "SYNC_ERROR");
+    assert_eq!(page.items[0].phase, "download");
+    assert_eq!(
+        page.items[1].delta_key.as_deref(),
+        Some("deltas/dev-b/delta-1.gz")
+    );
+}
+
+#[test]
+fn error_log_is_capped() {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        for _ in 0..510 {
+            record_sync_error(&conn, "upload", &AppError::Sync("x".into()), None).unwrap();
+        }
+    }
+
+    let page = list_sync_errors(&pool, SyncErrorListReq::default()).unwrap();
+    assert_eq!(page.total, 500);
+}
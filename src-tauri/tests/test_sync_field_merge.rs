@@ -0,0 +1,233 @@
+//! Field-level merge tests for projects/persons (in-memory SQLite)
+
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::field_merge::merge_fields;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::{Delta, DeltaSyncEngine, Operation, OperationType, VectorClock};
+use serde_json::{json, Value};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn person(display_name: &str, email: &str, role: &str, version: i64) -> Value {
+    json!({
+        "id": "person-1",
+        "display_name": display_name,
+        "email": email,
+        "role": role,
+        "note": "",
+        "is_active": 1,
+        "created_at": "2026-01-01T00:00:00Z",
+        "updated_at": "2026-01-01T00:00:00Z",
+        "_version": version
+    })
+}
+
+fn setup() -> (app_lib::infra::DbPool, String) {
+    let pool = init_test_db();
+    let device_id = {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE sync_config SET value = '1' WHERE key = 'sync_enabled'",
+            [],
+        )
+        .unwrap();
+        conn.query_row(
+            "SELECT value FROM sync_config WHERE key = 'device_id'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get::<_, String>(0),
+        )
+        .unwrap()
+    };
+    (pool, device_id)
+}
+
+/// Local row + shared base at version 1, then a local-only edit of `email` (version 2).
+fn seed_with_base_and_local_edit(pool: &app_lib::infra::DbPool) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at, _version)
+         VALUES ('person-1', 'Alice', 'a@t.com', 'dev', '', 1, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z', 1)",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO sync_record_base (table_name, record_id, data, updated_at) VALUES ('persons', 'person-1', ?1, '2026-01-01T00:00:00Z')",
+        [person("Alice", "a@t.com", "dev", 1).to_string()],
+    )
+    .unwrap();
+    conn.execute(
+        "UPDATE persons SET email = 'local@t.com', _version = 2 WHERE id = 'person-1'",
+        [],
+    )
+    .unwrap();
+}
+
+fn remote_delta(data: Value, version: i64) -> Delta {
+    let operations = vec![Operation {
+        table_name: "persons".into(),
+        record_id: "person-1".into(),
+        op_type: OperationType::Update,
+        data: Some(data),
+        version,
+    }];
+    let checksum = Delta::calculate_checksum(&operations);
+    Delta {
+        id: 1,
+        operations,
+        device_id: "remote-device".into(),
+        vector_clock: VectorClock::new("remote-device".into()),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        checksum,
+        format_version: SYNC_FORMAT_VERSION,
+    }
+}
+
+fn load_person(pool: &app_lib::infra::DbPool) -> (String, String, String, i64) {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT display_name, email, role, _version FROM persons WHERE id = 'person-1'",
+        [],
+        |r: &rusqlite::Row<'_>| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+    )
+    .unwrap()
+}
+
+// ──────────────────────── merge_fields ────────────────────────
+
+#[test]
+fn disjoint_column_edits_both_survive() {
+    let base = person("Alice", "a@t.com", "dev", 1);
+    let local = person("Alice", "local@t.com", "dev", 2);
+    let remote = person("Alice", "a@t.com", "lead", 2);
+
+    let merge = merge_fields(&base, &local, &remote, true);
+    assert_eq!(merge.merged["email"], "local@t.com");
+    assert_eq!(merge.merged["role"], "lead");
+    assert_eq!(merge.remote_applied, vec!["role".to_string()]);
+    assert!(merge.conflicting_columns.is_empty());
+    assert!(merge.keeps_local_changes);
+}
+
+#[test]
+fn same_column_edits_follow_version_lww() {
+    let base = person("Alice", "a@t.com", "dev", 1);
+    let local = person("Alice Local", "a@t.com", "dev", 2);
+    let remote = person("Alice Remote", "a@t.com", "dev", 2);
+
+    let remote_wins = merge_fields(&base, &local, &remote, true);
+    assert_eq!(remote_wins.merged["display_name"], "Alice Remote");
+    assert_eq!(remote_wins.conflicting_columns, vec!["display_name"]);
+    assert!(!remote_wins.keeps_local_changes);
+
+    let local_wins = merge_fields(&base, &local, &remote, false);
+    assert_eq!(local_wins.merged["display_name"], "Alice Local");
+    assert!(local_wins.remote_applied.is_empty());
+    assert!(local_wins.keeps_local_changes);
+}
+
+#[test]
+fn identical_edits_are_not_conflicts() {
+    let base = person("Alice", "a@t.com", "dev", 1);
+    let local = person("Alice", "a@t.com", "lead", 2);
+    let remote = person("Alice", "a@t.com", "lead", 2);
+
+    let merge = merge_fields(&base, &local, &remote, true);
+    assert!(merge.remote_applied.is_empty());
+    assert!(merge.conflicting_columns.is_empty());
+    assert!(!merge.keeps_local_changes);
+}
+
+// ──────────────────────── apply_delta ────────────────────────
+
+#[test]
+fn apply_delta_merges_concurrent_person_edits() {
+    let (pool, device_id) = setup();
+    seed_with_base_and_local_edit(&pool);
+
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    engine
+        .apply_delta(&remote_delta(person("Alice", "a@t.com", "lead", 2), 2))
+        .unwrap();
+
+    let (name, email, role, version) = load_person(&pool);
+    assert_eq!(name, "Alice");
+    assert_eq!(email, "local@t.com");
+    assert_eq!(role, "lead");
+    assert_eq!(version, 3);
+
+    let conn = pool.0.lock().unwrap();
+    // Older local snapshot is superseded by the merged one, which stays queued for upload.
+    let pending: Vec<i64> = conn
+        .prepare(
+            "SELECT version FROM sync_metadata WHERE synced = 0 AND table_name = 'persons' AND record_id = 'person-1'",
+        )
+        .unwrap()
+        .query_map([], |r: &rusqlite::Row<'_>| r.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(pending, vec![3]);
+
+    let base: String = conn
+        .query_row(
+            "SELECT data FROM sync_record_base WHERE table_name = 'persons' AND record_id = 'person-1'",
+            [],
+            |r: &rusqlite::Row<'_>| r.get(0),
+        )
+        .unwrap();
+    let base: Value = serde_json::from_str(&base).unwrap();
+    assert_eq!(base["role"], "lead");
+    assert_eq!(base["email"], "a@t.com");
+}
+
+#[test]
+fn apply_delta_merges_stale_remote_non_conflicting_columns() {
+    let (pool, device_id) = setup();
+    seed_with_base_and_local_edit(&pool);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute("UPDATE persons SET _version = 5 WHERE id = 'person-1'", [])
+            .unwrap();
+    }
+
+    // Remote version is older than local, but only touched a column local did not edit.
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    engine
+        .apply_delta(&remote_delta(person("Alice", "a@t.com", "lead", 2), 2))
+        .unwrap();
+
+    let (_, email, role, version) = load_person(&pool);
+    assert_eq!(email, "local@t.com");
+    assert_eq!(role, "lead");
+    assert_eq!(version, 6);
+}
+
+#[test]
+fn mark_synced_promotes_uploaded_snapshot_to_base() {
+    let (pool, device_id) = setup();
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO persons (id, display_name, email, role, note, is_active, created_at, updated_at, _version)
+             VALUES ('person-1', 'Alice', 'a@t.com', 'dev', '', 1, datetime('now'), datetime('now'), 1)",
+            [],
+        )
+        .unwrap();
+    }
+
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    let collected = engine.collect_local_delta().unwrap();
+    engine
+        .mark_synced(collected.max_sync_meta_id.unwrap())
+        .unwrap();
+
+    let conn = pool.0.lock().unwrap();
+    let base_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sync_record_base WHERE table_name = 'persons' AND record_id = 'person-1'",
+            [],
+            |r: &rusqlite::Row<'_>| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(base_count, 1);
+}
@@ -0,0 +1,162 @@
+//! Sync backend selection and the Google Drive backend (OAuth helpers, token storage)
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::backend::{BackendKind, SYNC_BACKEND_KEY};
+use app_lib::sync::gdrive::{
+    begin_authorization, finish_authorization, multipart_related_body, parse_error_body,
+    pkce_challenge, GoogleDriveCredentials, GDRIVE_CLIENT_ID_KEY, GDRIVE_REFRESH_TOKEN_KEY,
+};
+use app_lib::sync_gdrive_connect_for_pool;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn config_value(pool: &DbPool, key: &str) -> Option<String> {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [key],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+fn credentials() -> GoogleDriveCredentials {
+    GoogleDriveCredentials {
+        client_id: "client-1.apps.googleusercontent.com".to_string(),
+        client_secret: Some("secret-1".to_string()),
+        refresh_token: "1//refresh".to_string(),
+        access_token: "ya29.access".to_string(),
+        expires_at: 1_700_000_000,
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn backend_kind_defaults_to_s3() {
+    assert_eq!(BackendKind::parse(Some("gdrive")), BackendKind::GoogleDrive);
+    assert_eq!(
+        BackendKind::parse(Some(" gdrive ")),
+        BackendKind::GoogleDrive
+    );
+    assert_eq!(BackendKind::parse(Some("s3")), BackendKind::S3);
+    assert_eq!(BackendKind::parse(Some("dropbox")), BackendKind::S3);
+    assert_eq!(BackendKind::parse(None), BackendKind::S3);
+    assert_eq!(BackendKind::GoogleDrive.as_str(), "gdrive");
+}
+
+#[test]
+fn pkce_challenge_matches_rfc_7636() {
+    assert_eq!(
+        pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+        "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+    );
+}
+
+#[test]
+fn drive_and_oauth_error_bodies_are_parsed() {
+    let drive =
+        br#"{"error":{"code":404,"message":"File not found: x","errors":[{"reason":"notFound"}]}}"#;
+    assert_eq!(
+        parse_error_body(drive),
+        (
+            Some("notFound".to_string()),
+            Some("File not found: x".to_string())
+        )
+    );
+    let oauth =
+        br#"{"error":"invalid_grant","error_description":"Token has been expired or revoked."}"#;
+    assert_eq!(
+        parse_error_body(oauth),
+        (
+            Some("invalid_grant".to_string()),
+            Some("Token has been expired or revoked.".to_string())
+        )
+    );
+    assert_eq!(parse_error_body(b"<html>"), (None, None));
+}
+
+#[test]
+fn multipart_body_has_metadata_then_content() {
+    let metadata = serde_json::json!({ "name": "deltas/a.gz", "parents": ["appDataFolder"] });
+    let body = multipart_related_body("b1", &metadata, b"\x1f\x8bdata");
+    let text = String::from_utf8_lossy(&body);
+    assert!(text.starts_with("--b1\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{"));
+    assert!(text.contains("\"parents\":[\"appDataFolder\"]"));
+    assert!(text.contains("Content-Type: application/octet-stream\r\n\r\n"));
+    assert!(body.ends_with(b"data\r\n--b1--\r\n"));
+}
+
+#[test]
+fn credentials_round_trip_and_clear() {
+    let pool = init_test_db();
+    let conn = pool.0.lock().unwrap();
+    assert_eq!(GoogleDriveCredentials::load(&conn).unwrap(), None);
+
+    credentials().save(&conn).unwrap();
+    let loaded = GoogleDriveCredentials::load(&conn).unwrap().unwrap();
+    assert_eq!(loaded, credentials());
+    assert!(loaded.access_token_valid(1_700_000_000 - 120));
+    assert!(!loaded.access_token_valid(1_700_000_000 - 30));
+
+    GoogleDriveCredentials::clear(&conn).unwrap();
+    assert_eq!(GoogleDriveCredentials::load(&conn).unwrap(), None);
+    drop(conn);
+    // The client id stays for the next authorization.
+    assert!(config_value(&pool, GDRIVE_CLIENT_ID_KEY).is_some());
+    assert!(config_value(&pool, GDRIVE_REFRESH_TOKEN_KEY).is_none());
+}
+
+#[test]
+fn connecting_drive_selects_the_backend() {
+    let pool = init_test_db();
+    let resp = sync_gdrive_connect_for_pool(&pool, &credentials()).unwrap();
+    assert_eq!(resp.backend, "gdrive");
+    assert!(resp.gdrive_connected);
+    assert_eq!(
+        config_value(&pool, SYNC_BACKEND_KEY).as_deref(),
+        Some("gdrive")
+    );
+}
+
+#[tokio::test]
+async fn authorization_checks_the_redirect_state() {
+    let err = finish_authorization(Duration::from_secs(1))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    let err = begin_authorization("  ", None).await.unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+
+    let auth_url = begin_authorization("client-1", None).await.unwrap();
+    let url = reqwest::Url::parse(&auth_url).unwrap();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.into_owned())
+            .unwrap()
+    };
+    assert_eq!(param("code_challenge_method"), "S256");
+    assert_eq!(param("access_type"), "offline");
+    let redirect = reqwest::Url::parse(&param("redirect_uri")).unwrap();
+    assert_eq!(redirect.host_str(), Some("127.0.0.1"));
+
+    let finish = tokio::spawn(finish_authorization(Duration::from_secs(10)));
+    let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", redirect.port().unwrap()))
+        .await
+        .unwrap();
+    stream
+        .write_all(b"GET /?code=abc&state=forged HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"));
+
+    let err = finish.await.unwrap().unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert!(err.to_string().contains("GDRIVE_AUTH_STATE_MISMATCH"));
+}
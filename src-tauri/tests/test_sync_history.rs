@@ -0,0 +1,107 @@
+//! Sync history log tests
+
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::history::{list_sync_history, record_sync_history};
+use app_lib::sync::{SyncHistoryReq, SyncRunStats};
+
+// ──────────────────────── Tests ────────────────────────
+
+#[tokio::test]
+async fn failed_sync_attempt_is_recorded() {
+    let pool = init_test_db();
+
+    // No S3 config → pipeline fails before any transfer.
+    let err = app_lib::sync_full_for_pool(&pool).await.unwrap_err();
+
+    let page = list_sync_history(&pool, SyncHistoryReq::default()).unwrap();
+    assert_eq!(page.total, 1);
+    let entry = &page.items[0];
+    assert_eq!(entry.result, "failed");
+    assert_eq!(entry.direction, "none");
+    assert_eq!(entry.ops_uploaded, 0);
+    assert_eq!(entry.error.as_deref(), Some(err.to_string().as_str()));
+}
+
+#[test]
+fn history_is_paginated_newest_first() {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        for i in 0..5 {
+            let stats = SyncRunStats {
+                ops_uploaded: i,
+                bytes_uploaded: i * 10,
+                ..Default::default()
+            };
+            record_sync_history(&conn, "2026-01-01T00:00:00Z", &stats, None).unwrap();
+        }
+    }
+
+    let page = list_sync_history(
+        &pool,
+        SyncHistoryReq {
+            limit: Some(2),
+            offset: Some(1),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(page.total, 5);
+    assert_eq!(page.limit, 2);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.items[0].ops_uploaded, 3);
+    assert_eq!(page.items[1].ops_uploaded, 2);
+    assert_eq!(page.items[0].result, "success");
+    assert_eq!(page.items[0].direction, "up");
+}
+
+#[test]
+fn direction_reflects_transfers() {
+    let none = SyncRunStats::default();
+    assert_eq!(none.direction(), "none");
+
+    let down = SyncRunStats {
+        ops_downloaded: 1,
+        ..Default::default()
+    };
+    assert_eq!(down.direction(), "down");
+
+    let both = SyncRunStats {
+        ops_uploaded: 1,
+        bytes_downloaded: 42,
+        ..Default::default()
+    };
+    assert_eq!(both.direction(), "both");
+}
+
+#[test]
+fn outcome_counters_are_recorded_and_returned() {
+    let pool = init_test_db();
+    let stats = SyncRunStats {
+        ops_downloaded: 3,
+        deltas_uploaded: 1,
+        deltas_applied: 2,
+        skipped_stale_upserts: 1,
+        conflicts_detected: 1,
+        quarantined_objects: vec!["deltas/dev-b/garbage.gz".to_string()],
+        ..Default::default()
+    };
+    {
+        let conn = pool.0.lock().unwrap();
+        record_sync_history(&conn, "2026-01-01T00:00:00Z", &stats, None).unwrap();
+    }
+
+    let entry = &list_sync_history(&pool, SyncHistoryReq::default())
+        .unwrap()
+        .items[0];
+    assert_eq!(entry.deltas_uploaded, 1);
+    assert_eq!(entry.deltas_applied, 2);
+    assert_eq!(entry.skipped_stale_upserts, 1);
+    assert_eq!(entry.conflicts_detected, 1);
+    assert_eq!(entry.quarantined_objects, 1);
+
+    let result = stats.to_result("Sync completed");
+    assert_eq!(result.message, "Sync completed");
+    assert_eq!(result.direction, "down");
+    assert_eq!(result.quarantined_objects, vec!["deltas/dev-b/garbage.gz"]);
+}
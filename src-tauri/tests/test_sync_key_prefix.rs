@@ -0,0 +1,97 @@
+//! Sync key prefix tests: validation and saving without a remote
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::s3_client::{normalize_key_prefix, S3_KEY_PREFIX_KEY};
+use app_lib::{sync_set_key_prefix_for_pool, SyncSetKeyPrefixReq};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn set_prefix(key_prefix: &str, migrate: bool) -> SyncSetKeyPrefixReq {
+    SyncSetKeyPrefixReq {
+        key_prefix: key_prefix.to_string(),
+        migrate: Some(migrate),
+    }
+}
+
+fn saved_prefix(pool: &DbPool) -> Option<String> {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = ?1",
+        [S3_KEY_PREFIX_KEY],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+fn set_config(pool: &DbPool, key: &str, value: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )
+    .unwrap();
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[tokio::test]
+async fn prefix_is_normalized_and_saved_without_a_configured_remote() {
+    let pool = init_test_db();
+    let result = sync_set_key_prefix_for_pool(&pool, set_prefix(" /teams/alpha ", true))
+        .await
+        .unwrap();
+    assert_eq!(result.key_prefix, "teams/alpha/");
+    assert_eq!(result.moved_objects, 0);
+    assert_eq!(saved_prefix(&pool).as_deref(), Some("teams/alpha/"));
+
+    let cleared = sync_set_key_prefix_for_pool(&pool, set_prefix("", true))
+        .await
+        .unwrap();
+    assert_eq!(cleared.key_prefix, "");
+    assert_eq!(saved_prefix(&pool).as_deref(), Some(""));
+}
+
+#[tokio::test]
+async fn invalid_prefix_is_rejected_and_nothing_saved() {
+    let pool = init_test_db();
+    for bad in [
+        "teams//alpha",
+        "../alpha",
+        "deltas",
+        "teams/snapshots",
+        "a b",
+    ] {
+        let err = sync_set_key_prefix_for_pool(&pool, set_prefix(bad, true))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "VALIDATION_ERROR", "{}", bad);
+        assert!(err.to_string().contains("INVALID_KEY_PREFIX"), "{}", bad);
+    }
+    assert!(saved_prefix(&pool).is_none());
+}
+
+#[tokio::test]
+async fn migration_respects_the_remote_cleanup_guard() {
+    let pool = init_test_db();
+    set_config(&pool, "disable_remote_cleanup", "1");
+    let err = sync_set_key_prefix_for_pool(&pool, set_prefix("teams/alpha", true))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "SYNC_OPERATION_DISABLED");
+    assert!(saved_prefix(&pool).is_none());
+
+    // Joining an existing prefix moves nothing and is allowed.
+    let result = sync_set_key_prefix_for_pool(&pool, set_prefix("teams/alpha", false))
+        .await
+        .unwrap();
+    assert_eq!(result.key_prefix, "teams/alpha/");
+}
+
+#[test]
+fn normalized_prefixes_round_trip() {
+    for raw in ["teams/alpha/", "team-1", "a.b/c_d"] {
+        let once = normalize_key_prefix(raw).unwrap();
+        assert_eq!(normalize_key_prefix(&once).unwrap(), once);
+    }
+}
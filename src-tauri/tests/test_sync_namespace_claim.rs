@@ -0,0 +1,78 @@
+//! Namespace claim (one profile per bucket + device id) tests
+
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::namespace_claim::{
+    check_namespace_claim, is_namespace_verified, local_namespace_claim, local_profile,
+    mark_namespace_verified, namespace_claim_key, namespace_token, record_local_profile,
+    reset_device_id, NamespaceClaim, DEFAULT_CLAIM_PROFILE,
+};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn claim(device_id: &str, profile: &str) -> NamespaceClaim {
+    NamespaceClaim {
+        device_id: device_id.to_string(),
+        profile: profile.to_string(),
+        claimed_at: "2026-01-01T00:00:00Z".to_string(),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn claim_collision_only_for_other_profiles() {
+    let ours = claim("dev-1", "work");
+    assert!(check_namespace_claim(None, &ours).unwrap());
+    assert!(!check_namespace_claim(Some(&claim("dev-1", "work")), &ours).unwrap());
+
+    let err = check_namespace_claim(Some(&claim("dev-1", "personal")), &ours).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert!(err.to_string().contains("NAMESPACE_CLAIMED"));
+    assert!(err.to_string().contains("personal"));
+    assert_eq!(namespace_claim_key("dev-1"), "claims/dev-1.json");
+}
+
+#[test]
+fn local_claim_uses_recorded_profile() {
+    let pool = init_test_db();
+    let conn = pool.0.lock().unwrap();
+    assert_eq!(local_profile(&conn), DEFAULT_CLAIM_PROFILE);
+
+    record_local_profile(&conn, "work").unwrap();
+    let ours = local_namespace_claim(&conn, "dev-1");
+    assert_eq!(ours.profile, "work");
+    assert_eq!(ours.device_id, "dev-1");
+}
+
+#[test]
+fn reset_device_id_clears_verified_namespace() {
+    let pool = init_test_db();
+    let conn = pool.0.lock().unwrap();
+    let old_id: String = conn
+        .query_row(
+            "SELECT value FROM sync_config WHERE key = 'device_id'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get(0),
+        )
+        .unwrap();
+    let token = namespace_token(Some("http://s3"), "bucket", &claim(&old_id, "default"));
+    mark_namespace_verified(&conn, &token).unwrap();
+    assert!(is_namespace_verified(&conn, &token));
+    // Same namespace, other profile: must be re-checked.
+    assert!(!is_namespace_verified(
+        &conn,
+        &namespace_token(Some("http://s3"), "bucket", &claim(&old_id, "work"))
+    ));
+
+    let new_id = reset_device_id(&conn).unwrap();
+    assert_ne!(new_id, old_id);
+    let stored: String = conn
+        .query_row(
+            "SELECT value FROM sync_config WHERE key = 'device_id'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(stored, new_id);
+    assert!(!is_namespace_verified(&conn, &token));
+}
@@ -0,0 +1,64 @@
+//! Offline queue (connectivity backoff) tests
+
+use app_lib::sync::offline::{offline_retry_delay, probe_target, OfflineQueue};
+use std::time::Duration;
+
+#[test]
+fn probe_target_defaults_to_aws() {
+    assert_eq!(probe_target(None), "s3.amazonaws.com:443");
+    assert_eq!(probe_target(Some("  ")), "s3.amazonaws.com:443");
+}
+
+#[test]
+fn probe_target_uses_scheme_default_or_explicit_port() {
+    assert_eq!(
+        probe_target(Some("http://localhost:9000")),
+        "localhost:9000"
+    );
+    assert_eq!(probe_target(Some("http://minio/bucket")), "minio:80");
+    assert_eq!(
+        probe_target(Some("https://oss-cn-shanghai.aliyuncs.com")),
+        "oss-cn-shanghai.aliyuncs.com:443"
+    );
+    assert_eq!(probe_target(Some("http://[::1]:9000")), "[::1]:9000");
+    assert_eq!(probe_target(Some("https://[::1]")), "[::1]:443");
+}
+
+#[test]
+fn retry_delay_doubles_up_to_interval() {
+    let interval = Duration::from_secs(60);
+    assert_eq!(offline_retry_delay(0, interval), Duration::from_secs(5));
+    assert_eq!(offline_retry_delay(1, interval), Duration::from_secs(10));
+    assert_eq!(offline_retry_delay(3, interval), Duration::from_secs(40));
+    assert_eq!(offline_retry_delay(4, interval), interval);
+    assert_eq!(offline_retry_delay(100, interval), interval);
+}
+
+#[test]
+fn queue_tracks_offline_period() {
+    let mut queue = OfflineQueue::default();
+    let now = chrono::Utc::now();
+    let interval = Duration::from_secs(300);
+
+    assert_eq!(queue.mark_offline(now, interval), Duration::from_secs(5));
+    let status = queue.status();
+    assert!(status.offline);
+    assert_eq!(status.retry_attempt, 0);
+    assert_eq!(status.offline_since, Some(now.to_rfc3339()));
+    assert_eq!(
+        status.next_retry_at,
+        Some((now + chrono::Duration::seconds(5)).to_rfc3339())
+    );
+
+    let later = now + chrono::Duration::seconds(5);
+    assert_eq!(queue.mark_offline(later, interval), Duration::from_secs(10));
+    let status = queue.status();
+    assert_eq!(status.retry_attempt, 1);
+    // Offline period start is kept across retries.
+    assert_eq!(status.offline_since, Some(now.to_rfc3339()));
+
+    assert!(queue.mark_online());
+    assert!(!queue.is_offline());
+    assert!(!queue.mark_online());
+    assert_eq!(queue.status().next_retry_at, None);
+}
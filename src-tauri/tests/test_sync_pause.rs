@@ -0,0 +1,75 @@
+//! Sync pause/resume and write-debounce tests
+
+use app_lib::sync::pause::{SyncPause, WriteDebounce};
+use app_lib::SyncRuntime;
+use std::time::{Duration, Instant};
+
+#[test]
+fn timed_pause_expires() {
+    let now = chrono::Utc::now();
+    let pause = SyncPause::Until(now + chrono::Duration::minutes(30));
+    assert!(pause.is_paused(now));
+    assert_eq!(pause.remaining(now), Some(Duration::from_secs(30 * 60)));
+    let later = now + chrono::Duration::minutes(31);
+    assert!(!pause.is_paused(later));
+    assert_eq!(pause.remaining(later), None);
+    assert!(!pause.state(later).paused);
+}
+
+#[test]
+fn indefinite_pause_has_no_end() {
+    let now = chrono::Utc::now();
+    let state = SyncPause::Indefinite.state(now);
+    assert!(state.paused);
+    assert_eq!(state.paused_until, None);
+    assert_eq!(SyncPause::Indefinite.remaining(now), None);
+}
+
+#[test]
+fn runtime_pause_and_resume() {
+    let runtime = SyncRuntime::new();
+    assert!(!runtime.pause_state().paused);
+
+    let state = runtime.pause(Some(Duration::from_secs(600)));
+    assert!(state.paused);
+    assert!(state.paused_until.is_some());
+    assert_eq!(runtime.pause_state(), state);
+
+    assert!(!runtime.resume().paused);
+    assert!(!runtime.pause_state().paused);
+
+    assert!(runtime.pause(None).paused);
+    assert!(runtime.pause_state().paused_until.is_none());
+}
+
+#[test]
+fn debounce_waits_for_quiet_period() {
+    let start = Instant::now();
+    let mut debounce = WriteDebounce::new(Duration::from_secs(10), None);
+
+    assert!(!debounce.observe(None, start));
+    assert!(!debounce.observe(Some(1), start));
+    assert!(!debounce.observe(Some(1), start + Duration::from_secs(5)));
+    // Another write restarts the quiet period.
+    assert!(!debounce.observe(Some(3), start + Duration::from_secs(8)));
+    assert!(!debounce.observe(Some(3), start + Duration::from_secs(15)));
+    assert!(debounce.observe(Some(3), start + Duration::from_secs(18)));
+}
+
+#[test]
+fn debounce_ignores_changes_already_pending() {
+    let start = Instant::now();
+    let mut debounce = WriteDebounce::new(Duration::from_secs(1), Some(7));
+    assert!(!debounce.observe(Some(7), start + Duration::from_secs(60)));
+}
+
+#[test]
+fn debounce_resets_after_manual_sync() {
+    let start = Instant::now();
+    let mut debounce = WriteDebounce::new(Duration::from_secs(1), None);
+    assert!(!debounce.observe(Some(4), start));
+    assert!(!debounce.observe(None, start + Duration::from_secs(2)));
+    assert!(!debounce.observe(None, start + Duration::from_secs(5)));
+    assert!(!debounce.observe(Some(5), start + Duration::from_secs(6)));
+    assert!(debounce.observe(Some(5), start + Duration::from_secs(7)));
+}
@@ -0,0 +1,179 @@
+//! Presence heartbeat tests: config, active window, throttle and project names
+
+use app_lib::app::{
+    partner_create, person_create, project_create, PartnerCreateReq, PersonCreateReq,
+    ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::presence::{
+    load_presence_config, local_heartbeat, presence_key, recent_presence, resolve_project_names,
+    save_presence_config, PresenceConfig, PresenceHeartbeat, PresenceThrottle,
+    PRESENCE_ACTIVE_WINDOW_SECS, PRESENCE_HEARTBEAT_INTERVAL_SECS,
+};
+use chrono::{Duration, TimeZone, Utc};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn beat(device_id: &str, name: Option<&str>, project_id: Option<&str>) -> PresenceHeartbeat {
+    PresenceHeartbeat {
+        device_id: device_id.to_string(),
+        display_name: name.map(str::to_string),
+        project_id: project_id.map(str::to_string),
+        updated_at: "2026-01-01T00:00:00Z".to_string(),
+    }
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn presence_config_defaults_off_and_validates_name() {
+    let pool = init_test_db();
+    let conn = pool.0.lock().unwrap();
+    let config = load_presence_config(&conn).unwrap();
+    assert!(!config.enabled);
+    assert_eq!(config.display_name, "");
+
+    save_presence_config(
+        &conn,
+        &PresenceConfig {
+            enabled: true,
+            display_name: "  Alice laptop ".to_string(),
+        },
+    )
+    .unwrap();
+    let config = load_presence_config(&conn).unwrap();
+    assert!(config.enabled);
+    assert_eq!(config.display_name, "Alice laptop");
+
+    let err = save_presence_config(
+        &conn,
+        &PresenceConfig {
+            enabled: true,
+            display_name: "x".repeat(65),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert_eq!(presence_key("dev-1"), "presence/dev-1.json");
+}
+
+#[test]
+fn recent_presence_keeps_active_window_and_prefers_s3_time() {
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+    let fresh = (now - Duration::seconds(30)).timestamp();
+    let stale = (now - Duration::seconds(PRESENCE_ACTIVE_WINDOW_SECS + 1)).timestamp();
+
+    let entries = recent_presence(
+        vec![
+            (beat("me", Some("Mine"), None), Some(fresh - 10)),
+            (beat("dev-2", None, Some("p1")), Some(fresh)),
+            // Own clock says "now", but S3 saw it long ago.
+            (
+                PresenceHeartbeat {
+                    updated_at: now.to_rfc3339(),
+                    ..beat("dev-3", Some("Old"), None)
+                },
+                Some(stale),
+            ),
+            // No listing timestamp: fall back to updated_at.
+            (
+                PresenceHeartbeat {
+                    updated_at: (now - Duration::seconds(5)).to_rfc3339(),
+                    ..beat("dev-4", Some("  "), None)
+                },
+                None,
+            ),
+        ],
+        "me",
+        now,
+    );
+
+    let ids: Vec<&str> = entries.iter().map(|e| e.device_id.as_str()).collect();
+    assert_eq!(ids, vec!["dev-4", "dev-2", "me"]);
+    assert_eq!(entries[0].display_name, "dev-4");
+    assert_eq!(entries[1].project_id.as_deref(), Some("p1"));
+    assert!(entries[2].is_self);
+    assert!(!entries[1].is_self);
+}
+
+#[test]
+fn throttle_waits_for_interval_unless_project_changes() {
+    let mut throttle = PresenceThrottle::new();
+    assert!(throttle.is_due(None, 1_000));
+    throttle.mark_published(Some("p1"), 1_000);
+
+    assert!(!throttle.is_due(Some("p1"), 1_010));
+    assert!(throttle.is_due(Some("p2"), 1_010));
+    assert!(throttle.is_due(None, 1_010));
+    assert!(throttle.is_due(Some("p1"), 1_000 + PRESENCE_HEARTBEAT_INTERVAL_SECS));
+
+    throttle.reset();
+    assert!(throttle.is_due(Some("p1"), 1_010));
+}
+
+#[test]
+fn local_heartbeat_and_project_names() {
+    let pool = init_test_db();
+    let person = person_create(
+        &pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        &pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        &pool,
+        ProjectCreateReq {
+            name: "Alpha".to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+
+    let now = Utc::now();
+    let config = PresenceConfig {
+        enabled: true,
+        display_name: String::new(),
+    };
+    let ours = local_heartbeat("me", &config, Some(&project.id), now);
+    assert_eq!(ours.display_name, None);
+    assert_eq!(ours.project_id.as_deref(), Some(project.id.as_str()));
+
+    let mut entries = recent_presence(
+        vec![
+            (ours, Some(now.timestamp())),
+            (
+                beat("dev-2", Some("Bob"), Some("unknown")),
+                Some(now.timestamp()),
+            ),
+        ],
+        "me",
+        now,
+    );
+    let conn = pool.0.lock().unwrap();
+    resolve_project_names(&conn, &mut entries).unwrap();
+    let names: Vec<Option<&str>> = entries.iter().map(|e| e.project_name.as_deref()).collect();
+    assert!(names.contains(&Some("Alpha")));
+    assert!(names.contains(&None));
+}
@@ -0,0 +1,100 @@
+//! Sync payload format tests: schema marker check, legacy upgrade and newer-format refusal
+
+use app_lib::error::AppError;
+use app_lib::sync::protocol::{
+    check_remote_schema, local_schema_meta, LEGACY_FORMAT_VERSION, SYNC_FORMAT_VERSION,
+};
+use app_lib::sync::snapshot::Snapshot;
+use app_lib::sync::Delta;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::json;
+use std::io::Write;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn gzip(value: &serde_json::Value) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(value.to_string().as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn delta_json(format_version: Option<u32>) -> serde_json::Value {
+    let mut delta = json!({
+        "id": 0,
+        "operations": [],
+        "device_id": "old-device",
+        "vector_clock": { "clocks": {} },
+        "created_at": "2026-01-01T00:00:00Z",
+        "checksum": Delta::calculate_checksum(&[]),
+    });
+    if let Some(version) = format_version {
+        delta["format_version"] = json!(version);
+    }
+    delta
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn schema_marker_is_written_when_missing_or_older() {
+    assert!(check_remote_schema(None).unwrap());
+
+    let mut older = local_schema_meta("other");
+    older.format_version = LEGACY_FORMAT_VERSION;
+    assert!(check_remote_schema(Some(&older)).unwrap());
+
+    let current = local_schema_meta("other");
+    assert!(!check_remote_schema(Some(&current)).unwrap());
+}
+
+#[test]
+fn newer_bucket_format_is_refused() {
+    let mut newer = local_schema_meta("other");
+    newer.format_version = SYNC_FORMAT_VERSION + 1;
+    let err = check_remote_schema(Some(&newer)).unwrap_err();
+    assert!(matches!(err, AppError::SyncFormatUnsupported(_)));
+    assert_eq!(err.code(), "SYNC_FORMAT_UNSUPPORTED");
+}
+
+#[test]
+fn legacy_delta_is_upgraded_on_decode() {
+    let delta = Delta::decompress(&gzip(&delta_json(None))).unwrap();
+    assert_eq!(delta.format_version, SYNC_FORMAT_VERSION);
+    assert_eq!(delta.device_id, "old-device");
+    assert_eq!(Delta::calculate_checksum(&delta.operations), delta.checksum);
+}
+
+#[test]
+fn newer_delta_is_refused_on_decode() {
+    let data = gzip(&delta_json(Some(SYNC_FORMAT_VERSION + 1)));
+    assert!(matches!(
+        Delta::decompress(&data),
+        Err(AppError::SyncFormatUnsupported(_))
+    ));
+}
+
+#[test]
+fn snapshot_format_roundtrip_and_legacy_upgrade() {
+    let data = r#"{"persons":[]}"#;
+    let legacy = json!({
+        "version": 1,
+        "created_at": "2026-01-01T00:00:00Z",
+        "device_id": "old-device",
+        "data": data,
+        "checksum": Snapshot::calculate_checksum(data),
+    });
+    let snapshot = Snapshot::decompress(&gzip(&legacy)).unwrap();
+    assert_eq!(snapshot.format_version, SYNC_FORMAT_VERSION);
+    assert!(snapshot.verify());
+
+    let restored = Snapshot::decompress(&snapshot.compress().unwrap()).unwrap();
+    assert_eq!(restored.format_version, SYNC_FORMAT_VERSION);
+
+    let mut newer = legacy;
+    newer["format_version"] = json!(SYNC_FORMAT_VERSION + 1);
+    assert!(matches!(
+        Snapshot::decompress(&gzip(&newer)),
+        Err(AppError::SyncFormatUnsupported(_))
+    ));
+}
@@ -0,0 +1,168 @@
+//! Repair sync tests: row-hash manifests, repair planning and requeueing
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::repair::{
+    build_manifest, compress_manifest, decompress_manifest, parse_row_hashes_key, plan_repair,
+    repair_request_key, requeue_rows, row_hashes_key, RowHashManifest,
+};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn add_partner(pool: &DbPool, id: &str, name: &str, version: i64) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO partners (id, name, created_at, updated_at, _version) VALUES (?1, ?2, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z', ?3)",
+        rusqlite::params![id, name, version],
+    )
+    .unwrap();
+}
+
+fn manifest(pool: &DbPool, device: &str) -> RowHashManifest {
+    let conn = pool.0.lock().unwrap();
+    build_manifest(&conn, device, &["partners", "project_tags"]).unwrap()
+}
+
+fn ids(plan: &std::collections::BTreeMap<String, Vec<String>>, table: &str) -> Vec<String> {
+    plan.get(table).cloned().unwrap_or_default()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn identical_tables_need_no_repair() {
+    let (a, b) = (init_test_db(), init_test_db());
+    for pool in [&a, &b] {
+        add_partner(pool, "pt1", "Acme", 1);
+    }
+    let (ma, mb) = (manifest(&a, "a"), manifest(&b, "b"));
+    assert_eq!(ma.tables["partners"].root, mb.tables["partners"].root);
+    assert_eq!(plan_repair(&ma, &mb), Default::default());
+}
+
+#[test]
+fn both_sides_agree_on_who_resends_each_row() {
+    let (a, b) = (init_test_db(), init_test_db());
+    // Newer version on a.
+    add_partner(&a, "pt1", "Acme v2", 2);
+    add_partner(&b, "pt1", "Acme", 1);
+    // Same version, different content: tie broken by hash.
+    add_partner(&a, "pt2", "Left", 1);
+    add_partner(&b, "pt2", "Right", 1);
+    // Missing on one side.
+    add_partner(&a, "pt3", "Only a", 1);
+    add_partner(&b, "pt4", "Only b", 1);
+
+    let (ma, mb) = (manifest(&a, "a"), manifest(&b, "b"));
+    let from_a = plan_repair(&ma, &mb);
+    let from_b = plan_repair(&mb, &ma);
+
+    assert!(ids(&from_a.upload, "partners").contains(&"pt1".to_string()));
+    assert!(ids(&from_a.upload, "partners").contains(&"pt3".to_string()));
+    assert!(ids(&from_a.request, "partners").contains(&"pt4".to_string()));
+    // Mirror images: what a uploads, b requests, and vice versa.
+    assert_eq!(from_a.upload, from_b.request);
+    assert_eq!(from_a.request, from_b.upload);
+    let pt2_uploaders = [&from_a, &from_b]
+        .iter()
+        .filter(|p| ids(&p.upload, "partners").contains(&"pt2".to_string()))
+        .count();
+    assert_eq!(pt2_uploaders, 1);
+}
+
+#[test]
+fn newer_delete_beats_stale_row() {
+    let (a, b) = (init_test_db(), init_test_db());
+    add_partner(&a, "pt1", "Acme", 2);
+    add_partner(&b, "pt1", "Acme", 1);
+    {
+        let conn = a.0.lock().unwrap();
+        conn.execute("DELETE FROM partners WHERE id = 'pt1'", [])
+            .unwrap();
+    }
+
+    let (ma, mb) = (manifest(&a, "a"), manifest(&b, "b"));
+    assert_eq!(ma.tables["partners"].tombstones.get("pt1"), Some(&2));
+    let plan = plan_repair(&ma, &mb);
+    assert_eq!(ids(&plan.upload, "partners"), vec!["pt1".to_string()]);
+}
+
+#[test]
+fn requeue_writes_rows_and_deletes_to_sync_metadata() {
+    let pool = init_test_db();
+    add_partner(&pool, "pt1", "Acme", 3);
+    add_partner(&pool, "pt2", "Gone", 2);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute("DELETE FROM partners WHERE id = 'pt2'", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO project_tags (project_id, tag, created_at) VALUES ('proj1', 'urgent', '2026-01-01T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+    }
+
+    let conn = pool.0.lock().unwrap();
+    let ids = vec!["pt1".to_string(), "pt2".to_string(), "missing".to_string()];
+    assert_eq!(requeue_rows(&conn, "dev", "partners", &ids).unwrap(), 2);
+    assert_eq!(
+        requeue_rows(&conn, "dev", "project_tags", &["proj1:urgent".to_string()]).unwrap(),
+        1
+    );
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT table_name, record_id, operation, version, data_snapshot IS NOT NULL
+             FROM sync_metadata WHERE synced = 0 ORDER BY id",
+        )
+        .unwrap();
+    let rows: Vec<(String, String, String, i64, bool)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            ("partners".into(), "pt1".into(), "UPDATE".into(), 3, true),
+            ("partners".into(), "pt2".into(), "DELETE".into(), 2, false),
+            (
+                "project_tags".into(),
+                "proj1:urgent".into(),
+                "INSERT".into(),
+                1,
+                true
+            ),
+        ]
+    );
+}
+
+#[test]
+fn manifest_roundtrip_and_keys() {
+    let pool = init_test_db();
+    add_partner(&pool, "pt1", "Acme", 1);
+    let m = manifest(&pool, "dev-a");
+    assert_eq!(
+        decompress_manifest(&compress_manifest(&m).unwrap()).unwrap(),
+        m
+    );
+
+    assert_eq!(
+        parse_row_hashes_key(&row_hashes_key("dev-a")),
+        Some("dev-a")
+    );
+    assert_eq!(parse_row_hashes_key("devices/dev-a/cursors.json"), None);
+    assert_eq!(
+        repair_request_key("dev-b", "dev-a"),
+        "repair/dev-b/dev-a.json"
+    );
+}
@@ -0,0 +1,134 @@
+//! Read-only replica mode: trigger WHEN condition + write command gate
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::replica::{
+    blocks_command, ensure_writable, is_read_only_replica, READ_ONLY_REPLICA_KEY,
+};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn setup() -> DbPool {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE sync_config SET value = '1' WHERE key = 'sync_enabled'",
+            [],
+        )
+        .unwrap();
+    }
+    pool
+}
+
+fn set_replica(pool: &DbPool, on: bool) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO sync_config (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        [READ_ONLY_REPLICA_KEY, if on { "1" } else { "0" }],
+    )
+    .unwrap();
+}
+
+fn pending_tables(pool: &DbPool) -> Vec<String> {
+    let conn = pool.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT table_name FROM sync_metadata WHERE synced = 0 ORDER BY table_name",
+        )
+        .unwrap();
+    stmt.query_map([], |row: &rusqlite::Row<'_>| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn replica_records_only_device_registry_changes() {
+    let pool = setup();
+    set_replica(&pool, true);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO persons (id, display_name, created_at, updated_at) VALUES ('p1', 'Alice', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO partners (id, name, created_at, updated_at) VALUES ('pt1', 'Acme', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        conn.execute("DELETE FROM partners WHERE id = 'pt1'", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO devices (id, name, created_at, updated_at) VALUES ('d1', 'Tablet', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+    }
+    assert_eq!(pending_tables(&pool), vec!["devices".to_string()]);
+
+    // Turning the mode off only affects later edits.
+    set_replica(&pool, false);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE persons SET display_name = 'Alicia' WHERE id = 'p1'",
+            [],
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        pending_tables(&pool),
+        vec!["devices".to_string(), "persons".to_string()]
+    );
+}
+
+#[test]
+fn writes_are_rejected_only_on_a_replica() {
+    let pool = setup();
+    {
+        let conn = pool.0.lock().unwrap();
+        assert!(!is_read_only_replica(&conn).unwrap());
+        assert!(ensure_writable(&conn, "cmd_project_create").is_ok());
+    }
+
+    set_replica(&pool, true);
+    let conn = pool.0.lock().unwrap();
+    assert!(is_read_only_replica(&conn).unwrap());
+    let err = ensure_writable(&conn, "cmd_project_create").unwrap_err();
+    assert_eq!(err.code(), "READ_ONLY_REPLICA");
+    assert!(err.to_string().contains("cmd_project_create"));
+}
+
+#[test]
+fn only_local_business_writes_are_blocked() {
+    for command in [
+        "cmd_project_create",
+        "cmd_person_merge",
+        "cmd_comment_update",
+        "cmd_import_json",
+        "cmd_undo_last",
+        "cmd_sync_resolve_conflict",
+        "cmd_sync_repair",
+    ] {
+        assert!(blocks_command(command), "{} should be blocked", command);
+    }
+    // Reads, device-local settings and remote-driven sync keep working.
+    for command in [
+        "cmd_project_list",
+        "cmd_saved_view_create",
+        "cmd_sync_update_config",
+        "cmd_sync_full",
+        "cmd_sync_apply_staged",
+        "cmd_sync_restore_snapshot",
+        "cmd_sync_bootstrap_from_remote",
+        "cmd_sync_rename_device",
+    ] {
+        assert!(!blocks_command(command), "{} should be allowed", command);
+    }
+}
@@ -0,0 +1,247 @@
+//! Review-before-apply staging tests (in-memory SQLite)
+
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::device_registry::revoke_device;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::staging::{
+    apply_staged_deltas, discard_staged_deltas, list_staged_deltas, should_stage_remote_deltas,
+    stage_remote_delta, summarize_operation,
+};
+use app_lib::sync::{Delta, Operation, OperationType, VectorClock};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn person_delta(record_id: &str, name: &str) -> Delta {
+    Delta {
+        id: 1,
+        operations: vec![Operation {
+            table_name: "persons".into(),
+            record_id: record_id.into(),
+            op_type: OperationType::Insert,
+            data: Some(serde_json::json!({
+                "id": record_id,
+                "display_name": name,
+                "email": "",
+                "role": "",
+                "note": "",
+                "is_active": 1,
+                "created_at": "2026-01-01T00:00:00Z",
+                "updated_at": "2026-01-01T00:00:00Z"
+            })),
+            version: 1,
+        }],
+        device_id: "remote-device".into(),
+        vector_clock: VectorClock::new("remote-device".into()),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        checksum: "ignored".into(),
+        format_version: SYNC_FORMAT_VERSION,
+    }
+}
+
+fn person_count(pool: &app_lib::infra::DbPool, id: &str) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT COUNT(*) FROM persons WHERE id = ?1",
+        [id],
+        |row: &rusqlite::Row<'_>| row.get(0),
+    )
+    .unwrap()
+}
+
+fn stage_from(pool: &app_lib::infra::DbPool, device_id: &str, record_id: &str, name: &str) {
+    let mut delta = person_delta(record_id, name);
+    delta.device_id = device_id.into();
+    delta.vector_clock = VectorClock::new(device_id.into());
+    let conn = pool.0.lock().unwrap();
+    stage_remote_delta(
+        &conn,
+        &format!("deltas/{}/delta-1-a.gz", device_id),
+        device_id,
+        1,
+        &delta,
+    )
+    .unwrap();
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn staging_is_off_by_default_and_follows_flag() {
+    let pool = init_test_db();
+    let conn = pool.0.lock().unwrap();
+    assert!(!should_stage_remote_deltas(&conn).unwrap());
+
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES ('review_before_apply', '1')",
+        [],
+    )
+    .unwrap();
+    assert!(should_stage_remote_deltas(&conn).unwrap());
+}
+
+#[test]
+fn staged_delta_is_listed_with_summary_and_not_applied() {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        let staged = stage_remote_delta(
+            &conn,
+            "deltas/remote-device/delta-1-a.gz",
+            "remote-device",
+            1,
+            &person_delta("staged-p1", "Alice"),
+        )
+        .unwrap();
+        assert!(staged);
+
+        // Same key again is a no-op.
+        let again = stage_remote_delta(
+            &conn,
+            "deltas/remote-device/delta-1-a.gz",
+            "remote-device",
+            1,
+            &person_delta("staged-p1", "Alice"),
+        )
+        .unwrap();
+        assert!(!again);
+
+        // Non-empty queue keeps staging even with review mode off.
+        assert!(should_stage_remote_deltas(&conn).unwrap());
+    }
+
+    let staged = list_staged_deltas(&pool).unwrap();
+    assert_eq!(staged.len(), 1);
+    assert_eq!(staged[0].changes.len(), 1);
+    assert_eq!(staged[0].changes[0].operation, "INSERT");
+    assert_eq!(staged[0].changes[0].summary, "Create person \"Alice\"");
+    assert_eq!(person_count(&pool, "staged-p1"), 0);
+}
+
+#[test]
+fn apply_staged_applies_in_order_and_clears_queue() {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        stage_remote_delta(
+            &conn,
+            "deltas/remote-device/delta-2-b.gz",
+            "remote-device",
+            2,
+            &person_delta("staged-p2", "Bob"),
+        )
+        .unwrap();
+        stage_remote_delta(
+            &conn,
+            "deltas/remote-device/delta-1-a.gz",
+            "remote-device",
+            1,
+            &person_delta("staged-p1", "Alice"),
+        )
+        .unwrap();
+    }
+
+    let result = apply_staged_deltas(&pool).unwrap();
+    assert_eq!(result.applied_deltas, 2);
+    assert_eq!(result.applied_operations, 2);
+    assert_eq!(person_count(&pool, "staged-p1"), 1);
+    assert_eq!(person_count(&pool, "staged-p2"), 1);
+    assert!(list_staged_deltas(&pool).unwrap().is_empty());
+}
+
+#[test]
+fn revoking_a_device_drops_its_staged_deltas() {
+    let pool = init_test_db();
+    // "old-laptop" sorts before "remote-device" in the apply order.
+    stage_from(&pool, "old-laptop", "staged-p1", "Alice");
+    stage_from(&pool, "remote-device", "staged-p2", "Bob");
+    {
+        let conn = pool.0.lock().unwrap();
+        let local_device_id: String = conn
+            .query_row(
+                "SELECT value FROM sync_config WHERE key = 'device_id'",
+                [],
+                |row: &rusqlite::Row<'_>| row.get(0),
+            )
+            .unwrap();
+        revoke_device(&conn, &local_device_id, "old-laptop").unwrap();
+    }
+    let staged = list_staged_deltas(&pool).unwrap();
+    assert_eq!(staged.len(), 1);
+    assert_eq!(staged[0].source_device_id, "remote-device");
+
+    let result = apply_staged_deltas(&pool).unwrap();
+    assert_eq!(result.applied_deltas, 1);
+    assert_eq!(result.revoked_deltas, 0);
+    assert_eq!(person_count(&pool, "staged-p1"), 0);
+    assert_eq!(person_count(&pool, "staged-p2"), 1);
+    assert!(list_staged_deltas(&pool).unwrap().is_empty());
+}
+
+#[test]
+fn apply_staged_skips_deltas_from_devices_revoked_by_sync() {
+    let pool = init_test_db();
+    stage_from(&pool, "old-laptop", "staged-p1", "Alice");
+    stage_from(&pool, "remote-device", "staged-p2", "Bob");
+    {
+        // Revocation that arrived as a synced registry row, not through revoke_device.
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO devices (id, name, platform, revoked_at, created_at, updated_at, _version)
+             VALUES ('old-laptop', 'old-laptop', '', '2026-01-02T00:00:00Z',
+                     '2026-01-02T00:00:00Z', '2026-01-02T00:00:00Z', 1)",
+            [],
+        )
+        .unwrap();
+    }
+
+    let result = apply_staged_deltas(&pool).unwrap();
+    assert_eq!(result.applied_deltas, 1);
+    assert_eq!(result.revoked_deltas, 1);
+    assert_eq!(person_count(&pool, "staged-p1"), 0);
+    assert_eq!(person_count(&pool, "staged-p2"), 1);
+    assert!(list_staged_deltas(&pool).unwrap().is_empty());
+}
+
+#[test]
+fn discard_staged_drops_queue_without_applying() {
+    let pool = init_test_db();
+    {
+        let conn = pool.0.lock().unwrap();
+        stage_remote_delta(
+            &conn,
+            "deltas/remote-device/delta-1-a.gz",
+            "remote-device",
+            1,
+            &person_delta("staged-p1", "Alice"),
+        )
+        .unwrap();
+    }
+
+    assert_eq!(discard_staged_deltas(&pool).unwrap(), 1);
+    assert!(list_staged_deltas(&pool).unwrap().is_empty());
+    assert_eq!(person_count(&pool, "staged-p1"), 0);
+}
+
+#[test]
+fn summarize_status_change_and_delete() {
+    let status = Operation {
+        table_name: "status_history".into(),
+        record_id: "h1".into(),
+        op_type: OperationType::Insert,
+        data: Some(serde_json::json!({"from_status": "BACKLOG", "to_status": "PLANNED"})),
+        version: 1,
+    };
+    assert_eq!(
+        summarize_operation(&status),
+        "Create status change \"BACKLOG -> PLANNED\""
+    );
+
+    let delete = Operation {
+        table_name: "projects".into(),
+        record_id: "proj-1".into(),
+        op_type: OperationType::Delete,
+        data: None,
+        version: 1,
+    };
+    assert_eq!(summarize_operation(&delete), "Delete project proj-1");
+}
@@ -0,0 +1,142 @@
+//! Snapshot storage class / tags validation and the delta lifecycle rule
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::s3_client::S3_KEY_PREFIX_KEY;
+use app_lib::sync::storage_class::{
+    format_object_tags, lifecycle_rule_id, normalize_storage_class, parse_object_tags,
+    tagging_header, DeltaLifecycleRule,
+};
+use app_lib::{sync_install_lifecycle_for_pool, SyncInstallLifecycleReq};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn set_config(pool: &DbPool, key: &str, value: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_config (key, value) VALUES (?1, ?2)",
+        [key, value],
+    )
+    .unwrap();
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn storage_class_is_normalized_and_limited_to_instant_access() {
+    assert_eq!(
+        normalize_storage_class(" standard_ia ").unwrap(),
+        "STANDARD_IA"
+    );
+    assert_eq!(normalize_storage_class("GLACIER_IR").unwrap(), "GLACIER_IR");
+    assert_eq!(normalize_storage_class("").unwrap(), "STANDARD");
+    for bad in ["GLACIER", "DEEP_ARCHIVE", "cheap"] {
+        let err = normalize_storage_class(bad).unwrap_err();
+        assert!(err.starts_with("INVALID_STORAGE_CLASS"), "{}", err);
+    }
+}
+
+#[test]
+fn object_tags_are_parsed_and_encoded() {
+    let tags = parse_object_tags("team = alpha , kind=snapshot,owner=a b").unwrap();
+    assert_eq!(
+        format_object_tags(&tags),
+        "team=alpha,kind=snapshot,owner=a b"
+    );
+    assert_eq!(
+        tagging_header(&tags),
+        "team=alpha&kind=snapshot&owner=a%20b"
+    );
+    assert_eq!(parse_object_tags("a=1&b=2").unwrap().len(), 2);
+    assert!(parse_object_tags("").unwrap().is_empty());
+    assert_eq!(parse_object_tags("archived").unwrap()[0].1, "");
+}
+
+#[test]
+fn invalid_object_tags_are_rejected() {
+    let eleven = (0..11)
+        .map(|i| format!("k{}=v", i))
+        .collect::<Vec<_>>()
+        .join(",");
+    for bad in [
+        "=value".to_string(),
+        "a=1,a=2".to_string(),
+        "aws:created=now".to_string(),
+        format!("{}=v", "k".repeat(129)),
+        eleven,
+    ] {
+        assert!(parse_object_tags(&bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn lifecycle_rule_targets_the_deltas_of_the_key_prefix() {
+    assert_eq!(lifecycle_rule_id(""), "projex-deltas");
+    assert_eq!(
+        lifecycle_rule_id("teams/alpha/"),
+        "projex-teams-alpha-deltas"
+    );
+
+    let rule = DeltaLifecycleRule::new("teams/alpha/", None, None, None).unwrap();
+    assert_eq!(rule.prefix, "teams/alpha/deltas/");
+    assert_eq!(rule.storage_class, "STANDARD_IA");
+    assert_eq!(rule.transition_after_days, 30);
+    assert_eq!(rule.abort_multipart_after_days, 7);
+
+    let json = rule.to_json();
+    let first = &json["Rules"][0];
+    assert_eq!(first["ID"], "projex-teams-alpha-deltas");
+    assert_eq!(first["Filter"]["Prefix"], "teams/alpha/deltas/");
+    assert_eq!(first["Transitions"][0]["StorageClass"], "STANDARD_IA");
+    assert_eq!(
+        first["AbortIncompleteMultipartUpload"]["DaysAfterInitiation"],
+        7
+    );
+}
+
+#[test]
+fn lifecycle_rule_rejects_transitions_s3_would_refuse() {
+    assert!(DeltaLifecycleRule::new("", Some(10), Some("STANDARD_IA"), None).is_err());
+    assert!(DeltaLifecycleRule::new("", Some(10), Some("GLACIER_IR"), None).is_ok());
+    assert!(DeltaLifecycleRule::new("", None, Some("STANDARD"), None).is_err());
+    assert!(DeltaLifecycleRule::new("", None, Some("DEEP_ARCHIVE"), None).is_err());
+    assert!(DeltaLifecycleRule::new("", None, None, Some(0)).is_err());
+}
+
+#[tokio::test]
+async fn install_without_a_configured_remote_only_returns_the_rule() {
+    let pool = init_test_db();
+    set_config(&pool, "s3_bucket", "shared-bucket");
+    set_config(&pool, S3_KEY_PREFIX_KEY, "teams/alpha/");
+
+    let result = sync_install_lifecycle_for_pool(
+        &pool,
+        SyncInstallLifecycleReq {
+            transition_after_days: Some(60),
+            storage_class: Some("glacier_ir".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert!(!result.installed);
+    assert_eq!(result.rule_id, "projex-teams-alpha-deltas");
+    assert!(result.lifecycle_json.contains("\"GLACIER_IR\""));
+    assert!(result.lifecycle_json.contains("\"Days\": 60"));
+    assert!(result.cli_command.contains("--bucket shared-bucket"));
+}
+
+#[tokio::test]
+async fn install_rejects_an_invalid_rule() {
+    let pool = init_test_db();
+    let err = sync_install_lifecycle_for_pool(
+        &pool,
+        SyncInstallLifecycleReq {
+            storage_class: Some("GLACIER".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
@@ -0,0 +1,304 @@
+//! Sync scope tests: trigger WHEN conditions, collect / apply filters and scoped snapshots
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::table_filter::{
+    load_ignored_tables, set_exclude_archived_projects, set_ignored_tables,
+};
+use app_lib::sync::{
+    Delta, DeltaSyncEngine, Operation, OperationType, SnapshotManager, VectorClock,
+};
+use serde_json::json;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn setup() -> (DbPool, String) {
+    let pool = init_test_db();
+    let device_id = {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE sync_config SET value = '1' WHERE key = 'sync_enabled'",
+            [],
+        )
+        .unwrap();
+        conn.query_row(
+            "SELECT value FROM sync_config WHERE key = 'device_id'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get::<_, String>(0),
+        )
+        .unwrap()
+    };
+    (pool, device_id)
+}
+
+fn ignore(pool: &DbPool, tables: &[&str]) {
+    let conn = pool.0.lock().unwrap();
+    let tables: Vec<String> = tables.iter().map(|t| t.to_string()).collect();
+    set_ignored_tables(&conn, &tables).unwrap();
+}
+
+fn pending_tables(pool: &DbPool) -> Vec<String> {
+    let conn = pool.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT table_name FROM sync_metadata WHERE synced = 0 ORDER BY table_name",
+        )
+        .unwrap();
+    stmt.query_map([], |row: &rusqlite::Row<'_>| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+fn count(pool: &DbPool, table: &str) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM {}", table),
+        [],
+        |row: &rusqlite::Row<'_>| row.get(0),
+    )
+    .unwrap()
+}
+
+fn remote_delta(operations: Vec<Operation>) -> Delta {
+    let checksum = Delta::calculate_checksum(&operations);
+    Delta {
+        id: 1,
+        operations,
+        device_id: "remote-device".into(),
+        vector_clock: VectorClock::new("remote-device".into()),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        checksum,
+        format_version: SYNC_FORMAT_VERSION,
+    }
+}
+
+fn insert_op(table: &str, data: serde_json::Value) -> Operation {
+    Operation {
+        table_name: table.into(),
+        record_id: data["id"].as_str().unwrap().into(),
+        op_type: OperationType::Insert,
+        data: Some(data),
+        version: 1,
+    }
+}
+
+fn exclude_archived(pool: &DbPool) {
+    let conn = pool.0.lock().unwrap();
+    set_exclude_archived_projects(&conn, true).unwrap();
+}
+
+fn exec(pool: &DbPool, sql: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(sql, []).unwrap();
+}
+
+/// Person, partner, an active and an archived project, each with one comment.
+fn seed_projects(pool: &DbPool) {
+    exec(pool, "INSERT INTO persons (id, display_name, created_at, updated_at) VALUES ('p1', 'Alice', '2026-01-01', '2026-01-01')");
+    exec(pool, "INSERT INTO partners (id, name, created_at, updated_at) VALUES ('pt1', 'Acme', '2026-01-01', '2026-01-01')");
+    for (id, status) in [("active", "IN_PROGRESS"), ("old", "ARCHIVED")] {
+        exec(
+            pool,
+            &format!(
+                "INSERT INTO projects (id, name, description, priority, current_status, country_code, partner_id, owner_person_id, created_at, updated_at)
+                 VALUES ('{id}', '{id}', '', 3, '{status}', 'CN', 'pt1', 'p1', '2026-01-01', '2026-01-01')"
+            ),
+        );
+        exec(
+            pool,
+            &format!(
+                "INSERT INTO project_comments (id, project_id, person_id, content, is_pinned, created_at, updated_at)
+                 VALUES ('c-{id}', '{id}', 'p1', 'note', 0, '2026-01-01', '2026-01-01')"
+            ),
+        );
+    }
+}
+
+fn collected_records(pool: &DbPool, device_id: &str) -> Vec<String> {
+    let engine = DeltaSyncEngine::new(pool, device_id.to_string());
+    let mut records: Vec<String> = engine
+        .collect_local_delta()
+        .unwrap()
+        .delta
+        .operations
+        .iter()
+        .map(|op| format!("{}:{}", op.table_name, op.record_id))
+        .collect();
+    records.sort();
+    records
+}
+
+fn ids(pool: &DbPool, table: &str) -> Vec<String> {
+    let conn = pool.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare(&format!("SELECT id FROM {} ORDER BY id", table))
+        .unwrap();
+    stmt.query_map([], |row: &rusqlite::Row<'_>| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn ignored_table_edits_are_not_tracked() {
+    let (pool, _) = setup();
+    ignore(&pool, &["persons"]);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT INTO persons (id, display_name, created_at, updated_at) VALUES ('p1', 'Alice', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO partners (id, name, created_at, updated_at) VALUES ('pt1', 'Acme', datetime('now'), datetime('now'))",
+            [],
+        )
+        .unwrap();
+    }
+    assert_eq!(pending_tables(&pool), vec!["partners".to_string()]);
+
+    // Re-enabling only affects later edits.
+    ignore(&pool, &[]);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE persons SET display_name = 'Alicia' WHERE id = 'p1'",
+            [],
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        pending_tables(&pool),
+        vec!["partners".to_string(), "persons".to_string()]
+    );
+}
+
+#[test]
+fn apply_drops_operations_on_ignored_tables() {
+    let (pool, device_id) = setup();
+    ignore(&pool, &["partners"]);
+
+    let delta = remote_delta(vec![
+        insert_op(
+            "persons",
+            json!({"id": "p1", "display_name": "Alice", "email": "", "role": "", "note": "",
+                   "is_active": 1, "created_at": "2026-01-01T00:00:00Z",
+                   "updated_at": "2026-01-01T00:00:00Z", "_version": 1}),
+        ),
+        insert_op(
+            "partners",
+            json!({"id": "pt1", "name": "Acme", "note": "", "is_active": 1,
+                   "created_at": "2026-01-01T00:00:00Z",
+                   "updated_at": "2026-01-01T00:00:00Z", "_version": 1}),
+        ),
+    ]);
+    let stats = DeltaSyncEngine::new(&pool, device_id)
+        .apply_delta(&delta)
+        .unwrap();
+
+    assert_eq!(stats.ignored_table_ops, 1);
+    assert_eq!(count(&pool, "persons"), 1);
+    assert_eq!(count(&pool, "partners"), 0);
+}
+
+#[test]
+fn unknown_table_is_rejected_without_changes() {
+    let (pool, _) = setup();
+    ignore(&pool, &["project_comments"]);
+
+    let conn = pool.0.lock().unwrap();
+    let err =
+        set_ignored_tables(&conn, &["project_comments".into(), "sync_config".into()]).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    assert_eq!(
+        load_ignored_tables(&conn).unwrap(),
+        vec!["project_comments".to_string()]
+    );
+}
+
+#[test]
+fn collect_drops_pending_operations_outside_the_scope() {
+    let (pool, device_id) = setup();
+    seed_projects(&pool);
+    // Recorded while the table was still synced.
+    ignore(&pool, &["persons"]);
+    exclude_archived(&pool);
+
+    let records = collected_records(&pool, &device_id);
+    assert!(records.contains(&"projects:old".to_string()));
+    assert!(records.contains(&"project_comments:c-active".to_string()));
+    assert!(!records.contains(&"project_comments:c-old".to_string()));
+    assert!(!records.iter().any(|r| r.starts_with("persons:")));
+    // Dropped operations are not pending anymore.
+    assert!(!pending_tables(&pool).contains(&"persons".to_string()));
+}
+
+#[test]
+fn apply_drops_details_of_locally_archived_projects() {
+    let (pool, device_id) = setup();
+    seed_projects(&pool);
+    exclude_archived(&pool);
+
+    let comment = |id: &str, project_id: &str| {
+        insert_op(
+            "project_comments",
+            json!({"id": id, "project_id": project_id, "person_id": "p1", "content": "remote",
+                   "is_pinned": 0, "created_at": "2026-01-02T00:00:00Z",
+                   "updated_at": "2026-01-02T00:00:00Z", "_version": 1}),
+        )
+    };
+    let delta = remote_delta(vec![comment("r1", "active"), comment("r2", "old")]);
+    let stats = DeltaSyncEngine::new(&pool, device_id)
+        .apply_delta(&delta)
+        .unwrap();
+
+    assert_eq!(stats.archived_project_ops, 1);
+    assert_eq!(
+        ids(&pool, "project_comments"),
+        vec!["c-active", "c-old", "r1"]
+    );
+}
+
+#[test]
+fn scoped_snapshot_leaves_out_rows_and_restore_keeps_them() {
+    let (pool, device_id) = setup();
+    seed_projects(&pool);
+    ignore(&pool, &["persons"]);
+    exclude_archived(&pool);
+    let mgr = SnapshotManager::new(&pool, device_id);
+    let snapshot = mgr.create_snapshot().unwrap();
+    assert_eq!(snapshot.scope.ignored_tables, vec!["persons".to_string()]);
+    assert!(snapshot.scope.exclude_archived_projects);
+    let data: serde_json::Value = serde_json::from_str(&snapshot.data).unwrap();
+    assert!(data["persons"].as_array().unwrap().is_empty());
+    assert_eq!(data["comments"].as_array().unwrap().len(), 1);
+    assert_eq!(data["projects"].as_array().unwrap().len(), 2);
+
+    // A device syncing everything restores the snapshot without losing the excluded rows.
+    let (other, other_device) = setup();
+    seed_projects(&other);
+    exec(&other, "UPDATE project_comments SET content = 'local edit'");
+    exec(&other, "INSERT INTO persons (id, display_name, created_at, updated_at) VALUES ('p2', 'Bob', '2026-01-01', '2026-01-01')");
+    let other_mgr = SnapshotManager::new(&other, other_device);
+    let preview = other_mgr.diff_snapshot(&snapshot).unwrap();
+    assert!(!preview.iter().any(|d| d.table == "persons"));
+    other_mgr.restore_snapshot(&snapshot).unwrap();
+
+    assert_eq!(ids(&other, "persons"), vec!["p1", "p2"]);
+    let conn = other.0.lock().unwrap();
+    let content = |id: &str| -> String {
+        conn.query_row(
+            "SELECT content FROM project_comments WHERE id = ?1",
+            [id],
+            |row: &rusqlite::Row<'_>| row.get(0),
+        )
+        .unwrap()
+    };
+    assert_eq!(content("c-active"), "note");
+    assert_eq!(content("c-old"), "local edit");
+}
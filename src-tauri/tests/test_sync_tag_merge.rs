@@ -0,0 +1,293 @@
+//! Project tag set merge: concurrent adds survive, removals propagate via tombstones
+
+use app_lib::app::{
+    partner_create, person_create, project_create, project_update, PartnerCreateReq,
+    PersonCreateReq, ProjectCreateReq, ProjectUpdateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::{Delta, DeltaSyncEngine, Operation, OperationType, VectorClock};
+use serde_json::json;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn device_id(pool: &DbPool) -> String {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT value FROM sync_config WHERE key = 'device_id'",
+        [],
+        |row: &rusqlite::Row<'_>| row.get::<_, String>(0),
+    )
+    .unwrap()
+}
+
+fn seed_project(pool: &DbPool, tags: Vec<&str>) -> String {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Alpha".to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(tags.into_iter().map(str::to_string).collect()),
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn set_tags(pool: &DbPool, project_id: &str, tags: Vec<&str>) {
+    project_update(
+        pool,
+        ProjectUpdateReq {
+            id: project_id.to_string(),
+            name: None,
+            description: None,
+            priority: None,
+            country_code: None,
+            owner_person_id: None,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(tags.into_iter().map(str::to_string).collect()),
+            parent_project_id: None,
+            partner_id: None,
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap();
+}
+
+fn make_delta(operations: Vec<Operation>) -> Delta {
+    let checksum = Delta::calculate_checksum(&operations);
+    Delta {
+        id: 1,
+        operations,
+        device_id: "remote-device".into(),
+        vector_clock: VectorClock::new("remote-device".into()),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        checksum,
+        format_version: SYNC_FORMAT_VERSION,
+    }
+}
+
+fn tag_add(project_id: &str, tag: &str, created_at: &str) -> Operation {
+    Operation {
+        table_name: "project_tags".into(),
+        record_id: format!("{}:{}", project_id, tag),
+        op_type: OperationType::Insert,
+        data: Some(json!({ "project_id": project_id, "tag": tag, "created_at": created_at })),
+        version: 1,
+    }
+}
+
+fn tag_remove(project_id: &str, tag: &str, deleted_at: Option<&str>) -> Operation {
+    Operation {
+        table_name: "project_tags".into(),
+        record_id: format!("{}:{}", project_id, tag),
+        op_type: OperationType::Delete,
+        data: deleted_at
+            .map(|at| json!({ "project_id": project_id, "tag": tag, "deleted_at": at })),
+        version: 1,
+    }
+}
+
+fn tags(pool: &DbPool, project_id: &str) -> Vec<String> {
+    let conn = pool.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare("SELECT tag FROM project_tags WHERE project_id = ?1 ORDER BY tag")
+        .unwrap();
+    stmt.query_map([project_id], |row: &rusqlite::Row<'_>| row.get(0))
+        .unwrap()
+        .collect::<Result<Vec<String>, _>>()
+        .unwrap()
+}
+
+fn tombstone(pool: &DbPool, project_id: &str, tag: &str) -> Option<String> {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT deleted_at FROM sync_tombstones WHERE table_name = 'project_tags' AND record_id = ?1",
+        [format!("{}:{}", project_id, tag)],
+        |row: &rusqlite::Row<'_>| row.get(0),
+    )
+    .ok()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn concurrent_adds_from_both_devices_survive() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, vec!["local"]);
+    let engine = DeltaSyncEngine::new(&pool, device_id(&pool));
+
+    let stats = engine
+        .apply_delta(&make_delta(vec![
+            tag_add(&project_id, "remote", "2026-01-01T00:00:00Z"),
+            tag_add(&project_id, "local", "2026-01-01T00:00:00Z"),
+        ]))
+        .unwrap();
+    assert_eq!(stats.applied_ops, 2);
+    assert_eq!(tags(&pool, &project_id), vec!["local", "remote"]);
+
+    // The existing local row keeps its own created_at.
+    let conn = pool.0.lock().unwrap();
+    let created_at: String = conn
+        .query_row(
+            "SELECT created_at FROM project_tags WHERE project_id = ?1 AND tag = 'local'",
+            [&project_id],
+            |row: &rusqlite::Row<'_>| row.get(0),
+        )
+        .unwrap();
+    assert_ne!(created_at, "2026-01-01T00:00:00Z");
+}
+
+#[test]
+fn remote_removal_propagates_and_leaves_tombstone() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, vec!["a", "b"]);
+    let engine = DeltaSyncEngine::new(&pool, device_id(&pool));
+
+    engine
+        .apply_delta(&make_delta(vec![tag_remove(
+            &project_id,
+            "a",
+            Some("2999-01-01T00:00:00.000Z"),
+        )]))
+        .unwrap();
+    assert_eq!(tags(&pool, &project_id), vec!["b"]);
+    assert_eq!(
+        tombstone(&pool, &project_id, "a").as_deref(),
+        Some("2999-01-01T00:00:00.000Z")
+    );
+
+    // A concurrent add made before the removal does not bring it back...
+    let stats = engine
+        .apply_delta(&make_delta(vec![tag_add(
+            &project_id,
+            "a",
+            "2998-01-01T00:00:00Z",
+        )]))
+        .unwrap();
+    assert_eq!(stats.skipped_stale_upserts, 1);
+    assert_eq!(tags(&pool, &project_id), vec!["b"]);
+
+    // ...but a later re-add does, and clears the tombstone.
+    engine
+        .apply_delta(&make_delta(vec![tag_add(
+            &project_id,
+            "a",
+            "2999-06-01T00:00:00Z",
+        )]))
+        .unwrap();
+    assert_eq!(tags(&pool, &project_id), vec!["a", "b"]);
+    assert_eq!(tombstone(&pool, &project_id, "a"), None);
+}
+
+#[test]
+fn local_readd_wins_over_older_remote_removal() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, vec!["a"]);
+    let engine = DeltaSyncEngine::new(&pool, device_id(&pool));
+
+    let stats = engine
+        .apply_delta(&make_delta(vec![tag_remove(
+            &project_id,
+            "a",
+            Some("2000-01-01T00:00:00.000Z"),
+        )]))
+        .unwrap();
+    assert_eq!(stats.applied_ops, 0);
+    assert_eq!(stats.skipped_stale_upserts, 1);
+    assert_eq!(tags(&pool, &project_id), vec!["a"]);
+
+    // Removal of a tag that is already gone keeps the later tombstone.
+    set_tags(&pool, &project_id, vec![]);
+    let local = tombstone(&pool, &project_id, "a").unwrap();
+    engine
+        .apply_delta(&make_delta(vec![tag_remove(
+            &project_id,
+            "a",
+            Some("2000-01-01T00:00:00.000Z"),
+        )]))
+        .unwrap();
+    assert_eq!(tombstone(&pool, &project_id, "a"), Some(local));
+}
+
+#[test]
+fn legacy_delete_without_time_still_removes() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, vec!["a"]);
+    let engine = DeltaSyncEngine::new(&pool, device_id(&pool));
+
+    engine
+        .apply_delta(&make_delta(vec![tag_remove(&project_id, "a", None)]))
+        .unwrap();
+    assert!(tags(&pool, &project_id).is_empty());
+}
+
+#[test]
+fn project_update_only_records_changed_tags() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, vec!["keep", "drop"]);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_config (key, value) VALUES ('sync_enabled', '1')",
+            [],
+        )
+        .unwrap();
+    }
+
+    set_tags(&pool, &project_id, vec!["keep", "new", " new "]);
+    assert_eq!(tags(&pool, &project_id), vec!["keep", "new"]);
+
+    let conn = pool.0.lock().unwrap();
+    let mut stmt = conn
+        .prepare(
+            "SELECT record_id, operation, data_snapshot FROM sync_metadata
+             WHERE table_name = 'project_tags' ORDER BY id",
+        )
+        .unwrap();
+    let ops: Vec<(String, String, Option<String>)> = stmt
+        .query_map([], |row: &rusqlite::Row<'_>| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(ops.len(), 2);
+    assert_eq!(ops[0].0, format!("{}:drop", project_id));
+    assert_eq!(ops[0].1, "DELETE");
+    let data: serde_json::Value = serde_json::from_str(ops[0].2.as_deref().unwrap()).unwrap();
+    assert_eq!(data["tag"], "drop");
+    assert!(data["deleted_at"].as_str().unwrap().ends_with('Z'));
+    assert_eq!(ops[1].0, format!("{}:new", project_id));
+    assert_eq!(ops[1].1, "INSERT");
+}
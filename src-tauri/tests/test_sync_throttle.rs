@@ -0,0 +1,49 @@
+//! Bandwidth limiter (token bucket) tests
+
+use app_lib::sync::throttle::{kbps_to_bytes_per_sec, BandwidthLimiter};
+use std::time::{Duration, Instant};
+
+#[test]
+fn kbps_zero_means_unlimited() {
+    assert_eq!(kbps_to_bytes_per_sec(0), None);
+    assert_eq!(kbps_to_bytes_per_sec(8), Some(1000));
+    assert_eq!(kbps_to_bytes_per_sec(1), Some(125));
+}
+
+#[test]
+fn unlimited_never_waits() {
+    let limiter = BandwidthLimiter::unlimited();
+    assert_eq!(limiter.reserve(usize::MAX, Instant::now()), Duration::ZERO);
+}
+
+#[test]
+fn burst_up_to_one_second_is_free() {
+    let limiter = BandwidthLimiter::new(Some(1000));
+    let now = Instant::now();
+    assert_eq!(limiter.reserve(600, now), Duration::ZERO);
+    assert_eq!(limiter.reserve(400, now), Duration::ZERO);
+    assert_eq!(limiter.reserve(500, now), Duration::from_millis(500));
+}
+
+#[test]
+fn debt_is_paid_back_over_time() {
+    let limiter = BandwidthLimiter::new(Some(1000));
+    let now = Instant::now();
+    // 3s worth of data in one go: wait for the 2s not covered by the bucket.
+    assert_eq!(limiter.reserve(3000, now), Duration::from_secs(2));
+    // After those 2s the bucket is empty again; 1s later it holds 1000 bytes.
+    assert_eq!(
+        limiter.reserve(1000, now + Duration::from_secs(3)),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn idle_time_refills_only_to_capacity() {
+    let limiter = BandwidthLimiter::new(Some(1000));
+    let now = Instant::now();
+    limiter.reserve(1000, now);
+    let later = now + Duration::from_secs(60);
+    assert_eq!(limiter.reserve(1000, later), Duration::ZERO);
+    assert_eq!(limiter.reserve(1000, later), Duration::from_secs(1));
+}
@@ -0,0 +1,218 @@
+//! Delete tombstones: stale remote upserts must not resurrect deleted rows
+
+use app_lib::infra::db::init_test_db;
+use app_lib::sync::protocol::SYNC_FORMAT_VERSION;
+use app_lib::sync::{Delta, DeltaSyncEngine, Operation, OperationType, VectorClock};
+use serde_json::json;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn setup() -> (app_lib::infra::DbPool, String) {
+    let pool = init_test_db();
+    let device_id = {
+        let conn = pool.0.lock().unwrap();
+        conn.query_row(
+            "SELECT value FROM sync_config WHERE key = 'device_id'",
+            [],
+            |row: &rusqlite::Row<'_>| row.get::<_, String>(0),
+        )
+        .unwrap()
+    };
+    (pool, device_id)
+}
+
+fn make_delta(operations: Vec<Operation>) -> Delta {
+    let checksum = Delta::calculate_checksum(&operations);
+    Delta {
+        id: 1,
+        operations,
+        device_id: "remote-device".into(),
+        vector_clock: VectorClock::new("remote-device".into()),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        checksum,
+        format_version: SYNC_FORMAT_VERSION,
+    }
+}
+
+fn partner_upsert(name: &str, version: i64) -> Operation {
+    Operation {
+        table_name: "partners".into(),
+        record_id: "partner-1".into(),
+        op_type: OperationType::Update,
+        data: Some(json!({
+            "id": "partner-1", "name": name, "note": "", "is_active": 1,
+            "created_at": "2026-01-01T00:00:00Z", "updated_at": "2026-01-01T00:00:00Z"
+        })),
+        version,
+    }
+}
+
+fn partner_delete(version: i64) -> Operation {
+    Operation {
+        table_name: "partners".into(),
+        record_id: "partner-1".into(),
+        op_type: OperationType::Delete,
+        data: None,
+        version,
+    }
+}
+
+fn partner_name(pool: &app_lib::infra::DbPool) -> Option<String> {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT name FROM partners WHERE id = 'partner-1'",
+        [],
+        |row: &rusqlite::Row<'_>| row.get(0),
+    )
+    .ok()
+}
+
+fn tombstone_version(pool: &app_lib::infra::DbPool) -> Option<i64> {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(
+        "SELECT version FROM sync_tombstones WHERE table_name = 'partners' AND record_id = 'partner-1'",
+        [],
+        |row: &rusqlite::Row<'_>| row.get(0),
+    )
+    .ok()
+}
+
+fn seed_partner(pool: &app_lib::infra::DbPool, version: i64) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO partners (id, name, note, is_active, created_at, updated_at, _version)
+         VALUES ('partner-1', 'Corp', '', 1, datetime('now'), datetime('now'), ?1)",
+        [version],
+    )
+    .unwrap();
+}
+
+/// Insert `row-1` into `table` (FK checks off, so no parents are needed), delete it, and return
+/// the tombstone version after the delete and whether re-inserting cleared it.
+fn local_delete_tombstone(
+    pool: &app_lib::infra::DbPool,
+    table: &str,
+    insert_sql: &str,
+) -> (Option<i64>, bool) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+    let version = || -> Option<i64> {
+        conn.query_row(
+            "SELECT version FROM sync_tombstones WHERE table_name = ?1 AND record_id = 'row-1'",
+            [table],
+            |row: &rusqlite::Row<'_>| row.get(0),
+        )
+        .ok()
+    };
+    conn.execute(insert_sql, []).unwrap();
+    conn.execute(&format!("DELETE FROM {} WHERE id = 'row-1'", table), [])
+        .unwrap();
+    let deleted = version();
+    conn.execute(insert_sql, []).unwrap();
+    (deleted, version().is_none())
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn local_delete_blocks_stale_remote_upsert() {
+    let (pool, device_id) = setup();
+    seed_partner(&pool, 3);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute("DELETE FROM partners WHERE id = 'partner-1'", [])
+            .unwrap();
+    }
+    assert_eq!(tombstone_version(&pool), Some(3));
+
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    engine
+        .apply_delta(&make_delta(vec![partner_upsert("Stale", 2)]))
+        .unwrap();
+    engine
+        .apply_delta(&make_delta(vec![partner_upsert("Same", 3)]))
+        .unwrap();
+    assert_eq!(partner_name(&pool), None);
+}
+
+#[test]
+fn remote_delete_of_unknown_row_blocks_late_insert() {
+    let (pool, device_id) = setup();
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+
+    // The delete arrives before the insert it supersedes.
+    engine
+        .apply_delta(&make_delta(vec![partner_delete(2)]))
+        .unwrap();
+    assert_eq!(tombstone_version(&pool), Some(2));
+
+    engine
+        .apply_delta(&make_delta(vec![partner_upsert("Late", 1)]))
+        .unwrap();
+    assert_eq!(partner_name(&pool), None);
+}
+
+#[test]
+fn newer_remote_upsert_revives_and_clears_tombstone() {
+    let (pool, device_id) = setup();
+    seed_partner(&pool, 1);
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    engine
+        .apply_delta(&make_delta(vec![partner_delete(1)]))
+        .unwrap();
+    assert_eq!(partner_name(&pool), None);
+
+    engine
+        .apply_delta(&make_delta(vec![partner_upsert("Revived", 2)]))
+        .unwrap();
+    assert_eq!(partner_name(&pool), Some("Revived".to_string()));
+    assert_eq!(tombstone_version(&pool), None);
+}
+
+#[test]
+fn tombstone_keeps_highest_deleted_version() {
+    let (pool, device_id) = setup();
+    seed_partner(&pool, 4);
+    let engine = DeltaSyncEngine::new(&pool, device_id);
+    // Remote reports an older version than the row we actually deleted.
+    engine
+        .apply_delta(&make_delta(vec![partner_delete(2)]))
+        .unwrap();
+    assert_eq!(tombstone_version(&pool), Some(4));
+}
+
+#[test]
+fn local_reaction_delete_writes_tombstone() {
+    let (pool, _) = setup();
+    let result = local_delete_tombstone(
+        &pool,
+        "comment_reactions",
+        "INSERT INTO comment_reactions (id, comment_id, project_id, person_id, emoji, created_at, _version)
+         VALUES ('row-1', 'c-1', 'p-1', 'u-1', '👍', datetime('now'), 2)",
+    );
+    assert_eq!(result, (Some(2), true));
+}
+
+#[test]
+fn local_revision_delete_writes_tombstone() {
+    let (pool, _) = setup();
+    let result = local_delete_tombstone(
+        &pool,
+        "comment_revisions",
+        "INSERT INTO comment_revisions (id, comment_id, project_id, person_id, content, written_at, replaced_at, _version)
+         VALUES ('row-1', 'c-1', 'p-1', NULL, 'old', datetime('now'), datetime('now'), 2)",
+    );
+    assert_eq!(result, (Some(2), true));
+}
+
+#[test]
+fn local_checklist_item_delete_writes_tombstone() {
+    let (pool, _) = setup();
+    let result = local_delete_tombstone(
+        &pool,
+        "checklist_items",
+        "INSERT INTO checklist_items (id, project_id, text, done, sort_order, created_at, updated_at, _version)
+         VALUES ('row-1', 'p-1', 'Ship it', 0, 1, datetime('now'), datetime('now'), 2)",
+    );
+    assert_eq!(result, (Some(2), true));
+}
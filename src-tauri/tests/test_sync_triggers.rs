@@ -493,7 +493,10 @@ fn trigger_project_tags_insert_delete() {
     let (_, record_id, op, snapshot) = get_last_sync_metadata(&pool);
     assert_eq!(record_id, "proj-tag:urgent");
     assert_eq!(op, "DELETE");
-    assert!(snapshot.is_none());
+    // Tag removals carry deleted_at for the tag set merge (migration 0027).
+    let json: serde_json::Value = serde_json::from_str(&snapshot.unwrap()).unwrap();
+    assert_eq!(json["tag"], "urgent");
+    assert!(json["deleted_at"].is_string());
 }
 
 // ══════════════════════════════════════════════════════════
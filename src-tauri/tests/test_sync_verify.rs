@@ -0,0 +1,96 @@
+//! Sync integrity check (cmd_sync_verify) tests: row hashing and table comparison
+
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use app_lib::sync::verify::{
+    compare_databases, table_row_hashes, verify_tables, TableVerifyResult, MAX_SAMPLE_IDS,
+};
+
+// ──────────────────────── Helper ────────────────────────
+
+fn add_partner(pool: &DbPool, id: &str, name: &str) {
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO partners (id, name, created_at, updated_at) VALUES (?1, ?2, '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+        [id, name],
+    )
+    .unwrap();
+}
+
+fn compare(local: &DbPool, remote: &DbPool, ignored: &[&str]) -> Vec<TableVerifyResult> {
+    let ignored: Vec<String> = ignored.iter().map(|t| t.to_string()).collect();
+    let local = local.0.lock().unwrap();
+    let remote = remote.0.lock().unwrap();
+    compare_databases(&local, &remote, &ignored).unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn identical_databases_have_no_divergence() {
+    let (local, remote) = (init_test_db(), init_test_db());
+    for pool in [&local, &remote] {
+        add_partner(pool, "pt1", "Acme");
+    }
+
+    let tables = compare(&local, &remote, &[]);
+    assert_eq!(tables.len(), verify_tables().len());
+    assert!(tables.iter().all(|t| !t.is_diverged()));
+    let partners = tables.iter().find(|t| t.table == "partners").unwrap();
+    assert_eq!((partners.local_rows, partners.remote_rows), (1, 1));
+}
+
+#[test]
+fn reports_missing_and_differing_rows() {
+    let (local, remote) = (init_test_db(), init_test_db());
+    add_partner(&local, "pt1", "Acme");
+    add_partner(&remote, "pt1", "Acme Corp");
+    add_partner(&local, "pt2", "Local only");
+    add_partner(&remote, "pt3", "Remote only");
+
+    let tables = compare(&local, &remote, &[]);
+    let partners = tables.iter().find(|t| t.table == "partners").unwrap();
+    assert_eq!(partners.differing_ids, vec!["pt1".to_string()]);
+    assert_eq!(partners.only_local_ids, vec!["pt2".to_string()]);
+    assert_eq!(partners.only_remote_ids, vec!["pt3".to_string()]);
+    assert!(partners.is_diverged());
+    assert!(!partners.ignored);
+}
+
+#[test]
+fn ignored_tables_are_flagged() {
+    let (local, remote) = (init_test_db(), init_test_db());
+    add_partner(&local, "pt1", "Acme");
+
+    let tables = compare(&local, &remote, &["partners"]);
+    let partners = tables.iter().find(|t| t.table == "partners").unwrap();
+    assert!(partners.ignored);
+    assert_eq!(partners.only_local, 1);
+}
+
+#[test]
+fn sample_ids_are_capped() {
+    let (local, remote) = (init_test_db(), init_test_db());
+    for i in 0..(MAX_SAMPLE_IDS + 5) {
+        add_partner(&local, &format!("pt{:03}", i), "Acme");
+    }
+
+    let tables = compare(&local, &remote, &[]);
+    let partners = tables.iter().find(|t| t.table == "partners").unwrap();
+    assert_eq!(partners.only_local, MAX_SAMPLE_IDS + 5);
+    assert_eq!(partners.only_local_ids.len(), MAX_SAMPLE_IDS);
+}
+
+#[test]
+fn project_tags_are_keyed_by_project_and_tag() {
+    let pool = init_test_db();
+    let conn = pool.0.lock().unwrap();
+    conn.execute(
+        "INSERT INTO project_tags (project_id, tag, created_at) VALUES ('proj1', 'urgent', '2026-01-01T00:00:00Z')",
+        [],
+    )
+    .unwrap();
+
+    let hashes = table_row_hashes(&conn, "project_tags").unwrap();
+    assert_eq!(hashes.keys().collect::<Vec<_>>(), vec!["proj1:urgent"]);
+}
@@ -0,0 +1,292 @@
+//! Tag catalog tests: list with usage, colors, rename, merge and deleting unused tags
+
+use app_lib::app::{
+    audit_list, partner_create, person_create, project_create, project_get, tag_create, tag_delete,
+    tag_delete_unused, tag_list, tag_merge, tag_rename, tag_update, trash_delete, AuditListReq,
+    PartnerCreateReq, PersonCreateReq, ProjectCreateReq, TagCreateReq, TagMergeReq, TagRenameReq,
+    TagUpdateReq, TrashItemReq, TrashKind,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn project(pool: &DbPool, name: &str, tags: &[&str]) -> String {
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "DE".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn tags_of(pool: &DbPool, id: &str) -> Vec<String> {
+    let mut tags = project_get(pool, id).unwrap().tags;
+    tags.sort();
+    tags
+}
+
+fn usage(pool: &DbPool) -> Vec<(String, i64)> {
+    tag_list(pool)
+        .unwrap()
+        .into_iter()
+        .map(|t| (t.name, t.project_count))
+        .collect()
+}
+
+fn create(pool: &DbPool, name: &str, color: Option<&str>, description: &str) {
+    tag_create(
+        pool,
+        TagCreateReq {
+            name: name.to_string(),
+            color: color.map(str::to_string),
+            description: Some(description.to_string()),
+        },
+    )
+    .unwrap();
+}
+
+fn rename(pool: &DbPool, from: &str, to: &str) -> Result<usize, String> {
+    tag_rename(
+        pool,
+        TagRenameReq {
+            from: from.to_string(),
+            to: to.to_string(),
+            changed_by_person_id: None,
+        },
+    )
+    .map(|r| r.projects_updated)
+    .map_err(|e| e.code().to_string())
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn list_combines_catalog_and_tags_in_use() {
+    let pool = init_test_db();
+    project(&pool, "Alpha", &["urgent", "backend"]);
+    project(&pool, "Beta", &["urgent"]);
+    let trashed = project(&pool, "Gamma", &["legacy"]);
+    trash_delete(
+        &pool,
+        TrashItemReq {
+            kind: TrashKind::Project,
+            id: trashed,
+        },
+    )
+    .unwrap();
+    create(&pool, "Design", Some("#F0A"), "UI work");
+
+    assert_eq!(
+        usage(&pool),
+        vec![
+            ("backend".to_string(), 1),
+            ("Design".to_string(), 0),
+            ("urgent".to_string(), 2),
+        ]
+    );
+    let design = tag_list(&pool).unwrap().remove(1);
+    assert_eq!(design.color.as_deref(), Some("#ff00aa"));
+    assert_eq!(design.description, "UI work");
+
+    let err = tag_create(
+        &pool,
+        TagCreateReq {
+            name: "Design".to_string(),
+            color: None,
+            description: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "CONFLICT");
+}
+
+#[test]
+fn update_sets_color_of_tags_in_use() {
+    let pool = init_test_db();
+    project(&pool, "Alpha", &["urgent"]);
+
+    let tag = tag_update(
+        &pool,
+        TagUpdateReq {
+            name: "urgent".to_string(),
+            color: Some("#CC0000".to_string()),
+            description: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(tag.color.as_deref(), Some("#cc0000"));
+    assert_eq!(tag.project_count, 1);
+
+    let tag = tag_update(
+        &pool,
+        TagUpdateReq {
+            name: "urgent".to_string(),
+            color: Some(String::new()),
+            description: Some(" Needs attention ".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(tag.color, None);
+    assert_eq!(tag.description, "Needs attention");
+
+    for (name, color) in [("urgent", "red"), ("urgent", "#12345"), ("missing", "#123")] {
+        let err = tag_update(
+            &pool,
+            TagUpdateReq {
+                name: name.to_string(),
+                color: Some(color.to_string()),
+                description: None,
+            },
+        )
+        .unwrap_err();
+        let expected = if name == "missing" {
+            "NOT_FOUND"
+        } else {
+            "VALIDATION_ERROR"
+        };
+        assert_eq!(err.code(), expected, "{} {}", name, color);
+    }
+}
+
+#[test]
+fn rename_updates_every_project_and_the_catalog() {
+    let pool = init_test_db();
+    let alpha = project(&pool, "Alpha", &["urgnet", "backend"]);
+    let beta = project(&pool, "Beta", &["urgnet"]);
+    project(&pool, "Gamma", &["frontend"]);
+    create(&pool, "urgnet", Some("#cc0000"), "");
+
+    assert_eq!(rename(&pool, " urgnet ", "urgent"), Ok(2));
+    assert_eq!(tags_of(&pool, &alpha), vec!["backend", "urgent"]);
+    assert_eq!(tags_of(&pool, &beta), vec!["urgent"]);
+    let list = tag_list(&pool).unwrap();
+    let urgent = list.iter().find(|t| t.name == "urgent").unwrap();
+    assert_eq!(urgent.color.as_deref(), Some("#cc0000"));
+    assert_eq!(urgent.project_count, 2);
+    assert!(list.iter().all(|t| t.name != "urgnet"));
+
+    let audit = audit_list(
+        &pool,
+        AuditListReq {
+            entity_type: Some("project".to_string()),
+            entity_id: Some(beta),
+            limit: None,
+            offset: None,
+        },
+    )
+    .unwrap();
+    let entry = audit.items.iter().find(|e| e.field == "tags").unwrap();
+    assert_eq!(entry.old_value.as_deref(), Some("urgnet"));
+    assert_eq!(entry.new_value.as_deref(), Some("urgent"));
+
+    assert_eq!(
+        rename(&pool, "urgent", "frontend"),
+        Err("CONFLICT".to_string())
+    );
+    assert_eq!(
+        rename(&pool, "missing", "other"),
+        Err("NOT_FOUND".to_string())
+    );
+    assert_eq!(
+        rename(&pool, "urgent", "  "),
+        Err("VALIDATION_ERROR".to_string())
+    );
+    assert_eq!(
+        rename(&pool, "urgent", "urgent"),
+        Err("VALIDATION_ERROR".to_string())
+    );
+}
+
+#[test]
+fn merge_moves_projects_and_keeps_target_metadata() {
+    let pool = init_test_db();
+    let alpha = project(&pool, "Alpha", &["bug", "defect"]);
+    let beta = project(&pool, "Beta", &["defect"]);
+    create(&pool, "bug", None, "Something is broken");
+    create(&pool, "defect", Some("#aa0000"), "Old name");
+
+    let result = tag_merge(
+        &pool,
+        TagMergeReq {
+            source: "defect".to_string(),
+            target: "bug".to_string(),
+            changed_by_person_id: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(result.projects_updated, 2);
+    assert_eq!(result.tag.name, "bug");
+    assert_eq!(result.tag.color.as_deref(), Some("#aa0000"));
+    assert_eq!(result.tag.description, "Something is broken");
+    assert_eq!(result.tag.project_count, 2);
+    assert_eq!(tags_of(&pool, &alpha), vec!["bug"]);
+    assert_eq!(tags_of(&pool, &beta), vec!["bug"]);
+    assert_eq!(usage(&pool), vec![("bug".to_string(), 2)]);
+
+    for (source, target, code) in [
+        ("bug", "bug", "VALIDATION_ERROR"),
+        ("missing", "bug", "NOT_FOUND"),
+        ("bug", "missing", "NOT_FOUND"),
+    ] {
+        let err = tag_merge(
+            &pool,
+            TagMergeReq {
+                source: source.to_string(),
+                target: target.to_string(),
+                changed_by_person_id: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), code, "{} -> {}", source, target);
+    }
+}
+
+#[test]
+fn only_unused_tags_can_be_deleted() {
+    let pool = init_test_db();
+    project(&pool, "Alpha", &["urgent"]);
+    create(&pool, "urgent", Some("#cc0000"), "");
+    create(&pool, "stale", None, "");
+    create(&pool, "Obsolete", None, "");
+    create(&pool, "unused", None, "");
+
+    assert_eq!(tag_delete(&pool, "urgent").unwrap_err().code(), "CONFLICT");
+    tag_delete(&pool, "unused").unwrap();
+    assert_eq!(tag_delete(&pool, "unused").unwrap_err().code(), "NOT_FOUND");
+
+    assert_eq!(tag_delete_unused(&pool).unwrap(), vec!["Obsolete", "stale"]);
+    assert_eq!(usage(&pool), vec![("urgent".to_string(), 1)]);
+    assert!(tag_delete_unused(&pool).unwrap().is_empty());
+}
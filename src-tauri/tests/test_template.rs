@@ -0,0 +1,205 @@
+//! Project template save / instantiate tests
+
+use app_lib::app::{
+    assignment_add_member, comment_list_by_project, partner_create, person_create,
+    person_deactivate, project_create, project_create_from_template, project_save_as_template,
+    render_name_pattern, template_delete, template_list, AssignmentAddReq, PartnerCreateReq,
+    PersonCreateReq, ProjectCreateFromTemplateReq, ProjectCreateReq, ProjectSaveAsTemplateReq,
+    TemplateMilestoneDto,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn person(pool: &DbPool, name: &str) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+/// Project "Monthly report" owned by Owner, with Member as "analyst".
+fn seed_project(pool: &DbPool) -> (String, String) {
+    let owner = person(pool, "Owner");
+    let member = person(pool, "Member");
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Monthly report".to_string(),
+            description: Some("compliance".to_string()),
+            priority: Some(2),
+            country_code: "DE".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(vec!["report".to_string()]),
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap();
+    assignment_add_member(
+        pool,
+        AssignmentAddReq {
+            project_id: project.id.clone(),
+            person_id: member.clone(),
+            role: Some("analyst".to_string()),
+            start_at: None,
+        },
+    )
+    .unwrap();
+    (project.id, member)
+}
+
+fn save_template(pool: &DbPool, project_id: &str) -> String {
+    project_save_as_template(
+        pool,
+        ProjectSaveAsTemplateReq {
+            project_id: project_id.to_string(),
+            template_name: "Monthly".to_string(),
+            name_pattern: Some("Report {month} #{n}".to_string()),
+            milestones: Some(vec![
+                TemplateMilestoneDto {
+                    title: "Submit".to_string(),
+                    offset_days: 20,
+                },
+                TemplateMilestoneDto {
+                    title: "Draft".to_string(),
+                    offset_days: 10,
+                },
+            ]),
+        },
+    )
+    .unwrap()
+    .id
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn save_as_template_captures_defaults_and_members() {
+    let pool = init_test_db();
+    let (project_id, member) = seed_project(&pool);
+    save_template(&pool, &project_id);
+
+    let templates = template_list(&pool).unwrap();
+    assert_eq!(templates.len(), 1);
+    let template = &templates[0];
+    assert_eq!(template.priority, 2);
+    assert_eq!(template.country_code, "DE");
+    assert_eq!(template.tags, vec!["report".to_string()]);
+    assert_eq!(template.members.len(), 1);
+    assert_eq!(template.members[0].person_id, member);
+    assert_eq!(template.members[0].role, "analyst");
+    assert_eq!(template.milestones.len(), 2);
+
+    let err = project_save_as_template(
+        &pool,
+        ProjectSaveAsTemplateReq {
+            project_id,
+            template_name: "Monthly".to_string(),
+            name_pattern: None,
+            milestones: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "CONFLICT");
+}
+
+#[test]
+fn create_from_template_applies_pattern_members_and_milestones() {
+    let pool = init_test_db();
+    let (project_id, member) = seed_project(&pool);
+    let template_id = save_template(&pool, &project_id);
+
+    let project = project_create_from_template(
+        &pool,
+        ProjectCreateFromTemplateReq {
+            template_id: template_id.clone(),
+            start_date: Some("2026-03-01".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(project.name.starts_with("Report ") && project.name.ends_with(" #1"));
+    assert_eq!(project.priority, 2);
+    assert_eq!(project.tags, vec!["report".to_string()]);
+    assert!(project
+        .assignments
+        .iter()
+        .any(|a| a.person_id == member && a.role == "analyst"));
+
+    let comments = comment_list_by_project(&pool, project.id.clone()).unwrap();
+    assert_eq!(comments.len(), 1);
+    assert!(comments[0].is_pinned);
+    assert_eq!(
+        comments[0].content,
+        "Milestones:\n- Draft (2026-03-11)\n- Submit (2026-03-21)"
+    );
+
+    // Second instance: counter advances; inactive members are skipped.
+    person_deactivate(&pool, &member).unwrap();
+    let second = project_create_from_template(
+        &pool,
+        ProjectCreateFromTemplateReq {
+            template_id,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(second.name.ends_with(" #2"));
+    assert!(!second.assignments.iter().any(|a| a.person_id == member));
+}
+
+#[test]
+fn failed_instantiation_does_not_advance_counter() {
+    let pool = init_test_db();
+    let (project_id, _) = seed_project(&pool);
+    let template_id = save_template(&pool, &project_id);
+
+    let err = project_create_from_template(
+        &pool,
+        ProjectCreateFromTemplateReq {
+            template_id: template_id.clone(),
+            name: Some("Monthly report".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "CONFLICT");
+    assert_eq!(template_list(&pool).unwrap()[0].instance_count, 0);
+
+    template_delete(&pool, &template_id).unwrap();
+    assert!(template_list(&pool).unwrap().is_empty());
+    assert_eq!(
+        template_delete(&pool, &template_id).unwrap_err().code(),
+        "NOT_FOUND"
+    );
+}
+
+#[test]
+fn name_pattern_placeholders() {
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 2, 5).unwrap();
+    assert_eq!(
+        render_name_pattern("{year}/{month}/{date} #{n}", date, 3),
+        "2026/2026-02/2026-02-05 #3"
+    );
+}
@@ -0,0 +1,321 @@
+//! Trash bin tests: soft delete, restore rules, purge and sync propagation
+
+use app_lib::app::{
+    comment_create, partner_create, partner_list, person_create, person_get, project_create,
+    project_get, project_list, trash_delete, trash_list, trash_purge, trash_restore,
+    CommentCreateReq, PartnerCreateReq, PersonCreateReq, ProjectCreateReq, ProjectListReq,
+    TrashItemReq, TrashKind, TrashPurgeReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use chrono::{Duration, Utc};
+
+// ──────────────────────── Helper ────────────────────────
+
+struct Seed {
+    person_id: String,
+    partner_id: String,
+    project_id: String,
+}
+
+fn seed(pool: &DbPool) -> Seed {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let project = create_project(pool, "Alpha", &partner.id, &person.id, None);
+    Seed {
+        person_id: person.id,
+        partner_id: partner.id,
+        project_id: project,
+    }
+}
+
+fn create_project(
+    pool: &DbPool,
+    name: &str,
+    partner_id: &str,
+    owner_id: &str,
+    parent: Option<&str>,
+) -> String {
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: None,
+            country_code: "CN".to_string(),
+            partner_id: partner_id.to_string(),
+            owner_person_id: owner_id.to_string(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: Some(vec!["t".to_string()]),
+            created_by_person_id: None,
+            parent_project_id: parent.map(str::to_string),
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn item(kind: TrashKind, id: &str) -> TrashItemReq {
+    TrashItemReq {
+        kind,
+        id: id.to_string(),
+    }
+}
+
+fn count(pool: &DbPool, sql: &str, id: &str) -> i64 {
+    let conn = pool.0.lock().unwrap();
+    conn.query_row(sql, [id], |r| r.get(0)).unwrap()
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn deleted_project_is_hidden_and_restorable() {
+    let pool = init_test_db();
+    let s = seed(&pool);
+
+    let trashed = trash_delete(&pool, item(TrashKind::Project, &s.project_id)).unwrap();
+    assert_eq!(trashed.name, "Alpha");
+    assert_eq!(
+        project_list(&pool, ProjectListReq::default())
+            .unwrap()
+            .total,
+        0
+    );
+    assert_eq!(
+        project_get(&pool, &s.project_id).unwrap_err().code(),
+        "NOT_FOUND"
+    );
+    let trash = trash_list(&pool).unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].kind, TrashKind::Project);
+
+    // Deleting twice is a not-found; the name stays reserved while in the trash.
+    assert_eq!(
+        trash_delete(&pool, item(TrashKind::Project, &s.project_id))
+            .unwrap_err()
+            .code(),
+        "NOT_FOUND"
+    );
+
+    trash_restore(&pool, item(TrashKind::Project, &s.project_id)).unwrap();
+    assert_eq!(project_get(&pool, &s.project_id).unwrap().name, "Alpha");
+    assert!(trash_list(&pool).unwrap().is_empty());
+    assert_eq!(
+        trash_restore(&pool, item(TrashKind::Project, &s.project_id))
+            .unwrap_err()
+            .code(),
+        "NOT_FOUND"
+    );
+}
+
+#[test]
+fn references_block_delete_and_restore_order() {
+    let pool = init_test_db();
+    let s = seed(&pool);
+    create_project(
+        &pool,
+        "Alpha child",
+        &s.partner_id,
+        &s.person_id,
+        Some(&s.project_id),
+    );
+
+    let err = trash_delete(&pool, item(TrashKind::Partner, &s.partner_id)).unwrap_err();
+    assert!(err.to_string().contains("TRASH_IN_USE"));
+    let err = trash_delete(&pool, item(TrashKind::Person, &s.person_id)).unwrap_err();
+    assert!(err.to_string().contains("TRASH_IN_USE"));
+    let err = trash_delete(&pool, item(TrashKind::Project, &s.project_id)).unwrap_err();
+    assert!(err.to_string().contains("sub-projects"));
+
+    let child = project_list(
+        &pool,
+        ProjectListReq {
+            parent_project_id: Some(s.project_id.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .items
+    .remove(0)
+    .id;
+    trash_delete(&pool, item(TrashKind::Project, &child)).unwrap();
+    trash_delete(&pool, item(TrashKind::Project, &s.project_id)).unwrap();
+    trash_delete(&pool, item(TrashKind::Partner, &s.partner_id)).unwrap();
+    trash_delete(&pool, item(TrashKind::Person, &s.person_id)).unwrap();
+    assert!(partner_list(&pool, false).unwrap().is_empty());
+    assert_eq!(
+        person_get(&pool, &s.person_id).unwrap_err().code(),
+        "NOT_FOUND"
+    );
+
+    let err = trash_restore(&pool, item(TrashKind::Project, &child)).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("TRASH_RESTORE_BLOCKED"));
+    assert!(message.contains("partner Acme"));
+    assert!(message.contains("person Owner"));
+    assert!(message.contains("parent project Alpha"));
+
+    trash_restore(&pool, item(TrashKind::Partner, &s.partner_id)).unwrap();
+    trash_restore(&pool, item(TrashKind::Person, &s.person_id)).unwrap();
+    trash_restore(&pool, item(TrashKind::Project, &s.project_id)).unwrap();
+    trash_restore(&pool, item(TrashKind::Project, &child)).unwrap();
+    assert_eq!(project_get(&pool, &child).unwrap().name, "Alpha child");
+}
+
+#[test]
+fn purge_respects_age_and_removes_project_children() {
+    let pool = init_test_db();
+    let s = seed(&pool);
+    comment_create(
+        &pool,
+        CommentCreateReq {
+            project_id: s.project_id.clone(),
+            person_id: Some(s.person_id.clone()),
+            content: "note".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+    trash_delete(&pool, item(TrashKind::Project, &s.project_id)).unwrap();
+    trash_delete(&pool, item(TrashKind::Partner, &s.partner_id)).unwrap();
+
+    let result = trash_purge(
+        &pool,
+        TrashPurgeReq {
+            older_than_days: 30,
+        },
+        Utc::now(),
+    )
+    .unwrap();
+    assert!(result.purged.is_empty());
+    assert_eq!(trash_list(&pool).unwrap().len(), 2);
+
+    let later = Utc::now() + Duration::days(31);
+    let result = trash_purge(
+        &pool,
+        TrashPurgeReq {
+            older_than_days: 30,
+        },
+        later,
+    )
+    .unwrap();
+    let purged: Vec<_> = result.purged.iter().map(|i| i.kind).collect();
+    assert_eq!(purged, vec![TrashKind::Project, TrashKind::Partner]);
+    assert!(result.skipped.is_empty());
+    assert!(trash_list(&pool).unwrap().is_empty());
+
+    for sql in [
+        "SELECT COUNT(*) FROM projects WHERE id = ?1",
+        "SELECT COUNT(*) FROM project_comments WHERE project_id = ?1",
+        "SELECT COUNT(*) FROM project_tags WHERE project_id = ?1",
+        "SELECT COUNT(*) FROM status_history WHERE project_id = ?1",
+    ] {
+        assert_eq!(count(&pool, sql, &s.project_id), 0, "{}", sql);
+    }
+    assert_eq!(
+        count(
+            &pool,
+            "SELECT COUNT(*) FROM sync_tombstones WHERE table_name = 'projects' AND record_id = ?1",
+            &s.project_id
+        ),
+        1
+    );
+
+    let err = trash_purge(
+        &pool,
+        TrashPurgeReq {
+            older_than_days: -1,
+        },
+        Utc::now(),
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
+
+#[test]
+fn purge_skips_items_still_referenced() {
+    let pool = init_test_db();
+    let s = seed(&pool);
+    let child = create_project(
+        &pool,
+        "Alpha child",
+        &s.partner_id,
+        &s.person_id,
+        Some(&s.project_id),
+    );
+    trash_delete(&pool, item(TrashKind::Project, &child)).unwrap();
+    trash_delete(&pool, item(TrashKind::Project, &s.project_id)).unwrap();
+    trash_delete(&pool, item(TrashKind::Partner, &s.partner_id)).unwrap();
+
+    // The child was trashed only recently: it stays, and keeps parent and partner referenced.
+    let now = Utc::now() + Duration::days(2);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE projects SET deleted_at = ?1 WHERE id = ?2",
+            rusqlite::params![now.to_rfc3339(), child],
+        )
+        .unwrap();
+    }
+    let result = trash_purge(&pool, TrashPurgeReq { older_than_days: 1 }, now).unwrap();
+    assert!(result.purged.is_empty());
+    let reasons: Vec<_> = result.skipped.iter().map(|i| i.reason.as_str()).collect();
+    assert_eq!(
+        reasons,
+        vec!["sub-projects still in the trash", "still has projects"]
+    );
+
+    // Emptying the trash purges the child first, which unblocks its parent.
+    let result = trash_purge(&pool, TrashPurgeReq { older_than_days: 0 }, now).unwrap();
+    assert_eq!(result.purged.len(), 3);
+    assert!(result.skipped.is_empty());
+    assert_eq!(result.purged[0].id, child);
+}
+
+#[test]
+fn soft_delete_syncs_as_update_with_deleted_at() {
+    let pool = init_test_db();
+    let s = seed(&pool);
+    {
+        let conn = pool.0.lock().unwrap();
+        conn.execute(
+            "UPDATE sync_config SET value = '1' WHERE key = 'sync_enabled'",
+            [],
+        )
+        .unwrap();
+    }
+    trash_delete(&pool, item(TrashKind::Project, &s.project_id)).unwrap();
+
+    let conn = pool.0.lock().unwrap();
+    let (operation, snapshot): (String, String) = conn
+        .query_row(
+            "SELECT operation, data_snapshot FROM sync_metadata
+             WHERE table_name = 'projects' AND record_id = ?1 ORDER BY id DESC LIMIT 1",
+            [&s.project_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(operation, "UPDATE");
+    let data: serde_json::Value = serde_json::from_str(&snapshot).unwrap();
+    assert!(data["deleted_at"].is_string());
+    assert_eq!(data["_version"], 2);
+}
@@ -0,0 +1,26 @@
+//! Activity-aware apply deferral tests
+
+use app_lib::sync::activity::{is_user_active, record_user_activity, wait_for_user_idle};
+use std::time::{Duration, Instant};
+
+// Both scenarios share one process-wide tracker, so keep them in a single test.
+#[tokio::test]
+async fn apply_waits_for_idle_but_respects_max_wait() {
+    // No heartbeat yet → no wait.
+    let waited = wait_for_user_idle(Duration::from_secs(3), Duration::from_secs(5)).await;
+    assert_eq!(waited, Duration::ZERO);
+
+    record_user_activity();
+    assert!(is_user_active(Duration::from_secs(3)));
+
+    // Short idle window: returns once the user has been quiet long enough.
+    let started = Instant::now();
+    let waited = wait_for_user_idle(Duration::from_millis(300), Duration::from_secs(5)).await;
+    assert!(waited > Duration::ZERO);
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    // Long idle window: bounded by max_wait.
+    record_user_activity();
+    let waited = wait_for_user_idle(Duration::from_secs(60), Duration::from_millis(500)).await;
+    assert_eq!(waited, Duration::from_millis(500));
+}
@@ -0,0 +1,296 @@
+//! Outbound webhook tests (queueing, signing, retry / backoff, delivery log)
+
+use app_lib::app::{
+    comment_create, partner_create, person_create, project_change_status, project_create,
+    retry_delay_secs, webhook_create, webhook_delete, webhook_delivery_list,
+    webhook_dispatch_due_with, webhook_list, webhook_update, CommentCreateReq, PartnerCreateReq,
+    PersonCreateReq, ProjectChangeStatusReq, ProjectCreateReq, WebhookCreateReq,
+    WebhookDeliveryListReq, WebhookUpdateReq, WEBHOOK_EVENT_COMMENT_CREATED,
+    WEBHOOK_EVENT_PROJECT_STATUS_CHANGED, WEBHOOK_MAX_ATTEMPTS,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::webhook::{signature, WebhookRequest, SIGNATURE_HEADER};
+use app_lib::infra::DbPool;
+use chrono::{Duration, Utc};
+use std::cell::RefCell;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn project(pool: &DbPool) -> String {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Owner".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: "P".to_string(),
+            description: None,
+            priority: None,
+            country_code: "US".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn hook(pool: &DbPool, name: &str, secret: Option<&str>, events: Option<Vec<&str>>) -> String {
+    webhook_create(
+        pool,
+        WebhookCreateReq {
+            name: name.to_string(),
+            url: format!("https://hooks.example.com/{}", name),
+            secret: secret.map(str::to_string),
+            events: events.map(|e| e.into_iter().map(str::to_string).collect()),
+            enabled: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn change_status(pool: &DbPool, project_id: &str, to: &str) {
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: project_id.to_string(),
+            to_status: to.to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .unwrap();
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn events_are_filtered_signed_and_delivered() {
+    let pool = init_test_db();
+    let p = project(&pool);
+    let status_hook = hook(
+        &pool,
+        "status",
+        Some("s3cret"),
+        Some(vec![WEBHOOK_EVENT_PROJECT_STATUS_CHANGED]),
+    );
+    let all_hook = hook(&pool, "all", None, None);
+    let listed = webhook_list(&pool).unwrap();
+    assert_eq!(listed.len(), 2);
+    assert!(listed.iter().any(|w| w.id == status_hook && w.has_secret));
+
+    change_status(&pool, &p, "PLANNED");
+    comment_create(
+        &pool,
+        CommentCreateReq {
+            project_id: p.clone(),
+            person_id: None,
+            content: "Kick-off done".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+
+    let sent: RefCell<Vec<WebhookRequest>> = RefCell::new(Vec::new());
+    let result = webhook_dispatch_due_with(&pool, Utc::now(), &|req| {
+        sent.borrow_mut().push(req.clone());
+        Ok(204)
+    })
+    .unwrap();
+    assert_eq!(result.delivered, 3);
+    let sent = sent.into_inner();
+    assert_eq!(sent.len(), 3);
+
+    let to_status_hook: Vec<_> = sent.iter().filter(|r| r.url.ends_with("/status")).collect();
+    assert_eq!(to_status_hook.len(), 1);
+    let req = to_status_hook[0];
+    let body: serde_json::Value = serde_json::from_str(&req.body).unwrap();
+    assert_eq!(body["event"], WEBHOOK_EVENT_PROJECT_STATUS_CHANGED);
+    assert_eq!(body["data"]["projectId"], p.as_str());
+    assert_eq!(body["data"]["toStatus"], "PLANNED");
+    let sig = req
+        .headers
+        .iter()
+        .find(|(k, _)| k == SIGNATURE_HEADER)
+        .map(|(_, v)| v.clone())
+        .unwrap();
+    assert_eq!(sig, signature("s3cret", &req.body));
+
+    // No secret: no signature header.
+    let comment_req = sent
+        .iter()
+        .find(|r| r.body.contains(WEBHOOK_EVENT_COMMENT_CREATED))
+        .unwrap();
+    assert!(comment_req.url.ends_with("/all"));
+    assert!(!comment_req
+        .headers
+        .iter()
+        .any(|(k, _)| k == SIGNATURE_HEADER));
+
+    let log = webhook_delivery_list(
+        &pool,
+        WebhookDeliveryListReq {
+            webhook_id: Some(all_hook.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(log.len(), 2);
+    assert!(log
+        .iter()
+        .all(|d| d.status == "delivered" && d.attempts == 1));
+    assert_eq!(log[0].last_status_code, Some(204));
+
+    // Nothing left to send.
+    let again =
+        webhook_dispatch_due_with(&pool, Utc::now(), &|_| panic!("nothing is due")).unwrap();
+    assert_eq!(again.delivered, 0);
+}
+
+#[test]
+fn failed_deliveries_back_off_and_give_up() {
+    let pool = init_test_db();
+    let p = project(&pool);
+    hook(&pool, "flaky", None, None);
+    change_status(&pool, &p, "PLANNED");
+
+    let mut now = Utc::now();
+    let first = webhook_dispatch_due_with(&pool, now, &|_| Ok(500)).unwrap();
+    assert_eq!(first.retrying, 1);
+    let pending = &webhook_delivery_list(&pool, WebhookDeliveryListReq::default()).unwrap()[0];
+    assert_eq!(pending.status, "pending");
+    assert_eq!(pending.last_status_code, Some(500));
+    assert_eq!(pending.last_error.as_deref(), Some("HTTP 500"));
+
+    // Not due before the backoff elapses.
+    let early = webhook_dispatch_due_with(&pool, now + Duration::seconds(10), &|_| {
+        panic!("delivery is not due yet")
+    })
+    .unwrap();
+    assert_eq!(early.retrying, 0);
+
+    for attempt in 1..WEBHOOK_MAX_ATTEMPTS {
+        now += Duration::seconds(retry_delay_secs(attempt));
+        webhook_dispatch_due_with(&pool, now, &|_| Err("connection refused".to_string())).unwrap();
+    }
+    let failed = &webhook_delivery_list(&pool, WebhookDeliveryListReq::default()).unwrap()[0];
+    assert_eq!(failed.status, "failed");
+    assert_eq!(failed.attempts, WEBHOOK_MAX_ATTEMPTS);
+    assert_eq!(failed.last_error.as_deref(), Some("connection refused"));
+    assert!(failed.next_attempt_at.is_none());
+
+    assert_eq!(retry_delay_secs(1), 30);
+    assert_eq!(retry_delay_secs(2), 60);
+    assert_eq!(retry_delay_secs(20), 3600);
+}
+
+#[test]
+fn validation_disable_and_delete() {
+    let pool = init_test_db();
+    let p = project(&pool);
+
+    let bad_url = webhook_create(
+        &pool,
+        WebhookCreateReq {
+            name: "x".to_string(),
+            url: "ftp://example.com".to_string(),
+            secret: None,
+            events: None,
+            enabled: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(bad_url.code(), "VALIDATION_ERROR");
+    let bad_event = webhook_create(
+        &pool,
+        WebhookCreateReq {
+            name: "x".to_string(),
+            url: "https://example.com".to_string(),
+            secret: None,
+            events: Some(vec!["project.deleted".to_string()]),
+            enabled: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(bad_event.code(), "VALIDATION_ERROR");
+
+    let id = hook(&pool, "h", Some("k"), None);
+    let updated = webhook_update(
+        &pool,
+        WebhookUpdateReq {
+            id: id.clone(),
+            secret: Some(String::new()),
+            enabled: Some(false),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(!updated.has_secret);
+    assert!(!updated.enabled);
+
+    // Disabled webhooks do not receive events.
+    change_status(&pool, &p, "PLANNED");
+    assert!(
+        webhook_delivery_list(&pool, WebhookDeliveryListReq::default())
+            .unwrap()
+            .is_empty()
+    );
+
+    webhook_update(
+        &pool,
+        WebhookUpdateReq {
+            id: id.clone(),
+            enabled: Some(true),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    change_status(&pool, &p, "IN_PROGRESS");
+    assert_eq!(
+        webhook_delivery_list(&pool, WebhookDeliveryListReq::default())
+            .unwrap()
+            .len(),
+        1
+    );
+
+    webhook_delete(&pool, &id).unwrap();
+    assert!(
+        webhook_delivery_list(&pool, WebhookDeliveryListReq::default())
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(webhook_delete(&pool, &id).unwrap_err().code(), "NOT_FOUND");
+    let bad_status = webhook_delivery_list(
+        &pool,
+        WebhookDeliveryListReq {
+            status: Some("sent".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(bad_status.code(), "VALIDATION_ERROR");
+}
@@ -59,6 +59,7 @@ fn seed_minimal(pool: &app_lib::infra::db::DbPool) -> (String, String, String) {
             due_date: None,
             tags: Some(vec!["wipe-tag".to_string()]),
             created_by_person_id: None,
+            parent_project_id: None,
         },
     )
     .unwrap();
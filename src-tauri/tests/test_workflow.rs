@@ -0,0 +1,458 @@
+//! Configurable status workflow integration tests
+
+use app_lib::app::{
+    partner_create, person_create, project_change_status, project_create, project_get,
+    workflow_activate, workflow_create, workflow_delete, workflow_list, workflow_update,
+    workflow_wip_status, PartnerCreateReq, PersonCreateReq, ProjectChangeStatusReq,
+    ProjectCreateReq, ProjectDetailDto, WorkflowCreateReq, WorkflowUpdateReq,
+};
+use app_lib::domain::{Workflow, WorkflowStatus, WorkflowTransition};
+use app_lib::error::AppError;
+use app_lib::infra::db::init_test_db;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn seed_project(pool: &app_lib::infra::DbPool, name: &str) -> String {
+    let person = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: format!("Owner of {}", name),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner of {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: Some(3),
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: person.id,
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+fn change(
+    pool: &app_lib::infra::DbPool,
+    project_id: &str,
+    to: &str,
+    note: Option<&str>,
+) -> Result<String, String> {
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: project_id.to_string(),
+            to_status: to.to_string(),
+            note: note.map(str::to_string),
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: None,
+        },
+    )
+    .map(|p| p.current_status)
+    .map_err(|e| e.code().to_string())
+}
+
+fn statuses(keys: &[&str]) -> Vec<WorkflowStatus> {
+    keys.iter()
+        .map(|key| WorkflowStatus {
+            key: key.to_string(),
+            label: String::new(),
+            wip_limit: None,
+            owner_wip_limit: None,
+        })
+        .collect()
+}
+
+fn transition(from: &str, to: &str, note_required: bool) -> WorkflowTransition {
+    WorkflowTransition {
+        from: from.to_string(),
+        to: to.to_string(),
+        note_required,
+    }
+}
+
+/// Move a fresh project to PLANNED, ready for IN_PROGRESS.
+fn planned_project(pool: &app_lib::infra::DbPool, name: &str, owner_person_id: &str) -> String {
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: format!("Partner of {}", name),
+            note: None,
+        },
+    )
+    .unwrap();
+    let id = project_create(
+        pool,
+        ProjectCreateReq {
+            name: name.to_string(),
+            description: None,
+            priority: Some(3),
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner_person_id.to_string(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id;
+    change(pool, &id, "PLANNED", None).unwrap();
+    id
+}
+
+fn person(pool: &app_lib::infra::DbPool, name: &str) -> String {
+    person_create(
+        pool,
+        PersonCreateReq {
+            display_name: name.to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap()
+    .id
+}
+
+/// Set WIP limits on IN_PROGRESS of the (active) default workflow.
+fn limit_in_progress(
+    pool: &app_lib::infra::DbPool,
+    wip_limit: Option<u32>,
+    owner_wip_limit: Option<u32>,
+) {
+    let default = workflow_list(pool).unwrap().remove(0);
+    let statuses = default
+        .statuses
+        .into_iter()
+        .map(|s| match s.key.as_str() {
+            "IN_PROGRESS" => WorkflowStatus {
+                wip_limit,
+                owner_wip_limit,
+                ..s
+            },
+            _ => s,
+        })
+        .collect();
+    workflow_update(
+        pool,
+        WorkflowUpdateReq {
+            id: default.id,
+            name: None,
+            statuses: Some(statuses),
+            transitions: None,
+        },
+    )
+    .unwrap();
+}
+
+fn start(
+    pool: &app_lib::infra::DbPool,
+    project_id: &str,
+    force: bool,
+) -> Result<ProjectDetailDto, AppError> {
+    project_change_status(
+        pool,
+        ProjectChangeStatusReq {
+            project_id: project_id.to_string(),
+            to_status: "IN_PROGRESS".to_string(),
+            note: None,
+            changed_by_person_id: None,
+            if_match_updated_at: None,
+            force: Some(force),
+        },
+    )
+}
+
+/// BACKLOG -> REVIEW -> DONE -> ARCHIVED, with REVIEW -> BACKLOG needing a note.
+fn review_workflow(pool: &app_lib::infra::DbPool) -> String {
+    workflow_create(
+        pool,
+        WorkflowCreateReq {
+            name: "Review".to_string(),
+            statuses: statuses(&["BACKLOG", "REVIEW", "DONE", "ARCHIVED"]),
+            transitions: vec![
+                transition("BACKLOG", "REVIEW", false),
+                transition("REVIEW", "DONE", false),
+                transition("REVIEW", "BACKLOG", true),
+                transition("DONE", "ARCHIVED", false),
+            ],
+        },
+    )
+    .unwrap()
+    .id
+}
+
+// ══════════════════════════════════════════════════════════
+//  Seeded default
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn default_workflow_is_the_builtin_machine() {
+    let pool = init_test_db();
+    let workflows = workflow_list(&pool).unwrap();
+    assert_eq!(workflows.len(), 1);
+    let default = &workflows[0];
+    assert!(default.is_active);
+    assert_eq!(default.name, "Default");
+
+    let builtin = Workflow::builtin();
+    assert_eq!(default.statuses, builtin.statuses);
+    assert_eq!(default.transitions, builtin.transitions);
+
+    let project_id = seed_project(&pool, "Default flow");
+    assert_eq!(
+        change(&pool, &project_id, "DONE", None).unwrap_err(),
+        "INVALID_STATUS_TRANSITION"
+    );
+    assert_eq!(
+        change(&pool, &project_id, "ARCHIVED", None).unwrap_err(),
+        "NOTE_REQUIRED"
+    );
+    assert_eq!(
+        change(&pool, &project_id, "PLANNED", None).unwrap(),
+        "PLANNED"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+//  project_change_status against a custom workflow
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn custom_workflow_drives_status_changes() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, "Custom flow");
+    let id = review_workflow(&pool);
+    workflow_activate(&pool, &id).unwrap();
+
+    // Built-in statuses left out of the workflow are unknown now.
+    assert_eq!(
+        change(&pool, &project_id, "PLANNED", None).unwrap_err(),
+        "INVALID_STATUS_TRANSITION"
+    );
+    assert_eq!(
+        change(&pool, &project_id, "DONE", None).unwrap_err(),
+        "INVALID_STATUS_TRANSITION"
+    );
+    assert_eq!(
+        change(&pool, &project_id, "REVIEW", None).unwrap(),
+        "REVIEW"
+    );
+    assert_eq!(
+        change(&pool, &project_id, "BACKLOG", Some("  ")).unwrap_err(),
+        "NOTE_REQUIRED"
+    );
+    assert_eq!(
+        change(&pool, &project_id, "BACKLOG", Some("Needs rework")).unwrap(),
+        "BACKLOG"
+    );
+    change(&pool, &project_id, "REVIEW", None).unwrap();
+    change(&pool, &project_id, "DONE", None).unwrap();
+    assert_eq!(
+        change(&pool, &project_id, "ARCHIVED", None).unwrap(),
+        "ARCHIVED"
+    );
+    assert!(project_get(&pool, &project_id)
+        .unwrap()
+        .archived_at
+        .is_some());
+}
+
+#[test]
+fn status_outside_the_workflow_moves_anywhere_with_a_note() {
+    let pool = init_test_db();
+    let project_id = seed_project(&pool, "Stranded");
+    change(&pool, &project_id, "PLANNED", None).unwrap();
+    let id = review_workflow(&pool);
+    workflow_activate(&pool, &id).unwrap();
+
+    assert_eq!(
+        change(&pool, &project_id, "DONE", None).unwrap_err(),
+        "NOTE_REQUIRED"
+    );
+    assert_eq!(
+        change(&pool, &project_id, "DONE", Some("Review dropped")).unwrap(),
+        "DONE"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+//  workflow_create / workflow_update
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn create_and_update_validate_the_definition() {
+    let pool = init_test_db();
+    let err = workflow_create(
+        &pool,
+        WorkflowCreateReq {
+            name: "No archive".to_string(),
+            statuses: statuses(&["BACKLOG", "DONE"]),
+            transitions: vec![],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+
+    let err = workflow_create(
+        &pool,
+        WorkflowCreateReq {
+            name: " default ".to_string(),
+            statuses: statuses(&["BACKLOG", "DONE", "ARCHIVED"]),
+            transitions: vec![],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "CONFLICT");
+
+    let id = review_workflow(&pool);
+    let err = workflow_update(
+        &pool,
+        WorkflowUpdateReq {
+            id: id.clone(),
+            name: None,
+            statuses: Some(statuses(&["BACKLOG", "DONE", "ARCHIVED"])),
+            transitions: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR"); // transitions still use REVIEW
+
+    let updated = workflow_update(
+        &pool,
+        WorkflowUpdateReq {
+            id,
+            name: Some("Review v2".to_string()),
+            statuses: None,
+            transitions: Some(vec![transition("BACKLOG", "REVIEW", true)]),
+        },
+    )
+    .unwrap();
+    assert_eq!(updated.name, "Review v2");
+    assert_eq!(updated.statuses[1].label, "REVIEW");
+    assert_eq!(updated.transitions.len(), 1);
+}
+
+// ══════════════════════════════════════════════════════════
+//  workflow_activate / workflow_delete
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn activate_switches_and_active_cannot_be_deleted() {
+    let pool = init_test_db();
+    let default_id = workflow_list(&pool).unwrap()[0].id.clone();
+    let id = review_workflow(&pool);
+
+    assert!(workflow_activate(&pool, &id).unwrap().is_active);
+    let workflows = workflow_list(&pool).unwrap();
+    assert_eq!(workflows[0].id, id);
+    assert_eq!(workflows.iter().filter(|w| w.is_active).count(), 1);
+
+    let err = workflow_delete(&pool, &id).unwrap_err();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    workflow_delete(&pool, &default_id).unwrap();
+    assert_eq!(
+        workflow_delete(&pool, &default_id).unwrap_err().code(),
+        "NOT_FOUND"
+    );
+    assert_eq!(
+        workflow_activate(&pool, "missing").unwrap_err().code(),
+        "NOT_FOUND"
+    );
+}
+
+// ══════════════════════════════════════════════════════════
+//  WIP limits
+// ══════════════════════════════════════════════════════════
+
+#[test]
+fn wip_limit_rejects_unless_forced() {
+    let pool = init_test_db();
+    let owner = person(&pool, "Owner");
+    let ids: Vec<String> = (0..3)
+        .map(|i| planned_project(&pool, &format!("P{}", i), &owner))
+        .collect();
+    limit_in_progress(&pool, Some(2), None);
+
+    start(&pool, &ids[0], false).unwrap();
+    start(&pool, &ids[1], false).unwrap();
+    let err = start(&pool, &ids[2], false).unwrap_err();
+    assert_eq!(err.code(), "WIP_LIMIT_EXCEEDED");
+    let details = err.to_serde().details.unwrap();
+    assert_eq!(details["status"], "IN_PROGRESS");
+    assert_eq!(details["count"], 3);
+    assert_eq!(details["limit"], 2);
+
+    let project = start(&pool, &ids[2], true).unwrap();
+    assert_eq!(project.current_status, "IN_PROGRESS");
+    assert!(project
+        .status_history
+        .iter()
+        .any(|h| h.note == "[auto] over WIP limit in IN_PROGRESS: 3/2"));
+
+    let wip = workflow_wip_status(&pool).unwrap();
+    let in_progress = wip.iter().find(|s| s.status == "IN_PROGRESS").unwrap();
+    assert_eq!((in_progress.count, in_progress.wip_limit), (3, Some(2)));
+    assert!(in_progress.over_limit);
+    assert!(in_progress.owners.is_empty());
+}
+
+#[test]
+fn owner_wip_limit_counts_per_owner() {
+    let pool = init_test_db();
+    let alice = person(&pool, "Alice");
+    let bob = person(&pool, "Bob");
+    let a1 = planned_project(&pool, "A1", &alice);
+    let a2 = planned_project(&pool, "A2", &alice);
+    let b1 = planned_project(&pool, "B1", &bob);
+    limit_in_progress(&pool, None, Some(1));
+
+    start(&pool, &a1, false).unwrap();
+    start(&pool, &b1, false).unwrap();
+    let err = start(&pool, &a2, false).unwrap_err();
+    let details = err.to_serde().details.unwrap();
+    assert_eq!(details["ownerPersonId"], alice.as_str());
+    assert_eq!(
+        (details["count"].as_i64(), details["ownerCount"].as_i64()),
+        (Some(3), Some(2))
+    );
+
+    // Leaving the status is never limited.
+    change(&pool, &a1, "BLOCKED", None).unwrap();
+    start(&pool, &a2, false).unwrap();
+
+    let wip = workflow_wip_status(&pool).unwrap();
+    let in_progress = wip.iter().find(|s| s.status == "IN_PROGRESS").unwrap();
+    let owners: Vec<(&str, i64)> = in_progress
+        .owners
+        .iter()
+        .map(|o| (o.display_name.as_str(), o.count))
+        .collect();
+    assert_eq!(owners, vec![("Alice", 1), ("Bob", 1)]);
+    assert!(!in_progress.over_limit);
+}
@@ -0,0 +1,170 @@
+//! XLSX workspace export
+
+use app_lib::app::{
+    comment_create, export_xlsx, partner_create, person_create, project_create, CommentCreateReq,
+    PartnerCreateReq, PersonCreateReq, ProjectCreateReq,
+};
+use app_lib::infra::db::init_test_db;
+use app_lib::infra::DbPool;
+use flate2::read::DeflateDecoder;
+use std::io::Read;
+
+// ──────────────────────── Helper ────────────────────────
+
+fn seed(pool: &DbPool) {
+    let owner = person_create(
+        pool,
+        PersonCreateReq {
+            display_name: "Alice <QA>".to_string(),
+            email: None,
+            role: None,
+            note: None,
+        },
+    )
+    .unwrap();
+    let partner = partner_create(
+        pool,
+        PartnerCreateReq {
+            name: "Acme & Co".to_string(),
+            note: None,
+        },
+    )
+    .unwrap();
+    let id = project_create(
+        pool,
+        ProjectCreateReq {
+            name: "Alpha".to_string(),
+            description: None,
+            priority: Some(2),
+            country_code: "CN".to_string(),
+            partner_id: partner.id,
+            owner_person_id: owner.id.clone(),
+            product_name: None,
+            start_date: None,
+            due_date: None,
+            tags: None,
+            created_by_person_id: None,
+            parent_project_id: None,
+        },
+    )
+    .unwrap()
+    .id;
+    comment_create(
+        pool,
+        CommentCreateReq {
+            project_id: id,
+            person_id: Some(owner.id),
+            content: "ship it\u{1}".to_string(),
+            is_pinned: None,
+        },
+    )
+    .unwrap();
+}
+
+/// Read every entry of the ZIP by walking its local headers.
+fn unzip(bytes: &[u8]) -> Vec<(String, String)> {
+    let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]) as usize;
+    let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap()) as usize;
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while u32_at(pos) == 0x0403_4b50 {
+        assert_eq!(u16_at(pos + 8), 8, "entries are deflated");
+        let compressed = u32_at(pos + 18);
+        let name_len = u16_at(pos + 26);
+        let extra_len = u16_at(pos + 28);
+        let name = String::from_utf8(bytes[pos + 30..pos + 30 + name_len].to_vec()).unwrap();
+        let start = pos + 30 + name_len + extra_len;
+        let mut content = String::new();
+        DeflateDecoder::new(&bytes[start..start + compressed])
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content.len(), u32_at(pos + 22));
+        entries.push((name, content));
+        pos = start + compressed;
+    }
+    assert_eq!(
+        u32_at(pos),
+        0x0201_4b50,
+        "central directory follows entries"
+    );
+    entries
+}
+
+fn entry<'a>(entries: &'a [(String, String)], name: &str) -> &'a str {
+    entries
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, c)| c.as_str())
+        .unwrap_or_else(|| panic!("missing {}", name))
+}
+
+// ──────────────────────── Tests ────────────────────────
+
+#[test]
+fn xlsx_contains_one_sheet_per_entity() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let export = export_xlsx(&pool).unwrap();
+    let names: Vec<&str> = export.sheets.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "Projects",
+            "Persons",
+            "Partners",
+            "Assignments",
+            "Status history",
+            "Comments"
+        ]
+    );
+    let rows: Vec<usize> = export.sheets.iter().map(|s| s.rows).collect();
+    // project_create records the owner assignment and the initial status.
+    assert_eq!(rows, vec![1, 1, 1, 1, 1, 1]);
+
+    let entries = unzip(&export.bytes);
+    assert_eq!(entries[0].0, "[Content_Types].xml");
+    let workbook = entry(&entries, "xl/workbook.xml");
+    assert!(workbook.contains(r#"<sheet name="Status history" sheetId="5" r:id="rId5"/>"#));
+    assert!(entry(&entries, "[Content_Types].xml").contains("/xl/worksheets/sheet6.xml"));
+    entry(&entries, "xl/styles.xml");
+    entry(&entries, "xl/_rels/workbook.xml.rels");
+}
+
+#[test]
+fn xlsx_cells_are_escaped_and_typed() {
+    let pool = init_test_db();
+    seed(&pool);
+
+    let entries = unzip(&export_xlsx(&pool).unwrap().bytes);
+    let projects = entry(&entries, "xl/worksheets/sheet1.xml");
+    assert!(projects.contains(r#"<c r="A1" s="1" t="inlineStr"><is><t>id</t></is></c>"#));
+    assert!(projects.contains("Acme &amp; Co"));
+    assert!(projects.contains("Alice &lt;QA&gt;"));
+    // priority is a numeric cell, not text.
+    assert!(projects.contains(r#"<c r="D2"><v>2</v></c>"#));
+
+    let comments = entry(&entries, "xl/worksheets/sheet6.xml");
+    assert!(
+        comments.contains(">ship it<"),
+        "control characters are dropped"
+    );
+}
+
+#[test]
+fn xlsx_excludes_trashed_projects() {
+    let pool = init_test_db();
+    seed(&pool);
+    pool.0
+        .lock()
+        .unwrap()
+        .execute(
+            "UPDATE projects SET deleted_at = '2026-01-01T00:00:00Z'",
+            [],
+        )
+        .unwrap();
+
+    let export = export_xlsx(&pool).unwrap();
+    let rows: Vec<usize> = export.sheets.iter().map(|s| s.rows).collect();
+    assert_eq!(rows, vec![0, 1, 1, 0, 0, 0]);
+}
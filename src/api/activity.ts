@@ -0,0 +1,45 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type ActivityType =
+  | 'project_created'
+  | 'status_change'
+  | 'comment'
+  | 'assignment_start'
+  | 'assignment_end'
+  | 'edit';
+
+export interface ActivityListReq {
+  projectId?: string;
+  personId?: string;
+  /** Inclusive, YYYY-MM-DD or RFC3339 (UTC). */
+  from?: string;
+  /** Exclusive, YYYY-MM-DD or RFC3339 (UTC). */
+  to?: string;
+  types?: ActivityType[];
+  limit?: number;
+  offset?: number;
+}
+
+export interface ActivityItemDto {
+  type: ActivityType;
+  at: string;
+  projectId?: string | null;
+  projectName?: string | null;
+  personId?: string | null;
+  personName?: string | null;
+  entityId: string;
+  detail?: string | null;
+}
+
+export interface ActivityPage {
+  items: ActivityItemDto[];
+  total: number;
+  limit: number;
+  offset: number;
+}
+
+export const activityApi = {
+  async list(req: ActivityListReq = {}): Promise<ActivityPage> {
+    return await invoke<ActivityPage>('cmd_activity_list', { req });
+  },
+};
@@ -0,0 +1,36 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type AuditEntityType = 'project' | 'person' | 'partner';
+
+export interface AuditListReq {
+  entityType?: AuditEntityType;
+  entityId?: string;
+  limit?: number;
+  offset?: number;
+}
+
+export interface AuditEntryDto {
+  id: number;
+  entityType: AuditEntityType;
+  entityId: string;
+  /** Column name; projects also record `tags` (sorted, comma separated). */
+  field: string;
+  oldValue?: string | null;
+  newValue?: string | null;
+  changedByPersonId?: string | null;
+  changedByName?: string | null;
+  changedAt: string;
+}
+
+export interface AuditPage {
+  items: AuditEntryDto[];
+  total: number;
+  limit: number;
+  offset: number;
+}
+
+export const auditApi = {
+  async list(req: AuditListReq = {}): Promise<AuditPage> {
+    return await invoke<AuditPage>('cmd_audit_list', { req });
+  },
+};
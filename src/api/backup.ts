@@ -0,0 +1,57 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface BackupInfoDto {
+  fileName: string;
+  reason: string;
+  createdAt: string;
+  sizeBytes: number;
+}
+
+export interface BackupRestoreResult {
+  restoredFrom: string;
+  safetyBackup: string | null;
+}
+
+export type BackupSchedule = 'off' | 'daily' | 'weekly';
+
+export interface BackupConfigDto {
+  schedule: BackupSchedule;
+  targetDir: string | null;
+  effectiveDir: string | null;
+  maxCount: number;
+  lastScheduledAt: string | null;
+  nextDueAt: string | null;
+}
+
+export interface BackupConfigReq {
+  schedule?: BackupSchedule;
+  /** Absolute path; empty string resets to the default dir. */
+  targetDir?: string;
+  maxCount?: number;
+}
+
+export const backupApi = {
+  /** Local DB backups, newest first. */
+  async list(): Promise<BackupInfoDto[]> {
+    return await invoke<BackupInfoDto[]>('cmd_backup_list');
+  },
+
+  /** Replace business data with a backup; the current state is backed up first (undo point). */
+  async restore(fileName: string): Promise<BackupRestoreResult> {
+    return await invoke<BackupRestoreResult>('cmd_backup_restore', { req: { fileName } });
+  },
+
+  /** Write a manual backup now (null for in-memory databases). */
+  async create(): Promise<BackupInfoDto | null> {
+    return await invoke<BackupInfoDto | null>('cmd_backup_create');
+  },
+
+  async getConfig(): Promise<BackupConfigDto> {
+    return await invoke<BackupConfigDto>('cmd_backup_get_config');
+  },
+
+  /** Save schedule / target dir / retention; the scheduler restarts with the new settings. */
+  async updateConfig(req: BackupConfigReq): Promise<BackupConfigDto> {
+    return await invoke<BackupConfigDto>('cmd_backup_update_config', { req });
+  },
+};
@@ -0,0 +1,44 @@
+import { invokeCmd } from './invoke';
+
+export interface ChecklistItemDto {
+  id: string;
+  projectId: string;
+  text: string;
+  done: boolean;
+  /** Position within the project (ascending). */
+  sortOrder: number;
+  /** When the item was ticked; null while open. */
+  doneAt: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface ChecklistAddReq {
+  projectId: string;
+  text: string;
+}
+
+export interface ChecklistToggleReq {
+  id: string;
+  /** Omitted = flip the current state. */
+  done?: boolean;
+}
+
+export const checklistApi = {
+  list: (projectId: string) =>
+    invokeCmd<ChecklistItemDto[]>('cmd_checklist_list', { req: { projectId } }),
+
+  /** Appends to the end of the checklist. */
+  add: (req: ChecklistAddReq) =>
+    invokeCmd<ChecklistItemDto>('cmd_checklist_add', { req }),
+
+  toggle: (req: ChecklistToggleReq) =>
+    invokeCmd<ChecklistItemDto>('cmd_checklist_toggle', { req }),
+
+  /** Items left out of `itemIds` follow in their current order. */
+  reorder: (projectId: string, itemIds: string[]) =>
+    invokeCmd<ChecklistItemDto[]>('cmd_checklist_reorder', { req: { projectId, itemIds } }),
+
+  delete: (id: string) =>
+    invokeCmd<void>('cmd_checklist_delete', { req: { id } }),
+};
@@ -9,6 +9,34 @@ export interface CommentDto {
   isPinned: boolean;
   createdAt: string;
   updatedAt: string;
+  /** Top-level comment this one replies to; null for top-level comments. */
+  parentCommentId: string | null;
+  /** Position among the project's pinned comments (ascending); null when unpinned. */
+  pinOrder: number | null;
+  /** Number of earlier versions; 0 = never edited. */
+  revisionCount: number;
+  reactions: CommentReactionDto[];
+  /** Oldest first; always empty on replies. */
+  replies: CommentDto[];
+}
+
+export interface CommentReactionDto {
+  emoji: string;
+  count: number;
+  personIds: string[];
+  personNames: string[];
+}
+
+export interface CommentRevisionDto {
+  id: string;
+  commentId: string;
+  /** Author at the time of this version. */
+  personId: string | null;
+  personName: string | null;
+  /** Content before the edit. */
+  content: string;
+  writtenAt: string;
+  replacedAt: string;
 }
 
 export interface CommentCreateReq {
@@ -18,6 +46,19 @@ export interface CommentCreateReq {
   isPinned?: boolean;
 }
 
+export interface CommentReplyReq {
+  /** Replies to a reply join its top-level comment. */
+  commentId: string;
+  personId?: string | null;
+  content: string;
+}
+
+export interface CommentReactReq {
+  commentId: string;
+  personId: string;
+  emoji: string;
+}
+
 export interface CommentUpdateReq {
   id: string;
   content?: string;
@@ -32,9 +73,24 @@ export const commentApi = {
   create: (req: CommentCreateReq) =>
     invokeCmd<CommentDto>('cmd_comment_create', { req }),
 
+  reply: (req: CommentReplyReq) =>
+    invokeCmd<CommentDto>('cmd_comment_reply', { req }),
+
+  /** Toggles: reacting again with the same emoji removes the reaction. */
+  react: (req: CommentReactReq) =>
+    invokeCmd<CommentDto>('cmd_comment_react', { req }),
+
   update: (req: CommentUpdateReq) =>
     invokeCmd<CommentDto>('cmd_comment_update', { req }),
 
+  /** Earlier versions, most recent edit first. */
+  history: (commentId: string) =>
+    invokeCmd<CommentRevisionDto[]>('cmd_comment_history', { req: { commentId } }),
+
+  /** Pinned comments left out of `commentIds` follow in their current order. */
+  reorderPins: (projectId: string, commentIds: string[]) =>
+    invokeCmd<CommentDto[]>('cmd_comment_reorder_pins', { req: { projectId, commentIds } }),
+
   delete: (id: string) =>
     invokeCmd<void>('cmd_comment_delete', { req: { id } }),
 };
@@ -0,0 +1,29 @@
+import { invokeCmd } from './invoke';
+
+export interface CountryDto {
+  code: string;
+  alpha3: string;
+  /** Name in the requested locale. */
+  name: string;
+  nameEn: string;
+  nameZh: string;
+  flag: string;
+}
+
+export interface CountryUsageDto {
+  code: string;
+  /** Name in the requested locale; the code itself when not in the catalog. */
+  name: string;
+  flag: string;
+  known: boolean;
+  projectCount: number;
+}
+
+export const countryApi = {
+  /** Every ISO 3166-1 entry, ordered by code. */
+  list: (locale?: string) =>
+    invokeCmd<CountryDto[]>('cmd_countries_list', { req: { locale } }),
+  /** Codes stored on projects (for list filters), including ones missing from the catalog. */
+  inUse: (locale?: string) =>
+    invokeCmd<CountryUsageDto[]>('cmd_countries_in_use', { req: { locale } }),
+};
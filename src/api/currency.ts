@@ -0,0 +1,84 @@
+import { invokeCmd } from './invoke';
+
+export type CurrencyRateSource = 'manual' | 'fetched';
+
+export interface CurrencyConfigDto {
+  baseCurrency: string;
+  /** `{base}` is replaced with the base currency. */
+  ratesUrl: string;
+}
+
+export interface CurrencyRateDto {
+  currency: string;
+  /** Units of the base currency per 1 unit of `currency`. */
+  rate: number;
+  source: CurrencyRateSource;
+  updatedAt: string;
+}
+
+export interface CurrencyRatesFetchResult {
+  baseCurrency: string;
+  updated: number;
+  fetchedAt: string;
+}
+
+export interface BudgetReportProjectDto {
+  projectId: string;
+  projectName: string;
+  partnerId: string;
+  partnerName: string;
+  amount: number;
+  /** Effective currency (project, else partner default, else base currency). */
+  currency: string;
+  /** null when there is no rate for `currency`. */
+  amountBase: number | null;
+}
+
+export interface BudgetReportPartnerDto {
+  partnerId: string;
+  partnerName: string;
+  projectCount: number;
+  totalBase: number;
+  /** Budgets left out of `totalBase` for lack of a rate. */
+  unconvertedCount: number;
+}
+
+export interface BudgetReportCurrencyDto {
+  currency: string;
+  projectCount: number;
+  total: number;
+  rate: number | null;
+  totalBase: number | null;
+}
+
+export interface BudgetReportDto {
+  baseCurrency: string;
+  totalBase: number;
+  projects: BudgetReportProjectDto[];
+  partners: BudgetReportPartnerDto[];
+  currencies: BudgetReportCurrencyDto[];
+  /** Currencies in use without an exchange rate. */
+  missingRates: string[];
+}
+
+export const currencyApi = {
+  getConfig: () => invokeCmd<CurrencyConfigDto>('cmd_currency_get_config'),
+
+  /** Changing the base currency converts the existing rates. `ratesUrl: ''` restores the default. */
+  updateConfig: (req: { baseCurrency?: string; ratesUrl?: string }) =>
+    invokeCmd<CurrencyConfigDto>('cmd_currency_update_config', { req }),
+
+  listRates: () => invokeCmd<CurrencyRateDto[]>('cmd_currency_rate_list'),
+
+  setRate: (req: { currency: string; rate: number }) =>
+    invokeCmd<CurrencyRateDto>('cmd_currency_rate_set', { req }),
+
+  deleteRate: (currency: string) =>
+    invokeCmd<void>('cmd_currency_rate_delete', { req: { currency } }),
+
+  /** Download current rates from the configured URL. */
+  fetchRates: () => invokeCmd<CurrencyRatesFetchResult>('cmd_currency_rates_fetch'),
+
+  budgetReport: (req?: { onlyUnarchived?: boolean }) =>
+    invokeCmd<BudgetReportDto>('cmd_budget_report', req ? { req } : {}),
+};
@@ -0,0 +1,60 @@
+import { invokeCmd } from './invoke';
+
+export type CustomFieldType = 'text' | 'number' | 'date' | 'select';
+
+export interface CustomFieldDef {
+  id: string;
+  name: string;
+  fieldType: CustomFieldType;
+  /** Allowed values (select only). */
+  options: string[];
+  sortOrder: number;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface CustomFieldDefCreateReq {
+  name: string;
+  fieldType: CustomFieldType;
+  options?: string[];
+  sortOrder?: number;
+}
+
+export interface CustomFieldDefUpdateReq {
+  id: string;
+  name?: string;
+  /** Select fields only; replaces the option list. */
+  options?: string[];
+  sortOrder?: number;
+}
+
+export interface ProjectCustomValue {
+  fieldId: string;
+  name: string;
+  fieldType: CustomFieldType;
+  value: string | null;
+}
+
+export interface CustomFieldFilter {
+  fieldId: string;
+  value: string;
+}
+
+export const customFieldApi = {
+  list: () => invokeCmd<CustomFieldDef[]>('cmd_custom_field_list'),
+
+  create: (req: CustomFieldDefCreateReq) =>
+    invokeCmd<CustomFieldDef>('cmd_custom_field_create', { req }),
+
+  update: (req: CustomFieldDefUpdateReq) =>
+    invokeCmd<CustomFieldDef>('cmd_custom_field_update', { req }),
+
+  delete: (id: string) =>
+    invokeCmd<void>('cmd_custom_field_delete', { req: { id } }),
+
+  /** null or blank clears the value. */
+  setValue: (projectId: string, fieldId: string, value: string | null) =>
+    invokeCmd<ProjectCustomValue[]>('cmd_project_set_custom_value', {
+      req: { projectId, fieldId, value },
+    }),
+};
@@ -0,0 +1,31 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface DataDirInfoDto {
+  currentDir: string;
+  defaultDir: string;
+  configuredDir: string;
+  isCustom: boolean;
+  pendingCleanup: string | null;
+}
+
+export interface DataDirMoveResult {
+  fromDir: string;
+  toDir: string;
+  copiedFiles: string[];
+  /** The live switch failed: the old dir is read-only until restart. */
+  requiresRestart: boolean;
+}
+
+export const dataDirApi = {
+  async get(): Promise<DataDirInfoDto> {
+    return await invoke<DataDirInfoDto>('cmd_data_dir_get');
+  },
+
+  /**
+   * Pass `null` to move back to the default location. Switches to the new dir right away;
+   * reload all data afterwards.
+   */
+  async move(targetDir: string | null): Promise<DataDirMoveResult> {
+    return await invoke<DataDirMoveResult>('cmd_data_dir_move', { req: { targetDir } });
+  },
+};
@@ -0,0 +1,79 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type DigestFrequency = 'daily' | 'weekly';
+export type DigestFormat = 'slack' | 'teams';
+
+export interface DigestConfigDto {
+  enabled: boolean;
+  frequency: DigestFrequency;
+  format: DigestFormat;
+  /** The URL itself is never returned. */
+  hasWebhookUrl: boolean;
+  lastSentAt: string | null;
+  lastError: string | null;
+}
+
+export interface DigestConfigReq {
+  /** Enabling requires a webhook URL. */
+  enabled?: boolean;
+  frequency?: DigestFrequency;
+  format?: DigestFormat;
+  /** https incoming-webhook URL; `''` removes it. */
+  webhookUrl?: string;
+}
+
+export interface DigestProjectItem {
+  projectId: string;
+  name: string;
+  status: string;
+  dueDate: string | null;
+}
+
+export interface DigestStatusChangeItem {
+  projectId: string;
+  name: string;
+  fromStatus: string | null;
+  toStatus: string;
+  changedAt: string;
+  changedBy: string | null;
+}
+
+export interface DigestDto {
+  frequency: DigestFrequency;
+  periodStart: string;
+  periodEnd: string;
+  newProjects: DigestProjectItem[];
+  statusChanges: DigestStatusChangeItem[];
+  overdue: DigestProjectItem[];
+}
+
+export interface DigestPreviewDto {
+  digest: DigestDto;
+  /** Exact JSON body that would be posted. */
+  payload: Record<string, unknown>;
+}
+
+export interface DigestSendResult {
+  periodStart: string;
+  periodEnd: string;
+  posted: boolean;
+}
+
+export const digestApi = {
+  async getConfig(): Promise<DigestConfigDto> {
+    return await invoke<DigestConfigDto>('cmd_digest_get_config');
+  },
+
+  async updateConfig(req: DigestConfigReq): Promise<DigestConfigDto> {
+    return await invoke<DigestConfigDto>('cmd_digest_update_config', { req });
+  },
+
+  async preview(req?: { frequency?: DigestFrequency; format?: DigestFormat }): Promise<DigestPreviewDto> {
+    return await invoke<DigestPreviewDto>('cmd_digest_preview', req ? { req } : {});
+  },
+
+  /** Post the digest for the last period now (the schedule is unchanged). */
+  async send(): Promise<DigestSendResult> {
+    return await invoke<DigestSendResult>('cmd_digest_send');
+  },
+};
@@ -0,0 +1,25 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface DbEncryptionStatusDto {
+  /** This build includes SQLCipher. */
+  supported: boolean;
+  encrypted: boolean;
+  /** Local backups written before encryption that are still plaintext. */
+  plaintextBackups: number;
+}
+
+export const encryptionApi = {
+  async status(): Promise<DbEncryptionStatusDto> {
+    return await invoke<DbEncryptionStatusDto>('cmd_db_encryption_status');
+  },
+
+  /**
+   * Encrypt (plaintext DB), change the passphrase (encrypted DB) or decrypt (`null`).
+   * The passphrase is kept in the system keychain; at least 8 characters.
+   */
+  async setPassphrase(passphrase: string | null): Promise<DbEncryptionStatusDto> {
+    return await invoke<DbEncryptionStatusDto>('cmd_db_set_passphrase', {
+      req: { passphrase },
+    });
+  },
+};
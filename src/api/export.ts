@@ -1,4 +1,5 @@
 import { invokeCmd } from './invoke';
+import type { ProjectListReq } from './projects';
 
 export interface ImportResult {
   persons: number;
@@ -20,9 +21,81 @@ export interface WipeResult {
   deleted_partners: number;
 }
 
+/** Omit every selection field to export the whole workspace. */
+export interface ExportJsonReq {
+  schemaVersion?: number;
+  /** Union with `filter`; related persons / partners / custom fields come along. */
+  projectIds?: string[];
+  filter?: ProjectListReq;
+  /** Default true. */
+  includeComments?: boolean;
+  /** Status and size history; default true. */
+  includeHistory?: boolean;
+  /** RFC 3339 timestamp or `YYYY-MM-DD` (UTC). */
+  changedSince?: string;
+}
+
+/** Column names per export; `columns` picks and orders them (default: all, in this order). */
+export type ProjectCsvColumn =
+  | 'id' | 'name' | 'status' | 'priority' | 'country_code' | 'partner' | 'owner'
+  | 'product_name' | 'start_date' | 'due_date' | 'tags' | 'parent_project_id'
+  | 'description' | 'created_at' | 'updated_at' | 'archived_at';
+export type AssignmentCsvColumn =
+  | 'id' | 'project_id' | 'project_name' | 'person_id' | 'person_name' | 'role' | 'start_at' | 'end_at';
+export type StatusHistoryCsvColumn =
+  | 'id' | 'project_id' | 'project_name' | 'from_status' | 'to_status' | 'changed_at' | 'changed_by' | 'note';
+export type CommentCsvColumn =
+  | 'id' | 'project_id' | 'project_name' | 'author' | 'content' | 'is_pinned' | 'created_at' | 'updated_at';
+
+export type ProjectImportField =
+  | 'name' | 'partner' | 'owner' | 'country_code' | 'description' | 'priority'
+  | 'product_name' | 'start_date' | 'due_date' | 'tags' | 'parent';
+
+export interface ProjectCsvImportReq {
+  csv: string;
+  /** Default true: validate and roll back. */
+  dryRun?: boolean;
+  /** CSV header → field; other headers match by default names (e.g. `partner_id`, `owner_name`). */
+  columnMap?: Record<string, ProjectImportField>;
+}
+
+export interface ProjectCsvImportReport {
+  dryRun: boolean;
+  committed: boolean;
+  totalRows: number;
+  okRows: number;
+  /** `row` is 1-based, header excluded. */
+  errors: { row: number; name: string | null; reasons: string[] }[];
+  ignoredColumns: string[];
+  projectIds: string[];
+}
+
+export interface ExportXlsxResult {
+  path: string;
+  bytes: number;
+  /** Projects, Persons, Partners, Assignments, Status history, Comments; `rows` excludes the header. */
+  sheets: { name: string; rows: number }[];
+}
+
 export const exportApi = {
-  exportJson: (req?: { schemaVersion?: number }) =>
+  exportJson: (req?: ExportJsonReq) =>
     invokeCmd<string>('cmd_export_json', req ? { req } : {}),
+  /** iCalendar text (due / start dates, milestones); sort and paging fields are ignored. */
+  exportIcs: (req?: ProjectListReq) =>
+    invokeCmd<string>('cmd_export_ics', req ? { req } : {}),
+  exportProjectsCsv: (columns?: ProjectCsvColumn[]) =>
+    invokeCmd<string>('cmd_export_projects_csv', { req: { columns } }),
+  exportAssignmentsCsv: (columns?: AssignmentCsvColumn[]) =>
+    invokeCmd<string>('cmd_export_assignments_csv', { req: { columns } }),
+  exportStatusHistoryCsv: (columns?: StatusHistoryCsvColumn[]) =>
+    invokeCmd<string>('cmd_export_status_history_csv', { req: { columns } }),
+  exportCommentsCsv: (columns?: CommentCsvColumn[]) =>
+    invokeCmd<string>('cmd_export_comments_csv', { req: { columns } }),
+  /** Writes the workbook to `path` (absolute, from the save dialog). */
+  exportXlsx: (path: string) =>
+    invokeCmd<ExportXlsxResult>('cmd_export_xlsx', { req: { path } }),
+  importProjectsCsv: (req: ProjectCsvImportReq) =>
+    invokeCmd<ProjectCsvImportReport>('cmd_import_projects_csv', { req }),
   importJson: (json: string) =>
     invokeCmd<ImportResult>('cmd_import_json', { req: { json } }),
   wipeBusinessData: () => invokeCmd<WipeResult>('cmd_wipe_business_data'),
@@ -0,0 +1,20 @@
+import { invokeCmd } from './invoke';
+
+export type DateFormat = 'YYYY-MM-DD' | 'YYYY/MM/DD' | 'DD/MM/YYYY' | 'MM/DD/YYYY' | 'DD.MM.YYYY';
+export type WeekStart = 'monday' | 'sunday' | 'saturday';
+export type HourCycle = '24h' | '12h';
+
+/** Applied to Rust-generated report and export text. */
+export interface FormatSettings {
+  dateFormat: DateFormat;
+  weekStart: WeekStart;
+  hourCycle: HourCycle;
+}
+
+export const formattingApi = {
+  get: () => invokeCmd<FormatSettings>('cmd_format_settings_get'),
+
+  /** Omitted fields keep their current value. */
+  update: (req: Partial<FormatSettings>) =>
+    invokeCmd<FormatSettings>('cmd_format_settings_update', { req }),
+};
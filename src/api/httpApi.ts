@@ -0,0 +1,31 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface HttpApiConfigDto {
+  enabled: boolean;
+  port: number;
+  /** Bearer token; null until the API is enabled for the first time. */
+  token: string | null;
+  /** e.g. `http://127.0.0.1:27121/api/v1` */
+  baseUrl: string;
+}
+
+export interface HttpApiConfigReq {
+  enabled?: boolean;
+  /** 1024–65535 */
+  port?: number;
+}
+
+export const httpApi = {
+  async getConfig(): Promise<HttpApiConfigDto> {
+    return await invoke<HttpApiConfigDto>('cmd_http_api_get_config');
+  },
+
+  /** Restarts the server; fails if the port is in use. */
+  async updateConfig(req: HttpApiConfigReq): Promise<HttpApiConfigDto> {
+    return await invoke<HttpApiConfigDto>('cmd_http_api_update_config', { req });
+  },
+
+  async regenerateToken(): Promise<HttpApiConfigDto> {
+    return await invoke<HttpApiConfigDto>('cmd_http_api_regenerate_token');
+  },
+};
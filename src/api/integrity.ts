@@ -0,0 +1,16 @@
+import { invokeCmd } from './invoke';
+
+/** Strict foreign-key mode (off by default). */
+export interface StrictFkConfig {
+  enabled: boolean;
+  /** Remote operations waiting for referenced rows to arrive. */
+  deferredOps: number;
+}
+
+export const integrityApi = {
+  getStrictFk: () => invokeCmd<StrictFkConfig>('cmd_strict_fk_get_config'),
+
+  /** Turning strict mode off applies deferred operations right away. */
+  updateStrictFk: (enabled: boolean) =>
+    invokeCmd<StrictFkConfig>('cmd_strict_fk_update_config', { req: { enabled } }),
+};
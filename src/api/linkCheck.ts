@@ -0,0 +1,67 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type LinkSource = 'description' | 'comment' | 'custom_field';
+export type LinkOutcome = 'ok' | 'dead' | 'unverified';
+
+export interface LinkCheckConfigDto {
+  enabled: boolean;
+  lastCheckedAt: string | null;
+  /** null when disabled. */
+  nextDueAt: string | null;
+}
+
+export interface LinkCheckRunResult {
+  checkedAt: string;
+  totalLinks: number;
+  checkedUrls: number;
+  skippedUrls: number;
+  dead: number;
+  unverified: number;
+}
+
+export interface LinkIssueDto {
+  url: string;
+  source: LinkSource;
+  /** Comment id / custom field id; null for the description. */
+  sourceId: string | null;
+  outcome: LinkOutcome;
+  statusCode: number | null;
+  error: string | null;
+}
+
+export interface ProjectLinkHealthDto {
+  projectId: string;
+  projectName: string;
+  dead: number;
+  unverified: number;
+  links: LinkIssueDto[];
+}
+
+export interface LinkCheckReportDto {
+  checkedAt: string | null;
+  totalLinks: number;
+  dead: number;
+  unverified: number;
+  projects: ProjectLinkHealthDto[];
+}
+
+export const linkCheckApi = {
+  async getConfig(): Promise<LinkCheckConfigDto> {
+    return await invoke<LinkCheckConfigDto>('cmd_link_check_get_config');
+  },
+
+  /** Turn the weekly check on/off (it reaches external servers, so it is off by default). */
+  async updateConfig(enabled: boolean): Promise<LinkCheckConfigDto> {
+    return await invoke<LinkCheckConfigDto>('cmd_link_check_update_config', { req: { enabled } });
+  },
+
+  /** Check all links now; may take a while for many links. */
+  async run(): Promise<LinkCheckRunResult> {
+    return await invoke<LinkCheckRunResult>('cmd_link_check_run');
+  },
+
+  /** Dead and unverified links of the latest check, optionally for one project. */
+  async report(projectId?: string): Promise<LinkCheckReportDto> {
+    return await invoke<LinkCheckReportDto>('cmd_link_check_report', { req: { projectId } });
+  },
+};
@@ -0,0 +1,51 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type VacuumMode = 'incremental' | 'full' | 'skipped';
+
+export interface IndexStatDto {
+  name: string;
+  table: string;
+  rows: number;
+  /** Average rows per distinct leading-column value (1 = unique). */
+  avgRowsPerKey: number | null;
+}
+
+export interface DbMaintenanceReport {
+  startedAt: string;
+  durationMs: number;
+  integrityOk: boolean;
+  /** Problems still reported after REINDEX (empty when ok). */
+  integrityErrors: string[];
+  reindexed: boolean;
+  vacuum: VacuumMode;
+  sizeBeforeBytes: number;
+  sizeAfterBytes: number;
+  freePagesBefore: number;
+  freePagesAfter: number;
+  indexes: IndexStatDto[];
+}
+
+export interface DbMaintenanceConfigDto {
+  enabled: boolean;
+  lastRunAt: string | null;
+  /** null when disabled. */
+  nextDueAt: string | null;
+}
+
+export const maintenanceApi = {
+  /** Integrity check, ANALYZE and vacuum now; blocks other writes while it runs. */
+  async run(): Promise<DbMaintenanceReport> {
+    return await invoke<DbMaintenanceReport>('cmd_db_maintenance');
+  },
+
+  async getConfig(): Promise<DbMaintenanceConfigDto> {
+    return await invoke<DbMaintenanceConfigDto>('cmd_db_maintenance_get_config');
+  },
+
+  /** Turn the monthly run on/off. */
+  async updateConfig(enabled: boolean): Promise<DbMaintenanceConfigDto> {
+    return await invoke<DbMaintenanceConfigDto>('cmd_db_maintenance_update_config', {
+      req: { enabled },
+    });
+  },
+};
@@ -0,0 +1,19 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface AppNoticeDto {
+  id: number;
+  kind: string;
+  level: 'info' | 'warning';
+  message: string;
+  createdAt: string;
+}
+
+export const noticeApi = {
+  async list(): Promise<AppNoticeDto[]> {
+    return await invoke<AppNoticeDto[]>('cmd_notice_list');
+  },
+
+  async dismiss(id: number): Promise<void> {
+    await invoke<void>('cmd_notice_dismiss', { req: { id } });
+  },
+};
@@ -0,0 +1,85 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type NotificationKind =
+  | 'DUE_SOON'
+  | 'OVERDUE'
+  | 'BLOCKED_TOO_LONG'
+  | 'MENTIONED'
+  | 'SLA_BREACHED';
+
+export interface NotificationDto {
+  id: number;
+  kind: NotificationKind;
+  projectId: string;
+  /** Mentioned person (MENTIONED only). */
+  personId: string | null;
+  title: string;
+  message: string;
+  createdAt: string;
+  dismissedAt: string | null;
+}
+
+export interface NotificationConfigDto {
+  enabled: boolean;
+  dueWindowDays: number;
+  blockedDays: number;
+  desktopEnabled: boolean;
+  lastCheckedAt: string | null;
+}
+
+export interface NotificationConfigReq {
+  enabled?: boolean;
+  /** 0–90 */
+  dueWindowDays?: number;
+  /** 1–365 */
+  blockedDays?: number;
+  desktopEnabled?: boolean;
+}
+
+export interface NotificationCheckResult {
+  checkedAt: string;
+  created: NotificationDto[];
+  resolved: number;
+}
+
+/** Emitted by the backend with new notifications when desktop notifications are enabled. */
+export const NOTIFICATION_CREATED_EVENT = 'notification-created';
+
+export const notificationApi = {
+  async getConfig(): Promise<NotificationConfigDto> {
+    return await invoke<NotificationConfigDto>('cmd_notification_get_config');
+  },
+
+  async updateConfig(req: NotificationConfigReq): Promise<NotificationConfigDto> {
+    return await invoke<NotificationConfigDto>('cmd_notification_update_config', { req });
+  },
+
+  /** Check now (also works while the background checker is off). */
+  async check(): Promise<NotificationCheckResult> {
+    return await invoke<NotificationCheckResult>('cmd_notification_check');
+  },
+
+  /** Newest first; open notifications only unless `includeDismissed`. */
+  async list(req?: { includeDismissed?: boolean; limit?: number }): Promise<NotificationDto[]> {
+    return await invoke<NotificationDto[]>('cmd_notification_list', req ? { req } : {});
+  },
+
+  async dismiss(id: number): Promise<void> {
+    await invoke<void>('cmd_notification_dismiss', { req: { id } });
+  },
+
+  /** Show new notifications as system notifications (Web Notification API of the webview). */
+  async listenDesktop(): Promise<UnlistenFn> {
+    return await listen<NotificationDto[]>(NOTIFICATION_CREATED_EVENT, async (event) => {
+      if (typeof Notification === 'undefined') return;
+      if (Notification.permission === 'default') {
+        await Notification.requestPermission();
+      }
+      if (Notification.permission !== 'granted') return;
+      for (const item of event.payload) {
+        new Notification(item.title, { body: item.message });
+      }
+    });
+  },
+};
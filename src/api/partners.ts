@@ -7,6 +7,8 @@ export interface PartnerDto {
   is_active: boolean;
   created_at: string;
   updated_at: string;
+  /** ISO 4217 default for project budgets; null = base currency. */
+  default_currency: string | null;
 }
 
 export interface PartnerProjectItem {
@@ -16,16 +18,40 @@ export interface PartnerProjectItem {
   updated_at: string;
 }
 
+export interface PartnerDuplicate {
+  /** Suggested merge target (created earlier). */
+  primary: PartnerDto;
+  duplicate: PartnerDto;
+  score: number;
+}
+
+export interface PartnerMergeResult {
+  target: PartnerDto;
+  sourceId: string;
+  projectsMoved: number;
+}
+
 export const partnersApi = {
   list: (onlyActive = true) =>
     invokeCmd<PartnerDto[]>('cmd_partner_list', { req: { onlyActive } }),
   get: (id: string) => invokeCmd<PartnerDto>('cmd_partner_get', { req: { id } }),
   create: (req: { name: string; note?: string }) =>
     invokeCmd<PartnerDto>('cmd_partner_create', { req }),
-  update: (req: { id: string; name?: string; note?: string }) =>
+  update: (req: { id: string; name?: string; note?: string; changedByPersonId?: string }) =>
     invokeCmd<PartnerDto>('cmd_partner_update', { req }),
+  /** null clears the default (budgets fall back to the base currency). */
+  setDefaultCurrency: (req: {
+    partnerId: string;
+    defaultCurrency: string | null;
+    changedByPersonId?: string;
+  }) => invokeCmd<PartnerDto>('cmd_partner_set_default_currency', { req }),
   deactivate: (id: string) =>
     invokeCmd<PartnerDto>('cmd_partner_deactivate', { req: { id } }),
   projects: (partnerId: string) =>
     invokeCmd<PartnerProjectItem[]>('cmd_partner_projects', { req: { id: partnerId } }),
+  findDuplicates: (req?: { threshold?: number; includeInactive?: boolean }) =>
+    invokeCmd<PartnerDuplicate[]>('cmd_partner_find_duplicates', req ? { req } : {}),
+  /** Moves every project of `sourceId` to `targetId` and deactivates the source. */
+  merge: (req: { targetId: string; sourceId: string; changedByPersonId?: string }) =>
+    invokeCmd<PartnerMergeResult>('cmd_partner_merge', { req }),
 };
@@ -26,13 +26,59 @@ export interface PersonImportResult {
   errors: string[];
 }
 
+export interface PersonDuplicate {
+  /** Suggested survivor (created earlier). */
+  primary: PersonDto;
+  duplicate: PersonDto;
+  /** 1 for the same email / normalized name, otherwise the name similarity. */
+  score: number;
+  reasons: ('email' | 'name' | 'similar_name')[];
+}
+
+export interface PersonMergeResult {
+  primary: PersonDto;
+  duplicateId: string;
+  projectsOwned: number;
+  assignments: number;
+  assignmentsEnded: number;
+  comments: number;
+  commentReactions: number;
+  /** Comments whose @mentions of the duplicate now point at the primary. */
+  commentMentions: number;
+  /** Earlier comment versions authored by the duplicate. */
+  commentRevisions: number;
+  statusHistory: number;
+  sizeHistory: number;
+}
+
+export interface PersonMention {
+  commentId: string;
+  /** Set when the mention is in a reply. */
+  parentCommentId: string | null;
+  projectId: string;
+  projectName: string;
+  authorPersonId: string | null;
+  authorName: string | null;
+  /** Rich-text JSON, as in `CommentDto.content`. */
+  content: string;
+  mentionedAt: string;
+}
+
 export const peopleApi = {
   list: (onlyActive = true) =>
     invokeCmd<PersonDto[]>('cmd_person_list', { req: { onlyActive } }),
   get: (id: string) => invokeCmd<PersonDto>('cmd_person_get', { req: { id } }),
   create: (req: { displayName: string; email?: string; role?: string; note?: string }) =>
     invokeCmd<PersonDto>('cmd_person_create', { req }),
-  update: (req: { id: string; displayName?: string; email?: string; role?: string; note?: string }) =>
+  update: (req: {
+    id: string;
+    displayName?: string;
+    email?: string;
+    role?: string;
+    note?: string;
+    /** Recorded in the audit log. */
+    changedByPersonId?: string;
+  }) =>
     invokeCmd<PersonDto>('cmd_person_update', { req }),
   deactivate: (id: string) =>
     invokeCmd<PersonDto>('cmd_person_deactivate', { req: { id } }),
@@ -40,6 +86,14 @@ export const peopleApi = {
     invokeCmd<PersonProjectItem[]>('cmd_person_current_projects', { req: { id: personId } }),
   allProjects: (personId: string) =>
     invokeCmd<PersonProjectItem[]>('cmd_person_all_projects', { req: { id: personId } }),
+  /** Comments that @mention the person, newest first (projects in the trash are skipped). */
+  mentions: (personId: string, limit?: number) =>
+    invokeCmd<PersonMention[]>('cmd_person_mentions', { req: { personId, limit } }),
+  findDuplicates: (req?: { threshold?: number; includeInactive?: boolean }) =>
+    invokeCmd<PersonDuplicate[]>('cmd_person_find_duplicates', req ? { req } : {}),
+  /** Moves every reference to `primaryId` and deactivates the duplicate. */
+  merge: (req: { primaryId: string; duplicateId: string; changedByPersonId?: string }) =>
+    invokeCmd<PersonMergeResult>('cmd_person_merge', { req }),
   exportCsv: () => invokeCmd<string>('cmd_export_persons_csv', {}),
   importCsv: (csv: string) =>
     invokeCmd<PersonImportResult>('cmd_import_persons_csv', { req: { csv } }),
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ProfileDto {
+  name: string;
+  dataDir: string;
+  isCustomDir: boolean;
+  isCurrent: boolean;
+  /** Locked by a running instance (always true for the current profile). */
+  inUse: boolean;
+}
+
+export const profileApi = {
+  async list(): Promise<ProfileDto[]> {
+    return await invoke<ProfileDto[]>('cmd_profile_list');
+  },
+
+  async current(): Promise<ProfileDto> {
+    return await invoke<ProfileDto>('cmd_profile_current');
+  },
+
+  /** Creates an empty profile; its database is initialized on first use. */
+  async create(name: string): Promise<ProfileDto> {
+    return await invoke<ProfileDto>('cmd_profile_create', { req: { name } });
+  },
+
+  async delete(name: string): Promise<void> {
+    await invoke('cmd_profile_delete', { req: { name } });
+  },
+
+  /** Switches without restarting; reload all data afterwards. */
+  async switch(name: string): Promise<ProfileDto> {
+    return await invoke<ProfileDto>('cmd_profile_switch', { req: { name } });
+  },
+};
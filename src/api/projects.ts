@@ -1,3 +1,4 @@
+import type { CustomFieldFilter, ProjectCustomValue } from './customFields';
 import { invokeCmd } from './invoke';
 
 export interface ProjectListItem {
@@ -6,11 +7,25 @@ export interface ProjectListItem {
   current_status: string;
   priority: number;
   country_code: string;
+  country_name: string;
   partner_name: string;
   owner_name: string;
   due_date: string | null;
   updated_at: string;
   tags: string[];
+  parent_project_id: string | null;
+  /** Manual Kanban rank within the status column (null = never reordered). */
+  rank: number | null;
+  /** Size estimate in points (null = not estimated). */
+  size_points: number | null;
+  checklist_done: number;
+  checklist_total: number;
+  /** checklist_done / checklist_total (null without checklist items). */
+  checklist_ratio: number | null;
+  /** Names of the SLA rules the project currently breaks. */
+  flags: string[];
+  /** Past due and not DONE / ARCHIVED; always false while the overdue flag is off. */
+  overdue: boolean;
 }
 
 export interface ProjectListPage {
@@ -28,6 +43,14 @@ export interface ProjectListReq {
   ownerPersonIds?: string[];
   participantPersonIds?: string[];
   tags?: string[];
+  /** Direct children of this project; '' = top-level projects only. */
+  parentProjectId?: string;
+  /** All must match; values are compared in their normalized form. */
+  customFields?: CustomFieldFilter[];
+  /** true = only projects with an SLA flag, false = only projects without. */
+  flagged?: boolean;
+  /** Flagged by any of these SLA rules. */
+  flagRuleIds?: string[];
   sortBy?: string;
   sortOrder?: string;
   limit?: number;
@@ -41,6 +64,7 @@ export interface ProjectDetail {
   priority: number;
   current_status: string;
   country_code: string;
+  country_name: string;
   partner_id: string;
   owner_person_id: string;
   product_name: string | null;
@@ -50,10 +74,24 @@ export interface ProjectDetail {
   updated_at: string;
   archived_at: string | null;
   tags: string[];
+  parent_project_id: string | null;
+  /** Project this one was cloned from (may no longer exist). */
+  cloned_from_id: string | null;
   owner_name: string;
   partner_name: string;
   assignments: AssignmentDto[];
   status_history: StatusHistoryDto[];
+  size_points: number | null;
+  size_history: SizeHistoryDto[];
+  /** Planned effort in hours; null = not estimated. */
+  estimated_hours: number | null;
+  /** Planned spend; null = no budget. */
+  budget_amount: number | null;
+  /** ISO 4217 code of the budget; null = the partner's default currency. */
+  budget_currency: string | null;
+  custom_fields: ProjectCustomValue[];
+  /** Past due and not DONE / ARCHIVED; always false while the overdue flag is off. */
+  overdue: boolean;
 }
 
 export interface AssignmentDto {
@@ -78,10 +116,74 @@ export interface StatusHistoryDto {
   note: string;
 }
 
+export interface SizeHistoryDto {
+  id: string;
+  project_id: string;
+  from_points: number | null;
+  to_points: number | null;
+  changed_at: string;
+  changed_by_person_id: string | null;
+  changed_by_name: string | null;
+  note: string;
+}
+
+/** Allowed size points (Fibonacci) and T-shirt aliases. */
+export const SIZE_POINTS = [0, 1, 2, 3, 5, 8, 13, 21] as const;
+export const SIZE_LABELS: Record<string, number> = { XS: 1, S: 2, M: 3, L: 5, XL: 8 };
+
+export interface ThroughputWeek {
+  weekStart: string;
+  label: string;
+  completed: number;
+  points: number;
+  unestimated: number;
+}
+
+export interface Throughput {
+  weeks: ThroughputWeek[];
+  totalCompleted: number;
+  totalPoints: number;
+  avgPointsPerWeek: number;
+}
+
+export interface ProjectTreeNode {
+  id: string;
+  name: string;
+  currentStatus: string;
+  priority: number;
+  ownerName: string;
+  dueDate: string | null;
+  parentProjectId: string | null;
+  /** Status -> count over this project and all its descendants. */
+  rollupStatusCounts: Record<string, number>;
+  descendantCount: number;
+  children: ProjectTreeNode[];
+}
+
+export interface ProjectShareResult {
+  url: string;
+  objectKey: string;
+  expiresAt: string;
+}
+
 export const projectApi = {
   list: (req?: ProjectListReq) =>
     invokeCmd<ProjectListPage>('cmd_project_list', req ? { req } : {}),
   get: (id: string) => invokeCmd<ProjectDetail>('cmd_project_get', { req: { id } }),
+  /** Markdown document (details, members, status timeline, comments) for wikis / emails. */
+  exportMarkdown: (projectId: string) =>
+    invokeCmd<string>('cmd_project_export_markdown', { req: { projectId } }),
+  /** Copy as a new BACKLOG project; tags, members and milestones are copied by default. */
+  clone: (req: {
+    projectId: string;
+    name?: string;
+    includeTags?: boolean;
+    includeMembers?: boolean;
+    includeMilestones?: boolean;
+    startDate?: string;
+    dueDate?: string;
+    createdByPersonId?: string;
+  }) => invokeCmd<ProjectDetail>('cmd_project_clone', { req }),
   create: (req: {
     name: string;
     countryCode: string;
@@ -93,6 +195,7 @@ export const projectApi = {
     startDate?: string;
     dueDate?: string;
     tags?: string[];
+    parentProjectId?: string;
   }) => invokeCmd<ProjectDetail>('cmd_project_create', { req }),
   update: (req: {
     id: string;
@@ -105,11 +208,43 @@ export const projectApi = {
     startDate?: string | null;
     dueDate?: string | null;
     tags?: string[];
+    /** '' = make top-level. */
+    parentProjectId?: string;
+    /** Recorded in the audit log. */
+    changedByPersonId?: string;
   }) => invokeCmd<ProjectDetail>('cmd_project_update', { req }),
+  /** Place a card between `beforeId` (above) and `afterId` (below) in its status column. */
+  reorder: (req: { projectId: string; beforeId?: string | null; afterId?: string | null }) =>
+    invokeCmd<{ projectId: string; currentStatus: string; rank: number }>('cmd_project_reorder', {
+      req,
+    }),
+  /** `size`: points on the scale or an XS–XL label; null clears the estimate. */
+  setSize: (req: {
+    projectId: string;
+    size: number | string | null;
+    note?: string;
+    changedByPersonId?: string | null;
+  }) => invokeCmd<ProjectDetail>('cmd_project_set_size', { req }),
+  /** All values are saved together; null clears. */
+  setBudget: (req: {
+    projectId: string;
+    estimatedHours: number | null;
+    budgetAmount: number | null;
+    budgetCurrency?: string | null;
+  }) => invokeCmd<ProjectDetail>('cmd_project_set_budget', { req }),
+  throughput: (req?: { weeks?: number }) =>
+    invokeCmd<Throughput>('cmd_project_throughput', req ? { req } : {}),
+  tree: (req?: { rootProjectId?: string; onlyUnarchived?: boolean }) =>
+    invokeCmd<ProjectTreeNode[]>('cmd_project_tree', req ? { req } : {}),
   changeStatus: (req: {
     projectId: string;
     toStatus: string;
     note?: string;
     changedByPersonId?: string | null;
+    /** Change status even past a WIP limit (otherwise WIP_LIMIT_EXCEEDED). */
+    force?: boolean;
   }) => invokeCmd<ProjectDetail>('cmd_project_change_status', { req }),
+  /** Redacted read-only bundle behind a time-limited presigned link (requires S3 config). */
+  share: (req: { projectId: string; expiresInHours?: number }) =>
+    invokeCmd<ProjectShareResult>('cmd_project_share', { req }),
 };
@@ -0,0 +1,38 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { SchemaRelationDto, SchemaTableDto } from './schema';
+
+export interface ExecuteQueryReq {
+  /** A single SELECT / WITH / VALUES statement. */
+  sql: string;
+  /** Array = positional (`?`, `?1`); object = named (`:name`, prefix optional). Scalars only. */
+  params?: unknown[] | Record<string, unknown>;
+  /** Default 200, at most 5000. */
+  maxRows?: number;
+}
+
+export interface QueryResultDto {
+  columns: string[];
+  /** BLOB values are hex strings. */
+  rows: unknown[][];
+  truncated: boolean;
+  elapsedMs: number;
+}
+
+export interface QuerySchemaDto {
+  schemaVersion: number;
+  tables: SchemaTableDto[];
+  relations: SchemaRelationDto[];
+  blockedTables: string[];
+  maxRows: number;
+  timeoutMs: number;
+}
+
+export const queryApi = {
+  async execute(req: ExecuteQueryReq): Promise<QueryResultDto> {
+    return await invoke<QueryResultDto>('cmd_execute_query', { req });
+  },
+
+  async schema(): Promise<QuerySchemaDto> {
+    return await invoke<QuerySchemaDto>('cmd_query_schema');
+  },
+};
@@ -0,0 +1,61 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type RecurrenceUnit = 'day' | 'week' | 'month';
+
+export interface RecurrenceRuleDto {
+  id: string;
+  templateId: string;
+  templateName: string | null;
+  name: string;
+  intervalUnit: RecurrenceUnit;
+  intervalCount: number;
+  anchorOn: string;
+  nextRunOn: string;
+  dueOffsetDays: number | null;
+  enabled: boolean;
+  lastRunAt: string | null;
+  lastProjectId: string | null;
+  lastError: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface RecurrenceRuleCreateReq {
+  templateId: string;
+  name: string;
+  intervalUnit: RecurrenceUnit;
+  intervalCount: number;
+  /** First run date (YYYY-MM-DD). */
+  startOn: string;
+  dueOffsetDays?: number;
+}
+
+export interface RecurrenceRunItem {
+  ruleId: string;
+  runOn: string;
+  projectId: string | null;
+  error: string | null;
+}
+
+export const recurrenceApi = {
+  async list(): Promise<RecurrenceRuleDto[]> {
+    return await invoke<RecurrenceRuleDto[]>('cmd_recurrence_list');
+  },
+
+  /** Rules already due run immediately. */
+  async create(req: RecurrenceRuleCreateReq): Promise<RecurrenceRuleDto> {
+    return await invoke<RecurrenceRuleDto>('cmd_recurrence_create', { req });
+  },
+
+  async update(req: { id: string; enabled?: boolean; name?: string }): Promise<RecurrenceRuleDto> {
+    return await invoke<RecurrenceRuleDto>('cmd_recurrence_update', { req });
+  },
+
+  async delete(id: string): Promise<void> {
+    await invoke<void>('cmd_recurrence_delete', { req: { id } });
+  },
+
+  async runDue(): Promise<RecurrenceRunItem[]> {
+    return await invoke<RecurrenceRunItem[]>('cmd_recurrence_run_due');
+  },
+};
@@ -0,0 +1,166 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { RecurrenceUnit } from './recurrence';
+
+export type ReportKind = 'partner_scorecard';
+
+export interface ReportScheduleDto {
+  id: string;
+  name: string;
+  reportKind: ReportKind;
+  partnerId: string | null;
+  partnerName: string | null;
+  intervalUnit: RecurrenceUnit;
+  intervalCount: number;
+  anchorOn: string;
+  nextRunOn: string;
+  /** null = `<data dir>/reports`. */
+  outputDir: string | null;
+  enabled: boolean;
+  lastRunAt: string | null;
+  lastOutputPath: string | null;
+  lastError: string | null;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface ReportScheduleCreateReq {
+  name: string;
+  reportKind: ReportKind;
+  partnerId?: string;
+  intervalUnit: RecurrenceUnit;
+  intervalCount: number;
+  /** First run date (YYYY-MM-DD); pick a Monday for "every Monday". */
+  startOn: string;
+  /** Absolute path or `~/...`; omitted = `<data dir>/reports`. */
+  outputDir?: string;
+}
+
+export interface ReportRunItem {
+  scheduleId: string;
+  runOn: string;
+  outputPath: string | null;
+  error: string | null;
+}
+
+export interface ScorecardProject {
+  id: string;
+  name: string;
+  currentStatus: string;
+  ownerName: string;
+  dueDate: string | null;
+  completedOn: string | null;
+  overdue: boolean;
+}
+
+export interface PartnerScorecard {
+  partnerId: string;
+  partnerName: string;
+  asOf: string;
+  total: number;
+  active: number;
+  done: number;
+  archived: number;
+  overdue: number;
+  completedRecently: number;
+  onTimeRate: number | null;
+  projects: ScorecardProject[];
+}
+
+export interface PartnerSummary {
+  partnerId: string;
+  partnerName: string;
+  isActive: boolean;
+  /** Not DONE or ARCHIVED. */
+  active: number;
+  done: number;
+  archived: number;
+  /** Creation → latest move to DONE; null if no project got there. */
+  avgDurationDays: number | null;
+  lastActivityAt: string | null;
+}
+
+export interface ReportSummary {
+  from: string;
+  to: string;
+  total: number;
+  statusCounts: { status: string; count: number; overdue: number }[];
+  /** `avgDays` is null when no stay in that status ended in the range. */
+  avgTimeInStatus: { status: string; avgDays: number | null; samples: number }[];
+  /** One entry per week (Monday start), oldest first. */
+  throughput: { weekStart: string; done: number }[];
+  overdue: number;
+}
+
+export interface WorkloadDue {
+  projectId: string;
+  projectName: string;
+  currentStatus: string;
+  dueDate: string;
+  overdue: boolean;
+}
+
+export interface PersonWorkload {
+  personId: string;
+  displayName: string;
+  role: string | null;
+  activeAssignments: number;
+  projectsOwned: number;
+  /** Projects the person is on or owns; every status except ARCHIVED. */
+  statusCounts: { status: string; count: number; overdue: number }[];
+  /** Open projects due by `upcomingUntil` (overdue included), earliest first. */
+  upcomingDue: WorkloadDue[];
+}
+
+export interface PersonWorkloadReport {
+  asOf: string;
+  upcomingUntil: string;
+  persons: PersonWorkload[];
+}
+
+export const reportApi = {
+  async listSchedules(): Promise<ReportScheduleDto[]> {
+    return await invoke<ReportScheduleDto[]>('cmd_report_schedule_list');
+  },
+
+  /** Schedules already due run immediately. */
+  async createSchedule(req: ReportScheduleCreateReq): Promise<ReportScheduleDto> {
+    return await invoke<ReportScheduleDto>('cmd_report_schedule_create', { req });
+  },
+
+  /** `outputDir: ''` resets to the default folder. */
+  async updateSchedule(req: {
+    id: string;
+    name?: string;
+    enabled?: boolean;
+    outputDir?: string;
+  }): Promise<ReportScheduleDto> {
+    return await invoke<ReportScheduleDto>('cmd_report_schedule_update', { req });
+  },
+
+  async deleteSchedule(id: string): Promise<void> {
+    await invoke<void>('cmd_report_schedule_delete', { req: { id } });
+  },
+
+  /** Generate now; the next scheduled run is unchanged. */
+  async runSchedule(id: string): Promise<ReportRunItem> {
+    return await invoke<ReportRunItem>('cmd_report_schedule_run', { req: { id } });
+  },
+
+  async partnerScorecard(partnerId: string): Promise<PartnerScorecard> {
+    return await invoke<PartnerScorecard>('cmd_report_partner_scorecard', { req: { partnerId } });
+  },
+
+  async partnerSummary(): Promise<PartnerSummary[]> {
+    return await invoke<PartnerSummary[]>('cmd_report_partner_summary');
+  },
+
+  /** Dates are inclusive `YYYY-MM-DD`; defaults to the last 12 weeks. */
+  async summary(req: { from?: string; to?: string } = {}): Promise<ReportSummary> {
+    return await invoke<ReportSummary>('cmd_report_summary', { req });
+  },
+
+  /** Active people only; `upcomingDays` defaults to 14. */
+  async personWorkload(req: { upcomingDays?: number } = {}): Promise<PersonWorkloadReport> {
+    return await invoke<PersonWorkloadReport>('cmd_report_person_workload', { req });
+  },
+};
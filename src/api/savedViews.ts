@@ -0,0 +1,24 @@
+import { invokeCmd } from './invoke';
+import type { ProjectListPage, ProjectListReq } from './projects';
+
+export interface SavedViewDto {
+  id: string;
+  name: string;
+  /** Stored filters and sort (limit/offset are never stored). */
+  filters: ProjectListReq;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export const savedViewApi = {
+  list: () => invokeCmd<SavedViewDto[]>('cmd_saved_view_list'),
+
+  create: (name: string, filters: ProjectListReq) =>
+    invokeCmd<SavedViewDto>('cmd_saved_view_create', { req: { name, filters } }),
+
+  delete: (id: string) =>
+    invokeCmd<void>('cmd_saved_view_delete', { req: { id } }),
+
+  apply: (id: string, page?: { limit?: number; offset?: number }) =>
+    invokeCmd<ProjectListPage>('cmd_saved_view_apply', { req: { id, ...page } }),
+};
@@ -0,0 +1,43 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type SchemaFormat = 'json' | 'mermaid' | 'graphviz';
+
+export interface SchemaColumnDto {
+  name: string;
+  dataType: string;
+  notNull: boolean;
+  defaultValue: string | null;
+  /** 1-based position in the primary key; 0 = not part of it. */
+  primaryKey: number;
+}
+
+export interface SchemaTableDto {
+  name: string;
+  columns: SchemaColumnDto[];
+  indexes: { name: string; unique: boolean; columns: string[] }[];
+  triggers: string[];
+}
+
+export interface SchemaRelationDto {
+  fromTable: string;
+  fromColumn: string;
+  toTable: string;
+  toColumn: string;
+  /** false = reference enforced by the app layer only (no FOREIGN KEY clause). */
+  declared: boolean;
+}
+
+export interface DbSchemaExportDto {
+  format: SchemaFormat;
+  schemaVersion: number;
+  tables: SchemaTableDto[];
+  relations: SchemaRelationDto[];
+  /** Rendered output: pretty JSON, a Mermaid `erDiagram` or a Graphviz `digraph`. */
+  content: string;
+}
+
+export const schemaApi = {
+  async export(format: SchemaFormat = 'json'): Promise<DbSchemaExportDto> {
+    return await invoke<DbSchemaExportDto>('cmd_db_schema_export', { req: { format } });
+  },
+};
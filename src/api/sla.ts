@@ -0,0 +1,66 @@
+import { invokeCmd } from './invoke';
+
+export interface SlaRuleDto {
+  id: string;
+  name: string;
+  /** null = any open status (not DONE / ARCHIVED). */
+  status: string | null;
+  maxDays: number;
+  enabled: boolean;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface SlaRuleCreateReq {
+  name: string;
+  /** Omitted or '' = any open status. */
+  status?: string;
+  /** 1–365 */
+  maxDays: number;
+}
+
+export interface SlaRuleUpdateReq {
+  id: string;
+  name?: string;
+  /** '' = any open status. */
+  status?: string;
+  maxDays?: number;
+  enabled?: boolean;
+}
+
+export interface ProjectFlagDto {
+  projectId: string;
+  projectName: string;
+  ruleId: string;
+  ruleName: string;
+  currentStatus: string;
+  /** When the project entered its current status. */
+  since: string;
+  maxDays: number;
+  flaggedAt: string;
+}
+
+export interface SlaEvaluateResult {
+  flagged: number;
+  cleared: number;
+}
+
+export const slaApi = {
+  listRules: () => invokeCmd<SlaRuleDto[]>('cmd_sla_rule_list'),
+
+  /** Rules are re-evaluated right away. */
+  createRule: (req: SlaRuleCreateReq) =>
+    invokeCmd<SlaRuleDto>('cmd_sla_rule_create', { req }),
+
+  updateRule: (req: SlaRuleUpdateReq) =>
+    invokeCmd<SlaRuleDto>('cmd_sla_rule_update', { req }),
+
+  deleteRule: (id: string) =>
+    invokeCmd<void>('cmd_sla_rule_delete', { req: { id } }),
+
+  evaluate: () => invokeCmd<SlaEvaluateResult>('cmd_sla_evaluate'),
+
+  /** Longest-standing first. */
+  listFlags: (projectId?: string) =>
+    invokeCmd<ProjectFlagDto[]>('cmd_project_flag_list', { req: { projectId } }),
+};
@@ -0,0 +1,36 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface StatusAutomationConfigDto {
+  /** Compute the `overdue` flag of projects. */
+  overdueEnabled: boolean;
+  /** Move BACKLOG projects to PLANNED once their start date is reached. */
+  autoPlanEnabled: boolean;
+}
+
+export interface StatusAutomationConfigReq {
+  overdueEnabled?: boolean;
+  autoPlanEnabled?: boolean;
+}
+
+export interface StatusAutomationItem {
+  projectId: string;
+  startDate: string;
+  toStatus: string;
+  /** Why the project was not moved; retried on the next run. */
+  error: string | null;
+}
+
+export const statusAutomationApi = {
+  async getConfig(): Promise<StatusAutomationConfigDto> {
+    return await invoke<StatusAutomationConfigDto>('cmd_status_automation_get_config');
+  },
+
+  /** Turning auto-plan on moves projects whose start date has passed right away. */
+  async updateConfig(req: StatusAutomationConfigReq): Promise<StatusAutomationConfigDto> {
+    return await invoke<StatusAutomationConfigDto>('cmd_status_automation_update_config', { req });
+  },
+
+  async run(): Promise<StatusAutomationItem[]> {
+    return await invoke<StatusAutomationItem[]>('cmd_status_automation_run');
+  },
+};
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 export interface SyncConfigDto {
   enabled: boolean;
@@ -7,18 +8,84 @@ export interface SyncConfigDto {
   access_key?: string;
   has_secret_key?: boolean;
   secret_key_masked?: string;
+  /** Named AWS profile used instead of the static keys. */
+  profile?: string;
+  /** A session token is stored (never returned). */
+  has_session_token: boolean;
+  /** IAM role assumed through STS. */
+  role_arn?: string;
+  external_id?: string;
+  /** Prefix of every sync object in the bucket (`''` = bucket root). */
+  key_prefix: string;
   device_id: string;
   last_sync?: string;
   auto_sync_interval_minutes: number;
+  disable_snapshot_restore: boolean;
+  disable_remote_cleanup: boolean;
+  review_before_apply: boolean;
+  /** Apply remote changes only; business writes fail with `READ_ONLY_REPLICA`. */
+  read_only_replica: boolean;
+  auto_compact: boolean;
+  compact_older_than_days: number;
+  last_compact_at?: string;
+  snapshot_retention_count: number;
+  /** Storage class new snapshots are uploaded with (e.g. `STANDARD_IA`). */
+  snapshot_storage_class: string;
+  /** Tags set on new snapshots: `k=v,k2=v2` (empty = none). */
+  snapshot_tags: string;
+  /** Remote store used by sync. */
+  backend: 's3' | 'gdrive';
+  /** A Google Drive authorization is saved. */
+  gdrive_connected: boolean;
+  backup_max_count: number;
+  syncable_tables: string[];
+  /** Tables this device ignores: local edits are not uploaded, remote changes are dropped. */
+  ignored_tables: string[];
+  /** Details (assignments, history, tags, comments, custom values) of archived projects stay local. */
+  exclude_archived_projects: boolean;
+  /** Imports adding at least this many rows upload a snapshot instead of deltas (0 = never). */
+  auto_snapshot_import_threshold: number;
 }
 
-export interface SyncConfigUpdateDto {
+/** AWS auth fields of a config draft: omitted = keep the saved value, `''` = clear it. */
+export interface SyncAuthDraft {
+  profile?: string;
+  session_token?: string;
+  role_arn?: string;
+  external_id?: string;
+}
+
+export interface SyncConfigUpdateDto extends SyncAuthDraft {
   enabled: boolean;
   bucket: string;
   endpoint?: string;
   access_key?: string;
   secret_key?: string;
   auto_sync_interval_minutes: number;
+  disable_snapshot_restore?: boolean;
+  disable_remote_cleanup?: boolean;
+  review_before_apply?: boolean;
+  read_only_replica?: boolean;
+  auto_compact?: boolean;
+  compact_older_than_days?: number;
+  snapshot_retention_count?: number;
+  /** `''` = STANDARD. */
+  snapshot_storage_class?: string;
+  /** `k=v,k2=v2`; `''` clears the tags. */
+  snapshot_tags?: string;
+  backup_max_count?: number;
+  /** Replaces the ignored set; `[]` syncs all tables. */
+  ignored_tables?: string[];
+  exclude_archived_projects?: boolean;
+  auto_snapshot_import_threshold?: number;
+}
+
+export interface SyncOfflineQueueDto {
+  /** S3 endpoint unreachable; pending changes stay queued and are retried with backoff. */
+  offline: boolean;
+  offline_since?: string;
+  retry_attempt: number;
+  next_retry_at?: string;
 }
 
 export interface SyncStatusDto {
@@ -26,13 +93,133 @@ export interface SyncStatusDto {
   pending_changes: number;
   last_sync?: string;
   last_error?: string;
+  offline_queue: SyncOfflineQueueDto;
+  pause: SyncPauseState;
+}
+
+export interface DeviceSyncHealth {
+  device_id: string;
+  /** Name from the device registry. */
+  name?: string;
+  revoked_at?: string;
+  is_local: boolean;
+  delta_count: number;
+  last_delta_at?: string;
+  last_snapshot_at?: string;
+  /** Newest delta of this device applied (or staged) here. */
+  applied_cursor_ts?: number;
+  pending_deltas: number;
+  /** Local device only: changes not uploaded yet. */
+  pending_local_changes?: number;
+  /** Last time the device published its cursors (finished a sync). */
+  last_sync_at?: string;
+}
+
+export interface DeviceInfo {
+  id: string;
+  name: string;
+  platform: string;
+  last_seen_at?: string;
+  revoked_at?: string;
+  created_at: string;
+  updated_at: string;
+  is_local: boolean;
+}
+
+export interface SyncRevokeDeviceReq {
+  device_id: string;
+  /** Also delete the device's deltas, snapshots, cursors and presence from the bucket. */
+  purge_remote?: boolean;
+}
+
+export interface SyncRevokeDeviceResult {
+  device: DeviceInfo;
+  purged_objects: number;
+  failed_deletes: number;
+}
+
+export interface TableVerifyResult {
+  table: string;
+  local_rows: number;
+  remote_rows: number;
+  only_local: number;
+  only_remote: number;
+  /** Rows on both sides with different content. */
+  differing: number;
+  /** Ignored on this device: differences are expected. */
+  ignored: boolean;
+  only_local_ids: string[];
+  only_remote_ids: string[];
+  differing_ids: string[];
+}
+
+export interface SyncVerifyReport {
+  checked_at: string;
+  /** Snapshot the remote state was rebuilt from; empty when rebuilt from deltas only. */
+  snapshot_key?: string;
+  deltas_applied: number;
+  deltas_failed: string[];
+  pending_local_changes: number;
+  staged_deltas: number;
+  in_sync: boolean;
+  tables: TableVerifyResult[];
 }
 
-export interface SyncTestConnectionReq {
+export interface TableRepairSummary {
+  table: string;
+  /** Rows (or deletes) this device wins, queued for the next upload. */
+  requeued: number;
+  /** Rows requested from other devices. */
+  requested: number;
+}
+
+export interface SyncRepairResult {
+  manifest_key: string;
+  peers: string[];
+  rows_requeued: number;
+  rows_requested: number;
+  tables: TableRepairSummary[];
+}
+
+export interface SyncPauseState {
+  paused: boolean;
+  /** End of a timed pause; empty while paused until resumed. */
+  paused_until?: string;
+}
+
+export interface SyncTestConnectionReq extends SyncAuthDraft {
   bucket?: string;
   endpoint?: string;
   access_key?: string;
   secret_key?: string;
+  /** Also write, read back and delete a canary object under `probes/`. */
+  probe_write?: boolean;
+}
+
+export interface SyncValidateConfigReq extends Omit<SyncTestConnectionReq, 'probe_write'> {
+  auto_sync_interval_minutes?: number;
+}
+
+export type ConfigCheckStatus = 'pass' | 'warn' | 'fail' | 'skip';
+
+export interface ConfigCheck {
+  /** bucket | endpoint | https_policy | auto_sync_interval | access_key | secret_key | auth | list | write | read | delete */
+  name: string;
+  status: ConfigCheckStatus;
+  message: string;
+}
+
+export interface SyncTestConnectionResp {
+  /** All performed checks passed. */
+  ok: boolean;
+  message: string;
+  /** list / write / read / delete */
+  checks: ConfigCheck[];
+}
+
+export interface SyncValidateConfigResp {
+  ok: boolean;
+  checks: ConfigCheck[];
 }
 
 export interface PendingWipeInfo {
@@ -43,6 +230,167 @@ export interface PendingWipeInfo {
   createdAt: string;
 }
 
+export interface SnapshotInfo {
+  key: string;
+  device_id: string;
+  created_at?: string;
+  last_modified_unix?: number;
+  size_bytes?: number;
+  legacy: boolean;
+}
+
+export interface PresenceConfig {
+  enabled: boolean;
+  display_name: string;
+}
+
+export interface PresenceEntry {
+  device_id: string;
+  display_name: string;
+  project_id?: string;
+  project_name?: string;
+  last_seen_at: string;
+  is_self: boolean;
+}
+
+export interface TableRestoreDiff {
+  table: string;
+  added: number;
+  updated: number;
+  deleted: number;
+  unchanged: number;
+}
+
+export interface SnapshotRestorePreview {
+  snapshot_key: string;
+  device_id: string;
+  created_at: string;
+  checksum: string;
+  tables: TableRestoreDiff[];
+}
+
+export interface SyncCompactResult {
+  snapshot_key?: string;
+  deleted_deltas: number;
+  failed_deletes: number;
+  retained_deltas: number;
+  blocked_by_devices: string[];
+  older_than_days: number;
+}
+
+export interface SyncSetKeyPrefixResult {
+  /** Normalized prefix now in use. */
+  key_prefix: string;
+  moved_objects: number;
+}
+
+export interface SyncInstallLifecycleReq {
+  transition_after_days?: number;
+  storage_class?: string;
+  abort_multipart_after_days?: number;
+  /** Only return the rule, do not touch the bucket. */
+  dry_run?: boolean;
+}
+
+export interface SyncInstallLifecycleResult {
+  /** The rule was written to the bucket. */
+  installed: boolean;
+  rule_id: string;
+  /** `put-bucket-lifecycle-configuration` JSON. */
+  lifecycle_json: string;
+  /** Equivalent AWS CLI command. */
+  cli_command: string;
+}
+
+export const SYNC_BOOTSTRAP_PROGRESS_EVENT = 'sync-bootstrap-progress';
+
+export interface SyncBootstrapProgress {
+  stage: 'download_snapshot' | 'restore_snapshot' | 'apply_deltas' | 'done';
+  /** 1-based position of `stage`. */
+  step: number;
+  total_steps: number;
+  snapshot_key?: string;
+}
+
+export interface SyncBootstrapResult {
+  snapshot_key: string;
+  /** Device that created the snapshot. */
+  source_device_id: string;
+  snapshot_created_at: string;
+  /** Sync run that applied the deltas newer than the snapshot. */
+  sync: SyncRunResult;
+}
+
+export interface SyncGdriveBeginAuthReq {
+  /** OAuth client id of a Google Cloud "Desktop app" client. */
+  client_id: string;
+  client_secret?: string;
+}
+
+export interface SyncGdriveBeginAuthResp {
+  /** Open in the system browser, then call `gdriveFinishAuth`. */
+  auth_url: string;
+}
+
+export interface SyncRunResult {
+  message: string;
+  direction: 'none' | 'up' | 'down' | 'both';
+  ops_uploaded: number;
+  ops_downloaded: number;
+  deltas_uploaded: number;
+  deltas_applied: number;
+  deltas_staged: number;
+  skipped_stale_upserts: number;
+  conflicts_detected: number;
+  quarantined_objects: string[];
+}
+
+export interface SyncErrorEntry {
+  id: number;
+  occurred_at: string;
+  phase: string;
+  error_code: string;
+  message: string;
+  delta_key?: string;
+}
+
+export interface SyncErrorPage {
+  items: SyncErrorEntry[];
+  total: number;
+  limit: number;
+  offset: number;
+}
+
+export interface SyncConflictDto {
+  id: number;
+  table_name: string;
+  record_id: string;
+  local_data?: Record<string, unknown>;
+  local_version?: number;
+  remote_data: Record<string, unknown>;
+  remote_version: number;
+  remote_device_id: string;
+  auto_resolution: 'local' | 'remote';
+  detected_at: string;
+  resolved_at?: string;
+  resolution?: 'local' | 'remote' | 'merged';
+}
+
+export type PresignMethod = 'GET' | 'PUT';
+
+export interface SyncPresignReq {
+  key: string;
+  method: PresignMethod;
+  expires_in_secs?: number;
+}
+
+export interface SyncPresignResp {
+  url: string;
+  method: PresignMethod;
+  key: string;
+  expires_at: string;
+}
+
 export const syncApi = {
   async getConfig(): Promise<SyncConfigDto> {
     return await invoke<SyncConfigDto>('cmd_sync_get_config');
@@ -60,39 +408,189 @@ export const syncApi = {
     return await invoke<string>('cmd_sync_reveal_secret_key');
   },
 
-  async testConnection(req?: SyncTestConnectionReq): Promise<string> {
+  async testConnection(req?: SyncTestConnectionReq): Promise<SyncTestConnectionResp> {
+    if (req) {
+      return await invoke<SyncTestConnectionResp>('cmd_sync_test_connection', { req });
+    }
+    return await invoke<SyncTestConnectionResp>('cmd_sync_test_connection');
+  },
+
+  async validateConfig(req?: SyncValidateConfigReq): Promise<SyncValidateConfigResp> {
     if (req) {
-      return await invoke<string>('cmd_sync_test_connection', { req });
+      return await invoke<SyncValidateConfigResp>('cmd_sync_validate_config', { req });
     }
-    return await invoke<string>('cmd_sync_test_connection');
+    return await invoke<SyncValidateConfigResp>('cmd_sync_validate_config');
   },
 
   async getStatus(): Promise<SyncStatusDto> {
     return await invoke<SyncStatusDto>('cmd_sync_get_status');
   },
 
+  async reportUserActivity(): Promise<void> {
+    await invoke<void>('cmd_user_activity');
+  },
+
+  async setNetworkMetered(metered: boolean): Promise<void> {
+    await invoke<void>('cmd_sync_set_network_metered', { metered });
+  },
+
+  async networkOnline(): Promise<void> {
+    await invoke<void>('cmd_sync_network_online');
+  },
+
+  async pause(durationMinutes?: number): Promise<SyncPauseState> {
+    return await invoke<SyncPauseState>('cmd_sync_pause', {
+      req: { duration_minutes: durationMinutes },
+    });
+  },
+
+  async resume(): Promise<SyncPauseState> {
+    return await invoke<SyncPauseState>('cmd_sync_resume');
+  },
+
+  async listDevices(): Promise<DeviceSyncHealth[]> {
+    return await invoke<DeviceSyncHealth[]>('cmd_sync_list_devices');
+  },
+
+  async verify(): Promise<SyncVerifyReport> {
+    return await invoke<SyncVerifyReport>('cmd_sync_verify');
+  },
+
+  async repair(): Promise<SyncRepairResult> {
+    return await invoke<SyncRepairResult>('cmd_sync_repair');
+  },
+
+  async listRegisteredDevices(): Promise<DeviceInfo[]> {
+    return await invoke<DeviceInfo[]>('cmd_sync_list_registered_devices');
+  },
+
+  async renameDevice(name: string): Promise<DeviceInfo> {
+    return await invoke<DeviceInfo>('cmd_sync_rename_device', { name });
+  },
+
+  async revokeDevice(req: SyncRevokeDeviceReq): Promise<SyncRevokeDeviceResult> {
+    return await invoke<SyncRevokeDeviceResult>('cmd_sync_revoke_device', { req });
+  },
+
+  async getPresenceConfig(): Promise<PresenceConfig> {
+    return await invoke<PresenceConfig>('cmd_sync_presence_get_config');
+  },
+
+  async updatePresenceConfig(req: PresenceConfig): Promise<PresenceConfig> {
+    return await invoke<PresenceConfig>('cmd_sync_presence_update_config', { req });
+  },
+
+  async presenceHeartbeat(projectId?: string): Promise<void> {
+    await invoke<void>('cmd_sync_presence_heartbeat', { req: { project_id: projectId } });
+  },
+
+  async listPresence(projectId?: string): Promise<PresenceEntry[]> {
+    return await invoke<PresenceEntry[]>('cmd_sync_presence_list', {
+      req: { project_id: projectId },
+    });
+  },
+
+  async listErrors(limit?: number, offset?: number): Promise<SyncErrorPage> {
+    return await invoke<SyncErrorPage>('cmd_sync_errors_list', { req: { limit, offset } });
+  },
+
+  async listConflicts(includeResolved = false): Promise<SyncConflictDto[]> {
+    return await invoke<SyncConflictDto[]>('cmd_sync_list_conflicts', {
+      req: { include_resolved: includeResolved },
+    });
+  },
+
+  async resolveConflict(
+    id: number,
+    keep: 'local' | 'remote' | 'merged',
+    mergedJson?: string,
+  ): Promise<SyncConflictDto> {
+    return await invoke<SyncConflictDto>('cmd_sync_resolve_conflict', {
+      req: { id, keep, merged_json: mergedJson },
+    });
+  },
+
   async getPendingWipe(): Promise<PendingWipeInfo | null> {
     return await invoke<PendingWipeInfo | null>('cmd_sync_get_pending_wipe');
   },
 
-  async confirmWipe(wipeId: string, phrase: string): Promise<string> {
-    return await invoke<string>('cmd_sync_confirm_wipe', { req: { wipeId, phrase } });
+  async confirmWipe(wipeId: string, phrase: string): Promise<SyncRunResult> {
+    return await invoke<SyncRunResult>('cmd_sync_confirm_wipe', { req: { wipeId, phrase } });
   },
 
   async rejectWipe(wipeId: string): Promise<string> {
     return await invoke<string>('cmd_sync_reject_wipe', { req: { wipeId } });
   },
 
-  async syncFull(): Promise<string> {
-    return await invoke<string>('cmd_sync_full');
+  async syncFull(): Promise<SyncRunResult> {
+    return await invoke<SyncRunResult>('cmd_sync_full');
   },
 
   async createSnapshot(): Promise<string> {
     return await invoke<string>('cmd_sync_create_snapshot');
   },
 
-  async restoreSnapshot(): Promise<string> {
-    return await invoke<string>('cmd_sync_restore_snapshot');
+  async listSnapshots(): Promise<SnapshotInfo[]> {
+    return await invoke<SnapshotInfo[]>('cmd_sync_list_snapshots');
+  },
+
+  async previewRestoreSnapshot(key?: string): Promise<SnapshotRestorePreview> {
+    return await invoke<SnapshotRestorePreview>('cmd_sync_preview_restore_snapshot', {
+      req: { key },
+    });
+  },
+
+  async restoreSnapshot(key?: string): Promise<string> {
+    return await invoke<string>('cmd_sync_restore_snapshot', { req: { key } });
+  },
+
+  /** First run on an empty device: restore the newest remote snapshot, then apply newer deltas. */
+  async bootstrapFromRemote(
+    onProgress?: (progress: SyncBootstrapProgress) => void,
+  ): Promise<SyncBootstrapResult> {
+    const unlisten = onProgress
+      ? await listen<SyncBootstrapProgress>(SYNC_BOOTSTRAP_PROGRESS_EVENT, (event) =>
+          onProgress(event.payload),
+        )
+      : undefined;
+    try {
+      return await invoke<SyncBootstrapResult>('cmd_sync_bootstrap_from_remote');
+    } finally {
+      unlisten?.();
+    }
+  },
+
+  async compact(olderThanDays?: number): Promise<SyncCompactResult> {
+    return await invoke<SyncCompactResult>('cmd_sync_compact', {
+      req: { older_than_days: olderThanDays },
+    });
+  },
+
+  /** Change the bucket key prefix; `migrate` (default true) moves the existing sync objects. */
+  async setKeyPrefix(keyPrefix: string, migrate?: boolean): Promise<SyncSetKeyPrefixResult> {
+    return await invoke<SyncSetKeyPrefixResult>('cmd_sync_set_key_prefix', {
+      req: { key_prefix: keyPrefix, migrate },
+    });
+  },
+
+  /** Install (or, with `dry_run`, only describe) the lifecycle rule that moves old deltas to a cheaper class. */
+  async installLifecycle(req: SyncInstallLifecycleReq = {}): Promise<SyncInstallLifecycleResult> {
+    return await invoke<SyncInstallLifecycleResult>('cmd_sync_install_lifecycle', { req });
+  },
+
+  /** Start the Google Drive authorization; returns the URL to open in the browser. */
+  async gdriveBeginAuth(req: SyncGdriveBeginAuthReq): Promise<SyncGdriveBeginAuthResp> {
+    return await invoke<SyncGdriveBeginAuthResp>('cmd_sync_gdrive_begin_auth', { req });
+  },
+
+  /** Wait for the browser redirect (up to 5 minutes) and switch sync to Google Drive. */
+  async gdriveFinishAuth(): Promise<SyncConfigDto> {
+    return await invoke<SyncConfigDto>('cmd_sync_gdrive_finish_auth');
+  },
+
+  /** Revoke the Google Drive authorization; sync goes back to S3. */
+  async gdriveDisconnect(): Promise<SyncConfigDto> {
+    return await invoke<SyncConfigDto>('cmd_sync_gdrive_disconnect');
   },
 
   async exportConfig(): Promise<string> {
@@ -102,4 +600,14 @@ export const syncApi = {
   async importConfig(json: string): Promise<SyncConfigDto> {
     return await invoke<SyncConfigDto>('cmd_sync_import_config', { req: { json } });
   },
+
+  /** New device id for this profile (way out of a NAMESPACE_CLAIMED error). */
+  async resetDeviceId(): Promise<string> {
+    return await invoke<string>('cmd_sync_reset_device_id');
+  },
+
+  /** Presigned URL for one bucket object (default lifetime 1 hour, max 7 days). */
+  async presignObject(req: SyncPresignReq): Promise<SyncPresignResp> {
+    return await invoke<SyncPresignResp>('cmd_sync_presign_object', { req });
+  },
 };
@@ -0,0 +1,52 @@
+import { invokeCmd } from './invoke';
+
+export interface TagDto {
+  name: string;
+  /** `#rrggbb`; null when unset or the tag has no catalog entry. */
+  color: string | null;
+  description: string;
+  /** Projects (not in the trash) carrying the tag. */
+  projectCount: number;
+}
+
+export interface TagCreateReq {
+  name: string;
+  color?: string;
+  description?: string;
+}
+
+export interface TagUpdateReq {
+  name: string;
+  /** '' clears the color. */
+  color?: string;
+  description?: string;
+}
+
+export interface TagChangeResult {
+  tag: TagDto;
+  /** Projects whose tags changed (including projects in the trash). */
+  projectsUpdated: number;
+}
+
+export const tagsApi = {
+  list: () => invokeCmd<TagDto[]>('cmd_tag_list'),
+
+  create: (req: TagCreateReq) => invokeCmd<TagDto>('cmd_tag_create', { req }),
+
+  /** Also works for tags in use without a catalog entry. */
+  update: (req: TagUpdateReq) => invokeCmd<TagDto>('cmd_tag_update', { req }),
+
+  /** Fails with CONFLICT when `to` already exists (merge instead). */
+  rename: (req: { from: string; to: string; changedByPersonId?: string }) =>
+    invokeCmd<TagChangeResult>('cmd_tag_rename', { req }),
+
+  /** `source` is replaced by `target` on every project; `target` keeps its color. */
+  merge: (req: { source: string; target: string; changedByPersonId?: string }) =>
+    invokeCmd<TagChangeResult>('cmd_tag_merge', { req }),
+
+  /** Only unused tags can be deleted. */
+  delete: (name: string) => invokeCmd<void>('cmd_tag_delete', { req: { name } }),
+
+  /** Returns the deleted tag names. */
+  deleteUnused: () => invokeCmd<string[]>('cmd_tag_delete_unused'),
+};
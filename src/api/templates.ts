@@ -0,0 +1,65 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { ProjectDetail } from './projects';
+
+export interface TemplateMemberDto {
+  personId: string;
+  role: string;
+}
+
+export interface TemplateMilestoneDto {
+  title: string;
+  /** Days after the project start date (or creation date). */
+  offsetDays: number;
+}
+
+export interface ProjectTemplateDto {
+  id: string;
+  name: string;
+  namePattern: string;
+  description: string;
+  priority: number;
+  countryCode: string;
+  partnerId: string;
+  ownerPersonId: string;
+  productName: string | null;
+  tags: string[];
+  members: TemplateMemberDto[];
+  milestones: TemplateMilestoneDto[];
+  instanceCount: number;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface ProjectSaveAsTemplateReq {
+  projectId: string;
+  templateName: string;
+  /** Placeholders: {date} {month} {year} {n}. */
+  namePattern?: string;
+  milestones?: TemplateMilestoneDto[];
+}
+
+export interface ProjectCreateFromTemplateReq {
+  templateId: string;
+  name?: string;
+  startDate?: string;
+  dueDate?: string;
+  createdByPersonId?: string;
+}
+
+export const templateApi = {
+  async list(): Promise<ProjectTemplateDto[]> {
+    return await invoke<ProjectTemplateDto[]>('cmd_template_list');
+  },
+
+  async delete(id: string): Promise<void> {
+    await invoke<void>('cmd_template_delete', { req: { id } });
+  },
+
+  async saveFromProject(req: ProjectSaveAsTemplateReq): Promise<ProjectTemplateDto> {
+    return await invoke<ProjectTemplateDto>('cmd_project_save_as_template', { req });
+  },
+
+  async createProject(req: ProjectCreateFromTemplateReq): Promise<ProjectDetail> {
+    return await invoke<ProjectDetail>('cmd_project_create_from_template', { req });
+  },
+};
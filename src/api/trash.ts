@@ -0,0 +1,45 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type TrashKind = 'project' | 'person' | 'partner';
+
+export interface TrashItemDto {
+  kind: TrashKind;
+  id: string;
+  name: string;
+  deletedAt: string;
+}
+
+export interface TrashSkippedDto {
+  kind: TrashKind;
+  id: string;
+  name: string;
+  reason: string;
+}
+
+export interface TrashPurgeResult {
+  purged: TrashItemDto[];
+  /** Old enough but still referenced; they stay in the trash. */
+  skipped: TrashSkippedDto[];
+}
+
+export const trashApi = {
+  /** Move to the trash (fails with TRASH_IN_USE while live projects depend on it). */
+  async delete(kind: TrashKind, id: string): Promise<TrashItemDto> {
+    return await invoke<TrashItemDto>('cmd_trash_delete', { req: { kind, id } });
+  },
+
+  /** Most recently deleted first. */
+  async list(): Promise<TrashItemDto[]> {
+    return await invoke<TrashItemDto[]>('cmd_trash_list');
+  },
+
+  /** A project needs its partner, owner and parent restored first (TRASH_RESTORE_BLOCKED). */
+  async restore(kind: TrashKind, id: string): Promise<TrashItemDto> {
+    return await invoke<TrashItemDto>('cmd_trash_restore', { req: { kind, id } });
+  },
+
+  /** Permanently delete items trashed at least `olderThanDays` days ago (0 = empty the trash). */
+  async purge(olderThanDays: number): Promise<TrashPurgeResult> {
+    return await invoke<TrashPurgeResult>('cmd_trash_purge', { req: { olderThanDays } });
+  },
+};
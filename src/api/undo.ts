@@ -0,0 +1,23 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type ActionKind = 'project_update' | 'project_status' | 'assignment_end' | 'comment_delete';
+
+export interface ActionLogEntryDto {
+  id: number;
+  action: ActionKind;
+  entityId: string;
+  createdAt: string;
+  undoneAt?: string | null;
+}
+
+export const undoApi = {
+  /** Returns null when there is nothing to undo. */
+  async undoLast(): Promise<ActionLogEntryDto | null> {
+    return await invoke<ActionLogEntryDto | null>('cmd_undo_last');
+  },
+
+  /** Returns null when there is nothing to redo. */
+  async redoLast(): Promise<ActionLogEntryDto | null> {
+    return await invoke<ActionLogEntryDto | null>('cmd_redo_last');
+  },
+};
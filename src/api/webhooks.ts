@@ -0,0 +1,94 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type WebhookEvent = 'project.status_changed' | 'comment.created' | 'sync.completed';
+
+export const WEBHOOK_EVENTS: WebhookEvent[] = [
+  'project.status_changed',
+  'comment.created',
+  'sync.completed',
+];
+
+export interface WebhookDto {
+  id: string;
+  name: string;
+  url: string;
+  /** The secret itself is never returned. */
+  hasSecret: boolean;
+  /** Empty = all events. */
+  events: WebhookEvent[];
+  enabled: boolean;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface WebhookCreateReq {
+  name: string;
+  url: string;
+  secret?: string;
+  events?: WebhookEvent[];
+  enabled?: boolean;
+}
+
+export interface WebhookUpdateReq {
+  id: string;
+  name?: string;
+  url?: string;
+  /** `''` removes the secret. */
+  secret?: string;
+  events?: WebhookEvent[];
+  enabled?: boolean;
+}
+
+export type WebhookDeliveryStatus = 'pending' | 'delivered' | 'failed';
+
+export interface WebhookDeliveryDto {
+  id: number;
+  webhookId: string;
+  event: WebhookEvent;
+  payload: string;
+  status: WebhookDeliveryStatus;
+  attempts: number;
+  nextAttemptAt: string | null;
+  lastStatusCode: number | null;
+  lastError: string | null;
+  createdAt: string;
+  deliveredAt: string | null;
+}
+
+export interface WebhookDispatchResult {
+  delivered: number;
+  retrying: number;
+  failed: number;
+}
+
+export const webhookApi = {
+  async create(req: WebhookCreateReq): Promise<WebhookDto> {
+    return await invoke<WebhookDto>('cmd_webhook_create', { req });
+  },
+
+  async update(req: WebhookUpdateReq): Promise<WebhookDto> {
+    return await invoke<WebhookDto>('cmd_webhook_update', { req });
+  },
+
+  async remove(id: string): Promise<void> {
+    await invoke<void>('cmd_webhook_delete', { req: { id } });
+  },
+
+  async list(): Promise<WebhookDto[]> {
+    return await invoke<WebhookDto[]>('cmd_webhook_list');
+  },
+
+  /** Delivery log, newest first. */
+  async deliveries(req?: {
+    webhookId?: string;
+    status?: WebhookDeliveryStatus;
+    limit?: number;
+  }): Promise<WebhookDeliveryDto[]> {
+    return await invoke<WebhookDeliveryDto[]>('cmd_webhook_delivery_list', req ? { req } : {});
+  },
+
+  /** Send due deliveries now. */
+  async dispatch(): Promise<WebhookDispatchResult> {
+    return await invoke<WebhookDispatchResult>('cmd_webhook_dispatch');
+  },
+};
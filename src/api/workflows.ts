@@ -0,0 +1,93 @@
+import { invokeCmd } from './invoke';
+
+export interface WorkflowStatus {
+  /** Stored as the project's status (`[A-Z][A-Z0-9_]*`). */
+  key: string;
+  /** Display name; empty = the key. */
+  label?: string;
+  /** Most projects allowed in this status; null = unlimited. */
+  wipLimit?: number | null;
+  /** Most projects one owner may have in this status; null = unlimited. */
+  ownerWipLimit?: number | null;
+}
+
+export interface WorkflowTransition {
+  from: string;
+  to: string;
+  noteRequired?: boolean;
+}
+
+export interface WorkflowDto {
+  id: string;
+  name: string;
+  /** Board order. */
+  statuses: Required<WorkflowStatus>[];
+  transitions: Required<WorkflowTransition>[];
+  isActive: boolean;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface WipOwnerCountDto {
+  personId: string;
+  displayName: string;
+  count: number;
+  overLimit: boolean;
+}
+
+export interface WipStatusDto {
+  status: string;
+  label: string;
+  count: number;
+  wipLimit: number | null;
+  ownerWipLimit: number | null;
+  overLimit: boolean;
+  /** Empty without `ownerWipLimit`. */
+  owners: WipOwnerCountDto[];
+}
+
+/** `details` of a WIP_LIMIT_EXCEEDED error; counts include the moved project. */
+export interface WipLimitInfo {
+  status: string;
+  count: number;
+  limit: number | null;
+  ownerPersonId: string;
+  ownerCount: number;
+  ownerLimit: number | null;
+}
+
+export interface WorkflowCreateReq {
+  name: string;
+  /** Must include BACKLOG, DONE and ARCHIVED. */
+  statuses: WorkflowStatus[];
+  transitions: WorkflowTransition[];
+}
+
+export interface WorkflowUpdateReq {
+  id: string;
+  name?: string;
+  statuses?: WorkflowStatus[];
+  transitions?: WorkflowTransition[];
+}
+
+export const workflowApi = {
+  /** Active workflow first. */
+  list: () => invokeCmd<WorkflowDto[]>('cmd_workflow_list'),
+
+  create: (req: WorkflowCreateReq) =>
+    invokeCmd<WorkflowDto>('cmd_workflow_create', { req }),
+
+  update: (req: WorkflowUpdateReq) =>
+    invokeCmd<WorkflowDto>('cmd_workflow_update', { req }),
+
+  /** Status changes are checked against the active workflow. */
+  activate: (id: string) =>
+    invokeCmd<WorkflowDto>('cmd_workflow_activate', { req: { id } }),
+
+  /** Counts against the active workflow's WIP limits, in board order. */
+  wipStatus: () => invokeCmd<WipStatusDto[]>('cmd_workflow_wip_status'),
+
+  /** The active workflow cannot be deleted. */
+  delete: (id: string) =>
+    invokeCmd<void>('cmd_workflow_delete', { req: { id } }),
+};
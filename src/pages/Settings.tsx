@@ -360,7 +360,12 @@ export function Settings() {
       if (nextEnabled) {
         // Re-check config completeness from backend state (includes has_secret_key).
         const cfg = await syncManager.getConfig();
-        const ok = Boolean(cfg.bucket?.trim()) && Boolean(cfg.access_key?.trim()) && Boolean(cfg.has_secret_key);
+        // A profile or role supplies its own credentials.
+        const hasCredentials =
+          (Boolean(cfg.access_key?.trim()) && Boolean(cfg.has_secret_key)) ||
+          Boolean(cfg.profile) ||
+          Boolean(cfg.role_arn);
+        const ok = Boolean(cfg.bucket?.trim()) && hasCredentials;
         if (!ok) {
           showError(t('settings.sync.configIncomplete'));
           setSyncEnabled(false);
@@ -405,8 +410,16 @@ export function Settings() {
           }
         : undefined;
 
-      await syncManager.testConnection(draft);
-      showSuccess(t('settings.sync.testConnectionSuccess'));
+      const result = await syncManager.testConnection(draft, { probeWrite: true });
+      if (result.ok) {
+        showSuccess(t('settings.sync.testConnectionSuccess'));
+      } else {
+        const failed = result.checks
+          .filter((c) => c.status === 'fail')
+          .map((c) => `${c.name}: ${c.message}`)
+          .join('\n');
+        showError(failed, t('settings.sync.testConnectionMissingPermissions'));
+      }
     } catch (e: unknown) {
       const { code, message } = getErrorCodeAndMessage(e);
       if (code === 'SYNC_CONFIG_INCOMPLETE') {
@@ -5,9 +5,13 @@
 
 import {
   syncApi,
+  type SnapshotInfo,
+  type SnapshotRestorePreview,
   type SyncConfigDto,
+  type SyncRunResult,
   type SyncStatusDto,
   type SyncTestConnectionReq,
+  type SyncTestConnectionResp,
 } from '../api/sync';
 import { logger } from '../utils/logger';
 
@@ -16,6 +20,8 @@ export interface SyncState {
   lastSync?: Date;
   error?: string;
   pendingChanges: number;
+  /** 最近一次成功同步的结构化结果 */
+  lastResult?: SyncRunResult;
 }
 
 type SyncStateListener = (state: SyncState) => void;
@@ -29,6 +35,10 @@ export class SyncManager {
   private listeners: Set<SyncStateListener> = new Set();
   private autoSyncTimer?: number;
   private autoSyncInterval = 60000; // 默认 1 分钟
+  private activityHeartbeatInstalled = false;
+  private networkMonitorInstalled = false;
+  private lastActivityReport = 0;
+  private lastPresenceReport = 0;
 
   constructor() {
     // Initialization
@@ -86,20 +96,25 @@ export class SyncManager {
     return await syncApi.revealSecretKey();
   }
 
-  async testConnection(req?: {
-    bucket?: string;
-    endpoint?: string;
-    accessKey?: string;
-    secretKey?: string;
-  }): Promise<string> {
-    const payload: SyncTestConnectionReq | undefined = req
-      ? {
-          bucket: req.bucket,
-          endpoint: req.endpoint,
-          access_key: req.accessKey,
-          secret_key: req.secretKey,
-        }
-      : undefined;
+  async testConnection(
+    req?: {
+      bucket?: string;
+      endpoint?: string;
+      accessKey?: string;
+      secretKey?: string;
+    },
+    options?: { probeWrite?: boolean }
+  ): Promise<SyncTestConnectionResp> {
+    const payload: SyncTestConnectionReq | undefined =
+      req || options?.probeWrite
+        ? {
+            bucket: req?.bucket,
+            endpoint: req?.endpoint,
+            access_key: req?.accessKey,
+            secret_key: req?.secretKey,
+            probe_write: options?.probeWrite,
+          }
+        : undefined;
     return await syncApi.testConnection(payload);
   }
 
@@ -150,6 +165,7 @@ export class SyncManager {
         lastSync: new Date(),
         error: undefined,
         pendingChanges: status.pending_changes,
+        lastResult: result,
       });
     } catch (error: unknown) {
       logger.error('Sync failed:', error);
@@ -169,10 +185,24 @@ export class SyncManager {
   }
 
   /**
-   * 恢复快照
+   * 列出远端快照（最新在前）
    */
-  async restoreSnapshot(): Promise<string> {
-    return await syncApi.restoreSnapshot();
+  async listSnapshots(): Promise<SnapshotInfo[]> {
+    return await syncApi.listSnapshots();
+  }
+
+  /**
+   * 预览恢复快照的影响（不修改本地数据）
+   */
+  async previewRestoreSnapshot(key?: string): Promise<SnapshotRestorePreview> {
+    return await syncApi.previewRestoreSnapshot(key);
+  }
+
+  /**
+   * 恢复快照（未指定 key 时恢复最新一份）
+   */
+  async restoreSnapshot(key?: string): Promise<string> {
+    return await syncApi.restoreSnapshot(key);
   }
 
   /**
@@ -205,6 +235,73 @@ export class SyncManager {
     }
   }
 
+  /**
+   * 用户输入心跳：后端据此推迟同步 apply 阶段，避免输入卡顿（节流 1 秒）；每 15 秒改发 presence 心跳（带当前项目）
+   */
+  startActivityHeartbeat(): void {
+    if (this.activityHeartbeatInstalled) {
+      return;
+    }
+    this.activityHeartbeatInstalled = true;
+
+    const report = () => {
+      const now = Date.now();
+      if (now - this.lastActivityReport < 1000) {
+        return;
+      }
+      this.lastActivityReport = now;
+      // presence 心跳也计为输入活动；后端另有 60 秒节流，这里只避免过于频繁的调用
+      if (now - this.lastPresenceReport >= 15000) {
+        this.lastPresenceReport = now;
+        const match = window.location.pathname.match(/^\/projects\/([^/]+)/);
+        const projectId = match && match[1] !== 'new' ? match[1] : undefined;
+        syncApi.presenceHeartbeat(projectId).catch(() => {
+          // ignore: heartbeat is best-effort
+        });
+        return;
+      }
+      syncApi.reportUserActivity().catch(() => {
+        // ignore: heartbeat is best-effort
+      });
+    };
+    window.addEventListener('keydown', report, true);
+    window.addEventListener('input', report, true);
+  }
+
+  /**
+   * 监听网络状态：恢复联网时唤醒离线重试；上报是否按流量计费（蜂窝 / 省流量模式），后端据此在 pause_on_metered 开启时跳过定时同步
+   */
+  startNetworkMonitor(): void {
+    if (this.networkMonitorInstalled) {
+      return;
+    }
+    this.networkMonitorInstalled = true;
+
+    // 网络恢复时立即重试离线期间排队的变更
+    window.addEventListener('online', () => {
+      syncApi.networkOnline().catch(() => {
+        // ignore: the scheduler retries on its own backoff
+      });
+    });
+
+    const connection = (
+      navigator as Navigator & {
+        connection?: EventTarget & { type?: string; saveData?: boolean };
+      }
+    ).connection;
+    if (!connection) {
+      return;
+    }
+    const report = () => {
+      const metered = connection.type === 'cellular' || connection.saveData === true;
+      syncApi.setNetworkMetered(metered).catch(() => {
+        // ignore: best-effort hint for the scheduler
+      });
+    };
+    report();
+    connection.addEventListener('change', report);
+  }
+
   /**
    * 订阅状态变化
    */
@@ -237,3 +334,5 @@ export class SyncManager {
 
 // 全局单例
 export const syncManager = new SyncManager();
+syncManager.startActivityHeartbeat();
+syncManager.startNetworkMonitor();